@@ -0,0 +1,124 @@
+//! Benchmarks for quality-driven preprocessing on a clean photo
+//!
+//! Run with `cargo bench --bench preprocessing`. The page is synthetic: a light,
+//! straight, noise-free 1600x1200 page with dark text-like bars.
+//!
+//! `photo_clean/every_stage` runs the photo profile as if the image were tilted
+//! and noisy (deskew, denoise and threshold, as before stage selection looked at
+//! measurements). `photo_clean/selected` runs the plan chosen from the real
+//! measurements, which skips deskewing and denoising. `assess_image_quality`
+//! measures the cost of taking those measurements, noise and skew included.
+//!
+//! The `pipeline_12mp` group runs every stage on a noisy 4000x3000 photo.
+//! `copy_per_stage` chains the borrowing stage functions, each of which returns a
+//! fresh image; `threaded` hands one owned grayscale buffer from stage to stage
+//! with [`PreprocessingStage::run_all`].
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use just_ingredients::ocr::apply_source_aware_preprocessing;
+use just_ingredients::preprocessing::{
+    apply_clahe, apply_morphological_operation, apply_otsu_threshold, assess_image_quality,
+    deskew_image, reduce_noise, ImageQuality, ImageQualityResult, MorphologicalOperation,
+    PipelineConfig, PreprocessingStage, SourceClass, SourceProfiles,
+};
+
+fn clean_page() -> image::DynamicImage {
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(1600, 1200, |x, y| {
+        let value = if (y / 24) % 3 == 0 && x > 100 && x < 1500 {
+            25
+        } else {
+            230
+        };
+        image::Rgb([value, value, value])
+    }))
+}
+
+fn noisy_photo_12mp() -> image::DynamicImage {
+    let mut seed: u32 = 42;
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(4000, 3000, |x, y| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let base = if (y / 60) % 3 == 0 && x > 200 && x < 3800 {
+            40
+        } else {
+            210
+        };
+        let value = (base + (seed >> 16) as i32 % 31 - 15).clamp(0, 255) as u8;
+        image::Rgb([value, value, value])
+    }))
+}
+
+fn bench_preprocessing(c: &mut Criterion) {
+    let page = clean_page();
+    let pipeline = PipelineConfig::default();
+    let profiles = SourceProfiles::default();
+    let profile = profiles.for_class(SourceClass::Photo);
+
+    // Same quality class for both runs, so only the measurement-driven stages differ
+    let measured = ImageQualityResult {
+        quality: ImageQuality::Medium,
+        ..assess_image_quality(&page).expect("synthetic page should be assessed")
+    };
+    let tilted_and_noisy = ImageQualityResult {
+        noise_estimate: 2.0 * pipeline.noise_threshold,
+        skew_angle: 2.0 * pipeline.deskew_min_angle_degrees.max(1.0),
+        ..measured.clone()
+    };
+
+    c.bench_function("assess_image_quality", |b| {
+        b.iter(|| assess_image_quality(black_box(&page)))
+    });
+    for (label, quality) in [("every_stage", &tilted_and_noisy), ("selected", &measured)] {
+        c.bench_function(&format!("photo_clean/{}", label), |b| {
+            b.iter(|| {
+                apply_source_aware_preprocessing(
+                    black_box(&page),
+                    quality,
+                    SourceClass::Photo,
+                    profile,
+                    &pipeline,
+                )
+            })
+        });
+    }
+}
+
+fn bench_pipeline_12mp(c: &mut Criterion) {
+    use PreprocessingStage::*;
+
+    let photo = noisy_photo_12mp();
+    let mut group = c.benchmark_group("pipeline_12mp");
+    group.sample_size(10);
+
+    group.bench_function("copy_per_stage", |b| {
+        b.iter(|| {
+            let image = apply_clahe(black_box(&photo), 3.0, (8, 8)).unwrap().image;
+            let image = deskew_image(&image).unwrap().image;
+            let image = reduce_noise(&image, 1.2).unwrap().image;
+            let image = apply_otsu_threshold(&image).unwrap().image;
+            let image = apply_morphological_operation(&image, MorphologicalOperation::Opening)
+                .unwrap()
+                .image;
+            apply_morphological_operation(&image, MorphologicalOperation::Closing)
+                .unwrap()
+                .image
+        })
+    });
+    group.bench_function("threaded", |b| {
+        b.iter_batched(
+            || photo.clone(),
+            |image| {
+                PreprocessingStage::run_all(
+                    &[Clahe, Deskew, Denoise, Threshold, Despeckle],
+                    image,
+                    1.2,
+                )
+                .unwrap()
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_preprocessing, bench_pipeline_12mp);
+criterion_main!(benches);
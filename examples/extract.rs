@@ -0,0 +1,69 @@
+//! # Extraction Example
+//!
+//! Reads the ingredients of a local recipe photo (or text file) with the same
+//! pipeline the bot uses, without Telegram or a database.
+//!
+//! ```bash
+//! cargo run --example extract -- path/to/recipe.jpg
+//! cargo run --example extract -- path/to/recipe.txt
+//! ```
+
+use just_ingredients::ocr_config::OcrConfig;
+use just_ingredients::text_processing::MeasurementConfig;
+use just_ingredients::RecipeExtractor;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("usage: extract <recipe image or text file>")?;
+
+    let extractor = RecipeExtractor::new(OcrConfig::default(), MeasurementConfig::default())?;
+
+    // Text files are parsed directly, anything else goes through OCR
+    let result = if path.ends_with(".txt") {
+        extractor
+            .extract_from_text(&std::fs::read_to_string(&path)?)
+            .await
+    } else {
+        extractor.extract_from_image_path(&path).await?
+    };
+
+    println!("📄 Extracted text:\n{}\n", result.text);
+
+    if let Some(confidence) = &result.confidence {
+        println!(
+            "🔍 OCR confidence: {:.0}% ({})",
+            confidence.overall_score * 100.0,
+            just_ingredients::ocr::get_confidence_description(confidence)
+        );
+    }
+
+    println!("📏 Found {} ingredients:", result.ingredients.len());
+    for (i, ingredient) in result.ingredients.iter().enumerate() {
+        let measurement = ingredient
+            .measurement
+            .as_deref()
+            .map(|unit| format!("{} {}", ingredient.quantity, unit))
+            .unwrap_or_else(|| ingredient.quantity.clone());
+        let check = if ingredient.requires_quantity_confirmation {
+            " ⚠️ check the quantity"
+        } else {
+            ""
+        };
+        println!(
+            "  {}. {} → {}{}",
+            i + 1,
+            measurement,
+            ingredient.ingredient_name,
+            check
+        );
+    }
+
+    println!(
+        "\n⏱️ OCR {:?}, parsing {:?}, total {:?}",
+        result.timing.ocr, result.timing.parsing, result.timing.total
+    );
+
+    Ok(())
+}
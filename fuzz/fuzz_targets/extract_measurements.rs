@@ -0,0 +1,20 @@
+//! Arbitrary bytes fed to measurement extraction: `cargo +nightly fuzz run extract_measurements`
+
+#![no_main]
+
+use just_ingredients::text_processing::MeasurementDetector;
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+static DETECTOR: LazyLock<MeasurementDetector> = LazyLock::new(|| {
+    MeasurementDetector::new().expect("Default measurement pattern should be valid")
+});
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let matches = DETECTOR.extract_ingredient_measurements(&text);
+    assert_eq!(DETECTOR.has_measurements(&text), !matches.is_empty());
+    for m in &matches {
+        assert!(m.start_pos <= m.end_pos && m.end_pos <= text.len());
+    }
+});
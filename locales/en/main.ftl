@@ -4,11 +4,11 @@
 welcome-title = Welcome to Ingredients Bot!
 welcome-description = I'm your OCR assistant that can extract text from images. Here's what I can do:
 welcome-features =
-    📸 **Send me photos** of ingredient lists, recipes, or any text you want to extract
-    � **Add captions** to automatically name your recipes
-    �📄 **Send me image files** (PNG, JPG, JPEG, BMP, TIFF, TIF)
-    🔍 **I'll process them with OCR** and send back the extracted text
-    💾 **All extracted text is stored** for future reference
+    📸 Send me photos of ingredient lists, recipes, or any text you want to extract
+    � Add captions to automatically name your recipes
+    �📄 Send me image files (PNG, JPG, JPEG, BMP, TIFF, TIF)
+    🔍 I'll process them with OCR and send back the extracted text
+    💾 All extracted text is stored for future reference
 welcome-commands = Commands:
 welcome-start = /start - Show this welcome message
 welcome-help = /help - Get help and usage instructions
@@ -26,6 +26,19 @@ help-limits = File size limit: 10MB for JPEG, 5MB for other formats
 help-commands = Commands:
 help-start = /start - Welcome message
 help-help = /help - This help message
+help-cancel = /cancel - Stop what you are doing (naming, reviewing, editing) at any time
+help-recent = /recent - Your recently saved and edited recipes
+help-stats = /stats - Statistics over all your recipes: most used ingredients, recipes per month
+help-language = /language - Choose the language I reply in (/language auto to reply in the language each message is written in, /language off to undo)
+help-export = /export - Download all your recipes as a JSON file (send it back to any bot instance to restore them)
+help-find = /find <ingredient> - List your recipes that use an ingredient
+help-tag = /recipes <tag> - List your recipes with a tag
+help-units = /units - Show quantities in metric or US/imperial units
+help-timezone = /timezone - Set your time zone, used for dates and today/this week counts
+help-digest = /digest - Get a weekly summary of the recipes you saved
+help-inline = @bot <recipe name> - In any chat, type my username and a recipe name to share its ingredients
+help-trash = /trash - Restore a recipe you deleted in the last 7 days
+help-delete-all = /delete_all - Permanently delete all your data
 help-tips = Tips:
 help-tip1 = • Use clear, well-lit images
 help-tip2 = • Ensure text is readable and not too small
@@ -38,17 +51,28 @@ help-final = Need help? Just send me an image! 😊
 error-download-failed = [DOWNLOAD] Failed to download the image. Please try again.
 error-unsupported-format = [FORMAT] Unsupported image format. Please use PNG, JPG, JPEG, BMP, TIFF, or TIF formats.
 error-no-text-found = [OCR_RESULT] No text was found in the image. Please try a clearer image with visible text.
-error-ocr-initialization = [OCR_INIT] OCR engine initialization failed. Please try again later.
-error-ocr-extraction = [OCR_EXTRACT] Failed to extract text from the image. Please try again with a different image.
-error-ocr-timeout = [OCR_TIMEOUT] OCR processing timed out: {$msg}
-error-ocr-corruption = [OCR_CORRUPT] OCR engine encountered an internal error. Please try again.
-error-ocr-exhaustion = [OCR_RESOURCE] System resources are exhausted. Please try again later.
-error-validation = [VALIDATION] Image validation failed: {$msg}
-error-image-load = [IMAGE_LOAD] The image format is not supported or the image is corrupted. Please try with a PNG, JPG, or BMP image.
+error-pdf-too-large = [PDF_TOO_LARGE] This PDF is too large to process. Please send a smaller PDF, or only the pages with the ingredient list.
+error-reply-validation = [VALIDATION] This image could not be checked for reading.
+error-reply-unsupported-format = [FORMAT] This file format is not supported, or the file is damaged.
+error-reply-file-too-large = [FILE_TOO_LARGE] This image is too large to process.
+error-file-too-large-limit = [FILE_TOO_LARGE] This file is too large: files up to { $max_mb } MB can be read.
+error-image-too-large-limit = [FILE_TOO_LARGE] This image is too large: images up to { $max_megapixels } megapixels can be read.
+error-reply-pdf-too-large = [PDF_TOO_LARGE] This PDF is too large to process.
+error-reply-ocr-timeout = [OCR_TIMEOUT] Reading this image took too long.
+error-reply-ocr-unavailable = [OCR_UNAVAILABLE] Reading images is temporarily unavailable.
+error-reply-ocr-failed = [OCR_EXTRACT] No text could be read from this image.
+error-reply-database-unavailable = [DATABASE] Your recipes can't be reached right now.
+error-reply-unknown = [ERROR] Something went wrong.
+error-next-clearer-photo = Try a sharper, well-lit photo of the ingredient list.
+error-next-supported-format = Send a PNG, JPG, BMP or TIFF image, or a PDF.
+error-next-smaller-photo = Try a smaller photo, for example a crop of the ingredient list.
+error-next-fewer-pages = Send a smaller PDF, or only the pages with the ingredient list.
+error-next-wait = Please try again in a minute.
+error-next-retry = Please try again.
 
 # Success messages
-success-extraction = ✅ **Text extracted successfully!**
-success-extracted-text = 📝 **Extracted Text:**
+success-extraction = ✅ Text extracted successfully!
+success-extracted-text = 📝 Extracted Text:
 success-photo-downloaded = Photo downloaded successfully! Processing...
 success-document-downloaded = Image document downloaded successfully! Processing...
 
@@ -62,10 +86,26 @@ total-ingredients-found = Total ingredients found
 original-text = Original extracted text
 error-processing-failed = [INGREDIENT_PROCESSING] Failed to process ingredients
 error-try-again = Please try again with a different image.
+error-recipe-save-failed = [SAVE] Your recipe couldn't be saved, and nothing was stored. Please try again in a moment.
 
 # Processing messages
 processing-photo = Photo downloaded successfully! Processing...
 processing-document = Image document downloaded successfully! Processing...
+processing-pdf = PDF downloaded successfully! Reading its first pages...
+processing-album = { $count ->
+    [one] Photo downloaded successfully! Processing...
+   *[other] Album of {$count} photos received! Reading them as one recipe...
+}
+duplicate-photo = 🔁 This looks like a photo you just sent, so it was not processed again.
+duplicate-photo-reprocess = Process it again
+ocr-retry-profiles = Try again with different processing
+ocr-retry-processing = 🔁 Reading the photo again with different processing...
+ocr-retry-expired = ⌛ This photo is no longer available for another try. Please send it again.
+ocr-retry-no-ingredients = Different processing did not find any measurements either. Try a sharper, well-lit photo taken straight above the recipe.
+rate-limit-ocr = { $count ->
+    [one] ⏳ You are sending photos faster than they can be processed. Please wait {$count} second before sending another one.
+   *[other] ⏳ You are sending photos faster than they can be processed. Please wait {$count} seconds before sending another one.
+}
 
 # Unsupported message types
 unsupported-title = 🤔 I can only process text messages and images.
@@ -79,10 +119,13 @@ unsupported-final = Try sending me an image with text! 📝
 # Regular text responses
 text-response = Received: {$text}
 text-tip = 💡 Tip: Send me an image with text to extract it using OCR!
+text-ingredients-detected = 📋 This looks like an ingredient list. Check it below, then confirm to save it as a recipe.
+unknown-command = Unknown command. Send /help to see what I can do.
 
 # Recipe name dialogue messages
 recipe-name-prompt = 🏷️ What would you like to call this recipe?
 recipe-name-prompt-hint = Please enter a name for your recipe (e.g., "Chocolate Chip Cookies", "Mom's Lasagna")
+recipe-name-suggestions-hint = Tap one of your recent names below, or type a new one.
 recipe-name-invalid = [RECIPE_NAME] Recipe name cannot be empty. Please enter a valid name for your recipe.
 recipe-name-too-long = [RECIPE_NAME] Recipe name is too long (maximum 255 characters). Please enter a shorter name.
 recipe-complete = ✅ Recipe "{$recipe_name}" saved successfully with {$ingredient_count} ingredients!
@@ -95,8 +138,33 @@ caption-empty = 💡 Tip: Add a caption to your photo to automatically name your
 # Ingredient review messages
 review-title = Review Your Ingredients
 review-description = Please review the extracted ingredients below. Use the buttons to edit or delete items, then confirm when ready.
+review-low-confidence-note = ⚠️ marks lines that were hard to read: please double-check them.
+review-ingredients-capped = ⚠️ Only the first { $kept } of the { $found } ingredients found were kept.
+album-photos-failed = { $count ->
+    [one] ⚠️ One photo of the album could not be read; the results come from the other photos.
+   *[other] ⚠️ {$count} photos of the album could not be read; the results come from the other photos.
+}
 review-confirm = Confirm and Save
 review-cancelled = Ingredient review cancelled. No ingredients were saved.
+review-cancelled-summary = { $count ->
+    [one] Review cancelled, 1 ingredient discarded.
+   *[other] Review cancelled, {$count} ingredients discarded.
+}
+review-expired = This review is no longer active. Send the photo again to start over.
+flood-wait-slow-down = ⏳ Slow down a moment, Telegram asks me to wait {$seconds} s before updating this message.
+review-rename = Rename
+review-recipe-name = Recipe: {$recipe_name}
+review-rename-instructions = Type the new name for this recipe, or "cancel" to keep the current one.
+review-rename-success = The recipe will be saved as "{$recipe_name}".
+review-rename-cancelled = Recipe name unchanged.
+review-show-raw-text = Show raw text
+raw-text-title = Text read from the photo
+raw-text-truncated = The rest of the text is too long to show here.
+raw-text-hide = Hide raw text
+raw-text-empty = No text was kept for this review.
+undo-delete = Undo
+adjust-quantity-done = Done
+adjust-quantity-not-numeric = This quantity is not a number. Use ✏️ to type it instead.
 processing-cancelled = Image processing cancelled. No ingredients were extracted.
 review-no-ingredients = No ingredients remaining
 review-no-ingredients-help = All ingredients have been deleted. You can add more ingredients by sending another image, or cancel this recipe.
@@ -113,10 +181,32 @@ edit-too-long = Ingredient text is too long (maximum 200 characters). Please ent
 edit-no-ingredient-name = Please specify an ingredient name (e.g., "2 cups flour" not just "2 cups").
 edit-ingredient-name-too-long = Ingredient name is too long (maximum 100 characters). Please use a shorter name.
 edit-invalid-quantity = Invalid quantity. Please use a positive number (e.g., "2.5 cups flour").
+edit-ambiguous = I'm not sure which part of the ingredient to change. It currently reads:
+edit-ambiguous-hint = Copy it, correct it and send the whole line back, or send just "3" or "3 cups" to change the quantity or the unit.
+edit-quantity-not-understood = I couldn't understand the quantity on these lines, so it was not saved:
+    { $lines }
 quantity-correction-prompt = We couldn't read the exact amount for {$ingredient}. Please type the quantity:
 error-invalid-edit = [INGREDIENT_EDIT] Invalid ingredient index for editing.
 review-help = Please reply with "confirm" to save these ingredients, or "cancel" to discard them.
 
+# Ingredient counts
+review-title-count = { $count ->
+    [one] Review your {$count} ingredient
+   *[other] Review your {$count} ingredients
+}
+ingredients-saved = { $count ->
+    [one] {$count} ingredient saved to "{$recipe_name}"
+   *[other] {$count} ingredients saved to "{$recipe_name}"
+}
+ingredient-total = { $count ->
+    [one] {$count} ingredient
+   *[other] {$count} ingredients
+}
+review-more-ingredients = { $count ->
+    [one] … and {$count} more ingredient, still in the buttons below
+   *[other] … and {$count} more ingredients, still in the buttons below
+}
+
 # Document messages
 document-image = Received image document from user {$user_id}
 document-non-image = Received non-image document from user {$user_id}
@@ -149,8 +239,162 @@ workflow-recipe-saved = ✅ Recipe saved successfully!
 workflow-what-next = What would you like to do next?
 workflow-list-recipes = List My Recipes
 workflow-search-recipes = Search Recipes
-workflow-search-coming-soon = Recipe search coming soon! For now, use the 'List My Recipes' button.
-caption-recipe-saved = Recipe saved as: "{$recipe_name}"
+
+# Recipe search
+search-prompt = 🔍 Send a word to look for in your recipe names and ingredients.
+search-empty-query = Please send at least one word to search for.
+search-results-title = Recipes matching “{ $query }”
+search-results-truncated = Showing the first { $shown } matches. Send a more precise search to narrow them down.
+search-no-results = No recipe matches “{ $query }”.
+search-no-results-suggestion = Try another word, or list all your recipes.
+find-usage = Tell me which ingredient to look for, e.g. /find butter
+find-results-title = Recipes with “{ $ingredient }”
+find-result-count = { $count ->
+    [one] {$count} matching ingredient
+   *[other] {$count} matching ingredients
+}
+find-no-results = None of your recipes contain “{ $ingredient }”.
+
+# Unit display settings (/units)
+units-prompt = ⚖️ Which units should ingredient quantities be shown in?
+units-original = As written in the recipe
+units-metric = Metric (g, ml)
+units-imperial = US / imperial (oz, cups)
+units-set-original = ✅ Quantities will be shown as written in each recipe.
+units-set-metric = ✅ Quantities will be shown in metric units.
+units-set-imperial = ✅ Quantities will be shown in US/imperial units.
+
+# Time zone settings (/timezone)
+timezone-prompt = 🕐 Your time zone is { $zone }. Pick yours below, or send an offset from UTC like /timezone +5:30.
+timezone-dst-note = Fixed offsets don't follow daylight saving time: change yours when the clocks change.
+timezone-set = ✅ Your time zone is now { $zone }. Dates are shown in it.
+timezone-invalid = I couldn't read that time zone. Send an offset from UTC between -12 and +14, like /timezone +2, /timezone UTC-3 or /timezone +5:30.
+
+# Dates
+datetime-long = { $month } { $day }, { $year } at { $time }
+month-1 = January
+month-2 = February
+month-3 = March
+month-4 = April
+month-5 = May
+month-6 = June
+month-7 = July
+month-8 = August
+month-9 = September
+month-10 = October
+month-11 = November
+month-12 = December
+
+# Recipe scaling
+scale-recipe = Scale recipe
+scale-prompt = ⚖️ By how much should the quantities be multiplied? Send a factor such as 0.5, 2 or 3.
+scale-invalid-factor = Please send a number greater than 0 and at most 100, such as 0.5, 2 or 1/2.
+scale-title = { $name } × { $factor }
+scale-unscaled-footnote = ⚠️ Ingredients without a numeric quantity were left as written.
+scale-save-as-new = Save as new recipe
+scale-saved = Saved as “{ $name }”.
+
+# Recipe nutrition estimate
+nutrition-button = Nutrition
+nutrition-title = Nutrition: { $name }
+nutrition-total = Estimated total
+nutrition-calories = Calories
+nutrition-protein = Protein
+nutrition-fat = Fat
+nutrition-carbs = Carbohydrates
+nutrition-per-ingredient = Per ingredient
+nutrition-none = None of this recipe's ingredients could be estimated.
+nutrition-skipped = Not included
+nutrition-skip-no-quantity = no quantity
+nutrition-skip-unknown-ingredient = not in the nutrition table
+nutrition-skip-unknown-unit = unit not convertible to grams
+nutrition-disclaimer = Estimates only, from typical values of common ingredients. Actual values depend on brands, varieties and cooking.
+
+# Original photo of a recipe
+show-original-photo = Show original photo
+original-photo-unavailable = The original photo of this recipe is no longer available.
+
+# Recipe sharing (share links)
+share-button = Share
+share-link-created = Send this link to share “{ $recipe_name }”. It adds a copy of the recipe to the account of whoever opens it first, and expires in { $days } days.
+share-received = A recipe was shared with you and saved to your recipes.
+share-expired = This share link has expired. Ask for a new one.
+share-already-used = This share link was already used. Ask for a new one.
+share-not-found = This share link is not valid, or the recipe was deleted.
+share-own-recipe = This is your own recipe, it is already in your recipes. Send the link to someone else.
+
+# Recipe tags
+tags-button = Tags
+tags-title = Tags of { $name }
+tags-current = Tags: { $tags }
+tags-none = This recipe has no tags yet.
+tags-instructions = Tap a tag to add or remove it, or send a new one. List tagged recipes with /recipes <tag>.
+tags-done = Done
+tags-added = Tagged “{ $tag }”.
+tags-invalid-empty = Please send a tag name.
+tags-invalid-too-long = Tags can be at most 32 characters long.
+tags-invalid-characters = Tags can't contain line breaks or other control characters.
+tags-recipes-title = Recipes tagged “{ $tag }”
+tags-no-recipes = None of your recipes are tagged “{ $tag }”.
+
+# Recipe notes
+notes-button = Notes
+notes-title = Notes on { $name }
+notes-none = This recipe has no notes yet.
+notes-edit = Edit
+notes-clear = Clear
+notes-prompt = 🗒️ Send your notes for this recipe, such as “reduce sugar next time” (at most { $max } characters). They replace the current notes.
+notes-saved = 🗒️ Notes saved.
+notes-cleared = 🗒️ Notes cleared.
+notes-invalid-empty = Please send the text of your notes, or use Clear to remove them.
+notes-invalid-too-long = Notes can be at most { $max } characters long, yours are { $length }. Please send a shorter version.
+
+# Recipe as text
+copy-as-text-button = Copy as text
+copy-as-text-document-caption = 📋 This recipe is too long for one message, here it is as a Markdown file.
+recipe-text-saved-on = Saved on
+recipe-text-ingredients = Ingredients
+recipe-text-notes = Notes
+
+# Weekly digest (/digest)
+digest-status-on = Weekly digest: on, { $day } at { $hour }:00 UTC.
+digest-status-off = Weekly digest: off.
+digest-usage = Turn it on with /digest on (Mondays at 08:00 UTC), or pick a day and hour (UTC) with /digest fri 18. /digest off stops it.
+digest-title = Your week in recipes
+digest-new-recipes = { $count ->
+    [one] {$count} new recipe this week
+   *[other] {$count} new recipes this week
+}
+digest-total-recipes = { $count ->
+    [one] {$count} recipe saved in total
+   *[other] {$count} recipes saved in total
+}
+digest-open-hint = Tap a recipe to open it. Stop these messages with /digest off.
+weekday-mon = Mondays
+weekday-tue = Tuesdays
+weekday-wed = Wednesdays
+weekday-thu = Thursdays
+weekday-fri = Fridays
+weekday-sat = Saturdays
+weekday-sun = Sundays
+
+# Inline mode (@bot <recipe name> in any chat)
+inline-recipe-ingredients = { $count ->
+    [one] {$count} ingredient
+   *[other] {$count} ingredients
+}
+inline-no-recipes-title = No recipes found
+inline-no-recipes-saved = Save a recipe in a private chat with me first.
+inline-no-recipes-match = None of your recipes starts with "{$query}".
+inline-no-recipes-message = I couldn't find a saved recipe to share.
+
+# Account deletion (/delete_all)
+delete-all-warning = ⚠️ This permanently deletes all your recipes, ingredients and settings. It cannot be undone.
+delete-all-continue = Delete all my data
+delete-all-phrase = DELETE EVERYTHING
+delete-all-type-phrase = To confirm, type { $phrase } exactly. Any other message cancels.
+delete-all-cancelled = Nothing was deleted.
+delete-all-done = 🗑️ All your data was deleted: { $recipes } recipes and { $ingredients } ingredients removed.
 
 # Duplicate recipe handling messages
 multiple-recipes-found = Found {$count} recipes with this name:
@@ -175,6 +419,17 @@ recent-activity = Recent Activity
 recipes-today = Recipes Today
 recipes-this-week = Recipes This Week
 favorite-units = Favorite Units
+recipe-units-used = Units used
+recipe-statistics-see-all = 📈 Statistics over all your recipes: /stats
+stats-no-recipes = You have no saved recipes yet. Send a photo of an ingredient list to start!
+stats-overview = Overview
+stats-longest-recipe = { $count ->
+    [one] Longest recipe: { $name } ({ $count } ingredient)
+   *[other] Longest recipe: { $name } ({ $count } ingredients)
+}
+stats-top-ingredients = Most used ingredients
+stats-recipes-per-month = Recipes per month
+stats-busiest-month = Busiest month: { $month } ({ $count })
 back-to-recipe = Back to Recipe
 
 # Recipe management messages
@@ -184,7 +439,7 @@ current-recipe-name = Current name
 rename-recipe-success = Recipe renamed successfully
 rename-recipe-success-details = Recipe renamed from "{$old_name}" to "{$new_name}"
 delete-recipe-title = Delete Recipe
-delete-recipe-confirmation = Are you sure you want to delete this recipe? This action cannot be undone.
+delete-recipe-confirmation = Are you sure you want to delete this recipe? You can still restore it with /trash for {$days} days.
 recipe-deleted = Recipe deleted successfully
 recipe-deleted-help = The recipe and all its ingredients have been permanently removed.
 delete-cancelled = Recipe deletion cancelled
@@ -206,16 +461,223 @@ ingredients-updated = Ingredients updated successfully
 ingredients-updated-help = Your recipe ingredients have been updated.
 no-changes-made = No changes were made to the ingredients.
 editing-cancelled = Ingredient editing cancelled
+dialogue-cancelled = ✖️ Cancelled. Send me a recipe photo or use /recipes whenever you're ready.
+nothing-to-cancel = Nothing to cancel. Send me a recipe photo to get started.
+use-buttons-instruction = Please use the buttons above, or /cancel to stop.
 no-ingredients-to-edit = No ingredients to edit
 no-ingredients-to-edit-help = This recipe has no ingredients to edit. Try adding some ingredients first.
 error-updating-ingredients = Failed to update ingredients
-error-adding-ingredients = Failed to add new ingredients
-error-deleting-ingredients = Failed to delete ingredients
+edit-conflict-title = This recipe changed since you started editing
+edit-conflict-help = Your changes were not saved so they don't overwrite the newer ones. Reload to edit the current ingredients.
+edit-conflict-reload = Reload and edit
 add-ingredient = Add Ingredient
 add-ingredient-prompt = Send me the new ingredient (e.g., "2 cups flour" or "3 eggs")
 ingredient-added = Ingredient added successfully!
 
+# Cross-recipe rename propagation messages
+rename-propagation-offer = Found "{$old_name}" in {$count} other recipes — fix them too?
+rename-propagation-preview = Affected recipes:
+rename-propagation-more = …and {$count} more
+rename-propagation-accept = Rename everywhere
+rename-propagation-decline = Only this recipe
+rename-propagation-done = Renamed "{$old_name}" to "{$new_name}" in {$count} ingredients.
+rename-propagation-skipped = Other recipes were left unchanged.
+error-rename-propagation = Failed to rename the ingredient in your other recipes
+
+# Caption recipe name conflict messages
+name-conflict-found = You already have "{$recipe_name}" (saved copies: {$count}).
+name-conflict-question = What should I do with these ingredients?
+name-conflict-save-new = Save as new copy
+name-conflict-replace = Replace newest
+name-conflict-view = View existing
+name-conflict-merge = Merge ingredients
+name-conflict-replaced = Replaced the ingredients of your newest "{$recipe_name}".
+name-conflict-merged = { $count ->
+    [0] Your newest "{$recipe_name}" already has all of these ingredients.
+    [one] Added 1 new ingredient to your newest "{$recipe_name}".
+   *[other] Added {$count} new ingredients to your newest "{$recipe_name}".
+}
+error-name-conflict-replace = Failed to replace the existing recipe. You can still save it as a new copy.
+error-name-conflict-merge = Failed to merge into the existing recipe. You can still save it as a new copy.
+
 # Focused editing interface messages
 edit-ingredient-title = Edit Ingredient
 edit-ingredient-current = Current
-edit-ingredient-instruction = Enter the new ingredient text (e.g., "3 cups whole wheat flour"):
+edit-ingredient-instruction = Enter the new ingredient text (e.g., "3 cups whole wheat flour"), or just "3" or "3 cups" to change the quantity or the unit:
+
+# Admin debug messages
+debug-locales-title = Localization bundles
+debug-locales-entry = {$language}: {$state} (attempts: {$attempts}, ~{$kilobytes} KB)
+debug-locales-loaded = loaded
+debug-locales-not-loaded = not loaded
+debug-locales-failed = failed, using English
+
+# Quickbar reply keyboard
+quickbar-new-recipe = 📷 New recipe
+quickbar-my-recipes = 📚 My recipes
+quickbar-search = 🔍 Search
+quickbar-enabled = ⌨️ Quickbar enabled. Use the buttons below the text field for quick actions.
+quickbar-disabled = Quickbar disabled.
+quickbar-updated = ⌨️ Quickbar updated.
+quickbar-usage = Usage: /quickbar on or /quickbar off
+quickbar-new-recipe-hint = 📸 Send me a photo of your recipe. Add a caption to name it.
+
+# Per-message language detection
+language-auto-enabled = 🌐 Auto language enabled. I'll reply to your messages in the language they're written in; buttons keep your usual language.
+language-auto-disabled = 🌐 Auto language disabled. I'll always reply in your usual language.
+language-usage = Usage: /language, /language en, /language fr, /language auto or /language off
+language-prompt = 🌐 Which language should I reply in?
+language-set = 🌐 I'll reply in English from now on.
+language-name = English
+
+# Admin experiment stats
+admin-stats-title = Review keyboard experiment
+admin-stats-experiment = Experiment {$experiment}, last {$days} days
+admin-stats-variant = {$variant}: {$shown} reviews, {$edits} edits ({$edits_per_review}/review), {$confirmed} confirmed ({$confirm_rate}%)
+admin-stats-no-data = No funnel events recorded yet.
+admin-stats-global-title = Bot statistics
+admin-stats-totals = Users: {$users}, recipes: {$recipes}, ingredients: {$ingredients}
+admin-stats-week = This week: {$recipes} new recipes, {$users} active users
+admin-stats-ocr = OCR success rate since startup: {$rate}% ({$successes}/{$total})
+admin-stats-ocr-none = OCR success rate since startup: no OCR run yet
+
+# Admin broadcast
+broadcast-usage = Usage: /broadcast <message>
+broadcast-header = Message from the Ingredients Bot team
+broadcast-progress = 📢 Broadcasting… delivered: {$delivered}, failed: {$failed}
+broadcast-finished = ✅ Broadcast finished. Delivered: {$delivered}, failed: {$failed}
+
+# Weekly OCR digest sent to the admin
+ocr-digest-title = Weekly OCR digest
+ocr-digest-period = Week ending {$date}, compared with the week before
+ocr-digest-success-rate = OCR success rate
+ocr-digest-median-edits = Median edits per confirmed recipe
+ocr-digest-circuit-open = Circuit breaker open
+ocr-digest-unit-candidates = Top unmatched unit candidates
+ocr-digest-none = none
+ocr-digest-not-available = n/a
+
+# Admin webhook events test
+events-test-not-configured = Webhook events are disabled. Set WEBHOOK_EVENTS_URL and WEBHOOK_EVENTS_SECRET to enable them.
+events-test-delivered = Ping event delivered (attempts: {$attempts}).
+events-test-failed = Ping event could not be delivered: {$error}
+
+# Extraction problem reports
+report-problem = Report a problem
+report-comment-prompt = What went wrong with this extraction? Send a short comment, or tap Skip to report it as is.
+report-skip-comment = Skip
+report-thanks = Thanks! Your report was sent to the maintainers.
+report-expired = This extraction can no longer be reported. Please send the photo again.
+error-report-failed = Sorry, your report couldn't be saved. Please try again later.
+report-admin-summary = 🚩 Extraction report #{$report_id}
+    User: {$user}
+    Correlation id: {$correlation_id}
+    Ingredients found: {$match_count}
+    Preprocessing: {$variant}
+    Text hash: {$text_hash}
+    Comment: {$comment}
+report-admin-no-comment = (none)
+report-request-photo = Ask for the photo
+report-photo-requested = The reporter was asked to share the photo.
+report-photo-unavailable = The photo of this report can't be requested.
+report-photo-consent-prompt = A maintainer would like to see the photo from your report #{$report_id} to improve ingredient extraction. It is only shared if you agree.
+report-photo-share = Share photo
+report-photo-keep-private = Keep private
+report-photo-shared = Thanks, your photo was shared with the maintainers.
+report-photo-declined = OK, your photo will not be shared.
+report-photo-caption = Photo for extraction report #{$report_id}
+
+# Recent activity
+recent-title = Your recent activity
+recent-empty = No recent activity yet. Send me a photo of a recipe to get started!
+recent-saved = { $count ->
+    [one] Saved "{$recipe_name}" ({$count} ingredient) — {$when}
+   *[other] Saved "{$recipe_name}" ({$count} ingredients) — {$when}
+}
+recent-renamed = Renamed "{$old_name}" → "{$new_name}" — {$when}
+recent-edited = { $count ->
+    [one] Edited "{$recipe_name}" ({$count} ingredient) — {$when}
+   *[other] Edited "{$recipe_name}" ({$count} ingredients) — {$when}
+}
+unnamed-recipe = Unnamed recipe
+time-just-now = just now
+time-minutes-ago = { $count ->
+    [one] {$count} minute ago
+   *[other] {$count} minutes ago
+}
+time-hours-ago = { $count ->
+    [one] {$count} hour ago
+   *[other] {$count} hours ago
+}
+time-days-ago = { $count ->
+    [one] {$count} day ago
+   *[other] {$count} days ago
+}
+time-months-ago = { $count ->
+    [one] {$count} month ago
+   *[other] {$count} months ago
+}
+time-years-ago = { $count ->
+    [one] {$count} year ago
+   *[other] {$count} years ago
+}
+
+# Bulk recipe deletion
+bulk-delete-select = Manage
+bulk-delete-title = Manage recipes
+bulk-delete-instructions = Tap recipes to select them; your selection is kept when you change pages. Delete the selected recipes at once, or exit manage mode to go back to your list.
+bulk-delete-selected = { $count ->
+    [one] {$count} recipe selected
+   *[other] {$count} recipes selected
+}
+bulk-delete-selected-button = Delete selected ({$count})
+bulk-delete-exit = Exit manage mode
+bulk-delete-confirm-title = { $count ->
+    [one] Delete {$count} recipe?
+   *[other] Delete {$count} recipes?
+}
+bulk-delete-confirm-warning = Their ingredients will be deleted too. This action cannot be undone.
+bulk-delete-confirm-button = Yes, delete
+bulk-delete-done = { $count ->
+    [one] Deleted {$count} recipe.
+   *[other] Deleted {$count} recipes.
+}
+
+# Trash of deleted recipes (/trash)
+recipe-trashed = 🗑️ Recipe moved to the trash. Restore it with /trash within {$days} days.
+trash-title = Trash
+trash-intro = Deleted recipes can be restored with their ingredients for {$days} days, then they are removed for good.
+trash-empty = 🗑️ Your trash is empty. Deleted recipes stay here for {$days} days.
+trash-entry = {$recipe_name}, deleted {$when}
+trash-days-left = { $count ->
+    [one] {$count} day left
+   *[other] {$count} days left
+}
+trash-restore-button = Restore {$recipe_name}
+trash-restored = ✅ "{$recipe_name}" is back in your recipes with its ingredients.
+trash-restore-expired = This recipe is no longer in your trash.
+
+# Recipe export
+export-caption = { $count ->
+    [one] Your {$count} recipe, exported as JSON.
+   *[other] Your {$count} recipes, exported as JSON.
+}
+export-empty = You have no saved recipes to export yet.
+import-done = Import finished: {$created} recipes created, {$skipped} skipped because they were already saved.
+import-invalid-file = This file is not a recipe export. Send a .json file created with /export.
+import-file-too-large = This file is too large to import (maximum 5 MB).
+import-progress = Importing recipes: {$done} of {$total} saved…
+import-resume-prompt = The import of this file stopped after {$done} of {$total} recipes.
+import-resume-button = ▶️ Resume from recipe {$next}
+import-start-over-button = 🔄 Start over
+import-cancel-button = ❌ Cancel
+import-cancelled = Import cancelled. Recipes already imported were kept.
+import-resume-expired = This import is no longer in progress.
+
+# Voice messages
+voice-not-configured = 🎙️ Voice messages are not available on this bot yet. Please type the ingredient list or send a photo of it.
+voice-transcription-failed = 🎙️ I couldn't understand this voice message. Please try again, speaking clearly, or type the ingredient list.
+voice-transcription-unavailable = 🎙️ Voice messages are temporarily unavailable. Please try again in a minute.
+voice-no-ingredients = 🎙️ I heard: "{$transcript}"
+
+    I couldn't find any measurements in it. Try saying quantities and units, like "two cups of flour".
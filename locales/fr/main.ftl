@@ -4,10 +4,10 @@
 welcome-title = Bienvenue sur Ingredients Bot !
 welcome-description = Je suis votre assistant OCR qui peut extraire le texte des images. Voici ce que je peux faire :
 welcome-features =
-    📸 **Envoyez-moi des photos** de listes d'ingrédients, de recettes ou de tout texte à extraire
-    📄 **Envoyez-moi des fichiers image** (PNG, JPG, JPEG, BMP, TIFF, TIF)
-    🔍 **Je les traiterai avec OCR** et vous renverrai le texte extrait
-    💾 **Tout texte extrait est stocké** pour référence future
+    📸 Envoyez-moi des photos de listes d'ingrédients, de recettes ou de tout texte à extraire
+    📄 Envoyez-moi des fichiers image (PNG, JPG, JPEG, BMP, TIFF, TIF)
+    🔍 Je les traiterai avec OCR et vous renverrai le texte extrait
+    💾 Tout texte extrait est stocké pour référence future
 welcome-commands = Commandes :
 welcome-start = /start - Afficher ce message de bienvenue
 welcome-help = /help - Obtenir de l'aide et des instructions d'utilisation
@@ -24,6 +24,19 @@ help-limits = Limite de taille de fichier : 10 Mo pour JPEG, 5 Mo pour les autre
 help-commands = Commandes :
 help-start = /start - Message de bienvenue
 help-help = /help - Ce message d'aide
+help-cancel = /cancel - Arrêter ce que vous faites (nommer, vérifier, modifier) à tout moment
+help-recent = /recent - Vos recettes récemment enregistrées et modifiées
+help-stats = /stats - Statistiques sur toutes vos recettes : ingrédients les plus utilisés, recettes par mois
+help-language = /language - Choisir la langue de mes réponses (/language auto pour répondre dans la langue de chaque message, /language off pour annuler)
+help-export = /export - Télécharger toutes vos recettes dans un fichier JSON (renvoyez-le à n'importe quelle instance du bot pour les restaurer)
+help-find = /find <ingrédient> - Lister vos recettes qui utilisent un ingrédient
+help-tag = /recipes <étiquette> - Lister vos recettes portant une étiquette
+help-units = /units - Afficher les quantités en unités métriques ou américaines/impériales
+help-timezone = /timezone - Choisir votre fuseau horaire, utilisé pour les dates et les compteurs du jour et de la semaine
+help-digest = /digest - Recevoir un résumé hebdomadaire des recettes enregistrées
+help-inline = @bot <nom de recette> - Dans n'importe quelle discussion, tapez mon nom d'utilisateur et un nom de recette pour partager ses ingrédients
+help-trash = /trash - Restaurer une recette supprimée ces 7 derniers jours
+help-delete-all = /delete_all - Supprimer définitivement toutes vos données
 help-tips = Conseils :
 help-tip1 = • Utilisez des images claires et bien éclairées
 help-tip2 = • Assurez-vous que le texte est lisible et pas trop petit
@@ -35,17 +48,28 @@ help-final = Besoin d'aide ? Envoyez-moi simplement une image ! 😊
 error-download-failed = [DOWNLOAD] Échec du téléchargement de l'image. Veuillez réessayer.
 error-unsupported-format = [FORMAT] Format d'image non supporté. Veuillez utiliser les formats PNG, JPG, JPEG, BMP, TIFF ou TIF.
 error-no-text-found = [OCR_RESULT] Aucun texte n'a été trouvé dans l'image. Essayez avec une image plus claire contenant du texte visible.
-error-ocr-initialization = [OCR_INIT] L'initialisation du moteur OCR a échoué. Veuillez réessayer plus tard.
-error-ocr-extraction = [OCR_EXTRACT] Échec de l'extraction du texte de l'image. Essayez avec une image différente.
-error-ocr-timeout = [OCR_TIMEOUT] Le traitement OCR a expiré : {$msg}
-error-ocr-corruption = [OCR_CORRUPT] Le moteur OCR a rencontré une erreur interne. Veuillez réessayer.
-error-ocr-exhaustion = [OCR_RESOURCE] Les ressources système sont épuisées. Veuillez réessayer plus tard.
-error-validation = [VALIDATION] La validation de l'image a échoué : {$msg}
-error-image-load = [IMAGE_LOAD] Le format d'image n'est pas supporté ou l'image est corrompue. Essayez avec une image PNG, JPG ou BMP.
+error-pdf-too-large = [PDF_TOO_LARGE] Ce PDF est trop volumineux pour être traité. Veuillez envoyer un PDF plus léger, ou seulement les pages contenant la liste d'ingrédients.
+error-reply-validation = [VALIDATION] Cette image n'a pas pu être vérifiée pour la lecture.
+error-reply-unsupported-format = [FORMAT] Ce format de fichier n'est pas supporté, ou le fichier est endommagé.
+error-reply-file-too-large = [FILE_TOO_LARGE] Cette image est trop grande pour être traitée.
+error-file-too-large-limit = [FILE_TOO_LARGE] Ce fichier est trop volumineux : les fichiers jusqu'à { $max_mb } Mo peuvent être lus.
+error-image-too-large-limit = [FILE_TOO_LARGE] Cette image est trop grande : les images jusqu'à { $max_megapixels } mégapixels peuvent être lues.
+error-reply-pdf-too-large = [PDF_TOO_LARGE] Ce PDF est trop volumineux pour être traité.
+error-reply-ocr-timeout = [OCR_TIMEOUT] La lecture de cette image a pris trop de temps.
+error-reply-ocr-unavailable = [OCR_UNAVAILABLE] La lecture des images est temporairement indisponible.
+error-reply-ocr-failed = [OCR_EXTRACT] Aucun texte n'a pu être lu dans cette image.
+error-reply-database-unavailable = [DATABASE] Vos recettes sont inaccessibles pour le moment.
+error-reply-unknown = [ERROR] Une erreur est survenue.
+error-next-clearer-photo = Essayez une photo plus nette et bien éclairée de la liste d'ingrédients.
+error-next-supported-format = Envoyez une image PNG, JPG, BMP ou TIFF, ou un PDF.
+error-next-smaller-photo = Essayez une photo plus petite, par exemple un recadrage de la liste d'ingrédients.
+error-next-fewer-pages = Envoyez un PDF plus léger, ou seulement les pages contenant la liste d'ingrédients.
+error-next-wait = Veuillez réessayer dans une minute.
+error-next-retry = Veuillez réessayer.
 
 # Messages de succès
-success-extraction = ✅ **Texte extrait avec succès !**
-success-extracted-text = 📝 **Texte extrait :**
+success-extraction = ✅ Texte extrait avec succès !
+success-extracted-text = 📝 Texte extrait :
 success-photo-downloaded = Photo téléchargée avec succès ! Traitement en cours...
 success-document-downloaded = Document image téléchargé avec succès ! Traitement en cours...
 
@@ -59,10 +83,26 @@ total-ingredients-found = Total des ingrédients trouvés
 original-text = Texte extrait original
 error-processing-failed = [INGREDIENT_PROCESSING] Échec du traitement des ingrédients
 error-try-again = Veuillez réessayer avec une image différente.
+error-recipe-save-failed = [SAVE] Votre recette n'a pas pu être enregistrée, et rien n'a été conservé. Veuillez réessayer dans un instant.
 
 # Messages de traitement
 processing-photo = Photo téléchargée avec succès ! Traitement en cours...
 processing-document = Document image téléchargé avec succès ! Traitement en cours...
+processing-pdf = PDF téléchargé avec succès ! Lecture de ses premières pages...
+processing-album = { $count ->
+    [one] Photo téléchargée avec succès ! Traitement en cours...
+   *[other] Album de {$count} photos reçu ! Lecture comme une seule recette...
+}
+duplicate-photo = 🔁 Cette photo ressemble à celle que vous venez d'envoyer, elle n'a donc pas été traitée à nouveau.
+duplicate-photo-reprocess = La traiter à nouveau
+ocr-retry-profiles = Réessayer avec un autre traitement
+ocr-retry-processing = 🔁 Nouvelle lecture de la photo avec un autre traitement...
+ocr-retry-expired = ⌛ Cette photo n'est plus disponible pour un nouvel essai. Veuillez l'envoyer à nouveau.
+ocr-retry-no-ingredients = Un autre traitement n'a trouvé aucune mesure non plus. Essayez une photo plus nette et bien éclairée, prise juste au-dessus de la recette.
+rate-limit-ocr = { $count ->
+    [one] ⏳ Vous envoyez des photos plus vite qu'elles ne peuvent être traitées. Veuillez patienter {$count} seconde avant d'en envoyer une autre.
+   *[other] ⏳ Vous envoyez des photos plus vite qu'elles ne peuvent être traitées. Veuillez patienter {$count} secondes avant d'en envoyer une autre.
+}
 
 # Types de messages non supportés
 unsupported-title = 🤔 Je ne peux traiter que les messages texte et les images.
@@ -76,10 +116,13 @@ unsupported-final = Essayez d'envoyer une image avec du texte ! 📝
 # Réponses texte régulières
 text-response = Reçu : {$text}
 text-tip = 💡 Conseil : Envoyez-moi une image avec du texte pour l'extraire avec OCR !
+text-ingredients-detected = 📋 Cela ressemble à une liste d'ingrédients. Vérifiez-la ci-dessous, puis confirmez pour l'enregistrer comme recette.
+unknown-command = Commande inconnue. Envoyez /help pour voir ce que je sais faire.
 
 # Messages de dialogue pour le nom de recette
 recipe-name-prompt = 🏷️ Comment souhaitez-vous nommer cette recette ?
 recipe-name-prompt-hint = Veuillez entrer un nom pour votre recette (par ex. "Cookies aux pépites de chocolat", "Lasagnes de Maman")
+recipe-name-suggestions-hint = Touchez un de vos noms récents ci-dessous, ou tapez-en un nouveau.
 recipe-name-invalid = [RECIPE_NAME] Le nom de recette ne peut pas être vide. Veuillez entrer un nom valide pour votre recette.
 recipe-name-too-long = [RECIPE_NAME] Le nom de recette est trop long (maximum 255 caractères). Veuillez entrer un nom plus court.
 recipe-complete = ✅ Recette "{$recipe_name}" sauvegardée avec succès avec {$ingredient_count} ingrédients !
@@ -87,8 +130,33 @@ recipe-complete = ✅ Recette "{$recipe_name}" sauvegardée avec succès avec {$
 # Messages de révision des ingrédients
 review-title = Révisez vos ingrédients
 review-description = Veuillez réviser les ingrédients extraits ci-dessous. Utilisez les boutons pour modifier ou supprimer des éléments, puis confirmez quand vous êtes prêt.
+review-low-confidence-note = ⚠️ signale les lignes difficiles à lire : vérifiez-les bien.
+review-ingredients-capped = ⚠️ Seuls les { $kept } premiers des { $found } ingrédients trouvés ont été conservés.
+album-photos-failed = { $count ->
+    [one] ⚠️ Une photo de l'album n'a pas pu être lue ; les résultats proviennent des autres photos.
+   *[other] ⚠️ {$count} photos de l'album n'ont pas pu être lues ; les résultats proviennent des autres photos.
+}
 review-confirm = Confirmer et sauvegarder
 review-cancelled = Révision des ingrédients annulée. Aucun ingrédient n'a été sauvegardé.
+review-cancelled-summary = { $count ->
+    [one] Révision annulée, 1 ingrédient ignoré.
+   *[other] Révision annulée, {$count} ingrédients ignorés.
+}
+review-expired = Cette révision n'est plus active. Renvoyez la photo pour recommencer.
+flood-wait-slow-down = ⏳ Doucement, Telegram me demande d'attendre {$seconds} s avant de mettre à jour ce message.
+review-rename = Renommer
+review-recipe-name = Recette : {$recipe_name}
+review-rename-instructions = Tapez le nouveau nom de cette recette, ou "cancel" pour garder le nom actuel.
+review-rename-success = La recette sera enregistrée sous "{$recipe_name}".
+review-rename-cancelled = Nom de la recette inchangé.
+review-show-raw-text = Voir le texte brut
+raw-text-title = Texte lu sur la photo
+raw-text-truncated = La suite du texte est trop longue pour être affichée ici.
+raw-text-hide = Masquer le texte brut
+raw-text-empty = Aucun texte n'a été conservé pour cette vérification.
+undo-delete = Annuler la suppression
+adjust-quantity-done = Terminé
+adjust-quantity-not-numeric = Cette quantité n'est pas un nombre. Utilisez ✏️ pour la saisir.
 processing-cancelled = Traitement de l'image annulé. Aucun ingrédient n'a été extrait.
 review-no-ingredients = Aucun ingrédient restant
 review-no-ingredients-help = Tous les ingrédients ont été supprimés. Vous pouvez ajouter plus d'ingrédients en envoyant une autre image, ou annuler cette recette.
@@ -103,12 +171,34 @@ edit-too-long = Le texte d'ingrédient est trop long (maximum 200 caractères).
 edit-no-ingredient-name = Veuillez spécifier un nom d'ingrédient (par ex. "2 tasses de farine" et non pas seulement "2 tasses").
 edit-ingredient-name-too-long = Le nom d'ingrédient est trop long (maximum 100 caractères). Veuillez utiliser un nom plus court.
 edit-invalid-quantity = Quantité invalide. Veuillez utiliser un nombre positif (par ex. "2,5 tasses de farine").
+edit-ambiguous = Je ne sais pas quelle partie de l'ingrédient modifier. Il est actuellement :
+edit-ambiguous-hint = Copiez-le, corrigez-le et renvoyez la ligne entière, ou envoyez seulement "3" ou "3 tasses" pour changer la quantité ou l'unité.
+edit-quantity-not-understood = Je n'ai pas compris la quantité de ces lignes, elle n'a donc pas été enregistrée :
+    { $lines }
 quantity-correction-prompt = Nous n'avons pas pu lire la quantité exacte pour {$ingredient}. Veuillez taper la quantité :
 error-invalid-edit = [INGREDIENT_EDIT] Index d'ingrédient invalide pour l'édition.
 confirm = Confirmer
 cancel = Annuler
 review-help = Veuillez répondre avec "confirm" pour sauvegarder ces ingrédients, ou "cancel" pour les annuler.
 
+# Ingredient counts
+review-title-count = { $count ->
+    [one] {$count} ingrédient à vérifier
+   *[other] {$count} ingrédients à vérifier
+}
+ingredients-saved = { $count ->
+    [one] {$count} ingrédient enregistré dans "{$recipe_name}"
+   *[other] {$count} ingrédients enregistrés dans "{$recipe_name}"
+}
+ingredient-total = { $count ->
+    [one] {$count} ingrédient
+   *[other] {$count} ingrédients
+}
+review-more-ingredients = { $count ->
+    [one] … et {$count} autre ingrédient, toujours dans les boutons ci-dessous
+   *[other] … et {$count} autres ingrédients, toujours dans les boutons ci-dessous
+}
+
 # Messages de document
 document-image = Document image reçu de l'utilisateur {$user_id}
 document-non-image = Document non-image reçu de l'utilisateur {$user_id}
@@ -141,8 +231,162 @@ workflow-recipe-saved = ✅ Recette sauvegardée avec succès !
 workflow-what-next = Que souhaitez-vous faire ensuite ?
 workflow-list-recipes = Lister mes recettes
 workflow-search-recipes = Rechercher des recettes
-workflow-search-coming-soon = Recherche de recettes bientôt disponible ! Pour l'instant, utilisez le bouton 'Lister mes recettes'.
-caption-recipe-saved = Recette sauvegardée sous : "{$recipe_name}"
+
+# Recipe search
+search-prompt = 🔍 Envoyez un mot à chercher dans les noms et les ingrédients de vos recettes.
+search-empty-query = Veuillez envoyer au moins un mot à rechercher.
+search-results-title = Recettes correspondant à « { $query } »
+search-results-truncated = Affichage des { $shown } premiers résultats. Envoyez une recherche plus précise pour les affiner.
+search-no-results = Aucune recette ne correspond à « { $query } ».
+search-no-results-suggestion = Essayez un autre mot, ou affichez toutes vos recettes.
+find-usage = Indiquez l'ingrédient à chercher, par exemple /find beurre
+find-results-title = Recettes avec « { $ingredient } »
+find-result-count = { $count ->
+    [one] {$count} ingrédient correspondant
+   *[other] {$count} ingrédients correspondants
+}
+find-no-results = Aucune de vos recettes ne contient « { $ingredient } ».
+
+# Réglage des unités (/units)
+units-prompt = ⚖️ Dans quelles unités afficher les quantités des ingrédients ?
+units-original = Telles qu'écrites dans la recette
+units-metric = Métriques (g, ml)
+units-imperial = Américaines / impériales (oz, tasses)
+units-set-original = ✅ Les quantités seront affichées telles qu'écrites dans chaque recette.
+units-set-metric = ✅ Les quantités seront affichées en unités métriques.
+units-set-imperial = ✅ Les quantités seront affichées en unités américaines/impériales.
+
+# Réglage du fuseau horaire (/timezone)
+timezone-prompt = 🕐 Votre fuseau horaire est { $zone }. Choisissez le vôtre ci-dessous, ou envoyez un décalage par rapport à UTC comme /timezone +5:30.
+timezone-dst-note = Les décalages fixes ne suivent pas l'heure d'été : changez le vôtre au changement d'heure.
+timezone-set = ✅ Votre fuseau horaire est maintenant { $zone }. Les dates y sont affichées.
+timezone-invalid = Je n'ai pas compris ce fuseau horaire. Envoyez un décalage par rapport à UTC entre -12 et +14, comme /timezone +2, /timezone UTC-3 ou /timezone +5:30.
+
+# Dates
+datetime-long = { $day } { $month } { $year } à { $time }
+month-1 = janvier
+month-2 = février
+month-3 = mars
+month-4 = avril
+month-5 = mai
+month-6 = juin
+month-7 = juillet
+month-8 = août
+month-9 = septembre
+month-10 = octobre
+month-11 = novembre
+month-12 = décembre
+
+# Recipe scaling
+scale-recipe = Ajuster les quantités
+scale-prompt = ⚖️ Par combien multiplier les quantités ? Envoyez un facteur comme 0.5, 2 ou 3.
+scale-invalid-factor = Veuillez envoyer un nombre supérieur à 0 et au plus 100, comme 0.5, 2 ou 1/2.
+scale-title = { $name } × { $factor }
+scale-unscaled-footnote = ⚠️ Les ingrédients sans quantité numérique sont laissés tels quels.
+scale-save-as-new = Enregistrer comme nouvelle recette
+scale-saved = Enregistrée sous « { $name } ».
+
+# Estimation nutritionnelle d'une recette
+nutrition-button = Nutrition
+nutrition-title = Nutrition : { $name }
+nutrition-total = Total estimé
+nutrition-calories = Calories
+nutrition-protein = Protéines
+nutrition-fat = Lipides
+nutrition-carbs = Glucides
+nutrition-per-ingredient = Par ingrédient
+nutrition-none = Aucun ingrédient de cette recette n'a pu être estimé.
+nutrition-skipped = Non inclus
+nutrition-skip-no-quantity = sans quantité
+nutrition-skip-unknown-ingredient = absent de la table nutritionnelle
+nutrition-skip-unknown-unit = unité non convertible en grammes
+nutrition-disclaimer = Estimations uniquement, à partir de valeurs typiques d'ingrédients courants. Les valeurs réelles dépendent des marques, des variétés et de la cuisson.
+
+# Photo d'origine d'une recette
+show-original-photo = Voir la photo d'origine
+original-photo-unavailable = La photo d'origine de cette recette n'est plus disponible.
+
+# Partage de recettes (liens de partage)
+share-button = Partager
+share-link-created = Envoyez ce lien pour partager « { $recipe_name } ». Il ajoute une copie de la recette au compte de la première personne qui l'ouvre, et expire dans { $days } jours.
+share-received = Une recette vous a été partagée et a été ajoutée à vos recettes.
+share-expired = Ce lien de partage a expiré. Demandez-en un nouveau.
+share-already-used = Ce lien de partage a déjà été utilisé. Demandez-en un nouveau.
+share-not-found = Ce lien de partage n'est pas valide, ou la recette a été supprimée.
+share-own-recipe = C'est votre propre recette, elle est déjà dans vos recettes. Envoyez le lien à quelqu'un d'autre.
+
+# Étiquettes des recettes
+tags-button = Étiquettes
+tags-title = Étiquettes de { $name }
+tags-current = Étiquettes : { $tags }
+tags-none = Cette recette n'a pas encore d'étiquette.
+tags-instructions = Touchez une étiquette pour l'ajouter ou la retirer, ou envoyez-en une nouvelle. Listez les recettes étiquetées avec /recipes <étiquette>.
+tags-done = Terminé
+tags-added = Étiquette « { $tag } » ajoutée.
+tags-invalid-empty = Veuillez envoyer un nom d'étiquette.
+tags-invalid-too-long = Les étiquettes font au plus 32 caractères.
+tags-invalid-characters = Les étiquettes ne peuvent pas contenir de retours à la ligne ni d'autres caractères de contrôle.
+tags-recipes-title = Recettes étiquetées « { $tag } »
+tags-no-recipes = Aucune de vos recettes n'est étiquetée « { $tag } ».
+
+# Notes des recettes
+notes-button = Notes
+notes-title = Notes sur { $name }
+notes-none = Cette recette n'a pas encore de notes.
+notes-edit = Modifier
+notes-clear = Effacer
+notes-prompt = 🗒️ Envoyez vos notes pour cette recette, par exemple « moins de sucre la prochaine fois » ({ $max } caractères au plus). Elles remplacent les notes actuelles.
+notes-saved = 🗒️ Notes enregistrées.
+notes-cleared = 🗒️ Notes effacées.
+notes-invalid-empty = Veuillez envoyer le texte de vos notes, ou utilisez Effacer pour les supprimer.
+notes-invalid-too-long = Les notes font au plus { $max } caractères, les vôtres en font { $length }. Veuillez envoyer une version plus courte.
+
+# Recette en texte
+copy-as-text-button = Copier en texte
+copy-as-text-document-caption = 📋 Cette recette est trop longue pour un seul message, la voici en fichier Markdown.
+recipe-text-saved-on = Enregistrée le
+recipe-text-ingredients = Ingrédients
+recipe-text-notes = Notes
+
+# Résumé hebdomadaire (/digest)
+digest-status-on = Résumé hebdomadaire : activé, le { $day } à { $hour }h00 UTC.
+digest-status-off = Résumé hebdomadaire : désactivé.
+digest-usage = Activez-le avec /digest on (le lundi à 08h00 UTC), ou choisissez un jour et une heure (UTC) avec /digest fri 18. /digest off l'arrête.
+digest-title = Votre semaine en recettes
+digest-new-recipes = { $count ->
+    [one] {$count} nouvelle recette cette semaine
+   *[other] {$count} nouvelles recettes cette semaine
+}
+digest-total-recipes = { $count ->
+    [one] {$count} recette enregistrée au total
+   *[other] {$count} recettes enregistrées au total
+}
+digest-open-hint = Touchez une recette pour l'ouvrir. Arrêtez ces messages avec /digest off.
+weekday-mon = lundi
+weekday-tue = mardi
+weekday-wed = mercredi
+weekday-thu = jeudi
+weekday-fri = vendredi
+weekday-sat = samedi
+weekday-sun = dimanche
+
+# Mode inline (@bot <nom de recette> dans n'importe quelle discussion)
+inline-recipe-ingredients = { $count ->
+    [one] {$count} ingrédient
+   *[other] {$count} ingrédients
+}
+inline-no-recipes-title = Aucune recette trouvée
+inline-no-recipes-saved = Enregistrez d'abord une recette dans une discussion privée avec moi.
+inline-no-recipes-match = Aucune de vos recettes ne commence par « {$query} ».
+inline-no-recipes-message = Je n'ai pas trouvé de recette enregistrée à partager.
+
+# Suppression du compte (/delete_all)
+delete-all-warning = ⚠️ Ceci supprime définitivement toutes vos recettes, ingrédients et réglages. Cette action est irréversible.
+delete-all-continue = Supprimer toutes mes données
+delete-all-phrase = TOUT SUPPRIMER
+delete-all-type-phrase = Pour confirmer, tapez exactement { $phrase }. Tout autre message annule.
+delete-all-cancelled = Rien n'a été supprimé.
+delete-all-done = 🗑️ Toutes vos données ont été supprimées : { $recipes } recettes et { $ingredients } ingrédients effacés.
 
 # Messages de gestion des recettes dupliquées
 multiple-recipes-found = {$count} recettes trouvées avec ce nom :
@@ -167,6 +411,17 @@ recent-activity = Activité Récente
 recipes-today = Recettes Aujourd'hui
 recipes-this-week = Recettes Cette Semaine
 favorite-units = Unités Préférées
+recipe-units-used = Unités utilisées
+recipe-statistics-see-all = 📈 Statistiques sur toutes vos recettes : /stats
+stats-no-recipes = Vous n'avez encore aucune recette enregistrée. Envoyez une photo d'une liste d'ingrédients pour commencer !
+stats-overview = Vue d'ensemble
+stats-longest-recipe = { $count ->
+    [one] Recette la plus longue : { $name } ({ $count } ingrédient)
+   *[other] Recette la plus longue : { $name } ({ $count } ingrédients)
+}
+stats-top-ingredients = Ingrédients les plus utilisés
+stats-recipes-per-month = Recettes par mois
+stats-busiest-month = Mois le plus actif : { $month } ({ $count })
 back-to-recipe = Retour à la Recette
 
 # Messages de gestion de recette
@@ -174,7 +429,7 @@ rename-recipe-title = Renommer la recette
 rename-recipe-instructions = Entrez le nouveau nom pour cette recette :
 current-recipe-name = Nom actuel
 delete-recipe-title = Supprimer la recette
-delete-recipe-confirmation = Êtes-vous sûr de vouloir supprimer cette recette ? Cette action ne peut pas être annulée.
+delete-recipe-confirmation = Êtes-vous sûr de vouloir supprimer cette recette ? Vous pourrez encore la restaurer avec /trash pendant {$days} jours.
 recipe-deleted = Recette supprimée avec succès
 recipe-deleted-help = La recette et tous ses ingrédients ont été supprimés définitivement.
 delete-cancelled = Suppression de recette annulée
@@ -199,20 +454,227 @@ ingredients-updated = Ingrédients mis à jour avec succès
 ingredients-updated-help = Les ingrédients de votre recette ont été mis à jour.
 no-changes-made = Aucune modification n'a été apportée aux ingrédients.
 editing-cancelled = Édition des ingrédients annulée
+dialogue-cancelled = ✖️ Annulé. Envoyez-moi une photo de recette ou utilisez /recipes quand vous voulez.
+nothing-to-cancel = Rien à annuler. Envoyez-moi une photo de recette pour commencer.
+use-buttons-instruction = Veuillez utiliser les boutons ci-dessus, ou /cancel pour arrêter.
 no-ingredients-to-edit = Aucun ingrédient à modifier
 no-ingredients-to-edit-help = Cette recette n'a pas d'ingrédients à modifier. Essayez d'ajouter des ingrédients d'abord.
 error-updating-ingredients = Échec de la mise à jour des ingrédients
-error-adding-ingredients = Échec de l'ajout de nouveaux ingrédients
-error-deleting-ingredients = Échec de la suppression des ingrédients
+edit-conflict-title = Cette recette a changé depuis le début de votre modification
+edit-conflict-help = Vos modifications n'ont pas été enregistrées pour ne pas écraser les plus récentes. Rechargez pour modifier les ingrédients actuels.
+edit-conflict-reload = Recharger et modifier
 add-ingredient = Ajouter un ingrédient
 add-ingredient-prompt = Envoyez-moi le nouvel ingrédient (ex: "2 tasses de farine" ou "3 œufs")
 ingredient-added = Ingrédient ajouté avec succès !
 
+# Messages de propagation du renommage entre recettes
+rename-propagation-offer = "{$old_name}" trouvé dans {$count} autres recettes — les corriger aussi ?
+rename-propagation-preview = Recettes concernées :
+rename-propagation-more = …et {$count} de plus
+rename-propagation-accept = Renommer partout
+rename-propagation-decline = Seulement cette recette
+rename-propagation-done = "{$old_name}" renommé en "{$new_name}" dans {$count} ingrédients.
+rename-propagation-skipped = Les autres recettes n'ont pas été modifiées.
+error-rename-propagation = Échec du renommage de l'ingrédient dans vos autres recettes
+
+# Caption recipe name conflict messages
+name-conflict-found = Vous avez déjà "{$recipe_name}" (copies enregistrées : {$count}).
+name-conflict-question = Que faire de ces ingrédients ?
+name-conflict-save-new = Enregistrer une nouvelle copie
+name-conflict-replace = Remplacer la plus récente
+name-conflict-view = Voir l'existante
+name-conflict-merge = Fusionner les ingrédients
+name-conflict-replaced = Les ingrédients de votre "{$recipe_name}" le plus récent ont été remplacés.
+name-conflict-merged = { $count ->
+    [0] Votre "{$recipe_name}" le plus récent contient déjà tous ces ingrédients.
+    [one] 1 nouvel ingrédient ajouté à votre "{$recipe_name}" le plus récent.
+   *[other] {$count} nouveaux ingrédients ajoutés à votre "{$recipe_name}" le plus récent.
+}
+error-name-conflict-replace = Échec du remplacement de la recette existante. Vous pouvez toujours l'enregistrer comme nouvelle copie.
+error-name-conflict-merge = Échec de la fusion avec la recette existante. Vous pouvez toujours l'enregistrer comme nouvelle copie.
+
 # Messages d'interface d'édition focalisée
 edit-ingredient-title = Modifier l'ingrédient
 edit-ingredient-current = Actuel
-edit-ingredient-instruction = Entrez le nouveau texte d'ingrédient (ex: "3 tasses de blé entier") :
+edit-ingredient-instruction = Entrez le nouveau texte d'ingrédient (ex: "3 tasses de blé entier"), ou seulement "3" ou "3 tasses" pour changer la quantité ou l'unité :
 
 # Messages de légende photo
 caption-used = 📝 Utilisation de la légende de la photo comme nom de recette : "{$caption}"
 caption-invalid = [CAPTION] La légende de la photo était invalide, utilisation du nom par défaut : "{$default_name}"
+
+# Messages de débogage administrateur
+debug-locales-title = Bundles de localisation
+debug-locales-entry = {$language} : {$state} (tentatives : {$attempts}, ~{$kilobytes} Ko)
+debug-locales-loaded = chargé
+debug-locales-not-loaded = non chargé
+debug-locales-failed = échec, anglais utilisé
+
+# Barre d'actions rapides
+quickbar-new-recipe = 📷 Nouvelle recette
+quickbar-my-recipes = 📚 Mes recettes
+quickbar-search = 🔍 Rechercher
+quickbar-enabled = ⌨️ Barre d'actions activée. Utilisez les boutons sous le champ de texte pour les actions rapides.
+quickbar-disabled = Barre d'actions désactivée.
+quickbar-updated = ⌨️ Barre d'actions mise à jour.
+quickbar-usage = Utilisation : /quickbar on ou /quickbar off
+quickbar-new-recipe-hint = 📸 Envoyez-moi une photo de votre recette. Ajoutez une légende pour la nommer.
+
+# Détection de la langue par message
+language-auto-enabled = 🌐 Langue automatique activée. Je répondrai à vos messages dans la langue dans laquelle ils sont écrits ; les boutons gardent votre langue habituelle.
+language-auto-disabled = 🌐 Langue automatique désactivée. Je répondrai toujours dans votre langue habituelle.
+language-usage = Utilisation : /language, /language en, /language fr, /language auto ou /language off
+language-prompt = 🌐 Dans quelle langue dois-je répondre ?
+language-set = 🌐 Je répondrai désormais en français.
+language-name = Français
+
+# Statistiques d'expérience administrateur
+admin-stats-title = Expérience du clavier de révision
+admin-stats-experiment = Expérience {$experiment}, {$days} derniers jours
+admin-stats-variant = {$variant} : {$shown} révisions, {$edits} modifications ({$edits_per_review}/révision), {$confirmed} confirmées ({$confirm_rate} %)
+admin-stats-no-data = Aucun événement d'entonnoir enregistré pour le moment.
+admin-stats-global-title = Statistiques du bot
+admin-stats-totals = Utilisateurs : {$users}, recettes : {$recipes}, ingrédients : {$ingredients}
+admin-stats-week = Cette semaine : {$recipes} nouvelles recettes, {$users} utilisateurs actifs
+admin-stats-ocr = Taux de réussite OCR depuis le démarrage : {$rate} % ({$successes}/{$total})
+admin-stats-ocr-none = Taux de réussite OCR depuis le démarrage : aucun OCR effectué
+
+# Annonce des administrateurs
+broadcast-usage = Utilisation : /broadcast <message>
+broadcast-header = Message de l'équipe Ingredients Bot
+broadcast-progress = 📢 Diffusion en cours… envoyés : {$delivered}, échecs : {$failed}
+broadcast-finished = ✅ Diffusion terminée. Envoyés : {$delivered}, échecs : {$failed}
+
+# Bilan OCR hebdomadaire envoyé à l'administrateur
+ocr-digest-title = Bilan OCR hebdomadaire
+ocr-digest-period = Semaine se terminant le {$date}, comparée à la précédente
+ocr-digest-success-rate = Taux de réussite OCR
+ocr-digest-median-edits = Modifications médianes par recette confirmée
+ocr-digest-circuit-open = Disjoncteur ouvert
+ocr-digest-unit-candidates = Unités non reconnues les plus fréquentes
+ocr-digest-none = aucune
+ocr-digest-not-available = n/d
+
+# Admin webhook events test
+events-test-not-configured = Les événements webhook sont désactivés. Définissez WEBHOOK_EVENTS_URL et WEBHOOK_EVENTS_SECRET pour les activer.
+events-test-delivered = Événement ping livré (tentatives : {$attempts}).
+events-test-failed = L'événement ping n'a pas pu être livré : {$error}
+
+# Extraction problem reports
+report-problem = Signaler un problème
+report-comment-prompt = Qu'est-ce qui n'a pas fonctionné avec cette extraction ? Envoyez un court commentaire, ou appuyez sur Passer pour la signaler telle quelle.
+report-skip-comment = Passer
+report-thanks = Merci ! Votre signalement a été transmis aux mainteneurs.
+report-expired = Cette extraction ne peut plus être signalée. Veuillez renvoyer la photo.
+error-report-failed = Désolé, votre signalement n'a pas pu être enregistré. Veuillez réessayer plus tard.
+report-admin-summary = 🚩 Signalement d'extraction n°{$report_id}
+    Utilisateur : {$user}
+    Identifiant de corrélation : {$correlation_id}
+    Ingrédients trouvés : {$match_count}
+    Prétraitement : {$variant}
+    Empreinte du texte : {$text_hash}
+    Commentaire : {$comment}
+report-admin-no-comment = (aucun)
+report-request-photo = Demander la photo
+report-photo-requested = L'auteur du signalement a été invité à partager la photo.
+report-photo-unavailable = La photo de ce signalement ne peut pas être demandée.
+report-photo-consent-prompt = Un mainteneur aimerait voir la photo de votre signalement n°{$report_id} pour améliorer l'extraction des ingrédients. Elle n'est partagée que si vous l'acceptez.
+report-photo-share = Partager la photo
+report-photo-keep-private = Garder privée
+report-photo-shared = Merci, votre photo a été partagée avec les mainteneurs.
+report-photo-declined = D'accord, votre photo ne sera pas partagée.
+report-photo-caption = Photo du signalement d'extraction n°{$report_id}
+
+# Activité récente
+recent-title = Votre activité récente
+recent-empty = Aucune activité récente. Envoyez-moi la photo d'une recette pour commencer !
+recent-saved = { $count ->
+    [one] « {$recipe_name} » enregistrée ({$count} ingrédient) — {$when}
+   *[other] « {$recipe_name} » enregistrée ({$count} ingrédients) — {$when}
+}
+recent-renamed = « {$old_name} » renommée en « {$new_name} » — {$when}
+recent-edited = { $count ->
+    [one] « {$recipe_name} » modifiée ({$count} ingrédient) — {$when}
+   *[other] « {$recipe_name} » modifiée ({$count} ingrédients) — {$when}
+}
+unnamed-recipe = Recette sans nom
+time-just-now = à l'instant
+time-minutes-ago = { $count ->
+    [one] il y a {$count} minute
+   *[other] il y a {$count} minutes
+}
+time-hours-ago = { $count ->
+    [one] il y a {$count} heure
+   *[other] il y a {$count} heures
+}
+time-days-ago = { $count ->
+    [one] il y a {$count} jour
+   *[other] il y a {$count} jours
+}
+time-months-ago = { $count ->
+    [one] il y a {$count} mois
+   *[other] il y a {$count} mois
+}
+time-years-ago = { $count ->
+    [one] il y a {$count} an
+   *[other] il y a {$count} ans
+}
+
+# Suppression groupée de recettes
+bulk-delete-select = Gérer
+bulk-delete-title = Gérer les recettes
+bulk-delete-instructions = Touchez les recettes pour les sélectionner ; votre sélection est conservée d'une page à l'autre. Supprimez les recettes sélectionnées en une seule fois, ou quittez la gestion pour revenir à votre liste.
+bulk-delete-selected = { $count ->
+    [one] {$count} recette sélectionnée
+   *[other] {$count} recettes sélectionnées
+}
+bulk-delete-selected-button = Supprimer la sélection ({$count})
+bulk-delete-exit = Quitter la gestion
+bulk-delete-confirm-title = { $count ->
+    [one] Supprimer {$count} recette ?
+   *[other] Supprimer {$count} recettes ?
+}
+bulk-delete-confirm-warning = Leurs ingrédients seront aussi supprimés. Cette action est irréversible.
+bulk-delete-confirm-button = Oui, supprimer
+bulk-delete-done = { $count ->
+    [one] {$count} recette supprimée.
+   *[other] {$count} recettes supprimées.
+}
+
+# Corbeille des recettes supprimées (/trash)
+recipe-trashed = 🗑️ Recette placée dans la corbeille. Restaurez-la avec /trash dans les {$days} jours.
+trash-title = Corbeille
+trash-intro = Les recettes supprimées peuvent être restaurées avec leurs ingrédients pendant {$days} jours, puis elles sont supprimées définitivement.
+trash-empty = 🗑️ Votre corbeille est vide. Les recettes supprimées y restent {$days} jours.
+trash-entry = {$recipe_name}, supprimée {$when}
+trash-days-left = { $count ->
+    [one] {$count} jour restant
+   *[other] {$count} jours restants
+}
+trash-restore-button = Restaurer {$recipe_name}
+trash-restored = ✅ « {$recipe_name} » est de retour dans vos recettes avec ses ingrédients.
+trash-restore-expired = Cette recette n'est plus dans votre corbeille.
+
+# Export des recettes
+export-caption = { $count ->
+    [one] Votre recette, exportée en JSON.
+   *[other] Vos {$count} recettes, exportées en JSON.
+}
+export-empty = Vous n'avez encore aucune recette enregistrée à exporter.
+import-done = Import terminé : {$created} recettes créées, {$skipped} ignorées car déjà enregistrées.
+import-invalid-file = Ce fichier n'est pas un export de recettes. Envoyez un fichier .json créé avec /export.
+import-file-too-large = Ce fichier est trop volumineux pour être importé (5 Mo maximum).
+import-progress = Import des recettes : {$done} sur {$total} enregistrées…
+import-resume-prompt = L'import de ce fichier s'est arrêté après {$done} recettes sur {$total}.
+import-resume-button = ▶️ Reprendre à la recette {$next}
+import-start-over-button = 🔄 Recommencer
+import-cancel-button = ❌ Annuler
+import-cancelled = Import annulé. Les recettes déjà importées ont été conservées.
+import-resume-expired = Cet import n'est plus en cours.
+
+# Messages vocaux
+voice-not-configured = 🎙️ Les messages vocaux ne sont pas encore disponibles sur ce bot. Veuillez écrire la liste d'ingrédients ou en envoyer une photo.
+voice-transcription-failed = 🎙️ Je n'ai pas compris ce message vocal. Veuillez réessayer en parlant distinctement, ou écrire la liste d'ingrédients.
+voice-transcription-unavailable = 🎙️ Les messages vocaux sont temporairement indisponibles. Veuillez réessayer dans une minute.
+voice-no-ingredients = 🎙️ J'ai entendu : « {$transcript} »
+
+    Je n'y ai trouvé aucune mesure. Essayez d'indiquer les quantités et les unités, par exemple « deux tasses de farine ».
@@ -16,11 +16,16 @@ struct MeasurementUnits {
 
 #[derive(Debug, Deserialize)]
 struct Units {
-    volume_units: Vec<String>,
-    weight_units: Vec<String>,
-    volume_units_metric: Vec<String>,
-    us_units: Vec<String>,
-    french_units: Vec<String>,
+    volume_units: Vec<UnitEntry>,
+    weight_units: Vec<UnitEntry>,
+    volume_units_metric: Vec<UnitEntry>,
+    us_units: Vec<UnitEntry>,
+    french_units: Vec<UnitEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnitEntry {
+    name: String,
 }
 
 #[derive(Debug)]
@@ -58,7 +63,7 @@ impl TrainingDataGenerator {
             .chain(&units.measurement_units.volume_units_metric)
             .chain(&units.measurement_units.us_units)
             .chain(&units.measurement_units.french_units)
-            .cloned()
+            .map(|entry| entry.name.clone())
             .collect();
 
         let ingredients: Vec<String> = all_words
@@ -108,7 +113,7 @@ impl TrainingDataGenerator {
             .choose(&mut self.rng)
             .expect("should have ingredients to choose from");
 
-        format!("{} {} {}", quantity, unit, ingredient)
+        format!("{} {} {}", quantity, unit.name, ingredient)
     }
 
     fn generate_recipe(&mut self, num_items: usize) -> Vec<String> {
@@ -0,0 +1,171 @@
+//! Account deletion behind the `/delete_all` command
+//!
+//! Wiping is confirmed twice: a button first, then a phrase the user has to type
+//! while the dialogue is in `ConfirmingAccountWipe`. Anything else typed cancels.
+//! All rows of the user are then deleted in one transaction (see
+//! `crate::db::delete_all_user_data`) and the cached copies are dropped.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardMarkup};
+use tracing::{debug, info};
+
+use crate::db::TelegramId;
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+
+use super::ui_components::{create_localized_button, create_localized_button_with_emoji};
+
+/// Whether the typed text is the confirmation phrase of the language it was asked in
+pub fn is_wipe_confirmation(
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    language_code: Option<&str>,
+) -> bool {
+    text.trim() == t_lang(localization, "delete-all-phrase", language_code)
+}
+
+/// Handle the /delete_all command by asking for a first confirmation
+pub async fn handle_delete_all_command(
+    bot: &Bot,
+    msg: &Message,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /delete_all command");
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![create_localized_button_with_emoji(
+            localization,
+            "🗑️",
+            "delete-all-continue",
+            "delete_all:confirm".to_string(),
+            language_code,
+        )],
+        vec![create_localized_button(
+            localization,
+            "cancel",
+            "delete_all:cancel".to_string(),
+            language_code,
+        )],
+    ]);
+    bot.send_message(
+        msg.chat.id,
+        t_lang(localization, "delete-all-warning", language_code),
+    )
+    .reply_markup(keyboard)
+    .await?;
+    Ok(())
+}
+
+/// Handle the buttons of the /delete_all warning
+///
+/// Confirming asks for the phrase instead of deleting anything yet.
+pub async fn handle_delete_all_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    match data {
+        "delete_all:confirm" => {
+            bot.edit_message_text(
+                chat_id,
+                message.id(),
+                t_args_lang(
+                    localization,
+                    "delete-all-type-phrase",
+                    &[(
+                        "phrase",
+                        &t_lang(localization, "delete-all-phrase", language_code),
+                    )],
+                    language_code,
+                ),
+            )
+            .await?;
+            dialogue
+                .update(RecipeDialogueState::ConfirmingAccountWipe {
+                    language_code: language_code.map(str::to_string),
+                })
+                .await?;
+        }
+        _ => {
+            bot.edit_message_text(
+                chat_id,
+                message.id(),
+                t_lang(localization, "delete-all-cancelled", language_code),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete the user's data if the text typed while in `ConfirmingAccountWipe` is the phrase
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_wipe_confirmation_input(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    cache: Option<&crate::cache::SharedCacheManager>,
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    language_code: Option<&str>,
+) -> Result<()> {
+    dialogue.update(RecipeDialogueState::Start).await?;
+
+    if !is_wipe_confirmation(localization, text, language_code) {
+        debug!(user_id = %msg.chat.id, "Account wipe not confirmed");
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "delete-all-cancelled", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let telegram_id = TelegramId(msg.chat.id.0);
+    let deleted = crate::db::delete_all_user_data(pool, telegram_id).await?;
+    if let Some(cache) = cache {
+        let backend = {
+            let mut cache = cache.lock();
+            cache.invalidate_user(telegram_id);
+            cache.backend()
+        };
+        crate::cache_backend::invalidate_user(backend.as_ref(), telegram_id).await;
+    }
+    super::unit_settings::forget_unit_preference(msg.chat.id);
+    super::timezone_settings::forget_timezone(msg.chat.id);
+    super::language_settings::forget_language_preference(telegram_id);
+    info!(
+        user_id = %msg.chat.id,
+        recipes = deleted.recipes,
+        ingredients = deleted.ingredients,
+        "User data wiped on request"
+    );
+
+    bot.send_message(
+        msg.chat.id,
+        t_args_lang(
+            localization,
+            "delete-all-done",
+            &[
+                ("recipes", &deleted.recipes.to_string()),
+                ("ingredients", &deleted.ingredients.to_string()),
+            ],
+            language_code,
+        ),
+    )
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Admin announcement sent to every user (`/broadcast <message>`)
+//!
+//! Only telegram ids listed in `ADMIN_TELEGRAM_IDS` (or `ADMIN_IDS`) reach this
+//! module; message_handler answers everyone else as for an unknown command.
+//!
+//! The broadcast runs in a background task so the admin's chat stays responsive.
+//! Users are read from the database in batches ordered by telegram id, each one gets
+//! the admin's text under a header in their own language, and sends are paced to
+//! stay under Telegram's limit of about 30 messages per second. After each batch the
+//! admin's progress message is edited with the running counts, and it ends with the
+//! final delivered/failed report.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::MessageId;
+use tracing::{debug, info, warn};
+
+use crate::db::TelegramId;
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+
+use super::bot_utils::send_with_retry;
+
+/// Users loaded from the database per batch
+pub const BROADCAST_BATCH_SIZE: i64 = 100;
+
+/// Pause between two sends, well under Telegram's broadcast limit
+pub const BROADCAST_SEND_PACING: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Parse the text of a /broadcast command
+///
+/// Returns the trimmed announcement, which is empty for a bare `/broadcast`, or
+/// `None` for text that is not a /broadcast command.
+pub fn parse_broadcast_command(text: &str) -> Option<&str> {
+    let argument = text.strip_prefix("/broadcast")?;
+    if !argument.is_empty() && !argument.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(argument.trim())
+}
+
+/// Running counts of a broadcast
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BroadcastProgress {
+    pub delivered: usize,
+    pub failed: usize,
+}
+
+impl BroadcastProgress {
+    /// Users handled so far
+    pub fn total(&self) -> usize {
+        self.delivered + self.failed
+    }
+}
+
+/// Announcement as received by one user: a localized header above the admin's text
+pub fn format_broadcast_notice(
+    message: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    format!(
+        "📢 {}\n\n{}",
+        t_lang(localization, "broadcast-header", language_code),
+        message
+    )
+}
+
+/// Text of the admin's progress message, while running or once `finished`
+pub fn format_broadcast_progress(
+    progress: BroadcastProgress,
+    finished: bool,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    let key = if finished {
+        "broadcast-finished"
+    } else {
+        "broadcast-progress"
+    };
+    t_args_lang(
+        localization,
+        key,
+        &[
+            ("delivered", &progress.delivered.to_string()),
+            ("failed", &progress.failed.to_string()),
+        ],
+        language_code,
+    )
+}
+
+/// Send `message` to every user, editing the admin's progress message as it goes
+///
+/// A failed send is counted and logged without stopping the broadcast. An error
+/// loading a batch of users ends it early, with the counts reached so far.
+pub async fn run_broadcast(
+    bot: &Bot,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    message: &str,
+    progress_chat: ChatId,
+    progress_message: MessageId,
+    admin_language_code: Option<&str>,
+) -> BroadcastProgress {
+    let mut progress = BroadcastProgress::default();
+    let mut after: Option<TelegramId> = None;
+
+    loop {
+        let users = match crate::db::get_users_batch(pool, after, BROADCAST_BATCH_SIZE).await {
+            Ok(users) => users,
+            Err(e) => {
+                warn!(error = %e, delivered = progress.delivered, "Failed to load users for broadcast");
+                break;
+            }
+        };
+        let Some(last) = users.last() else {
+            break;
+        };
+        after = Some(last.telegram_id);
+
+        for user in &users {
+            if progress.total() > 0 {
+                tokio::time::sleep(BROADCAST_SEND_PACING).await;
+            }
+            let notice =
+                format_broadcast_notice(message, localization, Some(user.language_code.as_str()));
+            match send_with_retry(bot.send_message(ChatId(user.telegram_id.0), notice)).await {
+                Ok(_) => progress.delivered += 1,
+                Err(e) => {
+                    debug!(telegram_id = %user.telegram_id, error = %e, "Failed to deliver broadcast");
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        if let Err(e) = bot
+            .edit_message_text(
+                progress_chat,
+                progress_message,
+                format_broadcast_progress(progress, false, localization, admin_language_code),
+            )
+            .await
+        {
+            debug!(error = %e, "Failed to update broadcast progress");
+        }
+
+        if (users.len() as i64) < BROADCAST_BATCH_SIZE {
+            break;
+        }
+    }
+
+    if let Err(e) = send_with_retry(bot.edit_message_text(
+        progress_chat,
+        progress_message,
+        format_broadcast_progress(progress, true, localization, admin_language_code),
+    ))
+    .await
+    {
+        warn!(error = %e, "Failed to send broadcast report");
+    }
+    info!(
+        delivered = progress.delivered,
+        failed = progress.failed,
+        "Broadcast finished"
+    );
+    progress
+}
+
+/// Handle the /broadcast <message> admin command
+///
+/// Replies with a progress message and starts the broadcast in the background.
+pub async fn handle_broadcast_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let message = parse_broadcast_command(text).unwrap_or_default();
+    if message.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "broadcast-usage", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    info!(admin_id = %msg.chat.id, message_length = message.len(), "Starting broadcast");
+    let progress_message = bot
+        .send_message(
+            msg.chat.id,
+            format_broadcast_progress(
+                BroadcastProgress::default(),
+                false,
+                localization,
+                language_code,
+            ),
+        )
+        .await?;
+
+    let bot = bot.clone();
+    let localization = Arc::clone(localization);
+    let message = message.to_string();
+    let admin_language_code = language_code.map(str::to_string);
+    let chat_id = msg.chat.id;
+    tokio::spawn(async move {
+        run_broadcast(
+            &bot,
+            &pool,
+            &localization,
+            &message,
+            chat_id,
+            progress_message.id,
+            admin_language_code.as_deref(),
+        )
+        .await;
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_broadcast_command() {
+        assert_eq!(
+            parse_broadcast_command("/broadcast  Maintenance tonight "),
+            Some("Maintenance tonight")
+        );
+        assert_eq!(
+            parse_broadcast_command("/broadcast Line one\nLine two"),
+            Some("Line one\nLine two")
+        );
+        assert_eq!(parse_broadcast_command("/broadcast"), Some(""));
+        assert_eq!(parse_broadcast_command("/broadcasts hello"), None);
+        assert_eq!(parse_broadcast_command("/recipes"), None);
+    }
+
+    #[test]
+    fn test_broadcast_progress_total() {
+        let progress = BroadcastProgress {
+            delivered: 41,
+            failed: 2,
+        };
+        assert_eq!(progress.total(), 43);
+        assert_eq!(BroadcastProgress::default().total(), 0);
+    }
+}
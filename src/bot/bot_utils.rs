@@ -0,0 +1,340 @@
+//! Bot utilities for talking to the Telegram API
+//!
+//! Sends and edits that follow a database write must not be lost to a network
+//! blip, or the user hears nothing although their recipe was saved.
+//! [`send_with_retry`] sends such requests again with bounded exponential backoff
+//! when the failure is transient, and gives up at once when it is not.
+
+use std::future::Future;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::requests::{HasPayload, Output, Payload};
+use teloxide::{ApiError, RequestError};
+use tracing::{debug, warn};
+
+use crate::observability;
+
+/// Default number of attempts, including the first one
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Default delay before the first retry, doubled after each failure
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Default longest delay worth waiting before a retry
+///
+/// A flood wait longer than this is not waited out, so a handler never hangs on it.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How failed Telegram requests are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_delay: DEFAULT_MAX_DELAY,
+        }
+    }
+}
+
+/// Whether a failed request may succeed when sent again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// The request will keep failing, e.g. message not modified or chat not found
+    GiveUp,
+    /// Transient failure such as a timeout or a Telegram server error
+    Backoff,
+    /// Flood control: Telegram asks to wait this long first
+    After(Duration),
+}
+
+impl RetryDecision {
+    fn reason(self) -> &'static str {
+        match self {
+            RetryDecision::GiveUp => "permanent",
+            RetryDecision::Backoff => "transient",
+            RetryDecision::After(_) => "retry_after",
+        }
+    }
+}
+
+/// Descriptions Telegram gives to its own server errors (HTTP 5xx)
+const SERVER_ERROR_DESCRIPTIONS: &[&str] = &[
+    "Internal Server Error",
+    "Bad Gateway",
+    "Service Unavailable",
+    "Gateway Timeout",
+];
+
+/// Classify a failed request
+pub fn retry_decision(error: &RequestError) -> RetryDecision {
+    match error {
+        RequestError::RetryAfter(seconds) => RetryDecision::After(seconds.duration()),
+        // Timeouts, refused connections, and proxies answering 5xx with an HTML page
+        RequestError::Network(_) | RequestError::Io(_) | RequestError::InvalidJson { .. } => {
+            RetryDecision::Backoff
+        }
+        RequestError::Api(ApiError::Unknown(description))
+            if SERVER_ERROR_DESCRIPTIONS
+                .iter()
+                .any(|server_error| description.contains(server_error)) =>
+        {
+            RetryDecision::Backoff
+        }
+        RequestError::Api(_) | RequestError::MigrateToChatId(_) => RetryDecision::GiveUp,
+    }
+}
+
+/// Wait Telegram imposed when a handler failed on flood control, `None` for other errors
+///
+/// Flood waits are not failures to report: the user is asked to slow down instead.
+pub fn flood_wait(error: &anyhow::Error) -> Option<Duration> {
+    error
+        .chain()
+        .find_map(|cause| match cause.downcast_ref::<RequestError>() {
+            Some(RequestError::RetryAfter(seconds)) => Some(seconds.duration()),
+            _ => None,
+        })
+}
+
+/// Whether Telegram no longer recognizes a file id it was sent
+///
+/// Files are forgotten after a long time; such ids cannot be used again.
+pub fn is_unknown_file(error: &RequestError) -> bool {
+    match error {
+        RequestError::Api(
+            ApiError::WrongFileId | ApiError::WrongFileIdOrUrl | ApiError::FileIdInvalid,
+        ) => true,
+        RequestError::Api(ApiError::Unknown(description)) => {
+            description.contains("file identifier") || description.contains("file id")
+        }
+        _ => false,
+    }
+}
+
+/// Run `send` until it succeeds, fails for good, or runs out of attempts
+///
+/// `method` labels the retry and failure metrics. Retryable failures wait
+/// `initial_backoff`, doubled after each attempt, or the delay Telegram asks for.
+pub async fn retry_request<T, F, Fut>(
+    policy: &RetryPolicy,
+    method: &str,
+    mut send: F,
+) -> Result<T, RequestError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, RequestError>>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut backoff = policy.initial_backoff;
+
+    for attempt in 1..=max_attempts {
+        let error = match send().await {
+            Ok(output) => return Ok(output),
+            Err(error) => error,
+        };
+
+        let decision = retry_decision(&error);
+        let delay = match decision {
+            RetryDecision::GiveUp => None,
+            RetryDecision::Backoff => Some(backoff),
+            RetryDecision::After(delay) => Some(delay),
+        }
+        .filter(|delay| attempt < max_attempts && *delay <= policy.max_delay);
+
+        let Some(delay) = delay else {
+            let retryable = decision != RetryDecision::GiveUp;
+            if retryable {
+                warn!(method, attempt, error = %error, "Telegram request failed, giving up");
+            }
+            observability::record_telegram_send_failure(method, retryable);
+            return Err(error);
+        };
+
+        debug!(method, attempt, delay_ms = delay.as_millis() as u64, error = %error, "Telegram request failed, retrying");
+        observability::record_telegram_send_retry(method, decision.reason());
+        tokio::time::sleep(delay).await;
+        backoff = backoff.saturating_mul(2);
+    }
+
+    unreachable!("the last attempt always returns")
+}
+
+/// Send a Telegram request, retrying transient failures with the default policy
+///
+/// ```ignore
+/// send_with_retry(bot.send_message(chat_id, text).reply_markup(keyboard)).await?;
+/// ```
+pub async fn send_with_retry<R>(request: R) -> Result<Output<R>, RequestError>
+where
+    R: Request<Err = RequestError>,
+{
+    let method = <<R as HasPayload>::Payload as Payload>::NAME;
+    retry_request(&RetryPolicy::default(), method, || request.send_ref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use teloxide::types::Seconds;
+
+    /// A fake send call failing with the given errors before succeeding
+    struct MockSend {
+        failures: std::sync::Mutex<Vec<RequestError>>,
+        calls: AtomicU32,
+    }
+
+    impl MockSend {
+        fn failing_with(mut failures: Vec<RequestError>) -> Self {
+            failures.reverse();
+            Self {
+                failures: std::sync::Mutex::new(failures),
+                calls: AtomicU32::new(0),
+            }
+        }
+
+        async fn send(&self) -> Result<&'static str, RequestError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.failures.lock().unwrap().pop() {
+                Some(error) => Err(error),
+                None => Ok("sent"),
+            }
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    fn timeout() -> RequestError {
+        RequestError::Io(Arc::new(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "timed out",
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let mock = MockSend::failing_with(vec![
+            timeout(),
+            RequestError::Api(ApiError::Unknown("Bad Gateway".to_string())),
+        ]);
+
+        let result = retry_request(&fast_policy(), "SendMessage", || mock.send()).await;
+
+        assert_eq!(result.unwrap(), "sent");
+        assert_eq!(mock.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_waits_the_requested_delay() {
+        let mock = MockSend::failing_with(vec![RequestError::RetryAfter(Seconds::from_seconds(1))]);
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(5),
+            ..fast_policy()
+        };
+
+        let started = std::time::Instant::now();
+        let result = retry_request(&policy, "SendMessage", || mock.send()).await;
+
+        assert!(result.is_ok());
+        assert_eq!(mock.calls(), 2);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_longer_than_max_delay_gives_up() {
+        let mock =
+            MockSend::failing_with(vec![RequestError::RetryAfter(Seconds::from_seconds(3600))]);
+
+        let result = retry_request(&fast_policy(), "SendMessage", || mock.send()).await;
+
+        assert!(matches!(result, Err(RequestError::RetryAfter(_))));
+        assert_eq!(mock.calls(), 1);
+    }
+
+    #[test]
+    fn test_flood_wait_classification() {
+        let flood: anyhow::Error = RequestError::RetryAfter(Seconds::from_seconds(7)).into();
+        assert_eq!(flood_wait(&flood), Some(Duration::from_secs(7)));
+
+        // Found behind the context handlers add
+        let wrapped = flood.context("Failed to edit recipes page");
+        assert_eq!(flood_wait(&wrapped), Some(Duration::from_secs(7)));
+
+        for error in [
+            anyhow::Error::from(timeout()),
+            RequestError::Api(ApiError::MessageNotModified).into(),
+            anyhow::anyhow!("Recipe not found"),
+        ] {
+            assert_eq!(flood_wait(&error), None, "{error}");
+        }
+        assert_eq!(
+            retry_decision(&RequestError::RetryAfter(Seconds::from_seconds(7))),
+            RetryDecision::After(Duration::from_secs(7))
+        );
+    }
+
+    #[test]
+    fn test_unknown_file_classification() {
+        for error in [
+            RequestError::Api(ApiError::WrongFileId),
+            RequestError::Api(ApiError::WrongFileIdOrUrl),
+            RequestError::Api(ApiError::FileIdInvalid),
+            RequestError::Api(ApiError::Unknown(
+                "Bad Request: wrong remote file identifier specified: Wrong padding length"
+                    .to_string(),
+            )),
+        ] {
+            assert!(is_unknown_file(&error), "{error}");
+        }
+        for error in [
+            RequestError::Api(ApiError::ChatNotFound),
+            RequestError::RetryAfter(Seconds::from_seconds(7)),
+            timeout(),
+        ] {
+            assert!(!is_unknown_file(&error), "{error}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failures_are_not_retried() {
+        for error in [
+            RequestError::Api(ApiError::MessageNotModified),
+            RequestError::Api(ApiError::ChatNotFound),
+        ] {
+            let mock = MockSend::failing_with(vec![error]);
+
+            let result = retry_request(&fast_policy(), "EditMessageText", || mock.send()).await;
+
+            assert!(matches!(result, Err(RequestError::Api(_))));
+            assert_eq!(mock.calls(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let mock = MockSend::failing_with(vec![timeout(), timeout(), timeout(), timeout()]);
+
+        let result = retry_request(&fast_policy(), "SendMessage", || mock.send()).await;
+
+        assert!(matches!(result, Err(RequestError::Io(_))));
+        assert_eq!(mock.calls(), 3);
+    }
+}
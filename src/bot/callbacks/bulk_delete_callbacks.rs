@@ -0,0 +1,270 @@
+//! Bulk delete callback handlers module
+//!
+//! Selection mode of the recipes list: `bulk_delete:start` switches the list to
+//! individual recipes with ✅ marks, `bulk_delete:toggle:{id}` and
+//! `bulk_delete:page:{page}` update the selection kept in the
+//! `SelectingRecipesToDelete` dialogue state, `bulk_delete:confirm` shows one combined
+//! confirmation and `bulk_delete:delete` removes the selected recipes in one
+//! transaction. `bulk_delete:cancel` restores the normal list.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::MaybeInaccessibleMessage;
+use tracing::{debug, info};
+
+use crate::bot::callbacks::workflow_callbacks::handle_recipes_pagination;
+use crate::bot::formatting::{titled, PARSE_MODE};
+use crate::bot::timezone_settings::display_timezone;
+use crate::bot::ui_builder::{
+    create_bulk_delete_confirmation_keyboard, create_bulk_delete_keyboard,
+    format_bulk_delete_confirmation,
+};
+use crate::cache::SharedCacheManager;
+use crate::db::{
+    delete_recipes, delete_recipes_cached, get_recipe_entries_by_ids,
+    get_user_recipe_entries_paginated, RecipeId, TelegramId,
+};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::events::RecipeEvent;
+use crate::localization::{t_lang, t_plural, LocalizationManager};
+
+/// Recipes shown per page in selection mode
+pub const BULK_DELETE_PAGE_SIZE: i64 = 8;
+
+/// Maximum number of recipes selected at once
+pub const MAX_BULK_DELETE_SELECTION: usize = 100;
+
+/// Toggle a recipe in the selection, ignoring new picks beyond the selection cap
+pub fn toggle_recipe_selection(selected_ids: &mut Vec<i64>, recipe_id: i64) {
+    if let Some(position) = selected_ids.iter().position(|&id| id == recipe_id) {
+        selected_ids.remove(position);
+    } else if selected_ids.len() < MAX_BULK_DELETE_SELECTION {
+        selected_ids.push(recipe_id);
+    }
+}
+
+/// Build the manage mode message HTML
+pub fn format_bulk_delete_message(
+    selected_count: usize,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    titled(
+        "🧹",
+        &t_lang(localization, "bulk-delete-title", language_code),
+        &[
+            &t_lang(localization, "bulk-delete-instructions", language_code),
+            &t_plural(
+                localization,
+                "bulk-delete-selected",
+                selected_count,
+                &[("count", &selected_count.to_string())],
+                language_code,
+            ),
+        ],
+    )
+}
+
+/// Handle all `bulk_delete:` callbacks
+pub async fn handle_bulk_delete_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    data: &str,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let Some(message @ MaybeInaccessibleMessage::Regular(msg)) = &q.message else {
+        return Ok(());
+    };
+    let action = data.strip_prefix("bulk_delete:").unwrap_or_default();
+    debug!(user_id = %msg.chat.id, action = %action, "Handling bulk delete callback");
+
+    // Selection survives page changes; any other state starts a fresh selection
+    let (mut selected_ids, mut page, language_code) = match dialogue.get().await? {
+        Some(RecipeDialogueState::SelectingRecipesToDelete {
+            selected_ids,
+            page,
+            language_code,
+        }) => (selected_ids, page, language_code),
+        _ => (Vec::new(), 0, q.from.language_code.clone()),
+    };
+
+    match action {
+        "start" => {
+            selected_ids.clear();
+            page = 0;
+        }
+        "cancel" => {
+            dialogue.update(RecipeDialogueState::Start).await?;
+            return handle_recipes_pagination(
+                bot,
+                message,
+                "page:0",
+                pool,
+                &language_code,
+                localization,
+                cache,
+            )
+            .await;
+        }
+        "confirm" if !selected_ids.is_empty() => {
+            let ids: Vec<RecipeId> = selected_ids.iter().copied().map(RecipeId).collect();
+            let entries = get_recipe_entries_by_ids(&pool, TelegramId(msg.chat.id.0), &ids).await?;
+            if !entries.is_empty() {
+                bot.edit_message_text(
+                    msg.chat.id,
+                    msg.id,
+                    format_bulk_delete_confirmation(
+                        &entries,
+                        display_timezone(msg.chat.id),
+                        language_code.as_deref(),
+                        localization,
+                    ),
+                )
+                .parse_mode(PARSE_MODE)
+                .reply_markup(create_bulk_delete_confirmation_keyboard(
+                    language_code.as_deref(),
+                    localization,
+                ))
+                .await?;
+                return Ok(());
+            }
+            // Everything selected is already gone
+            selected_ids.clear();
+        }
+        "delete" => {
+            let ids: Vec<RecipeId> = selected_ids.iter().copied().map(RecipeId).collect();
+            let telegram_id = TelegramId(msg.chat.id.0);
+            let deleted = match cache {
+                Some(cache) => delete_recipes_cached(&pool, telegram_id, &ids, cache).await?,
+                None => delete_recipes(&pool, telegram_id, &ids).await?,
+            };
+            for recipe_id in &deleted {
+                crate::events::emit(RecipeEvent::recipe_deleted(*recipe_id));
+            }
+            info!(user_id = %msg.chat.id, count = deleted.len(), "Bulk deleted recipes");
+
+            dialogue.update(RecipeDialogueState::Start).await?;
+            bot.edit_message_text(
+                msg.chat.id,
+                msg.id,
+                format!(
+                    "🗑 {}",
+                    t_plural(
+                        localization,
+                        "bulk-delete-done",
+                        deleted.len(),
+                        &[("count", &deleted.len().to_string())],
+                        language_code.as_deref(),
+                    )
+                ),
+            )
+            .await?;
+            return Ok(());
+        }
+        action => {
+            if let Some(recipe_id) = action
+                .strip_prefix("toggle:")
+                .and_then(|id| id.parse::<i64>().ok())
+            {
+                toggle_recipe_selection(&mut selected_ids, recipe_id);
+            } else if let Some(new_page) = action
+                .strip_prefix("page:")
+                .and_then(|page| page.parse::<usize>().ok())
+            {
+                page = new_page;
+            }
+            // "back" from the confirmation just redraws the selection
+        }
+    }
+
+    show_selection_page(
+        bot,
+        msg,
+        pool,
+        dialogue,
+        RecipeDialogueState::SelectingRecipesToDelete {
+            selected_ids,
+            page,
+            language_code,
+        },
+        localization,
+    )
+    .await
+}
+
+/// Redraw the selection list and store the selection in the dialogue state
+async fn show_selection_page(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    selection: RecipeDialogueState,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let RecipeDialogueState::SelectingRecipesToDelete {
+        selected_ids,
+        page,
+        language_code,
+    } = selection
+    else {
+        return Ok(());
+    };
+
+    let telegram_id = TelegramId(msg.chat.id.0);
+    let offset = page as i64 * BULK_DELETE_PAGE_SIZE;
+    let (mut entries, mut total_count) =
+        get_user_recipe_entries_paginated(&pool, telegram_id, BULK_DELETE_PAGE_SIZE, offset)
+            .await?;
+
+    // The page may have emptied since it was shown; fall back to the first one
+    let page = if entries.is_empty() && page > 0 {
+        (entries, total_count) =
+            get_user_recipe_entries_paginated(&pool, telegram_id, BULK_DELETE_PAGE_SIZE, 0).await?;
+        0
+    } else {
+        page
+    };
+
+    if entries.is_empty() {
+        dialogue.update(RecipeDialogueState::Start).await?;
+        bot.edit_message_text(
+            msg.chat.id,
+            msg.id,
+            t_lang(localization, "no-recipes-found", language_code.as_deref()),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let text =
+        format_bulk_delete_message(selected_ids.len(), language_code.as_deref(), localization);
+    let keyboard = create_bulk_delete_keyboard(
+        &entries,
+        &selected_ids,
+        page,
+        total_count,
+        BULK_DELETE_PAGE_SIZE,
+        display_timezone(msg.chat.id),
+        language_code.as_deref(),
+        localization,
+    );
+
+    dialogue
+        .update(RecipeDialogueState::SelectingRecipesToDelete {
+            selected_ids,
+            page,
+            language_code,
+        })
+        .await?;
+
+    bot.edit_message_text(msg.chat.id, msg.id, text)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
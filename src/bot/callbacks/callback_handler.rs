@@ -5,7 +5,7 @@ use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
 use teloxide::types::InlineKeyboardMarkup;
-use tracing::debug;
+use tracing::{debug, Instrument};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
@@ -22,11 +22,19 @@ use super::review_callbacks;
 // Import editing callbacks module
 use super::editing_callbacks;
 
+// Import the Postgres repositories
+use crate::repository::PgRepository;
+
 // Import observability
-use crate::observability;
+use crate::observability::{self, TelegramOperation};
+
+// Import Telegram send retries
+use crate::bot::bot_utils::{flood_wait, send_with_retry};
+use crate::bot::formatting::{HtmlMessage, PARSE_MODE};
 
 // Import localization
-use crate::localization::t_lang;
+use crate::bot::ui_builder::format_editing_title;
+use crate::localization::{t_args_lang, t_lang};
 
 /// Handle callback queries from inline keyboards
 pub async fn callback_handler(
@@ -36,136 +44,508 @@ pub async fn callback_handler(
     dialogue: RecipeDialogue,
     localization: Arc<crate::localization::LocalizationManager>,
 ) -> Result<()> {
+    handle_callback(bot, q, pool, dialogue, localization, None).await
+}
+
+/// Route a callback query, keeping cached recipe data fresh when a cache is available
+async fn handle_callback(
+    bot: Bot,
+    mut q: teloxide::types::CallbackQuery,
+    pool: Arc<PgPool>,
+    dialogue: RecipeDialogue,
+    localization: Arc<crate::localization::LocalizationManager>,
+    cache: Option<crate::cache::SharedCacheManager>,
+) -> Result<()> {
+    let cache = cache.as_ref();
     let span = crate::observability::telegram_span("callback_handler", Some(q.from.id.0 as i64));
-    let _enter = span.enter();
+    async move {
+        let start_time = std::time::Instant::now();
 
-    let start_time = std::time::Instant::now();
+        // Ingredient lists in any reply are shown in the user's unit system
+        crate::bot::unit_settings::load_unit_preference(&pool, q.from.id.into()).await;
 
-    // Check dialogue state
-    let dialogue_state = dialogue.get().await?;
-    debug!(user_id = %q.from.id, dialogue_state = ?dialogue_state, "Retrieved dialogue state");
-
-    let data = q.data.as_deref().unwrap_or("");
-
-    let result = match dialogue_state {
-        Some(RecipeDialogueState::ReviewIngredients { .. }) => {
-            review_callbacks::handle_review_ingredients_callbacks(
-                &bot,
-                &q,
-                data,
-                pool.clone(),
-                &dialogue,
-                &localization,
-            )
-            .await
-        }
-        Some(RecipeDialogueState::EditingSavedIngredients { .. }) => {
-            editing_callbacks::handle_editing_saved_ingredients_callbacks(
-                &bot,
-                &q,
-                data,
-                pool.clone(),
-                &dialogue,
-                &localization,
-            )
-            .await
+        // Handlers read the reply language from the sender, so apply the /language choice first
+        q.from.language_code =
+            crate::bot::language_settings::resolve_user_language(&pool, &q.from).await;
+
+        // Check dialogue state
+        let dialogue_state = dialogue.get().await?;
+        debug!(user_id = %q.from.id, dialogue_state = ?dialogue_state, "Retrieved dialogue state");
+        if let Some(state) = &dialogue_state {
+            crate::dialogue::record_state_size(state);
         }
-        Some(RecipeDialogueState::EditingIngredient { .. }) => {
-            handle_editing_ingredient_callbacks(&bot, &q, data, &dialogue, &localization).await
+
+        let repository = PgRepository::new(pool.clone(), cache.cloned());
+
+        let data = q.data.as_deref().unwrap_or("");
+        let operation = callback_operation(dialogue_state.as_ref(), data);
+        let interaction = observability::InteractionSpan::start(operation);
+
+        // A review button tapped after its dialogue ended would otherwise do nothing
+        let stale_review_button = matches!(dialogue_state, None | Some(RecipeDialogueState::Start))
+            && review_callbacks::is_review_keyboard_callback(data);
+        if stale_review_button {
+            observability::record_dialogue_callback_without_state(
+                dialogue_state.as_ref().map_or("none", RecipeDialogueState::name),
+            );
         }
-        Some(RecipeDialogueState::EditingSavedIngredient { .. }) => {
-            handle_editing_saved_ingredient_callbacks(&bot, &q, data, &dialogue, &localization)
+
+        let result = match dialogue_state {
+            Some(RecipeDialogueState::ReviewIngredients { .. }) => {
+                review_callbacks::handle_review_ingredients_callbacks(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
                 .await
-        }
-        _ => Ok(()), // No state-specific handling needed
-    };
-
-    // Handle general callbacks that work in any state
-    if let Some(msg) = &q.message {
-        if data.starts_with("select_recipe:") {
-            recipe_callbacks::handle_recipe_selection(
-                &bot,
-                msg,
-                data,
-                pool.clone(),
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data.starts_with("recipe_instance:") {
-            recipe_callbacks::handle_recipe_instance_selection(
-                &bot,
-                msg,
-                data,
-                pool.clone(),
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data.starts_with("recipe_action:") {
-            recipe_callbacks::handle_recipe_action(
-                &bot,
-                msg,
-                data,
-                pool.clone(),
-                &dialogue,
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data == "back_to_recipes" {
-            workflow_callbacks::handle_back_to_recipes(
-                &bot,
-                msg,
-                pool.clone(),
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data.starts_with("confirm_delete_recipe")
-            || data.starts_with("cancel_delete_recipe")
+            }
+            Some(RecipeDialogueState::EditingSavedIngredients { .. }) => {
+                editing_callbacks::handle_editing_saved_ingredients_callbacks(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            }
+            Some(RecipeDialogueState::ConfirmingRenamePropagation { .. }) => {
+                editing_callbacks::handle_rename_propagation_callbacks(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            }
+            Some(RecipeDialogueState::WaitingForRecipeNameAfterConfirm { .. }) => {
+                review_callbacks::handle_recipe_name_suggestion_callback(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            }
+            Some(RecipeDialogueState::ResolvingRecipeNameConflict { .. }) => {
+                review_callbacks::handle_name_conflict_callbacks(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            }
+            Some(RecipeDialogueState::EditingIngredient { .. }) => {
+                handle_editing_ingredient_callbacks(&bot, &q, data, &dialogue, &localization).await
+            }
+            Some(RecipeDialogueState::EditingSavedIngredient { .. }) => {
+                handle_editing_saved_ingredient_callbacks(&bot, &q, data, &dialogue, &localization)
+                    .await
+            }
+            _ => Ok(()), // No state-specific handling needed
+        };
+
+        // While Telegram makes the chat wait, page turns are answered without editing anything
+        let chat_id = q
+            .message
+            .as_ref()
+            .map_or(q.from.id.0 as i64, |msg| msg.chat().id.0);
+        let pending_flood_wait = cache
+            .and_then(|cache| cache.lock().pagination.flood_wait_remaining(chat_id))
+            .filter(|_| workflow_callbacks::is_pagination_callback(data));
+
+        // Handle general callbacks that work in any state
+        let general_result = if pending_flood_wait.is_some() {
+            Ok(())
+        } else if let Some(msg) = &q.message {
+            if recipe_callbacks::RecipeSelection::parse(data).is_some() {
+                recipe_callbacks::handle_recipe_selection(
+                    &bot,
+                    msg,
+                    data,
+                    pool.clone(),
+                    &q.from.language_code,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("recipe_instance:") {
+                recipe_callbacks::handle_recipe_instance_selection(
+                    &bot,
+                    msg,
+                    data,
+                    pool.clone(),
+                    &q.from.language_code,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("recipe_action:") {
+                recipe_callbacks::handle_recipe_action(
+                    &bot,
+                    msg,
+                    data,
+                    pool.clone(),
+                    &repository,
+                    &dialogue,
+                    &q.from.language_code,
+                    &localization,
+                )
+                .await
+            } else if data == "back_to_recipes" {
+                workflow_callbacks::handle_back_to_recipes(
+                    &bot,
+                    msg,
+                    pool.clone(),
+                    &q.from.language_code,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("confirm_delete_recipe")
+                || data.starts_with("cancel_delete_recipe")
+            {
+                recipe_callbacks::handle_delete_recipe_confirmation(
+                    &bot,
+                    msg,
+                    data,
+                    &repository,
+                    &q.from.language_code,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("bulk_delete:") {
+                super::bulk_delete_callbacks::handle_bulk_delete_callback(
+                    &bot,
+                    &q,
+                    data,
+                    pool.clone(),
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("page:") {
+                workflow_callbacks::handle_recipes_pagination(
+                    &bot,
+                    msg,
+                    data,
+                    pool,
+                    &q.from.language_code,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("recent_page:") {
+                crate::bot::recent_activity::handle_recent_pagination(
+                    &bot,
+                    msg,
+                    data,
+                    pool.clone(),
+                    &q.from.language_code,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("workflow_") {
+                workflow_callbacks::handle_workflow_button(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("delete_all:") {
+                crate::bot::account_deletion::handle_delete_all_callback(
+                    &bot,
+                    &q,
+                    data,
+                    dialogue,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("language:") {
+                crate::bot::language_settings::handle_language_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("units:") {
+                crate::bot::unit_settings::handle_units_callback(&bot, &q, data, &pool, &localization)
+                    .await
+            } else if data.starts_with("timezone:") {
+                crate::bot::timezone_settings::handle_timezone_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("trash_restore:") {
+                crate::bot::recipe_trash::handle_trash_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("scale_save:") {
+                crate::bot::scaled_recipes::handle_scale_save_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("tag:") {
+                crate::bot::recipe_tags::handle_tag_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &dialogue,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("notes:") {
+                crate::bot::recipe_notes::handle_notes_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &dialogue,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data.starts_with("tag_page:") {
+                crate::bot::recipe_tags::handle_tagged_recipes_pagination(
+                    &bot,
+                    msg,
+                    data,
+                    &pool,
+                    q.from.language_code.as_deref(),
+                    &localization,
+                )
+                .await
+            } else if data == "ocr_reprocess" {
+                crate::bot::media_handlers::handle_reprocess_photo_callback(
+                    &bot,
+                    &q,
+                    dialogue.clone(),
+                    pool.clone(),
+                    &localization,
+                )
+                .await
+            } else if data == "ocr_retry_profiles" {
+                crate::bot::image_processing::handle_retry_processing_callback(
+                    &bot,
+                    &q,
+                    &dialogue,
+                    &pool,
+                    &localization,
+                    cache,
+                )
+                .await
+            } else if data == "cancel_processing" {
+                handle_cancel_processing_button(&bot, &q, &dialogue, &localization).await
+            } else if crate::bot::ocr_raw_text::is_hide_raw_text_callback(data) {
+                crate::bot::ocr_raw_text::handle_hide_raw_text_callback(&bot, &q, data).await
+            } else if crate::import_jobs::ImportResumeChoice::from_callback_data(data).is_some() {
+                crate::bot::recipe_import::handle_import_resume_callback(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &localization,
+                )
+                .await
+            } else if data.starts_with("report_") {
+                crate::bot::problem_reports::handle_report_callbacks(
+                    &bot,
+                    &q,
+                    data,
+                    &pool,
+                    &dialogue,
+                    &localization,
+                )
+                .await
+            } else {
+                Ok(())
+            }
+        } else {
+            Ok(())
+        };
+        let result = result.and(general_result);
+
+        // A flood wait is no failure: the user is asked to slow down and may tap again later
+        let imposed_wait = match result.as_ref().err().and_then(flood_wait) {
+            Some(wait) => {
+                debug!(user_id = %q.from.id, wait_secs = wait.as_secs(), "Flood wait imposed by Telegram");
+                observability::record_error_metrics("flood_wait", "telegram_callback");
+                if let Some(cache) = cache {
+                    cache.lock().pagination.record_flood_wait(chat_id, wait);
+                }
+                Some(wait)
+            }
+            None => pending_flood_wait,
+        };
+        let result = if imposed_wait.is_some() {
+            Ok(())
+        } else {
+            result
+        };
+
+        // Answer the callback query to remove the loading state, explaining a failure
+        if let Some(wait) = imposed_wait {
+            let seconds = wait.as_secs().max(1).to_string();
+            // Answering may be refused too while the wait lasts
+            if let Err(e) = bot
+                .answer_callback_query(q.id)
+                .text(t_args_lang(
+                    &localization,
+                    "flood-wait-slow-down",
+                    &[("seconds", &seconds)],
+                    q.from.language_code.as_deref(),
+                ))
+                .await
+            {
+                debug!(user_id = %q.from.id, error = %e, "Could not answer callback during flood wait");
+            }
+        } else if let Some(e) = result
+            .as_ref()
+            .err()
+            .filter(|e| !crate::errors::is_telegram_error(e))
         {
-            recipe_callbacks::handle_delete_recipe_confirmation(
-                &bot,
-                msg,
-                data,
-                pool.clone(),
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data.starts_with("page:") {
-            workflow_callbacks::handle_recipes_pagination(
-                &bot,
-                msg,
-                data,
-                pool,
-                &q.from.language_code,
-                &localization,
-            )
-            .await?;
-        } else if data.starts_with("workflow_") {
-            workflow_callbacks::handle_workflow_button(
-                &bot,
-                &q,
-                data,
-                &pool,
-                &dialogue,
-                &localization,
-            )
-            .await?;
-        } else if data == "cancel_processing" {
-            handle_cancel_processing_button(&bot, &q, &dialogue, &localization).await?;
+            let kind = crate::errors::user_error_kind(e);
+            observability::record_error_metrics(kind.as_str(), "telegram_callback");
+            bot.answer_callback_query(q.id)
+                .text(crate::errors::user_reply(
+                    &localization,
+                    kind,
+                    q.from.language_code.as_deref(),
+                ))
+                .show_alert(true)
+                .await?;
+        } else if stale_review_button {
+            bot.answer_callback_query(q.id)
+                .text(t_lang(
+                    &localization,
+                    "review-expired",
+                    q.from.language_code.as_deref(),
+                ))
+                .await?;
+        } else {
+            // Also ends confirm and save callbacks, whose writes already happened
+            send_with_retry(bot.answer_callback_query(q.id)).await?;
         }
-    }
 
-    // Answer the callback query to remove the loading state
-    bot.answer_callback_query(q.id).await?;
+        let duration = start_time.elapsed();
+        observability::record_request_metrics("telegram_callback", 200, duration);
+        observability::record_telegram_handler_metrics(
+            operation,
+            duration,
+            interaction.trace_id().as_deref(),
+        );
 
-    let duration = start_time.elapsed();
-    observability::record_request_metrics("telegram_callback", 200, duration);
+        result
+    }
+    .instrument(span)
+    .await
+}
+
+/// Operation a callback is measured under, from the dialogue state and its data
+pub fn callback_operation(state: Option<&RecipeDialogueState>, data: &str) -> TelegramOperation {
+    match state {
+        Some(
+            RecipeDialogueState::EditingSavedIngredients { .. }
+            | RecipeDialogueState::EditingSavedIngredient { .. }
+            | RecipeDialogueState::ConfirmingRenamePropagation { .. },
+        ) => return TelegramOperation::SavedEdit,
+        Some(RecipeDialogueState::ResolvingRecipeNameConflict { .. }) => {
+            return TelegramOperation::ReviewConfirm
+        }
+        _ => {}
+    }
 
-    result
+    let is_review_deletion = data == "undo_delete"
+        || data
+            .strip_prefix("delete_")
+            .is_some_and(|index| index.parse::<usize>().is_ok());
+    if data == "confirm"
+        || data.starts_with("name_conflict_")
+        || crate::bot::ui_builder::parse_name_suggestion_callback(data).is_some()
+    {
+        TelegramOperation::ReviewConfirm
+    } else if is_review_deletion {
+        TelegramOperation::ReviewDelete
+    } else if review_callbacks::is_review_keyboard_callback(data) {
+        TelegramOperation::ReviewEdit
+    } else if workflow_callbacks::is_pagination_callback(data) {
+        TelegramOperation::Pagination
+    } else if recipe_callbacks::RecipeSelection::parse(data).is_some()
+        || data.starts_with("recipe_instance:")
+        || data == "back_to_recipes"
+    {
+        TelegramOperation::RecipeSelect
+    } else if data.starts_with("confirm_delete_recipe")
+        || data.starts_with("cancel_delete_recipe")
+        || data.starts_with("bulk_delete:")
+        || data.starts_with("delete_all:")
+        || data.starts_with("trash_restore:")
+    {
+        TelegramOperation::RecipeDelete
+    } else if data.starts_with("recipe_action:")
+        || data.starts_with("scale_save:")
+        || data.starts_with("tag:")
+        || data.starts_with("notes:")
+    {
+        TelegramOperation::RecipeAction
+    } else if data.starts_with("workflow_") {
+        TelegramOperation::Workflow
+    } else if data.starts_with("language:")
+        || data.starts_with("units:")
+        || data.starts_with("timezone:")
+    {
+        TelegramOperation::Settings
+    } else if matches!(
+        data,
+        "ocr_reprocess" | "ocr_retry_profiles" | "cancel_processing"
+    ) {
+        TelegramOperation::Ocr
+    } else if data.starts_with("report_") {
+        TelegramOperation::Report
+    } else if crate::import_jobs::ImportResumeChoice::from_callback_data(data).is_some() {
+        // The import of a sent document, resumed or restarted
+        TelegramOperation::Document
+    } else {
+        TelegramOperation::OtherCallback
+    }
 }
 
 /// Cache-enabled callback handler for improved performance
@@ -178,11 +558,9 @@ pub async fn callback_handler_with_cache(
     pool: Arc<PgPool>,
     dialogue: RecipeDialogue,
     localization: Arc<crate::localization::LocalizationManager>,
-    _cache: Arc<std::sync::Mutex<crate::cache::CacheManager>>,
+    cache: crate::cache::SharedCacheManager,
 ) -> Result<()> {
-    // For now, delegate to the original handler
-    // TODO: Integrate caching into specific operations
-    callback_handler(bot, q, pool, dialogue, localization).await
+    handle_callback(bot, q, pool, dialogue, localization, Some(cache)).await
 }
 
 /// Handle callbacks when in EditingIngredient dialogue state
@@ -224,21 +602,21 @@ async fn handle_editing_ingredient_callbacks(
                 );
 
                 // Restore the original recipe display
-                let review_message = format!(
-                    "📝 **{}**\n\n{}\n\n{}",
-                    t_lang(localization, "review-title", language_code.as_deref()),
-                    t_lang(localization, "review-description", language_code.as_deref()),
-                    crate::bot::format_ingredients_list(
-                        &ingredients,
-                        language_code.as_deref(),
-                        localization
-                    )
+                let review_message = crate::bot::format_review_message(
+                    &ingredients,
+                    recipe_name_from_caption.as_deref(),
+                    crate::bot::unit_settings::display_units(q.from.id.into()),
+                    language_code.as_deref(),
+                    localization,
                 );
 
-                let keyboard = crate::bot::create_ingredient_review_keyboard(
+                let keyboard = crate::bot::create_ingredient_review_keyboard_for_variant(
                     &ingredients,
                     language_code.as_deref(),
                     localization,
+                    crate::experiments::review_keyboard_variant(crate::db::TelegramId(
+                        q.from.id.0 as i64,
+                    )),
                 );
 
                 // Use the original message ID to restore the recipe display
@@ -249,6 +627,7 @@ async fn handle_editing_ingredient_callbacks(
                             teloxide::types::MessageId(original_msg_id),
                             review_message.clone(),
                         )
+                        .parse_mode(PARSE_MODE)
                         .reply_markup(keyboard.clone())
                         .await
                     {
@@ -262,6 +641,7 @@ async fn handle_editing_ingredient_callbacks(
                             );
                             // Fallback: send new message if editing fails
                             bot.send_message(msg.chat().id, review_message)
+                                .parse_mode(PARSE_MODE)
                                 .reply_markup(keyboard)
                                 .await?;
                         }
@@ -269,6 +649,7 @@ async fn handle_editing_ingredient_callbacks(
                 } else {
                     // No original message ID, send new message
                     bot.send_message(msg.chat().id, review_message)
+                        .parse_mode(PARSE_MODE)
                         .reply_markup(keyboard)
                         .await?;
                 }
@@ -282,6 +663,7 @@ async fn handle_editing_ingredient_callbacks(
                         message_id: original_message_id, // Use original message ID for the restored display
                         extracted_text,
                         recipe_name_from_caption, // Preserve original caption info
+                        last_deleted: None,
                     })
                     .await?;
             }
@@ -314,6 +696,7 @@ async fn handle_editing_saved_ingredient_callbacks(
         language_code,
         message_id: _,
         original_message_id,
+        ingredients_version,
     }) = dialogue_state
     {
         if data == "cancel_ingredient_editing" {
@@ -327,20 +710,27 @@ async fn handle_editing_saved_ingredient_callbacks(
                 );
 
                 // Restore the editing list view
-                let edit_message = format!(
-                    "📝 **{}**\n\n{}\n\n{}",
-                    t_lang(localization, "editing-recipe", language_code.as_deref()),
-                    t_lang(
+                let edit_message = HtmlMessage::titled(
+                    "📝",
+                    &format_editing_title(
+                        current_matches.len(),
+                        None,
+                        language_code.as_deref(),
                         localization,
-                        "editing-instructions",
-                        language_code.as_deref()
                     ),
-                    crate::bot::format_ingredients_list(
-                        &current_matches,
-                        language_code.as_deref(),
-                        localization
-                    )
-                );
+                )
+                .paragraph(&t_lang(
+                    localization,
+                    "editing-instructions",
+                    language_code.as_deref(),
+                ))
+                .paragraph_html(&crate::bot::format_ingredients_list(
+                    &current_matches,
+                    crate::bot::unit_settings::display_units(q.from.id.into()),
+                    language_code.as_deref(),
+                    localization,
+                ))
+                .build();
 
                 let keyboard = crate::bot::create_ingredient_review_keyboard(
                     &current_matches,
@@ -356,6 +746,7 @@ async fn handle_editing_saved_ingredient_callbacks(
                             teloxide::types::MessageId(original_msg_id),
                             edit_message.clone(),
                         )
+                        .parse_mode(PARSE_MODE)
                         .reply_markup(keyboard.clone())
                         .await
                     {
@@ -369,6 +760,7 @@ async fn handle_editing_saved_ingredient_callbacks(
                             );
                             // Fallback: send new message if editing fails
                             bot.send_message(msg.chat().id, edit_message)
+                                .parse_mode(PARSE_MODE)
                                 .reply_markup(keyboard)
                                 .await?;
                         }
@@ -376,6 +768,7 @@ async fn handle_editing_saved_ingredient_callbacks(
                 } else {
                     // No original message ID, send new message
                     bot.send_message(msg.chat().id, edit_message)
+                        .parse_mode(PARSE_MODE)
                         .reply_markup(keyboard)
                         .await?;
                 }
@@ -388,6 +781,8 @@ async fn handle_editing_saved_ingredient_callbacks(
                         current_matches,
                         language_code,
                         message_id: original_message_id, // Use original message ID for the restored display
+                        last_deleted: None,
+                        ingredients_version,
                     })
                     .await?;
             }
@@ -21,6 +21,7 @@ pub struct ReviewIngredientsParams<'a> {
     pub recipe_name_from_caption: Option<&'a Option<String>>,
     pub dialogue: &'a crate::dialogue::RecipeDialogue,
     pub pool: Option<&'a Arc<sqlx::postgres::PgPool>>,
+    pub cache: Option<&'a crate::cache::SharedCacheManager>,
 }
 
 /// Parameters for saved ingredients editing operations
@@ -32,9 +33,11 @@ pub struct SavedIngredientsParams<'a> {
     pub current_matches: Option<&'a mut Vec<crate::text_processing::MeasurementMatch>>,
     pub current_matches_slice: Option<&'a [crate::text_processing::MeasurementMatch]>,
     pub recipe_id: i64,
-    pub original_ingredients: &'a [crate::db::Ingredient],
+    pub original_ingredients: &'a [crate::ingredient_editing::IngredientSnapshot],
+    pub ingredients_version: i64,
     pub language_code: &'a Option<String>,
     pub message_id: Option<i32>,
     pub dialogue: &'a crate::dialogue::RecipeDialogue,
     pub pool: Option<&'a Arc<sqlx::postgres::PgPool>>,
+    pub cache: Option<&'a crate::cache::SharedCacheManager>,
 }
@@ -4,25 +4,45 @@ use anyhow::Result;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
-use tracing::error;
+use teloxide::types::InlineKeyboardMarkup;
+use tracing::info;
 
 // Import error logging utilities
 use crate::errors::error_logging;
 
+// Import typed database ids
+use crate::db::{RecipeId, TelegramId};
+
+// Import the shared cache evicted by ingredient writes
+use crate::cache::SharedCacheManager;
+
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_args_lang, t_lang};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 
 // Import UI builder functions
 use crate::bot::ui_builder::{
-    create_ingredient_review_keyboard, create_recipe_details_keyboard, format_ingredients_list,
+    create_ingredient_review_keyboard, create_quantity_adjust_keyboard,
+    create_recipe_details_keyboard, format_edit_ingredient_prompt, format_editing_title,
+    format_ingredients_list, with_undo_delete_button,
+};
+
+// Import HTML message formatting
+use crate::bot::formatting::{titled, HtmlMessage, PARSE_MODE};
+
+// Import quantity adjustment
+use crate::ingredient_editing::{
+    adjust_quantity, delete_ingredient, undo_ingredient_deletion, AdjustCallback,
 };
 
 // Import HandlerContext
 use crate::bot::HandlerContext;
 
+// Import UI components
+use crate::bot::ui_components::create_localized_button_with_emoji;
+
 // Import callback types module
 use super::callback_types::SavedIngredientsParams;
 
@@ -34,6 +54,7 @@ pub async fn handle_editing_saved_ingredients_callbacks(
     pool: Arc<PgPool>,
     dialogue: &RecipeDialogue,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     let dialogue_state = dialogue.get().await?;
     if let Some(RecipeDialogueState::EditingSavedIngredients {
@@ -42,6 +63,8 @@ pub async fn handle_editing_saved_ingredients_callbacks(
         mut current_matches,
         language_code,
         message_id,
+        last_deleted,
+        ingredients_version,
     }) = dialogue_state
     {
         if q.message.is_some() {
@@ -58,10 +81,12 @@ pub async fn handle_editing_saved_ingredients_callbacks(
                     current_matches_slice: Some(&current_matches),
                     recipe_id,
                     original_ingredients: &original_ingredients,
+                    ingredients_version,
                     language_code: &language_code,
                     message_id,
                     dialogue,
                     pool: None,
+                    cache,
                 })
                 .await?;
             } else if data.starts_with("delete_") {
@@ -77,12 +102,62 @@ pub async fn handle_editing_saved_ingredients_callbacks(
                     current_matches_slice: None,
                     recipe_id,
                     original_ingredients: &original_ingredients,
+                    ingredients_version,
                     language_code: &language_code,
                     message_id,
                     dialogue,
                     pool: None,
+                    cache,
                 })
                 .await?;
+            } else if let Some(callback) = AdjustCallback::from_callback_data(data) {
+                handle_adjust_saved_ingredient_button(
+                    SavedIngredientsParams {
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: language_code.as_deref(),
+                        },
+                        q,
+                        data: Some(data),
+                        current_matches: Some(&mut current_matches),
+                        current_matches_slice: None,
+                        recipe_id,
+                        original_ingredients: &original_ingredients,
+                        ingredients_version,
+                        language_code: &language_code,
+                        message_id,
+                        dialogue,
+                        pool: None,
+                        cache,
+                    },
+                    callback,
+                )
+                .await?;
+            } else if data == "undo_delete" {
+                handle_undo_delete_saved_ingredient_button(
+                    SavedIngredientsParams {
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: language_code.as_deref(),
+                        },
+                        q,
+                        data: None,
+                        current_matches: Some(&mut current_matches),
+                        current_matches_slice: None,
+                        recipe_id,
+                        original_ingredients: &original_ingredients,
+                        ingredients_version,
+                        language_code: &language_code,
+                        message_id,
+                        dialogue,
+                        pool: None,
+                        cache,
+                    },
+                    last_deleted,
+                )
+                .await?;
             } else if data == "confirm" {
                 handle_confirm_saved_ingredients_button(SavedIngredientsParams {
                     ctx: &HandlerContext {
@@ -96,10 +171,12 @@ pub async fn handle_editing_saved_ingredients_callbacks(
                     current_matches_slice: Some(&current_matches),
                     recipe_id,
                     original_ingredients: &original_ingredients,
+                    ingredients_version,
                     language_code: &language_code,
                     message_id,
                     dialogue,
                     pool: Some(&pool),
+                    cache,
                 })
                 .await?;
             } else if data == "add_ingredient" {
@@ -137,6 +214,7 @@ async fn handle_edit_saved_ingredient_button(params: SavedIngredientsParams<'_>)
         current_matches_slice,
         recipe_id,
         original_ingredients,
+        ingredients_version,
         language_code,
         message_id,
         dialogue,
@@ -162,27 +240,8 @@ async fn handle_edit_saved_ingredient_button(params: SavedIngredientsParams<'_>)
         );
 
         let ingredient = &current_matches[index];
-        let edit_prompt = format!(
-            "✏️ {}\n\n{}: **{} {} {}**\n\n{}",
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-title",
-                language_code.as_deref()
-            ),
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-current",
-                language_code.as_deref()
-            ),
-            ingredient.quantity,
-            ingredient.measurement.as_deref().unwrap_or(""),
-            ingredient.ingredient_name,
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-instruction",
-                language_code.as_deref()
-            )
-        );
+        let edit_prompt =
+            format_edit_ingredient_prompt(ingredient, language_code.as_deref(), ctx.localization);
 
         let keyboard = crate::bot::ui_components::create_ingredient_editing_keyboard(
             language_code.as_deref(),
@@ -204,6 +263,7 @@ async fn handle_edit_saved_ingredient_button(params: SavedIngredientsParams<'_>)
                     .id(),
                 edit_prompt.clone(),
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard.clone())
             .await
         {
@@ -225,6 +285,7 @@ async fn handle_edit_saved_ingredient_button(params: SavedIngredientsParams<'_>)
                             .id,
                         edit_prompt,
                     )
+                    .parse_mode(PARSE_MODE)
                     .reply_markup(keyboard)
                     .await?;
             }
@@ -237,6 +298,7 @@ async fn handle_edit_saved_ingredient_button(params: SavedIngredientsParams<'_>)
                 original_ingredients: original_ingredients.to_vec(),
                 current_matches: current_matches.to_vec(),
                 editing_index: index,
+                ingredients_version,
                 language_code: language_code.clone(),
                 message_id,
                 original_message_id: Some(
@@ -261,6 +323,7 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
         current_matches,
         recipe_id,
         original_ingredients,
+        ingredients_version,
         language_code,
         message_id,
         dialogue,
@@ -286,24 +349,26 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
             language_code.as_deref(),
         );
 
-        current_matches.remove(index);
+        let deleted = delete_ingredient(current_matches, index);
 
         // Check if all ingredients were deleted
         if current_matches.is_empty() {
             // All ingredients deleted - inform user and provide options
-            let empty_message = format!(
-                "🗑️ **{}**\n\n{}\n\n{}",
-                t_lang(ctx.localization, "review-title", language_code.as_deref()),
-                t_lang(
-                    ctx.localization,
-                    "review-no-ingredients",
-                    language_code.as_deref()
-                ),
-                t_lang(
-                    ctx.localization,
-                    "review-no-ingredients-help",
-                    language_code.as_deref()
-                )
+            let empty_message = titled(
+                "🗑️",
+                &t_lang(ctx.localization, "review-title", language_code.as_deref()),
+                &[
+                    &t_lang(
+                        ctx.localization,
+                        "review-no-ingredients",
+                        language_code.as_deref(),
+                    ),
+                    &t_lang(
+                        ctx.localization,
+                        "review-no-ingredients-help",
+                        language_code.as_deref(),
+                    ),
+                ],
             );
 
             let keyboard = vec![vec![
@@ -336,7 +401,12 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
                         .id(),
                     empty_message,
                 )
-                .reply_markup(teloxide::types::InlineKeyboardMarkup::new(keyboard))
+                .parse_mode(PARSE_MODE)
+                .reply_markup(with_undo_delete_button(
+                    teloxide::types::InlineKeyboardMarkup::new(keyboard),
+                    language_code.as_deref(),
+                    ctx.localization,
+                ))
                 .await
             {
                 Ok(_) => (),
@@ -351,23 +421,34 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
             }
         } else {
             // Update the message with remaining ingredients
-            let review_message = format!(
-                "✏️ **{}**\n\n{}\n\n{}",
-                t_lang(ctx.localization, "editing-recipe", language_code.as_deref()),
-                t_lang(
+            let review_message = HtmlMessage::titled(
+                "✏️",
+                &format_editing_title(
+                    current_matches.len(),
+                    None,
+                    language_code.as_deref(),
                     ctx.localization,
-                    "editing-instructions",
-                    language_code.as_deref()
                 ),
-                format_ingredients_list(
+            )
+            .paragraph(&t_lang(
+                ctx.localization,
+                "editing-instructions",
+                language_code.as_deref(),
+            ))
+            .paragraph_html(&format_ingredients_list(
+                current_matches,
+                crate::bot::unit_settings::display_units(q.from.id.into()),
+                language_code.as_deref(),
+                ctx.localization,
+            ))
+            .build();
+
+            let keyboard = with_undo_delete_button(
+                create_ingredient_review_keyboard(
                     current_matches,
                     language_code.as_deref(),
-                    ctx.localization
-                )
-            );
-
-            let keyboard = create_ingredient_review_keyboard(
-                current_matches,
+                    ctx.localization,
+                ),
                 language_code.as_deref(),
                 ctx.localization,
             );
@@ -387,6 +468,7 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
                         .id(),
                     review_message,
                 )
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await
             {
@@ -410,6 +492,8 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
                 current_matches: current_matches.clone(),
                 language_code: language_code.clone(),
                 message_id,
+                last_deleted: deleted, // Offered for undo until the next action
+                ingredients_version,
             })
             .await
         {
@@ -427,6 +511,232 @@ async fn handle_delete_saved_ingredient_button(params: SavedIngredientsParams<'_
     Ok(())
 }
 
+/// Handle undo button after a deletion while editing saved ingredients
+///
+/// Puts the last deleted ingredient back at its original position, so confirming
+/// leaves it untouched in the database.
+async fn handle_undo_delete_saved_ingredient_button(
+    params: SavedIngredientsParams<'_>,
+    last_deleted: Option<(usize, crate::text_processing::MeasurementMatch)>,
+) -> Result<()> {
+    let SavedIngredientsParams {
+        ctx,
+        q,
+        current_matches,
+        recipe_id,
+        original_ingredients,
+        ingredients_version,
+        language_code,
+        message_id,
+        dialogue,
+        ..
+    } = params;
+
+    let current_matches =
+        current_matches.expect("Current matches should be provided for undo callback");
+    let Some(deleted) = last_deleted else {
+        // Stale button from an earlier deletion, nothing to restore
+        return Ok(());
+    };
+    undo_ingredient_deletion(current_matches, deleted);
+
+    let review_message = HtmlMessage::titled(
+        "✏️",
+        &format_editing_title(
+            current_matches.len(),
+            None,
+            language_code.as_deref(),
+            ctx.localization,
+        ),
+    )
+    .paragraph(&t_lang(
+        ctx.localization,
+        "editing-instructions",
+        language_code.as_deref(),
+    ))
+    .paragraph_html(&format_ingredients_list(
+        current_matches,
+        crate::bot::unit_settings::display_units(q.from.id.into()),
+        language_code.as_deref(),
+        ctx.localization,
+    ))
+    .build();
+    let keyboard = create_ingredient_review_keyboard(
+        current_matches,
+        language_code.as_deref(),
+        ctx.localization,
+    );
+
+    if let Some(message) = &q.message {
+        if let Err(e) = ctx
+            .bot
+            .edit_message_text(message.chat().id, message.id(), review_message)
+            .parse_mode(PARSE_MODE)
+            .reply_markup(keyboard)
+            .await
+        {
+            error_logging::log_internal_error(
+                &e,
+                "callback_handler",
+                "Failed to edit message after undoing ingredient deletion",
+                Some(q.from.id.0 as i64),
+            );
+        }
+    }
+
+    dialogue
+        .update(RecipeDialogueState::EditingSavedIngredients {
+            recipe_id,
+            original_ingredients: original_ingredients.to_vec(),
+            current_matches: current_matches.clone(),
+            language_code: language_code.clone(),
+            message_id,
+            last_deleted: None,
+            ingredients_version,
+        })
+        .await?;
+    Ok(())
+}
+
+/// Handle quantity adjustment buttons for saved ingredients
+///
+/// Works like the adjustment in the initial review; changes are only written to
+/// the database when the edit is confirmed.
+async fn handle_adjust_saved_ingredient_button(
+    params: SavedIngredientsParams<'_>,
+    callback: AdjustCallback,
+) -> Result<()> {
+    let SavedIngredientsParams {
+        ctx,
+        q,
+        current_matches,
+        recipe_id,
+        original_ingredients,
+        ingredients_version,
+        language_code,
+        message_id,
+        dialogue,
+        ..
+    } = params;
+
+    let current_matches =
+        current_matches.expect("Current matches should be provided for adjust callback");
+    let message = q
+        .message
+        .as_ref()
+        .expect("Callback query should have a message");
+    let chat_id = message.chat().id;
+
+    let (index, step) = match callback {
+        AdjustCallback::Open(index) => {
+            if let Some(ingredient) = current_matches.get(index) {
+                ctx.bot
+                    .edit_message_reply_markup(chat_id, message.id())
+                    .reply_markup(create_quantity_adjust_keyboard(
+                        index,
+                        ingredient,
+                        ctx.language_code,
+                        ctx.localization,
+                    ))
+                    .await?;
+            }
+            return Ok(());
+        }
+        AdjustCallback::Done => {
+            ctx.bot
+                .edit_message_reply_markup(chat_id, message.id())
+                .reply_markup(create_ingredient_review_keyboard(
+                    current_matches,
+                    ctx.language_code,
+                    ctx.localization,
+                ))
+                .await?;
+            return Ok(());
+        }
+        AdjustCallback::Step(index, step) => (index, step),
+    };
+
+    let Some(ingredient) = current_matches.get_mut(index) else {
+        return Ok(());
+    };
+    let Some(quantity) = adjust_quantity(&ingredient.quantity, step) else {
+        ctx.bot
+            .send_message(
+                chat_id,
+                t_lang(
+                    ctx.localization,
+                    "adjust-quantity-not-numeric",
+                    ctx.language_code,
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+    ingredient.quantity = quantity;
+    ingredient.requires_quantity_confirmation = false;
+
+    crate::observability::record_user_engagement_metrics(
+        q.from.id.0 as i64,
+        crate::observability::UserAction::IngredientEdit,
+        None,
+        ctx.language_code,
+    );
+
+    let review_message = HtmlMessage::titled(
+        "✏️",
+        &format_editing_title(
+            current_matches.len(),
+            None,
+            ctx.language_code,
+            ctx.localization,
+        ),
+    )
+    .paragraph(&t_lang(
+        ctx.localization,
+        "editing-instructions",
+        ctx.language_code,
+    ))
+    .paragraph_html(&format_ingredients_list(
+        current_matches,
+        crate::bot::unit_settings::display_units(q.from.id.into()),
+        ctx.language_code,
+        ctx.localization,
+    ))
+    .build();
+    if let Err(e) = ctx
+        .bot
+        .edit_message_text(chat_id, message.id(), review_message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(create_quantity_adjust_keyboard(
+            index,
+            &current_matches[index],
+            ctx.language_code,
+            ctx.localization,
+        ))
+        .await
+    {
+        error_logging::log_internal_error(
+            &e,
+            "callback_handler",
+            "Failed to edit message after quantity adjustment",
+            Some(q.from.id.0 as i64),
+        );
+    }
+
+    dialogue
+        .update(RecipeDialogueState::EditingSavedIngredients {
+            recipe_id,
+            original_ingredients: original_ingredients.to_vec(),
+            current_matches: current_matches.clone(),
+            language_code: language_code.clone(),
+            message_id,
+            last_deleted: None,
+            ingredients_version,
+        })
+        .await?;
+    Ok(())
+}
+
 /// Handle confirm button for saved ingredients
 async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<'_>) -> Result<()> {
     let SavedIngredientsParams {
@@ -434,10 +744,12 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
         q,
         current_matches_slice,
         original_ingredients,
+        ingredients_version,
         recipe_id,
         language_code,
         dialogue,
         pool,
+        cache,
         ..
     } = params;
 
@@ -456,135 +768,71 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
     // Detect changes between original and current ingredients
     let changes =
         crate::ingredient_editing::detect_ingredient_changes(original_ingredients, current_matches);
+    let unparsed = crate::ingredient_editing::unparsed_quantities(current_matches);
 
-    // Apply changes to database
-    if !changes.to_update.is_empty() || !changes.to_add.is_empty() || !changes.to_delete.is_empty()
-    {
-        // Update existing ingredients
-        for (ingredient_id, new_data) in &changes.to_update {
-            if let Err(e) = crate::db::update_ingredient(
-                pool,
-                *ingredient_id,
-                Some(&new_data.ingredient_name),
-                new_data.quantity.parse().ok(),
-                new_data.measurement.as_deref(),
-            )
-            .await
-            {
+    // Renames are detected against the saved names, which the dialogue state no longer keeps
+    let renames = if changes.to_update.is_empty() {
+        Vec::new()
+    } else {
+        match crate::db::get_recipe_ingredients(pool, RecipeId(recipe_id)).await {
+            Ok(saved_ingredients) => crate::ingredient_editing::collect_propagatable_renames(
+                &saved_ingredients,
+                &changes,
+            ),
+            Err(e) => {
                 error_logging::log_database_error(
                     &e,
-                    "update_ingredient",
+                    "get_recipe_ingredients",
                     Some(q.from.id.0 as i64),
-                    Some(&[("ingredient_id", &ingredient_id.to_string())]),
+                    Some(&[("recipe_id", &recipe_id.to_string())]),
                 );
-                ctx.bot
-                    .send_message(
-                        q.message
-                            .as_ref()
-                            .expect("Callback query should have a message")
-                            .chat()
-                            .id,
-                        t_lang(
-                            ctx.localization,
-                            "error-updating-ingredients",
-                            language_code.as_deref(),
-                        ),
-                    )
-                    .await?;
-                return Ok(());
+                Vec::new()
             }
         }
+    };
 
-        // Add new ingredients
-        for new_ingredient in &changes.to_add {
-            // Get the internal user ID from the database
-            let user = match crate::db::get_or_create_user(
-                pool,
-                q.from.id.0 as i64,
-                language_code.as_deref(),
-            )
-            .await
-            {
-                Ok(user) => user,
-                Err(e) => {
-                    error_logging::log_database_error(
-                        &e,
-                        "get_or_create_user",
-                        Some(q.from.id.0 as i64),
-                        None,
-                    );
-                    ctx.bot
-                        .send_message(
-                            q.message
-                                .as_ref()
-                                .expect("Callback query should have a message")
-                                .chat()
-                                .id,
-                            t_lang(
-                                ctx.localization,
-                                "error-processing-failed",
-                                language_code.as_deref(),
-                            ),
-                        )
-                        .await?;
-                    return Ok(());
-                }
-            };
-
-            let quantity = new_ingredient.quantity.parse().ok();
-            let unit = new_ingredient.measurement.as_deref();
-            error!(
-                user_id = %user.id,
-                telegram_id = %q.from.id.0,
-                recipe_id = %recipe_id,
-                ingredient_name = %new_ingredient.ingredient_name,
-                quantity = ?quantity,
-                unit = ?unit,
-                "Attempting to add new ingredient"
-            );
-            if let Err(e) = crate::db::create_ingredient(
-                pool,
-                user.id, // Use internal database user ID
-                Some(recipe_id),
-                &new_ingredient.ingredient_name,
-                quantity,
-                unit,
-                "", // raw_text not meaningful for edited ingredients
-            )
-            .await
-            {
-                error_logging::log_database_error(
-                    &e,
-                    "create_ingredient",
-                    Some(q.from.id.0 as i64),
-                    Some(&[("recipe_id", &recipe_id.to_string())]),
+    // Apply changes to database
+    if !changes.to_update.is_empty() || !changes.to_add.is_empty() || !changes.to_delete.is_empty()
+    {
+        // Applied in one transaction, unless the recipe was edited elsewhere since this edit started
+        let applied = match cache {
+            Some(cache) => {
+                crate::db::update_recipe_ingredients_versioned_cached(
+                    pool,
+                    RecipeId(recipe_id),
+                    ingredients_version,
+                    current_matches,
+                    cache,
+                )
+                .await
+            }
+            None => {
+                crate::db::update_recipe_ingredients_versioned(
+                    pool,
+                    RecipeId(recipe_id),
+                    ingredients_version,
+                    current_matches,
+                )
+                .await
+            }
+        };
+        match applied {
+            Ok(true) => (),
+            Ok(false) => {
+                info!(
+                    user_id = %q.from.id,
+                    recipe_id,
+                    ingredients_version,
+                    "Saved ingredients changed since editing started, not overwriting"
                 );
-                ctx.bot
-                    .send_message(
-                        q.message
-                            .as_ref()
-                            .expect("Callback query should have a message")
-                            .chat()
-                            .id,
-                        t_lang(
-                            ctx.localization,
-                            "error-adding-ingredients",
-                            language_code.as_deref(),
-                        ),
-                    )
-                    .await?;
-                return Ok(());
+                return send_ingredients_edit_conflict(ctx, q, recipe_id, dialogue).await;
             }
-        }
-
-        // Delete ingredients
-        for ingredient_id in &changes.to_delete {
-            if let Err(e) = crate::db::delete_ingredient(pool, *ingredient_id).await {
+            Err(e) => {
                 error_logging::log_database_error(
                     &e,
-                    "delete_ingredient",
+                    "update_recipe_ingredients",
                     Some(q.from.id.0 as i64),
-                    Some(&[("ingredient_id", &ingredient_id.to_string())]),
+                    Some(&[("recipe_id", &recipe_id.to_string())]),
                 );
                 ctx.bot
                     .send_message(
@@ -595,7 +843,7 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
                             .id,
                         t_lang(
                             ctx.localization,
-                            "error-deleting-ingredients",
+                            "error-updating-ingredients",
                             language_code.as_deref(),
                         ),
                     )
@@ -605,7 +853,7 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
         }
 
         // Fetch updated recipe details and ingredients
-        let recipe = match crate::db::read_recipe_with_name(pool, recipe_id).await {
+        let recipe = match crate::db::read_recipe_with_name(pool, RecipeId(recipe_id)).await {
             Ok(Some(recipe)) => recipe,
             Ok(None) => {
                 error_logging::log_internal_error(
@@ -655,7 +903,31 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
             }
         };
 
-        let updated_ingredients = crate::db::get_recipe_ingredients(pool, recipe_id).await?;
+        let updated_ingredients =
+            crate::db::get_recipe_ingredients(pool, RecipeId(recipe_id)).await?;
+        crate::events::emit(crate::events::RecipeEvent::ingredients_updated(
+            RecipeId(recipe_id),
+            recipe.recipe_name.as_deref(),
+            updated_ingredients.len(),
+        ));
+        if let Err(e) = crate::db::record_recipe_activity(
+            pool,
+            TelegramId(q.from.id.0 as i64),
+            RecipeId(recipe_id),
+            crate::db::RecipeActivityKind::Edited,
+            recipe.recipe_name.as_deref(),
+            None,
+            Some(updated_ingredients.len()),
+        )
+        .await
+        {
+            error_logging::log_database_error(
+                &e,
+                "record_recipe_activity",
+                Some(q.from.id.0 as i64),
+                None,
+            );
+        }
         let updated_matches =
             crate::ingredient_editing::ingredients_to_measurement_matches(&updated_ingredients);
 
@@ -663,18 +935,25 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
         let recipe_name = recipe
             .recipe_name
             .unwrap_or_else(|| "Unnamed Recipe".to_string());
-        let recipe_message = format!(
-            "📝 **{}**\n\n{}",
-            recipe_name,
-            crate::bot::format_ingredients_list(
+        let recipe_message = HtmlMessage::titled("📝", &recipe_name)
+            .paragraph_html(&crate::bot::format_ingredients_list(
                 &updated_matches,
+                crate::bot::unit_settings::display_units(q.from.id.into()),
                 language_code.as_deref(),
-                ctx.localization
-            )
-        );
+                ctx.localization,
+            ))
+            .build();
 
-        let keyboard =
-            create_recipe_details_keyboard(recipe_id, language_code.as_deref(), ctx.localization);
+        let has_photo =
+            crate::db::get_recipe_photo(pool, TelegramId(q.from.id.0 as i64), RecipeId(recipe_id))
+                .await?
+                .is_some();
+        let keyboard = create_recipe_details_keyboard(
+            recipe_id,
+            has_photo,
+            language_code.as_deref(),
+            ctx.localization,
+        );
 
         // Update the message to show the updated recipe
         match ctx
@@ -691,6 +970,7 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
                     .id(),
                 recipe_message,
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await
         {
@@ -706,7 +986,7 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
         }
     } else {
         // No changes made - still show the recipe details
-        let recipe = match crate::db::read_recipe_with_name(pool, recipe_id).await {
+        let recipe = match crate::db::read_recipe_with_name(pool, RecipeId(recipe_id)).await {
             Ok(Some(recipe)) => recipe,
             Ok(None) => {
                 error_logging::log_internal_error(
@@ -756,24 +1036,31 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
             }
         };
 
-        let ingredients = crate::db::get_recipe_ingredients(pool, recipe_id).await?;
+        let ingredients = crate::db::get_recipe_ingredients(pool, RecipeId(recipe_id)).await?;
         let matches = crate::ingredient_editing::ingredients_to_measurement_matches(&ingredients);
 
         let recipe_name = recipe
             .recipe_name
             .unwrap_or_else(|| "Unnamed Recipe".to_string());
-        let recipe_message = format!(
-            "📝 **{}**\n\n{}",
-            recipe_name,
-            crate::bot::format_ingredients_list(
+        let recipe_message = HtmlMessage::titled("📝", &recipe_name)
+            .paragraph_html(&crate::bot::format_ingredients_list(
                 &matches,
+                crate::bot::unit_settings::display_units(q.from.id.into()),
                 language_code.as_deref(),
-                ctx.localization
-            )
-        );
+                ctx.localization,
+            ))
+            .build();
 
-        let keyboard =
-            create_recipe_details_keyboard(recipe_id, language_code.as_deref(), ctx.localization);
+        let has_photo =
+            crate::db::get_recipe_photo(pool, TelegramId(q.from.id.0 as i64), RecipeId(recipe_id))
+                .await?
+                .is_some();
+        let keyboard = create_recipe_details_keyboard(
+            recipe_id,
+            has_photo,
+            language_code.as_deref(),
+            ctx.localization,
+        );
 
         // Update the message to show the recipe details
         match ctx
@@ -790,6 +1077,7 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
                     .id(),
                 recipe_message,
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await
         {
@@ -805,12 +1093,325 @@ async fn handle_confirm_saved_ingredients_button(params: SavedIngredientsParams<
         }
     }
 
-    // End the dialogue
+    // Quantities that could not be read were kept as saved, tell the user which lines
+    if !unparsed.is_empty() {
+        let lines = unparsed
+            .iter()
+            .map(|ingredient| format!("• {} {}", ingredient.quantity, ingredient.ingredient_name))
+            .collect::<Vec<_>>()
+            .join("\n");
+        ctx.bot
+            .send_message(
+                q.message
+                    .as_ref()
+                    .expect("Callback query should have a message")
+                    .chat()
+                    .id,
+                t_args_lang(
+                    ctx.localization,
+                    "edit-quantity-not-understood",
+                    &[("lines", &lines)],
+                    language_code.as_deref(),
+                ),
+            )
+            .await?;
+    }
+
+    // Offer to fix renamed ingredients in the user's other recipes (ends the dialogue if none)
+    offer_next_rename_propagation(ctx, q, pool, recipe_id, renames, dialogue).await?;
+
+    Ok(())
+}
+
+/// Tell the user their edit was not saved because the recipe changed meanwhile
+///
+/// The stale editing message is replaced by the explanation and a button starting the
+/// edit again from the saved ingredients, and the dialogue is ended.
+async fn send_ingredients_edit_conflict(
+    ctx: &HandlerContext<'_>,
+    q: &teloxide::types::CallbackQuery,
+    recipe_id: i64,
+    dialogue: &RecipeDialogue,
+) -> Result<()> {
+    let message = q
+        .message
+        .as_ref()
+        .expect("Callback query should have a message");
+    let text = titled(
+        "⚠️",
+        &t_lang(ctx.localization, "edit-conflict-title", ctx.language_code),
+        &[&t_lang(
+            ctx.localization,
+            "edit-conflict-help",
+            ctx.language_code,
+        )],
+    );
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![create_localized_button_with_emoji(
+        ctx.localization,
+        "🔄",
+        "edit-conflict-reload",
+        format!("recipe_action:edit_ingredients:{}", recipe_id),
+        ctx.language_code,
+    )]]);
+
+    if let Err(e) = ctx
+        .bot
+        .edit_message_text(message.chat().id, message.id(), text.clone())
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard.clone())
+        .await
+    {
+        error_logging::log_internal_error(
+            &e,
+            "send_ingredients_edit_conflict",
+            "Failed to replace the editing message with the conflict notice",
+            Some(q.from.id.0 as i64),
+        );
+        ctx.bot
+            .send_message(message.chat().id, text)
+            .parse_mode(PARSE_MODE)
+            .reply_markup(keyboard)
+            .await?;
+    }
+
+    dialogue.exit().await?;
+    Ok(())
+}
+
+/// Maximum number of affected recipes listed in a rename propagation offer
+const RENAME_PROPAGATION_PREVIEW_LIMIT: usize = 5;
+
+/// Offer the next pending ingredient rename that also appears in the user's other recipes
+///
+/// Renames without matches in other recipes are skipped. When no rename is left to offer,
+/// the dialogue is ended.
+async fn offer_next_rename_propagation(
+    ctx: &HandlerContext<'_>,
+    q: &teloxide::types::CallbackQuery,
+    pool: &Arc<PgPool>,
+    recipe_id: i64,
+    renames: Vec<(String, String)>,
+    dialogue: &RecipeDialogue,
+) -> Result<()> {
+    let mut pending = renames.into_iter();
+
+    while let Some((old_name, new_name)) = pending.next() {
+        let affected = match crate::db::find_other_recipes_with_ingredient(
+            pool,
+            TelegramId(q.from.id.0 as i64),
+            RecipeId(recipe_id),
+            &old_name,
+        )
+        .await
+        {
+            Ok(recipes) => recipes,
+            Err(e) => {
+                error_logging::log_database_error(
+                    &e,
+                    "find_other_recipes_with_ingredient",
+                    Some(q.from.id.0 as i64),
+                    Some(&[("recipe_id", &recipe_id.to_string())]),
+                );
+                continue;
+            }
+        };
+
+        if affected.is_empty() {
+            continue;
+        }
+
+        let count = affected.len().to_string();
+        let mut offer_message = format!(
+            "{}\n\n{}",
+            t_args_lang(
+                ctx.localization,
+                "rename-propagation-offer",
+                &[("old_name", &old_name), ("count", &count)],
+                ctx.language_code,
+            ),
+            t_lang(
+                ctx.localization,
+                "rename-propagation-preview",
+                ctx.language_code
+            )
+        );
+        for recipe in affected.iter().take(RENAME_PROPAGATION_PREVIEW_LIMIT) {
+            let recipe_name = recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe");
+            offer_message.push_str(&format!("\n• {}", recipe_name));
+        }
+        if affected.len() > RENAME_PROPAGATION_PREVIEW_LIMIT {
+            let remaining = (affected.len() - RENAME_PROPAGATION_PREVIEW_LIMIT).to_string();
+            offer_message.push_str(&format!(
+                "\n{}",
+                t_args_lang(
+                    ctx.localization,
+                    "rename-propagation-more",
+                    &[("count", &remaining)],
+                    ctx.language_code,
+                )
+            ));
+        }
+
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            create_localized_button_with_emoji(
+                ctx.localization,
+                "✅",
+                "rename-propagation-accept",
+                "propagate_rename_accept".to_string(),
+                ctx.language_code,
+            ),
+            create_localized_button_with_emoji(
+                ctx.localization,
+                "❌",
+                "rename-propagation-decline",
+                "propagate_rename_decline".to_string(),
+                ctx.language_code,
+            ),
+        ]]);
+
+        ctx.bot
+            .send_message(
+                q.message
+                    .as_ref()
+                    .expect("Callback query should have a message")
+                    .chat()
+                    .id,
+                offer_message,
+            )
+            .reply_markup(keyboard)
+            .await?;
+
+        dialogue
+            .update(RecipeDialogueState::ConfirmingRenamePropagation {
+                recipe_id,
+                old_name,
+                new_name,
+                remaining_renames: pending.collect(),
+                language_code: ctx.language_code.map(|code| code.to_string()),
+            })
+            .await?;
+        return Ok(());
+    }
+
+    // Nothing left to offer
     dialogue.exit().await?;
 
     Ok(())
 }
 
+/// Handle callbacks when in ConfirmingRenamePropagation dialogue state
+///
+/// Accepting renames the ingredient in all of the user's other recipes in one transaction;
+/// declining leaves them untouched. Either way, the next pending rename is offered.
+pub async fn handle_rename_propagation_callbacks(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    data: &str,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let dialogue_state = dialogue.get().await?;
+    let Some(RecipeDialogueState::ConfirmingRenamePropagation {
+        recipe_id,
+        old_name,
+        new_name,
+        remaining_renames,
+        language_code,
+    }) = dialogue_state
+    else {
+        return Ok(());
+    };
+
+    if data != "propagate_rename_accept" && data != "propagate_rename_decline" {
+        return Ok(());
+    }
+
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+
+    let ctx = HandlerContext {
+        bot,
+        localization,
+        language_code: language_code.as_deref(),
+    };
+
+    let result_message = if data == "propagate_rename_accept" {
+        crate::observability::record_user_engagement_metrics(
+            q.from.id.0 as i64,
+            crate::observability::UserAction::IngredientEdit,
+            None,
+            language_code.as_deref(),
+        );
+
+        match crate::db::rename_ingredient_in_other_recipes(
+            &pool,
+            TelegramId(q.from.id.0 as i64),
+            RecipeId(recipe_id),
+            &old_name,
+            &new_name,
+        )
+        .await
+        {
+            Ok(renamed) => {
+                if let Some(cache) = cache {
+                    // The renamed recipes are not reported back, so drop every cached list
+                    cache.lock().invalidate_all_recipe_ingredients();
+                }
+                t_args_lang(
+                    localization,
+                    "rename-propagation-done",
+                    &[
+                        ("old_name", &old_name),
+                        ("new_name", &new_name),
+                        ("count", &renamed.to_string()),
+                    ],
+                    language_code.as_deref(),
+                )
+            }
+            Err(e) => {
+                error_logging::log_database_error(
+                    &e,
+                    "rename_ingredient_in_other_recipes",
+                    Some(q.from.id.0 as i64),
+                    Some(&[("recipe_id", &recipe_id.to_string())]),
+                );
+                t_lang(
+                    localization,
+                    "error-rename-propagation",
+                    language_code.as_deref(),
+                )
+            }
+        }
+    } else {
+        t_lang(
+            localization,
+            "rename-propagation-skipped",
+            language_code.as_deref(),
+        )
+    };
+
+    // Replace the offer with the outcome and remove its buttons
+    if let Err(e) = bot
+        .edit_message_text(msg.chat().id, msg.id(), result_message)
+        .reply_markup(InlineKeyboardMarkup::new(Vec::<
+            Vec<teloxide::types::InlineKeyboardButton>,
+        >::new()))
+        .await
+    {
+        error_logging::log_internal_error(
+            &e,
+            "handle_rename_propagation_callbacks",
+            "Failed to update rename propagation offer",
+            Some(q.from.id.0 as i64),
+        );
+    }
+
+    offer_next_rename_propagation(&ctx, q, &pool, recipe_id, remaining_renames, dialogue).await
+}
+
 /// Handle cancel button for saved ingredients editing
 async fn handle_cancel_saved_ingredients_button(
     bot: &Bot,
@@ -829,7 +1430,7 @@ async fn handle_cancel_saved_ingredients_button(
     }) = dialogue_state
     {
         // Fetch recipe details and ingredients from database
-        let recipe = match crate::db::read_recipe_with_name(&pool, recipe_id).await? {
+        let recipe = match crate::db::read_recipe_with_name(&pool, RecipeId(recipe_id)).await? {
             Some(recipe) => recipe,
             None => {
                 // Recipe not found, just exit dialogue
@@ -838,7 +1439,7 @@ async fn handle_cancel_saved_ingredients_button(
             }
         };
 
-        let ingredients = crate::db::get_recipe_ingredients(&pool, recipe_id).await?;
+        let ingredients = crate::db::get_recipe_ingredients(&pool, RecipeId(recipe_id)).await?;
 
         // Convert ingredients to measurement matches for display
         let measurement_matches =
@@ -848,18 +1449,25 @@ async fn handle_cancel_saved_ingredients_button(
         let recipe_name = recipe
             .recipe_name
             .unwrap_or_else(|| "Unnamed Recipe".to_string());
-        let recipe_message = format!(
-            "📝 **{}**\n\n{}",
-            recipe_name,
-            crate::bot::format_ingredients_list(
+        let recipe_message = HtmlMessage::titled("📝", &recipe_name)
+            .paragraph_html(&crate::bot::format_ingredients_list(
                 &measurement_matches,
+                crate::bot::unit_settings::display_units(q.from.id.into()),
                 language_code.as_deref(),
-                localization
-            )
-        );
+                localization,
+            ))
+            .build();
 
-        let keyboard =
-            create_recipe_details_keyboard(recipe_id, language_code.as_deref(), localization);
+        let has_photo =
+            crate::db::get_recipe_photo(&pool, TelegramId(q.from.id.0 as i64), RecipeId(recipe_id))
+                .await?
+                .is_some();
+        let keyboard = create_recipe_details_keyboard(
+            recipe_id,
+            has_photo,
+            language_code.as_deref(),
+            localization,
+        );
 
         // Edit the editing message back to the recipe details
         if let Some(message_id) = message_id {
@@ -873,6 +1481,7 @@ async fn handle_cancel_saved_ingredients_button(
                     teloxide::types::MessageId(message_id),
                     recipe_message,
                 )
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await
             {
@@ -920,6 +1529,7 @@ async fn handle_add_ingredient_button(
         original_ingredients,
         current_matches,
         message_id,
+        ingredients_version,
         ..
     }) = dialogue_state
     {
@@ -945,6 +1555,7 @@ async fn handle_add_ingredient_button(
                 current_matches,
                 language_code: language_code.clone(),
                 message_id,
+                ingredients_version,
             })
             .await?;
     }
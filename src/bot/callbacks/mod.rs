@@ -1,6 +1,7 @@
 //! Callbacks module for handling all inline keyboard callback queries
 //!
 //! This module is organized into submodules for different types of callbacks:
+//! - `bulk_delete_callbacks`: Selection mode for deleting several recipes at once
 //! - `callback_handler`: Main routing handler for all callback queries
 //! - `callback_types`: Shared parameter structs for callback handlers
 //! - `recipe_callbacks`: Recipe selection, actions, and management
@@ -8,6 +9,7 @@
 //! - `review_callbacks`: ReviewIngredients dialogue state handlers
 //! - `editing_callbacks`: EditingSavedIngredients dialogue state handlers
 
+pub mod bulk_delete_callbacks;
 pub mod callback_handler;
 pub mod callback_types;
 pub mod editing_callbacks;
@@ -7,14 +7,16 @@ use anyhow::Result;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
-use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage};
+use teloxide::types::{
+    InlineKeyboardButton, InlineKeyboardMarkup, InputFile, MaybeInaccessibleMessage,
+};
 use tracing::debug;
 
 // Import error logging utilities
 use crate::errors::error_logging;
 
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_args_lang, t_lang};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
@@ -22,11 +24,67 @@ use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 // Import UI builder functions
 use crate::bot::ui_builder::{
     create_ingredient_review_keyboard, create_recipe_details_keyboard,
-    create_recipe_instances_keyboard, format_database_ingredients_list, format_ingredients_list,
+    create_recipe_instances_keyboard, format_editing_title, format_ingredients_list,
+    format_recipe_details, format_recipe_markdown, recipe_markdown_file_name,
+};
+
+// Import HTML message formatting
+use crate::bot::formatting::{
+    labelled, message_length, send_long_message, titled, HtmlMessage, MAX_MESSAGE_LENGTH,
+    PARSE_MODE,
 };
 
 // Import database functions
-use crate::db::{get_recipes_by_name, read_recipe_with_name};
+use crate::db::{
+    find_recipe_name_by_token, get_ingredients_for_recipes, get_ingredients_for_recipes_cached,
+    get_recipe_ingredients, get_recipe_ingredients_cached, get_recipe_photo, get_recipes_by_name,
+    get_recipes_by_name_cached, read_recipe_with_name, read_recipe_with_name_cached, Ingredient,
+    RecipeId, TelegramId,
+};
+
+// Import cache types
+use crate::cache::SharedCacheManager;
+
+// Import recipe lifecycle events
+use crate::events::RecipeEvent;
+
+// Import repository traits
+use crate::repository::{RecipeRepository, Repository};
+
+/// Get a recipe's ingredients, from the cache when one is available
+async fn recipe_ingredients(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    cache: Option<&SharedCacheManager>,
+) -> Result<Vec<Ingredient>> {
+    match cache {
+        Some(cache) => get_recipe_ingredients_cached(pool, recipe_id, cache).await,
+        None => get_recipe_ingredients(pool, recipe_id).await,
+    }
+}
+
+/// Recipe name a recipe selection callback refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecipeSelection {
+    /// `select_recipe_token:{token}`, built by [`recipe_selection_callback`]
+    ///
+    /// [`recipe_selection_callback`]: crate::bot::ui_builder::recipe_selection_callback
+    Token(String),
+    /// `select_recipe:{name}`, still carried by buttons of older messages
+    Name(String),
+}
+
+impl RecipeSelection {
+    /// Parse recipe selection callback data
+    pub fn parse(data: &str) -> Option<Self> {
+        if let Some(token) = data.strip_prefix("select_recipe_token:") {
+            Some(Self::Token(token.to_string()))
+        } else {
+            data.strip_prefix("select_recipe:")
+                .map(|name| Self::Name(name.to_string()))
+        }
+    }
+}
 
 /// Handle recipe selection callback
 pub async fn handle_recipe_selection(
@@ -36,10 +94,12 @@ pub async fn handle_recipe_selection(
     pool: Arc<PgPool>,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
-    // Extract recipe name from callback data (format: "select_recipe:Recipe Name")
-    let recipe_name = data.strip_prefix("select_recipe:").unwrap_or("");
-    debug!(recipe_name = %recipe_name, "Handling recipe selection");
+    let Some(selection) = RecipeSelection::parse(data) else {
+        return Ok(());
+    };
+    debug!(selection = ?selection, "Handling recipe selection");
 
     // Extract chat id from the message
     let chat_id = match msg {
@@ -50,80 +110,104 @@ pub async fn handle_recipe_selection(
         }
     };
 
+    // Resolve the token to a name; an unknown token means the recipes were renamed or deleted
+    let recipe_name = match selection {
+        RecipeSelection::Token(token) => {
+            find_recipe_name_by_token(&pool, TelegramId(chat_id.0), &token)
+                .await?
+                .unwrap_or_default()
+        }
+        RecipeSelection::Name(name) => name,
+    };
+
     // Query for all recipes with this name for the user
-    let recipes = get_recipes_by_name(&pool, chat_id.0, recipe_name).await?;
+    let recipes = match cache {
+        _ if recipe_name.is_empty() => Vec::new(),
+        Some(cache) => {
+            get_recipes_by_name_cached(&pool, TelegramId(chat_id.0), &recipe_name, cache).await?
+        }
+        None => get_recipes_by_name(&pool, TelegramId(chat_id.0), &recipe_name).await?,
+    };
 
     match recipes.len() {
         0 => {
             // This shouldn't happen if the recipe exists in the list, but handle gracefully
-            let message = format!(
-                "❌ **{}**\n\n{}",
-                t_lang(localization, "recipe-not-found", language_code.as_deref()),
-                t_lang(
+            let message = titled(
+                "❌",
+                &t_lang(localization, "recipe-not-found", language_code.as_deref()),
+                &[&t_lang(
                     localization,
                     "recipe-not-found-help",
-                    language_code.as_deref()
-                )
+                    language_code.as_deref(),
+                )],
             );
-            bot.send_message(chat_id, message).await?;
+            bot.send_message(chat_id, message)
+                .parse_mode(PARSE_MODE)
+                .await?;
         }
         1 => {
             // Single recipe - show details directly
             let recipe = &recipes[0];
-            let ingredients = crate::db::get_recipe_ingredients(&pool, recipe.id).await?;
-
-            let message = format!(
-                "📖 **{}**\n\n📅 {}\n\n{}",
-                recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
-                recipe.created_at.format("%B %d, %Y at %H:%M"),
-                if ingredients.is_empty() {
-                    t_lang(
-                        localization,
-                        "no-ingredients-found",
-                        language_code.as_deref(),
-                    )
-                } else {
-                    format_database_ingredients_list(
-                        &ingredients,
-                        language_code.as_deref(),
-                        localization,
-                    )
-                }
+            let ingredients = recipe_ingredients(&pool, recipe.id, cache).await?;
+
+            let message = format_recipe_details(
+                recipe,
+                &ingredients,
+                crate::bot::unit_settings::display_units(chat_id),
+                crate::bot::timezone_settings::display_timezone(chat_id),
+                language_code.as_deref(),
+                localization,
             );
 
-            let keyboard =
-                create_recipe_details_keyboard(recipe.id, language_code.as_deref(), localization);
+            let has_photo = get_recipe_photo(&pool, TelegramId(chat_id.0), recipe.id)
+                .await?
+                .is_some();
+            let keyboard = create_recipe_details_keyboard(
+                recipe.id.0,
+                has_photo,
+                language_code.as_deref(),
+                localization,
+            );
 
-            bot.send_message(chat_id, message)
-                .reply_markup(keyboard)
-                .await?;
+            send_long_message(bot, chat_id, &message, Some(keyboard)).await?;
         }
         _ => {
             // Multiple recipes with same name - show disambiguation UI
-            let message = format!(
-                "📚 **{}**\n\n{}",
-                recipe_name,
-                t_lang(
+            let message = titled(
+                "📚",
+                &recipe_name,
+                &[&t_lang(
                     localization,
                     "select-recipe-instance",
-                    language_code.as_deref()
-                )
+                    language_code.as_deref(),
+                )],
             );
 
-            // Fetch ingredients for each recipe to show previews
-            let mut recipe_data = Vec::new();
-            for recipe in &recipes {
-                let ingredients = crate::db::get_recipe_ingredients(&pool, recipe.id).await?;
-                recipe_data.push((recipe.clone(), ingredients));
-            }
+            // Fetch the ingredients of all the recipes at once to show previews
+            let recipe_ids: Vec<RecipeId> = recipes.iter().map(|recipe| recipe.id).collect();
+            let mut ingredients = match cache {
+                Some(cache) => {
+                    get_ingredients_for_recipes_cached(&pool, &recipe_ids, cache).await?
+                }
+                None => get_ingredients_for_recipes(&pool, &recipe_ids).await?,
+            };
+            let recipe_data: Vec<_> = recipes
+                .iter()
+                .map(|recipe| {
+                    let preview = ingredients.remove(&recipe.id).unwrap_or_default();
+                    (recipe.clone(), preview)
+                })
+                .collect();
 
             let keyboard = create_recipe_instances_keyboard(
                 &recipe_data,
+                crate::bot::timezone_settings::display_timezone(chat_id),
                 language_code.as_deref(),
                 localization,
             );
 
             bot.send_message(chat_id, message)
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await?;
         }
@@ -140,6 +224,7 @@ pub async fn handle_recipe_instance_selection(
     pool: Arc<PgPool>,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     // Extract recipe ID from callback data (format: "recipe_instance:123")
     let recipe_id_str = data.strip_prefix("recipe_instance:").unwrap_or("");
@@ -156,42 +241,48 @@ pub async fn handle_recipe_instance_selection(
     };
 
     // Get recipe details
-    let recipe = read_recipe_with_name(&pool, recipe_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("Recipe not found"))?;
-    let ingredients = crate::db::get_recipe_ingredients(&pool, recipe_id).await?;
-
-    let message = format!(
-        "📖 **{}**\n\n📅 {}\n\n{}",
-        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
-        recipe.created_at.format("%B %d, %Y at %H:%M"),
-        if ingredients.is_empty() {
-            t_lang(
-                localization,
-                "no-ingredients-found",
-                language_code.as_deref(),
-            )
-        } else {
-            format_database_ingredients_list(&ingredients, language_code.as_deref(), localization)
-        }
+    let recipe = match cache {
+        Some(cache) => read_recipe_with_name_cached(&pool, RecipeId(recipe_id), cache).await?,
+        None => read_recipe_with_name(&pool, RecipeId(recipe_id)).await?,
+    }
+    .ok_or_else(|| anyhow::anyhow!("Recipe not found"))?;
+    let ingredients = recipe_ingredients(&pool, RecipeId(recipe_id), cache).await?;
+
+    let message = format_recipe_details(
+        &recipe,
+        &ingredients,
+        crate::bot::unit_settings::display_units(chat_id),
+        crate::bot::timezone_settings::display_timezone(chat_id),
+        language_code.as_deref(),
+        localization,
     );
 
-    let keyboard =
-        create_recipe_details_keyboard(recipe_id, language_code.as_deref(), localization);
+    let has_photo = get_recipe_photo(&pool, TelegramId(chat_id.0), RecipeId(recipe_id))
+        .await?
+        .is_some();
+    let keyboard = create_recipe_details_keyboard(
+        recipe_id,
+        has_photo,
+        language_code.as_deref(),
+        localization,
+    );
 
-    bot.send_message(chat_id, message)
-        .reply_markup(keyboard)
-        .await?;
+    send_long_message(bot, chat_id, &message, Some(keyboard)).await?;
 
     Ok(())
 }
 
-/// Handle recipe action callbacks (rename, delete)
+/// Handle recipe action callbacks (rename, delete, scale, tags, notes, copy as text, ...)
+///
+/// Renaming and statistics go through `repository`; the other actions still
+/// read `pool` directly.
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_recipe_action(
     bot: &Bot,
     msg: &MaybeInaccessibleMessage,
     data: &str,
     pool: Arc<PgPool>,
+    repository: &dyn Repository,
     dialogue: &RecipeDialogue,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
@@ -221,29 +312,34 @@ pub async fn handle_recipe_action(
     match action {
         "rename" => {
             // Get current recipe details
-            if let Ok(Some(recipe)) = crate::db::read_recipe_with_name(&pool, recipe_id).await {
+            if let Ok(Some(recipe)) = repository.read_recipe_with_name(RecipeId(recipe_id)).await {
                 let current_name = recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe");
 
-                let message = format!(
-                    "🏷️ **{}**\n\n{}: **{}**\n\n{}",
-                    t_lang(
+                let message = HtmlMessage::titled(
+                    "🏷️",
+                    &t_lang(
                         localization,
                         "rename-recipe-title",
-                        language_code.as_deref()
+                        language_code.as_deref(),
                     ),
-                    t_lang(
+                )
+                .paragraph_html(&labelled(
+                    &t_lang(
                         localization,
                         "current-recipe-name",
-                        language_code.as_deref()
+                        language_code.as_deref(),
                     ),
                     current_name,
-                    t_lang(
-                        localization,
-                        "rename-recipe-instructions",
-                        language_code.as_deref()
-                    )
-                );
-                bot.send_message(chat_id, message).await?;
+                ))
+                .paragraph(&t_lang(
+                    localization,
+                    "rename-recipe-instructions",
+                    language_code.as_deref(),
+                ))
+                .build();
+                bot.send_message(chat_id, message)
+                    .parse_mode(PARSE_MODE)
+                    .await?;
 
                 // Transition to renaming state
                 dialogue
@@ -265,18 +361,19 @@ pub async fn handle_recipe_action(
                 MaybeInaccessibleMessage::Inaccessible(_) => None,
             };
 
-            let message = format!(
-                "🗑️ **{}**\n\n{}",
-                t_lang(
+            let message = titled(
+                "🗑️",
+                &t_lang(
                     localization,
                     "delete-recipe-title",
-                    language_code.as_deref()
+                    language_code.as_deref(),
                 ),
-                t_lang(
+                &[&t_args_lang(
                     localization,
                     "delete-recipe-confirmation",
-                    language_code.as_deref()
-                )
+                    &[("days", &crate::db::TRASH_RETENTION_DAYS.to_string())],
+                    language_code.as_deref(),
+                )],
             );
 
             let keyboard = vec![vec![
@@ -305,6 +402,7 @@ pub async fn handle_recipe_action(
             ]];
 
             bot.send_message(chat_id, message)
+                .parse_mode(PARSE_MODE)
                 .reply_markup(teloxide::types::InlineKeyboardMarkup::new(keyboard))
                 .await?;
         }
@@ -320,10 +418,90 @@ pub async fn handle_recipe_action(
             )
             .await?;
         }
+        "scale" => {
+            bot.send_message(
+                chat_id,
+                t_lang(localization, "scale-prompt", language_code.as_deref()),
+            )
+            .await?;
+            dialogue
+                .update(RecipeDialogueState::ScalingRecipe {
+                    recipe_id,
+                    language_code: language_code.clone(),
+                })
+                .await?;
+        }
         "statistics" => {
-            handle_recipe_statistics(bot, msg, recipe_id, pool, language_code, localization)
+            handle_recipe_statistics(bot, msg, recipe_id, repository, language_code, localization)
                 .await?;
         }
+        "nutrition" => {
+            crate::bot::recipe_nutrition::handle_recipe_nutrition(
+                bot,
+                msg,
+                recipe_id,
+                &pool,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
+        "tags" => {
+            crate::bot::recipe_tags::handle_recipe_tags(
+                bot,
+                msg,
+                recipe_id,
+                &pool,
+                dialogue,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
+        "notes" => {
+            crate::bot::recipe_notes::handle_recipe_notes(
+                bot,
+                msg,
+                recipe_id,
+                &pool,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
+        "copy_text" => {
+            send_recipe_as_text(
+                bot,
+                chat_id,
+                RecipeId(recipe_id),
+                &pool,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
+        "photo" => {
+            send_original_photo(
+                bot,
+                chat_id,
+                RecipeId(recipe_id),
+                &pool,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
+        "share" => {
+            crate::bot::recipe_sharing::handle_share_recipe(
+                bot,
+                msg,
+                recipe_id,
+                &pool,
+                language_code.as_deref(),
+                localization,
+            )
+            .await?;
+        }
         _ => {
             debug!(action = %action, "Unknown recipe action");
         }
@@ -332,12 +510,157 @@ pub async fn handle_recipe_action(
     Ok(())
 }
 
+/// Send a recipe as Markdown text the user can copy into another app
+///
+/// Recipes too long for one message are sent as a `.md` document instead.
+async fn send_recipe_as_text(
+    bot: &Bot,
+    chat_id: ChatId,
+    recipe_id: RecipeId,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    let Some(recipe) = read_recipe_with_name(pool, recipe_id)
+        .await?
+        .filter(|recipe| recipe.telegram_id == TelegramId(chat_id.0))
+    else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+    let ingredients = get_recipe_ingredients(pool, recipe_id).await?;
+    let text = format_recipe_markdown(
+        &recipe,
+        &ingredients,
+        crate::bot::unit_settings::display_units(chat_id),
+        crate::bot::timezone_settings::display_timezone(chat_id),
+        language_code,
+        localization,
+    );
+
+    // Sent without parse mode, so the Markdown stays as typed and copies as is
+    if message_length(&text) <= MAX_MESSAGE_LENGTH {
+        bot.send_message(chat_id, text).await?;
+    } else {
+        debug!(recipe_id = %recipe_id, length = text.len(), "Recipe text sent as a document");
+        let document = InputFile::memory(text.into_bytes())
+            .file_name(recipe_markdown_file_name(recipe.recipe_name.as_deref()));
+        bot.send_document(chat_id, document)
+            .caption(t_lang(
+                localization,
+                "copy-as-text-document-caption",
+                language_code,
+            ))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Send the photo a recipe was read from again
+///
+/// The user is told the photo is no longer available when the recipe has none or
+/// Telegram has forgotten its file.
+async fn send_original_photo(
+    bot: &Bot,
+    chat_id: ChatId,
+    recipe_id: RecipeId,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    if let Some(photo) = get_recipe_photo(pool, TelegramId(chat_id.0), recipe_id).await? {
+        match bot
+            .send_photo(
+                chat_id,
+                InputFile::file_id(teloxide::types::FileId(photo.file_id)),
+            )
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(e) if crate::bot::bot_utils::is_unknown_file(&e) => {
+                debug!(recipe_id = %recipe_id, file_unique_id = %photo.file_unique_id, "Original photo is no longer available");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    bot.send_message(
+        chat_id,
+        t_lang(localization, "original-photo-unavailable", language_code),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Format the statistics of a recipe, as HTML
+///
+/// Statistics over all of the user's recipes are shown by /stats, which the view links to.
+pub fn format_recipe_statistics(
+    recipe: &crate::db::Recipe,
+    ingredients: &[crate::db::Ingredient],
+    timezone: chrono::FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let t = |key: &str| t_lang(localization, key, language_code);
+    let bullet = |key: &str, value: &dyn std::fmt::Display| format!("• {}: {}", t(key), value);
+
+    let mut details = vec![
+        bullet("ingredients-count", &ingredients.len()),
+        bullet(
+            "created-date",
+            &crate::timezone::format_local_datetime(
+                recipe.created_at,
+                timezone,
+                language_code,
+                localization,
+            ),
+        ),
+    ];
+    let mut units: Vec<&str> = Vec::new();
+    for unit in ingredients
+        .iter()
+        .filter_map(|ingredient| ingredient.unit.as_deref())
+        .filter(|unit| !unit.is_empty())
+    {
+        if !units.contains(&unit) {
+            units.push(unit);
+        }
+    }
+    if !units.is_empty() {
+        details.push(bullet("recipe-units-used", &units.join(", ")));
+    }
+
+    HtmlMessage::titled(
+        "📊",
+        &format!(
+            "{}: {}",
+            t("recipe-statistics-title"),
+            recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe")
+        ),
+    )
+    .paragraph_html(
+        &details
+            .iter()
+            .fold(
+                HtmlMessage::titled("📝", &t("recipe-details")),
+                |section, line| section.line(line),
+            )
+            .build(),
+    )
+    .paragraph(&t("recipe-statistics-see-all"))
+    .build()
+}
+
 /// Handle recipe statistics display
 pub async fn handle_recipe_statistics(
     bot: &Bot,
     msg: &MaybeInaccessibleMessage,
     recipe_id: i64,
-    pool: Arc<PgPool>,
+    repository: &dyn Repository,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> Result<()> {
@@ -353,7 +676,10 @@ pub async fn handle_recipe_statistics(
     };
 
     // Get recipe details
-    let recipe = match crate::db::read_recipe_with_name(&pool, recipe_id).await? {
+    let recipe = match repository
+        .read_recipe_with_name(RecipeId(recipe_id))
+        .await?
+    {
         Some(recipe) => recipe,
         None => {
             let message = t_lang(localization, "recipe-not-found", language_code.as_deref());
@@ -363,114 +689,34 @@ pub async fn handle_recipe_statistics(
     };
 
     // Get recipe ingredients
-    let ingredients = crate::db::get_recipe_ingredients(&pool, recipe_id).await?;
-    let ingredient_count = ingredients.len() as i64;
-
-    // Get user statistics
-    let user_stats = crate::db::get_user_recipe_statistics(&pool, chat_id.0).await?;
-
-    // Format statistics message
-    let recipe_name = recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe");
+    let ingredients = repository
+        .get_recipe_ingredients(RecipeId(recipe_id))
+        .await?;
 
-    let mut stats_message = format!(
-        "📊 **{}: {}**\n\n",
-        t_lang(
-            localization,
-            "recipe-statistics-title",
-            language_code.as_deref()
-        ),
-        recipe_name
+    let stats_message = format_recipe_statistics(
+        &recipe,
+        &ingredients,
+        crate::bot::timezone_settings::display_timezone(chat_id),
+        language_code.as_deref(),
+        localization,
     );
 
-    // Recipe-specific stats
-    stats_message.push_str(&format!(
-        "📝 **{}**\n",
-        t_lang(localization, "recipe-details", language_code.as_deref())
-    ));
-    stats_message.push_str(&format!(
-        "• {}: {}\n",
-        t_lang(localization, "ingredients-count", language_code.as_deref()),
-        ingredient_count
-    ));
-    stats_message.push_str(&format!(
-        "• {}: {}\n",
-        t_lang(localization, "created-date", language_code.as_deref()),
-        recipe.created_at.format("%B %d, %Y at %H:%M")
-    ));
-
-    // User overview stats
-    stats_message.push_str(&format!(
-        "\n📈 **{}**\n",
-        t_lang(localization, "your-statistics", language_code.as_deref())
-    ));
-    stats_message.push_str(&format!(
-        "• {}: {}\n",
-        t_lang(localization, "total-recipes", language_code.as_deref()),
-        user_stats.total_recipes
-    ));
-    stats_message.push_str(&format!(
-        "• {}: {}\n",
-        t_lang(localization, "total-ingredients", language_code.as_deref()),
-        user_stats.total_ingredients
-    ));
-    stats_message.push_str(&format!(
-        "• {}: {:.1}\n",
-        t_lang(
-            localization,
-            "avg-ingredients-per-recipe",
-            language_code.as_deref()
-        ),
-        user_stats.average_ingredients_per_recipe
-    ));
-
-    // Recent activity
-    if user_stats.recipes_created_today > 0 || user_stats.recipes_created_this_week > 0 {
-        stats_message.push_str(&format!(
-            "\n🕐 **{}**\n",
-            t_lang(localization, "recent-activity", language_code.as_deref())
-        ));
-
-        if user_stats.recipes_created_today > 0 {
-            stats_message.push_str(&format!(
-                "• {}: {}\n",
-                t_lang(localization, "recipes-today", language_code.as_deref()),
-                user_stats.recipes_created_today
-            ));
-        }
-
-        if user_stats.recipes_created_this_week > 0 {
-            stats_message.push_str(&format!(
-                "• {}: {}\n",
-                t_lang(localization, "recipes-this-week", language_code.as_deref()),
-                user_stats.recipes_created_this_week
-            ));
-        }
-    }
-
-    // Most common units (if any)
-    if !user_stats.most_common_units.is_empty() {
-        stats_message.push_str(&format!(
-            "\n🏷️ **{}**\n",
-            t_lang(localization, "favorite-units", language_code.as_deref())
-        ));
-
-        for (unit, count) in user_stats.most_common_units.iter().take(3) {
-            stats_message.push_str(&format!("• {} ({})\n", unit, count));
-        }
-    }
-
     // Add back button
     let keyboard = vec![vec![InlineKeyboardButton::callback(
         format!(
             "⬅️ {}",
             t_lang(localization, "back-to-recipe", language_code.as_deref())
         ),
-        format!("select_recipe:{}", recipe_name),
+        format!("recipe_instance:{}", recipe_id),
     )]];
 
-    bot.send_message(chat_id, stats_message)
-        .reply_markup(InlineKeyboardMarkup::new(keyboard))
-        .await?;
+    send_long_message(
+        bot,
+        chat_id,
+        &stats_message,
+        Some(InlineKeyboardMarkup::new(keyboard)),
+    )
+    .await?;
 
     Ok(())
 }
@@ -480,7 +726,7 @@ pub async fn handle_delete_recipe_confirmation(
     bot: &Bot,
     msg: &MaybeInaccessibleMessage,
     data: &str,
-    pool: Arc<PgPool>,
+    recipes: &dyn RecipeRepository,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> Result<()> {
@@ -507,10 +753,15 @@ pub async fn handle_delete_recipe_confirmation(
 
     match action {
         "confirm_delete_recipe" => {
-            // Attempt to delete the recipe
-            match crate::db::delete_recipe(&pool, recipe_id).await {
+            // Move the recipe to the trash, where /trash can restore it
+            let deleted = recipes
+                .trash_recipe(TelegramId(chat_id.0), RecipeId(recipe_id))
+                .await;
+            match deleted {
                 Ok(deleted) => {
                     if deleted {
+                        crate::events::emit(RecipeEvent::recipe_deleted(RecipeId(recipe_id)));
+
                         // Delete the confirmation message entirely
                         if let MaybeInaccessibleMessage::Regular(msg) = msg {
                             match bot.delete_message(chat_id, msg.id).await {
@@ -546,6 +797,14 @@ pub async fn handle_delete_recipe_confirmation(
                                 }
                             }
                         }
+
+                        let message = t_args_lang(
+                            localization,
+                            "recipe-trashed",
+                            &[("days", &crate::db::TRASH_RETENTION_DAYS.to_string())],
+                            language_code.as_deref(),
+                        );
+                        bot.send_message(chat_id, message).await?;
                     } else {
                         // Recipe not found - show error and delete both messages
                         if let MaybeInaccessibleMessage::Regular(msg) = msg {
@@ -628,20 +887,22 @@ pub async fn handle_delete_recipe_confirmation(
                         Some(chat_id.0),
                         Some(&[("recipe_id", &recipe_id.to_string())]),
                     );
-                    let message = format!(
-                        "❌ **{}**\n\n{}",
-                        t_lang(
+                    let message = titled(
+                        "❌",
+                        &t_lang(
                             localization,
                             "error-deleting-recipe",
-                            language_code.as_deref()
+                            language_code.as_deref(),
                         ),
-                        t_lang(
+                        &[&t_lang(
                             localization,
                             "error-deleting-recipe-help",
-                            language_code.as_deref()
-                        )
+                            language_code.as_deref(),
+                        )],
                     );
-                    bot.send_message(chat_id, message).await?;
+                    bot.send_message(chat_id, message)
+                        .parse_mode(PARSE_MODE)
+                        .await?;
                 }
             }
         }
@@ -707,7 +968,7 @@ async fn handle_edit_ingredients_callback(
     };
 
     // Get recipe details
-    let recipe = match crate::db::read_recipe_with_name(&pool, recipe_id).await? {
+    let recipe = match crate::db::read_recipe_with_name(&pool, RecipeId(recipe_id)).await? {
         Some(recipe) => recipe,
         None => {
             let message = t_lang(localization, "recipe-not-found", language_code.as_deref());
@@ -716,23 +977,29 @@ async fn handle_edit_ingredients_callback(
         }
     };
 
-    // Get current ingredients
-    let original_ingredients = crate::db::get_recipe_ingredients(&pool, recipe_id).await?;
+    // Get current ingredients, after their version so a write in between shows as a conflict
+    let ingredients_version = crate::db::get_recipe_ingredients_version(&pool, RecipeId(recipe_id))
+        .await?
+        .unwrap_or_default();
+    let original_ingredients =
+        crate::db::get_recipe_ingredients(&pool, RecipeId(recipe_id)).await?;
     if original_ingredients.is_empty() {
-        let message = format!(
-            "❌ **{}**\n\n{}",
-            t_lang(
+        let message = titled(
+            "❌",
+            &t_lang(
                 localization,
                 "no-ingredients-to-edit",
-                language_code.as_deref()
+                language_code.as_deref(),
             ),
-            t_lang(
+            &[&t_lang(
                 localization,
                 "no-ingredients-to-edit-help",
-                language_code.as_deref()
-            )
+                language_code.as_deref(),
+            )],
         );
-        bot.send_message(chat_id, message).await?;
+        bot.send_message(chat_id, message)
+            .parse_mode(PARSE_MODE)
+            .await?;
         return Ok(());
     }
 
@@ -741,23 +1008,34 @@ async fn handle_edit_ingredients_callback(
         crate::ingredient_editing::ingredients_to_measurement_matches(&original_ingredients);
 
     // Send editing interface
-    let edit_message = format!(
-        "✏️ **{}: {}**\n\n{}\n\n{}",
-        t_lang(localization, "editing-recipe", language_code.as_deref()),
-        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
-        t_lang(
+    let edit_message = HtmlMessage::titled(
+        "✏️",
+        &format_editing_title(
+            current_matches.len(),
+            Some(recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe")),
+            language_code.as_deref(),
             localization,
-            "editing-instructions",
-            language_code.as_deref()
         ),
-        format_ingredients_list(&current_matches, language_code.as_deref(), localization)
-    );
+    )
+    .paragraph(&t_lang(
+        localization,
+        "editing-instructions",
+        language_code.as_deref(),
+    ))
+    .paragraph_html(&format_ingredients_list(
+        &current_matches,
+        crate::bot::unit_settings::display_units(chat_id),
+        language_code.as_deref(),
+        localization,
+    ))
+    .build();
 
     let keyboard =
         create_ingredient_review_keyboard(&current_matches, language_code.as_deref(), localization);
 
     let sent_message = bot
         .send_message(chat_id, edit_message)
+        .parse_mode(PARSE_MODE)
         .reply_markup(keyboard)
         .await?;
 
@@ -765,10 +1043,14 @@ async fn handle_edit_ingredients_callback(
     dialogue
         .update(RecipeDialogueState::EditingSavedIngredients {
             recipe_id,
-            original_ingredients,
+            original_ingredients: crate::ingredient_editing::snapshot_ingredients(
+                &original_ingredients,
+            ),
             current_matches,
             language_code: language_code.clone(),
             message_id: Some(sent_message.id.0 as i32),
+            last_deleted: None,
+            ingredients_version,
         })
         .await?;
 
@@ -8,32 +8,86 @@ use anyhow::Result;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
-use teloxide::types::InlineKeyboardMarkup;
+use teloxide::types::{CallbackQuery, InlineKeyboardMarkup};
 use tracing::debug;
 
 // Import error logging utilities
 use crate::errors::error_logging;
 
 // Import localization
-use crate::localization::{t_args_lang, t_lang};
+use crate::localization::{t_args_lang, t_lang, t_plural};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 
 // Import UI components for the focused editing interface
+use crate::bot::formatting::{
+    escape_html, labelled, send_long_message, titled, HtmlMessage, PARSE_MODE,
+};
+use crate::bot::ocr_raw_text::{handle_show_raw_text_callback, SHOW_RAW_TEXT_CALLBACK};
+use crate::bot::ui_builder::{
+    create_name_conflict_keyboard, create_quantity_adjust_keyboard,
+    create_recipe_name_suggestions_keyboard, format_edit_ingredient_prompt,
+    format_name_conflict_prompt, format_recipe_details, format_review_message,
+    parse_name_suggestion_callback, with_undo_delete_button, RECIPE_NAME_SUGGESTIONS,
+};
 use crate::bot::ui_components::create_ingredient_editing_keyboard;
 use crate::bot::{
-    create_ingredient_review_keyboard, create_post_confirmation_keyboard, format_ingredients_list,
+    create_ingredient_review_keyboard_for_variant, create_post_confirmation_keyboard,
+};
+
+// Import review keyboard experiment helpers
+use crate::cache::SharedCacheManager;
+use crate::db::{
+    get_recipe_ingredients, read_recipe_with_name, update_recipe_ingredients,
+    update_recipe_ingredients_cached, RecipeId, TelegramId,
+};
+use crate::events::RecipeEvent;
+use crate::experiments::{review_keyboard_variant, track_review_funnel_event, FunnelEvent};
+use crate::extraction_feedback::{self, Correction};
+use crate::ingredient_editing::{
+    adjust_quantity, delete_ingredient, ingredients_missing_from,
+    ingredients_to_measurement_matches, undo_ingredient_deletion, AdjustCallback,
 };
 
 // Import HandlerContext
 use crate::bot::HandlerContext;
 
+// Import repositories
+use crate::repository::{NewRecipe, PgRepository, Repository};
+
 // Import callback types module
 use super::callback_types::ReviewIngredientsParams;
 
 // Import dialogue manager functions
-use crate::bot::dialogue_manager::save_ingredients_to_database;
+use crate::bot::dialogue_manager::{
+    save_idempotency_key, save_ingredients_to_database, save_recipe_with_name, saved_recipe_photo,
+    saved_recipe_text, send_saved_confirmation, RecipeNameAfterConfirmInputParams,
+};
+
+/// Whether callback data comes from an ingredient review keyboard
+///
+/// Used to spot buttons tapped on a review whose dialogue has already ended.
+pub fn is_review_keyboard_callback(data: &str) -> bool {
+    let indexed = |prefix: &str| {
+        data.strip_prefix(prefix)
+            .is_some_and(|index| index.parse::<usize>().is_ok())
+    };
+
+    matches!(
+        data,
+        "confirm"
+            | "add_more"
+            | "cancel_review"
+            | "cancel_ingredient_editing"
+            | "rename_pending"
+            | "undo_delete"
+            | SHOW_RAW_TEXT_CALLBACK
+    ) || data.starts_with("name_conflict_")
+        || AdjustCallback::from_callback_data(data).is_some()
+        || indexed("edit_")
+        || indexed("delete_")
+}
 
 /// Handle callbacks when in ReviewIngredients dialogue state
 pub async fn handle_review_ingredients_callbacks(
@@ -43,6 +97,7 @@ pub async fn handle_review_ingredients_callbacks(
     pool: Arc<PgPool>,
     dialogue: &RecipeDialogue,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     let dialogue_state = dialogue.get().await?;
     if let Some(RecipeDialogueState::ReviewIngredients {
@@ -52,6 +107,7 @@ pub async fn handle_review_ingredients_callbacks(
         message_id,
         extracted_text,
         recipe_name_from_caption,
+        last_deleted,
     }) = dialogue_state
     {
         if q.message.is_some() {
@@ -73,6 +129,7 @@ pub async fn handle_review_ingredients_callbacks(
                     recipe_name_from_caption: Some(&recipe_name_from_caption),
                     dialogue,
                     pool: None,
+                    cache,
                 })
                 .await?;
             } else if data.starts_with("delete_") {
@@ -92,34 +149,116 @@ pub async fn handle_review_ingredients_callbacks(
                     extracted_text: &extracted_text,
                     recipe_name_from_caption: Some(&recipe_name_from_caption),
                     dialogue,
-                    pool: None,
+                    pool: Some(&pool),
+                    cache,
                 })
                 .await?;
+            } else if data == "undo_delete" {
+                handle_undo_delete_button(
+                    ReviewIngredientsParams {
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: dialogue_lang_code.as_deref(),
+                        },
+                        q,
+                        data: None,
+                        ingredients: Some(&mut ingredients),
+                        ingredients_slice: None,
+                        recipe_name: &recipe_name,
+                        dialogue_lang_code: &dialogue_lang_code,
+                        message_id,
+                        extracted_text: &extracted_text,
+                        recipe_name_from_caption: Some(&recipe_name_from_caption),
+                        dialogue,
+                        pool: None,
+                        cache,
+                    },
+                    last_deleted,
+                )
+                .await?;
             } else if data == "confirm" {
-                handle_confirm_button(ReviewIngredientsParams {
-                    ctx: &HandlerContext {
-                        bot,
-                        localization,
-                        language_code: dialogue_lang_code.as_deref(),
+                let repository = PgRepository::new(pool.clone(), cache.cloned());
+                handle_confirm_button(
+                    ReviewIngredientsParams {
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: dialogue_lang_code.as_deref(),
+                        },
+                        q,
+                        data: None,
+                        ingredients: None,
+                        ingredients_slice: Some(&ingredients),
+                        recipe_name: &recipe_name,
+                        dialogue_lang_code: &dialogue_lang_code,
+                        message_id,
+                        extracted_text: &extracted_text,
+                        recipe_name_from_caption: Some(&recipe_name_from_caption),
+                        dialogue,
+                        pool: Some(&pool),
+                        cache,
                     },
-                    q,
-                    data: None,
-                    ingredients: None,
-                    ingredients_slice: Some(&ingredients),
-                    recipe_name: &recipe_name,
-                    dialogue_lang_code: &dialogue_lang_code,
-                    message_id,
-                    extracted_text: &extracted_text,
-                    recipe_name_from_caption: Some(&recipe_name_from_caption),
-                    dialogue,
-                    pool: Some(&pool),
-                })
+                    &repository,
+                )
+                .await?;
+            } else if let Some(callback) = AdjustCallback::from_callback_data(data) {
+                handle_adjust_button(
+                    ReviewIngredientsParams {
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: dialogue_lang_code.as_deref(),
+                        },
+                        q,
+                        data: Some(data),
+                        ingredients: Some(&mut ingredients),
+                        ingredients_slice: None,
+                        recipe_name: &recipe_name,
+                        dialogue_lang_code: &dialogue_lang_code,
+                        message_id,
+                        extracted_text: &extracted_text,
+                        recipe_name_from_caption: Some(&recipe_name_from_caption),
+                        dialogue,
+                        pool: Some(&pool),
+                        cache,
+                    },
+                    callback,
+                )
                 .await?;
             } else if data == "add_more" {
                 handle_add_more_button(bot, q, &dialogue_lang_code, dialogue, localization).await?;
+            } else if data == "rename_pending" {
+                handle_rename_pending_button(
+                    bot,
+                    q,
+                    recipe_name_from_caption.as_deref(),
+                    &dialogue_lang_code,
+                    dialogue,
+                    localization,
+                )
+                .await?;
+            } else if data == SHOW_RAW_TEXT_CALLBACK {
+                // Only reads the review, which stays as it is
+                handle_show_raw_text_callback(
+                    bot,
+                    q,
+                    dialogue,
+                    localization,
+                    dialogue_lang_code.as_deref(),
+                )
+                .await?;
             } else if data == "cancel_review" {
-                handle_cancel_review_button(bot, q, &dialogue_lang_code, dialogue, localization)
-                    .await?;
+                handle_cancel_review_button(
+                    bot,
+                    q,
+                    message_id,
+                    ingredients.len(),
+                    &dialogue_lang_code,
+                    dialogue,
+                    localization,
+                )
+                .await?;
             } else if data.starts_with("workflow_") {
                 super::workflow_callbacks::handle_workflow_button(
                     bot,
@@ -128,6 +267,7 @@ pub async fn handle_review_ingredients_callbacks(
                     &pool,
                     dialogue,
                     localization,
+                    cache,
                 )
                 .await?;
             }
@@ -180,26 +320,10 @@ async fn handle_edit_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
         let ingredient = &ingredients[index];
 
         // Create focused editing prompt message
-        let edit_prompt = format!(
-            "✏️ {}\n\n{}: **{} {} {}**\n\n{}",
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-title",
-                dialogue_lang_code.as_deref()
-            ),
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-current",
-                dialogue_lang_code.as_deref()
-            ),
-            ingredient.quantity,
-            ingredient.measurement.as_deref().unwrap_or(""),
-            ingredient.ingredient_name,
-            t_lang(
-                ctx.localization,
-                "edit-ingredient-instruction",
-                dialogue_lang_code.as_deref()
-            )
+        let edit_prompt = format_edit_ingredient_prompt(
+            ingredient,
+            dialogue_lang_code.as_deref(),
+            ctx.localization,
         );
 
         // Create focused editing keyboard with cancel button only
@@ -220,6 +344,7 @@ async fn handle_edit_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
                 ),
                 edit_prompt.clone(),
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard.clone())
             .await
         {
@@ -241,6 +366,7 @@ async fn handle_edit_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
                             .id,
                         edit_prompt,
                     )
+                    .parse_mode(PARSE_MODE)
                     .reply_markup(keyboard)
                     .await?
             }
@@ -263,6 +389,66 @@ async fn handle_edit_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
     Ok(())
 }
 
+/// Handle rename button in review ingredients state
+///
+/// Asks for the new name of the recipe under review; the typed name is handled by
+/// `handle_pending_recipe_rename_input`, which re-renders the review message.
+async fn handle_rename_pending_button(
+    bot: &Bot,
+    q: &CallbackQuery,
+    current_name: Option<&str>,
+    dialogue_lang_code: &Option<String>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    let Some(renaming) = dialogue
+        .get()
+        .await?
+        .and_then(RecipeDialogueState::into_pending_rename)
+    else {
+        return Ok(());
+    };
+
+    let mut prompt = HtmlMessage::titled(
+        "✏️",
+        &t_lang(
+            localization,
+            "rename-recipe-title",
+            dialogue_lang_code.as_deref(),
+        ),
+    );
+    if let Some(name) = current_name {
+        prompt = prompt.paragraph_html(&labelled(
+            &t_lang(
+                localization,
+                "current-recipe-name",
+                dialogue_lang_code.as_deref(),
+            ),
+            name,
+        ));
+    }
+    let prompt = prompt
+        .paragraph(&t_lang(
+            localization,
+            "review-rename-instructions",
+            dialogue_lang_code.as_deref(),
+        ))
+        .build();
+
+    let chat_id = q
+        .message
+        .as_ref()
+        .expect("Callback query should have a message")
+        .chat()
+        .id;
+    bot.send_message(chat_id, prompt)
+        .parse_mode(PARSE_MODE)
+        .await?;
+
+    dialogue.update(renaming).await?;
+    Ok(())
+}
+
 /// Handle delete button in review ingredients state
 async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
     let ReviewIngredientsParams {
@@ -276,6 +462,7 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
         extracted_text,
         recipe_name_from_caption,
         dialogue,
+        pool,
         ..
     } = params;
 
@@ -295,29 +482,36 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
             None, // No session duration for individual actions
             dialogue_lang_code.as_deref(),
         );
+        if let Some(pool) = pool {
+            track_review_funnel_event(pool, TelegramId(q.from.id.0 as i64), FunnelEvent::EditsMade)
+                .await;
+        }
+        extraction_feedback::record_correction(TelegramId(q.from.id.0 as i64), Correction::Deleted);
 
-        ingredients.remove(index);
+        let deleted = delete_ingredient(ingredients, index);
 
         // Check if all ingredients were deleted
         if ingredients.is_empty() {
             // All ingredients deleted - inform user and provide options
-            let empty_message = format!(
-                "🗑️ **{}**\n\n{}\n\n{}",
-                t_lang(
+            let empty_message = titled(
+                "🗑️",
+                &t_lang(
                     ctx.localization,
                     "review-title",
-                    dialogue_lang_code.as_deref()
-                ),
-                t_lang(
-                    ctx.localization,
-                    "review-no-ingredients",
-                    dialogue_lang_code.as_deref()
+                    dialogue_lang_code.as_deref(),
                 ),
-                t_lang(
-                    ctx.localization,
-                    "review-no-ingredients-help",
-                    dialogue_lang_code.as_deref()
-                )
+                &[
+                    &t_lang(
+                        ctx.localization,
+                        "review-no-ingredients",
+                        dialogue_lang_code.as_deref(),
+                    ),
+                    &t_lang(
+                        ctx.localization,
+                        "review-no-ingredients-help",
+                        dialogue_lang_code.as_deref(),
+                    ),
+                ],
             );
 
             let keyboard = vec![vec![
@@ -350,7 +544,12 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
                         .id(),
                     empty_message,
                 )
-                .reply_markup(teloxide::types::InlineKeyboardMarkup::new(keyboard))
+                .parse_mode(PARSE_MODE)
+                .reply_markup(with_undo_delete_button(
+                    teloxide::types::InlineKeyboardMarkup::new(keyboard),
+                    dialogue_lang_code.as_deref(),
+                    ctx.localization,
+                ))
                 .await
             {
                 Ok(_) => (),
@@ -365,27 +564,21 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
             }
         } else {
             // Update the message with remaining ingredients
-            let review_message = format!(
-                "📝 **{}**\n\n{}\n\n{}",
-                t_lang(
-                    ctx.localization,
-                    "review-title",
-                    dialogue_lang_code.as_deref()
-                ),
-                t_lang(
-                    ctx.localization,
-                    "review-description",
-                    dialogue_lang_code.as_deref()
-                ),
-                format_ingredients_list(
-                    ingredients,
-                    dialogue_lang_code.as_deref(),
-                    ctx.localization
-                )
+            let review_message = format_review_message(
+                ingredients,
+                recipe_name_from_caption.and_then(|name| name.as_deref()),
+                crate::bot::unit_settings::display_units(q.from.id.into()),
+                dialogue_lang_code.as_deref(),
+                ctx.localization,
             );
 
-            let keyboard = create_ingredient_review_keyboard(
-                ingredients,
+            let keyboard = with_undo_delete_button(
+                create_ingredient_review_keyboard_for_variant(
+                    ingredients,
+                    dialogue_lang_code.as_deref(),
+                    ctx.localization,
+                    review_keyboard_variant(TelegramId(q.from.id.0 as i64)),
+                ),
                 dialogue_lang_code.as_deref(),
                 ctx.localization,
             );
@@ -405,6 +598,7 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
                         .id(),
                     review_message,
                 )
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await
             {
@@ -429,6 +623,7 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
                 message_id,
                 extracted_text: extracted_text.to_string(),
                 recipe_name_from_caption: recipe_name_from_caption.cloned().flatten(), // Preserve caption info
+                last_deleted: deleted, // Offered for undo until the next action
             })
             .await
         {
@@ -446,8 +641,222 @@ async fn handle_delete_button(params: ReviewIngredientsParams<'_>) -> Result<()>
     Ok(())
 }
 
+/// Handle undo button after a deletion in review ingredients state
+///
+/// Puts the last deleted ingredient back at its original position. The button only
+/// covers the most recent deletion, and is gone once the review is re-rendered.
+async fn handle_undo_delete_button(
+    params: ReviewIngredientsParams<'_>,
+    last_deleted: Option<(usize, crate::text_processing::MeasurementMatch)>,
+) -> Result<()> {
+    let ReviewIngredientsParams {
+        ctx,
+        q,
+        ingredients,
+        recipe_name,
+        dialogue_lang_code,
+        message_id,
+        extracted_text,
+        recipe_name_from_caption,
+        dialogue,
+        ..
+    } = params;
+
+    let ingredients = ingredients.expect("Ingredients should be provided for undo callback");
+    let Some(deleted) = last_deleted else {
+        // Stale button from an earlier deletion, nothing to restore
+        return Ok(());
+    };
+    undo_ingredient_deletion(ingredients, deleted);
+    extraction_feedback::record_correction(
+        TelegramId(q.from.id.0 as i64),
+        Correction::DeletionUndone,
+    );
+
+    let review_message = format_review_message(
+        ingredients,
+        recipe_name_from_caption.and_then(|name| name.as_deref()),
+        crate::bot::unit_settings::display_units(q.from.id.into()),
+        dialogue_lang_code.as_deref(),
+        ctx.localization,
+    );
+    let keyboard = create_ingredient_review_keyboard_for_variant(
+        ingredients,
+        dialogue_lang_code.as_deref(),
+        ctx.localization,
+        review_keyboard_variant(TelegramId(q.from.id.0 as i64)),
+    );
+
+    if let Some(message) = &q.message {
+        if let Err(e) = ctx
+            .bot
+            .edit_message_text(message.chat().id, message.id(), review_message)
+            .parse_mode(PARSE_MODE)
+            .reply_markup(keyboard)
+            .await
+        {
+            error_logging::log_internal_error(
+                &e,
+                "callback_handler",
+                "Failed to edit message after undoing ingredient deletion",
+                Some(q.from.id.0 as i64),
+            );
+        }
+    }
+
+    dialogue
+        .update(RecipeDialogueState::ReviewIngredients {
+            recipe_name: recipe_name.to_string(),
+            ingredients: ingredients.clone(),
+            language_code: dialogue_lang_code.clone(),
+            message_id,
+            extracted_text: extracted_text.to_string(),
+            recipe_name_from_caption: recipe_name_from_caption.cloned().flatten(),
+            last_deleted: None,
+        })
+        .await?;
+    Ok(())
+}
+
+/// Handle quantity adjustment buttons in review ingredients state
+///
+/// Opening and closing only swap the keyboard; each step updates the quantity in
+/// the dialogue state and re-renders the review in place, keeping the step buttons.
+async fn handle_adjust_button(
+    params: ReviewIngredientsParams<'_>,
+    callback: AdjustCallback,
+) -> Result<()> {
+    let ReviewIngredientsParams {
+        ctx,
+        q,
+        ingredients,
+        recipe_name,
+        dialogue_lang_code,
+        message_id,
+        extracted_text,
+        recipe_name_from_caption,
+        dialogue,
+        pool,
+        ..
+    } = params;
+
+    let ingredients = ingredients.expect("Ingredients should be provided for adjust callback");
+    let message = q
+        .message
+        .as_ref()
+        .expect("Callback query should have a message");
+    let chat_id = message.chat().id;
+
+    let (index, step) = match callback {
+        AdjustCallback::Open(index) => {
+            if let Some(ingredient) = ingredients.get(index) {
+                ctx.bot
+                    .edit_message_reply_markup(chat_id, message.id())
+                    .reply_markup(create_quantity_adjust_keyboard(
+                        index,
+                        ingredient,
+                        ctx.language_code,
+                        ctx.localization,
+                    ))
+                    .await?;
+            }
+            return Ok(());
+        }
+        AdjustCallback::Done => {
+            ctx.bot
+                .edit_message_reply_markup(chat_id, message.id())
+                .reply_markup(create_ingredient_review_keyboard_for_variant(
+                    ingredients,
+                    ctx.language_code,
+                    ctx.localization,
+                    review_keyboard_variant(TelegramId(q.from.id.0 as i64)),
+                ))
+                .await?;
+            return Ok(());
+        }
+        AdjustCallback::Step(index, step) => (index, step),
+    };
+
+    let Some(ingredient) = ingredients.get_mut(index) else {
+        return Ok(());
+    };
+    let Some(quantity) = adjust_quantity(&ingredient.quantity, step) else {
+        ctx.bot
+            .send_message(
+                chat_id,
+                t_lang(
+                    ctx.localization,
+                    "adjust-quantity-not-numeric",
+                    ctx.language_code,
+                ),
+            )
+            .await?;
+        return Ok(());
+    };
+    ingredient.quantity = quantity;
+    ingredient.requires_quantity_confirmation = false;
+
+    crate::observability::record_user_engagement_metrics(
+        q.from.id.0 as i64,
+        crate::observability::UserAction::IngredientEdit,
+        None, // No session duration for individual actions
+        ctx.language_code,
+    );
+    if let Some(pool) = pool {
+        track_review_funnel_event(pool, TelegramId(q.from.id.0 as i64), FunnelEvent::EditsMade)
+            .await;
+    }
+    extraction_feedback::record_correction(TelegramId(q.from.id.0 as i64), Correction::Edited);
+
+    let review_message = format_review_message(
+        ingredients,
+        recipe_name_from_caption.and_then(|name| name.as_deref()),
+        crate::bot::unit_settings::display_units(q.from.id.into()),
+        ctx.language_code,
+        ctx.localization,
+    );
+    if let Err(e) = ctx
+        .bot
+        .edit_message_text(chat_id, message.id(), review_message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(create_quantity_adjust_keyboard(
+            index,
+            &ingredients[index],
+            ctx.language_code,
+            ctx.localization,
+        ))
+        .await
+    {
+        error_logging::log_internal_error(
+            &e,
+            "callback_handler",
+            "Failed to edit message after quantity adjustment",
+            Some(q.from.id.0 as i64),
+        );
+    }
+
+    dialogue
+        .update(RecipeDialogueState::ReviewIngredients {
+            recipe_name: recipe_name.to_string(),
+            ingredients: ingredients.clone(),
+            language_code: dialogue_lang_code.clone(),
+            message_id,
+            extracted_text: extracted_text.to_string(),
+            recipe_name_from_caption: recipe_name_from_caption.cloned().flatten(),
+            last_deleted: None,
+        })
+        .await?;
+    Ok(())
+}
+
 /// Handle confirm button in review ingredients state
-async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()> {
+///
+/// The recipe is looked up and saved through `repository`. The review funnel and
+/// extraction feedback are recorded in `pool` when the params carry one.
+pub async fn handle_confirm_button(
+    params: ReviewIngredientsParams<'_>,
+    repository: &dyn Repository,
+) -> Result<()> {
     let ReviewIngredientsParams {
         ctx,
         q,
@@ -462,7 +871,6 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
 
     let ingredients =
         ingredients_slice.expect("Ingredients slice should be provided for confirm callback");
-    let pool = pool.expect("Database pool should be provided for confirm callback");
 
     // Record user engagement metric for recipe confirmation
     crate::observability::record_user_engagement_metrics(
@@ -471,22 +879,92 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
         None, // No session duration for individual actions
         dialogue_lang_code.as_deref(),
     );
+    if let Some(pool) = pool {
+        track_review_funnel_event(pool, TelegramId(q.from.id.0 as i64), FunnelEvent::Confirmed)
+            .await;
+        extraction_feedback::record_confirmed_review(
+            pool,
+            TelegramId(q.from.id.0 as i64),
+            dialogue_lang_code.as_deref(),
+        )
+        .await;
+    }
 
     // Check if we have a recipe name from caption
     if let Some(caption_recipe_name) = recipe_name_from_caption.and_then(|opt| opt.as_ref()) {
         // STREAMLINED WORKFLOW: Skip recipe name input when caption is available
         debug!(user_id = %q.from.id, recipe_name = %caption_recipe_name, "Using recipe name from caption, skipping name input");
 
+        let chat_id = q
+            .message
+            .as_ref()
+            .expect("Callback query should have a message")
+            .chat()
+            .id;
+
+        // Pause instead of silently creating a second recipe with the same name
+        match repository
+            .get_recipes_by_name(TelegramId(q.from.id.0 as i64), caption_recipe_name)
+            .await
+        {
+            Ok(existing) if !existing.is_empty() => {
+                remove_review_keyboard(ctx, q, "handle_confirm_button").await;
+
+                let prompt = format_name_conflict_prompt(
+                    caption_recipe_name,
+                    existing.len(),
+                    dialogue_lang_code.as_deref(),
+                    ctx.localization,
+                );
+                ctx.bot
+                    .send_message(chat_id, prompt)
+                    .reply_markup(create_name_conflict_keyboard(
+                        true,
+                        dialogue_lang_code.as_deref(),
+                        ctx.localization,
+                    ))
+                    .await?;
+
+                // Most recent first, as returned by get_recipes_by_name
+                dialogue
+                    .update(RecipeDialogueState::ResolvingRecipeNameConflict {
+                        recipe_name: caption_recipe_name.clone(),
+                        ingredients: ingredients.to_vec(),
+                        language_code: dialogue_lang_code.clone(),
+                        extracted_text: extracted_text.to_string(),
+                        newest_recipe_id: existing[0].id.0,
+                        existing_count: existing.len(),
+                    })
+                    .await?;
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                // A failed lookup must not block saving the recipe
+                error_logging::log_database_error(
+                    &e,
+                    "get_recipes_by_name",
+                    Some(q.from.id.0 as i64),
+                    None,
+                );
+            }
+        }
+
         // Save ingredients directly to database
-        if let Err(e) = save_ingredients_to_database(
-            pool,
-            q.from.id.0 as i64,
-            extracted_text,
-            ingredients,
-            caption_recipe_name,
-            dialogue_lang_code.as_deref(),
-        )
-        .await
+        let idempotency_key = save_idempotency_key(chat_id);
+        let photo = saved_recipe_photo(chat_id);
+        let extracted_text = saved_recipe_text(chat_id, extracted_text);
+        if let Err(e) = repository
+            .save_recipe(NewRecipe {
+                telegram_id: TelegramId(q.from.id.0 as i64),
+                recipe_name: caption_recipe_name,
+                extracted_text: &extracted_text,
+                ingredients,
+                language_code: dialogue_lang_code.as_deref(),
+                idempotency_key: idempotency_key.as_deref(),
+                photo: photo.as_ref(),
+            })
+            .await
         {
             error_logging::log_database_error(
                 &e,
@@ -496,14 +974,10 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
             );
             ctx.bot
                 .send_message(
-                    q.message
-                        .as_ref()
-                        .expect("Callback query should have a message")
-                        .chat()
-                        .id,
+                    chat_id,
                     t_lang(
                         ctx.localization,
-                        "error-processing-failed",
+                        "error-recipe-save-failed",
                         dialogue_lang_code.as_deref(),
                     ),
                 )
@@ -512,66 +986,9 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
         }
 
         // Remove the keyboard from the ingredients message to keep it visible
-        match ctx
-            .bot
-            .edit_message_reply_markup(
-                q.message
-                    .as_ref()
-                    .expect("Callback query should have a message")
-                    .chat()
-                    .id,
-                q.message
-                    .as_ref()
-                    .expect("Callback query should have a message")
-                    .id(),
-            )
-            .await
-        {
-            Ok(_) => (),
-            Err(e) => {
-                error_logging::log_internal_error(
-                    &e,
-                    "handle_confirm_button",
-                    "Failed to remove keyboard from ingredients message",
-                    Some(q.from.id.0 as i64),
-                );
-            }
-        }
-
-        // Send confirmation as a new message
-        let confirmation_message = format!(
-            "✅ **{}**\n\n📝 {}\n\n{}",
-            t_lang(
-                ctx.localization,
-                "workflow-recipe-saved",
-                dialogue_lang_code.as_deref()
-            ),
-            t_args_lang(
-                ctx.localization,
-                "caption-recipe-saved",
-                &[("recipe_name", caption_recipe_name.as_str())],
-                dialogue_lang_code.as_deref()
-            ),
-            t_lang(
-                ctx.localization,
-                "workflow-what-next",
-                dialogue_lang_code.as_deref()
-            )
-        );
+        remove_review_keyboard(ctx, q, "handle_confirm_button").await;
 
-        let confirmation_keyboard =
-            create_post_confirmation_keyboard(dialogue_lang_code.as_deref(), ctx.localization);
-
-        ctx.bot
-            .send_message(
-                q.message
-                    .as_ref()
-                    .expect("Callback query should have a message")
-                    .chat()
-                    .id,
-                confirmation_message,
-            )
-            .reply_markup(confirmation_keyboard)
+        send_caption_save_confirmation(ctx, chat_id, caption_recipe_name, ingredients.len())
             .await?;
 
         // End the dialogue - workflow complete
@@ -581,48 +998,49 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
         debug!(user_id = %q.from.id, "No caption available, proceeding with recipe name input");
 
         // Remove the keyboard from the ingredients message to keep it visible
-        match ctx
-            .bot
-            .edit_message_reply_markup(
-                q.message
-                    .as_ref()
-                    .expect("Callback query should have a message")
-                    .chat()
-                    .id,
-                q.message
-                    .as_ref()
-                    .expect("Callback query should have a message")
-                    .id(),
+        remove_review_keyboard(ctx, q, "handle_confirm_button").await;
+
+        // Recent names are offered as buttons; a failed lookup only leaves them out
+        let name_suggestions = match repository
+            .get_recent_recipe_names(
+                TelegramId(q.from.id.0 as i64),
+                RECIPE_NAME_SUGGESTIONS as i64,
             )
             .await
         {
-            Ok(_) => (),
+            Ok(names) => names,
             Err(e) => {
-                error_logging::log_internal_error(
+                error_logging::log_database_error(
                     &e,
-                    "handle_confirm_button",
-                    "Failed to remove keyboard from ingredients message",
+                    "get_recent_recipe_names",
                     Some(q.from.id.0 as i64),
+                    None,
                 );
+                Vec::new()
             }
-        }
+        };
 
         // Send recipe name prompt as a new message
-        let recipe_name_prompt = format!(
-            "🏷️ **{}**\n\n{}",
-            t_lang(
+        let hint_key = if name_suggestions.is_empty() {
+            "recipe-name-prompt-hint"
+        } else {
+            "recipe-name-suggestions-hint"
+        };
+        let recipe_name_prompt = titled(
+            "🏷️",
+            &t_lang(
                 ctx.localization,
                 "recipe-name-prompt",
-                dialogue_lang_code.as_deref()
+                dialogue_lang_code.as_deref(),
             ),
-            t_lang(
+            &[&t_lang(
                 ctx.localization,
-                "recipe-name-prompt-hint",
-                dialogue_lang_code.as_deref()
-            )
+                hint_key,
+                dialogue_lang_code.as_deref(),
+            )],
         );
 
-        let prompt_msg = ctx
+        let request = ctx
             .bot
             .send_message(
                 q.message
@@ -632,7 +1050,14 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
                     .id,
                 recipe_name_prompt,
             )
-            .await?;
+            .parse_mode(PARSE_MODE);
+        let prompt_msg = if name_suggestions.is_empty() {
+            request.await?
+        } else {
+            request
+                .reply_markup(create_recipe_name_suggestions_keyboard(&name_suggestions))
+                .await?
+        };
 
         // Transition to waiting for recipe name after confirmation
         dialogue
@@ -642,6 +1067,7 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
                 extracted_text: extracted_text.to_string(),
                 recipe_name_from_caption: recipe_name_from_caption.cloned().flatten(), // Preserve caption info from ReviewIngredients state
                 message_id: Some(prompt_msg.id.0 as i32), // Store prompt message ID
+                name_suggestions,
             })
             .await?;
     }
@@ -649,6 +1075,452 @@ async fn handle_confirm_button(params: ReviewIngredientsParams<'_>) -> Result<()
     Ok(())
 }
 
+/// Handle a tap on a recent recipe name offered for the recipe awaiting one
+///
+/// The name is saved as if typed, duplicate check included.
+pub async fn handle_recipe_name_suggestion_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let Some(RecipeDialogueState::WaitingForRecipeNameAfterConfirm {
+        ingredients,
+        language_code,
+        extracted_text,
+        message_id,
+        name_suggestions,
+        ..
+    }) = dialogue.get().await?
+    else {
+        return Ok(());
+    };
+    let Some(name) = parse_name_suggestion_callback(data).and_then(|i| name_suggestions.get(i))
+    else {
+        return Ok(());
+    };
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+    debug!(user_id = %q.from.id, recipe_name = %name, "Recipe name suggestion tapped");
+
+    let ctx = HandlerContext {
+        bot,
+        localization,
+        language_code: language_code.as_deref(),
+    };
+    // The suggestions are answered; a name conflict prompt follows as its own message
+    remove_review_keyboard(&ctx, q, "handle_recipe_name_suggestion_callback").await;
+
+    save_recipe_with_name(
+        msg.chat().id,
+        dialogue.clone(),
+        RecipeNameAfterConfirmInputParams {
+            pool,
+            recipe_name_input: name,
+            ingredients,
+            ctx: &ctx,
+            extracted_text,
+            message_id,
+            cache,
+        },
+    )
+    .await
+}
+
+/// Remove the inline keyboard from the message a callback came from, keeping its text
+async fn remove_review_keyboard(ctx: &HandlerContext<'_>, q: &CallbackQuery, operation: &str) {
+    let Some(msg) = &q.message else {
+        return;
+    };
+
+    if let Err(e) = ctx
+        .bot
+        .edit_message_reply_markup(msg.chat().id, msg.id())
+        .await
+    {
+        error_logging::log_internal_error(
+            &e,
+            operation,
+            "Failed to remove keyboard from ingredients message",
+            Some(q.from.id.0 as i64),
+        );
+    }
+}
+
+/// Send the "recipe saved" confirmation used by the caption workflow
+async fn send_caption_save_confirmation(
+    ctx: &HandlerContext<'_>,
+    chat_id: ChatId,
+    recipe_name: &str,
+    ingredient_count: usize,
+) -> Result<()> {
+    let confirmation_message = titled(
+        "✅",
+        &t_lang(ctx.localization, "workflow-recipe-saved", ctx.language_code),
+        &[
+            &format!(
+                "📝 {}",
+                t_plural(
+                    ctx.localization,
+                    "ingredients-saved",
+                    ingredient_count,
+                    &[("recipe_name", recipe_name)],
+                    ctx.language_code,
+                )
+            ),
+            &t_lang(ctx.localization, "workflow-what-next", ctx.language_code),
+        ],
+    );
+
+    send_saved_confirmation(
+        ctx.bot,
+        chat_id,
+        None,
+        confirmation_message,
+        Some(create_post_confirmation_keyboard(
+            ctx.language_code,
+            ctx.localization,
+        )),
+    )
+    .await
+}
+
+/// Handle callbacks when a recipe with the chosen name already exists
+///
+/// The pending ingredients stay in the dialogue state until the user saves them as a
+/// new copy, replaces the newest existing recipe, or merges the missing ones into it;
+/// viewing the existing recipe keeps them untouched.
+pub async fn handle_name_conflict_callbacks(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let dialogue_state = dialogue.get().await?;
+    let Some(RecipeDialogueState::ResolvingRecipeNameConflict {
+        recipe_name,
+        ingredients,
+        language_code,
+        extracted_text,
+        newest_recipe_id,
+        ..
+    }) = dialogue_state
+    else {
+        return Ok(());
+    };
+
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+    let chat_id = msg.chat().id;
+
+    let ctx = HandlerContext {
+        bot,
+        localization,
+        language_code: language_code.as_deref(),
+    };
+
+    match data {
+        "name_conflict_new" => {
+            remove_review_keyboard(&ctx, q, "handle_name_conflict_callbacks").await;
+
+            if let Err(e) = save_ingredients_to_database(
+                &pool,
+                q.from.id.0 as i64,
+                &saved_recipe_text(chat_id, &extracted_text),
+                &ingredients,
+                &recipe_name,
+                language_code.as_deref(),
+                save_idempotency_key(chat_id).as_deref(),
+                saved_recipe_photo(chat_id).as_ref(),
+                cache,
+            )
+            .await
+            {
+                error_logging::log_database_error(
+                    &e,
+                    "save_ingredients_to_database",
+                    Some(q.from.id.0 as i64),
+                    None,
+                );
+                bot.send_message(
+                    chat_id,
+                    t_lang(
+                        localization,
+                        "error-recipe-save-failed",
+                        language_code.as_deref(),
+                    ),
+                )
+                .reply_markup(create_name_conflict_keyboard(
+                    true,
+                    language_code.as_deref(),
+                    localization,
+                ))
+                .await?;
+                return Ok(());
+            }
+
+            send_caption_save_confirmation(&ctx, chat_id, &recipe_name, ingredients.len()).await?;
+            dialogue.exit().await?;
+        }
+        "name_conflict_replace" => {
+            remove_review_keyboard(&ctx, q, "handle_name_conflict_callbacks").await;
+
+            if let Err(e) =
+                sync_recipe_ingredients(&pool, RecipeId(newest_recipe_id), &ingredients, cache)
+                    .await
+            {
+                error_logging::log_database_error(
+                    &e,
+                    "update_recipe_ingredients",
+                    Some(q.from.id.0 as i64),
+                    Some(&[("recipe_id", &newest_recipe_id.to_string())]),
+                );
+                // Keep the pending ingredients so they can still be saved as a new copy
+                bot.send_message(
+                    chat_id,
+                    t_lang(
+                        localization,
+                        "error-name-conflict-replace",
+                        language_code.as_deref(),
+                    ),
+                )
+                .reply_markup(create_name_conflict_keyboard(
+                    false,
+                    language_code.as_deref(),
+                    localization,
+                ))
+                .await?;
+                return Ok(());
+            }
+            finish_name_conflict_update(
+                &ctx,
+                &pool,
+                q,
+                RecipeId(newest_recipe_id),
+                &recipe_name,
+                ingredients.len(),
+                t_args_lang(
+                    localization,
+                    "name-conflict-replaced",
+                    &[("recipe_name", recipe_name.as_str())],
+                    language_code.as_deref(),
+                ),
+            )
+            .await?;
+            dialogue.exit().await?;
+        }
+        "name_conflict_merge" => {
+            remove_review_keyboard(&ctx, q, "handle_name_conflict_callbacks").await;
+
+            match merge_into_recipe(&pool, RecipeId(newest_recipe_id), &ingredients, cache).await {
+                Ok((added, total)) => {
+                    finish_name_conflict_update(
+                        &ctx,
+                        &pool,
+                        q,
+                        RecipeId(newest_recipe_id),
+                        &recipe_name,
+                        total,
+                        t_plural(
+                            localization,
+                            "name-conflict-merged",
+                            added,
+                            &[("recipe_name", recipe_name.as_str())],
+                            language_code.as_deref(),
+                        ),
+                    )
+                    .await?;
+                    dialogue.exit().await?;
+                }
+                Err(e) => {
+                    error_logging::log_database_error(
+                        &e,
+                        "update_recipe_ingredients",
+                        Some(q.from.id.0 as i64),
+                        Some(&[("recipe_id", &newest_recipe_id.to_string())]),
+                    );
+                    // Keep the pending ingredients so they can still be saved as a new copy
+                    bot.send_message(
+                        chat_id,
+                        t_lang(
+                            localization,
+                            "error-name-conflict-merge",
+                            language_code.as_deref(),
+                        ),
+                    )
+                    .reply_markup(create_name_conflict_keyboard(
+                        false,
+                        language_code.as_deref(),
+                        localization,
+                    ))
+                    .await?;
+                }
+            }
+        }
+        "name_conflict_view" => {
+            remove_review_keyboard(&ctx, q, "handle_name_conflict_callbacks").await;
+
+            let details = match read_recipe_with_name(&pool, RecipeId(newest_recipe_id)).await {
+                Ok(Some(recipe)) => {
+                    let existing_ingredients = get_recipe_ingredients(&pool, recipe.id)
+                        .await
+                        .unwrap_or_default();
+                    format_recipe_details(
+                        &recipe,
+                        &existing_ingredients,
+                        crate::bot::unit_settings::display_units(chat_id),
+                        crate::bot::timezone_settings::display_timezone(chat_id),
+                        language_code.as_deref(),
+                        localization,
+                    )
+                }
+                Ok(None) => escape_html(&t_lang(
+                    localization,
+                    "recipe-not-found",
+                    language_code.as_deref(),
+                )),
+                Err(e) => {
+                    error_logging::log_database_error(
+                        &e,
+                        "read_recipe_with_name",
+                        Some(q.from.id.0 as i64),
+                        Some(&[("recipe_id", &newest_recipe_id.to_string())]),
+                    );
+                    escape_html(&t_lang(
+                        localization,
+                        "recipe-not-found",
+                        language_code.as_deref(),
+                    ))
+                }
+            };
+
+            // The dialogue state is left as is, so the pending ingredients survive the detour
+            send_long_message(
+                bot,
+                chat_id,
+                &HtmlMessage::new()
+                    .html(&details)
+                    .paragraph(&t_lang(
+                        localization,
+                        "name-conflict-question",
+                        language_code.as_deref(),
+                    ))
+                    .build(),
+                Some(create_name_conflict_keyboard(
+                    false,
+                    language_code.as_deref(),
+                    localization,
+                )),
+            )
+            .await?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Add the pending ingredients missing from an existing recipe
+///
+/// Returns the number of ingredients added and the recipe's new ingredient count.
+async fn merge_into_recipe(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    pending: &[crate::text_processing::MeasurementMatch],
+    cache: Option<&SharedCacheManager>,
+) -> Result<(usize, usize)> {
+    let existing =
+        ingredients_to_measurement_matches(&get_recipe_ingredients(pool, recipe_id).await?);
+    let missing = ingredients_missing_from(&existing, pending);
+    let added = missing.len();
+
+    if added > 0 {
+        // Existing entries are passed back unchanged, so only the missing ones get inserted
+        let combined: Vec<_> = existing.into_iter().chain(missing).collect();
+        sync_recipe_ingredients(pool, recipe_id, &combined, cache).await?;
+        Ok((added, combined.len()))
+    } else {
+        Ok((0, existing.len()))
+    }
+}
+
+/// Synchronize a saved recipe's ingredients, evicting its cached copy when a cache is available
+async fn sync_recipe_ingredients(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    match cache {
+        Some(cache) => update_recipe_ingredients_cached(pool, recipe_id, ingredients, cache).await,
+        None => update_recipe_ingredients(pool, recipe_id, ingredients).await,
+    }
+}
+
+/// Record a replace or merge into an existing recipe and confirm it to the user
+async fn finish_name_conflict_update(
+    ctx: &HandlerContext<'_>,
+    pool: &PgPool,
+    q: &CallbackQuery,
+    recipe_id: RecipeId,
+    recipe_name: &str,
+    ingredient_count: usize,
+    outcome: String,
+) -> Result<()> {
+    crate::events::emit(RecipeEvent::ingredients_updated(
+        recipe_id,
+        Some(recipe_name),
+        ingredient_count,
+    ));
+    if let Err(e) = crate::db::record_recipe_activity(
+        pool,
+        TelegramId(q.from.id.0 as i64),
+        recipe_id,
+        crate::db::RecipeActivityKind::Edited,
+        Some(recipe_name),
+        None,
+        Some(ingredient_count),
+    )
+    .await
+    {
+        error_logging::log_database_error(
+            &e,
+            "record_recipe_activity",
+            Some(q.from.id.0 as i64),
+            None,
+        );
+    }
+
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+    let confirmation_message = titled(
+        "✅",
+        &t_lang(ctx.localization, "workflow-recipe-saved", ctx.language_code),
+        &[
+            &format!("📝 {}", outcome),
+            &t_lang(ctx.localization, "workflow-what-next", ctx.language_code),
+        ],
+    );
+    ctx.bot
+        .send_message(msg.chat().id, confirmation_message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(create_post_confirmation_keyboard(
+            ctx.language_code,
+            ctx.localization,
+        ))
+        .await?;
+    Ok(())
+}
+
 /// Handle add more button in review ingredients state
 async fn handle_add_more_button(
     bot: &Bot,
@@ -677,9 +1549,14 @@ async fn handle_add_more_button(
 }
 
 /// Handle cancel review button in review ingredients state
+///
+/// Edits the stored review message into a short summary without buttons, deleting it
+/// instead when it can no longer be edited.
 async fn handle_cancel_review_button(
     bot: &Bot,
     q: &teloxide::types::CallbackQuery,
+    message_id: Option<i32>,
+    ingredient_count: usize,
     dialogue_lang_code: &Option<String>,
     dialogue: &RecipeDialogue,
     localization: &Arc<crate::localization::LocalizationManager>,
@@ -689,21 +1566,54 @@ async fn handle_cancel_review_button(
         .as_ref()
         .expect("Callback query should have a message");
     let chat_id = message.chat().id;
+    // A cancelled review says nothing about extraction accuracy
+    extraction_feedback::take_review(TelegramId(q.from.id.0 as i64));
+    // The stored review message, or the one carrying the button if none was recorded
+    let review_message_id = message_id
+        .map(teloxide::types::MessageId)
+        .unwrap_or_else(|| message.id());
 
-    // Edit the existing message to show cancellation and remove all buttons
-    bot.edit_message_text(
-        chat_id,
-        message.id(),
-        t_lang(
-            localization,
-            "review-cancelled",
-            dialogue_lang_code.as_deref(),
-        ),
-    )
-    .reply_markup(InlineKeyboardMarkup::new(Vec::<
-        Vec<teloxide::types::InlineKeyboardButton>,
-    >::new())) // Remove all inline keyboard buttons
-    .await?;
+    // Collapse the review into a short summary without its now-dead buttons
+    let edited = bot
+        .edit_message_text(
+            chat_id,
+            review_message_id,
+            format!(
+                "❌ {}",
+                t_plural(
+                    localization,
+                    "review-cancelled-summary",
+                    ingredient_count,
+                    &[],
+                    dialogue_lang_code.as_deref(),
+                )
+            ),
+        )
+        .reply_markup(InlineKeyboardMarkup::new(Vec::<
+            Vec<teloxide::types::InlineKeyboardButton>,
+        >::new())) // Remove all inline keyboard buttons
+        .await;
+
+    if let Err(e) = edited {
+        debug!(user_id = %q.from.id, error = %e, "Failed to edit review message on cancel, deleting it");
+        if let Err(e) = bot.delete_message(chat_id, review_message_id).await {
+            error_logging::log_internal_error(
+                &e,
+                "handle_cancel_review_button",
+                "Failed to delete review message",
+                Some(q.from.id.0 as i64),
+            );
+            bot.send_message(
+                chat_id,
+                t_lang(
+                    localization,
+                    "review-cancelled",
+                    dialogue_lang_code.as_deref(),
+                ),
+            )
+            .await?;
+        }
+    }
 
     // End the dialogue
     dialogue.exit().await?;
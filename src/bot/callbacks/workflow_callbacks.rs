@@ -14,10 +14,30 @@ use tracing::debug;
 use crate::localization::t_lang;
 
 // Import UI builder functions
+use crate::bot::formatting::{titled, PARSE_MODE};
 use crate::bot::ui_builder::create_recipes_pagination_keyboard;
 
 // Import database functions
-use crate::db::get_user_recipes_paginated;
+use crate::db::{get_user_recipes_paginated, get_user_recipes_paginated_cached, TelegramId};
+
+// Import cache types
+use crate::cache::SharedCacheManager;
+
+/// Get a page of a user's recipe names, from the cache when one is available
+async fn recipes_page(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+    offset: i64,
+    cache: Option<&SharedCacheManager>,
+) -> Result<(Vec<String>, i64)> {
+    match cache {
+        Some(cache) => {
+            get_user_recipes_paginated_cached(pool, telegram_id, limit, offset, cache).await
+        }
+        None => get_user_recipes_paginated(pool, telegram_id, limit, offset).await,
+    }
+}
 
 /// Handle back to recipes callback - simply deletes the current message
 pub async fn handle_back_to_recipes(
@@ -55,6 +75,7 @@ pub async fn handle_recipes_pagination(
     pool: Arc<PgPool>,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     let page_str = data.strip_prefix("page:").unwrap_or("0");
     let page: usize = page_str.parse().unwrap_or(0);
@@ -75,7 +96,7 @@ pub async fn handle_recipes_pagination(
 
     // Get paginated recipes
     let (recipes, total_count) =
-        get_user_recipes_paginated(&pool, chat_id.0, limit, offset).await?;
+        recipes_page(&pool, TelegramId(chat_id.0), limit, offset, cache).await?;
 
     if recipes.is_empty() {
         // This shouldn't happen in normal pagination, but handle gracefully
@@ -85,10 +106,14 @@ pub async fn handle_recipes_pagination(
     }
 
     // Create updated message text
-    let recipes_message = format!(
-        "📚 **{}**\n\n{}",
-        t_lang(localization, "your-recipes", language_code.as_deref()),
-        t_lang(localization, "select-recipe", language_code.as_deref())
+    let recipes_message = titled(
+        "📚",
+        &t_lang(localization, "your-recipes", language_code.as_deref()),
+        &[&t_lang(
+            localization,
+            "select-recipe",
+            language_code.as_deref(),
+        )],
     );
 
     // Create updated keyboard
@@ -101,14 +126,42 @@ pub async fn handle_recipes_pagination(
         localization,
     );
 
+    // Rapid taps on the same page edit the message once
+    if let Some(cache) = cache {
+        if !cache
+            .lock()
+            .pagination
+            .claim_page(chat_id.0, message_id.0, page)
+        {
+            debug!(page = %page, "Recipes page already displayed");
+            return Ok(());
+        }
+    }
+
     // Edit the original message
-    bot.edit_message_text(chat_id, message_id, recipes_message)
+    let edited = bot
+        .edit_message_text(chat_id, message_id, recipes_message)
+        .parse_mode(PARSE_MODE)
         .reply_markup(keyboard)
-        .await?;
+        .await;
+    if let Err(e) = edited {
+        // The page was not shown, so tapping it again must edit the message
+        if let Some(cache) = cache {
+            cache.lock().pagination.forget_page(chat_id.0, message_id.0);
+        }
+        return Err(e.into());
+    }
 
     Ok(())
 }
 
+/// Whether a callback only turns the page of a list, and can wait out a flood wait
+pub fn is_pagination_callback(data: &str) -> bool {
+    ["page:", "recent_page:", "tag_page:"]
+        .iter()
+        .any(|prefix| data.starts_with(prefix))
+}
+
 /// Handle list recipes workflow callback
 pub async fn handle_list_recipes(
     bot: &Bot,
@@ -116,6 +169,7 @@ pub async fn handle_list_recipes(
     pool: Arc<PgPool>,
     language_code: &Option<String>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     debug!("Handling list recipes workflow");
 
@@ -132,28 +186,34 @@ pub async fn handle_list_recipes(
     let limit = 5i64;
     let offset = 0i64;
     let (recipes, total_count) =
-        get_user_recipes_paginated(&pool, chat_id.0, limit, offset).await?;
+        recipes_page(&pool, TelegramId(chat_id.0), limit, offset, cache).await?;
 
     if recipes.is_empty() {
         // No recipes found
-        let message = format!(
-            "📚 **{}**\n\n{}",
-            t_lang(localization, "your-recipes", language_code.as_deref()),
-            t_lang(
+        let message = titled(
+            "📚",
+            &t_lang(localization, "your-recipes", language_code.as_deref()),
+            &[&t_lang(
                 localization,
                 "no-recipes-suggestion",
-                language_code.as_deref()
-            )
+                language_code.as_deref(),
+            )],
         );
-        bot.send_message(chat_id, message).await?;
+        bot.send_message(chat_id, message)
+            .parse_mode(PARSE_MODE)
+            .await?;
         return Ok(());
     }
 
     // Create message text
-    let recipes_message = format!(
-        "📚 **{}**\n\n{}",
-        t_lang(localization, "your-recipes", language_code.as_deref()),
-        t_lang(localization, "select-recipe", language_code.as_deref())
+    let recipes_message = titled(
+        "📚",
+        &t_lang(localization, "your-recipes", language_code.as_deref()),
+        &[&t_lang(
+            localization,
+            "select-recipe",
+            language_code.as_deref(),
+        )],
     );
 
     // Create keyboard
@@ -168,6 +228,7 @@ pub async fn handle_list_recipes(
 
     // Send the message with keyboard
     bot.send_message(chat_id, recipes_message)
+        .parse_mode(PARSE_MODE)
         .reply_markup(keyboard)
         .await?;
 
@@ -182,6 +243,7 @@ pub async fn handle_workflow_button(
     pool: &Arc<PgPool>,
     dialogue: &crate::dialogue::RecipeDialogue,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     match data {
         "workflow_add_another" => {
@@ -227,29 +289,22 @@ pub async fn handle_workflow_button(
                 pool.clone(),
                 &q.from.language_code,
                 localization,
+                cache,
             )
             .await?;
         }
         "workflow_search_recipes" => {
-            // Record user engagement metric for recipe search
-            crate::observability::record_user_engagement_metrics(
-                q.from.id.0 as i64,
-                crate::observability::UserAction::RecipeSearch,
-                None, // No session duration for individual actions
-                q.from.language_code.as_deref(),
-            );
-
-            bot.send_message(
+            // The search metric is recorded once the query is run
+            crate::bot::recipe_search::start_recipe_search(
+                bot,
                 q.message
                     .as_ref()
                     .expect("Callback query should have a message")
                     .chat()
                     .id,
-                t_lang(
-                    localization,
-                    "workflow-search-coming-soon",
-                    q.from.language_code.as_deref(),
-                ),
+                dialogue,
+                localization,
+                q.from.language_code.as_deref(),
             )
             .await?;
         }
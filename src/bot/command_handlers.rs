@@ -4,16 +4,30 @@ use anyhow::Result;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
-use tracing::debug;
+use teloxide::types::ReplyMarkup;
+use tracing::{debug, warn};
 
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_args_lang, t_lang, t_plural};
 
 // Import database functions
-use crate::db::get_user_recipes_paginated;
+use crate::db::{
+    get_global_statistics, get_longest_recipe_by_user, get_recipe_counts_by_month,
+    get_recipes_with_ingredients_batch, get_review_funnel_report, get_top_ingredients_by_user,
+    get_user_recipe_statistics, get_user_recipes_paginated, GlobalStatistics, TelegramId,
+    EXPORT_BATCH_SIZE,
+};
+
+// Import recipe export format
+use crate::recipe_export::{export_file_name, ExportWriter, ExportedRecipe};
 
 // Import UI builder functions
-use super::ui_builder::create_recipes_pagination_keyboard;
+use super::ui_builder::{
+    create_recipes_pagination_keyboard, format_user_statistics, UserStatisticsView,
+};
+
+// Import HTML message formatting
+use super::formatting::{bold, escape_html, send_long_message, titled, HtmlMessage, PARSE_MODE};
 
 // Import HandlerContext
 // use super::HandlerContext;
@@ -21,12 +35,49 @@ use super::ui_builder::create_recipes_pagination_keyboard;
 // Import observability
 // use crate::observability;
 
+/// Number of days of funnel events summarized by /admin_stats
+const ADMIN_STATS_WINDOW_DAYS: i32 = 14;
+
+/// Ingredients listed by /stats
+const STATS_TOP_INGREDIENTS: i64 = 10;
+
+/// Months charted by /stats, the current one included
+const STATS_MONTHS: i32 = 12;
+
+/// Check whether a Telegram user may use admin commands
+///
+/// Admins are configured with ADMIN_TELEGRAM_IDS (or its short form ADMIN_IDS).
+pub fn is_admin_user(telegram_id: i64) -> bool {
+    crate::config::current()
+        .bot
+        .admin_user_ids
+        .contains(&telegram_id)
+}
+
+/// Admin guard for command handlers: whether a message was sent by an admin
+///
+/// Admin commands from anyone else are answered like any unknown command, so they
+/// don't reveal that the command exists.
+pub fn is_admin_message(msg: &Message) -> bool {
+    msg.from
+        .as_ref()
+        .is_some_and(|user| is_admin_user(user.id.0 as i64))
+}
+
+/// First configured admin, who receives user reports
+pub fn first_admin_user() -> Option<i64> {
+    crate::config::current().bot.admin_user_ids.first().copied()
+}
+
 /// Handle the /start command
+///
+/// `quickbar` is attached to the reply to show or remove the quickbar.
 pub async fn handle_start_command(
     bot: &Bot,
     msg: &Message,
     localization: &Arc<crate::localization::LocalizationManager>,
     language_code: Option<&str>,
+    quickbar: Option<ReplyMarkup>,
 ) -> Result<()> {
     // Record user engagement metric for start command
     if let Some(user) = msg.from.as_ref() {
@@ -38,26 +89,34 @@ pub async fn handle_start_command(
         );
     }
 
-    let welcome_message = format!(
-        "👋 **{}**\n\n{}\n\n{}\n\n{}\n{}\n{}\n\n{}",
-        t_lang(localization, "welcome-title", language_code),
-        t_lang(localization, "welcome-description", language_code),
-        t_lang(localization, "welcome-features", language_code),
-        t_lang(localization, "welcome-commands", language_code),
-        t_lang(localization, "welcome-start", language_code),
-        t_lang(localization, "welcome-help", language_code),
-        t_lang(localization, "welcome-send-image", language_code)
-    );
-    bot.send_message(msg.chat.id, welcome_message).await?;
+    let welcome_message =
+        HtmlMessage::titled("👋", &t_lang(localization, "welcome-title", language_code))
+            .paragraph(&t_lang(localization, "welcome-description", language_code))
+            .paragraph(&t_lang(localization, "welcome-features", language_code))
+            .paragraph(&t_lang(localization, "welcome-commands", language_code))
+            .line(&t_lang(localization, "welcome-start", language_code))
+            .line(&t_lang(localization, "welcome-help", language_code))
+            .paragraph(&t_lang(localization, "welcome-send-image", language_code))
+            .build();
+    let request = bot
+        .send_message(msg.chat.id, welcome_message)
+        .parse_mode(PARSE_MODE);
+    match quickbar {
+        Some(quickbar) => request.reply_markup(quickbar).await?,
+        None => request.await?,
+    };
     Ok(())
 }
 
 /// Handle the /help command
+///
+/// `quickbar` is attached to the reply to show or remove the quickbar.
 pub async fn handle_help_command(
     bot: &Bot,
     msg: &Message,
     localization: &Arc<crate::localization::LocalizationManager>,
     language_code: Option<&str>,
+    quickbar: Option<ReplyMarkup>,
 ) -> Result<()> {
     // Record user engagement metric for help command
     if let Some(user) = msg.from.as_ref() {
@@ -79,6 +138,19 @@ pub async fn handle_help_command(
         t_lang(localization, "help-formats", language_code),
         t_lang(localization, "help-commands", language_code),
         t_lang(localization, "help-start", language_code),
+        t_lang(localization, "help-cancel", language_code),
+        t_lang(localization, "help-recent", language_code),
+        t_lang(localization, "help-stats", language_code),
+        t_lang(localization, "help-language", language_code),
+        t_lang(localization, "help-export", language_code),
+        t_lang(localization, "help-find", language_code),
+        t_lang(localization, "help-tag", language_code),
+        t_lang(localization, "help-units", language_code),
+        t_lang(localization, "help-timezone", language_code),
+        t_lang(localization, "help-digest", language_code),
+        t_lang(localization, "help-trash", language_code),
+        t_lang(localization, "help-inline", language_code),
+        t_lang(localization, "help-delete-all", language_code),
         t_lang(localization, "help-tips", language_code),
         t_lang(localization, "help-tip1", language_code),
         t_lang(localization, "help-tip2", language_code),
@@ -87,7 +159,69 @@ pub async fn handle_help_command(
         t_lang(localization, "help-final", language_code),
     ]
     .join("\n\n");
-    bot.send_message(msg.chat.id, help_message).await?;
+    let request = bot.send_message(msg.chat.id, help_message);
+    match quickbar {
+        Some(quickbar) => request.reply_markup(quickbar).await?,
+        None => request.await?,
+    };
+    Ok(())
+}
+
+/// Handle the /export command by sending every saved recipe as a JSON document
+pub async fn handle_export_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /export command");
+    let telegram_id = TelegramId(msg.chat.id.0);
+    let now = chrono::Utc::now();
+
+    // Written to a temporary file batch by batch so large accounts stay out of memory
+    let file = tempfile::NamedTempFile::new()?;
+    let mut writer = ExportWriter::begin(std::io::BufWriter::new(file.reopen()?), now)?;
+    let mut last_recipe_id = None;
+    loop {
+        let batch = get_recipes_with_ingredients_batch(
+            &pool,
+            telegram_id,
+            last_recipe_id,
+            EXPORT_BATCH_SIZE,
+        )
+        .await?;
+        let Some((last_recipe, _)) = batch.last() else {
+            break;
+        };
+        last_recipe_id = Some(last_recipe.id);
+        for (recipe, ingredients) in &batch {
+            writer.write_recipe(&ExportedRecipe::from_stored(recipe, ingredients))?;
+        }
+    }
+    let (_, recipe_count) = writer.finish()?;
+
+    if recipe_count == 0 {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "export-empty", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let document =
+        teloxide::types::InputFile::file(file.path()).file_name(export_file_name(now.date_naive()));
+    bot.send_document(msg.chat.id, document)
+        .caption(t_plural(
+            localization,
+            "export-caption",
+            recipe_count,
+            &[("count", &recipe_count.to_string())],
+            language_code,
+        ))
+        .await?;
+    debug!(user_id = %msg.chat.id, recipe_count, "Recipe export sent");
     Ok(())
 }
 
@@ -102,7 +236,8 @@ pub async fn handle_recipes_command(
     debug!(user_id = %msg.chat.id, "Handling /recipes command");
 
     // Get paginated recipes for the user
-    let (recipes, total_count) = get_user_recipes_paginated(&pool, msg.chat.id.0, 5, 0).await?;
+    let (recipes, total_count) =
+        get_user_recipes_paginated(&pool, TelegramId(msg.chat.id.0), 5, 0).await?;
 
     if recipes.is_empty() {
         // No recipes found
@@ -114,10 +249,10 @@ pub async fn handle_recipes_command(
         bot.send_message(msg.chat.id, no_recipes_message).await?;
     } else {
         // Create the message text
-        let recipes_message = format!(
-            "📚 **{}**\n\n{}",
-            t_lang(localization, "your-recipes", language_code),
-            t_lang(localization, "select-recipe", language_code)
+        let recipes_message = titled(
+            "📚",
+            &t_lang(localization, "your-recipes", language_code),
+            &[&t_lang(localization, "select-recipe", language_code)],
         );
 
         // Create the pagination keyboard
@@ -131,6 +266,7 @@ pub async fn handle_recipes_command(
         );
 
         bot.send_message(msg.chat.id, recipes_message)
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await?;
     }
@@ -138,6 +274,42 @@ pub async fn handle_recipes_command(
     Ok(())
 }
 
+/// Handle the /stats command with statistics over all of the user's recipes
+pub async fn handle_stats_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /stats command");
+    let telegram_id = TelegramId(msg.chat.id.0);
+
+    let stats = get_user_recipe_statistics(
+        pool,
+        telegram_id,
+        super::timezone_settings::display_timezone(msg.chat.id),
+    )
+    .await?;
+    let top_ingredients =
+        get_top_ingredients_by_user(pool, telegram_id, STATS_TOP_INGREDIENTS).await?;
+    let monthly_counts = get_recipe_counts_by_month(pool, telegram_id, STATS_MONTHS).await?;
+    let longest_recipe = get_longest_recipe_by_user(pool, telegram_id).await?;
+
+    let message = format_user_statistics(
+        &UserStatisticsView {
+            stats: &stats,
+            top_ingredients: &top_ingredients,
+            monthly_counts: &monthly_counts,
+            longest_recipe: longest_recipe.as_ref(),
+        },
+        language_code,
+        localization,
+    );
+    send_long_message(bot, msg.chat.id, &message, None).await?;
+    Ok(())
+}
+
 /// Handle unsupported message types
 pub async fn handle_unsupported_message(
     bot: &Bot,
@@ -166,3 +338,212 @@ pub async fn handle_unsupported_message(
     bot.send_message(msg.chat.id, help_message).await?;
     Ok(())
 }
+
+/// Handle the /debug_locales admin command
+///
+/// Lists each language bundle's load state, load attempts and approximate memory usage.
+pub async fn handle_debug_locales_command(
+    bot: &Bot,
+    msg: &Message,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let mut lines = vec![format!(
+        "🌐 {}",
+        bold(&t_lang(localization, "debug-locales-title", language_code))
+    )];
+
+    for status in localization.language_statuses() {
+        let state_key = match status.state {
+            crate::localization::LanguageLoadState::Loaded => "debug-locales-loaded",
+            crate::localization::LanguageLoadState::NotLoaded => "debug-locales-not-loaded",
+            crate::localization::LanguageLoadState::Failed => "debug-locales-failed",
+        };
+        lines.push(escape_html(&t_args_lang(
+            localization,
+            "debug-locales-entry",
+            &[
+                ("language", &status.language),
+                ("state", &t_lang(localization, state_key, language_code)),
+                ("attempts", &status.load_attempts.to_string()),
+                (
+                    "kilobytes",
+                    &format!("{:.1}", status.approx_memory_bytes as f64 / 1024.0),
+                ),
+            ],
+            language_code,
+        )));
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n"))
+        .parse_mode(PARSE_MODE)
+        .await?;
+    Ok(())
+}
+
+/// Global section of /admin_stats as HTML lines: counts and OCR success rate since startup
+pub fn format_global_statistics(
+    stats: &GlobalStatistics,
+    ocr_counts: (u64, u64),
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Vec<String> {
+    let (successes, failures) = ocr_counts;
+    let total = successes + failures;
+    let ocr_line = if total == 0 {
+        t_lang(localization, "admin-stats-ocr-none", language_code)
+    } else {
+        t_args_lang(
+            localization,
+            "admin-stats-ocr",
+            &[
+                (
+                    "rate",
+                    &format!("{:.1}", successes as f64 * 100.0 / total as f64),
+                ),
+                ("successes", &successes.to_string()),
+                ("total", &total.to_string()),
+            ],
+            language_code,
+        )
+    };
+
+    let lines = [
+        t_args_lang(
+            localization,
+            "admin-stats-totals",
+            &[
+                ("users", &stats.users.to_string()),
+                ("recipes", &stats.recipes.to_string()),
+                ("ingredients", &stats.ingredients.to_string()),
+            ],
+            language_code,
+        ),
+        t_args_lang(
+            localization,
+            "admin-stats-week",
+            &[
+                ("recipes", &stats.recipes_this_week.to_string()),
+                ("users", &stats.active_users_this_week.to_string()),
+            ],
+            language_code,
+        ),
+        ocr_line,
+    ];
+    std::iter::once(format!(
+        "📊 {}",
+        bold(&t_lang(
+            localization,
+            "admin-stats-global-title",
+            language_code
+        ))
+    ))
+    .chain(lines.iter().map(|line| escape_html(line)))
+    .collect()
+}
+
+/// Handle the /admin_stats admin command
+///
+/// Shows global counts and the OCR success rate, then summarizes the review-keyboard
+/// experiment funnel per variant over the recent window.
+pub async fn handle_admin_stats_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let global = get_global_statistics(&pool).await?;
+    let experiment = crate::experiments::review_keyboard_experiment();
+    let report = get_review_funnel_report(&pool, &experiment.name, ADMIN_STATS_WINDOW_DAYS).await?;
+
+    let mut lines = format_global_statistics(
+        &global,
+        crate::observability::ocr_operation_counts(),
+        localization,
+        language_code,
+    );
+    lines.push(String::new());
+    lines.extend([
+        format!(
+            "📊 {}",
+            bold(&t_lang(localization, "admin-stats-title", language_code))
+        ),
+        escape_html(&t_args_lang(
+            localization,
+            "admin-stats-experiment",
+            &[
+                ("experiment", &experiment.name),
+                ("days", &ADMIN_STATS_WINDOW_DAYS.to_string()),
+            ],
+            language_code,
+        )),
+    ]);
+
+    if report.is_empty() {
+        lines.push(escape_html(&t_lang(
+            localization,
+            "admin-stats-no-data",
+            language_code,
+        )));
+    }
+
+    for stats in &report {
+        lines.push(escape_html(&t_args_lang(
+            localization,
+            "admin-stats-variant",
+            &[
+                ("variant", &stats.variant),
+                ("shown", &stats.reviews_shown.to_string()),
+                ("edits", &stats.edits_made.to_string()),
+                ("confirmed", &stats.confirmed.to_string()),
+                (
+                    "confirm_rate",
+                    &format!("{:.1}", stats.confirm_rate() * 100.0),
+                ),
+                (
+                    "edits_per_review",
+                    &format!("{:.2}", stats.edits_per_review()),
+                ),
+            ],
+            language_code,
+        )));
+    }
+
+    bot.send_message(msg.chat.id, lines.join("\n"))
+        .parse_mode(PARSE_MODE)
+        .await?;
+    Ok(())
+}
+
+/// Handle the /events_test admin command by delivering a ping to the webhook
+pub async fn handle_events_test_command(
+    bot: &Bot,
+    msg: &Message,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let message = match crate::events::webhook_sink() {
+        None => t_lang(localization, "events-test-not-configured", language_code),
+        Some(sink) => match sink.deliver_now(&crate::events::RecipeEvent::ping()).await {
+            Ok(attempts) => t_args_lang(
+                localization,
+                "events-test-delivered",
+                &[("attempts", &attempts.to_string())],
+                language_code,
+            ),
+            Err(e) => {
+                warn!(error = %e, "Webhook ping failed");
+                t_args_lang(
+                    localization,
+                    "events-test-failed",
+                    &[("error", &format!("{e:#}"))],
+                    language_code,
+                )
+            }
+        },
+    };
+
+    bot.send_message(msg.chat.id, message).await?;
+    Ok(())
+}
@@ -1,7 +1,7 @@
 //! Dialogue Manager module for handling dialogue state transitions
 
-use crate::localization::{t_args_lang, t_lang};
-use anyhow::Result;
+use crate::localization::{t_args_lang, t_lang, t_plural};
+use anyhow::{Context, Result};
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
@@ -17,26 +17,92 @@ use crate::text_processing::MeasurementMatch;
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 
 // Import validation functions
-use crate::validation::{parse_ingredient_from_text, parse_quantity, validate_recipe_name};
+use crate::validation::{
+    parse_ingredient_edit, parse_ingredient_from_text, validate_recipe_name, IngredientEdit,
+};
+
+// Import exact quantities saved with ingredients
+use crate::quantity::Quantity;
 
 // Import database types
 use crate::db::{
-    create_ingredient, create_recipe, get_or_create_user, update_recipe_name, Ingredient,
+    create_ingredients_bulk, create_recipe_idempotent_tx, create_recipe_tx, get_or_create_user,
+    get_or_create_user_cached, get_recipes_by_name, set_recipe_photo_tx, update_recipe_name,
+    update_recipe_name_cached, update_recipe_name_tx, NewIngredient, RecipeId, RecipePhoto,
+    TelegramId,
 };
 
+// Import the shared cache evicted by recipe writes
+use crate::cache::SharedCacheManager;
+
+// Import ingredient snapshots used for change detection
+use crate::ingredient_editing::IngredientSnapshot;
+
 // Import UI builder functions
 use super::ui_builder::{
-    create_ingredient_review_keyboard, create_post_confirmation_keyboard, format_ingredients_list,
+    create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+    create_name_conflict_keyboard, create_post_confirmation_keyboard, format_edit_clarification,
+    format_editing_title, format_ingredients_list, format_name_conflict_prompt,
+    format_review_message,
 };
 
+// Import recipe lifecycle events
+use crate::events::RecipeEvent;
+
+// Import review keyboard experiment helpers
+use crate::experiments::{
+    resolve_review_keyboard_variant, review_keyboard_variant, track_review_funnel_event,
+    FunnelEvent,
+};
+use crate::extraction_feedback::{self, Correction};
+
+// Import display unit preferences
+use super::formatting::{titled, HtmlMessage, PARSE_MODE};
+use super::unit_settings::display_units;
+
 // Import HandlerContext
 use super::HandlerContext;
 
+// Import Telegram send retries
+use super::bot_utils::send_with_retry;
+
 /// Check if the error is a "message not modified" Telegram API error
 fn is_message_not_modified_error(error: &teloxide::RequestError) -> bool {
     error.to_string().contains("message is not modified")
 }
 
+/// Tell the user their recipe was saved, editing `prompt` when there is one
+///
+/// The recipe is already stored, so transient send failures are retried: losing
+/// this message would leave the user believing nothing was saved. A prompt that
+/// cannot be edited is answered with a new message.
+pub async fn send_saved_confirmation(
+    bot: &Bot,
+    chat_id: ChatId,
+    prompt: Option<teloxide::types::MessageId>,
+    html: String,
+    keyboard: Option<teloxide::types::InlineKeyboardMarkup>,
+) -> Result<()> {
+    if let Some(prompt) = prompt {
+        let edit = bot
+            .edit_message_text(chat_id, prompt, html.clone())
+            .parse_mode(PARSE_MODE);
+        let edited = match keyboard.clone() {
+            Some(keyboard) => send_with_retry(edit.reply_markup(keyboard)).await,
+            None => send_with_retry(edit).await,
+        };
+        if edited.is_ok() {
+            return Ok(());
+        }
+    }
+    let request = bot.send_message(chat_id, html).parse_mode(PARSE_MODE);
+    match keyboard {
+        Some(keyboard) => send_with_retry(request.reply_markup(keyboard)).await?,
+        None => send_with_retry(request).await?,
+    };
+    Ok(())
+}
+
 /// Parameters for ingredient review input handling
 #[derive(Debug)]
 pub struct IngredientReviewInputParams<'a> {
@@ -46,19 +112,21 @@ pub struct IngredientReviewInputParams<'a> {
     pub ingredients: Vec<MeasurementMatch>,
     pub ctx: &'a HandlerContext<'a>,
     pub extracted_text: String,
+    pub cache: Option<&'a SharedCacheManager>,
 }
 
 /// Parameters for recipe name success handling
 #[derive(Debug)]
 struct RecipeNameSuccessParams<'a> {
     ctx: &'a HandlerContext<'a>,
-    msg: &'a Message,
+    chat_id: ChatId,
     dialogue: RecipeDialogue,
     pool: &'a PgPool,
     ingredients: &'a [MeasurementMatch],
     extracted_text: &'a str,
     validated_name: &'a str,
     message_id: Option<i32>, // ID of the prompt message to edit with confirmation
+    cache: Option<&'a SharedCacheManager>,
 }
 
 /// Parameters for edit cancellation handling
@@ -77,6 +145,7 @@ struct EditCancellationParams<'a> {
 /// Parameters for edit success handling
 #[derive(Debug)]
 struct EditSuccessParams<'a> {
+    pool: Arc<PgPool>,
     ctx: &'a HandlerContext<'a>,
     msg: &'a Message,
     dialogue: RecipeDialogue,
@@ -118,6 +187,7 @@ pub struct RecipeNameAfterConfirmInputParams<'a> {
     pub ctx: &'a HandlerContext<'a>,
     pub extracted_text: String,
     pub message_id: Option<i32>, // ID of the prompt message to edit with confirmation
+    pub cache: Option<&'a SharedCacheManager>,
 }
 
 /// Parameters for recipe rename input handling
@@ -128,11 +198,20 @@ pub struct RecipeRenameInputParams<'a> {
     pub recipe_id: i64,
     pub current_name: String,
     pub ctx: &'a HandlerContext<'a>,
+    pub cache: Option<&'a SharedCacheManager>,
+}
+
+/// Parameters for renaming the recipe under review before it is saved
+#[derive(Debug)]
+pub struct PendingRecipeRenameInputParams<'a> {
+    pub new_name_input: &'a str,
+    pub ctx: &'a HandlerContext<'a>,
 }
 
 /// Parameters for ingredient edit input handling
 #[derive(Debug)]
 pub struct IngredientEditInputParams<'a> {
+    pub pool: Arc<PgPool>,
     pub edit_input: &'a str,
     pub recipe_name: String,
     pub ingredients: Vec<MeasurementMatch>,
@@ -150,8 +229,9 @@ pub struct AddIngredientInputParams<'a> {
     pub pool: &'a PgPool,
     pub add_input: &'a str,
     pub recipe_id: i64,
-    pub original_ingredients: &'a [Ingredient],
+    pub original_ingredients: &'a [IngredientSnapshot],
     pub current_matches: &'a [MeasurementMatch],
+    pub ingredients_version: i64,
     pub ctx: &'a HandlerContext<'a>,
     pub message_id: Option<i32>,
 }
@@ -162,8 +242,9 @@ pub struct SavedIngredientEditInputParams<'a> {
     pub pool: &'a PgPool,
     pub edit_input: &'a str,
     pub recipe_id: i64,
-    pub original_ingredients: &'a [Ingredient],
+    pub original_ingredients: &'a [IngredientSnapshot],
     pub current_matches: &'a [MeasurementMatch],
+    pub ingredients_version: i64,
     pub ctx: &'a HandlerContext<'a>,
     pub message_id: Option<i32>,
     pub editing_index: usize,
@@ -184,7 +265,7 @@ pub async fn handle_recipe_name_input(
         localization: _,
     } = ctx;
     let RecipeNameInputParams {
-        pool: _pool,
+        pool,
         recipe_name_input,
         extracted_text,
         ingredients,
@@ -197,35 +278,29 @@ pub async fn handle_recipe_name_input(
     match validate_recipe_name(recipe_name_input) {
         Ok(validated_name) => {
             // Recipe name is valid, transition to ingredient review state
-            let review_message = format!(
-                "📝 **{}**\n\n{}\n\n{}",
-                t_lang(
-                    handler_ctx.localization,
-                    "review-title",
-                    handler_ctx.language_code
-                ),
-                t_lang(
-                    handler_ctx.localization,
-                    "review-description",
-                    handler_ctx.language_code
-                ),
-                format_ingredients_list(
-                    &ingredients,
-                    handler_ctx.language_code,
-                    handler_ctx.localization
-                )
+            let review_message = format_review_message(
+                &ingredients,
+                None,
+                display_units(msg.chat.id),
+                handler_ctx.language_code,
+                handler_ctx.localization,
             );
 
-            let keyboard = create_ingredient_review_keyboard(
+            let telegram_id = TelegramId(msg.chat.id.0);
+            let keyboard = create_ingredient_review_keyboard_for_variant(
                 &ingredients,
                 handler_ctx.language_code,
                 handler_ctx.localization,
+                resolve_review_keyboard_variant(&pool, telegram_id).await,
             );
 
             let sent_message = bot
                 .send_message(msg.chat.id, review_message)
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await?;
+            track_review_funnel_event(&pool, telegram_id, FunnelEvent::ReviewShown).await;
+            extraction_feedback::start_review(telegram_id, ingredients.len());
 
             // Update dialogue state to review ingredients
             dialogue
@@ -236,6 +311,7 @@ pub async fn handle_recipe_name_input(
                     message_id: Some(sent_message.id.0 as i32),
                     extracted_text,
                     recipe_name_from_caption: None, // Recipe name came from user input, not caption
+                    last_deleted: None,
                 })
                 .await?;
         }
@@ -300,16 +376,8 @@ pub async fn handle_recipe_name_after_confirm_input(
         dialogue,
         localization: _,
     } = ctx;
-    let RecipeNameAfterConfirmInputParams {
-        pool,
-        recipe_name_input,
-        ingredients,
-        ctx: handler_ctx,
-        extracted_text,
-        message_id,
-    } = params;
 
-    let input = recipe_name_input.trim().to_lowercase();
+    let input = params.recipe_name_input.trim().to_lowercase();
 
     // Check for cancellation commands
     if is_cancellation_command(&input) {
@@ -317,31 +385,53 @@ pub async fn handle_recipe_name_after_confirm_input(
             bot,
             msg,
             dialogue,
-            handler_ctx.localization,
-            handler_ctx.language_code,
+            params.ctx.localization,
+            params.ctx.language_code,
         )
         .await;
     }
 
-    // Validate and save recipe name
+    save_recipe_with_name(msg.chat.id, dialogue, params).await
+}
+
+/// Validate the name of the recipe awaiting one and save it
+///
+/// Shared by typed names and tapped name suggestions, so both get the same
+/// validation and duplicate handling.
+pub async fn save_recipe_with_name(
+    chat_id: ChatId,
+    dialogue: RecipeDialogue,
+    params: RecipeNameAfterConfirmInputParams<'_>,
+) -> Result<()> {
+    let RecipeNameAfterConfirmInputParams {
+        pool,
+        recipe_name_input,
+        ingredients,
+        ctx: handler_ctx,
+        extracted_text,
+        message_id,
+        cache,
+    } = params;
+
     match validate_recipe_name(recipe_name_input) {
         Ok(validated_name) => {
             handle_recipe_name_success(RecipeNameSuccessParams {
                 ctx: handler_ctx,
-                msg,
+                chat_id,
                 dialogue,
                 pool: &pool,
                 ingredients: &ingredients,
                 extracted_text: &extracted_text,
                 validated_name,
                 message_id,
+                cache,
             })
             .await
         }
         Err(error_type) => {
             handle_recipe_name_validation_error(
-                bot,
-                msg,
+                handler_ctx.bot,
+                chat_id,
                 handler_ctx.localization,
                 error_type,
                 handler_ctx.language_code,
@@ -373,30 +463,74 @@ async fn handle_recipe_name_cancellation(
 async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Result<()> {
     let RecipeNameSuccessParams {
         ctx,
-        msg,
+        chat_id,
         dialogue,
         pool,
         ingredients,
         extracted_text,
         validated_name,
         message_id,
+        cache,
     } = params;
 
+    // Pause instead of silently creating a second recipe with the same name
+    match get_recipes_by_name(pool, TelegramId(chat_id.0), validated_name).await {
+        Ok(existing) if !existing.is_empty() => {
+            ctx.bot
+                .send_message(
+                    chat_id,
+                    format_name_conflict_prompt(
+                        validated_name,
+                        existing.len(),
+                        ctx.language_code,
+                        ctx.localization,
+                    ),
+                )
+                .reply_markup(create_name_conflict_keyboard(
+                    true,
+                    ctx.language_code,
+                    ctx.localization,
+                ))
+                .await?;
+
+            // Most recent first, as returned by get_recipes_by_name
+            dialogue
+                .update(RecipeDialogueState::ResolvingRecipeNameConflict {
+                    recipe_name: validated_name.to_string(),
+                    ingredients: ingredients.to_vec(),
+                    language_code: ctx.language_code.map(str::to_string),
+                    extracted_text: extracted_text.to_string(),
+                    newest_recipe_id: existing[0].id.0,
+                    existing_count: existing.len(),
+                })
+                .await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(e) => {
+            // A failed lookup must not block saving the recipe
+            error_logging::log_database_error(&e, "get_recipes_by_name", Some(chat_id.0), None);
+        }
+    }
+
     // Recipe name is valid, save ingredients to database
     if let Err(e) = save_ingredients_to_database(
         pool,
-        msg.chat.id.0,
-        extracted_text,
+        chat_id.0,
+        &saved_recipe_text(chat_id, extracted_text),
         ingredients,
         validated_name,
         ctx.language_code,
+        save_idempotency_key(chat_id).as_deref(),
+        saved_recipe_photo(chat_id).as_ref(),
+        cache,
     )
     .await
     {
         error_logging::log_recipe_error(
             &e,
             "save_ingredients_to_database",
-            msg.chat.id.0,
+            chat_id.0,
             Some(validated_name),
             Some(ingredients.len()),
         );
@@ -405,11 +539,11 @@ async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Resu
             match ctx
                 .bot
                 .edit_message_text(
-                    msg.chat.id,
+                    chat_id,
                     teloxide::types::MessageId(prompt_msg_id),
                     t_lang(
                         ctx.localization,
-                        "error-processing-failed",
+                        "error-recipe-save-failed",
                         ctx.language_code,
                     ),
                 )
@@ -419,10 +553,10 @@ async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Resu
                 Err(_) => {
                     ctx.bot
                         .send_message(
-                            msg.chat.id,
+                            chat_id,
                             t_lang(
                                 ctx.localization,
-                                "error-processing-failed",
+                                "error-recipe-save-failed",
                                 ctx.language_code,
                             ),
                         )
@@ -432,15 +566,17 @@ async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Resu
         } else {
             ctx.bot
                 .send_message(
-                    msg.chat.id,
+                    chat_id,
                     t_lang(
                         ctx.localization,
-                        "error-processing-failed",
+                        "error-recipe-save-failed",
                         ctx.language_code,
                     ),
                 )
                 .await?;
         }
+        // Nothing was stored: keep the dialogue so the user can send the name again
+        return Ok(());
     } else {
         // Success! Edit the prompt message with confirmation
         let success_message = t_args_lang(
@@ -453,34 +589,27 @@ async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Resu
             ctx.language_code,
         );
 
-        if let Some(prompt_msg_id) = message_id {
-            match ctx
-                .bot
-                .edit_message_text(
-                    msg.chat.id,
-                    teloxide::types::MessageId(prompt_msg_id),
-                    success_message.clone(),
-                )
-                .await
-            {
-                Ok(_) => (),
-                Err(_) => {
-                    // Fallback: send new message if editing fails
-                    ctx.bot.send_message(msg.chat.id, success_message).await?;
-                }
-            }
+        send_saved_confirmation(
+            ctx.bot,
+            chat_id,
+            message_id.map(teloxide::types::MessageId),
+            HtmlMessage::new().text(&success_message).build(),
+            None,
+        )
+        .await?;
+        if message_id.is_some() {
             // Send post-confirmation menu for legacy workflow
             let confirmation_keyboard =
                 create_post_confirmation_keyboard(ctx.language_code, ctx.localization);
-            ctx.bot
-                .send_message(
-                    msg.chat.id,
-                    t_lang(ctx.localization, "workflow-what-next", ctx.language_code),
-                )
-                .reply_markup(confirmation_keyboard)
-                .await?;
-        } else {
-            ctx.bot.send_message(msg.chat.id, success_message).await?;
+            send_with_retry(
+                ctx.bot
+                    .send_message(
+                        chat_id,
+                        t_lang(ctx.localization, "workflow-what-next", ctx.language_code),
+                    )
+                    .reply_markup(confirmation_keyboard),
+            )
+            .await?;
         }
     }
 
@@ -492,7 +621,7 @@ async fn handle_recipe_name_success(params: RecipeNameSuccessParams<'_>) -> Resu
 /// Handle recipe name validation errors
 async fn handle_recipe_name_validation_error(
     bot: &Bot,
-    msg: &Message,
+    chat_id: ChatId,
     localization: &Arc<crate::localization::LocalizationManager>,
     error_type: &str,
     language_code: Option<&str>,
@@ -503,7 +632,7 @@ async fn handle_recipe_name_validation_error(
         _ => t_lang(localization, "recipe-name-invalid", language_code),
     };
 
-    bot.send_message(msg.chat.id, error_message).await?;
+    bot.send_message(chat_id, error_message).await?;
     // Keep dialogue active, user can try again
     Ok(())
 }
@@ -520,6 +649,7 @@ pub async fn handle_ingredient_edit_input(
         localization: _,
     } = ctx;
     let IngredientEditInputParams {
+        pool,
         edit_input,
         recipe_name,
         ingredients,
@@ -548,10 +678,11 @@ pub async fn handle_ingredient_edit_input(
         .await;
     }
 
-    // Parse and validate the user input
-    match parse_ingredient_from_text(edit_input) {
-        Ok(new_ingredient) => {
+    // Parse the user input, keeping what it leaves out of the edited ingredient
+    match edited_ingredient(&ingredients, editing_index, edit_input) {
+        Ok(IngredientEdit::Updated(new_ingredient)) => {
             handle_edit_success(EditSuccessParams {
+                pool,
                 ctx: handler_ctx,
                 msg,
                 dialogue,
@@ -566,6 +697,16 @@ pub async fn handle_ingredient_edit_input(
             })
             .await
         }
+        Ok(IngredientEdit::Ambiguous) => {
+            send_edit_clarification(
+                bot,
+                msg,
+                handler_ctx.localization,
+                &ingredients[editing_index],
+                handler_ctx.language_code,
+            )
+            .await
+        }
         Err(error_msg) => {
             handle_edit_error(
                 bot,
@@ -596,6 +737,7 @@ pub async fn handle_recipe_rename_input(
         recipe_id,
         current_name,
         ctx: handler_ctx,
+        cache,
     } = params;
 
     let input = new_name_input.trim().to_lowercase();
@@ -619,23 +761,61 @@ pub async fn handle_recipe_rename_input(
     match validate_recipe_name(new_name_input) {
         Ok(validated_name) => {
             // Update the recipe name in the database
-            match update_recipe_name(_pool, recipe_id, validated_name).await {
+            let renamed = match cache {
+                Some(cache) => {
+                    update_recipe_name_cached(
+                        _pool,
+                        TelegramId(msg.chat.id.0),
+                        RecipeId(recipe_id),
+                        validated_name,
+                        cache,
+                    )
+                    .await
+                }
+                None => update_recipe_name(_pool, RecipeId(recipe_id), validated_name).await,
+            };
+            match renamed {
                 Ok(true) => {
-                    let success_message = format!(
-                        "✅ **{}**\n\n{}",
-                        t_lang(
+                    crate::events::emit(RecipeEvent::recipe_renamed(
+                        RecipeId(recipe_id),
+                        validated_name,
+                    ));
+                    if let Err(e) = crate::db::record_recipe_activity(
+                        _pool,
+                        TelegramId(msg.chat.id.0),
+                        RecipeId(recipe_id),
+                        crate::db::RecipeActivityKind::Renamed,
+                        Some(validated_name),
+                        Some(&current_name),
+                        None,
+                    )
+                    .await
+                    {
+                        // The rename itself succeeded; only the history entry is missing
+                        error_logging::log_database_error(
+                            &e,
+                            "record_recipe_activity",
+                            Some(msg.chat.id.0),
+                            None,
+                        );
+                    }
+                    let success_message = titled(
+                        "✅",
+                        &t_lang(
                             handler_ctx.localization,
                             "rename-recipe-success",
-                            handler_ctx.language_code
+                            handler_ctx.language_code,
                         ),
-                        t_args_lang(
+                        &[&t_args_lang(
                             handler_ctx.localization,
                             "rename-recipe-success-details",
                             &[("old_name", &current_name), ("new_name", validated_name)],
-                            handler_ctx.language_code
-                        )
+                            handler_ctx.language_code,
+                        )],
                     );
-                    bot.send_message(msg.chat.id, success_message).await?;
+                    bot.send_message(msg.chat.id, success_message)
+                        .parse_mode(PARSE_MODE)
+                        .await?;
                 }
                 Ok(false) => {
                     let message = t_lang(
@@ -655,20 +835,22 @@ pub async fn handle_recipe_rename_input(
                             ("current_name", &current_name),
                         ]),
                     );
-                    let message = format!(
-                        "❌ **{}**\n\n{}",
-                        t_lang(
+                    let message = titled(
+                        "❌",
+                        &t_lang(
                             handler_ctx.localization,
                             "error-renaming-recipe",
-                            handler_ctx.language_code
+                            handler_ctx.language_code,
                         ),
-                        t_lang(
+                        &[&t_lang(
                             handler_ctx.localization,
                             "error-renaming-recipe-help",
-                            handler_ctx.language_code
-                        )
+                            handler_ctx.language_code,
+                        )],
                     );
-                    bot.send_message(msg.chat.id, message).await?;
+                    bot.send_message(msg.chat.id, message)
+                        .parse_mode(PARSE_MODE)
+                        .await?;
                 }
             }
         }
@@ -715,6 +897,180 @@ pub async fn handle_recipe_rename_input(
     Ok(())
 }
 
+/// Handle the new name typed for the recipe under review
+///
+/// The reviewed ingredients are left untouched: only the name changes, and the
+/// review message is re-rendered with it. Cancelling keeps the current name.
+pub async fn handle_pending_recipe_rename_input(
+    ctx: DialogueContext<'_>,
+    params: PendingRecipeRenameInputParams<'_>,
+) -> Result<()> {
+    let DialogueContext {
+        bot,
+        msg,
+        dialogue,
+        localization: _,
+    } = ctx;
+    let PendingRecipeRenameInputParams {
+        new_name_input,
+        ctx: handler_ctx,
+    } = params;
+
+    let new_name = if is_cancellation_command(&new_name_input.trim().to_lowercase()) {
+        None
+    } else {
+        match validate_recipe_name(new_name_input) {
+            Ok(validated_name) => Some(validated_name),
+            Err(error) => {
+                let key = if error == "too_long" {
+                    "recipe-name-too-long"
+                } else {
+                    "recipe-name-invalid"
+                };
+                bot.send_message(
+                    msg.chat.id,
+                    t_lang(handler_ctx.localization, key, handler_ctx.language_code),
+                )
+                .await?;
+                // Keep dialogue active, user can try again
+                return Ok(());
+            }
+        }
+    };
+
+    let Some(review) = dialogue
+        .get()
+        .await?
+        .and_then(|state| state.into_renamed_review(new_name))
+    else {
+        return Ok(());
+    };
+
+    if let RecipeDialogueState::ReviewIngredients {
+        ingredients,
+        message_id,
+        recipe_name_from_caption,
+        ..
+    } = &review
+    {
+        let review_message = format_review_message(
+            ingredients,
+            recipe_name_from_caption.as_deref(),
+            display_units(msg.chat.id),
+            handler_ctx.language_code,
+            handler_ctx.localization,
+        );
+        let keyboard = create_ingredient_review_keyboard_for_variant(
+            ingredients,
+            handler_ctx.language_code,
+            handler_ctx.localization,
+            review_keyboard_variant(TelegramId(msg.chat.id.0)),
+        );
+
+        // If we have a message_id, edit the existing message; otherwise send a new one
+        if let Some(msg_id) = message_id {
+            match bot
+                .edit_message_text(
+                    msg.chat.id,
+                    teloxide::types::MessageId(*msg_id),
+                    review_message,
+                )
+                .parse_mode(PARSE_MODE)
+                .reply_markup(keyboard)
+                .await
+            {
+                Ok(_) => (),
+                Err(e) if is_message_not_modified_error(&e) => {
+                    debug!("Message edit skipped - content unchanged (pending rename)");
+                }
+                Err(e) => {
+                    error_logging::log_internal_error(
+                        &e,
+                        "handle_pending_recipe_rename_input",
+                        "Failed to edit review message after rename",
+                        Some(msg.chat.id.0),
+                    );
+                }
+            }
+        } else {
+            bot.send_message(msg.chat.id, review_message)
+                .parse_mode(PARSE_MODE)
+                .reply_markup(keyboard)
+                .await?;
+        }
+    }
+
+    let confirmation = match new_name {
+        Some(name) => t_args_lang(
+            handler_ctx.localization,
+            "review-rename-success",
+            &[("recipe_name", name)],
+            handler_ctx.language_code,
+        ),
+        None => t_lang(
+            handler_ctx.localization,
+            "review-rename-cancelled",
+            handler_ctx.language_code,
+        ),
+    };
+    bot.send_message(msg.chat.id, confirmation).await?;
+
+    dialogue.update(review).await?;
+    Ok(())
+}
+
+/// Check if text is a command that leaves any dialogue instead of being read as its input
+///
+/// `/cancel` only leaves the dialogue; `/start` and `/help` are answered afterwards.
+pub fn is_dialogue_escape_command(text: &str) -> bool {
+    matches!(
+        text.split_whitespace().next(),
+        Some("/cancel" | "/start" | "/help")
+    )
+}
+
+/// Leave the current dialogue and remove the keyboards of its messages
+///
+/// Returns whether a dialogue was in progress.
+pub async fn exit_dialogue(bot: &Bot, chat_id: ChatId, dialogue: &RecipeDialogue) -> Result<bool> {
+    let state = dialogue.get().await?.unwrap_or_default();
+    if !state.is_active() {
+        return Ok(false);
+    }
+
+    for message_id in state.keyboard_message_ids() {
+        // The message may be gone or already stripped of its keyboard
+        if let Err(e) = bot
+            .edit_message_reply_markup(chat_id, teloxide::types::MessageId(message_id))
+            .await
+        {
+            debug!(user_id = %chat_id, message_id, error = %e, "Could not remove dialogue keyboard");
+        }
+    }
+
+    dialogue.exit().await?;
+    info!(user_id = %chat_id, state = state.name(), "Dialogue left by command");
+    Ok(true)
+}
+
+/// Handle the /cancel command: leave any dialogue and confirm it
+pub async fn handle_cancel_command(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let key = if exit_dialogue(bot, msg.chat.id, dialogue).await? {
+        "dialogue-cancelled"
+    } else {
+        "nothing-to-cancel"
+    };
+    bot.send_message(msg.chat.id, t_lang(localization, key, language_code))
+        .await?;
+    Ok(())
+}
+
 /// Check if input is a cancellation command
 fn is_cancellation_command(input: &str) -> bool {
     matches!(input, "cancel" | "stop" | "back")
@@ -734,15 +1090,20 @@ async fn handle_edit_cancellation(params: EditCancellationParams<'_>) -> Result<
     } = params;
 
     // User cancelled editing, return to review state without changes
-    let review_message = format!(
-        "📝 **{}**\n\n{}\n\n{}",
-        t_lang(ctx.localization, "review-title", ctx.language_code),
-        t_lang(ctx.localization, "review-description", ctx.language_code),
-        format_ingredients_list(ingredients, ctx.language_code, ctx.localization)
+    let review_message = format_review_message(
+        ingredients,
+        recipe_name_from_caption.as_deref(),
+        display_units(msg.chat.id),
+        ctx.language_code,
+        ctx.localization,
     );
 
-    let keyboard =
-        create_ingredient_review_keyboard(ingredients, ctx.language_code, ctx.localization);
+    let keyboard = create_ingredient_review_keyboard_for_variant(
+        ingredients,
+        ctx.language_code,
+        ctx.localization,
+        review_keyboard_variant(TelegramId(msg.chat.id.0)),
+    );
 
     // If we have a message_id, edit the existing message; otherwise send a new one
     if let Some(msg_id) = message_id {
@@ -753,6 +1114,7 @@ async fn handle_edit_cancellation(params: EditCancellationParams<'_>) -> Result<
                 teloxide::types::MessageId(msg_id),
                 review_message,
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await
         {
@@ -772,6 +1134,7 @@ async fn handle_edit_cancellation(params: EditCancellationParams<'_>) -> Result<
     } else {
         ctx.bot
             .send_message(msg.chat.id, review_message)
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await?;
     }
@@ -785,6 +1148,7 @@ async fn handle_edit_cancellation(params: EditCancellationParams<'_>) -> Result<
             message_id,
             extracted_text,
             recipe_name_from_caption, // Preserve caption info
+            last_deleted: None,
         })
         .await?;
 
@@ -794,6 +1158,7 @@ async fn handle_edit_cancellation(params: EditCancellationParams<'_>) -> Result<
 /// Handle successful ingredient editing
 async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
     let EditSuccessParams {
+        pool,
         ctx,
         msg,
         dialogue,
@@ -812,15 +1177,23 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
         ingredients[editing_index] = new_ingredient;
 
         // Return to review state with updated ingredients
-        let review_message = format!(
-            "📝 **{}**\n\n{}\n\n{}",
-            t_lang(ctx.localization, "review-title", ctx.language_code),
-            t_lang(ctx.localization, "review-description", ctx.language_code),
-            format_ingredients_list(&ingredients, ctx.language_code, ctx.localization)
+        let review_message = format_review_message(
+            &ingredients,
+            recipe_name_from_caption.as_deref(),
+            display_units(msg.chat.id),
+            ctx.language_code,
+            ctx.localization,
         );
 
-        let keyboard =
-            create_ingredient_review_keyboard(&ingredients, ctx.language_code, ctx.localization);
+        let telegram_id = TelegramId(msg.chat.id.0);
+        let keyboard = create_ingredient_review_keyboard_for_variant(
+            &ingredients,
+            ctx.language_code,
+            ctx.localization,
+            review_keyboard_variant(telegram_id),
+        );
+        track_review_funnel_event(&pool, telegram_id, FunnelEvent::EditsMade).await;
+        extraction_feedback::record_correction(telegram_id, Correction::Edited);
 
         // If we have a message_id, edit the existing message; otherwise send a new one
         if let Some(msg_id) = message_id {
@@ -831,6 +1204,7 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
                     teloxide::types::MessageId(msg_id),
                     review_message,
                 )
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard)
                 .await
             {
@@ -852,6 +1226,7 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
             let mut send_request = ctx
                 .bot
                 .send_message(msg.chat.id, review_message)
+                .parse_mode(PARSE_MODE)
                 .reply_markup(keyboard);
 
             if let Some(input_msg_id) = user_input_message_id {
@@ -872,6 +1247,7 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
                 message_id,
                 extracted_text,
                 recipe_name_from_caption: recipe_name_from_caption.clone(), // Preserve caption info
+                last_deleted: None,
             })
             .await?;
     } else {
@@ -890,6 +1266,7 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
                 message_id,
                 extracted_text,
                 recipe_name_from_caption: recipe_name_from_caption.clone(), // Preserve caption info
+                last_deleted: None,
             })
             .await?;
     }
@@ -897,6 +1274,40 @@ async fn handle_edit_success(params: EditSuccessParams<'_>) -> Result<()> {
     Ok(())
 }
 
+/// Parse an edited line against the ingredient at `editing_index`
+///
+/// Partial input ("3", "3 cups") only changes what it contains; see
+/// [`parse_ingredient_edit`]. Without an ingredient at that index the line is
+/// parsed as a whole.
+fn edited_ingredient(
+    ingredients: &[MeasurementMatch],
+    editing_index: usize,
+    edit_input: &str,
+) -> Result<IngredientEdit, &'static str> {
+    match ingredients.get(editing_index) {
+        Some(current) => parse_ingredient_edit(current, edit_input),
+        None => parse_ingredient_from_text(edit_input).map(IngredientEdit::Updated),
+    }
+}
+
+/// Ask which part of an ingredient an ambiguous edit meant to change
+async fn send_edit_clarification(
+    bot: &Bot,
+    msg: &Message,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    ingredient: &MeasurementMatch,
+    language_code: Option<&str>,
+) -> Result<()> {
+    bot.send_message(
+        msg.chat.id,
+        format_edit_clarification(ingredient, language_code, localization),
+    )
+    .parse_mode(PARSE_MODE)
+    .await?;
+    // Stay in editing state for the user to answer
+    Ok(())
+}
+
 /// Handle ingredient editing error
 async fn handle_edit_error(
     bot: &Bot,
@@ -934,6 +1345,7 @@ pub async fn handle_ingredient_review_input(
         ingredients,
         ctx: handler_ctx,
         extracted_text,
+        cache,
     } = params;
     let input = review_input.trim().to_lowercase();
 
@@ -986,10 +1398,13 @@ pub async fn handle_ingredient_review_input(
             if let Err(e) = save_ingredients_to_database(
                 &_pool,
                 msg.chat.id.0,
-                &extracted_text,
+                &saved_recipe_text(msg.chat.id, &extracted_text),
                 &ingredients,
                 &recipe_name,
                 handler_ctx.language_code,
+                save_idempotency_key(msg.chat.id).as_deref(),
+                saved_recipe_photo(msg.chat.id).as_ref(),
+                cache,
             )
             .await
             {
@@ -1004,23 +1419,33 @@ pub async fn handle_ingredient_review_input(
                     msg.chat.id,
                     t_lang(
                         handler_ctx.localization,
-                        "error-processing-failed",
+                        "error-recipe-save-failed",
                         handler_ctx.language_code,
                     ),
                 )
                 .await?;
+                // Nothing was stored: stay in review so "confirm" can be sent again
+                return Ok(());
             } else {
                 // Success! Send confirmation message
-                let success_message = t_args_lang(
-                    handler_ctx.localization,
-                    "recipe-complete",
-                    &[
-                        ("recipe_name", recipe_name.as_str()),
-                        ("ingredient_count", &ingredients.len().to_string()),
-                    ],
-                    handler_ctx.language_code,
+                let success_message = format!(
+                    "✅ {}",
+                    t_plural(
+                        handler_ctx.localization,
+                        "ingredients-saved",
+                        ingredients.len(),
+                        &[("recipe_name", recipe_name.as_str())],
+                        handler_ctx.language_code,
+                    )
                 );
-                bot.send_message(msg.chat.id, success_message).await?;
+                send_saved_confirmation(
+                    bot,
+                    msg.chat.id,
+                    None,
+                    HtmlMessage::new().text(&success_message).build(),
+                    None,
+                )
+                .await?;
             }
 
             // End the dialogue
@@ -1041,20 +1466,22 @@ pub async fn handle_ingredient_review_input(
         }
         _ => {
             // Unknown command, show help
-            let help_message = format!(
-                "{}\n\n{}",
-                t_lang(
+            let help_message = HtmlMessage::new()
+                .text(&t_lang(
                     handler_ctx.localization,
                     "review-help",
-                    handler_ctx.language_code
-                ),
-                format_ingredients_list(
+                    handler_ctx.language_code,
+                ))
+                .paragraph_html(&format_ingredients_list(
                     &ingredients,
+                    display_units(msg.chat.id),
                     handler_ctx.language_code,
-                    handler_ctx.localization
-                )
-            );
-            bot.send_message(msg.chat.id, help_message).await?;
+                    handler_ctx.localization,
+                ))
+                .build();
+            bot.send_message(msg.chat.id, help_message)
+                .parse_mode(PARSE_MODE)
+                .await?;
             // Keep dialogue active
         }
     }
@@ -1062,7 +1489,37 @@ pub async fn handle_ingredient_review_input(
     Ok(())
 }
 
+/// Idempotency key for saving the extraction currently under review in a chat
+///
+/// This is the correlation id of the chat's latest extraction, so a confirm that is
+/// retried after a handler error maps to the recipe the first attempt created.
+pub fn save_idempotency_key(chat_id: ChatId) -> Option<String> {
+    crate::extraction_reports::recent_extraction(chat_id.0).map(|context| context.correlation_id)
+}
+
+/// Text to save with the recipe from the extraction under review in a chat
+///
+/// The full OCR text when it is still kept, otherwise the bounded `state_text`
+/// from the dialogue state.
+pub fn saved_recipe_text(chat_id: ChatId, state_text: &str) -> String {
+    crate::dialogue::full_review_text(chat_id, state_text)
+}
+
+/// Photo to keep with the recipe saved from the extraction under review in a chat
+///
+/// `None` when the extraction was read from text or a document.
+pub fn saved_recipe_photo(chat_id: ChatId) -> Option<RecipePhoto> {
+    crate::extraction_reports::recent_extraction(chat_id.0).and_then(|context| context.recipe_photo)
+}
+
 /// Save ingredients to database
+///
+/// The recipe and its ingredients are written in a single transaction: on any
+/// failure nothing is stored. When `idempotency_key` is set and a recipe was already
+/// saved with it, nothing is written again and the save is reported as successful.
+/// The original `photo`, when given, is kept so the user can view it again.
+/// The user's cached recipe list is evicted when `cache` is given.
+#[allow(clippy::too_many_arguments)]
 pub async fn save_ingredients_to_database(
     pool: &PgPool,
     telegram_id: i64,
@@ -1070,14 +1527,25 @@ pub async fn save_ingredients_to_database(
     ingredients: &[MeasurementMatch],
     recipe_name: &str,
     language_code: Option<&str>,
+    idempotency_key: Option<&str>,
+    photo: Option<&RecipePhoto>,
+    cache: Option<&SharedCacheManager>,
 ) -> Result<()> {
     let start_time = std::time::Instant::now();
+    let telegram_id = TelegramId(telegram_id);
 
     info!(telegram_id = %telegram_id, ingredient_count = %ingredients.len(), "Starting ingredient save process");
 
     // Get or create user
     info!(telegram_id = %telegram_id, "Calling get_or_create_user");
-    let user = match get_or_create_user(pool, telegram_id, language_code).await {
+    let resolved = match cache {
+        Some(cache) => {
+            let backend = cache.lock().backend();
+            get_or_create_user_cached(pool, telegram_id, language_code, backend.as_ref()).await
+        }
+        None => get_or_create_user(pool, telegram_id, language_code).await,
+    };
+    let user = match resolved {
         Ok(user) => {
             info!(telegram_id = %telegram_id, user_id = %user.id, user_telegram_id = %user.telegram_id, "User resolved successfully");
             user
@@ -1103,10 +1571,29 @@ pub async fn save_ingredients_to_database(
         ));
     }
 
+    // The recipe, its name and all its ingredients are committed together, so a
+    // failure part way through leaves nothing behind and the save can be retried
+    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
     // Create recipe
     info!(telegram_id = %telegram_id, user_id = %user.id, "Creating recipe");
-    let recipe_id = match create_recipe(pool, telegram_id, extracted_text).await {
-        Ok(id) => {
+    let created = match idempotency_key {
+        Some(key) => create_recipe_idempotent_tx(&mut tx, telegram_id, extracted_text, key).await,
+        None => create_recipe_tx(&mut tx, telegram_id, extracted_text)
+            .await
+            .map(|id| (id, true)),
+    };
+    let recipe_id = match created {
+        Ok((id, false)) => {
+            info!(
+                telegram_id = %telegram_id,
+                recipe_id = %id,
+                idempotency_key = ?idempotency_key,
+                "Recipe already saved for this extraction, skipping retried save"
+            );
+            return Ok(());
+        }
+        Ok((id, true)) => {
             info!(telegram_id = %telegram_id, recipe_id = %id, "Recipe created successfully");
             id
         }
@@ -1118,58 +1605,50 @@ pub async fn save_ingredients_to_database(
 
     // Update recipe with recipe name
     info!(recipe_id = %recipe_id, recipe_name = %recipe_name, "Updating recipe name");
-    match update_recipe_name(pool, recipe_id, recipe_name).await {
-        Ok(_) => {
-            info!(recipe_id = %recipe_id, "Recipe name updated successfully");
-        }
-        Err(e) => {
-            error!(recipe_id = %recipe_id, recipe_name = %recipe_name, error = %e, "Recipe name update failed");
-            return Err(e);
-        }
-    };
+    if let Err(e) = update_recipe_name_tx(&mut tx, recipe_id, recipe_name).await {
+        error!(recipe_id = %recipe_id, recipe_name = %recipe_name, error = %e, "Recipe name update failed");
+        return Err(e);
+    }
+    info!(recipe_id = %recipe_id, "Recipe name updated successfully");
 
-    // Save each ingredient
-    for (i, ingredient) in ingredients.iter().enumerate() {
-        // Parse quantity from string (handle fractions)
-        let quantity = parse_quantity(&ingredient.quantity);
-        let unit = ingredient.measurement.as_deref();
+    if let Some(photo) = photo {
+        set_recipe_photo_tx(&mut tx, recipe_id, photo).await?;
+    }
 
-        info!(
-            ingredient_index = %i,
+    // Save all ingredients with a single INSERT. A range quantity keeps its lower
+    // bound, with the range itself as raw text instead of the OCR text
+    let range_texts: Vec<Option<String>> = ingredients.iter().map(|i| i.range_text()).collect();
+    let new_ingredients: Vec<NewIngredient> = ingredients
+        .iter()
+        .zip(&range_texts)
+        .map(|(ingredient, range_text)| NewIngredient {
+            name: &ingredient.ingredient_name,
+            // Keep the exact fraction written ("1/3"), not a rounded float
+            quantity: Quantity::parse(&ingredient.quantity),
+            unit: ingredient.measurement.as_deref(),
+            raw_text: Some(range_text.as_deref().unwrap_or(extracted_text)),
+        })
+        .collect();
+    info!(user_id = %user.id, recipe_id = %recipe_id, count = new_ingredients.len(), "Creating ingredients");
+    if let Err(e) = create_ingredients_bulk(&mut tx, user.id, recipe_id, &new_ingredients).await {
+        error!(
             user_id = %user.id,
             recipe_id = %recipe_id,
-            name = %ingredient.ingredient_name,
-            quantity = ?quantity,
-            unit = ?unit,
-            "Creating ingredient"
+            error = %e,
+            "Ingredient creation failed, rolling back recipe"
         );
-
-        match create_ingredient(
-            pool,
-            user.id,
-            Some(recipe_id),
-            &ingredient.ingredient_name,
-            quantity,
-            unit,
-            extracted_text,
-        )
-        .await
-        {
-            Ok(_) => {
-                info!(ingredient_index = %i, name = %ingredient.ingredient_name, "Ingredient created successfully");
-            }
-            Err(e) => {
-                error!(
-                    ingredient_index = %i,
-                    user_id = %user.id,
-                    recipe_id = %recipe_id,
-                    name = %ingredient.ingredient_name,
-                    error = %e,
-                    "Ingredient creation failed"
-                );
-                return Err(e);
-            }
+        if let Err(rollback_error) = tx.rollback().await {
+            error!(recipe_id = %recipe_id, error = %rollback_error, "Rollback failed");
         }
+        return Err(e);
+    }
+
+    tx.commit().await.context("Failed to commit recipe")?;
+
+    if let Some(cache) = cache {
+        let mut cache = cache.lock();
+        cache.invalidate_recipe(recipe_id);
+        cache.invalidate_recipe_list(telegram_id);
     }
 
     let processing_duration = start_time.elapsed();
@@ -1187,8 +1666,13 @@ pub async fn save_ingredients_to_database(
         ingredients.len(),
         naming_method,
         processing_duration,
-        user.id,
+        user.id.0,
     );
+    crate::events::emit(RecipeEvent::recipe_created(
+        recipe_id,
+        recipe_name,
+        ingredients.len(),
+    ));
 
     info!(
         telegram_id = %telegram_id,
@@ -1219,6 +1703,7 @@ pub async fn handle_add_ingredient_input(
         recipe_id,
         original_ingredients,
         current_matches,
+        ingredients_version,
         ctx: handler_ctx,
         message_id,
     } = params;
@@ -1236,6 +1721,7 @@ pub async fn handle_add_ingredient_input(
             recipe_id,
             original_ingredients,
             current_matches,
+            ingredients_version,
             language_code: handler_ctx.language_code,
             message_id,
             user_input_message_id: Some(msg.id.0), // Add user's input message ID for reply functionality
@@ -1260,6 +1746,7 @@ pub async fn handle_add_ingredient_input(
                 recipe_id,
                 original_ingredients,
                 current_matches: &updated_matches,
+                ingredients_version,
                 language_code: handler_ctx.language_code,
                 message_id,
                 user_input_message_id: Some(msg.id.0), // Add user's input message ID for reply functionality
@@ -1306,6 +1793,7 @@ pub async fn handle_saved_ingredient_edit_input(
         recipe_id,
         original_ingredients,
         current_matches,
+        ingredients_version,
         ctx: handler_ctx,
         message_id: _,
         editing_index,
@@ -1326,6 +1814,7 @@ pub async fn handle_saved_ingredient_edit_input(
             recipe_id,
             original_ingredients,
             current_matches,
+            ingredients_version,
             language_code: handler_ctx.language_code,
             message_id: original_message_id, // Use original message ID for editing
             user_input_message_id,
@@ -1334,9 +1823,9 @@ pub async fn handle_saved_ingredient_edit_input(
         return Ok(());
     }
 
-    // Parse and validate the user input
-    match parse_ingredient_from_text(edit_input) {
-        Ok(new_ingredient) => {
+    // Parse the user input, keeping what it leaves out of the edited ingredient
+    match edited_ingredient(current_matches, editing_index, edit_input) {
+        Ok(IngredientEdit::Updated(new_ingredient)) => {
             // Update the ingredient at the editing index
             if editing_index < current_matches.len() {
                 let mut updated_matches = current_matches.to_vec();
@@ -1351,6 +1840,7 @@ pub async fn handle_saved_ingredient_edit_input(
                     recipe_id,
                     original_ingredients,
                     current_matches: &updated_matches,
+                    ingredients_version,
                     language_code: handler_ctx.language_code,
                     message_id: original_message_id, // Use original message ID for editing
                     user_input_message_id,
@@ -1375,6 +1865,7 @@ pub async fn handle_saved_ingredient_edit_input(
                     recipe_id,
                     original_ingredients,
                     current_matches,
+                    ingredients_version,
                     language_code: handler_ctx.language_code,
                     message_id: original_message_id, // Use original message ID for editing
                     user_input_message_id,
@@ -1382,6 +1873,16 @@ pub async fn handle_saved_ingredient_edit_input(
                 .await?;
             }
         }
+        Ok(IngredientEdit::Ambiguous) => {
+            send_edit_clarification(
+                bot,
+                msg,
+                handler_ctx.localization,
+                &current_matches[editing_index],
+                handler_ctx.language_code,
+            )
+            .await?;
+        }
         Err(error_msg) => {
             // Invalid input, ask user to try again
             let error_message = format!(
@@ -1413,8 +1914,9 @@ struct ReturnToSavedIngredientsReviewParams<'a> {
     dialogue: RecipeDialogue,
     localization: &'a Arc<crate::localization::LocalizationManager>,
     recipe_id: i64,
-    original_ingredients: &'a [Ingredient],
+    original_ingredients: &'a [IngredientSnapshot],
     current_matches: &'a [MeasurementMatch],
+    ingredients_version: i64,
     language_code: Option<&'a str>,
     message_id: Option<i32>,
     user_input_message_id: Option<i32>, // ID of the user's input message for reply functionality
@@ -1432,17 +1934,24 @@ async fn return_to_saved_ingredients_review(
         recipe_id,
         original_ingredients,
         current_matches,
+        ingredients_version,
         language_code,
         message_id,
         user_input_message_id,
     } = params;
     // Send updated ingredient list message
-    let review_message = format!(
-        "✏️ **{}**\n\n{}\n\n{}",
-        t_lang(localization, "editing-recipe", language_code),
-        t_lang(localization, "editing-instructions", language_code),
-        format_ingredients_list(current_matches, language_code, localization)
-    );
+    let review_message = HtmlMessage::titled(
+        "✏️",
+        &format_editing_title(current_matches.len(), None, language_code, localization),
+    )
+    .paragraph(&t_lang(localization, "editing-instructions", language_code))
+    .paragraph_html(&format_ingredients_list(
+        current_matches,
+        display_units(msg.chat.id),
+        language_code,
+        localization,
+    ))
+    .build();
 
     let keyboard = create_ingredient_review_keyboard(current_matches, language_code, localization);
 
@@ -1454,6 +1963,7 @@ async fn return_to_saved_ingredients_review(
                 teloxide::types::MessageId(msg_id),
                 review_message,
             )
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard)
             .await
         {
@@ -1474,6 +1984,7 @@ async fn return_to_saved_ingredients_review(
         // Send new message with reply to user's input if available
         let mut send_request = bot
             .send_message(msg.chat.id, review_message)
+            .parse_mode(PARSE_MODE)
             .reply_markup(keyboard);
 
         if let Some(input_msg_id) = user_input_message_id {
@@ -1493,6 +2004,8 @@ async fn return_to_saved_ingredients_review(
             current_matches: current_matches.to_vec(),
             language_code: language_code.map(|s| s.to_string()),
             message_id,
+            last_deleted: None,
+            ingredients_version,
         })
         .await?;
 
@@ -1510,6 +2023,7 @@ pub struct QuantityCorrectionInputParams<'a> {
     pub ctx: &'a HandlerContext<'a>,
     pub extracted_text: String,
     pub recipe_name_from_caption: Option<String>,
+    pub cache: Option<&'a SharedCacheManager>,
 }
 
 /// Handle quantity correction input during dialogue
@@ -1532,6 +2046,7 @@ pub async fn handle_quantity_correction_input(
         ctx: handler_ctx,
         extracted_text,
         recipe_name_from_caption,
+        cache,
     } = params;
 
     let input = quantity_input.trim();
@@ -1604,10 +2119,13 @@ pub async fn handle_quantity_correction_input(
                 if let Err(e) = save_ingredients_to_database(
                     &pool,
                     msg.chat.id.0,
-                    &extracted_text,
+                    &saved_recipe_text(msg.chat.id, &extracted_text),
                     &ingredients,
                     &recipe_name,
                     handler_ctx.language_code,
+                    save_idempotency_key(msg.chat.id).as_deref(),
+                    saved_recipe_photo(msg.chat.id).as_ref(),
+                    cache,
                 )
                 .await
                 {
@@ -1622,23 +2140,45 @@ pub async fn handle_quantity_correction_input(
                         msg.chat.id,
                         t_lang(
                             handler_ctx.localization,
-                            "error-processing-failed",
+                            "error-recipe-save-failed",
                             handler_ctx.language_code,
                         ),
                     )
                     .await?;
+                    // Nothing was stored: return to review with the corrected quantities
+                    // so "confirm" can be sent again
+                    dialogue
+                        .update(RecipeDialogueState::ReviewIngredients {
+                            recipe_name,
+                            ingredients,
+                            language_code: handler_ctx.language_code.map(|s| s.to_string()),
+                            message_id: None,
+                            extracted_text,
+                            recipe_name_from_caption,
+                            last_deleted: None,
+                        })
+                        .await?;
+                    return Ok(());
                 } else {
                     // Success! Send confirmation message
-                    let success_message = t_args_lang(
-                        handler_ctx.localization,
-                        "recipe-complete",
-                        &[
-                            ("recipe_name", recipe_name.as_str()),
-                            ("ingredient_count", &ingredients.len().to_string()),
-                        ],
-                        handler_ctx.language_code,
+                    let success_message = format!(
+                        "✅ {}",
+                        t_plural(
+                            handler_ctx.localization,
+                            "ingredients-saved",
+                            ingredients.len(),
+                            &[("recipe_name", recipe_name.as_str())],
+                            handler_ctx.language_code,
+                        )
                     );
-                    bot.send_message(msg.chat.id, success_message).await?;
+                    send_saved_confirmation(
+                        bot,
+                        msg.chat.id,
+                        None,
+                        HtmlMessage::new().text(&success_message).build(),
+                        None,
+                    )
+                    .await?;
                 }
 
                 // End the dialogue
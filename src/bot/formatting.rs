@@ -0,0 +1,491 @@
+//! HTML formatting of bot messages
+//!
+//! Messages with highlighted text are sent with [`PARSE_MODE`] (Telegram HTML).
+//! Everything interpolated into them, whether localized strings, recipe names,
+//! ingredient names or numbers, is escaped with [`escape_html`], so a recipe called
+//! "2*2 [test]" or "<b>" shows as typed instead of breaking the markup or making
+//! Telegram reject the message. HTML mode only reserves `<`, `>` and `&`, which makes
+//! it far easier to escape than MarkdownV2.
+//!
+//! Functions of this module and the `format_*` message functions of `ui_builder`
+//! return HTML ready to send; everything else is plain text.
+//!
+//! Telegram rejects messages longer than [`MAX_MESSAGE_LENGTH`], and a recipe with
+//! dozens of ingredients gets there. [`send_long_message`] sends such messages in
+//! several parts cut between paragraphs or lines by [`split_message`]; messages
+//! edited in place are kept to one part with [`fit_lines`] instead.
+
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardMarkup, ParseMode};
+use teloxide::RequestError;
+
+/// Parse mode of every message built with this module
+pub const PARSE_MODE: ParseMode = ParseMode::Html;
+
+/// Escape plain text for a message sent with [`PARSE_MODE`]
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Plain text shown in bold
+pub fn bold(text: &str) -> String {
+    format!("<b>{}</b>", escape_html(text))
+}
+
+/// Plain text shown in italics
+pub fn italic(text: &str) -> String {
+    format!("<i>{}</i>", escape_html(text))
+}
+
+/// Plain text in monospace, copied with a tap in Telegram clients
+pub fn code(text: &str) -> String {
+    format!("<code>{}</code>", escape_html(text))
+}
+
+/// A label and its value in bold ("Current name: <b>Pancakes</b>")
+pub fn labelled(label: &str, value: &str) -> String {
+    format!("{}: {}", escape_html(label), bold(value))
+}
+
+/// The most common message: an emoji, a bold title and paragraphs of plain text
+///
+/// Empty paragraphs are skipped.
+pub fn titled(icon: &str, title: &str, paragraphs: &[&str]) -> String {
+    paragraphs
+        .iter()
+        .fold(HtmlMessage::titled(icon, title), |message, paragraph| {
+            message.paragraph(paragraph)
+        })
+        .build()
+}
+
+/// Builder of an HTML message from plain text and already formatted fragments
+///
+/// Plain text given to [`text`](Self::text), [`bold`](Self::bold),
+/// [`line`](Self::line) and [`paragraph`](Self::paragraph) is escaped; only the
+/// `*_html` methods take markup, which must come from this module or from a
+/// `ui_builder` format function.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HtmlMessage {
+    html: String,
+}
+
+impl HtmlMessage {
+    /// Empty message
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Message starting with an emoji and a bold title
+    pub fn titled(icon: &str, title: &str) -> Self {
+        Self::new().text(icon).text(" ").bold(title)
+    }
+
+    /// Append plain text
+    pub fn text(mut self, text: &str) -> Self {
+        self.html.push_str(&escape_html(text));
+        self
+    }
+
+    /// Append plain text in bold
+    pub fn bold(mut self, text: &str) -> Self {
+        self.html.push_str(&bold(text));
+        self
+    }
+
+    /// Append formatted HTML as is
+    pub fn html(mut self, html: &str) -> Self {
+        self.html.push_str(html);
+        self
+    }
+
+    /// Start a new line of plain text
+    pub fn line(self, text: &str) -> Self {
+        self.html("\n").text(text)
+    }
+
+    /// Start a new line with formatted HTML
+    pub fn line_html(self, html: &str) -> Self {
+        self.html("\n").html(html)
+    }
+
+    /// Append a paragraph of plain text after a blank line, skipped when empty
+    pub fn paragraph(self, text: &str) -> Self {
+        self.paragraph_html(&escape_html(text))
+    }
+
+    /// Append a paragraph of formatted HTML after a blank line, skipped when empty
+    pub fn paragraph_html(self, html: &str) -> Self {
+        if html.is_empty() {
+            return self;
+        }
+        if self.html.is_empty() {
+            self.html(html)
+        } else {
+            self.html("\n\n").html(html)
+        }
+    }
+
+    /// Whether nothing was appended yet
+    pub fn is_empty(&self) -> bool {
+        self.html.is_empty()
+    }
+
+    /// The finished HTML
+    pub fn build(self) -> String {
+        self.html
+    }
+}
+
+impl From<HtmlMessage> for String {
+    fn from(message: HtmlMessage) -> Self {
+        message.build()
+    }
+}
+
+/// Longest message Telegram accepts, in UTF-16 code units
+pub const MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Length of a message as Telegram counts it
+///
+/// Measured on the HTML, which is never shorter than the text Telegram renders.
+pub fn message_length(html: &str) -> usize {
+    html.encode_utf16().count()
+}
+
+/// Split an HTML message into parts of at most `max_length`
+///
+/// Parts end between paragraphs when the next paragraph fits in a part of its own,
+/// otherwise between lines, so an ingredient line is never cut. Only a line longer
+/// than a whole part is cut inside, with its tags closed and reopened around the cut.
+pub fn split_message(html: &str, max_length: usize) -> Vec<String> {
+    let mut parts = MessageParts::new(max_length);
+    for paragraph in html.split("\n\n") {
+        if parts.push("\n\n", paragraph) {
+            continue;
+        }
+        if message_length(paragraph) <= max_length {
+            parts.start(paragraph);
+            continue;
+        }
+
+        let mut separator = "\n\n";
+        for line in paragraph.split('\n') {
+            for piece in split_line(line, max_length) {
+                if !parts.push(separator, &piece) {
+                    parts.start(&piece);
+                }
+                separator = "\n";
+            }
+        }
+    }
+    parts.finish()
+}
+
+/// Keep the leading lines of an HTML message that fit in `max_length`
+///
+/// When lines are dropped, the line returned by `more` for their count ends the
+/// message, so the reader knows the list goes on.
+pub fn fit_lines(html: &str, max_length: usize, more: impl Fn(usize) -> String) -> String {
+    if message_length(html) <= max_length {
+        return html.to_string();
+    }
+
+    let lines: Vec<&str> = html.lines().collect();
+    let mut kept_length = 0;
+    let mut best = 0;
+    for (kept, line) in lines.iter().enumerate() {
+        // `kept_length` counts the first `kept` lines, each with its newline
+        if kept_length + message_length(&more(lines.len() - kept)) <= max_length {
+            best = kept;
+        }
+        kept_length += message_length(line) + 1;
+    }
+
+    let mut fitted = lines[..best].join("\n");
+    if !fitted.is_empty() {
+        fitted.push('\n');
+    }
+    fitted.push_str(&more(lines.len() - best));
+    fitted
+}
+
+/// Send an HTML message, in several parts when it is longer than Telegram allows
+///
+/// The keyboard goes on the last part, which is returned.
+pub async fn send_long_message(
+    bot: &Bot,
+    chat_id: ChatId,
+    html: &str,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> Result<Message, RequestError> {
+    let mut parts = split_message(html, MAX_MESSAGE_LENGTH);
+    let last = parts.pop().unwrap_or_default();
+    for part in parts {
+        bot.send_message(chat_id, part)
+            .parse_mode(PARSE_MODE)
+            .await?;
+    }
+
+    let request = bot.send_message(chat_id, last).parse_mode(PARSE_MODE);
+    match keyboard {
+        Some(keyboard) => request.reply_markup(keyboard).await,
+        None => request.await,
+    }
+}
+
+/// Parts of a message being split, filled one piece at a time
+struct MessageParts {
+    parts: Vec<String>,
+    current: String,
+    max_length: usize,
+}
+
+impl MessageParts {
+    fn new(max_length: usize) -> Self {
+        Self {
+            parts: Vec::new(),
+            current: String::new(),
+            max_length,
+        }
+    }
+
+    /// Append a piece to the current part if it fits, after `separator` unless first
+    fn push(&mut self, separator: &str, piece: &str) -> bool {
+        let separator = if self.current.is_empty() {
+            ""
+        } else {
+            separator
+        };
+        if message_length(&self.current) + message_length(separator) + message_length(piece)
+            > self.max_length
+        {
+            return false;
+        }
+        self.current.push_str(separator);
+        self.current.push_str(piece);
+        true
+    }
+
+    /// Start a new part with a piece
+    fn start(&mut self, piece: &str) {
+        self.flush();
+        self.current.push_str(piece);
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.parts.push(std::mem::take(&mut self.current));
+        }
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.flush();
+        self.parts
+    }
+}
+
+/// Cut a line longer than `max_length` between characters, tags and entities
+fn split_line(line: &str, max_length: usize) -> Vec<String> {
+    if message_length(line) <= max_length {
+        return vec![line.to_string()];
+    }
+
+    let closing =
+        |open: &[&str]| -> String { open.iter().rev().map(|tag| format!("</{tag}>")).collect() };
+    let opening = |open: &[&str]| -> String { open.iter().map(|tag| format!("<{tag}>")).collect() };
+
+    let mut pieces = Vec::new();
+    let mut piece = String::new();
+    let mut open: Vec<&str> = Vec::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        let token_length = match c {
+            '<' => rest.find('>').map_or(c.len_utf8(), |end| end + 1),
+            '&' => rest.find(';').map_or(c.len_utf8(), |end| end + 1),
+            _ => c.len_utf8(),
+        };
+        let (token, remaining) = rest.split_at(token_length);
+        rest = remaining;
+
+        let mut open_after = open.clone();
+        if token.starts_with("</") {
+            open_after.pop();
+        } else if let Some(tag) = token.strip_prefix('<').and_then(|t| t.strip_suffix('>')) {
+            open_after.push(tag.split(' ').next().unwrap_or(tag));
+        }
+
+        let reopened = opening(&open);
+        let length = message_length(&piece) + message_length(token);
+        if length + message_length(&closing(&open_after)) > max_length && piece != reopened {
+            piece.push_str(&closing(&open));
+            pieces.push(std::mem::replace(&mut piece, reopened));
+        }
+        piece.push_str(token);
+        open = open_after;
+    }
+    if !piece.is_empty() {
+        pieces.push(piece);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names that break Markdown or HTML when interpolated raw
+    const HOSTILE_NAMES: &[&str] = &[
+        "2*2 [test]",
+        "**bold** _italic_ `code`",
+        "<script>alert(1)</script>",
+        "Fish & Chips <3",
+        "snake_case_name",
+        "🍰 Gâteau > 🥧 pie",
+        "&amp; already escaped",
+    ];
+
+    /// Remove the tags this module emits and unescape entities, as Telegram renders
+    fn render(html: &str) -> String {
+        let mut text = String::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            text.push_str(&rest[..start]);
+            let end = rest[start..].find('>').expect("unclosed tag") + start;
+            let tag = &rest[start + 1..end];
+            assert!(
+                matches!(tag, "b" | "/b" | "i" | "/i"),
+                "unexpected tag <{tag}> in {html}"
+            );
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("a < b && c > d"),
+            "a &lt; b &amp;&amp; c &gt; d"
+        );
+        assert_eq!(escape_html("2*2 [test] _x_"), "2*2 [test] _x_");
+        assert_eq!(escape_html("🍰"), "🍰");
+    }
+
+    #[test]
+    fn test_hostile_names_render_as_typed() {
+        for name in HOSTILE_NAMES {
+            assert_eq!(render(&bold(name)), *name);
+            assert_eq!(render(&italic(name)), *name);
+            assert_eq!(render(&escape_html(name)), *name);
+        }
+    }
+
+    #[test]
+    fn test_titled() {
+        assert_eq!(
+            titled("📚", "Recipes <all>", &["First", "", "Second & last"]),
+            "📚 <b>Recipes &lt;all&gt;</b>\n\nFirst\n\nSecond &amp; last"
+        );
+        assert_eq!(titled("✅", "Saved", &[]), "✅ <b>Saved</b>");
+        assert_eq!(
+            labelled("Current name", "<b>"),
+            "Current name: <b>&lt;b&gt;</b>"
+        );
+    }
+
+    #[test]
+    fn test_html_message_builder() {
+        let message = HtmlMessage::titled("📖", "<Cake>")
+            .paragraph_html(&bold("1 cup"))
+            .text(" → ")
+            .text("flour & sugar")
+            .line("2*2 [test]")
+            .build();
+        assert_eq!(
+            message,
+            "📖 <b>&lt;Cake&gt;</b>\n\n<b>1 cup</b> → flour &amp; sugar\n2*2 [test]"
+        );
+        assert_eq!(
+            render(&message),
+            "📖 <Cake>\n\n1 cup → flour & sugar\n2*2 [test]"
+        );
+
+        assert!(HtmlMessage::new().is_empty());
+        assert_eq!(HtmlMessage::new().paragraph("first").build(), "first");
+    }
+
+    #[test]
+    fn test_split_message() {
+        // Short messages are left whole
+        assert_eq!(
+            split_message("<b>Title</b>\n\nText", 50),
+            ["<b>Title</b>\n\nText"]
+        );
+
+        // Paragraphs that fit on their own start a new part
+        let html = "Title\n\nfirst paragraph\n\nsecond paragraph";
+        assert_eq!(
+            split_message(html, 30),
+            ["Title\n\nfirst paragraph", "second paragraph"]
+        );
+
+        // Longer paragraphs are cut between lines
+        let list = (1..=6)
+            .map(|i| format!("• <b>{i} g</b> item {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let parts = split_message(&format!("Title\n\n{list}"), 45);
+        assert!(parts.len() > 1);
+        assert!(parts.iter().all(|part| message_length(part) <= 45));
+        let lines = |text: &str| {
+            render(text)
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(&parts.join("\n")), lines(&format!("Title\n{list}")));
+
+        // A single overlong line is cut with its tags closed and reopened
+        let line = format!("{}{}", bold(&"é".repeat(30)), "&amp;".repeat(10));
+        let parts = split_message(&line, 16);
+        assert!(parts.iter().all(|part| message_length(part) <= 16));
+        assert_eq!(
+            render(&parts.concat()),
+            format!("{}{}", "é".repeat(30), "&".repeat(10))
+        );
+    }
+
+    #[test]
+    fn test_message_length_counts_utf16() {
+        assert_eq!(message_length("abc"), 3);
+        assert_eq!(message_length("é"), 1);
+        assert_eq!(message_length("🍰"), 2);
+    }
+
+    #[test]
+    fn test_fit_lines() {
+        let more = |count: usize| format!("… {count} more");
+        assert_eq!(fit_lines("a\nb", 10, more), "a\nb");
+
+        let html = "line 1\nline 2\nline 3\nline 4";
+        let fitted = fit_lines(html, 22, more);
+        assert_eq!(fitted, "line 1\nline 2\n… 2 more");
+        assert_eq!(fit_lines(html, 21, more), "line 1\n… 3 more");
+
+        assert_eq!(fit_lines(html, 8, more), "… 4 more");
+    }
+}
@@ -9,31 +9,41 @@ use tempfile::NamedTempFile;
 use tracing::{debug, info, warn};
 
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_args_lang, t_lang, t_plural};
 
 // Import text processing
 use crate::text_processing::{MeasurementDetector, MeasurementMatch};
 
+// Import the extraction pipeline shared with library users
+use crate::extractor::RecipeExtractor;
+pub use crate::extractor::{is_valid_fraction, is_valid_recovered_quantity};
+
 // Import OCR types
-use crate::circuit_breaker::CircuitBreaker;
-use crate::instance_manager::OcrInstanceManager;
-use crate::ocr::{
-    extract_hocr_from_image, map_measurement_to_bbox, parse_hocr_to_lines, perform_constrained_ocr,
-};
-use crate::ocr_config::OcrConfig;
 use crate::ocr_errors::OcrError;
-use crate::preprocessing::{
-    crop_measurement_region, preprocess_measurement_region, CroppedImageResult,
-};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 
 // Import UI builder functions
 use super::ui_builder::{
-    create_ingredient_review_keyboard, create_processing_keyboard, format_ingredients_list,
+    create_ingredient_review_keyboard_for_variant, create_processing_keyboard,
+    create_report_problem_keyboard, format_review_cap_notice, format_review_message,
+    with_retry_processing_button,
 };
 
+// Import HTML message formatting
+use super::formatting::{escape_html, PARSE_MODE};
+
+// Import retained images for the retry with other preprocessing
+use crate::cache::{RetainedImage, RetainedImageKey, SharedCacheManager};
+
+// Import extraction reports
+use crate::extraction_reports::{remember_extraction, ExtractionContext};
+
+// Import review keyboard experiment
+use crate::db::TelegramId;
+use crate::experiments::{resolve_review_keyboard_variant, track_review_funnel_event, FunnelEvent};
+
 // Import HandlerContext
 // use super::HandlerContext;
 
@@ -53,7 +63,7 @@ impl TempFileGuard {
         Self { path }
     }
 
-    fn path(&self) -> &str {
+    pub fn path(&self) -> &str {
         &self.path
     }
 }
@@ -81,38 +91,143 @@ impl Drop for TempFileGuard {
 }
 
 /// Parameters for image processing
-#[derive(Debug)]
 pub struct ImageProcessingParams<'a> {
     pub file_id: teloxide::types::FileId,
     pub chat_id: ChatId,
+    /// User whose OCR rate limit paid for the job, refunded when the photo is a duplicate
+    pub sender: Option<teloxide::types::UserId>,
+    pub message_id: teloxide::types::MessageId, // Message carrying the image
     pub success_message: &'a str,
     pub language_code: Option<&'a str>,
     pub dialogue: RecipeDialogue,
     pub pool: Arc<PgPool>,
     pub caption: Option<String>,
+    /// Recently sent photos, to skip duplicates; `None` always processes the image
+    pub cache: Option<crate::cache::SharedCacheManager>,
+    /// Whether the file is an image or a PDF document
+    pub kind: InputKind,
+    /// Further photos of the same album, read after `file_id` as one recipe
+    pub album: Vec<teloxide::types::FileId>,
+    /// Photo kept with the saved recipe; `None` for documents
+    pub photo: Option<crate::db::RecipePhoto>,
+}
+
+/// Kind of file sent to OCR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputKind {
+    /// Photo or image document, read directly
+    Image,
+    /// PDF document, whose first pages are rendered to images first
+    Pdf,
+}
+
+// Extractor photos are read with, using the OCR configuration with the per-source-class
+// profile, pipeline and pool overrides
+static RECIPE_EXTRACTOR: std::sync::LazyLock<RecipeExtractor> = std::sync::LazyLock::new(|| {
+    let config = crate::config::current();
+    RecipeExtractor::new(config.ocr.clone(), config.text_processing.clone())
+        .expect("OCR and text processing configuration is validated at startup")
+});
+
+/// Get a Tesseract instance from the pool photos are read with, initializing it if needed
+///
+/// Used by the readiness check, so a passing check also leaves a warm instance behind.
+pub fn ensure_ocr_instance() -> Result<()> {
+    RECIPE_EXTRACTOR
+        .instance_manager()
+        .get_instance(RECIPE_EXTRACTOR.ocr_config())
+        .map(|_| ())
+}
+
+/// Create the warm Tesseract instances photos are read with, returning how many were created
+///
+/// Called at startup so the first photo does not pay Tesseract initialization.
+pub fn warm_ocr_pool() -> Result<usize> {
+    RECIPE_EXTRACTOR
+        .instance_manager()
+        .warm_up(RECIPE_EXTRACTOR.ocr_config())
+}
+
+/// Line confidence below which ingredients are marked for double-checking in review
+pub fn low_confidence_threshold() -> f32 {
+    RECIPE_EXTRACTOR.ocr_config().low_confidence_threshold
+}
+
+/// Whether a PDF document of this size is within the configured PDF limits
+pub fn pdf_size_allowed(file_size: u64) -> bool {
+    crate::pdf::check_pdf_file_size(file_size, RECIPE_EXTRACTOR.ocr_config()).is_ok()
+}
+
+/// Whether an ingredient was read from a line OCR was unsure about
+pub fn is_low_confidence(ingredient: &MeasurementMatch) -> bool {
+    ingredient
+        .confidence
+        .is_some_and(|confidence| confidence < low_confidence_threshold())
+}
+
+/// Total time the OCR circuit breaker has been open since startup
+pub fn ocr_circuit_open_duration() -> std::time::Duration {
+    RECIPE_EXTRACTOR.circuit_breaker().total_open_duration()
+}
+
+/// Reply explaining why a file was rejected before OCR, giving the limit it went over
+pub fn image_limit_reply(
+    error: &OcrError,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    let kind = crate::errors::UserErrorKind::from(error);
+    let message = match error {
+        OcrError::FileTooLarge(_) => t_args_lang(
+            localization,
+            "error-file-too-large-limit",
+            &[(
+                "max_mb",
+                &format_limit(RECIPE_EXTRACTOR.ocr_config().max_file_size, 1024 * 1024),
+            )],
+            language_code,
+        ),
+        OcrError::ImageTooLarge(_) => t_args_lang(
+            localization,
+            "error-image-too-large-limit",
+            &[(
+                "max_megapixels",
+                &format_limit(RECIPE_EXTRACTOR.ocr_config().max_pixels, 1_000_000),
+            )],
+            language_code,
+        ),
+        _ => return crate::errors::user_reply(localization, kind, language_code),
+    };
+    format!(
+        "{}\n{}",
+        message,
+        t_lang(localization, kind.next_step_key(), language_code)
+    )
 }
 
-// Create OCR configuration with default settings
-static OCR_CONFIG: std::sync::LazyLock<OcrConfig> = std::sync::LazyLock::new(OcrConfig::default);
-static OCR_INSTANCE_MANAGER: std::sync::LazyLock<OcrInstanceManager> =
-    std::sync::LazyLock::new(OcrInstanceManager::default);
-static CIRCUIT_BREAKER: std::sync::LazyLock<CircuitBreaker> =
-    std::sync::LazyLock::new(|| CircuitBreaker::new(OCR_CONFIG.recovery.clone()));
+/// A limit in larger units, with at most one decimal ("10", "2.5")
+fn format_limit(value: u64, unit: u64) -> String {
+    ((value as f64 / unit as f64 * 10.0).round() / 10.0).to_string()
+}
 
+/// Download a Telegram file to a temporary file
+///
+/// The file is fetched from the bot's API URL, so a bot pointed at a local Bot
+/// API server (or a test double) downloads from there too.
 pub async fn download_file(bot: &Bot, file_id: teloxide::types::FileId) -> Result<TempFileGuard> {
     let file = bot.get_file(file_id).await?;
-    let file_path = file.path;
-    let url = format!(
-        "https://api.telegram.org/file/bot{}/{}",
-        bot.token(),
-        file_path
-    );
+    // Telegram reports the size, so oversized files are never downloaded
+    crate::resource_limits::check_download_size(
+        u64::from(file.size),
+        RECIPE_EXTRACTOR.ocr_config(),
+    )?;
+    let url = file_download_url(&bot.api_url(), bot.token(), &file.path)?;
 
-    let response = reqwest::get(&url).await?;
+    let response = bot.client().get(url).send().await?.error_for_status()?;
 
     // Check Content-Length header to prevent downloading oversized files
     if let Some(content_length) = response.content_length() {
-        let max_file_size = OCR_CONFIG.max_file_size;
+        let max_file_size = RECIPE_EXTRACTOR.ocr_config().max_file_size;
         if content_length > max_file_size {
             return Err(anyhow::anyhow!(
                 "File too large: {} bytes (maximum allowed: {} bytes)",
@@ -134,6 +249,42 @@ pub async fn download_file(bot: &Bot, file_id: teloxide::types::FileId) -> Resul
     Ok(TempFileGuard::new(path))
 }
 
+/// URL of a file on the Bot API server at `api_url`
+pub fn file_download_url(
+    api_url: &reqwest::Url,
+    token: &str,
+    file_path: &str,
+) -> Result<reqwest::Url> {
+    Ok(api_url.join(&format!("file/bot{token}/{file_path}"))?)
+}
+
+/// Content hash of a downloaded image, `None` when it cannot be read
+async fn photo_hash(path: &str) -> Option<String> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Some(crate::cache::image_content_hash(&bytes)),
+        Err(e) => {
+            error_logging::log_filesystem_error(&e, "read_image_for_hash", Some(path), None);
+            None
+        }
+    }
+}
+
+/// Keyboard offering to process a duplicate photo anyway
+pub fn create_duplicate_photo_keyboard(
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> teloxide::types::InlineKeyboardMarkup {
+    teloxide::types::InlineKeyboardMarkup::new(vec![vec![
+        super::ui_components::create_localized_button_with_emoji(
+            localization,
+            "🔄",
+            "duplicate-photo-reprocess",
+            "ocr_reprocess".to_string(),
+            language_code,
+        ),
+    ]])
+}
+
 pub async fn download_and_process_image(
     bot: &Bot,
     params: ImageProcessingParams<'_>,
@@ -142,27 +293,72 @@ pub async fn download_and_process_image(
     let ImageProcessingParams {
         file_id,
         chat_id,
+        sender,
+        message_id,
         success_message,
         language_code,
         dialogue,
-        pool: _pool,
+        pool,
         caption,
+        cache,
+        kind,
+        album,
+        photo,
     } = params;
+    // A shutdown waits for the photo to be read and answered
+    let _in_flight = crate::shutdown::global().track();
+    // Kept so a reported extraction can share the image once the user consents
+    let photo_file_id = file_id.0.clone();
     let temp_file_guard = match download_file(bot, file_id).await {
         Ok(guard) => {
             debug!(user_id = %chat_id, temp_path = %guard, "Image downloaded successfully");
             guard
         }
         Err(e) => {
-            error_logging::log_network_error(&e, "download_image_file", None, None);
+            let reply = match e.downcast_ref::<OcrError>() {
+                Some(limit_error) => {
+                    warn!(user_id = %chat_id, error = %limit_error, "File rejected before download");
+                    image_limit_reply(limit_error, localization, language_code)
+                }
+                None => {
+                    error_logging::log_network_error(&e, "download_image_file", None, None);
+                    t_lang(localization, "error-download-failed", language_code)
+                }
+            };
+            bot.send_message(chat_id, reply).await?;
+            return Err(e);
+        }
+    }; // The guard will be moved into the async block below
+
+    // The same photo sent again shortly after is only processed if the user asks for it
+    let recorded_photo = match cache.as_ref().filter(|_| album.is_empty()) {
+        Some(cache) => photo_hash(temp_file_guard.path())
+            .await
+            .map(|image_hash| (cache, image_hash)),
+        None => None,
+    };
+    if let Some((cache, image_hash)) = &recorded_photo {
+        let duplicate = cache
+            .lock()
+            .ocr_result_cache
+            .check_and_record(chat_id.0, image_hash);
+        if duplicate {
+            info!(user_id = %chat_id, "Duplicate photo received, skipping OCR");
+            // No OCR ran, so the job does not count against the rate limit
+            if let Some(sender) = sender {
+                crate::rate_limiter::OCR_RATE_LIMITER.refund(sender.0 as i64);
+            }
             bot.send_message(
                 chat_id,
-                t_lang(localization, "error-download-failed", language_code),
+                t_lang(localization, "duplicate-photo", language_code),
             )
+            .reply_parameters(teloxide::types::ReplyParameters::new(message_id))
+            .reply_markup(create_duplicate_photo_keyboard(language_code, localization))
             .await?;
-            return Err(e);
+            return Ok(String::new());
         }
-    }; // The guard will be moved into the async block below
+    }
+    let mut review_shown = false;
     let result = async {
         info!("Image downloaded to: {}", temp_file_guard);
 
@@ -174,22 +370,59 @@ pub async fn download_and_process_image(
         let success_message_id = success_msg.id;
 
         // Validate image format before OCR processing
-        if !crate::ocr::is_supported_image_format(temp_file_guard.path(), &OCR_CONFIG) {
+        if kind == InputKind::Image
+            && !crate::ocr::is_supported_image_format(temp_file_guard.path(), RECIPE_EXTRACTOR.ocr_config())
+        {
             warn!(user_id = %chat_id, "Unsupported image format rejected");
             bot.edit_message_text(chat_id, success_message_id, t_lang(localization, "error-unsupported-format", language_code))
                 .await?;
             return Ok(String::new());
         }
 
-        // Extract text from the image using OCR with circuit breaker protection
-        match crate::ocr::extract_text_from_image(
-            temp_file_guard.path(),
-            &OCR_CONFIG,
-            &OCR_INSTANCE_MANAGER,
-            &CIRCUIT_BREAKER,
-        )
-        .await
-        {
+        // Reject images over the pixel limit and downscale those over the width or height limit
+        if kind == InputKind::Image {
+            if let Err(e) =
+                crate::resource_limits::prepare_image_for_ocr(temp_file_guard.path(), RECIPE_EXTRACTOR.ocr_config())
+                    .await
+            {
+                warn!(user_id = %chat_id, error = %e, "Image rejected before OCR");
+                observability::record_error_metrics(e.error_type(), "ocr");
+                bot.edit_message_text(chat_id, success_message_id, image_limit_reply(&e, localization, language_code))
+                    .await?;
+                return Ok(String::new());
+            }
+        }
+
+        // Extract text from the image (or the PDF pages) using OCR with circuit breaker protection
+        let mut pdf_pages = Vec::new();
+        let mut failed_photos = 0;
+        let extraction = match kind {
+            InputKind::Image if !album.is_empty() => {
+                extract_text_from_album(bot, temp_file_guard.path(), &album)
+                    .await
+                    .map(|(text, confidence, failed)| {
+                        failed_photos = failed;
+                        (text, confidence)
+                    })
+            }
+            InputKind::Image => {
+                RECIPE_EXTRACTOR.read_text(temp_file_guard.path())
+                .await
+            }
+            InputKind::Pdf => extract_text_from_pdf(temp_file_guard.path())
+                .await
+                .map(|(text, confidence, pages)| {
+                    pdf_pages = pages;
+                    (text, confidence)
+                }),
+        };
+        // Quantity recovery re-reads the first page or photo; lines of later ones do not map onto it
+        let recovery_image_path = pdf_pages
+            .first()
+            .map(|page| page.to_string_lossy().to_string())
+            .unwrap_or_else(|| temp_file_guard.path().to_string());
+
+        match extraction {
             Ok((extracted_text, confidence)) => {
                 // Log confidence information
                 info!(
@@ -223,83 +456,87 @@ pub async fn download_and_process_image(
                     );
 
                     // Process the extracted text to find ingredients with measurements and automated recovery
-                    let ingredients = process_ingredients_with_recovery(
+                    let ingredients = RECIPE_EXTRACTOR
+                        .find_ingredients(&extracted_text, Some(&recovery_image_path))
+                        .await;
+
+                    // Keep the review (and the dialogue state) within the review limit
+                    let cap_notice = format_review_cap_notice(ingredients.len(), language_code, localization);
+                    let mut ingredients = crate::dialogue::cap_review_ingredients(ingredients);
+                    crate::ocr::apply_line_confidences(&mut ingredients, &confidence.line_confidences);
+
+                    // Remember this extraction so the user can report it as bad
+                    let report_context = ExtractionContext::new(
                         &extracted_text,
-                        temp_file_guard.path(),
-                        &OCR_CONFIG,
-                        &OCR_INSTANCE_MANAGER,
-                        &CIRCUIT_BREAKER,
-                        language_code,
-                    ).await;
+                        ingredients.len(),
+                        confidence.preprocessing_strategy.clone(),
+                        Some(photo_file_id.clone()),
+                    )
+                    .with_recipe_photo(photo.clone());
+                    info!(user_id = %chat_id, correlation_id = %report_context.correlation_id, "Extraction ready for review");
+                    remember_extraction(chat_id.0, report_context);
+
+                    // Photos of an album that could not be read are reported along with the results
+                    let failed_photos_note = (failed_photos > 0).then(|| {
+                        t_plural(localization, "album-photos-failed", failed_photos, &[], language_code)
+                    });
 
                     if ingredients.is_empty() {
                         // No ingredients found, edit the success message
-                        let no_ingredients_msg = format!(
+                        let mut no_ingredients_msg = format!(
                             "📝 {}\n\n{}\n\n```\n{}\n```",
                             t_lang(localization, "no-ingredients-found", language_code),
                             t_lang(localization, "no-ingredients-suggestion", language_code),
                             extracted_text
                         );
-                        bot.edit_message_text(chat_id, success_message_id, &no_ingredients_msg).await?;
-                    } else {
-                        // Ingredients found, go directly to review interface
-                        info!(user_id = %chat_id, ingredients_count = ingredients.len(), "Sending ingredients review interface");
-                        let review_message = format!(
-                            "📝 **{}**\n\n{}\n\n{}",
-                            t_lang(localization, "review-title", language_code),
-                            t_lang(localization, "review-description", language_code),
-                            format_ingredients_list(&ingredients, language_code, localization)
-                        );
-
-                        let keyboard = create_ingredient_review_keyboard(&ingredients, language_code, localization);
-
-                        // Edit the success message with the ingredients review
-                        let sent_message = bot.edit_message_text(chat_id, success_message_id, review_message)
+                        if let Some(note) = &failed_photos_note {
+                            no_ingredients_msg.push_str("\n\n");
+                            no_ingredients_msg.push_str(note);
+                        }
+                        // A single image can be read again with other preprocessing, without a new upload
+                        let mut keyboard = create_report_problem_keyboard(language_code, localization);
+                        if kind == InputKind::Image
+                            && album.is_empty()
+                            && retain_image_for_retry(
+                                cache.as_ref(),
+                                RetainedImageKey {
+                                    chat_id: chat_id.0,
+                                    message_id: success_message_id.0,
+                                },
+                                temp_file_guard.path(),
+                                &photo_file_id,
+                                caption.as_deref(),
+                                photo.clone(),
+                            )
+                            .await
+                        {
+                            keyboard = with_retry_processing_button(keyboard, language_code, localization);
+                        }
+                        bot.edit_message_text(chat_id, success_message_id, &no_ingredients_msg)
                             .reply_markup(keyboard)
                             .await?;
+                    } else {
+                        let notes: Vec<String> = failed_photos_note.into_iter().chain(cap_notice).collect();
+                        let note = (!notes.is_empty()).then(|| notes.join("\n\n"));
 
-                        // Determine recipe name: use caption if valid, otherwise "Recipe"
-                        // PHOTO CAPTION FEATURE: Automatically uses photo captions as recipe name candidates
-                        // This enhances UX by allowing users to name recipes directly when sending photos
-                        let (recipe_name_candidate, recipe_name_from_caption) = match &caption {
-                            Some(caption_text) if !caption_text.trim().is_empty() => {
-                                // Validate the caption as a recipe name using existing validation logic
-                                // This ensures captions meet the same standards as manually entered names
-                                match crate::validation::validate_recipe_name(caption_text) {
-                                    Ok(validated_name) => {
-                                        info!(user_id = %chat_id, recipe_name = %validated_name, "Using caption as recipe name");
-                                        (validated_name.to_string(), Some(caption_text.clone())) // Caption was successfully used
-                                    }
-                                    Err(_) => {
-                                        // Caption is invalid (empty, too long, etc.), fall back to default
-                                        // This provides graceful degradation and maintains functionality
-                                        warn!(user_id = %chat_id, caption = %caption_text, "Caption is invalid, using default recipe name");
-                                        let default_name = "Recipe";
-                                        (default_name.to_string(), None) // Caption was not used
-                                    }
-                                }
-                            }
-                            _ => {
-                                // No caption or empty caption, use default
-                                // This maintains backward compatibility - existing users see no change
-                                debug!(user_id = %chat_id, "No caption provided, using default recipe name");
-                                ("Recipe".to_string(), None) // No caption available
-                            }
-                        };
-
-                        // Update dialogue state to review ingredients with caption-derived recipe name
-                        dialogue
-                            .update(RecipeDialogueState::ReviewIngredients {
-                                recipe_name: recipe_name_candidate,
+                        // Ingredients found, go directly to review interface
+                        show_ocr_review(
+                            bot,
+                            OcrReview {
+                                chat_id,
+                                message_id: success_message_id,
                                 ingredients,
-                                language_code: language_code.map(|s| s.to_string()),
-                                message_id: Some(sent_message.id.0 as i32),
-                                extracted_text: extracted_text.clone(),
-                                recipe_name_from_caption, // Only set when caption was successfully validated and used
-                            })
-                            .await?;
-
-                        info!(user_id = %chat_id, "Ingredients review interface sent successfully");
+                                extracted_text: &extracted_text,
+                                caption: caption.as_deref(),
+                                note: note.as_deref(),
+                                language_code,
+                                dialogue: &dialogue,
+                                pool: &pool,
+                            },
+                            localization,
+                        )
+                        .await?;
+                        review_shown = true;
                     }
 
                     Ok(extracted_text)
@@ -314,37 +551,13 @@ pub async fn download_and_process_image(
                     None,
                 );
 
-                // Provide more specific error messages based on the error type
-                let error_message = match &e {
-                    OcrError::Validation(msg) => {
-                        observability::record_error_metrics("validation", "ocr");
-                        t_lang(localization, "error-validation", language_code).replace("{}", msg)
-                    }
-                    OcrError::ImageLoad(_) => {
-                        observability::record_error_metrics("image_load", "ocr");
-                        t_lang(localization, "error-image-load", language_code)
-                    }
-                    OcrError::Initialization(_) => {
-                        observability::record_error_metrics("initialization", "ocr");
-                        t_lang(localization, "error-ocr-initialization", language_code)
-                    }
-                    OcrError::Extraction(_) => {
-                        observability::record_error_metrics("extraction", "ocr");
-                        t_lang(localization, "error-ocr-extraction", language_code)
-                    }
-                    OcrError::Timeout(msg) => {
-                        observability::record_error_metrics("timeout", "ocr");
-                        t_lang(localization, "error-ocr-timeout", language_code).replace("{}", msg)
-                    }
-                    OcrError::_InstanceCorruption(_) => {
-                        observability::record_error_metrics("instance_corruption", "ocr");
-                        t_lang(localization, "error-ocr-corruption", language_code)
-                    }
-                    OcrError::_ResourceExhaustion(_) => {
-                        observability::record_error_metrics("resource_exhaustion", "ocr");
-                        t_lang(localization, "error-ocr-exhaustion", language_code)
-                    }
-                };
+                // Tell the user what went wrong and what to try next
+                observability::record_error_metrics(e.error_type(), "ocr");
+                let error_message = crate::errors::user_reply(
+                    localization,
+                    crate::errors::UserErrorKind::from(&e),
+                    language_code,
+                );
 
                 bot.edit_message_text(chat_id, success_message_id, &error_message).await?;
                 Err(anyhow::anyhow!("OCR processing failed: {:?}", e))
@@ -353,241 +566,431 @@ pub async fn download_and_process_image(
     }
     .await;
 
+    // A photo that could not be read, or gave no ingredients, may be sent again to retry
+    if !review_shown {
+        if let Some((cache, image_hash)) = &recorded_photo {
+            cache.lock().ocr_result_cache.forget(chat_id.0, image_hash);
+        }
+    }
+
     result
 }
 
-/// Attempts automated recovery of anomalous quantity measurements using targeted re-OCR
-///
-/// This function implements the complete automated recovery pipeline:
-/// 1. Extract HOCR from the original image to get spatial data
-/// 2. Map the anomalous measurement to its bounding box
-/// 3. Crop the image around the measurement region
-/// 4. Apply targeted preprocessing for quantity recognition
-/// 5. Run constrained OCR optimized for numbers and fractions
-/// 6. Update the measurement match if recovery succeeds
-async fn attempt_automated_quantity_recovery(
-    image_path: &str,
-    measurement_match: &mut MeasurementMatch,
-    ocr_config: &OcrConfig,
-    instance_manager: &OcrInstanceManager,
-    circuit_breaker: &CircuitBreaker,
-) -> Result<bool, OcrError> {
-    info!(
-        line_number = measurement_match.line_number,
-        ingredient = %measurement_match.ingredient_name,
-        "Attempting automated quantity recovery for anomalous measurement"
-    );
-
-    // Step 1: Extract HOCR from the original image
-    let hocr_text =
-        extract_hocr_from_image(image_path, ocr_config, instance_manager, circuit_breaker).await?;
-
-    // Step 2: Parse HOCR to get line bounding boxes
-    let hocr_lines = parse_hocr_to_lines(&hocr_text)?;
-
-    // Step 3: Map the measurement to its bounding box
-    let bbox = map_measurement_to_bbox(measurement_match, &hocr_lines).ok_or_else(|| {
-        OcrError::Extraction(format!(
-            "Could not map measurement to bounding box for line {}",
-            measurement_match.line_number
-        ))
-    })?;
-
-    // Step 4: Load the original image for cropping
-    let _original_image = image::open(image_path)
-        .map_err(|e| OcrError::Validation(format!("Failed to load image for cropping: {}", e)))?;
-
-    // Step 5: Crop the measurement region
-    let CroppedImageResult {
-        image: cropped_image,
-        ..
-    } = crop_measurement_region(image_path, &bbox)
-        .map_err(|e| OcrError::Extraction(format!("Failed to crop measurement region: {}", e)))?;
-
-    // Step 6: Apply targeted preprocessing
-    let preprocessed_image = preprocess_measurement_region(&cropped_image).map_err(|e| {
-        OcrError::Extraction(format!("Failed to preprocess measurement region: {}", e))
-    })?;
-
-    // Step 7: Run constrained OCR
-    let constrained_result =
-        perform_constrained_ocr(&preprocessed_image.image, instance_manager, ocr_config).await?;
-
-    // Step 8: Validate and update the measurement if recovery succeeded
-    if is_valid_recovered_quantity(&constrained_result.text) {
-        info!(
-            original_quantity = %measurement_match.quantity,
-            recovered_quantity = %constrained_result.text,
-            confidence = constrained_result.confidence,
-            "Automated quantity recovery succeeded"
-        );
-
-        // Update the measurement match with recovered quantity
-        measurement_match.quantity = constrained_result.text.clone();
-        measurement_match.requires_quantity_confirmation = false;
-
-        Ok(true) // Recovery succeeded
-    } else {
-        debug!(
-            recovered_text = %constrained_result.text,
-            confidence = constrained_result.confidence,
-            "Automated quantity recovery produced invalid result, keeping original anomaly"
-        );
-        Ok(false) // Recovery failed, keep original anomaly
-    }
+/// Review of ingredients read from an image, shown in place of the processing message
+struct OcrReview<'a> {
+    chat_id: ChatId,
+    /// Processing message replaced by the review
+    message_id: teloxide::types::MessageId,
+    ingredients: Vec<MeasurementMatch>,
+    extracted_text: &'a str,
+    /// Photo caption, used as the recipe name when valid
+    caption: Option<&'a str>,
+    /// Appended to the review, e.g. album photos that could not be read
+    note: Option<&'a str>,
+    language_code: Option<&'a str>,
+    dialogue: &'a RecipeDialogue,
+    pool: &'a PgPool,
 }
 
-/// Validates if a recovered quantity string is acceptable for automated use
-pub fn is_valid_recovered_quantity(text: &str) -> bool {
-    let text = text.trim();
+/// Show the review of ingredients read from an image and enter the review state
+async fn show_ocr_review(
+    bot: &Bot,
+    review: OcrReview<'_>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    let OcrReview {
+        chat_id,
+        message_id,
+        ingredients,
+        extracted_text,
+        caption,
+        note,
+        language_code,
+        dialogue,
+        pool,
+    } = review;
+    info!(user_id = %chat_id, ingredients_count = ingredients.len(), "Sending ingredients review interface");
+    // Determine recipe name: use caption if valid, otherwise "Recipe"
+    // PHOTO CAPTION FEATURE: Automatically uses photo captions as recipe name candidates
+    // This enhances UX by allowing users to name recipes directly when sending photos
+    let (recipe_name_candidate, recipe_name_from_caption) = match caption {
+        Some(caption_text) if !caption_text.trim().is_empty() => {
+            // Validate the caption as a recipe name using existing validation logic
+            // This ensures captions meet the same standards as manually entered names
+            match crate::validation::validate_recipe_name(caption_text) {
+                Ok(validated_name) => {
+                    info!(user_id = %chat_id, recipe_name = %validated_name, "Using caption as recipe name");
+                    // Caption was successfully used
+                    (validated_name.to_string(), Some(caption_text.to_string()))
+                }
+                Err(_) => {
+                    // Caption is invalid (empty, too long, etc.), fall back to default
+                    // This provides graceful degradation and maintains functionality
+                    warn!(user_id = %chat_id, caption = %caption_text, "Caption is invalid, using default recipe name");
+                    let default_name = "Recipe";
+                    (default_name.to_string(), None) // Caption was not used
+                }
+            }
+        }
+        _ => {
+            // No caption or empty caption, use default
+            // This maintains backward compatibility - existing users see no change
+            debug!(user_id = %chat_id, "No caption provided, using default recipe name");
+            ("Recipe".to_string(), None) // No caption available
+        }
+    };
 
-    // Must not be empty
-    if text.is_empty() {
-        return false;
+    let mut review_message = format_review_message(
+        &ingredients,
+        recipe_name_from_caption.as_deref(),
+        crate::bot::unit_settings::display_units(chat_id),
+        language_code,
+        localization,
+    );
+    if ingredients.iter().any(is_low_confidence) {
+        review_message.push('\n');
+        review_message.push_str(&escape_html(&t_lang(
+            localization,
+            "review-low-confidence-note",
+            language_code,
+        )));
     }
-
-    // Must contain at least one digit
-    if !text.chars().any(|c| c.is_ascii_digit()) {
-        return false;
+    if let Some(note) = note {
+        review_message.push_str("\n\n");
+        review_message.push_str(&escape_html(note));
     }
 
-    // Must not contain letters (except in valid contexts like "1st", "2nd")
-    let has_letters = text.chars().any(|c| c.is_ascii_alphabetic());
-    if has_letters {
-        // Allow ordinal numbers like "1st", "2nd", "3rd" but not other letters
-        let valid_ordinals = ["1st", "2nd", "3rd"];
-        if !valid_ordinals.contains(&text) {
-            return false;
-        }
-    }
+    // Persist the review keyboard variant so the user keeps one layout
+    let telegram_id = TelegramId(chat_id.0);
+    let variant = resolve_review_keyboard_variant(pool, telegram_id).await;
+    let keyboard = create_ingredient_review_keyboard_for_variant(
+        &ingredients,
+        language_code,
+        localization,
+        variant,
+    );
 
-    // Must be a reasonable length (1-10 characters for quantities)
-    if text.len() > 10 {
-        return false;
-    }
+    // Edit the success message with the ingredients review
+    let sent_message = bot
+        .edit_message_text(chat_id, message_id, review_message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    track_review_funnel_event(pool, telegram_id, FunnelEvent::ReviewShown).await;
+    crate::extraction_feedback::start_review(telegram_id, ingredients.len());
+
+    // Only the measurement-bearing region of the OCR text is kept to bound the state size,
+    // the full text is kept aside for saving
+    let state_text = crate::dialogue::review_state_text(chat_id, extracted_text, &ingredients);
+
+    // Update dialogue state to review ingredients with caption-derived recipe name
+    dialogue
+        .update(RecipeDialogueState::ReviewIngredients {
+            recipe_name: recipe_name_candidate,
+            ingredients,
+            language_code: language_code.map(|s| s.to_string()),
+            message_id: Some(sent_message.id.0 as i32),
+            extracted_text: state_text,
+            recipe_name_from_caption, // Only set when caption was successfully validated and used
+            last_deleted: None,
+        })
+        .await?;
+
+    info!(user_id = %chat_id, "Ingredients review interface sent successfully");
+    Ok(())
+}
 
-    // Must be parseable as a valid number or fraction, or be a valid ordinal
-    let is_valid_ordinal = ["1st", "2nd", "3rd"].contains(&text);
-    if text.parse::<f64>().is_err() && !is_valid_fraction(text) && !is_valid_ordinal {
+/// Keep an image without ingredients so it can be retried with other preprocessing
+///
+/// Returns whether the retry can be offered: a cache is available, retry
+/// profiles are configured and the image could be read back.
+async fn retain_image_for_retry(
+    cache: Option<&SharedCacheManager>,
+    key: RetainedImageKey,
+    path: &str,
+    file_id: &str,
+    caption: Option<&str>,
+    photo: Option<crate::db::RecipePhoto>,
+) -> bool {
+    let Some(cache) = cache.filter(|_| !RECIPE_EXTRACTOR.ocr_config().retry_profiles.is_empty())
+    else {
         return false;
-    }
-
+    };
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error_logging::log_filesystem_error(&e, "read_image_for_retry", Some(path), None);
+            return false;
+        }
+    };
+    cache.lock().retained_images.retain(
+        key.chat_id,
+        key.message_id,
+        RetainedImage {
+            bytes,
+            file_id: file_id.to_string(),
+            caption: caption.map(str::to_string),
+            photo,
+        },
+    );
     true
 }
 
-/// Checks if a string represents a valid fraction (like "1/2", "3/4", etc.)
-pub fn is_valid_fraction(text: &str) -> bool {
-    if let Some(slash_pos) = text.find('/') {
-        let numerator = &text[..slash_pos];
-        let denominator = &text[slash_pos + 1..];
-
-        // Both parts must be numeric and denominator must not be zero
-        numerator.parse::<u32>().is_ok() && denominator.parse::<u32>().is_ok() && denominator != "0"
-    } else {
-        false
-    }
+/// Index of the retry run with the most measurement matches
+///
+/// Runs are `(profile name, match count)`; the first profile wins a tie, and
+/// `None` means no run found any measurement.
+pub fn pick_retry_winner(runs: &[(&str, usize)]) -> Option<usize> {
+    runs.iter()
+        .enumerate()
+        .filter(|(_, (_, matches))| *matches > 0)
+        .fold(
+            None,
+            |best: Option<(usize, usize)>, (index, (_, matches))| match best {
+                Some((_, best_matches)) if best_matches >= *matches => best,
+                _ => Some((index, *matches)),
+            },
+        )
+        .map(|(index, _)| index)
 }
 
-/// Process extracted text and return measurement matches with automated quantity recovery
-pub async fn process_ingredients_with_recovery(
-    extracted_text: &str,
-    image_path: &str,
-    ocr_config: &OcrConfig,
-    instance_manager: &OcrInstanceManager,
-    circuit_breaker: &CircuitBreaker,
-    _language_code: Option<&str>,
-) -> Vec<MeasurementMatch> {
-    debug!(
-        text_length = extracted_text.len(),
-        "Processing extracted text for ingredients with automated recovery"
-    );
+/// Handle the "Try again with different processing" button of an image without ingredients
+///
+/// The retained image is read once per retry profile and the run with the most
+/// measurement matches goes to review, as if it had been the first read.
+pub async fn handle_retry_processing_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    dialogue: &RecipeDialogue,
+    pool: &PgPool,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let Some(teloxide::types::MaybeInaccessibleMessage::Regular(prompt)) = q.message.as_ref()
+    else {
+        return Ok(());
+    };
+    let (chat_id, message_id) = (prompt.chat.id, prompt.id);
+    let language_code = q.from.language_code.as_deref();
+
+    let retained =
+        cache.and_then(|cache| cache.lock().retained_images.take(chat_id.0, message_id.0));
+    let Some(retained) = retained else {
+        debug!(user_id = %chat_id, "Retained image expired before the retry");
+        bot.edit_message_reply_markup(chat_id, message_id)
+            .reply_markup(create_report_problem_keyboard(language_code, localization))
+            .await?;
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "ocr-retry-expired", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
 
-    // Create measurement detector with default configuration
-    let detector = match MeasurementDetector::new() {
-        Ok(detector) => detector,
-        Err(e) => {
-            error_logging::log_internal_error(
-                &e,
-                "MeasurementDetector",
-                "create_measurement_detector",
-                None,
-            );
-            return Vec::new();
+    // A shutdown waits for the retry to be read and answered
+    let _in_flight = crate::shutdown::global().track();
+    bot.edit_message_text(
+        chat_id,
+        message_id,
+        t_lang(localization, "ocr-retry-processing", language_code),
+    )
+    .await?;
+
+    let mut image_file = NamedTempFile::new()?;
+    image_file.as_file_mut().write_all(&retained.bytes)?;
+    let image_path = image_file.path().to_string_lossy().to_string();
+
+    let mut runs = Vec::with_capacity(RECIPE_EXTRACTOR.ocr_config().retry_profiles.len());
+    for retry_profile in &RECIPE_EXTRACTOR.ocr_config().retry_profiles {
+        match crate::ocr::extract_text_from_image_with_profile(
+            &image_path,
+            RECIPE_EXTRACTOR.ocr_config(),
+            RECIPE_EXTRACTOR.instance_manager(),
+            RECIPE_EXTRACTOR.circuit_breaker(),
+            Some(retry_profile),
+        )
+        .await
+        {
+            Ok((text, confidence)) => {
+                let ingredients = process_ingredients_and_extract_matches(&text, language_code);
+                debug!(
+                    user_id = %chat_id,
+                    profile = retry_profile.name,
+                    matches = ingredients.len(),
+                    "Retry profile read the image"
+                );
+                runs.push((retry_profile.name, text, confidence, ingredients));
+            }
+            Err(OcrError::CircuitOpen(_)) => break,
+            Err(e) => {
+                warn!(user_id = %chat_id, profile = retry_profile.name, error = %e, "Retry profile failed");
+            }
         }
-    };
+    }
 
-    // Find all measurements in the text
-    let mut matches = detector.extract_ingredient_measurements(extracted_text);
+    let counts: Vec<(&str, usize)> = runs
+        .iter()
+        .map(|(name, _, _, ingredients)| (*name, ingredients.len()))
+        .collect();
+    let winner = pick_retry_winner(&counts);
+    observability::record_ocr_retry_profile_metrics(winner.map_or("none", |index| counts[index].0));
+
+    let Some((profile_name, extracted_text, confidence, ingredients)) =
+        winner.map(|index| runs.swap_remove(index))
+    else {
+        info!(user_id = %chat_id, profiles = runs.len(), "No retry profile found ingredients");
+        bot.edit_message_text(
+            chat_id,
+            message_id,
+            format!(
+                "📝 {}\n\n{}",
+                t_lang(localization, "no-ingredients-found", language_code),
+                t_lang(localization, "ocr-retry-no-ingredients", language_code)
+            ),
+        )
+        .reply_markup(create_report_problem_keyboard(language_code, localization))
+        .await?;
+        return Ok(());
+    };
     info!(
-        matches_found = matches.len(),
-        "Initial measurement detection completed"
+        user_id = %chat_id,
+        profile = profile_name,
+        ingredients_count = ingredients.len(),
+        "Retry profile found ingredients"
     );
 
-    // Count anomalies before recovery
-    let anomalies_before = matches
-        .iter()
-        .filter(|m| m.requires_quantity_confirmation)
-        .count();
-
-    if anomalies_before > 0 {
-        info!(
-            anomalies = anomalies_before,
-            "Detected anomalous measurements, attempting automated recovery"
-        );
-
-        // Attempt automated recovery for each anomalous measurement
-        for measurement_match in &mut matches {
-            if measurement_match.requires_quantity_confirmation {
-                match attempt_automated_quantity_recovery(
-                    image_path,
-                    measurement_match,
-                    ocr_config,
-                    instance_manager,
-                    circuit_breaker,
-                )
-                .await
-                {
-                    Ok(recovered) => {
-                        if recovered {
-                            info!(
-                                ingredient = %measurement_match.ingredient_name,
-                                "Successfully recovered quantity automatically"
-                            );
-                        } else {
-                            debug!(
-                                ingredient = %measurement_match.ingredient_name,
-                                "Automated recovery failed, keeping anomaly for manual confirmation"
-                            );
-                        }
-                    }
-                    Err(e) => {
-                        warn!(
-                            ingredient = %measurement_match.ingredient_name,
-                            error = %e,
-                            "Automated recovery encountered error, keeping anomaly for manual confirmation"
-                        );
-                    }
-                }
-            }
+    let cap_notice = format_review_cap_notice(ingredients.len(), language_code, localization);
+    let mut ingredients = crate::dialogue::cap_review_ingredients(ingredients);
+    crate::ocr::apply_line_confidences(&mut ingredients, &confidence.line_confidences);
+    remember_extraction(
+        chat_id.0,
+        ExtractionContext::new(
+            &extracted_text,
+            ingredients.len(),
+            confidence.preprocessing_strategy.clone(),
+            Some(retained.file_id.clone()),
+        )
+        .with_recipe_photo(retained.photo.clone()),
+    );
+
+    show_ocr_review(
+        bot,
+        OcrReview {
+            chat_id,
+            message_id,
+            ingredients,
+            extracted_text: &extracted_text,
+            caption: retained.caption.as_deref(),
+            note: cap_notice.as_deref(),
+            language_code,
+            dialogue,
+            pool,
+        },
+        localization,
+    )
+    .await
+}
+
+/// Read the text of the photos of an album, the first one already downloaded
+///
+/// Photos that cannot be downloaded or read are skipped; their number is returned
+/// with the combined text. Fails only when no photo could be read.
+async fn extract_text_from_album(
+    bot: &Bot,
+    first_photo_path: &str,
+    album: &[teloxide::types::FileId],
+) -> Result<(String, crate::ocr::OcrConfidence, usize), OcrError> {
+    let mut results = Vec::with_capacity(album.len() + 1);
+    let mut failed = 0;
+    let mut last_error = None;
+
+    match RECIPE_EXTRACTOR.read_text(first_photo_path).await {
+        Ok(result) => results.push(result),
+        Err(e) => {
+            warn!(photo = 1, error = %e, "Album photo could not be read");
+            failed += 1;
+            last_error = Some(e);
         }
+    }
 
-        // Count anomalies after recovery
-        let anomalies_after = matches
-            .iter()
-            .filter(|m| m.requires_quantity_confirmation)
-            .count();
-        let recovered_count = anomalies_before - anomalies_after;
-
-        info!(
-            recovered = recovered_count,
-            remaining_anomalies = anomalies_after,
-            "Automated quantity recovery completed"
-        );
-    } else {
-        debug!("No anomalous measurements detected, skipping automated recovery");
+    for (index, file_id) in album.iter().enumerate() {
+        let photo = match download_file(bot, file_id.clone()).await {
+            Ok(guard) => guard,
+            Err(e) => {
+                error_logging::log_network_error(&e, "download_album_photo", None, None);
+                failed += 1;
+                continue;
+            }
+        };
+        if let Err(e) = crate::resource_limits::prepare_image_for_ocr(
+            photo.path(),
+            RECIPE_EXTRACTOR.ocr_config(),
+        )
+        .await
+        {
+            warn!(photo = index + 2, error = %e, "Album photo rejected before OCR");
+            failed += 1;
+            last_error = Some(e);
+            continue;
+        }
+        match RECIPE_EXTRACTOR.read_text(photo.path()).await {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                warn!(photo = index + 2, error = %e, "Album photo could not be read");
+                failed += 1;
+                last_error = Some(e);
+            }
+        }
     }
+    info!(photos = album.len() + 1, failed, "Album photos extracted");
 
-    matches
+    let Some(fallback_confidence) = results.first().map(|(_, confidence)| confidence.clone())
+    else {
+        return Err(last_error.unwrap_or_else(|| {
+            OcrError::ImageLoad("No photo of the album could be downloaded".to_string())
+        }));
+    };
+    Ok(match crate::ocr::merge_page_results(results) {
+        Some((text, confidence)) => (text, confidence, failed),
+        None => (String::new(), fallback_confidence, failed),
+    })
+}
+
+/// Read the text of the first pages of a downloaded PDF
+///
+/// Returns the rendered pages too, which are deleted once dropped.
+async fn extract_text_from_pdf(
+    pdf_path: &str,
+) -> Result<(String, crate::ocr::OcrConfidence, Vec<tempfile::TempPath>), OcrError> {
+    let file_size = tokio::fs::metadata(pdf_path)
+        .await
+        .map_err(|e| OcrError::Validation(format!("Cannot read PDF: {}", e)))?
+        .len();
+    crate::pdf::check_pdf_file_size(file_size, RECIPE_EXTRACTOR.ocr_config())?;
+
+    let path = pdf_path.to_string();
+    let pages = tokio::task::spawn_blocking(move || {
+        crate::pdf::render_pdf_pages(&path, RECIPE_EXTRACTOR.ocr_config())
+    })
+    .await
+    .map_err(|e| OcrError::Extraction(format!("PDF rendering task failed: {}", e)))??;
+
+    let mut results = Vec::with_capacity(pages.len());
+    for page in &pages {
+        results.push(RECIPE_EXTRACTOR.read_text(&page.to_string_lossy()).await?);
+    }
+    info!(pages = pages.len(), "PDF pages extracted");
+
+    let fallback_confidence = results.first().map(|(_, confidence)| confidence.clone());
+    match crate::ocr::merge_page_results(results) {
+        Some((text, confidence)) => Ok((text, confidence, pages)),
+        None => {
+            let confidence = fallback_confidence
+                .ok_or_else(|| OcrError::Validation("PDF has no pages".to_string()))?;
+            Ok((String::new(), confidence, pages))
+        }
+    }
 }
 
 /// Process extracted text and return measurement matches
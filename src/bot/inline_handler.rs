@@ -0,0 +1,232 @@
+//! Inline mode: sharing a saved recipe into any chat (`@bot carbonara`)
+//!
+//! Typing the bot's username followed by a name in any chat searches the querying
+//! user's recipes whose name starts with that text. Each match is offered as an
+//! article whose message is the recipe's ingredient list, formatted in the user's
+//! language and display units. Results are paged with Telegram's `next_offset`, and
+//! answers are cached briefly and per user, since they come from private recipes.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{
+    InlineQuery, InlineQueryResult, InlineQueryResultArticle, InputMessageContent,
+    InputMessageContentText,
+};
+use tracing::debug;
+
+use crate::db::{Ingredient, Recipe, TelegramId};
+use crate::errors::error_logging;
+use crate::localization::{t_args_lang, t_lang, t_plural, LocalizationManager};
+use crate::text_processing::UnitSystem;
+
+use super::formatting::{HtmlMessage, PARSE_MODE};
+use super::ui_builder::format_database_ingredients_list;
+use super::unit_settings::display_units;
+
+/// Results per answer; Telegram accepts at most 50
+pub const INLINE_RESULTS_PER_PAGE: i64 = 20;
+
+/// Seconds Telegram may reuse an answer, kept low so new recipes show up quickly
+pub const INLINE_CACHE_TIME_SECS: u32 = 5;
+
+/// Longest text of a message sent through inline mode
+const MAX_INLINE_MESSAGE_LENGTH: usize = 4096;
+
+/// Appended to a recipe cut short to fit in a message
+const TRUNCATION_MARKER: &str = "\n...";
+
+/// Result id of the "no recipes found" placeholder
+const NO_RECIPES_RESULT_ID: &str = "no_recipes";
+
+/// Position in the results encoded in an inline query offset, 0 when unset or invalid
+pub fn parse_inline_offset(offset: &str) -> i64 {
+    offset.parse::<i64>().ok().filter(|n| *n >= 0).unwrap_or(0)
+}
+
+/// Offset of the next page, empty when `returned` shows this page was the last
+pub fn next_inline_offset(offset: i64, returned: usize) -> String {
+    if (returned as i64) < INLINE_RESULTS_PER_PAGE {
+        String::new()
+    } else {
+        (offset + INLINE_RESULTS_PER_PAGE).to_string()
+    }
+}
+
+/// Message inserted in the chat for a shared recipe: its name and ingredient list, as HTML
+///
+/// Ingredients that don't fit are cut at a line boundary so no tag or entity is
+/// split.
+pub fn format_inline_recipe(
+    recipe_name: &str,
+    ingredients: &[Ingredient],
+    units: Option<UnitSystem>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let mut message = HtmlMessage::titled("📖", recipe_name).build();
+    let list = format_database_ingredients_list(ingredients, units, language_code, localization);
+    for (i, line) in list.lines().enumerate() {
+        let separator = if i == 0 { "\n\n" } else { "\n" };
+        if message.chars().count() + separator.len() + line.chars().count()
+            > MAX_INLINE_MESSAGE_LENGTH - TRUNCATION_MARKER.len()
+        {
+            message.push_str(TRUNCATION_MARKER);
+            break;
+        }
+        message.push_str(separator);
+        message.push_str(line);
+    }
+    message
+}
+
+/// Inline result sharing one recipe
+fn recipe_result(
+    recipe: &Recipe,
+    ingredients: &[Ingredient],
+    units: Option<UnitSystem>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> InlineQueryResult {
+    let name = recipe
+        .recipe_name
+        .clone()
+        .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+    let text = format_inline_recipe(&name, ingredients, units, language_code, localization);
+    InlineQueryResult::Article(
+        InlineQueryResultArticle::new(
+            recipe.id.to_string(),
+            name,
+            InputMessageContent::Text(InputMessageContentText::new(text).parse_mode(PARSE_MODE)),
+        )
+        .description(t_plural(
+            localization,
+            "inline-recipe-ingredients",
+            ingredients.len(),
+            &[],
+            language_code,
+        )),
+    )
+}
+
+/// Placeholder shown when none of the user's recipes match
+fn no_recipes_result(
+    query: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineQueryResult {
+    let description = if query.trim().is_empty() {
+        t_lang(localization, "inline-no-recipes-saved", language_code)
+    } else {
+        t_args_lang(
+            localization,
+            "inline-no-recipes-match",
+            &[("query", query.trim())],
+            language_code,
+        )
+    };
+    InlineQueryResult::Article(
+        InlineQueryResultArticle::new(
+            NO_RECIPES_RESULT_ID,
+            t_lang(localization, "inline-no-recipes-title", language_code),
+            InputMessageContent::Text(InputMessageContentText::new(t_lang(
+                localization,
+                "inline-no-recipes-message",
+                language_code,
+            ))),
+        )
+        .description(description),
+    )
+}
+
+/// Answer an inline query with the user's recipes whose name starts with the query
+pub async fn handle_inline_query(
+    bot: &Bot,
+    query: &InlineQuery,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let telegram_id = TelegramId(query.from.id.0 as i64);
+    let language_code =
+        crate::bot::language_settings::resolve_user_language(pool, &query.from).await;
+    let language_code = language_code.as_deref();
+    let units = display_units(ChatId::from(query.from.id));
+    let offset = parse_inline_offset(&query.offset);
+
+    let recipes = crate::db::search_recipes_by_name_prefix(
+        pool,
+        telegram_id,
+        &query.query,
+        INLINE_RESULTS_PER_PAGE,
+        offset,
+    )
+    .await?;
+    debug!(user_id = %telegram_id, offset, results = recipes.len(), "Answering inline query");
+
+    let next_offset = next_inline_offset(offset, recipes.len());
+    let results: Vec<InlineQueryResult> = if recipes.is_empty() && offset == 0 {
+        vec![no_recipes_result(&query.query, localization, language_code)]
+    } else {
+        recipes
+            .iter()
+            .map(|(recipe, ingredients)| {
+                recipe_result(recipe, ingredients, units, language_code, localization)
+            })
+            .collect()
+    };
+
+    bot.answer_inline_query(query.id.clone(), results)
+        .cache_time(INLINE_CACHE_TIME_SECS)
+        .is_personal(true)
+        .next_offset(next_offset)
+        .await?;
+    Ok(())
+}
+
+/// Inline query endpoint of the dispatcher
+///
+/// Failures are logged; the user simply sees no results.
+pub async fn inline_query_handler(
+    bot: Bot,
+    query: InlineQuery,
+    pool: Arc<PgPool>,
+    localization: Arc<LocalizationManager>,
+) -> Result<()> {
+    if let Err(e) = handle_inline_query(&bot, &query, &pool, &localization).await {
+        error_logging::log_internal_error(
+            &e,
+            "inline_handler",
+            "handle_inline_query",
+            Some(query.from.id.0 as i64),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_inline_offset() {
+        assert_eq!(parse_inline_offset(""), 0);
+        assert_eq!(parse_inline_offset("40"), 40);
+        assert_eq!(parse_inline_offset("-20"), 0);
+        assert_eq!(parse_inline_offset("next"), 0);
+    }
+
+    #[test]
+    fn test_next_inline_offset() {
+        assert_eq!(
+            next_inline_offset(0, INLINE_RESULTS_PER_PAGE as usize),
+            INLINE_RESULTS_PER_PAGE.to_string()
+        );
+        assert_eq!(
+            next_inline_offset(20, INLINE_RESULTS_PER_PAGE as usize),
+            (20 + INLINE_RESULTS_PER_PAGE).to_string()
+        );
+        assert_eq!(next_inline_offset(0, 3), "");
+        assert_eq!(next_inline_offset(40, 0), "");
+    }
+}
@@ -0,0 +1,353 @@
+//! Language settings module for the `/language` command
+//!
+//! `/language` shows the supported languages as buttons, and `/language fr` picks
+//! one directly. A chosen language is stored in the users table and wins over the
+//! Telegram client language, so an English phone can still get French replies.
+//!
+//! `/language auto` makes the bot answer free-text messages in the language they
+//! were typed in, for households sharing one account across languages.
+//! `/language off` goes back to the fixed language. Button taps always use the
+//! fixed language since there is no text to detect.
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, User};
+use tracing::debug;
+
+use crate::db::TelegramId;
+use crate::errors::error_logging;
+use crate::localization::{detect_language, t_lang, LocalizationManager};
+
+use super::quickbar::enabled_quickbar_markup;
+
+lazy_static! {
+    /// Language chosen with /language by every user seen since startup
+    static ref LANGUAGE_PREFERENCES: Mutex<HashMap<i64, Option<String>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// What the /language command asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageCommand {
+    /// Show the supported languages as buttons
+    Menu,
+    /// Turn per-message language detection on or off
+    Auto(bool),
+    /// Always answer in this language
+    Set(String),
+}
+
+/// Parse the argument of the /language command
+///
+/// Returns `None` for arguments that are neither `auto`, `off` nor one of the
+/// `supported` language codes.
+pub fn parse_language_command(text: &str, supported: &[&str]) -> Option<LanguageCommand> {
+    let argument = text.strip_prefix("/language")?.trim().to_lowercase();
+    match argument.as_str() {
+        "" => Some(LanguageCommand::Menu),
+        "auto" => Some(LanguageCommand::Auto(true)),
+        "off" => Some(LanguageCommand::Auto(false)),
+        code if supported.contains(&code) => Some(LanguageCommand::Set(argument)),
+        _ => None,
+    }
+}
+
+/// Parse the data of a /language keyboard button
+pub fn parse_language_callback<'a>(data: &'a str, supported: &[&str]) -> Option<&'a str> {
+    data.strip_prefix("language:")
+        .filter(|code| supported.contains(code))
+}
+
+fn remember_language_preference(telegram_id: TelegramId, language: Option<String>) {
+    if let Ok(mut preferences) = LANGUAGE_PREFERENCES.lock() {
+        preferences.insert(telegram_id.0, language);
+    }
+}
+
+/// Forget a user's in-memory language choice, so it is read again from the database
+pub fn forget_language_preference(telegram_id: TelegramId) {
+    if let Ok(mut preferences) = LANGUAGE_PREFERENCES.lock() {
+        preferences.remove(&telegram_id.0);
+    }
+}
+
+/// Language a user chose with /language, read from the database the first time
+///
+/// Lookup failures count as no choice and are retried on the next update.
+async fn language_preference(pool: &PgPool, telegram_id: TelegramId) -> Option<String> {
+    let cached = LANGUAGE_PREFERENCES
+        .lock()
+        .ok()
+        .and_then(|preferences| preferences.get(&telegram_id.0).cloned());
+    if let Some(language) = cached {
+        return language;
+    }
+    match crate::db::get_user_language_preference(pool, telegram_id).await {
+        Ok(language) => {
+            remember_language_preference(telegram_id, language.clone());
+            language
+        }
+        Err(e) => {
+            error_logging::log_database_error(
+                &e,
+                "get_user_language_preference",
+                Some(telegram_id.0),
+                None,
+            );
+            None
+        }
+    }
+}
+
+/// Language code replies to a user should use
+///
+/// The language chosen with /language wins over the Telegram client language;
+/// `None` leaves the choice to `detect_language`, which falls back to English.
+pub async fn resolve_user_language(pool: &PgPool, user: &User) -> Option<String> {
+    let telegram_id = TelegramId(user.id.0 as i64);
+    language_preference(pool, telegram_id)
+        .await
+        .or_else(|| user.language_code.clone())
+}
+
+/// Whether free text from this chat should be answered in its detected language
+///
+/// Commands are never detected; lookup failures fall back to the fixed language.
+pub async fn auto_language_enabled(pool: &PgPool, chat_id: ChatId, text: &str) -> bool {
+    if text.starts_with('/') {
+        return false;
+    }
+    match crate::db::get_user_auto_language(pool, TelegramId(chat_id.0)).await {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            error_logging::log_database_error(&e, "get_user_auto_language", Some(chat_id.0), None);
+            false
+        }
+    }
+}
+
+fn language_keyboard(
+    current: &str,
+    localization: &Arc<LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(localization.supported_languages().iter().map(|code| {
+        let marker = if *code == current { "✅ " } else { "" };
+        // Each language is labelled in itself
+        vec![InlineKeyboardButton::callback(
+            format!(
+                "{marker}{}",
+                t_lang(localization, "language-name", Some(code))
+            ),
+            format!("language:{code}"),
+        )]
+    }))
+}
+
+/// Store a user's language choice and remember it for the following updates
+async fn save_language_preference(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    language: &str,
+) -> Result<()> {
+    crate::db::set_user_language_preference(pool, telegram_id, language).await?;
+    remember_language_preference(telegram_id, Some(language.to_string()));
+    Ok(())
+}
+
+/// Handle the /language, /language <code> and /language auto|off commands
+pub async fn handle_language_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let Some(command) = parse_language_command(text, localization.supported_languages()) else {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "language-usage", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+    debug!(user_id = %msg.chat.id, command = ?command, "Handling /language command");
+    let telegram_id = TelegramId(msg.chat.id.0);
+
+    let (saved, operation, confirmation_key, reply_language) = match &command {
+        LanguageCommand::Menu => {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, "language-prompt", language_code),
+            )
+            .reply_markup(language_keyboard(
+                &detect_language(localization, language_code),
+                localization,
+            ))
+            .await?;
+            return Ok(());
+        }
+        LanguageCommand::Auto(enabled) => (
+            crate::db::set_user_auto_language(&pool, telegram_id, *enabled).await,
+            "set_user_auto_language",
+            if *enabled {
+                "language-auto-enabled"
+            } else {
+                "language-auto-disabled"
+            },
+            language_code,
+        ),
+        LanguageCommand::Set(language) => (
+            save_language_preference(&pool, telegram_id, language).await,
+            "set_user_language_preference",
+            "language-set",
+            // The confirmation is already in the new language
+            Some(language.as_str()),
+        ),
+    };
+
+    if let Err(e) = saved {
+        error_logging::log_database_error(&e, operation, Some(msg.chat.id.0), None);
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "error-processing-failed", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // An enabled quickbar is sent again with its buttons in the new language
+    let quickbar = if matches!(command, LanguageCommand::Set(_)) {
+        enabled_quickbar_markup(&pool, msg.chat.id, localization, reply_language).await
+    } else {
+        None
+    };
+    let request = bot.send_message(
+        msg.chat.id,
+        t_lang(localization, confirmation_key, reply_language),
+    );
+    match quickbar {
+        Some(quickbar) => request.reply_markup(quickbar).await?,
+        None => request.await?,
+    };
+
+    Ok(())
+}
+
+/// Handle a tap on one of the /language buttons
+pub async fn handle_language_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some(language), Some(message)) = (
+        parse_language_callback(data, localization.supported_languages()),
+        q.message.as_ref(),
+    ) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    if let Err(e) = save_language_preference(pool, TelegramId(q.from.id.0 as i64), language).await {
+        error_logging::log_database_error(
+            &e,
+            "set_user_language_preference",
+            Some(chat_id.0),
+            None,
+        );
+        bot.send_message(
+            chat_id,
+            t_lang(
+                localization,
+                "error-processing-failed",
+                q.from.language_code.as_deref(),
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    // The confirmation is already in the new language
+    bot.edit_message_text(
+        chat_id,
+        message.id(),
+        t_lang(localization, "language-set", Some(language)),
+    )
+    .await?;
+
+    // An edited message cannot carry a reply keyboard, so an enabled quickbar is
+    // sent again in the new language
+    if let Some(quickbar) =
+        enabled_quickbar_markup(pool, chat_id, localization, Some(language)).await
+    {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "quickbar-updated", Some(language)),
+        )
+        .reply_markup(quickbar)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPORTED: &[&str] = &["en", "fr"];
+
+    #[test]
+    fn test_parse_language_command() {
+        assert_eq!(
+            parse_language_command("/language auto", SUPPORTED),
+            Some(LanguageCommand::Auto(true))
+        );
+        assert_eq!(
+            parse_language_command("/language OFF", SUPPORTED),
+            Some(LanguageCommand::Auto(false))
+        );
+        assert_eq!(
+            parse_language_command("/language", SUPPORTED),
+            Some(LanguageCommand::Menu)
+        );
+        assert_eq!(
+            parse_language_command("/language FR", SUPPORTED),
+            Some(LanguageCommand::Set("fr".to_string()))
+        );
+        assert_eq!(parse_language_command("/language klingon", SUPPORTED), None);
+        assert_eq!(parse_language_command("/recipes", SUPPORTED), None);
+    }
+
+    #[test]
+    fn test_parse_language_callback() {
+        assert_eq!(
+            parse_language_callback("language:fr", SUPPORTED),
+            Some("fr")
+        );
+        assert_eq!(parse_language_callback("language:de", SUPPORTED), None);
+        assert_eq!(parse_language_callback("units:metric", SUPPORTED), None);
+    }
+
+    #[test]
+    fn test_remembered_preference_is_forgotten() {
+        let telegram_id = TelegramId(955_101);
+        remember_language_preference(telegram_id, Some("fr".to_string()));
+        assert!(LANGUAGE_PREFERENCES
+            .lock()
+            .unwrap()
+            .get(&telegram_id.0)
+            .is_some_and(|language| language.as_deref() == Some("fr")));
+
+        forget_language_preference(telegram_id);
+        assert!(!LANGUAGE_PREFERENCES
+            .lock()
+            .unwrap()
+            .contains_key(&telegram_id.0));
+    }
+}
@@ -7,13 +7,24 @@ use teloxide::prelude::*;
 use tracing::debug;
 
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_lang, t_plural};
+
+// Import database types
+use crate::db::RecipePhoto;
 
 // Import dialogue types
 use crate::dialogue::RecipeDialogue;
 
 // Import image processing functions
-use super::image_processing::{download_and_process_image, ImageProcessingParams};
+use super::image_processing::{
+    download_and_process_image, pdf_size_allowed, ImageProcessingParams, InputKind,
+};
+
+// Import recipe import handling
+use super::recipe_import::{handle_recipe_import_document, is_json_document};
+
+// Import the OCR rate limit check
+use super::message_handler::allow_ocr_request;
 
 // Import HandlerContext
 // use super::HandlerContext;
@@ -28,6 +39,7 @@ pub async fn handle_photo_message(
     dialogue: RecipeDialogue,
     pool: Arc<PgPool>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<crate::cache::SharedCacheManager>,
 ) -> Result<()> {
     // Extract user's language code from Telegram
     let language_code = msg
@@ -59,11 +71,20 @@ pub async fn handle_photo_message(
                 ImageProcessingParams {
                     file_id: largest_photo.file.id.clone(),
                     chat_id: msg.chat.id,
+                    sender: msg.from.as_ref().map(|user| user.id),
+                    message_id: msg.id,
                     success_message: &t_lang(localization, "processing-photo", language_code),
                     language_code,
                     dialogue,
                     pool,
                     caption,
+                    cache,
+                    kind: InputKind::Image,
+                    album: Vec::new(),
+                    photo: Some(RecipePhoto {
+                        file_id: largest_photo.file.id.0.clone(),
+                        file_unique_id: largest_photo.file.unique_id.0.clone(),
+                    }),
                 },
                 localization,
             )
@@ -73,6 +94,73 @@ pub async fn handle_photo_message(
     Ok(())
 }
 
+/// Read the buffered photos of an album as a single recipe
+///
+/// `msg` is the album message that started the buffering; it gives the chat and
+/// the sender. The caption of the first photo is the recipe name candidate.
+pub async fn handle_album_photos(
+    bot: &Bot,
+    msg: &Message,
+    photos: Vec<crate::cache::AlbumPhoto>,
+    dialogue: RecipeDialogue,
+    pool: Arc<PgPool>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    let Some((first, rest)) = photos.split_first() else {
+        return Ok(());
+    };
+    let language_code = msg
+        .from
+        .as_ref()
+        .and_then(|user| user.language_code.as_ref())
+        .map(|s| s.as_str());
+
+    debug!(user_id = %msg.chat.id, photos = photos.len(), "Received photo album from user");
+
+    if let Some(user) = msg.from.as_ref() {
+        crate::observability::record_user_engagement_metrics(
+            user.id.0 as i64,
+            crate::observability::UserAction::PhotoUpload,
+            None,
+            language_code,
+        );
+    }
+
+    let _temp_path = download_and_process_image(
+        bot,
+        ImageProcessingParams {
+            file_id: teloxide::types::FileId(first.file_id.clone()),
+            chat_id: msg.chat.id,
+            sender: msg.from.as_ref().map(|user| user.id),
+            message_id: teloxide::types::MessageId(first.message_id),
+            success_message: &t_plural(
+                localization,
+                "processing-album",
+                photos.len(),
+                &[],
+                language_code,
+            ),
+            language_code,
+            dialogue,
+            pool,
+            caption: first.caption.clone(),
+            cache: None,
+            kind: InputKind::Image,
+            album: rest
+                .iter()
+                .map(|photo| teloxide::types::FileId(photo.file_id.clone()))
+                .collect(),
+            photo: Some(RecipePhoto {
+                file_id: first.file_id.clone(),
+                file_unique_id: first.file_unique_id.clone(),
+            }),
+        },
+        localization,
+    )
+    .await;
+    Ok(())
+}
+
 /// Handle document messages
 pub async fn handle_document_message(
     bot: &Bot,
@@ -80,6 +168,7 @@ pub async fn handle_document_message(
     dialogue: RecipeDialogue,
     pool: Arc<PgPool>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<crate::cache::SharedCacheManager>,
 ) -> Result<()> {
     // Extract user's language code from Telegram
     let language_code = msg
@@ -89,6 +178,14 @@ pub async fn handle_document_message(
         .map(|s| s.as_str());
 
     if let Some(doc) = msg.document() {
+        // Files produced by /export restore recipes instead of going through OCR
+        if is_json_document(
+            doc.file_name.as_deref(),
+            doc.mime_type.as_ref().map(|mime| mime.essence_str()),
+        ) {
+            return handle_recipe_import_document(bot, msg, doc, pool, localization, language_code)
+                .await;
+        }
         if let Some(mime_type) = &doc.mime_type {
             if mime_type.to_string().starts_with("image/") {
                 debug!(user_id = %msg.chat.id, mime_type = %mime_type, "Received image document from user");
@@ -108,6 +205,8 @@ pub async fn handle_document_message(
                     ImageProcessingParams {
                         file_id: doc.file.id.clone(),
                         chat_id: msg.chat.id,
+                        sender: msg.from.as_ref().map(|user| user.id),
+                        message_id: msg.id,
                         success_message: &t_lang(
                             localization,
                             "processing-document",
@@ -117,6 +216,54 @@ pub async fn handle_document_message(
                         dialogue,
                         pool,
                         caption: None, // Documents don't have captions like photos do
+                        cache,
+                        kind: InputKind::Image,
+                        album: Vec::new(),
+                        photo: None,
+                    },
+                    localization,
+                )
+                .await;
+            } else if crate::pdf::is_pdf_document(Some(mime_type.essence_str()))
+                && crate::pdf::pdf_support_enabled()
+            {
+                debug!(user_id = %msg.chat.id, size = doc.file.size, "Received PDF document from user");
+
+                // Oversized PDFs are rejected before downloading them
+                if !pdf_size_allowed(u64::from(doc.file.size)) {
+                    bot.send_message(
+                        msg.chat.id,
+                        t_lang(localization, "error-pdf-too-large", language_code),
+                    )
+                    .await?;
+                    return Ok(());
+                }
+
+                if let Some(user) = msg.from.as_ref() {
+                    crate::observability::record_user_engagement_metrics(
+                        user.id.0 as i64,
+                        crate::observability::UserAction::DocumentUpload,
+                        None,
+                        language_code,
+                    );
+                }
+
+                let _temp_path = download_and_process_image(
+                    bot,
+                    ImageProcessingParams {
+                        file_id: doc.file.id.clone(),
+                        chat_id: msg.chat.id,
+                        sender: msg.from.as_ref().map(|user| user.id),
+                        message_id: msg.id,
+                        success_message: &t_lang(localization, "processing-pdf", language_code),
+                        language_code,
+                        dialogue,
+                        pool,
+                        caption: msg.caption().map(|s| s.to_string()),
+                        cache,
+                        kind: InputKind::Pdf,
+                        album: Vec::new(),
+                        photo: None,
                     },
                     localization,
                 )
@@ -140,3 +287,82 @@ pub async fn handle_document_message(
     }
     Ok(())
 }
+
+/// Handle the "Process again" button sent for a duplicate photo
+///
+/// The button's message replies to the duplicate, which is processed without the
+/// duplicate check.
+pub async fn handle_reprocess_photo_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    dialogue: RecipeDialogue,
+    pool: Arc<PgPool>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<()> {
+    let Some(teloxide::types::MaybeInaccessibleMessage::Regular(prompt)) = q.message.as_ref()
+    else {
+        return Ok(());
+    };
+    let language_code = q.from.language_code.as_deref();
+    bot.delete_message(prompt.chat.id, prompt.id).await?;
+
+    let Some(original) = prompt.reply_to_message() else {
+        debug!(user_id = %prompt.chat.id, "Duplicate photo prompt without original message");
+        return Ok(());
+    };
+    // The duplicate's OCR job was refunded, so processing it now takes one
+    if !allow_ocr_request(bot, original, localization).await? {
+        return Ok(());
+    }
+    let (file_id, success_key, kind, photo) = match (original.photo(), original.document()) {
+        (Some(photos), _) => match photos.last() {
+            Some(photo) => (
+                photo.file.id.clone(),
+                "processing-photo",
+                InputKind::Image,
+                Some(RecipePhoto {
+                    file_id: photo.file.id.0.clone(),
+                    file_unique_id: photo.file.unique_id.0.clone(),
+                }),
+            ),
+            None => return Ok(()),
+        },
+        (None, Some(doc))
+            if crate::pdf::is_pdf_document(
+                doc.mime_type.as_ref().map(|mime| mime.essence_str()),
+            ) =>
+        {
+            (doc.file.id.clone(), "processing-pdf", InputKind::Pdf, None)
+        }
+        (None, Some(doc)) => (
+            doc.file.id.clone(),
+            "processing-document",
+            InputKind::Image,
+            None,
+        ),
+        (None, None) => return Ok(()),
+    };
+
+    debug!(user_id = %prompt.chat.id, "Processing duplicate photo on request");
+    let _temp_path = download_and_process_image(
+        bot,
+        ImageProcessingParams {
+            file_id,
+            chat_id: prompt.chat.id,
+            sender: Some(q.from.id),
+            message_id: original.id,
+            success_message: &t_lang(localization, success_key, language_code),
+            language_code,
+            dialogue,
+            pool,
+            caption: original.caption().map(|s| s.to_string()),
+            cache: None,
+            kind,
+            album: Vec::new(),
+            photo,
+        },
+        localization,
+    )
+    .await;
+    Ok(())
+}
@@ -4,33 +4,80 @@ use anyhow::Result;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 use teloxide::prelude::*;
-use tracing::debug;
+use tracing::{debug, error, Instrument};
 
 // Import localization
-use crate::localization::{t_args_lang, t_lang};
+use crate::localization::{t_args_lang, t_lang, t_plural};
 
 // Import dialogue types
 use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
 
 // Import command handlers
 use super::command_handlers::{
-    handle_help_command, handle_recipes_command, handle_start_command, handle_unsupported_message,
+    handle_admin_stats_command, handle_debug_locales_command, handle_events_test_command,
+    handle_export_command, handle_help_command, handle_recipes_command, handle_start_command,
+    handle_stats_command, handle_unsupported_message, is_admin_message, is_admin_user,
 };
 
+// Import quickbar handling
+use super::quickbar::{
+    handle_quickbar_action, handle_quickbar_command, match_quickbar_action,
+    quickbar_applies_to_state, stored_quickbar_markup,
+};
+
+// Import unit display settings
+use super::unit_settings::handle_units_command;
+
+// Import per-message language detection
+use super::language_settings::{
+    auto_language_enabled, handle_language_command, resolve_user_language,
+};
+use crate::language_detection::{resolve_reply_language, ReplyTrigger};
+
+// Import extraction problem reports
+use super::problem_reports::{handle_report_comment_input, PendingReport};
+
+// Import recipe search
+use super::recipe_search::{handle_find_command, handle_search_query};
+
+// Import recipe scaling
+use super::scaled_recipes::handle_scale_factor_input;
+
+// Import recipe notes
+use super::recipe_notes::handle_notes_input;
+
+// Import recipe tags
+use super::recipe_tags::{
+    handle_new_tag_input, handle_tagged_recipes_command, parse_recipes_tag_argument,
+};
+
+// Import weekly digest settings
+use super::user_digest::handle_digest_command;
+
+// Import account deletion
+use super::account_deletion::{handle_delete_all_command, handle_wipe_confirmation_input};
+
+// Import OCR rate limiting
+use crate::rate_limiter::{RateLimitDecision, OCR_RATE_LIMITER};
+
 // Import media handlers
-use super::media_handlers::{handle_document_message, handle_photo_message};
+use super::media_handlers::{handle_album_photos, handle_document_message, handle_photo_message};
+use super::voice_messages::{handle_voice_message, voice_file_id};
 
 // Import image processing
 // use super::image_processing::process_ingredients_and_extract_matches;
 
 // Import dialogue manager functions
 use super::dialogue_manager::{
-    handle_add_ingredient_input, handle_ingredient_edit_input, handle_ingredient_review_input,
-    handle_quantity_correction_input, handle_recipe_name_after_confirm_input,
-    handle_recipe_name_input, handle_recipe_rename_input, handle_saved_ingredient_edit_input,
-    AddIngredientInputParams, DialogueContext, IngredientEditInputParams,
-    IngredientReviewInputParams, QuantityCorrectionInputParams, RecipeNameAfterConfirmInputParams,
-    RecipeNameInputParams, RecipeRenameInputParams, SavedIngredientEditInputParams,
+    exit_dialogue, handle_add_ingredient_input, handle_cancel_command,
+    handle_ingredient_edit_input, handle_ingredient_review_input,
+    handle_pending_recipe_rename_input, handle_quantity_correction_input,
+    handle_recipe_name_after_confirm_input, handle_recipe_name_input, handle_recipe_rename_input,
+    handle_saved_ingredient_edit_input, is_dialogue_escape_command, AddIngredientInputParams,
+    DialogueContext, IngredientEditInputParams, IngredientReviewInputParams,
+    PendingRecipeRenameInputParams, QuantityCorrectionInputParams,
+    RecipeNameAfterConfirmInputParams, RecipeNameInputParams, RecipeRenameInputParams,
+    SavedIngredientEditInputParams,
 };
 
 // Import HandlerContext
@@ -45,6 +92,7 @@ async fn handle_text_message(
     dialogue: RecipeDialogue,
     pool: Arc<PgPool>,
     localization: &Arc<crate::localization::LocalizationManager>,
+    cache: Option<&crate::cache::SharedCacheManager>,
 ) -> Result<()> {
     if let Some(text) = msg.text() {
         debug!(user_id = %msg.chat.id, message_length = text.len(), "Received text message from user");
@@ -56,8 +104,24 @@ async fn handle_text_message(
             .and_then(|user| user.language_code.as_ref())
             .map(|s| s.as_str());
 
+        // In auto language mode, free text is answered in the language it was typed in
+        let auto_language = auto_language_enabled(&pool, msg.chat.id, text).await;
+
+        // /cancel, /start and /help get the user out of any dialogue, whatever it expects
+        if is_dialogue_escape_command(text) {
+            if text.split_whitespace().next() == Some("/cancel") {
+                return handle_cancel_command(bot, msg, &dialogue, localization, language_code)
+                    .await;
+            }
+            exit_dialogue(bot, msg.chat.id, &dialogue).await?;
+        }
+
         // Check dialogue state first
         let dialogue_state = dialogue.get().await?;
+        if let Some(state) = &dialogue_state {
+            crate::dialogue::record_state_size(state);
+        }
+        let quickbar_active = quickbar_applies_to_state(dialogue_state.as_ref());
         match dialogue_state {
             Some(RecipeDialogueState::WaitingForRecipeName {
                 extracted_text,
@@ -65,7 +129,11 @@ async fn handle_text_message(
                 language_code: dialogue_lang_code,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle recipe name input
                 return handle_recipe_name_input(
@@ -95,9 +163,14 @@ async fn handle_text_message(
                 extracted_text,
                 recipe_name_from_caption: _,
                 message_id,
+                name_suggestions: _,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle recipe name input after ingredient confirmation
                 return handle_recipe_name_after_confirm_input(
@@ -118,6 +191,7 @@ async fn handle_text_message(
                         },
                         extracted_text,
                         message_id,
+                        cache,
                     },
                 )
                 .await;
@@ -129,9 +203,14 @@ async fn handle_text_message(
                 message_id: _,
                 extracted_text,
                 recipe_name_from_caption: _,
+                last_deleted: _,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle ingredient review commands
                 return handle_ingredient_review_input(
@@ -152,6 +231,7 @@ async fn handle_text_message(
                             language_code: effective_language_code,
                         },
                         extracted_text,
+                        cache,
                     },
                 )
                 .await;
@@ -167,7 +247,11 @@ async fn handle_text_message(
                 recipe_name_from_caption,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle ingredient edit input
                 return handle_ingredient_edit_input(
@@ -178,6 +262,7 @@ async fn handle_text_message(
                         localization,
                     },
                     IngredientEditInputParams {
+                        pool,
                         edit_input: text,
                         recipe_name,
                         ingredients,
@@ -201,7 +286,11 @@ async fn handle_text_message(
                 language_code: dialogue_lang_code,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle recipe rename input
                 return handle_recipe_rename_input(
@@ -221,6 +310,7 @@ async fn handle_text_message(
                             localization,
                             language_code: effective_language_code,
                         },
+                        cache,
                     },
                 )
                 .await;
@@ -231,9 +321,14 @@ async fn handle_text_message(
                 current_matches,
                 language_code: dialogue_lang_code,
                 message_id,
+                ingredients_version,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle adding new ingredient input for saved recipes
                 return handle_add_ingredient_input(
@@ -249,6 +344,7 @@ async fn handle_text_message(
                         recipe_id,
                         original_ingredients: &original_ingredients,
                         current_matches: &current_matches,
+                        ingredients_version,
                         ctx: &HandlerContext {
                             bot,
                             localization,
@@ -267,9 +363,14 @@ async fn handle_text_message(
                 language_code: dialogue_lang_code,
                 message_id,
                 original_message_id,
+                ingredients_version,
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle editing individual ingredient input for saved recipes
                 return handle_saved_ingredient_edit_input(
@@ -285,6 +386,7 @@ async fn handle_text_message(
                         recipe_id,
                         original_ingredients: &original_ingredients,
                         current_matches: &current_matches,
+                        ingredients_version,
                         ctx: &HandlerContext {
                             bot,
                             localization,
@@ -298,9 +400,13 @@ async fn handle_text_message(
                 )
                 .await;
             }
-            Some(RecipeDialogueState::EditingSavedIngredients { .. }) => {
+            Some(RecipeDialogueState::EditingSavedIngredients { .. })
+            | Some(RecipeDialogueState::ConfirmingRenamePropagation { .. })
+            | Some(RecipeDialogueState::ResolvingRecipeNameConflict { .. }) => {
                 // Users should use buttons in this state, not type text
-                let effective_language_code = language_code; // No dialogue language code available
+                // No dialogue language code available
+                let effective_language_code =
+                    resolve_reply_language(auto_language, ReplyTrigger::Text(text), language_code);
                 bot.send_message(
                     msg.chat.id,
                     t_lang(
@@ -322,7 +428,11 @@ async fn handle_text_message(
                 ..
             }) => {
                 // Use dialogue language code if available, otherwise fall back to message language
-                let effective_language_code = dialogue_lang_code.as_deref().or(language_code);
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
 
                 // Handle quantity correction input
                 return handle_quantity_correction_input(
@@ -345,27 +455,355 @@ async fn handle_text_message(
                         },
                         extracted_text,
                         recipe_name_from_caption,
+                        cache,
+                    },
+                )
+                .await;
+            }
+            Some(RecipeDialogueState::AwaitingReportComment {
+                context,
+                language_code: dialogue_lang_code,
+                prompt_message_id,
+                resume_state,
+            }) => {
+                // The typed text is the optional comment of the pending report
+                return handle_report_comment_input(
+                    bot,
+                    msg,
+                    &pool,
+                    dialogue,
+                    localization,
+                    text,
+                    PendingReport {
+                        context,
+                        language_code: dialogue_lang_code.or(language_code.map(str::to_string)),
+                        prompt_message_id,
+                        resume_state,
                     },
                 )
                 .await;
             }
-            Some(RecipeDialogueState::Start) | None => {
+            Some(RecipeDialogueState::AwaitingSearchQuery {
+                language_code: dialogue_lang_code,
+            }) => {
+                // Commands and quickbar buttons leave the search instead of being searched for
+                if text.starts_with('/') || match_quickbar_action(localization, text).is_some() {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                } else {
+                    return handle_search_query(
+                        bot,
+                        msg,
+                        &pool,
+                        dialogue,
+                        localization,
+                        text,
+                        dialogue_lang_code.as_deref().or(language_code),
+                    )
+                    .await;
+                }
+            }
+            Some(RecipeDialogueState::ScalingRecipe {
+                recipe_id,
+                language_code: dialogue_lang_code,
+            }) => {
+                // Commands leave the scaling prompt instead of being read as a factor
+                if text.starts_with('/') {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                } else {
+                    return handle_scale_factor_input(
+                        bot,
+                        msg,
+                        &pool,
+                        dialogue,
+                        localization,
+                        text,
+                        recipe_id,
+                        dialogue_lang_code.as_deref().or(language_code),
+                    )
+                    .await;
+                }
+            }
+            Some(RecipeDialogueState::TaggingRecipe {
+                recipe_id,
+                language_code: dialogue_lang_code,
+            }) => {
+                // Commands and quickbar buttons leave the tags view instead of becoming tags
+                if text.starts_with('/') || match_quickbar_action(localization, text).is_some() {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                } else {
+                    return handle_new_tag_input(
+                        bot,
+                        msg,
+                        &pool,
+                        dialogue,
+                        localization,
+                        text,
+                        recipe_id,
+                        dialogue_lang_code.as_deref().or(language_code),
+                    )
+                    .await;
+                }
+            }
+            Some(RecipeDialogueState::EditingRecipeNotes {
+                recipe_id,
+                language_code: dialogue_lang_code,
+            }) => {
+                // Commands leave the notes prompt instead of being saved as notes
+                if text.starts_with('/') {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                } else {
+                    return handle_notes_input(
+                        bot,
+                        msg,
+                        &pool,
+                        dialogue,
+                        localization,
+                        text,
+                        recipe_id,
+                        dialogue_lang_code.as_deref().or(language_code),
+                        cache,
+                    )
+                    .await;
+                }
+            }
+            Some(RecipeDialogueState::ConfirmingAccountWipe {
+                language_code: dialogue_lang_code,
+            }) => {
+                // Commands leave the confirmation without deleting anything
+                if text.starts_with('/') {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                } else {
+                    return handle_wipe_confirmation_input(
+                        bot,
+                        msg,
+                        &pool,
+                        dialogue,
+                        cache,
+                        localization,
+                        text,
+                        dialogue_lang_code.as_deref().or(language_code),
+                    )
+                    .await;
+                }
+            }
+            Some(RecipeDialogueState::RenamingPendingRecipe {
+                language_code: dialogue_lang_code,
+                ..
+            }) => {
+                // Use dialogue language code if available, otherwise fall back to message language
+                let effective_language_code = resolve_reply_language(
+                    auto_language,
+                    ReplyTrigger::Text(text),
+                    dialogue_lang_code.as_deref().or(language_code),
+                );
+
+                // Handle the new name of the recipe under review
+                return handle_pending_recipe_rename_input(
+                    DialogueContext {
+                        bot,
+                        msg,
+                        dialogue,
+                        localization,
+                    },
+                    PendingRecipeRenameInputParams {
+                        new_name_input: text,
+                        ctx: &HandlerContext {
+                            bot,
+                            localization,
+                            language_code: effective_language_code,
+                        },
+                    },
+                )
+                .await;
+            }
+            Some(RecipeDialogueState::Start)
+            | Some(RecipeDialogueState::SelectingRecipesToDelete { .. })
+            | None => {
                 // Continue with normal command handling
             }
         }
 
-        // Handle /start command
-        if text == "/start" {
-            return handle_start_command(bot, msg, localization, language_code).await;
+        // Handle quickbar buttons, matched against every supported language
+        if quickbar_active {
+            if let Some(action) = match_quickbar_action(localization, text) {
+                return handle_quickbar_action(
+                    bot,
+                    msg,
+                    pool,
+                    &dialogue,
+                    action,
+                    localization,
+                    language_code,
+                )
+                .await;
+            }
+        }
+
+        // Quickbar taps and commands keep the fixed language, other text may be detected
+        let language_code =
+            resolve_reply_language(auto_language, ReplyTrigger::Text(text), language_code);
+
+        // Share links open the bot with /start share_<token>
+        if let Some(token) = crate::bot::recipe_sharing::parse_share_start_payload(text) {
+            return crate::bot::recipe_sharing::handle_share_start(
+                bot,
+                msg,
+                &pool,
+                token,
+                localization,
+                language_code,
+            )
+            .await;
+        }
+        // Handle /start command, including deep links with an unknown payload
+        else if text == "/start" || text.starts_with("/start ") {
+            let quickbar = if quickbar_active {
+                stored_quickbar_markup(&pool, msg.chat.id, localization, language_code).await
+            } else {
+                None
+            };
+            return handle_start_command(bot, msg, localization, language_code, quickbar).await;
         }
         // Handle /help command
         else if text == "/help" {
-            return handle_help_command(bot, msg, localization, language_code).await;
+            let quickbar = if quickbar_active {
+                stored_quickbar_markup(&pool, msg.chat.id, localization, language_code).await
+            } else {
+                None
+            };
+            return handle_help_command(bot, msg, localization, language_code, quickbar).await;
         }
         // Handle /recipes command
         else if text == "/recipes" {
             return handle_recipes_command(bot, msg, pool, language_code, localization).await;
         }
+        // Handle /recipes <tag> command
+        else if let Some(tag) = parse_recipes_tag_argument(text) {
+            return handle_tagged_recipes_command(
+                bot,
+                msg,
+                &pool,
+                tag,
+                localization,
+                language_code,
+            )
+            .await;
+        }
+        // Handle /recent command
+        else if text == "/recent" {
+            return crate::bot::recent_activity::handle_recent_command(
+                bot,
+                msg,
+                pool,
+                language_code,
+                localization,
+            )
+            .await;
+        }
+        // Handle /stats command
+        else if text == "/stats" {
+            return handle_stats_command(bot, msg, &pool, localization, language_code).await;
+        }
+        // Handle /export command
+        else if text == "/export" {
+            return handle_export_command(bot, msg, pool, localization, language_code).await;
+        }
+        // Handle /find <ingredient> command
+        else if text == "/find" || text.starts_with("/find ") {
+            return handle_find_command(bot, msg, &pool, text, localization, language_code).await;
+        }
+        // Handle /quickbar on|off command
+        else if text == "/quickbar" || text.starts_with("/quickbar ") {
+            return handle_quickbar_command(bot, msg, pool, text, localization, language_code)
+                .await;
+        }
+        // Handle /digest [on|off|<day> <hour>] command
+        else if text == "/digest" || text.starts_with("/digest ") {
+            return handle_digest_command(bot, msg, &pool, text, localization, language_code).await;
+        }
+        // Handle /units command
+        else if text == "/units" {
+            return handle_units_command(bot, msg, pool, localization, language_code).await;
+        }
+        // Handle /timezone [offset] command
+        else if text == "/timezone" || text.starts_with("/timezone ") {
+            return crate::bot::timezone_settings::handle_timezone_command(
+                bot,
+                msg,
+                &pool,
+                text,
+                localization,
+                language_code,
+            )
+            .await;
+        }
+        // Handle /trash command
+        else if text == "/trash" {
+            return crate::bot::recipe_trash::handle_trash_command(
+                bot,
+                msg,
+                &pool,
+                localization,
+                language_code,
+            )
+            .await;
+        }
+        // Handle /delete_all command
+        else if text == "/delete_all" {
+            return handle_delete_all_command(bot, msg, localization, language_code).await;
+        }
+        // Handle /language [code|auto|off] command
+        else if text == "/language" || text.starts_with("/language ") {
+            return handle_language_command(bot, msg, pool, text, localization, language_code)
+                .await;
+        }
+        // Handle /debug_locales admin command
+        else if text == "/debug_locales" && is_admin_message(msg) {
+            return handle_debug_locales_command(bot, msg, localization, language_code).await;
+        }
+        // Handle /admin_stats admin command
+        else if text == "/admin_stats" && is_admin_message(msg) {
+            return handle_admin_stats_command(bot, msg, pool, localization, language_code).await;
+        }
+        // Handle /events_test admin command
+        else if text == "/events_test" && is_admin_message(msg) {
+            return handle_events_test_command(bot, msg, localization, language_code).await;
+        }
+        // Handle /broadcast <message> admin command
+        else if crate::bot::admin_broadcast::parse_broadcast_command(text).is_some()
+            && is_admin_message(msg)
+        {
+            return crate::bot::admin_broadcast::handle_broadcast_command(
+                bot,
+                msg,
+                pool,
+                text,
+                localization,
+                language_code,
+            )
+            .await;
+        }
+        // Unknown commands, including admin commands sent by non-admins
+        else if text.starts_with('/') {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, "unknown-command", language_code),
+            )
+            .await?;
+        }
+        // Ingredient lists forwarded or pasted as text go to the review
+        else if crate::bot::text_ingredients::handle_ingredient_text_message(
+            bot,
+            msg,
+            &dialogue,
+            &pool,
+            localization,
+            language_code,
+        )
+        .await?
+        {
+            return Ok(());
+        }
         // Handle regular text messages
         else {
             bot.send_message(
@@ -387,6 +825,27 @@ async fn handle_text_message(
     Ok(())
 }
 
+/// Operation a message is measured under, from its kind
+pub fn message_operation(msg: &Message) -> observability::TelegramOperation {
+    use observability::TelegramOperation;
+
+    if let Some(text) = msg.text() {
+        if text.starts_with('/') {
+            TelegramOperation::Command
+        } else {
+            TelegramOperation::Text
+        }
+    } else if msg.photo().is_some() {
+        TelegramOperation::Photo
+    } else if msg.document().is_some() {
+        TelegramOperation::Document
+    } else if voice_file_id(msg).is_some() {
+        TelegramOperation::Voice
+    } else {
+        TelegramOperation::OtherMessage
+    }
+}
+
 /// Main message handler for Telegram bot interactions
 /// Main message handler for Telegram bot interactions
 /// Main message handler for Telegram bot interactions
@@ -404,7 +863,9 @@ async fn handle_text_message(
 /// 3. Route by message type:
 ///    ├── Text → handle_text_message()
 ///    ├── Photo → handle_photo_message()
+///    │     (album photos are collected, then read together by handle_album_photos())
 ///    ├── Document → handle_document_message()
+///    ├── Voice/Audio → handle_voice_message() (transcribed, then reviewed like text)
 ///    └── Other → handle_unsupported_message()
 /// 4. Handle dialogue state transitions
 /// 5. Record performance metrics
@@ -447,6 +908,11 @@ async fn handle_text_message(
 /// - Process supported image formats
 /// - Same OCR pipeline as photos (no caption support)
 ///
+/// ### Voice Messages
+/// - Download the OGG/Opus voice note (or audio file)
+/// - Transcribe it with the configured backend, hinted with the user's language
+/// - Extract ingredients from the transcript and show the review keyboard
+///
 /// ## Language Detection & Localization
 ///
 /// ```text
@@ -495,6 +961,7 @@ async fn handle_text_message(
 /// - **Text**: Commands, dialogue input, recipe management
 /// - **Photo**: Image processing with optional captions
 /// - **Document**: Image files uploaded as documents
+/// - **Voice/Audio**: Dictated ingredient lists, when transcription is configured
 /// - **Unsupported**: Guidance for unsupported message types
 pub async fn message_handler(
     bot: Bot,
@@ -503,81 +970,284 @@ pub async fn message_handler(
     dialogue: RecipeDialogue,
     localization: Arc<crate::localization::LocalizationManager>,
     deduplicator: Option<&crate::deduplication::SharedDeduplicator>,
+) -> Result<()> {
+    handle_message(bot, msg, pool, dialogue, localization, None, deduplicator).await
+}
+
+/// Route a message, skipping duplicate photos when a cache is available
+async fn handle_message(
+    bot: Bot,
+    mut msg: Message,
+    pool: Arc<PgPool>,
+    dialogue: RecipeDialogue,
+    localization: Arc<crate::localization::LocalizationManager>,
+    cache: Option<crate::cache::SharedCacheManager>,
+    deduplicator: Option<&crate::deduplication::SharedDeduplicator>,
 ) -> Result<()> {
     let span = crate::observability::telegram_span(
         "message_handler",
         msg.from.as_ref().map(|u| u.id.0 as i64),
     );
-    let _enter = span.enter();
-
-    // Check for duplicate requests if deduplicator is provided
-    if let Some(dedup) = deduplicator {
-        let request_id = crate::deduplication::RequestId::new(msg.chat.id, msg.id);
-        if dedup.is_duplicate(&request_id)? {
-            debug!(
-                "Ignoring duplicate request: chat_id={}, message_id={}",
-                msg.chat.id, msg.id
-            );
-            observability::record_telegram_duplicate_message();
+    async move {
+        // Check for duplicate requests if deduplicator is provided
+        if let Some(dedup) = deduplicator {
+            let request_id = crate::deduplication::RequestId::new(msg.chat.id, msg.id);
+            if dedup.is_duplicate(&request_id)? {
+                debug!(
+                    "Ignoring duplicate request: chat_id={}, message_id={}",
+                    msg.chat.id, msg.id
+                );
+                observability::record_telegram_duplicate_message();
+                return Ok(());
+            }
+        }
+
+        // Ingredient lists in any reply are shown in the user's unit system
+        super::unit_settings::load_unit_preference(&pool, msg.chat.id).await;
+        // Dates in any reply are shown in the user's time zone
+        super::timezone_settings::load_timezone(&pool, msg.chat.id).await;
+
+        // Handlers read the reply language from the sender, so apply the /language choice first
+        if let Some(user) = msg.from.as_mut() {
+            user.language_code = resolve_user_language(&pool, user).await;
+        }
+
+        let start_time = std::time::Instant::now();
+        let message_type = if msg.text().is_some() {
+            "text"
+        } else if msg.photo().is_some() {
+            "photo"
+        } else if msg.document().is_some() {
+            "document"
+        } else if voice_file_id(&msg).is_some() {
+            "voice"
+        } else {
+            "unsupported"
+        };
+
+        observability::record_telegram_message(message_type);
+        let operation = message_operation(&msg);
+        let interaction = observability::InteractionSpan::start(operation);
+
+        // Photos of an album are read together once all of them have arrived
+        if let Some(cache) = &cache {
+            if buffer_album_photo(&bot, &msg, &pool, &dialogue, &localization, cache) {
+                return Ok(());
+            }
+        }
+
+        if is_ocr_request(&msg) && !allow_ocr_request(&bot, &msg, &localization).await? {
             return Ok(());
         }
-    }
 
-    let start_time = std::time::Instant::now();
-    let message_type = if msg.text().is_some() {
-        "text"
-    } else if msg.photo().is_some() {
-        "photo"
-    } else if msg.document().is_some() {
-        "document"
-    } else {
-        "unsupported"
-    };
+        let result = if msg.text().is_some() {
+            handle_text_message(&bot, &msg, dialogue, pool, &localization, cache.as_ref()).await
+        } else if msg.photo().is_some() {
+            handle_photo_message(&bot, &msg, dialogue, pool, &localization, cache).await
+        } else if msg.document().is_some() {
+            handle_document_message(&bot, &msg, dialogue, pool, &localization, cache).await
+        } else if voice_file_id(&msg).is_some() {
+            handle_voice_message(&bot, &msg, &dialogue, &pool, &localization).await
+        } else {
+            handle_unsupported_message(&bot, &msg, &localization).await
+        };
 
-    observability::record_telegram_message(message_type);
+        let duration = start_time.elapsed();
+        observability::record_request_metrics("telegram_message", 200, duration);
+        observability::record_telegram_handler_metrics(
+            operation,
+            duration,
+            interaction.trace_id().as_deref(),
+        );
 
-    let result = if msg.text().is_some() {
-        handle_text_message(&bot, &msg, dialogue, pool, &localization).await
-    } else if msg.photo().is_some() {
-        handle_photo_message(&bot, &msg, dialogue, pool, &localization).await
-    } else if msg.document().is_some() {
-        handle_document_message(&bot, &msg, dialogue, pool, &localization).await
-    } else {
-        handle_unsupported_message(&bot, &msg, &localization).await
-    };
+        // Record enhanced Telegram performance metrics
+        let message_size =
+            msg.text().map(|t| t.len()).unwrap_or(0) + msg.caption().map(|c| c.len()).unwrap_or(0);
+        let has_media =
+            msg.photo().is_some() || msg.document().is_some() || voice_file_id(&msg).is_some();
+        observability::record_telegram_performance_metrics(
+            message_type,
+            duration,
+            msg.from.as_ref().map(|u| u.id.0 as i64),
+            message_size,
+            has_media,
+        );
 
-    let duration = start_time.elapsed();
-    observability::record_request_metrics("telegram_message", 200, duration);
+        if let Err(e) = &result {
+            reply_with_error(&bot, &msg, e, &localization).await;
+        }
+        result
+    }
+    .instrument(span)
+    .await
+}
 
-    // Record enhanced Telegram performance metrics
-    let message_size =
-        msg.text().map(|t| t.len()).unwrap_or(0) + msg.caption().map(|c| c.len()).unwrap_or(0);
-    let has_media = msg.photo().is_some() || msg.document().is_some();
-    observability::record_telegram_performance_metrics(
-        message_type,
-        duration,
-        msg.from.as_ref().map(|u| u.id.0 as i64),
-        message_size,
-        has_media,
+/// Tell the user why their message could not be handled and what to try next
+///
+/// Skipped when the failure comes from Telegram itself, as the reply would fail too.
+async fn reply_with_error(
+    bot: &Bot,
+    msg: &Message,
+    err: &anyhow::Error,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) {
+    if crate::errors::is_telegram_error(err) {
+        return;
+    }
+    let kind = crate::errors::user_error_kind(err);
+    observability::record_error_metrics(kind.as_str(), "telegram_message");
+    let language_code = msg.from.as_ref().and_then(|u| u.language_code.as_deref());
+    let reply = crate::errors::user_reply(localization, kind, language_code);
+    if let Err(e) = bot.send_message(msg.chat.id, reply).await {
+        error!(user_id = %msg.chat.id, error = %e, "Failed to send error reply");
+    }
+}
+
+/// Buffer a photo sent as part of an album, returning whether it was buffered
+///
+/// The first photo of an album schedules the album to be read once the album
+/// window has passed; the whole album counts as one OCR job.
+fn buffer_album_photo(
+    bot: &Bot,
+    msg: &Message,
+    pool: &Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    cache: &crate::cache::SharedCacheManager,
+) -> bool {
+    let (Some(media_group_id), Some(photo)) = (
+        msg.media_group_id(),
+        msg.photo().and_then(|photos| photos.last()),
+    ) else {
+        return false;
+    };
+    let media_group_id = media_group_id.0.clone();
+    let album_photo = crate::cache::AlbumPhoto {
+        message_id: msg.id.0,
+        file_id: photo.file.id.0.clone(),
+        file_unique_id: photo.file.unique_id.0.clone(),
+        caption: msg.caption().map(|s| s.to_string()),
+    };
+    let first_photo = cache
+        .lock()
+        .album_buffer
+        .push(msg.chat.id.0, &media_group_id, album_photo);
+    debug!(user_id = %msg.chat.id, media_group_id = %media_group_id, first_photo, "Buffered album photo");
+    if !first_photo {
+        return true;
+    }
+
+    let (bot, msg, pool, dialogue, localization, cache) = (
+        bot.clone(),
+        msg.clone(),
+        Arc::clone(pool),
+        dialogue.clone(),
+        Arc::clone(localization),
+        Arc::clone(cache),
     );
+    let shutdown = crate::shutdown::global();
+    // Counted from the first photo so a shutdown waits for the album to be read
+    let in_flight = shutdown.track();
+    let shutdown_token = shutdown.token();
+    tokio::spawn(
+        async move {
+            let _in_flight = in_flight;
+            // Stop waiting for further photos once shutdown starts
+            tokio::select! {
+                _ = tokio::time::sleep(crate::cache::album_window()) => {}
+                _ = shutdown_token.cancelled() => {}
+            }
+            let photos = cache
+                .lock()
+                .album_buffer
+                .take(msg.chat.id.0, &media_group_id);
+            let result = async {
+                if !allow_ocr_request(&bot, &msg, &localization).await? {
+                    return Ok(());
+                }
+                handle_album_photos(&bot, &msg, photos, dialogue, pool, &localization).await
+            }
+            .await;
+            if let Err(e) = result {
+                error!(user_id = %msg.chat.id, error = %e, "Failed to process photo album");
+                reply_with_error(&bot, &msg, &e, &localization).await;
+            }
+        }
+        // The album is read after the handler returns, still under its update's span
+        .in_current_span(),
+    );
+    true
+}
+
+/// Whether a message starts an OCR job: a photo, or an image or PDF sent as a document
+fn is_ocr_request(msg: &Message) -> bool {
+    msg.photo().is_some()
+        || msg
+            .document()
+            .and_then(|doc| doc.mime_type.as_ref())
+            .is_some_and(|mime_type| {
+                mime_type.to_string().starts_with("image/")
+                    || crate::pdf::is_pdf_document(Some(mime_type.essence_str()))
+            })
+}
+
+/// Take an OCR job from the sender's rate limit, telling them how long to wait when over it
+///
+/// Admins are never limited. Returns whether the job may run.
+pub(crate) async fn allow_ocr_request(
+    bot: &Bot,
+    msg: &Message,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Result<bool> {
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(true);
+    };
+    let user_id = user.id.0 as i64;
+    if is_admin_user(user_id) {
+        return Ok(true);
+    }
 
-    result
+    let RateLimitDecision::Limited { retry_after } = OCR_RATE_LIMITER.check(user_id) else {
+        return Ok(true);
+    };
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as usize;
+    debug!(user_id, wait_seconds = seconds, "OCR request rate limited");
+    observability::record_rate_limit_hit("ocr");
+    bot.send_message(
+        msg.chat.id,
+        t_plural(
+            localization,
+            "rate-limit-ocr",
+            seconds,
+            &[],
+            user.language_code.as_deref(),
+        ),
+    )
+    .await?;
+    Ok(false)
 }
 
 /// Cache-enabled message handler for improved performance
 ///
-/// This version includes caching for database queries and OCR results
-/// to reduce processing time and database load.
+/// This version recognizes photos sent twice in quick succession, so OCR does not
+/// run again for them, and reads the photos of an album as a single recipe.
 pub async fn message_handler_with_cache(
     bot: Bot,
     msg: Message,
     pool: Arc<PgPool>,
     dialogue: RecipeDialogue,
     localization: Arc<crate::localization::LocalizationManager>,
-    _cache: Arc<std::sync::Mutex<crate::cache::CacheManager>>,
+    cache: crate::cache::SharedCacheManager,
     deduplicator: Option<&crate::deduplication::SharedDeduplicator>,
 ) -> Result<()> {
-    // For now, delegate to the original handler
-    // TODO: Integrate caching into specific operations
-    message_handler(bot, msg, pool, dialogue, localization, deduplicator).await
+    handle_message(
+        bot,
+        msg,
+        pool,
+        dialogue,
+        localization,
+        Some(cache),
+        deduplicator,
+    )
+    .await
 }
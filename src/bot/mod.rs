@@ -1,19 +1,66 @@
 //! Bot module for handling Telegram interactions
 //!
 //! This module is split into several submodules for better organization:
+//! - `account_deletion`: Wipes all of a user's data (`/delete_all`)
+//! - `admin_broadcast`: Admin announcement sent to every user (`/broadcast`)
+//! - `bot_utils`: Telegram API helpers such as retrying transient send failures
 //! - `callbacks`: All callback query handling (organized into submodules)
+//! - `formatting`: HTML escaping and builders for messages with highlighted text
+//! - `inline_handler`: Inline mode, sharing a saved recipe into any chat
+//! - `language_settings`: Per-message language detection (`/language auto|off`)
 //! - `message_handler`: Handles incoming text, photo, and document messages
+//! - `ocr_digest`: Weekly OCR accuracy report sent to the maintainers
+//! - `ocr_raw_text`: Raw OCR text shown on demand during ingredient review
+//! - `problem_reports`: Lets users report a bad extraction to the maintainers
+//! - `quickbar`: Optional reply keyboard with quick actions
+//! - `recipe_import`: Restores recipes from files produced by `/export`
+//! - `recipe_notes`: The user's own notes on saved recipes
+//! - `recipe_nutrition`: Estimated calories and macronutrients of a saved recipe
+//! - `recipe_search`: Searches the user's recipes by name, ingredients and content
+//! - `recipe_sharing`: Share links copying a recipe into another user's account
+//! - `recipe_tags`: Tags on saved recipes and the tag-filtered recipe list
+//! - `recipe_trash`: Deleted recipes kept restorable for a week (`/trash`)
+//! - `scaled_recipes`: Shows a saved recipe scaled by a factor, and saves the copy
+//! - `text_ingredients`: Ingredient review for forwarded or pasted text
+//! - `timezone_settings`: Time zone dates and daily counts are shown in (`/timezone`)
 //! - `ui_builder`: Creates keyboards and formats messages
+//! - `unit_settings`: Metric or US/imperial display of quantities (`/units`)
+//! - `user_digest`: Opt-in weekly summary of the recipes a user saved (`/digest`)
+//! - `voice_messages`: Ingredient review for transcribed voice messages
 //! - `dialogue_manager`: Manages dialogue state transitions and validation
 
+pub mod account_deletion;
+pub mod admin_broadcast;
+pub mod bot_utils;
 pub mod callbacks;
 pub mod command_handlers;
 pub mod dialogue_manager;
+pub mod formatting;
 pub mod image_processing;
+pub mod inline_handler;
+pub mod language_settings;
 pub mod media_handlers;
 pub mod message_handler;
+pub mod ocr_digest;
+pub mod ocr_raw_text;
+pub mod problem_reports;
+pub mod quickbar;
+pub mod recent_activity;
+pub mod recipe_import;
+pub mod recipe_notes;
+pub mod recipe_nutrition;
+pub mod recipe_search;
+pub mod recipe_sharing;
+pub mod recipe_tags;
+pub mod recipe_trash;
+pub mod scaled_recipes;
+pub mod text_ingredients;
+pub mod timezone_settings;
 pub mod ui_builder;
 pub mod ui_components;
+pub mod unit_settings;
+pub mod user_digest;
+pub mod voice_messages;
 
 // Common context structures for handler functions
 use crate::localization::LocalizationManager;
@@ -38,7 +85,9 @@ pub use image_processing::{
     download_and_process_image, download_file, process_ingredients_and_extract_matches,
 };
 pub use ui_builder::{
-    create_ingredient_review_keyboard, create_post_confirmation_keyboard,
-    create_processing_keyboard, create_recipes_pagination_keyboard, format_ingredients_list,
+    create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+    create_post_confirmation_keyboard, create_processing_keyboard,
+    create_recipes_pagination_keyboard, format_ingredients_list, format_review_cap_notice,
+    format_review_message, with_retry_processing_button, with_undo_delete_button,
 };
 pub use ui_components::create_ingredient_editing_keyboard;
@@ -0,0 +1,367 @@
+//! Weekly OCR accuracy digest for maintainers
+//!
+//! Once a week the first admin receives a compact report on how extraction is
+//! doing, compared with the week before:
+//! - OCR success rate: reviews shown that were not reported as a bad extraction
+//! - median edits per confirmed recipe, from the review funnel events
+//! - top unmatched unit candidates, from ingredients saved without a unit
+//! - minutes the OCR circuit breaker spent open
+//!
+//! Circuit breaker time is only tracked in memory, so after a restart the first
+//! digest covers the time since startup and has no previous week to compare with.
+//! A stat that fails to load is shown as "n/a" instead of failing the whole report.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc, Weekday};
+use sqlx::postgres::PgPool;
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::db::{
+    get_extraction_feedback_counts, get_median_edits_per_confirmed_review,
+    get_unmatched_unit_candidates,
+};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::scheduler::{spawn_weekly, WeeklySchedule};
+
+use super::command_handlers::first_admin_user;
+use super::formatting::{HtmlMessage, PARSE_MODE};
+use super::image_processing::ocr_circuit_open_duration;
+
+/// Unit candidates listed in the digest
+pub const DIGEST_TOP_UNIT_CANDIDATES: i64 = 5;
+
+/// Digest schedule and enable flag, set with OCR_DIGEST_ENABLED, OCR_DIGEST_DAY and OCR_DIGEST_HOUR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OcrDigestConfig {
+    pub enabled: bool,
+    pub schedule: WeeklySchedule,
+}
+
+impl Default for OcrDigestConfig {
+    /// Disabled; Mondays at 08:00 UTC once enabled
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: WeeklySchedule {
+                weekday: Weekday::Mon,
+                hour: 8,
+            },
+        }
+    }
+}
+
+/// One week of OCR accuracy stats; `None` means the stat could not be loaded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OcrDigestStats {
+    /// Share of shown reviews without a bad-extraction report, between 0 and 1
+    pub ocr_success_rate: Option<f64>,
+    /// Median edits made in reviews that ended in a saved recipe
+    pub median_edits: Option<f64>,
+    /// Most frequent words saved where a unit was expected, with their counts
+    pub unit_candidates: Option<Vec<(String, i64)>>,
+    /// Minutes the OCR circuit breaker was open
+    pub circuit_open_minutes: Option<f64>,
+}
+
+/// Share of shown reviews that were not reported, `None` without reviews
+pub fn ocr_success_rate(reviews_shown: i64, reports: i64) -> Option<f64> {
+    if reviews_shown <= 0 {
+        return None;
+    }
+    Some((reviews_shown - reports).max(0) as f64 / reviews_shown as f64)
+}
+
+/// Week-over-week change, e.g. ` (▲ 2.5)`; empty when either week is unknown
+pub fn format_delta(current: Option<f64>, previous: Option<f64>, precision: usize) -> String {
+    let (Some(current), Some(previous)) = (current, previous) else {
+        return String::new();
+    };
+    let change = current - previous;
+    // Compare at display precision so "▲ 0.0" never shows up
+    let rounded = format!("{:.*}", precision, change.abs());
+    if rounded.parse::<f64>().unwrap_or(0.0) == 0.0 {
+        " (=)".to_string()
+    } else if change > 0.0 {
+        format!(" (▲ {rounded})")
+    } else {
+        format!(" (▼ {rounded})")
+    }
+}
+
+/// Splits the circuit breaker's cumulative open time into weekly amounts
+#[derive(Debug, Default)]
+pub struct CircuitOpenTracker {
+    last_total: std::time::Duration,
+    last_week_minutes: Option<f64>,
+}
+
+impl CircuitOpenTracker {
+    /// Record the cumulative open time at digest time
+    ///
+    /// Returns this week's and last week's open minutes.
+    pub fn advance(&mut self, total: std::time::Duration) -> (f64, Option<f64>) {
+        let minutes = total.saturating_sub(self.last_total).as_secs_f64() / 60.0;
+        let previous = self.last_week_minutes.replace(minutes);
+        self.last_total = total;
+        (minutes, previous)
+    }
+}
+
+/// Render the digest message as HTML from this week's and last week's stats
+pub fn format_ocr_digest(
+    current: &OcrDigestStats,
+    previous: &OcrDigestStats,
+    week_end: DateTime<Utc>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let not_available = t_lang(localization, "ocr-digest-not-available", None);
+    let value_or_na = |value: Option<String>| value.unwrap_or_else(|| not_available.clone());
+    let percent = |stats: &OcrDigestStats| stats.ocr_success_rate.map(|rate| rate * 100.0);
+
+    let unit_candidates = current.unit_candidates.as_ref().map(|candidates| {
+        if candidates.is_empty() {
+            t_lang(localization, "ocr-digest-none", None)
+        } else {
+            candidates
+                .iter()
+                .map(|(word, count)| format!("{word} ({count})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    });
+
+    let lines = [
+        t_args_lang(
+            localization,
+            "ocr-digest-period",
+            &[("date", &week_end.format("%Y-%m-%d").to_string())],
+            None,
+        ),
+        String::new(),
+        format!(
+            "{}: {}{}",
+            t_lang(localization, "ocr-digest-success-rate", None),
+            value_or_na(percent(current).map(|rate| format!("{rate:.1}%"))),
+            format_delta(percent(current), percent(previous), 1)
+        ),
+        format!(
+            "{}: {}{}",
+            t_lang(localization, "ocr-digest-median-edits", None),
+            value_or_na(current.median_edits.map(|median| format!("{median:.1}"))),
+            format_delta(current.median_edits, previous.median_edits, 1)
+        ),
+        format!(
+            "{}: {}{}",
+            t_lang(localization, "ocr-digest-circuit-open", None),
+            value_or_na(
+                current
+                    .circuit_open_minutes
+                    .map(|minutes| format!("{minutes:.0} min"))
+            ),
+            format_delta(
+                current.circuit_open_minutes,
+                previous.circuit_open_minutes,
+                0
+            )
+        ),
+        format!(
+            "{}: {}",
+            t_lang(localization, "ocr-digest-unit-candidates", None),
+            value_or_na(unit_candidates)
+        ),
+    ];
+    HtmlMessage::new()
+        .text("📈 ")
+        .bold(&t_lang(localization, "ocr-digest-title", None))
+        .line(&lines.join("\n"))
+        .build()
+}
+
+/// Load the database-backed stats for `[from, to)`, degrading each failure to `None`
+pub async fn load_ocr_digest_stats(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> OcrDigestStats {
+    fn loaded<T>(result: Result<T>, stat: &str) -> Option<T> {
+        result
+            .map_err(|e| warn!(stat = %stat, error = %e, "Failed to load OCR digest stat"))
+            .ok()
+    }
+
+    let ocr_success_rate = loaded(
+        get_extraction_feedback_counts(pool, from, to).await,
+        "ocr_success_rate",
+    )
+    .and_then(|(reviews_shown, reports)| ocr_success_rate(reviews_shown, reports));
+    let median_edits = loaded(
+        get_median_edits_per_confirmed_review(pool, from, to).await,
+        "median_edits",
+    )
+    .flatten();
+    let unit_candidates = loaded(
+        get_unmatched_unit_candidates(pool, from, to, DIGEST_TOP_UNIT_CANDIDATES).await,
+        "unit_candidates",
+    );
+
+    OcrDigestStats {
+        ocr_success_rate,
+        median_edits,
+        unit_candidates,
+        circuit_open_minutes: None,
+    }
+}
+
+/// Build and send the digest for the week ending now to the first admin
+pub async fn send_ocr_digest(
+    bot: &Bot,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    circuit_tracker: &Mutex<CircuitOpenTracker>,
+) -> Result<()> {
+    let Some(admin_id) = first_admin_user() else {
+        debug!("No admin configured, skipping OCR digest");
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    let week_start = now - Duration::weeks(1);
+    let mut current = load_ocr_digest_stats(pool, week_start, now).await;
+    let mut previous =
+        load_ocr_digest_stats(pool, week_start - Duration::weeks(1), week_start).await;
+
+    let (open_minutes, previous_open_minutes) = circuit_tracker
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Circuit tracker lock poisoned"))?
+        .advance(ocr_circuit_open_duration());
+    current.circuit_open_minutes = Some(open_minutes);
+    previous.circuit_open_minutes = previous_open_minutes;
+
+    bot.send_message(
+        ChatId(admin_id),
+        format_ocr_digest(&current, &previous, now, localization),
+    )
+    .parse_mode(PARSE_MODE)
+    .await?;
+    info!(admin_id, "OCR digest sent");
+    Ok(())
+}
+
+/// Start the weekly digest task when OCR_DIGEST_ENABLED is set
+pub fn start_ocr_digest(
+    bot: Bot,
+    pool: Arc<PgPool>,
+    localization: Arc<LocalizationManager>,
+    config: OcrDigestConfig,
+) -> Option<JoinHandle<()>> {
+    if !config.enabled {
+        debug!("OCR digest disabled");
+        return None;
+    }
+
+    let circuit_tracker = Arc::new(Mutex::new(CircuitOpenTracker::default()));
+    Some(spawn_weekly("ocr_digest", config.schedule, move || {
+        let bot = bot.clone();
+        let pool = Arc::clone(&pool);
+        let localization = Arc::clone(&localization);
+        let circuit_tracker = Arc::clone(&circuit_tracker);
+        async move { send_ocr_digest(&bot, &pool, &localization, &circuit_tracker).await }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn strip_isolation(text: &str) -> String {
+        text.replace(['\u{2068}', '\u{2069}'], "")
+    }
+
+    fn week_end() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 12, 8, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_ocr_success_rate() {
+        assert_eq!(ocr_success_rate(0, 0), None);
+        assert_eq!(ocr_success_rate(20, 5), Some(0.75));
+        assert_eq!(ocr_success_rate(2, 3), Some(0.0));
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(Some(92.5), Some(90.0), 1), " (▲ 2.5)");
+        assert_eq!(format_delta(Some(1.0), Some(1.5), 1), " (▼ 0.5)");
+        assert_eq!(format_delta(Some(3.0), Some(3.04), 1), " (=)");
+        assert_eq!(format_delta(Some(3.0), None, 1), "");
+        assert_eq!(format_delta(None, Some(3.0), 1), "");
+    }
+
+    #[test]
+    fn test_circuit_tracker_splits_weeks() {
+        let mut tracker = CircuitOpenTracker::default();
+        let minutes = |m: u64| std::time::Duration::from_secs(m * 60);
+
+        assert_eq!(tracker.advance(minutes(12)), (12.0, None));
+        assert_eq!(tracker.advance(minutes(15)), (3.0, Some(12.0)));
+        assert_eq!(tracker.advance(minutes(15)), (0.0, Some(3.0)));
+    }
+
+    #[test]
+    fn test_format_full_digest() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let current = OcrDigestStats {
+            ocr_success_rate: Some(0.925),
+            median_edits: Some(1.0),
+            unit_candidates: Some(vec![("sachets".to_string(), 4), ("pincées".to_string(), 2)]),
+            circuit_open_minutes: Some(12.0),
+        };
+        let previous = OcrDigestStats {
+            ocr_success_rate: Some(0.9),
+            median_edits: Some(1.5),
+            unit_candidates: Some(Vec::new()),
+            circuit_open_minutes: Some(0.0),
+        };
+
+        let digest = strip_isolation(&format_ocr_digest(
+            &current,
+            &previous,
+            week_end(),
+            &localization,
+        ));
+        assert!(digest.contains("2026-10-12"));
+        assert!(digest.contains("92.5% (▲ 2.5)"));
+        assert!(digest.contains("1.0 (▼ 0.5)"));
+        assert!(digest.contains("12 min (▲ 12)"));
+        assert!(digest.contains("sachets (4), pincées (2)"));
+    }
+
+    #[test]
+    fn test_format_partial_digest() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let current = OcrDigestStats {
+            ocr_success_rate: Some(0.8),
+            unit_candidates: Some(Vec::new()),
+            ..OcrDigestStats::default()
+        };
+
+        let digest = strip_isolation(&format_ocr_digest(
+            &current,
+            &OcrDigestStats::default(),
+            week_end(),
+            &localization,
+        ));
+        let lines: Vec<&str> = digest.lines().collect();
+        assert!(
+            lines.contains(&"OCR success rate: 80.0%"),
+            "no delta without last week"
+        );
+        assert!(lines.contains(&"Median edits per confirmed recipe: n/a"));
+        assert!(lines.contains(&"Circuit breaker open: n/a"));
+        assert!(lines.contains(&"Top unmatched unit candidates: none"));
+    }
+}
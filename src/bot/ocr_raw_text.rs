@@ -0,0 +1,221 @@
+//! Raw OCR text shown on demand during ingredient review
+//!
+//! The review keyboard of an unsaved recipe has a "Show raw text" button sending
+//! the text kept in the `ReviewIngredients` state, in several messages when it is
+//! long, so the user can tell a bad photo from a few misread lines. The last
+//! message has a Hide button deleting them all. Neither button changes the
+//! dialogue state: the review goes on where it was.
+
+use anyhow::Result;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardMarkup, MessageId};
+use tracing::debug;
+
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_lang, LocalizationManager};
+
+use super::formatting::{
+    fit_lines, italic, split_message, HtmlMessage, MAX_MESSAGE_LENGTH, PARSE_MODE,
+};
+use super::ui_components::create_localized_button_with_emoji;
+
+/// Callback data of the "Show raw text" button of the review keyboard
+pub const SHOW_RAW_TEXT_CALLBACK: &str = "raw_text_show";
+
+/// Prefix of the Hide button data, followed by the ids of the other parts
+const HIDE_RAW_TEXT_PREFIX: &str = "raw_text_hide:";
+
+/// Most messages the raw text is sent in
+///
+/// Bounded so the ids of the other parts fit in the 64 bytes of callback data.
+pub const MAX_RAW_TEXT_PARTS: usize = 5;
+
+/// Split raw text into HTML messages of at most `max_length`, after a bold title
+///
+/// The text is escaped and keeps its lines. At most [`MAX_RAW_TEXT_PARTS`] messages
+/// are returned; when text is left out, the last one ends with `truncated`.
+pub fn raw_text_parts(title: &str, text: &str, truncated: &str, max_length: usize) -> Vec<String> {
+    let html = HtmlMessage::new()
+        .bold(title)
+        .paragraph(text.trim())
+        .build();
+    let mut parts = split_message(&html, max_length);
+    if parts.len() > MAX_RAW_TEXT_PARTS {
+        parts.truncate(MAX_RAW_TEXT_PARTS);
+        if let Some(last) = parts.last_mut() {
+            let note = italic(truncated);
+            *last = fit_lines(&format!("{last}\n{note}"), max_length, |_| note.clone());
+        }
+    }
+    parts
+}
+
+/// Callback data of the Hide button, listing the parts sent before its message
+pub fn hide_raw_text_callback(other_parts: &[MessageId]) -> String {
+    let ids: Vec<String> = other_parts.iter().map(|id| id.0.to_string()).collect();
+    format!("{HIDE_RAW_TEXT_PREFIX}{}", ids.join(","))
+}
+
+/// Parse the data of a Hide button into the ids of the other parts
+pub fn parse_hide_raw_text_callback(data: &str) -> Option<Vec<MessageId>> {
+    let ids = data.strip_prefix(HIDE_RAW_TEXT_PREFIX)?;
+    if ids.is_empty() {
+        return Some(Vec::new());
+    }
+    ids.split(',')
+        .map(|id| id.parse().ok().map(MessageId))
+        .collect()
+}
+
+/// Whether callback data comes from a Hide button
+pub fn is_hide_raw_text_callback(data: &str) -> bool {
+    data.starts_with(HIDE_RAW_TEXT_PREFIX)
+}
+
+/// OCR text of the review in progress, read without touching the dialogue state
+///
+/// This is the full text while it is still kept, not only the bounded copy held by
+/// the dialogue state.
+pub async fn raw_text_for_review(dialogue: &RecipeDialogue) -> Result<Option<String>> {
+    Ok(match dialogue.get().await? {
+        Some(RecipeDialogueState::ReviewIngredients { extracted_text, .. }) => Some(
+            crate::dialogue::full_review_text(dialogue.chat_id(), &extracted_text),
+        ),
+        _ => None,
+    })
+}
+
+/// Handle a tap on "Show raw text": send the OCR text of the review under it
+pub async fn handle_show_raw_text_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let Some(message) = q.message.as_ref() else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let text = raw_text_for_review(dialogue).await?.unwrap_or_default();
+    if text.trim().is_empty() {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "raw-text-empty", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let parts = raw_text_parts(
+        &format!(
+            "📄 {}",
+            t_lang(localization, "raw-text-title", language_code)
+        ),
+        &text,
+        &t_lang(localization, "raw-text-truncated", language_code),
+        MAX_MESSAGE_LENGTH,
+    );
+    debug!(user_id = %chat_id, parts = parts.len(), "Sending raw OCR text");
+
+    let mut sent = Vec::new();
+    let last = parts.len().saturating_sub(1);
+    for (index, part) in parts.into_iter().enumerate() {
+        let request = bot.send_message(chat_id, part).parse_mode(PARSE_MODE);
+        let message = if index == last {
+            request
+                .reply_markup(InlineKeyboardMarkup::new(vec![vec![
+                    create_localized_button_with_emoji(
+                        localization,
+                        "🙈",
+                        "raw-text-hide",
+                        hide_raw_text_callback(&sent),
+                        language_code,
+                    ),
+                ]]))
+                .await?
+        } else {
+            request.await?
+        };
+        sent.push(message.id);
+    }
+    Ok(())
+}
+
+/// Handle a tap on Hide: delete the raw text messages
+pub async fn handle_hide_raw_text_callback(bot: &Bot, q: &CallbackQuery, data: &str) -> Result<()> {
+    let (Some(other_parts), Some(message)) =
+        (parse_hide_raw_text_callback(data), q.message.as_ref())
+    else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+
+    for message_id in other_parts.into_iter().chain([message.id()]) {
+        // A part the user already deleted is no reason to keep the others
+        if let Err(e) = bot.delete_message(chat_id, message_id).await {
+            debug!(user_id = %chat_id, message_id = message_id.0, error = %e, "Could not delete raw text part");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::formatting::message_length;
+
+    #[test]
+    fn test_short_text_fits_in_one_escaped_part() {
+        let parts = raw_text_parts("Raw text", "2 cups flour\n<b>1 & 2</b>", "cut", 100);
+        assert_eq!(
+            parts,
+            vec!["<b>Raw text</b>\n\n2 cups flour\n&lt;b&gt;1 &amp; 2&lt;/b&gt;"]
+        );
+    }
+
+    #[test]
+    fn test_long_text_is_split_between_lines() {
+        let text: Vec<String> = (1..=40).map(|n| format!("{n} g ingredient {n}")).collect();
+        let parts = raw_text_parts("Raw text", &text.join("\n"), "cut", 200);
+
+        assert!(parts.len() > 1 && parts.len() <= MAX_RAW_TEXT_PARTS);
+        assert!(parts.iter().all(|part| message_length(part) <= 200));
+        assert!(parts[0].starts_with("<b>Raw text</b>"));
+        let lines: Vec<&str> = parts
+            .iter()
+            .flat_map(|part| part.lines())
+            .filter(|line| !line.is_empty() && !line.starts_with("<b>"))
+            .collect();
+        assert_eq!(lines, text);
+    }
+
+    #[test]
+    fn test_text_beyond_the_last_part_is_cut_with_a_note() {
+        let text: Vec<String> = (1..=500).map(|n| format!("{n} g ingredient {n}")).collect();
+        let parts = raw_text_parts("Raw text", &text.join("\n"), "The rest is cut", 200);
+
+        assert_eq!(parts.len(), MAX_RAW_TEXT_PARTS);
+        assert!(parts.iter().all(|part| message_length(part) <= 200));
+        assert!(parts.last().unwrap().ends_with("\n<i>The rest is cut</i>"));
+    }
+
+    #[test]
+    fn test_hide_callback_round_trip() {
+        let ids = [MessageId(41), MessageId(42), MessageId(2_147_483_647)];
+        let data = hide_raw_text_callback(&ids);
+        assert!(is_hide_raw_text_callback(&data));
+        assert_eq!(parse_hide_raw_text_callback(&data), Some(ids.to_vec()));
+        assert_eq!(
+            parse_hide_raw_text_callback(&hide_raw_text_callback(&[])),
+            Some(Vec::new())
+        );
+        assert_eq!(parse_hide_raw_text_callback("raw_text_hide:4,x"), None);
+        assert_eq!(parse_hide_raw_text_callback(SHOW_RAW_TEXT_CALLBACK), None);
+
+        // Four other parts still fit in Telegram's 64 bytes of callback data
+        let most = vec![MessageId(i32::MAX); MAX_RAW_TEXT_PARTS - 1];
+        assert!(hide_raw_text_callback(&most).len() <= 64);
+    }
+}
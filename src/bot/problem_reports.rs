@@ -0,0 +1,469 @@
+//! Problem Reports module for flagging bad extractions
+//!
+//! The "🚩 Report a problem" button on the review screen and on the "no ingredients"
+//! diagnostic asks for an optional comment, stores an extraction report and sends a
+//! redacted summary to the first admin. The admin can then ask the reporter for the
+//! original photo, which is only forwarded after the reporter agrees.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, FileId, InlineKeyboardMarkup, InputFile};
+use tracing::{debug, info, warn};
+
+use crate::db::{
+    create_extraction_report, get_extraction_report, get_user_by_telegram_id,
+    transition_report_consent, ExtractionReport, TelegramId,
+};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::errors::error_logging;
+use crate::extraction_reports::{
+    normalize_report_comment, recent_extraction, AdminReportSummary, ConsentEvent,
+    ExtractionContext, PhotoConsent, ReportCallback,
+};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+
+use super::command_handlers::{first_admin_user, is_admin_user};
+use super::ui_builder::{
+    create_report_comment_keyboard, create_report_photo_consent_keyboard,
+    create_report_photo_request_keyboard,
+};
+
+/// Pending report carried by the `AwaitingReportComment` dialogue state
+#[derive(Debug)]
+pub struct PendingReport {
+    pub context: ExtractionContext,
+    pub language_code: Option<String>,
+    pub prompt_message_id: Option<i32>,
+    pub resume_state: Box<RecipeDialogueState>,
+}
+
+/// Handle the buttons of the report flow, in any dialogue state
+pub async fn handle_report_callbacks(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    match ReportCallback::from_callback_data(data) {
+        Some(ReportCallback::Report) => start_report(bot, q, dialogue, localization).await,
+        Some(ReportCallback::SkipComment) => {
+            skip_report_comment(bot, q, pool, dialogue, localization).await
+        }
+        Some(ReportCallback::RequestPhoto(report_id)) => {
+            request_report_photo(bot, q, pool, report_id, localization).await
+        }
+        Some(ReportCallback::GrantPhoto(report_id)) => {
+            answer_photo_consent(bot, q, pool, report_id, true, localization).await
+        }
+        Some(ReportCallback::DeclinePhoto(report_id)) => {
+            answer_photo_consent(bot, q, pool, report_id, false, localization).await
+        }
+        None => Ok(()),
+    }
+}
+
+/// Ask for an optional comment about the latest extraction of the chat
+async fn start_report(
+    bot: &Bot,
+    q: &CallbackQuery,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+    let chat_id = msg.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    let current_state = dialogue.get().await?.unwrap_or_default();
+    if matches!(
+        current_state,
+        RecipeDialogueState::AwaitingReportComment { .. }
+    ) {
+        debug!(user_id = %chat_id, "Report already in progress, ignoring repeated tap");
+        return Ok(());
+    }
+
+    let Some(context) = recent_extraction(chat_id.0) else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "report-expired", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let prompt = bot
+        .send_message(
+            chat_id,
+            t_lang(localization, "report-comment-prompt", language_code),
+        )
+        .reply_markup(create_report_comment_keyboard(language_code, localization))
+        .await?;
+
+    info!(user_id = %chat_id, correlation_id = %context.correlation_id, "Extraction report started");
+    dialogue
+        .update(RecipeDialogueState::AwaitingReportComment {
+            context,
+            language_code: language_code.map(str::to_string),
+            prompt_message_id: Some(prompt.id.0),
+            resume_state: Box::new(current_state),
+        })
+        .await?;
+    Ok(())
+}
+
+/// Send the pending report without a comment
+async fn skip_report_comment(
+    bot: &Bot,
+    q: &CallbackQuery,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let Some(RecipeDialogueState::AwaitingReportComment {
+        context,
+        language_code,
+        prompt_message_id,
+        resume_state,
+    }) = dialogue.get().await?
+    else {
+        return Ok(());
+    };
+    let Some(msg) = &q.message else {
+        return Ok(());
+    };
+
+    submit_report(
+        bot,
+        msg.chat().id,
+        pool,
+        dialogue,
+        localization,
+        PendingReport {
+            context,
+            language_code,
+            prompt_message_id,
+            resume_state,
+        },
+        None,
+    )
+    .await
+}
+
+/// Handle the comment typed while a report is pending
+pub async fn handle_report_comment_input(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    comment: &str,
+    pending: PendingReport,
+) -> Result<()> {
+    let comment = normalize_report_comment(comment);
+    submit_report(
+        bot,
+        msg.chat.id,
+        pool,
+        &dialogue,
+        localization,
+        pending,
+        comment.as_deref(),
+    )
+    .await
+}
+
+/// Store the report, notify the admin and restore the interrupted dialogue state
+async fn submit_report(
+    bot: &Bot,
+    chat_id: ChatId,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    pending: PendingReport,
+    comment: Option<&str>,
+) -> Result<()> {
+    let PendingReport {
+        context,
+        language_code,
+        prompt_message_id,
+        resume_state,
+    } = pending;
+    let language_code = language_code.as_deref();
+
+    // The prompt's Skip button is no longer meaningful
+    if let Some(prompt_id) = prompt_message_id {
+        if let Err(e) = bot
+            .edit_message_reply_markup(chat_id, teloxide::types::MessageId(prompt_id))
+            .reply_markup(InlineKeyboardMarkup::new(Vec::<Vec<_>>::new()))
+            .await
+        {
+            debug!(error = %e, "Could not remove the report prompt keyboard");
+        }
+    }
+
+    dialogue.update(*resume_state).await?;
+
+    let report =
+        match create_extraction_report(pool, TelegramId(chat_id.0), &context, comment).await {
+            Ok(report) => report,
+            Err(e) => {
+                error_logging::log_database_error(
+                    &e,
+                    "create_extraction_report",
+                    Some(chat_id.0),
+                    None,
+                );
+                bot.send_message(
+                    chat_id,
+                    t_lang(localization, "error-report-failed", language_code),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+    notify_admin(bot, &report, localization).await;
+
+    bot.send_message(
+        chat_id,
+        t_lang(localization, "report-thanks", language_code),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Localized, redacted summary of a report for the admin
+pub fn format_admin_report_summary(
+    summary: &AdminReportSummary,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let comment = summary
+        .comment
+        .clone()
+        .unwrap_or_else(|| t_lang(localization, "report-admin-no-comment", language_code));
+    t_args_lang(
+        localization,
+        "report-admin-summary",
+        &[
+            ("report_id", &summary.report_id.to_string()),
+            ("user", &summary.masked_user),
+            ("correlation_id", &summary.correlation_id),
+            ("match_count", &summary.match_count.to_string()),
+            ("variant", &summary.preprocessing_variant),
+            ("text_hash", &summary.text_hash_prefix),
+            ("comment", &comment),
+        ],
+        language_code,
+    )
+}
+
+/// Send the redacted report summary to the first admin, if one is configured
+async fn notify_admin(
+    bot: &Bot,
+    report: &ExtractionReport,
+    localization: &Arc<LocalizationManager>,
+) {
+    let Some(admin_id) = first_admin_user() else {
+        debug!(
+            report_id = report.id,
+            "No admin configured, report stored only"
+        );
+        return;
+    };
+
+    let summary = AdminReportSummary::from_report(report);
+    let mut request = bot.send_message(
+        ChatId(admin_id),
+        format_admin_report_summary(&summary, None, localization),
+    );
+    if summary.has_photo {
+        request = request.reply_markup(create_report_photo_request_keyboard(
+            report.id,
+            None,
+            localization,
+        ));
+    }
+    if let Err(e) = request.await {
+        error_logging::log_network_error(&e, "notify_admin_of_report", None, None);
+    }
+}
+
+/// Admin asks the reporter whether the original photo may be shared
+async fn request_report_photo(
+    bot: &Bot,
+    q: &CallbackQuery,
+    pool: &PgPool,
+    report_id: i64,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let admin_id = q.from.id.0 as i64;
+    if !is_admin_user(admin_id) {
+        warn!(
+            user_id = admin_id,
+            report_id, "Non-admin tried to request a report photo"
+        );
+        return Ok(());
+    }
+    let admin_language = q.from.language_code.as_deref();
+
+    let Some(report) = get_extraction_report(pool, report_id).await? else {
+        return Ok(());
+    };
+    let current = PhotoConsent::from_name(&report.photo_consent);
+    let next = current.and_then(|consent| consent.apply(ConsentEvent::AdminRequested));
+    let (Some(current), Some(next), Some(_)) = (current, next, &report.photo_file_id) else {
+        bot.send_message(
+            ChatId(admin_id),
+            t_lang(localization, "report-photo-unavailable", admin_language),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if !transition_report_consent(pool, report_id, current.as_str(), next.as_str()).await? {
+        return Ok(());
+    }
+
+    // Ask in the reporter's stored language
+    let reporter_language = get_user_by_telegram_id(pool, report.telegram_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|user| user.language_code);
+    let reporter_language = reporter_language.as_deref();
+    bot.send_message(
+        ChatId(report.telegram_id.0),
+        t_args_lang(
+            localization,
+            "report-photo-consent-prompt",
+            &[("report_id", &report_id.to_string())],
+            reporter_language,
+        ),
+    )
+    .reply_markup(create_report_photo_consent_keyboard(
+        report_id,
+        reporter_language,
+        localization,
+    ))
+    .await?;
+
+    info!(report_id, "Photo consent requested from reporter");
+    bot.send_message(
+        ChatId(admin_id),
+        t_lang(localization, "report-photo-requested", admin_language),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Reporter answers the photo request; the photo is forwarded only on consent
+async fn answer_photo_consent(
+    bot: &Bot,
+    q: &CallbackQuery,
+    pool: &PgPool,
+    report_id: i64,
+    granted: bool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let user_id = q.from.id.0 as i64;
+    let language_code = q.from.language_code.as_deref();
+
+    let Some(report) = get_extraction_report(pool, report_id).await? else {
+        return Ok(());
+    };
+    if report.telegram_id.0 != user_id {
+        warn!(
+            user_id,
+            report_id, "User tried to answer consent for another user's report"
+        );
+        return Ok(());
+    }
+
+    let event = if granted {
+        ConsentEvent::UserGranted
+    } else {
+        ConsentEvent::UserDeclined
+    };
+    let Some(current) = PhotoConsent::from_name(&report.photo_consent) else {
+        return Ok(());
+    };
+    let Some(next) = current.apply(event) else {
+        debug!(
+            report_id,
+            consent = current.as_str(),
+            "Ignoring consent answer in this state"
+        );
+        return Ok(());
+    };
+    if !transition_report_consent(pool, report_id, current.as_str(), next.as_str()).await? {
+        return Ok(());
+    }
+
+    // Drop the consent buttons so the answer can't be given twice
+    if let Some(msg) = &q.message {
+        if let Err(e) = bot
+            .edit_message_reply_markup(msg.chat().id, msg.id())
+            .reply_markup(InlineKeyboardMarkup::new(Vec::<Vec<_>>::new()))
+            .await
+        {
+            debug!(error = %e, "Could not remove the consent keyboard");
+        }
+    }
+
+    if next.allows_photo_sharing() {
+        if let (Some(admin_id), Some(file_id)) = (first_admin_user(), &report.photo_file_id) {
+            forward_report_photo(bot, ChatId(admin_id), report_id, file_id, localization).await;
+        }
+        info!(report_id, "Reporter shared the photo");
+        bot.send_message(
+            ChatId(user_id),
+            t_lang(localization, "report-photo-shared", language_code),
+        )
+        .await?;
+    } else {
+        info!(report_id, "Reporter declined to share the photo");
+        bot.send_message(
+            ChatId(user_id),
+            t_lang(localization, "report-photo-declined", language_code),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Send the reported image to the admin, as a photo or as the original document
+async fn forward_report_photo(
+    bot: &Bot,
+    admin_chat: ChatId,
+    report_id: i64,
+    file_id: &str,
+    localization: &Arc<LocalizationManager>,
+) {
+    let caption = t_args_lang(
+        localization,
+        "report-photo-caption",
+        &[("report_id", &report_id.to_string())],
+        None,
+    );
+    let file = || InputFile::file_id(FileId(file_id.to_string()));
+
+    if bot
+        .send_photo(admin_chat, file())
+        .caption(caption.clone())
+        .await
+        .is_ok()
+    {
+        return;
+    }
+    // Images sent as documents can only be re-sent as documents
+    if let Err(e) = bot.send_document(admin_chat, file()).caption(caption).await {
+        error_logging::log_network_error(&e, "forward_report_photo", None, None);
+    }
+}
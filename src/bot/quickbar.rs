@@ -0,0 +1,349 @@
+//! Quickbar module for the optional persistent reply keyboard
+//!
+//! Users can enable a reply keyboard with the most common actions shown under the
+//! text input (`/quickbar on|off`). Telegram keeps a reply keyboard until it is
+//! removed, so it is attached when enabled and removed with `ReplyKeyboardRemove`
+//! when disabled. /start and /help apply the stored setting again, and a language
+//! change sends an enabled quickbar again with its buttons in the new language.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{KeyboardButton, KeyboardMarkup, KeyboardRemove, ReplyMarkup};
+
+use crate::db::TelegramId;
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::errors::error_logging;
+use crate::localization::{t_lang, LocalizationManager};
+
+/// Actions available from the quickbar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickbarAction {
+    NewRecipe,
+    MyRecipes,
+    Search,
+}
+
+impl QuickbarAction {
+    /// All quickbar actions in display order
+    pub const ALL: [QuickbarAction; 3] = [
+        QuickbarAction::NewRecipe,
+        QuickbarAction::MyRecipes,
+        QuickbarAction::Search,
+    ];
+
+    /// Localization key of the button text
+    pub fn text_key(self) -> &'static str {
+        match self {
+            QuickbarAction::NewRecipe => "quickbar-new-recipe",
+            QuickbarAction::MyRecipes => "quickbar-my-recipes",
+            QuickbarAction::Search => "quickbar-search",
+        }
+    }
+}
+
+/// Parse the argument of the /quickbar command
+///
+/// Returns `Some(true)` for `on`, `Some(false)` for `off` and `None` otherwise.
+pub fn parse_quickbar_command(text: &str) -> Option<bool> {
+    let argument = text.strip_prefix("/quickbar")?.trim().to_lowercase();
+    match argument.as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Create the quickbar reply keyboard in the user's language
+pub fn create_quickbar_keyboard(
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> KeyboardMarkup {
+    let [new_recipe, my_recipes, search] =
+        QuickbarAction::ALL.map(|action| t_lang(localization, action.text_key(), language_code));
+
+    KeyboardMarkup::new(vec![
+        vec![KeyboardButton::new(new_recipe)],
+        vec![KeyboardButton::new(my_recipes), KeyboardButton::new(search)],
+    ])
+    .resize_keyboard()
+    .persistent()
+}
+
+/// Reply markup that shows or removes the quickbar
+pub fn quickbar_markup(
+    enabled: bool,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> ReplyMarkup {
+    if enabled {
+        ReplyMarkup::Keyboard(create_quickbar_keyboard(localization, language_code))
+    } else {
+        ReplyMarkup::KeyboardRemove(KeyboardRemove::new())
+    }
+}
+
+/// Reply markup that applies the user's stored quickbar setting
+///
+/// `None` when the setting cannot be read, so the keyboard is left as it is.
+pub async fn stored_quickbar_markup(
+    pool: &PgPool,
+    chat_id: ChatId,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Option<ReplyMarkup> {
+    match crate::db::get_user_quickbar_enabled(pool, TelegramId(chat_id.0)).await {
+        Ok(enabled) => Some(quickbar_markup(enabled, localization, language_code)),
+        Err(e) => {
+            error_logging::log_database_error(
+                &e,
+                "get_user_quickbar_enabled",
+                Some(chat_id.0),
+                None,
+            );
+            None
+        }
+    }
+}
+
+/// Quickbar reply keyboard, `None` unless the user enabled it
+pub async fn enabled_quickbar_markup(
+    pool: &PgPool,
+    chat_id: ChatId,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Option<ReplyMarkup> {
+    stored_quickbar_markup(pool, chat_id, localization, language_code)
+        .await
+        .filter(|markup| matches!(markup, ReplyMarkup::Keyboard(_)))
+}
+
+/// Match a message against the quickbar button texts
+///
+/// Texts are compared in every supported language so buttons sent before a
+/// language change keep working.
+pub fn match_quickbar_action(
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+) -> Option<QuickbarAction> {
+    let text = text.trim();
+    localization
+        .supported_languages()
+        .iter()
+        .find_map(|language| {
+            QuickbarAction::ALL
+                .into_iter()
+                .find(|action| t_lang(localization, action.text_key(), Some(language)) == text)
+        })
+}
+
+/// Check whether quickbar buttons should be routed in the current dialogue state
+///
+/// States waiting for free text (recipe names, ingredient edits, ...) receive the
+/// button text as regular input instead.
+pub fn quickbar_applies_to_state(state: Option<&RecipeDialogueState>) -> bool {
+    matches!(
+        state,
+        None | Some(RecipeDialogueState::Start)
+            | Some(RecipeDialogueState::SelectingRecipesToDelete { .. })
+            | Some(RecipeDialogueState::AwaitingSearchQuery { .. })
+            | Some(RecipeDialogueState::TaggingRecipe { .. })
+    )
+}
+
+/// Handle the /quickbar on|off command
+pub async fn handle_quickbar_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let Some(enabled) = parse_quickbar_command(text) else {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "quickbar-usage", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if let Err(e) =
+        crate::db::set_user_quickbar_enabled(&pool, TelegramId(msg.chat.id.0), enabled).await
+    {
+        error_logging::log_database_error(
+            &e,
+            "set_user_quickbar_enabled",
+            Some(msg.chat.id.0),
+            None,
+        );
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "error-processing-failed", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let confirmation_key = if enabled {
+        "quickbar-enabled"
+    } else {
+        "quickbar-disabled"
+    };
+    bot.send_message(
+        msg.chat.id,
+        t_lang(localization, confirmation_key, language_code),
+    )
+    .reply_markup(quickbar_markup(enabled, localization, language_code))
+    .await?;
+
+    Ok(())
+}
+
+/// Handle a tap on one of the quickbar buttons
+pub async fn handle_quickbar_action(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    dialogue: &RecipeDialogue,
+    action: QuickbarAction,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    match action {
+        QuickbarAction::NewRecipe => {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, "quickbar-new-recipe-hint", language_code),
+            )
+            .await?;
+        }
+        QuickbarAction::MyRecipes => {
+            if let Some(user) = msg.from.as_ref() {
+                crate::observability::record_user_engagement_metrics(
+                    user.id.0 as i64,
+                    crate::observability::UserAction::RecipesCommand,
+                    None,
+                    language_code,
+                );
+            }
+            super::command_handlers::handle_recipes_command(
+                bot,
+                msg,
+                pool,
+                language_code,
+                localization,
+            )
+            .await?;
+        }
+        QuickbarAction::Search => {
+            super::recipe_search::start_recipe_search(
+                bot,
+                msg.chat.id,
+                dialogue,
+                localization,
+                language_code,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localization() -> Arc<LocalizationManager> {
+        Arc::new(LocalizationManager::new().expect("Failed to create localization manager"))
+    }
+
+    #[test]
+    fn test_parse_quickbar_command() {
+        assert_eq!(parse_quickbar_command("/quickbar on"), Some(true));
+        assert_eq!(parse_quickbar_command("/quickbar OFF"), Some(false));
+        assert_eq!(parse_quickbar_command("/quickbar"), None);
+        assert_eq!(parse_quickbar_command("/quickbar maybe"), None);
+        assert_eq!(parse_quickbar_command("/recipes"), None);
+    }
+
+    #[test]
+    fn test_quickbar_markup_round_trip() {
+        let localization = localization();
+
+        match quickbar_markup(true, &localization, Some("en")) {
+            ReplyMarkup::Keyboard(keyboard) => {
+                let texts: Vec<&str> = keyboard
+                    .keyboard
+                    .iter()
+                    .flatten()
+                    .map(|button| button.text.as_str())
+                    .collect();
+                assert_eq!(texts.len(), 3);
+                assert!(keyboard.is_persistent);
+                assert!(keyboard.resize_keyboard);
+                for text in texts {
+                    assert!(match_quickbar_action(&localization, text).is_some());
+                }
+            }
+            other => panic!("Expected reply keyboard, got {:?}", other),
+        }
+
+        assert!(matches!(
+            quickbar_markup(false, &localization, Some("en")),
+            ReplyMarkup::KeyboardRemove(_)
+        ));
+    }
+
+    #[test]
+    fn test_match_quickbar_action_per_language() {
+        let localization = localization();
+
+        for language in ["en", "fr"] {
+            for action in QuickbarAction::ALL {
+                let text = t_lang(&localization, action.text_key(), Some(language));
+                assert_eq!(
+                    match_quickbar_action(&localization, &text),
+                    Some(action),
+                    "{} button should match in {}",
+                    action.text_key(),
+                    language
+                );
+            }
+        }
+
+        // Localized texts differ, so buttons from before a language change still match
+        assert_ne!(
+            t_lang(&localization, "quickbar-search", Some("en")),
+            t_lang(&localization, "quickbar-search", Some("fr"))
+        );
+        assert_eq!(match_quickbar_action(&localization, "search"), None);
+        assert_eq!(match_quickbar_action(&localization, "2 cups flour"), None);
+    }
+
+    #[test]
+    fn test_quickbar_ignored_in_free_text_states() {
+        assert!(quickbar_applies_to_state(None));
+        assert!(quickbar_applies_to_state(Some(&RecipeDialogueState::Start)));
+
+        let waiting_for_name = RecipeDialogueState::WaitingForRecipeNameAfterConfirm {
+            ingredients: Vec::new(),
+            language_code: Some("en".to_string()),
+            extracted_text: String::new(),
+            recipe_name_from_caption: None,
+            message_id: None,
+            name_suggestions: Vec::new(),
+        };
+        assert!(!quickbar_applies_to_state(Some(&waiting_for_name)));
+
+        let renaming = RecipeDialogueState::RenamingRecipe {
+            recipe_id: 1,
+            current_name: "Soup".to_string(),
+            language_code: None,
+        };
+        assert!(!quickbar_applies_to_state(Some(&renaming)));
+    }
+}
@@ -0,0 +1,228 @@
+//! Recent activity view (`/recent`)
+//!
+//! Lists a user's latest saves, renames and ingredient edits, newest first. Each
+//! entry is a button opening the recipe's details; longer histories are paginated.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::MaybeInaccessibleMessage;
+use tracing::debug;
+
+use crate::db::{get_recent_activity, RecipeActivity, RecipeActivityKind, TelegramId};
+use crate::localization::{t_args_lang, t_lang, t_plural, LocalizationManager};
+
+use super::formatting::{HtmlMessage, PARSE_MODE};
+use super::ui_builder::create_recent_activity_keyboard;
+
+/// Activities shown per page
+pub const RECENT_ACTIVITY_PAGE_SIZE: i64 = 10;
+
+/// Format how long ago `then` was, relative to `now` ("2 days ago")
+pub fn format_relative_time(
+    then: DateTime<Utc>,
+    now: DateTime<Utc>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let elapsed = now.signed_duration_since(then);
+    let (key, count) = match elapsed.num_minutes() {
+        minutes if minutes < 1 => return t_lang(localization, "time-just-now", language_code),
+        minutes if minutes < 60 => ("time-minutes-ago", minutes),
+        _ if elapsed.num_hours() < 24 => ("time-hours-ago", elapsed.num_hours()),
+        _ if elapsed.num_days() < 30 => ("time-days-ago", elapsed.num_days()),
+        _ if elapsed.num_days() < 365 => ("time-months-ago", elapsed.num_days() / 30),
+        _ => ("time-years-ago", elapsed.num_days() / 365),
+    };
+    let count = count as usize;
+    t_plural(
+        localization,
+        key,
+        count,
+        &[("count", &count.to_string())],
+        language_code,
+    )
+}
+
+/// Render one activity line, e.g. `Saved "Tarte Tatin" (9 ingredients) — 2 days ago`
+pub fn format_activity_line(
+    activity: &RecipeActivity,
+    now: DateTime<Utc>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let when = format_relative_time(activity.occurred_at, now, language_code, localization);
+    let name = activity
+        .recipe_name
+        .clone()
+        .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+
+    match activity.kind {
+        RecipeActivityKind::Renamed => t_args_lang(
+            localization,
+            "recent-renamed",
+            &[
+                (
+                    "old_name",
+                    activity.previous_name.as_deref().unwrap_or_default(),
+                ),
+                ("new_name", &name),
+                ("when", &when),
+            ],
+            language_code,
+        ),
+        RecipeActivityKind::Saved | RecipeActivityKind::Edited => {
+            let key = if activity.kind == RecipeActivityKind::Saved {
+                "recent-saved"
+            } else {
+                "recent-edited"
+            };
+            let count = activity.ingredient_count.unwrap_or(0) as usize;
+            t_plural(
+                localization,
+                key,
+                count,
+                &[
+                    ("count", &count.to_string()),
+                    ("recipe_name", &name),
+                    ("when", &when),
+                ],
+                language_code,
+            )
+        }
+    }
+}
+
+/// Build the /recent message HTML for one page of activities
+pub fn format_recent_activity_message(
+    activities: &[RecipeActivity],
+    first_number: usize,
+    now: DateTime<Utc>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    activities
+        .iter()
+        .enumerate()
+        .fold(
+            HtmlMessage::titled("🕘", &t_lang(localization, "recent-title", language_code)),
+            |message, (index, activity)| {
+                message.paragraph(&format!(
+                    "{}. {}",
+                    first_number + index,
+                    format_activity_line(activity, now, language_code, localization)
+                ))
+            },
+        )
+        .build()
+}
+
+/// Handle the /recent command
+pub async fn handle_recent_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /recent command");
+
+    let (activities, total_count) = get_recent_activity(
+        &pool,
+        TelegramId(msg.chat.id.0),
+        RECENT_ACTIVITY_PAGE_SIZE,
+        0,
+    )
+    .await?;
+
+    if activities.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "recent-empty", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let message =
+        format_recent_activity_message(&activities, 1, Utc::now(), language_code, localization);
+    let keyboard = create_recent_activity_keyboard(
+        &activities,
+        1,
+        0,
+        total_count,
+        RECENT_ACTIVITY_PAGE_SIZE,
+        language_code,
+        localization,
+    );
+
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handle `recent_page:{page}` callbacks by editing the /recent message in place
+pub async fn handle_recent_pagination(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    data: &str,
+    pool: Arc<PgPool>,
+    language_code: &Option<String>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let page: usize = data
+        .strip_prefix("recent_page:")
+        .and_then(|page| page.parse().ok())
+        .unwrap_or(0);
+    debug!(page = %page, "Handling recent activity pagination");
+
+    let MaybeInaccessibleMessage::Regular(msg) = msg else {
+        return Ok(());
+    };
+
+    let offset = page as i64 * RECENT_ACTIVITY_PAGE_SIZE;
+    let (activities, total_count) = get_recent_activity(
+        &pool,
+        TelegramId(msg.chat.id.0),
+        RECENT_ACTIVITY_PAGE_SIZE,
+        offset,
+    )
+    .await?;
+
+    if activities.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "recent-empty", language_code.as_deref()),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let first_number = offset as usize + 1;
+    let message = format_recent_activity_message(
+        &activities,
+        first_number,
+        Utc::now(),
+        language_code.as_deref(),
+        localization,
+    );
+    let keyboard = create_recent_activity_keyboard(
+        &activities,
+        first_number,
+        page,
+        total_count,
+        RECENT_ACTIVITY_PAGE_SIZE,
+        language_code.as_deref(),
+        localization,
+    );
+
+    bot.edit_message_text(msg.chat.id, msg.id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,344 @@
+//! Recipe import from `/export` files
+//!
+//! Sending the bot a JSON document produced by `/export` (from this or another bot
+//! instance) recreates its recipes for the sender. Recipes already present with
+//! the same name and ingredients are skipped, so sending a file twice is harmless.
+//!
+//! Large files are imported in batches tracked by an import job, with a status
+//! message showing progress. When a file whose import was interrupted is sent
+//! again, the user chooses to resume it, start over or cancel it.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::{
+    CallbackQuery, Document, InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage,
+    ReplyParameters,
+};
+use tracing::{debug, info, warn};
+
+use crate::db::{
+    find_incomplete_import_job, finish_import_job, import_recipes, start_import_job, ImportJob,
+    TelegramId,
+};
+use crate::import_jobs::{resume_offset, ImportJobStatus, ImportResumeChoice};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::recipe_export::{parse_recipe_export, ExportedRecipe, MAX_IMPORT_FILE_BYTES};
+
+/// Whether an uploaded document should be treated as a recipe export
+pub fn is_json_document(file_name: Option<&str>, mime_type: Option<&str>) -> bool {
+    mime_type.is_some_and(|mime| mime == "application/json")
+        || file_name.is_some_and(|name| name.to_lowercase().ends_with(".json"))
+}
+
+/// Import the recipes of an uploaded export file
+pub async fn handle_recipe_import_document(
+    bot: &Bot,
+    msg: &Message,
+    document: &Document,
+    pool: Arc<PgPool>,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, size = document.file.size, "Received recipe import file");
+
+    if document.file.size > MAX_IMPORT_FILE_BYTES {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "import-file-too-large", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(recipes) =
+        download_export(bot, msg.chat.id, document, localization, language_code).await?
+    else {
+        return Ok(());
+    };
+
+    let telegram_id = TelegramId(msg.chat.id.0);
+    if let Some(job) =
+        find_incomplete_import_job(&pool, telegram_id, &document.file.unique_id.0).await?
+    {
+        // The prompt replies to the file so its buttons can download it again
+        let done = resume_offset(job.rows_processed, recipes.len());
+        info!(user_id = %msg.chat.id, job_id = job.id, done, "Offering to resume recipe import");
+        bot.send_message(
+            msg.chat.id,
+            t_args_lang(
+                localization,
+                "import-resume-prompt",
+                &[
+                    ("done", &done.to_string()),
+                    ("total", &recipes.len().to_string()),
+                ],
+                language_code,
+            ),
+        )
+        .reply_parameters(ReplyParameters::new(msg.id))
+        .reply_markup(create_import_resume_keyboard(
+            done,
+            localization,
+            language_code,
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let job = start_import_job(
+        &pool,
+        telegram_id,
+        &document.file.unique_id.0,
+        recipes.len() as i64,
+    )
+    .await?;
+    run_recipe_import(
+        bot,
+        msg.chat.id,
+        &pool,
+        &job,
+        &recipes,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+/// Handle the resume, start over and cancel buttons of an interrupted import
+///
+/// The prompt replies to the file, which is downloaded again to resume or restart.
+pub async fn handle_import_resume_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some(choice), Some(MaybeInaccessibleMessage::Regular(prompt))) = (
+        ImportResumeChoice::from_callback_data(data),
+        q.message.as_ref(),
+    ) else {
+        return Ok(());
+    };
+    let chat_id = prompt.chat.id;
+    let telegram_id = TelegramId(chat_id.0);
+    let language_code = q.from.language_code.as_deref();
+
+    let Some(document) = prompt
+        .reply_to_message()
+        .and_then(|original| original.document())
+    else {
+        debug!(user_id = %chat_id, "Import resume prompt without original file");
+        return Ok(());
+    };
+    let file_unique_id = &document.file.unique_id.0;
+
+    // A second tap, or the file's import already finished
+    let Some(job) = find_incomplete_import_job(pool, telegram_id, file_unique_id).await? else {
+        bot.edit_message_text(
+            chat_id,
+            prompt.id,
+            t_lang(localization, "import-resume-expired", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    if choice == ImportResumeChoice::Cancel {
+        finish_import_job(pool, job.id, ImportJobStatus::Cancelled.as_str()).await?;
+        info!(user_id = %chat_id, job_id = job.id, "Recipe import cancelled");
+        bot.edit_message_text(
+            chat_id,
+            prompt.id,
+            t_lang(localization, "import-cancelled", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.delete_message(chat_id, prompt.id).await?;
+    let Some(recipes) =
+        download_export(bot, chat_id, document, localization, language_code).await?
+    else {
+        return Ok(());
+    };
+    // Starting over keeps the claimed rows, so the committed prefix is skipped, not duplicated
+    let job = match choice {
+        ImportResumeChoice::StartOver => {
+            start_import_job(pool, telegram_id, file_unique_id, recipes.len() as i64).await?
+        }
+        _ => job,
+    };
+    run_recipe_import(
+        bot,
+        chat_id,
+        pool,
+        &job,
+        &recipes,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+/// Buttons offered for a file whose import was interrupted after `done` recipes
+pub fn create_import_resume_keyboard(
+    done: usize,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![
+        vec![InlineKeyboardButton::callback(
+            t_args_lang(
+                localization,
+                "import-resume-button",
+                &[("next", &(done + 1).to_string())],
+                language_code,
+            ),
+            ImportResumeChoice::Resume.callback_data(),
+        )],
+        vec![
+            InlineKeyboardButton::callback(
+                t_lang(localization, "import-start-over-button", language_code),
+                ImportResumeChoice::StartOver.callback_data(),
+            ),
+            InlineKeyboardButton::callback(
+                t_lang(localization, "import-cancel-button", language_code),
+                ImportResumeChoice::Cancel.callback_data(),
+            ),
+        ],
+    ])
+}
+
+/// Download and parse an export file, telling the user when it is not one
+async fn download_export(
+    bot: &Bot,
+    chat_id: ChatId,
+    document: &Document,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<Option<Vec<ExportedRecipe>>> {
+    let file = bot.get_file(document.file.id.clone()).await?;
+    let mut contents = Vec::with_capacity(file.size as usize);
+    bot.download_file(&file.path, &mut contents).await?;
+
+    match parse_recipe_export(&contents) {
+        Ok(export) => Ok(Some(export.recipes)),
+        Err(e) => {
+            warn!(user_id = %chat_id, error = %e, "Rejected recipe import file");
+            bot.send_message(
+                chat_id,
+                t_lang(localization, "import-invalid-file", language_code),
+            )
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Run an import job to completion, editing a status message as batches commit
+async fn run_recipe_import(
+    bot: &Bot,
+    chat_id: ChatId,
+    pool: &PgPool,
+    job: &ImportJob,
+    recipes: &[ExportedRecipe],
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let total = recipes.len().to_string();
+    let progress_text = |done: usize| {
+        t_args_lang(
+            localization,
+            "import-progress",
+            &[("done", &done.to_string()), ("total", &total)],
+            language_code,
+        )
+    };
+    let status = bot
+        .send_message(
+            chat_id,
+            progress_text(resume_offset(job.rows_processed, recipes.len())),
+        )
+        .await?;
+
+    let summary = import_recipes(pool, job, recipes, |done| {
+        let text = progress_text(done);
+        async move {
+            // Progress is informative only; a failed edit must not stop the import
+            if let Err(e) = bot.edit_message_text(chat_id, status.id, text).await {
+                debug!(user_id = %chat_id, error = %e, "Could not update import progress");
+            }
+        }
+    })
+    .await?;
+    info!(
+        user_id = %chat_id,
+        job_id = job.id,
+        created = summary.created,
+        skipped = summary.skipped,
+        "Recipe import finished"
+    );
+
+    bot.send_message(
+        chat_id,
+        t_args_lang(
+            localization,
+            "import-done",
+            &[
+                ("created", &summary.created.to_string()),
+                ("skipped", &summary.skipped.to_string()),
+            ],
+            language_code,
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_json_document() {
+        assert!(is_json_document(
+            Some("just-ingredients-export-2026-10-17.json"),
+            None
+        ));
+        assert!(is_json_document(Some("BACKUP.JSON"), Some("text/plain")));
+        assert!(is_json_document(None, Some("application/json")));
+        assert!(!is_json_document(Some("photo.jpg"), Some("image/jpeg")));
+        assert!(!is_json_document(None, None));
+    }
+
+    #[test]
+    fn test_import_resume_keyboard() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let keyboard = create_import_resume_keyboard(3500, &localization, Some("en"));
+
+        let buttons: Vec<_> = keyboard.inline_keyboard.iter().flatten().collect();
+        assert_eq!(buttons.len(), 3);
+        assert!(buttons[0].text.contains("3501"));
+        let choices: Vec<_> = buttons
+            .iter()
+            .filter_map(|button| match &button.kind {
+                teloxide::types::InlineKeyboardButtonKind::CallbackData(data) => {
+                    ImportResumeChoice::from_callback_data(data)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            choices,
+            vec![
+                ImportResumeChoice::Resume,
+                ImportResumeChoice::StartOver,
+                ImportResumeChoice::Cancel
+            ]
+        );
+    }
+}
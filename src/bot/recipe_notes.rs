@@ -0,0 +1,360 @@
+//! Notes the user keeps on saved recipes, behind the "Notes" button
+//!
+//! The button shows the current notes with Edit and Clear buttons. Edit moves the
+//! dialogue to `EditingRecipeNotes`, where the next text replaces the notes once
+//! it passes `crate::validation::validate_recipe_notes`. A preview of the notes is
+//! shown in the recipe details.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardMarkup, MaybeInaccessibleMessage};
+use tracing::{debug, info};
+
+use crate::cache::SharedCacheManager;
+use crate::db::{Recipe, RecipeId, TelegramId};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::validation::{validate_recipe_notes, MAX_RECIPE_NOTES_LENGTH};
+
+use super::formatting::{titled, PARSE_MODE};
+use super::ui_components::create_localized_button_with_emoji;
+
+/// A tap on one of the buttons of the notes view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotesCallback {
+    /// Ask for new notes, replacing the current ones
+    Edit { recipe_id: i64 },
+    /// Remove the notes
+    Clear { recipe_id: i64 },
+}
+
+/// Parse the data of a notes view button ("notes:edit:{recipe_id}" or "notes:clear:{recipe_id}")
+pub fn parse_notes_callback(data: &str) -> Option<NotesCallback> {
+    let (action, recipe_id) = data.strip_prefix("notes:")?.split_once(':')?;
+    let recipe_id = recipe_id.parse().ok()?;
+    match action {
+        "edit" => Some(NotesCallback::Edit { recipe_id }),
+        "clear" => Some(NotesCallback::Clear { recipe_id }),
+        _ => None,
+    }
+}
+
+/// Message explaining why notes were rejected by `validate_recipe_notes`
+pub fn invalid_notes_message(
+    error: &str,
+    notes: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    match error {
+        "too_long" => t_args_lang(
+            localization,
+            "notes-invalid-too-long",
+            &[
+                ("max", &MAX_RECIPE_NOTES_LENGTH.to_string()),
+                ("length", &notes.trim().chars().count().to_string()),
+            ],
+            language_code,
+        ),
+        _ => t_lang(localization, "notes-invalid-empty", language_code),
+    }
+}
+
+/// HTML of the notes view: recipe name and its notes in full
+pub fn format_notes_message(
+    recipe_name: &str,
+    notes: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    let none = t_lang(localization, "notes-none", language_code);
+    titled(
+        "🗒️",
+        &t_args_lang(
+            localization,
+            "notes-title",
+            &[("name", recipe_name)],
+            language_code,
+        ),
+        &[notes.unwrap_or(&none)],
+    )
+}
+
+/// Keyboard of the notes view, offering Clear only when there are notes
+pub fn create_recipe_notes_keyboard(
+    recipe_id: i64,
+    has_notes: bool,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineKeyboardMarkup {
+    let mut row = vec![create_localized_button_with_emoji(
+        localization,
+        "✏️",
+        "notes-edit",
+        format!("notes:edit:{}", recipe_id),
+        language_code,
+    )];
+    if has_notes {
+        row.push(create_localized_button_with_emoji(
+            localization,
+            "🧹",
+            "notes-clear",
+            format!("notes:clear:{}", recipe_id),
+            language_code,
+        ));
+    }
+    InlineKeyboardMarkup::new(vec![row])
+}
+
+/// A recipe of the chat, `None` when it is not theirs
+async fn own_recipe(pool: &PgPool, chat_id: ChatId, recipe_id: i64) -> Result<Option<Recipe>> {
+    Ok(crate::db::read_recipe_with_name(pool, RecipeId(recipe_id))
+        .await?
+        .filter(|recipe| recipe.telegram_id == TelegramId(chat_id.0)))
+}
+
+/// Text and keyboard of the notes view of a recipe
+fn notes_view(
+    recipe: &Recipe,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> (String, InlineKeyboardMarkup) {
+    (
+        format_notes_message(
+            recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
+            recipe.notes.as_deref(),
+            localization,
+            language_code,
+        ),
+        create_recipe_notes_keyboard(
+            recipe.id.0,
+            recipe.notes.is_some(),
+            localization,
+            language_code,
+        ),
+    )
+}
+
+/// Set or clear the notes of a recipe, evicting it from the cache when there is one
+async fn save_notes(
+    pool: &PgPool,
+    chat_id: ChatId,
+    recipe_id: i64,
+    notes: Option<&str>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<bool> {
+    let telegram_id = TelegramId(chat_id.0);
+    match cache {
+        Some(cache) => {
+            crate::db::update_recipe_notes_cached(
+                pool,
+                telegram_id,
+                RecipeId(recipe_id),
+                notes,
+                cache,
+            )
+            .await
+        }
+        None => crate::db::update_recipe_notes(pool, telegram_id, RecipeId(recipe_id), notes).await,
+    }
+}
+
+/// Send the notes view of a recipe, or tell the chat it is not theirs
+async fn send_notes_view(
+    bot: &Bot,
+    chat_id: ChatId,
+    pool: &PgPool,
+    recipe_id: i64,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let Some(recipe) = own_recipe(pool, chat_id, recipe_id).await? else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+    let (message, keyboard) = notes_view(&recipe, localization, language_code);
+    bot.send_message(chat_id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Handle the "Notes" button of the recipe details
+pub async fn handle_recipe_notes(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    recipe_id: i64,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let MaybeInaccessibleMessage::Regular(msg) = msg else {
+        return Ok(());
+    };
+    debug!(user_id = %msg.chat.id, recipe_id, "Showing recipe notes");
+    send_notes_view(
+        bot,
+        msg.chat.id,
+        pool,
+        recipe_id,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+/// Handle a tap on Edit or Clear in the notes view
+pub async fn handle_notes_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let (Some(callback), Some(message)) = (parse_notes_callback(data), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    match callback {
+        NotesCallback::Edit { recipe_id } => {
+            if own_recipe(pool, chat_id, recipe_id).await?.is_none() {
+                bot.send_message(
+                    chat_id,
+                    t_lang(localization, "recipe-not-found", language_code),
+                )
+                .await?;
+                return Ok(());
+            }
+            debug!(user_id = %chat_id, recipe_id, "Asking for recipe notes");
+            bot.send_message(
+                chat_id,
+                t_args_lang(
+                    localization,
+                    "notes-prompt",
+                    &[("max", &MAX_RECIPE_NOTES_LENGTH.to_string())],
+                    language_code,
+                ),
+            )
+            .await?;
+            dialogue
+                .update(RecipeDialogueState::EditingRecipeNotes {
+                    recipe_id,
+                    language_code: language_code.map(str::to_string),
+                })
+                .await?;
+        }
+        NotesCallback::Clear { recipe_id } => {
+            if !save_notes(pool, chat_id, recipe_id, None, cache).await? {
+                return Ok(());
+            }
+            info!(user_id = %chat_id, recipe_id, "Recipe notes cleared");
+            // Notes being typed for this recipe would bring back what was just cleared
+            if let Some(RecipeDialogueState::EditingRecipeNotes {
+                recipe_id: editing_id,
+                ..
+            }) = dialogue.get().await?
+            {
+                if editing_id == recipe_id {
+                    dialogue.update(RecipeDialogueState::Start).await?;
+                }
+            }
+            bot.send_message(
+                chat_id,
+                t_lang(localization, "notes-cleared", language_code),
+            )
+            .await?;
+            if let Some(recipe) = own_recipe(pool, chat_id, recipe_id).await? {
+                let (text, keyboard) = notes_view(&recipe, localization, language_code);
+                bot.edit_message_text(chat_id, message.id(), text)
+                    .parse_mode(PARSE_MODE)
+                    .reply_markup(keyboard)
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Save the notes typed while in `EditingRecipeNotes` and show them
+///
+/// Rejected notes are explained and the dialogue keeps waiting for new ones.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_notes_input(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    recipe_id: i64,
+    language_code: Option<&str>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let notes = match validate_recipe_notes(text) {
+        Ok(notes) => notes,
+        Err(error) => {
+            bot.send_message(
+                msg.chat.id,
+                invalid_notes_message(error, text, localization, language_code),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    dialogue.update(RecipeDialogueState::Start).await?;
+    if !save_notes(pool, msg.chat.id, recipe_id, Some(notes), cache).await? {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+    info!(user_id = %msg.chat.id, recipe_id, length = notes.chars().count(), "Recipe notes saved");
+    bot.send_message(
+        msg.chat.id,
+        t_lang(localization, "notes-saved", language_code),
+    )
+    .await?;
+    send_notes_view(
+        bot,
+        msg.chat.id,
+        pool,
+        recipe_id,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_notes_callbacks() {
+        assert_eq!(
+            parse_notes_callback("notes:edit:12"),
+            Some(NotesCallback::Edit { recipe_id: 12 })
+        );
+        assert_eq!(
+            parse_notes_callback("notes:clear:12"),
+            Some(NotesCallback::Clear { recipe_id: 12 })
+        );
+        assert_eq!(parse_notes_callback("notes:edit:abc"), None);
+        assert_eq!(parse_notes_callback("notes:share:12"), None);
+        assert_eq!(parse_notes_callback("tag:done:12"), None);
+    }
+}
@@ -0,0 +1,195 @@
+//! Nutrition estimate of a saved recipe behind the "Nutrition" button
+//!
+//! Sums the estimates of `crate::nutrition` over the recipe's ingredients and
+//! lists the ingredients left out, with why. The values are approximate, and the
+//! view says so.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardMarkup, MaybeInaccessibleMessage};
+use tracing::debug;
+
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::nutrition::{estimate_saved_recipe, RecipeNutrition, SkipReason};
+
+use super::formatting::{bold, HtmlMessage, PARSE_MODE};
+use super::scaled_recipes::load_own_recipe;
+use super::ui_components::create_back_button;
+
+/// Localization key of the reason an ingredient is left out
+fn skip_reason_key(reason: SkipReason) -> &'static str {
+    match reason {
+        SkipReason::NoQuantity => "nutrition-skip-no-quantity",
+        SkipReason::UnknownIngredient => "nutrition-skip-unknown-ingredient",
+        SkipReason::UnknownUnit => "nutrition-skip-unknown-unit",
+    }
+}
+
+/// Nutrition view of a recipe as HTML: totals, per-ingredient estimates, skipped ingredients
+pub fn format_nutrition_message(
+    nutrition: &RecipeNutrition,
+    recipe_name: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    let t = |key: &str| t_lang(localization, key, language_code);
+    let mut message = HtmlMessage::titled(
+        "📊",
+        &t_args_lang(
+            localization,
+            "nutrition-title",
+            &[("name", recipe_name)],
+            language_code,
+        ),
+    );
+
+    if nutrition.is_empty() {
+        message = message.paragraph(&t("nutrition-none"));
+    } else {
+        let total = nutrition.total;
+        message = message
+            .paragraph_html(&bold(&t("nutrition-total")))
+            .line(&format!(
+                "• {}: {:.0} kcal",
+                t("nutrition-calories"),
+                total.calories
+            ))
+            .line(&format!(
+                "• {}: {:.1} g",
+                t("nutrition-protein"),
+                total.protein
+            ))
+            .line(&format!("• {}: {:.1} g", t("nutrition-fat"), total.fat))
+            .line(&format!("• {}: {:.1} g", t("nutrition-carbs"), total.carbs))
+            .paragraph_html(&bold(&t("nutrition-per-ingredient")));
+        for estimate in &nutrition.estimated {
+            message = message.line(&format!(
+                "• {}: {:.0} g, {:.0} kcal",
+                estimate.name, estimate.grams, estimate.nutrients.calories
+            ));
+        }
+    }
+
+    if !nutrition.skipped.is_empty() {
+        message = message.paragraph("⚠️ ").bold(&t("nutrition-skipped"));
+        for (name, reason) in &nutrition.skipped {
+            message = message.line(&format!("• {} ({})", name, t(skip_reason_key(*reason))));
+        }
+    }
+
+    message
+        .paragraph(&format!("ℹ️ {}", t("nutrition-disclaimer")))
+        .build()
+}
+
+/// Show the nutrition estimate of one of the user's recipes
+pub async fn handle_recipe_nutrition(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    recipe_id: i64,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let MaybeInaccessibleMessage::Regular(msg) = msg else {
+        // Can't respond to inaccessible messages
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    let Some((recipe, ingredients)) = load_own_recipe(pool, chat_id, recipe_id).await? else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let nutrition = estimate_saved_recipe(&ingredients);
+    debug!(
+        user_id = %chat_id,
+        recipe_id,
+        estimated = nutrition.estimated.len(),
+        skipped = nutrition.skipped.len(),
+        "Showing recipe nutrition estimate"
+    );
+
+    let recipe_name = recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe");
+    let keyboard = InlineKeyboardMarkup::new(vec![vec![create_back_button(
+        localization,
+        "back_to_recipes".to_string(),
+        language_code,
+    )]]);
+    bot.send_message(
+        chat_id,
+        format_nutrition_message(&nutrition, recipe_name, localization, language_code),
+    )
+    .parse_mode(PARSE_MODE)
+    .reply_markup(keyboard)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nutrition::{IngredientEstimate, Nutrients};
+
+    #[test]
+    fn test_message_lists_totals_and_skipped_ingredients() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let flour = Nutrients {
+            calories: 910.0,
+            protein: 25.0,
+            fat: 2.5,
+            carbs: 190.4,
+        };
+        let nutrition = RecipeNutrition {
+            total: flour,
+            estimated: vec![IngredientEstimate {
+                name: "flour".to_string(),
+                grams: 250.0,
+                nutrients: flour,
+            }],
+            skipped: vec![("salt".to_string(), SkipReason::NoQuantity)],
+        };
+
+        let message = format_nutrition_message(&nutrition, "Bread", &localization, Some("en"));
+        assert!(message.contains("Bread"));
+        assert!(message.contains("910 kcal"));
+        assert!(message.contains("190.4 g"));
+        assert!(message.contains("• flour: 250 g, 910 kcal"));
+        assert!(message.contains("• salt (no quantity)"));
+        assert!(message.contains("Estimates only"));
+    }
+
+    #[test]
+    fn test_message_without_estimates_keeps_disclaimer() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let nutrition = RecipeNutrition {
+            skipped: vec![("dragon fruit".to_string(), SkipReason::UnknownIngredient)],
+            ..RecipeNutrition::default()
+        };
+
+        let message = format_nutrition_message(&nutrition, "Salad", &localization, Some("en"));
+        assert!(!message.contains("kcal"));
+        assert!(message.contains("dragon fruit"));
+        assert!(message.contains("Estimates only"));
+    }
+
+    #[test]
+    fn test_message_escapes_ingredient_names() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let nutrition = RecipeNutrition {
+            skipped: vec![("<b>salt & pepper</b>".to_string(), SkipReason::NoQuantity)],
+            ..RecipeNutrition::default()
+        };
+
+        let message = format_nutrition_message(&nutrition, "2*2 [test]", &localization, Some("en"));
+        assert!(message.contains("2*2 [test]"));
+        assert!(message.contains("• &lt;b&gt;salt &amp; pepper&lt;/b&gt; (no quantity)"));
+    }
+}
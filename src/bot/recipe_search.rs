@@ -0,0 +1,391 @@
+//! Recipe search behind the "Search Recipes" button
+//!
+//! The button (or the quickbar's search action) asks for a query and moves the
+//! dialogue to `AwaitingSearchQuery`; the next text message is searched in the
+//! user's recipe names, ingredient names and contents, stemmed in the user's
+//! language, and matching recipes are listed best match first with the passage that
+//! matched and the same keyboard as `/recipes`.
+//!
+//! `/find <ingredient>` lists the recipes using an ingredient instead, with how
+//! many of each recipe's ingredients matched.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::InlineKeyboardMarkup;
+use tracing::{debug, info};
+
+use crate::db::{
+    search_recipes, search_recipes_by_ingredient, IngredientSearchMatch, RecipeSearchMatch,
+    TelegramId,
+};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_args_lang, t_lang, t_plural, LocalizationManager};
+
+use super::formatting::{escape_html, titled, HtmlMessage, PARSE_MODE};
+use super::ui_builder::create_recipes_pagination_keyboard;
+use super::ui_components::create_localized_button_with_emoji;
+
+/// Most recipes listed for one search
+pub const SEARCH_RESULTS_LIMIT: usize = 10;
+
+/// Ask for a search query and wait for it
+pub async fn start_recipe_search(
+    bot: &Bot,
+    chat_id: ChatId,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %chat_id, "Waiting for recipe search query");
+    bot.send_message(
+        chat_id,
+        t_lang(localization, "search-prompt", language_code),
+    )
+    .await?;
+    dialogue
+        .update(RecipeDialogueState::AwaitingSearchQuery {
+            language_code: language_code.map(str::to_string),
+        })
+        .await?;
+    Ok(())
+}
+
+/// Names of the matching recipes with their headline, best match first and without repeats
+///
+/// Recipes sharing a name are listed once with the headline of their best match,
+/// since selecting a name already offers the choice between them.
+pub fn search_match_entries(matches: &[RecipeSearchMatch]) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    for found in matches {
+        let Some(name) = found.recipe.recipe_name.as_ref() else {
+            continue;
+        };
+        if !entries.iter().any(|(existing, _)| existing == name) {
+            entries.push((name.clone(), found.headline.clone()));
+        }
+    }
+    entries
+}
+
+/// HTML of a search headline, with the matched words in bold
+///
+/// The database marks matched words with `<b>` and `</b>`; everything else is
+/// recipe text and escaped.
+pub fn headline_html(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for (index, marked) in headline.split("<b>").enumerate() {
+        match marked.split_once("</b>") {
+            Some((word, rest)) if index > 0 => {
+                html.push_str(&format!("<b>{}</b>", escape_html(word)));
+                html.push_str(&escape_html(rest));
+            }
+            _ => html.push_str(&escape_html(marked)),
+        }
+    }
+    html
+}
+
+/// Recipe names of ingredient search matches with their matching ingredient count
+///
+/// Matches come sorted by count, so a name shared by several recipes keeps the count
+/// of its best match.
+pub fn ingredient_match_entries(matches: &[IngredientSearchMatch]) -> Vec<(String, i64)> {
+    let mut entries: Vec<(String, i64)> = Vec::new();
+    for found in matches {
+        let Some(name) = found.recipe.recipe_name.as_ref() else {
+            continue;
+        };
+        if !entries.iter().any(|(existing, _)| existing == name) {
+            entries.push((name.clone(), found.matching_ingredients));
+        }
+    }
+    entries
+}
+
+/// Handle the /find <ingredient> command
+pub async fn handle_find_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let ingredient = text.strip_prefix("/find").unwrap_or_default().trim();
+    if ingredient.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "find-usage", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if let Some(user) = msg.from.as_ref() {
+        crate::observability::record_user_engagement_metrics(
+            user.id.0 as i64,
+            crate::observability::UserAction::RecipeSearch,
+            None,
+            language_code,
+        );
+    }
+
+    let matches = search_recipes_by_ingredient(pool, TelegramId(msg.chat.id.0), ingredient).await?;
+    let entries = ingredient_match_entries(&matches);
+    info!(user_id = %msg.chat.id, result_count = entries.len(), "Ingredient search answered");
+
+    if entries.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            format!(
+                "🔍 {}",
+                t_args_lang(
+                    localization,
+                    "find-no-results",
+                    &[("ingredient", ingredient)],
+                    language_code
+                )
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let shown = &entries[..entries.len().min(SEARCH_RESULTS_LIMIT)];
+    let lines: Vec<String> = shown
+        .iter()
+        .map(|(name, count)| {
+            format!(
+                "• {} ({})",
+                name,
+                t_plural(
+                    localization,
+                    "find-result-count",
+                    *count as usize,
+                    &[],
+                    language_code
+                )
+            )
+        })
+        .collect();
+    let truncated = if entries.len() > shown.len() {
+        t_args_lang(
+            localization,
+            "search-results-truncated",
+            &[("shown", &shown.len().to_string())],
+            language_code,
+        )
+    } else {
+        String::new()
+    };
+    let message = titled(
+        "🔍",
+        &t_args_lang(
+            localization,
+            "find-results-title",
+            &[("ingredient", ingredient)],
+            language_code,
+        ),
+        &[&lines.join("\n"), &truncated],
+    );
+
+    let names: Vec<String> = shown.iter().map(|(name, _)| name.clone()).collect();
+    let keyboard = create_recipes_pagination_keyboard(
+        &names,
+        0,
+        names.len() as i64,
+        SEARCH_RESULTS_LIMIT as i64,
+        language_code,
+        localization,
+    );
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Run the search typed while in `AwaitingSearchQuery` and list the matches
+pub async fn handle_search_query(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let query = text.trim();
+    if query.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "search-empty-query", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+    dialogue.update(RecipeDialogueState::Start).await?;
+
+    if let Some(user) = msg.from.as_ref() {
+        crate::observability::record_user_engagement_metrics(
+            user.id.0 as i64,
+            crate::observability::UserAction::RecipeSearch,
+            None,
+            language_code,
+        );
+    }
+
+    let matches = search_recipes(pool, TelegramId(msg.chat.id.0), query).await?;
+    let entries = search_match_entries(&matches);
+    info!(user_id = %msg.chat.id, result_count = entries.len(), "Recipe search answered");
+
+    if entries.is_empty() {
+        let message = format!(
+            "🔍 {}\n\n{}",
+            t_args_lang(
+                localization,
+                "search-no-results",
+                &[("query", query)],
+                language_code
+            ),
+            t_lang(localization, "search-no-results-suggestion", language_code)
+        );
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![create_localized_button_with_emoji(
+            localization,
+            "📚",
+            "workflow-list-recipes",
+            "workflow_list_recipes".to_string(),
+            language_code,
+        )]]);
+        bot.send_message(msg.chat.id, message)
+            .reply_markup(keyboard)
+            .await?;
+        return Ok(());
+    }
+
+    let shown = &entries[..entries.len().min(SEARCH_RESULTS_LIMIT)];
+    let lines: Vec<String> = shown
+        .iter()
+        .map(|(name, headline)| {
+            if headline.trim().is_empty() {
+                format!("• {}", escape_html(name))
+            } else {
+                format!(
+                    "• {} — {}",
+                    escape_html(name),
+                    headline_html(headline.trim())
+                )
+            }
+        })
+        .collect();
+    let truncated = if entries.len() > shown.len() {
+        t_args_lang(
+            localization,
+            "search-results-truncated",
+            &[("shown", &shown.len().to_string())],
+            language_code,
+        )
+    } else {
+        String::new()
+    };
+    let message = HtmlMessage::titled(
+        "🔍",
+        &t_args_lang(
+            localization,
+            "search-results-title",
+            &[("query", query)],
+            language_code,
+        ),
+    )
+    .paragraph_html(&lines.join("\n"))
+    .paragraph(&t_lang(localization, "select-recipe", language_code))
+    .paragraph(&truncated)
+    .build();
+
+    // Results fit on a single page: the page buttons belong to the full recipe list
+    let names: Vec<String> = shown.iter().map(|(name, _)| name.clone()).collect();
+    let keyboard = create_recipes_pagination_keyboard(
+        &names,
+        0,
+        names.len() as i64,
+        SEARCH_RESULTS_LIMIT as i64,
+        language_code,
+        localization,
+    );
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Recipe, RecipeId};
+    use chrono::Utc;
+
+    fn recipe(id: i64, name: Option<&str>) -> Recipe {
+        Recipe {
+            id: RecipeId(id),
+            telegram_id: TelegramId(42),
+            content: String::new(),
+            recipe_name: name.map(str::to_string),
+            created_at: Utc::now(),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_search_match_entries_keep_best_headline() {
+        let found = |id, name, headline: &str| RecipeSearchMatch {
+            recipe: recipe(id, name),
+            rank: 0.1,
+            headline: headline.to_string(),
+        };
+        let matches = [
+            found(3, Some("Crêpes"), "<b>farine</b>, lait"),
+            found(2, None, "farine"),
+            found(1, Some("Gâteau"), "beurre, <b>farine</b>"),
+            found(0, Some("Crêpes"), "<b>farine</b>"),
+        ];
+        assert_eq!(
+            search_match_entries(&matches),
+            vec![
+                ("Crêpes".to_string(), "<b>farine</b>, lait".to_string()),
+                ("Gâteau".to_string(), "beurre, <b>farine</b>".to_string()),
+            ]
+        );
+        assert!(search_match_entries(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_headline_html_escapes_recipe_text() {
+        assert_eq!(
+            headline_html("<b>tomates</b> & basilic <3"),
+            "<b>tomates</b> &amp; basilic &lt;3"
+        );
+        assert_eq!(headline_html("a</b>b"), "a&lt;/b&gt;b");
+        assert_eq!(headline_html("<b>x"), "x");
+    }
+
+    #[test]
+    fn test_ingredient_match_entries_keep_best_count() {
+        let found = |id, name, matching_ingredients| IngredientSearchMatch {
+            recipe: recipe(id, name),
+            matching_ingredients,
+        };
+        let matches = [
+            found(1, Some("Croissants"), 2),
+            found(2, None, 2),
+            found(3, Some("Sablés"), 1),
+            found(4, Some("Croissants"), 1),
+        ];
+        assert_eq!(
+            ingredient_match_entries(&matches),
+            vec![("Croissants".to_string(), 2), ("Sablés".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Recipe sharing between users through deep links
+//!
+//! The "📤 Share" button of a recipe stores a random single-use token and replies
+//! with a `https://t.me/<bot>?start=share_<token>` link. Whoever opens the link sends
+//! `/start share_<token>`, which copies the recipe and its ingredients into their
+//! own account and shows the copy. The copy is independent of the original: editing
+//! or deleting one leaves the other untouched.
+//!
+//! Links expire after [`SHARE_LINK_TTL`] and work once; the sharer opening their own
+//! link gets a notice without using it up.
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::MaybeInaccessibleMessage;
+use tracing::{debug, info};
+
+use crate::db::{ShareRedemption, TelegramId};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+
+use super::formatting::{send_long_message, HtmlMessage};
+use super::scaled_recipes::load_own_recipe;
+use super::timezone_settings::display_timezone;
+use super::ui_builder::{create_recipe_details_keyboard, format_recipe_details};
+use super::unit_settings::display_units;
+
+/// How long a share link can be opened
+pub const SHARE_LINK_TTL: Duration = Duration::days(7);
+
+/// Start parameter prefix of share links
+pub const SHARE_START_PREFIX: &str = "share_";
+
+/// Length of a share token: 128 random bits in hex
+const SHARE_TOKEN_LENGTH: usize = 32;
+
+/// New random share token, safe to use in a start parameter
+pub fn generate_share_token() -> String {
+    format!(
+        "{:016x}{:016x}",
+        rand::random::<u64>(),
+        rand::random::<u64>()
+    )
+}
+
+/// Token of a `/start share_<token>` deep link, `None` for any other text
+pub fn parse_share_start_payload(text: &str) -> Option<&str> {
+    let payload = text.strip_prefix("/start")?;
+    if !payload.starts_with(' ') {
+        return None;
+    }
+    let token = payload.trim().strip_prefix(SHARE_START_PREFIX)?;
+    (token.len() == SHARE_TOKEN_LENGTH && token.chars().all(|c| c.is_ascii_hexdigit()))
+        .then_some(token)
+}
+
+/// Deep link opening the bot with a share token
+pub fn share_link(bot_username: &str, token: &str) -> String {
+    format!("https://t.me/{bot_username}?start={SHARE_START_PREFIX}{token}")
+}
+
+/// Handle the Share button of a recipe: create a token and send its link
+pub async fn handle_share_recipe(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    recipe_id: i64,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let MaybeInaccessibleMessage::Regular(msg) = msg else {
+        // Can't respond to inaccessible messages
+        return Ok(());
+    };
+    let chat_id = msg.chat.id;
+
+    let Some((recipe, _)) = load_own_recipe(pool, chat_id, recipe_id).await? else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let token = generate_share_token();
+    crate::db::create_recipe_share(
+        pool,
+        recipe.id,
+        TelegramId(chat_id.0),
+        &token,
+        Utc::now() + SHARE_LINK_TTL,
+    )
+    .await?;
+    let me = bot.get_me().await?;
+    debug!(user_id = %chat_id, recipe_id, "Share link created");
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "📤 {}\n\n{}",
+            t_args_lang(
+                localization,
+                "share-link-created",
+                &[
+                    (
+                        "recipe_name",
+                        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
+                    ),
+                    ("days", &SHARE_LINK_TTL.num_days().to_string()),
+                ],
+                language_code,
+            ),
+            share_link(me.username(), &token)
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Localization key of the notice for a share link that gave no copy
+fn redemption_error_key(redemption: ShareRedemption) -> &'static str {
+    match redemption {
+        ShareRedemption::Expired => "share-expired",
+        ShareRedemption::AlreadyUsed => "share-already-used",
+        ShareRedemption::OwnRecipe => "share-own-recipe",
+        ShareRedemption::NotFound | ShareRedemption::Copied(_) => "share-not-found",
+    }
+}
+
+/// Handle `/start share_<token>`: copy the shared recipe and show it
+pub async fn handle_share_start(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    token: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let chat_id = msg.chat.id;
+    let redemption =
+        crate::db::redeem_recipe_share(pool, token, TelegramId(chat_id.0), Utc::now()).await?;
+
+    let ShareRedemption::Copied(copy_id) = redemption else {
+        debug!(user_id = %chat_id, redemption = ?redemption, "Share link not redeemed");
+        bot.send_message(
+            chat_id,
+            t_lang(
+                localization,
+                redemption_error_key(redemption),
+                language_code,
+            ),
+        )
+        .await?;
+        return Ok(());
+    };
+    info!(user_id = %chat_id, recipe_id = %copy_id, "Shared recipe received");
+
+    let Some((recipe, ingredients)) = load_own_recipe(pool, chat_id, copy_id.0).await? else {
+        return Ok(());
+    };
+    send_long_message(
+        bot,
+        chat_id,
+        &HtmlMessage::new()
+            .text(&format!(
+                "📥 {}",
+                t_lang(localization, "share-received", language_code)
+            ))
+            .paragraph_html(&format_recipe_details(
+                &recipe,
+                &ingredients,
+                display_units(chat_id),
+                display_timezone(chat_id),
+                language_code,
+                localization,
+            ))
+            .build(),
+        // The copy does not keep the sender's photo
+        Some(create_recipe_details_keyboard(
+            copy_id.0,
+            false,
+            language_code,
+            localization,
+        )),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_token_round_trip() {
+        let token = generate_share_token();
+        assert_eq!(token.len(), SHARE_TOKEN_LENGTH);
+        assert_ne!(token, generate_share_token());
+
+        let link = share_link("ingredients_bot", &token);
+        let payload = link.split("?start=").nth(1).unwrap();
+        // Deep-link start parameters are limited to 64 characters
+        assert!(payload.len() <= 64);
+        assert_eq!(
+            parse_share_start_payload(&format!("/start {payload}")),
+            Some(token.as_str())
+        );
+    }
+
+    #[test]
+    fn test_parse_share_start_payload_rejects_other_text() {
+        let token = "0123456789abcdef0123456789abcdef";
+        assert_eq!(parse_share_start_payload("/start"), None);
+        assert_eq!(parse_share_start_payload("/start hello"), None);
+        assert_eq!(parse_share_start_payload(&format!("/start{token}")), None);
+        assert_eq!(parse_share_start_payload("/start share_abc"), None);
+        assert_eq!(
+            parse_share_start_payload("/start share_0123456789abcdef0123456789abcdeg"),
+            None
+        );
+        assert_eq!(
+            parse_share_start_payload(&format!("/help share_{token}")),
+            None
+        );
+    }
+}
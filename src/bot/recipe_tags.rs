@@ -0,0 +1,487 @@
+//! Tags of saved recipes, behind the "Tags" button and `/recipes <tag>`
+//!
+//! The button shows the user's existing tags with the ones on the recipe ticked,
+//! and moves the dialogue to `TaggingRecipe`: tapping a tag toggles it, and text
+//! sent meanwhile is added as a new tag (see `crate::validation::validate_tag_name`).
+//! `/recipes <tag>` lists only the recipes carrying that tag.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{
+    CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage,
+};
+use tracing::{debug, info};
+
+use crate::db::{RecipeId, TelegramId};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::validation::validate_tag_name;
+
+use super::formatting::{titled, PARSE_MODE};
+use super::ui_builder::create_tagged_recipes_keyboard;
+use super::ui_components::create_localized_button_with_emoji;
+
+/// Recipes listed per page of `/recipes <tag>`, as in the unfiltered list
+const TAGGED_RECIPES_PAGE_SIZE: i64 = 5;
+
+/// A tap on one of the buttons of the tags view
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagCallback<'a> {
+    /// Add the tag to the recipe, or remove it if already there
+    Toggle { recipe_id: i64, tag: &'a str },
+    /// Close the tags view
+    Done { recipe_id: i64 },
+}
+
+/// Parse the data of a tags view button ("tag:toggle:{recipe_id}:{tag}" or "tag:done:{recipe_id}")
+pub fn parse_tag_callback(data: &str) -> Option<TagCallback<'_>> {
+    let rest = data.strip_prefix("tag:")?;
+    if let Some(recipe_id) = rest.strip_prefix("done:") {
+        return Some(TagCallback::Done {
+            recipe_id: recipe_id.parse().ok()?,
+        });
+    }
+    let (recipe_id, tag) = rest.strip_prefix("toggle:")?.split_once(':')?;
+    Some(TagCallback::Toggle {
+        recipe_id: recipe_id.parse().ok()?,
+        tag,
+    })
+}
+
+/// Parse the data of a `/recipes <tag>` page button ("tag_page:{tag}:{page}")
+pub fn parse_tag_page_callback(data: &str) -> Option<(&str, usize)> {
+    let (tag, page) = data.strip_prefix("tag_page:")?.rsplit_once(':')?;
+    Some((tag, page.parse().ok()?))
+}
+
+/// Tag asked for by `/recipes <tag>`, `None` for a plain `/recipes`
+pub fn parse_recipes_tag_argument(text: &str) -> Option<&str> {
+    let argument = text.strip_prefix("/recipes ")?.trim();
+    (!argument.is_empty()).then_some(argument)
+}
+
+/// Localization key explaining why a tag was rejected by `validate_tag_name`
+fn invalid_tag_key(error: &str) -> &'static str {
+    match error {
+        "too_long" => "tags-invalid-too-long",
+        "invalid_characters" => "tags-invalid-characters",
+        _ => "tags-invalid-empty",
+    }
+}
+
+/// HTML of the tags view: recipe name, current tags and instructions
+pub fn format_tags_message(
+    recipe_name: &str,
+    recipe_tags: &[String],
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    let current = if recipe_tags.is_empty() {
+        t_lang(localization, "tags-none", language_code)
+    } else {
+        t_args_lang(
+            localization,
+            "tags-current",
+            &[("tags", &recipe_tags.join(", "))],
+            language_code,
+        )
+    };
+    titled(
+        "🏷️",
+        &t_args_lang(
+            localization,
+            "tags-title",
+            &[("name", recipe_name)],
+            language_code,
+        ),
+        &[
+            &current,
+            &t_lang(localization, "tags-instructions", language_code),
+        ],
+    )
+}
+
+/// Keyboard of the tags view: every tag of the user, ticked when on the recipe
+pub fn create_recipe_tags_keyboard(
+    recipe_id: i64,
+    user_tags: &[String],
+    recipe_tags: &[String],
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineKeyboardMarkup {
+    let mut buttons: Vec<Vec<InlineKeyboardButton>> = user_tags
+        .chunks(2)
+        .map(|row| {
+            row.iter()
+                .map(|tag| {
+                    let mark = if recipe_tags.contains(tag) {
+                        "✅"
+                    } else {
+                        "⬜"
+                    };
+                    InlineKeyboardButton::callback(
+                        format!("{} {}", mark, tag),
+                        format!("tag:toggle:{}:{}", recipe_id, tag),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+    buttons.push(vec![create_localized_button_with_emoji(
+        localization,
+        "✔️",
+        "tags-done",
+        format!("tag:done:{}", recipe_id),
+        language_code,
+    )]);
+    InlineKeyboardMarkup::new(buttons)
+}
+
+/// Name of a recipe of the chat, `None` when it is not theirs
+async fn own_recipe_name(pool: &PgPool, chat_id: ChatId, recipe_id: i64) -> Result<Option<String>> {
+    Ok(crate::db::read_recipe_with_name(pool, RecipeId(recipe_id))
+        .await?
+        .filter(|recipe| recipe.telegram_id == TelegramId(chat_id.0))
+        .map(|recipe| {
+            recipe
+                .recipe_name
+                .unwrap_or_else(|| "Unnamed Recipe".to_string())
+        }))
+}
+
+/// Text and keyboard of the tags view of a recipe, `None` when it is not the chat's
+async fn tags_view(
+    pool: &PgPool,
+    chat_id: ChatId,
+    recipe_id: i64,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<Option<(String, InlineKeyboardMarkup)>> {
+    let Some(recipe_name) = own_recipe_name(pool, chat_id, recipe_id).await? else {
+        return Ok(None);
+    };
+    let recipe_tags = crate::db::get_recipe_tags(pool, RecipeId(recipe_id)).await?;
+    let user_tags = crate::db::get_user_tags(pool, TelegramId(chat_id.0)).await?;
+    Ok(Some((
+        format_tags_message(&recipe_name, &recipe_tags, localization, language_code),
+        create_recipe_tags_keyboard(
+            recipe_id,
+            &user_tags,
+            &recipe_tags,
+            localization,
+            language_code,
+        ),
+    )))
+}
+
+/// Send the tags view of a recipe and wait for new tags in `TaggingRecipe`
+async fn send_tags_view(
+    bot: &Bot,
+    chat_id: ChatId,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    recipe_id: i64,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let Some((message, keyboard)) =
+        tags_view(pool, chat_id, recipe_id, localization, language_code).await?
+    else {
+        dialogue.update(RecipeDialogueState::Start).await?;
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+    bot.send_message(chat_id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    dialogue
+        .update(RecipeDialogueState::TaggingRecipe {
+            recipe_id,
+            language_code: language_code.map(str::to_string),
+        })
+        .await?;
+    Ok(())
+}
+
+/// Handle the "Tags" button of the recipe details
+pub async fn handle_recipe_tags(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    recipe_id: i64,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let MaybeInaccessibleMessage::Regular(msg) = msg else {
+        return Ok(());
+    };
+    debug!(user_id = %msg.chat.id, recipe_id, "Showing recipe tags");
+    send_tags_view(
+        bot,
+        msg.chat.id,
+        pool,
+        dialogue,
+        recipe_id,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+/// Add the tag typed while in `TaggingRecipe` and show the updated tags
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_new_tag_input(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    recipe_id: i64,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let tag = match validate_tag_name(text) {
+        Ok(tag) => tag,
+        Err(error) => {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, invalid_tag_key(error), language_code),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    if crate::db::add_recipe_tag(pool, TelegramId(msg.chat.id.0), RecipeId(recipe_id), &tag).await?
+    {
+        info!(user_id = %msg.chat.id, recipe_id, tag = %tag, "Recipe tagged");
+        bot.send_message(
+            msg.chat.id,
+            t_args_lang(localization, "tags-added", &[("tag", &tag)], language_code),
+        )
+        .await?;
+    }
+    send_tags_view(
+        bot,
+        msg.chat.id,
+        pool,
+        &dialogue,
+        recipe_id,
+        localization,
+        language_code,
+    )
+    .await
+}
+
+/// Handle a tap on a tag or on "Done" in the tags view
+pub async fn handle_tag_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    dialogue: &RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some(callback), Some(message)) = (parse_tag_callback(data), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    match callback {
+        TagCallback::Toggle { recipe_id, tag } => {
+            // Button data comes back from the client, so it is checked like typed tags
+            let Ok(tag) = validate_tag_name(tag) else {
+                return Ok(());
+            };
+            let telegram_id = TelegramId(chat_id.0);
+            let removed =
+                crate::db::remove_recipe_tag(pool, telegram_id, RecipeId(recipe_id), &tag).await?;
+            if !removed {
+                crate::db::add_recipe_tag(pool, telegram_id, RecipeId(recipe_id), &tag).await?;
+            }
+            debug!(user_id = %chat_id, recipe_id, tag = %tag, removed, "Recipe tag toggled");
+
+            let Some((text, keyboard)) =
+                tags_view(pool, chat_id, recipe_id, localization, language_code).await?
+            else {
+                return Ok(());
+            };
+            bot.edit_message_text(chat_id, message.id(), text)
+                .parse_mode(PARSE_MODE)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        TagCallback::Done { recipe_id } => {
+            debug!(user_id = %chat_id, recipe_id, "Closing recipe tags");
+            if let Some(RecipeDialogueState::TaggingRecipe { .. }) = dialogue.get().await? {
+                dialogue.update(RecipeDialogueState::Start).await?;
+            }
+            bot.edit_message_reply_markup(chat_id, message.id()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Page of the user's recipes carrying a tag, with its title and keyboard
+async fn tagged_recipes_page(
+    pool: &PgPool,
+    chat_id: ChatId,
+    tag: &str,
+    page: usize,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<Option<(String, InlineKeyboardMarkup)>> {
+    let (recipes, total_count) = crate::db::get_user_recipes_by_tag_paginated(
+        pool,
+        TelegramId(chat_id.0),
+        tag,
+        TAGGED_RECIPES_PAGE_SIZE,
+        page as i64 * TAGGED_RECIPES_PAGE_SIZE,
+    )
+    .await?;
+    if recipes.is_empty() {
+        return Ok(None);
+    }
+
+    let message = titled(
+        "🏷️",
+        &t_args_lang(
+            localization,
+            "tags-recipes-title",
+            &[("tag", tag)],
+            language_code,
+        ),
+        &[&t_lang(localization, "select-recipe", language_code)],
+    );
+    let keyboard = create_tagged_recipes_keyboard(
+        &recipes,
+        tag,
+        page,
+        total_count,
+        TAGGED_RECIPES_PAGE_SIZE,
+        language_code,
+        localization,
+    );
+    Ok(Some((message, keyboard)))
+}
+
+/// Handle `/recipes <tag>` by listing the recipes carrying the tag
+pub async fn handle_tagged_recipes_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    tag: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let tag = match validate_tag_name(tag) {
+        Ok(tag) => tag,
+        Err(error) => {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, invalid_tag_key(error), language_code),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+    debug!(user_id = %msg.chat.id, tag = %tag, "Handling /recipes <tag> command");
+
+    match tagged_recipes_page(pool, msg.chat.id, &tag, 0, localization, language_code).await? {
+        Some((message, keyboard)) => {
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(PARSE_MODE)
+                .reply_markup(keyboard)
+                .await?;
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                t_args_lang(
+                    localization,
+                    "tags-no-recipes",
+                    &[("tag", &tag)],
+                    language_code,
+                ),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle a page button of the `/recipes <tag>` list
+pub async fn handle_tagged_recipes_pagination(
+    bot: &Bot,
+    msg: &MaybeInaccessibleMessage,
+    data: &str,
+    pool: &PgPool,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some((tag, page)), MaybeInaccessibleMessage::Regular(msg)) =
+        (parse_tag_page_callback(data), msg)
+    else {
+        return Ok(());
+    };
+    debug!(tag = %tag, page, "Handling tagged recipes pagination");
+
+    if let Some((message, keyboard)) =
+        tagged_recipes_page(pool, msg.chat.id, tag, page, localization, language_code).await?
+    {
+        bot.edit_message_text(msg.chat.id, msg.id, message)
+            .parse_mode(PARSE_MODE)
+            .reply_markup(keyboard)
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag_callbacks() {
+        assert_eq!(
+            parse_tag_callback("tag:toggle:12:quick dinner"),
+            Some(TagCallback::Toggle {
+                recipe_id: 12,
+                tag: "quick dinner"
+            })
+        );
+        assert_eq!(
+            parse_tag_callback("tag:done:12"),
+            Some(TagCallback::Done { recipe_id: 12 })
+        );
+        assert_eq!(parse_tag_callback("tag:toggle:abc:dessert"), None);
+        assert_eq!(parse_tag_callback("tag_page:dessert:1"), None);
+
+        assert_eq!(parse_tag_page_callback("tag_page:a:b:2"), Some(("a:b", 2)));
+        assert_eq!(parse_tag_page_callback("page:2"), None);
+    }
+
+    #[test]
+    fn test_parse_recipes_tag_argument() {
+        assert_eq!(
+            parse_recipes_tag_argument("/recipes dessert"),
+            Some("dessert")
+        );
+        assert_eq!(
+            parse_recipes_tag_argument("/recipes  Quick Dinner "),
+            Some("Quick Dinner")
+        );
+        assert_eq!(parse_recipes_tag_argument("/recipes"), None);
+        assert_eq!(parse_recipes_tag_argument("/recipes   "), None);
+        assert_eq!(parse_recipes_tag_argument("/recipesdessert"), None);
+    }
+}
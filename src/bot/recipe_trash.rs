@@ -0,0 +1,308 @@
+//! Trash of deleted recipes (`/trash`)
+//!
+//! Deleting a recipe only moves it to the trash: it disappears from listings,
+//! searches and statistics but stays restorable, with its ingredients, for
+//! `db::TRASH_RETENTION_DAYS` days. `/trash` lists those recipes with a Restore
+//! button each. A background task started from main purges the recipes whose
+//! restore window is over with `db::delete_recipe`.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardButton, InlineKeyboardMarkup};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::cache::SharedCacheManager;
+use crate::db::{RecipeId, TelegramId, TrashedRecipe, TRASH_RETENTION_DAYS};
+use crate::errors::error_logging;
+use crate::localization::{t_args_lang, t_lang, t_plural, LocalizationManager};
+
+use super::formatting::{escape_html, HtmlMessage, PARSE_MODE};
+use super::recent_activity::format_relative_time;
+use super::ui_components::truncate_text;
+
+/// How often the purge task looks for recipes past their restore window
+pub const TRASH_PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Most trashed recipes offered as Restore buttons
+pub const TRASH_RESTORE_BUTTONS: usize = 10;
+
+/// Whole days left before a trashed recipe is purged, counting a started day
+pub fn days_left(deleted_at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let remaining = deleted_at + Duration::days(i64::from(TRASH_RETENTION_DAYS)) - now;
+    (remaining.num_seconds().max(0) + 86_399) / 86_400
+}
+
+/// Parse the data of a Restore button ("trash_restore:<recipe_id>")
+pub fn parse_restore_callback(data: &str) -> Option<RecipeId> {
+    data.strip_prefix("trash_restore:")?
+        .parse()
+        .ok()
+        .map(RecipeId)
+}
+
+fn recipe_name(
+    recipe: &TrashedRecipe,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    recipe
+        .recipe_name
+        .clone()
+        .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code))
+}
+
+/// Build the /trash message HTML, `None` when the trash is empty
+pub fn format_trash_message(
+    recipes: &[TrashedRecipe],
+    now: DateTime<Utc>,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> Option<String> {
+    if recipes.is_empty() {
+        return None;
+    }
+    let days = TRASH_RETENTION_DAYS.to_string();
+    let lines: Vec<String> = recipes
+        .iter()
+        .map(|recipe| {
+            let left = days_left(recipe.deleted_at, now) as usize;
+            format!(
+                "• {} ({})",
+                t_args_lang(
+                    localization,
+                    "trash-entry",
+                    &[
+                        (
+                            "recipe_name",
+                            &recipe_name(recipe, localization, language_code)
+                        ),
+                        (
+                            "when",
+                            &format_relative_time(
+                                recipe.deleted_at,
+                                now,
+                                language_code,
+                                localization
+                            )
+                        ),
+                    ],
+                    language_code,
+                ),
+                t_plural(
+                    localization,
+                    "trash-days-left",
+                    left,
+                    &[("count", &left.to_string())],
+                    language_code,
+                )
+            )
+        })
+        .collect();
+
+    Some(
+        HtmlMessage::titled("🗑️", &t_lang(localization, "trash-title", language_code))
+            .paragraph(&t_args_lang(
+                localization,
+                "trash-intro",
+                &[("days", &days)],
+                language_code,
+            ))
+            .paragraph(&lines.join("\n"))
+            .build(),
+    )
+}
+
+/// One Restore button per trashed recipe, last deleted first
+pub fn create_trash_keyboard(
+    recipes: &[TrashedRecipe],
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(recipes.iter().take(TRASH_RESTORE_BUTTONS).map(|recipe| {
+        vec![InlineKeyboardButton::callback(
+            truncate_text(
+                &format!(
+                    "♻️ {}",
+                    t_args_lang(
+                        localization,
+                        "trash-restore-button",
+                        &[(
+                            "recipe_name",
+                            &recipe_name(recipe, localization, language_code)
+                        )],
+                        language_code,
+                    )
+                ),
+                40,
+            ),
+            format!("trash_restore:{}", recipe.id),
+        )]
+    }))
+}
+
+/// Handle the /trash command
+pub async fn handle_trash_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /trash command");
+
+    let recipes = crate::db::get_trashed_recipes(pool, TelegramId(msg.chat.id.0)).await?;
+    match format_trash_message(&recipes, Utc::now(), language_code, localization) {
+        Some(message) => {
+            bot.send_message(msg.chat.id, message)
+                .parse_mode(PARSE_MODE)
+                .reply_markup(create_trash_keyboard(&recipes, language_code, localization))
+                .await?;
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                t_args_lang(
+                    localization,
+                    "trash-empty",
+                    &[("days", &TRASH_RETENTION_DAYS.to_string())],
+                    language_code,
+                ),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle a tap on a Restore button of the /trash list
+///
+/// The list is redrawn without the restored recipe, under a confirmation.
+pub async fn handle_trash_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    cache: Option<&SharedCacheManager>,
+) -> Result<()> {
+    let (Some(recipe_id), Some(message)) = (parse_restore_callback(data), q.message.as_ref())
+    else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let telegram_id = TelegramId(chat_id.0);
+    let language_code = q.from.language_code.as_deref();
+
+    // Read the name first: trashed recipes are hidden from the usual lookups
+    let trashed = crate::db::get_trashed_recipes(pool, telegram_id).await?;
+    let name = trashed
+        .iter()
+        .find(|recipe| recipe.id == recipe_id)
+        .map(|recipe| recipe_name(recipe, localization, language_code));
+
+    let restored = match cache {
+        Some(cache) => crate::db::restore_recipe_cached(pool, telegram_id, recipe_id, cache).await,
+        None => crate::db::restore_recipe(pool, telegram_id, recipe_id).await,
+    };
+    let confirmation = match restored {
+        Ok(true) => {
+            let name =
+                name.unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+            info!(user_id = %chat_id, recipe_id = %recipe_id, "Recipe restored from trash");
+            t_args_lang(
+                localization,
+                "trash-restored",
+                &[("recipe_name", &name)],
+                language_code,
+            )
+        }
+        Ok(false) => t_lang(localization, "trash-restore-expired", language_code),
+        Err(e) => {
+            error_logging::log_database_error(
+                &e,
+                "restore_recipe",
+                Some(chat_id.0),
+                Some(&[("recipe_id", &recipe_id.to_string())]),
+            );
+            t_lang(localization, "error-processing-failed", language_code)
+        }
+    };
+
+    let remaining = crate::db::get_trashed_recipes(pool, telegram_id).await?;
+    let text = match format_trash_message(&remaining, Utc::now(), language_code, localization) {
+        Some(list) => format!("{}\n\n{list}", escape_html(&confirmation)),
+        None => escape_html(&confirmation),
+    };
+    bot.edit_message_text(chat_id, message.id(), text)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(create_trash_keyboard(
+            &remaining,
+            language_code,
+            localization,
+        ))
+        .await?;
+    Ok(())
+}
+
+/// Permanently delete the recipes trashed more than the retention period before `now`
+pub async fn run_trash_purge(pool: &PgPool, now: DateTime<Utc>) -> Result<usize> {
+    let cutoff = now - Duration::days(i64::from(TRASH_RETENTION_DAYS));
+    let purged = crate::db::purge_trashed_recipes(pool, cutoff).await?;
+    if !purged.is_empty() {
+        info!(purged = purged.len(), cutoff = %cutoff, "Purged recipes from the trash");
+    }
+    Ok(purged.len())
+}
+
+/// Start the task purging expired trashed recipes, until `shutdown` is cancelled
+pub fn start_trash_purge(pool: Arc<PgPool>, shutdown: CancellationToken) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TRASH_PURGE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            if let Err(e) = run_trash_purge(&pool, Utc::now()).await {
+                warn!(error = %e, "Trash purge failed");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_days_left_counts_started_days() {
+        assert_eq!(days_left(at(10, 12), at(10, 12)), 7);
+        assert_eq!(days_left(at(10, 12), at(11, 11)), 7);
+        assert_eq!(days_left(at(10, 12), at(11, 12)), 6);
+        assert_eq!(days_left(at(10, 12), at(17, 11)), 1);
+        assert_eq!(days_left(at(10, 12), at(17, 12)), 0);
+        assert_eq!(days_left(at(10, 12), at(20, 0)), 0);
+    }
+
+    #[test]
+    fn test_parse_restore_callback() {
+        assert_eq!(
+            parse_restore_callback("trash_restore:42"),
+            Some(RecipeId(42))
+        );
+        assert_eq!(parse_restore_callback("trash_restore:abc"), None);
+        assert_eq!(parse_restore_callback("recipe_instance:42"), None);
+    }
+}
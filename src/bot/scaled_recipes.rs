@@ -0,0 +1,339 @@
+//! Scaled view of a saved recipe behind the "Scale recipe" button
+//!
+//! The button asks for a factor and moves the dialogue to `ScalingRecipe`; the
+//! next text message is read as the factor and the recipe's ingredients are shown
+//! with their quantities multiplied (see `crate::recipe_scaling`). The view does
+//! not change the recipe, but offers to save the scaled quantities as a new one.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{CallbackQuery, InlineKeyboardMarkup};
+use tracing::{debug, info};
+
+use crate::db::{Ingredient, Recipe, RecipeId, TelegramId};
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::recipe_scaling::{
+    format_scale_factor, parse_scale_factor, scale_ingredients, scaled_recipe_name,
+    ScaledIngredients,
+};
+use crate::text_processing::{MeasurementMatch, UnitSystem};
+use crate::unit_conversion::display_measurement;
+
+use super::formatting::{titled, PARSE_MODE};
+use super::ui_components::{create_back_button, create_localized_button_with_emoji};
+use super::unit_settings::display_units;
+
+/// Saved ingredients in the form scaling works on
+///
+/// Ingredients saved without a quantity keep an empty one, so they are reported
+/// as not scaled instead of counting as one unit.
+pub fn saved_ingredients_as_matches(ingredients: &[Ingredient]) -> Vec<MeasurementMatch> {
+    let mut matches = crate::ingredient_editing::ingredients_to_measurement_matches(ingredients);
+    for (ingredient, saved) in matches.iter_mut().zip(ingredients) {
+        if saved.quantity.is_none() {
+            ingredient.quantity.clear();
+        }
+    }
+    matches
+}
+
+/// Ingredient list of a scaled recipe, marking the ingredients left unscaled
+pub fn format_scaled_ingredients_list(
+    scaled: &ScaledIngredients,
+    units: Option<UnitSystem>,
+) -> String {
+    let lines: Vec<String> = scaled
+        .ingredients
+        .iter()
+        .zip(&scaled.values)
+        .map(|(ingredient, value)| {
+            let unit = ingredient.measurement.as_deref().unwrap_or("");
+            let (quantity, unit) = match value {
+                Some(_) => display_measurement(&ingredient.quantity, unit, units),
+                None => (ingredient.quantity.clone(), unit.to_string()),
+            };
+            let mut parts: Vec<&str> = [quantity.as_str(), unit.as_str()]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect();
+            parts.push(&ingredient.ingredient_name);
+            let marker = if value.is_none() { " ⚠️" } else { "" };
+            format!("• {}{}", parts.join(" "), marker)
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Load a recipe of the chat with its ingredients, `None` when it is not theirs
+pub(crate) async fn load_own_recipe(
+    pool: &PgPool,
+    chat_id: ChatId,
+    recipe_id: i64,
+) -> Result<Option<(Recipe, Vec<Ingredient>)>> {
+    let Some(recipe) = crate::db::read_recipe_with_name(pool, RecipeId(recipe_id)).await? else {
+        return Ok(None);
+    };
+    if recipe.telegram_id != TelegramId(chat_id.0) {
+        return Ok(None);
+    }
+    let ingredients = crate::db::get_recipe_ingredients(pool, recipe.id).await?;
+    Ok(Some((recipe, ingredients)))
+}
+
+/// Show the recipe scaled by the factor typed while in `ScalingRecipe`
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_scale_factor_input(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    dialogue: RecipeDialogue,
+    localization: &Arc<LocalizationManager>,
+    text: &str,
+    recipe_id: i64,
+    language_code: Option<&str>,
+) -> Result<()> {
+    // Round to the precision kept in the save button, so the view and the copy agree
+    let Some(factor) = parse_scale_factor(text)
+        .and_then(|factor| format_scale_factor(factor).parse::<f64>().ok())
+        .filter(|factor| *factor > 0.0)
+    else {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "scale-invalid-factor", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+    dialogue.update(RecipeDialogueState::Start).await?;
+
+    let Some((recipe, ingredients)) = load_own_recipe(pool, msg.chat.id, recipe_id).await? else {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let scaled = scale_ingredients(&saved_ingredients_as_matches(&ingredients), factor);
+    debug!(
+        user_id = %msg.chat.id,
+        recipe_id,
+        factor,
+        unscaled = scaled.unscaled_count(),
+        "Showing scaled recipe"
+    );
+
+    let factor_text = format_scale_factor(factor);
+    let recipe_name = recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe");
+    let footnote = if scaled.unscaled_count() > 0 {
+        t_lang(localization, "scale-unscaled-footnote", language_code)
+    } else {
+        String::new()
+    };
+    let message = titled(
+        "⚖️",
+        &t_args_lang(
+            localization,
+            "scale-title",
+            &[("name", recipe_name), ("factor", &factor_text)],
+            language_code,
+        ),
+        &[
+            &format_scaled_ingredients_list(&scaled, display_units(msg.chat.id)),
+            &footnote,
+        ],
+    );
+
+    let keyboard = InlineKeyboardMarkup::new(vec![
+        vec![create_localized_button_with_emoji(
+            localization,
+            "💾",
+            "scale-save-as-new",
+            format!("scale_save:{}:{}", recipe_id, factor_text),
+            language_code,
+        )],
+        vec![create_back_button(
+            localization,
+            "back_to_recipes".to_string(),
+            language_code,
+        )],
+    ]);
+    bot.send_message(msg.chat.id, message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    Ok(())
+}
+
+/// Parse the data of a "Save as new recipe" button ("scale_save:{recipe_id}:{factor}")
+pub fn parse_scale_save_callback(data: &str) -> Option<(i64, f64)> {
+    let (recipe_id, factor) = data.strip_prefix("scale_save:")?.split_once(':')?;
+    Some((recipe_id.parse().ok()?, parse_scale_factor(factor)?))
+}
+
+/// Handle a tap on "Save as new recipe" by saving the scaled copy
+pub async fn handle_scale_save_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    cache: Option<&crate::cache::SharedCacheManager>,
+) -> Result<()> {
+    let (Some((recipe_id, factor)), Some(message)) =
+        (parse_scale_save_callback(data), q.message.as_ref())
+    else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    let Some((recipe, ingredients)) = load_own_recipe(pool, chat_id, recipe_id).await? else {
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "recipe-not-found", language_code),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let scaled = scale_ingredients(&saved_ingredients_as_matches(&ingredients), factor);
+    let name = scaled_recipe_name(
+        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
+        factor,
+    );
+
+    let user = match cache {
+        Some(cache) => {
+            let backend = cache.lock().backend();
+            crate::db::get_or_create_user_cached(
+                pool,
+                TelegramId(chat_id.0),
+                language_code,
+                backend.as_ref(),
+            )
+            .await?
+        }
+        None => crate::db::get_or_create_user(pool, TelegramId(chat_id.0), language_code).await?,
+    };
+    let new_recipe_id =
+        crate::db::create_recipe(pool, TelegramId(chat_id.0), &recipe.content).await?;
+    match cache {
+        Some(cache) => {
+            crate::db::update_recipe_name_cached(
+                pool,
+                TelegramId(chat_id.0),
+                new_recipe_id,
+                &name,
+                cache,
+            )
+            .await?
+        }
+        None => crate::db::update_recipe_name(pool, new_recipe_id, &name).await?,
+    };
+    for ((saved, ingredient), value) in ingredients
+        .iter()
+        .zip(&scaled.ingredients)
+        .zip(&scaled.values)
+    {
+        let raw_text = [
+            ingredient.quantity.as_str(),
+            ingredient.measurement.as_deref().unwrap_or(""),
+            ingredient.ingredient_name.as_str(),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+        crate::db::create_ingredient(
+            pool,
+            user.id,
+            Some(new_recipe_id),
+            &saved.name,
+            value.or(saved.quantity),
+            saved.unit.as_deref(),
+            &raw_text,
+        )
+        .await?;
+    }
+    info!(
+        user_id = %chat_id,
+        recipe_id,
+        new_recipe_id = %new_recipe_id,
+        factor,
+        "Saved scaled recipe copy"
+    );
+
+    bot.edit_message_reply_markup(chat_id, message.id()).await?;
+    bot.send_message(
+        chat_id,
+        format!(
+            "✅ {}",
+            t_args_lang(
+                localization,
+                "scale-saved",
+                &[("name", &name)],
+                language_code
+            )
+        ),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::UserId;
+    use chrono::Utc;
+
+    fn saved(name: &str, quantity: Option<f64>, unit: Option<&str>) -> Ingredient {
+        let now = Utc::now();
+        Ingredient {
+            id: 1,
+            user_id: UserId(1),
+            recipe_id: Some(RecipeId(1)),
+            name: name.to_string(),
+            quantity,
+            unit: unit.map(str::to_string),
+            created_at: now,
+            updated_at: now,
+            unit_dimension: None,
+            unit_system: None,
+        }
+    }
+
+    #[test]
+    fn test_scaled_list_marks_ingredients_without_quantity() {
+        let ingredients = [
+            saved("sugar", Some(0.333), Some("cup")),
+            saved("salt", None, None),
+            saved("eggs", Some(3.0), None),
+        ];
+        let scaled = scale_ingredients(&saved_ingredients_as_matches(&ingredients), 2.0);
+        assert_eq!(scaled.unscaled_count(), 1);
+        assert_eq!(
+            format_scaled_ingredients_list(&scaled, None),
+            "• 2/3 cup sugar\n• salt ⚠️\n• 6 eggs"
+        );
+    }
+
+    #[test]
+    fn test_parse_scale_save_callback() {
+        assert_eq!(
+            parse_scale_save_callback("scale_save:12:2"),
+            Some((12, 2.0))
+        );
+        assert_eq!(
+            parse_scale_save_callback("scale_save:12:0.5"),
+            Some((12, 0.5))
+        );
+        assert_eq!(parse_scale_save_callback("scale_save:12:0"), None);
+        assert_eq!(parse_scale_save_callback("scale_save:abc:2"), None);
+        assert_eq!(parse_scale_save_callback("units:metric"), None);
+    }
+}
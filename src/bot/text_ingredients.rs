@@ -0,0 +1,193 @@
+//! Ingredient review for text messages
+//!
+//! Ingredient lists forwarded from other bots or channels, or pasted with several
+//! measurements, go through the same review as a photo's OCR text. Formatting
+//! entities are applied first (see `crate::message_entities`), so struck-through
+//! items and links never reach extraction.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use tracing::{debug, info};
+
+use crate::db::TelegramId;
+use crate::dialogue::{RecipeDialogue, RecipeDialogueState};
+use crate::experiments::{resolve_review_keyboard_variant, track_review_funnel_event, FunnelEvent};
+use crate::extraction_reports::{remember_extraction, ExtractionContext};
+use crate::localization::{t_lang, LocalizationManager};
+use crate::message_entities::apply_message_entities;
+
+use super::formatting::{HtmlMessage, PARSE_MODE};
+use super::image_processing::process_ingredients_and_extract_matches;
+use super::ui_builder::{
+    create_ingredient_review_keyboard_for_variant, format_review_cap_notice, format_review_message,
+};
+use super::unit_settings::display_units;
+use super::HandlerContext;
+
+/// Measurements an unforwarded message needs before it is treated as an ingredient list
+pub const MIN_PASTED_INGREDIENTS: usize = 2;
+
+/// Configured minimum, never below one
+///
+/// Set with `PASTED_INGREDIENTS_MIN_MATCHES`, defaults to [`MIN_PASTED_INGREDIENTS`].
+pub fn min_pasted_ingredients() -> usize {
+    crate::config::current()
+        .bot
+        .pasted_ingredients_min_matches
+        .max(1)
+}
+
+/// Whether a text message with `match_count` measurements is an ingredient list
+///
+/// A single measurement is enough for a forwarded message; pasted or typed text
+/// needs `min_pasted` so ordinary messages mentioning a quantity are not reviewed.
+pub fn is_ingredient_list(match_count: usize, forwarded: bool, min_pasted: usize) -> bool {
+    let min_ingredients = if forwarded { 1 } else { min_pasted.max(1) };
+    match_count >= min_ingredients
+}
+
+/// Whether free text can start an ingredient review in this dialogue state
+///
+/// Only outside of any dialogue: text sent while another step waits for input, or
+/// while recipes are being selected, is never taken as a new ingredient list.
+pub fn accepts_ingredient_text(state: Option<&RecipeDialogueState>) -> bool {
+    matches!(state, None | Some(RecipeDialogueState::Start))
+}
+
+/// Start an ingredient review from a text message
+///
+/// Returns `false`, without replying, when the message does not look like an
+/// ingredient list so the caller can answer it as ordinary text.
+pub async fn handle_ingredient_text_message(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &RecipeDialogue,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<bool> {
+    let Some(text) = msg.text() else {
+        return Ok(false);
+    };
+    if !accepts_ingredient_text(dialogue.get().await?.as_ref()) {
+        return Ok(false);
+    }
+    let cleaned = apply_message_entities(text, msg.entities().unwrap_or_default());
+    let ingredients = process_ingredients_and_extract_matches(&cleaned.text, language_code);
+
+    if !is_ingredient_list(
+        ingredients.len(),
+        msg.forward_origin().is_some(),
+        min_pasted_ingredients(),
+    ) {
+        return Ok(false);
+    }
+    debug!(
+        user_id = %msg.chat.id,
+        section_headers = cleaned.section_headers.len(),
+        "Reviewing ingredients from text message"
+    );
+
+    start_text_review(
+        &HandlerContext {
+            bot,
+            localization,
+            language_code,
+        },
+        msg,
+        &cleaned.text,
+        ingredients,
+        Some(&t_lang(
+            localization,
+            "text-ingredients-detected",
+            language_code,
+        )),
+        dialogue,
+        pool,
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Show the review keyboard for ingredients extracted from text rather than a photo
+///
+/// Shared by pasted or forwarded lists and transcribed voice messages. `intro`,
+/// when given, is shown above the review.
+pub(crate) async fn start_text_review(
+    ctx: &HandlerContext<'_>,
+    msg: &Message,
+    text: &str,
+    ingredients: Vec<crate::text_processing::MeasurementMatch>,
+    intro: Option<&str>,
+    dialogue: &RecipeDialogue,
+    pool: &PgPool,
+) -> Result<()> {
+    let HandlerContext {
+        bot,
+        localization,
+        language_code,
+    } = *ctx;
+    let cap_notice = format_review_cap_notice(ingredients.len(), language_code, localization);
+    let ingredients = crate::dialogue::cap_review_ingredients(ingredients);
+    remember_extraction(
+        msg.chat.id.0,
+        ExtractionContext::new(text, ingredients.len(), None, None),
+    );
+
+    let review_message = format_review_message(
+        &ingredients,
+        None,
+        display_units(msg.chat.id),
+        language_code,
+        localization,
+    );
+    let review_message = match intro {
+        Some(intro) => HtmlMessage::new()
+            .text(intro)
+            .paragraph_html(&review_message)
+            .build(),
+        None => review_message,
+    };
+    let review_message = match cap_notice {
+        Some(notice) => HtmlMessage::new()
+            .html(&review_message)
+            .paragraph(&notice)
+            .build(),
+        None => review_message,
+    };
+    let telegram_id = TelegramId(msg.chat.id.0);
+    let variant = resolve_review_keyboard_variant(pool, telegram_id).await;
+    let keyboard = create_ingredient_review_keyboard_for_variant(
+        &ingredients,
+        language_code,
+        localization,
+        variant,
+    );
+
+    let sent_message = bot
+        .send_message(msg.chat.id, review_message)
+        .parse_mode(PARSE_MODE)
+        .reply_markup(keyboard)
+        .await?;
+    track_review_funnel_event(pool, telegram_id, FunnelEvent::ReviewShown).await;
+    crate::extraction_feedback::start_review(telegram_id, ingredients.len());
+
+    let extracted_text = crate::dialogue::review_state_text(msg.chat.id, text, &ingredients);
+    info!(user_id = %msg.chat.id, ingredients_count = ingredients.len(), "Text ingredients review sent");
+    dialogue
+        .update(RecipeDialogueState::ReviewIngredients {
+            recipe_name: "Recipe".to_string(),
+            ingredients,
+            language_code: language_code.map(str::to_string),
+            message_id: Some(sent_message.id.0),
+            extracted_text,
+            recipe_name_from_caption: None,
+            last_deleted: None,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+//! Time zone settings module for the `/timezone` command
+//!
+//! Lets users see dates, and the "today" and "this week" counts of their
+//! statistics, in their own time zone (see `crate::timezone`). `/timezone` offers a
+//! few common offsets as buttons, `/timezone <offset>` sets any other one. The
+//! choice is stored in the users table and kept in memory per chat, so the
+//! formatters can read it without a database round trip.
+
+use anyhow::Result;
+use chrono::FixedOffset;
+use lazy_static::lazy_static;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use tracing::debug;
+
+use crate::db::TelegramId;
+use crate::errors::error_logging;
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::timezone::{format_utc_offset, offset_from_minutes, offset_minutes, parse_utc_offset};
+
+lazy_static! {
+    /// Time zone of every chat seen since startup
+    static ref TIMEZONES: Mutex<HashMap<i64, FixedOffset>> = Mutex::new(HashMap::new());
+}
+
+/// Offsets offered as buttons by /timezone, in minutes east of UTC, with a city using each
+const TIMEZONE_CHOICES: [(i32, &str); 12] = [
+    (-8 * 60, "Los Angeles"),
+    (-5 * 60, "New York"),
+    (-3 * 60, "São Paulo"),
+    (0, "London"),
+    (60, "Paris"),
+    (2 * 60, "Athens"),
+    (3 * 60, "Moscow"),
+    (5 * 60 + 30, "Mumbai"),
+    (8 * 60, "Singapore"),
+    (9 * 60, "Tokyo"),
+    (10 * 60, "Sydney"),
+    (12 * 60, "Auckland"),
+];
+
+/// Buttons per row of the /timezone keyboard
+const CHOICES_PER_ROW: usize = 3;
+
+/// Time zone dates should be displayed in for a chat
+///
+/// UTC until the user picks one. Only reads the in-memory copy filled by
+/// `load_timezone`.
+pub fn display_timezone(chat_id: ChatId) -> FixedOffset {
+    TIMEZONES
+        .lock()
+        .ok()
+        .and_then(|timezones| timezones.get(&chat_id.0).copied())
+        .unwrap_or_else(crate::timezone::utc)
+}
+
+fn remember_timezone(chat_id: ChatId, offset: FixedOffset) {
+    if let Ok(mut timezones) = TIMEZONES.lock() {
+        timezones.insert(chat_id.0, offset);
+    }
+}
+
+/// Forget a chat's in-memory time zone, so it is read again from the database
+pub fn forget_timezone(chat_id: ChatId) {
+    if let Ok(mut timezones) = TIMEZONES.lock() {
+        timezones.remove(&chat_id.0);
+    }
+}
+
+/// Load a chat's time zone from the database the first time it is seen
+///
+/// Lookup failures leave dates in UTC and are retried on the next update.
+pub async fn load_timezone(pool: &PgPool, chat_id: ChatId) {
+    let cached = TIMEZONES
+        .lock()
+        .map(|timezones| timezones.contains_key(&chat_id.0))
+        .unwrap_or(false);
+    if cached {
+        return;
+    }
+    match crate::db::get_user_timezone(pool, TelegramId(chat_id.0)).await {
+        Ok(offset) => remember_timezone(chat_id, offset.unwrap_or_else(crate::timezone::utc)),
+        Err(e) => error_logging::log_database_error(&e, "get_user_timezone", Some(chat_id.0), None),
+    }
+}
+
+/// What the /timezone command asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimezoneCommand {
+    /// Show the current time zone and the choices
+    Show,
+    /// Use this offset
+    Set(FixedOffset),
+}
+
+/// Parse the /timezone command and its optional offset argument
+///
+/// Returns `None` for text that is not a /timezone command or has an invalid offset.
+pub fn parse_timezone_command(text: &str) -> Option<TimezoneCommand> {
+    let argument = text.strip_prefix("/timezone")?;
+    if !argument.is_empty() && !argument.starts_with(' ') {
+        return None;
+    }
+    if argument.trim().is_empty() {
+        return Some(TimezoneCommand::Show);
+    }
+    parse_utc_offset(argument).map(TimezoneCommand::Set)
+}
+
+/// Parse the data of a /timezone keyboard button (`timezone:{minutes}`)
+pub fn parse_timezone_callback(data: &str) -> Option<FixedOffset> {
+    offset_from_minutes(data.strip_prefix("timezone:")?.parse().ok()?)
+}
+
+fn timezone_keyboard(current: FixedOffset) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = TIMEZONE_CHOICES
+        .iter()
+        .map(|(minutes, city)| {
+            let marker = if *minutes == offset_minutes(current) {
+                "✅ "
+            } else {
+                ""
+            };
+            let offset = offset_from_minutes(*minutes).expect("choices are valid offsets");
+            InlineKeyboardButton::callback(
+                format!("{marker}{} · {city}", format_utc_offset(offset)),
+                format!("timezone:{minutes}"),
+            )
+        })
+        .collect();
+    InlineKeyboardMarkup::new(buttons.chunks(CHOICES_PER_ROW).map(<[_]>::to_vec))
+}
+
+/// Store a chat's new time zone, returning the confirmation to show
+async fn set_timezone(
+    pool: &PgPool,
+    chat_id: ChatId,
+    offset: FixedOffset,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    if let Err(e) = crate::db::set_user_timezone(pool, TelegramId(chat_id.0), offset).await {
+        error_logging::log_database_error(&e, "set_user_timezone", Some(chat_id.0), None);
+        return t_lang(localization, "error-processing-failed", language_code);
+    }
+    remember_timezone(chat_id, offset);
+    t_args_lang(
+        localization,
+        "timezone-set",
+        &[("zone", &format_utc_offset(offset))],
+        language_code,
+    )
+}
+
+/// Handle the /timezone and /timezone <offset> commands
+pub async fn handle_timezone_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let command = parse_timezone_command(text);
+    debug!(user_id = %msg.chat.id, command = ?command, "Handling /timezone command");
+
+    match command {
+        Some(TimezoneCommand::Show) => {
+            load_timezone(pool, msg.chat.id).await;
+            let current = display_timezone(msg.chat.id);
+            bot.send_message(
+                msg.chat.id,
+                format!(
+                    "{}\n\n{}",
+                    t_args_lang(
+                        localization,
+                        "timezone-prompt",
+                        &[("zone", &format_utc_offset(current))],
+                        language_code,
+                    ),
+                    t_lang(localization, "timezone-dst-note", language_code)
+                ),
+            )
+            .reply_markup(timezone_keyboard(current))
+            .await?;
+        }
+        Some(TimezoneCommand::Set(offset)) => {
+            let confirmation =
+                set_timezone(pool, msg.chat.id, offset, localization, language_code).await;
+            bot.send_message(msg.chat.id, confirmation).await?;
+        }
+        None => {
+            bot.send_message(
+                msg.chat.id,
+                t_lang(localization, "timezone-invalid", language_code),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle a tap on one of the /timezone buttons
+pub async fn handle_timezone_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some(offset), Some(message)) = (parse_timezone_callback(data), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    let confirmation = set_timezone(pool, chat_id, offset, localization, language_code).await;
+    bot.edit_message_text(chat_id, message.id(), confirmation)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset(minutes: i32) -> FixedOffset {
+        offset_from_minutes(minutes).unwrap()
+    }
+
+    #[test]
+    fn test_parse_timezone_command() {
+        assert_eq!(
+            parse_timezone_command("/timezone"),
+            Some(TimezoneCommand::Show)
+        );
+        assert_eq!(
+            parse_timezone_command("/timezone +5:30"),
+            Some(TimezoneCommand::Set(offset(330)))
+        );
+        assert_eq!(
+            parse_timezone_command("/timezone UTC-3"),
+            Some(TimezoneCommand::Set(offset(-180)))
+        );
+        assert_eq!(parse_timezone_command("/timezone +20"), None);
+        assert_eq!(parse_timezone_command("/timezone Paris"), None);
+        assert_eq!(parse_timezone_command("/timezones"), None);
+    }
+
+    #[test]
+    fn test_parse_timezone_callback() {
+        assert_eq!(parse_timezone_callback("timezone:720"), Some(offset(720)));
+        assert_eq!(parse_timezone_callback("timezone:-480"), Some(offset(-480)));
+        assert_eq!(parse_timezone_callback("timezone:7"), None);
+        assert_eq!(parse_timezone_callback("timezone:9999"), None);
+        assert_eq!(parse_timezone_callback("units:metric"), None);
+    }
+
+    #[test]
+    fn test_timezone_choices_are_valid_offsets() {
+        for (minutes, _) in TIMEZONE_CHOICES {
+            let data = format!("timezone:{minutes}");
+            assert!(data.len() <= 64);
+            assert_eq!(parse_timezone_callback(&data), Some(offset(minutes)));
+        }
+    }
+
+    #[test]
+    fn test_display_timezone_uses_remembered_choice() {
+        let chat_id = ChatId(955_101);
+        assert_eq!(display_timezone(chat_id), crate::timezone::utc());
+        remember_timezone(chat_id, offset(720));
+        assert_eq!(display_timezone(chat_id), offset(720));
+        forget_timezone(chat_id);
+        assert_eq!(display_timezone(chat_id), crate::timezone::utc());
+    }
+}
@@ -3,21 +3,64 @@
 use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
 
 // Import localization
-use crate::localization::t_lang;
+use crate::localization::{t_args_lang, t_lang, t_plural};
 use std::sync::Arc;
 
 // Import text processing types
-use crate::text_processing::MeasurementMatch;
+use crate::text_processing::{MeasurementMatch, UnitSystem};
+
+// Import exact quantities for display
+use crate::quantity::Quantity;
+
+// Import display unit conversion
+use crate::unit_conversion::{convert, display_measurement, format_quantity};
+
+// Import dates in the user's time zone
+use crate::timezone::{
+    format_local_date_iso, format_local_date_short, format_local_datetime,
+    format_local_datetime_short,
+};
+use chrono::FixedOffset;
+
+// Import review keyboard layout variants
+use crate::experiments::ReviewKeyboardVariant;
+
+// Import report button callback data
+use crate::extraction_reports::ReportCallback;
+
+// Import recipe name tokens for selection callbacks
+use crate::db::recipe_name_token;
+
+// Import quantity adjustment callbacks
+use crate::ingredient_editing::{AdjustCallback, QuantityStep};
+
+/// Ingredients per row in the compact review keyboard
+const COMPACT_INGREDIENTS_PER_ROW: usize = 2;
+
+/// Room kept in review messages for notes appended after the list, such as the
+/// low-confidence note of OCR extractions
+const REVIEW_NOTES_RESERVE: usize = 512;
+
+// Import HTML message formatting
+use super::formatting::{
+    bold, code, escape_html, fit_lines, italic, labelled, message_length, titled, HtmlMessage,
+    MAX_MESSAGE_LENGTH,
+};
+
+// Import the raw OCR text button of the review
+use super::ocr_raw_text::SHOW_RAW_TEXT_CALLBACK;
 
 // Import common UI components
 use super::ui_components::{
-    create_add_button, create_back_button, create_localized_button_with_emoji,
-    create_pagination_buttons, truncate_text, with_ui_metrics_sync,
+    create_add_button, create_back_button, create_cancel_button,
+    create_localized_button_with_emoji, create_pagination_buttons,
+    create_pagination_buttons_with_prefix, truncate_text, with_ui_metrics_sync,
 };
 
-/// Format ingredients as a simple numbered list for review
+/// Format ingredients as a simple numbered list for review, as HTML
 pub fn format_ingredients_list(
     ingredients: &[MeasurementMatch],
+    units: Option<UnitSystem>,
     language_code: Option<&str>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> String {
@@ -34,24 +77,29 @@ pub fn format_ingredients_list(
                 ingredient.ingredient_name.clone()
             };
 
+            // Ranges ("2–3") are shown as written, never converted
             let measurement_display = if let Some(ref unit) = ingredient.measurement {
-                format!("{} {}", ingredient.quantity, unit)
+                let (quantity, unit) =
+                    display_measurement(&ingredient.display_quantity(), unit, units);
+                format!("{} {}", quantity, unit)
             } else {
-                ingredient.quantity.clone()
+                ingredient.display_quantity()
             };
 
-            // Add warning emoji for quantities that need confirmation
-            let measurement_display = if ingredient.requires_quantity_confirmation {
+            // Add warning emoji for quantities that need confirmation or lines OCR was unsure about
+            let measurement_display = if ingredient.requires_quantity_confirmation
+                || super::image_processing::is_low_confidence(ingredient)
+            {
                 format!("⚠️ {}", measurement_display)
             } else {
                 measurement_display
             };
 
             result.push_str(&format!(
-                "{}. **{}** → {}\n",
+                "{}. {} → {}\n",
                 i + 1,
-                measurement_display,
-                ingredient_display
+                bold(&measurement_display),
+                escape_html(&ingredient_display)
             ));
         }
 
@@ -59,11 +107,201 @@ pub fn format_ingredients_list(
     })
 }
 
+/// Format the ingredient review message as HTML: title, recipe name when known, and the list
+pub fn format_review_message(
+    ingredients: &[MeasurementMatch],
+    recipe_name: Option<&str>,
+    units: Option<UnitSystem>,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let mut message = HtmlMessage::titled(
+        "📝",
+        &t_plural(
+            localization,
+            "review-title-count",
+            ingredients.len(),
+            &[],
+            language_code,
+        ),
+    );
+    if let Some(name) = recipe_name {
+        message = message.line(&t_args_lang(
+            localization,
+            "review-recipe-name",
+            &[("recipe_name", name)],
+            language_code,
+        ));
+    }
+    let message = message.paragraph(&t_lang(localization, "review-description", language_code));
+
+    // The review is edited in place on every change, so the list is shortened to keep
+    // it in one message; hidden ingredients keep their buttons in the review keyboard
+    let list_length = MAX_MESSAGE_LENGTH
+        .saturating_sub(REVIEW_NOTES_RESERVE)
+        .saturating_sub(message_length(&message.clone().build()) + 2);
+    let list = fit_lines(
+        &format_ingredients_list(ingredients, units, language_code, localization),
+        list_length,
+        |count| {
+            italic(&t_plural(
+                localization,
+                "review-more-ingredients",
+                count,
+                &[],
+                language_code,
+            ))
+        },
+    );
+    message.paragraph_html(&list).build()
+}
+
+/// Notice that only the first ingredients were kept for review
+///
+/// `found` is the number of ingredients detected before
+/// [`cap_review_ingredients`](crate::dialogue::cap_review_ingredients); `None` when
+/// none were left out.
+pub fn format_review_cap_notice(
+    found: usize,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> Option<String> {
+    (found > crate::dialogue::MAX_REVIEW_INGREDIENTS).then(|| {
+        t_args_lang(
+            localization,
+            "review-ingredients-capped",
+            &[
+                ("kept", &crate::dialogue::MAX_REVIEW_INGREDIENTS.to_string()),
+                ("found", &found.to_string()),
+            ],
+            language_code,
+        )
+    })
+}
+
+/// One ingredient as the user would type it ("2 cups flour", "3 eggs")
+pub fn format_ingredient_line(ingredient: &MeasurementMatch) -> String {
+    [
+        ingredient.quantity.as_str(),
+        ingredient.measurement.as_deref().unwrap_or(""),
+        ingredient.ingredient_name.as_str(),
+    ]
+    .iter()
+    .map(|part| part.trim())
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Format the question asked when an edited ingredient line is ambiguous, as HTML
+///
+/// The current value is shown in monospace so it can be copied and corrected.
+pub fn format_edit_clarification(
+    ingredient: &MeasurementMatch,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    HtmlMessage::new()
+        .text("🤔 ")
+        .text(&t_lang(localization, "edit-ambiguous", language_code))
+        .paragraph_html(&code(&format_ingredient_line(ingredient)))
+        .paragraph(&t_lang(localization, "edit-ambiguous-hint", language_code))
+        .build()
+}
+
+/// Format the prompt asking for a new value of one ingredient, as HTML
+pub fn format_edit_ingredient_prompt(
+    ingredient: &MeasurementMatch,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    HtmlMessage::new()
+        .text("✏️ ")
+        .text(&t_lang(
+            localization,
+            "edit-ingredient-title",
+            language_code,
+        ))
+        .paragraph_html(&labelled(
+            &t_lang(localization, "edit-ingredient-current", language_code),
+            &format_ingredient_line(ingredient),
+        ))
+        .paragraph(&t_lang(
+            localization,
+            "edit-ingredient-instruction",
+            language_code,
+        ))
+        .build()
+}
+
+/// Button label for an ingredient in the full review keyboard
+fn format_review_button_text(
+    ingredient: &MeasurementMatch,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let ingredient_display = if ingredient.ingredient_name.is_empty() {
+        format!(
+            "❓ {}",
+            t_lang(localization, "unknown-ingredient", language_code)
+        )
+    } else {
+        ingredient.ingredient_name.clone()
+    };
+
+    let measurement_display = if let Some(ref unit) = ingredient.measurement {
+        format!("{} {}", ingredient.quantity, unit)
+    } else {
+        ingredient.quantity.clone()
+    };
+
+    // Add warning emoji for quantities that need confirmation
+    let measurement_display = if ingredient.requires_quantity_confirmation {
+        format!("⚠️ {}", measurement_display)
+    } else {
+        measurement_display
+    };
+
+    format!("{} → {}", measurement_display, ingredient_display)
+}
+
 /// Create inline keyboard for ingredient review
+///
+/// Used when editing the ingredients of a saved recipe, so there is no extraction
+/// to report.
 pub fn create_ingredient_review_keyboard(
     ingredients: &[MeasurementMatch],
     language_code: Option<&str>,
     localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    build_ingredient_review_keyboard(
+        ingredients,
+        language_code,
+        localization,
+        ReviewKeyboardVariant::Full,
+        false,
+    )
+}
+
+/// Create inline keyboard for ingredient review in the given layout variant
+///
+/// Both variants use the same callback data, only the ingredient buttons differ.
+/// The OCR review always ends with "Rename" and "Report a problem" buttons.
+pub fn create_ingredient_review_keyboard_for_variant(
+    ingredients: &[MeasurementMatch],
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    variant: ReviewKeyboardVariant,
+) -> InlineKeyboardMarkup {
+    build_ingredient_review_keyboard(ingredients, language_code, localization, variant, true)
+}
+
+fn build_ingredient_review_keyboard(
+    ingredients: &[MeasurementMatch],
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+    variant: ReviewKeyboardVariant,
+    pending_recipe: bool,
 ) -> InlineKeyboardMarkup {
     with_ui_metrics_sync(
         "create_ingredient_review_keyboard",
@@ -72,42 +310,57 @@ pub fn create_ingredient_review_keyboard(
             let mut buttons = Vec::new();
 
             // Create Edit and Delete buttons for each ingredient
-            for (i, ingredient) in ingredients.iter().enumerate() {
-                let ingredient_display = if ingredient.ingredient_name.is_empty() {
-                    format!(
-                        "❓ {}",
-                        t_lang(localization, "unknown-ingredient", language_code)
-                    )
-                } else {
-                    ingredient.ingredient_name.clone()
-                };
-
-                let measurement_display = if let Some(ref unit) = ingredient.measurement {
-                    format!("{} {}", ingredient.quantity, unit)
-                } else {
-                    ingredient.quantity.clone()
-                };
-
-                // Add warning emoji for quantities that need confirmation
-                let measurement_display = if ingredient.requires_quantity_confirmation {
-                    format!("⚠️ {}", measurement_display)
-                } else {
-                    measurement_display
-                };
+            match variant {
+                ReviewKeyboardVariant::Full => {
+                    for (i, ingredient) in ingredients.iter().enumerate() {
+                        let button_text = truncate_text(
+                            &format_review_button_text(ingredient, language_code, localization),
+                            20,
+                        );
 
-                let display_text = format!("{} → {}", measurement_display, ingredient_display);
-                let button_text = truncate_text(&display_text, 20);
-
-                buttons.push(vec![
-                    InlineKeyboardButton::callback(
-                        format!("✏️ {}", button_text),
-                        format!("edit_{}", i),
-                    ),
-                    InlineKeyboardButton::callback(
-                        format!("🗑️ {}", button_text),
-                        format!("delete_{}", i),
-                    ),
-                ]);
+                        buttons.push(vec![
+                            InlineKeyboardButton::callback(
+                                format!("✏️ {}", button_text),
+                                format!("edit_{}", i),
+                            ),
+                            InlineKeyboardButton::callback(
+                                format!("🗑️ {}", button_text),
+                                format!("delete_{}", i),
+                            ),
+                            InlineKeyboardButton::callback(
+                                "±",
+                                AdjustCallback::Open(i).callback_data(),
+                            ),
+                        ]);
+                    }
+                }
+                ReviewKeyboardVariant::Compact => {
+                    // Numbered buttons matching the list in the message, several per row
+                    let indices: Vec<usize> = (0..ingredients.len()).collect();
+                    for chunk in indices.chunks(COMPACT_INGREDIENTS_PER_ROW) {
+                        buttons.push(
+                            chunk
+                                .iter()
+                                .flat_map(|&i| {
+                                    [
+                                        InlineKeyboardButton::callback(
+                                            format!("✏️ {}", i + 1),
+                                            format!("edit_{}", i),
+                                        ),
+                                        InlineKeyboardButton::callback(
+                                            format!("🗑️ {}", i + 1),
+                                            format!("delete_{}", i),
+                                        ),
+                                        InlineKeyboardButton::callback(
+                                            format!("± {}", i + 1),
+                                            AdjustCallback::Open(i).callback_data(),
+                                        ),
+                                    ]
+                                })
+                                .collect(),
+                        );
+                    }
+                }
             }
 
             // Add Confirm and Cancel buttons at the bottom
@@ -138,11 +391,94 @@ pub fn create_ingredient_review_keyboard(
                 )]);
             }
 
+            // The recipe is not saved yet, so its name can still be changed here
+            if pending_recipe {
+                buttons.push(vec![create_localized_button_with_emoji(
+                    localization,
+                    "✏️",
+                    "review-rename",
+                    "rename_pending".to_string(),
+                    language_code,
+                )]);
+                buttons.push(vec![
+                    create_report_problem_button(language_code, localization),
+                    create_localized_button_with_emoji(
+                        localization,
+                        "📄",
+                        "review-show-raw-text",
+                        SHOW_RAW_TEXT_CALLBACK.to_string(),
+                        language_code,
+                    ),
+                ]);
+            }
+
             InlineKeyboardMarkup::new(buttons)
         },
     )
 }
 
+/// Add an "Undo" button restoring the last deleted ingredient, as the first row
+///
+/// Only shown on the keyboard re-rendered right after a deletion; any other action
+/// re-renders the keyboard without it.
+pub fn with_undo_delete_button(
+    keyboard: InlineKeyboardMarkup,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    let mut rows = keyboard.inline_keyboard;
+    rows.insert(
+        0,
+        vec![create_localized_button_with_emoji(
+            localization,
+            "↩️",
+            "undo-delete",
+            "undo_delete".to_string(),
+            language_code,
+        )],
+    );
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Create inline keyboard adjusting the quantity of one ingredient
+///
+/// Replaces the review keyboard until "Done" is tapped; the done button shows the
+/// ingredient's current quantity.
+pub fn create_quantity_adjust_keyboard(
+    index: usize,
+    ingredient: &MeasurementMatch,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_quantity_adjust_keyboard", 1, || {
+        let steps = QuantityStep::ALL
+            .into_iter()
+            .map(|step| {
+                InlineKeyboardButton::callback(
+                    step.label(),
+                    AdjustCallback::Step(index, step).callback_data(),
+                )
+            })
+            .collect();
+        let done_label = format!(
+            "✅ {}: {}",
+            t_lang(localization, "adjust-quantity-done", language_code),
+            truncate_text(
+                &format_review_button_text(ingredient, language_code, localization),
+                30
+            )
+        );
+
+        InlineKeyboardMarkup::new(vec![
+            steps,
+            vec![InlineKeyboardButton::callback(
+                done_label,
+                AdjustCallback::Done.callback_data(),
+            )],
+        ])
+    })
+}
+
 /// Create inline keyboard for post-confirmation workflow
 pub fn create_post_confirmation_keyboard(
     language_code: Option<&str>,
@@ -188,6 +524,14 @@ pub fn create_processing_keyboard(
     })
 }
 
+/// Callback data of a button opening the recipes named `recipe_name`
+///
+/// The name is replaced by its [`recipe_name_token`] so the data stays within
+/// Telegram's 64-byte limit whatever the name's length or characters.
+pub fn recipe_selection_callback(recipe_name: &str) -> String {
+    format!("select_recipe_token:{}", recipe_name_token(recipe_name))
+}
+
 /// Create inline keyboard for paginated recipe list
 pub fn create_recipes_pagination_keyboard(
     recipes: &[String],
@@ -205,7 +549,7 @@ pub fn create_recipes_pagination_keyboard(
             let button_text = truncate_text(recipe_name, 30);
             buttons.push(vec![InlineKeyboardButton::callback(
                 button_text,
-                format!("select_recipe:{}", recipe_name),
+                recipe_selection_callback(recipe_name),
             )]);
         }
 
@@ -219,6 +563,242 @@ pub fn create_recipes_pagination_keyboard(
             buttons.push(nav_buttons);
         }
 
+        // Switch the list to manage mode, selecting recipes to delete them together
+        buttons.push(vec![create_localized_button_with_emoji(
+            localization,
+            "🧹",
+            "bulk-delete-select",
+            "bulk_delete:start".to_string(),
+            language_code,
+        )]);
+
+        InlineKeyboardMarkup::new(buttons)
+    })
+}
+
+/// Create inline keyboard for the recipes list filtered by `/recipes <tag>`
+///
+/// Page buttons carry the tag (`tag_page:{tag}:{page}`) so every page stays filtered.
+pub fn create_tagged_recipes_keyboard(
+    recipes: &[String],
+    tag: &str,
+    current_page: usize,
+    total_count: i64,
+    limit: i64,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_tagged_recipes_keyboard", recipes.len(), || {
+        let mut buttons: Vec<Vec<InlineKeyboardButton>> = recipes
+            .iter()
+            .map(|recipe_name| {
+                vec![InlineKeyboardButton::callback(
+                    truncate_text(recipe_name, 30),
+                    recipe_selection_callback(recipe_name),
+                )]
+            })
+            .collect();
+
+        let total_pages = (total_count as usize).div_ceil(limit as usize);
+        if total_pages > 1 {
+            buttons.push(create_pagination_buttons_with_prefix(
+                localization,
+                &format!("tag_page:{}", tag),
+                current_page,
+                total_pages,
+                language_code,
+            ));
+        }
+
+        InlineKeyboardMarkup::new(buttons)
+    })
+}
+
+/// Label of a recipe in the bulk-delete selection list, e.g. `Tarte Tatin · 03/10/26`
+///
+/// The date tells apart recipes sharing a name.
+pub fn format_recipe_list_entry(
+    entry: &crate::db::RecipeListEntry,
+    timezone: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let name = entry
+        .recipe_name
+        .clone()
+        .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+    format!(
+        "{} · {}",
+        truncate_text(&name, 25),
+        format_local_date_short(entry.created_at, timezone)
+    )
+}
+
+/// Create inline keyboard for the bulk-delete selection mode of the recipes list
+///
+/// Tapping a recipe toggles its ✅ mark (`bulk_delete:toggle:{id}`); selected ids are
+/// passed in so marks stay consistent across pages.
+#[allow(clippy::too_many_arguments)]
+pub fn create_bulk_delete_keyboard(
+    entries: &[crate::db::RecipeListEntry],
+    selected_ids: &[i64],
+    current_page: usize,
+    total_count: i64,
+    limit: i64,
+    timezone: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_bulk_delete_keyboard", entries.len(), || {
+        let mut buttons: Vec<Vec<InlineKeyboardButton>> = entries
+            .iter()
+            .map(|entry| {
+                let mark = if selected_ids.contains(&entry.id.0) {
+                    "✅"
+                } else {
+                    "⬜"
+                };
+                vec![InlineKeyboardButton::callback(
+                    format!(
+                        "{} {}",
+                        mark,
+                        format_recipe_list_entry(entry, timezone, language_code, localization)
+                    ),
+                    format!("bulk_delete:toggle:{}", entry.id),
+                )]
+            })
+            .collect();
+
+        let total_pages = (total_count as usize).div_ceil(limit as usize);
+        if total_pages > 1 {
+            buttons.push(create_pagination_buttons_with_prefix(
+                localization,
+                "bulk_delete:page",
+                current_page,
+                total_pages,
+                language_code,
+            ));
+        }
+
+        let mut actions = Vec::new();
+        if !selected_ids.is_empty() {
+            actions.push(InlineKeyboardButton::callback(
+                format!(
+                    "🗑 {}",
+                    crate::localization::t_args_lang(
+                        localization,
+                        "bulk-delete-selected-button",
+                        &[("count", &selected_ids.len().to_string())],
+                        language_code,
+                    )
+                ),
+                "bulk_delete:confirm".to_string(),
+            ));
+        }
+        actions.push(create_localized_button_with_emoji(
+            localization,
+            "❌",
+            "bulk-delete-exit",
+            "bulk_delete:cancel".to_string(),
+            language_code,
+        ));
+        buttons.push(actions);
+
+        InlineKeyboardMarkup::new(buttons)
+    })
+}
+
+/// Create inline keyboard for the combined bulk-delete confirmation
+pub fn create_bulk_delete_confirmation_keyboard(
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        create_localized_button_with_emoji(
+            localization,
+            "🗑",
+            "bulk-delete-confirm-button",
+            "bulk_delete:delete".to_string(),
+            language_code,
+        ),
+        create_cancel_button(localization, "bulk_delete:back".to_string(), language_code),
+    ]])
+}
+
+/// Format the combined bulk-delete confirmation naming every selected recipe, as HTML
+pub fn format_bulk_delete_confirmation(
+    entries: &[crate::db::RecipeListEntry],
+    timezone: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let count = entries.len();
+    let names = entries
+        .iter()
+        .map(|entry| {
+            format!(
+                "• {}",
+                format_recipe_list_entry(entry, timezone, language_code, localization)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    titled(
+        "🗑",
+        &t_plural(
+            localization,
+            "bulk-delete-confirm-title",
+            count,
+            &[("count", &count.to_string())],
+            language_code,
+        ),
+        &[
+            &names,
+            &t_lang(localization, "bulk-delete-confirm-warning", language_code),
+        ],
+    )
+}
+
+/// Create inline keyboard for the /recent activity list
+///
+/// Each activity gets a button opening the recipe's details; pages beyond the first
+/// are reached with `recent_page:{page}` buttons.
+pub fn create_recent_activity_keyboard(
+    activities: &[crate::db::RecipeActivity],
+    first_number: usize,
+    current_page: usize,
+    total_count: i64,
+    limit: i64,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_recent_activity_keyboard", activities.len(), || {
+        let mut buttons: Vec<Vec<InlineKeyboardButton>> = activities
+            .iter()
+            .enumerate()
+            .map(|(index, activity)| {
+                let name = activity
+                    .recipe_name
+                    .clone()
+                    .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+                vec![InlineKeyboardButton::callback(
+                    truncate_text(&format!("{}. {}", first_number + index, name), 40),
+                    format!("recipe_instance:{}", activity.recipe_id),
+                )]
+            })
+            .collect();
+
+        let total_pages = (total_count as usize).div_ceil(limit as usize);
+        if total_pages > 1 {
+            buttons.push(create_pagination_buttons_with_prefix(
+                localization,
+                "recent_page",
+                current_page,
+                total_pages,
+                language_code,
+            ));
+        }
+
         InlineKeyboardMarkup::new(buttons)
     })
 }
@@ -226,6 +806,7 @@ pub fn create_recipes_pagination_keyboard(
 /// Create inline keyboard for selecting specific recipe instance from duplicates
 pub fn create_recipe_instances_keyboard(
     recipe_data: &[(crate::db::Recipe, Vec<crate::db::Ingredient>)],
+    timezone: FixedOffset,
     language_code: Option<&str>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> InlineKeyboardMarkup {
@@ -237,7 +818,7 @@ pub fn create_recipe_instances_keyboard(
 
             // Add buttons for each recipe instance
             for (recipe, ingredients) in recipe_data {
-                let created_at = recipe.created_at.format("%b %d, %Y %H:%M");
+                let created_at = format_local_datetime_short(recipe.created_at, timezone);
 
                 // Create ingredient preview (first 3 ingredients)
                 let ingredient_preview = if ingredients.is_empty() {
@@ -273,13 +854,16 @@ pub fn create_recipe_instances_keyboard(
 }
 
 /// Create inline keyboard for recipe details actions
+///
+/// The original photo can be shown again when `has_photo` is set.
 pub fn create_recipe_details_keyboard(
     recipe_id: i64,
+    has_photo: bool,
     language_code: Option<&str>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> InlineKeyboardMarkup {
     with_ui_metrics_sync("create_recipe_details_keyboard", 0, || {
-        let buttons = vec![
+        let mut buttons = vec![
             vec![
                 create_localized_button_with_emoji(
                     localization,
@@ -312,40 +896,614 @@ pub fn create_recipe_details_keyboard(
                     language_code,
                 ),
             ],
-            vec![create_back_button(
+            vec![
+                create_localized_button_with_emoji(
+                    localization,
+                    "⚖️",
+                    "scale-recipe",
+                    format!("recipe_action:scale:{}", recipe_id),
+                    language_code,
+                ),
+                create_localized_button_with_emoji(
+                    localization,
+                    "📊",
+                    "nutrition-button",
+                    format!("recipe_action:nutrition:{}", recipe_id),
+                    language_code,
+                ),
+            ],
+            vec![
+                create_localized_button_with_emoji(
+                    localization,
+                    "🏷️",
+                    "tags-button",
+                    format!("recipe_action:tags:{}", recipe_id),
+                    language_code,
+                ),
+                create_localized_button_with_emoji(
+                    localization,
+                    "📤",
+                    "share-button",
+                    format!("recipe_action:share:{}", recipe_id),
+                    language_code,
+                ),
+            ],
+            vec![
+                create_localized_button_with_emoji(
+                    localization,
+                    "🗒️",
+                    "notes-button",
+                    format!("recipe_action:notes:{}", recipe_id),
+                    language_code,
+                ),
+                create_localized_button_with_emoji(
+                    localization,
+                    "📋",
+                    "copy-as-text-button",
+                    format!("recipe_action:copy_text:{}", recipe_id),
+                    language_code,
+                ),
+            ],
+        ];
+        if has_photo {
+            buttons.push(vec![create_localized_button_with_emoji(
                 localization,
-                "back_to_recipes".to_string(),
+                "🖼️",
+                "show-original-photo",
+                format!("recipe_action:photo:{}", recipe_id),
                 language_code,
-            )],
-        ];
+            )]);
+        }
+        buttons.push(vec![create_back_button(
+            localization,
+            "back_to_recipes".to_string(),
+            language_code,
+        )]);
+
+        InlineKeyboardMarkup::new(buttons)
+    })
+}
+
+/// Format the saved-ingredients editor title with the current ingredient count
+pub fn format_editing_title(
+    ingredient_count: usize,
+    recipe_name: Option<&str>,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let title = t_lang(localization, "editing-recipe", language_code);
+    let count = t_plural(
+        localization,
+        "ingredient-total",
+        ingredient_count,
+        &[],
+        language_code,
+    );
+    match recipe_name {
+        Some(name) => format!("{title}: {name} ({count})"),
+        None => format!("{title} ({count})"),
+    }
+}
+
+/// Format the prompt shown when the user already has a recipe with the chosen name
+pub fn format_name_conflict_prompt(
+    recipe_name: &str,
+    existing_count: usize,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    format!(
+        "⚠️ {}\n\n{}",
+        t_args_lang(
+            localization,
+            "name-conflict-found",
+            &[
+                ("recipe_name", recipe_name),
+                ("count", &existing_count.to_string()),
+            ],
+            language_code
+        ),
+        t_lang(localization, "name-conflict-question", language_code)
+    )
+}
+
+/// Create inline keyboard offered when a recipe with the same name already exists
+///
+/// The "view existing" button is left out when the keyboard is attached to the
+/// existing recipe itself.
+pub fn create_name_conflict_keyboard(
+    include_view: bool,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_name_conflict_keyboard", 0, || {
+        let mut buttons = vec![vec![
+            create_localized_button_with_emoji(
+                localization,
+                "➕",
+                "name-conflict-save-new",
+                "name_conflict_new".to_string(),
+                language_code,
+            ),
+            create_localized_button_with_emoji(
+                localization,
+                "🔄",
+                "name-conflict-replace",
+                "name_conflict_replace".to_string(),
+                language_code,
+            ),
+        ]];
+        let mut second_row = vec![create_localized_button_with_emoji(
+            localization,
+            "🔀",
+            "name-conflict-merge",
+            "name_conflict_merge".to_string(),
+            language_code,
+        )];
+        if include_view {
+            second_row.push(create_localized_button_with_emoji(
+                localization,
+                "👀",
+                "name-conflict-view",
+                "name_conflict_view".to_string(),
+                language_code,
+            ));
+        }
+        buttons.push(second_row);
 
         InlineKeyboardMarkup::new(buttons)
     })
 }
 
-/// Format a list of database ingredients for display
+/// Most recent recipe names offered when naming a recipe after its review
+pub const RECIPE_NAME_SUGGESTIONS: usize = 6;
+
+/// Prefix of the data of a recipe name suggestion, followed by its index
+const NAME_SUGGESTION_PREFIX: &str = "name_suggestion:";
+
+/// Create inline keyboard of recent recipe names, two per row
+///
+/// Buttons carry the index of their name, which the dialogue state keeps: a name
+/// may be longer than the 64 bytes of callback data.
+pub fn create_recipe_name_suggestions_keyboard(names: &[String]) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync(
+        "create_recipe_name_suggestions_keyboard",
+        names.len(),
+        || {
+            let buttons: Vec<InlineKeyboardButton> = names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    InlineKeyboardButton::callback(
+                        truncate_text(name, 30),
+                        format!("{NAME_SUGGESTION_PREFIX}{index}"),
+                    )
+                })
+                .collect();
+            InlineKeyboardMarkup::new(buttons.chunks(2).map(<[_]>::to_vec))
+        },
+    )
+}
+
+/// Index of the tapped recipe name suggestion, from its callback data
+pub fn parse_name_suggestion_callback(data: &str) -> Option<usize> {
+    data.strip_prefix(NAME_SUGGESTION_PREFIX)?.parse().ok()
+}
+
+/// Create the "Report a problem" button for a bad extraction
+pub fn create_report_problem_button(
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardButton {
+    create_localized_button_with_emoji(
+        localization,
+        "🚩",
+        "report-problem",
+        ReportCallback::Report.callback_data(),
+        language_code,
+    )
+}
+
+/// Create the keyboard of the "no ingredients found" diagnostic
+pub fn create_report_problem_keyboard(
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_report_problem_keyboard", 0, || {
+        InlineKeyboardMarkup::new(vec![vec![create_report_problem_button(
+            language_code,
+            localization,
+        )]])
+    })
+}
+
+/// Add a "Try again with different processing" row on top of a keyboard
+///
+/// Only offered while the image is retained for a retry, see
+/// [`crate::cache::RetainedImageCache`].
+pub fn with_retry_processing_button(
+    keyboard: InlineKeyboardMarkup,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    let mut rows = keyboard.inline_keyboard;
+    rows.insert(
+        0,
+        vec![create_localized_button_with_emoji(
+            localization,
+            "🔁",
+            "ocr-retry-profiles",
+            "ocr_retry_profiles".to_string(),
+            language_code,
+        )],
+    );
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Create the keyboard of the report comment prompt
+pub fn create_report_comment_keyboard(
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_report_comment_keyboard", 0, || {
+        InlineKeyboardMarkup::new(vec![vec![create_localized_button_with_emoji(
+            localization,
+            "⏭️",
+            "report-skip-comment",
+            ReportCallback::SkipComment.callback_data(),
+            language_code,
+        )]])
+    })
+}
+
+/// Create the admin button asking the reporter for the original photo
+pub fn create_report_photo_request_keyboard(
+    report_id: i64,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_report_photo_request_keyboard", 0, || {
+        InlineKeyboardMarkup::new(vec![vec![create_localized_button_with_emoji(
+            localization,
+            "📷",
+            "report-request-photo",
+            ReportCallback::RequestPhoto(report_id).callback_data(),
+            language_code,
+        )]])
+    })
+}
+
+/// Create the keyboard asking the reporter to share the original photo
+pub fn create_report_photo_consent_keyboard(
+    report_id: i64,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> InlineKeyboardMarkup {
+    with_ui_metrics_sync("create_report_photo_consent_keyboard", 0, || {
+        InlineKeyboardMarkup::new(vec![vec![
+            create_localized_button_with_emoji(
+                localization,
+                "✅",
+                "report-photo-share",
+                ReportCallback::GrantPhoto(report_id).callback_data(),
+                language_code,
+            ),
+            create_localized_button_with_emoji(
+                localization,
+                "🚫",
+                "report-photo-keep-private",
+                ReportCallback::DeclinePhoto(report_id).callback_data(),
+                language_code,
+            ),
+        ]])
+    })
+}
+
+/// Characters of the notes previewed in the recipe details
+pub const NOTES_PREVIEW_LENGTH: usize = 120;
+
+/// Format a saved recipe with its creation date, notes preview and ingredients, as HTML
+pub fn format_recipe_details(
+    recipe: &crate::db::Recipe,
+    ingredients: &[crate::db::Ingredient],
+    units: Option<UnitSystem>,
+    timezone: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let message = HtmlMessage::titled(
+        "📖",
+        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
+    )
+    .paragraph(&format!(
+        "📅 {}",
+        format_local_datetime(recipe.created_at, timezone, language_code, localization)
+    ));
+    let message = match recipe.notes.as_deref() {
+        // Notes are previewed on one line, the Notes button shows them in full
+        Some(notes) => message.paragraph_html(&format!(
+            "🗒️ {}",
+            italic(&truncate_text(
+                &notes.split_whitespace().collect::<Vec<_>>().join(" "),
+                NOTES_PREVIEW_LENGTH
+            ))
+        )),
+        None => message,
+    };
+    message
+        .paragraph_html(&format_database_ingredients_list(
+            ingredients,
+            units,
+            language_code,
+            localization,
+        ))
+        .build()
+}
+
+/// Format a list of database ingredients for display, as HTML
 pub fn format_database_ingredients_list(
     ingredients: &[crate::db::Ingredient],
+    units: Option<UnitSystem>,
     language_code: Option<&str>,
     localization: &Arc<crate::localization::LocalizationManager>,
 ) -> String {
     if ingredients.is_empty() {
-        return t_lang(localization, "no-ingredients-found", language_code);
+        return escape_html(&t_lang(localization, "no-ingredients-found", language_code));
     }
 
     let mut result = String::new();
     for ingredient in ingredients {
-        let quantity_text = ingredient
-            .quantity
-            .map_or(String::new(), |q| format!("{} ", q));
-        let unit_text = ingredient.unit.as_deref().unwrap_or("");
-        let unit_space = if unit_text.is_empty() { "" } else { " " };
         let line = format!(
-            "• {}{}{}{}\n",
-            quantity_text, unit_text, unit_space, ingredient.name
+            "• {}\n",
+            escape_html(&database_ingredient_text(ingredient, units))
         );
         result.push_str(&line);
     }
 
     result.trim_end().to_string()
 }
+
+/// Plain text of a database ingredient ("250 g flour"), converted to `units` when set
+fn database_ingredient_text(
+    ingredient: &crate::db::Ingredient,
+    units: Option<UnitSystem>,
+) -> String {
+    let converted = match (ingredient.quantity, ingredient.unit.as_deref(), units) {
+        (Some(quantity), Some(unit), Some(units)) => convert(quantity, unit, units),
+        _ => None,
+    };
+    let (quantity_text, unit_text) = match &converted {
+        Some((quantity, unit)) => (format!("{} ", format_quantity(*quantity)), unit.as_str()),
+        None => (
+            ingredient
+                .quantity
+                .and_then(Quantity::from_f64)
+                .map_or(String::new(), |q| format!("{} ", q)),
+            ingredient.unit.as_deref().unwrap_or(""),
+        ),
+    };
+    let unit_space = if unit_text.is_empty() { "" } else { " " };
+    format!(
+        "{}{}{}{}",
+        quantity_text, unit_text, unit_space, ingredient.name
+    )
+}
+
+/// Bars of a sparkline, lowest to highest
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Text chart with one bar per count, scaled to the highest count ("▁▃█▂")
+///
+/// Zero counts get the lowest bar and any other count at least the second one,
+/// so empty months stand out from quiet ones.
+pub fn sparkline(counts: &[i64]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .map(|&count| {
+            if count <= 0 {
+                return SPARKLINE_BARS[0];
+            }
+            let top = SPARKLINE_BARS.len() as i64 - 1;
+            let level = ((count * top + max - 1) / max).clamp(1, top);
+            SPARKLINE_BARS[level as usize]
+        })
+        .collect()
+}
+
+/// Everything shown by /stats about a user's recipes
+pub struct UserStatisticsView<'a> {
+    pub stats: &'a crate::db::RecipeStatistics,
+    /// Ingredients used in the most recipes, with their recipe count
+    pub top_ingredients: &'a [(String, i64)],
+    /// Recipes saved per month, oldest month first
+    pub monthly_counts: &'a [(chrono::NaiveDate, i64)],
+    pub longest_recipe: Option<&'a crate::db::LongestRecipe>,
+}
+
+/// Format the statistics of all of a user's recipes, shown by /stats, as HTML
+///
+/// Ingredient and recipe names come from the user and may be long; send the
+/// result with `send_long_message`.
+pub fn format_user_statistics(
+    view: &UserStatisticsView<'_>,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let t = |key: &str| t_lang(localization, key, language_code);
+    let bullet = |key: &str, value: &dyn std::fmt::Display| format!("• {}: {}", t(key), value);
+    let section = |icon: &str, key: &str, lines: &[String]| {
+        lines
+            .iter()
+            .fold(HtmlMessage::titled(icon, &t(key)), |section, line| {
+                section.line(line)
+            })
+            .build()
+    };
+    let stats = view.stats;
+
+    let message = HtmlMessage::titled("📈", &t("your-statistics"));
+    if stats.total_recipes == 0 {
+        return message.paragraph(&t("stats-no-recipes")).build();
+    }
+
+    let mut overview = vec![
+        bullet("total-recipes", &stats.total_recipes),
+        bullet("total-ingredients", &stats.total_ingredients),
+        bullet(
+            "avg-ingredients-per-recipe",
+            &format!("{:.1}", stats.average_ingredients_per_recipe),
+        ),
+    ];
+    if let Some(longest) = view.longest_recipe {
+        let name = longest
+            .recipe_name
+            .clone()
+            .unwrap_or_else(|| t("unnamed-recipe"));
+        overview.push(format!(
+            "• {}",
+            t_plural(
+                localization,
+                "stats-longest-recipe",
+                longest.ingredient_count.max(0) as usize,
+                &[
+                    ("name", &name),
+                    ("count", &longest.ingredient_count.to_string())
+                ],
+                language_code,
+            )
+        ));
+    }
+    let mut message = message.paragraph_html(&section("📊", "stats-overview", &overview));
+
+    let mut recent = Vec::new();
+    if stats.recipes_created_today > 0 {
+        recent.push(bullet("recipes-today", &stats.recipes_created_today));
+    }
+    if stats.recipes_created_this_week > 0 {
+        recent.push(bullet(
+            "recipes-this-week",
+            &stats.recipes_created_this_week,
+        ));
+    }
+    if !recent.is_empty() {
+        message = message.paragraph_html(&section("🕐", "recent-activity", &recent));
+    }
+
+    if !view.top_ingredients.is_empty() {
+        let ingredients: Vec<String> = view
+            .top_ingredients
+            .iter()
+            .enumerate()
+            .map(|(i, (name, count))| format!("{}. {} ({})", i + 1, name, count))
+            .collect();
+        message = message.paragraph_html(&section("🥕", "stats-top-ingredients", &ingredients));
+    }
+
+    if let (Some((first_month, _)), Some((last_month, _))) =
+        (view.monthly_counts.first(), view.monthly_counts.last())
+    {
+        let counts: Vec<i64> = view
+            .monthly_counts
+            .iter()
+            .map(|(_, count)| *count)
+            .collect();
+        let mut chart = HtmlMessage::titled("📅", &t("stats-recipes-per-month"))
+            .line_html(&code(&sparkline(&counts)))
+            .line(&format!(
+                "{} – {}",
+                first_month.format("%Y-%m"),
+                last_month.format("%Y-%m")
+            ));
+        // The latest month wins ties, being the one the user remembers
+        if let Some((busiest_month, busiest_count)) = view
+            .monthly_counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .max_by_key(|(month, count)| (*count, *month))
+        {
+            chart = chart.line(&t_args_lang(
+                localization,
+                "stats-busiest-month",
+                &[
+                    ("month", &busiest_month.format("%Y-%m").to_string()),
+                    ("count", &busiest_count.to_string()),
+                ],
+                language_code,
+            ));
+        }
+        message = message.paragraph_html(&chart.build());
+    }
+
+    if !stats.most_common_units.is_empty() {
+        let units: Vec<String> = stats
+            .most_common_units
+            .iter()
+            .take(3)
+            .map(|(unit, count)| format!("• {} ({})", unit, count))
+            .collect();
+        message = message.paragraph_html(&section("🏷️", "favorite-units", &units));
+    }
+
+    message.build()
+}
+
+/// Name of the Markdown file a recipe too long for one message is sent as
+///
+/// Letters and digits of the recipe name are kept, other characters become
+/// dashes, so "Crêpes & Jam" gives `crêpes-jam.md`.
+pub fn recipe_markdown_file_name(recipe_name: Option<&str>) -> String {
+    let slug = recipe_name
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "recipe.md".to_string()
+    } else {
+        let slug: String = slug.chars().take(64).collect();
+        format!("{}.md", slug.trim_end_matches('-'))
+    }
+}
+
+/// Format a saved recipe as Markdown, to be pasted into a note-taking app
+///
+/// The output reads as plain text too: a title, the date the recipe was saved,
+/// its ingredients as a list and its notes when there are some. Labels take no
+/// arguments, so no invisible bidi isolation marks end up in the copied text.
+pub fn format_recipe_markdown(
+    recipe: &crate::db::Recipe,
+    ingredients: &[crate::db::Ingredient],
+    units: Option<UnitSystem>,
+    timezone: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<crate::localization::LocalizationManager>,
+) -> String {
+    let mut text = format!(
+        "# {}\n\n{} {}\n\n## {}\n\n",
+        recipe.recipe_name.as_deref().unwrap_or("Unnamed Recipe"),
+        t_lang(localization, "recipe-text-saved-on", language_code),
+        format_local_date_iso(recipe.created_at, timezone),
+        t_lang(localization, "recipe-text-ingredients", language_code),
+    );
+    if ingredients.is_empty() {
+        text.push_str(&t_lang(localization, "no-ingredients-found", language_code));
+        text.push('\n');
+    }
+    for ingredient in ingredients {
+        text.push_str(&format!(
+            "- {}\n",
+            database_ingredient_text(ingredient, units)
+        ));
+    }
+    if let Some(notes) = recipe.notes.as_deref() {
+        text.push_str(&format!(
+            "\n## {}\n\n{}\n",
+            t_lang(localization, "recipe-text-notes", language_code),
+            notes
+        ));
+    }
+    text
+}
@@ -102,6 +102,23 @@ pub fn create_pagination_buttons(
     current_page: usize,
     total_pages: usize,
     language_code: Option<&str>,
+) -> Vec<InlineKeyboardButton> {
+    create_pagination_buttons_with_prefix(
+        localization,
+        "page",
+        current_page,
+        total_pages,
+        language_code,
+    )
+}
+
+/// Create pagination buttons whose callback data is `"{prefix}:{page}"`
+pub fn create_pagination_buttons_with_prefix(
+    localization: &Arc<crate::localization::LocalizationManager>,
+    prefix: &str,
+    current_page: usize,
+    total_pages: usize,
+    language_code: Option<&str>,
 ) -> Vec<InlineKeyboardButton> {
     let mut buttons = Vec::new();
 
@@ -111,7 +128,7 @@ pub fn create_pagination_buttons(
             localization,
             "⬅️",
             "previous",
-            format!("page:{}", current_page - 1),
+            format!("{}:{}", prefix, current_page - 1),
             language_code,
         ));
     }
@@ -135,7 +152,7 @@ pub fn create_pagination_buttons(
             localization,
             "➡️",
             "next",
-            format!("page:{}", current_page + 1),
+            format!("{}:{}", prefix, current_page + 1),
             language_code,
         ));
     }
@@ -0,0 +1,195 @@
+//! Unit settings module for the `/units` command
+//!
+//! Lets users see ingredient quantities in metric or US/imperial units whatever
+//! the recipe was written in (see `crate::unit_conversion`). The choice is stored
+//! in the users table and kept in memory per chat, so the ingredient list
+//! formatters can read it without a database round trip.
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use tracing::debug;
+
+use crate::db::TelegramId;
+use crate::errors::error_logging;
+use crate::localization::{t_lang, LocalizationManager};
+use crate::text_processing::UnitSystem;
+
+lazy_static! {
+    /// Unit preference of every chat seen since startup
+    static ref UNIT_PREFERENCES: Mutex<HashMap<i64, Option<UnitSystem>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Choices offered by /units, with their callback value and label key
+const UNIT_CHOICES: [(Option<UnitSystem>, &str, &str); 3] = [
+    (None, "original", "units-original"),
+    (Some(UnitSystem::Metric), "metric", "units-metric"),
+    (Some(UnitSystem::Imperial), "imperial", "units-imperial"),
+];
+
+/// Unit system ingredient lists should be displayed in for a chat
+///
+/// `None` shows quantities as written. Only reads the in-memory copy filled by
+/// `load_unit_preference`.
+pub fn display_units(chat_id: ChatId) -> Option<UnitSystem> {
+    UNIT_PREFERENCES
+        .lock()
+        .ok()
+        .and_then(|preferences| preferences.get(&chat_id.0).copied())
+        .flatten()
+}
+
+fn remember_unit_preference(chat_id: ChatId, units: Option<UnitSystem>) {
+    if let Ok(mut preferences) = UNIT_PREFERENCES.lock() {
+        preferences.insert(chat_id.0, units);
+    }
+}
+
+/// Forget a chat's in-memory unit preference, so it is read again from the database
+pub fn forget_unit_preference(chat_id: ChatId) {
+    if let Ok(mut preferences) = UNIT_PREFERENCES.lock() {
+        preferences.remove(&chat_id.0);
+    }
+}
+
+/// Load a chat's unit preference from the database the first time it is seen
+///
+/// Lookup failures leave quantities as written and are retried on the next update.
+pub async fn load_unit_preference(pool: &PgPool, chat_id: ChatId) {
+    let cached = UNIT_PREFERENCES
+        .lock()
+        .map(|preferences| preferences.contains_key(&chat_id.0))
+        .unwrap_or(false);
+    if cached {
+        return;
+    }
+    match crate::db::get_user_unit_preference(pool, TelegramId(chat_id.0)).await {
+        Ok(units) => remember_unit_preference(chat_id, units),
+        Err(e) => {
+            error_logging::log_database_error(&e, "get_user_unit_preference", Some(chat_id.0), None)
+        }
+    }
+}
+
+/// Parse the data of a /units keyboard button
+///
+/// Returns `Some(None)` for "as written" and `None` for unrelated data.
+pub fn parse_units_callback(data: &str) -> Option<Option<UnitSystem>> {
+    let value = data.strip_prefix("units:")?;
+    UNIT_CHOICES
+        .iter()
+        .find(|(_, choice, _)| *choice == value)
+        .map(|(units, _, _)| *units)
+}
+
+fn units_keyboard(
+    current: Option<UnitSystem>,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(UNIT_CHOICES.iter().map(|(units, choice, label_key)| {
+        let marker = if *units == current { "✅ " } else { "" };
+        vec![InlineKeyboardButton::callback(
+            format!("{marker}{}", t_lang(localization, label_key, language_code)),
+            format!("units:{choice}"),
+        )]
+    }))
+}
+
+/// Handle the /units command by showing the unit choices
+pub async fn handle_units_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: Arc<PgPool>,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    debug!(user_id = %msg.chat.id, "Handling /units command");
+    load_unit_preference(&pool, msg.chat.id).await;
+
+    bot.send_message(
+        msg.chat.id,
+        t_lang(localization, "units-prompt", language_code),
+    )
+    .reply_markup(units_keyboard(
+        display_units(msg.chat.id),
+        localization,
+        language_code,
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Handle a tap on one of the /units buttons
+pub async fn handle_units_callback(
+    bot: &Bot,
+    q: &teloxide::types::CallbackQuery,
+    data: &str,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let (Some(units), Some(message)) = (parse_units_callback(data), q.message.as_ref()) else {
+        return Ok(());
+    };
+    let chat_id = message.chat().id;
+    let language_code = q.from.language_code.as_deref();
+
+    if let Err(e) = crate::db::set_user_unit_preference(pool, TelegramId(chat_id.0), units).await {
+        error_logging::log_database_error(&e, "set_user_unit_preference", Some(chat_id.0), None);
+        bot.send_message(
+            chat_id,
+            t_lang(localization, "error-processing-failed", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+    remember_unit_preference(chat_id, units);
+
+    let confirmation_key = match units {
+        None => "units-set-original",
+        Some(UnitSystem::Metric) => "units-set-metric",
+        Some(_) => "units-set-imperial",
+    };
+    bot.edit_message_text(
+        chat_id,
+        message.id(),
+        t_lang(localization, confirmation_key, language_code),
+    )
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_units_callback() {
+        assert_eq!(parse_units_callback("units:original"), Some(None));
+        assert_eq!(
+            parse_units_callback("units:metric"),
+            Some(Some(UnitSystem::Metric))
+        );
+        assert_eq!(
+            parse_units_callback("units:imperial"),
+            Some(Some(UnitSystem::Imperial))
+        );
+        assert_eq!(parse_units_callback("units:klingon"), None);
+        assert_eq!(parse_units_callback("page:1"), None);
+    }
+
+    #[test]
+    fn test_display_units_uses_remembered_choice() {
+        let chat_id = ChatId(955_001);
+        assert_eq!(display_units(chat_id), None);
+        remember_unit_preference(chat_id, Some(UnitSystem::Metric));
+        assert_eq!(display_units(chat_id), Some(UnitSystem::Metric));
+        remember_unit_preference(chat_id, None);
+        assert_eq!(display_units(chat_id), None);
+    }
+}
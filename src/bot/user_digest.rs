@@ -0,0 +1,406 @@
+//! Opt-in weekly digest of the recipes a user saved (`/digest`)
+//!
+//! `/digest on` or `/digest <day> <hour>` stores a weekday and hour (UTC) in the
+//! users table. A background task started from main wakes every few minutes, asks
+//! `db::get_users_due_for_digest` for the users whose hour has come, and sends each
+//! one the count of recipes saved in the last 7 days with buttons to open them.
+//!
+//! Every user is handled on their own: a failed send is logged and the sweep moves
+//! on. Handled users get `digest_last_sent_at` set, which keeps them out of later
+//! sweeps in the same hour, including after a restart.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc, Weekday};
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::db::{DigestRecipient, RecipeListEntry, TelegramId};
+use crate::localization::{t_args_lang, t_lang, t_plural, LocalizationManager};
+use crate::observability;
+use crate::scheduler::WeeklySchedule;
+
+use super::bot_utils::{retry_decision, send_with_retry, RetryDecision};
+use super::formatting::{HtmlMessage, PARSE_MODE};
+use super::ui_components::truncate_text;
+
+/// How often the digest task looks for users whose digest is due
+pub const DIGEST_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Pause between two digests of a sweep, well under Telegram's broadcast limit
+pub const DIGEST_SEND_PACING: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Recipes offered as buttons in a digest
+pub const DIGEST_RECIPE_BUTTONS: i64 = 5;
+
+/// Schedule used by `/digest on`: Mondays at 08:00 UTC
+pub const DEFAULT_DIGEST_SCHEDULE: WeeklySchedule = WeeklySchedule {
+    weekday: Weekday::Mon,
+    hour: 8,
+};
+
+/// What the /digest command asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestCommand {
+    /// Show the current setting
+    Show,
+    /// Send the digest at this weekday and hour
+    On(WeeklySchedule),
+    /// Stop sending the digest
+    Off,
+}
+
+/// Parse the argument of the /digest command
+///
+/// Returns `None` for text that is not a /digest command or has an invalid argument.
+pub fn parse_digest_command(text: &str) -> Option<DigestCommand> {
+    let argument = text.strip_prefix("/digest")?;
+    if !argument.is_empty() && !argument.starts_with(' ') {
+        return None;
+    }
+    let words: Vec<String> = argument.split_whitespace().map(str::to_lowercase).collect();
+    match words.as_slice() {
+        [] => Some(DigestCommand::Show),
+        [word] if word == "on" => Some(DigestCommand::On(DEFAULT_DIGEST_SCHEDULE)),
+        [word] if word == "off" => Some(DigestCommand::Off),
+        [day, hour] => WeeklySchedule::parse(day, hour).ok().map(DigestCommand::On),
+        _ => None,
+    }
+}
+
+/// Localization key of a weekday name
+fn weekday_key(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "weekday-mon",
+        Weekday::Tue => "weekday-tue",
+        Weekday::Wed => "weekday-wed",
+        Weekday::Thu => "weekday-thu",
+        Weekday::Fri => "weekday-fri",
+        Weekday::Sat => "weekday-sat",
+        Weekday::Sun => "weekday-sun",
+    }
+}
+
+/// Current /digest setting, e.g. "Weekly digest: on, Fridays at 18:00 UTC"
+pub fn format_digest_status(
+    schedule: Option<WeeklySchedule>,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    match schedule {
+        Some(schedule) => t_args_lang(
+            localization,
+            "digest-status-on",
+            &[
+                (
+                    "day",
+                    &t_lang(localization, weekday_key(schedule.weekday), language_code),
+                ),
+                ("hour", &format!("{:02}", schedule.hour)),
+            ],
+            language_code,
+        ),
+        None => t_lang(localization, "digest-status-off", language_code),
+    }
+}
+
+/// Handle the /digest, /digest on|off and /digest <day> <hour> commands
+pub async fn handle_digest_command(
+    bot: &Bot,
+    msg: &Message,
+    pool: &PgPool,
+    text: &str,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> Result<()> {
+    let command = parse_digest_command(text);
+    debug!(user_id = %msg.chat.id, command = ?command, "Handling /digest command");
+    let telegram_id = TelegramId(msg.chat.id.0);
+
+    let message = match command {
+        Some(DigestCommand::Show) => {
+            let schedule = crate::db::get_user_digest_schedule(pool, telegram_id).await?;
+            format!(
+                "📬 {}\n\n{}",
+                format_digest_status(schedule, localization, language_code),
+                t_lang(localization, "digest-usage", language_code)
+            )
+        }
+        Some(DigestCommand::On(schedule)) => {
+            crate::db::set_user_digest_schedule(pool, telegram_id, Some(schedule)).await?;
+            format!(
+                "✅ {}",
+                format_digest_status(Some(schedule), localization, language_code)
+            )
+        }
+        Some(DigestCommand::Off) => {
+            crate::db::set_user_digest_schedule(pool, telegram_id, None).await?;
+            format!(
+                "✅ {}",
+                format_digest_status(None, localization, language_code)
+            )
+        }
+        None => t_lang(localization, "digest-usage", language_code),
+    };
+    bot.send_message(msg.chat.id, message).await?;
+    Ok(())
+}
+
+/// HTML of a weekly digest
+pub fn format_digest_message(
+    new_recipes: i64,
+    total_recipes: i64,
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> String {
+    HtmlMessage::titled("📬", &t_lang(localization, "digest-title", language_code))
+        .paragraph(&t_plural(
+            localization,
+            "digest-new-recipes",
+            new_recipes.max(0) as usize,
+            &[],
+            language_code,
+        ))
+        .line(&t_plural(
+            localization,
+            "digest-total-recipes",
+            total_recipes.max(0) as usize,
+            &[],
+            language_code,
+        ))
+        .paragraph(&t_lang(localization, "digest-open-hint", language_code))
+        .build()
+}
+
+/// Buttons opening the recipes saved this week
+pub fn create_digest_keyboard(
+    recipes: &[RecipeListEntry],
+    localization: &Arc<LocalizationManager>,
+    language_code: Option<&str>,
+) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(recipes.iter().map(|recipe| {
+        let name = recipe
+            .recipe_name
+            .clone()
+            .unwrap_or_else(|| t_lang(localization, "unnamed-recipe", language_code));
+        vec![InlineKeyboardButton::callback(
+            format!("📖 {}", truncate_text(&name, 30)),
+            format!("recipe_instance:{}", recipe.id),
+        )]
+    }))
+}
+
+/// Outcome of the digest of one user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestOutcome {
+    Sent,
+    /// Nothing saved this week, so no message was sent
+    Skipped,
+    Failed,
+}
+
+impl DigestOutcome {
+    /// Label used in the digest metrics
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DigestOutcome::Sent => "sent",
+            DigestOutcome::Skipped => "skipped",
+            DigestOutcome::Failed => "failed",
+        }
+    }
+}
+
+/// Counts of a digest sweep
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DigestSweep {
+    pub sent: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// Build and send the digest of one user
+///
+/// Returns whether the user is done for this week: true once sent or skipped, and
+/// after a failure that would repeat (e.g. the user blocked the bot); false after a
+/// transient failure, so the next sweep in the same hour tries again.
+async fn send_user_digest(
+    bot: &Bot,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    recipient: &DigestRecipient,
+    now: DateTime<Utc>,
+) -> Result<(DigestOutcome, bool)> {
+    let language_code = Some(recipient.language_code.as_str());
+    // The week is the last 7 days in the user's time zone, as in /stats
+    let timezone = crate::db::get_user_timezone(pool, recipient.telegram_id)
+        .await?
+        .unwrap_or_else(crate::timezone::utc);
+    let stats =
+        crate::db::get_user_recipe_statistics(pool, recipient.telegram_id, timezone).await?;
+    if stats.recipes_created_this_week == 0 {
+        return Ok((DigestOutcome::Skipped, true));
+    }
+    let recipes = crate::db::get_user_recipes_created_since(
+        pool,
+        recipient.telegram_id,
+        crate::timezone::StatisticsWindows::new(now, timezone).week,
+        DIGEST_RECIPE_BUTTONS,
+    )
+    .await?;
+
+    let request = bot
+        .send_message(
+            ChatId(recipient.telegram_id.0),
+            format_digest_message(
+                stats.recipes_created_this_week,
+                stats.total_recipes,
+                localization,
+                language_code,
+            ),
+        )
+        .parse_mode(PARSE_MODE)
+        .reply_markup(create_digest_keyboard(
+            &recipes,
+            localization,
+            language_code,
+        ));
+    match send_with_retry(request).await {
+        Ok(_) => Ok((DigestOutcome::Sent, true)),
+        Err(e) => {
+            let permanent = retry_decision(&e) == RetryDecision::GiveUp;
+            warn!(telegram_id = %recipient.telegram_id, error = %e, permanent, "Failed to send weekly digest");
+            Ok((DigestOutcome::Failed, permanent))
+        }
+    }
+}
+
+/// Send the digests due at `now`, one user at a time
+///
+/// A user's failure, whether loading their stats or sending, is counted and logged
+/// without stopping the sweep.
+pub async fn run_digest_sweep(
+    bot: &Bot,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+    now: DateTime<Utc>,
+) -> Result<DigestSweep> {
+    let recipients = crate::db::get_users_due_for_digest(pool, now).await?;
+    let mut sweep = DigestSweep::default();
+
+    for (index, recipient) in recipients.iter().enumerate() {
+        if index > 0 {
+            tokio::time::sleep(DIGEST_SEND_PACING).await;
+        }
+        let (outcome, done) = match send_user_digest(bot, pool, localization, recipient, now).await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(telegram_id = %recipient.telegram_id, error = %e, "Failed to build weekly digest");
+                (DigestOutcome::Failed, false)
+            }
+        };
+        observability::record_user_digest_outcome(outcome.as_str());
+        match outcome {
+            DigestOutcome::Sent => sweep.sent += 1,
+            DigestOutcome::Skipped => sweep.skipped += 1,
+            DigestOutcome::Failed => sweep.failed += 1,
+        }
+
+        if done {
+            if let Err(e) = crate::db::mark_digest_sent(pool, recipient.telegram_id, now).await {
+                warn!(telegram_id = %recipient.telegram_id, error = %e, "Failed to record weekly digest send");
+            }
+        }
+    }
+
+    if !recipients.is_empty() {
+        info!(
+            sent = sweep.sent,
+            skipped = sweep.skipped,
+            failed = sweep.failed,
+            "Weekly digest sweep finished"
+        );
+    }
+    Ok(sweep)
+}
+
+/// Start the task sending weekly digests, until `shutdown` is cancelled
+pub fn start_user_digest(
+    bot: Bot,
+    pool: Arc<PgPool>,
+    localization: Arc<LocalizationManager>,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            if let Err(e) = run_digest_sweep(&bot, &pool, &localization, Utc::now()).await {
+                warn!(error = %e, "Weekly digest sweep failed");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_isolation(text: &str) -> String {
+        text.replace(['\u{2068}', '\u{2069}'], "")
+    }
+
+    #[test]
+    fn test_parse_digest_command() {
+        assert_eq!(parse_digest_command("/digest"), Some(DigestCommand::Show));
+        assert_eq!(
+            parse_digest_command("/digest ON"),
+            Some(DigestCommand::On(DEFAULT_DIGEST_SCHEDULE))
+        );
+        assert_eq!(
+            parse_digest_command("/digest off"),
+            Some(DigestCommand::Off)
+        );
+        assert_eq!(
+            parse_digest_command("/digest friday 18"),
+            Some(DigestCommand::On(WeeklySchedule {
+                weekday: Weekday::Fri,
+                hour: 18
+            }))
+        );
+        assert_eq!(parse_digest_command("/digest fri 24"), None);
+        assert_eq!(parse_digest_command("/digest sometimes"), None);
+        assert_eq!(parse_digest_command("/digests"), None);
+        assert_eq!(parse_digest_command("/recipes"), None);
+    }
+
+    #[test]
+    fn test_format_digest_message() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let message = strip_isolation(&format_digest_message(3, 12, &localization, Some("en")));
+        assert!(message.contains("3 new recipes this week"));
+        assert!(message.contains("12 recipes saved in total"));
+
+        let message = strip_isolation(&format_digest_message(1, 1, &localization, Some("en")));
+        assert!(message.contains("1 new recipe this week"));
+
+        let status = strip_isolation(&format_digest_status(
+            Some(WeeklySchedule {
+                weekday: Weekday::Fri,
+                hour: 7,
+            }),
+            &localization,
+            Some("en"),
+        ));
+        assert!(status.contains("Fridays at 07:00 UTC"));
+    }
+}
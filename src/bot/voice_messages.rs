@@ -0,0 +1,109 @@
+//! Ingredient review for voice messages
+//!
+//! A dictated ingredient list is transcribed (see `crate::transcription`) and the
+//! transcript goes through the same extraction and review as pasted text. Voice
+//! notes and audio files are both accepted.
+
+use anyhow::Result;
+use sqlx::postgres::PgPool;
+use std::sync::Arc;
+use teloxide::prelude::*;
+use teloxide::types::{ChatAction, FileId};
+use tracing::{debug, info};
+
+use crate::dialogue::RecipeDialogue;
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+use crate::transcription::{is_transcription_configured, transcribe_voice, TranscriptionError};
+
+use super::image_processing::{download_file, process_ingredients_and_extract_matches};
+use super::text_ingredients::start_text_review;
+use super::HandlerContext;
+
+/// File of a voice note or audio message
+pub fn voice_file_id(msg: &Message) -> Option<FileId> {
+    msg.voice()
+        .map(|voice| voice.file.id.clone())
+        .or_else(|| msg.audio().map(|audio| audio.file.id.clone()))
+}
+
+/// Transcribe a voice message and start an ingredient review from it
+pub async fn handle_voice_message(
+    bot: &Bot,
+    msg: &Message,
+    dialogue: &RecipeDialogue,
+    pool: &PgPool,
+    localization: &Arc<LocalizationManager>,
+) -> Result<()> {
+    let language_code = msg
+        .from
+        .as_ref()
+        .and_then(|user| user.language_code.as_deref());
+    let Some(file_id) = voice_file_id(msg) else {
+        return Ok(());
+    };
+    debug!(user_id = %msg.chat.id, "Received voice message from user");
+
+    if !is_transcription_configured() {
+        bot.send_message(
+            msg.chat.id,
+            t_lang(localization, "voice-not-configured", language_code),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    bot.send_chat_action(msg.chat.id, ChatAction::Typing)
+        .await?;
+    let audio = download_file(bot, file_id).await?;
+
+    let transcript = match transcribe_voice(audio.path(), language_code).await {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            let key = match e {
+                TranscriptionError::NotConfigured => "voice-not-configured",
+                TranscriptionError::CircuitOpen => "voice-transcription-unavailable",
+                TranscriptionError::Failed(_) | TranscriptionError::Timeout => {
+                    "voice-transcription-failed"
+                }
+            };
+            bot.send_message(msg.chat.id, t_lang(localization, key, language_code))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let ingredients = process_ingredients_and_extract_matches(&transcript, language_code);
+    if ingredients.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            t_args_lang(
+                localization,
+                "voice-no-ingredients",
+                &[("transcript", transcript.as_str())],
+                language_code,
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+    info!(
+        user_id = %msg.chat.id,
+        transcript_chars = transcript.len(),
+        "Reviewing ingredients from voice message"
+    );
+
+    start_text_review(
+        &HandlerContext {
+            bot,
+            localization,
+            language_code,
+        },
+        msg,
+        &transcript,
+        ingredients,
+        None,
+        dialogue,
+        pool,
+    )
+    .await
+}
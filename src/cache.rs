@@ -7,7 +7,12 @@
 //!
 //! - **Memory Cache**: In-memory TTL-based cache for fast access
 //! - **OCR Result Cache**: Specialized cache for OCR processing results
+//! - **Recent Photo Cache**: Photos each chat sent recently, to skip duplicate OCR runs
 //! - **Database Query Cache**: Cache for frequently accessed database queries
+//! - **Cache Backend**: Users looked up through `db::*_cached`, see [`crate::cache_backend`]
+//! - **Recipe List / Ingredients Caches**: Recipe pages and name lookups per user,
+//!   recipes and their ingredients per recipe, evicted by the `db::*_cached` writes
+//!   that change them
 //!
 //! ## Usage Examples
 //!
@@ -22,11 +27,14 @@
 //! let ocr_cache = OcrResultCache::new(std::time::Duration::from_secs(3600)); // 1 hour
 //! ```
 
+use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::sync::{Arc, RwLock};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+use crate::cache_backend::{CacheBackend, MemoryCacheBackend};
+
 /// Generic cache entry with expiration time
 #[derive(Debug, Clone)]
 pub struct CacheEntry<T> {
@@ -120,31 +128,23 @@ where
     }
 
     /// Helper method to acquire read lock on data with proper error handling
-    fn read_data(&self) -> std::sync::RwLockReadGuard<'_, HashMap<K, CacheEntry<V>>> {
-        self.data
-            .read()
-            .expect("Failed to acquire read lock on cache data")
+    fn read_data(&self) -> parking_lot::RwLockReadGuard<'_, HashMap<K, CacheEntry<V>>> {
+        self.data.read()
     }
 
     /// Helper method to acquire write lock on data with proper error handling
-    fn write_data(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<K, CacheEntry<V>>> {
-        self.data
-            .write()
-            .expect("Failed to acquire write lock on cache data")
+    fn write_data(&self) -> parking_lot::RwLockWriteGuard<'_, HashMap<K, CacheEntry<V>>> {
+        self.data.write()
     }
 
     /// Helper method to acquire read lock on stats with proper error handling
-    fn read_stats(&self) -> std::sync::RwLockReadGuard<'_, CacheStats> {
-        self.stats
-            .read()
-            .expect("Failed to acquire read lock on cache stats")
+    fn read_stats(&self) -> parking_lot::RwLockReadGuard<'_, CacheStats> {
+        self.stats.read()
     }
 
     /// Helper method to acquire write lock on stats with proper error handling
-    fn write_stats(&self) -> std::sync::RwLockWriteGuard<'_, CacheStats> {
-        self.stats
-            .write()
-            .expect("Failed to acquire write lock on cache stats")
+    fn write_stats(&self) -> parking_lot::RwLockWriteGuard<'_, CacheStats> {
+        self.stats.write()
     }
 }
 
@@ -299,6 +299,358 @@ impl OcrResultCache {
     }
 }
 
+/// Default time during which a photo sent again counts as a duplicate
+pub const DEFAULT_DUPLICATE_PHOTO_WINDOW: Duration = Duration::from_secs(120);
+
+/// Time during which a photo sent again counts as a duplicate
+///
+/// Configurable in seconds with `DUPLICATE_PHOTO_WINDOW_SECS`, defaults to
+/// [`DEFAULT_DUPLICATE_PHOTO_WINDOW`].
+pub fn duplicate_photo_window() -> Duration {
+    crate::config::current().cache.duplicate_photo_window
+}
+
+/// Hash of an image's content, identical for identical bytes
+pub fn image_content_hash(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Recent photo cache key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecentPhotoKey {
+    /// Chat the photo was sent in
+    pub chat_id: i64,
+    /// Hash of the image content
+    pub image_hash: String,
+}
+
+/// Photos each chat sent recently, being processed or already processed
+///
+/// Lets a photo sent twice in quick succession be recognized before OCR runs again.
+pub struct RecentPhotoCache {
+    cache: MemoryCache<RecentPhotoKey, Instant>,
+    window: Duration,
+}
+
+impl RecentPhotoCache {
+    /// Create a cache remembering photos for `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            cache: MemoryCache::new(),
+            window,
+        }
+    }
+
+    /// Record a photo, returning whether the chat already sent it within the window
+    ///
+    /// A duplicate does not extend the window of the original photo.
+    pub fn check_and_record(&mut self, chat_id: i64, image_hash: &str) -> bool {
+        let key = RecentPhotoKey {
+            chat_id,
+            image_hash: image_hash.to_string(),
+        };
+        if self.cache.get(&key).is_some() {
+            return true;
+        }
+        self.cache.insert(key, Instant::now(), self.window);
+        false
+    }
+
+    /// Forget a recorded photo, so sending it again processes it instead of flagging a duplicate
+    ///
+    /// Used when reading the photo failed or found nothing.
+    pub fn forget(&mut self, chat_id: i64, image_hash: &str) {
+        self.cache.remove(&RecentPhotoKey {
+            chat_id,
+            image_hash: image_hash.to_string(),
+        });
+    }
+
+    /// Clean up expired entries
+    pub fn cleanup(&mut self) {
+        self.cache.cleanup();
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Clear all recorded photos
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Default time the photos of an album are collected before being read together
+pub const DEFAULT_ALBUM_WINDOW: Duration = Duration::from_millis(2500);
+
+/// Time the photos of an album are collected before being read together
+///
+/// Configurable in milliseconds with `ALBUM_WINDOW_MS`, defaults to
+/// [`DEFAULT_ALBUM_WINDOW`].
+pub fn album_window() -> Duration {
+    crate::config::current().cache.album_window
+}
+
+/// Album buffer key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AlbumKey {
+    /// Chat the album was sent in
+    pub chat_id: i64,
+    /// Telegram media group shared by the photos of the album
+    pub media_group_id: String,
+}
+
+/// Photo of an album waiting to be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlbumPhoto {
+    /// Message carrying the photo
+    pub message_id: i32,
+    /// Telegram file id of the largest photo size
+    pub file_id: String,
+    /// Stable Telegram id of the same photo
+    pub file_unique_id: String,
+    /// Photo caption; Telegram puts the album caption on its first photo
+    pub caption: Option<String>,
+}
+
+/// Photos of albums being received, per chat and media group
+///
+/// Telegram delivers each photo of an album as its own message. They are collected
+/// here so the album is read as a single recipe.
+#[derive(Debug, Default)]
+pub struct AlbumBuffer {
+    albums: HashMap<AlbumKey, Vec<AlbumPhoto>>,
+}
+
+impl AlbumBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer a photo, returning whether it is the first received for its album
+    pub fn push(&mut self, chat_id: i64, media_group_id: &str, photo: AlbumPhoto) -> bool {
+        let photos = self
+            .albums
+            .entry(AlbumKey {
+                chat_id,
+                media_group_id: media_group_id.to_string(),
+            })
+            .or_default();
+        photos.push(photo);
+        photos.len() == 1
+    }
+
+    /// Remove the photos of an album, in the order they were sent
+    pub fn take(&mut self, chat_id: i64, media_group_id: &str) -> Vec<AlbumPhoto> {
+        let mut photos = self
+            .albums
+            .remove(&AlbumKey {
+                chat_id,
+                media_group_id: media_group_id.to_string(),
+            })
+            .unwrap_or_default();
+        photos.sort_by_key(|photo| photo.message_id);
+        photos
+    }
+
+    /// Number of albums still being received
+    pub fn len(&self) -> usize {
+        self.albums.len()
+    }
+
+    /// Whether no album is being received
+    pub fn is_empty(&self) -> bool {
+        self.albums.is_empty()
+    }
+
+    /// Drop all buffered photos
+    pub fn clear(&mut self) {
+        self.albums.clear();
+    }
+}
+
+/// Default time an image without ingredients is kept for a retry with other processing
+pub const DEFAULT_RETAINED_IMAGE_TTL: Duration = Duration::from_secs(600);
+
+/// Time an image without ingredients is kept for a retry with other processing
+///
+/// Configurable in seconds with `RETAINED_IMAGE_TTL_SECS`, defaults to
+/// [`DEFAULT_RETAINED_IMAGE_TTL`].
+pub fn retained_image_ttl() -> Duration {
+    crate::config::current().cache.retained_image_ttl
+}
+
+/// Retained image key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetainedImageKey {
+    /// Chat the image was sent in
+    pub chat_id: i64,
+    /// Message offering the retry, replaced by its result
+    pub message_id: i32,
+}
+
+/// Downloaded image kept so it can be read again without a new upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetainedImage {
+    /// Image file content
+    pub bytes: Vec<u8>,
+    /// Telegram file id, kept for extraction reports
+    pub file_id: String,
+    /// Photo caption, used as the recipe name once ingredients are found
+    pub caption: Option<String>,
+    /// Photo kept with the recipe once saved, `None` for documents
+    pub photo: Option<crate::db::RecipePhoto>,
+}
+
+/// Images that yielded no ingredients, per chat and retry message
+pub struct RetainedImageCache {
+    cache: MemoryCache<RetainedImageKey, RetainedImage>,
+    ttl: Duration,
+}
+
+impl RetainedImageCache {
+    /// Create a cache keeping images for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            cache: MemoryCache::new(),
+            ttl,
+        }
+    }
+
+    /// Keep an image until it is retried or expires
+    pub fn retain(&mut self, chat_id: i64, message_id: i32, image: RetainedImage) {
+        self.cache.insert(
+            RetainedImageKey {
+                chat_id,
+                message_id,
+            },
+            image,
+            self.ttl,
+        );
+    }
+
+    /// Remove a retained image, `None` once it expired
+    pub fn take(&mut self, chat_id: i64, message_id: i32) -> Option<RetainedImage> {
+        let key = RetainedImageKey {
+            chat_id,
+            message_id,
+        };
+        let image = self.cache.get(&key);
+        self.cache.remove(&key);
+        image
+    }
+
+    /// Clean up expired entries
+    pub fn cleanup(&mut self) {
+        self.cache.cleanup();
+    }
+
+    /// Get cache statistics
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Drop all retained images
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+}
+
+/// Time a displayed page is remembered for its message
+///
+/// Long enough to absorb a burst of taps, short enough that a list edited elsewhere
+/// (a recipe renamed or deleted) is redrawn when its page is tapped again later.
+pub const DISPLAYED_PAGE_TTL: Duration = Duration::from_secs(30);
+
+/// Paginated message being navigated
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PagedMessageKey {
+    /// Chat the list was sent in
+    pub chat_id: i64,
+    /// Message showing the list
+    pub message_id: i32,
+}
+
+/// Paces the navigation of paginated lists
+///
+/// Rapid Next/Previous taps turn into a flood of message edits that Telegram answers
+/// with a flood wait. Tapping a page that is already displayed (or being displayed)
+/// edits nothing, and a chat under a flood wait is not edited until the wait is over.
+pub struct PaginationThrottle {
+    displayed_pages: MemoryCache<PagedMessageKey, usize>,
+    flood_waits: MemoryCache<i64, Instant>,
+}
+
+impl PaginationThrottle {
+    /// Create an empty throttle
+    pub fn new() -> Self {
+        Self {
+            displayed_pages: MemoryCache::new(),
+            flood_waits: MemoryCache::new(),
+        }
+    }
+
+    /// Mark `page` as displayed in a message, returning `false` if it already was
+    ///
+    /// Marked before the edit is sent, so taps arriving during the edit are ignored;
+    /// call [`Self::forget_page`] when the edit fails.
+    pub fn claim_page(&mut self, chat_id: i64, message_id: i32, page: usize) -> bool {
+        let key = PagedMessageKey {
+            chat_id,
+            message_id,
+        };
+        if self.displayed_pages.get(&key) == Some(page) {
+            return false;
+        }
+        self.displayed_pages.insert(key, page, DISPLAYED_PAGE_TTL);
+        true
+    }
+
+    /// Forget the page displayed in a message, after its edit failed
+    pub fn forget_page(&mut self, chat_id: i64, message_id: i32) {
+        self.displayed_pages.remove(&PagedMessageKey {
+            chat_id,
+            message_id,
+        });
+    }
+
+    /// Record a flood wait Telegram imposed on a chat
+    pub fn record_flood_wait(&mut self, chat_id: i64, wait: Duration) {
+        self.flood_waits
+            .insert(chat_id, Instant::now() + wait, wait);
+    }
+
+    /// Time left before a chat may be edited again, `None` when it is not waiting
+    pub fn flood_wait_remaining(&self, chat_id: i64) -> Option<Duration> {
+        self.flood_waits
+            .get(&chat_id)
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Clean up expired entries
+    pub fn cleanup(&mut self) {
+        self.displayed_pages.cleanup();
+        self.flood_waits.cleanup();
+    }
+
+    /// Forget all pages and flood waits
+    pub fn clear(&mut self) {
+        self.displayed_pages.clear();
+        self.flood_waits.clear();
+    }
+}
+
+impl Default for PaginationThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Database query cache key
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DbCacheKey {
@@ -358,30 +710,21 @@ impl DbQueryCache {
         let value_size = value.size_bytes;
 
         // Check if adding this entry would exceed max size
-        let current_size = *self
-            .current_size_bytes
-            .read()
-            .expect("Failed to read current size");
+        let current_size = *self.current_size_bytes.read();
         if current_size + value_size > self.max_size_bytes {
             // Evict some entries to make room (simple LRU-like eviction)
             self.evict_to_make_room(value_size);
         }
 
         self.cache.insert(key, value, ttl);
-        *self
-            .current_size_bytes
-            .write()
-            .expect("Failed to write current size") += value_size;
+        *self.current_size_bytes.write() += value_size;
     }
 
     /// Remove query result from cache
     pub fn remove(&mut self, key: &DbCacheKey) -> Option<DbCacheValue> {
         let result = self.cache.remove(key);
         if let Some(ref value) = result {
-            *self
-                .current_size_bytes
-                .write()
-                .expect("Failed to write current size") -= value.size_bytes;
+            *self.current_size_bytes.write() -= value.size_bytes;
         }
         result
     }
@@ -401,18 +744,12 @@ impl DbQueryCache {
     /// Clear all cached results
     pub fn clear(&mut self) {
         self.cache.clear();
-        *self
-            .current_size_bytes
-            .write()
-            .expect("Failed to write current size") = 0;
+        *self.current_size_bytes.write() = 0;
     }
 
     /// Get current cache size in bytes
     pub fn current_size_bytes(&self) -> usize {
-        *self
-            .current_size_bytes
-            .read()
-            .expect("Failed to read current size")
+        *self.current_size_bytes.read()
     }
 
     /// Get maximum cache size in bytes
@@ -431,18 +768,12 @@ impl DbQueryCache {
         for (key, entry) in data.iter() {
             if entry.is_expired() {
                 entries_to_remove.push(key.clone());
-                *self
-                    .current_size_bytes
-                    .write()
-                    .expect("Failed to write current size") -= entry.value.size_bytes;
+                *self.current_size_bytes.write() -= entry.value.size_bytes;
             }
         }
 
         // If we still need more space, remove additional entries
-        let current_size = *self
-            .current_size_bytes
-            .read()
-            .expect("Failed to read current size");
+        let current_size = *self.current_size_bytes.read();
         let space_needed = (current_size + needed_bytes).saturating_sub(self.max_size_bytes);
 
         if space_needed > 0 {
@@ -457,10 +788,7 @@ impl DbQueryCache {
                 }
                 entries_to_remove.push(key.clone());
                 freed_space += entry.value.size_bytes;
-                *self
-                    .current_size_bytes
-                    .write()
-                    .expect("Failed to write current size") -= entry.value.size_bytes;
+                *self.current_size_bytes.write() -= entry.value.size_bytes;
             }
         }
 
@@ -477,26 +805,72 @@ impl DbQueryCache {
     }
 }
 
+/// Cache manager shared between handlers
+///
+/// `parking_lot` locks are not poisoned, so a handler panicking while holding the
+/// lock does not break the cache for every later request. Never hold the lock
+/// across an `.await`; clone what is needed (e.g. [`CacheManager::backend`]) first.
+pub type SharedCacheManager = Arc<parking_lot::Mutex<CacheManager>>;
+
+/// Time a cached recipe list page or ingredient list stays valid
+///
+/// Writes through `db::*_cached` evict entries right away; the TTL only bounds
+/// staleness after writes made without the cache handle. Recipe data is not cached
+/// at all with a shared backend, see [`CacheManager::caches_recipes`].
+pub const RECIPE_CACHE_TTL: Duration = Duration::from_secs(120);
+
+/// Pages of a user's recipe names, keyed by `(limit, offset)`
+pub type RecipeListPages = HashMap<(i64, i64), (Vec<String>, i64)>;
+
+/// A user's recipes looked up by name, keyed by the name as selected
+pub type RecipesByName = HashMap<String, Vec<crate::db::Recipe>>;
+
 /// Global cache manager for coordinating multiple caches
 pub struct CacheManager {
     /// OCR result cache
     pub ocr_cache: OcrResultCache,
+    /// Photos recently sent per chat, keyed by content hash
+    pub ocr_result_cache: RecentPhotoCache,
+    /// Photos of albums being received, read together once complete
+    pub album_buffer: AlbumBuffer,
+    /// Images without ingredients, kept for a retry with other processing
+    pub retained_images: RetainedImageCache,
+    /// Pages displayed per paginated message and flood waits per chat
+    pub pagination: PaginationThrottle,
     /// Database query cache
     pub db_cache: DbQueryCache,
-    /// User data cache
-    pub user_cache: MemoryCache<i64, crate::db::User>,
+    /// Backend of the cached user lookups, in memory or shared through Redis
+    pub backend: Arc<dyn CacheBackend>,
     /// Recipe data cache
-    pub recipe_cache: MemoryCache<i64, crate::db::Recipe>,
+    pub recipe_cache: MemoryCache<crate::db::RecipeId, crate::db::Recipe>,
+    /// Pages of recipe names per user
+    pub recipe_list_cache: MemoryCache<crate::db::TelegramId, RecipeListPages>,
+    /// Recipes per user and name, evicted together with the user's recipe list
+    pub recipes_by_name_cache: MemoryCache<crate::db::TelegramId, RecipesByName>,
+    /// Ingredients per recipe
+    pub recipe_ingredients_cache: MemoryCache<crate::db::RecipeId, Vec<crate::db::Ingredient>>,
 }
 
 impl CacheManager {
-    /// Create a new cache manager with default settings
+    /// Create a new cache manager with default settings and an in-memory backend
     pub fn new() -> Self {
+        Self::with_backend(Arc::new(MemoryCacheBackend::new()))
+    }
+
+    /// Create a cache manager with default settings around the given backend
+    pub fn with_backend(backend: Arc<dyn CacheBackend>) -> Self {
         Self {
             ocr_cache: OcrResultCache::new(Duration::from_secs(3600)), // 1 hour
+            ocr_result_cache: RecentPhotoCache::new(duplicate_photo_window()),
+            album_buffer: AlbumBuffer::new(),
+            retained_images: RetainedImageCache::new(retained_image_ttl()),
+            pagination: PaginationThrottle::new(),
             db_cache: DbQueryCache::new(Duration::from_secs(300), 50 * 1024 * 1024), // 5 min, 50MB
-            user_cache: MemoryCache::new(),
+            backend,
             recipe_cache: MemoryCache::new(),
+            recipe_list_cache: MemoryCache::new(),
+            recipes_by_name_cache: MemoryCache::new(),
+            recipe_ingredients_cache: MemoryCache::new(),
         }
     }
 
@@ -510,31 +884,165 @@ impl CacheManager {
     ) -> Self {
         Self {
             ocr_cache: OcrResultCache::new(ocr_ttl),
+            ocr_result_cache: RecentPhotoCache::new(duplicate_photo_window()),
+            album_buffer: AlbumBuffer::new(),
+            retained_images: RetainedImageCache::new(retained_image_ttl()),
+            pagination: PaginationThrottle::new(),
             db_cache: DbQueryCache::new(db_ttl, db_max_size_bytes),
-            user_cache: MemoryCache::new(),
+            backend: Arc::new(MemoryCacheBackend::new()),
             recipe_cache: MemoryCache::new(),
+            recipe_list_cache: MemoryCache::new(),
+            recipes_by_name_cache: MemoryCache::new(),
+            recipe_ingredients_cache: MemoryCache::new(),
         }
     }
 
-    /// Find a user by internal ID across the user cache
-    pub fn find_user_by_id(&self, user_id: i64) -> Option<crate::db::User> {
-        // This is not the most efficient approach, but works for small caches
-        // In production, you might want a separate cache or index
-        let data = self.user_cache.read_data();
-        for (_, entry) in data.iter() {
-            if !entry.is_expired() && entry.value.id == user_id {
-                return Some(entry.value.clone());
-            }
+    /// Shared handle on the backend, to use after releasing the manager's lock
+    pub fn backend(&self) -> Arc<dyn CacheBackend> {
+        Arc::clone(&self.backend)
+    }
+
+    /// Whether recipes, recipe lists and ingredients are cached in this process
+    ///
+    /// False with a shared backend: another bot process writing a recipe could not
+    /// evict this process's copy, which would stay stale until it expires.
+    pub fn caches_recipes(&self) -> bool {
+        !self.backend.is_shared()
+    }
+
+    /// Drop the recipes cached for a user, once their data is deleted
+    ///
+    /// The user entry itself lives in the backend, see
+    /// [`crate::cache_backend::invalidate_user`].
+    pub fn invalidate_user(&mut self, telegram_id: crate::db::TelegramId) {
+        let recipe_ids: Vec<crate::db::RecipeId> = {
+            let mut recipes = self.recipe_cache.write_data();
+            let ids = recipes
+                .iter()
+                .filter(|(_, entry)| entry.value.telegram_id == telegram_id)
+                .map(|(id, _)| *id)
+                .collect();
+            recipes.retain(|_, entry| entry.value.telegram_id != telegram_id);
+            ids
+        };
+        for recipe_id in recipe_ids {
+            self.invalidate_recipe_ingredients(recipe_id);
+        }
+        self.invalidate_recipe_list(telegram_id);
+    }
+
+    /// Get a cached page of a user's recipe names
+    pub fn recipe_list_page(
+        &self,
+        telegram_id: crate::db::TelegramId,
+        limit: i64,
+        offset: i64,
+    ) -> Option<(Vec<String>, i64)> {
+        let page = self
+            .recipe_list_cache
+            .read_data()
+            .get(&telegram_id)
+            .filter(|entry| !entry.is_expired())
+            .and_then(|entry| entry.value.get(&(limit, offset)).cloned());
+
+        let mut stats = self.recipe_list_cache.write_stats();
+        if page.is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        page
+    }
+
+    /// Cache a page of a user's recipe names next to the user's other pages
+    pub fn insert_recipe_list_page(
+        &mut self,
+        telegram_id: crate::db::TelegramId,
+        limit: i64,
+        offset: i64,
+        page: (Vec<String>, i64),
+    ) {
+        let mut lists = self.recipe_list_cache.write_data();
+        let entry = lists
+            .entry(telegram_id)
+            .or_insert_with(|| CacheEntry::new(RecipeListPages::new(), RECIPE_CACHE_TTL));
+        if entry.is_expired() {
+            *entry = CacheEntry::new(RecipeListPages::new(), RECIPE_CACHE_TTL);
         }
-        None
+        entry.value.insert((limit, offset), page);
+    }
+
+    /// Get a user's cached recipes with the given name
+    pub fn recipes_by_name(
+        &self,
+        telegram_id: crate::db::TelegramId,
+        recipe_name: &str,
+    ) -> Option<Vec<crate::db::Recipe>> {
+        let recipes = self
+            .recipes_by_name_cache
+            .read_data()
+            .get(&telegram_id)
+            .filter(|entry| !entry.is_expired())
+            .and_then(|entry| entry.value.get(recipe_name).cloned());
+
+        let mut stats = self.recipes_by_name_cache.write_stats();
+        if recipes.is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        recipes
+    }
+
+    /// Cache a user's recipes with the given name next to the user's other lookups
+    pub fn insert_recipes_by_name(
+        &mut self,
+        telegram_id: crate::db::TelegramId,
+        recipe_name: &str,
+        recipes: Vec<crate::db::Recipe>,
+    ) {
+        let mut lookups = self.recipes_by_name_cache.write_data();
+        let entry = lookups
+            .entry(telegram_id)
+            .or_insert_with(|| CacheEntry::new(RecipesByName::new(), RECIPE_CACHE_TTL));
+        if entry.is_expired() {
+            *entry = CacheEntry::new(RecipesByName::new(), RECIPE_CACHE_TTL);
+        }
+        entry.value.insert(recipe_name.to_string(), recipes);
+    }
+
+    /// Drop every cached page and name lookup of a user's recipe list
+    pub fn invalidate_recipe_list(&mut self, telegram_id: crate::db::TelegramId) {
+        self.recipe_list_cache.remove(&telegram_id);
+        self.recipes_by_name_cache.remove(&telegram_id);
+    }
+
+    /// Drop a cached recipe, once it is renamed or deleted
+    pub fn invalidate_recipe(&mut self, recipe_id: crate::db::RecipeId) {
+        self.recipe_cache.remove(&recipe_id);
+    }
+
+    /// Drop the cached ingredients of a recipe
+    pub fn invalidate_recipe_ingredients(&mut self, recipe_id: crate::db::RecipeId) {
+        self.recipe_ingredients_cache.remove(&recipe_id);
+    }
+
+    /// Drop the cached ingredients of every recipe, for writes spanning unknown recipes
+    pub fn invalidate_all_recipe_ingredients(&mut self) {
+        self.recipe_ingredients_cache.write_data().clear();
     }
 
     /// Clean up all expired entries across all caches
     pub fn cleanup_all(&mut self) {
         self.ocr_cache.cleanup();
+        self.ocr_result_cache.cleanup();
+        self.retained_images.cleanup();
+        self.pagination.cleanup();
         self.db_cache.cleanup();
-        // Note: user_cache and recipe_cache cleanup would need to be implemented
-        // if they had their own cleanup methods
+        self.recipe_cache.cleanup();
+        self.recipe_list_cache.cleanup();
+        self.recipes_by_name_cache.cleanup();
+        self.recipe_ingredients_cache.cleanup();
     }
 
     /// Get comprehensive cache statistics
@@ -542,8 +1050,11 @@ impl CacheManager {
         CacheManagerStats {
             ocr_cache: self.ocr_cache.stats(),
             db_cache: self.db_cache.stats(),
-            user_cache_entries: self.user_cache.len(),
+            cache_backend: self.backend.name(),
             recipe_cache_entries: self.recipe_cache.len(),
+            recipe_list_cache: self.recipe_list_cache.stats(),
+            recipes_by_name_cache: self.recipes_by_name_cache.stats(),
+            recipe_ingredients_cache: self.recipe_ingredients_cache.stats(),
             db_cache_size_bytes: self.db_cache.current_size_bytes(),
             db_cache_max_size_bytes: self.db_cache.max_size_bytes(),
         }
@@ -552,9 +1063,15 @@ impl CacheManager {
     /// Clear all caches
     pub fn clear_all(&mut self) {
         self.ocr_cache.clear();
+        self.ocr_result_cache.clear();
+        self.album_buffer.clear();
+        self.retained_images.clear();
+        self.pagination.clear();
         self.db_cache.clear();
-        self.user_cache.clear();
         self.recipe_cache.clear();
+        self.recipe_list_cache.clear();
+        self.recipes_by_name_cache.clear();
+        self.recipe_ingredients_cache.clear();
     }
 }
 
@@ -564,6 +1081,24 @@ impl Default for CacheManager {
     }
 }
 
+impl std::fmt::Debug for CacheManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheManager")
+            .field("backend", &self.backend.name())
+            .field("recipe_cache_entries", &self.recipe_cache.len())
+            .field("recipe_list_cache_entries", &self.recipe_list_cache.len())
+            .field(
+                "recipes_by_name_cache_entries",
+                &self.recipes_by_name_cache.len(),
+            )
+            .field(
+                "recipe_ingredients_cache_entries",
+                &self.recipe_ingredients_cache.len(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
 /// Comprehensive cache statistics for the cache manager
 #[derive(Debug, Clone)]
 pub struct CacheManagerStats {
@@ -571,10 +1106,16 @@ pub struct CacheManagerStats {
     pub ocr_cache: CacheStats,
     /// Database cache statistics
     pub db_cache: CacheStats,
-    /// Number of user entries in cache
-    pub user_cache_entries: usize,
+    /// Backend of the cached user lookups
+    pub cache_backend: &'static str,
     /// Number of recipe entries in cache
     pub recipe_cache_entries: usize,
+    /// Recipe list cache statistics
+    pub recipe_list_cache: CacheStats,
+    /// Recipes by name cache statistics
+    pub recipes_by_name_cache: CacheStats,
+    /// Recipe ingredients cache statistics
+    pub recipe_ingredients_cache: CacheStats,
     /// Current database cache size in bytes
     pub db_cache_size_bytes: usize,
     /// Maximum database cache size in bytes
@@ -587,6 +1128,11 @@ mod tests {
     use std::thread;
     use std::time::Duration;
 
+    #[test]
+    fn test_process_local_backend_caches_recipes() {
+        assert!(CacheManager::new().caches_recipes());
+    }
+
     #[test]
     fn test_memory_cache_basic_operations() {
         let mut cache = MemoryCache::new();
@@ -648,6 +1194,245 @@ mod tests {
         assert_ne!(key1, key3);
     }
 
+    #[test]
+    fn test_recent_photo_cache_detects_identical_bytes() {
+        let mut cache = RecentPhotoCache::new(Duration::from_secs(60));
+        let photo = image_content_hash(b"same photo bytes");
+
+        assert!(!cache.check_and_record(1, &photo));
+        assert!(cache.check_and_record(1, &image_content_hash(b"same photo bytes")));
+        // The same photo in another chat is not a duplicate
+        assert!(!cache.check_and_record(2, &photo));
+    }
+
+    #[test]
+    fn test_recent_photo_cache_ignores_different_bytes() {
+        let mut cache = RecentPhotoCache::new(Duration::from_secs(60));
+
+        assert!(!cache.check_and_record(1, &image_content_hash(b"first photo")));
+        assert!(!cache.check_and_record(1, &image_content_hash(b"second photo")));
+        assert_ne!(
+            image_content_hash(b"first photo"),
+            image_content_hash(b"second photo")
+        );
+    }
+
+    #[test]
+    fn test_recent_photo_cache_forget_allows_retry() {
+        let mut cache = RecentPhotoCache::new(Duration::from_secs(60));
+        let photo = image_content_hash(b"unreadable photo");
+
+        assert!(!cache.check_and_record(1, &photo));
+        cache.forget(1, &photo);
+        assert!(!cache.check_and_record(1, &photo));
+        assert!(cache.check_and_record(1, &photo));
+    }
+
+    #[test]
+    fn test_recent_photo_cache_expiry() {
+        let mut cache = RecentPhotoCache::new(Duration::from_millis(10));
+        let photo = image_content_hash(b"photo");
+
+        assert!(!cache.check_and_record(1, &photo));
+        thread::sleep(Duration::from_millis(20));
+        // Once the window has passed the photo is processed again
+        assert!(!cache.check_and_record(1, &photo));
+        assert!(cache.check_and_record(1, &photo));
+    }
+
+    #[test]
+    fn test_pagination_throttle_ignores_displayed_page() {
+        let mut throttle = PaginationThrottle::new();
+
+        // A burst of taps on Next from page 0 edits the message once
+        assert!(throttle.claim_page(1, 10, 1));
+        assert!(!throttle.claim_page(1, 10, 1));
+        assert!(!throttle.claim_page(1, 10, 1));
+
+        // Other pages, messages and chats are not affected
+        assert!(throttle.claim_page(1, 10, 2));
+        assert!(throttle.claim_page(1, 10, 1));
+        assert!(throttle.claim_page(1, 11, 1));
+        assert!(throttle.claim_page(2, 10, 1));
+
+        // A failed edit does not leave the page marked as displayed
+        throttle.forget_page(1, 10);
+        assert!(throttle.claim_page(1, 10, 1));
+    }
+
+    #[test]
+    fn test_pagination_throttle_flood_wait() {
+        let mut throttle = PaginationThrottle::new();
+        assert_eq!(throttle.flood_wait_remaining(1), None);
+
+        throttle.record_flood_wait(1, Duration::from_millis(30));
+        let remaining = throttle.flood_wait_remaining(1).unwrap();
+        assert!(remaining <= Duration::from_millis(30) && !remaining.is_zero());
+        assert_eq!(throttle.flood_wait_remaining(2), None);
+
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(throttle.flood_wait_remaining(1), None);
+    }
+
+    fn album_photo(message_id: i32, caption: Option<&str>) -> AlbumPhoto {
+        AlbumPhoto {
+            message_id,
+            file_id: format!("file-{}", message_id),
+            file_unique_id: format!("unique-{}", message_id),
+            caption: caption.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_album_buffer_collects_photos_per_album() {
+        let mut buffer = AlbumBuffer::new();
+
+        assert!(buffer.push(1, "album", album_photo(11, None)));
+        assert!(!buffer.push(1, "album", album_photo(10, Some("Crêpes"))));
+        // Same media group id in another chat is another album
+        assert!(buffer.push(2, "album", album_photo(12, None)));
+        assert_eq!(buffer.len(), 2);
+
+        let photos = buffer.take(1, "album");
+        assert_eq!(
+            photos.iter().map(|p| p.message_id).collect::<Vec<_>>(),
+            vec![10, 11]
+        );
+        assert_eq!(photos[0].caption.as_deref(), Some("Crêpes"));
+        assert!(buffer.take(1, "album").is_empty());
+
+        // A later album with the same id starts over
+        assert!(buffer.push(1, "album", album_photo(20, None)));
+    }
+
+    #[test]
+    fn test_retained_image_is_taken_once_and_expires() {
+        let image = RetainedImage {
+            bytes: vec![1, 2, 3],
+            file_id: "photo".to_string(),
+            caption: Some("Crêpes".to_string()),
+            photo: None,
+        };
+        let mut retained = RetainedImageCache::new(Duration::from_secs(60));
+        retained.retain(1, 10, image.clone());
+
+        // Another message of the same chat has nothing to retry
+        assert_eq!(retained.take(1, 11), None);
+        assert_eq!(retained.take(1, 10), Some(image.clone()));
+        assert_eq!(retained.take(1, 10), None);
+
+        let mut short_lived = RetainedImageCache::new(Duration::from_millis(10));
+        short_lived.retain(1, 10, image);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(short_lived.take(1, 10), None);
+    }
+
+    #[test]
+    fn test_invalidate_user_drops_recipes() {
+        use crate::db::{Recipe, RecipeId, TelegramId};
+        let now = chrono::Utc::now();
+        let recipe = |id, telegram_id| Recipe {
+            id: RecipeId(id),
+            telegram_id: TelegramId(telegram_id),
+            content: String::new(),
+            recipe_name: None,
+            created_at: now,
+            notes: None,
+        };
+        let mut manager = CacheManager::new();
+        let ttl = Duration::from_secs(60);
+        manager
+            .recipe_cache
+            .insert(RecipeId(10), recipe(10, 1), ttl);
+        manager
+            .recipe_cache
+            .insert(RecipeId(20), recipe(20, 2), ttl);
+
+        manager.invalidate_user(TelegramId(1));
+
+        assert!(manager.recipe_cache.get(&RecipeId(10)).is_none());
+        assert!(manager.recipe_cache.get(&RecipeId(20)).is_some());
+    }
+
+    #[test]
+    fn test_recipe_list_pages_are_invalidated_per_user() {
+        use crate::db::TelegramId;
+        let mut manager = CacheManager::new();
+        let page = |name: &str| (vec![name.to_string()], 1);
+        manager.insert_recipe_list_page(TelegramId(1), 5, 0, page("Crêpes"));
+        manager.insert_recipe_list_page(TelegramId(1), 5, 5, page("Tarte"));
+        manager.insert_recipe_list_page(TelegramId(2), 5, 0, page("Gaufres"));
+
+        assert_eq!(
+            manager.recipe_list_page(TelegramId(1), 5, 0),
+            Some(page("Crêpes"))
+        );
+        assert_eq!(manager.recipe_list_page(TelegramId(1), 10, 0), None);
+
+        manager.invalidate_recipe_list(TelegramId(1));
+
+        assert_eq!(manager.recipe_list_page(TelegramId(1), 5, 0), None);
+        assert_eq!(manager.recipe_list_page(TelegramId(1), 5, 5), None);
+        assert_eq!(
+            manager.recipe_list_page(TelegramId(2), 5, 0),
+            Some(page("Gaufres"))
+        );
+    }
+
+    #[test]
+    fn test_recipes_by_name_are_invalidated_with_recipe_list() {
+        use crate::db::{Recipe, RecipeId, TelegramId};
+        let recipe = Recipe {
+            id: RecipeId(10),
+            telegram_id: TelegramId(1),
+            content: String::new(),
+            recipe_name: Some("Crêpes".to_string()),
+            created_at: chrono::Utc::now(),
+            notes: None,
+        };
+        let mut manager = CacheManager::new();
+        manager.insert_recipes_by_name(TelegramId(1), "Crêpes", vec![recipe]);
+
+        assert!(manager.recipes_by_name(TelegramId(1), "Crêpes").is_some());
+        assert!(manager.recipes_by_name(TelegramId(1), "Tarte").is_none());
+        assert!(manager.recipes_by_name(TelegramId(2), "Crêpes").is_none());
+        let stats = manager.stats().recipes_by_name_cache;
+        assert_eq!((stats.hits, stats.misses), (1, 2));
+
+        manager.invalidate_recipe_list(TelegramId(1));
+
+        assert!(manager.recipes_by_name(TelegramId(1), "Crêpes").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_manager_usable_after_panicking_task() {
+        let cache: SharedCacheManager = Arc::new(parking_lot::Mutex::new(CacheManager::new()));
+
+        let panicking = Arc::clone(&cache);
+        let result = tokio::spawn(async move {
+            let _guard = panicking.lock();
+            panic!("handler panicked while holding the cache");
+        })
+        .await;
+        assert!(result.is_err());
+
+        let tasks: Vec<_> = (0..4)
+            .map(|chat_id| {
+                let cache = Arc::clone(&cache);
+                tokio::spawn(async move {
+                    cache
+                        .lock()
+                        .ocr_result_cache
+                        .check_and_record(chat_id, "hash")
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert!(!task.await.expect("Cache access should not panic"));
+        }
+        assert!(cache.lock().ocr_result_cache.check_and_record(0, "hash"));
+    }
+
     #[test]
     fn test_db_cache_size_management() {
         let mut cache = DbQueryCache::new(Duration::from_secs(60), 100); // 100 bytes max
@@ -0,0 +1,360 @@
+//! # Cache Backend Module
+//!
+//! Storage behind the cached database lookups (`db::*_cached`). The in-memory
+//! backend keeps entries in the bot process; the Redis backend keeps them across
+//! restarts and shares them between bot processes. Recipe data is only cached
+//! with the in-memory backend, since it lives in per-process caches that other
+//! processes' writes cannot evict.
+//!
+//! The backend is chosen with `CACHE_BACKEND` (`memory` or `redis`, default
+//! `memory`); Redis also needs `REDIS_URL`. Values are stored as JSON under
+//! namespaced string keys, and every entry carries its own TTL.
+//!
+//! Cache failures never fail a request: a Redis error is logged, counted in
+//! `cache_backend_errors_total` and treated as a miss, so lookups fall through
+//! to Postgres.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::cache::CacheEntry;
+use crate::db::{TelegramId, User, UserId};
+
+/// Time allowed for a single Redis command before it counts as a miss
+const REDIS_COMMAND_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Entry count above which expired in-memory entries are dropped on insert
+const MEMORY_PRUNE_THRESHOLD: usize = 1024;
+
+/// Prefix of every key written to Redis, so the database can be shared
+const REDIS_KEY_PREFIX: &str = "just_ingredients:";
+
+/// Key/value store with per-entry TTLs used by the cached lookups
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    /// Get a live entry, `None` on a miss, an expired entry or a backend error
+    async fn get(&self, key: &str) -> Option<Vec<u8>>;
+
+    /// Store an entry that expires after `ttl`
+    async fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration);
+
+    /// Remove an entry if present
+    async fn invalidate(&self, key: &str);
+
+    /// Backend name used in logs and metrics
+    fn name(&self) -> &'static str;
+
+    /// Whether other bot processes read and write the same entries
+    ///
+    /// Data cached only in this process cannot be evicted by their writes, so it
+    /// must not be cached locally when this is true.
+    fn is_shared(&self) -> bool {
+        false
+    }
+}
+
+/// Process-local backend, lost on restart
+#[derive(Default)]
+pub struct MemoryCacheBackend {
+    entries: RwLock<HashMap<String, CacheEntry<Vec<u8>>>>,
+}
+
+impl MemoryCacheBackend {
+    /// Create an empty in-memory backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CacheBackend for MemoryCacheBackend {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let entries = self.entries.read();
+        entries
+            .get(key)
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| entry.value.clone())
+    }
+
+    async fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        let mut entries = self.entries.write();
+        if entries.len() >= MEMORY_PRUNE_THRESHOLD {
+            entries.retain(|_, entry| !entry.is_expired());
+        }
+        entries.insert(key.to_string(), CacheEntry::new(value, ttl));
+    }
+
+    async fn invalidate(&self, key: &str) {
+        self.entries.write().remove(key);
+    }
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+}
+
+/// Redis backend, shared between bot processes and kept across restarts
+pub struct RedisCacheBackend {
+    connection: redis::aio::ConnectionManager,
+}
+
+impl RedisCacheBackend {
+    /// Connect to the Redis server at `url`
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = tokio::time::timeout(
+            Duration::from_secs(5),
+            redis::aio::ConnectionManager::new(client),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out connecting to Redis"))??;
+        Ok(Self { connection })
+    }
+
+    /// Run a command with a timeout, turning any failure into `None`
+    async fn run<T: redis::FromRedisValue>(
+        &self,
+        operation: &'static str,
+        command: redis::Cmd,
+    ) -> Option<T> {
+        let mut connection = self.connection.clone();
+        let result =
+            tokio::time::timeout(REDIS_COMMAND_TIMEOUT, command.query_async(&mut connection)).await;
+        match result {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                record_backend_error(self.name(), operation, &e);
+                None
+            }
+            Err(_) => {
+                record_backend_error(self.name(), operation, &"command timed out");
+                None
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl CacheBackend for RedisCacheBackend {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        let mut command = redis::cmd("GET");
+        command.arg(format!("{REDIS_KEY_PREFIX}{key}"));
+        self.run::<Option<Vec<u8>>>("get", command).await.flatten()
+    }
+
+    async fn insert(&self, key: &str, value: Vec<u8>, ttl: Duration) {
+        // Redis rejects a zero expiry; such an entry would be expired anyway
+        let ttl_ms = ttl.as_millis().min(u64::MAX as u128) as u64;
+        if ttl_ms == 0 {
+            return;
+        }
+        let mut command = redis::cmd("SET");
+        command
+            .arg(format!("{REDIS_KEY_PREFIX}{key}"))
+            .arg(value)
+            .arg("PX")
+            .arg(ttl_ms);
+        self.run::<()>("insert", command).await;
+    }
+
+    async fn invalidate(&self, key: &str) {
+        let mut command = redis::cmd("DEL");
+        command.arg(format!("{REDIS_KEY_PREFIX}{key}"));
+        self.run::<()>("invalidate", command).await;
+    }
+
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+}
+
+/// Log a backend failure and count it, the lookup continues against Postgres
+fn record_backend_error(
+    backend: &'static str,
+    operation: &'static str,
+    error: &dyn std::fmt::Display,
+) {
+    metrics::counter!(
+        "cache_backend_errors_total",
+        "backend" => backend,
+        "operation" => operation
+    )
+    .increment(1);
+    warn!(backend, operation, error = %error, "Cache backend error, falling back to the database");
+}
+
+/// Backend selected by `CACHE_BACKEND` and `REDIS_URL`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheBackendKind {
+    /// Entries kept in the bot process
+    Memory,
+    /// Entries kept in the Redis server at `url`
+    Redis { url: String },
+}
+
+/// Create the selected backend
+///
+/// Falls back to the in-memory backend, with a warning, when Redis is unreachable.
+pub async fn connect_cache_backend(kind: &CacheBackendKind) -> Arc<dyn CacheBackend> {
+    if let CacheBackendKind::Redis { url } = kind {
+        match RedisCacheBackend::connect(url).await {
+            Ok(backend) => {
+                info!("Using Redis cache backend");
+                return Arc::new(backend);
+            }
+            Err(e) => warn!(error = %e, "Failed to connect to Redis, using the in-memory cache"),
+        }
+    }
+
+    info!("Using in-memory cache backend");
+    Arc::new(MemoryCacheBackend::new())
+}
+
+/// Get and decode a JSON entry; undecodable entries count as misses
+pub async fn get_json<T: DeserializeOwned>(backend: &dyn CacheBackend, key: &str) -> Option<T> {
+    let bytes = backend.get(key).await?;
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            record_backend_error(backend.name(), "decode", &e);
+            None
+        }
+    }
+}
+
+/// Encode and store a JSON entry
+pub async fn insert_json<T: Serialize>(
+    backend: &dyn CacheBackend,
+    key: &str,
+    value: &T,
+    ttl: Duration,
+) {
+    match serde_json::to_vec(value) {
+        Ok(bytes) => backend.insert(key, bytes, ttl).await,
+        Err(e) => record_backend_error(backend.name(), "encode", &e),
+    }
+}
+
+/// Key of a user cached by Telegram ID
+pub fn user_by_telegram_id_key(telegram_id: TelegramId) -> String {
+    format!("user:tg:{}", telegram_id.0)
+}
+
+/// Key of a user cached by internal ID
+pub fn user_by_id_key(user_id: UserId) -> String {
+    format!("user:id:{}", user_id.0)
+}
+
+/// Cache a user under both of its IDs
+pub async fn cache_user(backend: &dyn CacheBackend, user: &User, ttl: Duration) {
+    insert_json(
+        backend,
+        &user_by_telegram_id_key(user.telegram_id),
+        user,
+        ttl,
+    )
+    .await;
+    insert_json(backend, &user_by_id_key(user.id), user, ttl).await;
+}
+
+/// Drop a cached user under both of its IDs
+pub async fn invalidate_user(backend: &dyn CacheBackend, telegram_id: TelegramId) {
+    let key = user_by_telegram_id_key(telegram_id);
+    if let Some(user) = get_json::<User>(backend, &key).await {
+        backend.invalidate(&user_by_id_key(user.id)).await;
+    }
+    backend.invalidate(&key).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn test_user(id: i64, telegram_id: i64) -> User {
+        User {
+            id: UserId(id),
+            telegram_id: TelegramId(telegram_id),
+            language_code: "en".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_backend_honors_ttl() {
+        let backend = MemoryCacheBackend::new();
+        backend
+            .insert("short", b"a".to_vec(), Duration::from_millis(20))
+            .await;
+        backend
+            .insert("long", b"b".to_vec(), Duration::from_secs(60))
+            .await;
+
+        assert_eq!(backend.get("short").await, Some(b"a".to_vec()));
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(backend.get("short").await, None);
+        assert_eq!(backend.get("long").await, Some(b"b".to_vec()));
+
+        backend.invalidate("long").await;
+        assert_eq!(backend.get("long").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_cache_and_invalidate_user() {
+        let backend = MemoryCacheBackend::new();
+        let user = test_user(7, 42);
+        cache_user(&backend, &user, Duration::from_secs(60)).await;
+
+        let by_telegram_id: Option<User> =
+            get_json(&backend, &user_by_telegram_id_key(TelegramId(42))).await;
+        let by_id: Option<User> = get_json(&backend, &user_by_id_key(UserId(7))).await;
+        assert_eq!(by_telegram_id, Some(user.clone()));
+        assert_eq!(by_id, Some(user));
+
+        invalidate_user(&backend, TelegramId(42)).await;
+        assert!(backend
+            .get(&user_by_telegram_id_key(TelegramId(42)))
+            .await
+            .is_none());
+        assert!(backend.get(&user_by_id_key(UserId(7))).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_redis_backend_honors_ttl() {
+        // Only runs against a real server
+        let Some(url) = crate::config::env_lookup("REDIS_URL") else {
+            return;
+        };
+        let backend = RedisCacheBackend::connect(&url)
+            .await
+            .expect("Failed to connect to Redis");
+        let key = format!("test:ttl:{}", std::process::id());
+
+        backend
+            .insert(&key, b"value".to_vec(), Duration::from_millis(200))
+            .await;
+        assert_eq!(backend.get(&key).await, Some(b"value".to_vec()));
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(backend.get(&key).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_undecodable_entry_is_a_miss() {
+        let backend = MemoryCacheBackend::new();
+        backend
+            .insert("user:tg:1", b"not json".to_vec(), Duration::from_secs(60))
+            .await;
+        assert!(get_json::<User>(&backend, "user:tg:1").await.is_none());
+    }
+}
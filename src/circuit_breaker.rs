@@ -77,9 +77,19 @@ use crate::ocr_config::RecoveryConfig;
 pub struct CircuitBreaker {
     failure_count: Mutex<u32>,
     last_failure_time: Mutex<Option<Instant>>,
+    open_time: Mutex<OpenTime>,
     config: RecoveryConfig,
 }
 
+/// Time spent open, kept for the maintainer OCR digest
+#[derive(Debug, Default)]
+struct OpenTime {
+    /// Total duration of open windows that already ended
+    closed_windows: Duration,
+    /// Start and scheduled end of the latest open window
+    current_window: Option<(Instant, Instant)>,
+}
+
 impl CircuitBreaker {
     /// Create a new circuit breaker with the given configuration
     ///
@@ -100,6 +110,7 @@ impl CircuitBreaker {
         Self {
             failure_count: Mutex::new(0),
             last_failure_time: Mutex::new(None),
+            open_time: Mutex::new(OpenTime::default()),
             config,
         }
     }
@@ -185,14 +196,36 @@ impl CircuitBreaker {
     ///
     /// Uses internal mutex for thread-safe updates.
     pub fn record_failure(&self) {
-        *self
-            .failure_count
-            .lock()
-            .expect("Failed to acquire failure count lock") += 1;
+        let now = Instant::now();
+        let failure_count = {
+            let mut failure_count = self
+                .failure_count
+                .lock()
+                .expect("Failed to acquire failure count lock");
+            *failure_count += 1;
+            *failure_count
+        };
         *self
             .last_failure_time
             .lock()
-            .expect("Failed to acquire last failure time lock") = Some(Instant::now());
+            .expect("Failed to acquire last failure time lock") = Some(now);
+
+        if failure_count >= self.config.circuit_breaker_threshold {
+            let open_until = now + Duration::from_secs(self.config.circuit_breaker_reset_secs);
+            let mut open_time = self
+                .open_time
+                .lock()
+                .expect("Failed to acquire open time lock");
+            open_time.current_window = match open_time.current_window {
+                // Still open: the new failure pushes the reset further out
+                Some((opened_at, until)) if until > now => Some((opened_at, open_until)),
+                Some((opened_at, until)) => {
+                    open_time.closed_windows += until - opened_at;
+                    Some((now, open_until))
+                }
+                None => Some((now, open_until)),
+            };
+        }
     }
 
     /// Record a success to reset the failure counter
@@ -204,6 +237,18 @@ impl CircuitBreaker {
     ///
     /// Uses internal mutex for thread-safe updates.
     pub fn record_success(&self) {
+        {
+            let now = Instant::now();
+            let mut open_time = self
+                .open_time
+                .lock()
+                .expect("Failed to acquire open time lock");
+            if let Some((opened_at, until)) = open_time.current_window {
+                if until > now {
+                    open_time.current_window = Some((opened_at, now));
+                }
+            }
+        }
         *self
             .failure_count
             .lock()
@@ -213,4 +258,22 @@ impl CircuitBreaker {
             .lock()
             .expect("Failed to acquire last failure time lock") = None;
     }
+
+    /// Total time the circuit has been open since it was created
+    ///
+    /// An open window still in progress counts up to now.
+    pub fn total_open_duration(&self) -> Duration {
+        let open_time = self
+            .open_time
+            .lock()
+            .expect("Failed to acquire open time lock");
+        let current = open_time
+            .current_window
+            .map_or(Duration::ZERO, |(opened_at, until)| {
+                until
+                    .min(Instant::now())
+                    .saturating_duration_since(opened_at)
+            });
+        open_time.closed_windows + current
+    }
 }
@@ -1,21 +1,98 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, FixedOffset, Timelike, Utc};
 use sqlx::postgres::PgPool;
 use sqlx::Row;
-use tracing::{debug, error, info};
+use std::collections::{HashMap, HashSet};
+
+use crate::ingredient_normalization::normalize_ingredient_name;
+use crate::quantity::Quantity;
+use crate::scheduler::WeeklySchedule;
+use crate::text_processing::{classify_unit, UnitDimension, UnitSystem};
+use tracing::{debug, error, info, Instrument};
 
 // Import cache types
-use crate::cache::Cache;
+use crate::cache::{Cache, SharedCacheManager};
+use crate::cache_backend::{
+    cache_user, get_json, user_by_id_key, user_by_telegram_id_key, CacheBackend,
+};
 
 // Re-export types for easier access
 use crate::errors::error_logging;
 pub use crate::observability;
 
+/// Telegram user identifier (`users.telegram_id`, `recipes.telegram_id`)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct TelegramId(pub i64);
+
+/// Internal user identifier (`users.id`, `ingredients.user_id`)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct UserId(pub i64);
+
+/// Recipe identifier (`recipes.id`, `ingredients.recipe_id`)
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(transparent)]
+#[sqlx(transparent)]
+pub struct RecipeId(pub i64);
+
+macro_rules! impl_id_display {
+    ($($id:ty),*) => {
+        $(
+            impl std::fmt::Display for $id {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+impl_id_display!(TelegramId, UserId, RecipeId);
+
 /// Represents a user in the database
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct User {
-    pub id: i64,
-    pub telegram_id: i64,
+    pub id: UserId,
+    pub telegram_id: TelegramId,
     pub language_code: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -24,24 +101,115 @@ pub struct User {
 /// Represents a recipe in the database
 #[derive(Debug, Clone, PartialEq)]
 pub struct Recipe {
-    pub id: i64,
-    pub telegram_id: i64,
+    pub id: RecipeId,
+    pub telegram_id: TelegramId,
     pub content: String,
     pub recipe_name: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// The user's own notes on the recipe, shown with its details
+    pub notes: Option<String>,
+}
+
+/// Original photo a recipe was read from, sent again by its Telegram file id
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RecipePhoto {
+    /// Id to send the photo with, only valid for this bot
+    pub file_id: String,
+    /// Id of the file itself, stable across bots and over time
+    pub file_unique_id: String,
 }
 
 /// Represents an ingredient in the database
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Ingredient {
     pub id: i64,
-    pub user_id: i64,
-    pub recipe_id: Option<i64>,
+    pub user_id: UserId,
+    pub recipe_id: Option<RecipeId>,
     pub name: String,
     pub quantity: Option<f64>,
     pub unit: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// What the unit measures, classified from the measurement units config
+    #[serde(default)]
+    pub unit_dimension: Option<UnitDimension>,
+    /// Which measurement system the unit belongs to
+    #[serde(default)]
+    pub unit_system: Option<UnitSystem>,
+}
+
+/// An ingredient to insert with [`create_ingredients_bulk`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewIngredient<'a> {
+    pub name: &'a str,
+    /// Exact quantity as parsed from the ingredient text
+    pub quantity: Option<Quantity>,
+    pub unit: Option<&'a str>,
+    pub raw_text: Option<&'a str>,
+}
+
+const INGREDIENT_COLUMNS: &str =
+    "id, user_id, recipe_id, name, COALESCE(quantity_numerator::float8 / quantity_denominator, quantity::float8), \
+     unit, created_at, updated_at, unit_dimension, unit_system";
+
+/// Recipe order of ingredients; rows without a position come last
+const INGREDIENT_ORDER: &str = "position NULLS LAST, created_at, id";
+
+fn ingredient_from_row(row: &sqlx::postgres::PgRow) -> Ingredient {
+    let unit_dimension: Option<String> = row.get(8);
+    let unit_system: Option<String> = row.get(9);
+    Ingredient {
+        id: row.get(0),
+        user_id: row.get(1),
+        recipe_id: row.get(2),
+        name: row.get(3),
+        quantity: row.get(4),
+        unit: row.get(5),
+        created_at: row.get(6),
+        updated_at: row.get(7),
+        unit_dimension: unit_dimension.as_deref().and_then(UnitDimension::from_name),
+        unit_system: unit_system.as_deref().and_then(UnitSystem::from_name),
+    }
+}
+
+/// Numerator and denominator columns of an exact quantity
+///
+/// The `quantity` column rounds to three decimals, so 1/3 is read back from these.
+fn exact_fraction(quantity: Option<Quantity>) -> (Option<i64>, Option<i64>) {
+    quantity
+        .map(|quantity| (quantity.numerator(), quantity.denominator()))
+        .unzip()
+}
+
+/// Fraction columns for a quantity only known as a float, recovering the fraction
+/// it was computed from
+fn quantity_fraction(quantity: Option<f64>) -> (Option<i64>, Option<i64>) {
+    exact_fraction(quantity.and_then(Quantity::from_f64))
+}
+
+/// Quantity of a row, exact when its fraction columns are set
+fn stored_quantity(
+    numerator: Option<i64>,
+    denominator: Option<i64>,
+    decimal: Option<f64>,
+) -> Option<Quantity> {
+    numerator
+        .zip(denominator)
+        .and_then(|(numerator, denominator)| Quantity::new(numerator, denominator))
+        .or_else(|| decimal.and_then(Quantity::from_f64))
+}
+
+/// Dimension and system names stored alongside a unit
+fn unit_metadata(unit: Option<&str>) -> (Option<&'static str>, Option<&'static str>) {
+    unit.and_then(classify_unit)
+        .map(|(dimension, system)| (dimension.as_str(), system.as_str()))
+        .unzip()
+}
+
+/// Canonical name and notes stored next to an ingredient name
+fn name_metadata(name: &str) -> (String, Option<String>) {
+    let normalized = normalize_ingredient_name(name);
+    (normalized.name, normalized.notes)
 }
 
 /// Initialize the database schema using the migration system
@@ -56,45 +224,133 @@ pub async fn init_database_schema(pool: &PgPool) -> Result<()> {
 }
 
 /// Create a new recipe in the database
-pub async fn create_recipe(pool: &PgPool, telegram_id: i64, content: &str) -> Result<i64> {
-    let span = crate::observability::db_span("create_recipe", "recipes");
-    let _enter = span.enter();
+pub async fn create_recipe(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    content: &str,
+) -> Result<RecipeId> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Failed to acquire connection")?;
+    create_recipe_tx(&mut conn, telegram_id, content).await
+}
 
-    let start_time = std::time::Instant::now();
-    debug!(telegram_id = %telegram_id, "Creating new recipe");
+/// Create a new recipe on a connection, typically a transaction's
+pub async fn create_recipe_tx(
+    conn: &mut sqlx::PgConnection,
+    telegram_id: TelegramId,
+    content: &str,
+) -> Result<RecipeId> {
+    let span = crate::observability::db_span("create_recipe", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, "Creating new recipe");
+
+        let result = sqlx::query!(
+            "INSERT INTO recipes (telegram_id, content) VALUES ($1, $2) RETURNING id",
+            telegram_id.0,
+            content
+        )
+        .fetch_one(conn)
+        .await
+        .context("Failed to insert new recipe");
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "create_recipe",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    let result = sqlx::query!(
-        "INSERT INTO recipes (telegram_id, content) VALUES ($1, $2) RETURNING id",
-        telegram_id,
-        content
-    )
-    .fetch_one(pool)
+        match result {
+            Ok(row) => {
+                let recipe_id = RecipeId(row.id);
+                debug!(recipe_id = %recipe_id, duration_ms = %duration.as_millis(), telegram_id = %telegram_id, "Recipe created successfully");
+                Ok(recipe_id)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    .instrument(span)
     .await
-    .context("Failed to insert new recipe");
+}
 
-    let duration = start_time.elapsed();
-    observability::record_db_performance_metrics(
-        "create_recipe",
-        duration,
-        1,
-        crate::observability::QueryComplexity::Simple,
-    );
+/// Create a recipe unless one was already created with the same idempotency key
+///
+/// Returns the recipe id and whether it was newly created. A retried save carrying
+/// the same key returns the existing recipe instead of inserting a second one.
+pub async fn create_recipe_idempotent(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    content: &str,
+    idempotency_key: &str,
+) -> Result<(RecipeId, bool)> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Failed to acquire connection")?;
+    create_recipe_idempotent_tx(&mut conn, telegram_id, content, idempotency_key).await
+}
 
-    match result {
-        Ok(row) => {
-            let recipe_id: i64 = row.id;
-            debug!(recipe_id = %recipe_id, duration_ms = %duration.as_millis(), telegram_id = %telegram_id, "Recipe created successfully");
-            Ok(recipe_id)
-        }
-        Err(e) => Err(e),
+/// Create a recipe unless one exists with the same idempotency key, on a connection
+pub async fn create_recipe_idempotent_tx(
+    conn: &mut sqlx::PgConnection,
+    telegram_id: TelegramId,
+    content: &str,
+    idempotency_key: &str,
+) -> Result<(RecipeId, bool)> {
+    let span = crate::observability::db_span("create_recipe_idempotent", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, idempotency_key = %idempotency_key, "Creating recipe idempotently");
+
+        let inserted: Option<i64> = sqlx::query_scalar(
+            "INSERT INTO recipes (telegram_id, content, idempotency_key) VALUES ($1, $2, $3)
+         ON CONFLICT (telegram_id, idempotency_key) WHERE idempotency_key IS NOT NULL DO NOTHING
+         RETURNING id",
+        )
+        .bind(telegram_id.0)
+        .bind(content)
+        .bind(idempotency_key)
+        .fetch_optional(&mut *conn)
+        .await
+        .context("Failed to insert new recipe")?;
+
+        let result = match inserted {
+            Some(id) => (RecipeId(id), true),
+            None => {
+                let existing: i64 = sqlx::query_scalar(
+                    "SELECT id FROM recipes WHERE telegram_id = $1 AND idempotency_key = $2",
+                )
+                .bind(telegram_id.0)
+                .bind(idempotency_key)
+                .fetch_one(&mut *conn)
+                .await
+                .context("Failed to read recipe for idempotency key")?;
+                (RecipeId(existing), false)
+            }
+        };
+
+        observability::record_db_performance_metrics(
+            "create_recipe_idempotent",
+            start_time.elapsed(),
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        Ok(result)
     }
+    .instrument(span)
+    .await
 }
 
 /// Read a recipe from the database by ID
-pub async fn read_recipe(pool: &PgPool, recipe_id: i64) -> Result<Option<Recipe>> {
+pub async fn read_recipe(pool: &PgPool, recipe_id: RecipeId) -> Result<Option<Recipe>> {
     debug!(recipe_id = %recipe_id, "Reading recipe");
 
-    let row = sqlx::query("SELECT id, telegram_id, content, created_at FROM recipes WHERE id = $1")
+    let row = sqlx::query("SELECT id, telegram_id, content, created_at, notes FROM recipes WHERE id = $1 AND deleted_at IS NULL")
         .bind(recipe_id)
         .fetch_optional(pool)
         .await
@@ -108,6 +364,7 @@ pub async fn read_recipe(pool: &PgPool, recipe_id: i64) -> Result<Option<Recipe>
                 content: row.get(2),
                 recipe_name: None, // For backward compatibility, existing entries have no recipe name
                 created_at: row.get(3),
+                notes: row.get(4),
             };
             debug!(recipe_id = %recipe_id, "Recipe found");
             Ok(Some(recipe))
@@ -120,7 +377,7 @@ pub async fn read_recipe(pool: &PgPool, recipe_id: i64) -> Result<Option<Recipe>
 }
 
 /// Update an existing recipe in the database
-pub async fn update_recipe(pool: &PgPool, recipe_id: i64, new_content: &str) -> Result<bool> {
+pub async fn update_recipe(pool: &PgPool, recipe_id: RecipeId, new_content: &str) -> Result<bool> {
     debug!(recipe_id = %recipe_id, "Updating recipe");
 
     let result = sqlx::query("UPDATE recipes SET content = $1 WHERE id = $2")
@@ -140,8 +397,11 @@ pub async fn update_recipe(pool: &PgPool, recipe_id: i64, new_content: &str) ->
     }
 }
 
-/// Delete a recipe from the database
-pub async fn delete_recipe(pool: &PgPool, recipe_id: i64) -> Result<bool> {
+/// Permanently delete a recipe and its ingredients from the database
+///
+/// Deleting from the bot only moves a recipe to the trash (see [`trash_recipe`]);
+/// this is the purge path, used once the restore window is over.
+pub async fn delete_recipe(pool: &PgPool, recipe_id: RecipeId) -> Result<bool> {
     debug!(recipe_id = %recipe_id, "Deleting recipe");
 
     // First, delete all ingredients associated with this recipe
@@ -171,10 +431,149 @@ pub async fn delete_recipe(pool: &PgPool, recipe_id: i64) -> Result<bool> {
     }
 }
 
+/// Days a recipe stays in the trash, restorable with /trash, before it is purged
+pub const TRASH_RETENTION_DAYS: i32 = 7;
+
+/// A recipe in the trash, as listed by /trash
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrashedRecipe {
+    pub id: RecipeId,
+    pub recipe_name: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Move a user's recipe to the trash
+///
+/// The recipe and its ingredients stay in the database but every listing, search
+/// and statistic skips them until the recipe is restored or purged. Returns false
+/// when the user has no such recipe outside the trash.
+pub async fn trash_recipe(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+) -> Result<bool> {
+    let span = crate::observability::db_span("trash_recipe", "recipes");
+    async move {
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, "Moving recipe to trash");
+
+        let result = sqlx::query(
+            "UPDATE recipes SET deleted_at = NOW() \
+             WHERE id = $1 AND telegram_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .execute(pool)
+        .await
+        .context("Failed to move recipe to trash")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Take a recipe back out of the trash, with its ingredients
+///
+/// Returns false when the recipe is not in the user's trash or its restore window
+/// is over.
+pub async fn restore_recipe(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+) -> Result<bool> {
+    let span = crate::observability::db_span("restore_recipe", "recipes");
+    async move {
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, "Restoring recipe from trash");
+
+        let result = sqlx::query(
+            "UPDATE recipes SET deleted_at = NULL \
+             WHERE id = $1 AND telegram_id = $2 \
+             AND deleted_at > NOW() - make_interval(days => $3)",
+        )
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .bind(TRASH_RETENTION_DAYS)
+        .execute(pool)
+        .await
+        .context("Failed to restore recipe")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the recipes in a user's trash that can still be restored, last deleted first
+pub async fn get_trashed_recipes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Vec<TrashedRecipe>> {
+    let span = crate::observability::db_span("get_trashed_recipes", "recipes");
+    async move {
+        let rows = sqlx::query(
+            "SELECT id, recipe_name, deleted_at FROM recipes \
+             WHERE telegram_id = $1 AND deleted_at > NOW() - make_interval(days => $2) \
+             ORDER BY deleted_at DESC, id DESC",
+        )
+        .bind(telegram_id)
+        .bind(TRASH_RETENTION_DAYS)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get trashed recipes")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TrashedRecipe {
+                id: RecipeId(row.get(0)),
+                recipe_name: row.get(1),
+                deleted_at: row.get(2),
+            })
+            .collect())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Permanently delete the recipes moved to the trash before `cutoff`
+///
+/// Returns the ids of the purged recipes.
+pub async fn purge_trashed_recipes(pool: &PgPool, cutoff: DateTime<Utc>) -> Result<Vec<RecipeId>> {
+    let span = crate::observability::db_span("purge_trashed_recipes", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let expired: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM recipes WHERE deleted_at IS NOT NULL AND deleted_at <= $1 ORDER BY id",
+        )
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await
+        .context("Failed to find expired trashed recipes")?;
+
+        let mut purged = Vec::with_capacity(expired.len());
+        for recipe_id in expired.into_iter().map(RecipeId) {
+            if delete_recipe(pool, recipe_id).await? {
+                purged.push(recipe_id);
+            }
+        }
+
+        observability::record_db_performance_metrics(
+            "purge_trashed_recipes",
+            start_time.elapsed(),
+            purged.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        Ok(purged)
+    }
+    .instrument(span)
+    .await
+}
+
 /// Get or create a user by Telegram ID
 pub async fn get_or_create_user(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     language_code: Option<&str>,
 ) -> Result<User> {
     debug!(telegram_id = %telegram_id, "Starting get_or_create_user");
@@ -245,7 +644,10 @@ pub async fn get_or_create_user(
 }
 
 /// Get a user by Telegram ID
-pub async fn get_user_by_telegram_id(pool: &PgPool, telegram_id: i64) -> Result<Option<User>> {
+pub async fn get_user_by_telegram_id(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<User>> {
     debug!(telegram_id = %telegram_id, "Getting user by telegram_id");
 
     let row = sqlx::query("SELECT id, telegram_id, language_code, created_at, updated_at FROM users WHERE telegram_id = $1")
@@ -273,8 +675,39 @@ pub async fn get_user_by_telegram_id(pool: &PgPool, telegram_id: i64) -> Result<
     }
 }
 
+/// Get up to `limit` users with a Telegram ID above `after`, in Telegram ID order
+///
+/// Walks the whole users table in batches for broadcasts: pass the last ID of a
+/// batch to get the next one.
+pub async fn get_users_batch(
+    pool: &PgPool,
+    after: Option<TelegramId>,
+    limit: i64,
+) -> Result<Vec<User>> {
+    let rows = sqlx::query(
+        "SELECT id, telegram_id, language_code, created_at, updated_at FROM users \
+         WHERE $1::BIGINT IS NULL OR telegram_id > $1 ORDER BY telegram_id LIMIT $2",
+    )
+    .bind(after)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get users batch")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| User {
+            id: row.get(0),
+            telegram_id: row.get(1),
+            language_code: row.get(2),
+            created_at: row.get(3),
+            updated_at: row.get(4),
+        })
+        .collect())
+}
+
 /// Get a user by internal database ID
-pub async fn get_user_by_id(pool: &PgPool, user_id: i64) -> Result<Option<User>> {
+pub async fn get_user_by_id(pool: &PgPool, user_id: UserId) -> Result<Option<User>> {
     debug!(user_id = %user_id, "Getting user by internal ID");
 
     let row = sqlx::query(
@@ -304,41 +737,27 @@ pub async fn get_user_by_id(pool: &PgPool, user_id: i64) -> Result<Option<User>>
     }
 }
 
+/// How long looked-up users stay cached
+const USER_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
 /// Get or create a user by Telegram ID with caching
 pub async fn get_or_create_user_cached(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     language_code: Option<&str>,
-    cache: &std::sync::Mutex<crate::cache::CacheManager>,
+    cache: &dyn CacheBackend,
 ) -> Result<User> {
     // Try cache first
-    {
-        let cache_manager = match cache.lock() {
-            Ok(manager) => manager,
-            Err(poisoned) => {
-                crate::observability::record_mutex_poisoning("cache_manager", "user_lookup");
-                // For poisoned mutex, we can still access the data but log the incident
-                poisoned.into_inner()
-            }
-        };
-        if let Some(user) = cache_manager.user_cache.get(&telegram_id) {
-            debug!(telegram_id = %telegram_id, "User found in cache");
-            return Ok(user);
-        }
+    if let Some(user) = get_json::<User>(cache, &user_by_telegram_id_key(telegram_id)).await {
+        debug!(telegram_id = %telegram_id, "User found in cache");
+        observability::record_cache_metrics("user", true);
+        return Ok(user);
     }
 
     // Cache miss - fetch from database
+    observability::record_cache_metrics("user", false);
     let user = get_or_create_user(pool, telegram_id, language_code).await?;
-
-    // Cache the result
-    {
-        let mut cache_manager = cache.lock().expect("Failed to acquire cache manager lock");
-        cache_manager.user_cache.insert(
-            telegram_id,
-            user.clone(),
-            std::time::Duration::from_secs(300),
-        ); // 5 minutes
-    }
+    cache_user(cache, &user, USER_CACHE_TTL).await;
 
     Ok(user)
 }
@@ -346,29 +765,23 @@ pub async fn get_or_create_user_cached(
 /// Get a user by Telegram ID with caching
 pub async fn get_user_by_telegram_id_cached(
     pool: &PgPool,
-    telegram_id: i64,
-    cache: &std::sync::Mutex<crate::cache::CacheManager>,
+    telegram_id: TelegramId,
+    cache: &dyn CacheBackend,
 ) -> Result<Option<User>> {
     // Try cache first
-    {
-        let cache_manager = cache.lock().expect("Failed to acquire cache manager lock");
-        if let Some(user) = cache_manager.user_cache.get(&telegram_id) {
-            debug!(telegram_id = %telegram_id, "User found in cache");
-            return Ok(Some(user));
-        }
+    if let Some(user) = get_json::<User>(cache, &user_by_telegram_id_key(telegram_id)).await {
+        debug!(telegram_id = %telegram_id, "User found in cache");
+        observability::record_cache_metrics("user", true);
+        return Ok(Some(user));
     }
 
     // Cache miss - fetch from database
+    observability::record_cache_metrics("user", false);
     let user = get_user_by_telegram_id(pool, telegram_id).await?;
 
     // Cache the result if found
     if let Some(ref user) = user {
-        let mut cache_manager = cache.lock().expect("Failed to acquire cache manager lock");
-        cache_manager.user_cache.insert(
-            telegram_id,
-            user.clone(),
-            std::time::Duration::from_secs(300),
-        ); // 5 minutes
+        cache_user(cache, user, USER_CACHE_TTL).await;
     }
 
     Ok(user)
@@ -377,557 +790,3993 @@ pub async fn get_user_by_telegram_id_cached(
 /// Get a user by internal ID with caching
 pub async fn get_user_by_id_cached(
     pool: &PgPool,
-    user_id: i64,
-    cache: &std::sync::Mutex<crate::cache::CacheManager>,
+    user_id: UserId,
+    cache: &dyn CacheBackend,
 ) -> Result<Option<User>> {
-    // Try cache first using the helper method
-    {
-        let cache_manager = cache.lock().expect("Failed to acquire cache manager lock");
-        if let Some(user) = cache_manager.find_user_by_id(user_id) {
-            debug!(user_id = %user_id, "User found in cache by ID");
-            return Ok(Some(user));
-        }
+    // Try cache first
+    if let Some(user) = get_json::<User>(cache, &user_by_id_key(user_id)).await {
+        debug!(user_id = %user_id, "User found in cache by ID");
+        observability::record_cache_metrics("user", true);
+        return Ok(Some(user));
     }
 
     // Cache miss - fetch from database
+    observability::record_cache_metrics("user", false);
     let user = get_user_by_id(pool, user_id).await?;
 
-    // Cache the result if found (by telegram_id for future lookups)
+    // Cache the result if found, under both ids for future lookups
     if let Some(ref user) = user {
-        let mut cache_manager = cache.lock().expect("Failed to acquire cache manager lock");
-        cache_manager.user_cache.insert(
-            user.telegram_id,
-            user.clone(),
-            std::time::Duration::from_secs(300),
-        );
+        cache_user(cache, user, USER_CACHE_TTL).await;
     }
 
     Ok(user)
 }
 
-/// Create a new ingredient in the database
-pub async fn create_ingredient(
+/// Get a page of a user's recipe names with caching
+///
+/// Pages are evicted by the `_cached` writes below; see [`crate::cache::RECIPE_CACHE_TTL`].
+pub async fn get_user_recipes_paginated_cached(
     pool: &PgPool,
-    user_id: i64,
-    recipe_id: Option<i64>,
-    name: &str,
-    quantity: Option<f64>,
-    unit: Option<&str>,
-    raw_text: &str,
-) -> Result<i64> {
-    let span = crate::observability::db_span("create_ingredient", "ingredients");
-    let _enter = span.enter();
+    telegram_id: TelegramId,
+    limit: i64,
+    offset: i64,
+    cache: &SharedCacheManager,
+) -> Result<(Vec<String>, i64)> {
+    if !cache.lock().caches_recipes() {
+        return get_user_recipes_paginated(pool, telegram_id, limit, offset).await;
+    }
+    if let Some(page) = cache.lock().recipe_list_page(telegram_id, limit, offset) {
+        debug!(telegram_id = %telegram_id, limit = %limit, offset = %offset, "Recipe page found in cache");
+        observability::record_cache_metrics("recipe_list", true);
+        return Ok(page);
+    }
 
-    let start_time = std::time::Instant::now();
-    info!("Creating new ingredient for user_id: {user_id}");
+    observability::record_cache_metrics("recipe_list", false);
+    let page = get_user_recipes_paginated(pool, telegram_id, limit, offset).await?;
+    cache
+        .lock()
+        .insert_recipe_list_page(telegram_id, limit, offset, page.clone());
+    Ok(page)
+}
 
-    let result = sqlx::query(
-        "INSERT INTO ingredients (user_id, recipe_id, name, quantity, unit, raw_text) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id"
-    )
-    .bind(user_id)
-    .bind(recipe_id)
-    .bind(name)
-    .bind(quantity)
-    .bind(unit)
-    .bind(raw_text)
-    .fetch_one(pool)
-    .await
-    .context("Failed to insert new ingredient");
+/// Get the ingredients of a recipe with caching
+pub async fn get_recipe_ingredients_cached(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    cache: &SharedCacheManager,
+) -> Result<Vec<Ingredient>> {
+    if !cache.lock().caches_recipes() {
+        return get_recipe_ingredients(pool, recipe_id).await;
+    }
+    if let Some(ingredients) = cache.lock().recipe_ingredients_cache.get(&recipe_id) {
+        debug!(recipe_id = %recipe_id, "Recipe ingredients found in cache");
+        observability::record_cache_metrics("recipe_ingredients", true);
+        return Ok(ingredients);
+    }
 
-    let duration = start_time.elapsed();
-    observability::record_db_performance_metrics(
-        "create_ingredient",
-        duration,
-        1,
-        crate::observability::QueryComplexity::Simple,
+    observability::record_cache_metrics("recipe_ingredients", false);
+    let ingredients = get_recipe_ingredients(pool, recipe_id).await?;
+    cache.lock().recipe_ingredients_cache.insert(
+        recipe_id,
+        ingredients.clone(),
+        crate::cache::RECIPE_CACHE_TTL,
     );
+    Ok(ingredients)
+}
 
-    match result {
-        Ok(row) => {
-            let ingredient_id: i64 = row.get(0);
-            info!(ingredient_id = %ingredient_id, duration_ms = %duration.as_millis(), user_id = %user_id, recipe_id = ?recipe_id, name = %name, "Ingredient created successfully");
-            Ok(ingredient_id)
-        }
-        Err(e) => {
-            error_logging::log_database_error(
-                &e,
-                "create_ingredient",
-                Some(user_id),
-                Some(&[
-                    ("table", &"ingredients"),
-                    (
-                        "recipe_id",
-                        &recipe_id.map_or("None".to_string(), |id| id.to_string()),
-                    ),
-                    ("name", &name.to_string()),
-                ]),
-            );
-            Err(e)
+/// Get the ingredients of several recipes with caching
+///
+/// Only the recipes missing from the cache are read, in one query.
+pub async fn get_ingredients_for_recipes_cached(
+    pool: &PgPool,
+    recipe_ids: &[RecipeId],
+    cache: &SharedCacheManager,
+) -> Result<HashMap<RecipeId, Vec<Ingredient>>> {
+    if !cache.lock().caches_recipes() {
+        return get_ingredients_for_recipes(pool, recipe_ids).await;
+    }
+    let mut by_recipe = HashMap::new();
+    let mut missing = Vec::new();
+    {
+        let cache = cache.lock();
+        for &recipe_id in recipe_ids {
+            match cache.recipe_ingredients_cache.get(&recipe_id) {
+                Some(ingredients) => {
+                    by_recipe.insert(recipe_id, ingredients);
+                }
+                None => missing.push(recipe_id),
+            }
         }
     }
+    observability::record_cache_metrics("recipe_ingredients", missing.is_empty());
+    if missing.is_empty() {
+        return Ok(by_recipe);
+    }
+
+    let fetched = get_ingredients_for_recipes(pool, &missing).await?;
+    let mut cache = cache.lock();
+    for (recipe_id, ingredients) in fetched {
+        cache.recipe_ingredients_cache.insert(
+            recipe_id,
+            ingredients.clone(),
+            crate::cache::RECIPE_CACHE_TTL,
+        );
+        by_recipe.insert(recipe_id, ingredients);
+    }
+    Ok(by_recipe)
 }
 
-/// Read a single ingredient by ID
-pub async fn read_ingredient(pool: &PgPool, ingredient_id: i64) -> Result<Option<Ingredient>> {
-    info!("Reading ingredient with ID: {ingredient_id}");
+/// Get a recipe with its name with caching
+pub async fn read_recipe_with_name_cached(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    cache: &SharedCacheManager,
+) -> Result<Option<Recipe>> {
+    if !cache.lock().caches_recipes() {
+        return read_recipe_with_name(pool, recipe_id).await;
+    }
+    if let Some(recipe) = cache.lock().recipe_cache.get(&recipe_id) {
+        debug!(recipe_id = %recipe_id, "Recipe found in cache");
+        observability::record_cache_metrics("recipe", true);
+        return Ok(Some(recipe));
+    }
 
-    let row = sqlx::query(
-        "SELECT id, user_id, recipe_id, name, quantity::float8, unit, created_at, updated_at FROM ingredients WHERE id = $1"
-    )
-    .bind(ingredient_id)
-    .fetch_optional(pool)
-    .await
-    .context("Failed to fetch ingredient")?;
+    observability::record_cache_metrics("recipe", false);
+    let recipe = read_recipe_with_name(pool, recipe_id).await?;
+    if let Some(ref recipe) = recipe {
+        cache
+            .lock()
+            .recipe_cache
+            .insert(recipe_id, recipe.clone(), crate::cache::RECIPE_CACHE_TTL);
+    }
+    Ok(recipe)
+}
 
-    match row {
-        Some(row) => {
-            let ingredient = Ingredient {
-                id: row.get(0),
-                user_id: row.get(1),
-                recipe_id: row.get(2),
-                name: row.get(3),
-                quantity: row.get(4),
-                unit: row.get(5),
-                created_at: row.get(6),
-                updated_at: row.get(7),
-            };
-            info!("Ingredient found: {:?}", ingredient);
-            Ok(Some(ingredient))
-        }
-        None => {
-            info!("No ingredient found with ID: {ingredient_id}");
-            Ok(None)
-        }
+/// Get all recipes with a specific name for a user with caching
+///
+/// Lookups are evicted with the user's recipe list by the `_cached` writes below.
+pub async fn get_recipes_by_name_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_name: &str,
+    cache: &SharedCacheManager,
+) -> Result<Vec<Recipe>> {
+    if !cache.lock().caches_recipes() {
+        return get_recipes_by_name(pool, telegram_id, recipe_name).await;
     }
+    if let Some(recipes) = cache.lock().recipes_by_name(telegram_id, recipe_name) {
+        debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, "Recipes by name found in cache");
+        observability::record_cache_metrics("recipes_by_name", true);
+        return Ok(recipes);
+    }
+
+    observability::record_cache_metrics("recipes_by_name", false);
+    let recipes = get_recipes_by_name(pool, telegram_id, recipe_name).await?;
+    cache
+        .lock()
+        .insert_recipes_by_name(telegram_id, recipe_name, recipes.clone());
+    Ok(recipes)
 }
 
-/// Update an existing ingredient in the database
-pub async fn update_ingredient(
+/// Rename a recipe and evict it and the owner's recipe list from the cache
+pub async fn update_recipe_name_cached(
     pool: &PgPool,
-    ingredient_id: i64,
-    name: Option<&str>,
-    quantity: Option<f64>,
-    unit: Option<&str>,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    recipe_name: &str,
+    cache: &SharedCacheManager,
 ) -> Result<bool> {
-    info!("Updating ingredient with ID: {ingredient_id}");
+    let updated = update_recipe_name(pool, recipe_id, recipe_name).await?;
+    let mut cache = cache.lock();
+    cache.invalidate_recipe(recipe_id);
+    cache.invalidate_recipe_list(telegram_id);
+    Ok(updated)
+}
 
-    let result = sqlx::query("UPDATE ingredients SET name = COALESCE($1, name), quantity = COALESCE($2, quantity), unit = COALESCE($3, unit), updated_at = CURRENT_TIMESTAMP WHERE id = $4")
-        .bind(name)
-        .bind(quantity)
-        .bind(unit)
-        .bind(ingredient_id)
-        .execute(pool)
-        .await
-        .context("Failed to update ingredient")?;
+/// Update the notes of a recipe and evict it from the cache
+pub async fn update_recipe_notes_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    notes: Option<&str>,
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let updated = update_recipe_notes(pool, telegram_id, recipe_id, notes).await?;
+    let mut cache = cache.lock();
+    cache.invalidate_recipe(recipe_id);
+    cache.invalidate_recipe_list(telegram_id);
+    Ok(updated)
+}
 
-    let rows_affected = result.rows_affected();
-    if rows_affected > 0 {
-        info!("Ingredient updated successfully with ID: {ingredient_id}");
-        Ok(true)
-    } else {
-        info!("No ingredient found with ID: {ingredient_id}");
-        Ok(false)
-    }
+/// Delete a recipe and evict it, its ingredients and the owner's recipe list from the cache
+pub async fn delete_recipe_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let deleted = delete_recipe(pool, recipe_id).await?;
+    let mut cache = cache.lock();
+    cache.invalidate_recipe(recipe_id);
+    cache.invalidate_recipe_ingredients(recipe_id);
+    cache.invalidate_recipe_list(telegram_id);
+    Ok(deleted)
 }
 
-/// Delete an ingredient from the database
-pub async fn delete_ingredient(pool: &PgPool, ingredient_id: i64) -> Result<bool> {
+/// Move a recipe to the trash and evict it, its ingredients and the owner's recipe
+/// list from the cache
+pub async fn trash_recipe_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let trashed = trash_recipe(pool, telegram_id, recipe_id).await?;
+    let mut cache = cache.lock();
+    cache.invalidate_recipe(recipe_id);
+    cache.invalidate_recipe_ingredients(recipe_id);
+    cache.invalidate_recipe_list(telegram_id);
+    Ok(trashed)
+}
+
+/// Restore a recipe from the trash and evict the owner's recipe list from the cache
+pub async fn restore_recipe_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let restored = restore_recipe(pool, telegram_id, recipe_id).await?;
+    cache.lock().invalidate_recipe_list(telegram_id);
+    Ok(restored)
+}
+
+/// Delete several recipes and evict them, their ingredients and the owner's recipe list
+/// from the cache
+pub async fn delete_recipes_cached(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_ids: &[RecipeId],
+    cache: &SharedCacheManager,
+) -> Result<Vec<RecipeId>> {
+    let deleted = delete_recipes(pool, telegram_id, recipe_ids).await?;
+    let mut cache = cache.lock();
+    for recipe_id in &deleted {
+        cache.invalidate_recipe(*recipe_id);
+        cache.invalidate_recipe_ingredients(*recipe_id);
+    }
+    cache.invalidate_recipe_list(telegram_id);
+    Ok(deleted)
+}
+
+/// Synchronize a recipe's ingredients and evict its cached ingredient list
+pub async fn update_recipe_ingredients_cached(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+    cache: &SharedCacheManager,
+) -> Result<()> {
+    let result = update_recipe_ingredients(pool, recipe_id, ingredients).await;
+    // Evict even on failure, part of the changes may have been written
+    cache.lock().invalidate_recipe_ingredients(recipe_id);
+    result
+}
+
+/// Synchronize a recipe's ingredients unless they changed since `expected_version`,
+/// evicting its cached ingredient list
+pub async fn update_recipe_ingredients_versioned_cached(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    expected_version: i64,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let result =
+        update_recipe_ingredients_versioned(pool, recipe_id, expected_version, ingredients).await;
+    cache.lock().invalidate_recipe_ingredients(recipe_id);
+    result
+}
+
+/// Create an ingredient and evict its recipe's cached ingredient list
+#[allow(clippy::too_many_arguments)]
+pub async fn create_ingredient_cached(
+    pool: &PgPool,
+    user_id: UserId,
+    recipe_id: Option<RecipeId>,
+    name: &str,
+    quantity: Option<f64>,
+    unit: Option<&str>,
+    raw_text: &str,
+    cache: &SharedCacheManager,
+) -> Result<i64> {
+    let id = create_ingredient(pool, user_id, recipe_id, name, quantity, unit, raw_text).await?;
+    if let Some(recipe_id) = recipe_id {
+        cache.lock().invalidate_recipe_ingredients(recipe_id);
+    }
+    Ok(id)
+}
+
+/// Delete an ingredient and evict its recipe's cached ingredient list
+pub async fn delete_ingredient_cached(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    ingredient_id: i64,
+    cache: &SharedCacheManager,
+) -> Result<bool> {
+    let deleted = delete_ingredient(pool, ingredient_id).await?;
+    cache.lock().invalidate_recipe_ingredients(recipe_id);
+    Ok(deleted)
+}
+
+/// Check whether the reply-keyboard quickbar is enabled for a user
+///
+/// Returns `false` for users that don't exist yet.
+pub async fn get_user_quickbar_enabled(pool: &PgPool, telegram_id: TelegramId) -> Result<bool> {
+    let span = crate::observability::db_span("get_user_quickbar_enabled", "users");
+    async move {
+        debug!(telegram_id = %telegram_id, "Getting quickbar setting");
+
+        let row = sqlx::query("SELECT quickbar_enabled FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get quickbar setting")?;
+
+        Ok(row.map(|row| row.get(0)).unwrap_or(false))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Enable or disable the reply-keyboard quickbar for a user, creating the user if needed
+pub async fn set_user_quickbar_enabled(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    enabled: bool,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_quickbar_enabled", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, enabled = %enabled, "Setting quickbar setting");
+
+        sqlx::query(
+            "INSERT INTO users (telegram_id, quickbar_enabled) VALUES ($1, $2) \
+         ON CONFLICT (telegram_id) DO UPDATE SET quickbar_enabled = EXCLUDED.quickbar_enabled, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(telegram_id)
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .context("Failed to set quickbar setting")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_quickbar_enabled",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, enabled = %enabled, "Quickbar setting updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Check whether per-message language detection is enabled for a user
+///
+/// Returns `false` for users that don't exist yet.
+pub async fn get_user_auto_language(pool: &PgPool, telegram_id: TelegramId) -> Result<bool> {
+    let span = crate::observability::db_span("get_user_auto_language", "users");
+    async move {
+        debug!(telegram_id = %telegram_id, "Getting auto language setting");
+
+        let row = sqlx::query("SELECT auto_language FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get auto language setting")?;
+
+        Ok(row.map(|row| row.get(0)).unwrap_or(false))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Enable or disable per-message language detection for a user, creating the user if needed
+pub async fn set_user_auto_language(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    enabled: bool,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_auto_language", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, enabled = %enabled, "Setting auto language setting");
+
+        sqlx::query(
+            "INSERT INTO users (telegram_id, auto_language) VALUES ($1, $2) \
+         ON CONFLICT (telegram_id) DO UPDATE SET auto_language = EXCLUDED.auto_language, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(telegram_id)
+        .bind(enabled)
+        .execute(pool)
+        .await
+        .context("Failed to set auto language setting")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_auto_language",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, enabled = %enabled, "Auto language setting updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the language a user chose with /language
+///
+/// Returns `None` for users that don't exist yet or never chose one, whose replies
+/// follow their Telegram client language.
+pub async fn get_user_language_preference(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<String>> {
+    let span = crate::observability::db_span("get_user_language_preference", "users");
+    async move {
+        debug!(telegram_id = %telegram_id, "Getting language preference");
+
+        let row = sqlx::query(
+            "SELECT language_code FROM users WHERE telegram_id = $1 AND explicit_language",
+        )
+        .bind(telegram_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get language preference")?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Set the language a user is answered in, creating the user if needed
+pub async fn set_user_language_preference(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    language_code: &str,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_language_preference", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, language_code = %language_code, "Setting language preference");
+
+        sqlx::query(
+            "INSERT INTO users (telegram_id, language_code, explicit_language) VALUES ($1, $2, TRUE) \
+         ON CONFLICT (telegram_id) DO UPDATE SET language_code = EXCLUDED.language_code, explicit_language = TRUE, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(telegram_id)
+        .bind(language_code)
+        .execute(pool)
+        .await
+        .context("Failed to set language preference")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_language_preference",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, language_code = %language_code, "Language preference updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the unit system a user wants ingredients displayed in
+///
+/// Returns `None` (quantities shown as written) for users that don't exist yet or
+/// never chose one.
+pub async fn get_user_unit_preference(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<UnitSystem>> {
+    let span = crate::observability::db_span("get_user_unit_preference", "users");
+    async move {
+        debug!(telegram_id = %telegram_id, "Getting unit preference");
+
+        let row = sqlx::query("SELECT unit_preference FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get unit preference")?;
+
+        let preference: Option<String> = row.and_then(|row| row.get(0));
+        Ok(preference.as_deref().and_then(UnitSystem::from_name))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Set the unit system ingredients are displayed in, creating the user if needed
+pub async fn set_user_unit_preference(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    units: Option<UnitSystem>,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_unit_preference", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        let units_name = units.map(UnitSystem::as_str);
+        debug!(telegram_id = %telegram_id, units = ?units_name, "Setting unit preference");
+
+        sqlx::query(
+            "INSERT INTO users (telegram_id, unit_preference) VALUES ($1, $2) \
+         ON CONFLICT (telegram_id) DO UPDATE SET unit_preference = EXCLUDED.unit_preference, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(telegram_id)
+        .bind(units_name)
+        .execute(pool)
+        .await
+        .context("Failed to set unit preference")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_unit_preference",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, units = ?units_name, "Unit preference updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the time zone a user wants dates displayed in
+///
+/// Returns `None` (UTC) for users that don't exist yet or never chose one.
+pub async fn get_user_timezone(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<FixedOffset>> {
+    let span = crate::observability::db_span("get_user_timezone", "users");
+    async move {
+        debug!(telegram_id = %telegram_id, "Getting time zone");
+
+        let row = sqlx::query("SELECT utc_offset_minutes FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .fetch_optional(pool)
+            .await
+            .context("Failed to get time zone")?;
+
+        let minutes: Option<i16> = row.and_then(|row| row.get(0));
+        Ok(minutes.and_then(|minutes| crate::timezone::offset_from_minutes(minutes.into())))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Set the time zone dates are displayed in, creating the user if needed
+pub async fn set_user_timezone(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    offset: FixedOffset,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_timezone", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        let minutes = crate::timezone::offset_minutes(offset) as i16;
+        debug!(telegram_id = %telegram_id, minutes, "Setting time zone");
+
+        sqlx::query(
+            "INSERT INTO users (telegram_id, utc_offset_minutes) VALUES ($1, $2) \
+         ON CONFLICT (telegram_id) DO UPDATE SET utc_offset_minutes = EXCLUDED.utc_offset_minutes, updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(telegram_id)
+        .bind(minutes)
+        .execute(pool)
+        .await
+        .context("Failed to set time zone")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_timezone",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, minutes, "Time zone updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the weekly digest schedule of a user, `None` when the digest is off
+pub async fn get_user_digest_schedule(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<WeeklySchedule>> {
+    let span = crate::observability::db_span("get_user_digest_schedule", "users");
+    async move {
+        let row = sqlx::query(
+            "SELECT digest_weekday, digest_hour FROM users WHERE telegram_id = $1 AND digest_enabled",
+        )
+        .bind(telegram_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get digest schedule")?;
+
+        row.map(|row| digest_schedule_from_columns(row.get(0), row.get(1)))
+            .transpose()
+    }
+    .instrument(span)
+    .await
+}
+
+/// Turn the weekly digest on at `schedule`, creating the user if needed, or off with `None`
+///
+/// The last send is kept, so turning the digest off and on again within its hour
+/// does not send it twice.
+pub async fn set_user_digest_schedule(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    schedule: Option<WeeklySchedule>,
+) -> Result<()> {
+    let span = crate::observability::db_span("set_user_digest_schedule", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, schedule = ?schedule, "Setting digest schedule");
+
+        let enabled = schedule.is_some();
+        match schedule {
+            Some(schedule) => sqlx::query(
+                "INSERT INTO users (telegram_id, digest_enabled, digest_weekday, digest_hour) VALUES ($1, TRUE, $2, $3) \
+             ON CONFLICT (telegram_id) DO UPDATE SET digest_enabled = TRUE, \
+             digest_weekday = EXCLUDED.digest_weekday, digest_hour = EXCLUDED.digest_hour, updated_at = CURRENT_TIMESTAMP",
+            )
+            .bind(telegram_id)
+            .bind(schedule.weekday.num_days_from_monday() as i16)
+            .bind(schedule.hour as i16),
+            // Turning the digest off keeps the schedule for the next time it is turned on
+            None => sqlx::query(
+                "UPDATE users SET digest_enabled = FALSE, updated_at = CURRENT_TIMESTAMP WHERE telegram_id = $1",
+            )
+            .bind(telegram_id),
+        }
+        .execute(pool)
+        .await
+        .context("Failed to set digest schedule")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "set_user_digest_schedule",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        info!(telegram_id = %telegram_id, enabled, "Digest schedule updated");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+fn digest_schedule_from_columns(weekday: i16, hour: i16) -> Result<WeeklySchedule> {
+    let weekday = u8::try_from(weekday)
+        .ok()
+        .and_then(|day| chrono::Weekday::try_from(day).ok())
+        .ok_or_else(|| anyhow::anyhow!("Invalid stored digest weekday: {weekday}"))?;
+    WeeklySchedule::new(weekday, u32::try_from(hour).unwrap_or(u32::MAX))
+}
+
+/// A user whose weekly digest is due
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigestRecipient {
+    pub telegram_id: TelegramId,
+    pub language_code: String,
+}
+
+/// Get the users whose weekly digest falls in the hour containing `now`
+///
+/// The hour is the send window: users already sent a digest since it started are
+/// left out, so a sweep after a restart does not send twice.
+pub async fn get_users_due_for_digest(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+) -> Result<Vec<DigestRecipient>> {
+    let span = crate::observability::db_span("get_users_due_for_digest", "users");
+    async move {
+        let window_start = digest_window_start(now);
+        let rows = sqlx::query(
+            "SELECT telegram_id, language_code FROM users \
+         WHERE digest_enabled AND digest_weekday = $1 AND digest_hour = $2 \
+         AND (digest_last_sent_at IS NULL OR digest_last_sent_at < $3) \
+         ORDER BY telegram_id",
+        )
+        .bind(now.weekday().num_days_from_monday() as i16)
+        .bind(now.hour() as i16)
+        .bind(window_start)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get users due for digest")?;
+
+        let recipients = rows
+            .into_iter()
+            .map(|row| DigestRecipient {
+                telegram_id: TelegramId(row.get(0)),
+                language_code: row.get(1),
+            })
+            .collect::<Vec<_>>();
+        debug!(count = recipients.len(), window_start = %window_start, "Users due for digest");
+        Ok(recipients)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Start of the digest send window containing `now`: the top of its hour
+pub fn digest_window_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive()
+        .and_hms_opt(now.hour(), 0, 0)
+        .map(|start| start.and_utc())
+        .unwrap_or(now)
+}
+
+/// Record that a user's weekly digest was handled at `sent_at`
+pub async fn mark_digest_sent(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    sent_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query("UPDATE users SET digest_last_sent_at = $2 WHERE telegram_id = $1")
+        .bind(telegram_id)
+        .bind(sent_at)
+        .execute(pool)
+        .await
+        .context("Failed to record digest send")?;
+    Ok(())
+}
+
+/// Create a new ingredient in the database
+pub async fn create_ingredient(
+    pool: &PgPool,
+    user_id: UserId,
+    recipe_id: Option<RecipeId>,
+    name: &str,
+    quantity: Option<f64>,
+    unit: Option<&str>,
+    raw_text: &str,
+) -> Result<i64> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Failed to acquire connection")?;
+    create_ingredient_tx(
+        &mut conn, user_id, recipe_id, name, quantity, unit, raw_text,
+    )
+    .await
+}
+
+/// Create a new ingredient on a connection, typically a transaction's
+///
+/// Lets a recipe and its ingredients be written atomically.
+pub async fn create_ingredient_tx(
+    conn: &mut sqlx::PgConnection,
+    user_id: UserId,
+    recipe_id: Option<RecipeId>,
+    name: &str,
+    quantity: Option<f64>,
+    unit: Option<&str>,
+    raw_text: &str,
+) -> Result<i64> {
+    let span = crate::observability::db_span("create_ingredient", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        info!("Creating new ingredient for user_id: {user_id}");
+
+        let (unit_dimension, unit_system) = unit_metadata(unit);
+        let (normalized_name, notes) = name_metadata(name);
+        let (numerator, denominator) = quantity_fraction(quantity);
+        let result = sqlx::query(
+            "INSERT INTO ingredients (user_id, recipe_id, name, quantity, unit, raw_text, unit_dimension, unit_system, normalized_name, notes, \
+         quantity_numerator, quantity_denominator, position) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, \
+         (SELECT COALESCE(MAX(position) + 1, 0) FROM ingredients WHERE recipe_id = $2)) RETURNING id"
+        )
+        .bind(user_id)
+        .bind(recipe_id)
+        .bind(name)
+        .bind(quantity)
+        .bind(unit)
+        .bind(raw_text)
+        .bind(unit_dimension)
+        .bind(unit_system)
+        .bind(normalized_name)
+        .bind(notes)
+        .bind(numerator)
+        .bind(denominator)
+        .fetch_one(conn)
+        .await
+        .context("Failed to insert new ingredient");
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "create_ingredient",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        match result {
+            Ok(row) => {
+                let ingredient_id: i64 = row.get(0);
+                info!(ingredient_id = %ingredient_id, duration_ms = %duration.as_millis(), user_id = %user_id, recipe_id = ?recipe_id, name = %name, "Ingredient created successfully");
+                Ok(ingredient_id)
+            }
+            Err(e) => {
+                error_logging::log_database_error(
+                    &e,
+                    "create_ingredient",
+                    Some(user_id.0),
+                    Some(&[
+                        ("table", &"ingredients"),
+                        (
+                            "recipe_id",
+                            &recipe_id.map_or("None".to_string(), |id| id.to_string()),
+                        ),
+                        ("name", &name.to_string()),
+                    ]),
+                );
+                Err(e)
+            }
+        }
+    }
+    .instrument(span)
+    .await
+}
+
+/// Create several ingredients of a recipe with a single multi-row INSERT
+///
+/// Returns the generated ids in the order of `ingredients`, which are positioned
+/// after the recipe's existing ingredients. Runs on the given connection, so a
+/// transaction's connection makes the batch part of it.
+pub async fn create_ingredients_bulk(
+    conn: &mut sqlx::PgConnection,
+    user_id: UserId,
+    recipe_id: RecipeId,
+    ingredients: &[NewIngredient<'_>],
+) -> Result<Vec<i64>> {
+    if ingredients.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let span = crate::observability::db_span("create_ingredients_bulk", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        let names: Vec<&str> = ingredients.iter().map(|i| i.name).collect();
+        let quantities: Vec<Option<f64>> = ingredients
+            .iter()
+            .map(|i| i.quantity.map(Quantity::to_f64))
+            .collect();
+        let (numerators, denominators): (Vec<Option<i64>>, Vec<Option<i64>>) = ingredients
+            .iter()
+            .map(|i| exact_fraction(i.quantity))
+            .unzip();
+        let units: Vec<Option<&str>> = ingredients.iter().map(|i| i.unit).collect();
+        let raw_texts: Vec<Option<&str>> = ingredients.iter().map(|i| i.raw_text).collect();
+        let (dimensions, systems): (Vec<Option<&str>>, Vec<Option<&str>>) =
+            units.iter().map(|unit| unit_metadata(*unit)).unzip();
+        let (normalized_names, notes): (Vec<String>, Vec<Option<String>>) =
+            names.iter().map(|name| name_metadata(name)).unzip();
+
+        let result: Result<Vec<i64>> = sqlx::query_scalar(
+            "INSERT INTO ingredients \
+         (user_id, recipe_id, name, quantity, unit, raw_text, unit_dimension, unit_system, normalized_name, notes, \
+         quantity_numerator, quantity_denominator, position) \
+         SELECT $1, $2, name, quantity, unit, raw_text, unit_dimension, unit_system, normalized_name, notes, \
+         quantity_numerator, quantity_denominator, \
+         (SELECT COALESCE(MAX(position) + 1, 0) FROM ingredients WHERE recipe_id = $2) + ordinal - 1 \
+         FROM UNNEST($3::text[], $4::float8[], $5::text[], $6::text[], $7::text[], $8::text[], $9::text[], $10::text[], \
+         $11::int8[], $12::int8[]) \
+         WITH ORDINALITY AS t(name, quantity, unit, raw_text, unit_dimension, unit_system, normalized_name, notes, \
+         quantity_numerator, quantity_denominator, ordinal) \
+         ORDER BY ordinal \
+         RETURNING id",
+        )
+        .bind(user_id)
+        .bind(recipe_id)
+        .bind(&names)
+        .bind(&quantities)
+        .bind(&units)
+        .bind(&raw_texts)
+        .bind(&dimensions)
+        .bind(&systems)
+        .bind(&normalized_names)
+        .bind(&notes)
+        .bind(&numerators)
+        .bind(&denominators)
+        .fetch_all(conn)
+        .await
+        .context("Failed to insert ingredients");
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "create_ingredients_bulk",
+            duration,
+            ingredients.len() as u64,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        match result {
+            Ok(ids) => {
+                info!(count = ids.len(), duration_ms = %duration.as_millis(), user_id = %user_id, recipe_id = %recipe_id, "Ingredients created successfully");
+                Ok(ids)
+            }
+            Err(e) => {
+                error_logging::log_database_error(
+                    &e,
+                    "create_ingredients_bulk",
+                    Some(user_id.0),
+                    Some(&[
+                        ("table", &"ingredients"),
+                        ("recipe_id", &recipe_id.to_string()),
+                        ("count", &ingredients.len().to_string()),
+                    ]),
+                );
+                Err(e)
+            }
+        }
+    }
+    .instrument(span)
+    .await
+}
+
+/// Read a single ingredient by ID
+pub async fn read_ingredient(pool: &PgPool, ingredient_id: i64) -> Result<Option<Ingredient>> {
+    info!("Reading ingredient with ID: {ingredient_id}");
+
+    let row = sqlx::query(&format!(
+        "SELECT {INGREDIENT_COLUMNS} FROM ingredients WHERE id = $1"
+    ))
+    .bind(ingredient_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to fetch ingredient")?;
+
+    match row {
+        Some(row) => {
+            let ingredient = ingredient_from_row(&row);
+            info!("Ingredient found: {:?}", ingredient);
+            Ok(Some(ingredient))
+        }
+        None => {
+            info!("No ingredient found with ID: {ingredient_id}");
+            Ok(None)
+        }
+    }
+}
+
+/// Update an existing ingredient in the database
+pub async fn update_ingredient(
+    pool: &PgPool,
+    ingredient_id: i64,
+    name: Option<&str>,
+    quantity: Option<f64>,
+    unit: Option<&str>,
+) -> Result<bool> {
+    info!("Updating ingredient with ID: {ingredient_id}");
+
+    let (unit_dimension, unit_system) = unit_metadata(unit);
+    let (normalized_name, notes) = name.map(name_metadata).unzip();
+    let (numerator, denominator) = quantity_fraction(quantity);
+    let result = sqlx::query(
+        "UPDATE ingredients SET name = COALESCE($1, name), quantity = COALESCE($2, quantity), unit = COALESCE($3, unit), \
+         quantity_numerator = CASE WHEN $2::FLOAT8 IS NULL THEN quantity_numerator ELSE $9 END, \
+         quantity_denominator = CASE WHEN $2::FLOAT8 IS NULL THEN quantity_denominator ELSE $10 END, \
+         unit_dimension = CASE WHEN $3::TEXT IS NULL THEN unit_dimension ELSE $5 END, \
+         unit_system = CASE WHEN $3::TEXT IS NULL THEN unit_system ELSE $6 END, \
+         normalized_name = COALESCE($7, normalized_name), \
+         notes = CASE WHEN $1::TEXT IS NULL THEN notes ELSE $8 END, \
+         updated_at = CURRENT_TIMESTAMP WHERE id = $4",
+    )
+        .bind(name)
+        .bind(quantity)
+        .bind(unit)
+        .bind(ingredient_id)
+        .bind(unit_dimension)
+        .bind(unit_system)
+        .bind(normalized_name)
+        .bind(notes.flatten())
+        .bind(numerator)
+        .bind(denominator)
+        .execute(pool)
+        .await
+        .context("Failed to update ingredient")?;
+
+    let rows_affected = result.rows_affected();
+    if rows_affected > 0 {
+        info!("Ingredient updated successfully with ID: {ingredient_id}");
+        Ok(true)
+    } else {
+        info!("No ingredient found with ID: {ingredient_id}");
+        Ok(false)
+    }
+}
+
+/// Delete an ingredient from the database
+pub async fn delete_ingredient(pool: &PgPool, ingredient_id: i64) -> Result<bool> {
     info!("Deleting ingredient with ID: {ingredient_id}");
 
-    let result = sqlx::query("DELETE FROM ingredients WHERE id = $1")
-        .bind(ingredient_id)
+    let result = sqlx::query("DELETE FROM ingredients WHERE id = $1")
+        .bind(ingredient_id)
+        .execute(pool)
+        .await
+        .context("Failed to delete ingredient")?;
+
+    let rows_affected = result.rows_affected();
+    if rows_affected > 0 {
+        info!("Ingredient deleted successfully with ID: {ingredient_id}");
+        Ok(true)
+    } else {
+        info!("No ingredient found with ID: {ingredient_id}");
+        Ok(false)
+    }
+}
+
+/// List all ingredients for a user
+pub async fn list_ingredients_by_user(pool: &PgPool, user_id: UserId) -> Result<Vec<Ingredient>> {
+    info!("Listing ingredients for user_id: {user_id}");
+
+    let rows = sqlx::query(&format!(
+        "SELECT {INGREDIENT_COLUMNS} FROM ingredients WHERE user_id = $1 \
+         AND (recipe_id IS NULL OR recipe_id IN (SELECT id FROM recipes WHERE deleted_at IS NULL)) \
+         ORDER BY created_at DESC"
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list ingredients by user")?;
+
+    let ingredients: Vec<Ingredient> = rows
+        .into_iter()
+        .map(|row| ingredient_from_row(&row))
+        .collect();
+
+    info!(
+        "Found {} ingredients for user_id: {user_id}",
+        ingredients.len()
+    );
+    Ok(ingredients)
+}
+
+/// Get all ingredients for a specific recipe
+pub async fn get_recipe_ingredients(pool: &PgPool, recipe_id: RecipeId) -> Result<Vec<Ingredient>> {
+    info!("Getting ingredients for recipe_id: {recipe_id}");
+
+    let rows = sqlx::query(&format!(
+        "SELECT {INGREDIENT_COLUMNS} FROM ingredients WHERE recipe_id = $1 ORDER BY {INGREDIENT_ORDER}"
+    ))
+    .bind(recipe_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get recipe ingredients")?;
+
+    let ingredients: Vec<Ingredient> = rows
+        .into_iter()
+        .map(|row| ingredient_from_row(&row))
+        .collect();
+
+    info!(
+        "Found {} ingredients for recipe_id: {recipe_id}",
+        ingredients.len()
+    );
+    Ok(ingredients)
+}
+
+/// Bulk update ingredients for a recipe (add/update/delete)
+///
+/// This function handles the complex task of synchronizing edited ingredients
+/// with the database, performing the minimal set of operations needed.
+pub async fn update_recipe_ingredients(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+) -> Result<()> {
+    apply_recipe_ingredients(pool, recipe_id, None, ingredients).await?;
+    Ok(())
+}
+
+/// Bulk update ingredients for a recipe, unless they changed since `expected_version`
+///
+/// The version is the one `get_recipe_ingredients_version` returned when the edit
+/// started. Returns `false`, writing nothing, when another edit got there first.
+pub async fn update_recipe_ingredients_versioned(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    expected_version: i64,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+) -> Result<bool> {
+    apply_recipe_ingredients(pool, recipe_id, Some(expected_version), ingredients).await
+}
+
+/// Current version of a recipe's ingredient list, bumped by every ingredient write
+///
+/// Read it before the ingredients themselves: a write landing in between then shows
+/// up as a conflict instead of going unnoticed.
+pub async fn get_recipe_ingredients_version(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+) -> Result<Option<i64>> {
+    sqlx::query_scalar("SELECT ingredients_version FROM recipes WHERE id = $1")
+        .bind(recipe_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get recipe ingredients version")
+}
+
+/// Synchronize a recipe's ingredients in one transaction, checking their version when given
+async fn apply_recipe_ingredients(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    expected_version: Option<i64>,
+    ingredients: &[crate::text_processing::MeasurementMatch],
+) -> Result<bool> {
+    let span = crate::observability::db_span("update_recipe_ingredients", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        info!(
+            "Bulk updating {} ingredients for recipe {}",
+            ingredients.len(),
+            recipe_id
+        );
+
+        // Added ingredients belong to the recipe owner, resolved before the recipe is
+        // locked since creating their user would wait on that lock
+        let owner = match read_recipe_with_name(pool, recipe_id).await? {
+            Some(recipe) if !ingredients.is_empty() => {
+                Some(get_or_create_user(pool, recipe.telegram_id, None).await?)
+            }
+            _ => None,
+        };
+
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        // Lock the recipe so concurrent updates apply one after the other
+        let current_version: Option<i64> = sqlx::query_scalar(
+            "SELECT ingredients_version FROM recipes WHERE id = $1 FOR UPDATE",
+        )
+        .bind(recipe_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to lock recipe")?;
+        if let Some(expected) = expected_version {
+            if current_version != Some(expected) {
+                info!(
+                    "Ingredients of recipe {} changed since version {} (now {:?}), not updating",
+                    recipe_id, expected, current_version
+                );
+                return Ok(false);
+            }
+        }
+
+        // Get existing ingredients for this recipe
+        let existing_ingredients: Vec<Ingredient> = sqlx::query(&format!(
+            "SELECT {INGREDIENT_COLUMNS} FROM ingredients WHERE recipe_id = $1 ORDER BY {INGREDIENT_ORDER}"
+        ))
+        .bind(recipe_id)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to get recipe ingredients")?
+        .iter()
+        .map(ingredient_from_row)
+        .collect();
+
+        // Use the change detection logic from ingredient_editing module
+        let changes = crate::ingredient_editing::detect_ingredient_changes(
+            &crate::ingredient_editing::snapshot_ingredients(&existing_ingredients),
+            ingredients,
+        );
+
+        // Delete ingredients that are no longer present
+        for &ingredient_id in &changes.to_delete {
+            sqlx::query("DELETE FROM ingredients WHERE id = $1")
+                .bind(ingredient_id)
+                .execute(&mut *tx)
+                .await
+                .context(format!("Failed to delete ingredient {}", ingredient_id))?;
+            info!("Deleted ingredient ID {}", ingredient_id);
+        }
+
+        // Update existing ingredients
+        for (ingredient_id, new_match) in &changes.to_update {
+            // A quantity that cannot be read keeps the saved one instead of erasing it
+            let exact = Quantity::parse(&new_match.quantity);
+            let quantity = exact.map(Quantity::to_f64);
+            let (numerator, denominator) = exact_fraction(exact);
+            let unit = new_match.measurement.as_deref();
+            let (unit_dimension, unit_system) = unit_metadata(unit);
+            let (normalized_name, notes) = name_metadata(&new_match.ingredient_name);
+
+            sqlx::query(
+                "UPDATE ingredients SET name = $1, quantity = COALESCE($2, quantity), unit = $3, unit_dimension = $5, unit_system = $6, \
+             normalized_name = $7, notes = $8, \
+             quantity_numerator = CASE WHEN $2::FLOAT8 IS NULL THEN quantity_numerator ELSE $9 END, \
+             quantity_denominator = CASE WHEN $2::FLOAT8 IS NULL THEN quantity_denominator ELSE $10 END, \
+             updated_at = CURRENT_TIMESTAMP WHERE id = $4",
+            )
+                .bind(&new_match.ingredient_name)
+                .bind(quantity)
+                .bind(unit)
+                .bind(ingredient_id)
+                .bind(unit_dimension)
+                .bind(unit_system)
+                .bind(normalized_name)
+                .bind(notes)
+                .bind(numerator)
+                .bind(denominator)
+                .execute(&mut *tx)
+                .await
+                .context(format!("Failed to update ingredient {}", ingredient_id))?;
+            info!("Updated ingredient ID {}", ingredient_id);
+        }
+
+        // Add new ingredients, owned by the recipe owner's internal user id
+        if !changes.to_add.is_empty() {
+            let owner = owner.ok_or_else(|| anyhow::anyhow!("Recipe not found during update"))?;
+
+            // Range quantities keep their lower bound, with the range itself as raw text
+            let range_texts: Vec<Option<String>> =
+                changes.to_add.iter().map(|m| m.range_text()).collect();
+            let new_ingredients: Vec<NewIngredient> = changes
+                .to_add
+                .iter()
+                .zip(&range_texts)
+                .map(|(new_match, range_text)| NewIngredient {
+                    name: &new_match.ingredient_name,
+                    quantity: Quantity::parse(&new_match.quantity),
+                    unit: new_match.measurement.as_deref(),
+                    raw_text: range_text.as_deref(),
+                })
+                .collect();
+            create_ingredients_bulk(&mut tx, owner.id, recipe_id, &new_ingredients).await?;
+            info!("Added {} new ingredients", new_ingredients.len());
+        }
+
+        // Close the gaps deletions leave so positions keep following the edited list
+        if !changes.to_delete.is_empty() || !changes.to_add.is_empty() {
+            renumber_recipe_ingredients_tx(&mut tx, recipe_id).await?;
+        }
+
+        // Commit transaction
+        tx.commit()
+            .await
+            .context("Failed to commit ingredient updates")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "update_recipe_ingredients",
+            duration,
+            ingredients.len() as u64,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        info!(
+            "Successfully updated ingredients for recipe {}: {} deleted, {} updated, {} added",
+            recipe_id,
+            changes.to_delete.len(),
+            changes.to_update.len(),
+            changes.to_add.len()
+        );
+
+        Ok(true)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Number the ingredients of a recipe from 0 in their current order
+async fn renumber_recipe_ingredients_tx(
+    conn: &mut sqlx::PgConnection,
+    recipe_id: RecipeId,
+) -> Result<()> {
+    sqlx::query(&format!(
+        "UPDATE ingredients i SET position = o.position \
+         FROM (SELECT id, ROW_NUMBER() OVER (ORDER BY {INGREDIENT_ORDER}) - 1 AS position \
+               FROM ingredients WHERE recipe_id = $1) o \
+         WHERE i.id = o.id AND i.position IS DISTINCT FROM o.position"
+    ))
+    .bind(recipe_id)
+    .execute(conn)
+    .await
+    .context("Failed to renumber recipe ingredients")?;
+    Ok(())
+}
+
+/// Reassign ingredients whose `user_id` holds a Telegram ID instead of a `users.id`
+///
+/// Older code paths wrote `recipes.telegram_id` into `ingredients.user_id`. A row is
+/// repaired when its `user_id` matches a user's `telegram_id` and either references no
+/// user at all or belongs to a recipe owned by that Telegram user.
+pub const REPAIR_INGREDIENT_USER_IDS_SQL: &str = "UPDATE ingredients i SET user_id = u.id, updated_at = CURRENT_TIMESTAMP \
+     FROM users u \
+     WHERE i.user_id = u.telegram_id AND i.user_id <> u.id \
+     AND (NOT EXISTS (SELECT 1 FROM users x WHERE x.id = i.user_id) \
+          OR EXISTS (SELECT 1 FROM recipes r WHERE r.id = i.recipe_id AND r.telegram_id = u.telegram_id))";
+
+/// Repair ingredient rows written with a Telegram ID as `user_id`
+///
+/// Returns the number of repaired rows.
+pub async fn repair_ingredient_user_ids(pool: &PgPool) -> Result<u64> {
+    let span = crate::observability::db_span("repair_ingredient_user_ids", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let result = sqlx::query(REPAIR_INGREDIENT_USER_IDS_SQL)
+            .execute(pool)
+            .await
+            .context("Failed to repair ingredient user ids")?;
+
+        let repaired = result.rows_affected();
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "repair_ingredient_user_ids",
+            duration,
+            repaired,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        if repaired > 0 {
+            info!(repaired = %repaired, duration_ms = %duration.as_millis(), "Repaired ingredients with misattributed user ids");
+        } else {
+            debug!(duration_ms = %duration.as_millis(), "No ingredients with misattributed user ids");
+        }
+        Ok(repaired)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Classify saved ingredients whose unit metadata is missing
+///
+/// Rows written before units carried a dimension and system are filled in from the
+/// measurement units config. Returns the number of updated rows.
+pub async fn backfill_ingredient_unit_metadata(pool: &PgPool) -> Result<u64> {
+    let span = crate::observability::db_span("backfill_ingredient_unit_metadata", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let units: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT LOWER(unit) FROM ingredients WHERE unit IS NOT NULL AND unit_dimension IS NULL",
+        )
+        .fetch_all(pool)
+        .await
+        .context("Failed to list unclassified ingredient units")?;
+
+        let mut updated = 0;
+        for unit in &units {
+            let (Some(unit_dimension), Some(unit_system)) = unit_metadata(Some(unit)) else {
+                continue;
+            };
+            updated += sqlx::query(
+                "UPDATE ingredients SET unit_dimension = $2, unit_system = $3 \
+             WHERE LOWER(unit) = $1 AND unit_dimension IS NULL",
+            )
+            .bind(unit)
+            .bind(unit_dimension)
+            .bind(unit_system)
+            .execute(pool)
+            .await
+            .context(format!("Failed to classify ingredient unit '{}'", unit))?
+            .rows_affected();
+        }
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "backfill_ingredient_unit_metadata",
+            duration,
+            updated,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        if updated > 0 {
+            info!(updated = %updated, duration_ms = %duration.as_millis(), "Backfilled ingredient unit metadata");
+        }
+        Ok(updated)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Fill in the canonical name of ingredients saved before names were normalized
+///
+/// Returns the number of updated rows.
+pub async fn backfill_ingredient_normalized_names(pool: &PgPool) -> Result<u64> {
+    let span = crate::observability::db_span("backfill_ingredient_normalized_names", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT name FROM ingredients WHERE normalized_name IS NULL")
+                .fetch_all(pool)
+                .await
+                .context("Failed to list ingredients without a normalized name")?;
+
+        let (normalized_names, notes): (Vec<String>, Vec<Option<String>>) =
+            names.iter().map(|name| name_metadata(name)).unzip();
+        let updated = sqlx::query(
+            "UPDATE ingredients i SET normalized_name = t.normalized_name, notes = t.notes \
+         FROM UNNEST($1::text[], $2::text[], $3::text[]) AS t(name, normalized_name, notes) \
+         WHERE i.name = t.name AND i.normalized_name IS NULL",
+        )
+        .bind(&names)
+        .bind(&normalized_names)
+        .bind(&notes)
+        .execute(pool)
+        .await
+        .context("Failed to backfill normalized ingredient names")?
+        .rows_affected();
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "backfill_ingredient_normalized_names",
+            duration,
+            updated,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        if updated > 0 {
+            info!(updated = %updated, duration_ms = %duration.as_millis(), "Backfilled normalized ingredient names");
+        }
+        Ok(updated)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Update the recipe name for a recipe
+pub async fn update_recipe_name(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    recipe_name: &str,
+) -> Result<bool> {
+    let mut conn = pool
+        .acquire()
+        .await
+        .context("Failed to acquire connection")?;
+    update_recipe_name_tx(&mut conn, recipe_id, recipe_name).await
+}
+
+/// Update the recipe name for a recipe on a connection, typically a transaction's
+pub async fn update_recipe_name_tx(
+    conn: &mut sqlx::PgConnection,
+    recipe_id: RecipeId,
+    recipe_name: &str,
+) -> Result<bool> {
+    debug!(recipe_id = %recipe_id, "Updating recipe recipe name");
+
+    let result = sqlx::query("UPDATE recipes SET recipe_name = $1 WHERE id = $2")
+        .bind(recipe_name)
+        .bind(recipe_id)
+        .execute(conn)
+        .await
+        .context("Failed to update recipe recipe name")?;
+
+    let rows_affected = result.rows_affected();
+    if rows_affected > 0 {
+        debug!(recipe_id = %recipe_id, "Recipe recipe name updated successfully");
+        Ok(true)
+    } else {
+        info!("No recipe found with ID: {recipe_id}");
+        Ok(false)
+    }
+}
+
+/// Keep the original photo of a recipe on a connection, typically a transaction's
+pub async fn set_recipe_photo_tx(
+    conn: &mut sqlx::PgConnection,
+    recipe_id: RecipeId,
+    photo: &RecipePhoto,
+) -> Result<()> {
+    debug!(recipe_id = %recipe_id, file_unique_id = %photo.file_unique_id, "Setting recipe photo");
+
+    sqlx::query("UPDATE recipes SET file_id = $1, file_unique_id = $2 WHERE id = $3")
+        .bind(&photo.file_id)
+        .bind(&photo.file_unique_id)
+        .bind(recipe_id)
+        .execute(conn)
+        .await
+        .context("Failed to set recipe photo")?;
+    Ok(())
+}
+
+/// Original photo of a user's recipe, `None` for recipes read from text or saved
+/// before photos were kept
+pub async fn get_recipe_photo(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+) -> Result<Option<RecipePhoto>> {
+    let span = crate::observability::db_span("get_recipe_photo", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, "Getting recipe photo");
+
+        let row = sqlx::query(
+            "SELECT file_id, file_unique_id FROM recipes \
+         WHERE id = $1 AND telegram_id = $2 AND deleted_at IS NULL \
+         AND file_id IS NOT NULL AND file_unique_id IS NOT NULL",
+        )
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get recipe photo")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_recipe_photo",
+            duration,
+            row.is_some() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        let photo = row.map(|row| RecipePhoto {
+            file_id: row.get(0),
+            file_unique_id: row.get(1),
+        });
+        debug!(recipe_id = %recipe_id, found = photo.is_some(), duration_ms = %duration.as_millis(), "Recipe photo lookup completed");
+        Ok(photo)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Set the notes of one of the user's recipes, clearing them with `None`
+///
+/// Returns whether the recipe was found among the user's recipes.
+pub async fn update_recipe_notes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    notes: Option<&str>,
+) -> Result<bool> {
+    let span = crate::observability::db_span("update_recipe_notes", "recipes");
+    async move {
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, clear = notes.is_none(), "Updating recipe notes");
+
+        let result = sqlx::query(
+            "UPDATE recipes SET notes = $1 WHERE id = $2 AND telegram_id = $3 AND deleted_at IS NULL",
+        )
+        .bind(notes)
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .execute(pool)
+        .await
+        .context("Failed to update recipe notes")?;
+
+        Ok(result.rows_affected() > 0)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get recipe with recipe name
+pub async fn read_recipe_with_name(pool: &PgPool, recipe_id: RecipeId) -> Result<Option<Recipe>> {
+    debug!(recipe_id = %recipe_id, "Reading recipe with recipe name");
+
+    let row = sqlx::query(
+        "SELECT id, telegram_id, content, recipe_name, created_at, notes FROM recipes WHERE id = $1 AND deleted_at IS NULL",
+    )
+    .bind(recipe_id)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to read recipe with recipe")?;
+
+    match row {
+        Some(row) => {
+            let recipe = Recipe {
+                id: row.get(0),
+                telegram_id: row.get(1),
+                content: row.get(2),
+                recipe_name: row.get(3),
+                created_at: row.get(4),
+                notes: row.get(5),
+            };
+            debug!(recipe_id = %recipe_id, "Recipe with recipe found");
+            Ok(Some(recipe))
+        }
+        None => {
+            debug!(recipe_id = %recipe_id, "No recipe found");
+            Ok(None)
+        }
+    }
+}
+
+/// A recipe found by full-text search
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeSearchMatch {
+    pub recipe: Recipe,
+    /// Relevance from `ts_rank`, 0 when only the name substring matched
+    pub rank: f32,
+    /// Excerpt of the ingredient names, or of the content without ingredients,
+    /// matched words being wrapped in `<b>` and `</b>`
+    pub headline: String,
+}
+
+/// Search recipes by full text, or by a substring of their name
+///
+/// The searched document holds the recipe name, its ingredient names and its
+/// content, stemmed in French for users whose language is French and in English
+/// otherwise. Matches come most relevant first, then newest.
+pub async fn search_recipes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    query: &str,
+) -> Result<Vec<RecipeSearchMatch>> {
+    info!("Searching recipes for telegram_id: {telegram_id} with query: {query}");
+
+    let rows = sqlx::query(&format!(
+        "WITH q AS (SELECT recipe_search_config($1) AS config, plainto_tsquery(recipe_search_config($1), $2) AS query) \
+         SELECT r.id, r.telegram_id, r.content, r.recipe_name, r.created_at, r.notes, \
+                ts_rank(r.search_tsv, q.query) AS rank, \
+                ts_headline(q.config, COALESCE( \
+                    (SELECT string_agg(name, ', ' ORDER BY {INGREDIENT_ORDER}) \
+                     FROM ingredients WHERE recipe_id = r.id), r.content), \
+                    q.query, 'MaxWords=12, MinWords=4, MaxFragments=1') AS headline \
+         FROM recipes r, q \
+         WHERE r.telegram_id = $1 AND r.deleted_at IS NULL \
+         AND (r.search_tsv @@ q.query OR r.recipe_name ILIKE '%' || $3 || '%') \
+         ORDER BY rank DESC, r.created_at DESC"
+    ))
+    .bind(telegram_id)
+    .bind(query)
+    .bind(escape_like_pattern(query.trim()))
+    .fetch_all(pool)
+    .await
+    .context("Failed to search recipes")?;
+
+    let matches: Vec<RecipeSearchMatch> = rows
+        .into_iter()
+        .map(|row| RecipeSearchMatch {
+            recipe: Recipe {
+                id: row.get(0),
+                telegram_id: row.get(1),
+                content: row.get(2),
+                recipe_name: row.get(3),
+                created_at: row.get(4),
+                notes: row.get(5),
+            },
+            rank: row.get(6),
+            headline: row.get(7),
+        })
+        .collect();
+
+    info!(telegram_id = %telegram_id, query = %query, result_count = matches.len(), "Recipe search completed");
+    Ok(matches)
+}
+
+/// Get all recipes with a specific name for a user
+pub async fn get_recipes_by_name(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_name: &str,
+) -> Result<Vec<Recipe>> {
+    let span = crate::observability::db_span("get_recipes_by_name", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, "Getting recipes by name");
+
+        let rows = sqlx::query(
+            "SELECT id, telegram_id, content, recipe_name, created_at, notes FROM recipes WHERE telegram_id = $1 AND recipe_name = $2 AND deleted_at IS NULL ORDER BY created_at DESC"
+        )
+        .bind(telegram_id)
+        .bind(recipe_name)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recipes by name")?;
+
+        let recipes: Vec<Recipe> = rows
+            .into_iter()
+            .map(|row| Recipe {
+                id: row.get(0),
+                telegram_id: row.get(1),
+                content: row.get(2),
+                recipe_name: row.get(3),
+                created_at: row.get(4),
+                notes: row.get(5),
+            })
+            .collect();
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_recipes_by_name",
+            duration,
+            recipes.len() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, count = recipes.len(), duration_ms = %duration.as_millis(), "Recipes by name retrieved successfully");
+        Ok(recipes)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Distinct names of a user's recipes, most recently used first
+///
+/// A name counts from its latest recipe; trashed recipes are left out.
+pub async fn get_recent_recipe_names(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+) -> Result<Vec<String>> {
+    let span = crate::observability::db_span("get_recent_recipe_names", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, limit, "Getting recent recipe names");
+
+        let names: Vec<String> = sqlx::query_scalar(
+            "SELECT recipe_name FROM recipes
+             WHERE telegram_id = $1 AND recipe_name IS NOT NULL AND deleted_at IS NULL
+             GROUP BY recipe_name
+             ORDER BY MAX(created_at) DESC, recipe_name
+             LIMIT $2",
+        )
+        .bind(telegram_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recent recipe names")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_recent_recipe_names",
+            duration,
+            names.len() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        debug!(telegram_id = %telegram_id, count = names.len(), duration_ms = %duration.as_millis(), "Recent recipe names retrieved successfully");
+        Ok(names)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Length of a recipe name token, in hex characters
+pub const RECIPE_NAME_TOKEN_LENGTH: usize = 16;
+
+/// Short token standing for a recipe name in callback data
+///
+/// Telegram caps callback data at 64 bytes, which long or emoji-heavy names exceed,
+/// so buttons carry the first 64 bits of the name's SHA-256 in hex instead.
+pub fn recipe_name_token(recipe_name: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut token = hex::encode(Sha256::digest(recipe_name.as_bytes()));
+    token.truncate(RECIPE_NAME_TOKEN_LENGTH);
+    token
+}
+
+/// Find the name of a user's recipes from its [`recipe_name_token`]
+pub async fn find_recipe_name_by_token(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    token: &str,
+) -> Result<Option<String>> {
+    let span = crate::observability::db_span("find_recipe_name_by_token", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, token = %token, "Finding recipe name by token");
+
+        let recipe_name: Option<String> = sqlx::query_scalar(
+            "SELECT recipe_name FROM recipes WHERE telegram_id = $1 AND recipe_name IS NOT NULL AND deleted_at IS NULL \
+         AND LEFT(ENCODE(SHA256(CONVERT_TO(recipe_name, 'UTF8')), 'hex'), $3) = $2 LIMIT 1",
+        )
+        .bind(telegram_id)
+        .bind(token)
+        .bind(RECIPE_NAME_TOKEN_LENGTH as i32)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to find recipe name by token")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "find_recipe_name_by_token",
+            duration,
+            recipe_name.is_some() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        debug!(telegram_id = %telegram_id, found = recipe_name.is_some(), duration_ms = %duration.as_millis(), "Recipe name lookup by token completed");
+        Ok(recipe_name)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Check if a recipe name has duplicates for a user
+pub async fn has_duplicate_recipes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_name: &str,
+) -> Result<bool> {
+    let span = crate::observability::db_span("has_duplicate_recipes", "recipes");
+    async move {
+        debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, "Checking for duplicate recipes");
+
+        let row =
+            sqlx::query("SELECT COUNT(*) FROM recipes WHERE telegram_id = $1 AND recipe_name = $2 AND deleted_at IS NULL")
+                .bind(telegram_id)
+                .bind(recipe_name)
+                .fetch_one(pool)
+                .await
+                .context("Failed to check for duplicate recipes")?;
+
+        let count: i64 = row.get(0);
+        let has_duplicates = count > 1;
+
+        debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, count = %count, has_duplicates = %has_duplicates, "Duplicate check completed");
+        Ok(has_duplicates)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get a page of the user's recipes whose name starts with `prefix`, with ingredients
+///
+/// The match ignores case and uses the `(telegram_id, lower(recipe_name))` index.
+/// Recipes come ordered by name, then newest first; unnamed recipes never match.
+pub async fn search_recipes_by_name_prefix(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    prefix: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<(Recipe, Vec<Ingredient>)>> {
+    let span = crate::observability::db_span("search_recipes_by_name_prefix", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let recipes: Vec<Recipe> = sqlx::query(
+            "SELECT id, telegram_id, content, recipe_name, created_at, notes FROM recipes \
+         WHERE telegram_id = $1 AND deleted_at IS NULL AND lower(recipe_name) LIKE $2 || '%' \
+         ORDER BY lower(recipe_name), created_at DESC, id DESC LIMIT $3 OFFSET $4",
+        )
+        .bind(telegram_id)
+        .bind(escape_like_pattern(&prefix.trim().to_lowercase()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("Failed to search recipes by name prefix")?
+        .into_iter()
+        .map(|row| Recipe {
+            id: row.get(0),
+            telegram_id: row.get(1),
+            content: row.get(2),
+            recipe_name: row.get(3),
+            created_at: row.get(4),
+            notes: row.get(5),
+        })
+        .collect();
+
+        let results = with_ingredients(pool, recipes).await?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "search_recipes_by_name_prefix",
+            duration,
+            results.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        debug!(telegram_id = %telegram_id, prefix = %prefix, offset, results = results.len(), "Recipes by name prefix retrieved");
+        Ok(results)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Escape `%`, `_` and `\\` so a value matches literally in an ILIKE pattern
+pub fn escape_like_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Find the user's other recipes that contain an ingredient with the given name
+///
+/// Names are compared in their canonical form, so "Butter, softened" matches "butter".
+/// Only recipes owned by `telegram_id` are considered, and `exclude_recipe_id` is skipped.
+pub async fn find_other_recipes_with_ingredient(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    exclude_recipe_id: RecipeId,
+    ingredient_name: &str,
+) -> Result<Vec<Recipe>> {
+    let span = crate::observability::db_span("find_other_recipes_with_ingredient", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, exclude_recipe_id = %exclude_recipe_id, ingredient_name = %ingredient_name, "Finding other recipes with ingredient");
+
+        let rows = sqlx::query(
+            "SELECT r.id, r.telegram_id, r.content, r.recipe_name, r.created_at, r.notes FROM recipes r \
+         WHERE r.telegram_id = $1 AND r.id <> $2 AND r.deleted_at IS NULL \
+         AND EXISTS (SELECT 1 FROM ingredients i WHERE i.recipe_id = r.id AND i.normalized_name = $3) \
+         ORDER BY r.created_at DESC",
+        )
+        .bind(telegram_id)
+        .bind(exclude_recipe_id)
+        .bind(normalize_ingredient_name(ingredient_name).name)
+        .fetch_all(pool)
+        .await
+        .context("Failed to find other recipes with ingredient")?;
+
+        let recipes: Vec<Recipe> = rows
+            .into_iter()
+            .map(|row| Recipe {
+                id: row.get(0),
+                telegram_id: row.get(1),
+                content: row.get(2),
+                recipe_name: row.get(3),
+                created_at: row.get(4),
+                notes: row.get(5),
+            })
+            .collect();
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "find_other_recipes_with_ingredient",
+            duration,
+            recipes.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        debug!(telegram_id = %telegram_id, count = recipes.len(), duration_ms = %duration.as_millis(), "Other recipes with ingredient retrieved successfully");
+        Ok(recipes)
+    }
+    .instrument(span)
+    .await
+}
+
+/// A recipe found by ingredient search
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientSearchMatch {
+    pub recipe: Recipe,
+    /// Number of the recipe's ingredients whose name matched
+    pub matching_ingredients: i64,
+}
+
+/// Find the user's recipes containing an ingredient whose name includes `ingredient_query`
+///
+/// The match is partial ("butter" finds "unsalted butter") on canonical names, so
+/// case, hyphens and aliases ("AP flour") do not matter.
+/// Ingredients not attached to a recipe (`recipe_id IS NULL`) are never matched.
+/// Recipes with the most matching ingredients come first, then the newest.
+pub async fn search_recipes_by_ingredient(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    ingredient_query: &str,
+) -> Result<Vec<IngredientSearchMatch>> {
+    let span = crate::observability::db_span("search_recipes_by_ingredient", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, ingredient_query = %ingredient_query, "Searching recipes by ingredient");
+
+        let rows = sqlx::query(
+            "SELECT r.id, r.telegram_id, r.content, r.recipe_name, r.created_at, r.notes, COUNT(i.id) \
+         FROM recipes r JOIN ingredients i ON i.recipe_id = r.id \
+         WHERE r.telegram_id = $1 AND r.deleted_at IS NULL AND i.normalized_name LIKE '%' || $2 || '%' \
+         GROUP BY r.id \
+         ORDER BY COUNT(i.id) DESC, r.created_at DESC",
+        )
+        .bind(telegram_id)
+        .bind(escape_like_pattern(
+            &normalize_ingredient_name(ingredient_query).name,
+        ))
+        .fetch_all(pool)
+        .await
+        .context("Failed to search recipes by ingredient")?;
+
+        let matches: Vec<IngredientSearchMatch> = rows
+            .into_iter()
+            .map(|row| IngredientSearchMatch {
+                recipe: Recipe {
+                    id: row.get(0),
+                    telegram_id: row.get(1),
+                    content: row.get(2),
+                    recipe_name: row.get(3),
+                    created_at: row.get(4),
+                    notes: row.get(5),
+                },
+                matching_ingredients: row.get(6),
+            })
+            .collect();
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "search_recipes_by_ingredient",
+            duration,
+            matches.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        debug!(telegram_id = %telegram_id, count = matches.len(), duration_ms = %duration.as_millis(), "Recipes by ingredient retrieved successfully");
+        Ok(matches)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Rename an ingredient across all of the user's other recipes
+///
+/// Runs a single batched UPDATE inside a transaction, scoped to recipes owned by
+/// `telegram_id` and excluding `exclude_recipe_id`. Returns the number of renamed rows.
+pub async fn rename_ingredient_in_other_recipes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    exclude_recipe_id: RecipeId,
+    old_name: &str,
+    new_name: &str,
+) -> Result<u64> {
+    let span = crate::observability::db_span("rename_ingredient_in_other_recipes", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        info!(telegram_id = %telegram_id, exclude_recipe_id = %exclude_recipe_id, old_name = %old_name, new_name = %new_name, "Renaming ingredient across recipes");
+
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        let (normalized_name, notes) = name_metadata(new_name);
+        let result = sqlx::query(
+            "UPDATE ingredients SET name = $1, normalized_name = $5, notes = $6, updated_at = CURRENT_TIMESTAMP \
+         WHERE normalized_name = $2 \
+         AND recipe_id IN (SELECT id FROM recipes WHERE telegram_id = $3 AND id <> $4 AND deleted_at IS NULL)",
+        )
+        .bind(new_name.trim())
+        .bind(normalize_ingredient_name(old_name).name)
+        .bind(telegram_id)
+        .bind(exclude_recipe_id)
+        .bind(normalized_name)
+        .bind(notes)
+        .execute(&mut *tx)
+        .await
+        .context(format!("Failed to rename ingredient '{}'", old_name))?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit ingredient rename")?;
+
+        let renamed = result.rows_affected();
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "rename_ingredient_in_other_recipes",
+            duration,
+            renamed,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        info!(telegram_id = %telegram_id, renamed = %renamed, duration_ms = %duration.as_millis(), "Ingredient renamed across recipes");
+        Ok(renamed)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get paginated list of recipe names for a user
+pub async fn get_user_recipes_paginated(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<String>, i64)> {
+    // Validate pagination parameters to prevent DoS attacks
+    if !(1..=100).contains(&limit) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination limit: {} (must be between 1 and 100)",
+            limit
+        ));
+    }
+    if !(0..=10000).contains(&offset) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination offset: {} (must be between 0 and 10000)",
+            offset
+        ));
+    }
+
+    debug!(telegram_id = %telegram_id, limit = %limit, offset = %offset, "Getting paginated recipes for user");
+
+    // Get total count of distinct recipe names
+    let total_row = sqlx::query(
+        "SELECT COUNT(DISTINCT recipe_name) FROM recipes WHERE telegram_id = $1 AND recipe_name IS NOT NULL AND deleted_at IS NULL"
+    )
+    .bind(telegram_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to get total recipe count")?;
+    let total: i64 = total_row.get(0);
+
+    // Get paginated recipe names
+    let rows = sqlx::query(
+        "SELECT DISTINCT recipe_name FROM recipes WHERE telegram_id = $1 AND recipe_name IS NOT NULL AND deleted_at IS NULL ORDER BY recipe_name LIMIT $2 OFFSET $3"
+    )
+    .bind(telegram_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get paginated recipes")?;
+
+    let recipe_names: Vec<String> = rows.into_iter().map(|row| row.get(0)).collect();
+
+    debug!(total = %total, count = %recipe_names.len(), "Retrieved paginated recipes");
+    Ok((recipe_names, total))
+}
+
+/// A single saved recipe as shown in the bulk-delete selection list
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeListEntry {
+    pub id: RecipeId,
+    pub recipe_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Get a page of individual recipes for a user, ordered by name then newest first
+///
+/// Unlike `get_user_recipes_paginated`, recipes sharing a name are listed separately
+/// so each one can be selected on its own.
+pub async fn get_user_recipe_entries_paginated(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<RecipeListEntry>, i64)> {
+    if !(1..=100).contains(&limit) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination limit: {} (must be between 1 and 100)",
+            limit
+        ));
+    }
+    if !(0..=10000).contains(&offset) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination offset: {} (must be between 0 and 10000)",
+            offset
+        ));
+    }
+
+    debug!(telegram_id = %telegram_id, limit = %limit, offset = %offset, "Getting paginated recipe entries for user");
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM recipes WHERE telegram_id = $1 AND deleted_at IS NULL",
+    )
+    .bind(telegram_id)
+    .fetch_one(pool)
+    .await
+    .context("Failed to count recipes")?;
+
+    let rows = sqlx::query(
+        "SELECT id, recipe_name, created_at FROM recipes WHERE telegram_id = $1 AND deleted_at IS NULL \
+         ORDER BY recipe_name NULLS LAST, created_at DESC, id DESC LIMIT $2 OFFSET $3",
+    )
+    .bind(telegram_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get paginated recipe entries")?;
+
+    let entries = rows
+        .into_iter()
+        .map(|row| RecipeListEntry {
+            id: RecipeId(row.get(0)),
+            recipe_name: row.get(1),
+            created_at: row.get(2),
+        })
+        .collect::<Vec<_>>();
+
+    debug!(total = %total, count = %entries.len(), "Retrieved paginated recipe entries");
+    Ok((entries, total))
+}
+
+/// Get the user's recipes among the given ids, in list order
+///
+/// Ids that don't exist or belong to another user are skipped.
+pub async fn get_recipe_entries_by_ids(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_ids: &[RecipeId],
+) -> Result<Vec<RecipeListEntry>> {
+    let ids: Vec<i64> = recipe_ids.iter().map(|id| id.0).collect();
+
+    let rows = sqlx::query(
+        "SELECT id, recipe_name, created_at FROM recipes WHERE telegram_id = $1 AND id = ANY($2) AND deleted_at IS NULL \
+         ORDER BY recipe_name NULLS LAST, created_at DESC, id DESC",
+    )
+    .bind(telegram_id)
+    .bind(&ids)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get recipes by ids")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RecipeListEntry {
+            id: RecipeId(row.get(0)),
+            recipe_name: row.get(1),
+            created_at: row.get(2),
+        })
+        .collect())
+}
+
+/// Get a user's recipes saved since `since`, newest first
+pub async fn get_user_recipes_created_since(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    since: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<RecipeListEntry>> {
+    let rows = sqlx::query(
+        "SELECT id, recipe_name, created_at FROM recipes WHERE telegram_id = $1 AND created_at >= $2 AND deleted_at IS NULL \
+         ORDER BY created_at DESC, id DESC LIMIT $3",
+    )
+    .bind(telegram_id)
+    .bind(since)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get recently saved recipes")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RecipeListEntry {
+            id: RecipeId(row.get(0)),
+            recipe_name: row.get(1),
+            created_at: row.get(2),
+        })
+        .collect())
+}
+
+/// Tag one of a user's recipes
+///
+/// Returns false when the recipe already has the tag or is not the user's.
+/// Tags are expected to be validated with `validation::validate_tag_name` first.
+pub async fn add_recipe_tag(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    tag: &str,
+) -> Result<bool> {
+    let span = crate::observability::db_span("add_recipe_tag", "recipe_tags");
+    async move {
+        let result = sqlx::query(
+            "INSERT INTO recipe_tags (recipe_id, telegram_id, tag) \
+         SELECT id, telegram_id, $3 FROM recipes WHERE id = $1 AND telegram_id = $2 AND deleted_at IS NULL \
+         ON CONFLICT (recipe_id, tag) DO NOTHING",
+        )
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .bind(tag)
+        .execute(pool)
+        .await
+        .context("Failed to add recipe tag")?;
+
+        let added = result.rows_affected() > 0;
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, tag = %tag, added, "Recipe tag added");
+        Ok(added)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Remove a tag from one of a user's recipes, returning whether it was there
+pub async fn remove_recipe_tag(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    tag: &str,
+) -> Result<bool> {
+    let span = crate::observability::db_span("remove_recipe_tag", "recipe_tags");
+    async move {
+        let result = sqlx::query(
+            "DELETE FROM recipe_tags WHERE recipe_id = $1 AND telegram_id = $2 AND tag = $3",
+        )
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .bind(tag)
+        .execute(pool)
+        .await
+        .context("Failed to remove recipe tag")?;
+
+        let removed = result.rows_affected() > 0;
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, tag = %tag, removed, "Recipe tag removed");
+        Ok(removed)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the tags of a recipe, in alphabetical order
+pub async fn get_recipe_tags(pool: &PgPool, recipe_id: RecipeId) -> Result<Vec<String>> {
+    sqlx::query_scalar("SELECT tag FROM recipe_tags WHERE recipe_id = $1 ORDER BY tag")
+        .bind(recipe_id)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recipe tags")
+}
+
+/// Get every tag a user has put on at least one recipe, in alphabetical order
+pub async fn get_user_tags(pool: &PgPool, telegram_id: TelegramId) -> Result<Vec<String>> {
+    sqlx::query_scalar(
+        "SELECT DISTINCT t.tag FROM recipe_tags t JOIN recipes r ON r.id = t.recipe_id \
+         WHERE t.telegram_id = $1 AND r.deleted_at IS NULL ORDER BY t.tag",
+    )
+    .bind(telegram_id)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get user tags")
+}
+
+/// Get paginated list of the names of a user's recipes carrying a tag
+///
+/// Same listing as `get_user_recipes_paginated`, restricted to tagged recipes: a
+/// name is listed when at least one recipe with that name has the tag, and the
+/// total counts those names only.
+pub async fn get_user_recipes_by_tag_paginated(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    tag: &str,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<String>, i64)> {
+    if !(1..=100).contains(&limit) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination limit: {} (must be between 1 and 100)",
+            limit
+        ));
+    }
+    if !(0..=10000).contains(&offset) {
+        return Err(anyhow::anyhow!(
+            "Invalid pagination offset: {} (must be between 0 and 10000)",
+            offset
+        ));
+    }
+
+    debug!(telegram_id = %telegram_id, tag = %tag, limit = %limit, offset = %offset, "Getting paginated recipes by tag");
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(DISTINCT r.recipe_name) FROM recipes r \
+         JOIN recipe_tags t ON t.recipe_id = r.id \
+         WHERE r.telegram_id = $1 AND t.tag = $2 AND r.recipe_name IS NOT NULL AND r.deleted_at IS NULL",
+    )
+    .bind(telegram_id)
+    .bind(tag)
+    .fetch_one(pool)
+    .await
+    .context("Failed to count tagged recipes")?;
+
+    let recipe_names: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT r.recipe_name FROM recipes r \
+         JOIN recipe_tags t ON t.recipe_id = r.id \
+         WHERE r.telegram_id = $1 AND t.tag = $2 AND r.recipe_name IS NOT NULL AND r.deleted_at IS NULL \
+         ORDER BY r.recipe_name LIMIT $3 OFFSET $4",
+    )
+    .bind(telegram_id)
+    .bind(tag)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .context("Failed to get paginated tagged recipes")?;
+
+    debug!(total = %total, count = %recipe_names.len(), "Retrieved paginated tagged recipes");
+    Ok((recipe_names, total))
+}
+
+/// Delete several of a user's recipes and their ingredients in one transaction
+///
+/// Returns the ids actually deleted; ids belonging to other users are left untouched.
+pub async fn delete_recipes(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_ids: &[RecipeId],
+) -> Result<Vec<RecipeId>> {
+    let span = crate::observability::db_span("delete_recipes", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        let ids: Vec<i64> = recipe_ids.iter().map(|id| id.0).collect();
+        debug!(telegram_id = %telegram_id, count = ids.len(), "Deleting recipes");
+
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        let ingredients_deleted = sqlx::query(
+            "DELETE FROM ingredients WHERE recipe_id IN \
+         (SELECT id FROM recipes WHERE telegram_id = $1 AND id = ANY($2))",
+        )
+        .bind(telegram_id)
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to delete ingredients for recipes")?;
+
+        let deleted: Vec<i64> = sqlx::query_scalar(
+            "DELETE FROM recipes WHERE telegram_id = $1 AND id = ANY($2) RETURNING id",
+        )
+        .bind(telegram_id)
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to delete recipes")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit recipe deletion")?;
+
+        observability::record_db_performance_metrics(
+            "delete_recipes",
+            start_time.elapsed(),
+            deleted.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        info!(
+            telegram_id = %telegram_id,
+            recipes_deleted = deleted.len(),
+            ingredients_deleted = ingredients_deleted.rows_affected(),
+            "Recipes deleted"
+        );
+        Ok(deleted.into_iter().map(RecipeId).collect())
+    }
+    .instrument(span)
+    .await
+}
+
+/// What was removed by [`delete_all_user_data`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DeletedUserData {
+    /// Number of recipes deleted
+    pub recipes: u64,
+    /// Number of ingredients deleted
+    pub ingredients: u64,
+}
+
+/// Delete everything stored about a user in one transaction
+///
+/// Removes the user's ingredients, recipes and users row, along with their
+/// activity, tags, import jobs, extraction reports and experiment data, so no row
+/// refers to the telegram id afterwards.
+pub async fn delete_all_user_data(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<DeletedUserData> {
+    let span = crate::observability::db_span("delete_all_user_data", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        debug!(telegram_id = %telegram_id, "Deleting all user data");
+
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        let ingredients = sqlx::query(
+            "DELETE FROM ingredients WHERE user_id IN (SELECT id FROM users WHERE telegram_id = $1) \
+         OR recipe_id IN (SELECT id FROM recipes WHERE telegram_id = $1)",
+        )
+        .bind(telegram_id)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to delete user ingredients")?
+        .rows_affected();
+
+        for (table, what) in [
+            ("recipe_activity", "recipe activity"),
+            ("recipe_tags", "recipe tags"),
+            ("recipe_shares", "recipe shares"),
+            ("import_jobs", "import jobs"),
+            ("extraction_reports", "extraction reports"),
+            ("review_funnel_events", "review funnel events"),
+            ("experiment_assignments", "experiment assignments"),
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE telegram_id = $1"))
+                .bind(telegram_id)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to delete user {what}"))?;
+        }
+
+        let recipes = sqlx::query("DELETE FROM recipes WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete user recipes")?
+            .rows_affected();
+
+        sqlx::query("DELETE FROM users WHERE telegram_id = $1")
+            .bind(telegram_id)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to delete user")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit user data deletion")?;
+
+        observability::record_db_performance_metrics(
+            "delete_all_user_data",
+            start_time.elapsed(),
+            recipes + ingredients,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        info!(
+            telegram_id = %telegram_id,
+            recipes_deleted = recipes,
+            ingredients_deleted = ingredients,
+            "All user data deleted"
+        );
+        Ok(DeletedUserData {
+            recipes,
+            ingredients,
+        })
+    }
+    .instrument(span)
+    .await
+}
+
+/// Recipes read per round trip when exporting an account
+pub const EXPORT_BATCH_SIZE: i64 = 100;
+
+/// Get the ingredients of several recipes in one query
+///
+/// Every requested recipe has an entry, empty when it has no ingredients; each
+/// list is in the order of [`get_recipe_ingredients`].
+pub async fn get_ingredients_for_recipes(
+    pool: &PgPool,
+    recipe_ids: &[RecipeId],
+) -> Result<HashMap<RecipeId, Vec<Ingredient>>> {
+    let span = crate::observability::db_span("get_ingredients_for_recipes", "ingredients");
+    async move {
+        let mut by_recipe: HashMap<RecipeId, Vec<Ingredient>> = recipe_ids
+            .iter()
+            .map(|&recipe_id| (recipe_id, Vec::new()))
+            .collect();
+        if recipe_ids.is_empty() {
+            return Ok(by_recipe);
+        }
+
+        let start_time = std::time::Instant::now();
+        let ids: Vec<i64> = recipe_ids.iter().map(|recipe_id| recipe_id.0).collect();
+        let rows = sqlx::query(&format!(
+            "SELECT {INGREDIENT_COLUMNS} FROM ingredients WHERE recipe_id = ANY($1) \
+             ORDER BY recipe_id, {INGREDIENT_ORDER}"
+        ))
+        .bind(&ids)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get ingredients for recipes batch")?;
+
+        for ingredient in rows.iter().map(ingredient_from_row) {
+            if let Some(recipe_id) = ingredient.recipe_id {
+                by_recipe.entry(recipe_id).or_default().push(ingredient);
+            }
+        }
+
+        let duration = start_time.elapsed();
+        observability::record_db_batch_size("get_ingredients_for_recipes", recipe_ids.len());
+        observability::record_db_performance_metrics(
+            "get_ingredients_for_recipes",
+            duration,
+            rows.len() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
+        debug!(recipes = recipe_ids.len(), ingredients = rows.len(), duration_ms = %duration.as_millis(), "Ingredients of recipes batch retrieved");
+        Ok(by_recipe)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Pair recipes with their ingredients, loaded in one query
+async fn with_ingredients(
+    pool: &PgPool,
+    recipes: Vec<Recipe>,
+) -> Result<Vec<(Recipe, Vec<Ingredient>)>> {
+    if recipes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let recipe_ids: Vec<RecipeId> = recipes.iter().map(|recipe| recipe.id).collect();
+    let mut by_recipe = get_ingredients_for_recipes(pool, &recipe_ids).await?;
+    Ok(recipes
+        .into_iter()
+        .map(|recipe| {
+            let ingredients = by_recipe.remove(&recipe.id).unwrap_or_default();
+            (recipe, ingredients)
+        })
+        .collect())
+}
+
+/// Get a batch of a user's recipes with their ingredients, oldest first
+///
+/// Batches are keyset-paginated on the recipe id: pass the last id of the
+/// previous batch as `after` to continue. An empty batch means the end.
+pub async fn get_recipes_with_ingredients_batch(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    after: Option<RecipeId>,
+    limit: i64,
+) -> Result<Vec<(Recipe, Vec<Ingredient>)>> {
+    let span = crate::observability::db_span("get_recipes_with_ingredients_batch", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let recipes: Vec<Recipe> = sqlx::query(
+            "SELECT id, telegram_id, content, recipe_name, created_at, notes FROM recipes \
+         WHERE telegram_id = $1 AND id > $2 AND deleted_at IS NULL ORDER BY id LIMIT $3",
+        )
+        .bind(telegram_id)
+        .bind(after.map_or(0, |id| id.0))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recipes batch")?
+        .into_iter()
+        .map(|row| Recipe {
+            id: row.get(0),
+            telegram_id: row.get(1),
+            content: row.get(2),
+            recipe_name: row.get(3),
+            created_at: row.get(4),
+            notes: row.get(5),
+        })
+        .collect();
+
+        let batch = with_ingredients(pool, recipes).await?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_recipes_with_ingredients_batch",
+            duration,
+            batch.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        debug!(telegram_id = %telegram_id, recipes = batch.len(), "Recipes batch retrieved");
+        Ok(batch)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Outcome of importing an export file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ImportSummary {
+    /// Recipes created, with their ingredients
+    pub created: usize,
+    /// Recipes skipped because the same name and ingredient set already existed
+    pub skipped: usize,
+}
+
+/// Recreate the exported recipes of an import job for its user, in batches
+///
+/// Each batch is committed with the job's progress, so an interrupted import resumes
+/// after its last committed batch; `on_progress` receives the recipes committed so far.
+/// A recipe whose name and ingredient set match an existing recipe, or one earlier
+/// in the same file, is skipped. Original creation dates are kept.
+pub async fn import_recipes<F, Fut>(
+    pool: &PgPool,
+    job: &ImportJob,
+    recipes: &[crate::recipe_export::ExportedRecipe],
+    on_progress: F,
+) -> Result<ImportSummary>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    use crate::import_jobs::{row_content_hash, run_import_job};
+    use crate::recipe_export::recipe_identity_key;
+
+    let telegram_id = job.telegram_id;
+    let span = crate::observability::db_span("import_recipes", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        let user = get_or_create_user(pool, telegram_id, None).await?;
+        let writer = RecipeImportWriter {
+            telegram_id,
+            user_id: user.id,
+        };
+
+        let outcome = run_import_job(
+            pool,
+            job,
+            recipes,
+            |recipe| {
+                row_content_hash(&recipe_identity_key(
+                    recipe.name.as_deref(),
+                    &recipe.ingredients,
+                ))
+            },
+            &writer,
+            on_progress,
+        )
+        .await?;
+        let summary = ImportSummary {
+            created: outcome.inserted,
+            skipped: outcome.skipped,
+        };
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "import_recipes",
+            duration,
+            recipes.len() as u64,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        info!(telegram_id = %telegram_id, job_id = job.id, created = summary.created, skipped = summary.skipped, "Recipes imported");
+        Ok(summary)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Writes one batch of an export file's recipes with their ingredients
+struct RecipeImportWriter {
+    telegram_id: TelegramId,
+    user_id: UserId,
+}
+
+impl crate::import_jobs::ImportBatchWriter<crate::recipe_export::ExportedRecipe>
+    for RecipeImportWriter
+{
+    async fn write_rows(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        recipes: &[&crate::recipe_export::ExportedRecipe],
+    ) -> Result<usize> {
+        use crate::recipe_export::{recipe_identity_key, ExportedIngredient};
+
+        // Only recipes sharing a name with the batch can be duplicates
+        let names: Vec<String> = recipes
+            .iter()
+            .map(|recipe| {
+                recipe
+                    .name
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            })
+            .collect();
+        let rows = sqlx::query(
+            "SELECT r.id, r.recipe_name, i.name, \
+         COALESCE(i.quantity_numerator::float8 / i.quantity_denominator, i.quantity::float8), i.unit \
+         FROM recipes r LEFT JOIN ingredients i ON i.recipe_id = r.id \
+         WHERE r.telegram_id = $1 AND r.deleted_at IS NULL AND lower(trim(COALESCE(r.recipe_name, ''))) = ANY($2)",
+        )
+        .bind(self.telegram_id)
+        .bind(&names)
+        .fetch_all(&mut *conn)
+        .await
+        .context("Failed to load existing recipes for import")?;
+
+        let mut existing: HashMap<i64, (Option<String>, Vec<ExportedIngredient>)> = HashMap::new();
+        for row in rows {
+            let entry = existing
+                .entry(row.get(0))
+                .or_insert_with(|| (row.get(1), Vec::new()));
+            if let Some(name) = row.get::<Option<String>, _>(2) {
+                entry.1.push(ExportedIngredient {
+                    name,
+                    quantity: row.get(3),
+                    unit: row.get(4),
+                });
+            }
+        }
+        let known_keys: HashSet<String> = existing
+            .values()
+            .map(|(name, ingredients)| recipe_identity_key(name.as_deref(), ingredients))
+            .collect();
+
+        let mut created = 0;
+        for recipe in recipes {
+            if known_keys.contains(&recipe_identity_key(
+                recipe.name.as_deref(),
+                &recipe.ingredients,
+            )) {
+                continue;
+            }
+
+            let recipe_id: RecipeId = sqlx::query_scalar(
+                "INSERT INTO recipes (telegram_id, content, recipe_name, created_at) \
+             VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(self.telegram_id)
+            .bind(&recipe.content)
+            .bind(&recipe.name)
+            .bind(recipe.created_at)
+            .fetch_one(&mut *conn)
+            .await
+            .context("Failed to insert imported recipe")?;
+
+            if !recipe.ingredients.is_empty() {
+                let names: Vec<&str> = recipe.ingredients.iter().map(|i| i.name.as_str()).collect();
+                let quantities: Vec<Option<f64>> =
+                    recipe.ingredients.iter().map(|i| i.quantity).collect();
+                let (numerators, denominators): (Vec<Option<i64>>, Vec<Option<i64>>) = quantities
+                    .iter()
+                    .map(|quantity| quantity_fraction(*quantity))
+                    .unzip();
+                let units: Vec<Option<&str>> = recipe
+                    .ingredients
+                    .iter()
+                    .map(|i| i.unit.as_deref())
+                    .collect();
+                let (dimensions, systems): (Vec<Option<&str>>, Vec<Option<&str>>) =
+                    units.iter().map(|unit| unit_metadata(*unit)).unzip();
+                let (normalized_names, notes): (Vec<String>, Vec<Option<String>>) =
+                    names.iter().map(|name| name_metadata(name)).unzip();
+
+                sqlx::query(
+                    "INSERT INTO ingredients \
+                 (user_id, recipe_id, name, quantity, unit, raw_text, unit_dimension, unit_system, normalized_name, notes, \
+                 quantity_numerator, quantity_denominator, position) \
+                 SELECT $1, $2, name, quantity, unit, '', unit_dimension, unit_system, normalized_name, notes, \
+                 quantity_numerator, quantity_denominator, ordinal - 1 \
+                 FROM UNNEST($3::text[], $4::float8[], $5::text[], $6::text[], $7::text[], $8::text[], $9::text[], \
+                 $10::int8[], $11::int8[]) \
+                 WITH ORDINALITY AS t(name, quantity, unit, unit_dimension, unit_system, normalized_name, notes, \
+                 quantity_numerator, quantity_denominator, ordinal)",
+                )
+                .bind(self.user_id)
+                .bind(recipe_id)
+                .bind(&names)
+                .bind(&quantities)
+                .bind(&units)
+                .bind(&dimensions)
+                .bind(&systems)
+                .bind(&normalized_names)
+                .bind(&notes)
+                .bind(&numerators)
+                .bind(&denominators)
+                .execute(&mut *conn)
+                .await
+                .context("Failed to insert imported ingredients")?;
+            }
+            created += 1;
+        }
+        Ok(created)
+    }
+}
+
+/// Store a share token giving one copy of `recipe_id` until `expires_at`
+pub async fn create_recipe_share(
+    pool: &PgPool,
+    recipe_id: RecipeId,
+    telegram_id: TelegramId,
+    token: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<()> {
+    let span = crate::observability::db_span("create_recipe_share", "recipe_shares");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        sqlx::query(
+            "INSERT INTO recipe_shares (token, recipe_id, telegram_id, expires_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(token)
+        .bind(recipe_id)
+        .bind(telegram_id)
+        .bind(expires_at)
+        .execute(pool)
+        .await
+        .context("Failed to create recipe share")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "create_recipe_share",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        debug!(recipe_id = %recipe_id, telegram_id = %telegram_id, expires_at = %expires_at, "Recipe share created");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// Result of opening a share link
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareRedemption {
+    /// The recipe was copied into the recipient's account under this id
+    Copied(RecipeId),
+    /// Unknown token, or the shared recipe was deleted since
+    NotFound,
+    Expired,
+    AlreadyUsed,
+    /// The recipient owns the shared recipe; the token stays usable
+    OwnRecipe,
+}
+
+/// Copy the recipe behind a share token into the recipient's account
+///
+/// The copy gets new recipe and ingredient rows owned by `telegram_id`, so later
+/// changes on either side never reach the other. A token is used once: the copy and
+/// marking the token used happen in one transaction, with the share row locked.
+pub async fn redeem_recipe_share(
+    pool: &PgPool,
+    token: &str,
+    telegram_id: TelegramId,
+    now: DateTime<Utc>,
+) -> Result<ShareRedemption> {
+    let span = crate::observability::db_span("redeem_recipe_share", "recipe_shares");
+    async move {
+        let start_time = std::time::Instant::now();
+        let user = get_or_create_user(pool, telegram_id, None).await?;
+
+        let mut tx = pool.begin().await.context("Failed to start transaction")?;
+
+        let share = sqlx::query(
+            "SELECT s.recipe_id, s.expires_at, s.used_at IS NOT NULL, r.telegram_id, r.content, r.recipe_name \
+         FROM recipe_shares s JOIN recipes r ON r.id = s.recipe_id \
+         WHERE s.token = $1 AND r.deleted_at IS NULL FOR UPDATE OF s",
+        )
+        .bind(token)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("Failed to look up recipe share")?;
+
+        let Some(share) = share else {
+            return Ok(ShareRedemption::NotFound);
+        };
+        let recipe_id: RecipeId = share.get(0);
+        let expires_at: DateTime<Utc> = share.get(1);
+        let used: bool = share.get(2);
+        let owner: TelegramId = share.get(3);
+        if used {
+            return Ok(ShareRedemption::AlreadyUsed);
+        }
+        if expires_at <= now {
+            return Ok(ShareRedemption::Expired);
+        }
+        if owner == telegram_id {
+            return Ok(ShareRedemption::OwnRecipe);
+        }
+        let content: String = share.get(4);
+        let recipe_name: Option<String> = share.get(5);
+
+        let copy_id: RecipeId = sqlx::query_scalar(
+            "INSERT INTO recipes (telegram_id, content, recipe_name) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(telegram_id)
+        .bind(&content)
+        .bind(&recipe_name)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to insert shared recipe copy")?;
+
+        // The copy keeps the exact fraction of each quantity, not its rounded float
+        let ingredients: Vec<(String, Option<Quantity>, Option<String>)> = sqlx::query(&format!(
+            "SELECT name, quantity_numerator, quantity_denominator, quantity::float8, unit \
+             FROM ingredients WHERE recipe_id = $1 ORDER BY {INGREDIENT_ORDER}"
+        ))
+        .bind(recipe_id)
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to get shared recipe ingredients")?
+        .iter()
+        .map(|row| {
+            let quantity = stored_quantity(row.get(1), row.get(2), row.get(3));
+            (row.get(0), quantity, row.get(4))
+        })
+        .collect();
+        let new_ingredients: Vec<NewIngredient> = ingredients
+            .iter()
+            .map(|(name, quantity, unit)| NewIngredient {
+                name,
+                quantity: *quantity,
+                unit: unit.as_deref(),
+                raw_text: None,
+            })
+            .collect();
+        create_ingredients_bulk(&mut tx, user.id, copy_id, &new_ingredients).await?;
+
+        sqlx::query("UPDATE recipe_shares SET used_at = $2 WHERE token = $1")
+            .bind(token)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to mark recipe share used")?;
+
+        tx.commit()
+            .await
+            .context("Failed to commit shared recipe copy")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "redeem_recipe_share",
+            duration,
+            new_ingredients.len() as u64 + 1,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        info!(recipe_id = %recipe_id, copy_id = %copy_id, telegram_id = %telegram_id, "Shared recipe copied");
+        Ok(ShareRedemption::Copied(copy_id))
+    }
+    .instrument(span)
+    .await
+}
+
+/// Recipe statistics data structure
+#[derive(Debug)]
+pub struct RecipeStatistics {
+    pub total_recipes: i64,
+    pub total_ingredients: i64,
+    pub average_ingredients_per_recipe: f64,
+    pub oldest_recipe_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub newest_recipe_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub most_common_units: Vec<(String, i64)>,
+    pub recipes_created_today: i64,
+    pub recipes_created_this_week: i64,
+    pub recipes_created_this_month: i64,
+}
+
+/// The recipe of a user with the most ingredients, shown by /stats
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongestRecipe {
+    pub id: RecipeId,
+    pub recipe_name: Option<String>,
+    pub ingredient_count: i64,
+}
+
+/// Counts over every user, shown to admins by /admin_stats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlobalStatistics {
+    pub users: i64,
+    pub recipes: i64,
+    pub ingredients: i64,
+    /// Recipes saved in the last 7 days
+    pub recipes_this_week: i64,
+    /// Users who saved a recipe in the last 7 days
+    pub active_users_this_week: i64,
+}
+
+/// Get the global user, recipe and ingredient counts
+pub async fn get_global_statistics(pool: &PgPool) -> Result<GlobalStatistics> {
+    let span = crate::observability::db_span("get_global_statistics", "users");
+    async move {
+        let start_time = std::time::Instant::now();
+        let row = sqlx::query(
+            r#"
+        SELECT
+            (SELECT COUNT(*) FROM users),
+            (SELECT COUNT(*) FROM recipes WHERE deleted_at IS NULL),
+            (SELECT COUNT(*) FROM ingredients i
+             WHERE NOT EXISTS (SELECT 1 FROM recipes r WHERE r.id = i.recipe_id AND r.deleted_at IS NOT NULL)),
+            (SELECT COUNT(*) FROM recipes WHERE deleted_at IS NULL AND created_at >= NOW() - INTERVAL '7 days'),
+            (SELECT COUNT(DISTINCT telegram_id) FROM recipes WHERE deleted_at IS NULL AND created_at >= NOW() - INTERVAL '7 days')
+        "#,
+        )
+        .fetch_one(pool)
+        .await
+        .context("Failed to get global statistics")?;
+
+        let stats = GlobalStatistics {
+            users: row.get(0),
+            recipes: row.get(1),
+            ingredients: row.get(2),
+            recipes_this_week: row.get(3),
+            active_users_this_week: row.get(4),
+        };
+
+        observability::record_db_performance_metrics(
+            "get_global_statistics",
+            start_time.elapsed(),
+            1,
+            crate::observability::QueryComplexity::Medium,
+        );
+        debug!(stats = ?stats, "Retrieved global statistics");
+        Ok(stats)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the user's variant for an experiment, storing `variant` on first assignment
+///
+/// Existing assignments always win so a user keeps one variant for the whole experiment.
+pub async fn get_or_create_experiment_assignment(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    experiment: &str,
+    variant: &str,
+) -> Result<String> {
+    let span = crate::observability::db_span(
+        "get_or_create_experiment_assignment",
+        "experiment_assignments",
+    );
+    async move {
+        let start_time = std::time::Instant::now();
+
+        sqlx::query(
+            "INSERT INTO experiment_assignments (telegram_id, experiment, variant) VALUES ($1, $2, $3) \
+         ON CONFLICT (telegram_id, experiment) DO NOTHING",
+        )
+        .bind(telegram_id)
+        .bind(experiment)
+        .bind(variant)
         .execute(pool)
         .await
-        .context("Failed to delete ingredient")?;
+        .context("Failed to store experiment assignment")?;
 
-    let rows_affected = result.rows_affected();
-    if rows_affected > 0 {
-        info!("Ingredient deleted successfully with ID: {ingredient_id}");
-        Ok(true)
-    } else {
-        info!("No ingredient found with ID: {ingredient_id}");
-        Ok(false)
+        let row = sqlx::query(
+            "SELECT variant FROM experiment_assignments WHERE telegram_id = $1 AND experiment = $2",
+        )
+        .bind(telegram_id)
+        .bind(experiment)
+        .fetch_one(pool)
+        .await
+        .context("Failed to read experiment assignment")?;
+        let assigned: String = row.get(0);
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_or_create_experiment_assignment",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
+
+        debug!(telegram_id = %telegram_id, experiment = %experiment, variant = %assigned, "Experiment assignment resolved");
+        Ok(assigned)
     }
+    .instrument(span)
+    .await
 }
 
-/// List all ingredients for a user
-pub async fn list_ingredients_by_user(pool: &PgPool, user_id: i64) -> Result<Vec<Ingredient>> {
-    info!("Listing ingredients for user_id: {user_id}");
+/// Store a review funnel event tagged with the user's experiment variant
+pub async fn record_review_funnel_event(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    experiment: &str,
+    variant: &str,
+    event: &str,
+) -> Result<()> {
+    let span = crate::observability::db_span("record_review_funnel_event", "review_funnel_events");
+    async move {
+        let start_time = std::time::Instant::now();
 
-    let rows = sqlx::query("SELECT id, user_id, recipe_id, name, quantity::float8, unit, created_at, updated_at FROM ingredients WHERE user_id = $1 ORDER BY created_at DESC")
-        .bind(user_id)
-        .fetch_all(pool)
+        sqlx::query(
+            "INSERT INTO review_funnel_events (telegram_id, experiment, variant, event) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(telegram_id)
+        .bind(experiment)
+        .bind(variant)
+        .bind(event)
+        .execute(pool)
         .await
-        .context("Failed to list ingredients by user")?;
+        .context("Failed to record review funnel event")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "record_review_funnel_event",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    let ingredients: Vec<Ingredient> = rows
-        .into_iter()
-        .map(|row| Ingredient {
-            id: row.get(0),
-            user_id: row.get(1),
-            recipe_id: row.get(2),
-            name: row.get(3),
-            quantity: row.get(4),
-            unit: row.get(5),
-            created_at: row.get(6),
-            updated_at: row.get(7),
-        })
-        .collect();
+        debug!(telegram_id = %telegram_id, experiment = %experiment, variant = %variant, event = %event, "Review funnel event recorded");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
 
-    info!(
-        "Found {} ingredients for user_id: {user_id}",
-        ingredients.len()
-    );
-    Ok(ingredients)
+/// Review funnel counts for one experiment variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantFunnelStats {
+    pub variant: String,
+    pub reviews_shown: i64,
+    pub edits_made: i64,
+    pub confirmed: i64,
 }
 
-/// Get all ingredients for a specific recipe
-pub async fn get_recipe_ingredients(pool: &PgPool, recipe_id: i64) -> Result<Vec<Ingredient>> {
-    info!("Getting ingredients for recipe_id: {recipe_id}");
+impl VariantFunnelStats {
+    /// Share of shown reviews that were confirmed, between 0 and 1
+    pub fn confirm_rate(&self) -> f64 {
+        if self.reviews_shown == 0 {
+            0.0
+        } else {
+            self.confirmed as f64 / self.reviews_shown as f64
+        }
+    }
 
-    let rows = sqlx::query("SELECT id, user_id, recipe_id, name, quantity::float8, unit, created_at, updated_at FROM ingredients WHERE recipe_id = $1 ORDER BY created_at ASC")
-        .bind(recipe_id)
+    /// Average number of edits per shown review
+    pub fn edits_per_review(&self) -> f64 {
+        if self.reviews_shown == 0 {
+            0.0
+        } else {
+            self.edits_made as f64 / self.reviews_shown as f64
+        }
+    }
+}
+
+/// Get review funnel counts per variant for an experiment over the last `days` days
+pub async fn get_review_funnel_report(
+    pool: &PgPool,
+    experiment: &str,
+    days: i32,
+) -> Result<Vec<VariantFunnelStats>> {
+    let span = crate::observability::db_span("get_review_funnel_report", "review_funnel_events");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let rows = sqlx::query(
+            "SELECT variant, \
+         COUNT(*) FILTER (WHERE event = 'review_shown'), \
+         COUNT(*) FILTER (WHERE event = 'edits_made'), \
+         COUNT(*) FILTER (WHERE event = 'confirmed') \
+         FROM review_funnel_events \
+         WHERE experiment = $1 AND created_at >= NOW() - make_interval(days => $2) \
+         GROUP BY variant ORDER BY variant",
+        )
+        .bind(experiment)
+        .bind(days)
         .fetch_all(pool)
         .await
-        .context("Failed to get recipe ingredients")?;
+        .context("Failed to get review funnel report")?;
+
+        let report: Vec<VariantFunnelStats> = rows
+            .into_iter()
+            .map(|row| VariantFunnelStats {
+                variant: row.get(0),
+                reviews_shown: row.get(1),
+                edits_made: row.get(2),
+                confirmed: row.get(3),
+            })
+            .collect();
 
-    let ingredients: Vec<Ingredient> = rows
-        .into_iter()
-        .map(|row| Ingredient {
-            id: row.get(0),
-            user_id: row.get(1),
-            recipe_id: row.get(2),
-            name: row.get(3),
-            quantity: row.get(4),
-            unit: row.get(5),
-            created_at: row.get(6),
-            updated_at: row.get(7),
-        })
-        .collect();
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_review_funnel_report",
+            duration,
+            report.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
 
-    info!(
-        "Found {} ingredients for recipe_id: {recipe_id}",
-        ingredients.len()
-    );
-    Ok(ingredients)
+        debug!(experiment = %experiment, days = %days, variants = report.len(), "Review funnel report retrieved");
+        Ok(report)
+    }
+    .instrument(span)
+    .await
 }
 
-/// Bulk update ingredients for a recipe (add/update/delete)
+/// Record how much of one confirmed review the user had to correct
 ///
-/// This function handles the complex task of synchronizing edited ingredients
-/// with the database, performing the minimal set of operations needed.
-pub async fn update_recipe_ingredients(
+/// Only counts are stored, never the recipe text, so the table carries no user data.
+pub async fn record_extraction_feedback(
     pool: &PgPool,
-    recipe_id: i64,
-    ingredients: &[crate::text_processing::MeasurementMatch],
+    language_code: &str,
+    extracted: i32,
+    edited: i32,
+    deleted: i32,
 ) -> Result<()> {
-    let span = crate::observability::db_span("update_recipe_ingredients", "ingredients");
-    let _enter = span.enter();
+    let span = crate::observability::db_span("record_extraction_feedback", "extraction_feedback");
+    async move {
+        let start_time = std::time::Instant::now();
 
-    let start_time = std::time::Instant::now();
-    info!(
-        "Bulk updating {} ingredients for recipe {}",
-        ingredients.len(),
-        recipe_id
-    );
-
-    // Get existing ingredients for this recipe
-    let existing_ingredients = get_recipe_ingredients(pool, recipe_id).await?;
-
-    // Use the change detection logic from ingredient_editing module
-    let changes =
-        crate::ingredient_editing::detect_ingredient_changes(&existing_ingredients, ingredients);
-
-    // Execute changes in transaction
-    let mut tx = pool.begin().await.context("Failed to start transaction")?;
+        sqlx::query(
+            "INSERT INTO extraction_feedback (language_code, extracted_count, edited_count, deleted_count) \
+         VALUES ($1, $2, $3, $4)",
+        )
+        .bind(language_code)
+        .bind(extracted)
+        .bind(edited)
+        .bind(deleted)
+        .execute(pool)
+        .await
+        .context("Failed to record extraction feedback")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "record_extraction_feedback",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    // Delete ingredients that are no longer present
-    for &ingredient_id in &changes.to_delete {
-        sqlx::query("DELETE FROM ingredients WHERE id = $1")
-            .bind(ingredient_id)
-            .execute(&mut *tx)
-            .await
-            .context(format!("Failed to delete ingredient {}", ingredient_id))?;
-        info!("Deleted ingredient ID {}", ingredient_id);
+        debug!(language_code = %language_code, extracted, edited, deleted, "Extraction feedback recorded");
+        Ok(())
     }
+    .instrument(span)
+    .await
+}
 
-    // Update existing ingredients
-    for (ingredient_id, new_match) in &changes.to_update {
-        let quantity = new_match.quantity.parse::<f64>().ok();
-        let unit = new_match.measurement.as_deref();
+/// Correction counts of the reviews confirmed in one language
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageExtractionAccuracy {
+    pub language_code: String,
+    pub reviews: i64,
+    pub extracted: i64,
+    pub edited: i64,
+    pub deleted: i64,
+}
 
-        sqlx::query("UPDATE ingredients SET name = $1, quantity = $2, unit = $3, updated_at = CURRENT_TIMESTAMP WHERE id = $4")
-            .bind(&new_match.ingredient_name)
-            .bind(quantity)
-            .bind(unit)
-            .bind(ingredient_id)
-            .execute(&mut *tx)
-            .await
-            .context(format!("Failed to update ingredient {}", ingredient_id))?;
-        info!("Updated ingredient ID {}", ingredient_id);
+impl LanguageExtractionAccuracy {
+    /// Share of extracted ingredients that were edited or deleted, between 0 and 1
+    pub fn correction_ratio(&self) -> f64 {
+        if self.extracted == 0 {
+            0.0
+        } else {
+            ((self.edited + self.deleted) as f64 / self.extracted as f64).min(1.0)
+        }
     }
+}
 
-    // Add new ingredients
-    let recipe = read_recipe_with_name(pool, recipe_id)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("Recipe not found during update"))?;
+/// Get the extraction feedback per language over the last `days` days
+pub async fn get_extraction_accuracy_by_language(
+    pool: &PgPool,
+    days: i32,
+) -> Result<Vec<LanguageExtractionAccuracy>> {
+    let span =
+        crate::observability::db_span("get_extraction_accuracy_by_language", "extraction_feedback");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let rows = sqlx::query(
+            "SELECT language_code, COUNT(*), \
+         COALESCE(SUM(extracted_count), 0)::BIGINT, \
+         COALESCE(SUM(edited_count), 0)::BIGINT, \
+         COALESCE(SUM(deleted_count), 0)::BIGINT \
+         FROM extraction_feedback \
+         WHERE created_at >= NOW() - make_interval(days => $1) \
+         GROUP BY language_code ORDER BY language_code",
+        )
+        .bind(days)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get extraction accuracy by language")?;
+
+        let report: Vec<LanguageExtractionAccuracy> = rows
+            .into_iter()
+            .map(|row| LanguageExtractionAccuracy {
+                language_code: row.get(0),
+                reviews: row.get(1),
+                extracted: row.get(2),
+                edited: row.get(3),
+                deleted: row.get(4),
+            })
+            .collect();
 
-    for new_match in &changes.to_add {
-        let quantity = new_match.quantity.parse::<f64>().ok();
-        let unit = new_match.measurement.as_deref();
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_extraction_accuracy_by_language",
+            duration,
+            report.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
 
-        sqlx::query("INSERT INTO ingredients (user_id, recipe_id, name, quantity, unit) VALUES ($1, $2, $3, $4, $5)")
-            .bind(recipe.telegram_id)
-            .bind(recipe_id)
-            .bind(&new_match.ingredient_name)
-            .bind(quantity)
-            .bind(unit)
-            .execute(&mut *tx)
-            .await
-            .context(format!("Failed to add new ingredient '{}'", new_match.ingredient_name))?;
-        info!("Added new ingredient '{}'", new_match.ingredient_name);
+        debug!(days = %days, languages = report.len(), "Extraction accuracy by language retrieved");
+        Ok(report)
     }
+    .instrument(span)
+    .await
+}
 
-    // Commit transaction
-    tx.commit()
+/// Reviews shown and bad-extraction reports filed in `[from, to)`
+pub async fn get_extraction_feedback_counts(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<(i64, i64)> {
+    let span =
+        crate::observability::db_span("get_extraction_feedback_counts", "review_funnel_events");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let row = sqlx::query(
+            "SELECT \
+         (SELECT COUNT(*) FROM review_funnel_events \
+          WHERE event = 'review_shown' AND created_at >= $1 AND created_at < $2), \
+         (SELECT COUNT(*) FROM extraction_reports WHERE created_at >= $1 AND created_at < $2)",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
         .await
-        .context("Failed to commit ingredient updates")?;
+        .context("Failed to get extraction feedback counts")?;
 
-    let duration = start_time.elapsed();
-    observability::record_db_performance_metrics(
-        "update_recipe_ingredients",
-        duration,
-        ingredients.len() as u64,
-        crate::observability::QueryComplexity::Complex,
-    );
+        let counts: (i64, i64) = (row.get(0), row.get(1));
 
-    info!(
-        "Successfully updated ingredients for recipe {}: {} deleted, {} updated, {} added",
-        recipe_id,
-        changes.to_delete.len(),
-        changes.to_update.len(),
-        changes.to_add.len()
-    );
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_extraction_feedback_counts",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Medium,
+        );
 
-    Ok(())
+        debug!(
+            reviews_shown = counts.0,
+            reports = counts.1,
+            "Extraction feedback counts retrieved"
+        );
+        Ok(counts)
+    }
+    .instrument(span)
+    .await
 }
 
-/// Update the recipe name for a recipe
-pub async fn update_recipe_name(pool: &PgPool, recipe_id: i64, recipe_name: &str) -> Result<bool> {
-    debug!(recipe_id = %recipe_id, "Updating recipe recipe name");
-
-    let result = sqlx::query("UPDATE recipes SET recipe_name = $1 WHERE id = $2")
-        .bind(recipe_name)
-        .bind(recipe_id)
-        .execute(pool)
+/// Median number of edits made in reviews shown and confirmed in `[from, to)`
+///
+/// Funnel events carry no recipe id, so a review spans a user's events from one
+/// `review_shown` to the next. Returns `None` when nothing was confirmed.
+pub async fn get_median_edits_per_confirmed_review(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Option<f64>> {
+    let span = crate::observability::db_span(
+        "get_median_edits_per_confirmed_review",
+        "review_funnel_events",
+    );
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let median: Option<f64> = sqlx::query_scalar(
+            "WITH numbered AS ( \
+             SELECT telegram_id, event, \
+             COUNT(*) FILTER (WHERE event = 'review_shown') \
+                 OVER (PARTITION BY telegram_id ORDER BY created_at, id) AS review_number \
+             FROM review_funnel_events WHERE created_at >= $1 AND created_at < $2 \
+         ), reviews AS ( \
+             SELECT COUNT(*) FILTER (WHERE event = 'edits_made') AS edits, \
+             BOOL_OR(event = 'confirmed') AS confirmed \
+             FROM numbered WHERE review_number > 0 GROUP BY telegram_id, review_number \
+         ) \
+         SELECT percentile_cont(0.5) WITHIN GROUP (ORDER BY edits)::FLOAT8 \
+         FROM reviews WHERE confirmed",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_one(pool)
         .await
-        .context("Failed to update recipe recipe name")?;
+        .context("Failed to get median edits per confirmed review")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_median_edits_per_confirmed_review",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Complex,
+        );
 
-    let rows_affected = result.rows_affected();
-    if rows_affected > 0 {
-        debug!(recipe_id = %recipe_id, "Recipe recipe name updated successfully");
-        Ok(true)
-    } else {
-        info!("No recipe found with ID: {recipe_id}");
-        Ok(false)
+        debug!(median = ?median, "Median edits per confirmed review retrieved");
+        Ok(median)
     }
+    .instrument(span)
+    .await
 }
 
-/// Get recipe with recipe name
-pub async fn read_recipe_with_name(pool: &PgPool, recipe_id: i64) -> Result<Option<Recipe>> {
-    debug!(recipe_id = %recipe_id, "Reading recipe with recipe name");
+/// Most frequent words saved in place of a unit in `[from, to)`
+///
+/// Ingredients with a quantity but no recognized unit keep the would-be unit as the
+/// first word of their name ("2 sachets levure"); those words are unit candidates.
+pub async fn get_unmatched_unit_candidates(
+    pool: &PgPool,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    limit: i64,
+) -> Result<Vec<(String, i64)>> {
+    let span = crate::observability::db_span("get_unmatched_unit_candidates", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let rows = sqlx::query(
+            "SELECT candidate, COUNT(*) AS occurrences FROM ( \
+             SELECT split_part(lower(trim(name)), ' ', 1) AS candidate FROM ingredients \
+             WHERE quantity IS NOT NULL AND unit IS NULL AND name LIKE '% %' \
+             AND created_at >= $1 AND created_at < $2 \
+         ) words WHERE candidate ~ '^[[:alpha:]]{2,}\\.?$' \
+         GROUP BY candidate ORDER BY occurrences DESC, candidate LIMIT $3",
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get unmatched unit candidates")?;
 
-    let row = sqlx::query(
-        "SELECT id, telegram_id, content, recipe_name, created_at FROM recipes WHERE id = $1",
-    )
-    .bind(recipe_id)
-    .fetch_optional(pool)
-    .await
-    .context("Failed to read recipe with recipe")?;
+        let candidates: Vec<(String, i64)> = rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
 
-    match row {
-        Some(row) => {
-            let recipe = Recipe {
-                id: row.get(0),
-                telegram_id: row.get(1),
-                content: row.get(2),
-                recipe_name: row.get(3),
-                created_at: row.get(4),
-            };
-            debug!(recipe_id = %recipe_id, "Recipe with recipe found");
-            Ok(Some(recipe))
-        }
-        None => {
-            debug!(recipe_id = %recipe_id, "No recipe found");
-            Ok(None)
-        }
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "get_unmatched_unit_candidates",
+            duration,
+            candidates.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+
+        debug!(
+            candidates = candidates.len(),
+            "Unmatched unit candidates retrieved"
+        );
+        Ok(candidates)
     }
+    .instrument(span)
+    .await
 }
 
-/// Search recipes using full-text search
-pub async fn search_recipes(pool: &PgPool, telegram_id: i64, query: &str) -> Result<Vec<Recipe>> {
-    info!("Searching recipes for telegram_id: {telegram_id} with query: {query}");
+/// Progress of a resumable document import
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportJob {
+    pub id: i64,
+    pub telegram_id: TelegramId,
+    pub file_unique_id: String,
+    pub rows_total: i64,
+    pub rows_processed: i64,
+    pub status: String,
+}
+
+const IMPORT_JOB_COLUMNS: &str =
+    "id, telegram_id, file_unique_id, rows_total, rows_processed, status";
+
+fn import_job_from_row(row: &sqlx::postgres::PgRow) -> ImportJob {
+    ImportJob {
+        id: row.get(0),
+        telegram_id: row.get(1),
+        file_unique_id: row.get(2),
+        rows_total: row.get(3),
+        rows_processed: row.get(4),
+        status: row.get(5),
+    }
+}
 
-    let rows = sqlx::query("SELECT id, telegram_id, content, recipe_name, created_at FROM recipes WHERE telegram_id = $1 AND content_tsv @@ plainto_tsquery('english', $2) ORDER BY created_at DESC")
+/// Find an unfinished import of the same file for a user
+pub async fn find_incomplete_import_job(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    file_unique_id: &str,
+) -> Result<Option<ImportJob>> {
+    let span = crate::observability::db_span("find_incomplete_import_job", "import_jobs");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let row = sqlx::query(&format!(
+            "SELECT {IMPORT_JOB_COLUMNS} FROM import_jobs \
+         WHERE telegram_id = $1 AND file_unique_id = $2 AND status = 'in_progress'"
+        ))
         .bind(telegram_id)
-        .bind(query)
-        .fetch_all(pool)
+        .bind(file_unique_id)
+        .fetch_optional(pool)
         .await
-        .context("Failed to search recipes")?;
-
-    let recipes: Vec<Recipe> = rows
-        .into_iter()
-        .map(|row| Recipe {
-            id: row.get(0),
-            telegram_id: row.get(1),
-            content: row.get(2),
-            recipe_name: row.get(3),
-            created_at: row.get(4),
-        })
-        .collect();
+        .context("Failed to look up import job")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "find_incomplete_import_job",
+            duration,
+            row.is_some() as u64,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    info!(telegram_id = %telegram_id, query = %query, result_count = recipes.len(), "Recipe search completed");
-    Ok(recipes)
+        Ok(row.as_ref().map(import_job_from_row))
+    }
+    .instrument(span)
+    .await
 }
 
-/// Get all recipes with a specific name for a user
-pub async fn get_recipes_by_name(
+/// Start (or restart from row 0) the import job for a file
+///
+/// Row hashes claimed by an earlier run are kept, so restarting never duplicates
+/// rows that were already committed.
+pub async fn start_import_job(
     pool: &PgPool,
-    telegram_id: i64,
-    recipe_name: &str,
-) -> Result<Vec<Recipe>> {
-    let span = crate::observability::db_span("get_recipes_by_name", "recipes");
-    let _enter = span.enter();
+    telegram_id: TelegramId,
+    file_unique_id: &str,
+    rows_total: i64,
+) -> Result<ImportJob> {
+    let span = crate::observability::db_span("start_import_job", "import_jobs");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO import_jobs (telegram_id, file_unique_id, rows_total) VALUES ($1, $2, $3) \
+         ON CONFLICT (telegram_id, file_unique_id) DO UPDATE \
+         SET rows_total = EXCLUDED.rows_total, rows_processed = 0, status = 'in_progress', \
+             updated_at = CURRENT_TIMESTAMP \
+         RETURNING {IMPORT_JOB_COLUMNS}"
+        ))
+        .bind(telegram_id)
+        .bind(file_unique_id)
+        .bind(rows_total)
+        .fetch_one(pool)
+        .await
+        .context("Failed to start import job")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "start_import_job",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    let start_time = std::time::Instant::now();
-    debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, "Getting recipes by name");
+        let job = import_job_from_row(&row);
+        info!(job_id = job.id, telegram_id = %telegram_id, rows_total, "Import job started");
+        Ok(job)
+    }
+    .instrument(span)
+    .await
+}
 
+/// Claim row content hashes for an import job, returning only the hashes not seen before
+///
+/// Runs on the batch's transaction so claims roll back together with the batch.
+pub async fn claim_import_rows(
+    conn: &mut sqlx::PgConnection,
+    job_id: i64,
+    row_hashes: &[String],
+) -> Result<HashSet<String>> {
     let rows = sqlx::query(
-        "SELECT id, telegram_id, content, recipe_name, created_at FROM recipes WHERE telegram_id = $1 AND recipe_name = $2 ORDER BY created_at DESC"
+        "INSERT INTO import_job_rows (job_id, row_hash) SELECT $1, UNNEST($2::TEXT[]) \
+         ON CONFLICT DO NOTHING RETURNING row_hash",
     )
-    .bind(telegram_id)
-    .bind(recipe_name)
-    .fetch_all(pool)
+    .bind(job_id)
+    .bind(row_hashes)
+    .fetch_all(conn)
     .await
-    .context("Failed to get recipes by name")?;
+    .context("Failed to claim import rows")?;
 
-    let recipes: Vec<Recipe> = rows
-        .into_iter()
-        .map(|row| Recipe {
-            id: row.get(0),
-            telegram_id: row.get(1),
-            content: row.get(2),
-            recipe_name: row.get(3),
-            created_at: row.get(4),
-        })
-        .collect();
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Record how many rows of an import job are committed
+pub async fn advance_import_job(
+    conn: &mut sqlx::PgConnection,
+    job_id: i64,
+    rows_processed: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE import_jobs SET rows_processed = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(rows_processed)
+    .execute(conn)
+    .await
+    .context("Failed to record import progress")?;
+    Ok(())
+}
+
+/// Mark an import job as completed or cancelled
+pub async fn finish_import_job(pool: &PgPool, job_id: i64, status: &str) -> Result<()> {
+    let span = crate::observability::db_span("finish_import_job", "import_jobs");
+    async move {
+        sqlx::query(
+            "UPDATE import_jobs SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
+        )
+        .bind(job_id)
+        .bind(status)
+        .execute(pool)
+        .await
+        .context("Failed to finish import job")?;
+
+        info!(job_id, status = %status, "Import job finished");
+        Ok(())
+    }
+    .instrument(span)
+    .await
+}
+
+/// A user's report about a bad extraction
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractionReport {
+    pub id: i64,
+    pub telegram_id: TelegramId,
+    pub correlation_id: String,
+    pub extracted_text_hash: String,
+    pub match_count: i32,
+    pub preprocessing_variant: Option<String>,
+    pub comment: Option<String>,
+    pub photo_file_id: Option<String>,
+    pub photo_consent: String,
+}
+
+const EXTRACTION_REPORT_COLUMNS: &str = "id, telegram_id, correlation_id, extracted_text_hash, \
+     match_count, preprocessing_variant, comment, photo_file_id, photo_consent";
+
+fn extraction_report_from_row(row: &sqlx::postgres::PgRow) -> ExtractionReport {
+    ExtractionReport {
+        id: row.get(0),
+        telegram_id: row.get(1),
+        correlation_id: row.get(2),
+        extracted_text_hash: row.get(3),
+        match_count: row.get(4),
+        preprocessing_variant: row.get(5),
+        comment: row.get(6),
+        photo_file_id: row.get(7),
+        photo_consent: row.get(8),
+    }
+}
 
-    let duration = start_time.elapsed();
-    observability::record_db_performance_metrics(
-        "get_recipes_by_name",
-        duration,
-        recipes.len() as u64,
-        crate::observability::QueryComplexity::Simple,
-    );
+/// Store a user's report about a bad extraction
+pub async fn create_extraction_report(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    context: &crate::extraction_reports::ExtractionContext,
+    comment: Option<&str>,
+) -> Result<ExtractionReport> {
+    let span = crate::observability::db_span("create_extraction_report", "extraction_reports");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO extraction_reports \
+         (telegram_id, correlation_id, extracted_text_hash, match_count, preprocessing_variant, \
+          comment, photo_file_id) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING {EXTRACTION_REPORT_COLUMNS}"
+        ))
+        .bind(telegram_id)
+        .bind(&context.correlation_id)
+        .bind(&context.extracted_text_hash)
+        .bind(i32::try_from(context.match_count).unwrap_or(i32::MAX))
+        .bind(&context.preprocessing_variant)
+        .bind(comment)
+        .bind(&context.photo_file_id)
+        .fetch_one(pool)
+        .await
+        .context("Failed to create extraction report")?;
+
+        let duration = start_time.elapsed();
+        observability::record_db_performance_metrics(
+            "create_extraction_report",
+            duration,
+            1,
+            crate::observability::QueryComplexity::Simple,
+        );
 
-    debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, count = recipes.len(), duration_ms = %duration.as_millis(), "Recipes by name retrieved successfully");
-    Ok(recipes)
+        let report = extraction_report_from_row(&row);
+        info!(report_id = report.id, telegram_id = %telegram_id, correlation_id = %report.correlation_id, "Extraction report created");
+        Ok(report)
+    }
+    .instrument(span)
+    .await
 }
 
-/// Check if a recipe name has duplicates for a user
-pub async fn has_duplicate_recipes(
+/// Get an extraction report by id
+pub async fn get_extraction_report(
     pool: &PgPool,
-    telegram_id: i64,
-    recipe_name: &str,
-) -> Result<bool> {
-    let span = crate::observability::db_span("has_duplicate_recipes", "recipes");
-    let _enter = span.enter();
+    report_id: i64,
+) -> Result<Option<ExtractionReport>> {
+    let span = crate::observability::db_span("get_extraction_report", "extraction_reports");
+    async move {
+        let row = sqlx::query(&format!(
+            "SELECT {EXTRACTION_REPORT_COLUMNS} FROM extraction_reports WHERE id = $1"
+        ))
+        .bind(report_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get extraction report")?;
 
-    debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, "Checking for duplicate recipes");
+        Ok(row.as_ref().map(extraction_report_from_row))
+    }
+    .instrument(span)
+    .await
+}
 
-    let row =
-        sqlx::query("SELECT COUNT(*) FROM recipes WHERE telegram_id = $1 AND recipe_name = $2")
-            .bind(telegram_id)
-            .bind(recipe_name)
-            .fetch_one(pool)
-            .await
-            .context("Failed to check for duplicate recipes")?;
+/// Move a report's photo consent from `from` to `to`
+///
+/// Returns `false` when the consent was no longer `from`, so concurrent or replayed
+/// button presses cannot skip a step of the consent flow.
+pub async fn transition_report_consent(
+    pool: &PgPool,
+    report_id: i64,
+    from: &str,
+    to: &str,
+) -> Result<bool> {
+    let span = crate::observability::db_span("transition_report_consent", "extraction_reports");
+    async move {
+        let result = sqlx::query(
+            "UPDATE extraction_reports SET photo_consent = $3 WHERE id = $1 AND photo_consent = $2",
+        )
+        .bind(report_id)
+        .bind(from)
+        .bind(to)
+        .execute(pool)
+        .await
+        .context("Failed to update extraction report consent")?;
 
-    let count: i64 = row.get(0);
-    let has_duplicates = count > 1;
+        let updated = result.rows_affected() == 1;
+        debug!(report_id, from = %from, to = %to, updated, "Extraction report consent transition");
+        Ok(updated)
+    }
+    .instrument(span)
+    .await
+}
 
-    debug!(telegram_id = %telegram_id, recipe_name = %recipe_name, count = %count, has_duplicates = %has_duplicates, "Duplicate check completed");
-    Ok(has_duplicates)
+/// Kind of entry shown in a user's recent activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeActivityKind {
+    /// A recipe was saved from an extraction
+    Saved,
+    /// A saved recipe was renamed
+    Renamed,
+    /// The ingredients of a saved recipe were edited
+    Edited,
 }
 
-/// Get paginated list of recipe names for a user
-pub async fn get_user_recipes_paginated(
-    pool: &PgPool,
-    telegram_id: i64,
-    limit: i64,
-    offset: i64,
-) -> Result<(Vec<String>, i64)> {
-    // Validate pagination parameters to prevent DoS attacks
-    if !(1..=100).contains(&limit) {
-        return Err(anyhow::anyhow!(
-            "Invalid pagination limit: {} (must be between 1 and 100)",
-            limit
-        ));
+impl RecipeActivityKind {
+    /// Value stored in the `activity` column
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Saved => "saved",
+            Self::Renamed => "renamed",
+            Self::Edited => "edited",
+        }
     }
-    if !(0..=10000).contains(&offset) {
-        return Err(anyhow::anyhow!(
-            "Invalid pagination offset: {} (must be between 0 and 10000)",
-            offset
-        ));
+
+    /// Parse a stored `activity` value
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "saved" => Some(Self::Saved),
+            "renamed" => Some(Self::Renamed),
+            "edited" => Some(Self::Edited),
+            _ => None,
+        }
     }
+}
 
-    debug!(telegram_id = %telegram_id, limit = %limit, offset = %offset, "Getting paginated recipes for user");
+/// One entry of a user's recent activity
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeActivity {
+    pub recipe_id: RecipeId,
+    pub kind: RecipeActivityKind,
+    /// Recipe name at the time of the activity (current name for saves)
+    pub recipe_name: Option<String>,
+    /// Name before a rename
+    pub previous_name: Option<String>,
+    /// Ingredients after the activity, when known
+    pub ingredient_count: Option<i64>,
+    pub occurred_at: DateTime<Utc>,
+}
 
-    // Get total count of distinct recipe names
-    let total_row = sqlx::query(
-        "SELECT COUNT(DISTINCT recipe_name) FROM recipes WHERE telegram_id = $1 AND recipe_name IS NOT NULL"
-    )
-    .bind(telegram_id)
-    .fetch_one(pool)
-    .await
-    .context("Failed to get total recipe count")?;
-    let total: i64 = total_row.get(0);
+/// Record a rename or ingredient edit for the recent activity view
+///
+/// Saves are not recorded here: they are read straight from `recipes`.
+pub async fn record_recipe_activity(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    recipe_id: RecipeId,
+    kind: RecipeActivityKind,
+    recipe_name: Option<&str>,
+    previous_name: Option<&str>,
+    ingredient_count: Option<usize>,
+) -> Result<()> {
+    let span = crate::observability::db_span("record_recipe_activity", "recipe_activity");
+    async move {
+        sqlx::query(
+            "INSERT INTO recipe_activity (telegram_id, recipe_id, activity, recipe_name, previous_name, ingredient_count) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(telegram_id.0)
+        .bind(recipe_id.0)
+        .bind(kind.as_str())
+        .bind(recipe_name)
+        .bind(previous_name)
+        .bind(ingredient_count.map(|count| count as i32))
+        .execute(pool)
+        .await
+        .context("Failed to record recipe activity")?;
 
-    // Get paginated recipe names
-    let rows = sqlx::query(
-        "SELECT DISTINCT recipe_name FROM recipes WHERE telegram_id = $1 AND recipe_name IS NOT NULL ORDER BY recipe_name LIMIT $2 OFFSET $3"
-    )
-    .bind(telegram_id)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(pool)
+        debug!(telegram_id = %telegram_id, recipe_id = %recipe_id, activity = kind.as_str(), "Recipe activity recorded");
+        Ok(())
+    }
+    .instrument(span)
     .await
-    .context("Failed to get paginated recipes")?;
+}
 
-    let recipe_names: Vec<String> = rows.into_iter().map(|row| row.get(0)).collect();
+/// Get a page of a user's recent activity, newest first, with the total entry count
+///
+/// Unions recipe creations with the recorded renames and ingredient edits.
+pub async fn get_recent_activity(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<RecipeActivity>, i64)> {
+    let span = crate::observability::db_span("get_recent_activity", "recipe_activity");
+    async move {
+        let start_time = std::time::Instant::now();
+
+        let rows = sqlx::query(
+            "SELECT recipe_id, activity, recipe_name, previous_name, ingredient_count, occurred_at, seq FROM ( \
+             SELECT r.id AS recipe_id, 'saved' AS activity, r.recipe_name, NULL::VARCHAR AS previous_name, \
+                    (SELECT COUNT(*) FROM ingredients i WHERE i.recipe_id = r.id) AS ingredient_count, \
+                    r.created_at AS occurred_at, 0::BIGINT AS seq \
+             FROM recipes r WHERE r.telegram_id = $1 AND r.deleted_at IS NULL \
+             UNION ALL \
+             SELECT a.recipe_id, a.activity, a.recipe_name, a.previous_name, \
+                    a.ingredient_count::BIGINT, a.created_at, a.id \
+             FROM recipe_activity a WHERE a.telegram_id = $1 \
+             AND a.recipe_id NOT IN (SELECT id FROM recipes WHERE deleted_at IS NOT NULL) \
+         ) activity \
+         ORDER BY occurred_at DESC, seq DESC, recipe_id DESC \
+         LIMIT $2 OFFSET $3",
+        )
+        .bind(telegram_id.0)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recent activity")?;
 
-    debug!(total = %total, count = %recipe_names.len(), "Retrieved paginated recipes");
-    Ok((recipe_names, total))
-}
+        let total_count: i64 = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM recipes WHERE telegram_id = $1 AND deleted_at IS NULL) \
+              + (SELECT COUNT(*) FROM recipe_activity WHERE telegram_id = $1 \
+                 AND recipe_id NOT IN (SELECT id FROM recipes WHERE deleted_at IS NOT NULL))",
+        )
+        .bind(telegram_id.0)
+        .fetch_one(pool)
+        .await
+        .context("Failed to count recent activity")?;
 
-/// Recipe statistics data structure
-#[derive(Debug)]
-pub struct RecipeStatistics {
-    pub total_recipes: i64,
-    pub total_ingredients: i64,
-    pub average_ingredients_per_recipe: f64,
-    pub oldest_recipe_date: Option<chrono::DateTime<chrono::Utc>>,
-    pub newest_recipe_date: Option<chrono::DateTime<chrono::Utc>>,
-    pub most_common_units: Vec<(String, i64)>,
-    pub recipes_created_today: i64,
-    pub recipes_created_this_week: i64,
-    pub recipes_created_this_month: i64,
+        let activities = rows
+            .iter()
+            .filter_map(|row| {
+                let kind = RecipeActivityKind::from_name(row.get::<String, _>(1).as_str())?;
+                Some(RecipeActivity {
+                    recipe_id: RecipeId(row.get(0)),
+                    kind,
+                    recipe_name: row.get(2),
+                    previous_name: row.get(3),
+                    ingredient_count: row.get(4),
+                    occurred_at: row.get(5),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        observability::record_db_performance_metrics(
+            "get_recent_activity",
+            start_time.elapsed(),
+            activities.len() as u64,
+            crate::observability::QueryComplexity::Complex,
+        );
+
+        Ok((activities, total_count))
+    }
+    .instrument(span)
+    .await
 }
 
 /// Get comprehensive recipe statistics for a user
+///
+/// Today, this week and this month are counted from midnight in the user's time
+/// zone `timezone` (see [`crate::timezone::StatisticsWindows`]).
 pub async fn get_user_recipe_statistics(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
+    timezone: FixedOffset,
 ) -> Result<RecipeStatistics> {
     debug!(telegram_id = %telegram_id, "Getting recipe statistics for user");
 
@@ -945,7 +4794,7 @@ pub async fn get_user_recipe_statistics(
             FROM ingredients
             GROUP BY recipe_id
         ) ic ON r.id = ic.recipe_id
-        WHERE r.telegram_id = $1
+        WHERE r.telegram_id = $1 AND r.deleted_at IS NULL
         "#,
     )
     .bind(telegram_id)
@@ -959,7 +4808,7 @@ pub async fn get_user_recipe_statistics(
 
     // Get date ranges
     let date_stats =
-        sqlx::query("SELECT MIN(created_at), MAX(created_at) FROM recipes WHERE telegram_id = $1")
+        sqlx::query("SELECT MIN(created_at), MAX(created_at) FROM recipes WHERE telegram_id = $1 AND deleted_at IS NULL")
             .bind(telegram_id)
             .fetch_one(pool)
             .await
@@ -974,7 +4823,7 @@ pub async fn get_user_recipe_statistics(
         SELECT COALESCE(i.unit, 'no unit') as unit_name, COUNT(*) as count
         FROM ingredients i
         JOIN recipes r ON i.recipe_id = r.id
-        WHERE r.telegram_id = $1 AND i.unit IS NOT NULL AND i.unit != ''
+        WHERE r.telegram_id = $1 AND r.deleted_at IS NULL AND i.unit IS NOT NULL AND i.unit != ''
         GROUP BY i.unit
         ORDER BY count DESC
         LIMIT 5
@@ -990,15 +4839,8 @@ pub async fn get_user_recipe_statistics(
         .map(|row| (row.get(0), row.get(1)))
         .collect();
 
-    // Get creation statistics
-    let now = chrono::Utc::now();
-    let today_start = now
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .ok_or_else(|| anyhow::anyhow!("Failed to create start of day datetime"))?
-        .and_utc();
-    let week_start = now - chrono::Duration::days(7);
-    let month_start = now - chrono::Duration::days(30);
+    // Get creation statistics, with day boundaries in the user's time zone
+    let windows = crate::timezone::StatisticsWindows::new(chrono::Utc::now(), timezone);
 
     let creation_stats = sqlx::query(
         r#"
@@ -1007,13 +4849,13 @@ pub async fn get_user_recipe_statistics(
             COUNT(CASE WHEN created_at >= $3 THEN 1 END) as week,
             COUNT(CASE WHEN created_at >= $4 THEN 1 END) as month
         FROM recipes
-        WHERE telegram_id = $1
+        WHERE telegram_id = $1 AND deleted_at IS NULL
         "#,
     )
     .bind(telegram_id)
-    .bind(today_start)
-    .bind(week_start)
-    .bind(month_start)
+    .bind(windows.today)
+    .bind(windows.week)
+    .bind(windows.month)
     .fetch_one(pool)
     .await
     .context("Failed to get recipe creation statistics")?;
@@ -1038,12 +4880,148 @@ pub async fn get_user_recipe_statistics(
     Ok(stats)
 }
 
+/// Get the ingredients used in the most recipes of a user, by normalized name
+///
+/// Returns up to `limit` names with the number of recipes using each, most used first.
+pub async fn get_top_ingredients_by_user(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    limit: i64,
+) -> Result<Vec<(String, i64)>> {
+    let span = crate::observability::db_span("get_top_ingredients_by_user", "ingredients");
+    async move {
+        let start_time = std::time::Instant::now();
+        let rows = sqlx::query(
+            r#"
+        SELECT COALESCE(NULLIF(i.normalized_name, ''), LOWER(i.name)) AS ingredient,
+               COUNT(DISTINCT i.recipe_id) AS recipe_count
+        FROM ingredients i
+        JOIN recipes r ON r.id = i.recipe_id
+        WHERE r.telegram_id = $1 AND r.deleted_at IS NULL
+        GROUP BY ingredient
+        ORDER BY recipe_count DESC, ingredient
+        LIMIT $2
+        "#,
+        )
+        .bind(telegram_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .context("Failed to get top ingredients")?;
+
+        let ingredients: Vec<(String, i64)> = rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+
+        observability::record_db_performance_metrics(
+            "get_top_ingredients_by_user",
+            start_time.elapsed(),
+            ingredients.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+        debug!(telegram_id = %telegram_id, count = ingredients.len(), "Retrieved top ingredients");
+        Ok(ingredients)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Count the recipes a user saved in each of the last `months` months, oldest first
+///
+/// Months are UTC calendar months, the current one included; months without recipes count 0.
+pub async fn get_recipe_counts_by_month(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    months: i32,
+) -> Result<Vec<(chrono::NaiveDate, i64)>> {
+    let span = crate::observability::db_span("get_recipe_counts_by_month", "recipes");
+    async move {
+        let start_time = std::time::Instant::now();
+        let rows = sqlx::query(
+            r#"
+        SELECT m.month::date, COUNT(r.id)
+        FROM generate_series(
+            date_trunc('month', NOW() AT TIME ZONE 'UTC') - make_interval(months => $2 - 1),
+            date_trunc('month', NOW() AT TIME ZONE 'UTC'),
+            INTERVAL '1 month'
+        ) AS m(month)
+        LEFT JOIN recipes r ON r.telegram_id = $1 AND r.deleted_at IS NULL
+            AND date_trunc('month', r.created_at AT TIME ZONE 'UTC') = m.month
+        GROUP BY m.month
+        ORDER BY m.month
+        "#,
+        )
+        .bind(telegram_id)
+        .bind(months.max(1))
+        .fetch_all(pool)
+        .await
+        .context("Failed to get recipe counts by month")?;
+
+        let counts: Vec<(chrono::NaiveDate, i64)> = rows
+            .into_iter()
+            .map(|row| (row.get(0), row.get(1)))
+            .collect();
+
+        observability::record_db_performance_metrics(
+            "get_recipe_counts_by_month",
+            start_time.elapsed(),
+            counts.len() as u64,
+            crate::observability::QueryComplexity::Medium,
+        );
+        Ok(counts)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Get the recipe of a user with the most ingredients, the newest one on ties
+pub async fn get_longest_recipe_by_user(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<Option<LongestRecipe>> {
+    let span = crate::observability::db_span("get_longest_recipe_by_user", "recipes");
+    async move {
+        let row = sqlx::query(
+            r#"
+        SELECT r.id, r.recipe_name, COUNT(i.id) AS ingredient_count
+        FROM recipes r
+        JOIN ingredients i ON i.recipe_id = r.id
+        WHERE r.telegram_id = $1 AND r.deleted_at IS NULL
+        GROUP BY r.id
+        ORDER BY ingredient_count DESC, r.created_at DESC, r.id DESC
+        LIMIT 1
+        "#,
+        )
+        .bind(telegram_id)
+        .fetch_optional(pool)
+        .await
+        .context("Failed to get longest recipe")?;
+
+        Ok(row.map(|row| LongestRecipe {
+            id: RecipeId(row.get(0)),
+            recipe_name: row.get(1),
+            ingredient_count: row.get(2),
+        }))
+    }
+    .instrument(span)
+    .await
+}
+
 /// Validate that the database schema matches what the application expects
 pub async fn validate_database_schema(pool: &PgPool) -> Result<()> {
     info!("Validating database schema");
 
     // Check that all required tables exist
-    let required_tables = vec!["users", "recipes", "ingredients"];
+    let required_tables = vec![
+        "users",
+        "recipes",
+        "ingredients",
+        "experiment_assignments",
+        "review_funnel_events",
+        "import_jobs",
+        "import_job_rows",
+    ];
     for table_name in required_tables {
         let exists: bool = sqlx::query_scalar(
             "SELECT EXISTS (SELECT 1 FROM information_schema.tables WHERE table_name = $1 AND table_schema = 'public')"
@@ -1072,6 +5050,9 @@ pub async fn validate_database_schema(pool: &PgPool) -> Result<()> {
             ("language_code", "character varying"),
             ("created_at", "timestamp with time zone"),
             ("updated_at", "timestamp with time zone"),
+            ("quickbar_enabled", "boolean"),
+            ("auto_language", "boolean"),
+            ("explicit_language", "boolean"),
         ],
     )
     .await?;
@@ -1087,6 +5068,8 @@ pub async fn validate_database_schema(pool: &PgPool) -> Result<()> {
             ("recipe_name", "character varying"),
             ("created_at", "timestamp with time zone"),
             ("content_tsv", "tsvector"),
+            ("search_tsv", "tsvector"),
+            ("deleted_at", "timestamp with time zone"),
         ],
     )
     .await?;
@@ -1103,6 +5086,8 @@ pub async fn validate_database_schema(pool: &PgPool) -> Result<()> {
             ("quantity", "numeric"),
             ("unit", "character varying"),
             ("raw_text", "text"),
+            ("normalized_name", "character varying"),
+            ("notes", "text"),
             ("created_at", "timestamp with time zone"),
             ("updated_at", "timestamp with time zone"),
         ],
@@ -1110,7 +5095,16 @@ pub async fn validate_database_schema(pool: &PgPool) -> Result<()> {
     .await?;
 
     // Validate indexes exist
-    validate_indexes(pool, "recipes", &["recipes_content_tsv_idx"]).await?;
+    validate_indexes(
+        pool,
+        "recipes",
+        &[
+            "recipes_content_tsv_idx",
+            "recipes_search_tsv_idx",
+            "recipes_deleted_at_idx",
+        ],
+    )
+    .await?;
     validate_indexes(
         pool,
         "ingredients",
@@ -1219,10 +5213,11 @@ pub mod migrations {
 
     /// Get all available migrations in order
     pub fn get_migrations() -> Vec<Migration> {
-        vec![Migration {
-            version: 1,
-            name: "create_initial_tables",
-            up: r#"
+        vec![
+            Migration {
+                version: 1,
+                name: "create_initial_tables",
+                up: r#"
                     -- Create users table
                     CREATE TABLE IF NOT EXISTS users (
                         id BIGSERIAL PRIMARY KEY,
@@ -1260,27 +5255,618 @@ pub mod migrations {
                     CREATE INDEX IF NOT EXISTS ingredients_user_id_idx ON ingredients(user_id);
                     CREATE INDEX IF NOT EXISTS ingredients_recipe_id_idx ON ingredients(recipe_id);
                 "#,
-            down: Some(
-                r#"
+                down: Some(
+                    r#"
                     DROP TABLE IF EXISTS ingredients;
                     DROP TABLE IF EXISTS recipes;
                     DROP TABLE IF EXISTS users;
                 "#,
-            ),
-        }]
+                ),
+            },
+            Migration {
+                version: 2,
+                name: "add_user_quickbar_setting",
+                up: r#"
+                    -- Persistent reply-keyboard quick actions, opt-in per user
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS quickbar_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE users DROP COLUMN IF EXISTS quickbar_enabled;
+                "#,
+                ),
+            },
+            Migration {
+                version: 3,
+                name: "repair_ingredient_user_ids",
+                // Data repair only, there is nothing to roll back
+                up: super::REPAIR_INGREDIENT_USER_IDS_SQL,
+                down: None,
+            },
+            Migration {
+                version: 4,
+                name: "create_review_funnel_tables",
+                up: r#"
+                    -- Sticky experiment variant per user
+                    CREATE TABLE IF NOT EXISTS experiment_assignments (
+                        telegram_id BIGINT NOT NULL,
+                        experiment VARCHAR(100) NOT NULL,
+                        variant VARCHAR(50) NOT NULL,
+                        assigned_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+                        PRIMARY KEY (telegram_id, experiment)
+                    );
+
+                    -- Review funnel events tagged with the experiment variant
+                    CREATE TABLE IF NOT EXISTS review_funnel_events (
+                        id BIGSERIAL PRIMARY KEY,
+                        telegram_id BIGINT NOT NULL,
+                        experiment VARCHAR(100) NOT NULL,
+                        variant VARCHAR(50) NOT NULL,
+                        event VARCHAR(50) NOT NULL,
+                        created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+                    );
+
+                    CREATE INDEX IF NOT EXISTS review_funnel_events_experiment_idx ON review_funnel_events(experiment, created_at);
+                "#,
+                down: Some(
+                    r#"
+                    DROP TABLE IF EXISTS review_funnel_events;
+                    DROP TABLE IF EXISTS experiment_assignments;
+                "#,
+                ),
+            },
+            Migration {
+                version: 5,
+                name: "create_import_job_tables",
+                up: r#"
+                    -- Progress of resumable document imports
+                    CREATE TABLE IF NOT EXISTS import_jobs (
+                        id BIGSERIAL PRIMARY KEY,
+                        telegram_id BIGINT NOT NULL,
+                        file_unique_id VARCHAR(255) NOT NULL,
+                        rows_total BIGINT NOT NULL,
+                        rows_processed BIGINT NOT NULL DEFAULT 0,
+                        status VARCHAR(20) NOT NULL DEFAULT 'in_progress',
+                        created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+                        updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+                        UNIQUE (telegram_id, file_unique_id)
+                    );
+
+                    -- Content hashes of rows already committed by an import job
+                    CREATE TABLE IF NOT EXISTS import_job_rows (
+                        job_id BIGINT NOT NULL REFERENCES import_jobs(id) ON DELETE CASCADE,
+                        row_hash VARCHAR(64) NOT NULL,
+                        PRIMARY KEY (job_id, row_hash)
+                    );
+                "#,
+                down: Some(
+                    r#"
+                    DROP TABLE IF EXISTS import_job_rows;
+                    DROP TABLE IF EXISTS import_jobs;
+                "#,
+                ),
+            },
+            Migration {
+                version: 6,
+                name: "add_ingredient_unit_metadata",
+                up: r#"
+                    -- Unit dimension (volume/weight/count) and system (metric/us/imperial/neutral)
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS unit_dimension VARCHAR(10);
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS unit_system VARCHAR(10);
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS unit_system;
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS unit_dimension;
+                "#,
+                ),
+            },
+            Migration {
+                version: 7,
+                name: "create_extraction_reports_table",
+                up: r#"
+                    -- User reports about bad extractions, without the OCR text itself
+                    CREATE TABLE IF NOT EXISTS extraction_reports (
+                        id BIGSERIAL PRIMARY KEY,
+                        telegram_id BIGINT NOT NULL,
+                        correlation_id VARCHAR(64) NOT NULL,
+                        extracted_text_hash VARCHAR(64) NOT NULL,
+                        match_count INTEGER NOT NULL,
+                        preprocessing_variant VARCHAR(50),
+                        comment TEXT,
+                        photo_file_id VARCHAR(255),
+                        photo_consent VARCHAR(20) NOT NULL DEFAULT 'not_requested',
+                        created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+                    );
+
+                    CREATE INDEX IF NOT EXISTS extraction_reports_created_at_idx ON extraction_reports(created_at);
+                "#,
+                down: Some(
+                    r#"
+                    DROP TABLE IF EXISTS extraction_reports;
+                "#,
+                ),
+            },
+            Migration {
+                version: 8,
+                name: "widen_extraction_report_preprocessing_variant",
+                up: r#"
+                    -- Variants now carry the source class and stage list
+                    ALTER TABLE extraction_reports ALTER COLUMN preprocessing_variant TYPE VARCHAR(120);
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE extraction_reports ALTER COLUMN preprocessing_variant TYPE VARCHAR(50);
+                "#,
+                ),
+            },
+            Migration {
+                version: 9,
+                name: "add_recipe_idempotency_key",
+                up: r#"
+                    -- Extraction correlation id used to make retried saves idempotent
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS idempotency_key VARCHAR(64);
+
+                    CREATE UNIQUE INDEX IF NOT EXISTS recipes_idempotency_key_idx
+                        ON recipes(telegram_id, idempotency_key)
+                        WHERE idempotency_key IS NOT NULL;
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS recipes_idempotency_key_idx;
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS idempotency_key;
+                "#,
+                ),
+            },
+            Migration {
+                version: 10,
+                name: "create_recipe_activity_table",
+                up: r#"
+                    -- Renames and ingredient edits shown by /recent (saves come from recipes)
+                    CREATE TABLE IF NOT EXISTS recipe_activity (
+                        id BIGSERIAL PRIMARY KEY,
+                        telegram_id BIGINT NOT NULL,
+                        recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+                        activity VARCHAR(20) NOT NULL,
+                        recipe_name VARCHAR(255),
+                        previous_name VARCHAR(255),
+                        ingredient_count INTEGER,
+                        created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+                    );
+
+                    CREATE INDEX IF NOT EXISTS recipe_activity_user_created_at_idx
+                        ON recipe_activity(telegram_id, created_at DESC);
+                "#,
+                down: Some(
+                    r#"
+                    DROP TABLE IF EXISTS recipe_activity;
+                "#,
+                ),
+            },
+            Migration {
+                version: 11,
+                name: "add_user_auto_language_setting",
+                up: r#"
+                    -- Reply to free text in its detected language (/language auto)
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS auto_language BOOLEAN NOT NULL DEFAULT FALSE;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE users DROP COLUMN IF EXISTS auto_language;
+                "#,
+                ),
+            },
+            Migration {
+                version: 12,
+                name: "add_user_unit_preference",
+                up: r#"
+                    -- Unit system ingredients are displayed in (/units), NULL keeps them as written
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS unit_preference VARCHAR(20);
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE users DROP COLUMN IF EXISTS unit_preference;
+                "#,
+                ),
+            },
+            Migration {
+                version: 13,
+                name: "add_user_explicit_language",
+                up: r#"
+                    -- language_code was chosen with /language rather than copied from the Telegram client
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS explicit_language BOOLEAN NOT NULL DEFAULT FALSE;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE users DROP COLUMN IF EXISTS explicit_language;
+                "#,
+                ),
+            },
+            Migration {
+                version: 14,
+                name: "add_ingredient_normalized_name",
+                up: r#"
+                    -- Canonical name for matching and statistics; name keeps the text as written.
+                    -- Existing rows are filled in at startup by backfill_ingredient_normalized_names
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS normalized_name VARCHAR(255);
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS notes TEXT;
+                    CREATE INDEX IF NOT EXISTS idx_ingredients_normalized_name ON ingredients(normalized_name);
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS idx_ingredients_normalized_name;
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS notes;
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS normalized_name;
+                "#,
+                ),
+            },
+            Migration {
+                version: 15,
+                name: "create_recipe_tags_table",
+                up: r#"
+                    -- Tags a user put on their recipes, stored lowercased (/recipes <tag>)
+                    CREATE TABLE IF NOT EXISTS recipe_tags (
+                        recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+                        telegram_id BIGINT NOT NULL,
+                        tag VARCHAR(32) NOT NULL,
+                        created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+                        PRIMARY KEY (recipe_id, tag)
+                    );
+
+                    CREATE INDEX IF NOT EXISTS recipe_tags_user_tag_idx
+                        ON recipe_tags(telegram_id, tag);
+                "#,
+                down: Some(
+                    r#"
+                    DROP TABLE IF EXISTS recipe_tags;
+                "#,
+                ),
+            },
+            Migration {
+                version: 16,
+                name: "add_user_weekly_digest",
+                up: r#"
+                    -- Opt-in weekly summary (/digest): UTC weekday (0 = Monday) and hour, and the last send
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS digest_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS digest_weekday SMALLINT NOT NULL DEFAULT 0;
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS digest_hour SMALLINT NOT NULL DEFAULT 8;
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS digest_last_sent_at TIMESTAMPTZ;
+                    CREATE INDEX IF NOT EXISTS idx_users_digest_schedule
+                        ON users(digest_weekday, digest_hour) WHERE digest_enabled;
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS idx_users_digest_schedule;
+                    ALTER TABLE users DROP COLUMN IF EXISTS digest_last_sent_at;
+                    ALTER TABLE users DROP COLUMN IF EXISTS digest_hour;
+                    ALTER TABLE users DROP COLUMN IF EXISTS digest_weekday;
+                    ALTER TABLE users DROP COLUMN IF EXISTS digest_enabled;
+                "#,
+                ),
+            },
+            Migration {
+                version: 17,
+                name: "add_extraction_feedback",
+                up: r#"
+                    -- Per-review correction counts at confirm time, without any recipe text
+                    CREATE TABLE IF NOT EXISTS extraction_feedback (
+                        id BIGSERIAL PRIMARY KEY,
+                        language_code VARCHAR(10) NOT NULL,
+                        extracted_count INTEGER NOT NULL CHECK (extracted_count >= 0),
+                        edited_count INTEGER NOT NULL CHECK (edited_count >= 0),
+                        deleted_count INTEGER NOT NULL CHECK (deleted_count >= 0),
+                        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+                    );
+                    CREATE INDEX IF NOT EXISTS idx_extraction_feedback_language_created
+                        ON extraction_feedback(language_code, created_at);
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS idx_extraction_feedback_language_created;
+                    DROP TABLE IF EXISTS extraction_feedback;
+                "#,
+                ),
+            },
+            Migration {
+                version: 18,
+                name: "add_recipe_name_prefix_index",
+                up: r#"
+                    -- Case-insensitive recipe name prefix lookups for inline queries
+                    CREATE INDEX IF NOT EXISTS idx_recipes_user_lower_name
+                        ON recipes(telegram_id, lower(recipe_name) text_pattern_ops);
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS idx_recipes_user_lower_name;
+                "#,
+                ),
+            },
+            Migration {
+                version: 19,
+                name: "add_recipe_shares",
+                up: r#"
+                    -- Single-use share links (/start share_<token>) copying a recipe to another user
+                    CREATE TABLE IF NOT EXISTS recipe_shares (
+                        token VARCHAR(64) PRIMARY KEY,
+                        recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+                        telegram_id BIGINT NOT NULL,
+                        expires_at TIMESTAMPTZ NOT NULL,
+                        used_at TIMESTAMPTZ,
+                        created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+                    );
+                    CREATE INDEX IF NOT EXISTS recipe_shares_recipe_id_idx ON recipe_shares(recipe_id);
+                    CREATE INDEX IF NOT EXISTS recipe_shares_telegram_id_idx ON recipe_shares(telegram_id);
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS recipe_shares_telegram_id_idx;
+                    DROP INDEX IF EXISTS recipe_shares_recipe_id_idx;
+                    DROP TABLE IF EXISTS recipe_shares;
+                "#,
+                ),
+            },
+            Migration {
+                version: 20,
+                name: "add_recipe_photo",
+                up: r#"
+                    -- Telegram ids of the photo a recipe was read from, so it can be sent again
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS file_id TEXT;
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS file_unique_id TEXT;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS file_unique_id;
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS file_id;
+                "#,
+                ),
+            },
+            Migration {
+                version: 21,
+                name: "add_ingredient_position",
+                up: r#"
+                    -- Order of ingredients within their recipe, from 0
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS position INTEGER;
+                    UPDATE ingredients i SET position = o.position
+                    FROM (
+                        SELECT id, ROW_NUMBER() OVER (PARTITION BY recipe_id ORDER BY created_at, id) - 1 AS position
+                        FROM ingredients WHERE recipe_id IS NOT NULL
+                    ) o
+                    WHERE i.id = o.id AND i.position IS NULL;
+                    CREATE INDEX IF NOT EXISTS ingredients_recipe_position_idx ON ingredients(recipe_id, position);
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS ingredients_recipe_position_idx;
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS position;
+                "#,
+                ),
+            },
+            Migration {
+                version: 22,
+                name: "add_recipe_search_vector",
+                up: r#"
+                    -- Text search configuration of a user's recipes, following their language
+                    CREATE OR REPLACE FUNCTION recipe_search_config(p_owner BIGINT) RETURNS regconfig
+                    LANGUAGE sql STABLE AS $$
+                        SELECT COALESCE(
+                            (SELECT CASE WHEN language_code LIKE 'fr%' THEN 'french' ELSE 'english' END::regconfig
+                             FROM users WHERE telegram_id = p_owner),
+                            'english'::regconfig)
+                    $$;
+                    -- Searched document of a recipe: its name, its ingredient names, then its text
+                    CREATE OR REPLACE FUNCTION recipe_search_vector(p_recipe_id BIGINT, p_owner BIGINT, p_content TEXT, p_recipe_name TEXT)
+                    RETURNS tsvector LANGUAGE sql STABLE AS $$
+                        SELECT setweight(to_tsvector(recipe_search_config(p_owner), COALESCE(p_recipe_name, '')), 'A')
+                            || setweight(to_tsvector(recipe_search_config(p_owner), COALESCE(
+                                (SELECT string_agg(name, ' ') FROM ingredients WHERE recipe_id = p_recipe_id), '')), 'B')
+                            || setweight(to_tsvector(recipe_search_config(p_owner), p_content), 'C')
+                    $$;
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS search_tsv tsvector;
+
+                    CREATE OR REPLACE FUNCTION recipes_search_tsv_refresh() RETURNS trigger
+                    LANGUAGE plpgsql AS $$
+                    BEGIN
+                        NEW.search_tsv := recipe_search_vector(NEW.id, NEW.telegram_id, NEW.content, NEW.recipe_name);
+                        RETURN NEW;
+                    END
+                    $$;
+                    DROP TRIGGER IF EXISTS recipes_search_tsv_refresh ON recipes;
+                    CREATE TRIGGER recipes_search_tsv_refresh
+                        BEFORE INSERT OR UPDATE OF content, recipe_name ON recipes
+                        FOR EACH ROW EXECUTE FUNCTION recipes_search_tsv_refresh();
+
+                    CREATE OR REPLACE FUNCTION ingredients_search_tsv_refresh() RETURNS trigger
+                    LANGUAGE plpgsql AS $$
+                    DECLARE
+                        changed BIGINT[] := ARRAY[]::BIGINT[];
+                    BEGIN
+                        IF TG_OP <> 'INSERT' THEN
+                            changed := changed || OLD.recipe_id;
+                        END IF;
+                        IF TG_OP <> 'DELETE' THEN
+                            changed := changed || NEW.recipe_id;
+                        END IF;
+                        UPDATE recipes SET search_tsv = recipe_search_vector(id, telegram_id, content, recipe_name)
+                        WHERE id = ANY(changed);
+                        RETURN NULL;
+                    END
+                    $$;
+                    DROP TRIGGER IF EXISTS ingredients_search_tsv_refresh ON ingredients;
+                    CREATE TRIGGER ingredients_search_tsv_refresh
+                        AFTER INSERT OR DELETE OR UPDATE OF name, recipe_id ON ingredients
+                        FOR EACH ROW EXECUTE FUNCTION ingredients_search_tsv_refresh();
+
+                    CREATE OR REPLACE FUNCTION users_search_tsv_refresh() RETURNS trigger
+                    LANGUAGE plpgsql AS $$
+                    BEGIN
+                        IF TG_OP = 'UPDATE' AND OLD.language_code IS NOT DISTINCT FROM NEW.language_code THEN
+                            RETURN NULL;
+                        END IF;
+                        UPDATE recipes SET search_tsv = recipe_search_vector(id, telegram_id, content, recipe_name)
+                        WHERE telegram_id = NEW.telegram_id;
+                        RETURN NULL;
+                    END
+                    $$;
+                    DROP TRIGGER IF EXISTS users_search_tsv_refresh ON users;
+                    CREATE TRIGGER users_search_tsv_refresh
+                        AFTER INSERT OR UPDATE OF language_code ON users
+                        FOR EACH ROW EXECUTE FUNCTION users_search_tsv_refresh();
+
+                    UPDATE recipes SET search_tsv = recipe_search_vector(id, telegram_id, content, recipe_name);
+                    CREATE INDEX IF NOT EXISTS recipes_search_tsv_idx ON recipes USING GIN (search_tsv);
+                "#,
+                down: Some(
+                    r#"
+                    DROP TRIGGER IF EXISTS users_search_tsv_refresh ON users;
+                    DROP TRIGGER IF EXISTS ingredients_search_tsv_refresh ON ingredients;
+                    DROP TRIGGER IF EXISTS recipes_search_tsv_refresh ON recipes;
+                    DROP FUNCTION IF EXISTS users_search_tsv_refresh();
+                    DROP FUNCTION IF EXISTS ingredients_search_tsv_refresh();
+                    DROP FUNCTION IF EXISTS recipes_search_tsv_refresh();
+                    DROP INDEX IF EXISTS recipes_search_tsv_idx;
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS search_tsv;
+                    DROP FUNCTION IF EXISTS recipe_search_vector(BIGINT, BIGINT, TEXT, TEXT);
+                    DROP FUNCTION IF EXISTS recipe_search_config(BIGINT);
+                "#,
+                ),
+            },
+            Migration {
+                version: 23,
+                name: "add_recipe_soft_delete",
+                up: r#"
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+                    CREATE INDEX IF NOT EXISTS recipes_deleted_at_idx ON recipes (deleted_at) WHERE deleted_at IS NOT NULL;
+                "#,
+                down: Some(
+                    r#"
+                    DROP INDEX IF EXISTS recipes_deleted_at_idx;
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS deleted_at;
+                "#,
+                ),
+            },
+            Migration {
+                version: 24,
+                name: "add_recipe_notes",
+                up: r#"
+                    -- The user's own notes on a recipe, such as "reduce sugar next time"
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS notes TEXT;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS notes;
+                "#,
+                ),
+            },
+            Migration {
+                version: 25,
+                name: "add_recipe_ingredients_version",
+                up: r#"
+                    -- Bumped by every ingredient write, so an edit started from an older
+                    -- version of the list can tell it would overwrite newer changes
+                    ALTER TABLE recipes ADD COLUMN IF NOT EXISTS ingredients_version BIGINT NOT NULL DEFAULT 0;
+
+                    CREATE OR REPLACE FUNCTION ingredients_version_bump() RETURNS trigger
+                    LANGUAGE plpgsql AS $$
+                    DECLARE
+                        changed BIGINT[] := ARRAY[]::BIGINT[];
+                    BEGIN
+                        IF TG_OP <> 'INSERT' THEN
+                            changed := changed || OLD.recipe_id;
+                        END IF;
+                        IF TG_OP <> 'DELETE' THEN
+                            changed := changed || NEW.recipe_id;
+                        END IF;
+                        UPDATE recipes SET ingredients_version = ingredients_version + 1
+                        WHERE id = ANY(changed);
+                        RETURN NULL;
+                    END
+                    $$;
+                    DROP TRIGGER IF EXISTS ingredients_version_bump ON ingredients;
+                    CREATE TRIGGER ingredients_version_bump
+                        AFTER INSERT OR DELETE OR UPDATE ON ingredients
+                        FOR EACH ROW EXECUTE FUNCTION ingredients_version_bump();
+                "#,
+                down: Some(
+                    r#"
+                    DROP TRIGGER IF EXISTS ingredients_version_bump ON ingredients;
+                    DROP FUNCTION IF EXISTS ingredients_version_bump();
+                    ALTER TABLE recipes DROP COLUMN IF EXISTS ingredients_version;
+                "#,
+                ),
+            },
+            Migration {
+                version: 26,
+                name: "add_user_utc_offset",
+                up: r#"
+                    -- Time zone dates are displayed in (/timezone), in minutes east of UTC; NULL is UTC
+                    ALTER TABLE users ADD COLUMN IF NOT EXISTS utc_offset_minutes SMALLINT;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE users DROP COLUMN IF EXISTS utc_offset_minutes;
+                "#,
+                ),
+            },
+            Migration {
+                version: 27,
+                name: "add_ingredient_quantity_fraction",
+                up: r#"
+                    -- Exact quantity as a reduced fraction, so 1/3 is not read back as 0.333;
+                    -- quantity keeps the rounded value for sorting and arithmetic in SQL
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS quantity_numerator BIGINT;
+                    ALTER TABLE ingredients ADD COLUMN IF NOT EXISTS quantity_denominator BIGINT
+                        CHECK (quantity_denominator > 0);
+
+                    -- Existing quantities that are a rounded half, third, quarter or eighth
+                    -- become that fraction; other decimals are left as they are. The
+                    -- backfill does not change what the ingredients say, so it does not bump
+                    -- ingredients_version either
+                    ALTER TABLE ingredients DISABLE TRIGGER ingredients_version_bump;
+                    UPDATE ingredients i
+                    SET quantity_numerator = f.numerator, quantity_denominator = f.denominator
+                    FROM (
+                        SELECT DISTINCT ON (id) id, ROUND(quantity * d)::BIGINT AS numerator, d AS denominator
+                        FROM ingredients CROSS JOIN (VALUES (1), (2), (3), (4), (8)) AS nice(d)
+                        WHERE quantity IS NOT NULL AND ROUND(ROUND(quantity * d) / d, 3) = quantity
+                        ORDER BY id, d
+                    ) f
+                    WHERE i.id = f.id;
+                    ALTER TABLE ingredients ENABLE TRIGGER ingredients_version_bump;
+                "#,
+                down: Some(
+                    r#"
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS quantity_denominator;
+                    ALTER TABLE ingredients DROP COLUMN IF EXISTS quantity_numerator;
+                "#,
+                ),
+            },
+        ]
     }
 
     /// Split SQL string into individual statements by semicolons
+    ///
+    /// Semicolons in string literals, comments and `$$`-quoted function bodies
+    /// don't end a statement.
     pub fn split_sql_statements(sql: &str) -> Result<Vec<String>, String> {
         let mut statements = Vec::new();
         let mut current_statement = String::new();
         let mut in_string = false;
         let mut string_char = '\0';
         let mut in_comment = false;
+        let mut in_dollar_quote = false;
         let mut chars = sql.chars().peekable();
 
         while let Some(ch) = chars.next() {
             match ch {
+                // Handle function bodies, everything up to the closing $$ being literal
+                '$' if !in_string && !in_comment && chars.peek() == Some(&'$') => {
+                    in_dollar_quote = !in_dollar_quote;
+                    current_statement.push(ch);
+                    current_statement.extend(chars.next());
+                }
+                _ if in_dollar_quote => {
+                    current_statement.push(ch);
+                }
                 // Handle string literals
                 '"' | '\'' if !in_comment => {
                     if !in_string {
@@ -1293,21 +5879,15 @@ pub mod migrations {
                     current_statement.push(ch);
                 }
                 // Handle comments
-                '-' if !in_string && !in_comment => {
-                    // Check if this is the start of a comment
-                    if chars.peek() == Some(&'-') {
-                        in_comment = true;
-                        current_statement.push(ch); // Push first -
-                        match chars.next() {
-                            Some(second_dash) => current_statement.push(second_dash), // Push second -
-                            None => {
-                                return Err(
-                                    "Unexpected end of input while parsing comment".to_string()
-                                )
-                            }
+                '-' if !in_string && !in_comment && chars.peek() == Some(&'-') => {
+                    // Start of a comment
+                    in_comment = true;
+                    current_statement.push(ch); // Push first -
+                    match chars.next() {
+                        Some(second_dash) => current_statement.push(second_dash), // Push second -
+                        None => {
+                            return Err("Unexpected end of input while parsing comment".to_string())
                         }
-                    } else {
-                        current_statement.push(ch);
                     }
                 }
                 '\n' if in_comment => {
@@ -5,10 +5,10 @@
 //! might be delivered multiple times due to network issues or retries.
 
 use crate::errors::{AppError, AppResult};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use teloxide::types::{ChatId, MessageId};
+use teloxide::types::{ChatId, MessageId, UpdateId};
 
 /// Represents a unique request identifier
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -171,6 +171,70 @@ pub fn create_shared_deduplicator(ttl_secs: u64, max_entries: usize) -> SharedDe
     Arc::new(RequestDeduplicator::new(ttl_secs, max_entries))
 }
 
+/// Remembers the most recent Telegram update ids seen by this process
+///
+/// Telegram may deliver an update again when a handler fails (notably in webhook
+/// mode). Handlers are not idempotent, so redelivered updates are dropped at the
+/// dispatcher entry. Only the last `capacity` update ids are kept, in arrival order.
+#[derive(Debug)]
+pub struct UpdateDeduplicator {
+    /// Seen update ids, oldest first, with a set for constant-time lookups
+    seen: Mutex<(VecDeque<UpdateId>, HashSet<UpdateId>)>,
+    /// Maximum number of update ids to remember
+    capacity: usize,
+}
+
+impl UpdateDeduplicator {
+    /// Create a deduplicator remembering up to `capacity` update ids
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            seen: Mutex::new((VecDeque::with_capacity(capacity), HashSet::new())),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record an update id and report whether it was already processed
+    ///
+    /// Returns true for a redelivered update that should be skipped.
+    pub fn is_redelivery(&self, update_id: UpdateId) -> AppResult<bool> {
+        let mut guard = self.seen.lock().map_err(|e| {
+            AppError::Internal(format!(
+                "Failed to acquire update deduplication lock: {}",
+                e
+            ))
+        })?;
+        let (order, ids) = &mut *guard;
+
+        if !ids.insert(update_id) {
+            return Ok(true);
+        }
+
+        order.push_back(update_id);
+        if order.len() > self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                ids.remove(&oldest);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Number of update ids currently remembered
+    pub fn len(&self) -> usize {
+        self.seen.lock().map(|guard| guard.0.len()).unwrap_or(0)
+    }
+
+    /// Whether no update id has been recorded yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for UpdateDeduplicator {
+    fn default() -> Self {
+        Self::new(4096)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,4 +417,30 @@ mod tests {
             Err(e) => panic!("Failed to check duplicate: {}", e),
         }
     }
+
+    #[test]
+    fn test_update_redelivery_is_skipped() {
+        let deduplicator = UpdateDeduplicator::new(100);
+
+        assert!(!deduplicator.is_redelivery(UpdateId(1)).unwrap());
+        assert!(!deduplicator.is_redelivery(UpdateId(2)).unwrap());
+
+        // Telegram redelivers update 1 after a handler error
+        assert!(deduplicator.is_redelivery(UpdateId(1)).unwrap());
+        assert!(deduplicator.is_redelivery(UpdateId(1)).unwrap());
+        assert_eq!(deduplicator.len(), 2);
+    }
+
+    #[test]
+    fn test_update_deduplicator_forgets_oldest_ids() {
+        let deduplicator = UpdateDeduplicator::new(3);
+        for id in 1..=4 {
+            assert!(!deduplicator.is_redelivery(UpdateId(id)).unwrap());
+        }
+
+        assert_eq!(deduplicator.len(), 3);
+        // Update 1 fell out of the window; 2-4 are still recognised
+        assert!(!deduplicator.is_redelivery(UpdateId(1)).unwrap());
+        assert!(deduplicator.is_redelivery(UpdateId(4)).unwrap());
+    }
 }
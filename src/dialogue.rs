@@ -1,11 +1,54 @@
 //! Recipe name dialogue module for handling conversation state with users.
+//!
+//! Dialogue states are kept in a [`TrackedStorage`], which counts the active
+//! dialogues of each state for the `dialogues_active` gauge and remembers when each
+//! one last changed, so dialogues left idle for too long can be exited by
+//! [`start_dialogue_sweep`].
 
 use crate::text_processing::MeasurementMatch;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use teloxide::dispatching::dialogue::{Dialogue, InMemStorage};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use teloxide::dispatching::dialogue::{Dialogue, InMemStorage, Storage};
+use teloxide::types::ChatId;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
-// Import database types for editing saved ingredients
-use crate::db::Ingredient;
+// Import ingredient snapshots for editing saved ingredients
+use crate::ingredient_editing::IngredientSnapshot;
+
+// Import extraction report context
+use crate::extraction_reports::ExtractionContext;
+
+/// Maximum number of ingredients kept in a review state
+pub const MAX_REVIEW_INGREDIENTS: usize = 50;
+
+/// Lines of OCR text kept around the measurement-bearing region
+pub const DEFAULT_EXTRACTED_TEXT_MARGIN_LINES: usize = 3;
+
+/// Hard cap on the OCR text stored in dialogue state
+pub const MAX_STORED_EXTRACTED_TEXT_BYTES: usize = 16 * 1024;
+
+/// Chats whose full review text is kept in memory
+const MAX_REMEMBERED_REVIEW_TEXTS: usize = 1_000;
+
+/// Serialized state size above which a bounded state is considered a bug
+pub const MAX_SERIALIZED_STATE_BYTES: usize = 64 * 1024;
+
+/// Handled updates per measurement of the serialized dialogue state size
+pub const STATE_SIZE_SAMPLE_INTERVAL: u64 = 16;
+
+/// Default time a dialogue may stay unchanged before the sweep exits it
+pub const DEFAULT_DIALOGUE_MAX_IDLE_SECS: u64 = 24 * 60 * 60;
+
+/// How often idle dialogues are looked for
+pub const DIALOGUE_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
 
 /// Represents the conversation state for recipe name dialogue
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -24,6 +67,8 @@ pub enum RecipeDialogueState {
         message_id: Option<i32>, // ID of the review message to edit
         extracted_text: String,  // Store the original OCR text
         recipe_name_from_caption: Option<String>, // Track recipe name from photo caption
+        #[serde(default)]
+        last_deleted: Option<(usize, MeasurementMatch)>, // Most recent deletion and its index, for undo
     },
     EditingIngredient {
         recipe_name: String,
@@ -41,6 +86,8 @@ pub enum RecipeDialogueState {
         extracted_text: String, // Store the original OCR text
         recipe_name_from_caption: Option<String>, // Track recipe name from photo caption
         message_id: Option<i32>, // ID of the prompt message to edit with confirmation
+        #[serde(default)]
+        name_suggestions: Vec<String>, // Recent recipe names offered as buttons, tapped by index
     },
     RenamingRecipe {
         recipe_id: i64,
@@ -49,26 +96,34 @@ pub enum RecipeDialogueState {
     },
     EditingSavedIngredients {
         recipe_id: i64,
-        original_ingredients: Vec<Ingredient>, // Keep original for comparison
-        current_matches: Vec<MeasurementMatch>, // Working copy for editing
+        original_ingredients: Vec<IngredientSnapshot>, // Ids and hashes of originals for change detection
+        current_matches: Vec<MeasurementMatch>,        // Working copy for editing
         language_code: Option<String>,
         message_id: Option<i32>,
+        #[serde(default)]
+        last_deleted: Option<(usize, MeasurementMatch)>, // Most recent deletion and its index, for undo
+        #[serde(default)]
+        ingredients_version: i64, // Version of the saved list the edit started from, to detect concurrent edits
     },
     EditingSavedIngredient {
         recipe_id: i64,
-        original_ingredients: Vec<Ingredient>, // Keep original for comparison
-        current_matches: Vec<MeasurementMatch>, // Working copy for editing
-        editing_index: usize,                  // Which ingredient is being edited
+        original_ingredients: Vec<IngredientSnapshot>, // Ids and hashes of originals for change detection
+        current_matches: Vec<MeasurementMatch>,        // Working copy for editing
+        editing_index: usize,                          // Which ingredient is being edited
         language_code: Option<String>,
         message_id: Option<i32>,
         original_message_id: Option<i32>, // ID of the original recipe display message to replace during focused editing
+        #[serde(default)]
+        ingredients_version: i64, // Version of the saved list the edit started from, to detect concurrent edits
     },
     AddingIngredientToSavedRecipe {
         recipe_id: i64,
-        original_ingredients: Vec<Ingredient>, // Keep original for comparison
-        current_matches: Vec<MeasurementMatch>, // Working copy for editing
+        original_ingredients: Vec<IngredientSnapshot>, // Ids and hashes of originals for change detection
+        current_matches: Vec<MeasurementMatch>,        // Working copy for editing
         language_code: Option<String>,
         message_id: Option<i32>,
+        #[serde(default)]
+        ingredients_version: i64, // Version of the saved list the edit started from, to detect concurrent edits
     },
     AwaitingQuantityCorrection {
         recipe_name: String,
@@ -79,7 +134,578 @@ pub enum RecipeDialogueState {
         extracted_text: String,
         recipe_name_from_caption: Option<String>,
     },
+    ConfirmingRenamePropagation {
+        recipe_id: i64, // Recipe where the rename was made (excluded from propagation)
+        old_name: String,
+        new_name: String,
+        remaining_renames: Vec<(String, String)>, // Further (old, new) renames to offer afterwards
+        language_code: Option<String>,
+    },
+    ResolvingRecipeNameConflict {
+        recipe_name: String,
+        ingredients: Vec<MeasurementMatch>, // Pending ingredients, kept while the user decides
+        language_code: Option<String>,
+        extracted_text: String,
+        newest_recipe_id: i64, // Most recent existing recipe with the same name
+        existing_count: usize,
+    },
+    AwaitingReportComment {
+        context: ExtractionContext, // Extraction being reported
+        language_code: Option<String>,
+        prompt_message_id: Option<i32>, // Comment prompt carrying the Skip button
+        resume_state: Box<RecipeDialogueState>, // State restored once the report is sent
+    },
+    SelectingRecipesToDelete {
+        selected_ids: Vec<i64>, // Selected recipes, kept across page changes
+        page: usize,            // Page of the selection list currently shown
+        language_code: Option<String>,
+    },
+    AwaitingSearchQuery {
+        language_code: Option<String>,
+    },
+    ScalingRecipe {
+        recipe_id: i64, // Saved recipe whose quantities are being scaled
+        language_code: Option<String>,
+    },
+    ConfirmingAccountWipe {
+        language_code: Option<String>, // Language the confirmation phrase was asked in
+    },
+    RenamingPendingRecipe {
+        recipe_name: String,
+        ingredients: Vec<MeasurementMatch>, // Reviewed ingredients, kept untouched by the rename
+        language_code: Option<String>,
+        message_id: Option<i32>, // Review message re-rendered with the new name
+        extracted_text: String,
+        recipe_name_from_caption: Option<String>,
+    },
+    TaggingRecipe {
+        recipe_id: i64, // Saved recipe whose tags are being edited
+        language_code: Option<String>,
+    },
+    EditingRecipeNotes {
+        recipe_id: i64, // Saved recipe whose notes are being written
+        language_code: Option<String>,
+    },
 }
 
+/// Storage of every chat's dialogue state, with activity metrics
+pub type DialogueStorage = TrackedStorage<InMemStorage<RecipeDialogueState>>;
+
 /// Type alias for our recipe dialogue
-pub type RecipeDialogue = Dialogue<RecipeDialogueState, InMemStorage<RecipeDialogueState>>;
+pub type RecipeDialogue = Dialogue<RecipeDialogueState, DialogueStorage>;
+
+impl RecipeDialogueState {
+    /// Short name of the state variant, used as a metric label
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::WaitingForRecipeName { .. } => "waiting_for_recipe_name",
+            Self::ReviewIngredients { .. } => "review_ingredients",
+            Self::EditingIngredient { .. } => "editing_ingredient",
+            Self::WaitingForRecipeNameAfterConfirm { .. } => {
+                "waiting_for_recipe_name_after_confirm"
+            }
+            Self::RenamingRecipe { .. } => "renaming_recipe",
+            Self::EditingSavedIngredients { .. } => "editing_saved_ingredients",
+            Self::EditingSavedIngredient { .. } => "editing_saved_ingredient",
+            Self::AddingIngredientToSavedRecipe { .. } => "adding_ingredient_to_saved_recipe",
+            Self::AwaitingQuantityCorrection { .. } => "awaiting_quantity_correction",
+            Self::ConfirmingRenamePropagation { .. } => "confirming_rename_propagation",
+            Self::ResolvingRecipeNameConflict { .. } => "resolving_recipe_name_conflict",
+            Self::AwaitingReportComment { .. } => "awaiting_report_comment",
+            Self::SelectingRecipesToDelete { .. } => "selecting_recipes_to_delete",
+            Self::AwaitingSearchQuery { .. } => "awaiting_search_query",
+            Self::ScalingRecipe { .. } => "scaling_recipe",
+            Self::ConfirmingAccountWipe { .. } => "confirming_account_wipe",
+            Self::RenamingPendingRecipe { .. } => "renaming_pending_recipe",
+            Self::TaggingRecipe { .. } => "tagging_recipe",
+            Self::EditingRecipeNotes { .. } => "editing_recipe_notes",
+        }
+    }
+
+    /// Whether the user is in the middle of a dialogue, i.e. anywhere but [`Self::Start`]
+    pub fn is_active(&self) -> bool {
+        !matches!(self, Self::Start)
+    }
+
+    /// Messages whose inline keyboards belong to this dialogue
+    ///
+    /// Their keyboards are removed when the dialogue is left with a command, so no
+    /// stale button acts on a dialogue that is gone.
+    pub fn keyboard_message_ids(&self) -> Vec<i32> {
+        match self {
+            Self::ReviewIngredients { message_id, .. }
+            | Self::WaitingForRecipeNameAfterConfirm { message_id, .. }
+            | Self::EditingSavedIngredients { message_id, .. }
+            | Self::AddingIngredientToSavedRecipe { message_id, .. }
+            | Self::AwaitingQuantityCorrection { message_id, .. }
+            | Self::RenamingPendingRecipe { message_id, .. } => {
+                message_id.iter().copied().collect()
+            }
+            Self::EditingIngredient {
+                message_id,
+                original_message_id,
+                ..
+            }
+            | Self::EditingSavedIngredient {
+                message_id,
+                original_message_id,
+                ..
+            } => message_id
+                .iter()
+                .chain(original_message_id)
+                .copied()
+                .collect(),
+            Self::AwaitingReportComment {
+                prompt_message_id,
+                resume_state,
+                ..
+            } => prompt_message_id
+                .iter()
+                .copied()
+                .chain(resume_state.keyboard_message_ids())
+                .collect(),
+            Self::Start
+            | Self::WaitingForRecipeName { .. }
+            | Self::RenamingRecipe { .. }
+            | Self::ConfirmingRenamePropagation { .. }
+            | Self::ResolvingRecipeNameConflict { .. }
+            | Self::SelectingRecipesToDelete { .. }
+            | Self::AwaitingSearchQuery { .. }
+            | Self::ScalingRecipe { .. }
+            | Self::ConfirmingAccountWipe { .. }
+            | Self::TaggingRecipe { .. }
+            | Self::EditingRecipeNotes { .. } => Vec::new(),
+        }
+    }
+
+    /// Rename prompt for the recipe under review, `None` outside a review
+    pub fn into_pending_rename(self) -> Option<Self> {
+        match self {
+            Self::ReviewIngredients {
+                recipe_name,
+                ingredients,
+                language_code,
+                message_id,
+                extracted_text,
+                recipe_name_from_caption,
+                last_deleted: _,
+            } => Some(Self::RenamingPendingRecipe {
+                recipe_name,
+                ingredients,
+                language_code,
+                message_id,
+                extracted_text,
+                recipe_name_from_caption,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Review state after a pending rename, keeping the old name when `new_name` is `None`
+    ///
+    /// The new name is also used as the caption name, so confirming saves under it
+    /// without asking again. `None` outside a pending rename.
+    pub fn into_renamed_review(self, new_name: Option<&str>) -> Option<Self> {
+        match self {
+            Self::RenamingPendingRecipe {
+                recipe_name,
+                ingredients,
+                language_code,
+                message_id,
+                extracted_text,
+                recipe_name_from_caption,
+            } => {
+                let (recipe_name, recipe_name_from_caption) = match new_name {
+                    Some(name) => (name.to_string(), Some(name.to_string())),
+                    None => (recipe_name, recipe_name_from_caption),
+                };
+                Some(Self::ReviewIngredients {
+                    recipe_name,
+                    ingredients,
+                    language_code,
+                    message_id,
+                    extracted_text,
+                    recipe_name_from_caption,
+                    last_deleted: None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Cap the ingredients kept for review at [`MAX_REVIEW_INGREDIENTS`]
+pub fn cap_review_ingredients(mut ingredients: Vec<MeasurementMatch>) -> Vec<MeasurementMatch> {
+    ingredients.truncate(MAX_REVIEW_INGREDIENTS);
+    ingredients
+}
+
+/// Margin of OCR text lines kept in dialogue state
+///
+/// Configurable with `DIALOGUE_TEXT_MARGIN_LINES`, defaults to
+/// [`DEFAULT_EXTRACTED_TEXT_MARGIN_LINES`].
+pub fn extracted_text_margin_lines() -> usize {
+    crate::config::current().bot.dialogue_text_margin_lines
+}
+
+/// Trim OCR text stored in dialogue state to the measurement-bearing region
+///
+/// Keeps the lines from the first to the last detected ingredient, plus `margin_lines`
+/// on each side, and never more than [`MAX_STORED_EXTRACTED_TEXT_BYTES`].
+pub fn bound_extracted_text(
+    text: &str,
+    ingredients: &[MeasurementMatch],
+    margin_lines: usize,
+) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let region = match (
+        ingredients.iter().map(|m| m.line_number).min(),
+        ingredients.iter().map(|m| m.line_number).max(),
+    ) {
+        (Some(first), Some(last)) if first < lines.len() => {
+            let start = first.saturating_sub(margin_lines);
+            let end = (last + margin_lines + 1).min(lines.len());
+            lines[start..end].join("\n")
+        }
+        _ => text.to_string(),
+    };
+
+    if region.len() <= MAX_STORED_EXTRACTED_TEXT_BYTES {
+        return region;
+    }
+
+    // Cut on a character boundary
+    let mut end = MAX_STORED_EXTRACTED_TEXT_BYTES;
+    while !region.is_char_boundary(end) {
+        end -= 1;
+    }
+    region[..end].to_string()
+}
+
+/// Full OCR text of a review, kept outside its dialogue state
+struct ReviewText {
+    stored_at: Instant,
+    text: String,
+    /// Hash of the bounded copy held by the dialogue state
+    state_text_hash: String,
+}
+
+/// Updates seen by [`record_state_size`]
+static STATE_SIZE_SAMPLES: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    /// Full OCR text of the latest review per chat
+    static ref REVIEW_TEXTS: Mutex<HashMap<ChatId, ReviewText>> = Mutex::new(HashMap::new());
+}
+
+/// Time a full review text is kept, the longest a dialogue may stay idle
+fn review_text_ttl() -> Duration {
+    Duration::from_secs(crate::config::current().bot.dialogue_max_idle_secs)
+}
+
+/// Text to store in the dialogue state of a new review
+///
+/// Returns the [`bound_extracted_text`] copy and keeps the full text aside, so the
+/// recipe is saved and shown with all of it, see [`full_review_text`].
+pub fn review_state_text(chat_id: ChatId, text: &str, ingredients: &[MeasurementMatch]) -> String {
+    let state_text = bound_extracted_text(text, ingredients, extracted_text_margin_lines());
+
+    let mut texts = REVIEW_TEXTS.lock().unwrap_or_else(PoisonError::into_inner);
+    if texts.len() >= MAX_REMEMBERED_REVIEW_TEXTS && !texts.contains_key(&chat_id) {
+        let ttl = review_text_ttl();
+        texts.retain(|_, review| review.stored_at.elapsed() < ttl);
+        if texts.len() >= MAX_REMEMBERED_REVIEW_TEXTS {
+            if let Some(oldest) = texts
+                .iter()
+                .min_by_key(|(_, review)| review.stored_at)
+                .map(|(id, _)| *id)
+            {
+                texts.remove(&oldest);
+            }
+        }
+    }
+    texts.insert(
+        chat_id,
+        ReviewText {
+            stored_at: Instant::now(),
+            text: text.to_string(),
+            state_text_hash: crate::extraction_reports::extracted_text_hash(&state_text),
+        },
+    );
+
+    state_text
+}
+
+/// Full OCR text of the review whose dialogue state holds `state_text`
+///
+/// Falls back to `state_text` once the full text expired, or when the chat has
+/// started another review since.
+pub fn full_review_text(chat_id: ChatId, state_text: &str) -> String {
+    let mut texts = REVIEW_TEXTS.lock().unwrap_or_else(PoisonError::into_inner);
+    match texts.get(&chat_id) {
+        Some(review) if review.stored_at.elapsed() >= review_text_ttl() => {
+            texts.remove(&chat_id);
+            state_text.to_string()
+        }
+        Some(review)
+            if review.state_text_hash
+                == crate::extraction_reports::extracted_text_hash(state_text) =>
+        {
+            review.text.clone()
+        }
+        _ => state_text.to_string(),
+    }
+}
+
+/// Serialized size of a dialogue state in bytes
+pub fn serialized_state_size(state: &RecipeDialogueState) -> usize {
+    serde_json::to_vec(state)
+        .map(|bytes| bytes.len())
+        .unwrap_or(0)
+}
+
+/// Record the serialized size of one dialogue state in [`STATE_SIZE_SAMPLE_INTERVAL`]
+///
+/// Serializing every update would cost more than the states it measures. A sampled
+/// state over [`MAX_SERIALIZED_STATE_BYTES`] is counted and logged.
+pub fn record_state_size(state: &RecipeDialogueState) {
+    let sample = STATE_SIZE_SAMPLES.fetch_add(1, Ordering::Relaxed);
+    if !sample.is_multiple_of(STATE_SIZE_SAMPLE_INTERVAL) {
+        return;
+    }
+
+    let size = serialized_state_size(state);
+    crate::observability::record_dialogue_state_size(state.name(), size);
+    if size > MAX_SERIALIZED_STATE_BYTES {
+        crate::observability::record_dialogue_state_oversized(state.name());
+        warn!(
+            state = state.name(),
+            size_bytes = size,
+            max_bytes = MAX_SERIALIZED_STATE_BYTES,
+            "Dialogue state is larger than its size bound"
+        );
+    }
+}
+
+/// A dialogue that has not changed for a while
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleDialogue {
+    pub chat_id: ChatId,
+    /// Name of its state, see [`RecipeDialogueState::name`]
+    pub state: &'static str,
+    /// Time since it last changed
+    pub idle: Duration,
+}
+
+/// State and last change of every active dialogue
+#[derive(Debug, Default)]
+struct DialogueActivity {
+    dialogues: HashMap<ChatId, (&'static str, Instant)>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl DialogueActivity {
+    /// Record a chat's new state, `None` once it has left its dialogue
+    ///
+    /// Returns the states whose count changed, with their new count.
+    fn set(
+        &mut self,
+        chat_id: ChatId,
+        state: Option<&'static str>,
+        now: Instant,
+    ) -> Vec<(&'static str, usize)> {
+        let previous = match state {
+            Some(state) => self.dialogues.insert(chat_id, (state, now)),
+            None => self.dialogues.remove(&chat_id),
+        }
+        .map(|(previous, _)| previous);
+        if previous == state {
+            return Vec::new();
+        }
+
+        let mut changed = Vec::new();
+        if let Some(previous) = previous {
+            let count = self.counts.entry(previous).or_default();
+            *count = count.saturating_sub(1);
+            changed.push((previous, *count));
+        }
+        if let Some(state) = state {
+            let count = self.counts.entry(state).or_default();
+            *count += 1;
+            changed.push((state, *count));
+        }
+        changed
+    }
+}
+
+/// Dialogue storage wrapper keeping track of the dialogues it holds
+///
+/// Every update and exit goes through the storage, so all handlers using a
+/// [`RecipeDialogue`] are counted without doing anything. Dialogues back at
+/// [`RecipeDialogueState::Start`] count as exited. States the inner storage fails
+/// to load, e.g. a persistent storage reading a state saved by an older release,
+/// are counted in `dialogue_state_load_failures_total`.
+#[derive(Debug)]
+pub struct TrackedStorage<S> {
+    inner: Arc<S>,
+    activity: Mutex<DialogueActivity>,
+}
+
+impl DialogueStorage {
+    /// Tracked storage keeping dialogues in memory
+    pub fn in_memory() -> Arc<Self> {
+        Self::new(InMemStorage::new())
+    }
+}
+
+impl<S> TrackedStorage<S> {
+    /// Track the dialogues of `inner`
+    pub fn new(inner: Arc<S>) -> Arc<Self> {
+        Arc::new(Self {
+            inner,
+            activity: Mutex::new(DialogueActivity::default()),
+        })
+    }
+
+    fn activity(&self) -> MutexGuard<'_, DialogueActivity> {
+        self.activity.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, chat_id: ChatId, state: Option<&'static str>) {
+        for (state, count) in self.activity().set(chat_id, state, Instant::now()) {
+            crate::observability::record_active_dialogues(state, count);
+        }
+    }
+
+    /// Number of active dialogues in each state
+    pub fn active_dialogues(&self) -> HashMap<&'static str, usize> {
+        self.activity()
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(state, count)| (*state, *count))
+            .collect()
+    }
+
+    /// Dialogues unchanged for longer than `max_idle` at `now`
+    pub fn idle_dialogues(&self, max_idle: Duration, now: Instant) -> Vec<IdleDialogue> {
+        self.activity()
+            .dialogues
+            .iter()
+            .map(|(chat_id, (state, changed_at))| IdleDialogue {
+                chat_id: *chat_id,
+                state,
+                idle: now.saturating_duration_since(*changed_at),
+            })
+            .filter(|dialogue| dialogue.idle > max_idle)
+            .collect()
+    }
+}
+
+type StorageFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+impl<S> Storage<RecipeDialogueState> for TrackedStorage<S>
+where
+    S: Storage<RecipeDialogueState> + Send + Sync + 'static,
+    S::Error: std::fmt::Display + Send + 'static,
+{
+    type Error = S::Error;
+
+    fn remove_dialogue(self: Arc<Self>, chat_id: ChatId) -> StorageFuture<(), Self::Error> {
+        Box::pin(async move {
+            let result = Arc::clone(&self.inner).remove_dialogue(chat_id).await;
+            self.record(chat_id, None);
+            result
+        })
+    }
+
+    fn update_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+        dialogue: RecipeDialogueState,
+    ) -> StorageFuture<(), Self::Error> {
+        Box::pin(async move {
+            let state = dialogue.is_active().then(|| dialogue.name());
+            Arc::clone(&self.inner)
+                .update_dialogue(chat_id, dialogue)
+                .await?;
+            self.record(chat_id, state);
+            Ok(())
+        })
+    }
+
+    fn get_dialogue(
+        self: Arc<Self>,
+        chat_id: ChatId,
+    ) -> StorageFuture<Option<RecipeDialogueState>, Self::Error> {
+        Box::pin(async move {
+            match Arc::clone(&self.inner).get_dialogue(chat_id).await {
+                Ok(state) => {
+                    // A persistent storage can hold dialogues from before a restart
+                    let untracked = !self.activity().dialogues.contains_key(&chat_id);
+                    if let Some(state) = state.as_ref().filter(|state| state.is_active()) {
+                        if untracked {
+                            self.record(chat_id, Some(state.name()));
+                        }
+                    }
+                    Ok(state)
+                }
+                Err(e) => {
+                    warn!(chat_id = chat_id.0, error = %e, "Dialogue state could not be loaded");
+                    crate::observability::record_dialogue_state_load_failure();
+                    Err(e)
+                }
+            }
+        })
+    }
+}
+
+/// Exit the dialogues unchanged for longer than `max_idle`, returning how many were exited
+///
+/// Their buttons then get the answer given to buttons of an ended dialogue.
+pub async fn sweep_idle_dialogues<S>(
+    storage: &Arc<TrackedStorage<S>>,
+    max_idle: Duration,
+    now: Instant,
+) -> usize
+where
+    TrackedStorage<S>: Storage<RecipeDialogueState>,
+    <TrackedStorage<S> as Storage<RecipeDialogueState>>::Error: std::fmt::Display,
+{
+    let idle = storage.idle_dialogues(max_idle, now);
+    for dialogue in &idle {
+        info!(
+            chat_id = dialogue.chat_id.0,
+            state = dialogue.state,
+            idle_secs = dialogue.idle.as_secs(),
+            "Exiting idle dialogue"
+        );
+        crate::observability::record_dialogue_expired(dialogue.state);
+        if let Err(e) = Arc::clone(storage).remove_dialogue(dialogue.chat_id).await {
+            warn!(chat_id = dialogue.chat_id.0, error = %e, "Could not exit idle dialogue");
+        }
+    }
+    idle.len()
+}
+
+/// Start the task exiting idle dialogues, until `shutdown` is cancelled
+pub fn start_dialogue_sweep(
+    storage: Arc<DialogueStorage>,
+    max_idle: Duration,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIALOGUE_SWEEP_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            sweep_idle_dialogues(&storage, max_idle, Instant::now()).await;
+        }
+    })
+}
@@ -61,6 +61,162 @@ impl From<crate::ocr_errors::OcrError> for AppError {
 /// Result type alias for convenience
 pub type AppResult<T> = Result<T, AppError>;
 
+/// What went wrong, in the terms the user is told about
+///
+/// Each kind has a localized message and a concrete next step, so a failure
+/// tells the user what to do instead of a generic "processing failed".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserErrorKind {
+    /// The image failed validation
+    Validation,
+    /// The image format is not supported or the file is corrupted
+    UnsupportedFormat,
+    /// The image is too large to process
+    FileTooLarge,
+    /// The PDF has too many or too large pages
+    PdfTooLarge,
+    /// Reading the image took too long
+    OcrTimeout,
+    /// The OCR engine is unavailable, e.g. while the circuit breaker is open
+    OcrUnavailable,
+    /// No text could be read from the image
+    OcrFailed,
+    /// The database could not be reached
+    DatabaseUnavailable,
+    /// Any other failure
+    Unknown,
+}
+
+impl UserErrorKind {
+    /// Localization key of the message explaining the failure
+    pub fn message_key(self) -> &'static str {
+        match self {
+            UserErrorKind::Validation => "error-reply-validation",
+            UserErrorKind::UnsupportedFormat => "error-reply-unsupported-format",
+            UserErrorKind::FileTooLarge => "error-reply-file-too-large",
+            UserErrorKind::PdfTooLarge => "error-reply-pdf-too-large",
+            UserErrorKind::OcrTimeout => "error-reply-ocr-timeout",
+            UserErrorKind::OcrUnavailable => "error-reply-ocr-unavailable",
+            UserErrorKind::OcrFailed => "error-reply-ocr-failed",
+            UserErrorKind::DatabaseUnavailable => "error-reply-database-unavailable",
+            UserErrorKind::Unknown => "error-reply-unknown",
+        }
+    }
+
+    /// Localization key of the next step suggested to the user
+    pub fn next_step_key(self) -> &'static str {
+        match self {
+            UserErrorKind::Validation | UserErrorKind::OcrFailed => "error-next-clearer-photo",
+            UserErrorKind::UnsupportedFormat => "error-next-supported-format",
+            UserErrorKind::FileTooLarge | UserErrorKind::OcrTimeout => "error-next-smaller-photo",
+            UserErrorKind::PdfTooLarge => "error-next-fewer-pages",
+            UserErrorKind::OcrUnavailable | UserErrorKind::DatabaseUnavailable => "error-next-wait",
+            UserErrorKind::Unknown => "error-next-retry",
+        }
+    }
+
+    /// Label of the kind in metrics
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UserErrorKind::Validation => "validation",
+            UserErrorKind::UnsupportedFormat => "unsupported_format",
+            UserErrorKind::FileTooLarge => "file_too_large",
+            UserErrorKind::PdfTooLarge => "pdf_too_large",
+            UserErrorKind::OcrTimeout => "ocr_timeout",
+            UserErrorKind::OcrUnavailable => "ocr_unavailable",
+            UserErrorKind::OcrFailed => "ocr_failed",
+            UserErrorKind::DatabaseUnavailable => "database_unavailable",
+            UserErrorKind::Unknown => "unknown",
+        }
+    }
+}
+
+impl From<&crate::ocr_errors::OcrError> for UserErrorKind {
+    fn from(err: &crate::ocr_errors::OcrError) -> Self {
+        use crate::ocr_errors::OcrError;
+        // No wildcard: a new variant must be given a user-facing kind
+        match err {
+            OcrError::Validation(_) => UserErrorKind::Validation,
+            OcrError::ImageLoad(_) => UserErrorKind::UnsupportedFormat,
+            OcrError::FileTooLarge(_) | OcrError::ImageTooLarge(_) => UserErrorKind::FileTooLarge,
+            OcrError::PdfTooLarge(_) => UserErrorKind::PdfTooLarge,
+            OcrError::Timeout(_) => UserErrorKind::OcrTimeout,
+            OcrError::CircuitOpen(_)
+            | OcrError::Initialization(_)
+            | OcrError::_InstanceCorruption(_)
+            | OcrError::_ResourceExhaustion(_) => UserErrorKind::OcrUnavailable,
+            OcrError::Extraction(_) => UserErrorKind::OcrFailed,
+        }
+    }
+}
+
+impl From<&sqlx::Error> for UserErrorKind {
+    fn from(err: &sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::Protocol(_)
+            | sqlx::Error::WorkerCrashed => UserErrorKind::DatabaseUnavailable,
+            _ => UserErrorKind::Unknown,
+        }
+    }
+}
+
+impl From<&AppError> for UserErrorKind {
+    fn from(err: &AppError) -> Self {
+        match err {
+            AppError::Validation(_) => UserErrorKind::Validation,
+            AppError::Database(_) => UserErrorKind::DatabaseUnavailable,
+            AppError::Ocr(_) => UserErrorKind::OcrFailed,
+            AppError::Config(_)
+            | AppError::FileSystem(_)
+            | AppError::Network(_)
+            | AppError::Internal(_) => UserErrorKind::Unknown,
+        }
+    }
+}
+
+/// Kind of the first known error in the chain of `err`
+pub fn user_error_kind(err: &anyhow::Error) -> UserErrorKind {
+    err.chain()
+        .find_map(|cause| {
+            if let Some(ocr) = cause.downcast_ref::<crate::ocr_errors::OcrError>() {
+                Some(UserErrorKind::from(ocr))
+            } else if let Some(db) = cause.downcast_ref::<sqlx::Error>() {
+                Some(UserErrorKind::from(db))
+            } else {
+                cause.downcast_ref::<AppError>().map(UserErrorKind::from)
+            }
+        })
+        .unwrap_or(UserErrorKind::Unknown)
+}
+
+/// Localization key of the message explaining `err` to the user
+pub fn user_message_key(err: &anyhow::Error) -> &'static str {
+    user_error_kind(err).message_key()
+}
+
+/// Localized reply explaining a failure of `kind` and what to do next
+pub fn user_reply(
+    localization: &std::sync::Arc<crate::localization::LocalizationManager>,
+    kind: UserErrorKind,
+    language_code: Option<&str>,
+) -> String {
+    format!(
+        "{}\n{}",
+        crate::localization::t_lang(localization, kind.message_key(), language_code),
+        crate::localization::t_lang(localization, kind.next_step_key(), language_code)
+    )
+}
+
+/// Whether `err` comes from the Telegram API itself, so replying would fail too
+pub fn is_telegram_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .any(|cause| cause.is::<teloxide::RequestError>())
+}
+
 /// Standardized error logging utilities for consistent error reporting across the application
 pub mod error_logging {
     use tracing::error;
@@ -193,3 +349,127 @@ pub mod error_logging {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr_errors::OcrError;
+
+    /// One error per `OcrError` variant with the kind users are told about
+    ///
+    /// The match has no wildcard, so adding a variant fails to compile until it
+    /// is listed here with its expected kind.
+    fn every_ocr_error() -> Vec<(OcrError, UserErrorKind)> {
+        let errors = vec![
+            (
+                OcrError::Validation(String::new()),
+                UserErrorKind::Validation,
+            ),
+            (
+                OcrError::Initialization(String::new()),
+                UserErrorKind::OcrUnavailable,
+            ),
+            (
+                OcrError::ImageLoad(String::new()),
+                UserErrorKind::UnsupportedFormat,
+            ),
+            (
+                OcrError::Extraction(String::new()),
+                UserErrorKind::OcrFailed,
+            ),
+            (
+                OcrError::_InstanceCorruption(String::new()),
+                UserErrorKind::OcrUnavailable,
+            ),
+            (OcrError::Timeout(String::new()), UserErrorKind::OcrTimeout),
+            (
+                OcrError::_ResourceExhaustion(String::new()),
+                UserErrorKind::OcrUnavailable,
+            ),
+            (
+                OcrError::ImageTooLarge(String::new()),
+                UserErrorKind::FileTooLarge,
+            ),
+            (
+                OcrError::PdfTooLarge(String::new()),
+                UserErrorKind::PdfTooLarge,
+            ),
+            (
+                OcrError::FileTooLarge(String::new()),
+                UserErrorKind::FileTooLarge,
+            ),
+            (
+                OcrError::CircuitOpen(String::new()),
+                UserErrorKind::OcrUnavailable,
+            ),
+        ];
+        for (err, _) in &errors {
+            match err {
+                OcrError::Validation(_)
+                | OcrError::Initialization(_)
+                | OcrError::ImageLoad(_)
+                | OcrError::Extraction(_)
+                | OcrError::_InstanceCorruption(_)
+                | OcrError::Timeout(_)
+                | OcrError::_ResourceExhaustion(_)
+                | OcrError::ImageTooLarge(_)
+                | OcrError::PdfTooLarge(_)
+                | OcrError::FileTooLarge(_)
+                | OcrError::CircuitOpen(_) => {}
+            }
+        }
+        errors
+    }
+
+    #[test]
+    fn test_every_ocr_error_maps_to_specific_message() {
+        for (err, expected) in every_ocr_error() {
+            assert_eq!(UserErrorKind::from(&err), expected, "{}", err);
+            assert_ne!(
+                user_message_key(&anyhow::Error::new(err.clone())),
+                UserErrorKind::Unknown.message_key(),
+                "{} falls through to the generic message",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_kind_found_through_context() {
+        let err = anyhow::Error::new(OcrError::CircuitOpen("open".to_string()))
+            .context("Processing photo failed");
+        assert_eq!(user_error_kind(&err), UserErrorKind::OcrUnavailable);
+
+        let err = anyhow::Error::new(sqlx::Error::PoolTimedOut).context("Saving recipe failed");
+        assert_eq!(user_error_kind(&err), UserErrorKind::DatabaseUnavailable);
+
+        assert_eq!(
+            user_error_kind(&anyhow::anyhow!("Something else")),
+            UserErrorKind::Unknown
+        );
+    }
+
+    #[test]
+    fn test_every_reply_is_translated_with_next_step() {
+        let localization = crate::localization::create_localization_manager().unwrap();
+        let kinds = every_ocr_error()
+            .into_iter()
+            .map(|(_, kind)| kind)
+            .chain([UserErrorKind::DatabaseUnavailable, UserErrorKind::Unknown]);
+        for kind in kinds {
+            for language in ["en", "fr"] {
+                for key in [kind.message_key(), kind.next_step_key()] {
+                    let text = localization.get_message_in_language(key, language, None);
+                    assert!(
+                        !text.starts_with("Missing translation:"),
+                        "{} missing in {}",
+                        key,
+                        language
+                    );
+                }
+            }
+            let reply = user_reply(&localization, kind, Some("en"));
+            assert_eq!(reply.lines().count(), 2, "{}", reply);
+        }
+    }
+}
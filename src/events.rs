@@ -0,0 +1,472 @@
+//! # Events Module
+//!
+//! Recipe lifecycle events published on a small internal bus. Every registered sink
+//! receives each event and must never block the caller, so emitting from a handler is
+//! always safe.
+//!
+//! The only sink today is the optional outbound webhook (`WEBHOOK_EVENTS_URL`), which
+//! POSTs a compact JSON event signed with HMAC-SHA256 over the request body using
+//! `WEBHOOK_EVENTS_SECRET`. Events never carry OCR content.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use serde::Serialize;
+use sha2::Sha256;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::db::RecipeId;
+
+/// Header carrying the `sha256=<hex>` signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-JustIngredients-Signature";
+
+/// Delivery attempts per event when `WEBHOOK_EVENTS_MAX_ATTEMPTS` is not set
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+
+/// Upper bound for `WEBHOOK_EVENTS_MAX_ATTEMPTS`
+pub const MAX_ATTEMPTS_LIMIT: u32 = 10;
+
+/// Events buffered for delivery before new ones are dead-lettered
+pub const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// Delay before the first retry, doubled for each further attempt
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Timeout of a single delivery attempt
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kind of recipe lifecycle event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeEventType {
+    RecipeCreated,
+    RecipeRenamed,
+    RecipeDeleted,
+    IngredientsUpdated,
+    /// Sent by the `/events_test` admin command
+    Ping,
+}
+
+impl RecipeEventType {
+    /// Event name used in payloads and metric labels
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecipeEventType::RecipeCreated => "recipe_created",
+            RecipeEventType::RecipeRenamed => "recipe_renamed",
+            RecipeEventType::RecipeDeleted => "recipe_deleted",
+            RecipeEventType::IngredientsUpdated => "ingredients_updated",
+            RecipeEventType::Ping => "ping",
+        }
+    }
+}
+
+/// A recipe lifecycle event as sent to sinks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecipeEvent {
+    pub event: RecipeEventType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredient_count: Option<usize>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl RecipeEvent {
+    fn new(
+        event: RecipeEventType,
+        recipe_id: Option<RecipeId>,
+        recipe_name: Option<&str>,
+        ingredient_count: Option<usize>,
+    ) -> Self {
+        Self {
+            event,
+            recipe_id: recipe_id.map(|id| id.0),
+            recipe_name: recipe_name.map(str::to_string),
+            ingredient_count,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn recipe_created(recipe_id: RecipeId, recipe_name: &str, ingredient_count: usize) -> Self {
+        Self::new(
+            RecipeEventType::RecipeCreated,
+            Some(recipe_id),
+            Some(recipe_name),
+            Some(ingredient_count),
+        )
+    }
+
+    pub fn recipe_renamed(recipe_id: RecipeId, recipe_name: &str) -> Self {
+        Self::new(
+            RecipeEventType::RecipeRenamed,
+            Some(recipe_id),
+            Some(recipe_name),
+            None,
+        )
+    }
+
+    pub fn recipe_deleted(recipe_id: RecipeId) -> Self {
+        Self::new(RecipeEventType::RecipeDeleted, Some(recipe_id), None, None)
+    }
+
+    pub fn ingredients_updated(
+        recipe_id: RecipeId,
+        recipe_name: Option<&str>,
+        ingredient_count: usize,
+    ) -> Self {
+        Self::new(
+            RecipeEventType::IngredientsUpdated,
+            Some(recipe_id),
+            recipe_name,
+            Some(ingredient_count),
+        )
+    }
+
+    pub fn ping() -> Self {
+        Self::new(RecipeEventType::Ping, None, None, None)
+    }
+}
+
+/// Receiver of published events
+///
+/// Implementations must return immediately; slow work belongs on a background task.
+pub trait EventSink: Send + Sync {
+    fn publish(&self, event: &RecipeEvent);
+}
+
+lazy_static! {
+    /// Sinks receiving every emitted event
+    static ref SINKS: RwLock<Vec<Arc<dyn EventSink>>> = RwLock::new(Vec::new());
+    /// Configured webhook sink, kept separately for `/events_test`
+    static ref WEBHOOK: RwLock<Option<Arc<WebhookSink>>> = RwLock::new(None);
+}
+
+/// Add a sink to the event bus
+pub fn register_sink(sink: Arc<dyn EventSink>) {
+    SINKS.write().push(sink);
+}
+
+/// Publish an event to every registered sink
+pub fn emit(event: RecipeEvent) {
+    for sink in SINKS.read().iter() {
+        sink.publish(&event);
+    }
+}
+
+/// Outbound webhook settings
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: reqwest::Url,
+    pub secret: String,
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub queue_capacity: usize,
+}
+
+impl WebhookConfig {
+    /// Validate raw webhook settings
+    pub fn parse(url: &str, secret: Option<&str>, max_attempts: Option<&str>) -> Result<Self> {
+        let url = reqwest::Url::parse(url.trim())
+            .with_context(|| format!("WEBHOOK_EVENTS_URL is not a valid URL: {url}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("WEBHOOK_EVENTS_URL must use http or https");
+        }
+        if url.host_str().is_none_or(str::is_empty) {
+            anyhow::bail!("WEBHOOK_EVENTS_URL must include a host");
+        }
+
+        let secret = secret.map(str::trim).unwrap_or_default();
+        if secret.is_empty() {
+            anyhow::bail!("WEBHOOK_EVENTS_SECRET must be set when WEBHOOK_EVENTS_URL is set");
+        }
+
+        let max_attempts = match max_attempts {
+            Some(value) => value
+                .trim()
+                .parse::<u32>()
+                .context("WEBHOOK_EVENTS_MAX_ATTEMPTS must be a valid number")?,
+            None => DEFAULT_MAX_ATTEMPTS,
+        };
+        if max_attempts == 0 || max_attempts > MAX_ATTEMPTS_LIMIT {
+            anyhow::bail!("WEBHOOK_EVENTS_MAX_ATTEMPTS must be between 1 and {MAX_ATTEMPTS_LIMIT}");
+        }
+
+        Ok(Self {
+            url,
+            secret: secret.to_string(),
+            max_attempts,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+        })
+    }
+}
+
+/// Signature header value for a request body
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Run `attempt` until it succeeds or `max_attempts` is reached
+///
+/// Returns the number of attempts used. The delay doubles after each failure.
+pub async fn retry_bounded<F, Fut>(
+    max_attempts: u32,
+    initial_backoff: Duration,
+    mut attempt: F,
+) -> Result<u32>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut backoff = initial_backoff;
+
+    for attempt_number in 1..=max_attempts {
+        match attempt(attempt_number).await {
+            Ok(()) => return Ok(attempt_number),
+            Err(e) if attempt_number == max_attempts => {
+                return Err(e.context(format!("Gave up after {max_attempts} attempts")));
+            }
+            Err(e) => {
+                debug!(attempt = attempt_number, error = %e, "Webhook delivery attempt failed, retrying");
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2);
+            }
+        }
+    }
+
+    unreachable!("the last attempt always returns")
+}
+
+/// HTTP delivery shared by the background worker and `/events_test`
+struct WebhookDelivery {
+    client: reqwest::Client,
+    config: WebhookConfig,
+}
+
+impl WebhookDelivery {
+    async fn post(&self, event: &RecipeEvent) -> Result<()> {
+        let body = serde_json::to_vec(event).context("Failed to serialize event")?;
+        let signature = sign_payload(&self.config.secret, &body);
+
+        self.client
+            .post(self.config.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .body(body)
+            .send()
+            .await
+            .context("Webhook request failed")?
+            .error_for_status()
+            .context("Webhook endpoint returned an error status")?;
+        Ok(())
+    }
+
+    async fn deliver(&self, event: &RecipeEvent) -> Result<u32> {
+        retry_bounded(
+            self.config.max_attempts,
+            self.config.initial_backoff,
+            |_| self.post(event),
+        )
+        .await
+    }
+}
+
+/// Event sink posting events to the configured webhook from a background task
+pub struct WebhookSink {
+    sender: mpsc::Sender<RecipeEvent>,
+    delivery: Arc<WebhookDelivery>,
+    dead_lettered: Arc<AtomicU64>,
+}
+
+impl WebhookSink {
+    /// Start the delivery worker; must be called from within a Tokio runtime
+    pub fn spawn(config: WebhookConfig) -> Result<Arc<Self>> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("Failed to create webhook HTTP client")?;
+        let (sender, mut receiver) = mpsc::channel::<RecipeEvent>(config.queue_capacity.max(1));
+        let delivery = Arc::new(WebhookDelivery { client, config });
+        let dead_lettered = Arc::new(AtomicU64::new(0));
+
+        let worker_delivery = Arc::clone(&delivery);
+        let worker_dead_lettered = Arc::clone(&dead_lettered);
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                match worker_delivery.deliver(&event).await {
+                    Ok(attempts) => {
+                        debug!(event = %event.event.as_str(), attempts, "Webhook event delivered");
+                        crate::observability::record_webhook_event_metrics(
+                            event.event.as_str(),
+                            "delivered",
+                        );
+                    }
+                    Err(e) => dead_letter(&worker_dead_lettered, &event, &e.to_string()),
+                }
+            }
+        });
+
+        Ok(Arc::new(Self {
+            sender,
+            delivery,
+            dead_lettered,
+        }))
+    }
+
+    /// Deliver an event now, bypassing the queue
+    pub async fn deliver_now(&self, event: &RecipeEvent) -> Result<u32> {
+        self.delivery.deliver(event).await
+    }
+
+    /// Events dropped after exhausting retries or finding the queue full
+    pub fn dead_lettered(&self) -> u64 {
+        self.dead_lettered.load(Ordering::Relaxed)
+    }
+}
+
+impl EventSink for WebhookSink {
+    fn publish(&self, event: &RecipeEvent) {
+        if let Err(e) = self.sender.try_send(event.clone()) {
+            dead_letter(&self.dead_lettered, event, &e.to_string());
+        }
+    }
+}
+
+fn dead_letter(counter: &AtomicU64, event: &RecipeEvent, reason: &str) {
+    counter.fetch_add(1, Ordering::Relaxed);
+    crate::observability::record_webhook_event_metrics(event.event.as_str(), "dead_letter");
+    warn!(event = %event.event.as_str(), recipe_id = ?event.recipe_id, reason = %reason, "Webhook event dead-lettered");
+}
+
+/// Register the webhook sink when `WEBHOOK_EVENTS_URL` is configured
+pub fn init_webhook(config: Option<WebhookConfig>) -> Result<()> {
+    let Some(config) = config else {
+        debug!("WEBHOOK_EVENTS_URL not set, webhook events disabled");
+        return Ok(());
+    };
+
+    info!(host = %config.url.host_str().unwrap_or_default(), max_attempts = config.max_attempts, "Webhook events enabled");
+    let sink = WebhookSink::spawn(config)?;
+    register_sink(sink.clone());
+    *WEBHOOK.write() = Some(sink);
+    Ok(())
+}
+
+/// The configured webhook sink, if any
+pub fn webhook_sink() -> Option<Arc<WebhookSink>> {
+    WEBHOOK.read().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    #[test]
+    fn test_sign_payload_matches_rfc_4231() {
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_event_payload_has_no_ocr_content() {
+        let event = RecipeEvent::recipe_created(RecipeId(7), "Banana Bread", 5);
+        let payload = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(payload["event"], "recipe_created");
+        assert_eq!(payload["recipe_id"], 7);
+        assert_eq!(payload["recipe_name"], "Banana Bread");
+        assert_eq!(payload["ingredient_count"], 5);
+        assert!(payload.get("timestamp").is_some());
+        assert_eq!(payload.as_object().unwrap().len(), 5);
+
+        let ping = serde_json::to_value(RecipeEvent::ping()).unwrap();
+        assert_eq!(ping["event"], "ping");
+        assert!(ping.get("recipe_id").is_none());
+    }
+
+    #[test]
+    fn test_webhook_config_validation() {
+        let config =
+            WebhookConfig::parse("https://hooks.example.com/recipes", Some("s3cret"), None)
+                .unwrap();
+        assert_eq!(config.max_attempts, DEFAULT_MAX_ATTEMPTS);
+
+        assert!(WebhookConfig::parse("ftp://example.com", Some("s3cret"), None).is_err());
+        assert!(WebhookConfig::parse("not a url", Some("s3cret"), None).is_err());
+        assert!(WebhookConfig::parse("https://example.com", None, None).is_err());
+        assert!(WebhookConfig::parse("https://example.com", Some("  "), None).is_err());
+        assert!(WebhookConfig::parse("https://example.com", Some("s"), Some("0")).is_err());
+        assert!(WebhookConfig::parse("https://example.com", Some("s"), Some("11")).is_err());
+        assert!(WebhookConfig::parse("https://example.com", Some("s"), Some("x")).is_err());
+        assert_eq!(
+            WebhookConfig::parse("https://example.com", Some("s"), Some("5"))
+                .unwrap()
+                .max_attempts,
+            5
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_is_bounded() {
+        let calls = AtomicU32::new(0);
+        let result = retry_bounded(3, Duration::from_millis(1), |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("endpoint down")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let result = retry_bounded(3, Duration::from_millis(1), |attempt| async move {
+            if attempt < 2 {
+                Err(anyhow::anyhow!("transient"))
+            } else {
+                Ok(())
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_publish_does_not_block_when_endpoint_is_down() {
+        // Nothing listens on port 9 locally, so every delivery fails
+        let mut config =
+            WebhookConfig::parse("http://127.0.0.1:9/events", Some("s3cret"), Some("2")).unwrap();
+        config.initial_backoff = Duration::from_millis(1);
+        config.queue_capacity = 2;
+        let sink = WebhookSink::spawn(config).unwrap();
+
+        let started = std::time::Instant::now();
+        for id in 0..50 {
+            sink.publish(&RecipeEvent::recipe_deleted(RecipeId(id)));
+        }
+        assert!(started.elapsed() < Duration::from_millis(100));
+
+        // Everything beyond the queue and the event in flight is dropped right away
+        assert!(sink.dead_lettered() >= 47);
+
+        // Queued events are dead-lettered once their retries run out
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        while sink.dead_lettered() < 50 && std::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(sink.dead_lettered(), 50);
+    }
+}
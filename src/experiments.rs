@@ -0,0 +1,418 @@
+//! # Experiments Module
+//!
+//! Lightweight A/B measurement of UI variants. Users are bucketed deterministically
+//! from their Telegram ID, and the first assignment is persisted so a user keeps one
+//! variant for the whole experiment, even if the configured percentages change.
+//!
+//! Variant definitions live in `config/experiments.json` (or `EXPERIMENTS_CONFIG_PATH`)
+//! and are reloaded when the file changes, so switching percentages needs no redeploy.
+//! The file's modification time is checked at most every [`CONFIG_RECHECK_INTERVAL`].
+//! Renaming the experiment starts a new window with fresh assignments.
+
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use parking_lot::RwLock;
+use serde::Deserialize;
+use sqlx::postgres::PgPool;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, warn};
+
+use crate::cache::{Cache, MemoryCache};
+use crate::db::TelegramId;
+
+/// Default location of the experiments configuration file
+pub const DEFAULT_EXPERIMENTS_CONFIG_PATH: &str = "config/experiments.json";
+
+/// Number of buckets users are spread over; variant weights are percentages
+pub const BUCKET_COUNT: u32 = 100;
+
+/// Shortest time between two checks of the configuration file for changes
+pub const CONFIG_RECHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long a resolved assignment is kept before being read from the database again
+const ASSIGNMENT_TTL: Duration = Duration::from_secs(3600);
+
+/// A named variant and its share of users in percent
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct VariantWeight {
+    pub name: String,
+    pub weight: u32,
+}
+
+/// Definition of a single experiment
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExperimentConfig {
+    pub name: String,
+    pub variants: Vec<VariantWeight>,
+}
+
+impl ExperimentConfig {
+    /// Check that the experiment has variants whose weights add up to 100
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            anyhow::bail!("Experiment name must not be empty");
+        }
+        if self.variants.is_empty() {
+            anyhow::bail!("Experiment '{}' has no variants", self.name);
+        }
+        let total: u32 = self.variants.iter().map(|v| v.weight).sum();
+        if total != BUCKET_COUNT {
+            anyhow::bail!(
+                "Variant weights of experiment '{}' add up to {} instead of {}",
+                self.name,
+                total,
+                BUCKET_COUNT
+            );
+        }
+        Ok(())
+    }
+
+    /// Name of the variant covering `bucket`
+    pub fn variant_for_bucket(&self, bucket: u32) -> &str {
+        let mut upper = 0;
+        for variant in &self.variants {
+            upper += variant.weight;
+            if bucket < upper {
+                return &variant.name;
+            }
+        }
+        self.variants
+            .last()
+            .map(|v| v.name.as_str())
+            .unwrap_or(ReviewKeyboardVariant::Full.name())
+    }
+}
+
+/// All experiments known to the bot
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ExperimentsConfig {
+    pub review_keyboard: ExperimentConfig,
+}
+
+impl Default for ExperimentsConfig {
+    /// Everyone sees the full layout when no configuration is available
+    fn default() -> Self {
+        Self {
+            review_keyboard: ExperimentConfig {
+                name: "review_keyboard_default".to_string(),
+                variants: vec![VariantWeight {
+                    name: ReviewKeyboardVariant::Full.name().to_string(),
+                    weight: BUCKET_COUNT,
+                }],
+            },
+        }
+    }
+}
+
+/// Load and validate an experiments configuration file
+pub fn load_experiments_config(path: &str) -> Result<ExperimentsConfig> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read experiments config '{}'", path))?;
+    let config: ExperimentsConfig = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse experiments config '{}'", path))?;
+    config.review_keyboard.validate()?;
+    Ok(config)
+}
+
+/// Layout variants of the ingredient review keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReviewKeyboardVariant {
+    /// One row per ingredient with labelled edit and delete buttons
+    Full,
+    /// Numbered edit/delete buttons, several ingredients per row
+    Compact,
+}
+
+impl ReviewKeyboardVariant {
+    /// Name used in the configuration and funnel events
+    pub fn name(self) -> &'static str {
+        match self {
+            ReviewKeyboardVariant::Full => "full",
+            ReviewKeyboardVariant::Compact => "compact",
+        }
+    }
+
+    /// Parse a configured variant name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "full" => Some(ReviewKeyboardVariant::Full),
+            "compact" => Some(ReviewKeyboardVariant::Compact),
+            _ => None,
+        }
+    }
+}
+
+/// Steps of the ingredient review funnel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunnelEvent {
+    ReviewShown,
+    EditsMade,
+    Confirmed,
+}
+
+impl FunnelEvent {
+    /// Name stored in the funnel table and used as metric label
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FunnelEvent::ReviewShown => "review_shown",
+            FunnelEvent::EditsMade => "edits_made",
+            FunnelEvent::Confirmed => "confirmed",
+        }
+    }
+}
+
+/// Deterministic bucket in `0..BUCKET_COUNT` for a user in an experiment
+///
+/// Uses FNV-1a so buckets stay stable across builds and restarts.
+pub fn bucket_for_user(experiment: &str, telegram_id: TelegramId) -> u32 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in experiment
+        .as_bytes()
+        .iter()
+        .chain(b":")
+        .chain(telegram_id.0.to_le_bytes().iter())
+    {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    (hash % u64::from(BUCKET_COUNT)) as u32
+}
+
+/// Loaded configuration with the file modification time it was read at
+struct CachedConfig {
+    /// When the file was last checked for changes
+    checked_at: Instant,
+    modified: Option<SystemTime>,
+    config: ExperimentsConfig,
+}
+
+lazy_static! {
+    static ref CONFIG_CACHE: RwLock<Option<CachedConfig>> = RwLock::new(None);
+    /// Variants already resolved in this process, keyed by experiment and user
+    static ref ASSIGNMENTS: RwLock<MemoryCache<(String, TelegramId), ReviewKeyboardVariant>> =
+        RwLock::new(MemoryCache::new());
+}
+
+/// Current review keyboard experiment, reloaded when the config file changes
+///
+/// Handlers call this on every review, so the file is only looked at once per
+/// [`CONFIG_RECHECK_INTERVAL`]; expired assignments are dropped at the same time.
+pub fn review_keyboard_experiment() -> ExperimentConfig {
+    if let Some(cached) = CONFIG_CACHE.read().as_ref() {
+        if cached.checked_at.elapsed() < CONFIG_RECHECK_INTERVAL {
+            return cached.config.review_keyboard.clone();
+        }
+    }
+
+    let path = &crate::config::current().paths.experiments;
+    let modified = std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    ASSIGNMENTS.write().cleanup();
+
+    if let Some(cached) = CONFIG_CACHE.write().as_mut() {
+        if cached.modified == modified {
+            cached.checked_at = Instant::now();
+            return cached.config.review_keyboard.clone();
+        }
+    }
+
+    let config = match load_experiments_config(path) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!(path = %path, error = %e, "Using default experiments configuration");
+            ExperimentsConfig::default()
+        }
+    };
+    let experiment = config.review_keyboard.clone();
+    *CONFIG_CACHE.write() = Some(CachedConfig {
+        checked_at: Instant::now(),
+        modified,
+        config,
+    });
+    experiment
+}
+
+fn variant_from_config(experiment: &ExperimentConfig, telegram_id: TelegramId) -> &str {
+    experiment.variant_for_bucket(bucket_for_user(&experiment.name, telegram_id))
+}
+
+/// Review keyboard variant for a user, without touching the database
+///
+/// Returns the assignment already resolved in this process, falling back to the
+/// deterministic bucket for the current configuration.
+pub fn review_keyboard_variant(telegram_id: TelegramId) -> ReviewKeyboardVariant {
+    let experiment = review_keyboard_experiment();
+    let key = (experiment.name.clone(), telegram_id);
+
+    if let Some(variant) = ASSIGNMENTS.read().get(&key) {
+        return variant;
+    }
+
+    ReviewKeyboardVariant::from_name(variant_from_config(&experiment, telegram_id))
+        .unwrap_or(ReviewKeyboardVariant::Full)
+}
+
+/// Resolve and persist the user's review keyboard variant
+///
+/// The stored assignment wins over the current percentages so users never switch
+/// variants during an experiment.
+pub async fn assign_review_keyboard_variant(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> Result<(ExperimentConfig, ReviewKeyboardVariant)> {
+    let experiment = review_keyboard_experiment();
+    let key = (experiment.name.clone(), telegram_id);
+
+    if let Some(variant) = ASSIGNMENTS.read().get(&key) {
+        return Ok((experiment, variant));
+    }
+
+    let assigned = crate::db::get_or_create_experiment_assignment(
+        pool,
+        telegram_id,
+        &experiment.name,
+        variant_from_config(&experiment, telegram_id),
+    )
+    .await?;
+    let variant = ReviewKeyboardVariant::from_name(&assigned).unwrap_or_else(|| {
+        warn!(variant = %assigned, "Unknown review keyboard variant, using full layout");
+        ReviewKeyboardVariant::Full
+    });
+
+    ASSIGNMENTS.write().insert(key, variant, ASSIGNMENT_TTL);
+    debug!(telegram_id = %telegram_id, experiment = %experiment.name, variant = %variant.name(), "Review keyboard variant assigned");
+    Ok((experiment, variant))
+}
+
+/// Resolve the user's persisted review keyboard variant, falling back to the bucket
+///
+/// Database failures are logged and never interrupt the user flow.
+pub async fn resolve_review_keyboard_variant(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+) -> ReviewKeyboardVariant {
+    match assign_review_keyboard_variant(pool, telegram_id).await {
+        Ok((_, variant)) => variant,
+        Err(e) => {
+            warn!(telegram_id = %telegram_id, error = %e, "Failed to resolve review keyboard variant");
+            review_keyboard_variant(telegram_id)
+        }
+    }
+}
+
+/// Record a review funnel event tagged with the user's variant
+///
+/// Failures are logged and never interrupt the user flow.
+pub async fn track_review_funnel_event(pool: &PgPool, telegram_id: TelegramId, event: FunnelEvent) {
+    let result = async {
+        let (experiment, variant) = assign_review_keyboard_variant(pool, telegram_id).await?;
+        crate::db::record_review_funnel_event(
+            pool,
+            telegram_id,
+            &experiment.name,
+            variant.name(),
+            event.as_str(),
+        )
+        .await?;
+        crate::observability::record_review_funnel_metrics(
+            &experiment.name,
+            variant.name(),
+            event.as_str(),
+        );
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        warn!(telegram_id = %telegram_id, event = %event.as_str(), error = %e, "Failed to record review funnel event");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn experiment(weights: &[(&str, u32)]) -> ExperimentConfig {
+        ExperimentConfig {
+            name: "test_experiment".to_string(),
+            variants: weights
+                .iter()
+                .map(|(name, weight)| VariantWeight {
+                    name: name.to_string(),
+                    weight: *weight,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_bucket_is_stable_and_in_range() {
+        for id in [1, 42, 123_456_789, -5] {
+            let bucket = bucket_for_user("exp", TelegramId(id));
+            assert!(bucket < BUCKET_COUNT);
+            assert_eq!(bucket, bucket_for_user("exp", TelegramId(id)));
+        }
+
+        // Users are spread over both halves
+        let buckets: Vec<u32> = (0..1000)
+            .map(|id| bucket_for_user("exp", TelegramId(id)))
+            .collect();
+        let lower = buckets.iter().filter(|b| **b < 50).count();
+        assert!((350..=650).contains(&lower), "lower half got {}", lower);
+    }
+
+    #[test]
+    fn test_variant_for_bucket_boundaries() {
+        let config = experiment(&[("full", 30), ("compact", 70)]);
+        assert_eq!(config.variant_for_bucket(0), "full");
+        assert_eq!(config.variant_for_bucket(29), "full");
+        assert_eq!(config.variant_for_bucket(30), "compact");
+        assert_eq!(config.variant_for_bucket(99), "compact");
+    }
+
+    #[test]
+    fn test_validate_weights() {
+        assert!(experiment(&[("full", 50), ("compact", 50)])
+            .validate()
+            .is_ok());
+        assert!(experiment(&[("full", 50), ("compact", 40)])
+            .validate()
+            .is_err());
+        assert!(experiment(&[]).validate().is_err());
+        assert!(ExperimentsConfig::default()
+            .review_keyboard
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_repository_config_is_valid() {
+        let config = load_experiments_config(DEFAULT_EXPERIMENTS_CONFIG_PATH)
+            .expect("config/experiments.json should be valid");
+        for variant in &config.review_keyboard.variants {
+            assert!(
+                ReviewKeyboardVariant::from_name(&variant.name).is_some(),
+                "Unknown variant '{}'",
+                variant.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_variant_and_event_names() {
+        for variant in [ReviewKeyboardVariant::Full, ReviewKeyboardVariant::Compact] {
+            assert_eq!(
+                ReviewKeyboardVariant::from_name(variant.name()),
+                Some(variant)
+            );
+        }
+        assert_eq!(ReviewKeyboardVariant::from_name("unknown"), None);
+        assert_eq!(FunnelEvent::ReviewShown.as_str(), "review_shown");
+        assert_eq!(FunnelEvent::EditsMade.as_str(), "edits_made");
+        assert_eq!(FunnelEvent::Confirmed.as_str(), "confirmed");
+    }
+}
@@ -0,0 +1,197 @@
+//! # Extraction Feedback Module
+//!
+//! Measures extraction accuracy from what users correct during the ingredient review.
+//! Showing a review starts a tally of the extracted ingredients; edits and deletions
+//! made before confirming add to it. At confirm time the share of corrected
+//! ingredients goes to the `extraction_correction_ratio` histogram and the counts to
+//! the `extraction_feedback` table, per language.
+//!
+//! The tally lives in memory next to the dialogue state, which is in memory too, so
+//! a restart mid-review simply records nothing for that review. Only counts are
+//! kept: no recipe text and no user id reach the table.
+
+use lazy_static::lazy_static;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::db::TelegramId;
+
+/// Longest language code stored, matching the table column
+const MAX_LANGUAGE_CODE_LENGTH: usize = 10;
+
+lazy_static! {
+    /// Corrections of the review each user has open
+    static ref OPEN_REVIEWS: Mutex<HashMap<i64, ReviewCorrections>> = Mutex::new(HashMap::new());
+}
+
+/// Ingredients extracted for one review and how many the user corrected
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReviewCorrections {
+    pub extracted: usize,
+    pub edited: usize,
+    pub deleted: usize,
+}
+
+impl ReviewCorrections {
+    /// Share of extracted ingredients edited or deleted, between 0 and 1
+    ///
+    /// Editing the same ingredient twice counts twice, hence the cap.
+    pub fn correction_ratio(&self) -> f64 {
+        if self.extracted == 0 {
+            0.0
+        } else {
+            ((self.edited + self.deleted) as f64 / self.extracted as f64).min(1.0)
+        }
+    }
+
+    fn apply(&mut self, correction: Correction) {
+        match correction {
+            Correction::Edited => self.edited += 1,
+            Correction::Deleted => self.deleted += 1,
+            Correction::DeletionUndone => self.deleted = self.deleted.saturating_sub(1),
+        }
+    }
+}
+
+/// A change the user made to the extracted ingredients
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Correction {
+    Edited,
+    Deleted,
+    /// The last deletion was undone
+    DeletionUndone,
+}
+
+/// Start tallying a review of `extracted` ingredients, replacing any open one
+pub fn start_review(telegram_id: TelegramId, extracted: usize) {
+    let mut reviews = OPEN_REVIEWS.lock().unwrap_or_else(|e| e.into_inner());
+    reviews.insert(
+        telegram_id.0,
+        ReviewCorrections {
+            extracted,
+            ..Default::default()
+        },
+    );
+}
+
+/// Count a correction in the user's open review, if any
+pub fn record_correction(telegram_id: TelegramId, correction: Correction) {
+    let mut reviews = OPEN_REVIEWS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(review) = reviews.get_mut(&telegram_id.0) {
+        review.apply(correction);
+    }
+}
+
+/// Close the user's open review and return its tally
+pub fn take_review(telegram_id: TelegramId) -> Option<ReviewCorrections> {
+    OPEN_REVIEWS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&telegram_id.0)
+}
+
+/// Language label of the feedback: the primary subtag, "unknown" when missing
+pub fn feedback_language(language_code: Option<&str>) -> String {
+    let primary = language_code
+        .and_then(|code| code.split(['-', '_']).next())
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .unwrap_or("unknown");
+    primary
+        .chars()
+        .take(MAX_LANGUAGE_CODE_LENGTH)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Record the accuracy of the user's review as it is confirmed
+///
+/// Failures are logged and never interrupt the user flow.
+pub async fn record_confirmed_review(
+    pool: &PgPool,
+    telegram_id: TelegramId,
+    language_code: Option<&str>,
+) {
+    let Some(review) = take_review(telegram_id) else {
+        debug!(telegram_id = %telegram_id, "No open review to record extraction feedback for");
+        return;
+    };
+    if review.extracted == 0 {
+        return;
+    }
+
+    let language = feedback_language(language_code);
+    crate::observability::record_extraction_accuracy(
+        &language,
+        review.correction_ratio(),
+        review.extracted,
+    );
+    let count = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    if let Err(e) = crate::db::record_extraction_feedback(
+        pool,
+        &language,
+        count(review.extracted),
+        count(review.edited),
+        count(review.deleted),
+    )
+    .await
+    {
+        warn!(telegram_id = %telegram_id, error = %e, "Failed to record extraction feedback");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correction_ratio() {
+        let review = ReviewCorrections {
+            extracted: 8,
+            edited: 1,
+            deleted: 1,
+        };
+        assert!((review.correction_ratio() - 0.25).abs() < f64::EPSILON);
+
+        let over_edited = ReviewCorrections {
+            extracted: 2,
+            edited: 5,
+            deleted: 0,
+        };
+        assert_eq!(over_edited.correction_ratio(), 1.0);
+        assert_eq!(ReviewCorrections::default().correction_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_review_tally() {
+        let telegram_id = TelegramId(-4242);
+        record_correction(telegram_id, Correction::Edited);
+        assert_eq!(take_review(telegram_id), None);
+
+        start_review(telegram_id, 5);
+        record_correction(telegram_id, Correction::Edited);
+        record_correction(telegram_id, Correction::Deleted);
+        record_correction(telegram_id, Correction::Deleted);
+        record_correction(telegram_id, Correction::DeletionUndone);
+        assert_eq!(
+            take_review(telegram_id),
+            Some(ReviewCorrections {
+                extracted: 5,
+                edited: 1,
+                deleted: 1,
+            })
+        );
+        assert_eq!(take_review(telegram_id), None);
+    }
+
+    #[test]
+    fn test_feedback_language() {
+        assert_eq!(feedback_language(Some("fr")), "fr");
+        assert_eq!(feedback_language(Some("en-US")), "en");
+        assert_eq!(feedback_language(Some("PT_br")), "pt");
+        assert_eq!(feedback_language(Some("")), "unknown");
+        assert_eq!(feedback_language(None), "unknown");
+    }
+}
@@ -0,0 +1,446 @@
+//! # Extraction Reports Module
+//!
+//! Users can flag a bad extraction from the review screen or the "no ingredients"
+//! diagnostic. A report stores only context that helps reproduce the problem (a hash
+//! of the OCR text, the match count and the preprocessing strategy) plus an optional
+//! comment. The admin notification is redacted, and the original photo is only shared
+//! after the reporting user explicitly agrees.
+
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long the latest extraction of a chat can still be reported
+pub const REPORTABLE_EXTRACTION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Chats whose latest extraction is kept in memory for reporting
+const MAX_REMEMBERED_EXTRACTIONS: usize = 10_000;
+
+/// Longest comment stored with a report, in characters
+pub const MAX_REPORT_COMMENT_CHARS: usize = 500;
+
+/// Longest comment excerpt shown to the admin, in characters
+const ADMIN_COMMENT_EXCERPT_CHARS: usize = 200;
+
+/// Hex digits of the text hash shown to the admin
+const ADMIN_HASH_PREFIX_LEN: usize = 12;
+
+/// Placeholder for personal data removed from admin summaries
+const REDACTED: &str = "[redacted]";
+
+lazy_static! {
+    /// Latest reportable extraction per chat
+    static ref RECENT_EXTRACTIONS: Mutex<HashMap<i64, (Instant, ExtractionContext)>> =
+        Mutex::new(HashMap::new());
+
+    /// Emails, links, @handles and long digit runs (phone numbers, ids)
+    static ref PERSONAL_DATA_REGEX: Regex = Regex::new(
+        r"(?i)[\w.+-]+@[\w-]+\.[\w.-]+|https?://\S+|www\.\S+|@\w{3,}|\+?\d[\d \-]{5,}\d"
+    )
+    .expect("Invalid personal data regex pattern");
+}
+
+/// What a report knows about the extraction it is about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractionContext {
+    /// Random id also written to the processing logs
+    pub correlation_id: String,
+    /// SHA-256 of the OCR text, the text itself is never stored
+    pub extracted_text_hash: String,
+    pub match_count: usize,
+    /// Preprocessing strategy chosen for the image, when known
+    pub preprocessing_variant: Option<String>,
+    /// Telegram file id of the original image, only shared with consent
+    pub photo_file_id: Option<String>,
+    /// Photo kept with the recipe once saved, `None` for text and documents
+    #[serde(default)]
+    pub recipe_photo: Option<crate::db::RecipePhoto>,
+}
+
+impl ExtractionContext {
+    /// Build the context of a finished extraction with a fresh correlation id
+    pub fn new(
+        extracted_text: &str,
+        match_count: usize,
+        preprocessing_variant: Option<String>,
+        photo_file_id: Option<String>,
+    ) -> Self {
+        Self {
+            correlation_id: format!("{:016x}", rand::random::<u64>()),
+            extracted_text_hash: extracted_text_hash(extracted_text),
+            match_count,
+            preprocessing_variant,
+            photo_file_id,
+            recipe_photo: None,
+        }
+    }
+
+    /// Keep `photo` with the recipe saved from this extraction
+    pub fn with_recipe_photo(mut self, photo: Option<crate::db::RecipePhoto>) -> Self {
+        self.recipe_photo = photo;
+        self
+    }
+}
+
+/// Hex SHA-256 of the OCR text
+pub fn extracted_text_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// Remember the latest extraction of a chat so it can be reported later
+pub fn remember_extraction(chat_id: i64, context: ExtractionContext) {
+    let mut recent = RECENT_EXTRACTIONS.lock();
+    if recent.len() >= MAX_REMEMBERED_EXTRACTIONS && !recent.contains_key(&chat_id) {
+        recent.retain(|_, (stored_at, _)| stored_at.elapsed() < REPORTABLE_EXTRACTION_TTL);
+        if recent.len() >= MAX_REMEMBERED_EXTRACTIONS {
+            if let Some(oldest) = recent
+                .iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(id, _)| *id)
+            {
+                recent.remove(&oldest);
+            }
+        }
+    }
+    recent.insert(chat_id, (Instant::now(), context));
+}
+
+/// Latest extraction of a chat, if it is still reportable
+pub fn recent_extraction(chat_id: i64) -> Option<ExtractionContext> {
+    let mut recent = RECENT_EXTRACTIONS.lock();
+    match recent.get(&chat_id) {
+        Some((stored_at, context)) if stored_at.elapsed() < REPORTABLE_EXTRACTION_TTL => {
+            Some(context.clone())
+        }
+        Some(_) => {
+            recent.remove(&chat_id);
+            None
+        }
+        None => None,
+    }
+}
+
+/// Limit a user comment to what is stored with a report
+///
+/// Returns `None` for blank comments.
+pub fn normalize_report_comment(comment: &str) -> Option<String> {
+    let comment = comment.trim();
+    if comment.is_empty() {
+        None
+    } else {
+        Some(comment.chars().take(MAX_REPORT_COMMENT_CHARS).collect())
+    }
+}
+
+/// Whether the reporting user agreed to share the original photo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhotoConsent {
+    NotRequested,
+    /// An admin asked, waiting for the user's answer
+    Requested,
+    Granted,
+    Declined,
+}
+
+/// Steps of the photo consent flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsentEvent {
+    AdminRequested,
+    UserGranted,
+    UserDeclined,
+}
+
+impl PhotoConsent {
+    /// Consent name stored in `extraction_reports.photo_consent`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PhotoConsent::NotRequested => "not_requested",
+            PhotoConsent::Requested => "requested",
+            PhotoConsent::Granted => "granted",
+            PhotoConsent::Declined => "declined",
+        }
+    }
+
+    /// Parse a stored consent name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "not_requested" => Some(PhotoConsent::NotRequested),
+            "requested" => Some(PhotoConsent::Requested),
+            "granted" => Some(PhotoConsent::Granted),
+            "declined" => Some(PhotoConsent::Declined),
+            _ => None,
+        }
+    }
+
+    /// State after `event`, or `None` when the event is not allowed now
+    ///
+    /// The user is asked at most once and can only answer a pending request, so a
+    /// replayed or forged button can never share a photo on its own.
+    pub fn apply(self, event: ConsentEvent) -> Option<PhotoConsent> {
+        match (self, event) {
+            (PhotoConsent::NotRequested, ConsentEvent::AdminRequested) => {
+                Some(PhotoConsent::Requested)
+            }
+            (PhotoConsent::Requested, ConsentEvent::UserGranted) => Some(PhotoConsent::Granted),
+            (PhotoConsent::Requested, ConsentEvent::UserDeclined) => Some(PhotoConsent::Declined),
+            _ => None,
+        }
+    }
+
+    /// Whether the photo may be forwarded to the admin
+    pub fn allows_photo_sharing(self) -> bool {
+        self == PhotoConsent::Granted
+    }
+}
+
+/// Callback data of the report buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportCallback {
+    /// "Report a problem" on the review screen or diagnostic
+    Report,
+    /// Send the report without a comment
+    SkipComment,
+    /// Admin asks the user for the photo of a report
+    RequestPhoto(i64),
+    /// User agrees to share the photo of a report
+    GrantPhoto(i64),
+    /// User refuses to share the photo of a report
+    DeclinePhoto(i64),
+}
+
+impl ReportCallback {
+    /// Callback data for the button
+    pub fn callback_data(self) -> String {
+        match self {
+            ReportCallback::Report => "report_problem".to_string(),
+            ReportCallback::SkipComment => "report_skip_comment".to_string(),
+            ReportCallback::RequestPhoto(id) => format!("report_photo_request:{}", id),
+            ReportCallback::GrantPhoto(id) => format!("report_photo_grant:{}", id),
+            ReportCallback::DeclinePhoto(id) => format!("report_photo_decline:{}", id),
+        }
+    }
+
+    /// Parse report callback data
+    pub fn from_callback_data(data: &str) -> Option<Self> {
+        match data {
+            "report_problem" => return Some(ReportCallback::Report),
+            "report_skip_comment" => return Some(ReportCallback::SkipComment),
+            _ => {}
+        }
+        let (action, id) = data.split_once(':')?;
+        let id = id.parse().ok()?;
+        match action {
+            "report_photo_request" => Some(ReportCallback::RequestPhoto(id)),
+            "report_photo_grant" => Some(ReportCallback::GrantPhoto(id)),
+            "report_photo_decline" => Some(ReportCallback::DeclinePhoto(id)),
+            _ => None,
+        }
+    }
+}
+
+/// Report fields that may be shown to an admin
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminReportSummary {
+    pub report_id: i64,
+    /// Only the last digits of the reporter's Telegram id
+    pub masked_user: String,
+    pub correlation_id: String,
+    pub match_count: i32,
+    pub preprocessing_variant: String,
+    pub text_hash_prefix: String,
+    /// Comment excerpt with personal data removed
+    pub comment: Option<String>,
+    pub has_photo: bool,
+}
+
+impl AdminReportSummary {
+    /// Redacted view of a stored report
+    pub fn from_report(report: &crate::db::ExtractionReport) -> Self {
+        Self {
+            report_id: report.id,
+            masked_user: mask_telegram_id(report.telegram_id.0),
+            correlation_id: report.correlation_id.clone(),
+            match_count: report.match_count,
+            preprocessing_variant: report
+                .preprocessing_variant
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+            text_hash_prefix: report
+                .extracted_text_hash
+                .chars()
+                .take(ADMIN_HASH_PREFIX_LEN)
+                .collect(),
+            comment: report.comment.as_deref().map(redact_comment),
+            has_photo: report.photo_file_id.is_some(),
+        }
+    }
+}
+
+/// Keep only the last two digits of a Telegram id
+pub fn mask_telegram_id(telegram_id: i64) -> String {
+    let digits = telegram_id.unsigned_abs().to_string();
+    let visible = &digits[digits.len().saturating_sub(2)..];
+    format!("•••{}", visible)
+}
+
+/// Remove contact details and long numbers from a comment and shorten it
+pub fn redact_comment(comment: &str) -> String {
+    let redacted = PERSONAL_DATA_REGEX.replace_all(comment, REDACTED);
+    let mut excerpt: String = redacted.chars().take(ADMIN_COMMENT_EXCERPT_CHARS).collect();
+    if redacted.chars().count() > ADMIN_COMMENT_EXCERPT_CHARS {
+        excerpt.push('…');
+    }
+    excerpt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{ExtractionReport, TelegramId};
+
+    fn report(comment: Option<&str>) -> ExtractionReport {
+        ExtractionReport {
+            id: 7,
+            telegram_id: TelegramId(123456789),
+            correlation_id: "00ff00ff00ff00ff".to_string(),
+            extracted_text_hash: extracted_text_hash("2 cups flour"),
+            match_count: 1,
+            preprocessing_variant: Some("medium_quality_standard".to_string()),
+            comment: comment.map(str::to_string),
+            photo_file_id: Some("AgACAgQAAxkBAAI".to_string()),
+            photo_consent: PhotoConsent::NotRequested.as_str().to_string(),
+        }
+    }
+
+    #[test]
+    fn test_consent_flow_happy_paths() {
+        let requested = PhotoConsent::NotRequested
+            .apply(ConsentEvent::AdminRequested)
+            .unwrap();
+        assert_eq!(requested, PhotoConsent::Requested);
+        assert!(!requested.allows_photo_sharing());
+
+        let granted = requested.apply(ConsentEvent::UserGranted).unwrap();
+        assert!(granted.allows_photo_sharing());
+
+        let declined = requested.apply(ConsentEvent::UserDeclined).unwrap();
+        assert!(!declined.allows_photo_sharing());
+    }
+
+    #[test]
+    fn test_consent_requires_pending_request() {
+        // The user can't answer a request that was never made
+        assert_eq!(
+            PhotoConsent::NotRequested.apply(ConsentEvent::UserGranted),
+            None
+        );
+        // The user is asked only once
+        assert_eq!(
+            PhotoConsent::Requested.apply(ConsentEvent::AdminRequested),
+            None
+        );
+        assert_eq!(
+            PhotoConsent::Declined.apply(ConsentEvent::AdminRequested),
+            None
+        );
+        // Answers are final
+        assert_eq!(
+            PhotoConsent::Declined.apply(ConsentEvent::UserGranted),
+            None
+        );
+        assert_eq!(
+            PhotoConsent::Granted.apply(ConsentEvent::UserDeclined),
+            None
+        );
+        assert!(!PhotoConsent::NotRequested.allows_photo_sharing());
+    }
+
+    #[test]
+    fn test_consent_and_callback_round_trip() {
+        for consent in [
+            PhotoConsent::NotRequested,
+            PhotoConsent::Requested,
+            PhotoConsent::Granted,
+            PhotoConsent::Declined,
+        ] {
+            assert_eq!(PhotoConsent::from_name(consent.as_str()), Some(consent));
+        }
+        for callback in [
+            ReportCallback::Report,
+            ReportCallback::SkipComment,
+            ReportCallback::RequestPhoto(42),
+            ReportCallback::GrantPhoto(42),
+            ReportCallback::DeclinePhoto(42),
+        ] {
+            assert_eq!(
+                ReportCallback::from_callback_data(&callback.callback_data()),
+                Some(callback)
+            );
+        }
+        assert_eq!(
+            ReportCallback::from_callback_data("report_photo_grant:x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_admin_summary_is_redacted() {
+        let summary = AdminReportSummary::from_report(&report(Some(
+            "Missed the butter, mail me at jane.doe@example.com or +33 6 12 34 56 78, \
+             see https://example.com/photo and ping @janedoe",
+        )));
+
+        assert_eq!(summary.masked_user, "•••89");
+        assert_eq!(summary.text_hash_prefix.len(), ADMIN_HASH_PREFIX_LEN);
+        assert!(summary.has_photo);
+
+        let comment = summary.comment.unwrap();
+        assert!(comment.starts_with("Missed the butter"));
+        for leaked in ["jane.doe", "example.com", "12 34", "@janedoe"] {
+            assert!(!comment.contains(leaked), "{leaked} leaked into {comment}");
+        }
+        assert_eq!(comment.matches(REDACTED).count(), 4);
+    }
+
+    #[test]
+    fn test_admin_summary_keeps_recipe_quantities_and_truncates() {
+        let comment = redact_comment("It read 250 g as 2509");
+        assert_eq!(comment, "It read 250 g as 2509");
+
+        let long = "a".repeat(ADMIN_COMMENT_EXCERPT_CHARS + 50);
+        let excerpt = redact_comment(&long);
+        assert_eq!(excerpt.chars().count(), ADMIN_COMMENT_EXCERPT_CHARS + 1);
+        assert!(excerpt.ends_with('…'));
+
+        assert_eq!(AdminReportSummary::from_report(&report(None)).comment, None);
+    }
+
+    #[test]
+    fn test_normalize_report_comment() {
+        assert_eq!(normalize_report_comment("   "), None);
+        assert_eq!(
+            normalize_report_comment("  wrong unit "),
+            Some("wrong unit".to_string())
+        );
+        let long = "é".repeat(MAX_REPORT_COMMENT_CHARS + 1);
+        assert_eq!(
+            normalize_report_comment(&long).unwrap().chars().count(),
+            MAX_REPORT_COMMENT_CHARS
+        );
+    }
+
+    #[test]
+    fn test_recent_extraction_is_per_chat() {
+        let context = ExtractionContext::new("2 cups flour", 1, None, None);
+        assert_eq!(context.correlation_id.len(), 16);
+        assert_eq!(context.extracted_text_hash.len(), 64);
+
+        remember_extraction(-9_001, context.clone());
+        assert_eq!(recent_extraction(-9_001), Some(context));
+        assert_eq!(recent_extraction(-9_002), None);
+    }
+}
@@ -0,0 +1,393 @@
+//! # Recipe Extractor
+//!
+//! Library-level entry point to the extraction pipeline, usable without Telegram.
+//!
+//! [`RecipeExtractor`] owns an [`OcrConfig`] and a [`MeasurementConfig`], along with
+//! the Tesseract instance pool and the circuit breaker OCR calls go through. Its
+//! [`MeasurementDetector`] is built for each text from the current measurement units,
+//! so units reloaded with `reload_measurement_units` apply without a restart. It reads the text of a recipe photo with the preprocessing and
+//! OCR pipeline, finds the ingredient measurements in it, and recovers anomalous
+//! quantities by re-reading their region of the photo.
+//!
+//! The bot reads photos through the same extractor, so a batch tool built on it gets
+//! the same results as the bot.
+//!
+//! ```rust,no_run
+//! use just_ingredients::extractor::RecipeExtractor;
+//! use just_ingredients::ocr_config::OcrConfig;
+//! use just_ingredients::text_processing::MeasurementConfig;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let extractor = RecipeExtractor::new(OcrConfig::default(), MeasurementConfig::default())?;
+//! let result = extractor.extract_from_image_path("recipe.jpg").await?;
+//! for ingredient in &result.ingredients {
+//!     println!("{} {}", ingredient.quantity, ingredient.ingredient_name);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use tracing::{debug, error, info, warn};
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::errors::{AppError, AppResult};
+use crate::instance_manager::OcrInstanceManager;
+use crate::ocr::{
+    extract_hocr_from_image, map_measurement_to_bbox, parse_hocr_to_lines, perform_constrained_ocr,
+    OcrConfidence,
+};
+use crate::ocr_config::OcrConfig;
+use crate::ocr_errors::OcrError;
+use crate::preprocessing::{
+    crop_measurement_region, preprocess_measurement_region, CroppedImageResult,
+};
+use crate::text_processing::{MeasurementConfig, MeasurementDetector, MeasurementMatch};
+
+/// Time spent in each step of an extraction
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ExtractionTiming {
+    /// Reading the text of the image, zero for text input
+    pub ocr: Duration,
+    /// Finding the measurements, including quantity recovery
+    pub parsing: Duration,
+    /// Whole extraction
+    pub total: Duration,
+}
+
+/// Result of an extraction
+#[derive(Debug, Clone)]
+pub struct ExtractionResult {
+    /// Text the ingredients were read from
+    pub text: String,
+    /// Ingredient measurements found in the text
+    pub ingredients: Vec<MeasurementMatch>,
+    /// Quality of the OCR result, `None` for text input
+    pub confidence: Option<OcrConfidence>,
+    /// Time spent in each step
+    pub timing: ExtractionTiming,
+}
+
+/// Extracts ingredient measurements from recipe photos and text
+pub struct RecipeExtractor {
+    ocr_config: OcrConfig,
+    measurement_config: MeasurementConfig,
+    instance_manager: OcrInstanceManager,
+    circuit_breaker: CircuitBreaker,
+}
+
+impl RecipeExtractor {
+    /// Create an extractor with its own instance pool and circuit breaker
+    ///
+    /// Both configurations are validated first.
+    pub fn new(ocr_config: OcrConfig, measurement_config: MeasurementConfig) -> AppResult<Self> {
+        ocr_config.validate()?;
+        measurement_config.validate()?;
+        MeasurementDetector::with_config(measurement_config.clone())
+            .map_err(|e| AppError::Config(format!("invalid measurement pattern: {}", e)))?;
+        let circuit_breaker = CircuitBreaker::new(ocr_config.recovery.clone());
+        Ok(Self {
+            ocr_config,
+            measurement_config,
+            instance_manager: OcrInstanceManager::default(),
+            circuit_breaker,
+        })
+    }
+
+    /// OCR configuration images are read with
+    pub fn ocr_config(&self) -> &OcrConfig {
+        &self.ocr_config
+    }
+
+    /// Pool of Tesseract instances images are read with
+    pub fn instance_manager(&self) -> &OcrInstanceManager {
+        &self.instance_manager
+    }
+
+    /// Circuit breaker OCR calls go through
+    pub fn circuit_breaker(&self) -> &CircuitBreaker {
+        &self.circuit_breaker
+    }
+
+    /// Read the text of an image, without looking for ingredients
+    pub async fn read_text(&self, image_path: &str) -> Result<(String, OcrConfidence), OcrError> {
+        crate::ocr::extract_text_from_image(
+            image_path,
+            &self.ocr_config,
+            &self.instance_manager,
+            &self.circuit_breaker,
+        )
+        .await
+    }
+
+    /// Find the ingredient measurements in a text
+    ///
+    /// With the path of the image the text was read from, anomalous quantities are
+    /// re-read from their region of the image.
+    pub async fn find_ingredients(
+        &self,
+        text: &str,
+        image_path: Option<&str>,
+    ) -> Vec<MeasurementMatch> {
+        // Built from the current units snapshot, so a reload reaches the next text
+        let detector = match MeasurementDetector::with_config(self.measurement_config.clone()) {
+            Ok(detector) => detector,
+            Err(e) => {
+                error!(error = %e, "Failed to build measurement detector");
+                return Vec::new();
+            }
+        };
+        let mut matches = detector.extract_ingredient_measurements(text);
+        info!(
+            matches_found = matches.len(),
+            "Initial measurement detection completed"
+        );
+        if let Some(image_path) = image_path {
+            self.recover_quantities(image_path, &mut matches).await;
+        }
+        matches
+    }
+
+    /// Read a recipe photo and find the ingredient measurements in it
+    pub async fn extract_from_image_path(
+        &self,
+        image_path: &str,
+    ) -> Result<ExtractionResult, OcrError> {
+        let start = Instant::now();
+        let (text, confidence) = self.read_text(image_path).await?;
+        let ocr = start.elapsed();
+        let ingredients = if text.is_empty() {
+            Vec::new()
+        } else {
+            self.find_ingredients(&text, Some(image_path)).await
+        };
+        let total = start.elapsed();
+        Ok(ExtractionResult {
+            text,
+            ingredients,
+            confidence: Some(confidence),
+            timing: ExtractionTiming {
+                ocr,
+                parsing: total - ocr,
+                total,
+            },
+        })
+    }
+
+    /// Find the ingredient measurements in a recipe text
+    pub async fn extract_from_text(&self, text: &str) -> ExtractionResult {
+        let start = Instant::now();
+        let ingredients = self.find_ingredients(text, None).await;
+        let total = start.elapsed();
+        ExtractionResult {
+            text: text.to_string(),
+            ingredients,
+            confidence: None,
+            timing: ExtractionTiming {
+                ocr: Duration::ZERO,
+                parsing: total,
+                total,
+            },
+        }
+    }
+
+    /// Try to recover the quantities flagged for confirmation, keeping the flag when it fails
+    async fn recover_quantities(&self, image_path: &str, matches: &mut [MeasurementMatch]) {
+        // Count anomalies before recovery
+        let anomalies_before = matches
+            .iter()
+            .filter(|m| m.requires_quantity_confirmation)
+            .count();
+
+        if anomalies_before == 0 {
+            debug!("No anomalous measurements detected, skipping automated recovery");
+            return;
+        }
+
+        info!(
+            anomalies = anomalies_before,
+            "Detected anomalous measurements, attempting automated recovery"
+        );
+
+        // Attempt automated recovery for each anomalous measurement
+        for measurement_match in matches.iter_mut() {
+            if !measurement_match.requires_quantity_confirmation {
+                continue;
+            }
+            match attempt_automated_quantity_recovery(
+                image_path,
+                measurement_match,
+                &self.ocr_config,
+                &self.instance_manager,
+                &self.circuit_breaker,
+            )
+            .await
+            {
+                Ok(true) => {
+                    info!(
+                        ingredient = %measurement_match.ingredient_name,
+                        "Successfully recovered quantity automatically"
+                    );
+                }
+                Ok(false) => {
+                    debug!(
+                        ingredient = %measurement_match.ingredient_name,
+                        "Automated recovery failed, keeping anomaly for manual confirmation"
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        ingredient = %measurement_match.ingredient_name,
+                        error = %e,
+                        "Automated recovery encountered error, keeping anomaly for manual confirmation"
+                    );
+                }
+            }
+        }
+
+        // Count anomalies after recovery
+        let anomalies_after = matches
+            .iter()
+            .filter(|m| m.requires_quantity_confirmation)
+            .count();
+
+        info!(
+            recovered = anomalies_before - anomalies_after,
+            remaining_anomalies = anomalies_after,
+            "Automated quantity recovery completed"
+        );
+    }
+}
+
+/// Attempts automated recovery of anomalous quantity measurements using targeted re-OCR
+///
+/// This function implements the complete automated recovery pipeline:
+/// 1. Extract HOCR from the original image to get spatial data
+/// 2. Map the anomalous measurement to its bounding box
+/// 3. Crop the image around the measurement region
+/// 4. Apply targeted preprocessing for quantity recognition
+/// 5. Run constrained OCR optimized for numbers and fractions
+/// 6. Update the measurement match if recovery succeeds
+async fn attempt_automated_quantity_recovery(
+    image_path: &str,
+    measurement_match: &mut MeasurementMatch,
+    ocr_config: &OcrConfig,
+    instance_manager: &OcrInstanceManager,
+    circuit_breaker: &CircuitBreaker,
+) -> Result<bool, OcrError> {
+    info!(
+        line_number = measurement_match.line_number,
+        ingredient = %measurement_match.ingredient_name,
+        "Attempting automated quantity recovery for anomalous measurement"
+    );
+
+    // Step 1: Extract HOCR from the original image
+    let hocr_text =
+        extract_hocr_from_image(image_path, ocr_config, instance_manager, circuit_breaker).await?;
+
+    // Step 2: Parse HOCR to get line bounding boxes
+    let hocr_lines = parse_hocr_to_lines(&hocr_text)?;
+
+    // Step 3: Map the measurement to its bounding box
+    let bbox = map_measurement_to_bbox(measurement_match, &hocr_lines).ok_or_else(|| {
+        OcrError::Extraction(format!(
+            "Could not map measurement to bounding box for line {}",
+            measurement_match.line_number
+        ))
+    })?;
+
+    // Step 4: Load the original image for cropping
+    let _original_image = image::open(image_path)
+        .map_err(|e| OcrError::Validation(format!("Failed to load image for cropping: {}", e)))?;
+
+    // Step 5: Crop the measurement region
+    let CroppedImageResult {
+        image: cropped_image,
+        ..
+    } = crop_measurement_region(image_path, &bbox)
+        .map_err(|e| OcrError::Extraction(format!("Failed to crop measurement region: {}", e)))?;
+
+    // Step 6: Apply targeted preprocessing
+    let preprocessed_image = preprocess_measurement_region(&cropped_image).map_err(|e| {
+        OcrError::Extraction(format!("Failed to preprocess measurement region: {}", e))
+    })?;
+
+    // Step 7: Run constrained OCR
+    let constrained_result =
+        perform_constrained_ocr(&preprocessed_image.image, instance_manager, ocr_config).await?;
+
+    // Step 8: Validate and update the measurement if recovery succeeded
+    if is_valid_recovered_quantity(&constrained_result.text) {
+        info!(
+            original_quantity = %measurement_match.quantity,
+            recovered_quantity = %constrained_result.text,
+            confidence = constrained_result.confidence,
+            "Automated quantity recovery succeeded"
+        );
+
+        // Update the measurement match with recovered quantity
+        measurement_match.quantity = constrained_result.text.clone();
+        measurement_match.requires_quantity_confirmation = false;
+
+        Ok(true) // Recovery succeeded
+    } else {
+        debug!(
+            recovered_text = %constrained_result.text,
+            confidence = constrained_result.confidence,
+            "Automated quantity recovery produced invalid result, keeping original anomaly"
+        );
+        Ok(false) // Recovery failed, keep original anomaly
+    }
+}
+
+/// Validates if a recovered quantity string is acceptable for automated use
+pub fn is_valid_recovered_quantity(text: &str) -> bool {
+    let text = text.trim();
+
+    // Must not be empty
+    if text.is_empty() {
+        return false;
+    }
+
+    // Must contain at least one digit
+    if !text.chars().any(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    // Must not contain letters (except in valid contexts like "1st", "2nd")
+    let has_letters = text.chars().any(|c| c.is_ascii_alphabetic());
+    if has_letters {
+        // Allow ordinal numbers like "1st", "2nd", "3rd" but not other letters
+        let valid_ordinals = ["1st", "2nd", "3rd"];
+        if !valid_ordinals.contains(&text) {
+            return false;
+        }
+    }
+
+    // Must be a reasonable length (1-10 characters for quantities)
+    if text.len() > 10 {
+        return false;
+    }
+
+    // Must be parseable as a valid number or fraction, or be a valid ordinal
+    let is_valid_ordinal = ["1st", "2nd", "3rd"].contains(&text);
+    if text.parse::<f64>().is_err() && !is_valid_fraction(text) && !is_valid_ordinal {
+        return false;
+    }
+
+    true
+}
+
+/// Checks if a string represents a valid fraction (like "1/2", "3/4", etc.)
+pub fn is_valid_fraction(text: &str) -> bool {
+    if let Some(slash_pos) = text.find('/') {
+        let numerator = &text[..slash_pos];
+        let denominator = &text[slash_pos + 1..];
+
+        // Both parts must be numeric and denominator must not be zero
+        numerator.parse::<u32>().is_ok() && denominator.parse::<u32>().is_ok() && denominator != "0"
+    } else {
+        false
+    }
+}
@@ -0,0 +1,295 @@
+//! # Import Jobs Module
+//!
+//! Resumable processing of large document imports. Rows are committed in fixed-size
+//! batches, each in its own transaction together with the job's progress, so a bot
+//! restart mid-import leaves a consistent committed prefix the user can resume from.
+//!
+//! Every row is claimed by its content hash before it is written, which makes
+//! re-processing an already-committed prefix (after "start over" or a resend of the
+//! same file) a no-op instead of a source of duplicates.
+
+use anyhow::{Context, Result};
+use sqlx::postgres::PgPool;
+use std::future::Future;
+use std::ops::Range;
+use tracing::debug;
+
+use crate::db::{advance_import_job, claim_import_rows, finish_import_job, ImportJob};
+
+/// Number of rows committed per transaction
+pub const IMPORT_BATCH_SIZE: usize = 500;
+
+/// Edit the progress message after this many committed batches
+pub const PROGRESS_UPDATE_EVERY_BATCHES: usize = 4;
+
+/// Lifecycle of an import job as stored in `import_jobs.status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportJobStatus {
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+impl ImportJobStatus {
+    /// Status name stored in the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportJobStatus::InProgress => "in_progress",
+            ImportJobStatus::Completed => "completed",
+            ImportJobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Parse a stored status name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "in_progress" => Some(ImportJobStatus::InProgress),
+            "completed" => Some(ImportJobStatus::Completed),
+            "cancelled" => Some(ImportJobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// What to do with a file that has an unfinished import job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportResumeChoice {
+    Resume,
+    StartOver,
+    Cancel,
+}
+
+impl ImportResumeChoice {
+    /// Callback data for the resume prompt buttons
+    pub fn callback_data(self) -> &'static str {
+        match self {
+            ImportResumeChoice::Resume => "import_resume",
+            ImportResumeChoice::StartOver => "import_start_over",
+            ImportResumeChoice::Cancel => "import_cancel",
+        }
+    }
+
+    /// Parse resume prompt callback data
+    pub fn from_callback_data(data: &str) -> Option<Self> {
+        match data {
+            "import_resume" => Some(ImportResumeChoice::Resume),
+            "import_start_over" => Some(ImportResumeChoice::StartOver),
+            "import_cancel" => Some(ImportResumeChoice::Cancel),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of committing one batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchOutcome {
+    /// Rows written by this batch
+    pub inserted: usize,
+    /// Rows skipped because an earlier run already committed them, or the writer
+    /// found them already saved
+    pub skipped: usize,
+}
+
+/// Writes the new rows of a batch on the batch's transaction
+pub trait ImportBatchWriter<T> {
+    /// Write the rows, returning how many were actually written
+    fn write_rows(
+        &self,
+        conn: &mut sqlx::PgConnection,
+        rows: &[&T],
+    ) -> impl Future<Output = Result<usize>> + Send;
+}
+
+/// Stable content hash used to recognise a row that was already committed
+///
+/// Surrounding whitespace is ignored so re-exported files with trailing spaces still match.
+pub fn row_content_hash(row: &str) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let row = row.trim();
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in row.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    format!("{:016x}{:08x}", hash, row.len())
+}
+
+/// Row to resume from, clamped to the rows actually present in the file
+pub fn resume_offset(rows_processed: i64, total_rows: usize) -> usize {
+    usize::try_from(rows_processed.max(0))
+        .unwrap_or(usize::MAX)
+        .min(total_rows)
+}
+
+/// Row ranges of the batches still to process, starting at `start`
+pub fn batch_ranges(total_rows: usize, start: usize, batch_size: usize) -> Vec<Range<usize>> {
+    let batch_size = batch_size.max(1);
+    (start.min(total_rows)..total_rows)
+        .step_by(batch_size)
+        .map(|batch_start| batch_start..(batch_start + batch_size).min(total_rows))
+        .collect()
+}
+
+/// Whether the progress message should be edited after the given batch (1-based)
+pub fn should_report_progress(batches_committed: usize, is_last_batch: bool) -> bool {
+    is_last_batch || batches_committed.is_multiple_of(PROGRESS_UPDATE_EVERY_BATCHES)
+}
+
+/// Commit one batch of an import job
+///
+/// Claims the rows' content hashes, writes only the unclaimed rows and advances the
+/// job to `range.end`, all in one transaction.
+pub async fn commit_import_batch<T, W>(
+    pool: &PgPool,
+    job: &ImportJob,
+    rows: &[T],
+    range: Range<usize>,
+    row_hash: impl Fn(&T) -> String,
+    writer: &W,
+) -> Result<BatchOutcome>
+where
+    T: Sync,
+    W: ImportBatchWriter<T>,
+{
+    let batch = &rows[range.clone()];
+    let hashes: Vec<String> = batch.iter().map(&row_hash).collect();
+
+    let mut tx = pool
+        .begin()
+        .await
+        .context("Failed to start import batch transaction")?;
+
+    // Taking each claim once also skips rows repeated within the batch
+    let mut claimed = claim_import_rows(&mut tx, job.id, &hashes).await?;
+    let new_rows: Vec<&T> = batch
+        .iter()
+        .zip(&hashes)
+        .filter(|(_, hash)| claimed.remove(*hash))
+        .map(|(row, _)| row)
+        .collect();
+
+    let inserted = writer.write_rows(&mut tx, &new_rows).await?;
+    advance_import_job(&mut tx, job.id, range.end as i64).await?;
+
+    tx.commit().await.context("Failed to commit import batch")?;
+
+    let outcome = BatchOutcome {
+        inserted,
+        skipped: batch.len() - inserted,
+    };
+    debug!(
+        job_id = job.id,
+        rows_processed = range.end,
+        inserted = outcome.inserted,
+        skipped = outcome.skipped,
+        "Import batch committed"
+    );
+    Ok(outcome)
+}
+
+/// Commit the batches of an import job left after its committed prefix, then mark it completed
+///
+/// `on_progress` receives the rows committed so far each time [`should_report_progress`]
+/// says so. A failed batch leaves the job in progress, to be resumed later.
+pub async fn run_import_job<T, W, H, F, Fut>(
+    pool: &PgPool,
+    job: &ImportJob,
+    rows: &[T],
+    row_hash: H,
+    writer: &W,
+    mut on_progress: F,
+) -> Result<BatchOutcome>
+where
+    T: Sync,
+    W: ImportBatchWriter<T>,
+    H: Fn(&T) -> String,
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let start = resume_offset(job.rows_processed, rows.len());
+    let ranges = batch_ranges(rows.len(), start, IMPORT_BATCH_SIZE);
+    let batch_count = ranges.len();
+
+    let mut total = BatchOutcome::default();
+    for (index, range) in ranges.into_iter().enumerate() {
+        let rows_processed = range.end;
+        let outcome = commit_import_batch(pool, job, rows, range, &row_hash, writer).await?;
+        total.inserted += outcome.inserted;
+        total.skipped += outcome.skipped;
+        if should_report_progress(index + 1, index + 1 == batch_count) {
+            on_progress(rows_processed).await;
+        }
+    }
+
+    finish_import_job(pool, job.id, ImportJobStatus::Completed.as_str()).await?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_content_hash_is_stable_and_trimmed() {
+        assert_eq!(
+            row_content_hash("2 cups flour"),
+            row_content_hash("  2 cups flour\n")
+        );
+        assert_ne!(
+            row_content_hash("2 cups flour"),
+            row_content_hash("3 cups flour")
+        );
+        assert_eq!(row_content_hash("x").len(), 24);
+    }
+
+    #[test]
+    fn test_resume_offset_math() {
+        assert_eq!(resume_offset(0, 1200), 0);
+        assert_eq!(resume_offset(500, 1200), 500);
+        assert_eq!(resume_offset(-3, 1200), 0);
+        // The file shrank since the job started
+        assert_eq!(resume_offset(1500, 1200), 1200);
+    }
+
+    #[test]
+    fn test_batch_ranges() {
+        assert_eq!(
+            batch_ranges(1200, 0, 500),
+            vec![0..500, 500..1000, 1000..1200]
+        );
+        assert_eq!(batch_ranges(1200, 500, 500), vec![500..1000, 1000..1200]);
+        assert_eq!(batch_ranges(1200, 1200, 500), Vec::<Range<usize>>::new());
+        assert_eq!(batch_ranges(0, 0, 500), Vec::<Range<usize>>::new());
+        assert_eq!(batch_ranges(3, 0, 0), vec![0..1, 1..2, 2..3]);
+    }
+
+    #[test]
+    fn test_should_report_progress() {
+        assert!(!should_report_progress(1, false));
+        assert!(should_report_progress(PROGRESS_UPDATE_EVERY_BATCHES, false));
+        assert!(should_report_progress(1, true));
+    }
+
+    #[test]
+    fn test_status_and_choice_round_trip() {
+        for status in [
+            ImportJobStatus::InProgress,
+            ImportJobStatus::Completed,
+            ImportJobStatus::Cancelled,
+        ] {
+            assert_eq!(ImportJobStatus::from_name(status.as_str()), Some(status));
+        }
+        for choice in [
+            ImportResumeChoice::Resume,
+            ImportResumeChoice::StartOver,
+            ImportResumeChoice::Cancel,
+        ] {
+            assert_eq!(
+                ImportResumeChoice::from_callback_data(choice.callback_data()),
+                Some(choice)
+            );
+        }
+    }
+}
@@ -1,7 +1,9 @@
 //! Ingredient editing module for converting between database and editing formats
 
 use crate::db::Ingredient;
+use crate::quantity::Quantity;
 use crate::text_processing::MeasurementMatch;
+use serde::{Deserialize, Serialize};
 
 /// Convert database ingredients to measurement matches for editing
 ///
@@ -12,17 +14,90 @@ pub fn ingredients_to_measurement_matches(ingredients: &[Ingredient]) -> Vec<Mea
         .iter()
         .enumerate()
         .map(|(i, ing)| MeasurementMatch {
-            quantity: ing.quantity.map_or("1".to_string(), |q| q.to_string()),
+            quantity: ing
+                .quantity
+                .and_then(Quantity::from_f64)
+                .map_or("1".to_string(), |q| q.to_string()),
             measurement: ing.unit.clone(),
             ingredient_name: ing.name.clone(),
             line_number: i, // Use array index as line number
             start_pos: 0,   // Not meaningful for database data
             end_pos: ing.name.len(),
             requires_quantity_confirmation: false, // Use name length as approximation
+            unit_dimension: ing.unit_dimension,
+            unit_system: ing.unit_system,
+            confidence: None,
+            quantity_max: None,
         })
         .collect()
 }
 
+/// Compact record of a saved ingredient, kept in dialogue state for change detection
+///
+/// Stores only the database id and a hash of the editable fields instead of a full
+/// copy of the ingredient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngredientSnapshot {
+    pub id: i64,
+    pub hash: u64,
+}
+
+impl IngredientSnapshot {
+    /// Snapshot a saved ingredient
+    pub fn from_ingredient(ingredient: &Ingredient) -> Self {
+        Self {
+            id: ingredient.id,
+            hash: ingredient_content_hash(
+                &ingredient.name,
+                ingredient.quantity.unwrap_or(1.0),
+                ingredient.unit.as_deref().unwrap_or(""),
+            ),
+        }
+    }
+
+    /// Check whether an edited ingredient still matches this snapshot
+    pub fn matches(&self, edited: &MeasurementMatch) -> bool {
+        self.hash
+            == ingredient_content_hash(
+                &edited.ingredient_name,
+                crate::validation::parse_quantity(&edited.quantity).unwrap_or(1.0),
+                edited.measurement.as_deref().unwrap_or(""),
+            )
+    }
+}
+
+/// Snapshot a list of saved ingredients, preserving order
+pub fn snapshot_ingredients(ingredients: &[Ingredient]) -> Vec<IngredientSnapshot> {
+    ingredients
+        .iter()
+        .map(IngredientSnapshot::from_ingredient)
+        .collect()
+}
+
+/// Hash the editable fields of an ingredient
+///
+/// Uses FNV-1a so hashes stay stable across builds and restarts.
+fn ingredient_content_hash(name: &str, quantity: f64, unit: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    // Adding 0.0 folds -0.0 into 0.0 so both hash the same
+    let quantity_bits = (quantity + 0.0).to_bits().to_le_bytes();
+
+    let mut hash = FNV_OFFSET_BASIS;
+    for byte in name
+        .as_bytes()
+        .iter()
+        .chain(&[0xff])
+        .chain(quantity_bits.iter())
+        .chain(unit.as_bytes())
+    {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
 /// Represents the changes needed to update ingredients
 #[derive(Debug, Clone)]
 pub struct IngredientChanges {
@@ -36,11 +111,11 @@ pub struct IngredientChanges {
 
 /// Detect what changed between original and edited ingredients
 ///
-/// This function compares the original database ingredients with the edited
-/// measurement matches to determine what operations need to be performed.
+/// This function compares snapshots of the original database ingredients with the
+/// edited measurement matches to determine what operations need to be performed.
 /// Assumes that edited ingredients are in the same order as original ingredients.
 pub fn detect_ingredient_changes(
-    original: &[Ingredient],
+    original: &[IngredientSnapshot],
     edited: &[MeasurementMatch],
 ) -> IngredientChanges {
     let mut changes = IngredientChanges {
@@ -53,24 +128,8 @@ pub fn detect_ingredient_changes(
     let min_len = original.len().min(edited.len());
 
     // Check for updates (ingredients that exist in both lists but have changed)
-    for i in 0..min_len {
-        let orig = &original[i];
-        let edit = &edited[i];
-
-        // Compare the key data
-        let orig_quantity = orig.quantity.unwrap_or(1.0);
-        let orig_unit = orig.unit.as_deref().unwrap_or("");
-        let orig_name = &orig.name;
-
-        let edit_quantity = edit.quantity.parse::<f64>().unwrap_or(1.0);
-        let edit_unit = edit.measurement.as_deref().unwrap_or("");
-        let edit_name = &edit.ingredient_name;
-
-        // If any data changed, mark as update
-        if (orig_quantity - edit_quantity).abs() > f64::EPSILON
-            || orig_unit != edit_unit
-            || orig_name != edit_name
-        {
+    for (orig, edit) in original.iter().zip(edited) {
+        if !orig.matches(edit) {
             changes.to_update.push((orig.id, edit.clone()));
         }
     }
@@ -88,10 +147,208 @@ pub fn detect_ingredient_changes(
     changes
 }
 
+/// Edited ingredients whose quantity cannot be read as a number
+///
+/// Saving keeps the previous quantity of such an ingredient, or stores none for a
+/// new one, so these are the lines to point out to the user.
+pub fn unparsed_quantities(edited: &[MeasurementMatch]) -> Vec<&MeasurementMatch> {
+    edited
+        .iter()
+        .filter(|ingredient| {
+            !ingredient.quantity.trim().is_empty()
+                && crate::validation::parse_quantity(&ingredient.quantity).is_none()
+        })
+        .collect()
+}
+
+/// Ingredient names that are too generic to safely propagate across recipes
+const GENERIC_INGREDIENT_NAMES: &[&str] = &[
+    "salt", "sel", "pepper", "poivre", "water", "eau", "oil", "huile", "sugar", "sucre", "butter",
+    "beurre", "egg", "eggs", "oeuf", "oeufs", "milk", "lait", "flour", "farine",
+];
+
+/// Check whether an ingredient rename is worth offering across the user's other recipes
+///
+/// Names of 3 characters or fewer and generic pantry words are skipped, as are
+/// renames that only change letter case or surrounding whitespace.
+pub fn should_propagate_rename(old_name: &str, new_name: &str) -> bool {
+    let old_trimmed = old_name.trim();
+    let new_trimmed = new_name.trim();
+
+    if old_trimmed.chars().count() <= 3 || new_trimmed.is_empty() {
+        return false;
+    }
+
+    if old_trimmed.to_lowercase() == new_trimmed.to_lowercase() {
+        return false;
+    }
+
+    !GENERIC_INGREDIENT_NAMES.contains(&old_trimmed.to_lowercase().as_str())
+}
+
+/// Collect the ingredient renames from detected changes that qualify for propagation
+///
+/// Returns `(old_name, new_name)` pairs, without duplicates, in ingredient order.
+pub fn collect_propagatable_renames(
+    original: &[Ingredient],
+    changes: &IngredientChanges,
+) -> Vec<(String, String)> {
+    let mut renames: Vec<(String, String)> = Vec::new();
+
+    for (ingredient_id, new_data) in &changes.to_update {
+        let Some(orig) = original.iter().find(|ing| ing.id == *ingredient_id) else {
+            continue;
+        };
+
+        let old_name = orig.name.trim();
+        let new_name = new_data.ingredient_name.trim();
+
+        if should_propagate_rename(old_name, new_name)
+            && !renames
+                .iter()
+                .any(|(old, _)| old.to_lowercase() == old_name.to_lowercase())
+        {
+            renames.push((old_name.to_string(), new_name.to_string()));
+        }
+    }
+
+    renames
+}
+
+/// Pick the incoming ingredients whose names are not already in a recipe
+///
+/// Names are compared case-insensitively after trimming; an incoming name that
+/// appears twice is only kept once, in its first position.
+pub fn ingredients_missing_from(
+    existing: &[MeasurementMatch],
+    incoming: &[MeasurementMatch],
+) -> Vec<MeasurementMatch> {
+    let mut seen: std::collections::HashSet<String> = existing
+        .iter()
+        .map(|ing| ing.ingredient_name.trim().to_lowercase())
+        .collect();
+
+    incoming
+        .iter()
+        .filter(|ing| seen.insert(ing.ingredient_name.trim().to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+/// Remove the ingredient at `index`, returning it with its index so it can be restored
+pub fn delete_ingredient(
+    ingredients: &mut Vec<MeasurementMatch>,
+    index: usize,
+) -> Option<(usize, MeasurementMatch)> {
+    (index < ingredients.len()).then(|| (index, ingredients.remove(index)))
+}
+
+/// Put a deleted ingredient back where it was
+///
+/// The index is clamped to the end of the list.
+pub fn undo_ingredient_deletion(
+    ingredients: &mut Vec<MeasurementMatch>,
+    (index, ingredient): (usize, MeasurementMatch),
+) {
+    ingredients.insert(index.min(ingredients.len()), ingredient);
+}
+
+/// Step applied by a quantity adjustment button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantityStep {
+    MinusOne,
+    MinusHalf,
+    PlusHalf,
+    PlusOne,
+}
+
+impl QuantityStep {
+    /// All steps, in button order
+    pub const ALL: [QuantityStep; 4] = [
+        QuantityStep::MinusOne,
+        QuantityStep::MinusHalf,
+        QuantityStep::PlusHalf,
+        QuantityStep::PlusOne,
+    ];
+
+    /// Amount added to the quantity
+    pub fn delta(self) -> f64 {
+        match self {
+            QuantityStep::MinusOne => -1.0,
+            QuantityStep::MinusHalf => -0.5,
+            QuantityStep::PlusHalf => 0.5,
+            QuantityStep::PlusOne => 1.0,
+        }
+    }
+
+    /// Button label, also used in callback data
+    pub fn label(self) -> &'static str {
+        match self {
+            QuantityStep::MinusOne => "-1",
+            QuantityStep::MinusHalf => "-½",
+            QuantityStep::PlusHalf => "+½",
+            QuantityStep::PlusOne => "+1",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|step| step.label() == label)
+    }
+}
+
+/// Callback data of the quantity adjustment buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjustCallback {
+    /// Show the adjustment buttons for the ingredient at this index
+    Open(usize),
+    /// Apply a step to the ingredient at this index
+    Step(usize, QuantityStep),
+    /// Go back to the review keyboard
+    Done,
+}
+
+impl AdjustCallback {
+    /// Callback data for the button, at most 16 bytes
+    pub fn callback_data(self) -> String {
+        match self {
+            AdjustCallback::Open(index) => format!("adjust_{}", index),
+            AdjustCallback::Step(index, step) => format!("adjust_{}:{}", index, step.label()),
+            AdjustCallback::Done => "adjust_done".to_string(),
+        }
+    }
+
+    /// Parse quantity adjustment callback data
+    pub fn from_callback_data(data: &str) -> Option<Self> {
+        let rest = data.strip_prefix("adjust_")?;
+        if rest == "done" {
+            return Some(AdjustCallback::Done);
+        }
+        match rest.split_once(':') {
+            Some((index, step)) => Some(AdjustCallback::Step(
+                index.parse().ok()?,
+                QuantityStep::from_label(step)?,
+            )),
+            None => Some(AdjustCallback::Open(rest.parse().ok()?)),
+        }
+    }
+}
+
+/// Apply a step to a quantity written as text
+///
+/// Accepts the forms OCR and users produce ("2", "1.5", "1/2", "1 1/2", "2½") and
+/// clamps the result at zero. Returns `None` when the quantity is not a number,
+/// such as "a pinch" or a range.
+pub fn adjust_quantity(quantity: &str, step: QuantityStep) -> Option<String> {
+    let value = crate::recipe_scaling::parse_fractional_quantity(quantity)?;
+    Some(crate::unit_conversion::format_quantity(
+        (value + step.delta()).max(0.0),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::Ingredient;
+    use crate::db::{Ingredient, RecipeId, UserId};
     use chrono::Utc;
 
     fn create_test_ingredient(
@@ -102,13 +359,15 @@ mod tests {
     ) -> Ingredient {
         Ingredient {
             id,
-            user_id: 1,
-            recipe_id: Some(1),
+            user_id: UserId(1),
+            recipe_id: Some(RecipeId(1)),
             name: name.to_string(),
             quantity,
             unit: unit.map(|s| s.to_string()),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         }
     }
 
@@ -117,17 +376,22 @@ mod tests {
         let ingredients = vec![
             create_test_ingredient(1, "flour", Some(2.0), Some("cups")),
             create_test_ingredient(2, "sugar", Some(1.5), None),
+            create_test_ingredient(3, "milk", Some(1.0 / 3.0), Some("cup")),
+            create_test_ingredient(4, "salt", Some(0.2), Some("g")),
         ];
 
         let matches = ingredients_to_measurement_matches(&ingredients);
 
-        assert_eq!(matches.len(), 2);
+        assert_eq!(matches.len(), 4);
         assert_eq!(matches[0].quantity, "2");
         assert_eq!(matches[0].measurement, Some("cups".to_string()));
         assert_eq!(matches[0].ingredient_name, "flour");
-        assert_eq!(matches[1].quantity, "1.5");
+        assert_eq!(matches[1].quantity, "1 1/2");
         assert_eq!(matches[1].measurement, None);
         assert_eq!(matches[1].ingredient_name, "sugar");
+        // Fractions are shown as written instead of 0.3333333333333333
+        assert_eq!(matches[2].quantity, "1/3");
+        assert_eq!(matches[3].quantity, "0.2");
     }
 
     #[test]
@@ -146,6 +410,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 5,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "1".to_string(),
@@ -155,10 +423,14 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
-        let changes = detect_ingredient_changes(&original, &edited);
+        let changes = detect_ingredient_changes(&snapshot_ingredients(&original), &edited);
 
         // Should detect flour update and sugar->butter update
         assert_eq!(changes.to_update.len(), 2);
@@ -184,4 +456,284 @@ mod tests {
         assert_eq!(sugar_update.1.ingredient_name, "butter");
         assert_eq!(sugar_update.1.measurement, None);
     }
+
+    fn create_test_match(name: &str) -> MeasurementMatch {
+        MeasurementMatch {
+            quantity: "1".to_string(),
+            measurement: None,
+            ingredient_name: name.to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: name.len(),
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        }
+    }
+
+    #[test]
+    fn test_should_propagate_rename_skip_rules() {
+        assert!(should_propagate_rename("tumeric", "turmeric"));
+        assert!(should_propagate_rename("  tumeric ", "turmeric"));
+
+        // Short names are too ambiguous to match across recipes
+        assert!(!should_propagate_rename("egg", "eggs"));
+        assert!(!should_propagate_rename("oil", "olive oil"));
+
+        // Generic pantry words are skipped in any case
+        assert!(!should_propagate_rename("Salt", "sea salt"));
+        assert!(!should_propagate_rename("farine", "farine T55"));
+
+        // Case-only changes and empty replacements are not renames
+        assert!(!should_propagate_rename("Tumeric", "tumeric"));
+        assert!(!should_propagate_rename("tumeric", "   "));
+    }
+
+    #[test]
+    fn test_collect_propagatable_renames() {
+        let original = vec![
+            create_test_ingredient(1, "tumeric", Some(1.0), None),
+            create_test_ingredient(2, "salt", Some(1.0), None),
+            create_test_ingredient(3, "cumin", Some(1.0), None),
+            create_test_ingredient(4, "Tumeric", Some(2.0), None),
+        ];
+        let edited = vec![
+            create_test_match("turmeric"),
+            create_test_match("sea salt"),
+            create_test_match("cumin"),
+            create_test_match("turmeric"),
+        ];
+
+        let changes = detect_ingredient_changes(&snapshot_ingredients(&original), &edited);
+        let renames = collect_propagatable_renames(&original, &changes);
+
+        // Generic names are skipped, unchanged names are ignored and
+        // case-insensitive duplicates are only offered once
+        assert_eq!(
+            renames,
+            vec![("tumeric".to_string(), "turmeric".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detect_changes_with_snapshots() {
+        let original = vec![
+            create_test_ingredient(1, "flour", Some(2.0), Some("cups")),
+            create_test_ingredient(2, "eggs", None, None),
+            create_test_ingredient(3, "milk", Some(0.5), Some("l")),
+        ];
+        let snapshots = snapshot_ingredients(&original);
+
+        // Unchanged ingredients (including a missing quantity shown as "1") are not updates
+        let unchanged = ingredients_to_measurement_matches(&original);
+        let changes = detect_ingredient_changes(&snapshots, &unchanged);
+        assert!(changes.to_update.is_empty());
+        assert!(changes.to_add.is_empty());
+        assert!(changes.to_delete.is_empty());
+
+        // Unit-only change on one ingredient, and the last one removed
+        let mut edited = unchanged.clone();
+        edited[0].measurement = Some("g".to_string());
+        edited.pop();
+        let changes = detect_ingredient_changes(&snapshots, &edited);
+        assert_eq!(changes.to_update.len(), 1);
+        assert_eq!(changes.to_update[0].0, 1);
+        assert_eq!(changes.to_delete, vec![3]);
+
+        // Snapshots hash fields separately, so moving text between them is a change
+        assert_ne!(
+            ingredient_content_hash("ab", 1.0, "c"),
+            ingredient_content_hash("a", 1.0, "bc")
+        );
+        assert_eq!(
+            ingredient_content_hash("salt", 0.0, ""),
+            ingredient_content_hash("salt", -0.0, "")
+        );
+    }
+
+    #[test]
+    fn test_fractional_quantities_are_understood() {
+        let original = vec![
+            create_test_ingredient(1, "sugar", Some(0.5), Some("cup")),
+            create_test_ingredient(2, "milk", Some(1.0), Some("l")),
+        ];
+        let snapshots = snapshot_ingredients(&original);
+
+        // "½" is the saved 0.5, so it is not an update
+        let mut edited = ingredients_to_measurement_matches(&original);
+        edited[0].quantity = "½".to_string();
+        assert!(detect_ingredient_changes(&snapshots, &edited)
+            .to_update
+            .is_empty());
+
+        for (quantity, understood) in [
+            ("1/2", true),
+            ("½", true),
+            ("1½", true),
+            ("2.5", true),
+            ("a handful", false),
+            ("2-3", false),
+        ] {
+            let mut edited = ingredients_to_measurement_matches(&original);
+            edited[1].quantity = quantity.to_string();
+            edited.push(create_test_match("eggs"));
+            edited[2].quantity = quantity.to_string();
+
+            let unparsed = unparsed_quantities(&edited);
+            if understood {
+                assert!(unparsed.is_empty(), "{quantity} should be understood");
+            } else {
+                let names: Vec<_> = unparsed
+                    .iter()
+                    .map(|m| m.ingredient_name.as_str())
+                    .collect();
+                assert_eq!(names, vec!["milk", "eggs"], "{quantity} should be reported");
+            }
+        }
+    }
+
+    #[test]
+    fn test_ingredients_missing_from_matches_names_case_insensitively() {
+        let existing = vec![create_test_match("Flour"), create_test_match("sugar")];
+        let incoming = vec![
+            create_test_match("flour "),
+            create_test_match("SUGAR"),
+            create_test_match("butter"),
+            create_test_match("Eggs"),
+        ];
+
+        let missing = ingredients_missing_from(&existing, &incoming);
+
+        let names: Vec<&str> = missing
+            .iter()
+            .map(|ing| ing.ingredient_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["butter", "Eggs"]);
+    }
+
+    #[test]
+    fn test_ingredients_missing_from_dedupes_incoming() {
+        let incoming = vec![
+            create_test_match("milk"),
+            create_test_match("Milk"),
+            create_test_match("salt"),
+        ];
+
+        let missing = ingredients_missing_from(&[], &incoming);
+
+        assert_eq!(missing.len(), 2);
+        assert_eq!(missing[0].ingredient_name, "milk");
+        assert_eq!(missing[1].ingredient_name, "salt");
+        assert!(ingredients_missing_from(&incoming, &incoming).is_empty());
+    }
+
+    #[test]
+    fn test_undo_restores_deleted_ingredient_in_place() {
+        let original: Vec<MeasurementMatch> = ["flour", "eggs", "milk"]
+            .iter()
+            .map(|name| create_test_match(name))
+            .collect();
+
+        for index in 0..original.len() {
+            let mut ingredients = original.clone();
+            let deleted = delete_ingredient(&mut ingredients, index).unwrap();
+            assert_eq!(deleted.0, index);
+            assert_eq!(ingredients.len(), 2);
+
+            undo_ingredient_deletion(&mut ingredients, deleted);
+            assert_eq!(ingredients, original);
+        }
+
+        let mut ingredients = original.clone();
+        assert_eq!(delete_ingredient(&mut ingredients, 3), None);
+        assert_eq!(ingredients, original);
+    }
+
+    /// Deleting then undoing while editing a saved recipe leaves nothing to save
+    #[test]
+    fn test_delete_undo_confirm_keeps_saved_ingredients() {
+        let original = vec![
+            create_test_ingredient(1, "flour", Some(2.0), Some("cups")),
+            create_test_ingredient(2, "eggs", Some(3.0), None),
+            create_test_ingredient(3, "milk", Some(0.5), Some("l")),
+        ];
+        let snapshots = snapshot_ingredients(&original);
+
+        let mut edited = ingredients_to_measurement_matches(&original);
+        let deleted = delete_ingredient(&mut edited, 1).unwrap();
+        assert_eq!(
+            detect_ingredient_changes(&snapshots, &edited)
+                .to_delete
+                .len(),
+            1
+        );
+
+        undo_ingredient_deletion(&mut edited, deleted);
+        let changes = detect_ingredient_changes(&snapshots, &edited);
+        assert!(changes.to_update.is_empty());
+        assert!(changes.to_add.is_empty());
+        assert!(changes.to_delete.is_empty());
+    }
+
+    #[test]
+    fn test_adjust_quantity_forms() {
+        assert_eq!(
+            adjust_quantity("2", QuantityStep::PlusOne),
+            Some("3".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("1.5", QuantityStep::PlusHalf),
+            Some("2".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("1/2", QuantityStep::PlusOne),
+            Some("1.5".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("1 1/2", QuantityStep::MinusHalf),
+            Some("1".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("2½", QuantityStep::PlusHalf),
+            Some("3".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("0,5", QuantityStep::PlusHalf),
+            Some("1".to_string())
+        );
+        assert_eq!(adjust_quantity("a pinch", QuantityStep::PlusOne), None);
+    }
+
+    #[test]
+    fn test_adjust_quantity_clamps_at_zero() {
+        assert_eq!(
+            adjust_quantity("½", QuantityStep::MinusOne),
+            Some("0".to_string())
+        );
+        assert_eq!(
+            adjust_quantity("0", QuantityStep::MinusHalf),
+            Some("0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_adjust_callback_round_trip() {
+        let callbacks = [
+            AdjustCallback::Open(0),
+            AdjustCallback::Step(12, QuantityStep::MinusHalf),
+            AdjustCallback::Step(3, QuantityStep::PlusOne),
+            AdjustCallback::Done,
+        ];
+        for callback in callbacks {
+            let data = callback.callback_data();
+            assert!(data.len() <= 64, "{data}");
+            assert_eq!(AdjustCallback::from_callback_data(&data), Some(callback));
+        }
+
+        assert_eq!(AdjustCallback::from_callback_data("adjust_x"), None);
+        assert_eq!(AdjustCallback::from_callback_data("adjust_1:+2"), None);
+        assert_eq!(AdjustCallback::from_callback_data("edit_1"), None);
+    }
 }
@@ -0,0 +1,276 @@
+//! # Ingredient Normalization Module
+//!
+//! Canonical ingredient names, so "All-purpose flour", "all purpose flour" and
+//! "AP flour" count as one ingredient in searches and statistics.
+//!
+//! Names are lowercased and trimmed, hyphens and runs of whitespace collapse to a
+//! single space, and preparation descriptors ("softened", "room temperature") move
+//! into separate notes. Known aliases then map to their canonical name, from
+//! `config/ingredient_aliases.json` (or `INGREDIENT_ALIASES_PATH`). The name as
+//! written is always kept for display; the canonical one is stored next to it.
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fs;
+use tracing::{info, warn};
+
+/// Default location of the ingredient aliases file
+pub const DEFAULT_INGREDIENT_ALIASES_PATH: &str = "config/ingredient_aliases.json";
+
+/// Preparation descriptors moved from the end of a name into its notes
+///
+/// Multi-word descriptors come before their last word ("finely chopped" before
+/// "chopped") so the longest one is taken.
+const TRAILING_DESCRIPTORS: &[&str] = &[
+    "at room temperature",
+    "room temperature",
+    "finely chopped",
+    "roughly chopped",
+    "coarsely chopped",
+    "thinly sliced",
+    "lightly beaten",
+    "freshly ground",
+    "freshly grated",
+    "to taste",
+    "for garnish",
+    "for serving",
+    "softened",
+    "melted",
+    "chopped",
+    "diced",
+    "minced",
+    "sliced",
+    "sifted",
+    "packed",
+    "divided",
+    "beaten",
+    "grated",
+    "peeled",
+    "cubed",
+    "crushed",
+    "drained",
+    "rinsed",
+    "optional",
+    "ramolli",
+    "fondu",
+    "haché",
+    "hachée",
+    "émincé",
+    "émincée",
+    "râpé",
+    "râpée",
+    "tiède",
+    "râpés",
+    "râpées",
+    "pelé",
+    "pelée",
+    "pelés",
+    "pelées",
+];
+
+/// Words joining two trailing descriptors
+const DESCRIPTOR_CONNECTORS: &[&str] = &["and", "et"];
+
+/// A name reduced to its canonical form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedName {
+    /// Canonical name, e.g. "all purpose flour"
+    pub name: String,
+    /// Descriptors taken off the name, e.g. "softened"
+    pub notes: Option<String>,
+}
+
+/// Normalizes ingredient names, mapping known aliases to canonical names
+#[derive(Debug, Clone, Default)]
+pub struct IngredientNormalizer {
+    /// Canonical name of each alias, both already normalized
+    aliases: HashMap<String, String>,
+}
+
+impl IngredientNormalizer {
+    /// Create a normalizer from alias → canonical name pairs, in any casing
+    pub fn new(aliases: HashMap<String, String>) -> Self {
+        let aliases = aliases
+            .iter()
+            .map(|(alias, canonical)| (collapse(alias), collapse(canonical)))
+            .filter(|(alias, canonical)| !alias.is_empty() && !canonical.is_empty())
+            .collect();
+        Self { aliases }
+    }
+
+    /// Parse a JSON object mapping aliases to canonical names
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    /// Canonical form of an ingredient name
+    pub fn normalize(&self, raw: &str) -> NormalizedName {
+        let mut notes = Vec::new();
+
+        // Parenthesized and comma-separated parts are notes: "butter (cold), cubed"
+        let mut head = String::with_capacity(raw.len());
+        let mut depth = 0usize;
+        let mut note = String::new();
+        for c in raw.chars() {
+            match c {
+                '(' => {
+                    depth += 1;
+                }
+                ')' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        notes.push(std::mem::take(&mut note));
+                    }
+                }
+                _ if depth > 0 => note.push(c),
+                _ => head.push(c),
+            }
+        }
+        if depth > 0 {
+            notes.push(note);
+        }
+        let (head, after_comma) = match head.split_once(',') {
+            Some((head, rest)) => (head.to_string(), Some(rest.to_string())),
+            None => (head, None),
+        };
+
+        let mut name = collapse(&head);
+        let mut trailing = Vec::new();
+        loop {
+            if let Some(descriptor) = trailing_descriptor(&name) {
+                name.truncate(name.len() - descriptor.len() - 1);
+                trailing.push(descriptor);
+                continue;
+            }
+            // "peeled and diced": a connector goes too when a descriptor precedes it
+            let connected = DESCRIPTOR_CONNECTORS.iter().find_map(|connector| {
+                name.strip_suffix(connector)
+                    .and_then(|rest| rest.strip_suffix(' '))
+                    .filter(|rest| !trailing.is_empty() && trailing_descriptor(rest).is_some())
+                    .map(str::len)
+            });
+            match connected {
+                Some(length) => name.truncate(length),
+                None => break,
+            }
+        }
+        trailing.reverse();
+        notes.extend(trailing.into_iter().map(str::to_string));
+        notes.extend(after_comma);
+
+        if let Some(canonical) = self.aliases.get(&name) {
+            name = canonical.clone();
+        }
+
+        let notes: Vec<String> = notes
+            .iter()
+            .map(String::as_str)
+            .map(collapse)
+            .filter(|note| !note.is_empty())
+            .collect();
+        NormalizedName {
+            name,
+            notes: (!notes.is_empty()).then(|| notes.join(", ")),
+        }
+    }
+}
+
+/// Descriptor ending `name` after at least one other word
+fn trailing_descriptor(name: &str) -> Option<&'static str> {
+    TRAILING_DESCRIPTORS.iter().copied().find(|descriptor| {
+        name.strip_suffix(descriptor)
+            .is_some_and(|rest| rest.ends_with(' '))
+    })
+}
+
+/// Lowercase, trim, and collapse hyphens and whitespace runs to single spaces
+fn collapse(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Load the ingredient aliases, without any when no file is found
+///
+/// Reads `INGREDIENT_ALIASES_PATH`, or [`DEFAULT_INGREDIENT_ALIASES_PATH`] from
+/// the Docker or working directory.
+pub fn load_ingredient_normalizer() -> IngredientNormalizer {
+    let paths = match &crate::config::current().paths.ingredient_aliases {
+        Some(path) => vec![path.clone()],
+        None => vec![
+            format!("/app/{DEFAULT_INGREDIENT_ALIASES_PATH}"),
+            DEFAULT_INGREDIENT_ALIASES_PATH.to_string(),
+            format!("../{DEFAULT_INGREDIENT_ALIASES_PATH}"),
+        ],
+    };
+
+    for path in &paths {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        match IngredientNormalizer::from_json(&content) {
+            Ok(normalizer) => {
+                info!(path = %path, aliases = normalizer.aliases.len(), "Loaded ingredient aliases");
+                return normalizer;
+            }
+            Err(e) => warn!(path = %path, error = %e, "Failed to parse ingredient aliases"),
+        }
+    }
+
+    warn!("No ingredient aliases file found, names are normalized without aliases");
+    IngredientNormalizer::default()
+}
+
+lazy_static! {
+    static ref NORMALIZER: IngredientNormalizer = load_ingredient_normalizer();
+}
+
+/// Canonical form of an ingredient name, using the configured aliases
+pub fn normalize_ingredient_name(raw: &str) -> NormalizedName {
+    NORMALIZER.normalize(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalizer() -> IngredientNormalizer {
+        IngredientNormalizer::from_json(r#"{ "AP flour": "All-purpose flour" }"#).unwrap()
+    }
+
+    #[test]
+    fn test_collapse() {
+        assert_eq!(collapse("  All-Purpose \t Flour "), "all purpose flour");
+        assert_eq!(collapse("--"), "");
+    }
+
+    #[test]
+    fn test_descriptors_move_to_notes() {
+        let normalized = normalizer().normalize("Butter (cold), cubed");
+        assert_eq!(normalized.name, "butter");
+        assert_eq!(normalized.notes.as_deref(), Some("cold, cubed"));
+
+        let normalized = normalizer().normalize("eggs at room temperature");
+        assert_eq!(normalized.name, "eggs");
+        assert_eq!(normalized.notes.as_deref(), Some("at room temperature"));
+    }
+
+    /// A descriptor is only taken off after another word
+    #[test]
+    fn test_name_made_of_descriptors_is_kept() {
+        let normalized = normalizer().normalize("Melted");
+        assert_eq!(normalized.name, "melted");
+        assert_eq!(normalized.notes, None);
+    }
+
+    #[test]
+    fn test_aliases_map_to_canonical_name() {
+        assert_eq!(normalizer().normalize("AP Flour").name, "all purpose flour");
+        assert_eq!(
+            normalizer().normalize("all-purpose flour, sifted").name,
+            "all purpose flour"
+        );
+    }
+}
@@ -2,13 +2,314 @@
 //!
 //! This module provides thread-safe OCR instance management for reusing Tesseract instances.
 //! Reusing instances significantly improves performance by avoiding initialization overhead.
+//!
+//! Instances are pooled per language combination: a few are created ahead of the first
+//! photo, each is checked out by one OCR run at a time, and an instance that keeps
+//! failing is dropped so a fresh one replaces it.
 
 use leptess::LepTess;
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use tracing::info;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::{info, warn};
+
+use crate::ocr_config::{InstancePoolConfig, OcrConfig};
+
+/// Pool of reusable instances, keyed by configuration
+///
+/// Generic over the instance type so the checkout logic can be tested without
+/// Tesseract; [`OcrInstanceManager`] pools [`LepTess`] instances with it.
+pub struct InstancePool<T> {
+    pools: Mutex<HashMap<String, KeyPool<T>>>,
+}
+
+/// Instances of one configuration key
+struct KeyPool<T> {
+    slots: Vec<Slot<T>>,
+    /// One permit per instance that may be checked out at once
+    permits: Arc<Semaphore>,
+    next_id: u64,
+}
+
+struct Slot<T> {
+    id: u64,
+    instance: Arc<Mutex<T>>,
+    checked_out: bool,
+    consecutive_failures: u32,
+}
+
+impl<T> KeyPool<T> {
+    fn new(max_instances: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            permits: Arc::new(Semaphore::new(max_instances)),
+            next_id: 0,
+        }
+    }
+
+    fn push(&mut self, instance: Arc<Mutex<T>>, checked_out: bool) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.slots.push(Slot {
+            id,
+            instance,
+            checked_out,
+            consecutive_failures: 0,
+        });
+        id
+    }
+}
+
+/// An instance checked out of an [`InstancePool`], checked back in when dropped
+///
+/// Report extraction errors with [`PooledInstance::record_failure`]: after
+/// `max_instance_failures` consecutive ones, the instance is dropped at checkin
+/// and the next checkout creates a replacement.
+pub struct PooledInstance<'a, T> {
+    pool: &'a InstancePool<T>,
+    key: String,
+    id: u64,
+    instance: Arc<Mutex<T>>,
+    max_failures: u32,
+    checked_out_at: Instant,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<T> PooledInstance<'_, T> {
+    /// Lock the instance for an OCR run
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        self.instance
+            .lock()
+            .expect("Failed to acquire OCR instance lock")
+    }
+
+    /// Count an extraction error against this instance
+    pub fn record_failure(&self) {
+        self.pool.update_slot(&self.key, self.id, |slot| {
+            slot.consecutive_failures += 1;
+        });
+    }
+
+    /// Reset the instance's consecutive error count
+    pub fn record_success(&self) {
+        self.pool.update_slot(&self.key, self.id, |slot| {
+            slot.consecutive_failures = 0;
+        });
+    }
+}
+
+impl<T> Drop for PooledInstance<'_, T> {
+    fn drop(&mut self) {
+        crate::observability::record_ocr_pool_checkout(&self.key, self.checked_out_at.elapsed());
+        self.pool.checkin(&self.key, self.id, self.max_failures);
+    }
+}
+
+impl<T> InstancePool<T> {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self {
+            pools: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_pools(&self) -> MutexGuard<'_, HashMap<String, KeyPool<T>>> {
+        self.pools.lock().expect("Failed to acquire instances lock")
+    }
+
+    /// Check out an idle instance for `key`, creating one with `create` if none is idle
+    ///
+    /// Waits while `max_instances` instances of the key are checked out. The limit
+    /// is set when the key is first used.
+    pub async fn checkout(
+        &self,
+        key: &str,
+        config: &InstancePoolConfig,
+        create: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<PooledInstance<'_, T>> {
+        let permits = {
+            let mut pools = self.lock_pools();
+            let pool = pools
+                .entry(key.to_string())
+                .or_insert_with(|| KeyPool::new(config.max_instances));
+            Arc::clone(&pool.permits)
+        };
+
+        let wait_start = Instant::now();
+        let permit = permits
+            .acquire_owned()
+            .await
+            .expect("Instance pool semaphore is never closed");
+        crate::observability::record_ocr_pool_wait(key, wait_start.elapsed());
+
+        let idle = {
+            let mut pools = self.lock_pools();
+            pools.get_mut(key).and_then(|pool| {
+                pool.slots
+                    .iter_mut()
+                    .find(|slot| !slot.checked_out)
+                    .map(|slot| {
+                        slot.checked_out = true;
+                        (slot.id, Arc::clone(&slot.instance))
+                    })
+            })
+        };
+
+        // Holding a permit guarantees room for one more instance
+        let (id, instance) = match idle {
+            Some(idle) => idle,
+            None => {
+                let instance = Arc::new(Mutex::new(create()?));
+                let mut pools = self.lock_pools();
+                let pool = pools
+                    .entry(key.to_string())
+                    .or_insert_with(|| KeyPool::new(config.max_instances));
+                let id = pool.push(Arc::clone(&instance), true);
+                crate::observability::set_ocr_pool_size(key, pool.slots.len());
+                (id, instance)
+            }
+        };
+
+        Ok(PooledInstance {
+            pool: self,
+            key: key.to_string(),
+            id,
+            instance,
+            max_failures: config.max_instance_failures,
+            checked_out_at: Instant::now(),
+            _permit: permit,
+        })
+    }
+
+    /// Get the first instance for `key` without checking it out, creating it if needed
+    pub fn get_or_create(
+        &self,
+        key: &str,
+        config: &InstancePoolConfig,
+        create: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<Arc<Mutex<T>>> {
+        if let Some(slot) = self
+            .lock_pools()
+            .get(key)
+            .and_then(|pool| pool.slots.first())
+        {
+            return Ok(Arc::clone(&slot.instance));
+        }
+
+        let instance = Arc::new(Mutex::new(create()?));
+        let mut pools = self.lock_pools();
+        let pool = pools
+            .entry(key.to_string())
+            .or_insert_with(|| KeyPool::new(config.max_instances));
+        // Another caller may have created one meanwhile
+        if let Some(slot) = pool.slots.first() {
+            return Ok(Arc::clone(&slot.instance));
+        }
+        pool.push(Arc::clone(&instance), false);
+        crate::observability::set_ocr_pool_size(key, pool.slots.len());
+        Ok(instance)
+    }
+
+    /// Create instances for `key` until `warm_instances` exist, returning how many were created
+    pub fn warm(
+        &self,
+        key: &str,
+        config: &InstancePoolConfig,
+        mut create: impl FnMut() -> anyhow::Result<T>,
+    ) -> anyhow::Result<usize> {
+        let mut created = 0;
+        while self.instance_count(key) < config.warm_instances {
+            let instance = Arc::new(Mutex::new(create()?));
+            let mut pools = self.lock_pools();
+            let pool = pools
+                .entry(key.to_string())
+                .or_insert_with(|| KeyPool::new(config.max_instances));
+            pool.push(instance, false);
+            crate::observability::set_ocr_pool_size(key, pool.slots.len());
+            created += 1;
+        }
+        Ok(created)
+    }
+
+    /// Number of instances pooled for `key`, checked out or not
+    pub fn instance_count(&self, key: &str) -> usize {
+        self.lock_pools()
+            .get(key)
+            .map_or(0, |pool| pool.slots.len())
+    }
+
+    /// Number of instances pooled across all keys
+    pub fn total_count(&self) -> usize {
+        self.lock_pools()
+            .values()
+            .map(|pool| pool.slots.len())
+            .sum()
+    }
+
+    /// Drop the instances of `key`, returning whether there were any
+    ///
+    /// Checked out instances stay usable until checked in, then are dropped.
+    pub fn remove(&self, key: &str) -> bool {
+        let removed = self.lock_pools().remove(key);
+        crate::observability::set_ocr_pool_size(key, 0);
+        removed.is_some_and(|pool| !pool.slots.is_empty())
+    }
+
+    /// Drop the instances of every key, returning how many there were
+    pub fn clear(&self) -> usize {
+        let mut pools = self.lock_pools();
+        let count = pools.values().map(|pool| pool.slots.len()).sum();
+        for key in pools.keys() {
+            crate::observability::set_ocr_pool_size(key, 0);
+        }
+        pools.clear();
+        count
+    }
+
+    fn update_slot(&self, key: &str, id: u64, update: impl FnOnce(&mut Slot<T>)) {
+        if let Some(slot) = self
+            .lock_pools()
+            .get_mut(key)
+            .and_then(|pool| pool.slots.iter_mut().find(|slot| slot.id == id))
+        {
+            update(slot);
+        }
+    }
+
+    fn checkin(&self, key: &str, id: u64, max_failures: u32) {
+        let mut pools = self.lock_pools();
+        // The key may have been removed or cleared while the instance was out
+        let Some(pool) = pools.get_mut(key) else {
+            return;
+        };
+        let Some(index) = pool.slots.iter().position(|slot| slot.id == id) else {
+            return;
+        };
+
+        if pool.slots[index].consecutive_failures >= max_failures {
+            pool.slots.remove(index);
+            warn!(
+                pool = key,
+                failures = max_failures,
+                "Dropping OCR instance after repeated extraction errors"
+            );
+            crate::observability::record_ocr_pool_recycled(key);
+            crate::observability::set_ocr_pool_size(key, pool.slots.len());
+        } else {
+            pool.slots[index].checked_out = false;
+        }
+    }
+}
+
+impl<T> Default for InstancePool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-use crate::ocr_config::OcrConfig;
+/// A Tesseract instance checked out of an [`OcrInstanceManager`]
+pub type OcrInstance<'a> = PooledInstance<'a, LepTess>;
 
 /// Thread-safe OCR instance manager for reusing Tesseract instances
 ///
@@ -20,13 +321,17 @@ use crate::ocr_config::OcrConfig;
 ///
 /// - Eliminates Tesseract initialization overhead (~100-500ms per instance)
 /// - Reduces memory allocations for repeated OCR operations
-/// - Thread-safe with Arc<Mutex<>> for concurrent access
+/// - Concurrent photos get separate instances instead of queuing on one
 ///
 /// # Instance Lifecycle
 ///
-/// - Instances are created on first request for a language combination
-/// - Instances are reused for subsequent requests with same language config
-/// - Instances persist until explicitly removed or manager is dropped
+/// - [`OcrInstanceManager::warm_up`] creates `pool.warm_instances` instances ahead of use
+/// - [`OcrInstanceManager::checkout`] hands out an idle instance, creating one while
+///   fewer than `pool.max_instances` exist, and otherwise waits for a checkin
+/// - Instances with `pool.max_instance_failures` consecutive extraction errors are
+///   dropped at checkin and re-created on demand
+/// - [`OcrInstanceManager::recycle_all`] drops every instance, which extraction does
+///   when the circuit breaker opens
 ///
 /// # Thread Safety
 ///
@@ -35,18 +340,18 @@ use crate::ocr_config::OcrConfig;
 ///
 /// # Memory Management
 ///
-/// - Each language combination maintains one instance
+/// - Each language combination holds up to `pool.max_instances` instances
 /// - Memory usage scales with number of unique language combinations
 /// - Consider memory limits for applications with many language combinations
 pub struct OcrInstanceManager {
-    instances: Mutex<HashMap<String, Arc<Mutex<LepTess>>>>,
+    pool: InstancePool<LepTess>,
 }
 
 impl OcrInstanceManager {
     /// Create a new OCR instance manager
     ///
-    /// Initializes an empty instance pool. Instances will be created
-    /// on-demand when first requested via `get_instance()`.
+    /// Initializes an empty instance pool. Instances will be created by
+    /// `warm_up()` or on-demand when first requested.
     ///
     /// # Examples
     ///
@@ -58,14 +363,68 @@ impl OcrInstanceManager {
     /// ```
     pub fn new() -> Self {
         Self {
-            instances: Mutex::new(HashMap::new()),
+            pool: InstancePool::new(),
         }
     }
 
+    /// Pool key covering both languages and model type
+    fn pool_key(config: &OcrConfig) -> String {
+        format!("{}:{}", config.languages, config.model_type.tessdata_dir())
+    }
+
+    /// Check out a Tesseract instance for the given configuration
+    ///
+    /// The instance is checked back in when the returned guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use just_ingredients::instance_manager::OcrInstanceManager;
+    /// use just_ingredients::ocr_config::OcrConfig;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let manager = OcrInstanceManager::new();
+    /// let config = OcrConfig::default();
+    ///
+    /// let instance = manager.checkout(&config).await?;
+    /// let mut tess = instance.lock();
+    /// // Use the instance for OCR processing
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns error if Tesseract instance creation fails (e.g., invalid language codes)
+    pub async fn checkout(&self, config: &OcrConfig) -> anyhow::Result<OcrInstance<'_>> {
+        self.pool
+            .checkout(&Self::pool_key(config), &config.pool, || {
+                Self::create_instance(config)
+            })
+            .await
+    }
+
+    /// Create the configured number of warm instances, returning how many were created
+    ///
+    /// Blocks while Tesseract initializes; call it from a blocking task.
+    pub fn warm_up(&self, config: &OcrConfig) -> anyhow::Result<usize> {
+        let created = self.pool.warm(&Self::pool_key(config), &config.pool, || {
+            Self::create_instance(config)
+        })?;
+        if created > 0 {
+            info!(
+                "Warmed up {created} OCR instances for languages: {}",
+                config.languages
+            );
+        }
+        Ok(created)
+    }
+
     /// Get or create an OCR instance for the given configuration
     ///
-    /// Returns an existing instance if one exists for the language configuration,
-    /// otherwise creates a new instance and stores it for future reuse.
+    /// Returns the first pooled instance for the language configuration without
+    /// checking it out, otherwise creates a new instance and stores it for future reuse.
+    /// OCR runs should use [`OcrInstanceManager::checkout`] instead.
     ///
     /// # Arguments
     ///
@@ -100,21 +459,14 @@ impl OcrInstanceManager {
     /// - First call for a language: ~100-500ms (Tesseract initialization)
     /// - Subsequent calls: ~1ms (instance lookup and Arc clone)
     pub fn get_instance(&self, config: &OcrConfig) -> anyhow::Result<Arc<Mutex<LepTess>>> {
-        // Create a unique key that includes both languages and model type
-        let key = format!("{}:{}", config.languages, config.model_type.tessdata_dir());
-
-        // Try to get existing instance
-        {
-            let instances = self
-                .instances
-                .lock()
-                .expect("Failed to acquire instances lock");
-            if let Some(instance) = instances.get(&key) {
-                return Ok(Arc::clone(instance));
-            }
-        }
+        self.pool
+            .get_or_create(&Self::pool_key(config), &config.pool, || {
+                Self::create_instance(config)
+            })
+    }
 
-        // Create new instance if none exists
+    /// Create and configure a Tesseract instance
+    fn create_instance(config: &OcrConfig) -> anyhow::Result<LepTess> {
         info!(
             "Creating new OCR instance for languages: {} with model: {}",
             config.languages,
@@ -164,18 +516,7 @@ impl OcrInstanceManager {
             );
         }
 
-        let instance = Arc::new(Mutex::new(tess));
-
-        // Store the instance
-        {
-            let mut instances = self
-                .instances
-                .lock()
-                .expect("Failed to acquire instances lock");
-            instances.insert(key, Arc::clone(&instance));
-        }
-
-        Ok(instance)
+        Ok(tess)
     }
 
     /// Get the tessdata path for the specified model type
@@ -217,14 +558,21 @@ impl OcrInstanceManager {
         None
     }
 
+    /// Drop every pooled instance so later checkouts start from fresh ones
+    ///
+    /// Returns the number of instances dropped.
+    pub fn recycle_all(&self) -> usize {
+        let count = self.pool.clear();
+        if count > 0 {
+            info!("Cleared {count} OCR instances");
+        }
+        count
+    }
+
     /// Remove an instance (useful for cleanup or when configuration changes)
     pub fn _remove_instance(&self, languages: &str, model_type: crate::ocr_config::ModelType) {
         let key = format!("{}:{}", languages, model_type.tessdata_dir());
-        let mut instances = self
-            .instances
-            .lock()
-            .expect("Failed to acquire instances lock");
-        if instances.remove(&key).is_some() {
+        if self.pool.remove(&key) {
             info!(
                 "Removed OCR instance for languages: {} with model: {}",
                 languages,
@@ -235,24 +583,12 @@ impl OcrInstanceManager {
 
     /// Clear all instances (useful for memory cleanup)
     pub fn _clear_all_instances(&self) {
-        let mut instances = self
-            .instances
-            .lock()
-            .expect("Failed to acquire instances lock");
-        let count = instances.len();
-        instances.clear();
-        if count > 0 {
-            info!("Cleared {count} OCR instances");
-        }
+        self.recycle_all();
     }
 
     /// Get the number of cached instances
     pub fn _instance_count(&self) -> usize {
-        let instances = self
-            .instances
-            .lock()
-            .expect("Failed to acquire instances lock");
-        instances.len()
+        self.pool.total_count()
     }
 }
 
@@ -261,3 +597,157 @@ impl Default for OcrInstanceManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn pool_config(max_instances: usize) -> InstancePoolConfig {
+        InstancePoolConfig {
+            warm_instances: 0,
+            max_instances,
+            max_instance_failures: 2,
+        }
+    }
+
+    /// Factory numbering the instances it creates
+    fn counting(created: &AtomicUsize) -> impl FnOnce() -> anyhow::Result<usize> + '_ {
+        move || Ok(created.fetch_add(1, Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn test_checkout_under_contention_respects_max_instances() {
+        let pool = Arc::new(InstancePool::<usize>::new());
+        let created = Arc::new(AtomicUsize::new(0));
+        let in_use = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..8 {
+            let (pool, created, in_use, peak) = (
+                Arc::clone(&pool),
+                Arc::clone(&created),
+                Arc::clone(&in_use),
+                Arc::clone(&peak),
+            );
+            tasks.spawn(async move {
+                let instance = pool
+                    .checkout("eng", &pool_config(2), counting(&created))
+                    .await
+                    .unwrap();
+                let now = in_use.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_use.fetch_sub(1, Ordering::SeqCst);
+                drop(instance);
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.unwrap();
+        }
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.instance_count("eng"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_checkout_waits_for_checkin() {
+        let pool = InstancePool::<usize>::new();
+        let created = AtomicUsize::new(0);
+        let config = pool_config(1);
+
+        let first = pool
+            .checkout("eng", &config, counting(&created))
+            .await
+            .unwrap();
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(20),
+            pool.checkout("eng", &config, counting(&created)),
+        )
+        .await;
+        assert!(blocked.is_err(), "second checkout should wait");
+
+        // Other language combinations have their own instances
+        let other = pool
+            .checkout("deu", &config, counting(&created))
+            .await
+            .unwrap();
+        assert_eq!(*other.lock(), 1);
+
+        drop(first);
+        let second = pool
+            .checkout("eng", &config, counting(&created))
+            .await
+            .unwrap();
+        assert_eq!(*second.lock(), 0, "the checked in instance is reused");
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failing_instance_is_recycled() {
+        let pool = InstancePool::<usize>::new();
+        let created = AtomicUsize::new(0);
+        let config = pool_config(1);
+
+        // A success in between resets the consecutive error count
+        for outcome in [false, true, false] {
+            let instance = pool
+                .checkout("eng", &config, counting(&created))
+                .await
+                .unwrap();
+            if outcome {
+                instance.record_success();
+            } else {
+                instance.record_failure();
+            }
+        }
+        assert_eq!(pool.instance_count("eng"), 1);
+
+        let instance = pool
+            .checkout("eng", &config, counting(&created))
+            .await
+            .unwrap();
+        assert_eq!(*instance.lock(), 0);
+        instance.record_failure();
+        drop(instance);
+        assert_eq!(pool.instance_count("eng"), 0, "instance dropped at checkin");
+
+        let replacement = pool
+            .checkout("eng", &config, counting(&created))
+            .await
+            .unwrap();
+        assert_eq!(*replacement.lock(), 1);
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_warm_instances_are_checked_out_first() {
+        let pool = InstancePool::<usize>::new();
+        let created = AtomicUsize::new(0);
+        let config = InstancePoolConfig {
+            warm_instances: 2,
+            ..pool_config(3)
+        };
+
+        let warmed = pool
+            .warm(
+                "eng",
+                &config,
+                || Ok(created.fetch_add(1, Ordering::SeqCst)),
+            )
+            .unwrap();
+        assert_eq!(warmed, 2);
+        assert_eq!(pool.warm("eng", &config, || unreachable!()).unwrap(), 0);
+
+        let first = pool.checkout("eng", &config, || unreachable!()).await;
+        let second = pool.checkout("eng", &config, || unreachable!()).await;
+        assert!(first.is_ok() && second.is_ok());
+        assert_eq!(created.load(Ordering::SeqCst), 2);
+
+        assert_eq!(pool.clear(), 2);
+        assert_eq!(pool.total_count(), 0);
+    }
+}
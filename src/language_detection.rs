@@ -0,0 +1,200 @@
+//! # Language Detection Module
+//!
+//! Cheap stopword-based detection of the language a message is written in. Used by
+//! the per-message "auto" language mode (`/language auto`) so households sharing an
+//! account get replies in the language each message was typed in.
+//!
+//! Only languages with bundled translations are detected. A guess is trusted only
+//! when it wins by a clear margin, otherwise callers keep the stored language; this
+//! stops short or mixed messages from flipping the reply language back and forth.
+
+/// Common words that only appear in one supported language
+const STOPWORDS: [(&str, &[&str]); 2] = [
+    (
+        "en",
+        &[
+            "the", "and", "with", "of", "to", "is", "it", "my", "for", "this", "that", "please",
+            "add", "remove", "change", "some", "cup", "cups", "in", "without", "from", "what",
+            "how", "can", "you", "your", "i", "we", "our", "are", "was", "be", "not", "yes", "no",
+            "thanks", "thank", "cake", "bread", "recipe", "eggs",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "avec", "de", "du", "des", "au", "aux", "un", "une", "pour",
+            "est", "mon", "ma", "mes", "ce", "cette", "sans", "dans", "sur", "je", "nous", "vous",
+            "tu", "pas", "oui", "non", "merci", "ajoute", "ajouter", "supprime", "enlève",
+            "gâteau", "tarte", "pain", "recette", "oeufs", "œufs", "l", "qu",
+        ],
+    ),
+];
+
+/// Minimum share of the winning language's lead over all matched stopwords
+pub const MIN_CONFIDENCE: f32 = 0.5;
+
+/// Outcome of detecting the language of a text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LanguageGuess {
+    /// Detected language code (`en`, `fr`)
+    pub language: &'static str,
+    /// Stopwords of the detected language found in the text
+    pub hits: usize,
+    /// Lead over the runner-up relative to all hits, from 0.0 (tie) to 1.0 (unopposed)
+    pub confidence: f32,
+}
+
+impl LanguageGuess {
+    /// Whether the guess is clear enough to switch the reply language
+    pub fn is_confident(&self) -> bool {
+        self.hits > 0 && self.confidence >= MIN_CONFIDENCE
+    }
+}
+
+/// Detect the most likely language of a text from its stopwords
+///
+/// Returns `None` when no stopword of any supported language is found.
+pub fn detect_text_language(text: &str) -> Option<LanguageGuess> {
+    let lowercase = text.to_lowercase();
+    let words: Vec<&str> = lowercase
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut scores: Vec<(&'static str, usize)> = STOPWORDS
+        .iter()
+        .map(|(language, stopwords)| {
+            let hits = words.iter().filter(|word| stopwords.contains(word)).count();
+            (*language, hits)
+        })
+        .collect();
+    scores.sort_by_key(|(_, hits)| std::cmp::Reverse(*hits));
+
+    let (language, hits) = scores[0];
+    if hits == 0 {
+        return None;
+    }
+    let runner_up = scores.get(1).map_or(0, |(_, hits)| *hits);
+    let total: usize = scores.iter().map(|(_, hits)| hits).sum();
+
+    Some(LanguageGuess {
+        language,
+        hits,
+        confidence: (hits - runner_up) as f32 / total as f32,
+    })
+}
+
+/// Detect the language of a text, keeping only confident guesses
+pub fn confident_text_language(text: &str) -> Option<&'static str> {
+    detect_text_language(text)
+        .filter(LanguageGuess::is_confident)
+        .map(|guess| guess.language)
+}
+
+/// What triggered the reply being localized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyTrigger<'a> {
+    /// A free-text message typed by the user
+    Text(&'a str),
+    /// A button tap, which carries no text to detect
+    Button,
+}
+
+/// Pick the reply language for an interaction
+///
+/// In auto mode, free text is answered in its detected language when the guess is
+/// confident; every other case keeps the stored language.
+pub fn resolve_reply_language<'a>(
+    auto_language: bool,
+    trigger: ReplyTrigger<'_>,
+    stored_language: Option<&'a str>,
+) -> Option<&'a str> {
+    match trigger {
+        ReplyTrigger::Text(text) if auto_language => {
+            confident_text_language(text).or(stored_language)
+        }
+        _ => stored_language,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_short_english_messages() {
+        assert_eq!(confident_text_language("Add 2 cups of flour"), Some("en"));
+        assert_eq!(confident_text_language("my chocolate cake"), Some("en"));
+        assert_eq!(confident_text_language("thanks!"), Some("en"));
+    }
+
+    #[test]
+    fn test_detects_short_french_messages() {
+        assert_eq!(confident_text_language("Tarte aux pommes"), Some("fr"));
+        assert_eq!(
+            confident_text_language("ajoute 200 g de beurre et du sucre"),
+            Some("fr")
+        );
+        assert_eq!(
+            confident_text_language("Gâteau de l'anniversaire"),
+            Some("fr")
+        );
+    }
+
+    #[test]
+    fn test_no_stopwords_is_undetected() {
+        assert_eq!(detect_text_language("Tiramisu"), None);
+        assert_eq!(detect_text_language("250 g"), None);
+        assert_eq!(detect_text_language(""), None);
+    }
+
+    #[test]
+    fn test_margin_rejects_ambiguous_text() {
+        // One stopword each: a tie is never trusted
+        let guess = detect_text_language("the tarte").unwrap();
+        assert_eq!(guess.confidence, 0.0);
+        assert!(!guess.is_confident());
+
+        // 4 French vs 2 English stopwords: French leads but not by enough
+        let guess = detect_text_language("the pain with le beurre et sucre pour").unwrap();
+        assert_eq!(guess.language, "fr");
+        assert!(!guess.is_confident());
+        assert_eq!(
+            confident_text_language("the pain with le beurre et sucre pour"),
+            None
+        );
+
+        // 4 French vs 1 English stopword clears the margin
+        let guess = detect_text_language("la tarte et le crumble with").unwrap();
+        assert_eq!(guess.language, "fr");
+        assert!(guess.is_confident());
+    }
+
+    #[test]
+    fn test_resolve_reply_language_in_auto_mode() {
+        assert_eq!(
+            resolve_reply_language(true, ReplyTrigger::Text("with the eggs"), Some("fr")),
+            Some("en")
+        );
+        assert_eq!(
+            resolve_reply_language(true, ReplyTrigger::Text("Tiramisu"), Some("fr")),
+            Some("fr"),
+            "undetected text keeps the stored language"
+        );
+        assert_eq!(
+            resolve_reply_language(false, ReplyTrigger::Text("with the eggs"), Some("fr")),
+            Some("fr"),
+            "detection only runs in auto mode"
+        );
+    }
+
+    #[test]
+    fn test_auto_mode_never_affects_buttons() {
+        for stored in [Some("fr"), Some("en"), None] {
+            assert_eq!(
+                resolve_reply_language(true, ReplyTrigger::Button, stored),
+                stored
+            );
+        }
+    }
+}
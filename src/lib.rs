@@ -5,6 +5,7 @@
 
 pub mod bot;
 pub mod cache;
+pub mod cache_backend;
 pub mod circuit_breaker;
 pub mod config;
 pub mod db;
@@ -12,22 +13,45 @@ pub mod deduplication;
 pub mod dialogue;
 pub mod error_correction;
 pub mod errors;
+pub mod events;
+pub mod experiments;
+pub mod extraction_feedback;
+pub mod extraction_reports;
+pub mod extractor;
+pub mod import_jobs;
 pub mod ingredient_editing;
+pub mod ingredient_normalization;
 pub mod instance_manager;
+pub mod language_detection;
 pub mod localization;
+pub mod message_entities;
+pub mod nutrition;
 pub mod observability;
 pub mod observability_config;
 pub mod ocr;
 pub mod ocr_config;
 pub mod ocr_errors;
 pub mod path_validation;
+pub mod pdf;
 pub mod preprocessing;
+pub mod quantity;
+pub mod rate_limiter;
+pub mod recipe_export;
+pub mod recipe_scaling;
+pub mod repository;
+pub mod resource_limits;
+pub mod scheduler;
+pub mod shutdown;
 pub mod text_processing;
+pub mod timezone;
+pub mod transcription;
+pub mod unit_conversion;
 pub mod validation;
 
 // Re-export types for easier access
 pub use config::AppConfig;
-pub use deduplication::{RequestDeduplicator, RequestId, SharedDeduplicator};
+pub use deduplication::{RequestDeduplicator, RequestId, SharedDeduplicator, UpdateDeduplicator};
+pub use extractor::{ExtractionResult, ExtractionTiming, RecipeExtractor};
 pub use ocr::{
     map_measurement_to_bbox, parse_hocr_to_lines, perform_constrained_ocr, BBox, ConfidenceFlag,
     ConstrainedOcrResult, HocrLine, OcrConfidence,
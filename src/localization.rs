@@ -1,55 +1,239 @@
 use anyhow::Result;
-use fluent_bundle::{FluentBundle, FluentResource};
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 use unic_langid::LanguageIdentifier;
 
+/// Thread-safe Fluent bundle shared across handlers
+type ConcurrentBundle = fluent_bundle::concurrent::FluentBundle<FluentResource>;
+
+/// Language that is always loaded eagerly and used as the fallback
+const FALLBACK_LANGUAGE: &str = "en";
+
+/// Slot and metric label shared by every unsupported language code
+const UNSUPPORTED_LANGUAGE_BUCKET: &str = "other";
+
+/// Default time before a failed language load is attempted again
+const DEFAULT_NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Options controlling which bundles are loaded up front and how load failures are cached
+#[derive(Debug, Clone)]
+pub struct LocalizationOptions {
+    /// Languages to load at startup in addition to English
+    pub preload_languages: Vec<String>,
+    /// How long a failed language load is remembered before retrying
+    pub negative_cache_ttl: Duration,
+}
+
+impl Default for LocalizationOptions {
+    fn default() -> Self {
+        Self {
+            preload_languages: Vec::new(),
+            negative_cache_ttl: DEFAULT_NEGATIVE_CACHE_TTL,
+        }
+    }
+}
+
+/// Parse a comma-separated list of language codes, ignoring blanks and duplicates
+pub fn parse_language_list(value: &str) -> Vec<String> {
+    let mut languages: Vec<String> = Vec::new();
+    for language in value.split(',').map(|l| l.trim().to_lowercase()) {
+        if !language.is_empty() && !languages.contains(&language) {
+            languages.push(language);
+        }
+    }
+    languages
+}
+
+/// Load state of a single language bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageLoadState {
+    /// Not requested yet
+    NotLoaded,
+    /// Loaded and kept in memory
+    Loaded,
+    /// Last load attempt failed; English is served until the negative cache expires
+    Failed,
+}
+
+/// Load status and approximate memory usage of a language bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageLoadStatus {
+    pub language: String,
+    pub state: LanguageLoadState,
+    pub load_attempts: usize,
+    /// Approximation based on the size of the parsed FTL source
+    pub approx_memory_bytes: usize,
+}
+
+/// Lazily initialized bundle for one language
+#[derive(Default)]
+struct LocaleSlot {
+    bundle: OnceLock<Arc<ConcurrentBundle>>,
+    /// Time of the last failed load; the lock also serializes first loads
+    last_failure: Mutex<Option<Instant>>,
+    load_attempts: AtomicUsize,
+    approx_memory_bytes: AtomicUsize,
+}
+
 /// Localization manager for the Ingredients Bot
-#[derive(Debug)]
+///
+/// English is loaded eagerly as the fallback; other languages are parsed on first use
+/// and kept for the lifetime of the manager.
 pub struct LocalizationManager {
-    // No shared state - bundles are created on demand
+    slots: RwLock<HashMap<String, Arc<LocaleSlot>>>,
+    negative_cache_ttl: Duration,
+}
+
+impl std::fmt::Debug for LocalizationManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalizationManager")
+            .field("languages", &self.language_statuses())
+            .field("negative_cache_ttl", &self.negative_cache_ttl)
+            .finish()
+    }
 }
 
 impl LocalizationManager {
     /// Create a new localization manager with embedded resources
+    ///
+    /// Languages listed in `PRELOAD_LANGUAGES` are loaded at startup.
     pub fn new() -> Result<Self> {
-        // No initialization needed - bundles are created on demand
-        Ok(Self {})
-    }
-
-    /// Create a fluent bundle for a specific locale using embedded resources
-    fn create_bundle(
-        locale_str: &str,
-        locale: &LanguageIdentifier,
-    ) -> Result<FluentBundle<FluentResource>> {
-        let mut bundle = FluentBundle::new(vec![locale.clone()]);
-
-        // Load embedded resource based on locale
-        let content = match locale_str {
-            "en" => include_str!("../locales/en/main.ftl"),
-            "fr" => include_str!("../locales/fr/main.ftl"),
-            _ => return Err(anyhow::anyhow!("Unsupported locale: {}", locale_str)),
+        Self::with_options(crate::config::current().localization.clone())
+    }
+
+    /// Create a localization manager with explicit options
+    pub fn with_options(options: LocalizationOptions) -> Result<Self> {
+        let manager = Self {
+            slots: RwLock::new(HashMap::new()),
+            negative_cache_ttl: options.negative_cache_ttl,
         };
 
+        // English is the fallback for every lookup, so it must always be available
+        if manager.bundle(FALLBACK_LANGUAGE).is_none() {
+            return Err(anyhow::anyhow!(
+                "Failed to load fallback localization bundle '{}'",
+                FALLBACK_LANGUAGE
+            ));
+        }
+
+        for language in &options.preload_languages {
+            if manager.bundle(language).is_none() {
+                warn!(language = %language, "Failed to preload localization bundle");
+            }
+        }
+
+        Ok(manager)
+    }
+
+    /// Get the embedded FTL source for a locale
+    fn embedded_source(locale_str: &str) -> Result<&'static str> {
+        match locale_str {
+            "en" => Ok(include_str!("../locales/en/main.ftl")),
+            "fr" => Ok(include_str!("../locales/fr/main.ftl")),
+            _ => Err(anyhow::anyhow!("Unsupported locale: {}", locale_str)),
+        }
+    }
+
+    /// Create a fluent bundle for a specific language using embedded resources
+    fn create_bundle_for_language(language: &str) -> Result<(ConcurrentBundle, usize)> {
+        let locale: LanguageIdentifier = language.parse()?;
+        let content = Self::embedded_source(language)?;
+
+        let mut bundle = ConcurrentBundle::new_concurrent(vec![locale]);
+
         let resource = FluentResource::try_new(content.to_string()).map_err(|(_, errors)| {
             anyhow::anyhow!(
                 "Failed to parse localization resource for {}: {:?}",
-                locale_str,
+                language,
                 errors
             )
         })?;
 
         bundle
             .add_resource(resource)
-            .map_err(|e| anyhow::anyhow!("Failed to add resource for {}: {:?}", locale_str, e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to add resource for {}: {:?}", language, e))?;
 
-        Ok(bundle)
+        Ok((bundle, content.len()))
     }
 
-    /// Create a bundle for a specific language
-    fn create_bundle_for_language(language: &str) -> Result<FluentBundle<FluentResource>> {
-        let locale: LanguageIdentifier = language.parse()?;
-        Self::create_bundle(language, &locale)
+    /// Key of the slot and metric label used for a language
+    ///
+    /// Unsupported codes share one bucket, so client-provided language codes
+    /// cannot grow the slot map or the metric label set.
+    fn language_bucket<'a>(&self, language: &'a str) -> &'a str {
+        if self.is_language_supported(language) {
+            language
+        } else {
+            UNSUPPORTED_LANGUAGE_BUCKET
+        }
+    }
+
+    /// Get the slot for a language bucket, creating it on first request
+    fn slot(&self, language: &str) -> Arc<LocaleSlot> {
+        if let Some(slot) = self.slots.read().get(language) {
+            return slot.clone();
+        }
+        self.slots
+            .write()
+            .entry(language.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Get the bundle for a language, loading it once on first use
+    ///
+    /// Concurrent first requests wait for a single load. A failed load is remembered
+    /// for the negative cache TTL so callers fall back to English without retrying.
+    fn bundle(&self, language: &str) -> Option<Arc<ConcurrentBundle>> {
+        let bucket = self.language_bucket(language);
+        let slot = self.slot(bucket);
+        if let Some(bundle) = slot.bundle.get() {
+            return Some(bundle.clone());
+        }
+
+        let mut last_failure = slot.last_failure.lock();
+
+        // Another caller may have finished loading while we waited
+        if let Some(bundle) = slot.bundle.get() {
+            return Some(bundle.clone());
+        }
+
+        if let Some(failed_at) = *last_failure {
+            if failed_at.elapsed() < self.negative_cache_ttl {
+                return None;
+            }
+        }
+
+        slot.load_attempts.fetch_add(1, Ordering::SeqCst);
+        match Self::create_bundle_for_language(language) {
+            Ok((bundle, approx_memory_bytes)) => {
+                let bundle = Arc::new(bundle);
+                let _ = slot.bundle.set(bundle.clone());
+                slot.approx_memory_bytes
+                    .store(approx_memory_bytes, Ordering::SeqCst);
+                *last_failure = None;
+
+                crate::observability::record_localization_bundle_metrics(
+                    bucket,
+                    true,
+                    approx_memory_bytes,
+                );
+                debug!(language = %language, approx_memory_bytes, "Localization bundle loaded");
+                Some(bundle)
+            }
+            Err(e) => {
+                *last_failure = Some(Instant::now());
+
+                crate::observability::record_localization_bundle_metrics(bucket, false, 0);
+                warn!(language = %language, error = %e, "Failed to load localization bundle, falling back to English");
+                None
+            }
+        }
     }
 
     /// Get a localized message in a specific language with graceful fallback
@@ -59,25 +243,42 @@ impl LocalizationManager {
         language: &str,
         args: Option<&HashMap<&str, &str>>,
     ) -> String {
+        let fluent_args = args.map(|args_map| {
+            FluentArgs::from_iter(args_map.iter().map(|(k, v)| (*k, FluentValue::from(*v))))
+        });
+        self.format_message(key, language, fluent_args.as_ref())
+    }
+
+    /// Get a localized message whose variants are selected by `$count`
+    ///
+    /// The count is passed as a number so Fluent applies the language's plural
+    /// categories (French treats 0 and 1 as singular, English only 1).
+    pub fn get_plural_message_in_language(
+        &self,
+        key: &str,
+        language: &str,
+        count: usize,
+        args: &[(&str, &str)],
+    ) -> String {
+        let mut fluent_args =
+            FluentArgs::from_iter(args.iter().map(|(k, v)| (*k, FluentValue::from(*v))));
+        fluent_args.set("count", FluentValue::from(count));
+        self.format_message(key, language, Some(&fluent_args))
+    }
+
+    /// Format a message in the requested language, falling back to English
+    fn format_message(&self, key: &str, language: &str, args: Option<&FluentArgs>) -> String {
         // Try requested language first, then fallback to English
-        let languages_to_try = vec![language, "en"];
+        let languages_to_try = vec![language, FALLBACK_LANGUAGE];
 
         for lang in languages_to_try {
-            if let Ok(bundle) = Self::create_bundle_for_language(lang) {
+            if let Some(bundle) = self.bundle(lang) {
                 if let Some(msg) = bundle.get_message(key) {
                     if let Some(pattern) = msg.value() {
                         let mut value = String::new();
 
-                        let fluent_args = args.map(|args_map| {
-                            fluent_bundle::FluentArgs::from_iter(
-                                args_map
-                                    .iter()
-                                    .map(|(k, v)| (*k, fluent_bundle::FluentValue::from(*v))),
-                            )
-                        });
-
                         if bundle
-                            .write_pattern(&mut value, pattern, fluent_args.as_ref(), &mut vec![])
+                            .write_pattern(&mut value, pattern, args, &mut vec![])
                             .is_ok()
                         {
                             return value;
@@ -104,7 +305,70 @@ impl LocalizationManager {
 
     /// Check if a language is supported
     pub fn is_language_supported(&self, language: &str) -> bool {
-        matches!(language, "en" | "fr")
+        self.supported_languages().contains(&language)
+    }
+
+    /// List all supported languages
+    pub fn supported_languages(&self) -> &'static [&'static str] {
+        &["en", "fr"]
+    }
+
+    /// Report load state, attempts and approximate memory usage for each known language
+    ///
+    /// Includes every supported language, plus the `other` bucket once an unsupported
+    /// language was requested.
+    pub fn language_statuses(&self) -> Vec<LanguageLoadStatus> {
+        let slots = self.slots.read();
+        let mut languages: Vec<String> = slots.keys().cloned().collect();
+        for supported in self.supported_languages() {
+            if !slots.contains_key(*supported) {
+                languages.push(supported.to_string());
+            }
+        }
+        languages.sort();
+
+        languages
+            .into_iter()
+            .map(|language| match slots.get(&language) {
+                Some(slot) => {
+                    let state = if slot.bundle.get().is_some() {
+                        LanguageLoadState::Loaded
+                    } else if slot.last_failure.lock().is_some() {
+                        LanguageLoadState::Failed
+                    } else {
+                        LanguageLoadState::NotLoaded
+                    };
+                    LanguageLoadStatus {
+                        language,
+                        state,
+                        load_attempts: slot.load_attempts.load(Ordering::SeqCst),
+                        approx_memory_bytes: slot.approx_memory_bytes.load(Ordering::SeqCst),
+                    }
+                }
+                None => LanguageLoadStatus {
+                    language,
+                    state: LanguageLoadState::NotLoaded,
+                    load_attempts: 0,
+                    approx_memory_bytes: 0,
+                },
+            })
+            .collect()
+    }
+
+    /// Get the load status of a single language
+    ///
+    /// Unsupported languages report the status of the shared `other` bucket.
+    pub fn language_status(&self, language: &str) -> LanguageLoadStatus {
+        let language = self.language_bucket(language);
+        self.language_statuses()
+            .into_iter()
+            .find(|status| status.language == language)
+            .unwrap_or(LanguageLoadStatus {
+                language: language.to_string(),
+                state: LanguageLoadState::NotLoaded,
+                load_attempts: 0,
+                approx_memory_bytes: 0,
+            })
     }
 }
 
@@ -135,6 +399,20 @@ pub fn t_args_lang(
     manager.get_message_with_args_in_language(key, &language, args)
 }
 
+/// Convenience function to get a count-dependent message in user's language
+///
+/// The message selects its variant on `$count`; `args` supplies any other placeables.
+pub fn t_plural(
+    manager: &Arc<LocalizationManager>,
+    key: &str,
+    count: usize,
+    args: &[(&str, &str)],
+    language_code: Option<&str>,
+) -> String {
+    let language = detect_language(manager, language_code);
+    manager.get_plural_message_in_language(key, &language, count, args)
+}
+
 /// Detect the appropriate language based on user's Telegram language code
 pub fn detect_language(manager: &Arc<LocalizationManager>, language_code: Option<&str>) -> String {
     if let Some(code) = language_code {
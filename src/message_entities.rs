@@ -0,0 +1,260 @@
+//! # Message Entities Module
+//!
+//! Cleans up formatted ingredient text before extraction, using the Telegram
+//! message entities carried by text forwarded from other bots and channels:
+//! - struck-through spans ("~~2 cups sugar~~ use honey instead") are dropped,
+//!   since the author no longer wants them
+//! - bare URLs are dropped; text links keep their anchor text unless the anchor
+//!   is itself a URL
+//! - bold lines ending with ':' ("**Pâte:**") are section headers and are returned
+//!   separately instead of being mixed into the ingredient lines
+//!
+//! Entity offsets and lengths are counted in UTF-16 code units, not bytes or
+//! chars, so accented letters and emoji before an entity shift byte positions.
+//! Out-of-range entities are clamped rather than trusted.
+
+use teloxide::types::{MessageEntity, MessageEntityKind};
+
+/// Ingredient text with formatting entities applied
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityCleanText {
+    /// Remaining text, one line per original line that still has content
+    pub text: String,
+    /// Bold header lines, without the trailing ':'
+    pub section_headers: Vec<String>,
+}
+
+/// How a character is treated after applying entities
+#[derive(Debug, Clone, Copy, Default)]
+struct CharMarks {
+    removed: bool,
+    bold: bool,
+}
+
+/// Whether a piece of text is a bare link rather than readable anchor text
+fn looks_like_url(text: &str) -> bool {
+    let text = text.trim().to_lowercase();
+    text.starts_with("http://") || text.starts_with("https://") || text.starts_with("www.")
+}
+
+/// Apply strikethrough, URL and bold-header entities to a message text
+pub fn apply_message_entities(text: &str, entities: &[MessageEntity]) -> EntityCleanText {
+    // UTF-16 start offset of every char, plus the total length
+    let chars: Vec<char> = text.chars().collect();
+    let mut utf16_starts = Vec::with_capacity(chars.len());
+    let mut utf16_len = 0;
+    for c in &chars {
+        utf16_starts.push(utf16_len);
+        utf16_len += c.len_utf16();
+    }
+
+    let char_range = |entity: &MessageEntity| {
+        let start = entity.offset.min(utf16_len);
+        let end = entity.offset.saturating_add(entity.length).min(utf16_len);
+        let first = utf16_starts.partition_point(|&offset| offset < start);
+        let last = utf16_starts.partition_point(|&offset| offset < end);
+        first..last
+    };
+
+    let mut marks = vec![CharMarks::default(); chars.len()];
+    for entity in entities {
+        let range = char_range(entity);
+        let covered: String = chars[range.clone()].iter().collect();
+        let (remove, bold) = match &entity.kind {
+            MessageEntityKind::Strikethrough | MessageEntityKind::Url => (true, false),
+            MessageEntityKind::TextLink { .. } => (looks_like_url(&covered), false),
+            MessageEntityKind::Bold => (false, true),
+            _ => (false, false),
+        };
+        for mark in &mut marks[range] {
+            mark.removed |= remove;
+            mark.bold |= bold;
+        }
+    }
+
+    let mut result = EntityCleanText::default();
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_all_bold = true;
+    let mut line_had_removal = false;
+
+    let mut finish_line =
+        |line: &mut String, all_bold: bool, had_removal: bool, lines: &mut Vec<String>| {
+            // Removed spans leave doubled or dangling spaces behind
+            let cleaned = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            if all_bold && cleaned.ends_with(':') && cleaned.len() > 1 {
+                let header = cleaned.trim_end_matches(':').trim_end().to_string();
+                result.section_headers.push(header);
+            } else if !cleaned.is_empty() || !had_removal {
+                lines.push(cleaned);
+            }
+            line.clear();
+        };
+
+    for (c, mark) in chars.iter().zip(&marks) {
+        if *c == '\n' {
+            finish_line(&mut line, line_all_bold, line_had_removal, &mut lines);
+            line_all_bold = true;
+            line_had_removal = false;
+            continue;
+        }
+        if mark.removed {
+            line_had_removal = true;
+            continue;
+        }
+        if !c.is_whitespace() {
+            line_all_bold &= mark.bold;
+        }
+        line.push(*c);
+    }
+    finish_line(&mut line, line_all_bold, line_had_removal, &mut lines);
+
+    result.text = lines.join("\n").trim().to_string();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_processing::MeasurementDetector;
+
+    /// UTF-16 offset and length of the first occurrence of `needle` in `text`
+    fn span(text: &str, needle: &str) -> (usize, usize) {
+        let byte_start = text.find(needle).expect("needle in text");
+        (
+            text[..byte_start].encode_utf16().count(),
+            needle.encode_utf16().count(),
+        )
+    }
+
+    fn url(link: &str) -> reqwest::Url {
+        reqwest::Url::parse(link).unwrap()
+    }
+
+    #[test]
+    fn test_strikethrough_is_dropped() {
+        let text = "2 cups sugar use honey instead\n200 g flour";
+        let (offset, length) = span(text, "2 cups sugar");
+        let cleaned = apply_message_entities(text, &[MessageEntity::strikethrough(offset, length)]);
+        assert_eq!(cleaned.text, "use honey instead\n200 g flour");
+    }
+
+    #[test]
+    fn test_fully_struck_line_disappears() {
+        let text = "1 cup milk\n2 eggs\n3 tbsp butter";
+        let (offset, length) = span(text, "2 eggs");
+        let cleaned = apply_message_entities(text, &[MessageEntity::strikethrough(offset, length)]);
+        assert_eq!(cleaned.text, "1 cup milk\n3 tbsp butter");
+    }
+
+    #[test]
+    fn test_strikethrough_changes_extraction_count() {
+        let text = "2 cups sugar use honey instead\n200 g flour\n3 eggs";
+        let (offset, length) = span(text, "2 cups sugar");
+        let detector = MeasurementDetector::new().unwrap();
+
+        let raw_matches = detector.extract_ingredient_measurements(text);
+        let cleaned = apply_message_entities(text, &[MessageEntity::strikethrough(offset, length)]);
+        let cleaned_matches = detector.extract_ingredient_measurements(&cleaned.text);
+
+        assert_eq!(cleaned_matches.len(), raw_matches.len() - 1);
+        assert!(cleaned_matches
+            .iter()
+            .all(|m| !m.ingredient_name.contains("sugar")));
+    }
+
+    #[test]
+    fn test_urls_and_text_links() {
+        let text = "200 g flour https://example.com/flour\n1 pinch Fleur de sel\n2 eggs https://eggs.example";
+        let (url_offset, url_length) = span(text, "https://example.com/flour");
+        let (anchor_offset, anchor_length) = span(text, "Fleur de sel");
+        let (bare_offset, bare_length) = span(text, "https://eggs.example");
+        let entities = [
+            MessageEntity::new(MessageEntityKind::Url, url_offset, url_length),
+            MessageEntity::text_link(
+                url("https://shop.example/sel"),
+                anchor_offset,
+                anchor_length,
+            ),
+            MessageEntity::text_link(url("https://eggs.example"), bare_offset, bare_length),
+        ];
+
+        let cleaned = apply_message_entities(text, &entities);
+        assert_eq!(cleaned.text, "200 g flour\n1 pinch Fleur de sel\n2 eggs");
+    }
+
+    #[test]
+    fn test_bold_colon_lines_are_headers() {
+        let text = "Pâte:\n250 g farine\nGarniture :\n3 pommes\nNote: bake well";
+        let (pate_offset, pate_length) = span(text, "Pâte:");
+        let (garniture_offset, garniture_length) = span(text, "Garniture :");
+        let (note_offset, note_length) = span(text, "Note:");
+        let entities = [
+            MessageEntity::bold(pate_offset, pate_length),
+            MessageEntity::bold(garniture_offset, garniture_length),
+            // Only partly bold: stays an ordinary line
+            MessageEntity::bold(note_offset, note_length),
+        ];
+
+        let cleaned = apply_message_entities(text, &entities);
+        assert_eq!(cleaned.section_headers, vec!["Pâte", "Garniture"]);
+        assert_eq!(cleaned.text, "250 g farine\n3 pommes\nNote: bake well");
+    }
+
+    #[test]
+    fn test_multi_byte_offsets_use_utf16() {
+        // "🥚" is 4 bytes, 1 char and 2 UTF-16 code units; "é" is 2 bytes and 1 unit
+        let text = "🥚 Crème: 2 œufs\n🍋 1 citron pressé 100 g sucre";
+        let (offset, length) = span(text, "100 g sucre");
+        assert_ne!(offset, text.find("100 g sucre").unwrap());
+
+        let cleaned = apply_message_entities(text, &[MessageEntity::strikethrough(offset, length)]);
+        assert_eq!(cleaned.text, "🥚 Crème: 2 œufs\n🍋 1 citron pressé");
+    }
+
+    #[test]
+    fn test_overlapping_entities() {
+        let text = "Sauce:\n2 tbsp soy sauce 1 tsp sesame oil";
+        let (header_offset, header_length) = span(text, "Sauce:");
+        let (bold_offset, bold_length) = span(text, "soy sauce 1 tsp");
+        let (struck_offset, struck_length) = span(text, "1 tsp sesame oil");
+        let entities = [
+            MessageEntity::bold(header_offset, header_length),
+            // Bold overlapping a strikethrough: removal wins, the rest stays
+            MessageEntity::bold(bold_offset, bold_length),
+            MessageEntity::strikethrough(struck_offset, struck_length),
+            // An italic over everything changes nothing
+            MessageEntity::italic(0, text.encode_utf16().count()),
+        ];
+
+        let cleaned = apply_message_entities(text, &entities);
+        assert_eq!(cleaned.section_headers, vec!["Sauce"]);
+        assert_eq!(cleaned.text, "2 tbsp soy sauce");
+    }
+
+    #[test]
+    fn test_out_of_range_entities_are_clamped() {
+        let text = "2 eggs\n1 cup milk";
+        let (offset, _) = span(text, "1 cup milk");
+        let cleaned = apply_message_entities(
+            text,
+            &[
+                MessageEntity::strikethrough(offset, 1000),
+                MessageEntity::bold(5000, 3),
+            ],
+        );
+        assert_eq!(cleaned.text, "2 eggs");
+    }
+
+    #[test]
+    fn test_no_entities_keeps_text() {
+        let text = "2 eggs\n\n1 cup milk";
+        assert_eq!(
+            apply_message_entities(text, &[]),
+            EntityCleanText {
+                text: text.to_string(),
+                section_headers: Vec::new(),
+            }
+        );
+    }
+}
@@ -0,0 +1,441 @@
+//! # Nutrition Estimation Module
+//!
+//! Approximate calories and macronutrients of a saved recipe, behind the
+//! "Nutrition" button of the recipe details.
+//!
+//! Each ingredient is converted to grams, then looked up in a table of values per
+//! 100 g. The bundled table (`config/nutrition.json`) covers common ingredients:
+//! a unit is converted with the ingredient's own gram equivalence when it has one
+//! ("1 cup flour" is 125 g), otherwise with the table-wide one ("1 cup" is 240 g),
+//! and unitless quantities use the weight of one item ("2 eggs"). Ingredients that
+//! can't be converted or found are listed as such instead of failing the estimate.
+
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::ops::AddAssign;
+use tracing::warn;
+
+use crate::db::Ingredient;
+use crate::ingredient_normalization::{normalize_ingredient_name, IngredientNormalizer};
+
+/// Nutrition table shipped with the bot
+const BUNDLED_TABLE: &str = include_str!("../config/nutrition.json");
+
+/// Energy and macronutrients of an amount of food
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct Nutrients {
+    /// Energy in kcal
+    pub calories: f64,
+    /// Protein in grams
+    pub protein: f64,
+    /// Fat in grams
+    pub fat: f64,
+    /// Carbohydrates in grams
+    pub carbs: f64,
+}
+
+impl Nutrients {
+    /// The nutrients multiplied by `factor`
+    pub fn scaled(self, factor: f64) -> Self {
+        Self {
+            calories: self.calories * factor,
+            protein: self.protein * factor,
+            fat: self.fat * factor,
+            carbs: self.carbs * factor,
+        }
+    }
+}
+
+impl AddAssign for Nutrients {
+    fn add_assign(&mut self, other: Self) {
+        self.calories += other.calories;
+        self.protein += other.protein;
+        self.fat += other.fat;
+        self.carbs += other.carbs;
+    }
+}
+
+/// Source of nutrition values and gram equivalences
+pub trait NutritionProvider: Send + Sync {
+    /// Nutrients in 100 g of the named ingredient, `None` when it is not known
+    fn per_100g(&self, name: &str) -> Option<Nutrients>;
+
+    /// Weight in grams of `quantity` of the ingredient in `unit` (`None` for a count),
+    /// `None` when the unit can't be converted
+    fn grams(&self, name: &str, quantity: f64, unit: Option<&str>) -> Option<f64>;
+}
+
+/// An ingredient line to estimate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IngredientAmount<'a> {
+    pub name: &'a str,
+    pub quantity: Option<f64>,
+    pub unit: Option<&'a str>,
+}
+
+impl<'a> From<&'a Ingredient> for IngredientAmount<'a> {
+    fn from(ingredient: &'a Ingredient) -> Self {
+        Self {
+            name: &ingredient.name,
+            quantity: ingredient.quantity,
+            unit: ingredient.unit.as_deref(),
+        }
+    }
+}
+
+/// Why an ingredient is left out of an estimate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Saved without a quantity ("salt to taste")
+    NoQuantity,
+    /// Not in the nutrition table
+    UnknownIngredient,
+    /// Known ingredient whose unit has no gram equivalence
+    UnknownUnit,
+}
+
+/// Estimate of one ingredient
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientEstimate {
+    pub name: String,
+    pub grams: f64,
+    pub nutrients: Nutrients,
+}
+
+/// Estimate of a recipe: the total of the ingredients that could be estimated
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecipeNutrition {
+    pub total: Nutrients,
+    pub estimated: Vec<IngredientEstimate>,
+    pub skipped: Vec<(String, SkipReason)>,
+}
+
+impl RecipeNutrition {
+    /// Whether no ingredient could be estimated
+    pub fn is_empty(&self) -> bool {
+        self.estimated.is_empty()
+    }
+}
+
+/// Estimate one ingredient, or the reason it can't be
+pub fn estimate_ingredient(
+    provider: &dyn NutritionProvider,
+    ingredient: IngredientAmount<'_>,
+) -> Result<IngredientEstimate, SkipReason> {
+    let quantity = ingredient
+        .quantity
+        .filter(|quantity| *quantity > 0.0 && quantity.is_finite())
+        .ok_or(SkipReason::NoQuantity)?;
+    let per_100g = provider
+        .per_100g(ingredient.name)
+        .ok_or(SkipReason::UnknownIngredient)?;
+    let grams = provider
+        .grams(ingredient.name, quantity, ingredient.unit)
+        .ok_or(SkipReason::UnknownUnit)?;
+    Ok(IngredientEstimate {
+        name: ingredient.name.to_string(),
+        grams,
+        nutrients: per_100g.scaled(grams / 100.0),
+    })
+}
+
+/// Sum the estimates of a recipe's ingredients, listing the ones left out
+pub fn estimate_recipe<'a>(
+    provider: &dyn NutritionProvider,
+    ingredients: impl IntoIterator<Item = IngredientAmount<'a>>,
+) -> RecipeNutrition {
+    let mut nutrition = RecipeNutrition::default();
+    for ingredient in ingredients {
+        match estimate_ingredient(provider, ingredient) {
+            Ok(estimate) => {
+                nutrition.total += estimate.nutrients;
+                nutrition.estimated.push(estimate);
+            }
+            Err(reason) => nutrition
+                .skipped
+                .push((ingredient.name.to_string(), reason)),
+        }
+    }
+    nutrition
+}
+
+/// A food of the nutrition table
+#[derive(Debug, Clone, Deserialize)]
+struct Food {
+    #[serde(default)]
+    aliases: Vec<String>,
+    per_100g: Nutrients,
+    /// Gram equivalences specific to this food, overriding the table-wide ones
+    #[serde(default)]
+    grams_per_unit: HashMap<String, f64>,
+    /// Weight of one item, for quantities without a unit
+    #[serde(default)]
+    grams_each: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TableFile {
+    units: HashMap<String, f64>,
+    foods: HashMap<String, Food>,
+}
+
+/// [`NutritionProvider`] backed by a JSON table of foods
+///
+/// The JSON object has `units`, mapping unit names to grams, and `foods`, mapping
+/// food names to `per_100g` nutrients, optional `aliases`, `grams_per_unit` and
+/// `grams_each`. Names are matched after normalization; a name containing a known
+/// food ("boneless chicken breast") matches the longest one it contains.
+#[derive(Debug, Clone, Default)]
+pub struct TableNutritionProvider {
+    units: HashMap<String, f64>,
+    foods: Vec<Food>,
+    /// Normalized food names and aliases, to their index in `foods`
+    names: HashMap<String, usize>,
+}
+
+impl TableNutritionProvider {
+    /// Parse a nutrition table
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let table: TableFile = serde_json::from_str(json)?;
+        let normalizer = IngredientNormalizer::default();
+        let mut provider = Self {
+            units: table
+                .units
+                .into_iter()
+                .map(|(unit, grams)| (normalize_unit(&unit), grams))
+                .collect(),
+            ..Self::default()
+        };
+        for (name, mut food) in table.foods {
+            let index = provider.foods.len();
+            for alias in std::iter::once(&name).chain(&food.aliases) {
+                provider
+                    .names
+                    .insert(normalizer.normalize(alias).name, index);
+            }
+            food.grams_per_unit = food
+                .grams_per_unit
+                .into_iter()
+                .map(|(unit, grams)| (normalize_unit(&unit), grams))
+                .collect();
+            provider.foods.push(food);
+        }
+        Ok(provider)
+    }
+
+    /// The table bundled with the bot
+    pub fn bundled() -> Self {
+        Self::from_json(BUNDLED_TABLE).unwrap_or_else(|e| {
+            warn!(error = %e, "Failed to parse the bundled nutrition table");
+            Self::default()
+        })
+    }
+
+    /// The food an ingredient name refers to
+    fn food(&self, name: &str) -> Option<&Food> {
+        let name = normalize_ingredient_name(name).name;
+        let words: Vec<&str> = name.split(' ').collect();
+
+        // Longest run of words naming a food, leftmost first
+        for length in (1..=words.len()).rev() {
+            for window in words.windows(length) {
+                let candidate = window.join(" ");
+                let singular = candidate.strip_suffix('s').map(str::to_string);
+                let plural = format!("{candidate}s");
+                let found = std::iter::once(candidate)
+                    .chain(singular)
+                    .chain(std::iter::once(plural))
+                    .find_map(|candidate| self.names.get(&candidate));
+                if let Some(&index) = found {
+                    return Some(&self.foods[index]);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl NutritionProvider for TableNutritionProvider {
+    fn per_100g(&self, name: &str) -> Option<Nutrients> {
+        self.food(name).map(|food| food.per_100g)
+    }
+
+    fn grams(&self, name: &str, quantity: f64, unit: Option<&str>) -> Option<f64> {
+        let food = self.food(name)?;
+        let unit = unit.map(normalize_unit).filter(|unit| !unit.is_empty());
+        let grams_each = match unit {
+            // Plural units fall back to their singular ("cups" to "cup")
+            Some(unit) => std::iter::once(unit.as_str())
+                .chain(unit.strip_suffix('s'))
+                .find_map(|unit| {
+                    food.grams_per_unit
+                        .get(unit)
+                        .or_else(|| self.units.get(unit))
+                })
+                .copied(),
+            None => food.grams_each,
+        }?;
+        Some(quantity * grams_each)
+    }
+}
+
+/// Lowercase and trim a unit, dropping an abbreviation's final dot
+fn normalize_unit(unit: &str) -> String {
+    let unit = unit.trim().to_lowercase();
+    match unit.strip_suffix('.') {
+        Some(stripped) => stripped.to_string(),
+        None => unit,
+    }
+}
+
+lazy_static! {
+    static ref BUNDLED: TableNutritionProvider = TableNutritionProvider::bundled();
+}
+
+/// Estimate a saved recipe with the bundled nutrition table
+pub fn estimate_saved_recipe(ingredients: &[Ingredient]) -> RecipeNutrition {
+    estimate_recipe(&*BUNDLED, ingredients.iter().map(IngredientAmount::from))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> TableNutritionProvider {
+        TableNutritionProvider::from_json(
+            r#"{
+                "units": { "g": 1, "kg": 1000, "cup": 240, "tbsp": 15 },
+                "foods": {
+                    "flour": {
+                        "per_100g": { "calories": 364, "protein": 10, "fat": 1, "carbs": 76 },
+                        "grams_per_unit": { "cup": 125 }
+                    },
+                    "milk": {
+                        "aliases": ["lait"],
+                        "per_100g": { "calories": 60, "protein": 3, "fat": 3, "carbs": 5 }
+                    },
+                    "eggs": {
+                        "per_100g": { "calories": 140, "protein": 12, "fat": 10, "carbs": 1 },
+                        "grams_each": 50
+                    },
+                    "chicken": {
+                        "per_100g": { "calories": 200, "protein": 25, "fat": 10, "carbs": 0 }
+                    },
+                    "chicken breast": {
+                        "per_100g": { "calories": 120, "protein": 22, "fat": 3, "carbs": 0 }
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn amount<'a>(
+        quantity: Option<f64>,
+        unit: Option<&'a str>,
+        name: &'a str,
+    ) -> IngredientAmount<'a> {
+        IngredientAmount {
+            name,
+            quantity,
+            unit,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn test_units_convert_to_grams() {
+        let table = table();
+        // The food's own equivalence wins over the table-wide one
+        assert_close(table.grams("flour", 2.0, Some("Cups")).unwrap(), 250.0);
+        assert_close(table.grams("milk", 0.5, Some("cup")).unwrap(), 120.0);
+        assert_close(table.grams("flour", 1.5, Some("kg")).unwrap(), 1500.0);
+        assert_close(table.grams("milk", 2.0, Some("tbsp.")).unwrap(), 30.0);
+        // Counts use the weight of one item
+        assert_close(table.grams("eggs", 3.0, None).unwrap(), 150.0);
+        assert_close(table.grams("eggs", 3.0, Some(" ")).unwrap(), 150.0);
+
+        assert_eq!(table.grams("milk", 1.0, None), None);
+        assert_eq!(table.grams("flour", 1.0, Some("handful")), None);
+    }
+
+    #[test]
+    fn test_names_match_aliases_plurals_and_longest_food() {
+        let table = table();
+        assert!(table.per_100g("Lait").is_some());
+        assert!(table.per_100g("egg").is_some());
+        assert!(table.per_100g("all-purpose flour, sifted").is_some());
+        assert_eq!(
+            table.per_100g("boneless chicken breast").unwrap().calories,
+            120.0
+        );
+        assert_eq!(table.per_100g("roast chicken").unwrap().calories, 200.0);
+        assert_eq!(table.per_100g("dragon fruit"), None);
+    }
+
+    #[test]
+    fn test_ingredient_estimate_scales_per_100g() {
+        let estimate =
+            estimate_ingredient(&table(), amount(Some(2.0), Some("cups"), "flour")).unwrap();
+        assert_close(estimate.grams, 250.0);
+        assert_close(estimate.nutrients.calories, 910.0);
+        assert_close(estimate.nutrients.protein, 25.0);
+        assert_close(estimate.nutrients.fat, 2.5);
+        assert_close(estimate.nutrients.carbs, 190.0);
+    }
+
+    #[test]
+    fn test_recipe_sums_estimates_and_lists_skipped_ingredients() {
+        let nutrition = estimate_recipe(
+            &table(),
+            [
+                amount(Some(2.0), Some("cups"), "flour"),
+                amount(Some(2.0), None, "eggs"),
+                amount(Some(250.0), Some("g"), "milk"),
+                amount(None, None, "salt"),
+                amount(Some(1.0), None, "dragon fruit"),
+                amount(Some(1.0), Some("handful"), "flour"),
+            ],
+        );
+
+        assert_eq!(nutrition.estimated.len(), 3);
+        assert_close(nutrition.total.calories, 910.0 + 140.0 + 150.0);
+        assert_close(nutrition.total.protein, 25.0 + 12.0 + 7.5);
+        assert_close(nutrition.total.fat, 2.5 + 10.0 + 7.5);
+        assert_close(nutrition.total.carbs, 190.0 + 1.0 + 12.5);
+        assert_eq!(
+            nutrition.skipped,
+            [
+                ("salt".to_string(), SkipReason::NoQuantity),
+                ("dragon fruit".to_string(), SkipReason::UnknownIngredient),
+                ("flour".to_string(), SkipReason::UnknownUnit),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recipe_without_known_ingredients_is_empty() {
+        let nutrition = estimate_recipe(&table(), [amount(Some(1.0), None, "dragon fruit")]);
+        assert!(nutrition.is_empty());
+        assert_eq!(nutrition.total, Nutrients::default());
+    }
+
+    #[test]
+    fn test_bundled_table_parses() {
+        let bundled: TableFile = serde_json::from_str(BUNDLED_TABLE).unwrap();
+        assert!(bundled.foods.len() > 20);
+
+        let table = TableNutritionProvider::bundled();
+        let estimate =
+            estimate_ingredient(&table, amount(Some(2.0), Some("cups"), "flour")).unwrap();
+        assert_close(estimate.grams, 250.0);
+        assert!(estimate_ingredient(&table, amount(Some(2.0), None, "eggs")).is_ok());
+    }
+}
@@ -19,9 +19,9 @@ pub use metrics::*;
 pub use system_monitoring::*;
 pub use tracing_mod::*;
 
-/// Initialize the complete observability stack
+/// Initialize the complete observability stack from the installed configuration
 pub async fn init_observability() -> anyhow::Result<()> {
-    let config = crate::observability_config::ObservabilityConfig::from_env();
+    let config = crate::config::current().observability.clone();
     init_observability_with_config(config).await
 }
 
@@ -55,13 +55,13 @@ pub async fn init_observability_with_config(
     Ok(())
 }
 
-/// Initialize observability with health check dependencies
+/// Initialize observability with health check dependencies and the installed configuration
 pub async fn init_observability_with_health_checks(
     db_pool: Option<std::sync::Arc<sqlx::PgPool>>,
     bot_token: Option<String>,
 ) -> anyhow::Result<()> {
-    let config = crate::config::AppConfig::from_env()?;
-    init_observability_with_health_checks_and_config(db_pool, bot_token, &config).await
+    init_observability_with_health_checks_and_config(db_pool, bot_token, crate::config::current())
+        .await
 }
 
 /// Initialize observability with health check dependencies and custom configuration
@@ -90,6 +90,7 @@ pub async fn init_observability_with_health_checks_and_config(
         config.server.health_port,
         db_pool.clone(),
         bot_token.clone(),
+        config.observability.health_check_timeouts,
     )
     .await?;
 
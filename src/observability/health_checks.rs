@@ -3,32 +3,127 @@
 //! This module provides:
 //! - Database connectivity checks
 //! - OCR engine availability checks
-//! - Bot token validation checks
-//! - Comprehensive readiness checks
+//! - Bot token validation and Telegram API reachability checks
+//! - Comprehensive readiness checks, reported per dependency
 
 use anyhow::Result;
-use leptess::LepTess;
+use serde::Serialize;
 use sqlx::PgPool;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::observability_config::HealthCheckTimeouts;
+
+/// Outcome of one dependency check
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyStatus {
+    /// Dependency checked, as labelled in the health check metrics
+    pub name: &'static str,
+    /// Whether the check passed within its timeout
+    pub healthy: bool,
+    /// Time the check took
+    pub latency_ms: u64,
+    /// Why the check failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of the readiness checks, served as JSON by `/readyz`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessReport {
+    /// Whether every dependency is healthy
+    pub ready: bool,
+    /// Status of each dependency checked
+    pub checks: Vec<DependencyStatus>,
+}
+
+/// Run a check with a timeout, recording its outcome in the health check metrics
+async fn timed_check(
+    name: &'static str,
+    timeout: Duration,
+    check: impl Future<Output = Result<()>>,
+) -> DependencyStatus {
+    let start = Instant::now();
+    let result = tokio::time::timeout(timeout, check)
+        .await
+        .unwrap_or_else(|_| {
+            Err(anyhow::anyhow!(
+                "Health check timed out after {}ms",
+                timeout.as_millis()
+            ))
+        });
+    let latency = start.elapsed();
+    crate::observability::metrics::record_health_check_metrics(name, result.is_ok(), latency);
+
+    DependencyStatus {
+        name,
+        healthy: result.is_ok(),
+        latency_ms: latency.as_millis() as u64,
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+/// Check every dependency concurrently, each within its own timeout
+pub async fn run_readiness_checks(
+    db_pool: Option<&PgPool>,
+    bot_token: Option<&str>,
+    timeouts: &HealthCheckTimeouts,
+) -> ReadinessReport {
+    let (database, telegram, ocr) = tokio::join!(
+        async {
+            match db_pool {
+                Some(pool) => Some(
+                    timed_check("database", timeouts.database, check_database_health(pool)).await,
+                ),
+                None => None,
+            }
+        },
+        async {
+            match bot_token {
+                Some(token) => Some(
+                    timed_check(
+                        "telegram_bot",
+                        timeouts.telegram,
+                        check_telegram_api_health(token),
+                    )
+                    .await,
+                ),
+                None => None,
+            }
+        },
+        timed_check("ocr", timeouts.ocr, check_ocr_health()),
+    );
+
+    let checks: Vec<DependencyStatus> = [database, telegram, Some(ocr)]
+        .into_iter()
+        .flatten()
+        .collect();
+    ReadinessReport {
+        ready: checks.iter().all(|check| check.healthy),
+        checks,
+    }
+}
 
 /// Perform comprehensive readiness checks
 pub async fn perform_readiness_checks(
     db_pool: Option<std::sync::Arc<PgPool>>,
     bot_token: Option<String>,
 ) -> Result<()> {
-    // Check database connectivity
-    if let Some(pool) = &db_pool {
-        check_database_health(pool.as_ref()).await?;
-    }
-
-    // Check OCR engine availability
-    check_ocr_health().await?;
-
-    // Check bot token validity
-    if let Some(token) = &bot_token {
-        check_bot_token_health(token).await?;
+    let report = run_readiness_checks(
+        db_pool.as_deref(),
+        bot_token.as_deref(),
+        &HealthCheckTimeouts::default(),
+    )
+    .await;
+
+    match report.checks.iter().find(|check| !check.healthy) {
+        Some(failed) => Err(anyhow::anyhow!(
+            "{} health check failed: {}",
+            failed.name,
+            failed.error.as_deref().unwrap_or("unknown error")
+        )),
+        None => Ok(()),
     }
-
-    Ok(())
 }
 
 /// Check database connectivity and basic query capability
@@ -43,17 +138,17 @@ pub async fn check_database_health(pool: &PgPool) -> Result<()> {
     Ok(())
 }
 
-/// Check OCR engine availability by testing Tesseract initialization
+/// Check OCR engine availability by getting a Tesseract instance from the OCR pool
+///
+/// The first check initializes the instance photos are read with; later checks reuse it.
 pub async fn check_ocr_health() -> Result<()> {
-    // Try to create a minimal Tesseract instance to test OCR availability
-    // This is a lightweight check that doesn't require actual image processing
-    match LepTess::new(None, "eng") {
-        Ok(_) => {
-            tracing::debug!("OCR health check passed");
-            Ok(())
-        }
-        Err(e) => Err(anyhow::anyhow!("OCR health check failed: {}", e)),
-    }
+    tokio::task::spawn_blocking(crate::bot::image_processing::ensure_ocr_instance)
+        .await
+        .map_err(|e| anyhow::anyhow!("OCR health check panicked: {}", e))?
+        .map_err(|e| anyhow::anyhow!("OCR health check failed: {}", e))?;
+
+    tracing::debug!("OCR health check passed");
+    Ok(())
 }
 
 /// Check Telegram bot token validity by testing API access
@@ -73,16 +168,36 @@ pub async fn check_bot_token_health(token: &str) -> Result<()> {
     Ok(())
 }
 
+/// Check that the Telegram API is reachable and accepts the bot token with `getMe`
+pub async fn check_telegram_api_health(token: &str) -> Result<()> {
+    use teloxide::prelude::Requester;
+
+    check_bot_token_health(token).await?;
+    teloxide::Bot::new(token)
+        .get_me()
+        .await
+        .map_err(|e| anyhow::anyhow!("Telegram API health check failed: {}", e))?;
+
+    tracing::debug!("Telegram API health check passed");
+    Ok(())
+}
+
 /// Start a background task to periodically record health check metrics
+///
+/// The task stops once `shutdown` is cancelled.
 pub async fn start_health_metrics_recorder(
     db_pool: Option<std::sync::Arc<PgPool>>,
     bot_token: Option<String>,
+    shutdown: tokio_util::sync::CancellationToken,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(60)); // Every minute
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
 
             // Perform database health check
             if let Some(pool) = &db_pool {
@@ -9,7 +9,7 @@
 use anyhow::Result;
 use hyper::server::conn::http1;
 use hyper_util::rt::TokioIo;
-use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use sqlx::PgPool;
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
@@ -17,7 +17,8 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
 
-use crate::observability_config::ObservabilityConfig;
+use crate::observability::health_checks::{run_readiness_checks, ReadinessReport};
+use crate::observability_config::{HealthCheckTimeouts, ObservabilityConfig};
 
 /// Simple rate limiter for HTTP requests
 #[derive(Debug)]
@@ -63,10 +64,9 @@ impl RateLimiter {
 
 /// Check authentication token from Authorization header
 pub fn check_auth(req: &hyper::Request<hyper::body::Incoming>) -> bool {
-    // Get auth token from environment
-    let expected_token = match std::env::var("METRICS_AUTH_TOKEN") {
-        Ok(token) if !token.is_empty() => token,
-        _ => return true, // No token required if not set (for development)
+    // Token from METRICS_AUTH_TOKEN
+    let Some(expected_token) = &crate::config::current().server.metrics_auth_token else {
+        return true; // No token required if not set (for development)
     };
 
     // Check Authorization header
@@ -97,10 +97,24 @@ pub fn check_request_size(req: &hyper::Request<hyper::body::Incoming>) -> bool {
     true // No content-length header (GET requests)
 }
 
+/// Histogram buckets of Telegram handler durations, in seconds, from 10 ms to 10 s
+pub const TELEGRAM_HANDLER_BUCKETS: &[f64] =
+    &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Prometheus exporter with the bucket layout of the bot's histograms
+///
+/// Histograms without explicit buckets are exported as summaries.
+pub fn prometheus_builder() -> Result<PrometheusBuilder> {
+    Ok(PrometheusBuilder::new().set_buckets_for_metric(
+        Matcher::Full("telegram_handler_duration_seconds".to_string()),
+        TELEGRAM_HANDLER_BUCKETS,
+    )?)
+}
+
 /// Initialize metrics collection with Prometheus exporter and configuration
 pub fn init_metrics_with_config(config: &ObservabilityConfig) -> Result<PrometheusHandle> {
     // Create Prometheus recorder
-    let builder = PrometheusBuilder::new();
+    let builder = prometheus_builder()?;
     let handle = builder.install_recorder()?;
 
     tracing::info!(
@@ -114,7 +128,7 @@ pub fn init_metrics_with_config(config: &ObservabilityConfig) -> Result<Promethe
 #[allow(dead_code)]
 pub fn init_metrics() -> Result<PrometheusHandle> {
     // Create Prometheus recorder
-    let builder = PrometheusBuilder::new();
+    let builder = prometheus_builder()?;
     let handle = builder.install_recorder()?;
 
     tracing::info!("Metrics collection initialized");
@@ -127,10 +141,7 @@ pub async fn start_metrics_server_basic_with_config(
     port: u16,
 ) -> Result<()> {
     // Determine bind address - localhost for security unless explicitly configured
-    let bind_all = std::env::var("METRICS_BIND_ALL_INTERFACES")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse::<bool>()
-        .unwrap_or(false);
+    let bind_all = crate::config::current().server.metrics_bind_all_interfaces;
 
     let addr = if bind_all {
         SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port)
@@ -203,10 +214,10 @@ pub async fn start_metrics_server_basic_with_config(
                                                 metrics,
                                             ))
                                         }
-                                        (&hyper::Method::GET, "/health/live") => {
+                                        (&hyper::Method::GET, "/healthz" | "/health/live") => {
                                             Ok(hyper::Response::new("OK".to_string()))
                                         }
-                                        (&hyper::Method::GET, "/health/ready") => {
+                                        (&hyper::Method::GET, "/readyz" | "/health/ready") => {
                                             Ok(hyper::Response::new("OK".to_string()))
                                         }
                                         _ => {
@@ -246,18 +257,34 @@ pub async fn start_metrics_server_basic_with_config(
     Ok(())
 }
 
+/// Build the `/readyz` JSON response, 503 when a dependency is down
+pub fn readiness_response(report: &ReadinessReport) -> hyper::Response<String> {
+    let body = serde_json::to_string(report).unwrap_or_else(|_| "{}".to_string());
+    let mut response = hyper::Response::new(body);
+    if !report.ready {
+        *response.status_mut() = hyper::StatusCode::SERVICE_UNAVAILABLE;
+    }
+    response.headers_mut().insert(
+        "content-type",
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
 /// Start metrics server with health checks
+///
+/// Serves `/healthz` (liveness) and `/readyz` (readiness, per-dependency JSON) next
+/// to `/metrics`. The probes skip rate limiting and authentication so orchestrators
+/// can poll them freely.
 pub async fn start_metrics_server_with_health_checks(
     metrics_handle: PrometheusHandle,
     port: u16,
     db_pool: Option<Arc<PgPool>>,
     bot_token: Option<String>,
+    timeouts: HealthCheckTimeouts,
 ) -> Result<()> {
     // Determine bind address - localhost for security unless explicitly configured
-    let bind_all = std::env::var("METRICS_BIND_ALL_INTERFACES")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse::<bool>()
-        .unwrap_or(false);
+    let bind_all = crate::config::current().server.metrics_bind_all_interfaces;
 
     let addr = if bind_all {
         SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), port)
@@ -297,6 +324,26 @@ pub async fn start_metrics_server_with_health_checks(
                                 let peer_ip = peer_addr.ip().to_string();
                                 let rate_limiter = rate_limiter.clone();
                                 async move {
+                                    match (req.method(), req.uri().path()) {
+                                        (&hyper::Method::GET, "/healthz" | "/health/live") => {
+                                            // Liveness probe - just check if the service is running
+                                            return Ok::<_, std::convert::Infallible>(
+                                                hyper::Response::new("OK".to_string()),
+                                            );
+                                        }
+                                        (&hyper::Method::GET, "/readyz" | "/health/ready") => {
+                                            // Readiness probe - check if all dependencies are available
+                                            let report = run_readiness_checks(
+                                                db_pool.as_deref(),
+                                                bot_token.as_deref(),
+                                                &timeouts,
+                                            )
+                                            .await;
+                                            return Ok(readiness_response(&report));
+                                        }
+                                        _ => {}
+                                    }
+
                                     // Rate limiting check
                                     if !rate_limiter.is_allowed(&peer_ip) {
                                         let mut response =
@@ -341,29 +388,6 @@ pub async fn start_metrics_server_with_health_checks(
                                             );
                                             Ok::<_, std::convert::Infallible>(response)
                                         }
-                                        (&hyper::Method::GET, "/health/live") => {
-                                            // Liveness probe - just check if the service is running
-                                            Ok(hyper::Response::new("OK".to_string()))
-                                        }
-                                        (&hyper::Method::GET, "/health/ready") => {
-                                            // Readiness probe - check if all dependencies are available
-                                            match crate::observability::health_checks::perform_readiness_checks(
-                                                db_pool.clone(),
-                                                bot_token.clone(),
-                                            )
-                                            .await
-                                            {
-                                                Ok(_) => Ok(hyper::Response::new("OK".to_string())),
-                                                Err(e) => {
-                                                    let mut response = hyper::Response::new(
-                                                        format!("NOT READY: {}", e),
-                                                    );
-                                                    *response.status_mut() =
-                                                        hyper::StatusCode::SERVICE_UNAVAILABLE;
-                                                    Ok(response)
-                                                }
-                                            }
-                                        }
                                         _ => {
                                             let mut response =
                                                 hyper::Response::new("Not Found".to_string());
@@ -401,13 +425,89 @@ pub async fn start_metrics_server_with_health_checks(
     Ok(())
 }
 
+/// Successes and failures counted by `record_ocr_metrics`, kept in process so the
+/// bot can report them without scraping its own Prometheus endpoint
+static OCR_OPERATION_COUNTS: [std::sync::atomic::AtomicU64; 2] = [
+    std::sync::atomic::AtomicU64::new(0),
+    std::sync::atomic::AtomicU64::new(0),
+];
+
+/// OCR operations recorded since startup, as (successes, failures)
+pub fn ocr_operation_counts() -> (u64, u64) {
+    use std::sync::atomic::Ordering;
+    (
+        OCR_OPERATION_COUNTS[0].load(Ordering::Relaxed),
+        OCR_OPERATION_COUNTS[1].load(Ordering::Relaxed),
+    )
+}
+
 /// Record OCR operation metrics
 pub fn record_ocr_metrics(success: bool, duration: std::time::Duration, image_size: u64) {
+    OCR_OPERATION_COUNTS[usize::from(!success)].fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     metrics::counter!("ocr_operations_total", "result" => if success { "success" } else { "failure" }).increment(1);
     metrics::histogram!("ocr_duration_seconds").record(duration.as_secs_f64());
     metrics::histogram!("ocr_image_size_bytes").record(image_size as f64);
 }
 
+/// Record the source class chosen for OCR preprocessing (screenshot, photo, scan)
+pub fn record_preprocessing_source_class(source_class: &str) {
+    metrics::counter!("ocr_preprocessing_source_class_total", "class" => source_class.to_string())
+        .increment(1);
+}
+
+/// Record a preprocessing stage run by the quality-driven pipeline (clahe, deskew, ...)
+pub fn record_preprocessing_step(step: &str) {
+    metrics::counter!("ocr_preprocessing_steps_total", "step" => step.to_string()).increment(1);
+}
+
+/// Record the outcome of the second OCR pass over the densest text column
+/// (recovered, no_measurements, no_column, budget_exhausted, timeout, error)
+pub fn record_ocr_second_pass(outcome: &str) {
+    metrics::counter!("ocr_second_pass_total", "outcome" => outcome.to_string()).increment(1);
+}
+
+/// Record the Tesseract instances pooled for a language and model combination
+pub fn set_ocr_pool_size(pool: &str, instances: usize) {
+    metrics::gauge!("ocr_pool_instances", "pool" => pool.to_string()).set(instances as f64);
+}
+
+/// Record how long a checkout waited for a free pooled Tesseract instance
+pub fn record_ocr_pool_wait(pool: &str, wait: std::time::Duration) {
+    metrics::histogram!("ocr_pool_wait_seconds", "pool" => pool.to_string())
+        .record(wait.as_secs_f64());
+}
+
+/// Record how long a pooled Tesseract instance stayed checked out
+pub fn record_ocr_pool_checkout(pool: &str, held: std::time::Duration) {
+    metrics::histogram!("ocr_pool_checkout_seconds", "pool" => pool.to_string())
+        .record(held.as_secs_f64());
+}
+
+/// Record a pooled Tesseract instance dropped after repeated extraction errors
+pub fn record_ocr_pool_recycled(pool: &str) {
+    metrics::counter!("ocr_pool_instances_recycled_total", "pool" => pool.to_string()).increment(1);
+}
+
+/// Record the outcome of one user's weekly digest ("sent", "skipped" or "failed")
+pub fn record_user_digest_outcome(outcome: &str) {
+    metrics::counter!("user_digest_total", "outcome" => outcome.to_string()).increment(1);
+}
+
+/// Record which retry preprocessing profile found the most measurements ("none" when none did)
+pub fn record_ocr_retry_profile_metrics(profile: &str) {
+    metrics::counter!("ocr_retry_profile_wins_total", "profile" => profile.to_string())
+        .increment(1);
+}
+
+/// Publish the OCR budgets derived from the container memory limit
+pub fn record_resource_budget_metrics(budget: &crate::resource_limits::ResourceBudget) {
+    metrics::gauge!("ocr_memory_limit_bytes")
+        .set(budget.memory_limit_bytes.map_or(0.0, |limit| limit as f64));
+    metrics::gauge!("ocr_concurrency_limit").set(budget.ocr_concurrency as f64);
+    metrics::gauge!("ocr_request_memory_budget_bytes").set(budget.request_memory_bytes as f64);
+    metrics::gauge!("ocr_pixel_budget").set(budget.pixel_budget as f64);
+}
+
 /// Parameters for OCR performance metrics recording
 #[derive(Debug, Clone)]
 pub struct OcrPerformanceMetricsParams {
@@ -499,6 +599,12 @@ pub fn record_db_performance_metrics(
     metrics::counter!("db_performance_class_total", "operation" => operation, "class" => perf_class.to_string()).increment(1);
 }
 
+/// Record how many keys a batched database query looked up at once
+pub fn record_db_batch_size(operation: &str, batch_size: usize) {
+    let operation = operation.to_string();
+    metrics::histogram!("db_batch_size", "operation" => operation).record(batch_size as f64);
+}
+
 /// Query complexity classification for performance monitoring
 #[derive(Debug, Clone, Copy)]
 pub enum QueryComplexity {
@@ -574,6 +680,168 @@ pub fn record_telegram_message(message_type: &str) {
     metrics::counter!("telegram_messages_total", "type" => message_type).increment(1);
 }
 
+/// Logical operation of a Telegram interaction, the label of its handler metrics
+///
+/// A fixed set, so label cardinality stays bounded whatever users send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TelegramOperation {
+    /// Review confirmed, or a name conflict resolved, before saving
+    ReviewConfirm,
+    /// Ingredient deleted from a review, or the deletion undone
+    ReviewDelete,
+    /// Other review buttons: edit, adjust, rename, add more, cancel
+    ReviewEdit,
+    /// Saved recipe ingredients being edited
+    SavedEdit,
+    /// Page of a recipe list
+    Pagination,
+    /// Recipe opened from a list
+    RecipeSelect,
+    /// Recipe menu action: rename, scale, tags, share...
+    RecipeAction,
+    /// Recipe deletion, single or bulk
+    RecipeDelete,
+    /// Follow-up buttons after saving a recipe
+    Workflow,
+    /// Language and unit settings
+    Settings,
+    /// OCR retries and cancellation
+    Ocr,
+    /// Extraction problem reports
+    Report,
+    /// Callback matching no known button
+    OtherCallback,
+    /// Photo message
+    Photo,
+    /// Document message
+    Document,
+    /// Voice or audio message
+    Voice,
+    /// Text message starting with a command
+    Command,
+    /// Other text message
+    Text,
+    /// Unsupported message
+    OtherMessage,
+}
+
+impl TelegramOperation {
+    /// Every operation, in label order
+    pub const ALL: [TelegramOperation; 19] = [
+        Self::ReviewConfirm,
+        Self::ReviewDelete,
+        Self::ReviewEdit,
+        Self::SavedEdit,
+        Self::Pagination,
+        Self::RecipeSelect,
+        Self::RecipeAction,
+        Self::RecipeDelete,
+        Self::Workflow,
+        Self::Settings,
+        Self::Ocr,
+        Self::Report,
+        Self::OtherCallback,
+        Self::Photo,
+        Self::Document,
+        Self::Voice,
+        Self::Command,
+        Self::Text,
+        Self::OtherMessage,
+    ];
+
+    /// Metric label of the operation
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReviewConfirm => "review_confirm",
+            Self::ReviewDelete => "review_delete",
+            Self::ReviewEdit => "review_edit",
+            Self::SavedEdit => "saved_edit",
+            Self::Pagination => "pagination",
+            Self::RecipeSelect => "recipe_select",
+            Self::RecipeAction => "recipe_action",
+            Self::RecipeDelete => "recipe_delete",
+            Self::Workflow => "workflow",
+            Self::Settings => "settings",
+            Self::Ocr => "ocr",
+            Self::Report => "report",
+            Self::OtherCallback => "other_callback",
+            Self::Photo => "photo",
+            Self::Document => "document",
+            Self::Voice => "voice",
+            Self::Command => "command",
+            Self::Text => "text",
+            Self::OtherMessage => "other_message",
+        }
+    }
+}
+
+/// Handler duration from which an interaction's trace id is logged
+pub const SLOW_TELEGRAM_INTERACTION: Duration = Duration::from_secs(1);
+
+/// Record how long a Telegram handler took, by operation
+///
+/// The Prometheus exporter cannot attach exemplars, so the trace id of a slow
+/// interaction, when traces are exported, is logged next to the histogram instead.
+pub fn record_telegram_handler_metrics(
+    operation: TelegramOperation,
+    duration: Duration,
+    trace_id: Option<&str>,
+) {
+    let operation_label = operation.as_str();
+    metrics::counter!("telegram_handler_requests_total", "operation" => operation_label)
+        .increment(1);
+    metrics::histogram!("telegram_handler_duration_seconds", "operation" => operation_label)
+        .record(duration.as_secs_f64());
+
+    if duration >= SLOW_TELEGRAM_INTERACTION {
+        if let Some(trace_id) = trace_id {
+            tracing::info!(
+                operation = operation_label,
+                duration_ms = %duration.as_millis(),
+                trace_id = %trace_id,
+                "Slow Telegram interaction"
+            );
+        }
+    }
+}
+
+/// Record a redelivered Telegram update skipped at the dispatcher entry
+pub fn record_telegram_redelivered_update() {
+    metrics::counter!("telegram_redelivered_updates_total").increment(1);
+}
+
+/// Record a Telegram API request sent again after a retryable failure
+pub fn record_telegram_send_retry(method: &str, reason: &str) {
+    let method = method.to_string();
+    let reason = reason.to_string();
+    metrics::counter!("telegram_send_retries_total", "method" => method, "reason" => reason)
+        .increment(1);
+}
+
+/// Record a Telegram API request that failed for good, after any retries
+pub fn record_telegram_send_failure(method: &str, retryable: bool) {
+    let method = method.to_string();
+    let retryable = retryable.to_string();
+    metrics::counter!("telegram_send_failures_total", "method" => method, "retryable" => retryable)
+        .increment(1);
+}
+
+/// Record a request refused because the user is over their rate limit
+pub fn record_rate_limit_hit(operation: &str) {
+    let operation = operation.to_string();
+    metrics::counter!("rate_limit_hits_total", "operation" => operation).increment(1);
+}
+
+/// Record a lookup in one of the handler caches
+pub fn record_cache_metrics(cache: &str, hit: bool) {
+    metrics::counter!(
+        "cache_lookups_total",
+        "cache" => cache.to_string(),
+        "result" => if hit { "hit" } else { "miss" }
+    )
+    .increment(1);
+}
+
 /// Record duplicate Telegram message detection
 pub fn record_telegram_duplicate_message() {
     metrics::counter!("telegram_duplicate_messages_total").increment(1);
@@ -999,6 +1267,57 @@ pub fn record_mutex_poisoning(component: &str, operation: &str) {
     );
 }
 
+/// Record the serialized size of a dialogue state
+pub fn record_dialogue_state_size(state: &str, size_bytes: usize) {
+    metrics::histogram!("dialogue_state_size_bytes", "state" => state.to_string())
+        .record(size_bytes as f64);
+}
+
+/// Record a dialogue state serialized above its size bound
+pub fn record_dialogue_state_oversized(state: &str) {
+    metrics::counter!("dialogue_state_oversized_total", "state" => state.to_string()).increment(1);
+}
+
+/// Record the number of active dialogues in a state
+pub fn record_active_dialogues(state: &str, count: usize) {
+    metrics::gauge!("dialogues_active", "state" => state.to_string()).set(count as f64);
+}
+
+/// Record a dialogue button tapped while no dialogue handles it
+///
+/// `state` is the chat's dialogue state, "none" when it has none, e.g. after a restart.
+pub fn record_dialogue_callback_without_state(state: &str) {
+    metrics::counter!("dialogue_callbacks_without_state_total", "state" => state.to_string())
+        .increment(1);
+}
+
+/// Record a dialogue state that could not be loaded from storage
+pub fn record_dialogue_state_load_failure() {
+    metrics::counter!("dialogue_state_load_failures_total").increment(1);
+}
+
+/// Record a dialogue exited by the idle sweep
+pub fn record_dialogue_expired(state: &str) {
+    metrics::counter!("dialogues_expired_total", "state" => state.to_string()).increment(1);
+}
+
+/// Record localization bundle load status and approximate memory usage per language
+pub fn record_localization_bundle_metrics(
+    language: &str,
+    loaded: bool,
+    approx_memory_bytes: usize,
+) {
+    metrics::gauge!("localization_bundle_loaded", "language" => language.to_string())
+        .set(if loaded { 1.0 } else { 0.0 });
+    metrics::gauge!("localization_bundle_memory_bytes", "language" => language.to_string())
+        .set(approx_memory_bytes as f64);
+
+    if !loaded {
+        metrics::counter!("localization_bundle_load_failures_total", "language" => language.to_string())
+            .increment(1);
+    }
+}
+
 /// Record build time metrics for delivery performance tracking
 pub fn record_build_time(duration: std::time::Duration) {
     metrics::histogram!("cargo_build_time_seconds").record(duration.as_secs_f64());
@@ -1023,3 +1342,46 @@ pub fn set_classical_velocity_baseline(baseline: f64) {
 pub fn record_bug_fix() {
     metrics::counter!("bugs_fixed_total").increment(1);
 }
+
+/// Record a review funnel event tagged with the experiment variant
+pub fn record_review_funnel_metrics(experiment: &str, variant: &str, event: &str) {
+    metrics::counter!(
+        "review_funnel_events_total",
+        "experiment" => experiment.to_string(),
+        "variant" => variant.to_string(),
+        "event" => event.to_string()
+    )
+    .increment(1);
+}
+
+/// Record the share of a confirmed review's ingredients the user edited or deleted
+///
+/// `ratio` is between 0 (kept as extracted) and 1; `total` is the number extracted.
+pub fn record_extraction_accuracy(language: &str, ratio: f64, total: usize) {
+    metrics::histogram!("extraction_correction_ratio", "language" => language.to_string())
+        .record(ratio);
+    metrics::counter!("extraction_reviewed_ingredients_total", "language" => language.to_string())
+        .increment(total as u64);
+}
+
+/// Record the outcome of an outbound webhook event
+pub fn record_webhook_event_metrics(event: &str, outcome: &str) {
+    metrics::counter!(
+        "webhook_events_total",
+        "event" => event.to_string(),
+        "outcome" => outcome.to_string()
+    )
+    .increment(1);
+}
+
+/// Record the outcome and duration of a voice message transcription
+pub fn record_transcription_metrics(backend: &str, outcome: &str, duration: std::time::Duration) {
+    metrics::counter!(
+        "transcription_requests_total",
+        "backend" => backend.to_string(),
+        "outcome" => outcome.to_string()
+    )
+    .increment(1);
+    metrics::histogram!("transcription_duration_seconds", "backend" => backend.to_string())
+        .record(duration.as_secs_f64());
+}
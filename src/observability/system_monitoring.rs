@@ -43,12 +43,19 @@ pub fn record_system_resources() {
 }
 
 /// Start a background task to periodically record system metrics
-pub fn start_system_metrics_recorder() -> tokio::task::JoinHandle<()> {
-    tokio::spawn(async {
+///
+/// The task stops once `shutdown` is cancelled.
+pub fn start_system_metrics_recorder(
+    shutdown: tokio_util::sync::CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(30)); // Every 30 seconds
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
 
             // Record memory usage
             record_memory_usage();
@@ -7,29 +7,27 @@
 
 use anyhow::Result;
 use opentelemetry::global;
+use opentelemetry::trace::{Span as _, Tracer as _};
 use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use std::sync::OnceLock;
 use tracing_subscriber::prelude::*;
 
 use crate::observability_config::ObservabilityConfig;
 
+/// Tracer provider installed globally, kept to flush its batched spans at shutdown
+static TRACER_PROVIDER: OnceLock<SdkTracerProvider> = OnceLock::new();
+
 /// Initialize structured logging with tracing and configuration
 pub fn init_tracing_with_config(config: &ObservabilityConfig) -> Result<()> {
     // Create the filter based on configuration
-    let mut filter = tracing_subscriber::EnvFilter::from_default_env()
+    let filter = tracing_subscriber::EnvFilter::from_default_env()
         .add_directive(format!("just_ingredients={}", config.log_level).parse()?)
         .add_directive("sqlx=warn".parse()?)
         .add_directive("teloxide=warn".parse()?);
 
-    // Add observability-specific log level
-    if let Ok(obs_log) = std::env::var("OBSERVABILITY_LOG_LEVEL") {
-        filter =
-            filter.add_directive(format!("just_ingredients::observability={}", obs_log).parse()?);
-    }
-
     // Initialize based on environment (pretty for development, JSON for others)
-    if config.is_development()
-        || std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string()) == "pretty"
-    {
+    if config.is_development() || config.log_format == "pretty" {
         // Pretty formatting for development
         tracing_subscriber::registry()
             .with(filter)
@@ -79,6 +77,7 @@ pub async fn init_opentelemetry_tracing_with_config(config: &ObservabilityConfig
             .build();
 
         // Set global tracer provider
+        let _ = TRACER_PROVIDER.set(tracer_provider.clone());
         global::set_tracer_provider(tracer_provider);
 
         tracing::info!(
@@ -97,8 +96,8 @@ pub async fn init_opentelemetry_tracing_with_config(config: &ObservabilityConfig
 /// Initialize structured logging with tracing
 #[allow(dead_code)]
 pub fn init_tracing() -> Result<()> {
-    // Determine log format from environment variable (default to JSON for production)
-    let log_format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string());
+    // Log format from LOG_FORMAT (default to JSON for production)
+    let log_format = &crate::config::current().observability.log_format;
 
     // Create the filter
     let filter = tracing_subscriber::EnvFilter::from_default_env()
@@ -144,7 +143,11 @@ pub async fn init_opentelemetry_tracing() -> Result<()> {
     let otlp_exporter = opentelemetry_otlp::SpanExporter::builder()
         .with_tonic()
         .with_endpoint(
-            std::env::var("OTLP_ENDPOINT").unwrap_or_else(|_| "http://localhost:4317".to_string()),
+            crate::config::current()
+                .observability
+                .otlp_endpoint
+                .as_deref()
+                .unwrap_or("http://localhost:4317"),
         )
         .build()?;
 
@@ -154,12 +157,68 @@ pub async fn init_opentelemetry_tracing() -> Result<()> {
         .build();
 
     // Set global tracer provider
+    let _ = TRACER_PROVIDER.set(tracer_provider.clone());
     global::set_tracer_provider(tracer_provider);
 
     tracing::info!("OpenTelemetry tracing initialized with OTLP export");
     Ok(())
 }
 
+/// Export pending spans and shut the tracer provider down
+///
+/// Returns whether spans were flushed, `false` when OpenTelemetry tracing is disabled
+/// or the export failed.
+pub async fn shutdown_opentelemetry_tracing() -> bool {
+    let Some(provider) = TRACER_PROVIDER.get().cloned() else {
+        return false;
+    };
+
+    // Flushing blocks until the batch exporter is done
+    let result = tokio::task::spawn_blocking(move || {
+        provider.force_flush()?;
+        provider.shutdown()
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            tracing::warn!(error = %e, "Failed to flush OpenTelemetry spans");
+            false
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "OpenTelemetry shutdown task failed");
+            false
+        }
+    }
+}
+
+/// OpenTelemetry span of one Telegram interaction, ended when dropped
+///
+/// Only started when OTLP tracing is configured, so its trace id can be followed
+/// from a slow handler to the exported trace.
+pub struct InteractionSpan(Option<global::BoxedSpan>);
+
+impl InteractionSpan {
+    /// Start the span of an interaction, named after its operation
+    pub fn start(operation: crate::observability::TelegramOperation) -> Self {
+        Self(
+            TRACER_PROVIDER
+                .get()
+                .map(|_| global::tracer("just_ingredients").start(operation.as_str())),
+        )
+    }
+
+    /// Hex trace id of the span, `None` when traces are not exported
+    pub fn trace_id(&self) -> Option<String> {
+        self.0
+            .as_ref()
+            .map(|span| span.span_context())
+            .filter(|context| context.is_valid())
+            .map(|context| context.trace_id().to_string())
+    }
+}
+
 /// Create a span for OCR operations
 pub fn ocr_span(operation: &str) -> tracing::Span {
     tracing::info_span!("ocr_operation", operation = operation, component = "ocr")
@@ -175,6 +234,19 @@ pub fn db_span(operation: &str, table: &str) -> tracing::Span {
     )
 }
 
+/// Create the root span of one Telegram update
+///
+/// Handler, OCR and database spans of the update are entered beneath it, so a
+/// trace can be found by its update id or sender.
+pub fn update_span(update_id: u32, user_id: Option<i64>) -> tracing::Span {
+    tracing::info_span!(
+        "telegram_update",
+        update_id = update_id,
+        user_id = user_id,
+        component = "telegram"
+    )
+}
+
 /// Create a span for Telegram bot operations
 pub fn telegram_span(operation: &str, user_id: Option<i64>) -> tracing::Span {
     tracing::info_span!(
@@ -3,7 +3,31 @@
 //! Environment-specific configuration for observability features
 //! in production deployments.
 
-use std::env;
+use std::time::Duration;
+
+/// Time each readiness check may take before its dependency is reported down
+///
+/// Set in milliseconds with `HEALTH_CHECK_DB_TIMEOUT_MS`,
+/// `HEALTH_CHECK_TELEGRAM_TIMEOUT_MS` and `HEALTH_CHECK_OCR_TIMEOUT_MS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthCheckTimeouts {
+    /// Postgres `SELECT 1`
+    pub database: Duration,
+    /// Telegram `getMe`
+    pub telegram: Duration,
+    /// Tesseract instance initialization
+    pub ocr: Duration,
+}
+
+impl Default for HealthCheckTimeouts {
+    fn default() -> Self {
+        Self {
+            database: Duration::from_secs(2),
+            telegram: Duration::from_secs(5),
+            ocr: Duration::from_secs(5),
+        }
+    }
+}
 
 /// Observability configuration for different environments
 #[derive(Debug, Clone)]
@@ -16,6 +40,8 @@ pub struct ObservabilityConfig {
     pub metrics_port: u16,
     /// Log level for observability components
     pub log_level: String,
+    /// Log output, `json` or `pretty`; development always logs pretty
+    pub log_format: String,
     /// Whether to enable trace sampling
     pub enable_trace_sampling: bool,
     /// Trace sampling ratio (0.0-1.0)
@@ -24,6 +50,8 @@ pub struct ObservabilityConfig {
     pub enable_metrics_export: bool,
     /// Additional tags for metrics and traces
     pub tags: Vec<(String, String)>,
+    /// Timeouts of the readiness checks
+    pub health_check_timeouts: HealthCheckTimeouts,
 }
 
 impl Default for ObservabilityConfig {
@@ -33,44 +61,20 @@ impl Default for ObservabilityConfig {
             otlp_endpoint: None,
             metrics_port: 9090,
             log_level: "info".to_string(),
+            log_format: "json".to_string(),
             enable_trace_sampling: false,
             trace_sampling_ratio: 1.0,
             enable_metrics_export: true,
             tags: Vec::new(),
+            health_check_timeouts: HealthCheckTimeouts::default(),
         }
     }
 }
 
 impl ObservabilityConfig {
-    /// Load configuration from environment variables
-    pub fn from_env() -> Self {
-        Self {
-            environment: env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
-            otlp_endpoint: env::var("OTLP_ENDPOINT").ok(),
-            metrics_port: env::var("METRICS_PORT")
-                .unwrap_or_else(|_| "9090".to_string())
-                .parse()
-                .unwrap_or(9090),
-            log_level: env::var("OBSERVABILITY_LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
-            enable_trace_sampling: env::var("ENABLE_TRACE_SAMPLING")
-                .unwrap_or_else(|_| "false".to_string())
-                .parse()
-                .unwrap_or(false),
-            trace_sampling_ratio: env::var("TRACE_SAMPLING_RATIO")
-                .unwrap_or_else(|_| "1.0".to_string())
-                .parse()
-                .unwrap_or(1.0),
-            enable_metrics_export: env::var("ENABLE_METRICS_EXPORT")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .unwrap_or(true),
-            tags: Vec::new(),
-        }
-    }
-
     /// Add default tags based on environment and configuration
     #[allow(dead_code)]
-    fn add_default_tags(&mut self) {
+    fn add_default_tags(&mut self, version: Option<String>, hostname: Option<String>) {
         // Add environment tag
         self.tags
             .push(("environment".to_string(), self.environment.clone()));
@@ -80,12 +84,12 @@ impl ObservabilityConfig {
             .push(("service".to_string(), "just-ingredients-bot".to_string()));
 
         // Add version if available
-        if let Ok(version) = env::var("SERVICE_VERSION") {
+        if let Some(version) = version {
             self.tags.push(("version".to_string(), version));
         }
 
         // Add hostname if available
-        if let Ok(hostname) = env::var("HOSTNAME") {
+        if let Some(hostname) = hostname {
             self.tags.push(("hostname".to_string(), hostname));
         }
     }
@@ -125,6 +129,14 @@ impl ObservabilityConfig {
             )));
         }
 
+        // A zero timeout would report every dependency down
+        let timeouts = &self.health_check_timeouts;
+        if [timeouts.database, timeouts.telegram, timeouts.ocr].contains(&Duration::ZERO) {
+            return Err(crate::errors::AppError::Config(
+                "Health check timeouts must be greater than zero".to_string(),
+            ));
+        }
+
         // Validate port range
         if self.metrics_port == 0 {
             return Err(crate::errors::AppError::Config(format!(
@@ -243,6 +255,11 @@ mod tests {
         config.trace_sampling_ratio = 1.0;
         config.metrics_port = 0;
         assert!(config.validate().is_err());
+
+        // Reset and test zero health check timeout
+        config.metrics_port = 9090;
+        config.health_check_timeouts.database = Duration::ZERO;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -29,7 +29,7 @@ use regex;
 use std::fs::File;
 use std::io::{BufReader, Read};
 use tempfile::NamedTempFile;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn, Instrument};
 
 // Re-export types for easier access from documentation and external usage
 pub use crate::circuit_breaker::CircuitBreaker;
@@ -189,11 +189,11 @@ pub fn validate_image_with_format_limits(
         info!(
             "Quick rejecting file {image_path}: {file_size} bytes exceeds quick reject threshold"
         );
-        return Err(anyhow::anyhow!(
+        return Err(crate::ocr_errors::OcrError::FileTooLarge(format!(
             "File too large for processing: {} bytes (exceeds quick reject threshold of {} bytes)",
-            file_size,
-            config.format_limits.min_quick_reject
-        ));
+            file_size, config.format_limits.min_quick_reject
+        ))
+        .into());
     }
 
     // Try to detect format and apply format-specific limits
@@ -248,10 +248,11 @@ pub fn validate_image_with_format_limits(
                             };
 
                             if file_size > format_limit {
-                                return Err(anyhow::anyhow!(
+                                return Err(crate::ocr_errors::OcrError::FileTooLarge(format!(
                                     "Image file too large for {:?} format: {} bytes (maximum allowed: {} bytes)",
                                     format, file_size, format_limit
-                                ));
+                                ))
+                                .into());
                             }
 
                             // Estimate memory usage for processing
@@ -261,10 +262,15 @@ pub fn validate_image_with_format_limits(
                             );
 
                             // Check if estimated memory usage exceeds safe limits
-                            let max_memory_mb = std::env::var("OCR_MEMORY_LIMIT_MB")
-                                .unwrap_or_else(|_| "80".to_string())
-                                .parse::<f64>()
-                                .unwrap_or(80.0); // 80MB memory limit for OCR processing (conservative for Fly.io 512MB VMs)
+                            // Defaults to the per-request budget derived from the cgroup memory limit
+                            #[allow(clippy::cast_precision_loss)]
+                            let derived_memory_mb =
+                                crate::resource_limits::RESOURCE_BUDGET.request_memory_bytes as f64
+                                    / (1024.0 * 1024.0);
+                            let max_memory_mb = crate::config::current()
+                                .ocr_limits
+                                .memory_limit_mb
+                                .unwrap_or(derived_memory_mb);
                             if estimated_memory_mb > max_memory_mb {
                                 return Err(anyhow::anyhow!(
                                     "Estimated memory usage too high: {}MB (maximum allowed: {}MB). File would cause out-of-memory errors.",
@@ -278,11 +284,11 @@ pub fn validate_image_with_format_limits(
                             // Could not determine format, use general limit
                             info!("Could not determine image format for {image_path}, using general size limit");
                             if file_size > config.max_file_size {
-                                return Err(anyhow::anyhow!(
+                                return Err(crate::ocr_errors::OcrError::FileTooLarge(format!(
                                     "Image file too large: {} bytes (maximum allowed: {} bytes)",
-                                    file_size,
-                                    config.max_file_size
-                                ));
+                                    file_size, config.max_file_size
+                                ))
+                                .into());
                             }
                             Ok(())
                         }
@@ -292,11 +298,11 @@ pub fn validate_image_with_format_limits(
                     // Could not read enough bytes, use general limit
                     info!("Could not read enough bytes for format detection from {image_path}, using general size limit");
                     if file_size > config.max_file_size {
-                        return Err(anyhow::anyhow!(
+                        return Err(crate::ocr_errors::OcrError::FileTooLarge(format!(
                             "Image file too large: {} bytes (maximum allowed: {} bytes)",
-                            file_size,
-                            config.max_file_size
-                        ));
+                            file_size, config.max_file_size
+                        ))
+                        .into());
                     }
                     Ok(())
                 }
@@ -393,9 +399,11 @@ pub fn estimate_memory_usage(file_size: u64, format: &image::ImageFormat) -> f64
 /// 3. Retry Loop (up to max_retries + 1 attempts)
 ///    For each attempt:
 ///      a. Perform OCR extraction with timeout
-///      b. On success: Record success, update metrics, return result
-///      c. On failure: Calculate delay, wait, retry
-///      d. After max attempts: Record failure, return error
+///      b. On success without measurement lines: second pass over the densest
+///         text column, within the remaining `total_timeout_secs` budget
+///      c. On success: Record success, update metrics, return result
+///      d. On failure: Calculate delay, wait, retry
+///      e. After max attempts: Record failure, return error
 ///
 /// 4. Circuit Breaker Updates
 ///    - Record success/failure to track system health
@@ -608,13 +616,13 @@ pub fn estimate_memory_usage(file_size: u64, format: &image::ImageFormat) -> f64
 ///
 /// ### Circuit Breaker Errors
 /// - **Trigger**: Service temporarily unavailable due to repeated failures
-/// - **User Message**: "OCR service is temporarily unavailable..."
+/// - **Error**: `OcrError::CircuitOpen`, told to users as "try again in a minute"
 /// - **Recovery**: Automatic when circuit breaker resets
 ///
 /// ### Validation Errors
 /// - **File Access**: Cannot read image file
 /// - **Format Unsupported**: Image format not supported by Tesseract
-/// - **Size Limits**: File exceeds format-specific size limits
+/// - **Size Limits**: File exceeds format-specific size limits (`OcrError::FileTooLarge`)
 /// - **Corruption**: File appears corrupted or invalid
 ///
 /// ### Processing Errors
@@ -740,89 +748,108 @@ pub async fn extract_text_from_image(
     config: &crate::ocr_config::OcrConfig,
     instance_manager: &crate::instance_manager::OcrInstanceManager,
     circuit_breaker: &crate::circuit_breaker::CircuitBreaker,
+) -> Result<(String, OcrConfidence), crate::ocr_errors::OcrError> {
+    extract_text_from_image_with_profile(
+        image_path,
+        config,
+        instance_manager,
+        circuit_breaker,
+        None,
+    )
+    .await
+}
+
+/// Extract text like [`extract_text_from_image`], optionally with a retry profile
+///
+/// With `Some(retry_profile)`, its stages replace the source-aware preprocessing;
+/// validation, retries and the circuit breaker work the same.
+pub async fn extract_text_from_image_with_profile(
+    image_path: &str,
+    config: &crate::ocr_config::OcrConfig,
+    instance_manager: &crate::instance_manager::OcrInstanceManager,
+    circuit_breaker: &crate::circuit_breaker::CircuitBreaker,
+    retry_profile: Option<&crate::ocr_config::RetryProfile>,
 ) -> Result<(String, OcrConfidence), crate::ocr_errors::OcrError> {
     // Create a tracing span for the OCR operation
     let span = crate::observability::ocr_span("extract_text_from_image");
-    let _enter = span.enter();
-
-    // Start timing the entire OCR operation
-    let start_time = std::time::Instant::now();
+    async move {
+        // Start timing the entire OCR operation
+        let start_time = std::time::Instant::now();
+
+        // Check circuit breaker before processing
+        if circuit_breaker.is_open() {
+            warn!("Circuit breaker is open, rejecting OCR request for image: {image_path}");
+            observability::update_circuit_breaker_state(true);
+            return Err(crate::ocr_errors::OcrError::CircuitOpen(
+                "circuit breaker open after repeated failures".to_string(),
+            ));
+        }
+        observability::update_circuit_breaker_state(false);
 
-    // Check circuit breaker before processing
-    if circuit_breaker.is_open() {
-        warn!("Circuit breaker is open, rejecting OCR request for image: {image_path}");
-        observability::update_circuit_breaker_state(true);
-        return Err(crate::ocr_errors::OcrError::Extraction(
-            "OCR service is temporarily unavailable due to repeated failures. Please try again later.".to_string()
-        ));
-    }
-    observability::update_circuit_breaker_state(false);
+        // Validate input with enhanced format-specific validation
+        validate_image_with_format_limits(image_path, config).map_err(|e| {
+            e.downcast::<crate::ocr_errors::OcrError>()
+                .unwrap_or_else(|e| crate::ocr_errors::OcrError::Validation(e.to_string()))
+        })?;
 
-    // Validate input with enhanced format-specific validation
-    validate_image_with_format_limits(image_path, config)
-        .map_err(|e| crate::ocr_errors::OcrError::Validation(e.to_string()))?;
+        // Reject images whose estimated peak RSS would not fit this server's budget
+        let budget = &*crate::resource_limits::RESOURCE_BUDGET;
+        crate::resource_limits::check_image_file(image_path, budget)?;
 
-    info!("Starting OCR text extraction from image: {image_path}");
+        // Bound concurrent Tesseract runs to the derived concurrency
+        let _ocr_permit = crate::resource_limits::acquire_ocr_permit().await;
 
-    // Implement retry logic with exponential backoff
-    let mut attempt = 0;
-    let max_attempts = config.recovery.max_retries + 1; // +1 for initial attempt
+        info!("Starting OCR text extraction from image: {image_path}");
 
-    loop {
-        attempt += 1;
+        // Implement retry logic with exponential backoff
+        let mut attempt = 0;
+        let max_attempts = config.recovery.max_retries + 1; // +1 for initial attempt
 
-        match perform_ocr_extraction(image_path, config, instance_manager).await {
-            Ok((text, tesseract_confidence, ocr_duration)) => {
-                let total_duration = start_time.elapsed();
-                let total_ms = total_duration.as_millis();
+        loop {
+            attempt += 1;
 
-                // Calculate OCR confidence score (now incorporating Tesseract's confidence)
-                let confidence = calculate_ocr_confidence_with_tesseract(
-                    &text,
+            match perform_ocr_extraction(image_path, config, instance_manager, retry_profile).await {
+                Ok((
+                    text,
                     tesseract_confidence,
                     ocr_duration,
-                    config,
-                );
-
-                // Record success in circuit breaker
-                circuit_breaker.record_success();
-                observability::update_circuit_breaker_state(false);
-
-                // Record OCR metrics with enhanced performance data
-                let image_size = std::fs::metadata(image_path).map(|m| m.len()).unwrap_or(0);
-                let memory_estimate =
-                    crate::ocr::estimate_memory_usage(image_size, &image::ImageFormat::Png);
-                observability::record_ocr_performance_metrics(
-                    observability::OcrPerformanceMetricsParams {
-                        success: true,
-                        total_duration,
-                        ocr_duration,
-                        image_size,
-                        attempt_count: attempt,
-                        memory_estimate_mb: memory_estimate,
-                    },
-                );
+                    preprocessing_strategy,
+                    line_confidences,
+                )) => {
+                    // A page without measurement lines gets a second look at its densest text column
+                    let second_pass = if retry_profile.is_none()
+                        && config.second_pass_enabled
+                        && !has_measurement_lines(&text)
+                    {
+                        run_second_pass(image_path, config, instance_manager, start_time).await
+                    } else {
+                        None
+                    };
+                    let (text, preprocessing_strategy, line_confidences) = match second_pass {
+                        Some((column_text, column_confidences)) => (
+                            column_text,
+                            format!("{}+column_crop", preprocessing_strategy),
+                            column_confidences,
+                        ),
+                        None => (text, preprocessing_strategy, line_confidences),
+                    };
 
-                info!("OCR extraction completed successfully on attempt {} in {}ms. Extracted {} characters of text (Tesseract: {:.1}%, overall: {:.2})",
-                      attempt, total_ms, text.len(), tesseract_confidence, confidence.overall_score);
+                    let total_duration = start_time.elapsed();
+                    let total_ms = total_duration.as_millis();
 
-                // Log confidence warnings if needed
-                if should_flag_for_review(&confidence, 0.7) {
-                    warn!(
-                        "OCR result flagged for review: {}",
-                        get_confidence_description(&confidence)
+                    // Calculate OCR confidence score (now incorporating Tesseract's confidence)
+                    let mut confidence = calculate_ocr_confidence_with_tesseract(
+                        &text,
+                        tesseract_confidence,
+                        ocr_duration,
+                        config,
                     );
-                }
-
-                return Ok((text, confidence));
-            }
-            Err(err) => {
-                if attempt >= max_attempts {
-                    let total_duration = start_time.elapsed();
+                    confidence.preprocessing_strategy = Some(preprocessing_strategy);
+                    confidence.line_confidences = line_confidences;
 
-                    // Record failure in circuit breaker
-                    circuit_breaker.record_failure();
-                    observability::update_circuit_breaker_state(circuit_breaker.is_open());
+                    // Record success in circuit breaker
+                    circuit_breaker.record_success();
+                    observability::update_circuit_breaker_state(false);
 
                     // Record OCR metrics with enhanced performance data
                     let image_size = std::fs::metadata(image_path).map(|m| m.len()).unwrap_or(0);
@@ -830,32 +857,77 @@ pub async fn extract_text_from_image(
                         crate::ocr::estimate_memory_usage(image_size, &image::ImageFormat::Png);
                     observability::record_ocr_performance_metrics(
                         observability::OcrPerformanceMetricsParams {
-                            success: false,
+                            success: true,
                             total_duration,
-                            ocr_duration: std::time::Duration::from_millis(0), // No successful OCR duration on failure
+                            ocr_duration,
                             image_size,
                             attempt_count: attempt,
                             memory_estimate_mb: memory_estimate,
                         },
                     );
 
-                    error_logging::log_ocr_error(
-                        &err,
-                        "ocr_extraction_retry",
-                        None, // user_id not available in this context
-                        Some(image_size),
-                        Some(total_duration),
-                    );
-                    return Err(err);
+                    info!("OCR extraction completed successfully on attempt {} in {}ms. Extracted {} characters of text (Tesseract: {:.1}%, overall: {:.2})",
+                          attempt, total_ms, text.len(), tesseract_confidence, confidence.overall_score);
+
+                    // Log confidence warnings if needed
+                    if should_flag_for_review(&confidence, 0.7) {
+                        warn!(
+                            "OCR result flagged for review: {}",
+                            get_confidence_description(&confidence)
+                        );
+                    }
+
+                    return Ok((text, confidence));
                 }
+                Err(err) => {
+                    if attempt >= max_attempts {
+                        let total_duration = start_time.elapsed();
+
+                        // Record failure in circuit breaker
+                        circuit_breaker.record_failure();
+                        observability::update_circuit_breaker_state(circuit_breaker.is_open());
+
+                        // Requests after the breaker resets start from fresh Tesseract instances
+                        if circuit_breaker.is_open() {
+                            let recycled = instance_manager.recycle_all();
+                            warn!("Circuit breaker opened, recycled {recycled} OCR instances");
+                        }
 
-                let delay_ms = calculate_retry_delay(attempt, &config.recovery);
-                warn!("OCR extraction attempt {attempt} failed: {err:?}. Retrying in {delay_ms}ms");
+                        // Record OCR metrics with enhanced performance data
+                        let image_size = std::fs::metadata(image_path).map(|m| m.len()).unwrap_or(0);
+                        let memory_estimate =
+                            crate::ocr::estimate_memory_usage(image_size, &image::ImageFormat::Png);
+                        observability::record_ocr_performance_metrics(
+                            observability::OcrPerformanceMetricsParams {
+                                success: false,
+                                total_duration,
+                                ocr_duration: std::time::Duration::from_millis(0), // No successful OCR duration on failure
+                                image_size,
+                                attempt_count: attempt,
+                                memory_estimate_mb: memory_estimate,
+                            },
+                        );
+
+                        error_logging::log_ocr_error(
+                            &err,
+                            "ocr_extraction_retry",
+                            None, // user_id not available in this context
+                            Some(image_size),
+                            Some(total_duration),
+                        );
+                        return Err(err);
+                    }
+
+                    let delay_ms = calculate_retry_delay(attempt, &config.recovery);
+                    warn!("OCR extraction attempt {attempt} failed: {err:?}. Retrying in {delay_ms}ms");
 
-                tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                    tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                }
             }
         }
     }
+    .instrument(span)
+    .await
 }
 
 /// Helper function to perform OCR extraction with timeout
@@ -905,6 +977,8 @@ pub struct AdaptivePreprocessingResult {
     pub image: image::DynamicImage,
     /// Description of the preprocessing strategy applied
     pub preprocessing_strategy: String,
+    /// Stages applied after scaling, in pipeline order
+    pub stages: Vec<crate::preprocessing::PreprocessingStage>,
 }
 
 /// Result of constrained OCR processing for measurement extraction.
@@ -955,6 +1029,7 @@ pub fn apply_adaptive_preprocessing(
             Ok(AdaptivePreprocessingResult {
                 image: scaled_result.image,
                 preprocessing_strategy: "high_quality_minimal".to_string(),
+                stages: Vec::new(),
             })
         }
 
@@ -992,6 +1067,10 @@ pub fn apply_adaptive_preprocessing(
             Ok(AdaptivePreprocessingResult {
                 image: thresholded_result.image,
                 preprocessing_strategy: "medium_quality_standard".to_string(),
+                stages: vec![
+                    crate::preprocessing::PreprocessingStage::Denoise,
+                    crate::preprocessing::PreprocessingStage::Threshold,
+                ],
             })
         }
 
@@ -1081,24 +1160,153 @@ pub fn apply_adaptive_preprocessing(
                 } else {
                     "low_quality_full_with_deskew".to_string()
                 },
+                stages: {
+                    use crate::preprocessing::PreprocessingStage::*;
+                    let mut stages = vec![Deskew, Denoise, Threshold, Despeckle];
+                    if quality.contrast_ratio < 0.3 {
+                        stages.insert(0, Clahe);
+                    }
+                    stages
+                },
             })
         }
     }
 }
 
+/// Applies the preprocessing profile of an image's source class, adjusted for quality.
+///
+/// The class profile (see [`crate::preprocessing::SourceProfiles`]) is the
+/// starting point; [`crate::preprocessing::plan_preprocessing_stages`] then
+/// keeps the stages the quality measurements call for. Scaling always runs
+/// first. The chosen plan is recorded on a `preprocessing_pipeline` span and
+/// counted per stage.
+///
+/// The strategy string has the form `<class>_<quality>_quality:<stages>`,
+/// e.g. `scan_medium_quality:deskew+threshold+despeckle`, so the source class
+/// travels with the strategy into OCR confidence and extraction reports.
+///
+/// # Arguments
+///
+/// * `image` - The input image to preprocess
+/// * `quality` - The assessed image quality
+/// * `source_class` - The classified image source
+/// * `profile` - The profile configured for `source_class`
+/// * `pipeline` - Thresholds of the measurement-driven stages
+pub fn apply_source_aware_preprocessing(
+    image: &image::DynamicImage,
+    quality: &crate::preprocessing::types::ImageQualityResult,
+    source_class: crate::preprocessing::SourceClass,
+    profile: &crate::preprocessing::PreprocessingProfile,
+    pipeline: &crate::preprocessing::PipelineConfig,
+) -> Result<AdaptivePreprocessingResult, crate::ocr_errors::OcrError> {
+    let stage_error = |stage: &str, e: crate::preprocessing::PreprocessingError| {
+        crate::ocr_errors::OcrError::Extraction(format!(
+            "{} preprocessing {} failed: {:?}",
+            source_class, stage, e
+        ))
+    };
+
+    let plan = crate::preprocessing::plan_preprocessing_stages(profile, quality, pipeline);
+    let stage_list = plan.label();
+    let span = tracing::info_span!(
+        "preprocessing_pipeline",
+        source_class = %source_class,
+        quality = ?quality.quality,
+        plan = %stage_list
+    );
+    let _enter = span.enter();
+
+    let scaled = crate::preprocessing::scaling::ImageScaler::new()
+        .scale_for_ocr(image)
+        .map_err(|e| stage_error("scaling", e))?
+        .image;
+
+    for stage in &plan.stages {
+        crate::observability::record_preprocessing_step(stage.as_str());
+    }
+    let current =
+        crate::preprocessing::PreprocessingStage::run_all(&plan.stages, scaled, plan.denoise_sigma)
+            .map_err(|(stage, e)| stage_error(stage.as_str(), e))?;
+
+    let quality_label = match quality.quality {
+        crate::preprocessing::ImageQuality::High => "high",
+        crate::preprocessing::ImageQuality::Medium => "medium",
+        crate::preprocessing::ImageQuality::Low => "low",
+    };
+
+    Ok(AdaptivePreprocessingResult {
+        image: current,
+        preprocessing_strategy: format!(
+            "{}_{}_quality:{}",
+            source_class, quality_label, stage_list
+        ),
+        stages: plan.stages,
+    })
+}
+
+/// Applies a retry profile's stages as listed, after scaling.
+///
+/// The strategy string has the form `retry_<name>:<stages>`, e.g.
+/// `retry_no_deskew:denoise+threshold`.
+pub fn apply_retry_profile(
+    image: &image::DynamicImage,
+    retry_profile: &crate::ocr_config::RetryProfile,
+) -> Result<AdaptivePreprocessingResult, crate::ocr_errors::OcrError> {
+    let stage_error = |stage: &str, e: crate::preprocessing::PreprocessingError| {
+        crate::ocr_errors::OcrError::Extraction(format!(
+            "{} retry preprocessing {} failed: {:?}",
+            retry_profile.name, stage, e
+        ))
+    };
+
+    let scaled = crate::preprocessing::scaling::ImageScaler::new()
+        .scale_for_ocr(image)
+        .map_err(|e| stage_error("scaling", e))?
+        .image;
+    let stages = retry_profile.profile.stages().to_vec();
+    let current = crate::preprocessing::PreprocessingStage::run_all(
+        &stages,
+        scaled,
+        retry_profile.denoise_sigma,
+    )
+    .map_err(|(stage, e)| stage_error(stage.as_str(), e))?;
+
+    let stage_list = if stages.is_empty() {
+        "scale_only".to_string()
+    } else {
+        stages
+            .iter()
+            .map(|stage| stage.as_str())
+            .collect::<Vec<_>>()
+            .join("+")
+    };
+    Ok(AdaptivePreprocessingResult {
+        image: current,
+        preprocessing_strategy: format!("retry_{}:{}", retry_profile.name, stage_list),
+        stages,
+    })
+}
+
 /// Apply image preprocessing for OCR optimization
 ///
 /// This function loads an image, applies OCR-optimized preprocessing (scaling),
 /// and saves the result to a temporary file for Tesseract processing.
 ///
+/// The image work is CPU-bound, so it runs on Tokio's blocking thread pool and
+/// leaves the async runtime free for other handlers; concurrency is already
+/// bounded by the OCR permits. A caller's `tokio::time::timeout` covers the
+/// blocking work, though on expiry the blocking thread finishes its current
+/// image before being released.
+///
 /// # Arguments
 ///
 /// * `image_path` - Path to the original image file
-/// * `config` - OCR configuration (unused for now, but for future extensibility)
+/// * `config` - OCR configuration providing the per-source-class preprocessing profiles
+/// * `retry_profile` - Stages replacing the source-aware preprocessing, if any
 ///
 /// # Returns
 ///
-/// Returns a tuple of (temp_file, temp_file_path, preprocessing_duration) on success.
+/// Returns a tuple of (temp_file, temp_file_path, preprocessing_duration, preprocessing_strategy) on success.
 /// The NamedTempFile will be automatically cleaned up when it goes out of scope,
 /// but the OCR operation should complete before that happens.
 ///
@@ -1106,10 +1314,32 @@ pub fn apply_adaptive_preprocessing(
 ///
 /// Returns `OcrError::ImageLoad` if the image cannot be loaded or processed.
 /// Returns `OcrError::ProcessingFailed` if preprocessing fails.
-async fn apply_image_preprocessing(
+pub async fn apply_image_preprocessing(
+    image_path: &str,
+    config: &crate::ocr_config::OcrConfig,
+    retry_profile: Option<&crate::ocr_config::RetryProfile>,
+) -> Result<(NamedTempFile, String, std::time::Duration, String), crate::ocr_errors::OcrError> {
+    let image_path = image_path.to_string();
+    let config = config.clone();
+    let retry_profile = retry_profile.cloned();
+    let span = tracing::Span::current();
+
+    tokio::task::spawn_blocking(move || {
+        let _enter = span.enter();
+        preprocess_image_file(&image_path, &config, retry_profile.as_ref())
+    })
+    .await
+    .map_err(|e| {
+        crate::ocr_errors::OcrError::Extraction(format!("Preprocessing task failed: {e}"))
+    })?
+}
+
+/// Blocking body of [`apply_image_preprocessing`]
+fn preprocess_image_file(
     image_path: &str,
-    _config: &crate::ocr_config::OcrConfig,
-) -> Result<(NamedTempFile, String, std::time::Duration), crate::ocr_errors::OcrError> {
+    config: &crate::ocr_config::OcrConfig,
+    retry_profile: Option<&crate::ocr_config::RetryProfile>,
+) -> Result<(NamedTempFile, String, std::time::Duration, String), crate::ocr_errors::OcrError> {
     let preprocessing_start = std::time::Instant::now();
 
     // Load the original image
@@ -1147,18 +1377,75 @@ async fn apply_image_preprocessing(
                 preprocessing_duration.as_millis()
             );
 
-            return Ok((temp_file, temp_path, preprocessing_duration));
+            return Ok((
+                temp_file,
+                temp_path,
+                preprocessing_duration,
+                "original_fallback".to_string(),
+            ));
         }
     };
 
+    // A retry profile replaces the source-aware pipeline entirely
+    if let Some(retry_profile) = retry_profile {
+        let processed_image = apply_retry_profile(&img, retry_profile)?;
+        let temp_file = NamedTempFile::with_suffix(".png").map_err(|e| {
+            crate::ocr_errors::OcrError::Extraction(format!(
+                "Failed to create temporary file: {}",
+                e
+            ))
+        })?;
+        processed_image
+            .image
+            .save_with_format(temp_file.path(), image::ImageFormat::Png)
+            .map_err(|e| {
+                crate::ocr_errors::OcrError::Extraction(format!(
+                    "Failed to save preprocessed image: {}",
+                    e
+                ))
+            })?;
+        let temp_path = temp_file.path().to_string_lossy().to_string();
+        return Ok((
+            temp_file,
+            temp_path,
+            preprocessing_start.elapsed(),
+            processed_image.preprocessing_strategy,
+        ));
+    }
+
     // Assess image quality to determine preprocessing strategy
     let quality_result =
         crate::preprocessing::quality::assess_image_quality(&img).map_err(|e| {
             crate::ocr_errors::OcrError::Extraction(format!("Quality assessment failed: {:?}", e))
         })?;
 
-    // Apply adaptive preprocessing based on image quality
-    let processed_image = apply_adaptive_preprocessing(&img, &quality_result).map_err(|e| {
+    // Classify the image source to pick the starting preprocessing profile
+    let metadata =
+        crate::preprocessing::ImageMetadata::from_image(std::path::Path::new(image_path), &img);
+    let source_class = crate::preprocessing::classify_source(&metadata);
+    crate::observability::record_preprocessing_source_class(source_class.as_str());
+    debug!(
+        target: "ocr_preprocessing",
+        "Classified image source as {}: format={:?}, {}x{}, channels={}, bits={}, camera_exif={}, noise={:?}",
+        source_class,
+        metadata.format,
+        metadata.width,
+        metadata.height,
+        metadata.channels,
+        metadata.bits_per_channel,
+        metadata.has_camera_exif,
+        metadata.noise_estimate
+    );
+
+    // Apply the class profile, adjusted for image quality
+    let processed_image = apply_source_aware_preprocessing(
+        &img,
+        &quality_result,
+        source_class,
+        config.source_profiles.for_class(source_class),
+        &config.pipeline,
+    )
+    .map_err(|e| {
         crate::ocr_errors::OcrError::Extraction(format!("Adaptive preprocessing failed: {:?}", e))
     })?;
 
@@ -1192,14 +1479,20 @@ async fn apply_image_preprocessing(
         processed_image.preprocessing_strategy
     );
 
-    Ok((temp_file, temp_path, preprocessing_duration))
+    Ok((
+        temp_file,
+        temp_path,
+        preprocessing_duration,
+        processed_image.preprocessing_strategy,
+    ))
 }
 
 async fn perform_ocr_extraction(
     image_path: &str,
     config: &crate::ocr_config::OcrConfig,
     instance_manager: &crate::instance_manager::OcrInstanceManager,
-) -> Result<(String, f32, std::time::Duration), crate::ocr_errors::OcrError> {
+    retry_profile: Option<&crate::ocr_config::RetryProfile>,
+) -> Result<(String, f32, std::time::Duration, String, Vec<f32>), crate::ocr_errors::OcrError> {
     // Start timing the actual OCR processing
     let ocr_start_time = std::time::Instant::now();
 
@@ -1208,24 +1501,23 @@ async fn perform_ocr_extraction(
 
     let result = tokio::time::timeout(timeout_duration, async {
         // Apply image preprocessing for OCR optimization
-        let (_temp_file, processed_image_path, preprocessing_duration) =
-            apply_image_preprocessing(image_path, config).await?;
+        let (_temp_file, processed_image_path, preprocessing_duration, preprocessing_strategy) =
+            apply_image_preprocessing(image_path, config, retry_profile).await?;
 
         info!(
             "Using preprocessed image for OCR: preprocessing took {:.2}ms",
             preprocessing_duration.as_millis()
         );
 
-        // Get or create OCR instance from the manager
+        // Check out a pooled OCR instance, waiting if all of them are busy
         let instance = instance_manager
-            .get_instance(config)
+            .checkout(config)
+            .await
             .map_err(|e| crate::ocr_errors::OcrError::Initialization(e.to_string()))?;
 
         // Perform OCR processing with the reused instance
-        let (extracted_text, tesseract_confidence) = {
-            let mut tess = instance
-                .lock()
-                .expect("Failed to acquire Tesseract instance lock");
+        let (extracted_text, tesseract_confidence, line_confidences) = {
+            let mut tess = instance.lock();
             // Set the preprocessed image for OCR processing
             tess.set_image(&processed_image_path).map_err(|e| {
                 crate::ocr_errors::OcrError::ImageLoad(format!(
@@ -1233,12 +1525,14 @@ async fn perform_ocr_extraction(
                 ))
             })?;
 
-            // Extract text from the image
+            // Extract text from the image; errors count against the instance's health
             let text = tess.get_utf8_text().map_err(|e| {
+                instance.record_failure();
                 crate::ocr_errors::OcrError::Extraction(format!(
                     "Failed to extract text from preprocessed image: {e}"
                 ))
             })?;
+            instance.record_success();
 
             // Extract confidence score from Tesseract
             // NOTE: The leptess crate (v0.14) does not expose Tesseract's confidence methods.
@@ -1246,25 +1540,29 @@ async fn perform_ocr_extraction(
             // TODO: Consider using a different Tesseract binding that exposes confidence scores.
             let confidence = 75.0; // Default confidence for successful OCR
 
-            (text, confidence)
+            // Word confidences come from the TSV output of the recognition just made
+            let line_confidences = match tess.get_tsv_text(0) {
+                Ok(tsv) => line_confidences_from_tsv(&tsv),
+                Err(e) => {
+                    warn!("Failed to read OCR line confidences: {e}");
+                    Vec::new()
+                }
+            };
+
+            (text, confidence, line_confidences)
         };
 
         // Note: The temporary file will be automatically cleaned up when _temp_file goes out of scope
 
-        // Clean up the extracted text (remove extra whitespace and empty lines)
-        let cleaned_text = extracted_text
-            .trim()
-            .lines()
-            .map(str::trim)
-            .filter(|line| !line.is_empty())
-            .collect::<Vec<&str>>()
-            .join("\n");
-
-        // Apply comprehensive OCR error correction
-        let error_corrector = OcrErrorCorrector::new();
-        let corrected_text = error_corrector.correct_text(&cleaned_text);
+        let (corrected_text, line_confidences) =
+            clean_ocr_output(&extracted_text, line_confidences);
 
-        Ok((corrected_text, tesseract_confidence))
+        Ok((
+            corrected_text,
+            tesseract_confidence,
+            preprocessing_strategy,
+            line_confidences,
+        ))
     })
     .await;
 
@@ -1272,14 +1570,20 @@ async fn perform_ocr_extraction(
     let ocr_ms = ocr_duration.as_millis();
 
     match result {
-        Ok(Ok((text, confidence))) => {
+        Ok(Ok((text, confidence, preprocessing_strategy, line_confidences))) => {
             info!(
                 "OCR processing completed in {}ms, extracted {} characters (Tesseract confidence: {:.1}%)",
                 ocr_ms,
                 text.len(),
                 confidence
             );
-            Ok((text, confidence, ocr_duration))
+            Ok((
+                text,
+                confidence,
+                ocr_duration,
+                preprocessing_strategy,
+                line_confidences,
+            ))
         }
         Ok(Err(e)) => {
             warn!("OCR processing failed after {ocr_ms}ms: {e:?}");
@@ -1298,6 +1602,212 @@ async fn perform_ocr_extraction(
     }
 }
 
+/// Cleans raw Tesseract output and corrects common OCR errors
+///
+/// Line confidences are matched to text lines by position, so they are dropped
+/// when the cleaned text does not have one line per confidence.
+fn clean_ocr_output(extracted_text: &str, line_confidences: Vec<f32>) -> (String, Vec<f32>) {
+    // Clean up the extracted text (remove extra whitespace and empty lines)
+    let cleaned_text = extracted_text
+        .trim()
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    // Apply comprehensive OCR error correction
+    let error_corrector = OcrErrorCorrector::new();
+    let corrected_text = error_corrector.correct_text(&cleaned_text);
+
+    let line_count = corrected_text.lines().count();
+    let line_confidences = if line_confidences.len() == line_count {
+        line_confidences
+    } else {
+        debug!(
+            "Ignoring OCR line confidences: {} confidences for {} lines",
+            line_confidences.len(),
+            line_count
+        );
+        Vec::new()
+    };
+
+    (corrected_text, line_confidences)
+}
+
+/// Longest side of a text column upscaled before the second OCR pass;
+/// larger columns are only binarized, their print is big enough already
+const COLUMN_UPSCALE_MAX_SIDE: u32 = 1600;
+
+/// Whether OCR text has at least one measurement line
+fn has_measurement_lines(text: &str) -> bool {
+    // Without a detector the first pass is kept as is
+    crate::text_processing::MeasurementDetector::new()
+        .map(|detector| detector.has_measurements(text))
+        .unwrap_or(true)
+}
+
+/// Second OCR pass over the page's densest text column
+///
+/// Runs within what is left of `config.total_timeout_secs` since `started`,
+/// and returns the column's text and line confidences when it has measurement
+/// lines. Every run is counted in the `ocr_second_pass_total` metric.
+async fn run_second_pass(
+    image_path: &str,
+    config: &crate::ocr_config::OcrConfig,
+    instance_manager: &crate::instance_manager::OcrInstanceManager,
+    started: std::time::Instant,
+) -> Option<(String, Vec<f32>)> {
+    let budget = std::time::Duration::from_secs(config.total_timeout_secs);
+    let Some(remaining) = budget
+        .checked_sub(started.elapsed())
+        .filter(|remaining| !remaining.is_zero())
+    else {
+        observability::record_ocr_second_pass("budget_exhausted");
+        debug!("No OCR time budget left for a second pass over {image_path}");
+        return None;
+    };
+
+    let outcome = match tokio::time::timeout(
+        remaining,
+        perform_column_ocr(image_path, config, instance_manager),
+    )
+    .await
+    {
+        Err(_) => Err("timeout"),
+        Ok(Err(e)) => {
+            warn!("Second OCR pass failed for {image_path}: {e:?}");
+            Err("error")
+        }
+        Ok(Ok(None)) => Err("no_column"),
+        Ok(Ok(Some((text, line_confidences)))) if has_measurement_lines(&text) => {
+            Ok((text, line_confidences))
+        }
+        Ok(Ok(Some(_))) => Err("no_measurements"),
+    };
+
+    match outcome {
+        Ok(found) => {
+            observability::record_ocr_second_pass("recovered");
+            info!(
+                "Second OCR pass found measurements in the densest text column of {image_path} ({} characters)",
+                found.0.len()
+            );
+            Some(found)
+        }
+        Err(outcome) => {
+            observability::record_ocr_second_pass(outcome);
+            debug!("Second OCR pass over {image_path} found nothing: {outcome}");
+            None
+        }
+    }
+}
+
+/// Crops the densest text column, scales it up and saves it for Tesseract
+///
+/// Returns `Ok(None)` when layout analysis finds no column worth cropping.
+fn prepare_text_column(
+    image_path: &str,
+) -> Result<Option<NamedTempFile>, crate::ocr_errors::OcrError> {
+    let img = image::open(image_path).map_err(|e| {
+        crate::ocr_errors::OcrError::ImageLoad(format!(
+            "Failed to load image for the second OCR pass: {e}"
+        ))
+    })?;
+    let Some(column) = crate::preprocessing::locate_densest_text_column(&img) else {
+        return Ok(None);
+    };
+    let cropped = crate::preprocessing::crop_text_column(&img, &column);
+
+    // Small columns are upscaled so small print gets enough pixels per glyph
+    let longest_side = cropped.image.width().max(cropped.image.height());
+    let processed = if longest_side <= COLUMN_UPSCALE_MAX_SIDE {
+        crate::preprocessing::preprocess_measurement_region(&cropped.image).map(|r| r.image)
+    } else {
+        crate::preprocessing::apply_otsu_threshold(&cropped.image).map(|r| r.image)
+    }
+    .map_err(|e| {
+        crate::ocr_errors::OcrError::Extraction(format!(
+            "Text column preprocessing failed: {:?}",
+            e
+        ))
+    })?;
+    debug!(
+        "Second OCR pass over text column {:?}, read at {}x{}",
+        cropped.cropped_region,
+        processed.width(),
+        processed.height()
+    );
+
+    let temp_file = NamedTempFile::with_suffix(".png").map_err(|e| {
+        crate::ocr_errors::OcrError::Extraction(format!("Failed to create temporary file: {}", e))
+    })?;
+    processed
+        .save_with_format(temp_file.path(), image::ImageFormat::Png)
+        .map_err(|e| {
+            crate::ocr_errors::OcrError::Extraction(format!(
+                "Failed to save text column image: {}",
+                e
+            ))
+        })?;
+
+    Ok(Some(temp_file))
+}
+
+/// Reads the densest text column at a higher scale
+///
+/// Returns `Ok(None)` when layout analysis finds no column worth cropping.
+async fn perform_column_ocr(
+    image_path: &str,
+    config: &crate::ocr_config::OcrConfig,
+    instance_manager: &crate::instance_manager::OcrInstanceManager,
+) -> Result<Option<(String, Vec<f32>)>, crate::ocr_errors::OcrError> {
+    // Layout analysis and cropping are CPU-bound, like the first pass preprocessing
+    let path = image_path.to_string();
+    let span = tracing::Span::current();
+    let prepared = tokio::task::spawn_blocking(move || {
+        let _enter = span.enter();
+        prepare_text_column(&path)
+    })
+    .await
+    .map_err(|e| {
+        crate::ocr_errors::OcrError::Extraction(format!("Text column task failed: {e}"))
+    })??;
+    let Some(temp_file) = prepared else {
+        return Ok(None);
+    };
+
+    let instance = instance_manager
+        .checkout(config)
+        .await
+        .map_err(|e| crate::ocr_errors::OcrError::Initialization(e.to_string()))?;
+    let (extracted_text, line_confidences) = {
+        let mut tess = instance.lock();
+        tess.set_image(temp_file.path()).map_err(|e| {
+            crate::ocr_errors::OcrError::ImageLoad(format!(
+                "Failed to load text column image for OCR: {e}"
+            ))
+        })?;
+        let text = tess.get_utf8_text().map_err(|e| {
+            instance.record_failure();
+            crate::ocr_errors::OcrError::Extraction(format!(
+                "Failed to extract text from text column: {e}"
+            ))
+        })?;
+        instance.record_success();
+        let line_confidences = match tess.get_tsv_text(0) {
+            Ok(tsv) => line_confidences_from_tsv(&tsv),
+            Err(e) => {
+                warn!("Failed to read second pass line confidences: {e}");
+                Vec::new()
+            }
+        };
+        (text, line_confidences)
+    };
+
+    Ok(Some(clean_ocr_output(&extracted_text, line_confidences)))
+}
+
 /// Calculate retry delay with exponential backoff
 ///
 /// Implements exponential backoff with jitter to prevent thundering herd problems.
@@ -1546,6 +2056,58 @@ pub struct OcrConfidence {
     pub processing_score: f32,
     /// Flags indicating potential issues
     pub flags: Vec<ConfidenceFlag>,
+    /// Image preprocessing strategy used for this result, when known
+    #[serde(default)]
+    pub preprocessing_strategy: Option<String>,
+    /// Mean word confidence of each non-empty text line (0.0 to 1.0), empty when unknown
+    #[serde(default)]
+    pub line_confidences: Vec<f32>,
+}
+
+/// Combine the OCR results of the pages of a multi-page input into one
+///
+/// Used for PDF pages and album photos. Texts are joined in page order, pages
+/// without text are skipped. The combined confidence is the one of the least
+/// confident page, with the flags of all pages. Line confidences are kept only when
+/// every page has them, so they stay aligned with the lines of the joined text.
+pub fn merge_page_results(pages: Vec<(String, OcrConfidence)>) -> Option<(String, OcrConfidence)> {
+    let pages: Vec<_> = pages
+        .into_iter()
+        .filter(|(text, _)| !text.trim().is_empty())
+        .collect();
+
+    let mut combined = pages
+        .iter()
+        .map(|(_, confidence)| confidence)
+        .min_by(|a, b| a.overall_score.total_cmp(&b.overall_score))?
+        .clone();
+
+    let mut flags: Vec<ConfidenceFlag> = Vec::new();
+    for flag in pages.iter().flat_map(|(_, confidence)| &confidence.flags) {
+        if !flags.contains(flag) {
+            flags.push(flag.clone());
+        }
+    }
+    combined.flags = flags;
+
+    combined.line_confidences = if pages
+        .iter()
+        .all(|(text, confidence)| confidence.line_confidences.len() == text.lines().count())
+    {
+        pages
+            .iter()
+            .flat_map(|(_, confidence)| confidence.line_confidences.iter().copied())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let text = pages
+        .iter()
+        .map(|(text, _)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some((text, combined))
 }
 
 /// Flags indicating confidence issues
@@ -1607,6 +2169,8 @@ pub fn calculate_ocr_confidence_with_tesseract(
         pattern_score,
         processing_score,
         flags,
+        preprocessing_strategy: None,
+        line_confidences: Vec::new(),
     }
 }
 
@@ -1738,6 +2302,50 @@ fn calculate_processing_time_score(
     }
 }
 
+/// Mean word confidence of each text line in Tesseract TSV output (0.0 to 1.0)
+///
+/// Lines come in reading order and lines without words are skipped, so they line up
+/// with the non-empty lines of the extracted text.
+pub fn line_confidences_from_tsv(tsv: &str) -> Vec<f32> {
+    // (page, block, paragraph, line) of each line, with its confidence sum and word count
+    let mut lines: Vec<([&str; 4], f32, u32)> = Vec::new();
+    for row in tsv.lines() {
+        let columns: Vec<&str> = row.split('\t').collect();
+        // Level 5 rows are words; the header and layout rows are skipped
+        if columns.len() < 12 || columns[0] != "5" || columns[11].trim().is_empty() {
+            continue;
+        }
+        let Ok(confidence) = columns[10].trim().parse::<f32>() else {
+            continue;
+        };
+        if confidence < 0.0 {
+            continue;
+        }
+        let key = [columns[1], columns[2], columns[3], columns[4]];
+        match lines.last_mut() {
+            Some((last_key, sum, count)) if *last_key == key => {
+                *sum += confidence;
+                *count += 1;
+            }
+            _ => lines.push((key, confidence, 1)),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|(_, sum, count)| (sum / count as f32 / 100.0).clamp(0.0, 1.0))
+        .collect()
+}
+
+/// Attach the confidence of their source line to ingredients read by OCR
+pub fn apply_line_confidences(
+    ingredients: &mut [crate::text_processing::MeasurementMatch],
+    line_confidences: &[f32],
+) {
+    for ingredient in ingredients {
+        ingredient.confidence = line_confidences.get(ingredient.line_number).copied();
+    }
+}
+
 /// Check if OCR confidence indicates low quality results that need review
 pub fn should_flag_for_review(confidence: &OcrConfidence, threshold: f32) -> bool {
     confidence.overall_score < threshold || !confidence.flags.is_empty()
@@ -1809,9 +2417,10 @@ pub async fn extract_hocr_from_image(
     // Validate image format and size limits
     validate_image_with_format_limits(image_path, config)?;
 
-    // Get OCR instance from pool
+    // Check out an OCR instance from the pool
     let instance = instance_manager
-        .get_instance(config)
+        .checkout(config)
+        .await
         .map_err(|e| OcrError::Initialization(format!("Failed to get OCR instance: {}", e)))?;
 
     // Apply timeout to the entire HOCR extraction process
@@ -1819,9 +2428,7 @@ pub async fn extract_hocr_from_image(
 
     let result = match tokio::time::timeout(timeout_duration, async {
         // Get mutable access to the OCR instance
-        let mut tess = instance.lock().map_err(|e| {
-            OcrError::Extraction(format!("Failed to acquire OCR instance lock: {}", e))
-        })?;
+        let mut tess = instance.lock();
 
         // Set the image for OCR processing
         tess.set_image(image_path).map_err(|e| {
@@ -2173,16 +2780,15 @@ pub async fn perform_constrained_ocr(
             .to_str()
             .ok_or_else(|| OcrError::Validation("Failed to get temporary file path".to_string()))?;
 
-        // Get an OCR instance from the manager
+        // Check out an OCR instance from the manager
         let instance = instance_manager
-            .get_instance(config)
+            .checkout(config)
+            .await
             .map_err(|e| OcrError::Initialization(e.to_string()))?;
 
         // Perform constrained OCR with optimized settings
         let (extracted_text, confidence) = {
-            let mut tess = instance
-                .lock()
-                .expect("Failed to acquire Tesseract instance lock");
+            let mut tess = instance.lock();
 
             // Configure Tesseract for constrained OCR on quantities
             // Use PSM 8 (Single Word) for isolated quantity recognition
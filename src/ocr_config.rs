@@ -8,6 +8,16 @@ pub const DEFAULT_LANGUAGES: &str = "eng+fra";
 pub const FORMAT_DETECTION_BUFFER_SIZE: usize = 32;
 pub const MIN_FORMAT_BYTES: usize = 8;
 pub const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB limit for image files
+pub const DEFAULT_MAX_IMAGE_WIDTH: u32 = 10_000; // Wider images are downscaled before OCR
+pub const DEFAULT_MAX_IMAGE_HEIGHT: u32 = 10_000; // Taller images are downscaled before OCR
+pub const DEFAULT_MAX_IMAGE_PIXELS: u64 = 50_000_000; // Larger images are rejected without decoding
+pub const DEFAULT_LOW_CONFIDENCE_THRESHOLD: f32 = 0.6; // Lines read with less are flagged in review
+pub const DEFAULT_MAX_PDF_PAGES: usize = 3; // Pages of a PDF document sent to OCR
+pub const DEFAULT_MAX_PDF_PAGE_SIZE: u64 = 3 * 1024 * 1024; // 3MB per PDF page
+pub const DEFAULT_TOTAL_OCR_TIMEOUT_SECS: u64 = 45; // Both OCR passes of an image together
+pub const DEFAULT_POOL_WARM_INSTANCES: usize = 1; // Tesseract instances created at startup
+pub const DEFAULT_POOL_MAX_INSTANCES: usize = 2; // Tesseract instances per language combination
+pub const DEFAULT_POOL_MAX_INSTANCE_FAILURES: u32 = 3; // Consecutive errors before an instance is recycled
 
 /// Recovery configuration for error handling
 #[derive(Debug, Clone)]
@@ -39,6 +49,50 @@ impl Default for RecoveryConfig {
     }
 }
 
+/// Tesseract instance pool settings, applied per language combination
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstancePoolConfig {
+    /// Instances created ahead of the first photo
+    pub warm_instances: usize,
+    /// Instances checked out at once; further checkouts wait for a checkin
+    pub max_instances: usize,
+    /// Consecutive extraction errors after which an instance is dropped and re-created
+    pub max_instance_failures: u32,
+}
+
+impl Default for InstancePoolConfig {
+    fn default() -> Self {
+        Self {
+            warm_instances: DEFAULT_POOL_WARM_INSTANCES,
+            max_instances: DEFAULT_POOL_MAX_INSTANCES,
+            max_instance_failures: DEFAULT_POOL_MAX_INSTANCE_FAILURES,
+        }
+    }
+}
+
+impl InstancePoolConfig {
+    /// Validate pool settings
+    pub fn validate(&self) -> crate::errors::AppResult<()> {
+        if self.max_instances == 0 {
+            return Err(crate::errors::AppError::Config(
+                "max_instances must be greater than 0".to_string(),
+            ));
+        }
+        if self.warm_instances > self.max_instances {
+            return Err(crate::errors::AppError::Config(format!(
+                "warm_instances ({}) cannot exceed max_instances ({})",
+                self.warm_instances, self.max_instances
+            )));
+        }
+        if self.max_instance_failures == 0 {
+            return Err(crate::errors::AppError::Config(
+                "max_instance_failures must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Format-specific file size limits for different image formats
 #[derive(Debug, Clone)]
 pub struct FormatSizeLimits {
@@ -235,6 +289,47 @@ impl FormatSizeLimits {
     }
 }
 
+/// Alternate preprocessing tried when a photo yields no measurements
+///
+/// Unlike the source class profiles, the stages run as listed, without the
+/// quality-based adjustments of [`crate::preprocessing::plan_preprocessing_stages`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryProfile {
+    /// Stable name used in logs, strategy strings and metrics
+    pub name: &'static str,
+    /// Stages run after scaling
+    pub profile: crate::preprocessing::PreprocessingProfile,
+    /// Gaussian sigma of the denoise stage
+    pub denoise_sigma: f32,
+}
+
+/// Profiles offered by the "Try again with different processing" button
+///
+/// Each one drops or strengthens the stage most likely to have ruined a photo.
+pub fn default_retry_profiles() -> Vec<RetryProfile> {
+    use crate::preprocessing::{PreprocessingProfile, PreprocessingStage::*};
+    vec![
+        // Thresholding can erase faint or colored text on busy backgrounds
+        RetryProfile {
+            name: "no_threshold",
+            profile: PreprocessingProfile::new([Deskew, Denoise]),
+            denoise_sigma: 0.8,
+        },
+        // Grainy low-light photos need more smoothing before binarization
+        RetryProfile {
+            name: "aggressive_denoise",
+            profile: PreprocessingProfile::new([Clahe, Denoise, Threshold, Despeckle]),
+            denoise_sigma: 2.0,
+        },
+        // Deskewing can rotate a straight list when the skew estimate is wrong
+        RetryProfile {
+            name: "no_deskew",
+            profile: PreprocessingProfile::new([Denoise, Threshold]),
+            denoise_sigma: 0.8,
+        },
+    ]
+}
+
 /// Configuration structure for OCR processing
 #[derive(Debug, Clone)]
 pub struct OcrConfig {
@@ -248,6 +343,12 @@ pub struct OcrConfig {
     pub min_format_bytes: usize,
     /// Maximum allowed file size in bytes (general limit)
     pub max_file_size: u64,
+    /// Images wider than this are downscaled to fit before OCR
+    pub max_width: u32,
+    /// Images taller than this are downscaled to fit before OCR
+    pub max_height: u32,
+    /// Images with more pixels than this are rejected before being decoded
+    pub max_pixels: u64,
     /// Format-specific size limits
     pub format_limits: FormatSizeLimits,
     /// Recovery and error handling configuration
@@ -260,6 +361,24 @@ pub struct OcrConfig {
     pub user_patterns_file: Option<String>,
     /// Character whitelist to restrict OCR output to recipe-relevant characters
     pub character_whitelist: Option<String>,
+    /// Default preprocessing profile per image source class
+    pub source_profiles: crate::preprocessing::SourceProfiles,
+    /// Thresholds deciding when CLAHE, deskewing and denoising run
+    pub pipeline: crate::preprocessing::PipelineConfig,
+    /// Alternate preprocessing profiles tried when a photo yields no measurements
+    pub retry_profiles: Vec<RetryProfile>,
+    /// Re-read the densest text column when the full page yields no measurements
+    pub second_pass_enabled: bool,
+    /// Time budget in seconds of an image's OCR, second pass included
+    pub total_timeout_secs: u64,
+    /// Line confidence (0.0 to 1.0) below which ingredients are flagged for review
+    pub low_confidence_threshold: f32,
+    /// Number of leading PDF pages rendered and sent to OCR
+    pub max_pdf_pages: usize,
+    /// Maximum size in bytes of each PDF page, both in the document and once rendered
+    pub max_pdf_page_size: u64,
+    /// Pre-warmed Tesseract instances and checkout limits
+    pub pool: InstancePoolConfig,
 }
 
 impl Default for OcrConfig {
@@ -270,17 +389,34 @@ impl Default for OcrConfig {
             buffer_size: FORMAT_DETECTION_BUFFER_SIZE,
             min_format_bytes: MIN_FORMAT_BYTES,
             max_file_size: MAX_FILE_SIZE,
+            max_width: DEFAULT_MAX_IMAGE_WIDTH,
+            max_height: DEFAULT_MAX_IMAGE_HEIGHT,
+            max_pixels: DEFAULT_MAX_IMAGE_PIXELS,
             format_limits: FormatSizeLimits::default(),
             recovery: RecoveryConfig::default(),
             psm_mode: PageSegMode::default(),
             user_words_file: Some("config/user_words.txt".to_string()),
             user_patterns_file: Some("config/user_patterns.txt".to_string()),
             character_whitelist: Some("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyzÀÂÄÉÈÊËÏÎÔÖÙÛÜŸàâäéèêëïîôöùûüÿ¼½¾⅓⅔⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞/.,-() ".to_string()),
+            source_profiles: crate::preprocessing::SourceProfiles::default(),
+            pipeline: crate::preprocessing::PipelineConfig::default(),
+            retry_profiles: default_retry_profiles(),
+            second_pass_enabled: true,
+            total_timeout_secs: DEFAULT_TOTAL_OCR_TIMEOUT_SECS,
+            low_confidence_threshold: DEFAULT_LOW_CONFIDENCE_THRESHOLD,
+            max_pdf_pages: DEFAULT_MAX_PDF_PAGES,
+            max_pdf_page_size: DEFAULT_MAX_PDF_PAGE_SIZE,
+            pool: InstancePoolConfig::default(),
         }
     }
 }
 
 impl OcrConfig {
+    /// Largest PDF document accepted, `max_pdf_page_size` for each page sent to OCR
+    pub fn max_pdf_file_size(&self) -> u64 {
+        (self.max_pdf_pages as u64).saturating_mul(self.max_pdf_page_size)
+    }
+
     /// Validate OCR configuration parameters
     pub fn validate(&self) -> crate::errors::AppResult<()> {
         // Validate languages string
@@ -315,9 +451,69 @@ impl OcrConfig {
             ));
         }
 
+        // Validate image dimension limits
+        if self.max_width == 0 || self.max_height == 0 {
+            return Err(crate::errors::AppError::Config(format!(
+                "max_width ({}) and max_height ({}) must be greater than 0",
+                self.max_width, self.max_height
+            )));
+        }
+        if self.max_pixels == 0 {
+            return Err(crate::errors::AppError::Config(
+                "max_pixels must be greater than 0".to_string(),
+            ));
+        }
+
+        // Validate the review confidence threshold
+        if !(0.0..=1.0).contains(&self.low_confidence_threshold) {
+            return Err(crate::errors::AppError::Config(format!(
+                "low_confidence_threshold ({}) must be between 0.0 and 1.0",
+                self.low_confidence_threshold
+            )));
+        }
+
+        self.pipeline.validate()?;
+
+        // Validate retry profiles; the denoise sigma must be a usable Gaussian
+        for retry in &self.retry_profiles {
+            if !(retry.denoise_sigma > 0.0 && retry.denoise_sigma.is_finite()) {
+                return Err(crate::errors::AppError::Config(format!(
+                    "retry profile {} denoise_sigma ({}) must be greater than 0",
+                    retry.name, retry.denoise_sigma
+                )));
+            }
+        }
+
+        // The total budget must leave room for a full first pass
+        if self.total_timeout_secs < self.recovery.operation_timeout_secs {
+            return Err(crate::errors::AppError::Config(format!(
+                "total_timeout_secs ({}) cannot be less than operation_timeout_secs ({})",
+                self.total_timeout_secs, self.recovery.operation_timeout_secs
+            )));
+        }
+
+        // Validate PDF limits; rendered pages go through the image size limit too
+        if self.max_pdf_pages == 0 {
+            return Err(crate::errors::AppError::Config(
+                "max_pdf_pages must be greater than 0".to_string(),
+            ));
+        }
+        if self.max_pdf_page_size == 0 {
+            return Err(crate::errors::AppError::Config(
+                "max_pdf_page_size must be greater than 0".to_string(),
+            ));
+        }
+        if self.max_pdf_file_size() > self.max_file_size {
+            return Err(crate::errors::AppError::Config(format!(
+                "max_pdf_pages ({}) x max_pdf_page_size ({}) cannot exceed max_file_size ({})",
+                self.max_pdf_pages, self.max_pdf_page_size, self.max_file_size
+            )));
+        }
+
         // Validate nested configurations
         self.format_limits.validate()?;
         self.recovery.validate()?;
+        self.pool.validate()?;
 
         Ok(())
     }
@@ -411,6 +607,115 @@ mod tests {
         assert_eq!(ModelType::default(), ModelType::Fast);
     }
 
+    #[test]
+    fn test_low_confidence_threshold_validation() {
+        let mut config = OcrConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.low_confidence_threshold = 1.5;
+        assert!(config.validate().is_err());
+        config.low_confidence_threshold = -0.1;
+        assert!(config.validate().is_err());
+        config.low_confidence_threshold = f32::NAN;
+        assert!(config.validate().is_err());
+        config.low_confidence_threshold = 0.0;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_default_retry_profiles_differ_from_each_other() {
+        use crate::preprocessing::PreprocessingStage;
+        let profiles = default_retry_profiles();
+        let names: Vec<_> = profiles.iter().map(|retry| retry.name).collect();
+        assert_eq!(names, ["no_threshold", "aggressive_denoise", "no_deskew"]);
+
+        let stages = |name: &str| {
+            profiles
+                .iter()
+                .find(|retry| retry.name == name)
+                .unwrap()
+                .profile
+                .stages()
+                .to_vec()
+        };
+        assert!(!stages("no_threshold").contains(&PreprocessingStage::Threshold));
+        assert!(!stages("no_deskew").contains(&PreprocessingStage::Deskew));
+
+        let mut config = OcrConfig::default();
+        assert!(config.validate().is_ok());
+        config.retry_profiles[0].denoise_sigma = 0.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_total_timeout_covers_first_pass() {
+        let mut config = OcrConfig::default();
+        assert!(config.total_timeout_secs > config.recovery.operation_timeout_secs);
+
+        config.total_timeout_secs = config.recovery.operation_timeout_secs;
+        assert!(config.validate().is_ok());
+        config.total_timeout_secs -= 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_pool_config_validation() {
+        let mut config = OcrConfig::default();
+        assert!(config.pool.warm_instances <= config.pool.max_instances);
+
+        config.pool.warm_instances = config.pool.max_instances + 1;
+        assert!(config.validate().is_err());
+        config.pool = InstancePoolConfig {
+            max_instances: 0,
+            warm_instances: 0,
+            ..InstancePoolConfig::default()
+        };
+        assert!(config.validate().is_err());
+        config.pool = InstancePoolConfig {
+            max_instance_failures: 0,
+            ..InstancePoolConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_image_dimension_limits_validation() {
+        let mut config = OcrConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.max_width = 0;
+        assert!(config.validate().is_err());
+        config.max_width = DEFAULT_MAX_IMAGE_WIDTH;
+
+        config.max_height = 0;
+        assert!(config.validate().is_err());
+        config.max_height = DEFAULT_MAX_IMAGE_HEIGHT;
+
+        config.max_pixels = 0;
+        assert!(config.validate().is_err());
+        config.max_pixels = 1;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_pdf_limits_validation() {
+        let mut config = OcrConfig::default();
+        assert_eq!(config.max_pdf_file_size(), 9 * 1024 * 1024);
+
+        config.max_pdf_pages = 0;
+        assert!(config.validate().is_err());
+        config.max_pdf_pages = DEFAULT_MAX_PDF_PAGES;
+
+        config.max_pdf_page_size = 0;
+        assert!(config.validate().is_err());
+
+        // The whole document must still fit in the download limit
+        config.max_pdf_page_size = MAX_FILE_SIZE;
+        assert!(config.validate().is_err());
+        config.max_pdf_pages = 1;
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_ocr_config_with_model_type() {
         // Test OcrConfig with different model types
@@ -20,6 +20,33 @@ pub enum OcrError {
     Timeout(String),
     /// Resource exhaustion errors
     _ResourceExhaustion(String),
+    /// Image exceeds the per-request memory budget of this server
+    ImageTooLarge(String),
+    /// PDF document or one of its pages exceeds the configured PDF limits
+    PdfTooLarge(String),
+    /// Image file exceeds the configured file size limits
+    FileTooLarge(String),
+    /// OCR is rejected while the circuit breaker is open
+    CircuitOpen(String),
+}
+
+impl OcrError {
+    /// Label of the variant in error metrics
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            OcrError::Validation(_) => "validation",
+            OcrError::Initialization(_) => "initialization",
+            OcrError::ImageLoad(_) => "image_load",
+            OcrError::Extraction(_) => "extraction",
+            OcrError::_InstanceCorruption(_) => "instance_corruption",
+            OcrError::Timeout(_) => "timeout",
+            OcrError::_ResourceExhaustion(_) => "resource_exhaustion",
+            OcrError::ImageTooLarge(_) => "image_too_large",
+            OcrError::PdfTooLarge(_) => "pdf_too_large",
+            OcrError::FileTooLarge(_) => "file_too_large",
+            OcrError::CircuitOpen(_) => "circuit_open",
+        }
+    }
 }
 
 impl std::fmt::Display for OcrError {
@@ -48,6 +75,18 @@ impl std::fmt::Display for OcrError {
                 "[OCR_RESOURCE] System resources exhausted during OCR: {}",
                 msg
             ),
+            OcrError::ImageTooLarge(msg) => write!(
+                f,
+                "[IMAGE_TOO_LARGE] Image too large for this server: {}",
+                msg
+            ),
+            OcrError::PdfTooLarge(msg) => {
+                write!(f, "[PDF_TOO_LARGE] PDF exceeds the page limits: {}", msg)
+            }
+            OcrError::FileTooLarge(msg) => write!(f, "[FILE_TOO_LARGE] {}", msg),
+            OcrError::CircuitOpen(msg) => {
+                write!(f, "[OCR_UNAVAILABLE] OCR temporarily unavailable: {}", msg)
+            }
         }
     }
 }
@@ -0,0 +1,140 @@
+//! # PDF Module
+//!
+//! Turns PDF documents into page images so they go through the same preprocessing
+//! and OCR pipeline as photos. Only the first `max_pdf_pages` pages are rendered,
+//! and the text of each page is concatenated before measurement detection.
+//!
+//! Rendering uses the Pdfium library, loaded at runtime from `PDFIUM_LIBRARY_PATH`
+//! or the system library path, and is only compiled with the `pdf` feature.
+
+use crate::ocr_config::OcrConfig;
+use crate::ocr_errors::OcrError;
+
+/// Width in pixels pages are rendered at, enough for small recipe print
+#[cfg(feature = "pdf")]
+const RENDER_TARGET_WIDTH: i32 = 2000;
+
+/// Whether an uploaded document is a PDF
+pub fn is_pdf_document(mime_type: Option<&str>) -> bool {
+    mime_type.is_some_and(|mime| mime == "application/pdf")
+}
+
+/// Whether this build can render PDF documents
+pub fn pdf_support_enabled() -> bool {
+    cfg!(feature = "pdf")
+}
+
+/// Reject documents larger than `max_pdf_pages` pages of `max_pdf_page_size` each
+pub fn check_pdf_file_size(file_size: u64, config: &OcrConfig) -> Result<(), OcrError> {
+    let max_size = config.max_pdf_file_size();
+    if file_size > max_size {
+        return Err(OcrError::PdfTooLarge(format!(
+            "document is {} bytes (maximum allowed: {} bytes)",
+            file_size, max_size
+        )));
+    }
+    Ok(())
+}
+
+/// Render the first pages of a PDF to PNG files
+///
+/// Blocking; run it on a blocking thread. The files are deleted when the returned
+/// paths are dropped.
+#[cfg(feature = "pdf")]
+pub fn render_pdf_pages(
+    pdf_path: &str,
+    config: &OcrConfig,
+) -> Result<Vec<tempfile::TempPath>, OcrError> {
+    use pdfium_render::prelude::*;
+
+    let bindings = match &crate::config::current().ocr_limits.pdfium_library_path {
+        Some(dir) => Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(dir)),
+        None => Pdfium::bind_to_system_library(),
+    }
+    .map_err(|e| OcrError::Initialization(format!("Failed to load Pdfium: {}", e)))?;
+    let pdfium = Pdfium::new(bindings);
+
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, None)
+        .map_err(|e| OcrError::ImageLoad(format!("Failed to open PDF: {}", e)))?;
+    let render_config = PdfRenderConfig::new().set_target_width(RENDER_TARGET_WIDTH);
+
+    let mut pages = Vec::new();
+    for (index, page) in document
+        .pages()
+        .iter()
+        .take(config.max_pdf_pages)
+        .enumerate()
+    {
+        let image = page
+            .render_with_config(&render_config)
+            .map_err(|e| {
+                OcrError::ImageLoad(format!("Failed to render page {}: {}", index + 1, e))
+            })?
+            .as_image();
+
+        let path = tempfile::Builder::new()
+            .suffix(".png")
+            .tempfile()
+            .map_err(|e| OcrError::Extraction(format!("Failed to create page file: {}", e)))?
+            .into_temp_path();
+        image
+            .save_with_format(&path, image::ImageFormat::Png)
+            .map_err(|e| {
+                OcrError::Extraction(format!("Failed to save page {}: {}", index + 1, e))
+            })?;
+
+        let page_size = std::fs::metadata(&path)
+            .map_err(|e| OcrError::Extraction(format!("Failed to read page {}: {}", index + 1, e)))?
+            .len();
+        if page_size > config.max_pdf_page_size {
+            return Err(OcrError::PdfTooLarge(format!(
+                "page {} renders to {} bytes (maximum allowed: {} bytes)",
+                index + 1,
+                page_size,
+                config.max_pdf_page_size
+            )));
+        }
+        pages.push(path);
+    }
+
+    if pages.is_empty() {
+        return Err(OcrError::Validation("PDF has no pages".to_string()));
+    }
+    Ok(pages)
+}
+
+/// Render the first pages of a PDF to PNG files
+///
+/// Always fails: this build was compiled without the `pdf` feature.
+#[cfg(not(feature = "pdf"))]
+pub fn render_pdf_pages(
+    _pdf_path: &str,
+    _config: &OcrConfig,
+) -> Result<Vec<tempfile::TempPath>, OcrError> {
+    Err(OcrError::Initialization(
+        "PDF support is not enabled in this build".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_pdf_document() {
+        assert!(is_pdf_document(Some("application/pdf")));
+        assert!(!is_pdf_document(Some("image/png")));
+        assert!(!is_pdf_document(None));
+    }
+
+    #[test]
+    fn test_check_pdf_file_size() {
+        let config = OcrConfig::default();
+        assert!(check_pdf_file_size(config.max_pdf_file_size(), &config).is_ok());
+        assert!(matches!(
+            check_pdf_file_size(config.max_pdf_file_size() + 1, &config),
+            Err(OcrError::PdfTooLarge(_))
+        ));
+    }
+}
@@ -1,14 +1,35 @@
 //! # Image Cropping Module
 //!
 //! This module provides image cropping functionality for isolating specific regions
-//! of interest, particularly for targeted OCR operations on measurement quantities.
+//! of interest, particularly for targeted OCR operations on measurement quantities
+//! and for the second OCR pass over the page's densest text column.
 
 use std::time::Instant;
 use tracing;
 
+use image::DynamicImage;
+
 use super::types::{CroppedImageResult, PreprocessingError};
 use crate::ocr::BBox;
 
+/// Padding in pixels kept around a cropped text column
+const COLUMN_PADDING: u32 = 12;
+
+/// Tiles along the longest side of the page for layout analysis (at least 16 px each)
+const COLUMN_TILES_PER_SIDE: u32 = 75;
+
+/// Ink coverage of a text tile; fuller tiles are pictures or solid blocks
+const TEXT_TILE_FILL: std::ops::RangeInclusive<f32> = 0.03..=0.6;
+
+/// Ink/background transitions per inked row of a text tile; edges of blocks have one
+const TEXT_TILE_MIN_TRANSITIONS: f32 = 2.0;
+
+/// Empty tile columns that separate two text columns
+const COLUMN_GUTTER_TILES: u32 = 2;
+
+/// Fraction of the page above which cropping to a column is not worth it
+const COLUMN_MAX_PAGE_FRACTION: f32 = 0.8;
+
 /// Crops a specific region from an image based on a bounding box, targeting the left portion
 /// where measurement quantities are typically located.
 ///
@@ -53,20 +74,14 @@ pub fn crop_measurement_region(
     // Ensure crop region is within image bounds
     let img_width = img.width();
     let img_height = img.height();
-
-    let safe_crop_x0 = crop_region.x0.min(img_width.saturating_sub(1));
-    let safe_crop_y0 = crop_region.y0.min(img_height.saturating_sub(1));
-    let safe_crop_x1 = crop_region.x1.min(img_width).max(safe_crop_x0 + 1);
-    let safe_crop_y1 = crop_region.y1.min(img_height).max(safe_crop_y0 + 1);
-
-    let safe_crop_region = BBox::new(safe_crop_x0, safe_crop_y0, safe_crop_x1, safe_crop_y1);
+    let safe_crop_region = clamp_to_image(&crop_region, img_width, img_height);
 
     // Crop the image
     let cropped_img = img.crop(
-        safe_crop_x0,
-        safe_crop_y0,
-        safe_crop_x1 - safe_crop_x0,
-        safe_crop_y1 - safe_crop_y0,
+        safe_crop_region.x0,
+        safe_crop_region.y0,
+        safe_crop_region.width(),
+        safe_crop_region.height(),
     );
 
     let processing_time_ms = start_time.elapsed().as_millis() as u32;
@@ -89,6 +104,201 @@ pub fn crop_measurement_region(
     })
 }
 
+/// Crops a text column found by [`locate_densest_text_column`] out of an image.
+///
+/// Unlike [`crop_measurement_region`], the whole column is kept so ingredient
+/// names stay next to their quantities, with `COLUMN_PADDING` pixels around it.
+///
+/// # Arguments
+///
+/// * `image` - The full page
+/// * `column` - Bounding box of the text column
+pub fn crop_text_column(image: &DynamicImage, column: &BBox) -> CroppedImageResult {
+    let start_time = Instant::now();
+
+    let padded = BBox::new(
+        column.x0.saturating_sub(COLUMN_PADDING),
+        column.y0.saturating_sub(COLUMN_PADDING),
+        column.x1.saturating_add(COLUMN_PADDING),
+        column.y1.saturating_add(COLUMN_PADDING),
+    );
+    let region = clamp_to_image(&padded, image.width(), image.height());
+    let cropped_img = image.crop_imm(region.x0, region.y0, region.width(), region.height());
+
+    tracing::debug!(
+        "Cropped text column {:?} from {}x{} page",
+        region,
+        image.width(),
+        image.height()
+    );
+
+    CroppedImageResult {
+        image: cropped_img,
+        original_bbox: column.clone(),
+        cropped_region: region,
+        processing_time_ms: start_time.elapsed().as_millis() as u32,
+    }
+}
+
+/// Locates the column of the page holding the most text.
+///
+/// Layout analysis on a binarized copy of the page, split into square tiles:
+/// 1. A tile is text when ink partly fills it and its inked rows cross
+///    several strokes; solid blocks, pictures, rules and block edges are not
+/// 2. Tile columns holding text are grouped into bands, split by gutters
+/// 3. The band with the most text tiles, weighted by how densely they fill
+///    the band's bounding box, wins
+///
+/// Returns `None` when the page has no text, or when the best column covers
+/// most of the page, since cropping to it would not help.
+pub fn locate_densest_text_column(image: &DynamicImage) -> Option<BBox> {
+    let gray = image.to_luma8();
+    let (width, height) = gray.dimensions();
+    let tile = (width.max(height) / COLUMN_TILES_PER_SIDE).max(16);
+    if width < tile * 2 || height < tile * 2 {
+        return None;
+    }
+
+    // Ink is whatever falls on the other side of the threshold from the background,
+    // taken as the most common level
+    let mut histogram = [0u64; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+    let threshold = otsu_level(&histogram);
+    let background = (0..256)
+        .max_by_key(|&level| histogram[level])
+        .unwrap_or(255) as u8;
+    let light_background = background > threshold;
+    let is_ink = |x: u32, y: u32| (gray.get_pixel(x, y)[0] <= threshold) == light_background;
+
+    // 1. Mark text tiles
+    let (tile_cols, tile_rows) = (width.div_ceil(tile), height.div_ceil(tile));
+    let text: Vec<Vec<bool>> = (0..tile_rows)
+        .map(|ty| {
+            (0..tile_cols)
+                .map(|tx| {
+                    let x0 = tx * tile;
+                    let y0 = ty * tile;
+                    is_text_tile(
+                        &is_ink,
+                        x0,
+                        y0,
+                        (x0 + tile).min(width),
+                        (y0 + tile).min(height),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    // 2. Group tile columns holding text into bands
+    let mut bands: Vec<(u32, u32)> = Vec::new();
+    for tx in (0..tile_cols).filter(|&tx| text.iter().any(|row| row[tx as usize])) {
+        match bands.last_mut() {
+            Some((_, end)) if tx - *end < COLUMN_GUTTER_TILES => *end = tx + 1,
+            _ => bands.push((tx, tx + 1)),
+        }
+    }
+
+    // 3. Score each band
+    let mut best: Option<(f32, BBox)> = None;
+    for (tx0, tx1) in bands {
+        let rows: Vec<u32> = (0..tile_rows)
+            .filter(|&ty| (tx0..tx1).any(|tx| text[ty as usize][tx as usize]))
+            .collect();
+        let (Some(&ty0), Some(&ty1)) = (rows.first(), rows.last()) else {
+            continue;
+        };
+        let tiles = (ty0..=ty1)
+            .flat_map(|ty| (tx0..tx1).map(move |tx| (tx, ty)))
+            .filter(|&(tx, ty)| text[ty as usize][tx as usize])
+            .count() as f32;
+        let band_area = ((tx1 - tx0) * (ty1 + 1 - ty0)) as f32;
+        let score = tiles * tiles / band_area;
+
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score > *best_score)
+        {
+            let column = BBox::new(
+                tx0 * tile,
+                ty0 * tile,
+                (tx1 * tile).min(width),
+                ((ty1 + 1) * tile).min(height),
+            );
+            best = Some((score, column));
+        }
+    }
+
+    let (_, column) = best?;
+    let page_area = width as f32 * height as f32;
+    (column.area() as f32 / page_area < COLUMN_MAX_PAGE_FRACTION).then_some(column)
+}
+
+/// Whether a tile looks like text: partly inked, with several strokes per inked row
+fn is_text_tile(is_ink: &impl Fn(u32, u32) -> bool, x0: u32, y0: u32, x1: u32, y1: u32) -> bool {
+    let (mut ink, mut inked_rows, mut transitions) = (0u32, 0u32, 0u32);
+    for y in y0..y1 {
+        let mut previous = is_ink(x0, y);
+        let mut row_ink = previous as u32;
+        for x in x0 + 1..x1 {
+            let current = is_ink(x, y);
+            row_ink += current as u32;
+            transitions += (current != previous) as u32;
+            previous = current;
+        }
+        ink += row_ink;
+        inked_rows += (row_ink > 0) as u32;
+    }
+
+    let fill = ink as f32 / ((x1 - x0) * (y1 - y0)) as f32;
+    inked_rows > 0
+        && TEXT_TILE_FILL.contains(&fill)
+        && transitions as f32 / inked_rows as f32 >= TEXT_TILE_MIN_TRANSITIONS
+}
+
+/// Otsu's threshold of a grayscale histogram
+fn otsu_level(histogram: &[u64; 256]) -> u8 {
+    let total: f64 = histogram.iter().map(|&count| count as f64).sum();
+    let weighted_total: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(level, &count)| level as f64 * count as f64)
+        .sum();
+
+    let (mut background, mut weighted_background) = (0.0, 0.0);
+    let (mut best_level, mut best_variance) = (0u8, 0.0);
+    for (level, &count) in histogram.iter().enumerate() {
+        background += count as f64;
+        if background == 0.0 {
+            continue;
+        }
+        let foreground = total - background;
+        if foreground == 0.0 {
+            break;
+        }
+        weighted_background += level as f64 * count as f64;
+        let mean_background = weighted_background / background;
+        let mean_foreground = (weighted_total - weighted_background) / foreground;
+        let variance = background * foreground * (mean_background - mean_foreground).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best_level = level as u8;
+        }
+    }
+    best_level
+}
+
+/// Clamps a region to the image, keeping it at least one pixel wide and high
+fn clamp_to_image(region: &BBox, img_width: u32, img_height: u32) -> BBox {
+    let x0 = region.x0.min(img_width.saturating_sub(1));
+    let y0 = region.y0.min(img_height.saturating_sub(1));
+    let x1 = region.x1.min(img_width).max(x0 + 1);
+    let y1 = region.y1.min(img_height).max(y0 + 1);
+    BBox::new(x0, y0, x1, y1)
+}
+
 /// Calculates the crop region for measurement extraction from a bounding box.
 ///
 /// Targets the left 20% of the bounding box width with 7-pixel padding to isolate
@@ -244,4 +454,59 @@ mod tests {
         assert_eq!(crop_region.x1, 14);
         assert_eq!(crop_region.y1, 22);
     }
+
+    /// Light page with lines of thin vertical strokes in `region`, like small print
+    fn page_with_text_block(region: &BBox) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(800, 600, |x, y| {
+            let in_region =
+                (region.x0..region.x1).contains(&x) && (region.y0..region.y1).contains(&y);
+            if in_region && (y / 12) % 2 == 0 && x % 6 < 2 {
+                Rgb([20, 20, 20])
+            } else {
+                Rgb([240, 240, 240])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_locate_densest_text_column() {
+        let list = BBox::new(480, 200, 600, 380);
+        let column = locate_densest_text_column(&page_with_text_block(&list))
+            .expect("text block should be found");
+        assert!(
+            column.x0 <= list.x0 && column.x1 >= list.x1 - 16,
+            "{column:?}"
+        );
+        assert!(
+            column.y0 <= list.y0 && column.y1 >= list.y1 - 16,
+            "{column:?}"
+        );
+
+        // Blank pages and solid blocks have no text
+        let blank = DynamicImage::ImageRgb8(RgbImage::from_pixel(800, 600, Rgb([240, 240, 240])));
+        assert!(locate_densest_text_column(&blank).is_none());
+        let block = DynamicImage::ImageRgb8(RgbImage::from_fn(800, 600, |x, _| {
+            if x < 400 {
+                Rgb([30, 30, 30])
+            } else {
+                Rgb([240, 240, 240])
+            }
+        }));
+        assert!(locate_densest_text_column(&block).is_none());
+
+        // Text covering the whole page is not worth cropping
+        let full = BBox::new(0, 0, 800, 600);
+        assert!(locate_densest_text_column(&page_with_text_block(&full)).is_none());
+    }
+
+    #[test]
+    fn test_crop_text_column_pads_and_clamps() {
+        let page = page_with_text_block(&BBox::new(0, 0, 10, 10));
+        let result = crop_text_column(&page, &BBox::new(5, 100, 200, 300));
+        assert_eq!(result.cropped_region, BBox::new(0, 88, 212, 312));
+        assert_eq!(result.image.width(), 212);
+
+        let result = crop_text_column(&page, &BBox::new(700, 500, 800, 600));
+        assert_eq!(result.cropped_region, BBox::new(688, 488, 800, 600));
+    }
 }
@@ -40,7 +40,7 @@ pub fn deskew_image(image: &DynamicImage) -> Result<DeskewResult, PreprocessingE
     let start_time = std::time::Instant::now();
 
     // Convert to grayscale for analysis
-    let gray = image.to_luma8();
+    let gray = super::luma_view(image);
 
     // Detect skew angle using projection profile analysis
     let skew_angle = detect_skew_angle(&gray)?;
@@ -81,6 +81,38 @@ pub fn deskew_image(image: &DynamicImage) -> Result<DeskewResult, PreprocessingE
     })
 }
 
+/// Deskews an image the pipeline owns, like [`deskew_image`] without the report.
+///
+/// Below the 0.5° rotation threshold the image is handed back as is instead of
+/// being cloned.
+pub fn deskew_image_owned(image: DynamicImage) -> Result<DynamicImage, PreprocessingError> {
+    let skew_angle = detect_skew_angle(&super::luma_view(&image))?;
+    if skew_angle.abs() < 0.5 {
+        return Ok(image);
+    }
+    apply_rotation_correction(&image, skew_angle)
+}
+
+/// Longest side of the thumbnail skew is estimated on during quality assessment
+const SKEW_ESTIMATE_MAX_SIDE: u32 = 600;
+
+/// Estimates text skew in degrees without correcting it.
+///
+/// Runs the same projection profile search as [`deskew_image`] on a thumbnail,
+/// so quality assessment can decide whether deskewing is worth running at all.
+/// Returns 0.0 when the skew cannot be measured.
+pub fn estimate_skew_angle(image: &DynamicImage) -> f32 {
+    let (width, height) = image.dimensions();
+    let gray = if width.max(height) > SKEW_ESTIMATE_MAX_SIDE {
+        image
+            .thumbnail(SKEW_ESTIMATE_MAX_SIDE, SKEW_ESTIMATE_MAX_SIDE)
+            .to_luma8()
+    } else {
+        image.to_luma8()
+    };
+    detect_skew_angle(&gray).unwrap_or(0.0)
+}
+
 /// Detects the skew angle of text in an image using projection profile analysis.
 ///
 /// This method works by:
@@ -499,6 +531,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_estimate_skew_angle_on_thumbnail() {
+        // Larger than the thumbnail, so the estimate runs on a downscaled copy
+        let img = create_horizontal_lines_image(1200, 900, 20);
+        assert!(estimate_skew_angle(&img).abs() < 1.0);
+        assert_eq!(
+            estimate_skew_angle(&create_uniform_image(100, 100, 128)),
+            0.0
+        );
+    }
+
     #[test]
     fn test_detect_skew_angle_horizontal_lines() {
         let img = create_horizontal_lines_image(100, 100, 10).to_luma8();
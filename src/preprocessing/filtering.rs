@@ -113,7 +113,7 @@ pub fn apply_morphological_operation(
     let start_time = std::time::Instant::now();
 
     // Convert to grayscale if not already
-    let gray = image.to_luma8();
+    let gray = super::luma_view(image);
 
     // Apply the specified morphological operation
     let processed = match operation {
@@ -150,6 +150,20 @@ pub fn apply_morphological_operation(
     })
 }
 
+/// Despeckles a binary image in place: an opening followed by a closing.
+///
+/// Same result as [`apply_morphological_operation`] with `Opening` then `Closing`,
+/// but the four passes share one scratch buffer instead of allocating an image each.
+pub fn despeckle_in_place(image: &mut image::GrayImage) {
+    let mut scratch = image::GrayImage::new(image.width(), image.height());
+    // Opening: erosion followed by dilation
+    erode_into(image, &mut scratch);
+    dilate_into(&scratch, image);
+    // Closing: dilation followed by erosion
+    dilate_into(image, &mut scratch);
+    erode_into(&scratch, image);
+}
+
 /// Applies erosion morphological operation using a 3x3 kernel.
 ///
 /// Erosion shrinks bright regions and removes small bright artifacts.
@@ -163,26 +177,8 @@ pub fn apply_morphological_operation(
 ///
 /// Returns the eroded image
 fn apply_erosion(image: &image::GrayImage) -> image::GrayImage {
-    let (width, height) = image.dimensions();
-    let mut result = image::GrayImage::new(width, height);
-
-    // 3x3 kernel for erosion (min operation)
-    for y in 1..height - 1 {
-        for x in 1..width - 1 {
-            // Find minimum value in 3x3 neighborhood
-            let mut min_val = 255u8;
-            for ky in -1..=1 {
-                for kx in -1..=1 {
-                    let nx = (x as i32 + kx) as u32;
-                    let ny = (y as i32 + ky) as u32;
-                    let pixel = image.get_pixel(nx, ny);
-                    min_val = min_val.min(pixel[0]);
-                }
-            }
-            result.put_pixel(x, y, image::Luma([min_val]));
-        }
-    }
-
+    let mut result = image::GrayImage::new(image.width(), image.height());
+    erode_into(image, &mut result);
     result
 }
 
@@ -199,27 +195,48 @@ fn apply_erosion(image: &image::GrayImage) -> image::GrayImage {
 ///
 /// Returns the dilated image
 fn apply_dilation(image: &image::GrayImage) -> image::GrayImage {
-    let (width, height) = image.dimensions();
-    let mut result = image::GrayImage::new(width, height);
+    let mut result = image::GrayImage::new(image.width(), image.height());
+    dilate_into(image, &mut result);
+    result
+}
+
+/// Writes the 3x3 erosion (min operation) of `image` into `output`
+fn erode_into(image: &image::GrayImage, output: &mut image::GrayImage) {
+    apply_3x3_kernel(image, output, 255, u8::min);
+}
 
-    // 3x3 kernel for dilation (max operation)
-    for y in 1..height - 1 {
-        for x in 1..width - 1 {
-            // Find maximum value in 3x3 neighborhood
-            let mut max_val = 0u8;
-            for ky in -1..=1 {
-                for kx in -1..=1 {
-                    let nx = (x as i32 + kx) as u32;
-                    let ny = (y as i32 + ky) as u32;
-                    let pixel = image.get_pixel(nx, ny);
-                    max_val = max_val.max(pixel[0]);
+/// Writes the 3x3 dilation (max operation) of `image` into `output`
+fn dilate_into(image: &image::GrayImage, output: &mut image::GrayImage) {
+    apply_3x3_kernel(image, output, 0, u8::max);
+}
+
+/// Folds each 3x3 neighborhood of `image` into `output`.
+///
+/// The one-pixel border has no full neighborhood and is set to black, so
+/// `output` may hold stale data from an earlier pass.
+fn apply_3x3_kernel(
+    image: &image::GrayImage,
+    output: &mut image::GrayImage,
+    init: u8,
+    fold: fn(u8, u8) -> u8,
+) {
+    let (width, height) = image.dimensions();
+    for y in 0..height {
+        for x in 0..width {
+            let value = if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                0
+            } else {
+                let mut acc = init;
+                for ny in y - 1..=y + 1 {
+                    for nx in x - 1..=x + 1 {
+                        acc = fold(acc, image.get_pixel(nx, ny)[0]);
+                    }
                 }
-            }
-            result.put_pixel(x, y, image::Luma([max_val]));
+                acc
+            };
+            output.put_pixel(x, y, image::Luma([value]));
         }
     }
-
-    result
 }
 
 /// Applies Contrast Limited Adaptive Histogram Equalization (CLAHE) to enhance local contrast.
@@ -272,7 +289,7 @@ pub fn apply_clahe(
     }
 
     // Convert to grayscale for CLAHE processing
-    let gray = image.to_luma8();
+    let gray = super::luma_view(image);
     let (width, height) = gray.dimensions();
 
     // Ensure tile size is not larger than image
@@ -533,6 +550,27 @@ mod tests {
         assert!(result.processing_time_ms < 50); // Should be fast
     }
 
+    #[test]
+    fn test_despeckle_in_place_matches_opening_then_closing() {
+        // Speckled page: isolated dots and pinholes around a solid block
+        let gray = image::GrayImage::from_fn(40, 30, |x, y| {
+            let block = (10..30).contains(&x) && (8..22).contains(&y);
+            let speck = (x * 7 + y * 13) % 17 == 0;
+            image::Luma([if block != speck { 0 } else { 255 }])
+        });
+        let opened = apply_morphological_operation(
+            &DynamicImage::ImageLuma8(gray.clone()),
+            MorphologicalOperation::Opening,
+        )
+        .unwrap();
+        let expected =
+            apply_morphological_operation(&opened.image, MorphologicalOperation::Closing).unwrap();
+
+        let mut despeckled = gray;
+        despeckle_in_place(&mut despeckled);
+        assert_eq!(&despeckled, expected.image.as_luma8().unwrap());
+    }
+
     #[test]
     fn test_apply_erosion_basic() {
         let mut img = image::GrayImage::new(5, 5);
@@ -11,13 +11,17 @@
 //! - `deskewing`: Text rotation detection and correction
 //! - `cropping`: Image cropping for targeted OCR regions
 //! - `targeted`: Specialized preprocessing for cropped measurement regions
+//! - `source_class`: Screenshot / photo / scan classification and per-class profiles
+//! - `pipeline`: Quality-driven selection of CLAHE, deskewing and denoising
 //! - `types`: Shared types and error definitions
 
 pub mod cropping;
 pub mod deskewing;
 pub mod filtering;
+pub mod pipeline;
 pub mod quality;
 pub mod scaling;
+pub mod source_class;
 pub mod targeted;
 pub mod thresholding;
 pub mod types;
@@ -30,10 +34,25 @@ pub use types::{
 };
 
 // Re-export main functions from sub-modules
-pub use cropping::crop_measurement_region;
-pub use deskewing::deskew_image;
-pub use filtering::{apply_clahe, apply_morphological_operation, reduce_noise};
+pub use cropping::{crop_measurement_region, crop_text_column, locate_densest_text_column};
+pub use deskewing::{deskew_image, deskew_image_owned, estimate_skew_angle};
+pub use filtering::{apply_clahe, apply_morphological_operation, despeckle_in_place, reduce_noise};
+pub use pipeline::{select_pipeline, PipelineConfig, PipelinePlan};
 pub use quality::assess_image_quality;
 pub use scaling::ImageScaler;
+pub use source_class::{
+    classify_source, plan_preprocessing_stages, ImageMetadata, PreprocessingProfile,
+    PreprocessingStage, SourceClass, SourceProfiles,
+};
 pub use targeted::preprocess_measurement_region;
-pub use thresholding::apply_otsu_threshold;
+pub use thresholding::{apply_otsu_threshold, apply_otsu_threshold_in_place};
+
+/// Borrows the luminance of an image that is already grayscale instead of copying it.
+///
+/// The pipeline converts to grayscale once, so later stages read it in place.
+pub(crate) fn luma_view(image: &image::DynamicImage) -> std::borrow::Cow<'_, image::GrayImage> {
+    match image.as_luma8() {
+        Some(gray) => std::borrow::Cow::Borrowed(gray),
+        None => std::borrow::Cow::Owned(image.to_luma8()),
+    }
+}
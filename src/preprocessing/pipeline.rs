@@ -0,0 +1,193 @@
+//! # Preprocessing Pipeline Selection
+//!
+//! Chooses the measurement-driven preprocessing stages (CLAHE, deskewing and
+//! denoising) from an image quality assessment. Selection only looks at the
+//! numbers in [`ImageQualityResult`], so it can be tested without images.
+
+use super::source_class::{PreprocessingStage, PHOTO_NOISE_THRESHOLD};
+use super::types::{ImageQuality, ImageQualityResult};
+
+/// Default skew, in degrees, from which deskewing runs
+pub const DEFAULT_DESKEW_MIN_ANGLE_DEGREES: f32 = 0.5;
+
+/// Default contrast ratio below which CLAHE runs
+pub const DEFAULT_LOW_CONTRAST_THRESHOLD: f32 = 0.3;
+
+/// Thresholds deciding which measurement-driven stages run
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelineConfig {
+    /// Absolute skew in degrees from which deskewing runs
+    pub deskew_min_angle_degrees: f32,
+    /// Contrast ratio (0.0-1.0) below which CLAHE runs
+    pub low_contrast_threshold: f32,
+    /// Noise estimate (standard deviation, 0-255 scale) from which denoising runs
+    pub noise_threshold: f32,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            deskew_min_angle_degrees: DEFAULT_DESKEW_MIN_ANGLE_DEGREES,
+            low_contrast_threshold: DEFAULT_LOW_CONTRAST_THRESHOLD,
+            noise_threshold: PHOTO_NOISE_THRESHOLD,
+        }
+    }
+}
+
+impl PipelineConfig {
+    /// Validate the thresholds
+    pub fn validate(&self) -> crate::errors::AppResult<()> {
+        if !(self.deskew_min_angle_degrees >= 0.0 && self.deskew_min_angle_degrees.is_finite()) {
+            return Err(crate::errors::AppError::Config(format!(
+                "deskew_min_angle_degrees ({}) must be 0 or more",
+                self.deskew_min_angle_degrees
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.low_contrast_threshold) {
+            return Err(crate::errors::AppError::Config(format!(
+                "low_contrast_threshold ({}) must be between 0.0 and 1.0",
+                self.low_contrast_threshold
+            )));
+        }
+        if !(self.noise_threshold >= 0.0 && self.noise_threshold.is_finite()) {
+            return Err(crate::errors::AppError::Config(format!(
+                "noise_threshold ({}) must be 0 or more",
+                self.noise_threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Stages chosen for an image, run in order after scaling
+#[derive(Debug, Clone, PartialEq)]
+pub struct PipelinePlan {
+    /// Stages in pipeline order
+    pub stages: Vec<PreprocessingStage>,
+    /// Gaussian sigma of the denoise stage
+    pub denoise_sigma: f32,
+}
+
+impl PipelinePlan {
+    /// Whether the plan runs `stage`
+    pub fn contains(&self, stage: PreprocessingStage) -> bool {
+        self.stages.contains(&stage)
+    }
+
+    /// Stage names joined with `+`, or `scale_only` when no stage runs
+    pub fn label(&self) -> String {
+        if self.stages.is_empty() {
+            "scale_only".to_string()
+        } else {
+            self.stages
+                .iter()
+                .map(|stage| stage.as_str())
+                .collect::<Vec<_>>()
+                .join("+")
+        }
+    }
+}
+
+/// Selects the measurement-driven stages for an assessed image.
+///
+/// - High quality skips denoising and CLAHE entirely
+/// - Low contrast gets CLAHE
+/// - Noise at or above `noise_threshold` gets denoising, stronger on low quality
+/// - Skew at or above `deskew_min_angle_degrees` gets deskewing, whatever the quality
+///
+/// Thresholding and despeckling depend on the source class and are planned by
+/// [`super::plan_preprocessing_stages`].
+pub fn select_pipeline(quality: &ImageQualityResult, cfg: &PipelineConfig) -> PipelinePlan {
+    use PreprocessingStage::*;
+
+    let clean = quality.quality == ImageQuality::High;
+    let mut stages = Vec::new();
+    if !clean && quality.contrast_ratio < cfg.low_contrast_threshold {
+        stages.push(Clahe);
+    }
+    if quality.skew_angle.abs() >= cfg.deskew_min_angle_degrees {
+        stages.push(Deskew);
+    }
+    if !clean && quality.noise_estimate >= cfg.noise_threshold {
+        stages.push(Denoise);
+    }
+
+    PipelinePlan {
+        stages,
+        denoise_sigma: if quality.quality == ImageQuality::Low {
+            1.2
+        } else {
+            0.8
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PreprocessingStage::*;
+
+    fn assessed(
+        quality: ImageQuality,
+        contrast_ratio: f32,
+        noise_estimate: f32,
+        skew_angle: f32,
+    ) -> ImageQualityResult {
+        ImageQualityResult {
+            quality,
+            contrast_ratio,
+            brightness: 0.5,
+            sharpness: 0.5,
+            noise_estimate,
+            skew_angle,
+            processing_time_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_clean_image_skips_denoise_and_clahe() {
+        let cfg = PipelineConfig::default();
+        let plan = select_pipeline(&assessed(ImageQuality::High, 0.1, 9.0, 0.0), &cfg);
+        assert!(plan.stages.is_empty());
+        assert_eq!(plan.label(), "scale_only");
+    }
+
+    #[test]
+    fn test_measurements_pick_stages() {
+        let cfg = PipelineConfig::default();
+        assert_eq!(
+            select_pipeline(&assessed(ImageQuality::Medium, 0.2, 0.5, 0.1), &cfg).stages,
+            [Clahe]
+        );
+        assert_eq!(
+            select_pipeline(&assessed(ImageQuality::Medium, 0.6, 4.0, 0.1), &cfg).stages,
+            [Denoise]
+        );
+
+        let low = select_pipeline(&assessed(ImageQuality::Low, 0.1, 6.0, -3.0), &cfg);
+        assert_eq!(low.stages, [Clahe, Deskew, Denoise]);
+        assert_eq!(low.denoise_sigma, 1.2);
+        assert_eq!(low.label(), "clahe+deskew+denoise");
+    }
+
+    #[test]
+    fn test_deskew_angle_is_configurable() {
+        let tilted = assessed(ImageQuality::High, 0.8, 0.0, 1.5);
+        assert_eq!(
+            select_pipeline(&tilted, &PipelineConfig::default()).stages,
+            [Deskew]
+        );
+
+        let cfg = PipelineConfig {
+            deskew_min_angle_degrees: 2.0,
+            ..PipelineConfig::default()
+        };
+        assert!(select_pipeline(&tilted, &cfg).stages.is_empty());
+        assert!(PipelineConfig {
+            deskew_min_angle_degrees: -1.0,
+            ..PipelineConfig::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -51,6 +51,8 @@ pub fn assess_image_quality(
     let contrast_ratio = calculate_contrast_ratio(&gray);
     let brightness = calculate_brightness(&gray);
     let sharpness = calculate_sharpness(&gray);
+    let noise_estimate = super::source_class::estimate_noise(image);
+    let skew_angle = super::deskewing::estimate_skew_angle(image);
 
     // Classify overall quality based on metrics
     let quality = classify_image_quality(contrast_ratio, brightness, sharpness);
@@ -59,12 +61,14 @@ pub fn assess_image_quality(
 
     tracing::debug!(
         target: "ocr_preprocessing",
-        "Quality assessment completed in {:.2}ms: quality={:?}, contrast={:.3}, brightness={:.3}, sharpness={:.3}",
+        "Quality assessment completed in {:.2}ms: quality={:?}, contrast={:.3}, brightness={:.3}, sharpness={:.3}, noise={:.2}, skew={:.2}°",
         processing_time.as_millis(),
         quality,
         contrast_ratio,
         brightness,
-        sharpness
+        sharpness,
+        noise_estimate,
+        skew_angle
     );
 
     Ok(ImageQualityResult {
@@ -72,6 +76,8 @@ pub fn assess_image_quality(
         contrast_ratio,
         brightness,
         sharpness,
+        noise_estimate,
+        skew_angle,
         processing_time_ms: processing_time.as_millis() as u32,
     })
 }
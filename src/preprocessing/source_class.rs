@@ -0,0 +1,722 @@
+//! # Image Source Classification Module
+//!
+//! This module classifies an input image as a screenshot, camera photo, or
+//! document scan from cheap metadata (sniffed format, dimensions, bit depth,
+//! EXIF camera fields and a noise estimate). Each source class maps to a
+//! default preprocessing profile that the quality-adaptive pipeline then
+//! adjusts.
+
+use std::path::Path;
+
+use image::{DynamicImage, ImageDecoder, ImageFormat};
+
+use super::pipeline::{select_pipeline, PipelineConfig, PipelinePlan};
+use super::types::{ImageQuality, ImageQualityResult, PreprocessingError};
+
+/// Noise estimate (standard deviation on a 0-255 scale) above which a
+/// lossless image is treated as a camera photo rather than rendered content.
+pub const PHOTO_NOISE_THRESHOLD: f32 = 2.5;
+
+/// Minimum length of the longest side for a colour lossless image to be
+/// considered a page scan rather than a screenshot.
+const SCAN_MIN_LONG_SIDE: u32 = 2000;
+
+/// Aspect ratio range (long side / short side) of common paper formats
+/// (US Letter is ~1.29, A4 is ~1.41).
+const PAGE_ASPECT_RANGE: std::ops::RangeInclusive<f32> = 1.25..=1.5;
+
+/// Gradient magnitude above which a pixel is considered part of an edge and
+/// excluded from the noise estimate.
+const NOISE_EDGE_THRESHOLD: i32 = 48;
+
+/// Maximum number of sample points per axis used by the noise estimate.
+const NOISE_SAMPLE_GRID: u32 = 512;
+
+/// EXIF IFD0 tags identifying the capturing device (Make, Model).
+const EXIF_CAMERA_TAGS: [u16; 2] = [0x010F, 0x0110];
+
+/// Where an image most likely came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceClass {
+    /// Rendered screen content: clean, sharp, rarely skewed
+    Screenshot,
+    /// Camera capture: sensor noise, perspective and skew
+    Photo,
+    /// Flatbed or document scan: speckles, slight skew, often grayscale
+    Scan,
+}
+
+impl SourceClass {
+    /// All source classes, in a stable order.
+    pub const ALL: [SourceClass; 3] = [
+        SourceClass::Screenshot,
+        SourceClass::Photo,
+        SourceClass::Scan,
+    ];
+
+    /// Stable lowercase name used in logs, metrics and extraction reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceClass::Screenshot => "screenshot",
+            SourceClass::Photo => "photo",
+            SourceClass::Scan => "scan",
+        }
+    }
+}
+
+impl std::fmt::Display for SourceClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Metadata used to classify an image's source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageMetadata {
+    /// Sniffed container format, if recognised
+    pub format: Option<ImageFormat>,
+    /// Image width in pixels
+    pub width: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Number of colour channels (1 = grayscale, 3 = RGB, ...)
+    pub channels: u8,
+    /// Bits per colour channel
+    pub bits_per_channel: u8,
+    /// Whether EXIF data names a capturing device (Make or Model)
+    pub has_camera_exif: bool,
+    /// Estimated noise standard deviation on a 0-255 scale, if measured
+    pub noise_estimate: Option<f32>,
+}
+
+impl ImageMetadata {
+    /// Collects metadata for an image already decoded from `path`.
+    ///
+    /// Format sniffing and EXIF lookup failures are not fatal: the
+    /// corresponding fields are simply left empty.
+    pub fn from_image(path: &Path, image: &DynamicImage) -> Self {
+        let color = image.color();
+        let channels = color.channel_count();
+        let bits_per_channel = (color.bits_per_pixel() / u16::from(channels.max(1))) as u8;
+
+        let (format, has_camera_exif) = sniff_format_and_exif(path);
+
+        Self {
+            format,
+            width: image.width(),
+            height: image.height(),
+            channels,
+            bits_per_channel,
+            has_camera_exif,
+            noise_estimate: Some(estimate_noise(image)),
+        }
+    }
+
+    fn is_grayscale(&self) -> bool {
+        self.channels <= 2
+    }
+
+    fn is_page_shaped(&self) -> bool {
+        let long = self.width.max(self.height);
+        let short = self.width.min(self.height).max(1);
+        long >= SCAN_MIN_LONG_SIDE && PAGE_ASPECT_RANGE.contains(&(long as f32 / short as f32))
+    }
+}
+
+/// Classifies an image's source from its metadata.
+///
+/// Camera EXIF fields always win. TIFF is treated as a scan. Lossless
+/// formats (PNG, BMP, GIF) are photos when noisy, scans when grayscale or
+/// page-shaped, and screenshots otherwise. Everything else (JPEG, WebP,
+/// unknown) defaults to photo unless it is a clean grayscale page.
+pub fn classify_source(metadata: &ImageMetadata) -> SourceClass {
+    if metadata.has_camera_exif {
+        return SourceClass::Photo;
+    }
+
+    let noisy = metadata
+        .noise_estimate
+        .is_some_and(|noise| noise >= PHOTO_NOISE_THRESHOLD);
+
+    match metadata.format {
+        Some(ImageFormat::Tiff) => SourceClass::Scan,
+        Some(ImageFormat::Png | ImageFormat::Bmp | ImageFormat::Gif) => {
+            if noisy {
+                SourceClass::Photo
+            } else if metadata.is_grayscale() || metadata.is_page_shaped() {
+                SourceClass::Scan
+            } else {
+                SourceClass::Screenshot
+            }
+        }
+        _ => {
+            let clean = metadata.noise_estimate.is_some() && !noisy;
+            if clean && metadata.is_grayscale() && metadata.is_page_shaped() {
+                SourceClass::Scan
+            } else {
+                SourceClass::Photo
+            }
+        }
+    }
+}
+
+/// A single optional preprocessing stage. Scaling always runs first and is
+/// not listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PreprocessingStage {
+    /// CLAHE contrast enhancement
+    Clahe,
+    /// Text rotation correction
+    Deskew,
+    /// Gaussian noise reduction
+    Denoise,
+    /// Otsu binarization
+    Threshold,
+    /// Morphological opening and closing to remove speckles
+    Despeckle,
+}
+
+impl PreprocessingStage {
+    /// Stable lowercase name used in configuration and strategy strings.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PreprocessingStage::Clahe => "clahe",
+            PreprocessingStage::Deskew => "deskew",
+            PreprocessingStage::Denoise => "denoise",
+            PreprocessingStage::Threshold => "threshold",
+            PreprocessingStage::Despeckle => "despeckle",
+        }
+    }
+
+    /// Runs this stage on an image, consuming it.
+    ///
+    /// The pipeline threads one image through its stages: thresholding and
+    /// despeckling work in place on grayscale images, and deskewing hands the
+    /// image back untouched when there is nothing to correct.
+    ///
+    /// `denoise_sigma` is the Gaussian sigma of the [`PreprocessingStage::Denoise`]
+    /// stage and is ignored by the others.
+    pub fn apply(
+        &self,
+        image: DynamicImage,
+        denoise_sigma: f32,
+    ) -> Result<DynamicImage, PreprocessingError> {
+        Ok(match self {
+            PreprocessingStage::Clahe => super::filtering::apply_clahe(&image, 3.0, (8, 8))?.image,
+            PreprocessingStage::Deskew => super::deskewing::deskew_image_owned(image)?,
+            PreprocessingStage::Denoise => {
+                super::filtering::reduce_noise(&image, denoise_sigma)?.image
+            }
+            PreprocessingStage::Threshold => {
+                let mut gray = image.into_luma8();
+                super::thresholding::apply_otsu_threshold_in_place(&mut gray)?;
+                DynamicImage::ImageLuma8(gray)
+            }
+            PreprocessingStage::Despeckle => {
+                let mut gray = image.into_luma8();
+                super::filtering::despeckle_in_place(&mut gray);
+                DynamicImage::ImageLuma8(gray)
+            }
+        })
+    }
+
+    /// Runs `stages` in order on a scaled image.
+    ///
+    /// Every stage works on luminance, so the image is converted to grayscale
+    /// once up front rather than by each stage. The error names the failing stage.
+    pub fn run_all(
+        stages: &[PreprocessingStage],
+        image: DynamicImage,
+        denoise_sigma: f32,
+    ) -> Result<DynamicImage, (PreprocessingStage, PreprocessingError)> {
+        if stages.is_empty() {
+            return Ok(image);
+        }
+        let mut current = DynamicImage::ImageLuma8(image.into_luma8());
+        for stage in stages {
+            current = stage
+                .apply(current, denoise_sigma)
+                .map_err(|e| (*stage, e))?;
+        }
+        Ok(current)
+    }
+
+    /// Parses a stage from its configuration name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "clahe" => Some(PreprocessingStage::Clahe),
+            "deskew" => Some(PreprocessingStage::Deskew),
+            "denoise" => Some(PreprocessingStage::Denoise),
+            "threshold" => Some(PreprocessingStage::Threshold),
+            "despeckle" => Some(PreprocessingStage::Despeckle),
+            _ => None,
+        }
+    }
+}
+
+/// The default stage set for a source class, before quality adjustments.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PreprocessingProfile {
+    stages: Vec<PreprocessingStage>,
+}
+
+impl PreprocessingProfile {
+    /// Builds a profile from stages; order and duplicates are normalized.
+    pub fn new(stages: impl IntoIterator<Item = PreprocessingStage>) -> Self {
+        let mut stages: Vec<PreprocessingStage> = stages.into_iter().collect();
+        stages.sort();
+        stages.dedup();
+        Self { stages }
+    }
+
+    /// Parses a comma-separated stage list such as `"deskew,threshold"`.
+    ///
+    /// An empty string or `"none"` yields a scale-only profile.
+    pub fn parse(value: &str) -> Result<Self, PreprocessingError> {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("none") {
+            return Ok(Self::default());
+        }
+
+        value
+            .split(',')
+            .map(|name| {
+                PreprocessingStage::from_name(name).ok_or_else(|| {
+                    PreprocessingError::ProcessingFailed {
+                        message: format!("Unknown preprocessing stage '{}'", name.trim()),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self::new)
+    }
+
+    /// Stages in this profile, in pipeline order.
+    pub fn stages(&self) -> &[PreprocessingStage] {
+        &self.stages
+    }
+}
+
+/// Preprocessing profile per source class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceProfiles {
+    /// Profile for screenshots
+    pub screenshot: PreprocessingProfile,
+    /// Profile for camera photos
+    pub photo: PreprocessingProfile,
+    /// Profile for document scans
+    pub scan: PreprocessingProfile,
+}
+
+impl Default for SourceProfiles {
+    fn default() -> Self {
+        use PreprocessingStage::*;
+        Self {
+            // Rendered text is already clean and upright
+            screenshot: PreprocessingProfile::default(),
+            photo: PreprocessingProfile::new([Deskew, Denoise, Threshold]),
+            scan: PreprocessingProfile::new([Deskew, Threshold, Despeckle]),
+        }
+    }
+}
+
+impl SourceProfiles {
+    /// Returns the profile for a source class.
+    pub fn for_class(&self, class: SourceClass) -> &PreprocessingProfile {
+        match class {
+            SourceClass::Screenshot => &self.screenshot,
+            SourceClass::Photo => &self.photo,
+            SourceClass::Scan => &self.scan,
+        }
+    }
+
+    /// Environment variable overriding the profile of a source class,
+    /// e.g. `OCR_PROFILE_SCAN=deskew,threshold,despeckle`.
+    pub fn env_var_name(class: SourceClass) -> String {
+        format!("OCR_PROFILE_{}", class.as_str().to_ascii_uppercase())
+    }
+
+    /// Returns the profile for a source class, for replacing it.
+    pub fn for_class_mut(&mut self, class: SourceClass) -> &mut PreprocessingProfile {
+        match class {
+            SourceClass::Screenshot => &mut self.screenshot,
+            SourceClass::Photo => &mut self.photo,
+            SourceClass::Scan => &mut self.scan,
+        }
+    }
+}
+
+/// Plans the stages for a class profile and an assessed image.
+///
+/// CLAHE, deskewing and denoising are measurement-driven (see
+/// [`select_pipeline`]): they run when the measurements call for them and the
+/// profile lists them, or on any low quality image. Binarization follows the
+/// profile, adjusted for quality:
+///
+/// - Medium quality always binarizes
+/// - Low quality binarizes and despeckles
+///
+/// Despeckling operates on binary images, so it always implies thresholding.
+pub fn plan_preprocessing_stages(
+    profile: &PreprocessingProfile,
+    quality: &ImageQualityResult,
+    cfg: &PipelineConfig,
+) -> PipelinePlan {
+    use PreprocessingStage::*;
+
+    let selected = select_pipeline(quality, cfg);
+    let low = quality.quality == ImageQuality::Low;
+    let mut stages: Vec<PreprocessingStage> = selected
+        .stages
+        .iter()
+        .copied()
+        .filter(|stage| low || profile.stages().contains(stage))
+        .collect();
+    stages.extend(
+        profile
+            .stages()
+            .iter()
+            .copied()
+            .filter(|stage| matches!(stage, Threshold | Despeckle)),
+    );
+    match quality.quality {
+        ImageQuality::High => {}
+        ImageQuality::Medium => stages.push(Threshold),
+        ImageQuality::Low => stages.extend([Threshold, Despeckle]),
+    }
+    if stages.contains(&Despeckle) {
+        stages.push(Threshold);
+    }
+
+    PipelinePlan {
+        stages: PreprocessingProfile::new(stages).stages,
+        denoise_sigma: selected.denoise_sigma,
+    }
+}
+
+/// Estimates sensor noise as a standard deviation on a 0-255 scale.
+///
+/// Uses Immerkær's Laplacian-difference estimator on a sampled grid,
+/// skipping edge pixels so rendered text does not read as noise.
+pub fn estimate_noise(image: &DynamicImage) -> f32 {
+    let gray = image.to_luma8();
+    let (width, height) = gray.dimensions();
+    if width < 3 || height < 3 {
+        return 0.0;
+    }
+
+    let step_x = ((width - 2) / NOISE_SAMPLE_GRID).max(1);
+    let step_y = ((height - 2) / NOISE_SAMPLE_GRID).max(1);
+    let px = |x: u32, y: u32| i32::from(gray.get_pixel(x, y)[0]);
+
+    let mut sum = 0u64;
+    let mut count = 0u64;
+    for y in (1..height - 1).step_by(step_y as usize) {
+        for x in (1..width - 1).step_by(step_x as usize) {
+            let gx = px(x + 1, y) - px(x - 1, y);
+            let gy = px(x, y + 1) - px(x, y - 1);
+            if gx.abs() + gy.abs() > NOISE_EDGE_THRESHOLD {
+                continue;
+            }
+
+            let response = 4 * px(x, y)
+                - 2 * (px(x - 1, y) + px(x + 1, y) + px(x, y - 1) + px(x, y + 1))
+                + (px(x - 1, y - 1) + px(x + 1, y - 1) + px(x - 1, y + 1) + px(x + 1, y + 1));
+            sum += response.unsigned_abs() as u64;
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return 0.0;
+    }
+    (std::f64::consts::FRAC_PI_2.sqrt() * sum as f64 / (6.0 * count as f64)) as f32
+}
+
+/// Sniffs the container format and checks EXIF for camera fields.
+fn sniff_format_and_exif(path: &Path) -> (Option<ImageFormat>, bool) {
+    let Ok(reader) = image::ImageReader::open(path).and_then(|r| r.with_guessed_format()) else {
+        return (None, false);
+    };
+    let format = reader.format();
+
+    let has_camera_exif = reader
+        .into_decoder()
+        .ok()
+        .and_then(|mut decoder| decoder.exif_metadata().ok().flatten())
+        .is_some_and(|exif| exif_has_camera_fields(&exif));
+
+    (format, has_camera_exif)
+}
+
+/// Returns true when the EXIF IFD0 contains a Make or Model tag.
+fn exif_has_camera_fields(exif: &[u8]) -> bool {
+    let tiff = exif.strip_prefix(b"Exif\0\0").unwrap_or(exif);
+    let little_endian = match tiff.get(0..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return false,
+    };
+    let read_u16 = |offset: usize| {
+        tiff.get(offset..offset + 2).map(|b| {
+            let bytes = [b[0], b[1]];
+            if little_endian {
+                u16::from_le_bytes(bytes)
+            } else {
+                u16::from_be_bytes(bytes)
+            }
+        })
+    };
+    let read_u32 = |offset: usize| {
+        tiff.get(offset..offset + 4).map(|b| {
+            let bytes = [b[0], b[1], b[2], b[3]];
+            if little_endian {
+                u32::from_le_bytes(bytes)
+            } else {
+                u32::from_be_bytes(bytes)
+            }
+        })
+    };
+
+    let Some(ifd0) = read_u32(4).map(|offset| offset as usize) else {
+        return false;
+    };
+    let Some(entry_count) = read_u16(ifd0) else {
+        return false;
+    };
+    (0..usize::from(entry_count))
+        .filter_map(|i| read_u16(ifd0 + 2 + i * 12))
+        .any(|tag| EXIF_CAMERA_TAGS.contains(&tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(format: ImageFormat, width: u32, height: u32, channels: u8) -> ImageMetadata {
+        ImageMetadata {
+            format: Some(format),
+            width,
+            height,
+            channels,
+            bits_per_channel: 8,
+            has_camera_exif: false,
+            noise_estimate: Some(0.5),
+        }
+    }
+
+    /// A tilted, noisy image, so every measurement-driven stage is called for
+    fn quality(quality: ImageQuality, contrast_ratio: f32) -> ImageQualityResult {
+        measured(quality, contrast_ratio, 3.0, 2.0)
+    }
+
+    fn measured(
+        quality: ImageQuality,
+        contrast_ratio: f32,
+        noise_estimate: f32,
+        skew_angle: f32,
+    ) -> ImageQualityResult {
+        ImageQualityResult {
+            quality,
+            contrast_ratio,
+            brightness: 0.5,
+            sharpness: 0.5,
+            noise_estimate,
+            skew_angle,
+            processing_time_ms: 0,
+        }
+    }
+
+    /// White page with black text bars, optionally with deterministic noise.
+    fn synthetic_page(noise_amplitude: i32) -> DynamicImage {
+        let mut seed: u32 = 12345;
+        let img = image::GrayImage::from_fn(200, 200, |x, y| {
+            let base = if (y / 10) % 3 == 0 && x > 20 && x < 180 {
+                20
+            } else {
+                235
+            };
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let jitter = if noise_amplitude > 0 {
+                (seed >> 16) as i32 % (2 * noise_amplitude + 1) - noise_amplitude
+            } else {
+                0
+            };
+            image::Luma([(base + jitter).clamp(0, 255) as u8])
+        });
+        DynamicImage::ImageLuma8(img)
+    }
+
+    #[test]
+    fn test_classify_phone_screenshot() {
+        let meta = metadata(ImageFormat::Png, 1170, 2532, 4);
+        assert_eq!(classify_source(&meta), SourceClass::Screenshot);
+    }
+
+    #[test]
+    fn test_classify_camera_jpeg() {
+        let meta = ImageMetadata {
+            has_camera_exif: true,
+            noise_estimate: Some(4.0),
+            ..metadata(ImageFormat::Jpeg, 4032, 3024, 3)
+        };
+        assert_eq!(classify_source(&meta), SourceClass::Photo);
+    }
+
+    #[test]
+    fn test_classify_tiff_scan() {
+        let meta = metadata(ImageFormat::Tiff, 2480, 3508, 1);
+        assert_eq!(classify_source(&meta), SourceClass::Scan);
+    }
+
+    #[test]
+    fn test_classify_large_png_photo_export_without_exif() {
+        // Photo exported from an editor: EXIF stripped, but sensor noise remains
+        let meta = ImageMetadata {
+            noise_estimate: Some(5.2),
+            ..metadata(ImageFormat::Png, 4032, 3024, 3)
+        };
+        assert_eq!(classify_source(&meta), SourceClass::Photo);
+    }
+
+    #[test]
+    fn test_classify_ambiguous_cases() {
+        // Camera EXIF overrides a scan-like container
+        let tiff_from_camera = ImageMetadata {
+            has_camera_exif: true,
+            ..metadata(ImageFormat::Tiff, 2480, 3508, 1)
+        };
+        assert_eq!(classify_source(&tiff_from_camera), SourceClass::Photo);
+
+        // Clean colour PNG at A4 resolution is a scan, not a screenshot
+        let color_page_png = metadata(ImageFormat::Png, 2480, 3508, 3);
+        assert_eq!(classify_source(&color_page_png), SourceClass::Scan);
+
+        // Clean grayscale PNG is a scan
+        let gray_png = metadata(ImageFormat::Png, 800, 600, 1);
+        assert_eq!(classify_source(&gray_png), SourceClass::Scan);
+
+        // Clean grayscale JPEG page is a scan
+        let gray_jpeg_page = metadata(ImageFormat::Jpeg, 2550, 3300, 1);
+        assert_eq!(classify_source(&gray_jpeg_page), SourceClass::Scan);
+
+        // JPEG without EXIF or a noise estimate falls back to photo
+        let bare_jpeg = ImageMetadata {
+            noise_estimate: None,
+            ..metadata(ImageFormat::Jpeg, 2550, 3300, 1)
+        };
+        assert_eq!(classify_source(&bare_jpeg), SourceClass::Photo);
+
+        // Unknown format defaults to photo
+        let unknown = ImageMetadata {
+            format: None,
+            ..metadata(ImageFormat::Png, 1000, 1000, 3)
+        };
+        assert_eq!(classify_source(&unknown), SourceClass::Photo);
+    }
+
+    #[test]
+    fn test_estimate_noise_separates_clean_and_noisy_images() {
+        let clean = estimate_noise(&synthetic_page(0));
+        let noisy = estimate_noise(&synthetic_page(12));
+
+        assert!(clean < 0.5, "clean page should read as noise-free: {clean}");
+        assert!(
+            noisy >= PHOTO_NOISE_THRESHOLD,
+            "noisy page should exceed the photo threshold: {noisy}"
+        );
+    }
+
+    #[test]
+    fn test_exif_camera_fields_detection() {
+        // Little-endian TIFF header, IFD0 at offset 8 with a single Make entry
+        let mut exif = b"Exif\0\0II*\0\x08\0\0\0\x01\0".to_vec();
+        exif.extend_from_slice(&[0x0F, 0x01, 2, 0, 4, 0, 0, 0, b'A', b'c', b'm', 0]);
+        assert!(exif_has_camera_fields(&exif));
+
+        // Same layout with a Software tag only (0x0131)
+        let mut software_only = b"II*\0\x08\0\0\0\x01\0".to_vec();
+        software_only.extend_from_slice(&[0x31, 0x01, 2, 0, 4, 0, 0, 0, b'i', b'O', b'S', 0]);
+        assert!(!exif_has_camera_fields(&software_only));
+
+        assert!(!exif_has_camera_fields(b"garbage"));
+    }
+
+    #[test]
+    fn test_profile_parse() {
+        let profile = PreprocessingProfile::parse("threshold, Deskew,threshold").unwrap();
+        assert_eq!(
+            profile.stages(),
+            &[PreprocessingStage::Deskew, PreprocessingStage::Threshold]
+        );
+        assert!(PreprocessingProfile::parse("none")
+            .unwrap()
+            .stages()
+            .is_empty());
+        assert!(PreprocessingProfile::parse("sharpen").is_err());
+    }
+
+    fn plan(
+        profile: &PreprocessingProfile,
+        quality: &ImageQualityResult,
+    ) -> Vec<PreprocessingStage> {
+        plan_preprocessing_stages(profile, quality, &PipelineConfig::default()).stages
+    }
+
+    #[test]
+    fn test_plan_stages_per_class_and_quality() {
+        use PreprocessingStage::*;
+        let profiles = SourceProfiles::default();
+        let high = quality(ImageQuality::High, 0.8);
+        let medium = quality(ImageQuality::Medium, 0.5);
+        let low = quality(ImageQuality::Low, 0.1);
+
+        let screenshot = profiles.for_class(SourceClass::Screenshot);
+        assert!(plan(screenshot, &high).is_empty());
+        assert_eq!(plan(screenshot, &medium), [Threshold]);
+
+        let photo = profiles.for_class(SourceClass::Photo);
+        assert_eq!(plan(photo, &high), [Deskew, Threshold]);
+        assert_eq!(plan(photo, &medium), [Deskew, Denoise, Threshold]);
+
+        let scan = profiles.for_class(SourceClass::Scan);
+        assert_eq!(plan(scan, &high), [Deskew, Threshold, Despeckle]);
+
+        for class in SourceClass::ALL {
+            assert_eq!(
+                plan(profiles.for_class(class), &low),
+                [Clahe, Deskew, Denoise, Threshold, Despeckle]
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_skips_stages_the_measurements_do_not_call_for() {
+        use PreprocessingStage::*;
+        let profiles = SourceProfiles::default();
+        let photo = profiles.for_class(SourceClass::Photo);
+
+        // A clean, straight photo keeps only its binarization
+        assert_eq!(
+            plan(photo, &measured(ImageQuality::Medium, 0.6, 0.5, 0.1)),
+            [Threshold]
+        );
+        assert_eq!(
+            plan(photo, &measured(ImageQuality::Low, 0.6, 0.5, 0.1)),
+            [Threshold, Despeckle]
+        );
+
+        // Measured skew is not enough on its own for a screenshot profile
+        let screenshot = profiles.for_class(SourceClass::Screenshot);
+        assert!(plan(screenshot, &measured(ImageQuality::High, 0.8, 0.0, 3.0)).is_empty());
+    }
+
+    #[test]
+    fn test_despeckle_override_implies_threshold() {
+        let profile = PreprocessingProfile::new([PreprocessingStage::Despeckle]);
+        assert_eq!(
+            plan(&profile, &quality(ImageQuality::High, 0.8)),
+            [PreprocessingStage::Threshold, PreprocessingStage::Despeckle]
+        );
+    }
+}
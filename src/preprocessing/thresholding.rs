@@ -41,14 +41,40 @@ pub fn apply_otsu_threshold(
 ) -> Result<ThresholdedImageResult, PreprocessingError> {
     let start_time = std::time::Instant::now();
 
-    // Convert to grayscale for thresholding
-    let gray = image.to_luma8();
+    // Convert to grayscale for thresholding, then binarize that copy in place
+    let mut binary_img = image.to_luma8();
+    let optimal_threshold = apply_otsu_threshold_in_place(&mut binary_img)?;
 
+    let processing_time = start_time.elapsed();
+
+    tracing::debug!(
+        target: "ocr_preprocessing",
+        "Otsu thresholding completed in {:.2}ms: threshold={}, dimensions={}x{}",
+        processing_time.as_millis(),
+        optimal_threshold,
+        binary_img.width(),
+        binary_img.height()
+    );
+
+    Ok(ThresholdedImageResult {
+        image: DynamicImage::ImageLuma8(binary_img),
+        threshold: optimal_threshold,
+        processing_time_ms: processing_time.as_millis() as u32,
+    })
+}
+
+/// Binarizes a grayscale image in place with Otsu's method.
+///
+/// Pixels above the threshold become white (255), the others black (0).
+/// Returns the threshold found.
+pub fn apply_otsu_threshold_in_place(
+    image: &mut image::GrayImage,
+) -> Result<u8, PreprocessingError> {
     // Calculate histogram
     let mut histogram = [0u32; 256];
-    let total_pixels = (gray.width() * gray.height()) as f64;
+    let total_pixels = (image.width() * image.height()) as f64;
 
-    for pixel in gray.pixels() {
+    for pixel in image.pixels() {
         histogram[pixel[0] as usize] += 1;
     }
 
@@ -56,34 +82,15 @@ pub fn apply_otsu_threshold(
     let optimal_threshold = find_otsu_threshold(&histogram, total_pixels)?;
 
     // Apply binary thresholding
-    let mut binary_img = image::GrayImage::new(gray.width(), gray.height());
-
-    for (x, y, pixel) in gray.enumerate_pixels() {
-        let intensity = pixel[0];
-        let binary_value = if intensity > optimal_threshold {
+    for pixel in image.pixels_mut() {
+        pixel[0] = if pixel[0] > optimal_threshold {
             255u8
         } else {
             0u8
         };
-        binary_img.put_pixel(x, y, image::Luma([binary_value]));
     }
 
-    let processing_time = start_time.elapsed();
-
-    tracing::debug!(
-        target: "ocr_preprocessing",
-        "Otsu thresholding completed in {:.2}ms: threshold={}, dimensions={}x{}",
-        processing_time.as_millis(),
-        optimal_threshold,
-        gray.width(),
-        gray.height()
-    );
-
-    Ok(ThresholdedImageResult {
-        image: DynamicImage::ImageLuma8(binary_img),
-        threshold: optimal_threshold,
-        processing_time_ms: processing_time.as_millis() as u32,
-    })
+    Ok(optimal_threshold)
 }
 
 /// Finds the optimal threshold using Otsu's method by maximizing between-class variance.
@@ -101,6 +101,10 @@ pub struct ImageQualityResult {
     pub brightness: f32,
     /// Sharpness score (0.0-1.0, higher is sharper)
     pub sharpness: f32,
+    /// Estimated sensor noise (standard deviation on a 0-255 scale)
+    pub noise_estimate: f32,
+    /// Estimated text skew in degrees (0.0 when it cannot be measured)
+    pub skew_angle: f32,
     /// Processing time in milliseconds
     pub processing_time_ms: u32,
 }
@@ -0,0 +1,435 @@
+//! # Quantity Module
+//!
+//! Exact ingredient quantities. A quantity is kept as a reduced fraction, so the
+//! "1/3 cup" a user wrote is stored, scaled and shown again as 1/3 rather than as
+//! 0.333….
+//!
+//! Quantities are parsed from whole numbers ("2"), decimals ("1.5", "0,5"), ASCII
+//! fractions ("1/2"), mixed numbers ("1 1/2") and Unicode fraction characters
+//! ("½", "2¼") produced by OCR. They are displayed as a fraction when the
+//! denominator is one a cook measures with (halves, thirds, quarters, eighths) or
+//! cannot be written as a decimal, and as a decimal otherwise.
+
+use std::fmt;
+
+/// Denominators shown as fractions even though they have an exact decimal form
+const FRACTION_DENOMINATORS: [i64; 3] = [2, 4, 8];
+
+/// Largest denominator looked for when recovering a fraction from a float
+const MAX_RECOVERED_DENOMINATOR: i64 = 64;
+
+/// Distance within which a float is considered equal to a fraction
+const RECOVERY_TOLERANCE: f64 = 1e-9;
+
+/// Decimal places kept for floats that are not a simple fraction, as stored by the database
+const DECIMAL_PLACES: u32 = 3;
+
+/// An exact quantity as a reduced fraction, with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Quantity {
+    numerator: i64,
+    denominator: i64,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// Value of a Unicode vulgar fraction character
+fn unicode_fraction(c: char) -> Option<Quantity> {
+    let (numerator, denominator) = match c {
+        '½' => (1, 2),
+        '⅓' => (1, 3),
+        '⅔' => (2, 3),
+        '¼' => (1, 4),
+        '¾' => (3, 4),
+        '⅕' => (1, 5),
+        '⅖' => (2, 5),
+        '⅗' => (3, 5),
+        '⅘' => (4, 5),
+        '⅙' => (1, 6),
+        '⅚' => (5, 6),
+        '⅛' => (1, 8),
+        '⅜' => (3, 8),
+        '⅝' => (5, 8),
+        '⅞' => (7, 8),
+        _ => return None,
+    };
+    Quantity::new(numerator, denominator)
+}
+
+impl Quantity {
+    /// Zero
+    pub const ZERO: Quantity = Quantity {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    /// The fraction `numerator / denominator`, reduced; `None` when the denominator is zero
+    pub fn new(numerator: i64, denominator: i64) -> Option<Self> {
+        Self::reduce(i128::from(numerator), i128::from(denominator))
+    }
+
+    /// A whole number
+    pub fn whole(value: i64) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    fn reduce(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator).max(1) * denominator.signum();
+        Some(Self {
+            numerator: i64::try_from(numerator / divisor).ok()?,
+            denominator: i64::try_from(denominator / divisor).ok()?,
+        })
+    }
+
+    /// Numerator of the reduced fraction, carrying the sign
+    pub fn numerator(self) -> i64 {
+        self.numerator
+    }
+
+    /// Denominator of the reduced fraction, always positive
+    pub fn denominator(self) -> i64 {
+        self.denominator
+    }
+
+    /// Whether the quantity is a whole number
+    pub fn is_whole(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Closest float, for sorting, unit conversion and comparisons
+    pub fn to_f64(self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// Quantity for a float, recovering the fraction it was computed from
+    ///
+    /// Floats equal to a fraction with a small denominator (0.5, 1.0 / 3.0) give that
+    /// fraction; other values keep three decimals, like the database column. `None`
+    /// for infinite or NaN values.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+        for denominator in 1..=MAX_RECOVERED_DENOMINATOR {
+            let numerator = (value * denominator as f64).round();
+            if (numerator / denominator as f64 - value).abs() < RECOVERY_TOLERANCE {
+                return Self::new(numerator as i64, denominator);
+            }
+        }
+        let scale = 10_i64.pow(DECIMAL_PLACES);
+        Self::new((value * scale as f64).round() as i64, scale)
+    }
+
+    /// Parse a quantity written as a number, a fraction or a mixed number
+    ///
+    /// Accepts "2", "1.5", "0,5", "1/2", "1 1/2", "½", "2½" and "2 ¼". Returns `None`
+    /// for anything else, such as ranges ("2-3") or words ("some").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use just_ingredients::quantity::Quantity;
+    ///
+    /// let third = Quantity::parse("1/3").unwrap();
+    /// assert_eq!(third.to_string(), "1/3");
+    /// assert_eq!(Quantity::parse("1 1/2"), Quantity::new(3, 2));
+    /// assert_eq!(Quantity::parse("a pinch"), None);
+    /// ```
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest.trim_start()),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let magnitude = Self::parse_unsigned(unsigned)?;
+        Some(if negative {
+            Self {
+                numerator: -magnitude.numerator,
+                ..magnitude
+            }
+        } else {
+            magnitude
+        })
+    }
+
+    fn parse_unsigned(text: &str) -> Option<Self> {
+        if text.is_empty() {
+            return None;
+        }
+
+        // Trailing Unicode fraction, alone ("½") or after a whole number ("2½", "2 ½")
+        let mut chars = text.chars();
+        if let Some(fraction) = chars.next_back().and_then(unicode_fraction) {
+            let whole = chars.as_str().trim();
+            if whole.is_empty() {
+                return Some(fraction);
+            }
+            return Self::parse_decimal(whole)?.checked_add(fraction);
+        }
+
+        // Mixed number with an ASCII fraction ("1 1/2")
+        if let Some((whole, fraction)) = text.split_once(char::is_whitespace) {
+            let fraction = fraction.trim();
+            if !fraction.contains('/') {
+                return None;
+            }
+            return Self::parse_decimal(whole)?.checked_add(Self::parse_fraction(fraction)?);
+        }
+
+        Self::parse_fraction(text)
+    }
+
+    /// Parse a decimal or an ASCII fraction ("1.5", "1/2")
+    fn parse_fraction(text: &str) -> Option<Self> {
+        match text.split_once('/') {
+            Some((numerator, denominator)) => {
+                let numerator = Self::parse_decimal(numerator.trim())?;
+                let denominator = Self::parse_decimal(denominator.trim())?;
+                Self::reduce(
+                    i128::from(numerator.numerator) * i128::from(denominator.denominator),
+                    i128::from(numerator.denominator) * i128::from(denominator.numerator),
+                )
+            }
+            None => Self::parse_decimal(text),
+        }
+    }
+
+    /// Parse an unsigned decimal with a dot or comma separator ("2", "1.5", "0,25", ".5")
+    fn parse_decimal(text: &str) -> Option<Self> {
+        let (whole, fraction) = text.split_once(['.', ',']).unwrap_or((text, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let denominator = 10_i64.checked_pow(u32::try_from(fraction.len()).ok()?)?;
+        let digits = format!("{whole}{fraction}");
+        Self::new(digits.parse().ok()?, denominator)
+    }
+
+    /// Sum of two quantities, `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Self::reduce(
+            i128::from(self.numerator) * i128::from(other.denominator)
+                + i128::from(other.numerator) * i128::from(self.denominator),
+            i128::from(self.denominator) * i128::from(other.denominator),
+        )
+    }
+
+    /// Product of two quantities, `None` on overflow
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        Self::reduce(
+            i128::from(self.numerator) * i128::from(other.numerator),
+            i128::from(self.denominator) * i128::from(other.denominator),
+        )
+    }
+
+    /// Whether the quantity reads best as a fraction rather than a decimal
+    ///
+    /// True for halves, quarters and eighths, and for denominators with no exact
+    /// decimal form (thirds, sixths).
+    pub fn prefers_fraction(self) -> bool {
+        if self.is_whole() {
+            return false;
+        }
+        if FRACTION_DENOMINATORS.contains(&self.denominator) {
+            return true;
+        }
+        let mut rest = self.denominator;
+        for factor in [2, 5] {
+            while rest % factor == 0 {
+                rest /= factor;
+            }
+        }
+        rest != 1
+    }
+}
+
+impl fmt::Display for Quantity {
+    /// Whole numbers as is ("2"), cooking fractions as fractions ("1/3", "1 1/2")
+    /// and other values as exact decimals ("0.2", "1.25")
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.numerator < 0 {
+            write!(f, "-")?;
+        }
+        let numerator = self.numerator.unsigned_abs();
+        let denominator = self.denominator.unsigned_abs();
+        let (whole, remainder) = (numerator / denominator, numerator % denominator);
+
+        if remainder == 0 {
+            return write!(f, "{whole}");
+        }
+        if self.prefers_fraction() {
+            return match whole {
+                0 => write!(f, "{remainder}/{denominator}"),
+                _ => write!(f, "{whole} {remainder}/{denominator}"),
+            };
+        }
+
+        // The denominator divides a power of ten, so the decimal form is exact
+        let mut places = 0;
+        let mut scale = 1_u64;
+        while !scale.is_multiple_of(denominator) {
+            scale *= 10;
+            places += 1;
+        }
+        let digits = remainder * (scale / denominator);
+        write!(f, "{whole}.{digits:0places$}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(numerator: i64, denominator: i64) -> Quantity {
+        Quantity::new(numerator, denominator).unwrap()
+    }
+
+    #[test]
+    fn test_new_reduces() {
+        assert_eq!(q(2, 4), q(1, 2));
+        assert_eq!(q(2, 4).numerator(), 1);
+        assert_eq!(q(2, 4).denominator(), 2);
+        assert_eq!(q(1, -2).numerator(), -1);
+        assert_eq!(q(1, -2).denominator(), 2);
+        assert_eq!(q(0, 5), Quantity::ZERO);
+        assert_eq!(Quantity::new(1, 0), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Quantity::parse("2"), Some(Quantity::whole(2)));
+        assert_eq!(Quantity::parse("1.5"), Some(q(3, 2)));
+        assert_eq!(Quantity::parse("0,5"), Some(q(1, 2)));
+        assert_eq!(Quantity::parse(".25"), Some(q(1, 4)));
+        assert_eq!(Quantity::parse("1/3"), Some(q(1, 3)));
+        assert_eq!(Quantity::parse(" 2/3 "), Some(q(2, 3)));
+        assert_eq!(Quantity::parse("1 1/2"), Some(q(3, 2)));
+        assert_eq!(Quantity::parse("½"), Some(q(1, 2)));
+        assert_eq!(Quantity::parse("⅓"), Some(q(1, 3)));
+        assert_eq!(Quantity::parse("2½"), Some(q(5, 2)));
+        assert_eq!(Quantity::parse("2 ¼"), Some(q(9, 4)));
+        assert_eq!(Quantity::parse("1.5/2"), Some(q(3, 4)));
+        assert_eq!(Quantity::parse("-2"), Some(Quantity::whole(-2)));
+        assert_eq!(Quantity::parse("-1 1/2"), Some(q(-3, 2)));
+        assert_eq!(Quantity::parse("+3"), Some(Quantity::whole(3)));
+    }
+
+    #[test]
+    fn test_parse_rejects_non_quantities() {
+        for text in [
+            "", " ", "1/0", "2-3", "some", "1 2", "1e3", "inf", "NaN", ".", "1..2", "/2", "½½",
+            "a½", "--1",
+        ] {
+            assert_eq!(Quantity::parse(text), None, "{text:?}");
+        }
+        // Too many digits to hold
+        assert_eq!(Quantity::parse("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Quantity::whole(2).to_string(), "2");
+        assert_eq!(Quantity::ZERO.to_string(), "0");
+        assert_eq!(q(1, 3).to_string(), "1/3");
+        assert_eq!(q(1, 2).to_string(), "1/2");
+        assert_eq!(q(3, 2).to_string(), "1 1/2");
+        assert_eq!(q(7, 8).to_string(), "7/8");
+        assert_eq!(q(5, 6).to_string(), "5/6");
+        assert_eq!(q(5, 4).to_string(), "1 1/4");
+        assert_eq!(q(-3, 2).to_string(), "-1 1/2");
+        // Denominators with an exact decimal form that cooks don't measure with
+        assert_eq!(q(1, 5).to_string(), "0.2");
+        assert_eq!(q(1, 10).to_string(), "0.1");
+        assert_eq!(q(1, 16).to_string(), "0.0625");
+        assert_eq!(q(333, 1000).to_string(), "0.333");
+        assert_eq!(q(1, 100).to_string(), "0.01");
+        assert_eq!(q(-1, 20).to_string(), "-0.05");
+    }
+
+    #[test]
+    fn test_parse_display_round_trip() {
+        for text in [
+            "2", "1/3", "2/3", "1 1/2", "3/4", "1/8", "5/6", "2 1/3", "0.2", "1.75", "0.333",
+            "0.05", "250", "12.5", "-1/2", "1/7",
+        ] {
+            let quantity = Quantity::parse(text).unwrap();
+            assert_eq!(Quantity::parse(&quantity.to_string()), Some(quantity));
+        }
+        // Unicode and comma forms come back in their canonical spelling
+        assert_eq!(Quantity::parse("2½").unwrap().to_string(), "2 1/2");
+        assert_eq!(Quantity::parse("0,5").unwrap().to_string(), "1/2");
+        assert_eq!(Quantity::parse("1.5").unwrap().to_string(), "1 1/2");
+        assert_eq!(Quantity::parse("0.20").unwrap().to_string(), "0.2");
+    }
+
+    #[test]
+    fn test_from_f64_recovers_fractions() {
+        assert_eq!(Quantity::from_f64(0.5), Some(q(1, 2)));
+        assert_eq!(Quantity::from_f64(1.0 / 3.0), Some(q(1, 3)));
+        assert_eq!(Quantity::from_f64(2.0 / 3.0 * 4.0), Some(q(8, 3)));
+        assert_eq!(Quantity::from_f64(250.0), Some(Quantity::whole(250)));
+        assert_eq!(Quantity::from_f64(0.1), Some(q(1, 10)));
+        // Rounded decimals stay decimals instead of becoming a fraction
+        assert_eq!(Quantity::from_f64(0.333), Some(q(333, 1000)));
+        assert_eq!(Quantity::from_f64(0.1234), Some(q(123, 1000)));
+        assert_eq!(Quantity::from_f64(f64::NAN), None);
+        assert_eq!(Quantity::from_f64(f64::INFINITY), None);
+
+        for quantity in [q(1, 3), q(5, 8), q(7, 6), q(-2, 3), q(1001, 4)] {
+            assert_eq!(Quantity::from_f64(quantity.to_f64()), Some(quantity));
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_stays_exact() {
+        let third = q(1, 3);
+        assert_eq!(
+            third
+                .checked_add(third)
+                .and_then(|sum| sum.checked_add(third)),
+            Some(Quantity::whole(1))
+        );
+        assert_eq!(third.checked_mul(Quantity::whole(2)), Some(q(2, 3)));
+        assert_eq!(q(3, 4).checked_mul(q(2, 1)), Some(q(3, 2)));
+        assert_eq!(q(1, 2).checked_mul(q(1, 2)), Some(q(1, 4)));
+        assert_eq!(q(1, 6).checked_add(q(1, 4)), Some(q(5, 12)));
+        assert_eq!(
+            third.checked_mul(Quantity::whole(3)).unwrap().to_string(),
+            "1"
+        );
+        assert_eq!(
+            Quantity::whole(i64::MAX).checked_add(Quantity::whole(1)),
+            None
+        );
+        assert_eq!(
+            Quantity::whole(i64::MAX).checked_mul(Quantity::whole(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prefers_fraction() {
+        assert!(q(1, 3).prefers_fraction());
+        assert!(q(1, 2).prefers_fraction());
+        assert!(q(5, 12).prefers_fraction());
+        assert!(!q(1, 5).prefers_fraction());
+        assert!(!q(1, 16).prefers_fraction());
+        assert!(!Quantity::whole(3).prefers_fraction());
+    }
+}
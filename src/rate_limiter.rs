@@ -0,0 +1,211 @@
+//! # Rate Limiter Module
+//!
+//! Per-user token buckets limiting how many OCR jobs each Telegram user can start,
+//! so a single user sending photos in a loop cannot monopolize the Tesseract
+//! instances. Every user gets their own bucket holding up to N jobs, refilled at
+//! N jobs per minute.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of OCR jobs a user may start per minute
+pub const DEFAULT_OCR_JOBS_PER_MINUTE: u32 = 5;
+
+/// Buckets unused for this long are full again and can be dropped
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(600);
+
+/// OCR jobs each user may start, shared by all chats
+pub static OCR_RATE_LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::from_config);
+
+/// Outcome of a rate limit check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    /// The job may run; one token was taken
+    Allowed,
+    /// The user is over the limit and may retry after the given delay
+    Limited { retry_after: Duration },
+}
+
+/// Token bucket of one user
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token-bucket rate limiter keyed by Telegram user id
+#[derive(Debug)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<i64, TokenBucket>>,
+    capacity: f64,
+    refill_per_second: f64,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `jobs_per_minute` jobs per user, at most that many at once
+    pub fn new(jobs_per_minute: u32) -> Self {
+        let capacity = f64::from(jobs_per_minute.max(1));
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            capacity,
+            refill_per_second: capacity / 60.0,
+        }
+    }
+
+    /// Create a limiter from `OCR_RATE_LIMIT_PER_MINUTE`
+    ///
+    /// Defaults to [`DEFAULT_OCR_JOBS_PER_MINUTE`].
+    pub fn from_config() -> Self {
+        Self::new(crate::config::current().ocr_limits.jobs_per_minute)
+    }
+
+    /// Take a token from the user's bucket if one is available
+    pub fn check(&self, user_id: i64) -> RateLimitDecision {
+        self.check_at(user_id, Instant::now())
+    }
+
+    fn check_at(&self, user_id: i64, now: Instant) -> RateLimitDecision {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            // Never block OCR because the limiter itself failed
+            return RateLimitDecision::Allowed;
+        };
+        buckets.retain(|_, bucket| now.duration_since(bucket.last_refill) < IDLE_BUCKET_TTL);
+
+        let bucket = buckets.entry(user_id).or_insert(TokenBucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            RateLimitDecision::Allowed
+        } else {
+            let missing = 1.0 - bucket.tokens;
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs_f64(missing / self.refill_per_second),
+            }
+        }
+    }
+
+    /// Give back the token taken for a job that did not run
+    ///
+    /// The bucket never holds more than its capacity.
+    pub fn refund(&self, user_id: i64) {
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return;
+        };
+        if let Some(bucket) = buckets.get_mut(&user_id) {
+            bucket.tokens = (bucket.tokens + 1.0).min(self.capacity);
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_OCR_JOBS_PER_MINUTE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_bucket_allows_burst_then_limits() {
+        let limiter = RateLimiter::new(3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at(1, now), RateLimitDecision::Allowed);
+        }
+        match limiter.check_at(1, now) {
+            RateLimitDecision::Limited { retry_after } => {
+                // One job every 20 seconds at 3 per minute
+                assert_eq!(retry_after.as_secs_f64().round(), 20.0);
+            }
+            RateLimitDecision::Allowed => panic!("fourth job should be limited"),
+        }
+    }
+
+    #[test]
+    fn test_refund_returns_one_token_up_to_capacity() {
+        let limiter = RateLimiter::new(2);
+        let now = Instant::now();
+        limiter.check_at(1, now);
+        limiter.check_at(1, now);
+
+        limiter.refund(1);
+        assert_eq!(limiter.check_at(1, now), RateLimitDecision::Allowed);
+        assert!(matches!(
+            limiter.check_at(1, now),
+            RateLimitDecision::Limited { .. }
+        ));
+
+        // Refunds never grow the bucket past its capacity
+        let fresh = RateLimiter::new(1);
+        fresh.check_at(2, now);
+        fresh.refund(2);
+        fresh.refund(2);
+        assert_eq!(fresh.check_at(2, now), RateLimitDecision::Allowed);
+        assert!(matches!(
+            fresh.check_at(2, now),
+            RateLimitDecision::Limited { .. }
+        ));
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let limiter = RateLimiter::new(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            limiter.check_at(1, now);
+        }
+
+        assert!(matches!(
+            limiter.check_at(1, now + Duration::from_secs(10)),
+            RateLimitDecision::Limited { .. }
+        ));
+        assert_eq!(
+            limiter.check_at(1, now + Duration::from_secs(21)),
+            RateLimitDecision::Allowed
+        );
+    }
+
+    #[test]
+    fn test_users_do_not_share_a_bucket_under_concurrency() {
+        let limiter = Arc::new(RateLimiter::new(5));
+        let handles: Vec<_> = [1_i64, 2]
+            .into_iter()
+            .flat_map(|user_id| {
+                (0..4).map({
+                    let limiter = Arc::clone(&limiter);
+                    move |_| {
+                        let limiter = Arc::clone(&limiter);
+                        thread::spawn(move || {
+                            let allowed = (0..5)
+                                .filter(|_| limiter.check(user_id) == RateLimitDecision::Allowed)
+                                .count();
+                            (user_id, allowed)
+                        })
+                    }
+                })
+            })
+            .collect();
+
+        let mut allowed_per_user: HashMap<i64, usize> = HashMap::new();
+        for handle in handles {
+            let (user_id, allowed) = handle.join().unwrap();
+            *allowed_per_user.entry(user_id).or_default() += allowed;
+        }
+
+        // 20 attempts each, but only each user's own 5 tokens are granted
+        assert_eq!(allowed_per_user[&1], 5);
+        assert_eq!(allowed_per_user[&2], 5);
+    }
+}
@@ -0,0 +1,368 @@
+//! # Recipe Export Module
+//!
+//! Versioned JSON format used by `/export` to give users a copy of every recipe
+//! they saved, and read back when such a file is sent to the bot to restore them.
+//! The document is written recipe by recipe, so exporting a large account only
+//! keeps one database batch in memory:
+//!
+//! ```json
+//! {
+//!   "format": "just-ingredients-recipes",
+//!   "version": 1,
+//!   "exported_at": "2026-10-17T08:00:00Z",
+//!   "recipes": [
+//!     {
+//!       "name": "Crêpes",
+//!       "created_at": "2026-10-01T18:30:00Z",
+//!       "content": "250 g farine\n3 oeufs",
+//!       "ingredients": [{ "name": "farine", "quantity": 250.0, "unit": "g" }]
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Ingredient, Recipe};
+
+/// Identifies export documents
+pub const EXPORT_FORMAT: &str = "just-ingredients-recipes";
+
+/// Current export format version, bumped on incompatible changes
+pub const EXPORT_VERSION: u32 = 1;
+
+/// An ingredient as exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedIngredient {
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+}
+
+/// A recipe as exported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedRecipe {
+    pub name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub content: String,
+    pub ingredients: Vec<ExportedIngredient>,
+}
+
+impl ExportedRecipe {
+    /// Build the exported form of a stored recipe and its ingredients
+    pub fn from_stored(recipe: &Recipe, ingredients: &[Ingredient]) -> Self {
+        Self {
+            name: recipe.recipe_name.clone(),
+            created_at: recipe.created_at,
+            content: recipe.content.clone(),
+            ingredients: ingredients
+                .iter()
+                .map(|ingredient| ExportedIngredient {
+                    name: ingredient.name.clone(),
+                    quantity: ingredient.quantity,
+                    unit: ingredient.unit.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A complete export document, as read back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeExport {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub recipes: Vec<ExportedRecipe>,
+}
+
+/// Streams an export document to a writer one recipe at a time
+#[derive(Debug)]
+pub struct ExportWriter<W: Write> {
+    writer: W,
+    recipe_count: usize,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Write the document header
+    pub fn begin(mut writer: W, exported_at: DateTime<Utc>) -> Result<Self> {
+        write!(
+            writer,
+            "{{\"format\":{},\"version\":{},\"exported_at\":{},\"recipes\":[",
+            serde_json::to_string(EXPORT_FORMAT)?,
+            EXPORT_VERSION,
+            serde_json::to_string(&exported_at)?
+        )
+        .context("Failed to write export header")?;
+        Ok(Self {
+            writer,
+            recipe_count: 0,
+        })
+    }
+
+    /// Append one recipe to the document
+    pub fn write_recipe(&mut self, recipe: &ExportedRecipe) -> Result<()> {
+        if self.recipe_count > 0 {
+            self.writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut self.writer, recipe).context("Failed to write recipe")?;
+        self.recipe_count += 1;
+        Ok(())
+    }
+
+    /// Close the document, returning the writer and the number of recipes written
+    pub fn finish(mut self) -> Result<(W, usize)> {
+        self.writer
+            .write_all(b"]}")
+            .context("Failed to write export footer")?;
+        self.writer.flush()?;
+        Ok((self.writer, self.recipe_count))
+    }
+}
+
+/// Largest export file accepted for import
+pub const MAX_IMPORT_FILE_BYTES: u32 = 5 * 1024 * 1024;
+
+/// Why an uploaded file cannot be imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportFileError {
+    /// Not JSON, or JSON that does not match the export schema
+    InvalidDocument(String),
+    /// JSON from something other than a recipe export
+    UnknownFormat(String),
+    /// Export written by a newer version of the bot
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for ImportFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportFileError::InvalidDocument(msg) => write!(f, "Invalid export document: {msg}"),
+            ImportFileError::UnknownFormat(format) => write!(f, "Unknown export format: {format}"),
+            ImportFileError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported export version: {version}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportFileError {}
+
+/// Parse and validate an uploaded export file
+pub fn parse_recipe_export(bytes: &[u8]) -> Result<RecipeExport, ImportFileError> {
+    let document: RecipeExport = serde_json::from_slice(bytes)
+        .map_err(|e| ImportFileError::InvalidDocument(e.to_string()))?;
+    if document.format != EXPORT_FORMAT {
+        return Err(ImportFileError::UnknownFormat(document.format));
+    }
+    if document.version == 0 || document.version > EXPORT_VERSION {
+        return Err(ImportFileError::UnsupportedVersion(document.version));
+    }
+    Ok(document)
+}
+
+/// Key identifying a recipe by name and ingredient set, for duplicate detection
+///
+/// Names are compared case-insensitively, ingredient order does not matter and
+/// quantities are compared at the database's three-decimal precision.
+pub fn recipe_identity_key(name: Option<&str>, ingredients: &[ExportedIngredient]) -> String {
+    let mut ingredient_keys: Vec<String> = ingredients
+        .iter()
+        .map(|ingredient| {
+            format!(
+                "{}|{}|{}",
+                ingredient.name.trim().to_lowercase(),
+                ingredient
+                    .quantity
+                    .map(|quantity| format!("{quantity:.3}"))
+                    .unwrap_or_default(),
+                ingredient
+                    .unit
+                    .as_deref()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            )
+        })
+        .collect();
+    ingredient_keys.sort();
+    format!(
+        "{}\n{}",
+        name.unwrap_or_default().trim().to_lowercase(),
+        ingredient_keys.join("\n")
+    )
+}
+
+/// File name of an export made on `date`
+pub fn export_file_name(date: NaiveDate) -> String {
+    format!("just-ingredients-export-{}.json", date.format("%Y-%m-%d"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{RecipeId, TelegramId, UserId};
+    use chrono::TimeZone;
+
+    fn recipe(id: i64, name: Option<&str>) -> Recipe {
+        Recipe {
+            id: RecipeId(id),
+            telegram_id: TelegramId(42),
+            content: format!("content {id}"),
+            recipe_name: name.map(str::to_string),
+            created_at: Utc.with_ymd_and_hms(2026, 10, 1, 18, 30, 0).unwrap(),
+            notes: None,
+        }
+    }
+
+    fn ingredient(name: &str, quantity: Option<f64>, unit: Option<&str>) -> Ingredient {
+        let now = Utc::now();
+        Ingredient {
+            id: 1,
+            user_id: UserId(1),
+            recipe_id: Some(RecipeId(1)),
+            name: name.to_string(),
+            quantity,
+            unit: unit.map(str::to_string),
+            created_at: now,
+            updated_at: now,
+            unit_dimension: None,
+            unit_system: None,
+        }
+    }
+
+    #[test]
+    fn test_streamed_document_round_trips() {
+        let exported_at = Utc.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap();
+        let recipes = [
+            ExportedRecipe::from_stored(
+                &recipe(1, Some("Crêpes \"maison\"")),
+                &[
+                    ingredient("farine", Some(250.0), Some("g")),
+                    ingredient("oeufs", Some(3.0), None),
+                ],
+            ),
+            ExportedRecipe::from_stored(&recipe(2, None), &[]),
+        ];
+
+        let mut writer = ExportWriter::begin(Vec::new(), exported_at).unwrap();
+        for recipe in &recipes {
+            writer.write_recipe(recipe).unwrap();
+        }
+        let (bytes, count) = writer.finish().unwrap();
+        assert_eq!(count, 2);
+
+        let document: RecipeExport = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            document,
+            RecipeExport {
+                format: EXPORT_FORMAT.to_string(),
+                version: EXPORT_VERSION,
+                exported_at,
+                recipes: recipes.to_vec(),
+            }
+        );
+        assert_eq!(
+            document.recipes[0].ingredients[0].unit.as_deref(),
+            Some("g")
+        );
+    }
+
+    #[test]
+    fn test_empty_export_is_valid_json() {
+        let exported_at = Utc.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap();
+        let (bytes, count) = ExportWriter::begin(Vec::new(), exported_at)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(count, 0);
+        let document: RecipeExport = serde_json::from_slice(&bytes).unwrap();
+        assert!(document.recipes.is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_files() {
+        assert!(matches!(
+            parse_recipe_export(b"not json"),
+            Err(ImportFileError::InvalidDocument(_))
+        ));
+        assert!(matches!(
+            parse_recipe_export(br#"{"recipes": "nope"}"#),
+            Err(ImportFileError::InvalidDocument(_))
+        ));
+        assert_eq!(
+            parse_recipe_export(
+                br#"{"format":"other","version":1,"exported_at":"2026-10-17T08:00:00Z","recipes":[]}"#
+            ),
+            Err(ImportFileError::UnknownFormat("other".to_string()))
+        );
+        assert_eq!(
+            parse_recipe_export(
+                br#"{"format":"just-ingredients-recipes","version":2,"exported_at":"2026-10-17T08:00:00Z","recipes":[]}"#
+            ),
+            Err(ImportFileError::UnsupportedVersion(2))
+        );
+    }
+
+    #[test]
+    fn test_parse_accepts_exported_document() {
+        let exported_at = Utc.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap();
+        let mut writer = ExportWriter::begin(Vec::new(), exported_at).unwrap();
+        writer
+            .write_recipe(&ExportedRecipe::from_stored(
+                &recipe(1, Some("Crêpes")),
+                &[ingredient("farine", Some(250.0), Some("g"))],
+            ))
+            .unwrap();
+        let (bytes, _) = writer.finish().unwrap();
+
+        let document = parse_recipe_export(&bytes).unwrap();
+        assert_eq!(document.recipes.len(), 1);
+        assert_eq!(document.recipes[0].name.as_deref(), Some("Crêpes"));
+    }
+
+    #[test]
+    fn test_recipe_identity_key() {
+        let flour = ExportedIngredient {
+            name: "Farine".to_string(),
+            quantity: Some(250.0),
+            unit: Some("g".to_string()),
+        };
+        let eggs = ExportedIngredient {
+            name: "oeufs".to_string(),
+            quantity: Some(3.0),
+            unit: None,
+        };
+
+        // Order and case do not matter
+        assert_eq!(
+            recipe_identity_key(Some("Crêpes"), &[flour.clone(), eggs.clone()]),
+            recipe_identity_key(Some("crêpes "), &[eggs.clone(), flour.clone()])
+        );
+        // A different quantity or name is a different recipe
+        let more_flour = ExportedIngredient {
+            quantity: Some(300.0),
+            ..flour.clone()
+        };
+        assert_ne!(
+            recipe_identity_key(Some("Crêpes"), &[flour.clone(), eggs.clone()]),
+            recipe_identity_key(Some("Crêpes"), &[more_flour, eggs.clone()])
+        );
+        assert_ne!(
+            recipe_identity_key(Some("Crêpes"), std::slice::from_ref(&flour)),
+            recipe_identity_key(Some("Galettes"), &[flour])
+        );
+    }
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(
+            export_file_name(NaiveDate::from_ymd_opt(2026, 10, 17).unwrap()),
+            "just-ingredients-export-2026-10-17.json"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! # Recipe Scaling Module
+//!
+//! Multiplies ingredient quantities by a factor to cook a half, double or triple
+//! batch. Quantities are parsed from their text form, including fractions ("1/2",
+//! "1 1/2") and Unicode fraction characters ("½", "2¼") produced by OCR. Scaled
+//! values are rounded to what a cook would measure: common fractions below 10,
+//! one decimal above.
+
+use crate::quantity::Quantity;
+use crate::text_processing::MeasurementMatch;
+
+/// Largest accepted scaling factor
+pub const MAX_SCALE_FACTOR: f64 = 100.0;
+
+/// Fractions scaled quantities are snapped to, as (numerator, denominator)
+const COMMON_FRACTIONS: [(u32, u32); 9] = [
+    (1, 8),
+    (1, 4),
+    (1, 3),
+    (3, 8),
+    (1, 2),
+    (5, 8),
+    (2, 3),
+    (3, 4),
+    (7, 8),
+];
+
+/// Distance within which a value is considered equal to a common fraction
+const FRACTION_TOLERANCE: f64 = 0.02;
+
+/// Parse a quantity written as a number, a fraction or a mixed number
+///
+/// Accepts "2", "1.5", "0,5", "1/2", "1 1/2", "½" and "2½". Returns `None` for
+/// anything else, such as ranges ("2-3") or words ("some").
+///
+/// # Examples
+///
+/// ```
+/// use just_ingredients::recipe_scaling::parse_fractional_quantity;
+///
+/// assert_eq!(parse_fractional_quantity("1 1/2"), Some(1.5));
+/// assert_eq!(parse_fractional_quantity("2½"), Some(2.5));
+/// assert_eq!(parse_fractional_quantity("a pinch"), None);
+/// ```
+pub fn parse_fractional_quantity(text: &str) -> Option<f64> {
+    Quantity::parse(text).map(Quantity::to_f64)
+}
+
+/// Parse a scaling factor typed by the user ("2", "0.5", "x3", "×1.5", "1/2")
+///
+/// Factors must be positive and at most `MAX_SCALE_FACTOR`.
+pub fn parse_scale_factor(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let text = text
+        .strip_prefix(['x', 'X', '×', '*'])
+        .or_else(|| text.strip_suffix(['x', 'X', '×']))
+        .unwrap_or(text);
+    let factor = parse_fractional_quantity(text)?;
+    (factor > 0.0 && factor <= MAX_SCALE_FACTOR).then_some(factor)
+}
+
+/// Format a scaling factor for recipe names and callback data ("2", "0.5")
+pub fn format_scale_factor(factor: f64) -> String {
+    crate::unit_conversion::format_quantity(factor)
+}
+
+/// Format a scaled quantity the way it would be measured
+///
+/// Values below 10 snap to a common fraction when close to one ("2/3", "1 1/2"),
+/// otherwise they keep two decimals; larger values keep one decimal.
+pub fn format_scaled_quantity(value: f64) -> String {
+    if value >= 10.0 {
+        return crate::unit_conversion::format_quantity((value * 10.0).round() / 10.0);
+    }
+
+    let whole = value.floor();
+    let fraction = value - whole;
+    if fraction < FRACTION_TOLERANCE && whole > 0.0 {
+        return format!("{whole}");
+    }
+    if fraction > 1.0 - FRACTION_TOLERANCE {
+        return format!("{}", whole + 1.0);
+    }
+
+    let common = COMMON_FRACTIONS.iter().find(|(numerator, denominator)| {
+        (fraction - f64::from(*numerator) / f64::from(*denominator)).abs() < FRACTION_TOLERANCE
+    });
+    match common {
+        Some((numerator, denominator)) if whole == 0.0 => format!("{numerator}/{denominator}"),
+        Some((numerator, denominator)) => format!("{whole} {numerator}/{denominator}"),
+        None => crate::unit_conversion::format_quantity(value),
+    }
+}
+
+/// Scale a quantity and format the result the way it would be measured
+///
+/// Fractions are multiplied exactly, so 1/3 × 2 is 2/3 rather than a rounded
+/// float; results that are not a cooking fraction fall back to
+/// [`format_scaled_quantity`].
+fn scale_quantity(quantity: Quantity, factor: f64) -> String {
+    let exact = Quantity::from_f64(factor).and_then(|factor| quantity.checked_mul(factor));
+    match exact {
+        Some(scaled) if scaled.to_f64() < 10.0 && scaled.prefers_fraction() => scaled.to_string(),
+        _ => format_scaled_quantity(quantity.to_f64() * factor),
+    }
+}
+
+/// Ingredients with scaled quantities
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaledIngredients {
+    /// Every ingredient, scaled when its quantity is numeric
+    pub ingredients: Vec<MeasurementMatch>,
+    /// Scaled value of each ingredient, `None` when it was left alone
+    pub values: Vec<Option<f64>>,
+}
+
+impl ScaledIngredients {
+    /// Number of ingredients whose quantity could not be scaled
+    pub fn unscaled_count(&self) -> usize {
+        self.values.iter().filter(|value| value.is_none()).count()
+    }
+}
+
+/// Multiply every numeric quantity by `factor`
+///
+/// Non-numeric quantities ("a pinch", "2-3") are kept as written and flagged with
+/// `requires_quantity_confirmation`, so ingredient lists mark them for a footnote.
+pub fn scale_ingredients(ingredients: &[MeasurementMatch], factor: f64) -> ScaledIngredients {
+    let mut scaled = Vec::with_capacity(ingredients.len());
+    let mut values = Vec::with_capacity(ingredients.len());
+    for ingredient in ingredients {
+        let mut ingredient = ingredient.clone();
+        match Quantity::parse(&ingredient.quantity) {
+            Some(quantity) => {
+                let value = quantity.to_f64() * factor;
+                ingredient.quantity = scale_quantity(quantity, factor);
+                // The upper bound of a range scales with it
+                ingredient.quantity_max = ingredient
+                    .quantity_max
+                    .as_deref()
+                    .and_then(Quantity::parse)
+                    .map(|max| scale_quantity(max, factor));
+                ingredient.requires_quantity_confirmation = false;
+                values.push(Some(value));
+            }
+            None => {
+                ingredient.requires_quantity_confirmation = true;
+                values.push(None);
+            }
+        }
+        scaled.push(ingredient);
+    }
+    ScaledIngredients {
+        ingredients: scaled,
+        values,
+    }
+}
+
+/// Name of a scaled copy of a recipe, e.g. "Crêpes (x2)"
+pub fn scaled_recipe_name(name: &str, factor: f64) -> String {
+    format!("{} (x{})", name.trim(), format_scale_factor(factor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(quantity: &str, unit: Option<&str>, name: &str) -> MeasurementMatch {
+        MeasurementMatch {
+            quantity: quantity.to_string(),
+            measurement: unit.map(str::to_string),
+            ingredient_name: name.to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: 0,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_fractional_quantity() {
+        assert_eq!(parse_fractional_quantity("2"), Some(2.0));
+        assert_eq!(parse_fractional_quantity("1.5"), Some(1.5));
+        assert_eq!(parse_fractional_quantity("0,5"), Some(0.5));
+        assert_eq!(parse_fractional_quantity("1/2"), Some(0.5));
+        assert_eq!(parse_fractional_quantity("1 1/2"), Some(1.5));
+        assert_eq!(parse_fractional_quantity("½"), Some(0.5));
+        assert_eq!(parse_fractional_quantity("2½"), Some(2.5));
+        assert_eq!(parse_fractional_quantity("2 ¼"), Some(2.25));
+        assert_eq!(parse_fractional_quantity("⅓"), Some(1.0 / 3.0));
+        assert_eq!(parse_fractional_quantity("1/0"), None);
+        assert_eq!(parse_fractional_quantity("2-3"), None);
+        assert_eq!(parse_fractional_quantity("some"), None);
+        assert_eq!(parse_fractional_quantity("1 2"), None);
+        assert_eq!(parse_fractional_quantity(""), None);
+    }
+
+    #[test]
+    fn test_parse_scale_factor() {
+        assert_eq!(parse_scale_factor("2"), Some(2.0));
+        assert_eq!(parse_scale_factor("0.5"), Some(0.5));
+        assert_eq!(parse_scale_factor("x3"), Some(3.0));
+        assert_eq!(parse_scale_factor("×1,5"), Some(1.5));
+        assert_eq!(parse_scale_factor("2x"), Some(2.0));
+        assert_eq!(parse_scale_factor("1/2"), Some(0.5));
+        assert_eq!(parse_scale_factor("0"), None);
+        assert_eq!(parse_scale_factor("-2"), None);
+        assert_eq!(parse_scale_factor("1000"), None);
+        assert_eq!(parse_scale_factor("double"), None);
+    }
+
+    #[test]
+    fn test_format_scaled_quantity_rounds_awkward_results() {
+        // 1/3 × 2 is shown as a fraction, not 0.6666666666666666
+        assert_eq!(format_scaled_quantity(1.0 / 3.0 * 2.0), "2/3");
+        assert_eq!(format_scaled_quantity(1.0 / 3.0 * 3.0), "1");
+        assert_eq!(format_scaled_quantity(0.75 * 2.0), "1 1/2");
+        assert_eq!(format_scaled_quantity(2.0 / 3.0 * 4.0), "2 2/3");
+        assert_eq!(format_scaled_quantity(0.5 * 0.5), "1/4");
+        assert_eq!(format_scaled_quantity(0.2 * 0.5), "0.1");
+        assert_eq!(format_scaled_quantity(250.0 * 1.5), "375");
+        assert_eq!(format_scaled_quantity(100.0 / 3.0), "33.3");
+        assert_eq!(format_scaled_quantity(3.0), "3");
+        assert_eq!(format_scaled_quantity(0.01), "0.01");
+    }
+
+    #[test]
+    fn test_scale_ingredients() {
+        let ingredients = [
+            ingredient("1/2", Some("cup"), "sugar"),
+            ingredient("½", Some("tsp"), "salt"),
+            ingredient("2-3", None, "eggs"),
+            ingredient("250", Some("g"), "flour"),
+        ];
+        let scaled = scale_ingredients(&ingredients, 3.0);
+
+        let quantities: Vec<&str> = scaled
+            .ingredients
+            .iter()
+            .map(|i| i.quantity.as_str())
+            .collect();
+        assert_eq!(quantities, vec!["1 1/2", "1 1/2", "2-3", "750"]);
+        assert_eq!(scaled.values, vec![Some(1.5), Some(1.5), None, Some(750.0)]);
+        assert_eq!(scaled.unscaled_count(), 1);
+        assert!(scaled.ingredients[2].requires_quantity_confirmation);
+        assert!(!scaled.ingredients[0].requires_quantity_confirmation);
+    }
+
+    #[test]
+    fn test_scale_quantity_multiplies_fractions_exactly() {
+        let third = Quantity::new(1, 3).unwrap();
+        assert_eq!(scale_quantity(third, 2.0), "2/3");
+        assert_eq!(scale_quantity(third, 0.5), "1/6");
+        assert_eq!(scale_quantity(third, 3.0), "1");
+        assert_eq!(scale_quantity(Quantity::new(5, 8).unwrap(), 2.0), "1 1/4");
+        // Large or decimal results keep the measured rounding
+        assert_eq!(scale_quantity(Quantity::whole(100), 1.0 / 3.0), "33.3");
+        assert_eq!(scale_quantity(Quantity::new(1, 5).unwrap(), 0.5), "0.1");
+    }
+
+    #[test]
+    fn test_scaled_recipe_name() {
+        assert_eq!(scaled_recipe_name("Crêpes", 2.0), "Crêpes (x2)");
+        assert_eq!(scaled_recipe_name("Crêpes ", 0.5), "Crêpes (x0.5)");
+    }
+}
@@ -0,0 +1,233 @@
+//! # Repository Module
+//!
+//! Typed access to stored recipes, ingredients and users for the handlers, so
+//! their flows can be tested without a live Postgres.
+//!
+//! [`PgRepository`] is the implementation used by the bot: it delegates to the
+//! query functions of [`crate::db`], through their `_cached` variants when it
+//! holds the cache manager. Handler tests pass in-memory implementations of the
+//! same traits instead.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::FixedOffset;
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::cache::SharedCacheManager;
+use crate::db::{Ingredient, Recipe, RecipeId, RecipePhoto, RecipeStatistics, TelegramId, User};
+use crate::text_processing::MeasurementMatch;
+
+/// Recipe confirmed at the end of a review, ready to be saved
+#[derive(Debug, Clone, Copy)]
+pub struct NewRecipe<'a> {
+    pub telegram_id: TelegramId,
+    pub recipe_name: &'a str,
+    /// OCR or message text the ingredients were read from
+    pub extracted_text: &'a str,
+    pub ingredients: &'a [MeasurementMatch],
+    pub language_code: Option<&'a str>,
+    /// Key making a retried save map to the recipe of the first attempt
+    pub idempotency_key: Option<&'a str>,
+    /// Original photo kept with the recipe
+    pub photo: Option<&'a RecipePhoto>,
+}
+
+/// Stored recipes of the users
+#[async_trait]
+pub trait RecipeRepository: Send + Sync {
+    /// Recipe with its name, `None` when it does not exist or is in the trash
+    async fn read_recipe_with_name(&self, recipe_id: RecipeId) -> Result<Option<Recipe>>;
+
+    /// Recipes of a user with exactly this name, most recent first
+    async fn get_recipes_by_name(
+        &self,
+        telegram_id: TelegramId,
+        recipe_name: &str,
+    ) -> Result<Vec<Recipe>>;
+
+    /// Names of the user's latest recipes, most recent first, without duplicates
+    async fn get_recent_recipe_names(
+        &self,
+        telegram_id: TelegramId,
+        limit: i64,
+    ) -> Result<Vec<String>>;
+
+    /// Save a recipe and its ingredients together, creating the user if needed
+    async fn save_recipe(&self, recipe: NewRecipe<'_>) -> Result<()>;
+
+    /// Move a recipe of the user to the trash, `false` when they have no such recipe
+    async fn trash_recipe(&self, telegram_id: TelegramId, recipe_id: RecipeId) -> Result<bool>;
+}
+
+/// Stored ingredients of the recipes
+#[async_trait]
+pub trait IngredientRepository: Send + Sync {
+    /// Ingredients of a recipe, in their display order
+    async fn get_recipe_ingredients(&self, recipe_id: RecipeId) -> Result<Vec<Ingredient>>;
+
+    /// Ingredients of several recipes, with an entry for every requested id
+    async fn get_ingredients_for_recipes(
+        &self,
+        recipe_ids: &[RecipeId],
+    ) -> Result<HashMap<RecipeId, Vec<Ingredient>>>;
+}
+
+/// Users of the bot
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    /// User with this Telegram id, created on first use
+    async fn get_or_create_user(
+        &self,
+        telegram_id: TelegramId,
+        language_code: Option<&str>,
+    ) -> Result<User>;
+
+    /// Statistics over the user's whole recipe collection, with days starting at
+    /// midnight in `timezone`
+    async fn get_user_recipe_statistics(
+        &self,
+        telegram_id: TelegramId,
+        timezone: FixedOffset,
+    ) -> Result<RecipeStatistics>;
+}
+
+/// All the repositories a handler may need, behind one trait object
+pub trait Repository: RecipeRepository + IngredientRepository + UserRepository {}
+
+impl<T: RecipeRepository + IngredientRepository + UserRepository> Repository for T {}
+
+impl std::fmt::Debug for dyn Repository + '_ {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Repository")
+    }
+}
+
+/// Repositories backed by Postgres, with the cache manager when the bot has one
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: Arc<PgPool>,
+    cache: Option<SharedCacheManager>,
+}
+
+impl PgRepository {
+    /// Create repositories reading `pool`, through `cache` when given
+    pub fn new(pool: Arc<PgPool>, cache: Option<SharedCacheManager>) -> Self {
+        Self { pool, cache }
+    }
+}
+
+#[async_trait]
+impl RecipeRepository for PgRepository {
+    async fn read_recipe_with_name(&self, recipe_id: RecipeId) -> Result<Option<Recipe>> {
+        match &self.cache {
+            Some(cache) => {
+                crate::db::read_recipe_with_name_cached(&self.pool, recipe_id, cache).await
+            }
+            None => crate::db::read_recipe_with_name(&self.pool, recipe_id).await,
+        }
+    }
+
+    async fn get_recipes_by_name(
+        &self,
+        telegram_id: TelegramId,
+        recipe_name: &str,
+    ) -> Result<Vec<Recipe>> {
+        match &self.cache {
+            Some(cache) => {
+                crate::db::get_recipes_by_name_cached(&self.pool, telegram_id, recipe_name, cache)
+                    .await
+            }
+            None => crate::db::get_recipes_by_name(&self.pool, telegram_id, recipe_name).await,
+        }
+    }
+
+    async fn get_recent_recipe_names(
+        &self,
+        telegram_id: TelegramId,
+        limit: i64,
+    ) -> Result<Vec<String>> {
+        crate::db::get_recent_recipe_names(&self.pool, telegram_id, limit).await
+    }
+
+    async fn save_recipe(&self, recipe: NewRecipe<'_>) -> Result<()> {
+        crate::bot::save_ingredients_to_database(
+            &self.pool,
+            recipe.telegram_id.0,
+            recipe.extracted_text,
+            recipe.ingredients,
+            recipe.recipe_name,
+            recipe.language_code,
+            recipe.idempotency_key,
+            recipe.photo,
+            self.cache.as_ref(),
+        )
+        .await
+    }
+
+    async fn trash_recipe(&self, telegram_id: TelegramId, recipe_id: RecipeId) -> Result<bool> {
+        match &self.cache {
+            Some(cache) => {
+                crate::db::trash_recipe_cached(&self.pool, telegram_id, recipe_id, cache).await
+            }
+            None => crate::db::trash_recipe(&self.pool, telegram_id, recipe_id).await,
+        }
+    }
+}
+
+#[async_trait]
+impl IngredientRepository for PgRepository {
+    async fn get_recipe_ingredients(&self, recipe_id: RecipeId) -> Result<Vec<Ingredient>> {
+        match &self.cache {
+            Some(cache) => {
+                crate::db::get_recipe_ingredients_cached(&self.pool, recipe_id, cache).await
+            }
+            None => crate::db::get_recipe_ingredients(&self.pool, recipe_id).await,
+        }
+    }
+
+    async fn get_ingredients_for_recipes(
+        &self,
+        recipe_ids: &[RecipeId],
+    ) -> Result<HashMap<RecipeId, Vec<Ingredient>>> {
+        match &self.cache {
+            Some(cache) => {
+                crate::db::get_ingredients_for_recipes_cached(&self.pool, recipe_ids, cache).await
+            }
+            None => crate::db::get_ingredients_for_recipes(&self.pool, recipe_ids).await,
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgRepository {
+    async fn get_or_create_user(
+        &self,
+        telegram_id: TelegramId,
+        language_code: Option<&str>,
+    ) -> Result<User> {
+        match &self.cache {
+            Some(cache) => {
+                // Never hold the cache lock across the query
+                let backend = cache.lock().backend();
+                crate::db::get_or_create_user_cached(
+                    &self.pool,
+                    telegram_id,
+                    language_code,
+                    backend.as_ref(),
+                )
+                .await
+            }
+            None => crate::db::get_or_create_user(&self.pool, telegram_id, language_code).await,
+        }
+    }
+
+    async fn get_user_recipe_statistics(
+        &self,
+        telegram_id: TelegramId,
+        timezone: FixedOffset,
+    ) -> Result<RecipeStatistics> {
+        crate::db::get_user_recipe_statistics(&self.pool, telegram_id, timezone).await
+    }
+}
@@ -0,0 +1,477 @@
+//! # Resource Limits Module
+//!
+//! Derives OCR memory and concurrency budgets from the container's cgroup memory
+//! limit, so a single oversized image cannot push Tesseract past the limit and get
+//! the whole process OOM-killed.
+//!
+//! ## Budget formula
+//!
+//! ```text
+//! usable            = max(memory_limit - RESERVED_MEMORY, MIN_REQUEST_MEMORY)
+//! ocr_concurrency   = clamp(usable / TARGET_REQUEST_MEMORY, 1, min(cpus, MAX_OCR_CONCURRENCY))
+//! request_memory    = usable / ocr_concurrency
+//! pixel_budget      = (request_memory - TESSERACT_BASELINE) / (4 + WORKING_BYTES_PER_PIXEL)
+//! ```
+//!
+//! When no cgroup limit is set, `DEFAULT_MEMORY_LIMIT` is assumed. The pixel
+//! budget is expressed for 8-bit RGBA images; the per-request check uses the
+//! image's actual mode, so grayscale images may exceed it and 16-bit ones fall short.
+//!
+//! ## Image dimension limits
+//!
+//! Before OCR, [`prepare_image_for_ocr`] reads the image header and rejects images
+//! with more than `OcrConfig::max_pixels` pixels without decoding them. Other images
+//! are decoded off the runtime, under `DECODE_TIMEOUT`, and those wider or taller
+//! than `max_width` x `max_height` are downscaled in place to fit.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use image::{ImageDecoder, ImageReader};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::{info, warn};
+
+use crate::ocr_config::OcrConfig;
+use crate::ocr_errors::OcrError;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Memory kept for the runtime, database pool, caches and dialogue storage
+pub const RESERVED_MEMORY: u64 = 192 * MIB;
+/// Smallest per-request budget, used when the limit barely covers the reserve
+pub const MIN_REQUEST_MEMORY: u64 = 64 * MIB;
+/// Memory a single OCR request is sized for when deriving concurrency
+pub const TARGET_REQUEST_MEMORY: u64 = 384 * MIB;
+/// Upper bound on concurrent OCR requests regardless of available memory
+pub const MAX_OCR_CONCURRENCY: usize = 8;
+/// Limit assumed when the process runs without a cgroup memory limit
+pub const DEFAULT_MEMORY_LIMIT: u64 = 1024 * MIB;
+/// Fixed Tesseract cost per request (engine, language model, dictionaries)
+pub const TESSERACT_BASELINE: u64 = 48 * MIB;
+/// Working memory per pixel on top of the decoded image: grayscale and
+/// preprocessed copies plus Leptonica's 32-bit Pix and Tesseract's binarised buffers
+pub const WORKING_BYTES_PER_PIXEL: u64 = 16;
+
+/// cgroup v2 and v1 memory limit files, in lookup order
+const CGROUP_MEMORY_LIMIT_PATHS: [&str; 2] = [
+    "/sys/fs/cgroup/memory.max",
+    "/sys/fs/cgroup/memory/memory.limit_in_bytes",
+];
+
+/// cgroup v1 reports "no limit" as a huge page-aligned value instead of "max"
+const CGROUP_V1_UNLIMITED_THRESHOLD: u64 = 1 << 60;
+
+/// Time an image gets to be decoded, checked and downscaled before OCR
+pub const DECODE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Largest decoded size per pixel (16-bit RGBA), bounding decoder allocations
+const MAX_DECODED_BYTES_PER_PIXEL: u64 = 8;
+
+/// OCR budgets derived from the memory limit and CPU count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceBudget {
+    /// Detected cgroup memory limit, `None` when unlimited or unreadable
+    pub memory_limit_bytes: Option<u64>,
+    /// Maximum number of OCR requests processed at once
+    pub ocr_concurrency: usize,
+    /// Peak memory a single OCR request may use
+    pub request_memory_bytes: u64,
+    /// Largest 8-bit RGBA image (in pixels) that fits in `request_memory_bytes`
+    pub pixel_budget: u64,
+}
+
+impl ResourceBudget {
+    /// Derive budgets from a memory limit and the number of available CPUs
+    pub fn derive(memory_limit_bytes: Option<u64>, cpus: usize) -> Self {
+        let limit = memory_limit_bytes.unwrap_or(DEFAULT_MEMORY_LIMIT);
+        let usable = limit
+            .saturating_sub(RESERVED_MEMORY)
+            .max(MIN_REQUEST_MEMORY);
+
+        let max_concurrency = cpus.clamp(1, MAX_OCR_CONCURRENCY);
+        let ocr_concurrency = usize::try_from(usable / TARGET_REQUEST_MEMORY)
+            .map_or(max_concurrency, |n| n.clamp(1, max_concurrency));
+
+        let request_memory_bytes = usable / ocr_concurrency as u64;
+        let pixel_budget =
+            request_memory_bytes.saturating_sub(TESSERACT_BASELINE) / (4 + WORKING_BYTES_PER_PIXEL);
+
+        Self {
+            memory_limit_bytes,
+            ocr_concurrency,
+            request_memory_bytes,
+            pixel_budget,
+        }
+    }
+
+    /// Detect the cgroup memory limit and CPU count of the running process
+    pub fn detect() -> Self {
+        let cpus = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let mut budget = Self::derive(read_cgroup_memory_limit(), cpus);
+
+        if let Some(concurrency) = crate::config::current().ocr_limits.max_concurrency {
+            budget.ocr_concurrency = concurrency;
+        }
+
+        budget
+    }
+
+    /// Reject an image whose estimated peak RSS exceeds the per-request budget
+    pub fn check_image(
+        &self,
+        width: u32,
+        height: u32,
+        color_type: image::ColorType,
+    ) -> Result<(), OcrError> {
+        let estimate = estimate_peak_rss(width, height, color_type);
+        if estimate > self.request_memory_bytes {
+            return Err(OcrError::ImageTooLarge(format!(
+                "{}x{} {:?} image needs ~{}MB, per-request budget is {}MB",
+                width,
+                height,
+                color_type,
+                estimate / MIB,
+                self.request_memory_bytes / MIB
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Budgets for this process, detected once on first use
+pub static RESOURCE_BUDGET: LazyLock<ResourceBudget> = LazyLock::new(ResourceBudget::detect);
+
+/// Permits bounding concurrent OCR requests to the derived concurrency
+static OCR_PERMITS: LazyLock<Semaphore> =
+    LazyLock::new(|| Semaphore::new(RESOURCE_BUDGET.ocr_concurrency));
+
+/// Wait for an OCR slot; the slot is released when the permit is dropped
+pub async fn acquire_ocr_permit() -> SemaphorePermit<'static> {
+    OCR_PERMITS
+        .acquire()
+        .await
+        .expect("OCR semaphore is never closed")
+}
+
+/// Parse the contents of a cgroup v1 or v2 memory limit file
+pub fn parse_cgroup_memory_limit(contents: &str) -> Option<u64> {
+    let value = contents.trim();
+    if value == "max" {
+        return None;
+    }
+    value
+        .parse::<u64>()
+        .ok()
+        .filter(|&limit| limit > 0 && limit < CGROUP_V1_UNLIMITED_THRESHOLD)
+}
+
+/// Read the cgroup memory limit, trying cgroup v2 before v1
+pub fn read_cgroup_memory_limit() -> Option<u64> {
+    CGROUP_MEMORY_LIMIT_PATHS.iter().find_map(|path| {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| parse_cgroup_memory_limit(&contents))
+    })
+}
+
+/// Estimate the peak RSS of running OCR on an image of the given size and mode
+pub fn estimate_peak_rss(width: u32, height: u32, color_type: image::ColorType) -> u64 {
+    let pixels = u64::from(width) * u64::from(height);
+    let decoded_bytes_per_pixel = u64::from(color_type.bytes_per_pixel());
+    TESSERACT_BASELINE + pixels * (decoded_bytes_per_pixel + WORKING_BYTES_PER_PIXEL)
+}
+
+/// Check an image file against the per-request budget using only its header
+///
+/// Images whose header cannot be read are let through; decoding reports the error later.
+pub fn check_image_file(image_path: &str, budget: &ResourceBudget) -> Result<(), OcrError> {
+    let decoder = match ImageReader::open(image_path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(image::ImageError::IoError)
+        .and_then(|reader| reader.into_decoder())
+    {
+        Ok(decoder) => decoder,
+        Err(e) => {
+            warn!(error = %e, "Could not read image header for memory budget check");
+            return Ok(());
+        }
+    };
+    let (width, height) = decoder.dimensions();
+    budget.check_image(width, height, decoder.color_type())
+}
+
+/// Reject a file whose size, as reported by Telegram, exceeds `max_file_size`
+///
+/// Checked before downloading, so oversized files are never fetched.
+pub fn check_download_size(file_size: u64, config: &OcrConfig) -> Result<(), OcrError> {
+    if file_size > config.max_file_size {
+        return Err(OcrError::FileTooLarge(format!(
+            "File is {} bytes, maximum is {} bytes",
+            file_size, config.max_file_size
+        )));
+    }
+    Ok(())
+}
+
+/// How an image of a given size is handled before OCR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DimensionCheck {
+    /// Within every limit, read as is
+    Within,
+    /// Over `max_width` or `max_height`, read once downscaled to this size
+    Downscale { width: u32, height: u32 },
+}
+
+/// Check image dimensions against the limits of `config`
+///
+/// Images with more than `max_pixels` pixels are rejected; larger ones than
+/// `max_width` x `max_height` are downscaled to fit, keeping their aspect ratio.
+pub fn check_image_dimensions(
+    width: u32,
+    height: u32,
+    config: &OcrConfig,
+) -> Result<DimensionCheck, OcrError> {
+    let pixels = u64::from(width) * u64::from(height);
+    if pixels > config.max_pixels {
+        return Err(OcrError::ImageTooLarge(format!(
+            "{}x{} image has {} pixels, maximum is {}",
+            width, height, pixels, config.max_pixels
+        )));
+    }
+    if width <= config.max_width && height <= config.max_height {
+        return Ok(DimensionCheck::Within);
+    }
+
+    let scale = (f64::from(config.max_width) / f64::from(width))
+        .min(f64::from(config.max_height) / f64::from(height));
+    let fit = |side: u32, max: u32| ((f64::from(side) * scale).floor() as u32).clamp(1, max);
+    Ok(DimensionCheck::Downscale {
+        width: fit(width, config.max_width),
+        height: fit(height, config.max_height),
+    })
+}
+
+/// Decode an image file within the limits of `config`, downscaling it in place if needed
+///
+/// Blocking; see [`prepare_image_for_ocr`]. Returns the dimensions of the image
+/// OCR will read.
+pub fn prepare_image_file(image_path: &str, config: &OcrConfig) -> Result<(u32, u32), OcrError> {
+    let reader = ImageReader::open(image_path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| OcrError::Validation(format!("Cannot read image file: {}", e)))?;
+    let format = reader
+        .format()
+        .ok_or_else(|| OcrError::Validation("File is not a recognised image format".to_string()))?;
+
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| OcrError::ImageLoad(format!("Invalid {:?} image: {}", format, e)))?;
+    let (width, height) = decoder.dimensions();
+    let check = check_image_dimensions(width, height, config)?;
+
+    // Bound what the decoder may allocate, in case the header understates the image
+    let mut limits = image::Limits::default();
+    limits.max_alloc = Some(
+        config
+            .max_pixels
+            .saturating_mul(MAX_DECODED_BYTES_PER_PIXEL),
+    );
+    decoder
+        .set_limits(limits)
+        .map_err(|e| decode_error(format, e))?;
+    let img = image::DynamicImage::from_decoder(decoder).map_err(|e| decode_error(format, e))?;
+
+    match check {
+        DimensionCheck::Within => Ok((width, height)),
+        DimensionCheck::Downscale {
+            width: new_width,
+            height: new_height,
+        } => {
+            info!(
+                width,
+                height, new_width, new_height, "Downscaling image over the dimension limits"
+            );
+            let resized =
+                img.resize_exact(new_width, new_height, image::imageops::FilterType::Triangle);
+            // PNG stays lossless; anything else becomes a JPEG, which every format limit allows
+            let saved = if format == image::ImageFormat::Png {
+                resized.save_with_format(image_path, image::ImageFormat::Png)
+            } else {
+                image::DynamicImage::ImageRgb8(resized.to_rgb8())
+                    .save_with_format(image_path, image::ImageFormat::Jpeg)
+            };
+            saved
+                .map_err(|e| OcrError::ImageLoad(format!("Cannot save downscaled image: {}", e)))?;
+            Ok((new_width, new_height))
+        }
+    }
+}
+
+/// Map a decoding failure to the error shown to the user
+fn decode_error(format: image::ImageFormat, error: image::ImageError) -> OcrError {
+    match error {
+        image::ImageError::Limits(e) => {
+            OcrError::ImageTooLarge(format!("{:?} image exceeds decoding limits: {}", format, e))
+        }
+        e => OcrError::ImageLoad(format!("Cannot decode {:?} image: {}", format, e)),
+    }
+}
+
+/// Check, decode and if needed downscale an image before OCR, off the async runtime
+///
+/// Fails with `OcrError::ImageTooLarge` over `max_pixels`, `OcrError::Validation`
+/// for unreadable or unrecognised files, `OcrError::ImageLoad` for malformed images
+/// and `OcrError::Timeout` when decoding takes longer than `DECODE_TIMEOUT`.
+pub async fn prepare_image_for_ocr(
+    image_path: &str,
+    config: &OcrConfig,
+) -> Result<(u32, u32), OcrError> {
+    let path = image_path.to_string();
+    let config = config.clone();
+    let task = tokio::task::spawn_blocking(move || prepare_image_file(&path, &config));
+    match tokio::time::timeout(DECODE_TIMEOUT, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => Err(OcrError::ImageLoad(format!(
+            "Image decoding task failed: {}",
+            e
+        ))),
+        Err(_) => Err(OcrError::Timeout(format!(
+            "Image decoding took longer than {}s",
+            DECODE_TIMEOUT.as_secs()
+        ))),
+    }
+}
+
+/// Log the derived budgets and publish them as gauges
+pub fn report_budget(budget: &ResourceBudget) {
+    info!(
+        memory_limit_mb = budget.memory_limit_bytes.map(|limit| limit / MIB),
+        ocr_concurrency = budget.ocr_concurrency,
+        request_memory_mb = budget.request_memory_bytes / MIB,
+        pixel_budget = budget.pixel_budget,
+        "Derived OCR resource budgets"
+    );
+    crate::observability::record_resource_budget_metrics(budget);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::ColorType;
+
+    #[test]
+    fn test_parse_cgroup_memory_limit() {
+        assert_eq!(parse_cgroup_memory_limit("536870912\n"), Some(512 * MIB));
+        assert_eq!(parse_cgroup_memory_limit("max\n"), None);
+        assert_eq!(parse_cgroup_memory_limit("9223372036854771712"), None);
+        assert_eq!(parse_cgroup_memory_limit("garbage"), None);
+        assert_eq!(parse_cgroup_memory_limit("0"), None);
+    }
+
+    #[test]
+    fn test_derive_small_container() {
+        let budget = ResourceBudget::derive(Some(512 * MIB), 2);
+        assert_eq!(budget.ocr_concurrency, 1);
+        assert_eq!(budget.request_memory_bytes, 320 * MIB);
+        assert_eq!(budget.pixel_budget, (320 - 48) * MIB / 20);
+    }
+
+    #[test]
+    fn test_derive_below_reserve_keeps_minimum() {
+        let budget = ResourceBudget::derive(Some(128 * MIB), 4);
+        assert_eq!(budget.ocr_concurrency, 1);
+        assert_eq!(budget.request_memory_bytes, MIN_REQUEST_MEMORY);
+        assert_eq!(budget.pixel_budget, (64 - 48) * MIB / 20);
+    }
+
+    #[test]
+    fn test_derive_scales_with_memory() {
+        let one_gib = ResourceBudget::derive(Some(1024 * MIB), 4);
+        assert_eq!(one_gib.ocr_concurrency, 2);
+        assert_eq!(one_gib.request_memory_bytes, 416 * MIB);
+
+        let four_gib = ResourceBudget::derive(Some(4096 * MIB), 4);
+        assert_eq!(four_gib.ocr_concurrency, 4, "bounded by CPU count");
+        assert_eq!(four_gib.request_memory_bytes, 976 * MIB);
+
+        let large = ResourceBudget::derive(Some(64 * 1024 * MIB), 32);
+        assert_eq!(large.ocr_concurrency, MAX_OCR_CONCURRENCY);
+    }
+
+    #[test]
+    fn test_derive_without_limit_uses_default() {
+        let budget = ResourceBudget::derive(None, 4);
+        assert_eq!(budget.memory_limit_bytes, None);
+        assert_eq!(
+            budget,
+            ResourceBudget {
+                memory_limit_bytes: None,
+                ..ResourceBudget::derive(Some(DEFAULT_MEMORY_LIMIT), 4)
+            }
+        );
+    }
+
+    #[test]
+    fn test_estimate_depends_on_mode() {
+        let gray = estimate_peak_rss(1000, 1000, ColorType::L8);
+        let rgb = estimate_peak_rss(1000, 1000, ColorType::Rgb8);
+        let rgba16 = estimate_peak_rss(1000, 1000, ColorType::Rgba16);
+        assert!(gray < rgb && rgb < rgba16);
+        assert_eq!(rgb, TESSERACT_BASELINE + 1_000_000 * 19);
+    }
+
+    #[test]
+    fn test_rejection_boundary() {
+        let budget = ResourceBudget::derive(Some(512 * MIB), 1);
+
+        // Largest RGBA8 image inside the budget: exactly pixel_budget pixels
+        let width = 1000u32;
+        let height = u32::try_from(budget.pixel_budget / u64::from(width)).unwrap();
+        assert!(budget.check_image(width, height, ColorType::Rgba8).is_ok());
+        assert!(matches!(
+            budget.check_image(width, height + 1, ColorType::Rgba8),
+            Err(OcrError::ImageTooLarge(_))
+        ));
+
+        // A 12 MP phone photo fits, a 48 MP scan does not
+        assert!(budget.check_image(4000, 3000, ColorType::Rgb8).is_ok());
+        assert!(budget.check_image(8000, 6000, ColorType::Rgb8).is_err());
+    }
+
+    fn small_limits() -> OcrConfig {
+        OcrConfig {
+            max_width: 100,
+            max_height: 80,
+            max_pixels: 6_000,
+            ..OcrConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_check_image_dimensions() {
+        let config = small_limits();
+        assert_eq!(
+            check_image_dimensions(100, 60, &config).unwrap(),
+            DimensionCheck::Within
+        );
+        assert_eq!(
+            check_image_dimensions(200, 20, &config).unwrap(),
+            DimensionCheck::Downscale {
+                width: 100,
+                height: 10
+            }
+        );
+        assert_eq!(
+            check_image_dimensions(30, 160, &config).unwrap(),
+            DimensionCheck::Downscale {
+                width: 15,
+                height: 80
+            }
+        );
+        assert!(matches!(
+            check_image_dimensions(100, 61, &config),
+            Err(OcrError::ImageTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,138 @@
+//! # Scheduler Module
+//!
+//! Runs background jobs at a fixed weekday and hour (UTC), such as the weekly OCR
+//! digest sent to maintainers. Schedules are computed from the wall clock on every
+//! run rather than by sleeping a fixed week, so a late start or a slow job does not
+//! drift the next run.
+
+use std::future::Future;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc, Weekday};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// A weekly point in time, in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeeklySchedule {
+    pub weekday: Weekday,
+    /// Hour of the day, 0-23
+    pub hour: u32,
+}
+
+impl WeeklySchedule {
+    /// Create a schedule, rejecting hours outside 0-23
+    pub fn new(weekday: Weekday, hour: u32) -> Result<Self> {
+        if hour > 23 {
+            return Err(anyhow!(
+                "Schedule hour must be between 0 and 23, got {hour}"
+            ));
+        }
+        Ok(Self { weekday, hour })
+    }
+
+    /// Parse a weekday name ("mon", "Monday") and an hour
+    pub fn parse(weekday: &str, hour: &str) -> Result<Self> {
+        let weekday = weekday
+            .trim()
+            .parse::<Weekday>()
+            .map_err(|_| anyhow!("Invalid schedule weekday: {weekday}"))?;
+        let hour = hour
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| anyhow!("Invalid schedule hour: {hour}"))?;
+        Self::new(weekday, hour)
+    }
+
+    /// First scheduled time strictly after `now`
+    pub fn next_run_after(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let days_ahead = (i64::from(self.weekday.num_days_from_monday())
+            - i64::from(now.weekday().num_days_from_monday()))
+        .rem_euclid(7);
+        let time = NaiveTime::from_hms_opt(self.hour, 0, 0).unwrap_or(NaiveTime::MIN);
+        let candidate = (now.date_naive() + Duration::days(days_ahead))
+            .and_time(time)
+            .and_utc();
+
+        if candidate > now {
+            candidate
+        } else {
+            candidate + Duration::weeks(1)
+        }
+    }
+}
+
+/// Spawn a task running `job` at every occurrence of `schedule`
+///
+/// Job errors are logged and never stop the schedule.
+pub fn spawn_weekly<F, Fut>(name: &'static str, schedule: WeeklySchedule, job: F) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    tokio::spawn(async move {
+        loop {
+            let now = Utc::now();
+            let next_run = schedule.next_run_after(now);
+            info!(job = name, next_run = %next_run, "Scheduled weekly job");
+
+            let wait = (next_run - now).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            if let Err(e) = job().await {
+                warn!(job = name, error = %e, "Scheduled job failed");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // October 2026: the 12th is a Monday
+        Utc.with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_run_later_this_week() {
+        let schedule = WeeklySchedule::new(Weekday::Thu, 9).unwrap();
+        assert_eq!(schedule.next_run_after(at(12, 10, 0)), at(15, 9, 0));
+    }
+
+    #[test]
+    fn test_next_run_same_day() {
+        let schedule = WeeklySchedule::new(Weekday::Mon, 9).unwrap();
+        assert_eq!(schedule.next_run_after(at(12, 8, 59)), at(12, 9, 0));
+        // Exactly on time or past it waits a full week
+        assert_eq!(schedule.next_run_after(at(12, 9, 0)), at(19, 9, 0));
+        assert_eq!(schedule.next_run_after(at(12, 9, 30)), at(19, 9, 0));
+    }
+
+    #[test]
+    fn test_next_run_wraps_to_next_week() {
+        let schedule = WeeklySchedule::new(Weekday::Mon, 0).unwrap();
+        assert_eq!(schedule.next_run_after(at(17, 23, 0)), at(19, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        assert_eq!(
+            WeeklySchedule::parse("monday", "8").unwrap(),
+            WeeklySchedule {
+                weekday: Weekday::Mon,
+                hour: 8
+            }
+        );
+        assert_eq!(
+            WeeklySchedule::parse("Fri", " 23 ").unwrap().weekday,
+            Weekday::Fri
+        );
+        assert!(WeeklySchedule::parse("someday", "8").is_err());
+        assert!(WeeklySchedule::parse("mon", "24").is_err());
+        assert!(WeeklySchedule::parse("mon", "noon").is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! # Shutdown Module
+//!
+//! Coordinated shutdown of the bot. A [`Shutdown`] hands a [`CancellationToken`] to
+//! background tasks and counts in-flight OCR jobs, so a stopping bot can let the
+//! photos it is reading finish and reply within a grace period instead of dropping
+//! them.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// Default time in-flight OCR jobs get to finish once shutdown starts
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(20);
+
+/// Time in-flight OCR jobs get to finish once shutdown starts
+///
+/// Configurable in seconds with `SHUTDOWN_GRACE_PERIOD_SECS`, defaults to
+/// [`DEFAULT_GRACE_PERIOD`].
+pub fn grace_period() -> Duration {
+    crate::config::current().server.shutdown_grace_period
+}
+
+/// Count of in-flight jobs, notifying waiters when it drops to zero
+#[derive(Debug, Default)]
+struct InFlight {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+/// Shutdown signal and in-flight job tracker shared across the bot
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    token: CancellationToken,
+    in_flight: Arc<InFlight>,
+}
+
+/// Marks a job as in flight until dropped
+#[derive(Debug)]
+pub struct InFlightGuard {
+    in_flight: Arc<InFlight>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.in_flight.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.in_flight.idle.notify_waiters();
+        }
+    }
+}
+
+/// Outcome of waiting for in-flight jobs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainSummary {
+    /// Jobs that finished within the grace period
+    pub finished: usize,
+    /// Jobs still running when the grace period ran out
+    pub aborted: usize,
+}
+
+impl Shutdown {
+    /// Create a tracker with no job in flight
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token cancelled once shutdown starts
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Start shutting down, cancelling every token handed out
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether shutdown has started
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Mark a job as in flight until the returned guard is dropped
+    pub fn track(&self) -> InFlightGuard {
+        self.in_flight.count.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard {
+            in_flight: Arc::clone(&self.in_flight),
+        }
+    }
+
+    /// Number of jobs in flight
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.count.load(Ordering::Acquire)
+    }
+
+    /// Wait up to `grace` for every in-flight job to finish
+    pub async fn drain(&self, grace: Duration) -> DrainSummary {
+        let started = self.in_flight();
+        let idle = async {
+            loop {
+                // Created before the check so a job finishing in between is not missed
+                let notified = self.in_flight.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        let _ = tokio::time::timeout(grace, idle).await;
+
+        let aborted = self.in_flight();
+        DrainSummary {
+            finished: started.saturating_sub(aborted),
+            aborted,
+        }
+    }
+}
+
+static SHUTDOWN: LazyLock<Shutdown> = LazyLock::new(Shutdown::new);
+
+/// Shutdown tracker of the running bot
+pub fn global() -> &'static Shutdown {
+    &SHUTDOWN
+}
+
+/// Wait for Ctrl-C, or SIGTERM on Unix as sent by orchestrators
+pub async fn wait_for_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to listen for SIGTERM, only Ctrl-C stops the bot");
+            }
+        }
+    }
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::warn!(error = %e, "Failed to listen for Ctrl-C");
+        std::future::pending::<()>().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_reaches_running_job_which_finishes_in_grace() {
+        let shutdown = Shutdown::new();
+        let guard = shutdown.track();
+        let token = shutdown.token();
+        let job = tokio::spawn(async move {
+            let _guard = guard;
+            // A long-running job that wraps up as soon as shutdown starts
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(3600)) => false,
+                _ = token.cancelled() => true,
+            }
+        });
+
+        assert_eq!(shutdown.in_flight(), 1);
+        shutdown.trigger();
+        let summary = shutdown.drain(Duration::from_secs(5)).await;
+
+        assert!(job.await.unwrap(), "Job should have seen the cancellation");
+        assert_eq!(
+            summary,
+            DrainSummary {
+                finished: 1,
+                aborted: 0
+            }
+        );
+        assert_eq!(shutdown.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drain_reports_jobs_outliving_grace_period() {
+        let shutdown = Shutdown::new();
+        let guard = shutdown.track();
+        let stuck = tokio::spawn(async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_secs(3600)).await;
+        });
+
+        shutdown.trigger();
+        let summary = shutdown.drain(Duration::from_millis(20)).await;
+
+        assert_eq!(
+            summary,
+            DrainSummary {
+                finished: 0,
+                aborted: 1
+            }
+        );
+        stuck.abort();
+    }
+
+    #[tokio::test]
+    async fn test_drain_returns_immediately_when_idle() {
+        let shutdown = Shutdown::new();
+        drop(shutdown.track());
+
+        let summary = shutdown.drain(Duration::from_secs(3600)).await;
+
+        assert_eq!(
+            summary,
+            DrainSummary {
+                finished: 0,
+                aborted: 0
+            }
+        );
+        assert!(!shutdown.is_shutting_down());
+    }
+}
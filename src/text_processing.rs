@@ -9,14 +9,16 @@
 //! - Support for English and French measurement units
 //! - **Quantity-only ingredient support**: Recognizes ingredients with quantities but no units (e.g., "6 oeufs", "4 pommes")
 //! - **Fraction support**: Recognizes fractional quantities (e.g., "1/2 litre", "3/4 cup")
+//! - **Spelled-out quantities**: Reads English and French number words (e.g., "two cups flour", "une pincée de sel")
 //! - Ingredient name extraction alongside quantity and measurement
 //! - Line-by-line text analysis for ingredient lists
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
 /// Represents a detected measurement in text
@@ -36,6 +38,112 @@ pub struct MeasurementMatch {
     pub end_pos: usize,
     /// Whether this measurement requires user confirmation (e.g., missing or absurd quantity)
     pub requires_quantity_confirmation: bool,
+    /// What the unit measures (None for quantity-only matches or unclassified units)
+    #[serde(default)]
+    pub unit_dimension: Option<UnitDimension>,
+    /// Which measurement system the unit belongs to (None when the dimension is None)
+    #[serde(default)]
+    pub unit_system: Option<UnitSystem>,
+    /// OCR confidence of the source line (0.0 to 1.0), None when not read by OCR
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Upper bound of a range quantity ("3" in "2-3 cups"), `quantity` holding the lower bound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity_max: Option<String>,
+}
+
+impl MeasurementMatch {
+    /// Quantity as shown to users, "2–3" for a range
+    pub fn display_quantity(&self) -> String {
+        match &self.quantity_max {
+            Some(max) => format!("{}–{}", self.quantity, max),
+            None => self.quantity.clone(),
+        }
+    }
+
+    /// Text of a range ingredient as written ("2–3 tbsp olive oil"), None for a single quantity
+    ///
+    /// Only the lower bound fits the numeric quantity column, so this is kept as raw text.
+    pub fn range_text(&self) -> Option<String> {
+        self.quantity_max.as_ref()?;
+        let mut text = self.display_quantity();
+        for part in [
+            self.measurement.as_deref(),
+            Some(self.ingredient_name.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        {
+            text.push(' ');
+            text.push_str(part);
+        }
+        Some(text)
+    }
+}
+
+/// Physical dimension a measurement unit quantifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitDimension {
+    Volume,
+    Weight,
+    Count,
+}
+
+impl UnitDimension {
+    /// Name used in the config file and the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnitDimension::Volume => "volume",
+            UnitDimension::Weight => "weight",
+            UnitDimension::Count => "count",
+        }
+    }
+
+    /// Parse a stored dimension name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "volume" => Some(UnitDimension::Volume),
+            "weight" => Some(UnitDimension::Weight),
+            "count" => Some(UnitDimension::Count),
+            _ => None,
+        }
+    }
+}
+
+/// Measurement system a unit belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Metric,
+    Us,
+    Imperial,
+    /// Units like "pinch" or "slice" that belong to no system
+    Neutral,
+}
+
+impl UnitSystem {
+    /// Name used in the config file and the database
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "metric",
+            UnitSystem::Us => "us",
+            UnitSystem::Imperial => "imperial",
+            UnitSystem::Neutral => "neutral",
+        }
+    }
+
+    /// Parse a stored system name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "metric" => Some(UnitSystem::Metric),
+            "us" => Some(UnitSystem::Us),
+            "imperial" => Some(UnitSystem::Imperial),
+            "neutral" => Some(UnitSystem::Neutral),
+            _ => None,
+        }
+    }
 }
 
 /// Configuration options for measurement detection
@@ -54,6 +162,17 @@ pub struct MeasurementConfig {
     pub include_count_measurements: bool,
     /// Maximum number of lines to combine for multi-line ingredients
     pub max_combine_lines: usize,
+    /// Languages whose units are detected (e.g. `["en", "fr"]`). If None, uses
+    /// `MEASUREMENT_LANGUAGES`, or every language in `UNIT_LANGUAGES` when unset
+    pub unit_languages: Option<Vec<String>>,
+    /// Whether section headers and instruction lines are skipped (see [`MeasurementDetector::classify_line`])
+    pub skip_non_ingredient_lines: bool,
+    /// Whether a bare quantity wrapped onto its own line is joined with a next line
+    /// starting with a unit (see [`MeasurementDetector::wrapped_quantity_line`])
+    pub join_wrapped_quantities: bool,
+    /// Whether a quantity spelled out in English or French ("two cups", "une pincée")
+    /// is read as a number (see [`MeasurementDetector::number_word_line`])
+    pub parse_number_words: bool,
 }
 
 impl Default for MeasurementConfig {
@@ -64,6 +183,10 @@ impl Default for MeasurementConfig {
             max_ingredient_length: 100,
             include_count_measurements: true,
             max_combine_lines: 10,
+            unit_languages: None,
+            skip_non_ingredient_lines: true,
+            join_wrapped_quantities: true,
+            parse_number_words: true,
         }
     }
 }
@@ -85,6 +208,20 @@ impl MeasurementConfig {
             ));
         }
 
+        // Validate unit languages if provided
+        if let Some(languages) = &self.unit_languages {
+            if let Some(unknown) = languages
+                .iter()
+                .find(|language| !UNIT_LANGUAGES.contains(&language.as_str()))
+            {
+                return Err(crate::errors::AppError::Config(format!(
+                    "unit language '{}' is not one of {}",
+                    unknown,
+                    UNIT_LANGUAGES.join(", ")
+                )));
+            }
+        }
+
         // Validate custom regex pattern if provided
         if let Some(pattern) = &self.custom_pattern {
             if pattern.trim().is_empty() {
@@ -111,13 +248,127 @@ pub struct MeasurementUnitsConfig {
     pub measurement_units: MeasurementUnits,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Unit categories, each optional in an override file (see [`load_measurement_units_config`])
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct MeasurementUnits {
-    pub volume_units: Vec<String>,
-    pub weight_units: Vec<String>,
-    pub volume_units_metric: Vec<String>,
-    pub us_units: Vec<String>,
-    pub french_units: Vec<String>,
+    #[serde(default)]
+    pub volume_units: Vec<UnitEntry>,
+    #[serde(default)]
+    pub weight_units: Vec<UnitEntry>,
+    #[serde(default)]
+    pub volume_units_metric: Vec<UnitEntry>,
+    #[serde(default)]
+    pub us_units: Vec<UnitEntry>,
+    #[serde(default)]
+    pub french_units: Vec<UnitEntry>,
+    #[serde(default)]
+    pub german_units: Vec<UnitEntry>,
+    #[serde(default)]
+    pub spanish_units: Vec<UnitEntry>,
+}
+
+/// Languages with measurement units, English and metric units being always detected
+pub const UNIT_LANGUAGES: [&str; 4] = ["en", "fr", "de", "es"];
+
+/// Languages whose units are detected, from `MEASUREMENT_LANGUAGES` (e.g. `en,fr`)
+///
+/// Returns `None`, detecting every language, when the variable is unset or empty.
+pub fn measurement_languages() -> Option<Vec<String>> {
+    crate::config::current()
+        .text_processing
+        .unit_languages
+        .clone()
+}
+
+/// A configured measurement unit and its classification
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnitEntry {
+    pub name: String,
+    pub dimension: UnitDimension,
+    pub system: UnitSystem,
+}
+
+impl UnitEntry {
+    pub fn new(name: &str, dimension: UnitDimension, system: UnitSystem) -> Self {
+        Self {
+            name: name.to_string(),
+            dimension,
+            system,
+        }
+    }
+}
+
+impl MeasurementUnits {
+    /// All configured units across categories
+    pub fn entries(&self) -> impl Iterator<Item = &UnitEntry> {
+        self.volume_units
+            .iter()
+            .chain(&self.weight_units)
+            .chain(&self.volume_units_metric)
+            .chain(&self.us_units)
+            .chain(&self.french_units)
+            .chain(&self.german_units)
+            .chain(&self.spanish_units)
+    }
+
+    /// Units detected when only `languages` are enabled
+    ///
+    /// English and metric units are always included.
+    pub fn entries_for_languages<'a>(
+        &'a self,
+        languages: &'a [String],
+    ) -> impl Iterator<Item = &'a UnitEntry> {
+        let enabled = move |language: &str| languages.iter().any(|enabled| enabled == language);
+        let language_units = [
+            ("fr", &self.french_units),
+            ("de", &self.german_units),
+            ("es", &self.spanish_units),
+        ]
+        .into_iter()
+        .filter(move |(language, _)| enabled(language))
+        .flat_map(|(_, units)| units);
+
+        self.volume_units
+            .iter()
+            .chain(&self.weight_units)
+            .chain(&self.volume_units_metric)
+            .chain(&self.us_units)
+            .chain(language_units)
+    }
+
+    /// Classification of every unit, keyed by lowercase unit name
+    pub fn classifications(&self) -> HashMap<String, (UnitDimension, UnitSystem)> {
+        self.entries()
+            .map(|entry| (entry.name.to_lowercase(), (entry.dimension, entry.system)))
+            .collect()
+    }
+
+    /// Add the units of `overrides` to the same categories
+    ///
+    /// A unit already in its category, compared case-insensitively, takes the
+    /// override's classification instead of being listed twice.
+    pub fn merge(&mut self, overrides: MeasurementUnits) {
+        let categories = [
+            (&mut self.volume_units, overrides.volume_units),
+            (&mut self.weight_units, overrides.weight_units),
+            (&mut self.volume_units_metric, overrides.volume_units_metric),
+            (&mut self.us_units, overrides.us_units),
+            (&mut self.french_units, overrides.french_units),
+            (&mut self.german_units, overrides.german_units),
+            (&mut self.spanish_units, overrides.spanish_units),
+        ];
+        for (units, added) in categories {
+            for entry in added {
+                match units
+                    .iter_mut()
+                    .find(|unit| unit.name.to_lowercase() == entry.name.to_lowercase())
+                {
+                    Some(unit) => *unit = entry,
+                    None => units.push(entry),
+                }
+            }
+        }
+    }
 }
 
 impl MeasurementUnitsConfig {
@@ -151,24 +402,25 @@ impl MeasurementUnitsConfig {
         }
 
         // Validate that all unit strings are non-empty and contain valid characters
-        let validate_units = |units: &[String], category: &str| -> crate::errors::AppResult<()> {
-            for (i, unit) in units.iter().enumerate() {
-                if unit.trim().is_empty() {
-                    return Err(crate::errors::AppError::Config(format!(
-                        "{}[{}] cannot be empty",
-                        category, i
-                    )));
-                }
-                // Check for obviously invalid characters (control characters)
-                if unit.chars().any(|c| c.is_control()) {
-                    return Err(crate::errors::AppError::Config(format!(
-                        "{}[{}] '{}' contains control characters",
-                        category, i, unit
-                    )));
+        let validate_units =
+            |units: &[UnitEntry], category: &str| -> crate::errors::AppResult<()> {
+                for (i, unit) in units.iter().map(|entry| &entry.name).enumerate() {
+                    if unit.trim().is_empty() {
+                        return Err(crate::errors::AppError::Config(format!(
+                            "{}[{}] cannot be empty",
+                            category, i
+                        )));
+                    }
+                    // Check for obviously invalid characters (control characters)
+                    if unit.chars().any(|c| c.is_control()) {
+                        return Err(crate::errors::AppError::Config(format!(
+                            "{}[{}] '{}' contains control characters",
+                            category, i, unit
+                        )));
+                    }
                 }
-            }
-            Ok(())
-        };
+                Ok(())
+            };
 
         validate_units(&self.measurement_units.volume_units, "volume_units")?;
         validate_units(&self.measurement_units.weight_units, "weight_units")?;
@@ -178,6 +430,21 @@ impl MeasurementUnitsConfig {
         )?;
         validate_units(&self.measurement_units.us_units, "us_units")?;
         validate_units(&self.measurement_units.french_units, "french_units")?;
+        validate_units(&self.measurement_units.german_units, "german_units")?;
+        validate_units(&self.measurement_units.spanish_units, "spanish_units")?;
+
+        // A unit listed in several categories must be classified the same way everywhere
+        let mut seen: HashMap<String, &UnitEntry> = HashMap::new();
+        for entry in self.measurement_units.entries() {
+            if let Some(previous) = seen.insert(entry.name.to_lowercase(), entry) {
+                if (previous.dimension, previous.system) != (entry.dimension, entry.system) {
+                    return Err(crate::errors::AppError::Config(format!(
+                        "unit '{}' has conflicting classifications",
+                        entry.name
+                    )));
+                }
+            }
+        }
 
         Ok(())
     }
@@ -187,15 +454,84 @@ impl MeasurementUnitsConfig {
 // Uses named capture groups: quantity, measurement, and ingredient
 // NOTE: This pattern is now built dynamically from config/measurement_units.json
 
-/// Load measurement units configuration from JSON file
+/// Load measurement units configuration, with the deployment's units on top
+///
+/// Units from the file named by `MEASUREMENT_UNITS_PATH` are merged into the
+/// built-in ones (see [`MeasurementUnits::merge`]). An override that cannot be
+/// read or fails [`MeasurementUnitsConfig::validate`] is ignored with a warning.
 pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
-    // First, try to get path from environment variable
-    if let Ok(config_path) = std::env::var("MEASUREMENT_UNITS_CONFIG_PATH") {
+    let paths = &crate::config::current().paths;
+    load_measurement_units_config_from(
+        paths.measurement_units.as_deref(),
+        paths.measurement_units_override.as_deref(),
+    )
+}
+
+/// Load the built-in units from `builtin_path` and merge the override file at `override_path`
+///
+/// The built-in units are searched in the Docker and working directories when
+/// `builtin_path` is `None` or cannot be loaded.
+pub fn load_measurement_units_config_from(
+    builtin_path: Option<&str>,
+    override_path: Option<&str>,
+) -> MeasurementUnitsConfig {
+    let builtin = load_builtin_measurement_units_config(builtin_path);
+    match with_unit_overrides(builtin.clone(), override_path) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("{}. Using the built-in measurement units.", e);
+            builtin
+        }
+    }
+}
+
+/// Path of the measurement units override file, from `MEASUREMENT_UNITS_PATH`
+pub fn measurement_units_override_path() -> Option<String> {
+    crate::config::current()
+        .paths
+        .measurement_units_override
+        .clone()
+}
+
+/// Merge the units of the override file at `path` into `config` and validate the result
+fn with_unit_overrides(
+    mut config: MeasurementUnitsConfig,
+    path: Option<&str>,
+) -> crate::errors::AppResult<MeasurementUnitsConfig> {
+    let Some(path) = path else {
+        return Ok(config);
+    };
+    let content = fs::read_to_string(path).map_err(|e| {
+        crate::errors::AppError::Config(format!(
+            "Failed to read measurement units override '{}': {}",
+            path, e
+        ))
+    })?;
+    let overrides: MeasurementUnitsConfig = serde_json::from_str(&content).map_err(|e| {
+        crate::errors::AppError::Config(format!(
+            "Failed to parse measurement units override '{}': {}",
+            path, e
+        ))
+    })?;
+    config.measurement_units.merge(overrides.measurement_units);
+    config.validate().map_err(|e| {
+        crate::errors::AppError::Config(format!(
+            "Measurement units override '{}' is invalid: {}",
+            path, e
+        ))
+    })?;
+    Ok(config)
+}
+
+/// Load the built-in measurement units configuration from its JSON file
+fn load_builtin_measurement_units_config(config_path: Option<&str>) -> MeasurementUnitsConfig {
+    // First, try the path from MEASUREMENT_UNITS_CONFIG_PATH
+    if let Some(config_path) = config_path {
         info!(
-            "Loading measurement units config from environment variable: {}",
+            "Loading measurement units config from MEASUREMENT_UNITS_CONFIG_PATH: {}",
             config_path
         );
-        match fs::read_to_string(&config_path) {
+        match fs::read_to_string(config_path) {
             Ok(content) => match serde_json::from_str(&content) {
                 Ok(config) => {
                     info!(
@@ -258,6 +594,8 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
             volume_units_metric: vec![],
             us_units: vec![],
             french_units: vec![],
+            german_units: vec![],
+            spanish_units: vec![],
         },
     }
 }
@@ -272,7 +610,8 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 /// ### Step 1: Configuration Loading
 /// ```text
 /// Load measurement units from config/measurement_units.json
-/// Categories: volume_units, weight_units, volume_units_metric, us_units, french_units
+/// Categories: volume_units, weight_units, volume_units_metric, us_units, french_units,
+/// german_units, spanish_units (the last three only for enabled languages)
 /// ```
 ///
 /// ### Step 2: Unit Collection and Deduplication
@@ -306,6 +645,7 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 /// ```regex
 /// (?i)                           # Case-insensitive matching
 /// (?P<quantity>...)             # Named group for quantity (fractions/decimals)
+/// (?:[-–—]|to|à)(?P<quantity_max>...)? # Optional upper bound of a range ("2-3", "2 to 3")
 /// (?:                           # Non-capturing group for alternatives
 ///   \s*(?P<measurement>...)     # Optional whitespace + measurement unit
 ///   |                           # OR
@@ -320,12 +660,13 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 /// - **Decimals**: `1.5`, `2.25`, `0.5`
 /// - **Fractions**: `1/2`, `3/4`, `2¼` (Unicode fractions)
 /// - **Mixed**: `2½`, `1½` (Unicode fraction characters)
+/// - **Ranges**: `2-3`, `2 – 3`, `2 to 3`, `2 à 3`, the upper bound in `quantity_max`
 ///
 /// ## Measurement Unit Handling
 ///
 /// - **Escaping**: All regex special characters are escaped
 /// - **Ordering**: Longest units matched first to prevent partial matches
-/// - **Categories**: Supports English, French, metric, and US customary units
+/// - **Categories**: Supports English, French, German, Spanish, metric, and US customary units
 /// - **Case Insensitive**: All units matched regardless of case
 ///
 /// ## Examples of Generated Patterns
@@ -337,7 +678,7 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 ///
 /// ## Performance Characteristics
 ///
-/// - **Compilation**: Pattern compiled once at startup via lazy_static
+/// - **Compilation**: Pattern compiled once per load of the units, at first access or on reload
 /// - **Matching**: Efficient regex matching with pre-compiled patterns
 /// - **Memory**: Minimal memory usage (shared static pattern)
 /// - **Thread Safety**: Immutable static pattern, safe for concurrent access
@@ -354,18 +695,20 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 /// ```json
 /// {
 ///   "measurement_units": {
-///     "volume_units": ["cups", "tablespoons", "teaspoons"],
-///     "weight_units": ["grams", "kilograms", "pounds"],
-///     "volume_units_metric": ["litres", "millilitres"],
-///     "us_units": ["fl oz", "qt", "gal"],
-///     "french_units": ["litres", "grammes", "cuillères"]
+///     "volume_units": [{ "name": "cups", "dimension": "volume", "system": "us" }],
+///     "weight_units": [{ "name": "grams", "dimension": "weight", "system": "metric" }],
+///     "volume_units_metric": [{ "name": "litres", "dimension": "volume", "system": "metric" }],
+///     "us_units": [{ "name": "slice", "dimension": "count", "system": "neutral" }],
+///     "french_units": [{ "name": "cuillères", "dimension": "volume", "system": "metric" }],
+///     "german_units": [{ "name": "Esslöffel", "dimension": "volume", "system": "metric" }],
+///     "spanish_units": [{ "name": "cucharada", "dimension": "volume", "system": "metric" }]
 ///   }
 /// }
 /// ```
 ///
 /// ## Thread Safety and Performance
 ///
-/// - **Lazy Initialization**: Pattern built once at first access
+/// - **Lazy Initialization**: Pattern built at first access, then again on each reload
 /// - **Static Storage**: Compiled regex stored in static memory
 /// - **Concurrent Access**: Safe for use across multiple threads
 /// - **Memory Efficiency**: Single compiled pattern reused for all operations
@@ -378,48 +721,304 @@ pub fn load_measurement_units_config() -> MeasurementUnitsConfig {
 ///
 /// Note: This is a private function used internally to build the default regex pattern.
 /// The functionality is exposed through the public `MeasurementDetector::new()` constructor.
-fn build_measurement_regex_pattern() -> String {
-    let config = load_measurement_units_config();
+fn build_measurement_regex_pattern(
+    units: &MeasurementUnits,
+    languages: Option<&[String]>,
+) -> String {
+    // Combine the unit categories of the enabled languages into a single collection
+    let units_pattern = match languages {
+        Some(languages) => units_alternation(units.entries_for_languages(languages)),
+        None => units_alternation(units.entries()),
+    };
 
-    // Combine all unit categories into a single collection
-    let mut all_units: Vec<String> = Vec::new();
-    all_units.extend(config.measurement_units.volume_units);
-    all_units.extend(config.measurement_units.weight_units);
-    all_units.extend(config.measurement_units.volume_units_metric);
-    all_units.extend(config.measurement_units.us_units);
-    all_units.extend(config.measurement_units.french_units);
+    // Build the complete regex pattern with named capture groups
+    // Unified pattern: measurement is optional, ingredient extracted from text after match.
+    // A range ("2-3", "2 – 3", "2 to 3", "2 à 3") captures its upper bound as quantity_max.
+    format!(
+        r"(?i)(?P<quantity>{QUANTITY_PATTERN})(?:(?:\s*[-–—]\s*|\s+(?:to|à)\s+)(?P<quantity_max>{QUANTITY_PATTERN}))?(?:\s*(?P<measurement>{})(?:\s|$))?\s*",
+        units_pattern
+    )
+}
 
-    // Remove duplicates and sort by length (longest first) to avoid partial matches
-    let unique_units: std::collections::HashSet<String> = all_units.into_iter().collect();
-    let mut sorted_units: Vec<String> = unique_units.into_iter().collect();
+/// Quantity grammar: mixed numbers, Unicode fractions, fractions (with OCR'd l/O for 1/0) and decimals
+const QUANTITY_PATTERN: &str =
+    r"\d+\s+\d+/\d+|\d+[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞⅟]|[lO\d]+/\d+|\d*\.?\d+|[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞⅟]";
+
+/// Quantities spelled out in English and French, with whether the word is also an article
+///
+/// Articles only count as 1 before a unit: "a pinch of salt" is one pinch, while
+/// "a little butter" has no quantity.
+const NUMBER_WORDS: &[(&str, &str, bool)] = &[
+    ("a", "1", true),
+    ("an", "1", true),
+    ("one", "1", false),
+    ("two", "2", false),
+    ("three", "3", false),
+    ("four", "4", false),
+    ("five", "5", false),
+    ("six", "6", false),
+    ("seven", "7", false),
+    ("eight", "8", false),
+    ("nine", "9", false),
+    ("ten", "10", false),
+    ("eleven", "11", false),
+    ("twelve", "12", false),
+    ("thirteen", "13", false),
+    ("fourteen", "14", false),
+    ("fifteen", "15", false),
+    ("sixteen", "16", false),
+    ("seventeen", "17", false),
+    ("eighteen", "18", false),
+    ("nineteen", "19", false),
+    ("twenty", "20", false),
+    ("half", "1/2", false),
+    ("half a", "1/2", false),
+    ("half an", "1/2", false),
+    ("un", "1", true),
+    ("une", "1", true),
+    ("deux", "2", false),
+    ("trois", "3", false),
+    ("quatre", "4", false),
+    ("cinq", "5", false),
+    ("sept", "7", false),
+    ("huit", "8", false),
+    ("neuf", "9", false),
+    ("dix", "10", false),
+    ("onze", "11", false),
+    ("douze", "12", false),
+    ("treize", "13", false),
+    ("quatorze", "14", false),
+    ("quinze", "15", false),
+    ("seize", "16", false),
+    ("dix-sept", "17", false),
+    ("dix-huit", "18", false),
+    ("dix-neuf", "19", false),
+    ("vingt", "20", false),
+    ("demi", "1/2", false),
+    ("demie", "1/2", false),
+    ("un demi", "1/2", false),
+    ("une demi", "1/2", false),
+    ("une demie", "1/2", false),
+];
+
+/// Words that, right after a spelled-out number without a unit, show it is not a quantity
+/// ("one of the best", "deux de plus")
+const NOT_INGREDIENT_WORDS: [&str; 9] = ["of", "or", "more", "de", "du", "des", "ou", "plus", "d"];
+
+/// Spelled-out quantity opening a line, after any bullet
+///
+/// Returns the byte range of the words, to be replaced by the value, and whether they
+/// are an article. "demi" may be joined to its unit by a hyphen ("demi-litre"), which
+/// the range then covers. The longest match wins, so "half a cup" reads "half a".
+fn leading_number_word(line: &str) -> Option<(usize, usize, &'static str, bool)> {
+    let rest =
+        line.trim_start_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '•' | '*' | '·'));
+    let start = line.len() - rest.len();
+
+    NUMBER_WORDS
+        .iter()
+        .filter_map(|&(words, value, article)| {
+            if !rest.get(..words.len())?.eq_ignore_ascii_case(words) {
+                return None;
+            }
+            let next = rest[words.len()..].chars().next()?;
+            let hyphenated = next == '-' && (words.ends_with("demi") || words.ends_with("demie"));
+            (next.is_whitespace() || hyphenated).then(|| {
+                (
+                    start,
+                    start + words.len() + usize::from(hyphenated),
+                    value,
+                    article,
+                )
+            })
+        })
+        .max_by_key(|&(_, end, _, _)| end)
+}
+
+/// Join unit names into a regex alternation, longest first so a unit never loses to its prefix
+fn units_alternation<'a>(entries: impl Iterator<Item = &'a UnitEntry>) -> String {
+    // Remove duplicates, matching is case-insensitive anyway
+    let unique_units: std::collections::HashSet<&str> =
+        entries.map(|entry| entry.name.as_str()).collect();
+    let mut sorted_units: Vec<&str> = unique_units.into_iter().collect();
 
     // Sort by length descending, then alphabetically for consistency
     sorted_units.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
 
-    // Escape regex special characters in each unit
-    let escaped_units: Vec<String> = sorted_units
+    // Escape regex special characters in each unit and build the alternation
+    sorted_units
         .into_iter()
-        .map(|unit| regex::escape(&unit))
-        .collect();
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Ingredient text after a measurement, up to the next ingredient of the line
+///
+/// Stops at a comma ("2 cups flour, 1 cup sugar") or before a word followed by a
+/// quantity ("2 cups flour with 1 tbsp sugar"). The result is a slice of `remaining`,
+/// so no per-character buffer is built.
+fn ingredient_text(remaining: &str) -> &str {
+    let mut end = remaining.len();
+    let mut word_start = 0;
+    let mut in_word = false;
+
+    for (index, ch) in remaining.char_indices() {
+        // Stop at comma (next ingredient)
+        if ch == ',' {
+            end = index;
+            break;
+        }
 
-    // Build the alternation pattern
-    let units_pattern = escaped_units.join("|");
+        if ch.is_whitespace() || (!ch.is_alphanumeric() && ch != '-') {
+            // End of word
+            in_word = false;
 
-    // Build the complete regex pattern with named capture groups
-    // Unified pattern: measurement is optional, ingredient extracted from text after match
-    format!(
-        r"(?i)(?P<quantity>\d+\s+\d+/\d+|\d+[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞⅟]|[lO\d]+/\d+|\d*\.?\d+|[½⅓⅔¼¾⅕⅖⅗⅘⅙⅚⅛⅜⅝⅞⅟])(?:\s*(?P<measurement>{})(?:\s|$))?\s*",
-        units_pattern
-    )
+            // Look ahead past whitespace to see if this word is followed by a digit
+            let followed_by_digit = remaining[index + ch.len_utf8()..]
+                .chars()
+                .find(|next_ch| !next_ch.is_whitespace())
+                .is_some_and(|next_ch| next_ch.is_ascii_digit());
+
+            if followed_by_digit {
+                // Drop the current word and any trailing whitespace
+                end = word_start;
+                break;
+            }
+        } else if !in_word {
+            // Start of word
+            word_start = index;
+            in_word = true;
+        }
+    }
+    remaining[..end].trim()
+}
+
+/// Measurement units detection currently runs with, replaced as a whole on reload
+struct DetectionUnits {
+    units: MeasurementUnits,
+    /// Default pattern, built once per load to avoid recompilation
+    default_regex: Regex,
+    /// Unit classifications, keyed by lowercase name
+    classifications: HashMap<String, (UnitDimension, UnitSystem)>,
+}
+
+impl DetectionUnits {
+    fn build(config: MeasurementUnitsConfig) -> Result<Self, regex::Error> {
+        let units = config.measurement_units;
+        let default_regex = Regex::new(&build_measurement_regex_pattern(
+            &units,
+            measurement_languages().as_deref(),
+        ))?;
+        Ok(Self {
+            classifications: units.classifications(),
+            default_regex,
+            units,
+        })
+    }
+}
+
+lazy_static! {
+    static ref DETECTION_UNITS: parking_lot::RwLock<Arc<DetectionUnits>> =
+        parking_lot::RwLock::new(Arc::new(
+            DetectionUnits::build(load_measurement_units_config())
+                .expect("Default measurement pattern should be valid")
+        ));
+}
+
+/// Snapshot of the current units, unaffected by a reload while it is held
+fn detection_units() -> Arc<DetectionUnits> {
+    DETECTION_UNITS.read().clone()
+}
+
+/// Reload measurement units, merging the override file at `override_path`
+///
+/// Detectors created afterwards use the new units, those already created keep
+/// theirs. When the override cannot be read, fails validation or yields an
+/// invalid pattern, the error is returned and the previous units stay in use.
+pub fn reload_measurement_units(override_path: Option<&str>) -> crate::errors::AppResult<()> {
+    let builtin_path = crate::config::current().paths.measurement_units.as_deref();
+    let config = with_unit_overrides(
+        load_builtin_measurement_units_config(builtin_path),
+        override_path,
+    )?;
+    let units = DetectionUnits::build(config).map_err(|e| {
+        crate::errors::AppError::Config(format!("Invalid measurement units pattern: {}", e))
+    })?;
+    let unit_count = units.classifications.len();
+    *DETECTION_UNITS.write() = Arc::new(units);
+    info!(unit_count, "Reloaded measurement units");
+    Ok(())
+}
+
+/// Reload measurement units from `MEASUREMENT_UNITS_PATH` on every SIGHUP
+///
+/// A deployment can then add a unit by editing its override file, without a restart.
+#[cfg(unix)]
+pub fn spawn_measurement_units_reloader() {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!(error = %e, "Failed to listen for SIGHUP, measurement units won't be reloaded");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            if let Err(e) = reload_measurement_units(measurement_units_override_path().as_deref()) {
+                warn!(error = %e, "Keeping previous measurement units");
+            }
+        }
+    });
+}
+
+/// Dimension and system of a configured unit, matched case-insensitively
+pub fn classify_unit(unit: &str) -> Option<(UnitDimension, UnitSystem)> {
+    detection_units()
+        .classifications
+        .get(&unit.trim().to_lowercase())
+        .copied()
+}
+
+/// What a line of recipe text holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// Possibly an ingredient, left to measurement extraction
+    Ingredient,
+    /// Section header such as "For the topping:" or "Ingredients"
+    Header,
+    /// Method step: an imperative verb, an oven temperature or a cooking time
+    Instruction,
 }
 
-// Lazy static regex for default pattern to avoid recompilation
 lazy_static! {
-    static ref DEFAULT_REGEX: Regex = Regex::new(&build_measurement_regex_pattern())
-        .expect("Default measurement pattern should be valid");
+    /// Header words standing alone on a line, with or without a trailing colon
+    ///
+    /// Section names that are also ingredients ("sauce", "pâte") only count with a colon.
+    static ref HEADER_PATTERN: Regex = Regex::new(
+        r"(?i)^(?:ingredients?|ingrédients?|zutaten|ingredientes|instructions?|directions?|method|preparation|préparation|steps?|étapes?|zubereitung|preparación)\s*:?$"
+    )
+    .expect("Header pattern should be valid");
+
+    /// Imperative verbs opening a method step, after an optional step number
+    static ref INSTRUCTION_VERB_PATTERN: Regex = Regex::new(
+        r"(?i)^(?:(?:step\s*)?\d+[.)]\s*)?(?:preheat|bake|cook|stir|mix|whisk|combine|add|pour|heat|simmer|boil|serve|place|remove|let|beat|fold|knead|roll|spread|cover|bring|reduce|transfer|sprinkle|melt|grease|line|refrigerate|chill|garnish|slice|chop|dice|cut|toss|drain|rub|blend|sift|préchauffer|préchauffez|cuire|faites|faire|mélanger|mélangez|ajouter|ajoutez|verser|versez|laisser|laissez|enfourner|enfournez|battre|battez|servir|servez|incorporer|incorporez|porter|portez|vorheizen|backen|rühren|hornear|mezclar|añadir|agregar|precalentar)\b"
+    )
+    .expect("Instruction verb pattern should be valid");
+
+    /// Step number opening a method step ("3.", "Step 2)")
+    static ref NUMBERED_STEP_PATTERN: Regex = Regex::new(r"(?i)^(?:step\s*)?\d+[.)]\s")
+        .expect("Numbered step pattern should be valid");
+
+    /// Oven temperatures ("350°F", "180 °C", "200 degrees") and cooking times ("25 minutes", "1-2 hours")
+    static ref TEMPERATURE_OR_DURATION_PATTERN: Regex = Regex::new(
+        r"(?i)\d\s*(?:°|º|degrees?\b|degrés?\b|grad\b|grados?\b)|\b\d+(?:\s*[-–—]\s*\d+)?\s*(?:minutes?|mins?|hours?|hrs?|heures?|secondes?|seconds?|minuten|stunden?|minutos?|horas?)\b"
+    )
+    .expect("Temperature and duration pattern should be valid");
 }
 
-/// Measurement detector using regex patterns for English and French units
+/// Measurement detector using regex patterns for English, French, German and Spanish units
 pub struct MeasurementDetector {
     /// Compiled regex pattern for detecting measurements
     pattern: Regex,
@@ -443,7 +1042,7 @@ impl MeasurementDetector {
     pub fn new() -> Result<Self, regex::Error> {
         info!("Creating new MeasurementDetector with default configuration");
         Ok(Self {
-            pattern: DEFAULT_REGEX.clone(),
+            pattern: detection_units().default_regex.clone(),
             config: MeasurementConfig::default(),
         })
     }
@@ -519,9 +1118,15 @@ impl MeasurementDetector {
         let pattern = if let Some(custom_pattern) = &config.custom_pattern {
             debug!("Using custom regex pattern: {}", custom_pattern);
             Regex::new(custom_pattern)?
+        } else if let Some(languages) = &config.unit_languages {
+            debug!("Using regex pattern for unit languages: {:?}", languages);
+            Regex::new(&build_measurement_regex_pattern(
+                &detection_units().units,
+                Some(languages),
+            ))?
         } else {
             debug!("Using default regex pattern");
-            DEFAULT_REGEX.clone()
+            detection_units().default_regex.clone()
         };
 
         info!("Creating MeasurementDetector with custom config: postprocessing={}, max_length={}, count_measurements={}",
@@ -683,7 +1288,11 @@ impl MeasurementDetector {
     pub fn extract_ingredient_measurements(&self, text: &str) -> Vec<MeasurementMatch> {
         let start_time = std::time::Instant::now();
         let text_length = text.len();
-        let line_count = text.lines().count();
+
+        // Lines are collected once up front: multi-line extraction needs random access
+        // and the count feeds the metrics below
+        let all_lines: Vec<&str> = text.lines().collect();
+        let line_count = all_lines.len();
 
         let mut matches = Vec::new();
         let mut current_pos = 0;
@@ -699,7 +1308,6 @@ impl MeasurementDetector {
         // MAIN PROCESSING LOOP: Process lines with potential multi-line ingredient detection
         // Changed from iterator-based to index-based loop to support skipping consumed lines
         // when multi-line ingredients span multiple consecutive lines
-        let all_lines: Vec<&str> = text.lines().collect();
         let mut line_index = 0;
 
         while line_index < all_lines.len() {
@@ -710,6 +1318,51 @@ impl MeasurementDetector {
             // Track how many lines are consumed by this measurement (for multi-line ingredients)
             let mut lines_consumed = 1; // Default to 1 line consumed
 
+            // CLASSIFICATION: Headers and method steps never hold an ingredient
+            if self.is_skipped_line(line) {
+                debug!("Skipping non-ingredient line {}: '{}'", line_number, line);
+                current_pos += line.len() + 1; // +1 for newline
+                line_index += 1;
+                continue;
+            }
+
+            // WRAP JOIN: A quantity wrapped away from its unit is read as one line
+            let wrapped_line = self.wrapped_quantity_line(&all_lines, line_index);
+            let line = match &wrapped_line {
+                Some(joined) => {
+                    debug!(
+                        "Joined wrapped quantity on line {}: '{}'",
+                        line_number, joined
+                    );
+                    lines_consumed = 2;
+                    joined.as_str()
+                }
+                None => line,
+            };
+
+            // NUMBER WORDS: "two cups flour" is matched as "2 cups flour", while match
+            // positions keep pointing at the original wording
+            let number_word_line = self.convert_number_word(line);
+            let position_shift = number_word_line.as_ref().map(|(converted, shifted_from)| {
+                (
+                    *shifted_from,
+                    line.len() as isize - converted.len() as isize,
+                )
+            });
+            let original_pos = |pos: usize| match position_shift {
+                Some((shifted_from, shift)) if pos >= shifted_from => {
+                    pos.saturating_add_signed(shift)
+                }
+                _ => pos,
+            };
+            let line = match &number_word_line {
+                Some((converted, _)) => {
+                    debug!("Read number words on line {}: '{}'", line_number, converted);
+                    converted.as_str()
+                }
+                None => line,
+            };
+
             // CAPTURE LOOP: Find all measurement patterns in current line
             // This inner loop handles multiple measurements per line (rare but possible)
             'capture_loop: for capture in self.pattern.captures_iter(line) {
@@ -724,6 +1377,7 @@ impl MeasurementDetector {
 
                 // Extract named capture groups
                 let quantity = capture.name("quantity").map(|m| m.as_str()).unwrap_or("");
+                let quantity_max = capture.name("quantity_max").map(|m| m.as_str());
                 let measurement_unit = capture.name("measurement").map(|m| m.as_str());
 
                 // Debug output
@@ -756,75 +1410,8 @@ impl MeasurementDetector {
                     continue 'capture_loop;
                 }
 
-                // Extract ingredient from text after the match with improved boundary detection
-                let match_end = capture
-                    .get(0)
-                    .expect("Full match should always be available in regex capture")
-                    .end();
-                let remaining_text = &line[match_end..];
-                let trimmed_remaining = remaining_text.trim_start();
-
                 // For measurements at end of line, allow empty ingredients
-                let ingredient = if trimmed_remaining.is_empty() {
-                    String::new()
-                } else {
-                    // Extract ingredient until we hit another quantity or end of line
-                    // This handles cases like "2 cups flour, 1 cup sugar" by stopping at comma
-                    // or "2 cups flour with 1 tbsp sugar" by stopping before words followed by digits
-                    let mut result = String::new();
-                    let mut chars = trimmed_remaining.chars().peekable();
-                    let mut word_start_char_index = 0;
-                    let mut current_char_index = 0;
-                    let mut in_word = false;
-
-                    while let Some(ch) = chars.next() {
-                        // Stop at comma (next ingredient)
-                        if ch == ',' {
-                            break;
-                        }
-
-                        result.push(ch);
-                        current_char_index += 1;
-
-                        if ch.is_whitespace() || (!ch.is_alphanumeric() && ch != '-') {
-                            // End of word
-                            in_word = false;
-
-                            // Look ahead to see if this word is followed by a digit
-                            let mut temp_chars = chars.clone();
-                            let mut found_digit_after_whitespace = false;
-
-                            // Skip whitespace
-                            for next_ch in temp_chars.by_ref() {
-                                if !next_ch.is_whitespace() {
-                                    if next_ch.is_ascii_digit() {
-                                        found_digit_after_whitespace = true;
-                                    }
-                                    break;
-                                }
-                            }
-
-                            if found_digit_after_whitespace {
-                                // Remove the current word and any trailing whitespace
-                                // Use character index to safely slice the string
-                                let char_indices: Vec<(usize, char)> =
-                                    result.char_indices().collect();
-                                if word_start_char_index < char_indices.len() {
-                                    let byte_pos = char_indices[word_start_char_index].0;
-                                    result = result[..byte_pos].trim_end().to_string();
-                                }
-                                break;
-                            }
-                        } else if ch.is_alphanumeric() || ch == '-' {
-                            // Start of word
-                            if !in_word {
-                                word_start_char_index = current_char_index - 1; // Start of this character
-                                in_word = true;
-                            }
-                        }
-                    }
-                    result.trim().to_string()
-                };
+                let ingredient = ingredient_text(trimmed_remaining);
 
                 // Additional safeguard: skip if ingredient contains suspicious patterns
                 // that might indicate over-matching (like multiple measurements)
@@ -865,7 +1452,7 @@ impl MeasurementDetector {
                         )
                     };
 
-                let mut ingredient_name = self.post_process_ingredient_name(&ingredient);
+                let mut ingredient_name = self.post_process_ingredient_name(ingredient);
 
                 trace!(
                     "Extracted ingredient name: '{}' -> '{}'",
@@ -888,7 +1475,7 @@ impl MeasurementDetector {
                     let (combined_ingredient, consumed) =
                         self.extract_multi_line_ingredient(&all_lines, line_number);
 
-                    if consumed > 1 {
+                    if consumed > lines_consumed {
                         debug!(
                             "Combined {} lines for ingredient: '{}' -> '{}'",
                             consumed, ingredient_name, combined_ingredient
@@ -922,14 +1509,35 @@ impl MeasurementDetector {
                     &final_quantity
                 };
 
+                let (unit_dimension, unit_system) =
+                    final_measurement.as_deref().and_then(classify_unit).unzip();
+
+                // Keep the upper bound of a range only when it is above the lower bound
+                let quantity_max = quantity_max
+                    .map(|max| self.post_process_quantity(max))
+                    .filter(|max| {
+                        !requires_confirmation
+                            && matches!(
+                                (
+                                    crate::validation::parse_quantity(&final_quantity),
+                                    crate::validation::parse_quantity(max),
+                                ),
+                                (Some(min), Some(max)) if max > min
+                            )
+                    });
+
                 matches.push(MeasurementMatch {
                     quantity: confirmed_quantity.to_string(),
                     measurement: final_measurement,
                     ingredient_name,
                     line_number,
-                    start_pos: current_pos + full_match.start(),
-                    end_pos: current_pos + match_end_pos,
+                    start_pos: current_pos + original_pos(full_match.start()),
+                    end_pos: current_pos + original_pos(match_end_pos),
                     requires_quantity_confirmation: requires_confirmation,
+                    unit_dimension,
+                    unit_system,
+                    confidence: None,
+                    quantity_max,
                 });
             }
 
@@ -1005,7 +1613,9 @@ impl MeasurementDetector {
     pub fn extract_measurement_lines(&self, text: &str) -> Vec<(usize, String)> {
         text.lines()
             .enumerate()
-            .filter(|(_, line)| self.pattern.is_match(line))
+            .filter(|(_, line)| {
+                self.pattern.is_match(line) || self.number_word_line(line).is_some()
+            })
             .map(|(i, line)| (i, line.to_string()))
             .collect()
     }
@@ -1035,30 +1645,27 @@ impl MeasurementDetector {
     /// # Ok::<(), regex::Error>(())
     /// ```
     pub fn has_measurements(&self, text: &str) -> bool {
-        // Check if text contains measurements by looking for captures that have either:
-        // 1. A measurement unit, or
-        // 2. Ingredient text after the quantity
-        for capture in self.pattern.captures_iter(text) {
-            let measurement = capture.name("measurement");
-            if measurement.is_some() {
-                // Has a measurement unit
-                return true;
-            }
-
-            // Check if there's ingredient text after the match
-            let full_match = capture
-                .get(0)
-                .expect("Full match should always be available in regex capture");
-            let match_end = full_match.end();
-            if match_end < text.len() {
-                let remaining = &text[match_end..];
-                if !remaining.trim().is_empty() {
-                    // Has ingredient text after
-                    return true;
-                }
-            }
-        }
-        false
+        // Agrees with extract_ingredient_measurements: skipped lines don't count, and a
+        // capture needs a measurement unit or ingredient text after its quantity
+        text.lines()
+            .filter(|line| !self.is_skipped_line(line))
+            .any(|line| {
+                let number_word_line = self.number_word_line(line);
+                let line = number_word_line.as_deref().unwrap_or(line);
+                self.pattern.captures_iter(line).any(|capture| {
+                    let match_end = capture
+                        .get(0)
+                        .expect("Full match should always be available in regex capture")
+                        .end();
+                    let remaining = line[match_end..].trim_start();
+                    (capture.name("measurement").is_some() || !remaining.is_empty())
+                        && ingredient_text(remaining)
+                            .chars()
+                            .filter(|c| c.is_ascii_digit())
+                            .count()
+                            <= 2
+                })
+            })
     }
 
     /// Check if a line starts with a measurement pattern
@@ -1089,14 +1696,133 @@ impl MeasurementDetector {
     /// ```
     pub fn is_measurement_line(&self, line: &str) -> bool {
         // Check if the line starts with a measurement pattern
-        // We look for captures at the beginning of the line (start position 0)
-        if let Some(capture) = self.pattern.captures(line) {
-            if let Some(full_match) = capture.get(0) {
-                // The measurement must start at the beginning of the line
-                return full_match.start() == 0;
+        // The measurement must start at the beginning of the line (start position 0)
+        self.pattern
+            .find(line)
+            .is_some_and(|full_match| full_match.start() == 0)
+            || self.convert_number_word(line).is_some()
+    }
+
+    /// Join a bare quantity with the unit wrapped onto the next line
+    ///
+    /// OCR sometimes breaks a line between the quantity and its unit, leaving "2" on
+    /// one line and "cups sugar" on the next. Returns the joined line ("2 cups sugar")
+    /// when the line at `idx` holds nothing but a quantity and the next line starts
+    /// with a known unit, or `None` when joining is disabled, would exceed
+    /// `max_combine_lines`, or the lines do not look wrapped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use just_ingredients::text_processing::MeasurementDetector;
+    ///
+    /// let detector = MeasurementDetector::new()?;
+    /// let lines = ["1 1/2", "tsp salt"];
+    /// assert_eq!(
+    ///     detector.wrapped_quantity_line(&lines, 0),
+    ///     Some("1 1/2 tsp salt".to_string())
+    /// );
+    /// assert_eq!(detector.wrapped_quantity_line(&["2", "eggs"], 0), None);
+    /// # Ok::<(), regex::Error>(())
+    /// ```
+    pub fn wrapped_quantity_line(&self, lines: &[&str], idx: usize) -> Option<String> {
+        if !self.config.join_wrapped_quantities || self.config.max_combine_lines < 2 {
+            return None;
+        }
+
+        let quantity = lines.get(idx)?.trim();
+        let next_line = lines.get(idx + 1)?.trim();
+        if quantity.is_empty() || next_line.is_empty() || self.is_measurement_line(next_line) {
+            return None;
+        }
+
+        // The whole line must be a quantity, without unit or ingredient text
+        let capture = self.pattern.captures(quantity)?;
+        let full_match = capture.get(0)?;
+        if full_match.start() != 0
+            || full_match.end() != quantity.len()
+            || capture.name("measurement").is_some()
+        {
+            return None;
+        }
+
+        // Joined, the next line must contribute the unit of the measurement
+        let joined = format!("{} {}", quantity, next_line);
+        let capture = self.pattern.captures(&joined)?;
+        (capture.get(0)?.start() == 0 && capture.name("measurement").is_some()).then_some(joined)
+    }
+
+    /// Read a quantity spelled out at the start of a line as a number
+    ///
+    /// OCR of stylized cookbooks gives "two cups flour" or "une pincée de sel", which
+    /// the quantity pattern alone misses. Returns the line with its number words replaced
+    /// ("2 cups flour"), or `None` when number words are disabled or the line does not
+    /// open with a quantity that way. "a", "an", "un" and "une" only count before a
+    /// unit, so "a little salt" is left alone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use just_ingredients::text_processing::MeasurementDetector;
+    ///
+    /// let detector = MeasurementDetector::new()?;
+    /// assert_eq!(
+    ///     detector.number_word_line("two cups flour"),
+    ///     Some("2 cups flour".to_string())
+    /// );
+    /// assert_eq!(
+    ///     detector.number_word_line("une pincée de sel"),
+    ///     Some("1 pincée de sel".to_string())
+    /// );
+    /// assert_eq!(detector.number_word_line("a little salt"), None);
+    /// assert_eq!(detector.number_word_line("2 cups flour"), None);
+    /// # Ok::<(), regex::Error>(())
+    /// ```
+    pub fn number_word_line(&self, line: &str) -> Option<String> {
+        self.convert_number_word(line)
+            .map(|(converted, _)| converted)
+    }
+
+    /// [`Self::number_word_line`], with the byte offset from which the converted line is
+    /// shifted against the original
+    fn convert_number_word(&self, line: &str) -> Option<(String, usize)> {
+        if !self.config.parse_number_words {
+            return None;
+        }
+        let (start, end, value, article) = leading_number_word(line)?;
+        let replacement = if line[..end].ends_with('-') {
+            format!("{} ", value)
+        } else {
+            value.to_string()
+        };
+        let converted = format!("{}{}{}", &line[..start], replacement, &line[end..]);
+
+        // The number must start a measurement, and without a unit be followed by an ingredient
+        let capture = self.pattern.captures(&converted[start..])?;
+        let full_match = capture.get(0)?;
+        if full_match.start() != 0 {
+            return None;
+        }
+        if capture.name("measurement").is_none() {
+            let remaining = converted[start + full_match.end()..].trim_start();
+            let next_word = remaining
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap_or_default()
+                .to_lowercase();
+            if article || remaining.is_empty() || NOT_INGREDIENT_WORDS.contains(&next_word.as_str())
+            {
+                return None;
             }
         }
-        false
+
+        // "Two hours" is still a cooking time once read as "2 hours"
+        if self.is_skipped_line(&converted) {
+            return None;
+        }
+
+        let shifted_from = start + replacement.len();
+        Some((converted, shifted_from))
     }
 
     /// Check if an ingredient text appears incomplete (likely continues on next line)
@@ -1155,6 +1881,7 @@ impl MeasurementDetector {
     ///
     /// The function stops combining lines when it encounters:
     /// - A new measurement line (starts a new ingredient)
+    /// - A section header or instruction line, when those are skipped
     /// - An empty line or whitespace-only line
     /// - A punctuation-only line (contains only punctuation marks)
     /// - Combined text that ends with completion punctuation
@@ -1166,6 +1893,8 @@ impl MeasurementDetector {
     /// - **Punctuation-only lines**: Single characters like "." or ")" terminate combination
     /// - **Very long ingredients**: Limited by configurable max_combine_lines to prevent excessive processing
     /// - **Backward compatibility**: Single-line ingredients work unchanged
+    /// - **Wrapped quantities**: A bare quantity line is joined with a next line starting
+    ///   with a unit, and both count towards max_combine_lines
     ///
     /// # Arguments
     ///
@@ -1198,27 +1927,30 @@ impl MeasurementDetector {
             return (String::new(), 0);
         }
 
-        let first_line = lines[start_idx].trim();
-
-        // Extract ingredient text from first line (everything after the measurement)
-        let ingredient_start = if let Some(capture) = self.pattern.captures(first_line) {
-            if let Some(full_match) = capture.get(0) {
-                full_match.end()
-            } else {
-                0
+        // A quantity wrapped away from its unit starts the ingredient on two lines, and
+        // a spelled-out one is read as a number so it isn't taken for ingredient text
+        let (first_line, mut lines_consumed) = match self.wrapped_quantity_line(lines, start_idx) {
+            Some(joined) => (joined, 2),
+            None => {
+                let line = lines[start_idx].trim();
+                let converted = self.number_word_line(line);
+                (converted.unwrap_or_else(|| line.to_string()), 1)
             }
-        } else {
-            0
         };
 
+        // Extract ingredient text from first line (everything after the measurement)
+        let ingredient_start = self
+            .pattern
+            .find(&first_line)
+            .map_or(0, |full_match| full_match.end());
+
         let mut combined_ingredient = first_line[ingredient_start..].trim().to_string();
-        let mut lines_consumed = 1;
 
         // Maximum lines to combine (prevents runaway processing for very long ingredients)
         let max_combine_lines = self.config.max_combine_lines;
 
         // Continue reading lines until termination condition
-        for current_line in lines.iter().skip(start_idx + 1) {
+        for current_line in lines.iter().skip(start_idx + lines_consumed) {
             // Safety check: don't combine too many lines
             if lines_consumed >= max_combine_lines {
                 break;
@@ -1248,11 +1980,17 @@ impl MeasurementDetector {
                 break;
             }
 
+            // Termination condition 4: Section header or method step
+            if self.is_skipped_line(current_line) {
+                break;
+            }
+
             // Add this line to the combined ingredient
-            combined_ingredient = format!("{} {}", combined_ingredient, current_line);
+            combined_ingredient.push(' ');
+            combined_ingredient.push_str(current_line);
             lines_consumed += 1;
 
-            // Termination condition 4: Combined text is now complete
+            // Termination condition 5: Combined text is now complete
             if !self.is_incomplete_ingredient(&combined_ingredient) {
                 break;
             }
@@ -1261,6 +1999,37 @@ impl MeasurementDetector {
         (combined_ingredient, lines_consumed)
     }
 
+    /// Classify a line of recipe text before measurement extraction
+    ///
+    /// A line ending with a colon is a header, so "For the topping:" is skipped while
+    /// "Flour: 2 cups" is not. Temperatures and durations mark a line as an instruction
+    /// wherever they appear, since no ingredient is measured in minutes. A line opening
+    /// with a verb is an instruction unless it measures something in a known unit, so
+    /// "Preheat oven to 350" is skipped but "Mix 2 cups flour" is kept. A numbered
+    /// step opening with a verb is an instruction even when it measures something.
+    pub fn classify_line(&self, line: &str) -> LineKind {
+        let line = line.trim();
+        if line.ends_with(':') || HEADER_PATTERN.is_match(line) {
+            LineKind::Header
+        } else if TEMPERATURE_OR_DURATION_PATTERN.is_match(line)
+            || (INSTRUCTION_VERB_PATTERN.is_match(line)
+                && (NUMBERED_STEP_PATTERN.is_match(line)
+                    || !self
+                        .pattern
+                        .captures_iter(line)
+                        .any(|capture| capture.name("measurement").is_some())))
+        {
+            LineKind::Instruction
+        } else {
+            LineKind::Ingredient
+        }
+    }
+
+    /// Whether a line is a header or instruction that extraction skips
+    fn is_skipped_line(&self, line: &str) -> bool {
+        self.config.skip_non_ingredient_lines && self.classify_line(line) != LineKind::Ingredient
+    }
+
     /// Post-process an ingredient name to clean it up
     ///
     /// This function implements a multi-stage ingredient name cleaning algorithm that
@@ -1311,6 +2080,12 @@ impl MeasurementDetector {
     /// - `"au "`, `"aux "` → "au miel" → "miel"
     /// - `"un "`, `"une "` → "un oeuf" → "oeuf"
     ///
+    /// **German Prefixes**:
+    /// - `"von "`, `"vom "` → "von Zitronen" → "Zitronen"
+    ///
+    /// **Spanish Prefixes** (besides `"de "`, `"la "` and `"un "` shared with French):
+    /// - `"del "`, `"el "`, `"los "`, `"las "`, `"una "` → "del limón" → "limón"
+    ///
     /// **Processing Rules**:
     /// - Only remove one prefix per ingredient
     /// - Case-insensitive matching
@@ -1407,29 +2182,34 @@ impl MeasurementDetector {
     ///
     /// Returns a cleaned and normalized ingredient name string
     fn post_process_ingredient_name(&self, raw_name: &str) -> String {
-        if !self.config.enable_ingredient_postprocessing || raw_name.trim().is_empty() {
+        let original_name = raw_name.trim();
+        if !self.config.enable_ingredient_postprocessing || original_name.is_empty() {
             trace!("Post-processing disabled or empty name: '{}'", raw_name);
-            return raw_name.trim().to_string();
+            return original_name.to_string();
         }
 
-        let mut name = raw_name.trim().to_string();
-        let original_name = name.clone();
-
         // Remove trailing punctuation
-        name = name
-            .trim_end_matches(|c: char| !c.is_alphanumeric() && c != ' ' && c != '-' && c != '\'')
-            .to_string();
+        let mut name = original_name
+            .trim_end_matches(|c: char| !c.is_alphanumeric() && c != ' ' && c != '-' && c != '\'');
 
         // Common prepositions and articles to remove (English and French)
         let prefixes_to_remove = [
             // English
             "of ", "the ", "a ", "an ", // French
             "de ", "d'", "du ", "des ", "la ", "le ", "les ", "l'", "au ", "aux ", "un ", "une ",
+            // German
+            "von ", "vom ", // Spanish ("de", "la" and "un" are shared with French)
+            "del ", "el ", "los ", "las ", "una ",
         ];
 
         for prefix in &prefixes_to_remove {
-            if name.to_lowercase().starts_with(prefix) {
-                name = name[prefix.len()..].trim_start().to_string();
+            // Prefixes are ASCII, so an ASCII case-insensitive comparison of the leading
+            // bytes avoids lowercasing the whole name for every candidate
+            if name
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+            {
+                name = name[prefix.len()..].trim_start();
                 debug!(
                     "Removed prefix '{}' from ingredient name: '{}' -> '{}'",
                     prefix.trim(),
@@ -1442,13 +2222,17 @@ impl MeasurementDetector {
 
         // Limit length to prevent overly long extractions
         if name.len() > self.config.max_ingredient_length {
-            let truncated = name[..self.config.max_ingredient_length].to_string();
-            // Try to cut at word boundary
-            if let Some(last_space) = truncated.rfind(' ') {
-                name = truncated[..last_space].to_string();
-            } else {
-                name = truncated;
+            // Cut on a character boundary, accented names having multi-byte characters
+            let mut cut = self.config.max_ingredient_length;
+            while !name.is_char_boundary(cut) {
+                cut -= 1;
             }
+            let truncated = &name[..cut];
+            // Try to cut at word boundary
+            name = match truncated.rfind(' ') {
+                Some(last_space) => &truncated[..last_space],
+                None => truncated,
+            };
             warn!(
                 "Ingredient name truncated due to length limit ({} > {}): '{}' -> '{}'",
                 original_name.len(),
@@ -1458,15 +2242,21 @@ impl MeasurementDetector {
             );
         }
 
-        // Clean up multiple spaces
-        name = name.split_whitespace().collect::<Vec<&str>>().join(" ");
+        // Clean up multiple spaces while building the only owned copy of the name
+        let mut cleaned = String::with_capacity(name.len());
+        for word in name.split_whitespace() {
+            if !cleaned.is_empty() {
+                cleaned.push(' ');
+            }
+            cleaned.push_str(word);
+        }
 
         trace!(
             "Post-processed ingredient name: '{}' -> '{}'",
             original_name,
-            name
+            cleaned
         );
-        name.trim().to_string()
+        cleaned
     }
 
     /// Post-process a quantity string to correct common OCR errors in fractions
@@ -1507,10 +2297,13 @@ impl MeasurementDetector {
         // Handle mixed numbers (digit + Unicode fraction) specially
         // Replace Unicode fraction in mixed numbers with space + ASCII fraction
         for (unicode, ascii) in &unicode_fractions {
+            // Most quantities are plain ASCII; only rebuild the string when needed
+            if !corrected.contains(unicode) {
+                continue;
+            }
+
             // For mixed numbers like "1½", replace with "1 1/2"
-            if corrected.contains(unicode)
-                && corrected.chars().next().unwrap_or(' ').is_ascii_digit()
-            {
+            if corrected.chars().next().unwrap_or(' ').is_ascii_digit() {
                 corrected = corrected.replace(unicode, &format!(" {}", ascii));
             } else {
                 corrected = corrected.replace(unicode, ascii);
@@ -1551,10 +2344,12 @@ impl MeasurementDetector {
 
         // Additional validation: ensure fractions are in valid format
         if corrected.contains('/') {
-            let parts: Vec<&str> = corrected.split('/').collect();
-            if parts.len() == 2 {
+            let mut parts = corrected.split('/');
+            if let (Some(numerator), Some(denominator), None) =
+                (parts.next(), parts.next(), parts.next())
+            {
                 // Validate numerator and denominator are numeric
-                if let (Ok(_), Ok(_)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
+                if let (Ok(_), Ok(_)) = (numerator.parse::<u32>(), denominator.parse::<u32>()) {
                     // Valid fraction format
                 } else {
                     warn!("Invalid fraction format detected: '{}'", corrected);
@@ -1698,15 +2493,19 @@ impl MeasurementDetector {
         let mut units = HashSet::new();
         for capture in self.pattern.captures_iter(text) {
             let quantity = capture.name("quantity").map(|m| m.as_str()).unwrap_or("");
-            let corrected_quantity = self.post_process_quantity(quantity);
-            let measurement = capture.name("measurement").map(|m| m.as_str());
+            let mut unit = self.post_process_quantity(quantity);
+            if let Some(measurement) = capture.name("measurement") {
+                unit.push(' ');
+                unit.push_str(measurement.as_str());
+            }
 
-            let unit = if let Some(measurement) = measurement {
-                format!("{} {}", corrected_quantity, measurement)
+            // Lowercase in place for the common ASCII case
+            if unit.is_ascii() {
+                unit.make_ascii_lowercase();
             } else {
-                corrected_quantity
-            };
-            units.insert(unit.to_lowercase());
+                unit = unit.to_lowercase();
+            }
+            units.insert(unit);
         }
         units
     }
@@ -1748,15 +2547,21 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    fn unit(name: &str) -> UnitEntry {
+        UnitEntry::new(name, UnitDimension::Count, UnitSystem::Neutral)
+    }
+
     #[test]
     fn test_measurement_units_config_validation() {
         let mut config = MeasurementUnitsConfig {
             measurement_units: MeasurementUnits {
-                volume_units: vec!["cup".to_string(), "tablespoon".to_string()],
-                weight_units: vec!["g".to_string(), "kg".to_string()],
-                volume_units_metric: vec!["l".to_string(), "ml".to_string()],
-                us_units: vec!["slice".to_string()],
-                french_units: vec!["sachet".to_string()],
+                volume_units: vec![unit("cup"), unit("tablespoon")],
+                weight_units: vec![unit("g"), unit("kg")],
+                volume_units_metric: vec![unit("l"), unit("ml")],
+                us_units: vec![unit("slice")],
+                french_units: vec![unit("sachet")],
+                german_units: vec![],
+                spanish_units: vec![],
             },
         };
 
@@ -1766,37 +2571,45 @@ mod tests {
         // Test empty volume_units
         config.measurement_units.volume_units = vec![];
         assert!(config.validate().is_err());
-        config.measurement_units.volume_units = vec!["cup".to_string()];
+        config.measurement_units.volume_units = vec![unit("cup")];
 
         // Test empty weight_units
         config.measurement_units.weight_units = vec![];
         assert!(config.validate().is_err());
-        config.measurement_units.weight_units = vec!["g".to_string()];
+        config.measurement_units.weight_units = vec![unit("g")];
 
         // Test empty volume_units_metric
         config.measurement_units.volume_units_metric = vec![];
         assert!(config.validate().is_err());
-        config.measurement_units.volume_units_metric = vec!["l".to_string()];
+        config.measurement_units.volume_units_metric = vec![unit("l")];
 
         // Test empty us_units
         config.measurement_units.us_units = vec![];
         assert!(config.validate().is_err());
-        config.measurement_units.us_units = vec!["slice".to_string()];
+        config.measurement_units.us_units = vec![unit("slice")];
 
         // Test empty french_units
         config.measurement_units.french_units = vec![];
         assert!(config.validate().is_err());
-        config.measurement_units.french_units = vec!["sachet".to_string()];
+        config.measurement_units.french_units = vec![unit("sachet")];
 
         // Test empty unit string
-        config.measurement_units.volume_units = vec!["".to_string()];
+        config.measurement_units.volume_units = vec![unit("")];
         assert!(config.validate().is_err());
-        config.measurement_units.volume_units = vec!["cup".to_string()];
+        config.measurement_units.volume_units = vec![unit("cup")];
 
         // Test invalid characters in unit (control character)
-        config.measurement_units.volume_units = vec!["cup\ntablespoon".to_string()];
+        config.measurement_units.volume_units = vec![unit("cup\ntablespoon")];
+        assert!(config.validate().is_err());
+        config.measurement_units.volume_units = vec![unit("cup")];
+
+        // Test conflicting classifications of the same unit
+        config.measurement_units.us_units.push(UnitEntry::new(
+            "CUP",
+            UnitDimension::Volume,
+            UnitSystem::Us,
+        ));
         assert!(config.validate().is_err());
-        config.measurement_units.volume_units = vec!["cup".to_string()];
     }
 
     #[test]
@@ -1814,6 +2627,54 @@ mod tests {
             ..Default::default()
         };
         assert!(MeasurementDetector::with_config(invalid_config).is_err());
+
+        // Test with an unknown unit language
+        let invalid_config = MeasurementConfig {
+            unit_languages: Some(vec!["klingon".to_string()]),
+            ..Default::default()
+        };
+        assert!(MeasurementDetector::with_config(invalid_config).is_err());
+    }
+
+    #[test]
+    fn test_units_alternation_is_longest_first() {
+        let units = [
+            unit("cucharada"),
+            unit("cucharaditas"),
+            unit("Teel."),
+            unit("cucharadita"),
+            unit("cucharada"),
+        ];
+
+        assert_eq!(
+            units_alternation(units.iter()),
+            r"cucharaditas|cucharadita|cucharada|Teel\."
+        );
+    }
+
+    #[test]
+    fn test_entries_for_languages_keeps_english_and_metric_units() {
+        let units = MeasurementUnits {
+            volume_units: vec![unit("cup")],
+            weight_units: vec![unit("g")],
+            volume_units_metric: vec![unit("ml")],
+            us_units: vec![unit("slice")],
+            french_units: vec![unit("sachet")],
+            german_units: vec![unit("Prise")],
+            spanish_units: vec![unit("pizca")],
+        };
+        let names = |languages: &[String]| -> Vec<String> {
+            units
+                .entries_for_languages(languages)
+                .map(|entry| entry.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(&["en".to_string()]), ["cup", "g", "ml", "slice"]);
+        assert_eq!(
+            names(&["es".to_string(), "de".to_string()]),
+            ["cup", "g", "ml", "slice", "Prise", "pizca"]
+        );
     }
 
     #[test]
@@ -0,0 +1,288 @@
+//! # Time Zone Module
+//!
+//! Shows dates in the user's own time zone, following their `/timezone` choice, and
+//! works out the "today" and "this week" boundaries of their statistics. Dates are
+//! always stored in UTC.
+//!
+//! A time zone is a fixed offset from UTC between UTC-12:00 and UTC+14:00, in steps
+//! of 15 minutes. Fixed offsets don't follow daylight saving time, so users change
+//! theirs when the clocks change.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, Utc};
+
+use crate::localization::{t_args_lang, t_lang, LocalizationManager};
+
+/// Westernmost offset accepted, in minutes east of UTC
+pub const MIN_OFFSET_MINUTES: i32 = -12 * 60;
+
+/// Easternmost offset accepted, in minutes east of UTC
+pub const MAX_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// The UTC offset, used until a user picks a time zone
+pub fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).expect("zero is a valid offset")
+}
+
+/// Offset of `minutes` east of UTC, `None` outside UTC-12:00..=UTC+14:00 or off the quarter hour
+pub fn offset_from_minutes(minutes: i32) -> Option<FixedOffset> {
+    if !(MIN_OFFSET_MINUTES..=MAX_OFFSET_MINUTES).contains(&minutes) || minutes % 15 != 0 {
+        return None;
+    }
+    FixedOffset::east_opt(minutes * 60)
+}
+
+/// Minutes east of UTC of an offset
+pub fn offset_minutes(offset: FixedOffset) -> i32 {
+    offset.local_minus_utc() / 60
+}
+
+/// Parse an offset typed by a user, e.g. "UTC+12", "gmt-3", "+5:30", "-0330" or "UTC"
+pub fn parse_utc_offset(input: &str) -> Option<FixedOffset> {
+    let input = input.trim().to_ascii_uppercase();
+    let rest = input
+        .strip_prefix("UTC")
+        .or_else(|| input.strip_prefix("GMT"))
+        .unwrap_or(&input)
+        .trim_start();
+    if rest.is_empty() || rest == "0" || rest == "Z" {
+        return Some(utc());
+    }
+
+    let (sign, rest) = match rest.as_bytes()[0] {
+        b'+' => (1, &rest[1..]),
+        b'-' => (-1, &rest[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        // "+0530" is hours and minutes, "+5" and "+12" hours only
+        None if rest.len() > 2 => rest.split_at(rest.len() - 2),
+        None => (rest, "0"),
+    };
+    if hours.is_empty()
+        || hours.len() > 2
+        || minutes.len() > 2
+        || !hours
+            .chars()
+            .chain(minutes.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+    offset_from_minutes(sign * (hours * 60 + minutes))
+}
+
+/// Display name of an offset, e.g. "UTC", "UTC+12" or "UTC-3:30"
+pub fn format_utc_offset(offset: FixedOffset) -> String {
+    let minutes = offset_minutes(offset);
+    if minutes == 0 {
+        return "UTC".to_string();
+    }
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let (hours, minutes) = (minutes.abs() / 60, minutes.abs() % 60);
+    if minutes == 0 {
+        format!("UTC{sign}{hours}")
+    } else {
+        format!("UTC{sign}{hours}:{minutes:02}")
+    }
+}
+
+/// Start of the user's day containing `now`, as a UTC instant
+pub fn local_day_start(now: DateTime<Utc>, offset: FixedOffset) -> DateTime<Utc> {
+    now.with_timezone(&offset)
+        .date_naive()
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(offset)
+        .single()
+        .expect("fixed offsets map local times to a single instant")
+        .with_timezone(&Utc)
+}
+
+/// Starts of the periods counted by the recipe statistics, in the user's time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatisticsWindows {
+    /// Local midnight today
+    pub today: DateTime<Utc>,
+    /// Local midnight 6 days ago: the last 7 days, today included
+    pub week: DateTime<Utc>,
+    /// Local midnight 29 days ago: the last 30 days, today included
+    pub month: DateTime<Utc>,
+}
+
+impl StatisticsWindows {
+    /// Windows ending at `now` for a user at `offset`
+    pub fn new(now: DateTime<Utc>, offset: FixedOffset) -> Self {
+        let today = local_day_start(now, offset);
+        Self {
+            today,
+            week: today - Duration::days(6),
+            month: today - Duration::days(29),
+        }
+    }
+}
+
+/// Localization key of a month name, `month` being 1 for January
+fn month_key(month: u32) -> String {
+    format!("month-{}", month.clamp(1, 12))
+}
+
+/// Date and time in the user's time zone with the month spelled out, e.g. "March 5, 2026 at 14:30"
+pub fn format_local_datetime(
+    datetime: DateTime<Utc>,
+    offset: FixedOffset,
+    language_code: Option<&str>,
+    localization: &Arc<LocalizationManager>,
+) -> String {
+    let local = datetime.with_timezone(&offset);
+    t_args_lang(
+        localization,
+        "datetime-long",
+        &[
+            (
+                "month",
+                &t_lang(localization, &month_key(local.month()), language_code),
+            ),
+            ("day", &local.day().to_string()),
+            ("year", &local.year().to_string()),
+            ("time", &local.format("%H:%M").to_string()),
+        ],
+        language_code,
+    )
+}
+
+/// Date and time in the user's time zone in numbers, e.g. "05/03/26 14:30"
+///
+/// Used where space is short, such as button labels.
+pub fn format_local_datetime_short(datetime: DateTime<Utc>, offset: FixedOffset) -> String {
+    datetime
+        .with_timezone(&offset)
+        .format("%d/%m/%y %H:%M")
+        .to_string()
+}
+
+/// Date in the user's time zone in numbers, e.g. "05/03/26"
+pub fn format_local_date_short(datetime: DateTime<Utc>, offset: FixedOffset) -> String {
+    datetime
+        .with_timezone(&offset)
+        .format("%d/%m/%y")
+        .to_string()
+}
+
+/// Date in the user's time zone as ISO 8601, e.g. "2026-03-05"
+pub fn format_local_date_iso(datetime: DateTime<Utc>, offset: FixedOffset) -> String {
+    datetime
+        .with_timezone(&offset)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn offset(minutes: i32) -> FixedOffset {
+        offset_from_minutes(minutes).unwrap()
+    }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("UTC"), Some(utc()));
+        assert_eq!(parse_utc_offset("gmt"), Some(utc()));
+        assert_eq!(parse_utc_offset("0"), Some(utc()));
+        assert_eq!(parse_utc_offset("UTC+12"), Some(offset(12 * 60)));
+        assert_eq!(parse_utc_offset("utc -3"), Some(offset(-3 * 60)));
+        assert_eq!(parse_utc_offset("+5:30"), Some(offset(5 * 60 + 30)));
+        assert_eq!(parse_utc_offset("+0545"), Some(offset(5 * 60 + 45)));
+        assert_eq!(parse_utc_offset("-03:30"), Some(offset(-(3 * 60 + 30))));
+        assert_eq!(parse_utc_offset("UTC+14"), Some(offset(14 * 60)));
+
+        // Out of range, off the quarter hour or not an offset
+        assert_eq!(parse_utc_offset("UTC+15"), None);
+        assert_eq!(parse_utc_offset("UTC-13"), None);
+        assert_eq!(parse_utc_offset("+5:10"), None);
+        assert_eq!(parse_utc_offset("+5:75"), None);
+        assert_eq!(parse_utc_offset("5"), None);
+        assert_eq!(parse_utc_offset("Europe/Paris"), None);
+        assert_eq!(parse_utc_offset("+"), None);
+        assert_eq!(parse_utc_offset("+1a"), None);
+    }
+
+    #[test]
+    fn test_format_utc_offset() {
+        assert_eq!(format_utc_offset(utc()), "UTC");
+        assert_eq!(format_utc_offset(offset(12 * 60)), "UTC+12");
+        assert_eq!(format_utc_offset(offset(-3 * 60 - 30)), "UTC-3:30");
+        assert_eq!(format_utc_offset(offset(5 * 60 + 45)), "UTC+5:45");
+        for minutes in [0, 60, -90, 345, 14 * 60, -12 * 60] {
+            assert_eq!(
+                parse_utc_offset(&format_utc_offset(offset(minutes))),
+                Some(offset(minutes))
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_boundaries_around_midnight() {
+        // 11:30 UTC is already 23:30 in UTC+12, the day started at 12:00 UTC the day before
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 11, 30, 0).unwrap();
+        assert_eq!(
+            local_day_start(now, offset(12 * 60)),
+            Utc.with_ymd_and_hms(2026, 3, 9, 12, 0, 0).unwrap()
+        );
+        // Half an hour later it is the next day there
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 30, 0).unwrap();
+        assert_eq!(
+            local_day_start(now, offset(12 * 60)),
+            Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap()
+        );
+        // West of UTC the day starts later, and may still be the day before
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 2, 0, 0).unwrap();
+        assert_eq!(
+            local_day_start(now, offset(-5 * 60)),
+            Utc.with_ymd_and_hms(2026, 3, 9, 5, 0, 0).unwrap()
+        );
+        assert_eq!(
+            local_day_start(now, utc()),
+            Utc.with_ymd_and_hms(2026, 3, 10, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_statistics_windows() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 11, 0, 0).unwrap();
+        let windows = StatisticsWindows::new(now, offset(12 * 60));
+        assert_eq!(
+            windows.today,
+            Utc.with_ymd_and_hms(2025, 12, 31, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            windows.week,
+            Utc.with_ymd_and_hms(2025, 12, 25, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            windows.month,
+            Utc.with_ymd_and_hms(2025, 12, 2, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_local_dates_cross_midnight() {
+        // Saved at 23:50 UTC on New Year's Eve, it is already the new year in UTC+1
+        let saved = Utc.with_ymd_and_hms(2025, 12, 31, 23, 50, 0).unwrap();
+        assert_eq!(format_local_date_iso(saved, utc()), "2025-12-31");
+        assert_eq!(format_local_date_iso(saved, offset(60)), "2026-01-01");
+        assert_eq!(
+            format_local_datetime_short(saved, offset(60)),
+            "01/01/26 00:50"
+        );
+        assert_eq!(format_local_date_short(saved, offset(-60)), "31/12/25");
+    }
+}
@@ -0,0 +1,510 @@
+//! # Transcription Module
+//!
+//! Speech-to-text for voice messages, so an ingredient list can be dictated
+//! instead of photographed. The transcript goes through the same extraction and
+//! review as a photo's OCR text.
+//!
+//! The backend is chosen with `TRANSCRIPTION_BACKEND`:
+//! - `whisper_cpp`: runs the whisper.cpp CLI (`WHISPER_CPP_PATH`, default
+//!   `whisper-cli`) with the model at `WHISPER_MODEL_PATH`. Telegram voice notes
+//!   are OGG/Opus, so they are converted to 16 kHz WAV with `FFMPEG_PATH`
+//!   (default `ffmpeg`) first.
+//! - `http`: posts the audio file to `TRANSCRIPTION_URL` and reads the `text`
+//!   field of the JSON reply. `TRANSCRIPTION_API_KEY` is sent as a bearer token
+//!   when set.
+//!
+//! Without a backend, voice messages are answered with a "not available" reply.
+//! Transcription runs under the same timeout and circuit breaker settings as OCR.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::ocr_config::RecoveryConfig;
+
+/// Errors from transcribing a voice message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptionError {
+    /// No transcription backend is configured on this server
+    NotConfigured,
+    /// The backend failed or returned no usable text
+    Failed(String),
+    /// The backend did not answer within the operation timeout
+    Timeout,
+    /// Transcription is rejected while the circuit breaker is open
+    CircuitOpen,
+}
+
+impl TranscriptionError {
+    /// Label of the variant in metrics
+    pub fn error_type(&self) -> &'static str {
+        match self {
+            TranscriptionError::NotConfigured => "not_configured",
+            TranscriptionError::Failed(_) => "failed",
+            TranscriptionError::Timeout => "timeout",
+            TranscriptionError::CircuitOpen => "circuit_open",
+        }
+    }
+}
+
+impl std::fmt::Display for TranscriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptionError::NotConfigured => {
+                write!(f, "[TRANSCRIPTION] No transcription backend is configured")
+            }
+            TranscriptionError::Failed(msg) => {
+                write!(f, "[TRANSCRIPTION] Transcription failed: {}", msg)
+            }
+            TranscriptionError::Timeout => write!(f, "[TRANSCRIPTION] Transcription timed out"),
+            TranscriptionError::CircuitOpen => {
+                write!(f, "[TRANSCRIPTION] Transcription temporarily unavailable")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptionError {}
+
+/// Speech-to-text backend
+#[async_trait]
+pub trait Transcriber: Send + Sync {
+    /// Transcribe the audio file at `audio_path`
+    ///
+    /// `language` is a two-letter hint such as `en` or `fr`; `None` lets the
+    /// backend detect the language.
+    async fn transcribe(&self, audio_path: &str, language: Option<&str>) -> anyhow::Result<String>;
+
+    /// Backend name used in logs and metrics
+    fn name(&self) -> &'static str;
+}
+
+/// Runs the whisper.cpp command line tool on the local machine
+pub struct WhisperCppTranscriber {
+    binary: PathBuf,
+    model: PathBuf,
+    ffmpeg: PathBuf,
+}
+
+impl WhisperCppTranscriber {
+    /// Create a backend running `binary` with the model file at `model`
+    pub fn new(binary: PathBuf, model: PathBuf, ffmpeg: PathBuf) -> Self {
+        Self {
+            binary,
+            model,
+            ffmpeg,
+        }
+    }
+}
+
+#[async_trait]
+impl Transcriber for WhisperCppTranscriber {
+    async fn transcribe(&self, audio_path: &str, language: Option<&str>) -> anyhow::Result<String> {
+        // whisper.cpp only reads 16 kHz mono WAV
+        let wav = tempfile::Builder::new().suffix(".wav").tempfile()?;
+        let converted = tokio::process::Command::new(&self.ffmpeg)
+            .args(["-y", "-loglevel", "error", "-i", audio_path])
+            .args(["-ar", "16000", "-ac", "1", "-c:a", "pcm_s16le"])
+            .arg(wav.path())
+            .kill_on_drop(true)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", self.ffmpeg.display()))?;
+        if !converted.status.success() {
+            anyhow::bail!(
+                "Audio conversion failed: {}",
+                String::from_utf8_lossy(&converted.stderr).trim()
+            );
+        }
+
+        let output = tokio::process::Command::new(&self.binary)
+            .arg("-m")
+            .arg(&self.model)
+            .arg("-f")
+            .arg(wav.path())
+            .args(["-l", language.unwrap_or("auto")])
+            .args(["--no-timestamps", "--no-prints"])
+            .kill_on_drop(true)
+            .output()
+            .await
+            .with_context(|| format!("Failed to run {}", self.binary.display()))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "whisper.cpp exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn name(&self) -> &'static str {
+        "whisper_cpp"
+    }
+}
+
+/// Posts the audio to a speech-to-text HTTP service
+///
+/// The request body is the raw audio with the language hint as a `language`
+/// query parameter; the reply must be JSON with a `text` field.
+pub struct HttpTranscriber {
+    client: reqwest::Client,
+    url: reqwest::Url,
+    api_key: Option<String>,
+}
+
+impl HttpTranscriber {
+    /// Create a backend posting to `url`
+    pub fn new(url: reqwest::Url, api_key: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            api_key,
+        }
+    }
+}
+
+/// Reply of the HTTP transcription service
+#[derive(serde::Deserialize)]
+struct HttpTranscriptionResponse {
+    text: String,
+}
+
+#[async_trait]
+impl Transcriber for HttpTranscriber {
+    async fn transcribe(&self, audio_path: &str, language: Option<&str>) -> anyhow::Result<String> {
+        let audio = tokio::fs::read(audio_path).await?;
+        let mut request = self
+            .client
+            .post(self.url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "audio/ogg")
+            .body(audio);
+        if let Some(language) = language {
+            request = request.query(&[("language", language)]);
+        }
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?.error_for_status()?;
+        let body: HttpTranscriptionResponse = response
+            .json()
+            .await
+            .context("Transcription service reply has no text field")?;
+        Ok(body.text)
+    }
+
+    fn name(&self) -> &'static str {
+        "http"
+    }
+}
+
+/// Backend selected in the environment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptionBackend {
+    /// Local whisper.cpp binary and model
+    WhisperCpp {
+        binary: PathBuf,
+        model: PathBuf,
+        ffmpeg: PathBuf,
+    },
+    /// Remote speech-to-text service
+    Http {
+        url: reqwest::Url,
+        api_key: Option<String>,
+    },
+}
+
+/// Raw transcription settings, as read from the environment
+#[derive(Debug, Default, Clone)]
+pub struct TranscriptionSettings<'a> {
+    pub backend: Option<&'a str>,
+    pub whisper_path: Option<&'a str>,
+    pub model_path: Option<&'a str>,
+    pub ffmpeg_path: Option<&'a str>,
+    pub url: Option<&'a str>,
+    pub api_key: Option<&'a str>,
+}
+
+/// Trimmed value, `None` when unset or blank
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+impl TranscriptionBackend {
+    /// Validate raw settings, `None` when no backend is selected
+    pub fn parse(settings: &TranscriptionSettings<'_>) -> anyhow::Result<Option<Self>> {
+        let selected = non_empty(settings.backend)
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match selected.as_str() {
+            "" | "none" => Ok(None),
+            "whisper_cpp" | "whisper" => {
+                let model = non_empty(settings.model_path).context(
+                    "WHISPER_MODEL_PATH must be set when TRANSCRIPTION_BACKEND=whisper_cpp",
+                )?;
+                Ok(Some(Self::WhisperCpp {
+                    binary: non_empty(settings.whisper_path)
+                        .unwrap_or("whisper-cli")
+                        .into(),
+                    model: model.into(),
+                    ffmpeg: non_empty(settings.ffmpeg_path).unwrap_or("ffmpeg").into(),
+                }))
+            }
+            "http" => {
+                let url = non_empty(settings.url)
+                    .context("TRANSCRIPTION_URL must be set when TRANSCRIPTION_BACKEND=http")?;
+                let url = reqwest::Url::parse(url)
+                    .with_context(|| format!("TRANSCRIPTION_URL is not a valid URL: {url}"))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    anyhow::bail!("TRANSCRIPTION_URL must use http or https");
+                }
+                Ok(Some(Self::Http {
+                    url,
+                    api_key: non_empty(settings.api_key).map(str::to_string),
+                }))
+            }
+            other => anyhow::bail!(
+                "Unknown TRANSCRIPTION_BACKEND '{other}', expected whisper_cpp, http or none"
+            ),
+        }
+    }
+
+    /// Create the backend
+    pub fn into_transcriber(self) -> Arc<dyn Transcriber> {
+        match self {
+            Self::WhisperCpp {
+                binary,
+                model,
+                ffmpeg,
+            } => Arc::new(WhisperCppTranscriber::new(binary, model, ffmpeg)),
+            Self::Http { url, api_key } => Arc::new(HttpTranscriber::new(url, api_key)),
+        }
+    }
+}
+
+static TRANSCRIBER: LazyLock<Option<Arc<dyn Transcriber>>> = LazyLock::new(|| {
+    let transcriber = crate::config::current()
+        .transcription
+        .clone()?
+        .into_transcriber();
+    info!(backend = transcriber.name(), "Voice transcription enabled");
+    Some(transcriber)
+});
+static RECOVERY_CONFIG: LazyLock<RecoveryConfig> = LazyLock::new(RecoveryConfig::default);
+static CIRCUIT_BREAKER: LazyLock<CircuitBreaker> =
+    LazyLock::new(|| CircuitBreaker::new(RECOVERY_CONFIG.clone()));
+
+/// Whether voice messages can be transcribed on this server
+pub fn is_transcription_configured() -> bool {
+    TRANSCRIBER.is_some()
+}
+
+/// Primary language subtag used as the transcription hint (`fr-CA` → `fr`)
+pub fn language_hint(language_code: Option<&str>) -> Option<String> {
+    let primary = language_code?.split(['-', '_']).next()?.trim();
+    (primary.len() == 2 && primary.chars().all(|c| c.is_ascii_alphabetic()))
+        .then(|| primary.to_ascii_lowercase())
+}
+
+/// Transcribe with the configured backend, timeout and circuit breaker
+pub async fn transcribe_voice(
+    audio_path: &str,
+    language_code: Option<&str>,
+) -> Result<String, TranscriptionError> {
+    let Some(transcriber) = TRANSCRIBER.as_ref() else {
+        return Err(TranscriptionError::NotConfigured);
+    };
+    transcribe_with_recovery(
+        transcriber.as_ref(),
+        audio_path,
+        language_code,
+        &CIRCUIT_BREAKER,
+        Duration::from_secs(RECOVERY_CONFIG.operation_timeout_secs),
+    )
+    .await
+}
+
+/// Run a backend under a timeout, failing fast while the breaker is open
+///
+/// Timeouts, backend errors and blank transcripts all count as failures.
+pub async fn transcribe_with_recovery(
+    transcriber: &dyn Transcriber,
+    audio_path: &str,
+    language_code: Option<&str>,
+    circuit_breaker: &CircuitBreaker,
+    timeout: Duration,
+) -> Result<String, TranscriptionError> {
+    if circuit_breaker.is_open() {
+        warn!(
+            backend = transcriber.name(),
+            "Circuit breaker is open, skipping transcription"
+        );
+        crate::observability::record_transcription_metrics(
+            transcriber.name(),
+            "circuit_open",
+            Duration::ZERO,
+        );
+        return Err(TranscriptionError::CircuitOpen);
+    }
+
+    let start = Instant::now();
+    let language = language_hint(language_code);
+    let result = match tokio::time::timeout(
+        timeout,
+        transcriber.transcribe(audio_path, language.as_deref()),
+    )
+    .await
+    {
+        Ok(Ok(text)) if !text.trim().is_empty() => Ok(text.trim().to_string()),
+        Ok(Ok(_)) => Err(TranscriptionError::Failed("empty transcript".to_string())),
+        Ok(Err(e)) => Err(TranscriptionError::Failed(e.to_string())),
+        Err(_) => Err(TranscriptionError::Timeout),
+    };
+
+    match &result {
+        Ok(_) => circuit_breaker.record_success(),
+        Err(e) => {
+            warn!(backend = transcriber.name(), error = %e, "Voice transcription failed");
+            circuit_breaker.record_failure();
+        }
+    }
+    let outcome = result
+        .as_ref()
+        .map_or_else(|e| e.error_type(), |_| "success");
+    crate::observability::record_transcription_metrics(
+        transcriber.name(),
+        outcome,
+        start.elapsed(),
+    );
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedTranscriber(anyhow::Result<String>, Duration);
+
+    #[async_trait]
+    impl Transcriber for FixedTranscriber {
+        async fn transcribe(&self, _: &str, _: Option<&str>) -> anyhow::Result<String> {
+            tokio::time::sleep(self.1).await;
+            match &self.0 {
+                Ok(text) => Ok(text.clone()),
+                Err(e) => Err(anyhow::anyhow!("{e}")),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+    }
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(RecoveryConfig {
+            circuit_breaker_threshold: 2,
+            ..RecoveryConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_backend_selection() {
+        let none = TranscriptionSettings::default();
+        assert_eq!(TranscriptionBackend::parse(&none).unwrap(), None);
+
+        let whisper = TranscriptionSettings {
+            backend: Some("whisper_cpp"),
+            model_path: Some("/models/ggml-base.bin"),
+            ..Default::default()
+        };
+        assert_eq!(
+            TranscriptionBackend::parse(&whisper).unwrap(),
+            Some(TranscriptionBackend::WhisperCpp {
+                binary: "whisper-cli".into(),
+                model: "/models/ggml-base.bin".into(),
+                ffmpeg: "ffmpeg".into(),
+            })
+        );
+
+        let http = TranscriptionSettings {
+            backend: Some("HTTP"),
+            url: Some("https://stt.example.com/v1/transcribe"),
+            api_key: Some(" "),
+            ..Default::default()
+        };
+        assert!(matches!(
+            TranscriptionBackend::parse(&http).unwrap(),
+            Some(TranscriptionBackend::Http { api_key: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_backend_selection_rejects_incomplete_settings() {
+        let no_model = TranscriptionSettings {
+            backend: Some("whisper_cpp"),
+            ..Default::default()
+        };
+        assert!(TranscriptionBackend::parse(&no_model).is_err());
+
+        let bad_url = TranscriptionSettings {
+            backend: Some("http"),
+            url: Some("ftp://stt.example.com"),
+            ..Default::default()
+        };
+        assert!(TranscriptionBackend::parse(&bad_url).is_err());
+
+        let unknown = TranscriptionSettings {
+            backend: Some("vosk"),
+            ..Default::default()
+        };
+        assert!(TranscriptionBackend::parse(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_language_hint_uses_primary_subtag() {
+        assert_eq!(language_hint(Some("fr-CA")).as_deref(), Some("fr"));
+        assert_eq!(language_hint(Some("EN")).as_deref(), Some("en"));
+        assert_eq!(language_hint(Some("auto")), None);
+        assert_eq!(language_hint(None), None);
+    }
+
+    #[tokio::test]
+    async fn test_failures_open_the_circuit_breaker() {
+        let breaker = breaker();
+        let failing = FixedTranscriber(Err(anyhow::anyhow!("model missing")), Duration::ZERO);
+        let timeout = Duration::from_secs(1);
+
+        for _ in 0..2 {
+            let result = transcribe_with_recovery(&failing, "a.ogg", None, &breaker, timeout).await;
+            assert!(matches!(result, Err(TranscriptionError::Failed(_))));
+        }
+
+        let working = FixedTranscriber(Ok("2 cups flour".to_string()), Duration::ZERO);
+        let result = transcribe_with_recovery(&working, "a.ogg", None, &breaker, timeout).await;
+        assert_eq!(result, Err(TranscriptionError::CircuitOpen));
+    }
+
+    #[tokio::test]
+    async fn test_slow_or_blank_transcripts_fail() {
+        let breaker = breaker();
+
+        let slow = FixedTranscriber(Ok("2 cups flour".to_string()), Duration::from_secs(5));
+        let result =
+            transcribe_with_recovery(&slow, "a.ogg", None, &breaker, Duration::from_millis(10))
+                .await;
+        assert_eq!(result, Err(TranscriptionError::Timeout));
+
+        let blank = FixedTranscriber(Ok("  \n".to_string()), Duration::ZERO);
+        let result =
+            transcribe_with_recovery(&blank, "a.ogg", None, &breaker, Duration::from_secs(1)).await;
+        assert!(matches!(result, Err(TranscriptionError::Failed(_))));
+    }
+}
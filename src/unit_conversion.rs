@@ -0,0 +1,295 @@
+//! # Unit Conversion Module
+//!
+//! Converts ingredient quantities between metric and US/imperial units for display,
+//! following the user's `/units` preference. Stored quantities are never changed.
+//!
+//! Weight and volume units from `config/measurement_units.json` are converted
+//! through grams and milliliters. The US and imperial systems are treated as one
+//! target since cookbooks mix cups with ounces and pounds. Count-based units
+//! (slices, pinches, eggs) and units without a fixed size ("cuillère", "fl") are
+//! left unchanged.
+
+use crate::text_processing::{classify_unit, UnitDimension, UnitSystem};
+
+/// Size of a unit in grams (weight) or milliliters (volume)
+fn unit_factor(unit: &str) -> Option<f64> {
+    let factor = match unit {
+        "mg" => 0.001,
+        "g" | "gram" | "grams" | "gramme" | "grammes" => 1.0,
+        "kg" | "kilogram" | "kilograms" | "kilogramme" | "kilogrammes" => 1000.0,
+        "oz" | "ounce" | "ounces" => 28.349_523,
+        "lb" | "pound" | "pounds" => 453.592_37,
+        "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" | "cc" | "cm3" => 1.0,
+        "cl" => 10.0,
+        "dl" => 100.0,
+        "l" | "liter" | "liters" | "litre" | "litres" => 1000.0,
+        "tsp" | "teaspoon" | "teaspoons" => 4.928_922,
+        "tbsp" | "tablespoon" | "tablespoons" => 14.786_765,
+        "cup" | "cups" => 236.588_237,
+        "pint" | "pints" => 473.176_473,
+        "quart" | "quarts" => 946.352_946,
+        "gallon" | "gallons" => 3_785.411_784,
+        "cuil à café" | "cuil. à café" | "cuillère à café" | "cuillères à café" => 5.0,
+        "cuil à soupe" | "cuil. à soupe" | "cuillère à soupe" | "cuillères à soupe" => 15.0,
+        "tasse" | "tasses" => 250.0,
+        "tl" | "teel." | "teelöffel" => 5.0,
+        "el" | "essl." | "esslöffel" => 15.0,
+        "tassen" => 250.0,
+        "cucharadita" | "cucharaditas" | "cdta" | "cdtas" => 5.0,
+        "cucharada" | "cucharadas" | "cda" | "cdas" => 15.0,
+        "taza" | "tazas" => 250.0,
+        _ => return None,
+    };
+    Some(factor)
+}
+
+/// Whether a system is written in metric units
+fn is_metric(system: UnitSystem) -> bool {
+    system == UnitSystem::Metric
+}
+
+/// Round `value` to the nearest multiple of `step`, without float noise
+fn round_to_step(value: f64, step: f64) -> f64 {
+    let rounded = (value / step).round() * step;
+    // Quantities too small for the step keep two decimals instead of showing 0
+    let rounded = if rounded == 0.0 { value } else { rounded };
+    (rounded * 100.0).round() / 100.0
+}
+
+/// Express a metric amount in the best-fitting metric unit
+fn to_metric(base: f64, dimension: UnitDimension) -> (f64, &'static str) {
+    let (large_unit, small_unit) = match dimension {
+        UnitDimension::Weight => ("kg", "g"),
+        _ => ("l", "ml"),
+    };
+    if base >= 1000.0 {
+        (round_to_step(base / 1000.0, 0.05), large_unit)
+    } else if base >= 100.0 {
+        (round_to_step(base, 5.0), small_unit)
+    } else if base >= 10.0 {
+        (round_to_step(base, 1.0), small_unit)
+    } else {
+        (round_to_step(base, 0.1), small_unit)
+    }
+}
+
+/// Express a metric amount in the best-fitting US/imperial unit
+fn to_customary(base: f64, dimension: UnitDimension) -> (f64, &'static str) {
+    if dimension == UnitDimension::Weight {
+        let ounces = base / 28.349_523;
+        return if ounces >= 16.0 {
+            (round_to_step(ounces / 16.0, 0.25), "lb")
+        } else if ounces >= 10.0 {
+            (round_to_step(ounces, 1.0), "oz")
+        } else {
+            (round_to_step(ounces, 0.5), "oz")
+        };
+    }
+
+    let tablespoons = base / 14.786_765;
+    if tablespoons < 1.0 {
+        (round_to_step(base / 4.928_922, 0.25), "tsp")
+    } else if tablespoons < 4.0 {
+        (round_to_step(tablespoons, 0.25), "tbsp")
+    } else {
+        let cups = round_to_step(base / 236.588_237, 0.25);
+        (cups, if cups > 1.0 { "cups" } else { "cup" })
+    }
+}
+
+/// Convert a quantity to the given unit system
+///
+/// Returns the converted quantity and its unit, or `None` when the quantity should
+/// be shown as written: the unit is unknown, count-based, already in the target
+/// system, or the target is `UnitSystem::Neutral`.
+///
+/// # Examples
+///
+/// ```
+/// use just_ingredients::text_processing::UnitSystem;
+/// use just_ingredients::unit_conversion::convert;
+///
+/// assert_eq!(convert(1.5, "cups", UnitSystem::Metric), Some((355.0, "ml".to_string())));
+/// assert_eq!(convert(355.0, "ml", UnitSystem::Us), Some((1.5, "cups".to_string())));
+/// assert_eq!(convert(2.0, "slices", UnitSystem::Metric), None);
+/// ```
+pub fn convert(
+    quantity: f64,
+    from_unit: &str,
+    to_unit_system: UnitSystem,
+) -> Option<(f64, String)> {
+    if to_unit_system == UnitSystem::Neutral || !quantity.is_finite() {
+        return None;
+    }
+    let unit = from_unit.trim().to_lowercase();
+    let (dimension, system) = classify_unit(&unit)?;
+    if dimension == UnitDimension::Count
+        || system == UnitSystem::Neutral
+        || is_metric(system) == is_metric(to_unit_system)
+    {
+        return None;
+    }
+
+    let base = quantity * unit_factor(&unit)?;
+    let (converted, unit) = if is_metric(to_unit_system) {
+        to_metric(base, dimension)
+    } else {
+        to_customary(base, dimension)
+    };
+    Some((converted, unit.to_string()))
+}
+
+/// Format a quantity without trailing zeros ("1.5", "250")
+pub fn format_quantity(quantity: f64) -> String {
+    let rounded = (quantity * 100.0).round() / 100.0;
+    let text = format!("{rounded:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Quantity and unit text to display, converted when `units` asks for it
+///
+/// `quantity` is the text as extracted or stored ("1/2", "2½", "2"); quantities
+/// that cannot be parsed are shown as written.
+pub fn display_measurement(
+    quantity: &str,
+    unit: &str,
+    units: Option<UnitSystem>,
+) -> (String, String) {
+    units
+        .and_then(|system| {
+            let value = crate::recipe_scaling::parse_fractional_quantity(quantity)?;
+            convert(value, unit, system)
+        })
+        .map(|(value, unit)| (format_quantity(value), unit))
+        .unwrap_or_else(|| (quantity.to_string(), unit.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_processing::load_measurement_units_config;
+
+    #[test]
+    fn test_convert_volume_to_metric() {
+        assert_eq!(
+            convert(1.0, "cup", UnitSystem::Metric),
+            Some((235.0, "ml".into()))
+        );
+        assert_eq!(
+            convert(2.0, "tbsp", UnitSystem::Metric),
+            Some((30.0, "ml".into()))
+        );
+        assert_eq!(
+            convert(1.0, "tsp", UnitSystem::Metric),
+            Some((4.9, "ml".into()))
+        );
+        assert_eq!(
+            convert(1.0, "gallon", UnitSystem::Metric),
+            Some((3.8, "l".into()))
+        );
+    }
+
+    #[test]
+    fn test_convert_weight_to_metric() {
+        assert_eq!(
+            convert(8.0, "oz", UnitSystem::Metric),
+            Some((225.0, "g".into()))
+        );
+        assert_eq!(
+            convert(2.5, "lb", UnitSystem::Metric),
+            Some((1.15, "kg".into()))
+        );
+    }
+
+    #[test]
+    fn test_convert_to_customary() {
+        assert_eq!(
+            convert(250.0, "g", UnitSystem::Us),
+            Some((9.0, "oz".into()))
+        );
+        assert_eq!(
+            convert(1.0, "kg", UnitSystem::Imperial),
+            Some((2.25, "lb".into()))
+        );
+        assert_eq!(
+            convert(355.0, "ml", UnitSystem::Us),
+            Some((1.5, "cups".into()))
+        );
+        assert_eq!(
+            convert(250.0, "ml", UnitSystem::Us),
+            Some((1.0, "cup".into()))
+        );
+        assert_eq!(
+            convert(15.0, "ml", UnitSystem::Us),
+            Some((1.0, "tbsp".into()))
+        );
+        assert_eq!(
+            convert(1.0, "cuillère à café", UnitSystem::Us),
+            Some((1.0, "tsp".into()))
+        );
+        assert_eq!(
+            convert(0.5, "l", UnitSystem::Us),
+            Some((2.0, "cups".into()))
+        );
+    }
+
+    #[test]
+    fn test_rounding_is_readable() {
+        // 1.4999 cups is shown as 1.5 cups
+        let (cups, _) = convert(354.8, "ml", UnitSystem::Us).unwrap();
+        assert_eq!(cups, 1.5);
+        assert_eq!(format_quantity(cups), "1.5");
+        // Tiny amounts are not rounded away to 0
+        assert_eq!(
+            convert(0.5, "ml", UnitSystem::Us),
+            Some((0.1, "tsp".into()))
+        );
+    }
+
+    #[test]
+    fn test_pass_through() {
+        // Count-based, unknown and already-converted units are unchanged
+        assert_eq!(convert(2.0, "slices", UnitSystem::Metric), None);
+        assert_eq!(convert(3.0, "pinch", UnitSystem::Us), None);
+        assert_eq!(convert(2.0, "eggs", UnitSystem::Metric), None);
+        assert_eq!(convert(2.0, "cuillère", UnitSystem::Us), None);
+        assert_eq!(convert(200.0, "g", UnitSystem::Metric), None);
+        assert_eq!(convert(2.0, "cups", UnitSystem::Imperial), None);
+        assert_eq!(convert(2.0, "cups", UnitSystem::Neutral), None);
+    }
+
+    #[test]
+    fn test_every_configured_unit_is_classified() {
+        // Configured weight and volume units either convert or are known to have no fixed size
+        let no_fixed_size = ["fluid", "fl", "cuillère", "cuillères", "mm3", "cm²", "mm²"];
+        for (name, (dimension, _)) in load_measurement_units_config()
+            .measurement_units
+            .classifications()
+        {
+            if dimension != UnitDimension::Count && !no_fixed_size.contains(&name.as_str()) {
+                assert!(
+                    unit_factor(&name).is_some(),
+                    "no conversion factor for {name}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_measurement() {
+        assert_eq!(
+            display_measurement("1/2", "cup", Some(UnitSystem::Metric)),
+            ("120".to_string(), "ml".to_string())
+        );
+        assert_eq!(
+            display_measurement("1/2", "cup", None),
+            ("1/2".to_string(), "cup".to_string())
+        );
+        assert_eq!(
+            display_measurement("2-3", "cups", Some(UnitSystem::Metric)),
+            ("2-3".to_string(), "cups".to_string())
+        );
+        assert_eq!(format_quantity(250.0), "250");
+        assert_eq!(format_quantity(0.25), "0.25");
+    }
+}
@@ -4,6 +4,8 @@
 //! multiple modules, providing reusable validation functions for:
 //!
 //! - Recipe names
+//! - Recipe tags
+//! - Recipe notes
 //! - Ingredient input
 //! - Measurement matches
 //! - Quantity ranges
@@ -49,6 +51,80 @@ pub fn validate_recipe_name(name: &str) -> Result<&str, &'static str> {
     Ok(trimmed)
 }
 
+/// Longest recipe tag, in bytes, so tags fit in Telegram callback data
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// Validates a recipe tag and returns it in its stored, lowercased form
+///
+/// # Arguments
+/// * `tag` - The tag as typed by the user
+///
+/// # Returns
+/// * `Ok(String)` - The trimmed, lowercased tag if valid
+/// * `Err(&str)` - Error type: "empty", "too_long" or "invalid_characters"
+///
+/// # Examples
+/// ```
+/// use just_ingredients::validation::validate_tag_name;
+///
+/// assert_eq!(validate_tag_name("  Dessert "), Ok("dessert".to_string()));
+/// assert_eq!(validate_tag_name(" "), Err("empty"));
+/// assert_eq!(validate_tag_name(&"a".repeat(33)), Err("too_long"));
+/// assert_eq!(validate_tag_name("des\nsert"), Err("invalid_characters"));
+/// ```
+pub fn validate_tag_name(tag: &str) -> Result<String, &'static str> {
+    let trimmed = tag.trim();
+
+    if trimmed.is_empty() {
+        return Err("empty");
+    }
+
+    if trimmed.chars().any(char::is_control) {
+        return Err("invalid_characters");
+    }
+
+    let normalized = trimmed.to_lowercase();
+    if normalized.len() > MAX_TAG_LENGTH {
+        return Err("too_long");
+    }
+
+    Ok(normalized)
+}
+
+/// Longest recipe notes, in characters, so they fit in one Telegram message
+pub const MAX_RECIPE_NOTES_LENGTH: usize = 2000;
+
+/// Validates the notes a user keeps on a recipe
+///
+/// # Arguments
+/// * `notes` - The notes as typed by the user
+///
+/// # Returns
+/// * `Ok(&str)` - The trimmed notes if valid
+/// * `Err(&str)` - Error type: "empty" or "too_long"
+///
+/// # Examples
+/// ```
+/// use just_ingredients::validation::validate_recipe_notes;
+///
+/// assert_eq!(validate_recipe_notes(" Reduce sugar next time "), Ok("Reduce sugar next time"));
+/// assert_eq!(validate_recipe_notes("  "), Err("empty"));
+/// assert_eq!(validate_recipe_notes(&"é".repeat(2001)), Err("too_long"));
+/// ```
+pub fn validate_recipe_notes(notes: &str) -> Result<&str, &'static str> {
+    let trimmed = notes.trim();
+
+    if trimmed.is_empty() {
+        return Err("empty");
+    }
+
+    if trimmed.chars().count() > MAX_RECIPE_NOTES_LENGTH {
+        return Err("too_long");
+    }
+
+    Ok(trimmed)
+}
+
 /// Validate basic input constraints
 ///
 /// # Arguments
@@ -101,6 +177,10 @@ pub fn validate_basic_input(input: &str) -> Result<(), &'static str> {
 ///     start_pos: 0,
 ///     end_pos: 10,
 ///     requires_quantity_confirmation: false,
+///     unit_dimension: None,
+///     unit_system: None,
+///     confidence: None,
+///     quantity_max: None,
 /// };
 ///
 /// assert!(validate_measurement_match(&valid_match, "temp: 2 cups flour").is_ok());
@@ -143,6 +223,10 @@ pub fn validate_measurement_match(
 ///     start_pos: 7, // Position of "2" in "-2 "
 ///     end_pos: 10,
 ///     requires_quantity_confirmation: false,
+///     unit_dimension: None,
+///     unit_system: None,
+///     confidence: None,
+///     quantity_max: None,
 /// };
 ///
 /// adjust_quantity_for_negative(&mut match_with_negative, "temp: -2 cups flour");
@@ -190,6 +274,10 @@ pub fn adjust_quantity_for_negative(measurement_match: &mut MeasurementMatch, te
 ///     start_pos: 0,
 ///     end_pos: 10,
 ///     requires_quantity_confirmation: false,
+///     unit_dimension: None,
+///     unit_system: None,
+///     confidence: None,
+///     quantity_max: None,
 /// };
 ///
 /// assert!(validate_quantity_range(&valid_match).is_ok());
@@ -202,6 +290,10 @@ pub fn adjust_quantity_for_negative(measurement_match: &mut MeasurementMatch, te
 ///     start_pos: 0,
 ///     end_pos: 10,
 ///     requires_quantity_confirmation: false,
+///     unit_dimension: None,
+///     unit_system: None,
+///     confidence: None,
+///     quantity_max: None,
 /// };
 ///
 /// assert_eq!(validate_quantity_range(&invalid_match), Err("edit-invalid-quantity"));
@@ -215,7 +307,11 @@ pub fn validate_quantity_range(measurement_match: &MeasurementMatch) -> Result<(
     Ok(())
 }
 
-/// Parse quantity string to f64 (handles fractions and decimals)
+/// Parse quantity string to f64 (handles fractions, mixed numbers and decimals)
+///
+/// Accepts everything a `MeasurementMatch` may hold as a quantity: decimals with a
+/// dot or a comma, ASCII fractions ("1/2"), Unicode fractions ("½") and mixed
+/// numbers ("1 1/2", "1½").
 ///
 /// # Arguments
 /// * `quantity_str` - The quantity string to parse
@@ -230,32 +326,12 @@ pub fn validate_quantity_range(measurement_match: &MeasurementMatch) -> Result<(
 ///
 /// assert_eq!(parse_quantity("2"), Some(2.0));
 /// assert_eq!(parse_quantity("1/2"), Some(0.5));
+/// assert_eq!(parse_quantity("1½"), Some(1.5));
 /// assert_eq!(parse_quantity("2.5"), Some(2.5));
 /// assert_eq!(parse_quantity("invalid"), None);
 /// ```
 pub fn parse_quantity(quantity_str: &str) -> Option<f64> {
-    if quantity_str.contains('/') {
-        // Handle fractions like "1/2"
-        let parts: Vec<&str> = quantity_str.split('/').collect();
-        if parts.len() == 2 {
-            if let (Ok(numerator), Ok(denominator)) =
-                (parts[0].parse::<f64>(), parts[1].parse::<f64>())
-            {
-                if denominator != 0.0 {
-                    Some(numerator / denominator)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        } else {
-            None
-        }
-    } else {
-        // Handle regular numbers, replace comma with dot for European format
-        quantity_str.replace(',', ".").parse::<f64>().ok()
-    }
+    crate::recipe_scaling::parse_fractional_quantity(quantity_str)
 }
 
 /// Parse ingredient text input and create a MeasurementMatch
@@ -352,6 +428,85 @@ pub fn parse_ingredient_from_text(input: &str) -> Result<MeasurementMatch, &'sta
     }
 }
 
+/// An edited ingredient line, read against the ingredient being edited
+#[derive(Debug, Clone, PartialEq)]
+pub enum IngredientEdit {
+    /// The ingredient after the edit, with what the user did not retype kept
+    Updated(MeasurementMatch),
+    /// The input can be read several ways; the user should be asked to clarify
+    Ambiguous,
+}
+
+/// Parse an edited ingredient line, merging partial input into the current ingredient
+///
+/// - a bare quantity ("3", "1/2") changes the quantity only
+/// - a quantity and a unit ("3 cups") change both and keep the name
+/// - a full line ("3 cups flour", "4 eggs") replaces the ingredient
+///
+/// Text without a quantity ("flour") is ambiguous, as is a quantity followed by a
+/// single word that is not a unit when the ingredient has one ("3 mugs" for
+/// "2 cups flour": a new unit or a new ingredient?).
+///
+/// Errors are the localization keys of [`parse_ingredient_from_text`].
+pub fn parse_ingredient_edit(
+    current: &MeasurementMatch,
+    input: &str,
+) -> Result<IngredientEdit, &'static str> {
+    use crate::text_processing::MeasurementDetector;
+
+    let trimmed = input.trim();
+    validate_basic_input(trimmed)?;
+
+    if let Some(quantity) = parse_quantity(trimmed) {
+        if quantity <= 0.0 || quantity > 10000.0 {
+            return Err("edit-invalid-quantity");
+        }
+        return Ok(IngredientEdit::Updated(MeasurementMatch {
+            quantity: trimmed.to_string(),
+            quantity_max: None,
+            requires_quantity_confirmation: false,
+            ..current.clone()
+        }));
+    }
+
+    let detector = MeasurementDetector::new().map_err(|_| "error-processing-failed")?;
+    let temp_text = format!("temp: {}", trimmed);
+    let detected = detector
+        .extract_ingredient_measurements(&temp_text)
+        .into_iter()
+        .next();
+
+    if let Some(mut unit_match) = detected.clone() {
+        if unit_match.measurement.is_some() && unit_match.ingredient_name.trim().is_empty() {
+            adjust_quantity_for_negative(&mut unit_match, &temp_text);
+            validate_quantity_range(&unit_match)?;
+            return Ok(IngredientEdit::Updated(MeasurementMatch {
+                quantity: unit_match.quantity,
+                quantity_max: unit_match.quantity_max,
+                measurement: unit_match.measurement,
+                unit_dimension: unit_match.unit_dimension,
+                unit_system: unit_match.unit_system,
+                requires_quantity_confirmation: false,
+                ..current.clone()
+            }));
+        }
+    }
+
+    if detected.is_none() && !QUANTITY_PATTERN.is_match(trimmed) {
+        return Ok(IngredientEdit::Ambiguous);
+    }
+
+    let replacement = parse_ingredient_from_text(trimmed)?;
+    let single_word = !replacement
+        .ingredient_name
+        .trim()
+        .contains(char::is_whitespace);
+    if current.measurement.is_some() && replacement.measurement.is_none() && single_word {
+        return Ok(IngredientEdit::Ambiguous);
+    }
+    Ok(IngredientEdit::Updated(replacement))
+}
+
 /// Parse ingredient when no measurement detector match is found
 fn parse_without_measurement_detector(trimmed: &str) -> Result<MeasurementMatch, &'static str> {
     // Try to extract a simple quantity pattern
@@ -374,6 +529,10 @@ fn parse_without_measurement_detector(trimmed: &str) -> Result<MeasurementMatch,
         start_pos: 0,
         end_pos: trimmed.len(),
         requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
     })
 }
 
@@ -408,6 +567,10 @@ fn parse_with_quantity(
         start_pos: 0,
         end_pos: trimmed.len(),
         requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
     })
 }
 
@@ -434,6 +597,44 @@ mod tests {
         assert_eq!(validate_recipe_name(&long_name), Err("too_long"));
     }
 
+    #[test]
+    fn test_validate_tag_name() {
+        assert_eq!(validate_tag_name("Dessert"), Ok("dessert".to_string()));
+        assert_eq!(
+            validate_tag_name(" Quick Dinner "),
+            Ok("quick dinner".to_string())
+        );
+        assert_eq!(validate_tag_name(""), Err("empty"));
+        assert_eq!(validate_tag_name("tab\there"), Err("invalid_characters"));
+        assert_eq!(validate_tag_name("\u{7}bell"), Err("invalid_characters"));
+
+        // The limit is in bytes: 16 accented letters take 32
+        assert!(validate_tag_name(&"é".repeat(16)).is_ok());
+        assert_eq!(validate_tag_name(&"é".repeat(17)), Err("too_long"));
+    }
+
+    #[test]
+    fn test_validate_recipe_notes() {
+        assert_eq!(
+            validate_recipe_notes("Reduce sugar next time"),
+            Ok("Reduce sugar next time")
+        );
+        // Line breaks inside notes are kept, surrounding whitespace is not
+        assert_eq!(
+            validate_recipe_notes("\n Bake 5 min less\nUse brown sugar \n"),
+            Ok("Bake 5 min less\nUse brown sugar")
+        );
+        assert_eq!(validate_recipe_notes(""), Err("empty"));
+        assert_eq!(validate_recipe_notes(" \n\t "), Err("empty"));
+
+        // The limit is in characters, so accented notes get as much room
+        assert!(validate_recipe_notes(&"é".repeat(MAX_RECIPE_NOTES_LENGTH)).is_ok());
+        assert_eq!(
+            validate_recipe_notes(&"a".repeat(MAX_RECIPE_NOTES_LENGTH + 1)),
+            Err("too_long")
+        );
+    }
+
     #[test]
     fn test_validate_basic_input() {
         // Valid input
@@ -475,6 +676,124 @@ mod tests {
         assert_eq!(parse_quantity("/2"), None);
     }
 
+    #[test]
+    fn test_parse_quantity_unicode_and_mixed_fractions() {
+        assert_eq!(parse_quantity("½"), Some(0.5));
+        assert_eq!(parse_quantity("1½"), Some(1.5));
+        assert_eq!(parse_quantity("1 ½"), Some(1.5));
+        assert_eq!(parse_quantity("1 1/2"), Some(1.5));
+        assert_eq!(parse_quantity(" 2.5 "), Some(2.5));
+        assert_eq!(parse_quantity("a pinch"), None);
+        assert_eq!(parse_quantity("2-3"), None);
+        assert_eq!(parse_quantity("½½"), None);
+    }
+
+    fn edited(current: &MeasurementMatch, input: &str) -> MeasurementMatch {
+        match parse_ingredient_edit(current, input) {
+            Ok(IngredientEdit::Updated(ingredient)) => ingredient,
+            other => panic!("Expected an update for {input:?}, got {other:?}"),
+        }
+    }
+
+    fn ingredient(quantity: &str, unit: Option<&str>, name: &str) -> MeasurementMatch {
+        MeasurementMatch {
+            quantity: quantity.to_string(),
+            measurement: unit.map(str::to_string),
+            ingredient_name: name.to_string(),
+            line_number: 3,
+            start_pos: 0,
+            end_pos: 0,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_ingredient_edit_partial_input() {
+        let flour = ingredient("2", Some("cups"), "flour");
+
+        // A bare quantity keeps the unit and name
+        for (input, quantity) in [("3", "3"), (" 1/2 ", "1/2"), ("½", "½"), ("2,5", "2,5")] {
+            let updated = edited(&flour, input);
+            assert_eq!(updated.quantity, quantity);
+            assert_eq!(updated.measurement.as_deref(), Some("cups"));
+            assert_eq!(updated.ingredient_name, "flour");
+            assert_eq!(updated.line_number, 3);
+        }
+
+        // A quantity and a unit keep the name
+        for (input, quantity, unit) in [
+            ("3 tbsp", "3", "tbsp"),
+            ("500g", "500", "g"),
+            ("½ cup", "1/2", "cup"),
+        ] {
+            let updated = edited(&flour, input);
+            assert_eq!(updated.quantity, quantity);
+            assert_eq!(updated.measurement.as_deref(), Some(unit));
+            assert_eq!(updated.ingredient_name, "flour");
+        }
+
+        // A full line replaces everything
+        let updated = edited(&flour, "3 cups whole wheat flour");
+        assert_eq!(updated.quantity, "3");
+        assert_eq!(updated.measurement.as_deref(), Some("cups"));
+        assert_eq!(updated.ingredient_name, "whole wheat flour");
+        let updated = edited(&flour, "4 large eggs");
+        assert_eq!(updated.measurement, None);
+        assert_eq!(updated.ingredient_name, "large eggs");
+
+        // Without a unit to lose, a quantity and a word is a full line
+        let eggs = ingredient("2", None, "eggs");
+        let updated = edited(&eggs, "3 eggs");
+        assert_eq!(
+            (updated.quantity.as_str(), updated.measurement),
+            ("3", None)
+        );
+        assert_eq!(edited(&eggs, "6").ingredient_name, "eggs");
+        assert_eq!(edited(&eggs, "6").quantity, "6");
+    }
+
+    #[test]
+    fn test_parse_ingredient_edit_ambiguous_and_invalid_input() {
+        let flour = ingredient("2", Some("cups"), "flour");
+        let eggs = ingredient("2", None, "eggs");
+
+        // A name alone, or a word that may be a unit or a name
+        assert_eq!(
+            parse_ingredient_edit(&flour, "rye flour"),
+            Ok(IngredientEdit::Ambiguous)
+        );
+        assert_eq!(
+            parse_ingredient_edit(&eggs, "cups"),
+            Ok(IngredientEdit::Ambiguous)
+        );
+        assert_eq!(
+            parse_ingredient_edit(&flour, "3 mugs"),
+            Ok(IngredientEdit::Ambiguous)
+        );
+
+        assert_eq!(parse_ingredient_edit(&flour, ""), Err("edit-empty"));
+        assert_eq!(
+            parse_ingredient_edit(&flour, "0"),
+            Err("edit-invalid-quantity")
+        );
+        assert_eq!(
+            parse_ingredient_edit(&flour, "-3"),
+            Err("edit-invalid-quantity")
+        );
+        assert_eq!(
+            parse_ingredient_edit(&flour, "20000 g"),
+            Err("edit-invalid-quantity")
+        );
+        assert_eq!(
+            parse_ingredient_edit(&flour, &"a".repeat(201)),
+            Err("edit-too-long")
+        );
+    }
+
     #[test]
     fn test_validate_quantity_range() {
         let create_match = |quantity: &str| MeasurementMatch {
@@ -485,6 +804,10 @@ mod tests {
             start_pos: 0,
             end_pos: 10,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         // Valid ranges
@@ -518,6 +841,10 @@ mod tests {
             start_pos,
             end_pos: 10,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         // Should add negative sign
@@ -44,6 +44,35 @@ mod tests {
         assert!(!circuit_breaker.is_open());
     }
 
+    /// Test that open time is tracked from the failure that opens the circuit
+    #[test]
+    fn test_circuit_breaker_open_duration() {
+        let config = RecoveryConfig {
+            circuit_breaker_threshold: 2,
+            circuit_breaker_reset_secs: 60,
+            ..Default::default()
+        };
+        let circuit_breaker = CircuitBreaker::new(config);
+
+        circuit_breaker.record_failure();
+        assert_eq!(
+            circuit_breaker.total_open_duration(),
+            std::time::Duration::ZERO
+        );
+
+        circuit_breaker.record_failure();
+        assert!(circuit_breaker.is_open());
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        // A success closes the window; the total stops growing
+        circuit_breaker.record_success();
+        let open_duration = circuit_breaker.total_open_duration();
+        assert!(open_duration >= std::time::Duration::from_millis(20));
+        assert!(open_duration < std::time::Duration::from_secs(60));
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert_eq!(circuit_breaker.total_open_duration(), open_duration);
+    }
+
     /// Test OCR instance manager initialization
     #[test]
     fn test_ocr_instance_manager_initialization() {
@@ -409,6 +438,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -418,6 +451,10 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "1".to_string(),
@@ -427,6 +464,10 @@ mod tests {
                 start_pos: 15,
                 end_pos: 21,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
@@ -487,6 +528,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -496,6 +541,10 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
@@ -509,6 +558,7 @@ mod tests {
             message_id: None,
             extracted_text: "Test OCR text".to_string(),
             recipe_name_from_caption: None,
+            last_deleted: None,
         };
 
         // Simulate deleting an ingredient
@@ -522,6 +572,7 @@ mod tests {
             message_id: None,
             extracted_text: "Test OCR text".to_string(),
             recipe_name_from_caption: None,
+            last_deleted: None,
         };
 
         // Verify the states are different
@@ -555,6 +606,7 @@ mod tests {
             message_id: None,
             extracted_text: "Test OCR text".to_string(),
             recipe_name_from_caption: None,
+            last_deleted: None,
         };
 
         match empty_state {
@@ -587,6 +639,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -596,6 +652,10 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
@@ -610,15 +670,16 @@ mod tests {
             // Should have 4 rows: 2 ingredient rows + 1 confirm/cancel row + 1 add ingredient row
             assert_eq!(keyboard.len(), 4);
 
-            // First row: Edit and Delete buttons for first ingredient
-            assert_eq!(keyboard[0].len(), 2);
+            // First row: Edit, Delete and adjust buttons for first ingredient
+            assert_eq!(keyboard[0].len(), 3);
             assert!(keyboard[0][0].text.contains("✏️"));
             assert!(keyboard[0][0].text.contains("flour"));
             assert!(keyboard[0][1].text.contains("🗑️"));
             assert!(keyboard[0][1].text.contains("flour"));
+            assert_eq!(keyboard[0][2].text, "±");
 
-            // Second row: Edit and Delete buttons for second ingredient
-            assert_eq!(keyboard[1].len(), 2);
+            // Second row: Edit, Delete and adjust buttons for second ingredient
+            assert_eq!(keyboard[1].len(), 3);
             assert!(keyboard[1][0].text.contains("✏️"));
             assert!(keyboard[1][0].text.contains("eggs"));
             assert!(keyboard[1][1].text.contains("🗑️"));
@@ -676,6 +737,10 @@ mod tests {
             start_pos: 0,
             end_pos: 50,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         }];
 
         let keyboard = create_ingredient_review_keyboard(&ingredients, Some("en"), &manager);
@@ -706,6 +771,10 @@ mod tests {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         }];
 
         let keyboard = create_ingredient_review_keyboard(&ingredients, Some("en"), &manager);
@@ -719,6 +788,1474 @@ mod tests {
         }
     }
 
+    /// Test compact review keyboard keeps the full layout's callback data
+    #[test]
+    fn test_ingredient_review_keyboard_compact_variant() {
+        let manager = setup_localization();
+        use just_ingredients::bot::create_ingredient_review_keyboard_for_variant;
+        use just_ingredients::experiments::ReviewKeyboardVariant;
+        use just_ingredients::text_processing::MeasurementMatch;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let ingredients: Vec<MeasurementMatch> = ["flour", "eggs", "milk"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| MeasurementMatch {
+                quantity: "1".to_string(),
+                measurement: None,
+                ingredient_name: name.to_string(),
+                line_number: i,
+                start_pos: 0,
+                end_pos: 1,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            })
+            .collect();
+
+        let callback_data = |variant| -> Vec<String> {
+            let keyboard = create_ingredient_review_keyboard_for_variant(
+                &ingredients,
+                Some("en"),
+                &manager,
+                variant,
+            );
+            let mut data: Vec<String> = keyboard
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .filter_map(|button| match &button.kind {
+                    InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                    _ => None,
+                })
+                .collect();
+            data.sort();
+            data
+        };
+
+        assert_eq!(
+            callback_data(ReviewKeyboardVariant::Compact),
+            callback_data(ReviewKeyboardVariant::Full)
+        );
+
+        let compact = create_ingredient_review_keyboard_for_variant(
+            &ingredients,
+            Some("en"),
+            &manager,
+            ReviewKeyboardVariant::Compact,
+        )
+        .inline_keyboard;
+        // Two ingredients per row: 2 ingredient rows + confirm/cancel + add ingredient + rename + report
+        assert_eq!(compact.len(), 6);
+        assert_eq!(compact[0].len(), 6);
+        assert_eq!(compact[1].len(), 3);
+        assert_eq!(compact[0][0].text, "✏️ 1");
+        assert_eq!(compact[1][1].text, "🗑️ 3");
+        assert_eq!(compact[1][2].text, "± 3");
+    }
+
+    /// Test that only the review of an unsaved recipe offers renaming it
+    #[test]
+    fn test_review_keyboard_rename_button() {
+        let manager = setup_localization();
+        use just_ingredients::bot::{
+            create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+        };
+        use just_ingredients::experiments::ReviewKeyboardVariant;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let rename_button = |keyboard: &teloxide::types::InlineKeyboardMarkup| {
+            keyboard
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .find(|button| {
+                    matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == "rename_pending")
+                })
+                .map(|button| button.text.clone())
+        };
+
+        for variant in [ReviewKeyboardVariant::Full, ReviewKeyboardVariant::Compact] {
+            let review =
+                create_ingredient_review_keyboard_for_variant(&[], Some("en"), &manager, variant);
+            assert_eq!(rename_button(&review).as_deref(), Some("✏️ Rename"));
+        }
+        let review = create_ingredient_review_keyboard_for_variant(
+            &[],
+            Some("fr"),
+            &manager,
+            ReviewKeyboardVariant::Full,
+        );
+        assert_eq!(rename_button(&review).as_deref(), Some("✏️ Renommer"));
+
+        // Saved recipes are renamed from their own menu
+        assert_eq!(
+            rename_button(&create_ingredient_review_keyboard(
+                &[],
+                Some("en"),
+                &manager
+            )),
+            None
+        );
+    }
+
+    /// Test only recipes with a kept photo offer to show it again
+    #[test]
+    fn test_recipe_details_keyboard_photo_button() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ui_builder::create_recipe_details_keyboard;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let photo_button = |keyboard: &teloxide::types::InlineKeyboardMarkup| {
+            keyboard
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .find(|button| {
+                    matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == "recipe_action:photo:42")
+                })
+                .map(|button| button.text.clone())
+        };
+
+        let with_photo = create_recipe_details_keyboard(42, true, Some("en"), &manager);
+        assert_eq!(
+            photo_button(&with_photo).as_deref(),
+            Some("🖼️ Show original photo")
+        );
+        let french = create_recipe_details_keyboard(42, true, Some("fr"), &manager);
+        assert_eq!(
+            photo_button(&french).as_deref(),
+            Some("🖼️ Voir la photo d'origine")
+        );
+        // Recipes read from text, or saved before photos were kept, have none
+        let without_photo = create_recipe_details_keyboard(42, false, Some("en"), &manager);
+        assert_eq!(photo_button(&without_photo), None);
+        assert_eq!(
+            with_photo.inline_keyboard.len(),
+            without_photo.inline_keyboard.len() + 1
+        );
+    }
+
+    /// Test recipe notes get a button, a one-line preview in the details and a full view
+    #[test]
+    fn test_recipe_notes_button_and_preview() {
+        let manager = setup_localization();
+        use chrono::Utc;
+        use just_ingredients::bot::recipe_notes::{
+            create_recipe_notes_keyboard, format_notes_message, invalid_notes_message,
+        };
+        use just_ingredients::bot::ui_builder::{
+            create_recipe_details_keyboard, format_recipe_details, NOTES_PREVIEW_LENGTH,
+        };
+        use just_ingredients::db::{Recipe, RecipeId, TelegramId};
+        use just_ingredients::validation::{validate_recipe_notes, MAX_RECIPE_NOTES_LENGTH};
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let callbacks =
+            |keyboard: &teloxide::types::InlineKeyboardMarkup| -> Vec<(String, String)> {
+                keyboard
+                    .inline_keyboard
+                    .iter()
+                    .flatten()
+                    .filter_map(|button| match &button.kind {
+                        InlineKeyboardButtonKind::CallbackData(data) => {
+                            Some((button.text.clone(), data.clone()))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            };
+
+        let details_keyboard = create_recipe_details_keyboard(42, false, Some("fr"), &manager);
+        assert!(callbacks(&details_keyboard)
+            .contains(&("🗒️ Notes".to_string(), "recipe_action:notes:42".to_string())));
+
+        // Details: no notes, no preview
+        let mut recipe = Recipe {
+            id: RecipeId(42),
+            telegram_id: TelegramId(1),
+            content: String::new(),
+            recipe_name: Some("Cookies".to_string()),
+            created_at: Utc::now(),
+            notes: None,
+        };
+        let details = format_recipe_details(
+            &recipe,
+            &[],
+            None,
+            just_ingredients::timezone::utc(),
+            Some("en"),
+            &manager,
+        );
+        assert!(!details.contains("🗒️"));
+
+        // Long notes are previewed escaped, on one line and truncated
+        let notes = format!("Less <sugar> & more salt\n{}", "very long ".repeat(50));
+        recipe.notes = Some(notes.clone());
+        let details = format_recipe_details(
+            &recipe,
+            &[],
+            None,
+            just_ingredients::timezone::utc(),
+            Some("en"),
+            &manager,
+        );
+        let preview = details
+            .lines()
+            .find(|line| line.starts_with("🗒️ "))
+            .expect("notes preview");
+        assert!(preview.starts_with("🗒️ <i>Less &lt;sugar&gt; &amp; more salt very long"));
+        assert!(preview.ends_with("...</i>"));
+        assert!(preview.chars().count() < NOTES_PREVIEW_LENGTH + 40);
+
+        // Notes view: full notes, Clear only offered when there are notes
+        let view = format_notes_message("Cookies", Some(&notes), &manager, Some("en"));
+        assert!(view.contains("Less &lt;sugar&gt; &amp; more salt\n"));
+        let empty_view = format_notes_message("Cookies", None, &manager, Some("fr"));
+        assert!(empty_view.contains("Cette recette n'a pas encore de notes."));
+        assert_eq!(
+            callbacks(&create_recipe_notes_keyboard(
+                42,
+                true,
+                &manager,
+                Some("en")
+            )),
+            vec![
+                ("✏️ Edit".to_string(), "notes:edit:42".to_string()),
+                ("🧹 Clear".to_string(), "notes:clear:42".to_string()),
+            ]
+        );
+        assert_eq!(
+            callbacks(&create_recipe_notes_keyboard(
+                42,
+                false,
+                &manager,
+                Some("fr")
+            )),
+            vec![("✏️ Modifier".to_string(), "notes:edit:42".to_string())]
+        );
+
+        // Length limit error names the limit and the length sent
+        let too_long = "a".repeat(MAX_RECIPE_NOTES_LENGTH + 5);
+        let error = validate_recipe_notes(&too_long).unwrap_err();
+        let message = invalid_notes_message(error, &too_long, &manager, Some("en"));
+        assert!(message.contains("2000"), "{message}");
+        assert!(message.contains("2005"), "{message}");
+    }
+
+    /// Markdown of a fixture recipe, with English units and notes or French units and none
+    fn recipe_markdown_snapshot(language_code: &str) -> String {
+        use chrono::{TimeZone, Utc};
+        use just_ingredients::bot::ui_builder::format_recipe_markdown;
+        use just_ingredients::db::{Ingredient, Recipe, RecipeId, TelegramId, UserId};
+
+        let manager = setup_localization();
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        // Quantity, unit and name of an ingredient of the fixture
+        type FixtureIngredient = (Option<f64>, Option<&'static str>, &'static str);
+
+        let (name, notes, ingredients): (&str, Option<&str>, &[FixtureIngredient]) =
+            if language_code == "fr" {
+                (
+                    "Crêpes",
+                    None,
+                    &[
+                        (Some(250.0), Some("g"), "farine"),
+                        (Some(0.5), Some("l"), "lait"),
+                        (Some(2.0), Some("c. à soupe"), "sucre"),
+                        (Some(3.0), None, "œufs"),
+                        (None, None, "une pincée de sel"),
+                    ],
+                )
+            } else {
+                (
+                    "Chocolate Chip Cookies",
+                    Some("Reduce sugar next time.\nBake 2 minutes less for chewy cookies."),
+                    &[
+                        (Some(2.25), Some("cups"), "all-purpose flour"),
+                        (Some(1.0), Some("tsp"), "baking soda"),
+                        (Some(0.5), Some("cup"), "unsalted butter, softened"),
+                        (Some(2.0), None, "large eggs"),
+                    ],
+                )
+            };
+        let recipe = Recipe {
+            id: RecipeId(1),
+            telegram_id: TelegramId(1),
+            content: String::new(),
+            recipe_name: Some(name.to_string()),
+            created_at,
+            notes: notes.map(str::to_string),
+        };
+        let ingredients: Vec<Ingredient> = ingredients
+            .iter()
+            .enumerate()
+            .map(|(i, (quantity, unit, name))| Ingredient {
+                id: i as i64,
+                user_id: UserId(1),
+                recipe_id: Some(RecipeId(1)),
+                name: name.to_string(),
+                quantity: *quantity,
+                unit: unit.map(str::to_string),
+                created_at,
+                updated_at: created_at,
+                unit_dimension: None,
+                unit_system: None,
+            })
+            .collect();
+        format_recipe_markdown(
+            &recipe,
+            &ingredients,
+            None,
+            just_ingredients::timezone::utc(),
+            Some(language_code),
+            &manager,
+        )
+    }
+
+    /// Recipes copied as text must keep their layout
+    ///
+    /// Regenerate the golden files with `UPDATE_RECIPE_TEXT_GOLDEN=1` only for intended
+    /// layout changes.
+    #[test]
+    fn test_recipe_markdown_matches_golden_snapshot() {
+        for language_code in ["en", "fr"] {
+            let golden_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join(format!("tests/fixtures/recipe_text_{language_code}.md"));
+            let snapshot = recipe_markdown_snapshot(language_code);
+
+            // Labels take no arguments, so no bidi isolation marks are copied
+            assert!(!snapshot.contains(['\u{2068}', '\u{2069}']));
+
+            if std::env::var("UPDATE_RECIPE_TEXT_GOLDEN").is_ok() {
+                std::fs::write(&golden_path, &snapshot).unwrap();
+                continue;
+            }
+
+            let golden = std::fs::read_to_string(&golden_path).unwrap();
+            assert_eq!(snapshot, golden, "{}", golden_path.display());
+        }
+    }
+
+    /// Test recipes can be copied as text, as a file named after them when too long
+    #[test]
+    fn test_copy_as_text_button_and_file_name() {
+        use just_ingredients::bot::ui_builder::{
+            create_recipe_details_keyboard, recipe_markdown_file_name,
+        };
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let manager = setup_localization();
+        let keyboard = create_recipe_details_keyboard(42, false, Some("en"), &manager);
+        let button = keyboard
+            .inline_keyboard
+            .iter()
+            .flatten()
+            .find(|button| {
+                matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == "recipe_action:copy_text:42")
+            })
+            .expect("copy as text button");
+        assert_eq!(button.text, "📋 Copy as text");
+
+        assert_eq!(
+            recipe_markdown_file_name(Some("Crêpes & Jam")),
+            "crêpes-jam.md"
+        );
+        assert_eq!(
+            recipe_markdown_file_name(Some("  Mom's  Pie!  ")),
+            "mom-s-pie.md"
+        );
+        assert_eq!(recipe_markdown_file_name(Some("🍪")), "recipe.md");
+        assert_eq!(recipe_markdown_file_name(None), "recipe.md");
+        assert_eq!(recipe_markdown_file_name(Some(&"a".repeat(100))).len(), 67);
+    }
+
+    /// Test the undo button is added on top of a review keyboard after a deletion
+    #[test]
+    fn test_undo_delete_button_added_first() {
+        let manager = setup_localization();
+        use just_ingredients::bot::{
+            create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+            with_undo_delete_button,
+        };
+        use just_ingredients::experiments::ReviewKeyboardVariant;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let undo_buttons = |keyboard: &teloxide::types::InlineKeyboardMarkup| {
+            keyboard
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .filter(|button| {
+                    matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == "undo_delete")
+                })
+                .count()
+        };
+
+        let review = create_ingredient_review_keyboard_for_variant(
+            &[],
+            Some("en"),
+            &manager,
+            ReviewKeyboardVariant::Full,
+        );
+        assert_eq!(undo_buttons(&review), 0);
+        let rows = review.inline_keyboard.len();
+
+        let with_undo = with_undo_delete_button(review, Some("en"), &manager);
+        assert_eq!(undo_buttons(&with_undo), 1);
+        assert_eq!(with_undo.inline_keyboard.len(), rows + 1);
+        assert_eq!(with_undo.inline_keyboard[0][0].text, "↩️ Undo");
+
+        let saved = with_undo_delete_button(
+            create_ingredient_review_keyboard(&[], Some("fr"), &manager),
+            Some("fr"),
+            &manager,
+        );
+        assert_eq!(
+            saved.inline_keyboard[0][0].text,
+            "↩️ Annuler la suppression"
+        );
+    }
+
+    /// Test the retry button sits above the report button of the no-ingredients message
+    #[test]
+    fn test_retry_processing_button_added_first() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ui_builder::create_report_problem_keyboard;
+        use just_ingredients::bot::with_retry_processing_button;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let keyboard = with_retry_processing_button(
+            create_report_problem_keyboard(Some("en"), &manager),
+            Some("en"),
+            &manager,
+        );
+        assert_eq!(keyboard.inline_keyboard.len(), 2);
+        let retry = &keyboard.inline_keyboard[0][0];
+        assert_eq!(retry.text, "🔁 Try again with different processing");
+        assert!(matches!(
+            &retry.kind,
+            InlineKeyboardButtonKind::CallbackData(data) if data == "ocr_retry_profiles"
+        ));
+    }
+
+    /// Test the retry run with the most matches wins, the first profile on a tie
+    #[test]
+    fn test_pick_retry_winner() {
+        use just_ingredients::bot::image_processing::pick_retry_winner;
+
+        assert_eq!(
+            pick_retry_winner(&[
+                ("no_threshold", 1),
+                ("aggressive_denoise", 4),
+                ("no_deskew", 4)
+            ]),
+            Some(1)
+        );
+        assert_eq!(
+            pick_retry_winner(&[("no_threshold", 0), ("no_deskew", 2)]),
+            Some(1)
+        );
+        assert_eq!(
+            pick_retry_winner(&[("no_threshold", 0), ("no_deskew", 0)]),
+            None
+        );
+        assert_eq!(pick_retry_winner(&[]), None);
+    }
+
+    /// Test the review message shows the recipe name once one is known
+    #[test]
+    fn test_format_review_message_header() {
+        let manager = setup_localization();
+        use just_ingredients::bot::{format_ingredients_list, format_review_message};
+        use just_ingredients::text_processing::MeasurementDetector;
+
+        let strip_isolation = |text: String| text.replace(['\u{2068}', '\u{2069}'], "");
+        let detector = MeasurementDetector::new().unwrap();
+        let ingredients = detector.extract_ingredient_measurements("2 cups flour\n3 eggs");
+
+        let named = strip_isolation(format_review_message(
+            &ingredients,
+            Some("Chocolate Cake"),
+            None,
+            Some("en"),
+            &manager,
+        ));
+        assert!(
+            named.starts_with("📝 <b>Review your 2 ingredients</b>\nRecipe: Chocolate Cake\n\n")
+        );
+        assert!(named.ends_with(&strip_isolation(format_ingredients_list(
+            &ingredients,
+            None,
+            Some("en"),
+            &manager
+        ))));
+
+        let unnamed = strip_isolation(format_review_message(
+            &ingredients,
+            None,
+            None,
+            Some("en"),
+            &manager,
+        ));
+        assert!(unnamed.starts_with("📝 <b>Review your 2 ingredients</b>\n\n"));
+        assert!(!unnamed.contains("Recipe:"));
+
+        let french = strip_isolation(format_review_message(
+            &ingredients,
+            Some("Gâteau"),
+            None,
+            Some("fr"),
+            &manager,
+        ));
+        assert!(french.contains("Recette : Gâteau"));
+    }
+
+    /// Test the review says when ingredients beyond the review limit were left out
+    #[test]
+    fn test_format_review_cap_notice() {
+        let manager = setup_localization();
+        use just_ingredients::bot::format_review_cap_notice;
+        use just_ingredients::dialogue::MAX_REVIEW_INGREDIENTS;
+
+        let strip_isolation = |text: String| text.replace(['\u{2068}', '\u{2069}'], "");
+
+        assert_eq!(
+            format_review_cap_notice(MAX_REVIEW_INGREDIENTS, Some("en"), &manager),
+            None
+        );
+
+        let english = strip_isolation(
+            format_review_cap_notice(MAX_REVIEW_INGREDIENTS + 10, Some("en"), &manager).unwrap(),
+        );
+        assert_eq!(
+            english,
+            "⚠️ Only the first 50 of the 60 ingredients found were kept."
+        );
+
+        let french = strip_isolation(
+            format_review_cap_notice(MAX_REVIEW_INGREDIENTS + 10, Some("fr"), &manager).unwrap(),
+        );
+        assert!(french.contains("50 premiers des 60 ingrédients"));
+    }
+
+    /// Render Telegram HTML as shown to the user, failing on markup Telegram would reject
+    fn render_telegram_html(html: &str) -> String {
+        let mut text = String::new();
+        let mut open_tags = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find(['<', '&', '>']) {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if let Some(tag_rest) = rest.strip_prefix('<') {
+                let end = tag_rest.find('>').expect("unclosed tag");
+                match &tag_rest[..end] {
+                    tag @ ("b" | "i" | "code") => open_tags.push(tag.to_string()),
+                    closing => {
+                        let name = closing.strip_prefix('/').expect("unsupported tag");
+                        assert_eq!(open_tags.pop().as_deref(), Some(name), "misnested tags");
+                    }
+                }
+                rest = &tag_rest[end + 1..];
+            } else if rest.starts_with('>') {
+                panic!("unescaped '>' in {html}");
+            } else {
+                let (entity, character) = [("&amp;", '&'), ("&lt;", '<'), ("&gt;", '>')]
+                    .into_iter()
+                    .find(|(entity, _)| rest.starts_with(entity))
+                    .expect("unescaped '&'");
+                text.push(character);
+                rest = &rest[entity.len()..];
+            }
+        }
+        text.push_str(rest);
+        assert!(open_tags.is_empty(), "unclosed tags in {html}");
+        text.replace(['\u{2068}', '\u{2069}'], "")
+    }
+
+    /// Hostile recipe and ingredient names go through the HTML views unchanged
+    #[test]
+    fn test_hostile_names_render_in_html_views() {
+        use chrono::{TimeZone, Utc};
+        use just_ingredients::bot::callbacks::recipe_callbacks::format_recipe_statistics;
+        use just_ingredients::bot::formatting::titled;
+        use just_ingredients::bot::ui_builder::{
+            format_bulk_delete_confirmation, format_recipe_details, format_user_statistics,
+            UserStatisticsView,
+        };
+        use just_ingredients::bot::{format_ingredients_list, format_review_message};
+        use just_ingredients::db::{
+            Ingredient, LongestRecipe, Recipe, RecipeId, RecipeListEntry, RecipeStatistics,
+            TelegramId, UserId,
+        };
+        use just_ingredients::text_processing::MeasurementMatch;
+
+        let manager = setup_localization();
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let hostile_names = [
+            "2*2 [test]",
+            "**bold** _italic_ `code`",
+            "<script>alert(1)</script>",
+            "Fish & Chips <3",
+            "snake_case_name",
+            "🍰 Gâteau > 🥧 pie",
+            "&amp; already escaped",
+        ];
+
+        for name in hostile_names {
+            // Recipe creation: the review of extracted ingredients
+            let extracted = vec![MeasurementMatch {
+                quantity: "2".to_string(),
+                measurement: Some("cups".to_string()),
+                ingredient_name: name.to_string(),
+                line_number: 0,
+                start_pos: 0,
+                end_pos: 6,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            }];
+            let review = render_telegram_html(&format_review_message(
+                &extracted,
+                Some(name),
+                None,
+                Some("en"),
+                &manager,
+            ));
+            assert!(review.contains(&format!("Recipe: {name}")));
+            assert!(review.contains(&format!("2 cups → {name}")));
+            let list = render_telegram_html(&format_ingredients_list(
+                &extracted,
+                None,
+                Some("fr"),
+                &manager,
+            ));
+            assert!(list.contains(name));
+
+            // Listing: recipe list title and the bulk-delete confirmation naming recipes
+            let listing = render_telegram_html(&titled("📚", name, &[name]));
+            assert_eq!(listing, format!("📚 {name}\n\n{name}"));
+            let entry = RecipeListEntry {
+                id: RecipeId(1),
+                recipe_name: Some(name.to_string()),
+                created_at,
+            };
+            let confirmation = render_telegram_html(&format_bulk_delete_confirmation(
+                &[entry],
+                just_ingredients::timezone::utc(),
+                Some("en"),
+                &manager,
+            ));
+            assert!(confirmation.contains(&format!("• {name}")));
+
+            // Details: saved recipe with a hostile ingredient
+            let recipe = Recipe {
+                id: RecipeId(1),
+                telegram_id: TelegramId(1),
+                content: String::new(),
+                recipe_name: Some(name.to_string()),
+                created_at,
+                notes: None,
+            };
+            let ingredient = Ingredient {
+                id: 1,
+                user_id: UserId(1),
+                recipe_id: Some(RecipeId(1)),
+                name: name.to_string(),
+                quantity: Some(250.0),
+                unit: Some("g".to_string()),
+                created_at,
+                updated_at: created_at,
+                unit_dimension: None,
+                unit_system: None,
+            };
+            let details = render_telegram_html(&format_recipe_details(
+                &recipe,
+                std::slice::from_ref(&ingredient),
+                None,
+                just_ingredients::timezone::utc(),
+                Some("en"),
+                &manager,
+            ));
+            assert!(details.starts_with(&format!("📖 {name}\n\n")));
+            assert!(details.contains(&format!("• 250 g {name}")));
+
+            // Recipe statistics: recipe name and a hostile unit
+            let hostile_unit = Ingredient {
+                unit: Some(name.to_string()),
+                ..ingredient.clone()
+            };
+            let statistics = render_telegram_html(&format_recipe_statistics(
+                &recipe,
+                &[ingredient.clone(), hostile_unit],
+                just_ingredients::timezone::utc(),
+                Some("en"),
+                &manager,
+            ));
+            assert!(statistics.contains(&format!(": {name}\n\n")));
+            assert!(statistics.contains(&format!("Units used: g, {name}")));
+
+            // User statistics: hostile favorite unit, top ingredient and longest recipe
+            let stats = RecipeStatistics {
+                total_recipes: 3,
+                total_ingredients: 12,
+                average_ingredients_per_recipe: 4.0,
+                oldest_recipe_date: None,
+                newest_recipe_date: None,
+                most_common_units: vec![(name.to_string(), 2)],
+                recipes_created_today: 1,
+                recipes_created_this_week: 2,
+                recipes_created_this_month: 3,
+            };
+            let longest = LongestRecipe {
+                id: RecipeId(1),
+                recipe_name: Some(name.to_string()),
+                ingredient_count: 12,
+            };
+            let statistics = render_telegram_html(&format_user_statistics(
+                &UserStatisticsView {
+                    stats: &stats,
+                    top_ingredients: &[(name.to_string(), 3)],
+                    monthly_counts: &[],
+                    longest_recipe: Some(&longest),
+                },
+                Some("en"),
+                &manager,
+            ));
+            assert!(statistics.contains(&format!("• {name} (2)")));
+            assert!(statistics.contains(&format!("1. {name} (3)")));
+            assert!(statistics.contains(&format!("Longest recipe: {name} (12 ingredients)")));
+        }
+    }
+
+    /// /stats charts recipes per month and handles users without recipes
+    #[test]
+    fn test_user_statistics_view() {
+        use just_ingredients::bot::ui_builder::{
+            format_user_statistics, sparkline, UserStatisticsView,
+        };
+        use just_ingredients::db::RecipeStatistics;
+
+        assert_eq!(sparkline(&[0, 1, 4, 8, 2]), "▁▂▅█▃");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+
+        let manager = setup_localization();
+        let mut stats = RecipeStatistics {
+            total_recipes: 0,
+            total_ingredients: 0,
+            average_ingredients_per_recipe: 0.0,
+            oldest_recipe_date: None,
+            newest_recipe_date: None,
+            most_common_units: Vec::new(),
+            recipes_created_today: 0,
+            recipes_created_this_week: 0,
+            recipes_created_this_month: 0,
+        };
+        let empty = render_telegram_html(&format_user_statistics(
+            &UserStatisticsView {
+                stats: &stats,
+                top_ingredients: &[],
+                monthly_counts: &[],
+                longest_recipe: None,
+            },
+            Some("en"),
+            &manager,
+        ));
+        assert!(empty.contains("no saved recipes yet"));
+
+        stats.total_recipes = 5;
+        stats.total_ingredients = 20;
+        let month = |m| chrono::NaiveDate::from_ymd_opt(2026, m, 1).unwrap();
+        let view = render_telegram_html(&format_user_statistics(
+            &UserStatisticsView {
+                stats: &stats,
+                top_ingredients: &[("eggs".to_string(), 3), ("flour".to_string(), 2)],
+                monthly_counts: &[(month(8), 2), (month(9), 0), (month(10), 2)],
+                longest_recipe: None,
+            },
+            Some("en"),
+            &manager,
+        ));
+        assert!(view.contains("1. eggs (3)\n2. flour (2)"));
+        assert!(view.contains("█▁█\n2026-08 – 2026-10"));
+        // Ties go to the latest month
+        assert!(view.contains("Busiest month: 2026-10 (2)"));
+        assert!(!view.contains("Longest recipe"));
+    }
+
+    /// A 100-ingredient recipe is split under Telegram's limit without losing ingredients
+    #[test]
+    fn test_long_messages_fit_telegram_limit() {
+        use chrono::{TimeZone, Utc};
+        use just_ingredients::bot::callbacks::recipe_callbacks::format_recipe_statistics;
+        use just_ingredients::bot::format_review_message;
+        use just_ingredients::bot::formatting::{
+            message_length, split_message, MAX_MESSAGE_LENGTH,
+        };
+        use just_ingredients::bot::ui_builder::{
+            format_recipe_details, format_user_statistics, UserStatisticsView,
+        };
+        use just_ingredients::db::{
+            Ingredient, LongestRecipe, Recipe, RecipeId, RecipeStatistics, TelegramId, UserId,
+        };
+        use just_ingredients::text_processing::MeasurementMatch;
+
+        let manager = setup_localization();
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let names: Vec<String> = (1..=100)
+            .map(|i| format!("ingrédient n°{i} <finely> chopped & soaked overnight 🌿"))
+            .collect();
+        let recipe = Recipe {
+            id: RecipeId(1),
+            telegram_id: TelegramId(1),
+            content: String::new(),
+            recipe_name: Some("Grand buffet 🍽️".repeat(10)),
+            created_at,
+            notes: None,
+        };
+
+        // Details: every part fits and every ingredient is in exactly one part
+        let ingredients: Vec<Ingredient> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Ingredient {
+                id: i as i64,
+                user_id: UserId(1),
+                recipe_id: Some(RecipeId(1)),
+                name: name.clone(),
+                quantity: Some(i as f64 + 0.5),
+                unit: Some("tablespoons".to_string()),
+                created_at,
+                updated_at: created_at,
+                unit_dimension: None,
+                unit_system: None,
+            })
+            .collect();
+        let details = format_recipe_details(
+            &recipe,
+            &ingredients,
+            None,
+            just_ingredients::timezone::utc(),
+            Some("fr"),
+            &manager,
+        );
+        assert!(message_length(&details) > MAX_MESSAGE_LENGTH);
+        let parts = split_message(&details, MAX_MESSAGE_LENGTH);
+        assert!(parts.len() > 1);
+        let rendered: Vec<String> = parts
+            .iter()
+            .map(|part| {
+                assert!(message_length(part) <= MAX_MESSAGE_LENGTH);
+                render_telegram_html(part)
+            })
+            .collect();
+        for name in &names {
+            let line = format!(" tablespoons {name}\n");
+            let found = rendered
+                .iter()
+                .map(|part| format!("{part}\n").matches(&line).count())
+                .sum::<usize>();
+            assert_eq!(found, 1, "{name} should appear once");
+        }
+
+        // Statistics of a heavy user stay within the limit too
+        let stats = RecipeStatistics {
+            total_recipes: 5000,
+            total_ingredients: 100_000,
+            average_ingredients_per_recipe: 20.0,
+            oldest_recipe_date: None,
+            newest_recipe_date: None,
+            most_common_units: vec![("tablespoons".repeat(20), 999)],
+            recipes_created_today: 10,
+            recipes_created_this_week: 70,
+            recipes_created_this_month: 300,
+        };
+        let statistics = format_recipe_statistics(
+            &recipe,
+            &ingredients,
+            just_ingredients::timezone::utc(),
+            Some("en"),
+            &manager,
+        );
+        for part in split_message(&statistics, MAX_MESSAGE_LENGTH) {
+            assert!(message_length(&part) <= MAX_MESSAGE_LENGTH);
+        }
+        let top_ingredients: Vec<(String, i64)> = names
+            .iter()
+            .take(10)
+            .map(|name| (name.repeat(20), 999))
+            .collect();
+        let monthly_counts: Vec<(chrono::NaiveDate, i64)> = (1..=12)
+            .map(|month| {
+                (
+                    chrono::NaiveDate::from_ymd_opt(2026, month, 1).unwrap(),
+                    i64::from(month) * 10,
+                )
+            })
+            .collect();
+        let longest = LongestRecipe {
+            id: RecipeId(1),
+            recipe_name: recipe.recipe_name.clone(),
+            ingredient_count: 100,
+        };
+        let user_statistics = format_user_statistics(
+            &UserStatisticsView {
+                stats: &stats,
+                top_ingredients: &top_ingredients,
+                monthly_counts: &monthly_counts,
+                longest_recipe: Some(&longest),
+            },
+            Some("fr"),
+            &manager,
+        );
+        assert!(message_length(&user_statistics) > MAX_MESSAGE_LENGTH);
+        for part in split_message(&user_statistics, MAX_MESSAGE_LENGTH) {
+            assert!(message_length(&part) <= MAX_MESSAGE_LENGTH);
+        }
+
+        // Review: edited in place, so kept to one message that counts the hidden lines
+        let extracted: Vec<MeasurementMatch> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| MeasurementMatch {
+                quantity: (i + 1).to_string(),
+                measurement: Some("tablespoons".to_string()),
+                ingredient_name: name.clone(),
+                line_number: i,
+                start_pos: 0,
+                end_pos: 10,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            })
+            .collect();
+        for language in ["en", "fr"] {
+            let review = format_review_message(
+                &extracted,
+                recipe.recipe_name.as_deref(),
+                None,
+                Some(language),
+                &manager,
+            );
+            assert!(message_length(&review) <= MAX_MESSAGE_LENGTH - 512);
+            let review = render_telegram_html(&review);
+            let shown = names
+                .iter()
+                .filter(|name| review.contains(&format!(" → {name}\n")))
+                .count();
+            assert!(shown > 0 && shown < names.len());
+            let hidden = (names.len() - shown).to_string();
+            assert!(review.lines().last().unwrap().contains(&hidden));
+        }
+
+        // Short reviews are unchanged
+        let review = format_review_message(&extracted[..3], None, None, Some("en"), &manager);
+        assert!(!review.contains('…'));
+        assert!(review.ends_with("\n"));
+    }
+
+    /// Test that recent recipe names are offered by index, whatever their length
+    #[test]
+    fn test_recipe_name_suggestions_keyboard() {
+        use just_ingredients::bot::ui_builder::{
+            create_recipe_name_suggestions_keyboard, parse_name_suggestion_callback,
+        };
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let names = vec![
+            "Soup".to_string(),
+            "Soupe de légumes de saison du marché du dimanche matin".to_string(),
+            "Pancakes".to_string(),
+        ];
+        let keyboard = create_recipe_name_suggestions_keyboard(&names).inline_keyboard;
+        assert_eq!(keyboard.len(), 2);
+        assert_eq!(keyboard[0].len(), 2);
+        assert_eq!(keyboard[0][0].text, "Soup");
+        assert!(keyboard[0][1].text.ends_with("..."));
+
+        for (index, button) in keyboard.iter().flatten().enumerate() {
+            let InlineKeyboardButtonKind::CallbackData(data) = &button.kind else {
+                panic!("Expected callback data");
+            };
+            assert!(data.len() <= 64);
+            assert_eq!(parse_name_suggestion_callback(data), Some(index));
+        }
+        assert_eq!(parse_name_suggestion_callback("name_suggestion:x"), None);
+        assert_eq!(parse_name_suggestion_callback("name_conflict_new"), None);
+        assert!(create_recipe_name_suggestions_keyboard(&[])
+            .inline_keyboard
+            .is_empty());
+    }
+
+    /// Test that the review of an unsaved recipe, photo or text, offers its raw text
+    #[test]
+    fn test_review_keyboard_show_raw_text_button() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ocr_raw_text::SHOW_RAW_TEXT_CALLBACK;
+        use just_ingredients::bot::{
+            create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+        };
+        use just_ingredients::experiments::ReviewKeyboardVariant;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let raw_text_button = |keyboard: &teloxide::types::InlineKeyboardMarkup| {
+            keyboard
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .find(|button| {
+                    matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == SHOW_RAW_TEXT_CALLBACK)
+                })
+                .map(|button| button.text.clone())
+        };
+
+        // The caption only names the recipe: both flows share this keyboard
+        for variant in [ReviewKeyboardVariant::Full, ReviewKeyboardVariant::Compact] {
+            let review =
+                create_ingredient_review_keyboard_for_variant(&[], Some("en"), &manager, variant);
+            assert_eq!(
+                raw_text_button(&review).as_deref(),
+                Some("📄 Show raw text")
+            );
+        }
+        let review = create_ingredient_review_keyboard_for_variant(
+            &[],
+            Some("fr"),
+            &manager,
+            ReviewKeyboardVariant::Full,
+        );
+        assert_eq!(
+            raw_text_button(&review).as_deref(),
+            Some("📄 Voir le texte brut")
+        );
+
+        // Saved recipes have no OCR text left to show
+        assert_eq!(
+            raw_text_button(&create_ingredient_review_keyboard(
+                &[],
+                Some("en"),
+                &manager
+            )),
+            None
+        );
+    }
+
+    /// Test that only the OCR review offers the report button and the admin summary is redacted
+    #[test]
+    fn test_report_problem_buttons_and_admin_summary() {
+        let manager = setup_localization();
+        use just_ingredients::bot::problem_reports::format_admin_report_summary;
+        use just_ingredients::bot::{
+            create_ingredient_review_keyboard, create_ingredient_review_keyboard_for_variant,
+        };
+        use just_ingredients::db::{ExtractionReport, TelegramId};
+        use just_ingredients::experiments::ReviewKeyboardVariant;
+        use just_ingredients::extraction_reports::AdminReportSummary;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let has_report_button = |keyboard: &teloxide::types::InlineKeyboardMarkup| {
+            keyboard.inline_keyboard.iter().flatten().any(|button| {
+                matches!(&button.kind, InlineKeyboardButtonKind::CallbackData(data) if data == "report_problem")
+            })
+        };
+
+        let review = create_ingredient_review_keyboard_for_variant(
+            &[],
+            Some("en"),
+            &manager,
+            ReviewKeyboardVariant::Full,
+        );
+        assert!(has_report_button(&review));
+        assert!(review.inline_keyboard.last().unwrap()[0]
+            .text
+            .contains("🚩"));
+        assert!(!has_report_button(&create_ingredient_review_keyboard(
+            &[],
+            Some("en"),
+            &manager
+        )));
+
+        let report = ExtractionReport {
+            id: 3,
+            telegram_id: TelegramId(555123456),
+            correlation_id: "0123456789abcdef".to_string(),
+            extracted_text_hash: "f".repeat(64),
+            match_count: 0,
+            preprocessing_variant: None,
+            comment: Some("call me on 0612345678".to_string()),
+            photo_file_id: None,
+            photo_consent: "not_requested".to_string(),
+        };
+        let summary =
+            format_admin_report_summary(&AdminReportSummary::from_report(&report), None, &manager)
+                .replace(['\u{2068}', '\u{2069}'], "");
+        assert!(summary.contains("#3"));
+        assert!(summary.contains("0123456789abcdef"));
+        assert!(summary.contains("unknown"));
+        assert!(!summary.contains("555123456"));
+        assert!(!summary.contains("0612345678"));
+        assert!(!summary.contains(&"f".repeat(13)));
+    }
+
+    /// Test /recent rendering, relative times and click-through to recipe details
+    #[test]
+    fn test_recent_activity_rendering_and_buttons() {
+        let manager = setup_localization();
+        use chrono::{Duration, TimeZone, Utc};
+        use just_ingredients::bot::recent_activity::{
+            format_activity_line, format_recent_activity_message, format_relative_time,
+        };
+        use just_ingredients::bot::ui_builder::create_recent_activity_keyboard;
+        use just_ingredients::db::{RecipeActivity, RecipeActivityKind, RecipeId};
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let strip = |text: String| text.replace(['\u{2068}', '\u{2069}'], "");
+        let relative = |ago: Duration| strip(format_relative_time(now - ago, now, None, &manager));
+
+        assert_eq!(relative(Duration::seconds(20)), "just now");
+        assert_eq!(relative(Duration::minutes(1)), "1 minute ago");
+        assert_eq!(relative(Duration::hours(5)), "5 hours ago");
+        assert_eq!(relative(Duration::days(2)), "2 days ago");
+        assert_eq!(relative(Duration::days(65)), "2 months ago");
+        assert_eq!(relative(Duration::days(800)), "2 years ago");
+        assert_eq!(
+            strip(format_relative_time(
+                now - Duration::days(1),
+                now,
+                Some("fr"),
+                &manager
+            )),
+            "il y a 1 jour"
+        );
+
+        let activities = vec![
+            RecipeActivity {
+                recipe_id: RecipeId(42),
+                kind: RecipeActivityKind::Saved,
+                recipe_name: Some("Tarte Tatin".to_string()),
+                previous_name: None,
+                ingredient_count: Some(9),
+                occurred_at: now - Duration::days(2),
+            },
+            RecipeActivity {
+                recipe_id: RecipeId(7),
+                kind: RecipeActivityKind::Renamed,
+                recipe_name: Some("Chili".to_string()),
+                previous_name: Some("Recipe".to_string()),
+                ingredient_count: None,
+                occurred_at: now - Duration::days(5),
+            },
+            RecipeActivity {
+                recipe_id: RecipeId(7),
+                kind: RecipeActivityKind::Edited,
+                recipe_name: None,
+                previous_name: None,
+                ingredient_count: Some(1),
+                occurred_at: now - Duration::days(6),
+            },
+        ];
+
+        let lines: Vec<String> = activities
+            .iter()
+            .map(|activity| strip(format_activity_line(activity, now, None, &manager)))
+            .collect();
+        assert_eq!(
+            lines[0],
+            "Saved \"Tarte Tatin\" (9 ingredients) — 2 days ago"
+        );
+        assert_eq!(lines[1], "Renamed \"Recipe\" → \"Chili\" — 5 days ago");
+        assert_eq!(
+            lines[2],
+            "Edited \"Unnamed recipe\" (1 ingredient) — 6 days ago"
+        );
+
+        let message = strip(format_recent_activity_message(
+            &activities,
+            11,
+            now,
+            None,
+            &manager,
+        ));
+        assert!(message.contains("11. Saved \"Tarte Tatin\""));
+        assert!(message.contains("13. Edited"));
+
+        // Each row opens the recipe details; page 2 of 3 links both ways
+        let keyboard = create_recent_activity_keyboard(&activities, 11, 1, 25, 10, None, &manager);
+        let callback = |button: &teloxide::types::InlineKeyboardButton| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => data.clone(),
+            _ => String::new(),
+        };
+        let rows = &keyboard.inline_keyboard;
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0][0].text, "11. Tarte Tatin");
+        assert_eq!(callback(&rows[0][0]), "recipe_instance:42");
+        assert_eq!(callback(&rows[1][0]), "recipe_instance:7");
+        let navigation: Vec<String> = rows[3].iter().map(callback).collect();
+        assert_eq!(navigation, ["recent_page:0", "noop", "recent_page:2"]);
+    }
+
+    /// Test that the editor title tracks the ingredient count as rows are deleted
+    #[test]
+    fn test_editing_title_count_updates_after_deletions() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ui_builder::format_editing_title;
+        use just_ingredients::text_processing::MeasurementMatch;
+
+        let mut ingredients: Vec<MeasurementMatch> = ["flour", "eggs", "milk"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| MeasurementMatch {
+                quantity: "1".to_string(),
+                measurement: None,
+                ingredient_name: name.to_string(),
+                line_number: i,
+                start_pos: 0,
+                end_pos: 1,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            })
+            .collect();
+
+        let title = |count, lang| {
+            format_editing_title(count, Some("Pancakes"), Some(lang), &manager)
+                .replace(['\u{2068}', '\u{2069}'], "")
+        };
+
+        assert_eq!(
+            title(ingredients.len(), "en"),
+            "Editing recipe: Pancakes (3 ingredients)"
+        );
+        ingredients.remove(0);
+        assert_eq!(
+            title(ingredients.len(), "en"),
+            "Editing recipe: Pancakes (2 ingredients)"
+        );
+        ingredients.remove(0);
+        assert_eq!(
+            title(ingredients.len(), "en"),
+            "Editing recipe: Pancakes (1 ingredient)"
+        );
+        ingredients.remove(0);
+        assert_eq!(title(ingredients.len(), "fr"), title(0, "fr"));
+        assert!(title(ingredients.len(), "fr").ends_with("(0 ingrédient)"));
+
+        let untitled = format_editing_title(2, None, Some("en"), &manager)
+            .replace(['\u{2068}', '\u{2069}'], "");
+        assert_eq!(untitled, "Editing recipe (2 ingredients)");
+    }
+
+    /// Test the keyboard offered when a caption-named recipe already exists
+    #[test]
+    fn test_name_conflict_keyboard() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ui_builder::create_name_conflict_keyboard;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let callback_data = |include_view| -> Vec<String> {
+            create_name_conflict_keyboard(include_view, Some("en"), &manager)
+                .inline_keyboard
+                .iter()
+                .flatten()
+                .filter_map(|button| match &button.kind {
+                    InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            callback_data(true),
+            vec![
+                "name_conflict_new",
+                "name_conflict_replace",
+                "name_conflict_merge",
+                "name_conflict_view"
+            ]
+        );
+        // Shown under the existing recipe, where viewing it again makes no sense
+        assert_eq!(
+            callback_data(false),
+            vec![
+                "name_conflict_new",
+                "name_conflict_replace",
+                "name_conflict_merge"
+            ]
+        );
+
+        let keyboard = create_name_conflict_keyboard(true, Some("fr"), &manager);
+        assert_eq!(
+            keyboard.inline_keyboard[0][0].text,
+            "➕ Enregistrer une nouvelle copie"
+        );
+    }
+
+    /// Test the quantity adjustment keyboard for one ingredient
+    #[test]
+    fn test_quantity_adjust_keyboard() {
+        let manager = setup_localization();
+        use just_ingredients::bot::ui_builder::create_quantity_adjust_keyboard;
+        use just_ingredients::text_processing::MeasurementMatch;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let ingredient = MeasurementMatch {
+            quantity: "2".to_string(),
+            measurement: Some("cups".to_string()),
+            ingredient_name: "flour".to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: 12,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        };
+        let keyboard = create_quantity_adjust_keyboard(4, &ingredient, Some("en"), &manager);
+
+        let callback_data: Vec<String> = keyboard
+            .inline_keyboard
+            .iter()
+            .flatten()
+            .filter_map(|button| match &button.kind {
+                InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            callback_data,
+            vec![
+                "adjust_4:-1",
+                "adjust_4:-½",
+                "adjust_4:+½",
+                "adjust_4:+1",
+                "adjust_done"
+            ]
+        );
+        assert!(keyboard.inline_keyboard[1][0].text.contains("2 cups"));
+    }
+
+    /// Test which callbacks are recognised as coming from an ingredient review
+    #[test]
+    fn test_is_review_keyboard_callback() {
+        use just_ingredients::bot::callbacks::review_callbacks::is_review_keyboard_callback;
+
+        for data in [
+            "confirm",
+            "add_more",
+            "cancel_review",
+            "cancel_ingredient_editing",
+            "edit_3",
+            "delete_0",
+            "name_conflict_merge",
+            "adjust_2",
+            "adjust_2:+½",
+            "adjust_done",
+        ] {
+            assert!(is_review_keyboard_callback(data), "{data}");
+        }
+        for data in [
+            "delete_all:confirm",
+            "edit_",
+            "select_recipe:12",
+            "bulk_delete:confirm",
+            "cancel_processing",
+        ] {
+            assert!(!is_review_keyboard_callback(data), "{data}");
+        }
+    }
+
+    /// Test which callbacks wait out a flood wait instead of editing their message
+    #[test]
+    fn test_is_pagination_callback() {
+        use just_ingredients::bot::callbacks::workflow_callbacks::is_pagination_callback;
+
+        for data in ["page:0", "page:12", "recent_page:1", "tag_page:dessert:2"] {
+            assert!(is_pagination_callback(data), "{data}");
+        }
+        for data in [
+            "confirm",
+            "select_recipe:12",
+            "bulk_delete:page:1",
+            "scale_save:2",
+        ] {
+            assert!(!is_pagination_callback(data), "{data}");
+        }
+    }
+
+    /// Test that callbacks are measured under a fixed set of operations
+    #[test]
+    fn test_callback_operation_labels() {
+        use just_ingredients::bot::callbacks::callback_handler::callback_operation;
+        use just_ingredients::bot::ui_builder::recipe_selection_callback;
+        use just_ingredients::dialogue::RecipeDialogueState;
+        use just_ingredients::observability::TelegramOperation;
+
+        let selection = recipe_selection_callback("Grand-mère's \"secret\" cake 🎂");
+        for (data, expected) in [
+            ("confirm", TelegramOperation::ReviewConfirm),
+            ("name_conflict_new", TelegramOperation::ReviewConfirm),
+            ("name_suggestion:2", TelegramOperation::ReviewConfirm),
+            ("delete_3", TelegramOperation::ReviewDelete),
+            ("undo_delete", TelegramOperation::ReviewDelete),
+            ("edit_0", TelegramOperation::ReviewEdit),
+            ("add_more", TelegramOperation::ReviewEdit),
+            ("raw_text_show", TelegramOperation::ReviewEdit),
+            ("page:2", TelegramOperation::Pagination),
+            ("tag_page:dessert:1", TelegramOperation::Pagination),
+            (selection.as_str(), TelegramOperation::RecipeSelect),
+            ("recipe_instance:12", TelegramOperation::RecipeSelect),
+            ("back_to_recipes", TelegramOperation::RecipeSelect),
+            ("recipe_action:scale:12", TelegramOperation::RecipeAction),
+            (
+                "confirm_delete_recipe:12:5",
+                TelegramOperation::RecipeDelete,
+            ),
+            ("bulk_delete:page:1", TelegramOperation::RecipeDelete),
+            ("workflow_add_another", TelegramOperation::Workflow),
+            ("units:metric", TelegramOperation::Settings),
+            ("ocr_retry_profiles", TelegramOperation::Ocr),
+            ("report_problem", TelegramOperation::Report),
+            ("import_resume", TelegramOperation::Document),
+            (
+                "anything a user could forge",
+                TelegramOperation::OtherCallback,
+            ),
+            ("", TelegramOperation::OtherCallback),
+        ] {
+            assert_eq!(callback_operation(None, data), expected, "{data}");
+        }
+
+        // Buttons of the saved recipe editor reuse review data
+        let editing = RecipeDialogueState::EditingSavedIngredients {
+            recipe_id: 1,
+            original_ingredients: Vec::new(),
+            current_matches: Vec::new(),
+            language_code: None,
+            message_id: Some(1),
+            last_deleted: None,
+            ingredients_version: 0,
+        };
+        assert_eq!(
+            callback_operation(Some(&editing), "delete_0"),
+            TelegramOperation::SavedEdit
+        );
+        assert_eq!(
+            callback_operation(Some(&RecipeDialogueState::Start), "delete_0"),
+            TelegramOperation::ReviewDelete
+        );
+    }
+
     /// Test callback data parsing for ingredient actions
     #[test]
     fn test_callback_data_parsing() {
@@ -757,6 +2294,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -766,6 +2307,10 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "1".to_string(),
@@ -775,6 +2320,10 @@ mod tests {
                 start_pos: 15,
                 end_pos: 21,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
@@ -814,7 +2363,7 @@ mod tests {
     fn test_ingredient_list_formatting() {
         let manager = setup_localization();
         use just_ingredients::bot::format_ingredients_list;
-        use just_ingredients::text_processing::MeasurementMatch;
+        use just_ingredients::text_processing::{MeasurementMatch, UnitSystem};
 
         let ingredients = vec![
             MeasurementMatch {
@@ -825,6 +2374,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -834,6 +2387,10 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "0".to_string(),
@@ -843,10 +2400,14 @@ mod tests {
                 start_pos: 10,
                 end_pos: 16,
                 requires_quantity_confirmation: true,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
-        let formatted = format_ingredients_list(&ingredients, Some("en"), &manager);
+        let formatted = format_ingredients_list(&ingredients, None, Some("en"), &manager);
 
         // Should contain all ingredients
         assert!(formatted.contains("flour"));
@@ -858,6 +2419,140 @@ mod tests {
 
         // Should be formatted as a list
         assert!(formatted.contains("\n") || formatted.contains("•"));
+
+        // With a unit preference, convertible quantities are shown converted
+        let formatted =
+            format_ingredients_list(&ingredients, Some(UnitSystem::Metric), Some("en"), &manager);
+        assert!(formatted.contains("475 ml"));
+        assert!(formatted.contains("<b>3</b> → eggs"));
+    }
+
+    /// Test the account wipe only accepts the phrase of the language it was asked in
+    #[test]
+    fn test_wipe_confirmation_phrase() {
+        use just_ingredients::bot::account_deletion::is_wipe_confirmation;
+        let manager = setup_localization();
+
+        assert!(is_wipe_confirmation(
+            &manager,
+            "DELETE EVERYTHING",
+            Some("en")
+        ));
+        assert!(is_wipe_confirmation(
+            &manager,
+            "  DELETE EVERYTHING\n",
+            Some("en")
+        ));
+        assert!(is_wipe_confirmation(&manager, "TOUT SUPPRIMER", Some("fr")));
+        assert!(!is_wipe_confirmation(
+            &manager,
+            "delete everything",
+            Some("en")
+        ));
+        assert!(!is_wipe_confirmation(
+            &manager,
+            "TOUT SUPPRIMER",
+            Some("en")
+        ));
+        assert!(!is_wipe_confirmation(&manager, "yes", Some("en")));
+    }
+
+    /// Test ingredients read from doubtful OCR lines are marked for review
+    #[test]
+    fn test_format_ingredients_list_marks_low_confidence() {
+        let manager = setup_localization();
+        use just_ingredients::bot::format_ingredients_list;
+        use just_ingredients::text_processing::MeasurementMatch;
+
+        let ingredient = |name: &str, confidence: Option<f32>| MeasurementMatch {
+            quantity: "2".to_string(),
+            measurement: Some("cups".to_string()),
+            ingredient_name: name.to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: 6,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence,
+            quantity_max: None,
+        };
+        let ingredients = vec![
+            ingredient("flour", Some(0.95)),
+            ingredient("sugar", Some(0.2)),
+            ingredient("milk", None),
+        ];
+
+        let formatted = format_ingredients_list(&ingredients, None, Some("en"), &manager);
+
+        assert!(formatted.contains("<b>2 cups</b> → flour"));
+        assert!(formatted.contains("<b>⚠️ 2 cups</b> → sugar"));
+        assert!(formatted.contains("<b>2 cups</b> → milk"));
+    }
+
+    /// Test range quantities are listed with both bounds and never converted
+    #[test]
+    fn test_format_ingredients_list_shows_ranges() {
+        let manager = setup_localization();
+        use just_ingredients::bot::format_ingredients_list;
+        use just_ingredients::text_processing::{MeasurementDetector, UnitSystem};
+
+        let detector = MeasurementDetector::new().unwrap();
+        let ingredients = detector.extract_ingredient_measurements("2-3 tbsp olive oil\n2 eggs");
+
+        let formatted = format_ingredients_list(&ingredients, None, Some("en"), &manager);
+        assert!(formatted.contains("<b>2–3 tbsp</b> → olive oil"));
+        assert!(formatted.contains("<b>2</b> → eggs"));
+
+        let formatted =
+            format_ingredients_list(&ingredients, Some(UnitSystem::Metric), Some("en"), &manager);
+        assert!(formatted.contains("<b>2–3 tbsp</b> → olive oil"));
+    }
+
+    #[test]
+    fn test_format_database_ingredients_list_converts_units() {
+        use just_ingredients::bot::ui_builder::format_database_ingredients_list;
+        use just_ingredients::db::{Ingredient, UserId};
+        use just_ingredients::text_processing::UnitSystem;
+
+        let manager = setup_localization();
+        let now = chrono::Utc::now();
+        let ingredient = |name: &str, quantity: Option<f64>, unit: Option<&str>| Ingredient {
+            id: 1,
+            user_id: UserId(1),
+            recipe_id: None,
+            name: name.to_string(),
+            quantity,
+            unit: unit.map(str::to_string),
+            created_at: now,
+            updated_at: now,
+            unit_dimension: None,
+            unit_system: None,
+        };
+        let ingredients = vec![
+            ingredient("flour", Some(250.0), Some("g")),
+            ingredient("milk", Some(1.5), Some("cups")),
+            ingredient("bread", Some(2.0), Some("slices")),
+            ingredient("eggs", Some(3.0), None),
+            ingredient("sugar", Some(1.0 / 3.0), Some("cup")),
+        ];
+
+        let as_written = format_database_ingredients_list(&ingredients, None, Some("en"), &manager);
+        assert!(as_written.contains("• 250 g flour"));
+        assert!(as_written.contains("• 1 1/2 cups milk"));
+        assert!(as_written.contains("• 1/3 cup sugar"));
+
+        let imperial = format_database_ingredients_list(
+            &ingredients,
+            Some(UnitSystem::Imperial),
+            Some("en"),
+            &manager,
+        );
+        assert!(imperial.contains("• 9 oz flour"));
+        assert!(imperial.contains("• 1 1/2 cups milk"));
+        // Count-based units and bare counts pass through unchanged
+        assert!(imperial.contains("• 2 slices bread"));
+        assert!(imperial.contains("• 3 eggs"));
     }
 
     /// Test recipes pagination keyboard creation
@@ -865,6 +2560,7 @@ mod tests {
     fn test_recipes_pagination_keyboard_creation() {
         let manager = setup_localization();
         use just_ingredients::bot::create_recipes_pagination_keyboard;
+        use just_ingredients::bot::ui_builder::recipe_selection_callback;
         use teloxide::types::{InlineKeyboardButtonKind, InlineKeyboardMarkup};
 
         // Test with multiple recipes and first page
@@ -886,14 +2582,14 @@ mod tests {
             inline_keyboard: keyboard,
         } = keyboard;
         {
-            // Should have 3 rows: 2 recipe rows + 1 navigation row
-            assert_eq!(keyboard.len(), 3);
+            // Should have 4 rows: 2 recipe rows + 1 navigation row + bulk-delete toggle
+            assert_eq!(keyboard.len(), 4);
 
             // First row: Apple Pie button
             assert_eq!(keyboard[0].len(), 1);
             assert!(keyboard[0][0].text.contains("Apple Pie"));
             if let InlineKeyboardButtonKind::CallbackData(data) = &keyboard[0][0].kind {
-                assert!(data.contains("select_recipe:Apple Pie"));
+                assert_eq!(data, &recipe_selection_callback("Apple Pie"));
             } else {
                 panic!("Expected callback button");
             }
@@ -902,7 +2598,7 @@ mod tests {
             assert_eq!(keyboard[1].len(), 1);
             assert!(keyboard[1][0].text.contains("Chocolate Cake"));
             if let InlineKeyboardButtonKind::CallbackData(data) = &keyboard[1][0].kind {
-                assert!(data.contains("select_recipe:Chocolate Cake"));
+                assert_eq!(data, &recipe_selection_callback("Chocolate Cake"));
             } else {
                 panic!("Expected callback button");
             }
@@ -916,6 +2612,14 @@ mod tests {
             } else {
                 panic!("Expected callback button");
             }
+
+            // Last row: switch to manage mode
+            assert_eq!(keyboard[3][0].text, "🧹 Manage");
+            if let InlineKeyboardButtonKind::CallbackData(data) = &keyboard[3][0].kind {
+                assert_eq!(data, "bulk_delete:start");
+            } else {
+                panic!("Expected callback button");
+            }
         }
     }
 
@@ -944,8 +2648,8 @@ mod tests {
             inline_keyboard: keyboard,
         } = keyboard;
         {
-            // Should have 2 rows: 1 recipe row + 1 navigation row
-            assert_eq!(keyboard.len(), 2);
+            // Should have 3 rows: 1 recipe row + 1 navigation row + bulk-delete toggle
+            assert_eq!(keyboard.len(), 3);
 
             // First row: Banana Bread button
             assert_eq!(keyboard[0].len(), 1);
@@ -988,8 +2692,8 @@ mod tests {
             inline_keyboard: keyboard,
         } = keyboard;
         {
-            // Should have only 1 row: just the recipe button (no navigation)
-            assert_eq!(keyboard.len(), 1);
+            // Should have 2 rows: the recipe button and the bulk-delete toggle (no navigation)
+            assert_eq!(keyboard.len(), 2);
 
             // First row: Simple Recipe button
             assert_eq!(keyboard[0].len(), 1);
@@ -997,6 +2701,154 @@ mod tests {
         }
     }
 
+    /// Test that the bulk-delete selection survives page changes
+    #[test]
+    fn test_bulk_delete_selection_across_pages() {
+        let manager = setup_localization();
+        use chrono::{TimeZone, Utc};
+        use just_ingredients::bot::callbacks::bulk_delete_callbacks::{
+            format_bulk_delete_message, toggle_recipe_selection, MAX_BULK_DELETE_SELECTION,
+        };
+        use just_ingredients::bot::ui_builder::{
+            create_bulk_delete_keyboard, format_bulk_delete_confirmation,
+        };
+        use just_ingredients::db::{RecipeId, RecipeListEntry};
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let created_at = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let entry = |id: i64, name: &str| RecipeListEntry {
+            id: RecipeId(id),
+            recipe_name: Some(name.to_string()),
+            created_at,
+        };
+        let page_one = vec![entry(1, "Apple Pie"), entry(2, "Brownies")];
+        let page_two = vec![entry(3, "Crêpes"), entry(4, "Test recipe")];
+        let callback = |button: &teloxide::types::InlineKeyboardButton| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => data.clone(),
+            other => panic!("Expected callback button, got {:?}", other),
+        };
+
+        // Select one recipe on each page
+        let mut selected = Vec::new();
+        toggle_recipe_selection(&mut selected, 2);
+        toggle_recipe_selection(&mut selected, 3);
+        assert_eq!(selected, vec![2, 3]);
+
+        let keyboard = create_bulk_delete_keyboard(
+            &page_two,
+            &selected,
+            1,
+            4,
+            2,
+            just_ingredients::timezone::utc(),
+            None,
+            &manager,
+        )
+        .inline_keyboard;
+        assert!(keyboard[0][0].text.starts_with("✅ Crêpes · 10/03/26"));
+        assert!(keyboard[1][0].text.starts_with("⬜ Test recipe"));
+        assert_eq!(callback(&keyboard[0][0]), "bulk_delete:toggle:3");
+        assert_eq!(callback(&keyboard[2][0]), "bulk_delete:page:0");
+
+        // Back on the first page, the earlier pick is still marked
+        let keyboard = create_bulk_delete_keyboard(
+            &page_one,
+            &selected,
+            0,
+            4,
+            2,
+            just_ingredients::timezone::utc(),
+            None,
+            &manager,
+        )
+        .inline_keyboard;
+        assert!(keyboard[0][0].text.starts_with("⬜ Apple Pie"));
+        assert!(keyboard[1][0].text.starts_with("✅ Brownies"));
+        assert_eq!(callback(&keyboard[2][1]), "bulk_delete:page:1");
+        let actions = &keyboard[3];
+        assert!(actions[0]
+            .text
+            .replace(['\u{2068}', '\u{2069}'], "")
+            .contains("Delete selected (2)"));
+        assert_eq!(callback(&actions[0]), "bulk_delete:confirm");
+        assert_eq!(actions[1].text, "❌ Exit manage mode");
+        assert_eq!(callback(&actions[1]), "bulk_delete:cancel");
+
+        // Toggling again deselects; without a selection only the exit is offered
+        toggle_recipe_selection(&mut selected, 2);
+        toggle_recipe_selection(&mut selected, 3);
+        assert!(selected.is_empty());
+        let keyboard = create_bulk_delete_keyboard(
+            &page_one,
+            &selected,
+            0,
+            2,
+            2,
+            just_ingredients::timezone::utc(),
+            None,
+            &manager,
+        )
+        .inline_keyboard;
+        assert_eq!(keyboard.len(), 3);
+        assert_eq!(callback(&keyboard[2][0]), "bulk_delete:cancel");
+
+        // The selection is capped
+        let mut selected: Vec<i64> = (0..MAX_BULK_DELETE_SELECTION as i64).collect();
+        toggle_recipe_selection(&mut selected, 9999);
+        assert_eq!(selected.len(), MAX_BULK_DELETE_SELECTION);
+
+        let strip = |text: String| text.replace(['\u{2068}', '\u{2069}'], "");
+        let message = strip(format_bulk_delete_message(2, None, &manager));
+        assert!(message.starts_with("🧹 <b>Manage recipes</b>"));
+        assert!(message.contains("exit manage mode"));
+        assert!(message.contains("2 recipes selected"));
+
+        // One combined confirmation names every selected recipe
+        let confirmation = strip(format_bulk_delete_confirmation(
+            &[entry(2, "Brownies"), entry(3, "Crêpes")],
+            just_ingredients::timezone::utc(),
+            None,
+            &manager,
+        ));
+        assert!(confirmation.contains("Delete 2 recipes?"));
+        assert!(confirmation.contains("• Brownies · 10/03/26"));
+        assert!(confirmation.contains("• Crêpes · 10/03/26"));
+    }
+
+    /// Test that cancelling selection mode restores the normal list keyboard
+    #[test]
+    fn test_bulk_delete_cancel_restores_list_keyboard() {
+        let manager = setup_localization();
+        use just_ingredients::bot::create_recipes_pagination_keyboard;
+        use just_ingredients::bot::ui_builder::recipe_selection_callback;
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        // Cancel redraws page 0 with the regular keyboard: names open recipes, no marks
+        let recipes = vec!["Apple Pie".to_string(), "Brownies".to_string()];
+        let keyboard =
+            create_recipes_pagination_keyboard(&recipes, 0, 2, 5, None, &manager).inline_keyboard;
+        let callbacks: Vec<String> = keyboard
+            .iter()
+            .flatten()
+            .filter_map(|button| match &button.kind {
+                InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            callbacks,
+            vec![
+                recipe_selection_callback("Apple Pie"),
+                recipe_selection_callback("Brownies"),
+                "bulk_delete:start".to_string()
+            ]
+        );
+        assert!(keyboard
+            .iter()
+            .flatten()
+            .all(|button| !button.text.starts_with('✅') && !button.text.starts_with('⬜')));
+    }
+
     /// Test recipes pagination keyboard with long recipe names
     #[test]
     fn test_recipes_pagination_keyboard_long_names() {
@@ -1060,11 +2912,26 @@ mod tests {
     /// Test callback data parsing for recipes
     #[test]
     fn test_recipes_callback_data_parsing() {
+        use just_ingredients::bot::callbacks::recipe_callbacks::RecipeSelection;
+        use just_ingredients::bot::ui_builder::recipe_selection_callback;
+        use just_ingredients::db::recipe_name_token;
+
         // Test recipe selection callback parsing
-        let select_callback = "select_recipe:Chocolate Cake";
-        assert!(select_callback.starts_with("select_recipe:"));
-        let recipe_name = select_callback.strip_prefix("select_recipe:").unwrap();
-        assert_eq!(recipe_name, "Chocolate Cake");
+        let select_callback = recipe_selection_callback("Chocolate Cake");
+        assert_eq!(
+            RecipeSelection::parse(&select_callback),
+            Some(RecipeSelection::Token(recipe_name_token("Chocolate Cake")))
+        );
+
+        // Buttons of messages sent before tokens still carry the name
+        assert_eq!(
+            RecipeSelection::parse("select_recipe:Chocolate Cake"),
+            Some(RecipeSelection::Name("Chocolate Cake".to_string()))
+        );
+        assert_eq!(
+            RecipeSelection::parse("select_recipe:Soup: Part 2"),
+            Some(RecipeSelection::Name("Soup: Part 2".to_string()))
+        );
 
         // Test pagination callback parsing
         let page_callback = "page:2";
@@ -1080,10 +2947,50 @@ mod tests {
 
         // Test invalid callbacks (should not crash)
         let invalid_callback = "invalid_data";
-        assert!(!invalid_callback.starts_with("select_recipe:"));
+        assert_eq!(RecipeSelection::parse(invalid_callback), None);
         assert!(!invalid_callback.starts_with("page:"));
     }
 
+    /// Test that 100-character unicode recipe names fit in selection callbacks
+    #[test]
+    fn test_recipe_selection_callback_long_unicode_names() {
+        let manager = setup_localization();
+        use just_ingredients::bot::callbacks::recipe_callbacks::RecipeSelection;
+        use just_ingredients::bot::create_recipes_pagination_keyboard;
+        use just_ingredients::db::{recipe_name_token, RECIPE_NAME_TOKEN_LENGTH};
+        use teloxide::types::InlineKeyboardButtonKind;
+
+        let recipes = vec![
+            "🍰".repeat(100),
+            "Gâteau: ".repeat(12) + "fin!",
+            "Soupe à l'oignon gratinée de grand-mère Édith 🧅🧀".repeat(2) + "!!!!",
+            "Short".to_string(),
+        ];
+        let keyboard = create_recipes_pagination_keyboard(&recipes, 0, 4, 10, Some("fr"), &manager)
+            .inline_keyboard;
+
+        for (recipe_name, row) in recipes.iter().zip(&keyboard) {
+            let InlineKeyboardButtonKind::CallbackData(data) = &row[0].kind else {
+                panic!("Expected callback button");
+            };
+            assert!(data.len() <= 64, "{data} exceeds Telegram's limit");
+            let Some(RecipeSelection::Token(token)) = RecipeSelection::parse(data) else {
+                panic!("Expected a token selection in {data}");
+            };
+            assert_eq!(token.len(), RECIPE_NAME_TOKEN_LENGTH);
+            assert_eq!(token, recipe_name_token(recipe_name));
+        }
+
+        // Names sharing a long prefix still get distinct tokens
+        let tokens: std::collections::HashSet<String> =
+            recipes.iter().map(|name| recipe_name_token(name)).collect();
+        assert_eq!(tokens.len(), recipes.len());
+        assert_ne!(
+            recipe_name_token(&("a".repeat(99) + "b")),
+            recipe_name_token(&("a".repeat(99) + "c"))
+        );
+    }
+
     /// Test post-confirmation keyboard creation
     #[test]
     fn test_post_confirmation_keyboard_creation() {
@@ -1189,22 +3096,23 @@ mod tests {
     #[test]
     fn test_workflow_message_formatting() {
         let manager = setup_localization();
+        use just_ingredients::bot::formatting::titled;
         use just_ingredients::localization::t_lang;
 
         // Test confirmation message formatting
         let recipe_saved = t_lang(&manager, "workflow-recipe-saved", Some("en"));
         let what_next = t_lang(&manager, "workflow-what-next", Some("en"));
-        let confirmation_message = format!("✅ **{}**\n\n{}", recipe_saved, what_next);
+        let confirmation_message = titled("✅", &recipe_saved, &[&what_next]);
 
         assert!(confirmation_message.contains("Recipe saved"));
         assert!(confirmation_message.contains("What would you like to do next"));
         assert!(confirmation_message.contains("✅"));
-        assert!(confirmation_message.contains("**"));
+        assert!(confirmation_message.contains("<b>"));
 
         // Test French version
         let recipe_saved_fr = t_lang(&manager, "workflow-recipe-saved", Some("fr"));
         let what_next_fr = t_lang(&manager, "workflow-what-next", Some("fr"));
-        let confirmation_message_fr = format!("✅ **{}**\n\n{}", recipe_saved_fr, what_next_fr);
+        let confirmation_message_fr = titled("✅", &recipe_saved_fr, &[&what_next_fr]);
 
         assert!(confirmation_message_fr.contains("Recette"));
         assert!(confirmation_message_fr.contains("ensuite"));
@@ -1430,23 +3338,27 @@ mod tests {
         let original_ingredients = vec![
             just_ingredients::db::Ingredient {
                 id: 1,
-                user_id: 12345, // dummy user_id
-                recipe_id: Some(recipe_id),
+                user_id: just_ingredients::db::UserId(12345), // dummy user_id
+                recipe_id: Some(just_ingredients::db::RecipeId(recipe_id)),
                 name: "flour".to_string(),
                 quantity: Some(2.0),
                 unit: Some("cups".to_string()),
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
+                unit_dimension: None,
+                unit_system: None,
             },
             just_ingredients::db::Ingredient {
                 id: 2,
-                user_id: 12345, // dummy user_id
-                recipe_id: Some(recipe_id),
+                user_id: just_ingredients::db::UserId(12345), // dummy user_id
+                recipe_id: Some(just_ingredients::db::RecipeId(recipe_id)),
                 name: "eggs".to_string(),
                 quantity: Some(3.0),
                 unit: None,
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
+                unit_dimension: None,
+                unit_system: None,
             },
         ];
 
@@ -1459,6 +3371,10 @@ mod tests {
                 start_pos: 0,
                 end_pos: 6,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
             MeasurementMatch {
                 quantity: "3".to_string(),
@@ -1468,15 +3384,23 @@ mod tests {
                 start_pos: 8,
                 end_pos: 9,
                 requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
             },
         ];
 
         let dialogue_state = RecipeDialogueState::EditingSavedIngredients {
             recipe_id,
-            original_ingredients: original_ingredients.clone(),
+            original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(
+                &original_ingredients,
+            ),
             current_matches,
             language_code: Some("en".to_string()),
             message_id: Some(12345),
+            last_deleted: None,
+            ingredients_version: 3,
         };
 
         // Verify the dialogue state is correctly structured
@@ -1487,12 +3411,15 @@ mod tests {
                 current_matches: state_current,
                 language_code: state_lang,
                 message_id: state_msg_id,
+                last_deleted: _,
+                ingredients_version: state_version,
             } => {
                 assert_eq!(*state_recipe_id, recipe_id);
                 assert_eq!(state_original.len(), 2);
                 assert_eq!(state_current.len(), 2);
                 assert_eq!(*state_lang, Some("en".to_string()));
                 assert_eq!(*state_msg_id, Some(12345));
+                assert_eq!(*state_version, 3);
             }
             _ => panic!("Expected EditingSavedIngredients state"),
         }
@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
 use just_ingredients::db::*;
+use just_ingredients::quantity::Quantity;
 use sqlx::PgPool;
 use std::env;
 
@@ -53,17 +54,17 @@ async fn test_user_operations() -> Result<()> {
 }
 
 async fn test_user_operations_impl(pool: &PgPool) -> Result<()> {
-    let user = get_or_create_user(pool, 12345, Some("fr")).await?;
-    assert_eq!(user.telegram_id, 12345);
+    let user = get_or_create_user(pool, TelegramId(12345), Some("fr")).await?;
+    assert_eq!(user.telegram_id, TelegramId(12345));
     assert_eq!(user.language_code, "fr");
 
     // Test getting existing user
-    let user2 = get_or_create_user(pool, 12345, Some("en")).await?;
+    let user2 = get_or_create_user(pool, TelegramId(12345), Some("en")).await?;
     assert_eq!(user2.id, user.id); // Should return same user
     assert_eq!(user2.language_code, "fr"); // Should keep original language
 
     // Test get_user_by_telegram_id
-    let found_user = get_user_by_telegram_id(pool, 12345).await?;
+    let found_user = get_user_by_telegram_id(pool, TelegramId(12345)).await?;
     assert_eq!(found_user, Some(user.clone()));
 
     // Test get_user_by_id
@@ -79,14 +80,14 @@ async fn test_recipe_operations() -> Result<()> {
 }
 
 async fn test_recipe_operations_impl(pool: &PgPool) -> Result<()> {
-    let recipe_id = create_recipe(pool, 12345, "Test OCR content").await?;
-    assert!(recipe_id > 0);
+    let recipe_id = create_recipe(pool, TelegramId(12345), "Test OCR content").await?;
+    assert!(recipe_id > RecipeId(0));
 
     // Read recipe
     let recipe = read_recipe(pool, recipe_id).await?;
     assert!(recipe.is_some());
     let recipe = recipe.unwrap();
-    assert_eq!(recipe.telegram_id, 12345);
+    assert_eq!(recipe.telegram_id, TelegramId(12345));
     assert_eq!(recipe.content, "Test OCR content");
 
     // Update recipe
@@ -112,10 +113,10 @@ async fn test_ingredient_operations() -> Result<()> {
 }
 
 async fn test_ingredient_operations_impl(pool: &PgPool) -> Result<()> {
-    let user = get_or_create_user(pool, 12345, None).await?;
+    let user = get_or_create_user(pool, TelegramId(12345), None).await?;
 
     // Create recipe
-    let recipe_id = create_recipe(pool, 12345, "flour 2 cups").await?;
+    let recipe_id = create_recipe(pool, TelegramId(12345), "flour 2 cups").await?;
 
     // Create ingredient
     let ingredient_id = create_ingredient(
@@ -170,256 +171,2119 @@ async fn test_ingredient_operations_impl(pool: &PgPool) -> Result<()> {
 }
 
 #[tokio::test]
-async fn test_full_text_search() -> Result<()> {
-    skip_if_no_db!(test_full_text_search_impl)
+async fn test_fraction_quantities_read_back_exactly() -> Result<()> {
+    skip_if_no_db!(test_fraction_quantities_read_back_exactly_impl)
 }
 
-async fn test_full_text_search_impl(pool: &PgPool) -> Result<()> {
-    create_recipe(pool, 12345, "flour 2 cups sugar 1 cup").await?;
-    create_recipe(pool, 12345, "butter 100 grams milk 250 ml").await?;
-    create_recipe(pool, 67890, "chocolate 200 grams").await?;
+async fn test_fraction_quantities_read_back_exactly_impl(pool: &PgPool) -> Result<()> {
+    let user = get_or_create_user(pool, TelegramId(12346), None).await?;
+    let recipe_id = create_recipe(pool, TelegramId(12346), "1/3 cup milk").await?;
 
-    // Search for entries containing "flour"
-    let results = search_recipes(pool, 12345, "flour").await?;
-    assert_eq!(results.len(), 1);
-    assert!(results[0].content.contains("flour"));
+    // The quantity column rounds to 0.333, the fraction columns keep 1/3
+    let milk = create_ingredient(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "milk",
+        Some(1.0 / 3.0),
+        Some("cup"),
+        "1/3 cup milk",
+    )
+    .await?;
+    let stored: f64 = sqlx::query_scalar("SELECT quantity::float8 FROM ingredients WHERE id = $1")
+        .bind(milk)
+        .fetch_one(pool)
+        .await?;
+    assert_eq!(stored, 0.333);
+    let milk_quantity = read_ingredient(pool, milk).await?.unwrap().quantity;
+    assert_eq!(milk_quantity, Some(1.0 / 3.0));
+    assert_eq!(
+        milk_quantity
+            .and_then(Quantity::from_f64)
+            .unwrap()
+            .to_string(),
+        "1/3"
+    );
 
-    // Search for entries containing "grams"
-    let results = search_recipes(pool, 12345, "grams").await?;
-    assert_eq!(results.len(), 1);
-    assert!(results[0].content.contains("butter"));
+    // Updates keep the fraction exact too, and leave it alone without a quantity
+    update_ingredient(pool, milk, None, Some(2.0 / 3.0), None).await?;
+    update_ingredient(pool, milk, Some("whole milk"), None, None).await?;
+    assert_eq!(
+        read_ingredient(pool, milk).await?.unwrap().quantity,
+        Some(2.0 / 3.0)
+    );
 
-    // Search for non-existent term
-    let results = search_recipes(pool, 12345, "nonexistent").await?;
-    assert_eq!(results.len(), 0);
+    // Bulk inserts store the fraction parsed from the text as is
+    let mut conn = pool.acquire().await?;
+    let ids = create_ingredients_bulk(
+        &mut conn,
+        user.id,
+        recipe_id,
+        &[
+            NewIngredient {
+                name: "sugar",
+                quantity: Quantity::parse("1/6"),
+                unit: Some("cup"),
+                raw_text: None,
+            },
+            NewIngredient {
+                name: "flour",
+                quantity: Quantity::parse("1/3"),
+                unit: Some("cup"),
+                raw_text: None,
+            },
+        ],
+    )
+    .await?;
+    drop(conn);
+    assert_eq!(
+        read_ingredient(pool, ids[0]).await?.unwrap().quantity,
+        Some(1.0 / 6.0)
+    );
+    let fraction: (Option<i64>, Option<i64>) = sqlx::query_as(
+        "SELECT quantity_numerator, quantity_denominator FROM ingredients WHERE id = $1",
+    )
+    .bind(ids[1])
+    .fetch_one(pool)
+    .await?;
+    assert_eq!(fraction, (Some(1), Some(3)));
+
+    // Rows without a fraction fall back to the decimal column
+    sqlx::query(
+        "UPDATE ingredients SET quantity_numerator = NULL, quantity_denominator = NULL WHERE id = $1",
+    )
+    .bind(ids[0])
+    .execute(pool)
+    .await?;
+    assert_eq!(
+        read_ingredient(pool, ids[0]).await?.unwrap().quantity,
+        Some(0.167)
+    );
+
+    // Edited ingredient lists keep the typed fraction too
+    let mut edited = just_ingredients::ingredient_editing::ingredients_to_measurement_matches(
+        &get_recipe_ingredients(pool, recipe_id).await?,
+    );
+    assert_eq!(edited[1].ingredient_name, "sugar");
+    edited[1].quantity = "1/3".to_string();
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
+    let fraction: (Option<i64>, Option<i64>) = sqlx::query_as(
+        "SELECT quantity_numerator, quantity_denominator FROM ingredients WHERE id = $1",
+    )
+    .bind(ids[0])
+    .fetch_one(pool)
+    .await?;
+    assert_eq!(fraction, (Some(1), Some(3)));
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_get_user_recipes_paginated() -> Result<()> {
-    skip_if_no_db!(test_get_user_recipes_paginated_impl)
+async fn test_find_other_recipes_with_ingredient() -> Result<()> {
+    skip_if_no_db!(test_find_other_recipes_with_ingredient_impl)
 }
 
-async fn test_get_user_recipes_paginated_impl(pool: &PgPool) -> Result<()> {
-    // Create recipes with names
-    let recipe1_id = create_recipe(pool, 12345, "flour 2 cups").await?;
-    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
+async fn test_find_other_recipes_with_ingredient_impl(pool: &PgPool) -> Result<()> {
+    let user = get_or_create_user(pool, TelegramId(12345), None).await?;
+    let other_user = get_or_create_user(pool, TelegramId(67890), None).await?;
+
+    let edited_recipe = create_recipe(pool, TelegramId(12345), "tumeric 1 tsp").await?;
+    let curry = create_recipe(pool, TelegramId(12345), "tumeric 2 tsp").await?;
+    update_recipe_name(pool, curry, "Curry").await?;
+    let soup = create_recipe(pool, TelegramId(12345), "TUMERIC 1 tsp").await?;
+    update_recipe_name(pool, soup, "Soup").await?;
+    let unrelated = create_recipe(pool, TelegramId(12345), "flour 2 cups").await?;
+    let foreign = create_recipe(pool, TelegramId(67890), "tumeric 1 tsp").await?;
+
+    for (recipe_id, user_id, name) in [
+        (edited_recipe, user.id, "tumeric"),
+        (curry, user.id, "tumeric"),
+        (soup, user.id, "TUMERIC"),
+        (unrelated, user.id, "tumeric root powder"),
+        (foreign, other_user.id, "tumeric"),
+    ] {
+        create_ingredient(pool, user_id, Some(recipe_id), name, Some(1.0), None, "").await?;
+    }
 
-    let recipe2_id = create_recipe(pool, 12345, "butter 100g").await?;
-    update_recipe_name(pool, recipe2_id, "Apple Pie").await?;
+    // Case-insensitive whole-name match, excluding the edited recipe and other users
+    let found =
+        find_other_recipes_with_ingredient(pool, TelegramId(12345), edited_recipe, "tumeric")
+            .await?;
+    let mut found_ids: Vec<RecipeId> = found.iter().map(|r| r.id).collect();
+    found_ids.sort();
+    assert_eq!(found_ids, vec![curry, soup]);
 
-    let recipe3_id = create_recipe(pool, 12345, "sugar 1 cup").await?;
-    update_recipe_name(pool, recipe3_id, "Banana Bread").await?;
+    // LIKE wildcards in the name are matched literally
+    let wildcard =
+        find_other_recipes_with_ingredient(pool, TelegramId(12345), edited_recipe, "tum%").await?;
+    assert!(wildcard.is_empty());
 
-    // Create recipe for different user
-    let recipe4_id = create_recipe(pool, 67890, "milk 250ml").await?;
-    update_recipe_name(pool, recipe4_id, "Pancakes").await?;
+    Ok(())
+}
 
-    // Test pagination: limit 2, offset 0
-    let (recipes, total) = get_user_recipes_paginated(pool, 12345, 2, 0).await?;
-    assert_eq!(total, 3);
-    assert_eq!(recipes.len(), 2);
-    assert!(recipes.contains(&"Apple Pie".to_string()));
-    assert!(recipes.contains(&"Banana Bread".to_string()));
+#[tokio::test]
+async fn test_rename_ingredient_in_other_recipes_scoping() -> Result<()> {
+    skip_if_no_db!(test_rename_ingredient_in_other_recipes_scoping_impl)
+}
 
-    // Test pagination: limit 2, offset 2
-    let (recipes, total) = get_user_recipes_paginated(pool, 12345, 2, 2).await?;
-    assert_eq!(total, 3);
-    assert_eq!(recipes.len(), 1);
-    assert_eq!(recipes[0], "Chocolate Cake");
+async fn test_rename_ingredient_in_other_recipes_scoping_impl(pool: &PgPool) -> Result<()> {
+    let user = get_or_create_user(pool, TelegramId(12345), None).await?;
+    let other_user = get_or_create_user(pool, TelegramId(67890), None).await?;
 
-    // Test with different user
-    let (recipes, total) = get_user_recipes_paginated(pool, 67890, 10, 0).await?;
-    assert_eq!(total, 1);
-    assert_eq!(recipes.len(), 1);
-    assert_eq!(recipes[0], "Pancakes");
+    let edited_recipe = create_recipe(pool, TelegramId(12345), "turmeric 1 tsp").await?;
+    let own_recipe = create_recipe(pool, TelegramId(12345), "tumeric 2 tsp").await?;
+    let foreign_recipe = create_recipe(pool, TelegramId(67890), "tumeric 1 tsp").await?;
 
-    // Test with no recipes
-    let (recipes, total) = get_user_recipes_paginated(pool, 99999, 10, 0).await?;
-    assert_eq!(total, 0);
-    assert_eq!(recipes.len(), 0);
+    let edited_ingredient = create_ingredient(
+        pool,
+        user.id,
+        Some(edited_recipe),
+        "tumeric",
+        Some(1.0),
+        Some("tsp"),
+        "",
+    )
+    .await?;
+    let own_ingredient = create_ingredient(
+        pool,
+        user.id,
+        Some(own_recipe),
+        "Tumeric",
+        Some(2.0),
+        None,
+        "",
+    )
+    .await?;
+    let foreign_ingredient = create_ingredient(
+        pool,
+        other_user.id,
+        Some(foreign_recipe),
+        "tumeric",
+        Some(1.0),
+        None,
+        "",
+    )
+    .await?;
+
+    let renamed = rename_ingredient_in_other_recipes(
+        pool,
+        TelegramId(12345),
+        edited_recipe,
+        "tumeric",
+        "turmeric",
+    )
+    .await?;
+    assert_eq!(renamed, 1);
+
+    // Only the user's other recipes are touched
+    let own = read_ingredient(pool, own_ingredient).await?.unwrap();
+    assert_eq!(own.name, "turmeric");
+    assert_eq!(own.quantity, Some(2.0));
+
+    let edited = read_ingredient(pool, edited_ingredient).await?.unwrap();
+    assert_eq!(edited.name, "tumeric");
+
+    let foreign = read_ingredient(pool, foreign_ingredient).await?.unwrap();
+    assert_eq!(foreign.name, "tumeric");
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_get_recipes_by_name() -> Result<()> {
-    skip_if_no_db!(test_get_recipes_by_name_impl)
+async fn test_quickbar_setting_round_trip() -> Result<()> {
+    skip_if_no_db!(test_quickbar_setting_round_trip_impl)
 }
 
-async fn test_get_recipes_by_name_impl(pool: &PgPool) -> Result<()> {
-    // Create multiple recipes with the same name
-    let recipe1_id = create_recipe(pool, 12345, "flour 2 cups sugar 1 cup").await?;
-    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
-
-    let recipe2_id = create_recipe(pool, 12345, "butter 100g eggs 2").await?;
-    update_recipe_name(pool, recipe2_id, "Chocolate Cake").await?;
+async fn test_quickbar_setting_round_trip_impl(pool: &PgPool) -> Result<()> {
+    // Unknown users default to disabled
+    assert!(!get_user_quickbar_enabled(pool, TelegramId(24680)).await?);
 
-    let recipe3_id = create_recipe(pool, 12345, "milk 250ml vanilla 1 tsp").await?;
-    update_recipe_name(pool, recipe3_id, "Vanilla Pudding").await?;
+    // Enabling creates the user when needed
+    set_user_quickbar_enabled(pool, TelegramId(24680), true).await?;
+    assert!(get_user_quickbar_enabled(pool, TelegramId(24680)).await?);
+    assert!(get_user_by_telegram_id(pool, TelegramId(24680))
+        .await?
+        .is_some());
 
-    // Create recipe with same name for different user
-    let recipe4_id = create_recipe(pool, 67890, "flour 1 cup").await?;
-    update_recipe_name(pool, recipe4_id, "Chocolate Cake").await?;
+    set_user_quickbar_enabled(pool, TelegramId(24680), false).await?;
+    assert!(!get_user_quickbar_enabled(pool, TelegramId(24680)).await?);
 
-    // Test getting multiple recipes with same name
-    let recipes = get_recipes_by_name(pool, 12345, "Chocolate Cake").await?;
-    assert_eq!(recipes.len(), 2);
+    Ok(())
+}
 
-    // Verify the recipes are returned in descending creation order (most recent first)
-    assert_eq!(recipes[0].id, recipe2_id); // Second recipe created (most recent)
-    assert_eq!(recipes[1].id, recipe1_id); // First recipe created
-    assert_eq!(recipes[0].recipe_name.as_ref().unwrap(), "Chocolate Cake");
-    assert_eq!(recipes[1].recipe_name.as_ref().unwrap(), "Chocolate Cake");
+#[tokio::test]
+async fn test_stored_quickbar_setting_drives_markup() -> Result<()> {
+    skip_if_no_db!(test_stored_quickbar_setting_drives_markup_impl)
+}
 
-    // Test getting single recipe
-    let recipes = get_recipes_by_name(pool, 12345, "Vanilla Pudding").await?;
-    assert_eq!(recipes.len(), 1);
-    assert_eq!(recipes[0].id, recipe3_id);
-    assert_eq!(recipes[0].recipe_name.as_ref().unwrap(), "Vanilla Pudding");
+async fn test_stored_quickbar_setting_drives_markup_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::bot::quickbar::{enabled_quickbar_markup, stored_quickbar_markup};
+    use just_ingredients::localization::LocalizationManager;
+    use std::sync::Arc;
+    use teloxide::types::{ChatId, ReplyMarkup};
+
+    let localization = Arc::new(LocalizationManager::new()?);
+    let chat_id = ChatId(24682);
+    let button_texts = |markup: Option<ReplyMarkup>| match markup {
+        Some(ReplyMarkup::Keyboard(keyboard)) => keyboard
+            .keyboard
+            .into_iter()
+            .flatten()
+            .map(|button| button.text)
+            .collect::<Vec<_>>(),
+        other => panic!("Expected reply keyboard, got {:?}", other),
+    };
 
-    // Test getting recipes for different user
-    let recipes = get_recipes_by_name(pool, 67890, "Chocolate Cake").await?;
-    assert_eq!(recipes.len(), 1);
-    assert_eq!(recipes[0].id, recipe4_id);
+    // Disabled by default: replies remove the keyboard, language changes send none
+    assert!(matches!(
+        stored_quickbar_markup(pool, chat_id, &localization, Some("en")).await,
+        Some(ReplyMarkup::KeyboardRemove(_))
+    ));
+    assert!(
+        enabled_quickbar_markup(pool, chat_id, &localization, Some("en"))
+            .await
+            .is_none()
+    );
 
-    // Test getting non-existent recipe name
-    let recipes = get_recipes_by_name(pool, 12345, "Non-existent Recipe").await?;
-    assert_eq!(recipes.len(), 0);
+    // Enabled: the keyboard is attached in the requested language
+    set_user_quickbar_enabled(pool, TelegramId(chat_id.0), true).await?;
+    let english =
+        button_texts(stored_quickbar_markup(pool, chat_id, &localization, Some("en")).await);
+    let french =
+        button_texts(enabled_quickbar_markup(pool, chat_id, &localization, Some("fr")).await);
+    assert!(english.contains(&"🔍 Search".to_string()));
+    assert!(french.contains(&"🔍 Rechercher".to_string()));
+
+    set_user_quickbar_enabled(pool, TelegramId(chat_id.0), false).await?;
+    assert!(matches!(
+        stored_quickbar_markup(pool, chat_id, &localization, Some("en")).await,
+        Some(ReplyMarkup::KeyboardRemove(_))
+    ));
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_has_duplicate_recipes() -> Result<()> {
-    skip_if_no_db!(test_has_duplicate_recipes_impl)
+async fn test_bulk_delete_recipes() -> Result<()> {
+    skip_if_no_db!(test_bulk_delete_recipes_impl)
 }
 
-async fn test_has_duplicate_recipes_impl(pool: &PgPool) -> Result<()> {
-    // Create multiple recipes with the same name
-    let recipe1_id = create_recipe(pool, 12345, "flour 2 cups").await?;
-    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
-
-    let recipe2_id = create_recipe(pool, 12345, "butter 100g").await?;
-    update_recipe_name(pool, recipe2_id, "Chocolate Cake").await?;
-
-    // Create single recipe with different name
-    let recipe3_id = create_recipe(pool, 12345, "milk 250ml").await?;
-    update_recipe_name(pool, recipe3_id, "Vanilla Pudding").await?;
+async fn test_bulk_delete_recipes_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(24690);
+    let other = TelegramId(24691);
+    let owner_user = get_or_create_user(pool, owner, None).await?;
+    let other_user = get_or_create_user(pool, other, None).await?;
+
+    let mut owned = Vec::new();
+    for name in ["Bulk A", "Bulk B", "Bulk C"] {
+        let recipe_id = create_recipe(pool, owner, "content").await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+        create_ingredient(
+            pool,
+            owner_user.id,
+            Some(recipe_id),
+            "flour",
+            Some(1.0),
+            Some("cup"),
+            "1 cup flour",
+        )
+        .await?;
+        owned.push(recipe_id);
+    }
+    let foreign = create_recipe(pool, other, "content").await?;
+    create_ingredient(
+        pool,
+        other_user.id,
+        Some(foreign),
+        "sugar",
+        Some(2.0),
+        None,
+        "2 sugar",
+    )
+    .await?;
 
-    // Test duplicate detection - should return true for "Chocolate Cake"
-    let has_duplicates = has_duplicate_recipes(pool, 12345, "Chocolate Cake").await?;
-    assert!(has_duplicates);
+    // Selection entries list each recipe on its own, and lookups ignore other users' ids
+    let (entries, total) = get_user_recipe_entries_paginated(pool, owner, 2, 0).await?;
+    assert_eq!(total, 3);
+    assert_eq!(entries.len(), 2);
+    let selected = get_recipe_entries_by_ids(pool, owner, &[owned[0], owned[2], foreign]).await?;
+    assert_eq!(selected.len(), 2);
 
-    // Test no duplicates - should return false for "Vanilla Pudding"
-    let has_duplicates = has_duplicate_recipes(pool, 12345, "Vanilla Pudding").await?;
-    assert!(!has_duplicates);
+    // Another user's recipe in the selection is left untouched
+    let deleted = delete_recipes(pool, owner, &[owned[0], owned[2], foreign]).await?;
+    assert_eq!(deleted.len(), 2);
+    assert!(deleted.contains(&owned[0]) && deleted.contains(&owned[2]));
 
-    // Test non-existent recipe name - should return false
-    let has_duplicates = has_duplicate_recipes(pool, 12345, "Non-existent Recipe").await?;
-    assert!(!has_duplicates);
+    assert!(read_recipe_with_name(pool, owned[0]).await?.is_none());
+    assert!(get_recipe_ingredients(pool, owned[0]).await?.is_empty());
+    assert!(read_recipe_with_name(pool, owned[1]).await?.is_some());
+    assert_eq!(get_recipe_ingredients(pool, owned[1]).await?.len(), 1);
+    assert!(read_recipe_with_name(pool, foreign).await?.is_some());
+    assert_eq!(get_recipe_ingredients(pool, foreign).await?.len(), 1);
 
-    // Test with different user - should return false even if name exists for another user
-    let has_duplicates = has_duplicate_recipes(pool, 67890, "Chocolate Cake").await?;
-    assert!(!has_duplicates);
+    // Deleting again is a no-op
+    assert!(delete_recipes(pool, owner, &[owned[0]]).await?.is_empty());
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_schema_validation() -> Result<()> {
-    skip_if_no_db!(test_schema_validation_impl)
+async fn test_trash_and_restore_recipe() -> Result<()> {
+    skip_if_no_db!(test_trash_and_restore_recipe_impl)
 }
 
-async fn test_schema_validation_impl(pool: &PgPool) -> Result<()> {
-    // Schema should be valid after initialization
-    validate_database_schema(pool).await?;
+async fn test_trash_and_restore_recipe_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(24_790);
+    let user = get_or_create_user(pool, owner, Some("en")).await?;
+    let recipe_id = create_recipe(pool, owner, "2 cups flour").await?;
+    update_recipe_name(pool, recipe_id, "Trashed bread").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "flour",
+        Some(2.0),
+        Some("cups"),
+        "2 cups flour",
+    )
+    .await?;
 
-    // Test that validation fails if a required table is missing
-    sqlx::query("DROP TABLE IF EXISTS ingredients CASCADE")
+    // Another user can neither trash nor restore it
+    assert!(!trash_recipe(pool, TelegramId(24_791), recipe_id).await?);
+
+    // A trashed recipe is hidden everywhere but kept with its ingredients
+    assert!(trash_recipe(pool, owner, recipe_id).await?);
+    assert!(!trash_recipe(pool, owner, recipe_id).await?);
+    assert!(read_recipe_with_name(pool, recipe_id).await?.is_none());
+    assert!(get_recipes_by_name(pool, owner, "Trashed bread")
+        .await?
+        .is_empty());
+    assert_eq!(get_user_recipes_paginated(pool, owner, 10, 0).await?.1, 0);
+    assert!(search_recipes(pool, owner, "flour").await?.is_empty());
+    assert!(list_ingredients_by_user(pool, user.id).await?.is_empty());
+    assert_eq!(
+        get_user_recipe_statistics(pool, owner, just_ingredients::timezone::utc())
+            .await?
+            .total_recipes,
+        0
+    );
+    assert_eq!(get_recipe_ingredients(pool, recipe_id).await?.len(), 1);
+
+    let trashed = get_trashed_recipes(pool, owner).await?;
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].id, recipe_id);
+    assert_eq!(trashed[0].recipe_name.as_deref(), Some("Trashed bread"));
+
+    // Restoring brings everything back intact
+    assert!(!restore_recipe(pool, TelegramId(24_791), recipe_id).await?);
+    assert!(restore_recipe(pool, owner, recipe_id).await?);
+    let restored = read_recipe_with_name(pool, recipe_id).await?.unwrap();
+    assert_eq!(restored.recipe_name.as_deref(), Some("Trashed bread"));
+    assert_eq!(
+        get_recipe_ingredients(pool, recipe_id).await?[0].name,
+        "flour"
+    );
+    assert_eq!(search_recipes(pool, owner, "flour").await?.len(), 1);
+    assert!(get_trashed_recipes(pool, owner).await?.is_empty());
+
+    // Past the restore window the recipe can't be restored, and the sweeper removes it
+    assert!(trash_recipe(pool, owner, recipe_id).await?);
+    sqlx::query("UPDATE recipes SET deleted_at = NOW() - INTERVAL '8 days' WHERE id = $1")
+        .bind(recipe_id)
         .execute(pool)
         .await?;
+    assert!(get_trashed_recipes(pool, owner).await?.is_empty());
+    assert!(!restore_recipe(pool, owner, recipe_id).await?);
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(TRASH_RETENTION_DAYS.into());
+    assert_eq!(purge_trashed_recipes(pool, cutoff).await?, vec![recipe_id]);
+    let remaining: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE recipe_id = $1")
+            .bind(recipe_id)
+            .fetch_one(pool)
+            .await?;
+    assert_eq!(remaining, 0);
+    assert!(purge_trashed_recipes(pool, cutoff).await?.is_empty());
 
-    let result = validate_database_schema(pool).await;
-    assert!(result.is_err());
-    assert!(result.unwrap_err().to_string().contains("ingredients"));
+    Ok(())
+}
 
-    // Recreate the table and test again
-    init_database_schema(pool).await?;
-    validate_database_schema(pool).await?;
+#[tokio::test]
+async fn test_get_ingredients_for_recipes() -> Result<()> {
+    skip_if_no_db!(test_get_ingredients_for_recipes_impl)
+}
+
+async fn test_get_ingredients_for_recipes_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(24_800);
+    let user = get_or_create_user(pool, owner, Some("en")).await?;
+
+    // Three "Pizza" recipes, one of them without ingredients
+    let mut recipe_ids = Vec::new();
+    for lines in [
+        vec!["500g flour", "300ml water"],
+        vec![],
+        vec!["250g flour", "1 tsp salt", "2 tomatoes"],
+    ] {
+        let recipe_id = create_recipe(pool, owner, &lines.join("\n")).await?;
+        update_recipe_name(pool, recipe_id, "Pizza").await?;
+        for line in lines {
+            let name = line.split_whitespace().last().unwrap();
+            create_ingredient(pool, user.id, Some(recipe_id), name, None, None, line).await?;
+        }
+        recipe_ids.push(recipe_id);
+    }
+
+    let by_recipe = get_ingredients_for_recipes(pool, &recipe_ids).await?;
+    assert_eq!(by_recipe.len(), 3);
+    for recipe_id in &recipe_ids {
+        // Same ingredients, in the same order, as one recipe at a time
+        assert_eq!(
+            by_recipe[recipe_id],
+            get_recipe_ingredients(pool, *recipe_id).await?
+        );
+    }
+    let names = |recipe_id: &RecipeId| -> Vec<&str> {
+        by_recipe[recipe_id]
+            .iter()
+            .map(|ingredient| ingredient.name.as_str())
+            .collect()
+    };
+    assert_eq!(names(&recipe_ids[0]), vec!["flour", "water"]);
+    assert!(by_recipe[&recipe_ids[1]].is_empty());
+    assert_eq!(names(&recipe_ids[2]), vec!["flour", "salt", "tomatoes"]);
+
+    // Unknown ids get an empty entry; no ids, no query
+    let unknown = RecipeId(i64::MAX);
+    assert_eq!(
+        get_ingredients_for_recipes(pool, &[recipe_ids[0], unknown]).await?[&unknown],
+        Vec::new()
+    );
+    assert!(get_ingredients_for_recipes(pool, &[]).await?.is_empty());
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_migration_system() -> Result<()> {
-    skip_if_no_db!(test_migration_system_impl)
+async fn test_get_recent_recipe_names() -> Result<()> {
+    skip_if_no_db!(test_get_recent_recipe_names_impl)
 }
 
-async fn test_migration_system_impl(pool: &PgPool) -> Result<()> {
-    // Clean up any existing migration state
-    sqlx::query("DROP TABLE IF EXISTS schema_migrations CASCADE")
-        .execute(pool)
-        .await?;
-    sqlx::query("DROP TABLE IF EXISTS ingredients CASCADE")
-        .execute(pool)
-        .await?;
-    sqlx::query("DROP TABLE IF EXISTS recipes CASCADE")
-        .execute(pool)
-        .await?;
-    sqlx::query("DROP TABLE IF EXISTS users CASCADE")
+async fn test_get_recent_recipe_names_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(24_810);
+    get_or_create_user(pool, owner, Some("fr")).await?;
+
+    // Oldest first; "Soup" is used again last, and one recipe is left unnamed
+    for (days_ago, name) in [
+        (9, Some("Soup")),
+        (8, Some("Soupe de légumes")),
+        (7, Some("Pancakes")),
+        (6, None),
+        (5, Some("Trashed pie")),
+        (4, Some("Crêpes")),
+        (1, Some("Soup")),
+    ] {
+        let recipe_id = create_recipe(pool, owner, "2 cups flour").await?;
+        if let Some(name) = name {
+            update_recipe_name(pool, recipe_id, name).await?;
+        }
+        sqlx::query(
+            "UPDATE recipes SET created_at = NOW() - make_interval(days => $2) WHERE id = $1",
+        )
+        .bind(recipe_id)
+        .bind(days_ago)
         .execute(pool)
         .await?;
+        if name == Some("Trashed pie") {
+            trash_recipe(pool, owner, recipe_id).await?;
+        }
+    }
+    let other = create_recipe(pool, TelegramId(24_811), "1 egg").await?;
+    update_recipe_name(pool, other, "Someone else's omelette").await?;
 
-    // Test initial state - no migrations applied
-    let version = migrations::get_current_version(pool).await?;
-    assert_eq!(version, 0);
+    assert_eq!(
+        get_recent_recipe_names(pool, owner, 10).await?,
+        vec!["Soup", "Crêpes", "Pancakes", "Soupe de légumes"]
+    );
+    assert_eq!(
+        get_recent_recipe_names(pool, owner, 2).await?,
+        vec!["Soup", "Crêpes"]
+    );
+    assert!(get_recent_recipe_names(pool, TelegramId(24_812), 10)
+        .await?
+        .is_empty());
 
-    // Apply migrations
-    migrations::apply_pending_migrations(pool).await?;
+    Ok(())
+}
 
-    // Check that migrations table was created and version is updated
-    let version = migrations::get_current_version(pool).await?;
-    assert_eq!(version, 1);
+#[tokio::test]
+async fn test_tapped_recipe_name_suggestion_saves_recipe() -> Result<()> {
+    skip_if_no_db!(test_tapped_recipe_name_suggestion_saves_recipe_impl)
+}
 
-    // Verify that tables were created
-    validate_database_schema(pool).await?;
+async fn test_tapped_recipe_name_suggestion_saves_recipe_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::bot::save_ingredients_to_database;
+    use just_ingredients::bot::ui_builder::{
+        create_recipe_name_suggestions_keyboard, parse_name_suggestion_callback,
+        RECIPE_NAME_SUGGESTIONS,
+    };
+    use just_ingredients::text_processing::MeasurementMatch;
+    use just_ingredients::validation::validate_recipe_name;
+    use teloxide::types::InlineKeyboardButtonKind;
+
+    let owner = TelegramId(24_820);
+    let long_name = format!("Soupe de légumes {}", "et de saison ".repeat(8));
+    for (days_ago, name) in [(3, "Pancakes"), (1, long_name.trim())] {
+        let recipe_id = create_recipe(pool, owner, "2 carottes").await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+        sqlx::query(
+            "UPDATE recipes SET created_at = NOW() - make_interval(days => $2) WHERE id = $1",
+        )
+        .bind(recipe_id)
+        .bind(days_ago)
+        .execute(pool)
+        .await?;
+    }
 
-    // Test rollback (if down migrations are available)
-    // Note: Current migration doesn't have a down script, so this will fail gracefully
-    let rollback_result = migrations::rollback_to_version(pool, 0).await;
-    assert!(rollback_result.is_err()); // Should fail because no down migration
+    // The dialogue keeps the names; the buttons only carry their index
+    let suggestions = get_recent_recipe_names(pool, owner, RECIPE_NAME_SUGGESTIONS as i64).await?;
+    let keyboard = create_recipe_name_suggestions_keyboard(&suggestions);
+    let data: Vec<String> = keyboard
+        .inline_keyboard
+        .iter()
+        .flatten()
+        .map(|button| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => data.clone(),
+            other => panic!("Expected callback data, got {other:?}"),
+        })
+        .collect();
+    assert_eq!(data, vec!["name_suggestion:0", "name_suggestion:1"]);
+    assert!(data.iter().all(|data| data.len() <= 64));
+
+    // Tapping the long name saves the new recipe under it, as typed input would
+    let tapped = parse_name_suggestion_callback(&data[0]).expect("suggestion index");
+    let name = validate_recipe_name(&suggestions[tapped]).expect("valid suggestion");
+    assert_eq!(name, long_name.trim());
+    let ingredients = vec![MeasurementMatch {
+        quantity: "2".to_string(),
+        measurement: None,
+        ingredient_name: "carottes".to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    }];
+    save_ingredients_to_database(
+        pool,
+        owner.0,
+        "2 carottes",
+        &ingredients,
+        name,
+        Some("fr"),
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    let saved = get_recipes_by_name(pool, owner, name).await?;
+    assert_eq!(saved.len(), 2);
+    assert_eq!(get_recipe_ingredients(pool, saved[0].id).await?.len(), 1);
+    assert_eq!(get_recent_recipe_names(pool, owner, 1).await?, vec![name]);
 
     Ok(())
 }
 
 #[tokio::test]
-async fn test_schema_validation_details() -> Result<()> {
-    skip_if_no_db!(test_schema_validation_details_impl)
+async fn test_delete_all_user_data() -> Result<()> {
+    skip_if_no_db!(test_delete_all_user_data_impl)
 }
 
-async fn test_schema_validation_details_impl(pool: &PgPool) -> Result<()> {
-    // Test that all expected columns exist with correct types
-
-    // Check users table columns
-    let user_columns = vec![
-        ("id", "bigint"),
-        ("telegram_id", "bigint"),
-        ("language_code", "character varying"),
-        ("created_at", "timestamp with time zone"),
-        ("updated_at", "timestamp with time zone"),
-    ];
-
-    for (col_name, _expected_type) in user_columns {
-        let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT 1 FROM information_schema.columns
-             WHERE table_name = 'users' AND column_name = $1 AND table_schema = 'public')",
+async fn test_delete_all_user_data_impl(pool: &PgPool) -> Result<()> {
+    let wiped = TelegramId(24700);
+    let kept = TelegramId(24701);
+    let wiped_user = get_or_create_user(pool, wiped, Some("fr")).await?;
+    let kept_user = get_or_create_user(pool, kept, None).await?;
+
+    for (name, ingredient_count) in [("Wipe A", 2), ("Wipe B", 1)] {
+        let recipe_id = create_recipe(pool, wiped, "content").await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+        for _ in 0..ingredient_count {
+            create_ingredient(
+                pool,
+                wiped_user.id,
+                Some(recipe_id),
+                "flour",
+                Some(1.0),
+                Some("cup"),
+                "1 cup flour",
+            )
+            .await?;
+        }
+        record_recipe_activity(
+            pool,
+            wiped,
+            recipe_id,
+            RecipeActivityKind::Saved,
+            Some(name),
+            None,
+            Some(ingredient_count),
         )
-        .bind(col_name)
-        .fetch_one(pool)
         .await?;
-        assert!(exists, "Column {} should exist in users table", col_name);
+        add_recipe_tag(pool, wiped, recipe_id, "weeknight").await?;
+    }
+    get_or_create_experiment_assignment(pool, wiped, "review_keyboard", "compact").await?;
+    record_review_funnel_event(pool, wiped, "review_keyboard", "compact", "shown").await?;
+
+    let kept_recipe = create_recipe(pool, kept, "content").await?;
+    create_ingredient(
+        pool,
+        kept_user.id,
+        Some(kept_recipe),
+        "sugar",
+        Some(2.0),
+        None,
+        "2 sugar",
+    )
+    .await?;
+
+    let deleted = delete_all_user_data(pool, wiped).await?;
+    assert_eq!(
+        deleted,
+        DeletedUserData {
+            recipes: 2,
+            ingredients: 3
+        }
+    );
+
+    // No row refers to the user anymore
+    for table in [
+        "users",
+        "recipes",
+        "recipe_activity",
+        "recipe_tags",
+        "import_jobs",
+        "extraction_reports",
+        "review_funnel_events",
+        "experiment_assignments",
+    ] {
+        let remaining: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) FROM {table} WHERE telegram_id = $1"
+        ))
+        .bind(wiped)
+        .fetch_one(pool)
+        .await?;
+        assert_eq!(remaining, 0, "rows left in {table}");
+    }
+    let remaining_ingredients: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM ingredients WHERE user_id = $1")
+            .bind(wiped_user.id)
+            .fetch_one(pool)
+            .await?;
+    assert_eq!(remaining_ingredients, 0);
+
+    // Other users keep their data
+    assert!(read_recipe_with_name(pool, kept_recipe).await?.is_some());
+    assert_eq!(get_recipe_ingredients(pool, kept_recipe).await?.len(), 1);
+
+    // Deleting again finds nothing
+    assert_eq!(
+        delete_all_user_data(pool, wiped).await?,
+        DeletedUserData::default()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipes_with_ingredients_batches() -> Result<()> {
+    skip_if_no_db!(test_recipes_with_ingredients_batches_impl)
+}
+
+async fn test_recipes_with_ingredients_batches_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(24711);
+    let user = get_or_create_user(pool, owner, None).await?;
+    let other = get_or_create_user(pool, TelegramId(24712), None).await?;
+
+    let mut recipe_ids = Vec::new();
+    for index in 0..5 {
+        let recipe_id = create_recipe(pool, owner, &format!("recipe {index}")).await?;
+        for ingredient in 0..index {
+            create_ingredient(
+                pool,
+                user.id,
+                Some(recipe_id),
+                &format!("ingredient {ingredient}"),
+                Some(1.0),
+                Some("g"),
+                "",
+            )
+            .await?;
+        }
+        recipe_ids.push(recipe_id);
+    }
+    create_recipe(pool, other.telegram_id, "not exported").await?;
+
+    // Walk the batches the way /export does
+    let mut exported = Vec::new();
+    let mut after = None;
+    loop {
+        let batch = get_recipes_with_ingredients_batch(pool, owner, after, 2).await?;
+        let Some((last, _)) = batch.last() else {
+            break;
+        };
+        assert!(batch.len() <= 2);
+        after = Some(last.id);
+        exported.extend(batch);
+    }
+
+    assert_eq!(
+        exported
+            .iter()
+            .map(|(recipe, _)| recipe.id)
+            .collect::<Vec<_>>(),
+        recipe_ids
+    );
+    for (index, (_, ingredients)) in exported.iter().enumerate() {
+        assert_eq!(ingredients.len(), index);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_recipes_skips_duplicates() -> Result<()> {
+    skip_if_no_db!(test_import_recipes_skips_duplicates_impl)
+}
+
+async fn test_import_recipes_skips_duplicates_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::recipe_export::{ExportedIngredient, ExportedRecipe};
+
+    let owner = TelegramId(24721);
+    let ingredient = |name: &str, quantity: f64, unit: Option<&str>| ExportedIngredient {
+        name: name.to_string(),
+        quantity: Some(quantity),
+        unit: unit.map(str::to_string),
+    };
+    let recipe = |name: &str, ingredients: Vec<ExportedIngredient>| ExportedRecipe {
+        name: Some(name.to_string()),
+        created_at: chrono::Utc::now() - chrono::Duration::days(30),
+        content: format!("{name} content"),
+        ingredients,
+    };
+
+    // Already saved before the import, with the same ingredients in another order
+    let user = get_or_create_user(pool, owner, None).await?;
+    let existing = create_recipe(pool, owner, "content").await?;
+    update_recipe_name(pool, existing, "Crêpes").await?;
+    for (name, quantity, unit) in [("oeufs", 3.0, None), ("farine", 250.0, Some("g"))] {
+        create_ingredient(
+            pool,
+            user.id,
+            Some(existing),
+            name,
+            Some(quantity),
+            unit,
+            "",
+        )
+        .await?;
+    }
+
+    let recipes = vec![
+        recipe(
+            "crêpes",
+            vec![
+                ingredient("farine", 250.0, Some("g")),
+                ingredient("oeufs", 3.0, None),
+            ],
+        ),
+        // Same name, different ingredients: a different recipe
+        recipe("Crêpes", vec![ingredient("farine", 300.0, Some("g"))]),
+        recipe("Tarte", vec![ingredient("pommes", 4.0, None)]),
+        // Repeated within the file
+        recipe("Tarte", vec![ingredient("pommes", 4.0, None)]),
+    ];
+
+    let job = start_import_job(pool, owner, "export-file", recipes.len() as i64).await?;
+    let mut reported = Vec::new();
+    let summary = import_recipes(pool, &job, &recipes, |rows| {
+        reported.push(rows);
+        async {}
+    })
+    .await?;
+    assert_eq!(
+        summary,
+        ImportSummary {
+            created: 2,
+            skipped: 2
+        }
+    );
+    assert_eq!(reported, vec![recipes.len()]);
+    assert!(find_incomplete_import_job(pool, owner, "export-file")
+        .await?
+        .is_none());
+
+    let batch = get_recipes_with_ingredients_batch(pool, owner, Some(existing), 10).await?;
+    assert_eq!(batch.len(), 2);
+    let (tarte, tarte_ingredients) = &batch[1];
+    assert_eq!(tarte.recipe_name.as_deref(), Some("Tarte"));
+    assert_eq!(
+        tarte.created_at.timestamp(),
+        recipes[2].created_at.timestamp()
+    );
+    assert_eq!(tarte_ingredients.len(), 1);
+    assert_eq!(tarte_ingredients[0].quantity, Some(4.0));
+
+    // Importing the same recipes again, even from another file, changes nothing
+    let job = start_import_job(pool, owner, "export-file-copy", recipes.len() as i64).await?;
+    let summary = import_recipes(pool, &job, &recipes, |_| async {}).await?;
+    assert_eq!(
+        summary,
+        ImportSummary {
+            created: 0,
+            skipped: 4
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ocr_digest_aggregates() -> Result<()> {
+    skip_if_no_db!(test_ocr_digest_aggregates_impl)
+}
+
+async fn test_ocr_digest_aggregates_impl(pool: &PgPool) -> Result<()> {
+    use chrono::{TimeZone, Utc};
+
+    // A past week no other test writes to
+    let from = Utc.with_ymd_and_hms(2001, 1, 1, 0, 0, 0).unwrap();
+    let to = Utc.with_ymd_and_hms(2001, 1, 8, 0, 0, 0).unwrap();
+    let at = |day: u32, minute: u32| Utc.with_ymd_and_hms(2001, 1, day, 12, minute, 0).unwrap();
+
+    // User 1: review with 2 edits confirmed, then a review with 0 edits confirmed
+    // User 2: review with 5 edits confirmed, then a review abandoned with 1 edit
+    let events = [
+        (24701, "review_shown", at(2, 0)),
+        (24701, "edits_made", at(2, 1)),
+        (24701, "edits_made", at(2, 2)),
+        (24701, "confirmed", at(2, 3)),
+        (24701, "review_shown", at(3, 0)),
+        (24701, "confirmed", at(3, 1)),
+        (24702, "review_shown", at(4, 0)),
+        (24702, "edits_made", at(4, 1)),
+        (24702, "edits_made", at(4, 2)),
+        (24702, "edits_made", at(4, 3)),
+        (24702, "edits_made", at(4, 4)),
+        (24702, "edits_made", at(4, 5)),
+        (24702, "confirmed", at(4, 6)),
+        (24702, "review_shown", at(5, 0)),
+        (24702, "edits_made", at(5, 1)),
+        // Outside the window
+        (24702, "review_shown", at(9, 0)),
+    ];
+    for (telegram_id, event, created_at) in events {
+        sqlx::query(
+            "INSERT INTO review_funnel_events (telegram_id, experiment, variant, event, created_at) \
+             VALUES ($1, 'digest_test', 'a', $2, $3)",
+        )
+        .bind(telegram_id)
+        .bind(event)
+        .bind(created_at)
+        .execute(pool)
+        .await?;
+    }
+    sqlx::query(
+        "INSERT INTO extraction_reports (telegram_id, correlation_id, extracted_text_hash, match_count, created_at) \
+         VALUES (24702, 'digest-test', 'hash', 1, $1)",
+    )
+    .bind(at(5, 2))
+    .execute(pool)
+    .await?;
+
+    assert_eq!(
+        get_extraction_feedback_counts(pool, from, to).await?,
+        (4, 1)
+    );
+    assert_eq!(
+        get_median_edits_per_confirmed_review(pool, from, to).await?,
+        Some(2.0)
+    );
+
+    let user = get_or_create_user(pool, TelegramId(24701), None).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "content").await?;
+    for (name, quantity, unit) in [
+        ("sachets levure", Some(2.0), None),
+        ("sachets sucre vanillé", Some(1.0), None),
+        ("pincées sel", Some(2.0), None),
+        ("oeufs", Some(3.0), None),
+        ("farine", Some(200.0), Some("g")),
+        ("zeste citron", None, None),
+    ] {
+        let ingredient_id =
+            create_ingredient(pool, user.id, Some(recipe_id), name, quantity, unit, name).await?;
+        sqlx::query("UPDATE ingredients SET created_at = $2 WHERE id = $1")
+            .bind(ingredient_id)
+            .bind(at(6, 0))
+            .execute(pool)
+            .await?;
+    }
+
+    // Single words and ingredients with a unit or without a quantity are not candidates
+    assert_eq!(
+        get_unmatched_unit_candidates(pool, from, to, 5).await?,
+        vec![("sachets".to_string(), 2), ("pincées".to_string(), 1)]
+    );
+    assert!(get_unmatched_unit_candidates(pool, to, at(9, 0), 5)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unit_preference_round_trip() -> Result<()> {
+    skip_if_no_db!(test_unit_preference_round_trip_impl)
+}
+
+async fn test_unit_preference_round_trip_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::text_processing::UnitSystem;
+
+    // Unknown users see quantities as written
+    assert_eq!(
+        get_user_unit_preference(pool, TelegramId(955_101)).await?,
+        None
+    );
+
+    set_user_unit_preference(pool, TelegramId(955_101), Some(UnitSystem::Imperial)).await?;
+    assert_eq!(
+        get_user_unit_preference(pool, TelegramId(955_101)).await?,
+        Some(UnitSystem::Imperial)
+    );
+
+    set_user_unit_preference(pool, TelegramId(955_101), None).await?;
+    assert_eq!(
+        get_user_unit_preference(pool, TelegramId(955_101)).await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_timezone_round_trip() -> Result<()> {
+    skip_if_no_db!(test_timezone_round_trip_impl)
+}
+
+async fn test_timezone_round_trip_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::timezone::offset_from_minutes;
+
+    // Unknown users see dates in UTC
+    assert_eq!(get_user_timezone(pool, TelegramId(955_102)).await?, None);
+
+    let auckland = offset_from_minutes(12 * 60).unwrap();
+    set_user_timezone(pool, TelegramId(955_102), auckland).await?;
+    assert_eq!(
+        get_user_timezone(pool, TelegramId(955_102)).await?,
+        Some(auckland)
+    );
+
+    let newfoundland = offset_from_minutes(-(3 * 60 + 30)).unwrap();
+    set_user_timezone(pool, TelegramId(955_102), newfoundland).await?;
+    assert_eq!(
+        get_user_timezone(pool, TelegramId(955_102)).await?,
+        Some(newfoundland)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_statistics_count_days_in_user_timezone() -> Result<()> {
+    skip_if_no_db!(test_statistics_count_days_in_user_timezone_impl)
+}
+
+async fn test_statistics_count_days_in_user_timezone_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::timezone::{offset_from_minutes, utc, StatisticsWindows};
+
+    let owner = TelegramId(955_103);
+    get_or_create_user(pool, owner, None).await?;
+    let now = chrono::Utc::now();
+
+    // One recipe a minute before and one a minute after midnight in UTC+12
+    let auckland = offset_from_minutes(12 * 60).unwrap();
+    let midnight = StatisticsWindows::new(now, auckland).today;
+    for created_at in [
+        midnight - chrono::Duration::minutes(1),
+        midnight + chrono::Duration::minutes(1),
+    ] {
+        let recipe_id = create_recipe(pool, owner, "content").await?;
+        sqlx::query("UPDATE recipes SET created_at = $1 WHERE id = $2")
+            .bind(created_at)
+            .bind(recipe_id)
+            .execute(pool)
+            .await?;
+    }
+
+    let stats = get_user_recipe_statistics(pool, owner, auckland).await?;
+    assert_eq!(stats.recipes_created_today, 1);
+    assert_eq!(stats.recipes_created_this_week, 2);
+
+    // The same recipes counted with UTC days
+    let utc_midnight = StatisticsWindows::new(now, utc()).today;
+    let expected_today = [
+        midnight - chrono::Duration::minutes(1),
+        midnight + chrono::Duration::minutes(1),
+    ]
+    .iter()
+    .filter(|created_at| **created_at >= utc_midnight)
+    .count() as i64;
+    let stats = get_user_recipe_statistics(pool, owner, utc()).await?;
+    assert_eq!(stats.recipes_created_today, expected_today);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_auto_language_setting_round_trip() -> Result<()> {
+    skip_if_no_db!(test_auto_language_setting_round_trip_impl)
+}
+
+async fn test_auto_language_setting_round_trip_impl(pool: &PgPool) -> Result<()> {
+    // Unknown users keep their fixed language
+    assert!(!get_user_auto_language(pool, TelegramId(24681)).await?);
+
+    set_user_auto_language(pool, TelegramId(24681), true).await?;
+    assert!(get_user_auto_language(pool, TelegramId(24681)).await?);
+
+    // Independent of the quickbar setting stored on the same row
+    assert!(!get_user_quickbar_enabled(pool, TelegramId(24681)).await?);
+
+    set_user_auto_language(pool, TelegramId(24681), false).await?;
+    assert!(!get_user_auto_language(pool, TelegramId(24681)).await?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ingredient_unit_metadata_persisted() -> Result<()> {
+    skip_if_no_db!(test_ingredient_unit_metadata_persisted_impl)
+}
+
+async fn test_ingredient_unit_metadata_persisted_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::text_processing::{UnitDimension, UnitSystem};
+
+    let user = get_or_create_user(pool, TelegramId(11223), Some("en")).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "2 cups flour").await?;
+    let flour = create_ingredient(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "flour",
+        Some(2.0),
+        Some("Cups"),
+        "",
+    )
+    .await?;
+    let eggs =
+        create_ingredient(pool, user.id, Some(recipe_id), "eggs", Some(3.0), None, "").await?;
+
+    let flour = read_ingredient(pool, flour).await?.unwrap();
+    assert_eq!(flour.unit_dimension, Some(UnitDimension::Volume));
+    assert_eq!(flour.unit_system, Some(UnitSystem::Us));
+    let eggs = read_ingredient(pool, eggs).await?.unwrap();
+    assert_eq!(eggs.unit_dimension, None);
+    assert_eq!(eggs.unit_system, None);
+
+    // Changing the unit reclassifies it
+    update_ingredient(pool, flour.id, None, Some(250.0), Some("g")).await?;
+    let flour = read_ingredient(pool, flour.id).await?.unwrap();
+    assert_eq!(flour.unit_dimension, Some(UnitDimension::Weight));
+    assert_eq!(flour.unit_system, Some(UnitSystem::Metric));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_experiment_assignment_is_sticky() -> Result<()> {
+    skip_if_no_db!(test_experiment_assignment_is_sticky_impl)
+}
+
+async fn test_experiment_assignment_is_sticky_impl(pool: &PgPool) -> Result<()> {
+    let experiment = "test_assignment_sticky";
+
+    let first =
+        get_or_create_experiment_assignment(pool, TelegramId(13579), experiment, "compact").await?;
+    assert_eq!(first, "compact");
+
+    // A later config change must not move an already assigned user
+    let second =
+        get_or_create_experiment_assignment(pool, TelegramId(13579), experiment, "full").await?;
+    assert_eq!(second, "compact");
+
+    Ok(())
+}
+
+struct RecipeRowWriter {
+    telegram_id: TelegramId,
+}
+
+impl just_ingredients::import_jobs::ImportBatchWriter<String> for RecipeRowWriter {
+    async fn write_rows(&self, conn: &mut sqlx::PgConnection, rows: &[&String]) -> Result<usize> {
+        for row in rows {
+            sqlx::query("INSERT INTO recipes (telegram_id, content) VALUES ($1, $2)")
+                .bind(self.telegram_id)
+                .bind(row.as_str())
+                .execute(&mut *conn)
+                .await?;
+        }
+        Ok(rows.len())
+    }
+}
+
+#[tokio::test]
+async fn test_resumable_import_batches() -> Result<()> {
+    skip_if_no_db!(test_resumable_import_batches_impl)
+}
+
+async fn test_resumable_import_batches_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::import_jobs::{
+        batch_ranges, commit_import_batch, resume_offset, row_content_hash, ImportJobStatus,
+    };
+
+    let telegram_id = TelegramId(86420);
+    sqlx::query("DELETE FROM import_jobs WHERE telegram_id = $1")
+        .bind(telegram_id)
+        .execute(pool)
+        .await?;
+
+    let rows: Vec<String> = (1..=5).map(|i| format!("{} cups flour", i)).collect();
+    let writer = RecipeRowWriter { telegram_id };
+    let hash = |row: &String| row_content_hash(row);
+
+    // First run commits one batch, then the bot "restarts"
+    let job = start_import_job(pool, telegram_id, "file-abc", rows.len() as i64).await?;
+    let outcome = commit_import_batch(pool, &job, &rows, 0..2, hash, &writer).await?;
+    assert_eq!(outcome.inserted, 2);
+
+    let job = find_incomplete_import_job(pool, telegram_id, "file-abc")
+        .await?
+        .expect("job should still be in progress");
+    assert_eq!(job.rows_processed, 2);
+    assert_eq!(resume_offset(job.rows_processed, rows.len()), 2);
+
+    // Starting over re-processes the committed prefix without duplicating it
+    let job = start_import_job(pool, telegram_id, "file-abc", rows.len() as i64).await?;
+    assert_eq!(job.rows_processed, 0);
+    let mut inserted = 0;
+    let mut skipped = 0;
+    for range in batch_ranges(rows.len(), 0, 2) {
+        let outcome = commit_import_batch(pool, &job, &rows, range, hash, &writer).await?;
+        inserted += outcome.inserted;
+        skipped += outcome.skipped;
+    }
+    assert_eq!((inserted, skipped), (3, 2));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes WHERE telegram_id = $1")
+        .bind(telegram_id)
+        .fetch_one(pool)
+        .await?;
+    assert_eq!(count, 5);
+
+    finish_import_job(pool, job.id, ImportJobStatus::Completed.as_str()).await?;
+    assert!(find_incomplete_import_job(pool, telegram_id, "file-abc")
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_review_funnel_report() -> Result<()> {
+    skip_if_no_db!(test_review_funnel_report_impl)
+}
+
+async fn test_review_funnel_report_impl(pool: &PgPool) -> Result<()> {
+    let experiment = "test_review_funnel_report";
+    sqlx::query("DELETE FROM review_funnel_events WHERE experiment = $1")
+        .bind(experiment)
+        .execute(pool)
+        .await?;
+
+    for (telegram_id, variant) in [(1001, "full"), (1002, "full"), (1003, "compact")] {
+        record_review_funnel_event(
+            pool,
+            TelegramId(telegram_id),
+            experiment,
+            variant,
+            "review_shown",
+        )
+        .await?;
+    }
+    for event in ["edits_made", "edits_made", "edits_made", "confirmed"] {
+        record_review_funnel_event(pool, TelegramId(1001), experiment, "full", event).await?;
+    }
+    record_review_funnel_event(pool, TelegramId(1003), experiment, "compact", "confirmed").await?;
+
+    let report = get_review_funnel_report(pool, experiment, 14).await?;
+    assert_eq!(report.len(), 2);
+
+    let compact = report.iter().find(|s| s.variant == "compact").unwrap();
+    assert_eq!(compact.reviews_shown, 1);
+    assert_eq!(compact.edits_made, 0);
+    assert_eq!(compact.confirmed, 1);
+    assert_eq!(compact.confirm_rate(), 1.0);
+
+    let full = report.iter().find(|s| s.variant == "full").unwrap();
+    assert_eq!(full.reviews_shown, 2);
+    assert_eq!(full.edits_made, 3);
+    assert_eq!(full.confirmed, 1);
+    assert_eq!(full.confirm_rate(), 0.5);
+    assert_eq!(full.edits_per_review(), 1.5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extraction_accuracy_by_language() -> Result<()> {
+    skip_if_no_db!(test_extraction_accuracy_by_language_impl)
+}
+
+async fn test_extraction_accuracy_by_language_impl(pool: &PgPool) -> Result<()> {
+    sqlx::query("DELETE FROM extraction_feedback WHERE language_code IN ('xa', 'xb')")
+        .execute(pool)
+        .await?;
+
+    record_extraction_feedback(pool, "xa", 8, 1, 1).await?;
+    record_extraction_feedback(pool, "xa", 2, 0, 0).await?;
+    record_extraction_feedback(pool, "xb", 4, 0, 0).await?;
+
+    let report = get_extraction_accuracy_by_language(pool, 30).await?;
+    let xa = report.iter().find(|s| s.language_code == "xa").unwrap();
+    assert_eq!(xa.reviews, 2);
+    assert_eq!(xa.extracted, 10);
+    assert_eq!(xa.edited, 1);
+    assert_eq!(xa.deleted, 1);
+    assert!((xa.correction_ratio() - 0.2).abs() < 1e-9);
+
+    let xb = report.iter().find(|s| s.language_code == "xb").unwrap();
+    assert_eq!(xb.reviews, 1);
+    assert_eq!(xb.correction_ratio(), 0.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_recipe_ingredients_uses_internal_user_id() -> Result<()> {
+    skip_if_no_db!(test_update_recipe_ingredients_uses_internal_user_id_impl)
+}
+
+async fn test_update_recipe_ingredients_uses_internal_user_id_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::text_processing::MeasurementMatch;
+
+    let user = get_or_create_user(pool, TelegramId(97531), Some("en")).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "2 cups flour").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "flour",
+        Some(2.0),
+        Some("cups"),
+        "",
+    )
+    .await?;
+
+    // Keep flour and add sugar through the editing path
+    let edited = vec![
+        MeasurementMatch {
+            quantity: "2".to_string(),
+            measurement: Some("cups".to_string()),
+            ingredient_name: "flour".to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: 0,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        },
+        MeasurementMatch {
+            quantity: "1".to_string(),
+            measurement: Some("cup".to_string()),
+            ingredient_name: "sugar".to_string(),
+            line_number: 1,
+            start_pos: 0,
+            end_pos: 0,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        },
+    ];
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
+
+    let ingredients = get_recipe_ingredients(pool, recipe_id).await?;
+    assert_eq!(ingredients.len(), 2);
+    assert!(ingredients.iter().all(|i| i.user_id == user.id));
+    assert_eq!(list_ingredients_by_user(pool, user.id).await?.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_interleaved_ingredient_edits_do_not_overwrite() -> Result<()> {
+    skip_if_no_db!(test_interleaved_ingredient_edits_do_not_overwrite_impl)
+}
+
+async fn test_interleaved_ingredient_edits_do_not_overwrite_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::ingredient_editing::ingredients_to_measurement_matches;
+
+    let user = get_or_create_user(pool, TelegramId(97532), Some("en")).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "2 cups flour\n1 cup sugar").await?;
+    for (name, quantity, unit) in [("flour", 2.0, "cups"), ("sugar", 1.0, "cup")] {
+        create_ingredient(
+            pool,
+            user.id,
+            Some(recipe_id),
+            name,
+            Some(quantity),
+            Some(unit),
+            "",
+        )
+        .await?;
+    }
+
+    // Two editing sessions start from the same saved list
+    let start = |pool: &PgPool| {
+        let pool = pool.clone();
+        async move {
+            let version = get_recipe_ingredients_version(&pool, recipe_id)
+                .await?
+                .expect("recipe exists");
+            let matches = ingredients_to_measurement_matches(
+                &get_recipe_ingredients(&pool, recipe_id).await?,
+            );
+            anyhow::Ok((version, matches))
+        }
+    };
+    let (version_a, mut edit_a) = start(pool).await?;
+    let (version_b, mut edit_b) = start(pool).await?;
+    assert_eq!(version_a, version_b);
+
+    // The first session renames flour and saves
+    edit_a[0].ingredient_name = "whole wheat flour".to_string();
+    assert!(update_recipe_ingredients_versioned(pool, recipe_id, version_a, &edit_a).await?);
+
+    // The second one, still showing plain flour, removes sugar: nothing is written
+    edit_b.remove(1);
+    assert!(!update_recipe_ingredients_versioned(pool, recipe_id, version_b, &edit_b).await?);
+    let names: Vec<String> = get_recipe_ingredients(pool, recipe_id)
+        .await?
+        .into_iter()
+        .map(|ingredient| ingredient.name)
+        .collect();
+    assert_eq!(names, ["whole wheat flour", "sugar"]);
+
+    // Reloaded, the second session edits the current list
+    let (version_b, mut edit_b) = start(pool).await?;
+    assert_ne!(version_b, version_a);
+    edit_b.remove(1);
+    assert!(update_recipe_ingredients_versioned(pool, recipe_id, version_b, &edit_b).await?);
+    let names: Vec<String> = get_recipe_ingredients(pool, recipe_id)
+        .await?
+        .into_iter()
+        .map(|ingredient| ingredient.name)
+        .collect();
+    assert_eq!(names, ["whole wheat flour"]);
+
+    // Writes outside the editor count as changes too
+    let (version, _) = start(pool).await?;
+    let ingredient_id = get_recipe_ingredients(pool, recipe_id).await?[0].id;
+    update_ingredient(pool, ingredient_id, Some("spelt flour"), None, None).await?;
+    assert!(!update_recipe_ingredients_versioned(pool, recipe_id, version, &edit_b).await?);
+    assert_eq!(
+        get_recipe_ingredients(pool, recipe_id).await?[0].name,
+        "spelt flour"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_repair_ingredient_user_ids() -> Result<()> {
+    skip_if_no_db!(test_repair_ingredient_user_ids_impl)
+}
+
+async fn test_repair_ingredient_user_ids_impl(pool: &PgPool) -> Result<()> {
+    // A user whose internal id is reused as another user's Telegram id
+    let other = get_or_create_user(pool, TelegramId(13579), Some("en")).await?;
+    let owner = get_or_create_user(pool, TelegramId(other.id.0), Some("en")).await?;
+
+    let other_recipe = create_recipe(pool, other.telegram_id, "1 egg").await?;
+    let other_ingredient = create_ingredient(
+        pool,
+        other.id,
+        Some(other_recipe),
+        "egg",
+        Some(1.0),
+        None,
+        "",
+    )
+    .await?;
+
+    // Row written the old way, with the owner's Telegram id as user_id
+    let owner_recipe = create_recipe(pool, owner.telegram_id, "2 cups flour").await?;
+    let misattributed = create_ingredient(
+        pool,
+        UserId(owner.telegram_id.0),
+        Some(owner_recipe),
+        "flour",
+        Some(2.0),
+        Some("cups"),
+        "",
+    )
+    .await?;
+
+    assert_eq!(repair_ingredient_user_ids(pool).await?, 1);
+
+    let repaired = read_ingredient(pool, misattributed).await?.unwrap();
+    assert_eq!(repaired.user_id, owner.id);
+    let untouched = read_ingredient(pool, other_ingredient).await?.unwrap();
+    assert_eq!(untouched.user_id, other.id);
+
+    // Running the repair again is a no-op
+    assert_eq!(repair_ingredient_user_ids(pool).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_text_search() -> Result<()> {
+    skip_if_no_db!(test_full_text_search_impl)
+}
+
+async fn test_full_text_search_impl(pool: &PgPool) -> Result<()> {
+    create_recipe(pool, TelegramId(12345), "flour 2 cups sugar 1 cup").await?;
+    create_recipe(pool, TelegramId(12345), "butter 100 grams milk 250 ml").await?;
+    create_recipe(pool, TelegramId(67890), "chocolate 200 grams").await?;
+
+    // Search for entries containing "flour"
+    let results = search_recipes(pool, TelegramId(12345), "flour").await?;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].recipe.content.contains("flour"));
+
+    // Search for entries containing "grams"
+    let results = search_recipes(pool, TelegramId(12345), "grams").await?;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].recipe.content.contains("butter"));
+
+    // Search for non-existent term
+    let results = search_recipes(pool, TelegramId(12345), "nonexistent").await?;
+    assert_eq!(results.len(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_recipes_matches_names() -> Result<()> {
+    skip_if_no_db!(test_search_recipes_matches_names_impl)
+}
+
+async fn test_search_recipes_matches_names_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(953_001);
+    let other = TelegramId(953_002);
+    let tart = create_recipe(pool, owner, "3 apples 200 g flour").await?;
+    update_recipe_name(pool, tart, "Tarte Tatin").await?;
+    let cake = create_recipe(pool, owner, "4 eggs").await?;
+    update_recipe_name(pool, cake, "100% chocolate cake").await?;
+    let foreign = create_recipe(pool, other, "2 pears").await?;
+    update_recipe_name(pool, foreign, "Tarte aux poires").await?;
+
+    // A case-insensitive substring of the name matches, even when the content does not
+    let results = search_recipes(pool, owner, "tarte").await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].recipe.id, tart);
+
+    // LIKE wildcards in the query are matched literally
+    let results = search_recipes(pool, owner, "100%").await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].recipe.id, cake);
+    assert!(search_recipes(pool, owner, "_").await?.is_empty());
+
+    // Other users' recipes are never returned
+    assert!(search_recipes(pool, owner, "poires").await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_recipes_covers_ingredients_with_stemming() -> Result<()> {
+    skip_if_no_db!(test_search_recipes_covers_ingredients_with_stemming_impl)
+}
+
+async fn test_search_recipes_covers_ingredients_with_stemming_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(953_101);
+    let user = get_or_create_user(pool, owner, Some("fr")).await?;
+    let salad = create_recipe(pool, owner, "Couper et assaisonner").await?;
+    update_recipe_name(pool, salad, "Salade d'été").await?;
+    let tomato = create_ingredient(
+        pool,
+        user.id,
+        Some(salad),
+        "tomate",
+        Some(3.0),
+        None,
+        "3 tomate",
+    )
+    .await?;
+    let sauce = create_recipe(pool, owner, "Mijoter avec une tomate").await?;
+    update_recipe_name(pool, sauce, "Sauce tomate").await?;
+
+    // The plural finds the singular ingredient name, in French
+    let results = search_recipes(pool, owner, "tomates").await?;
+    let ids: Vec<_> = results.iter().map(|found| found.recipe.id).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&salad));
+    assert!(results.iter().all(|found| found.rank > 0.0));
+    let salad_match = results
+        .iter()
+        .find(|found| found.recipe.id == salad)
+        .unwrap();
+    assert!(salad_match.headline.contains("<b>tomate</b>"));
+
+    // A name match outranks a content match
+    assert_eq!(ids[0], sauce);
+
+    // Renamed or removed ingredients are searched no more
+    update_ingredient(pool, tomato, Some("concombre"), None, None).await?;
+    let results = search_recipes(pool, owner, "concombres").await?;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].recipe.id, salad);
+    delete_ingredient(pool, tomato).await?;
+    assert!(search_recipes(pool, owner, "concombre").await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_recipes_by_name_prefix() -> Result<()> {
+    skip_if_no_db!(test_search_recipes_by_name_prefix_impl)
+}
+
+async fn test_search_recipes_by_name_prefix_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(954_001);
+    let user = get_or_create_user(pool, owner, Some("en")).await?;
+    let carbonara = create_recipe(pool, owner, "200 g spaghetti").await?;
+    update_recipe_name(pool, carbonara, "Carbonara").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(carbonara),
+        "spaghetti",
+        Some(200.0),
+        Some("g"),
+        "200 g spaghetti",
+    )
+    .await?;
+    let cake = create_recipe(pool, owner, "4 eggs").await?;
+    update_recipe_name(pool, cake, "Carrot cake").await?;
+    let soup = create_recipe(pool, owner, "1 l stock").await?;
+    update_recipe_name(pool, soup, "Soupe de carottes").await?;
+    let foreign = create_recipe(pool, TelegramId(954_002), "2 eggs").await?;
+    update_recipe_name(pool, foreign, "Carbonade").await?;
+
+    // Case-insensitive prefix of the name, ordered by name, with ingredients
+    let results = search_recipes_by_name_prefix(pool, owner, "CAR", 10, 0).await?;
+    let ids: Vec<_> = results.iter().map(|(recipe, _)| recipe.id).collect();
+    assert_eq!(ids, vec![carbonara, cake]);
+    assert_eq!(results[0].1.len(), 1);
+    assert_eq!(results[0].1[0].name, "spaghetti");
+    assert!(results[1].1.is_empty());
+
+    // Pages follow the offset
+    let second_page = search_recipes_by_name_prefix(pool, owner, "car", 1, 1).await?;
+    assert_eq!(second_page.len(), 1);
+    assert_eq!(second_page[0].0.id, cake);
+
+    // A substring that is not a prefix does not match, nor do LIKE wildcards
+    assert!(search_recipes_by_name_prefix(pool, owner, "carott", 10, 0)
+        .await?
+        .is_empty());
+    assert!(search_recipes_by_name_prefix(pool, owner, "%", 10, 0)
+        .await?
+        .is_empty());
+
+    // An empty query lists all of the user's named recipes
+    let all = search_recipes_by_name_prefix(pool, owner, "", 10, 0).await?;
+    assert_eq!(all.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_search_recipes_by_ingredient() -> Result<()> {
+    skip_if_no_db!(test_search_recipes_by_ingredient_impl)
+}
+
+async fn test_search_recipes_by_ingredient_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(954_001);
+    let other = TelegramId(954_002);
+    let user = get_or_create_user(pool, owner, None).await?;
+    let other_user = get_or_create_user(pool, other, None).await?;
+
+    let croissants = create_recipe(pool, owner, "croissants").await?;
+    update_recipe_name(pool, croissants, "Croissants").await?;
+    for name in ["Unsalted Butter", "flour", "salted butter"] {
+        create_ingredient(
+            pool,
+            user.id,
+            Some(croissants),
+            name,
+            Some(100.0),
+            Some("g"),
+            "",
+        )
+        .await?;
+    }
+    let cookies = create_recipe(pool, owner, "cookies").await?;
+    update_recipe_name(pool, cookies, "Cookies").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(cookies),
+        "butter",
+        Some(50.0),
+        Some("g"),
+        "",
+    )
+    .await?;
+    let bread = create_recipe(pool, owner, "bread").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(bread),
+        "flour",
+        Some(500.0),
+        Some("g"),
+        "",
+    )
+    .await?;
+
+    // Ingredients without a recipe and other users' recipes are never matched
+    create_ingredient(pool, user.id, None, "butter", Some(10.0), Some("g"), "").await?;
+    let foreign = create_recipe(pool, other, "brioche").await?;
+    create_ingredient(
+        pool,
+        other_user.id,
+        Some(foreign),
+        "butter",
+        Some(80.0),
+        Some("g"),
+        "",
+    )
+    .await?;
+
+    // Partial, case-insensitive match; most matching ingredients first
+    let matches = search_recipes_by_ingredient(pool, owner, "BUTTER").await?;
+    let found: Vec<(RecipeId, i64)> = matches
+        .iter()
+        .map(|m| (m.recipe.id, m.matching_ingredients))
+        .collect();
+    assert_eq!(found, vec![(croissants, 2), (cookies, 1)]);
+
+    assert!(search_recipes_by_ingredient(pool, owner, "sugar")
+        .await?
+        .is_empty());
+    // LIKE wildcards are matched literally
+    assert!(search_recipes_by_ingredient(pool, owner, "%")
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_user_recipes_paginated() -> Result<()> {
+    skip_if_no_db!(test_get_user_recipes_paginated_impl)
+}
+
+async fn test_get_user_recipes_paginated_impl(pool: &PgPool) -> Result<()> {
+    // Create recipes with names
+    let recipe1_id = create_recipe(pool, TelegramId(12345), "flour 2 cups").await?;
+    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
+
+    let recipe2_id = create_recipe(pool, TelegramId(12345), "butter 100g").await?;
+    update_recipe_name(pool, recipe2_id, "Apple Pie").await?;
+
+    let recipe3_id = create_recipe(pool, TelegramId(12345), "sugar 1 cup").await?;
+    update_recipe_name(pool, recipe3_id, "Banana Bread").await?;
+
+    // Create recipe for different user
+    let recipe4_id = create_recipe(pool, TelegramId(67890), "milk 250ml").await?;
+    update_recipe_name(pool, recipe4_id, "Pancakes").await?;
+
+    // Test pagination: limit 2, offset 0
+    let (recipes, total) = get_user_recipes_paginated(pool, TelegramId(12345), 2, 0).await?;
+    assert_eq!(total, 3);
+    assert_eq!(recipes.len(), 2);
+    assert!(recipes.contains(&"Apple Pie".to_string()));
+    assert!(recipes.contains(&"Banana Bread".to_string()));
+
+    // Test pagination: limit 2, offset 2
+    let (recipes, total) = get_user_recipes_paginated(pool, TelegramId(12345), 2, 2).await?;
+    assert_eq!(total, 3);
+    assert_eq!(recipes.len(), 1);
+    assert_eq!(recipes[0], "Chocolate Cake");
+
+    // Test with different user
+    let (recipes, total) = get_user_recipes_paginated(pool, TelegramId(67890), 10, 0).await?;
+    assert_eq!(total, 1);
+    assert_eq!(recipes.len(), 1);
+    assert_eq!(recipes[0], "Pancakes");
+
+    // Test with no recipes
+    let (recipes, total) = get_user_recipes_paginated(pool, TelegramId(99999), 10, 0).await?;
+    assert_eq!(total, 0);
+    assert_eq!(recipes.len(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_recipes_by_name() -> Result<()> {
+    skip_if_no_db!(test_get_recipes_by_name_impl)
+}
+
+async fn test_get_recipes_by_name_impl(pool: &PgPool) -> Result<()> {
+    // Create multiple recipes with the same name
+    let recipe1_id = create_recipe(pool, TelegramId(12345), "flour 2 cups sugar 1 cup").await?;
+    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
+
+    let recipe2_id = create_recipe(pool, TelegramId(12345), "butter 100g eggs 2").await?;
+    update_recipe_name(pool, recipe2_id, "Chocolate Cake").await?;
+
+    let recipe3_id = create_recipe(pool, TelegramId(12345), "milk 250ml vanilla 1 tsp").await?;
+    update_recipe_name(pool, recipe3_id, "Vanilla Pudding").await?;
+
+    // Create recipe with same name for different user
+    let recipe4_id = create_recipe(pool, TelegramId(67890), "flour 1 cup").await?;
+    update_recipe_name(pool, recipe4_id, "Chocolate Cake").await?;
+
+    // Test getting multiple recipes with same name
+    let recipes = get_recipes_by_name(pool, TelegramId(12345), "Chocolate Cake").await?;
+    assert_eq!(recipes.len(), 2);
+
+    // Verify the recipes are returned in descending creation order (most recent first)
+    assert_eq!(recipes[0].id, recipe2_id); // Second recipe created (most recent)
+    assert_eq!(recipes[1].id, recipe1_id); // First recipe created
+    assert_eq!(recipes[0].recipe_name.as_ref().unwrap(), "Chocolate Cake");
+    assert_eq!(recipes[1].recipe_name.as_ref().unwrap(), "Chocolate Cake");
+
+    // Test getting single recipe
+    let recipes = get_recipes_by_name(pool, TelegramId(12345), "Vanilla Pudding").await?;
+    assert_eq!(recipes.len(), 1);
+    assert_eq!(recipes[0].id, recipe3_id);
+    assert_eq!(recipes[0].recipe_name.as_ref().unwrap(), "Vanilla Pudding");
+
+    // Test getting recipes for different user
+    let recipes = get_recipes_by_name(pool, TelegramId(67890), "Chocolate Cake").await?;
+    assert_eq!(recipes.len(), 1);
+    assert_eq!(recipes[0].id, recipe4_id);
+
+    // Test getting non-existent recipe name
+    let recipes = get_recipes_by_name(pool, TelegramId(12345), "Non-existent Recipe").await?;
+    assert_eq!(recipes.len(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_find_recipe_name_by_token() -> Result<()> {
+    skip_if_no_db!(test_find_recipe_name_by_token_impl)
+}
+
+async fn test_find_recipe_name_by_token_impl(pool: &PgPool) -> Result<()> {
+    // 100-character unicode names, one of them with colons
+    let names = ["🍰".repeat(100), "Gâteau: ".repeat(12) + "fin!"];
+    for name in &names {
+        let recipe_id = create_recipe(pool, TelegramId(12345), "flour 2 cups").await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+    }
+    let other_user_recipe = create_recipe(pool, TelegramId(67890), "milk 250ml").await?;
+    update_recipe_name(pool, other_user_recipe, &names[0]).await?;
+
+    // List → select → details: every listed name resolves back to its recipes
+    let (listed, _) = get_user_recipes_paginated(pool, TelegramId(12345), 10, 0).await?;
+    assert_eq!(listed.len(), names.len());
+    for name in &listed {
+        let token = recipe_name_token(name);
+        let resolved = find_recipe_name_by_token(pool, TelegramId(12345), &token).await?;
+        assert_eq!(resolved.as_ref(), Some(name));
+
+        let recipes = get_recipes_by_name(pool, TelegramId(12345), name).await?;
+        assert_eq!(recipes.len(), 1);
+        assert_eq!(recipes[0].recipe_name.as_ref(), Some(name));
+    }
+
+    // Tokens are scoped to the user, and unknown tokens resolve to nothing
+    let token = recipe_name_token(&names[1]);
+    assert_eq!(
+        find_recipe_name_by_token(pool, TelegramId(67890), &token).await?,
+        None
+    );
+    assert_eq!(
+        find_recipe_name_by_token(pool, TelegramId(12345), &recipe_name_token("Missing")).await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_newest_same_named_recipe() -> Result<()> {
+    skip_if_no_db!(test_replace_newest_same_named_recipe_impl)
+}
+
+async fn test_replace_newest_same_named_recipe_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::text_processing::MeasurementMatch;
+
+    let user = get_or_create_user(pool, TelegramId(86420), Some("en")).await?;
+    let older_id = create_recipe(pool, user.telegram_id, "2 bananas").await?;
+    update_recipe_name(pool, older_id, "Banana Bread").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(older_id),
+        "bananas",
+        Some(2.0),
+        None,
+        "",
+    )
+    .await?;
+    let newer_id = create_recipe(pool, user.telegram_id, "3 bananas").await?;
+    update_recipe_name(pool, newer_id, "Banana Bread").await?;
+    create_ingredient(
+        pool,
+        user.id,
+        Some(newer_id),
+        "bananas",
+        Some(3.0),
+        None,
+        "",
+    )
+    .await?;
+
+    // The conflict prompt replaces the first (most recent) recipe returned by name
+    let existing = get_recipes_by_name(pool, user.telegram_id, "Banana Bread").await?;
+    assert_eq!(existing.len(), 2);
+    assert_eq!(existing[0].id, newer_id);
+
+    let pending = vec![MeasurementMatch {
+        quantity: "250".to_string(),
+        measurement: Some("g".to_string()),
+        ingredient_name: "flour".to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    }];
+    update_recipe_ingredients(pool, existing[0].id, &pending).await?;
+
+    let replaced = get_recipe_ingredients(pool, newer_id).await?;
+    assert_eq!(replaced.len(), 1);
+    assert_eq!(replaced[0].name, "flour");
+
+    // The older copy is left untouched and no third recipe was created
+    let untouched = get_recipe_ingredients(pool, older_id).await?;
+    assert_eq!(untouched.len(), 1);
+    assert_eq!(untouched[0].name, "bananas");
+    assert_eq!(
+        get_recipes_by_name(pool, user.telegram_id, "Banana Bread")
+            .await?
+            .len(),
+        2
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extraction_report_consent_transitions() -> Result<()> {
+    skip_if_no_db!(test_extraction_report_consent_transitions_impl)
+}
+
+async fn test_extraction_report_consent_transitions_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::extraction_reports::ExtractionContext;
+
+    let context = ExtractionContext::new(
+        "2 cups flour",
+        1,
+        Some("high_quality_minimal".to_string()),
+        Some("photo-file-id".to_string()),
+    );
+    let report =
+        create_extraction_report(pool, TelegramId(97531), &context, Some("wrong unit")).await?;
+    assert_eq!(report.correlation_id, context.correlation_id);
+    assert_eq!(report.match_count, 1);
+    assert_eq!(report.comment.as_deref(), Some("wrong unit"));
+    assert_eq!(report.photo_consent, "not_requested");
+
+    // Only the expected current consent can be moved forward
+    assert!(!transition_report_consent(pool, report.id, "requested", "granted").await?);
+    assert!(transition_report_consent(pool, report.id, "not_requested", "requested").await?);
+    assert!(transition_report_consent(pool, report.id, "requested", "granted").await?);
+    assert!(!transition_report_consent(pool, report.id, "requested", "declined").await?);
+
+    let stored = get_extraction_report(pool, report.id).await?.unwrap();
+    assert_eq!(stored.photo_consent, "granted");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_has_duplicate_recipes() -> Result<()> {
+    skip_if_no_db!(test_has_duplicate_recipes_impl)
+}
+
+async fn test_has_duplicate_recipes_impl(pool: &PgPool) -> Result<()> {
+    // Create multiple recipes with the same name
+    let recipe1_id = create_recipe(pool, TelegramId(12345), "flour 2 cups").await?;
+    update_recipe_name(pool, recipe1_id, "Chocolate Cake").await?;
+
+    let recipe2_id = create_recipe(pool, TelegramId(12345), "butter 100g").await?;
+    update_recipe_name(pool, recipe2_id, "Chocolate Cake").await?;
+
+    // Create single recipe with different name
+    let recipe3_id = create_recipe(pool, TelegramId(12345), "milk 250ml").await?;
+    update_recipe_name(pool, recipe3_id, "Vanilla Pudding").await?;
+
+    // Test duplicate detection - should return true for "Chocolate Cake"
+    let has_duplicates = has_duplicate_recipes(pool, TelegramId(12345), "Chocolate Cake").await?;
+    assert!(has_duplicates);
+
+    // Test no duplicates - should return false for "Vanilla Pudding"
+    let has_duplicates = has_duplicate_recipes(pool, TelegramId(12345), "Vanilla Pudding").await?;
+    assert!(!has_duplicates);
+
+    // Test non-existent recipe name - should return false
+    let has_duplicates =
+        has_duplicate_recipes(pool, TelegramId(12345), "Non-existent Recipe").await?;
+    assert!(!has_duplicates);
+
+    // Test with different user - should return false even if name exists for another user
+    let has_duplicates = has_duplicate_recipes(pool, TelegramId(67890), "Chocolate Cake").await?;
+    assert!(!has_duplicates);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_schema_validation() -> Result<()> {
+    skip_if_no_db!(test_schema_validation_impl)
+}
+
+async fn test_schema_validation_impl(pool: &PgPool) -> Result<()> {
+    // Schema should be valid after initialization
+    validate_database_schema(pool).await?;
+
+    // Test that validation fails if a required table is missing
+    sqlx::query("DROP TABLE IF EXISTS ingredients CASCADE")
+        .execute(pool)
+        .await?;
+
+    let result = validate_database_schema(pool).await;
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("ingredients"));
+
+    // Recreate the table and test again
+    init_database_schema(pool).await?;
+    validate_database_schema(pool).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_migration_system() -> Result<()> {
+    skip_if_no_db!(test_migration_system_impl)
+}
+
+async fn test_migration_system_impl(pool: &PgPool) -> Result<()> {
+    // Clean up any existing migration state
+    sqlx::query("DROP TABLE IF EXISTS schema_migrations CASCADE")
+        .execute(pool)
+        .await?;
+    sqlx::query("DROP TABLE IF EXISTS ingredients CASCADE")
+        .execute(pool)
+        .await?;
+    sqlx::query("DROP TABLE IF EXISTS recipes CASCADE")
+        .execute(pool)
+        .await?;
+    sqlx::query("DROP TABLE IF EXISTS users CASCADE")
+        .execute(pool)
+        .await?;
+
+    // Test initial state - no migrations applied
+    let version = migrations::get_current_version(pool).await?;
+    assert_eq!(version, 0);
+
+    // Apply migrations
+    migrations::apply_pending_migrations(pool).await?;
+
+    // Check that migrations table was created and version is updated
+    let version = migrations::get_current_version(pool).await?;
+    let latest_version = migrations::get_migrations()
+        .last()
+        .map(|m| m.version)
+        .unwrap_or(0);
+    assert_eq!(version, latest_version);
+
+    // Verify that tables were created
+    validate_database_schema(pool).await?;
+
+    // Test rollback (if down migrations are available)
+    // Note: Current migration doesn't have a down script, so this will fail gracefully
+    let rollback_result = migrations::rollback_to_version(pool, 0).await;
+    assert!(rollback_result.is_err()); // Should fail because no down migration
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_schema_validation_details() -> Result<()> {
+    skip_if_no_db!(test_schema_validation_details_impl)
+}
+
+async fn test_schema_validation_details_impl(pool: &PgPool) -> Result<()> {
+    // Test that all expected columns exist with correct types
+
+    // Check users table columns
+    let user_columns = vec![
+        ("id", "bigint"),
+        ("telegram_id", "bigint"),
+        ("language_code", "character varying"),
+        ("created_at", "timestamp with time zone"),
+        ("updated_at", "timestamp with time zone"),
+    ];
+
+    for (col_name, _expected_type) in user_columns {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns
+             WHERE table_name = 'users' AND column_name = $1 AND table_schema = 'public')",
+        )
+        .bind(col_name)
+        .fetch_one(pool)
+        .await?;
+        assert!(exists, "Column {} should exist in users table", col_name);
     }
 
     // Check ingredients table has raw_text column
@@ -429,201 +2293,1404 @@ async fn test_schema_validation_details_impl(pool: &PgPool) -> Result<()> {
     )
     .fetch_one(pool)
     .await?;
-    assert!(
-        has_raw_text,
-        "raw_text column should exist in ingredients table"
+    assert!(
+        has_raw_text,
+        "raw_text column should exist in ingredients table"
+    );
+
+    // Check that required indexes exist
+    let indexes = vec![
+        ("recipes_content_tsv_idx", "recipes"),
+        ("ingredients_user_id_idx", "ingredients"),
+        ("ingredients_recipe_id_idx", "ingredients"),
+    ];
+
+    for (index_name, table_name) in indexes {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pg_indexes WHERE tablename = $1 AND indexname = $2)",
+        )
+        .bind(table_name)
+        .bind(index_name)
+        .fetch_one(pool)
+        .await?;
+        assert!(
+            exists,
+            "Index {} should exist on table {}",
+            index_name, table_name
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_split_sql_statements_single_statement() {
+    let sql = "CREATE TABLE test (id INT);";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec!["CREATE TABLE test (id INT);".to_string()])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_multiple_statements() {
+    let sql = "CREATE TABLE test (id INT); INSERT INTO test VALUES (1);";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec![
+            "CREATE TABLE test (id INT);".to_string(),
+            "INSERT INTO test VALUES (1);".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_with_string_literals() {
+    let sql = "INSERT INTO test VALUES ('hello;world'); CREATE TABLE test2 (id INT);";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec![
+            "INSERT INTO test VALUES ('hello;world');".to_string(),
+            "CREATE TABLE test2 (id INT);".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_with_double_quotes() {
+    let sql = r#"INSERT INTO test VALUES ("hello;world"); CREATE TABLE test2 (id INT);"#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec![
+            r#"INSERT INTO test VALUES ("hello;world");"#.to_string(),
+            "CREATE TABLE test2 (id INT);".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_with_comments() {
+    let sql = r#"
+        -- This is a comment
+        CREATE TABLE test (id INT);
+        -- Another comment
+        INSERT INTO test VALUES (1);
+    "#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec![
+            "-- This is a comment\n        CREATE TABLE test (id INT);".to_string(),
+            "-- Another comment\n        INSERT INTO test VALUES (1);".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_mixed_strings_and_comments() {
+    let sql = r#"
+        -- Create table
+        CREATE TABLE test (
+            id INT,
+            name VARCHAR(100) DEFAULT 'test;value'
+        );
+        -- Insert data
+        INSERT INTO test VALUES (1, 'hello;world');
+    "#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    match statements {
+        Ok(stmts) => {
+            assert_eq!(stmts.len(), 2);
+            assert!(stmts[0].contains("CREATE TABLE"));
+            assert!(stmts[1].contains("INSERT INTO"));
+        }
+        Err(e) => panic!("Failed to split SQL: {}", e),
+    }
+}
+
+#[test]
+fn test_split_sql_statements_empty_input() {
+    let sql = "";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(statements, Ok(Vec::<String>::new()));
+}
+
+#[test]
+fn test_split_sql_statements_only_whitespace() {
+    let sql = "   \n\t   ";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(statements, Ok(Vec::<String>::new()));
+}
+
+#[test]
+fn test_split_sql_statements_no_trailing_semicolon() {
+    let sql = "CREATE TABLE test (id INT)";
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    assert_eq!(
+        statements,
+        Ok(vec!["CREATE TABLE test (id INT)".to_string()])
+    );
+}
+
+#[test]
+fn test_split_sql_statements_complex_migration_sql() {
+    let sql = r#"
+        -- Create users table
+        CREATE TABLE IF NOT EXISTS users (
+            id BIGSERIAL PRIMARY KEY,
+            telegram_id BIGINT UNIQUE NOT NULL,
+            language_code VARCHAR(10) DEFAULT 'en',
+            created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+        );
+
+        -- Create recipes table
+        CREATE TABLE IF NOT EXISTS recipes (
+            id BIGSERIAL PRIMARY KEY,
+            telegram_id BIGINT NOT NULL,
+            content TEXT NOT NULL,
+            recipe_name VARCHAR(255),
+            created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+            content_tsv tsvector GENERATED ALWAYS AS (to_tsvector('english', content)) STORED
+        );
+
+        -- Create indexes
+        CREATE INDEX IF NOT EXISTS recipes_content_tsv_idx ON recipes USING GIN (content_tsv);
+    "#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    match statements {
+        Ok(stmts) => {
+            assert_eq!(stmts.len(), 3);
+            assert!(stmts[0].contains("CREATE TABLE IF NOT EXISTS users"));
+            assert!(stmts[1].contains("CREATE TABLE IF NOT EXISTS recipes"));
+            assert!(stmts[2].contains("CREATE INDEX IF NOT EXISTS recipes_content_tsv_idx"));
+        }
+        Err(e) => panic!("Failed to split SQL: {}", e),
+    }
+}
+
+#[test]
+fn test_split_sql_statements_semicolon_in_string_and_comment() {
+    let sql = r#"
+        -- This comment has ; in it
+        INSERT INTO test VALUES ('value;with;semicolons');
+        -- Another ; comment
+        UPDATE test SET value = 'new;value';
+    "#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    match statements {
+        Ok(stmts) => {
+            assert_eq!(stmts.len(), 2);
+            assert!(stmts[0].contains("INSERT INTO"));
+            assert!(stmts[1].contains("UPDATE"));
+        }
+        Err(e) => panic!("Failed to split SQL: {}", e),
+    }
+}
+
+#[test]
+fn test_split_sql_statements_with_dollar_quoted_body() {
+    let sql = r#"
+        CREATE FUNCTION touch() RETURNS trigger LANGUAGE plpgsql AS $$
+        BEGIN
+            NEW.updated_at := NOW(); -- keep ; here
+            RETURN NEW;
+        END
+        $$;
+        CREATE TRIGGER touch BEFORE UPDATE ON test FOR EACH ROW EXECUTE FUNCTION touch();
+    "#;
+    let statements = just_ingredients::db::migrations::split_sql_statements(sql).unwrap();
+    assert_eq!(statements.len(), 2);
+    assert!(statements[0].starts_with("CREATE FUNCTION"));
+    assert!(statements[0].ends_with("$$;"));
+    assert!(statements[0].contains("RETURN NEW;"));
+    assert!(statements[1].starts_with("CREATE TRIGGER"));
+}
+
+#[test]
+fn test_escape_like_pattern() {
+    assert_eq!(escape_like_pattern("tumeric"), "tumeric");
+    assert_eq!(escape_like_pattern("100%_pure"), "100\\%\\_pure");
+    assert_eq!(escape_like_pattern("a\\b"), "a\\\\b");
+}
+
+#[test]
+fn test_variant_funnel_stats_without_reviews() {
+    let stats = VariantFunnelStats {
+        variant: "full".to_string(),
+        reviews_shown: 0,
+        edits_made: 2,
+        confirmed: 1,
+    };
+    assert_eq!(stats.confirm_rate(), 0.0);
+    assert_eq!(stats.edits_per_review(), 0.0);
+}
+
+#[tokio::test]
+async fn test_retried_save_is_idempotent() -> Result<()> {
+    skip_if_no_db!(test_retried_save_is_idempotent_impl)
+}
+
+async fn test_retried_save_is_idempotent_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::bot::save_ingredients_to_database;
+    use just_ingredients::text_processing::MeasurementMatch;
+
+    let telegram_id = 75319;
+    let ingredients = vec![MeasurementMatch {
+        quantity: "2".to_string(),
+        measurement: Some("cups".to_string()),
+        ingredient_name: "flour".to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    }];
+
+    // The first confirm saves; Telegram then redelivers it after a handler error
+    for _ in 0..2 {
+        save_ingredients_to_database(
+            pool,
+            telegram_id,
+            "2 cups flour",
+            &ingredients,
+            "Retried Pancakes",
+            Some("en"),
+            Some("retry-correlation-id"),
+            None,
+            None,
+        )
+        .await?;
+    }
+
+    let saved = get_recipes_by_name(pool, TelegramId(telegram_id), "Retried Pancakes").await?;
+    assert_eq!(saved.len(), 1);
+    assert_eq!(get_recipe_ingredients(pool, saved[0].id).await?.len(), 1);
+
+    // A different extraction with the same content is a new recipe
+    save_ingredients_to_database(
+        pool,
+        telegram_id,
+        "2 cups flour",
+        &ingredients,
+        "Retried Pancakes",
+        Some("en"),
+        Some("another-correlation-id"),
+        None,
+        None,
+    )
+    .await?;
+    assert_eq!(
+        get_recipes_by_name(pool, TelegramId(telegram_id), "Retried Pancakes")
+            .await?
+            .len(),
+        2
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipe_photo_is_kept_with_saved_recipe() -> Result<()> {
+    skip_if_no_db!(test_recipe_photo_is_kept_with_saved_recipe_impl)
+}
+
+async fn test_recipe_photo_is_kept_with_saved_recipe_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::bot::save_ingredients_to_database;
+    use just_ingredients::text_processing::MeasurementMatch;
+
+    let telegram_id = 75321;
+    let ingredients = vec![MeasurementMatch {
+        quantity: "2".to_string(),
+        measurement: Some("cups".to_string()),
+        ingredient_name: "flour".to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    }];
+    let photo = RecipePhoto {
+        file_id: "AgACAgQAAxkBAAIB".to_string(),
+        file_unique_id: "AQADu7ExG".to_string(),
+    };
+
+    save_ingredients_to_database(
+        pool,
+        telegram_id,
+        "2 cups flour",
+        &ingredients,
+        "Photographed Pancakes",
+        None,
+        None,
+        Some(&photo),
+        None,
+    )
+    .await?;
+    save_ingredients_to_database(
+        pool,
+        telegram_id,
+        "2 cups flour",
+        &ingredients,
+        "Typed Pancakes",
+        None,
+        None,
+        None,
+        None,
+    )
+    .await?;
+
+    let photographed =
+        get_recipes_by_name(pool, TelegramId(telegram_id), "Photographed Pancakes").await?;
+    let typed = get_recipes_by_name(pool, TelegramId(telegram_id), "Typed Pancakes").await?;
+    assert_eq!(
+        get_recipe_photo(pool, TelegramId(telegram_id), photographed[0].id).await?,
+        Some(photo)
+    );
+    assert_eq!(
+        get_recipe_photo(pool, TelegramId(telegram_id), typed[0].id).await?,
+        None
+    );
+    // Another user cannot get the photo of the recipe
+    assert_eq!(
+        get_recipe_photo(pool, TelegramId(telegram_id + 1), photographed[0].id).await?,
+        None
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recent_activity_union_ordering() -> Result<()> {
+    skip_if_no_db!(test_recent_activity_union_ordering_impl)
+}
+
+async fn test_recent_activity_union_ordering_impl(pool: &PgPool) -> Result<()> {
+    let user = get_or_create_user(pool, TelegramId(64208), Some("en")).await?;
+
+    let first = create_recipe(pool, user.telegram_id, "2 eggs").await?;
+    update_recipe_name(pool, first, "Recipe").await?;
+    create_ingredient(pool, user.id, Some(first), "eggs", Some(2.0), None, "").await?;
+    update_recipe_name(pool, first, "Omelette").await?;
+    record_recipe_activity(
+        pool,
+        user.telegram_id,
+        first,
+        RecipeActivityKind::Renamed,
+        Some("Omelette"),
+        Some("Recipe"),
+        None,
+    )
+    .await?;
+    let second = create_recipe(pool, user.telegram_id, "1 apple").await?;
+    update_recipe_name(pool, second, "Tarte Tatin").await?;
+    record_recipe_activity(
+        pool,
+        user.telegram_id,
+        second,
+        RecipeActivityKind::Edited,
+        Some("Tarte Tatin"),
+        None,
+        Some(3),
+    )
+    .await?;
+
+    // Newest first: edit, second save, rename, first save
+    let (activities, total) = get_recent_activity(pool, user.telegram_id, 10, 0).await?;
+    assert_eq!(total, 4);
+    let kinds: Vec<(RecipeId, RecipeActivityKind)> = activities
+        .iter()
+        .map(|activity| (activity.recipe_id, activity.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (second, RecipeActivityKind::Edited),
+            (second, RecipeActivityKind::Saved),
+            (first, RecipeActivityKind::Renamed),
+            (first, RecipeActivityKind::Saved),
+        ]
+    );
+    assert_eq!(activities[2].previous_name.as_deref(), Some("Recipe"));
+    assert_eq!(activities[3].ingredient_count, Some(1));
+
+    // Pagination continues where the first page stopped
+    let (page, _) = get_recent_activity(pool, user.telegram_id, 3, 3).await?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].recipe_id, first);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipe_tags_filter_pagination() -> Result<()> {
+    skip_if_no_db!(test_recipe_tags_filter_pagination_impl)
+}
+
+async fn test_recipe_tags_filter_pagination_impl(pool: &PgPool) -> Result<()> {
+    let telegram_id = TelegramId(64315);
+    get_or_create_user(pool, telegram_id, Some("en")).await?;
+
+    let mut recipe_ids = Vec::new();
+    for name in ["Brownies", "Crumble", "Lasagna", "Tarte Tatin"] {
+        let recipe_id = create_recipe(pool, telegram_id, name).await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+        recipe_ids.push(recipe_id);
+    }
+    for recipe_id in [recipe_ids[0], recipe_ids[1], recipe_ids[3]] {
+        assert!(add_recipe_tag(pool, telegram_id, recipe_id, "dessert").await?);
+    }
+    assert!(add_recipe_tag(pool, telegram_id, recipe_ids[2], "dinner").await?);
+
+    // Tagging twice, or tagging someone else's recipe, changes nothing
+    assert!(!add_recipe_tag(pool, telegram_id, recipe_ids[0], "dessert").await?);
+    let other_user = TelegramId(64316);
+    assert!(!add_recipe_tag(pool, other_user, recipe_ids[2], "dessert").await?);
+
+    assert_eq!(
+        get_user_tags(pool, telegram_id).await?,
+        vec!["dessert", "dinner"]
+    );
+    assert_eq!(get_recipe_tags(pool, recipe_ids[2]).await?, vec!["dinner"]);
+
+    // Only the tagged recipes are listed, and the total counts them alone
+    let (names, total) =
+        get_user_recipes_by_tag_paginated(pool, telegram_id, "dessert", 2, 0).await?;
+    assert_eq!(total, 3);
+    assert_eq!(names, vec!["Brownies", "Crumble"]);
+    let (names, total) =
+        get_user_recipes_by_tag_paginated(pool, telegram_id, "dessert", 2, 2).await?;
+    assert_eq!(total, 3);
+    assert_eq!(names, vec!["Tarte Tatin"]);
+
+    assert!(remove_recipe_tag(pool, telegram_id, recipe_ids[1], "dessert").await?);
+    assert!(!remove_recipe_tag(pool, telegram_id, recipe_ids[1], "dessert").await?);
+    let (names, total) =
+        get_user_recipes_by_tag_paginated(pool, telegram_id, "dessert", 5, 0).await?;
+    assert_eq!(total, 2);
+    assert_eq!(names, vec!["Brownies", "Tarte Tatin"]);
+
+    // Deleting a recipe drops its tags
+    delete_recipe(pool, recipe_ids[2]).await?;
+    assert_eq!(get_user_tags(pool, telegram_id).await?, vec!["dessert"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipe_notes_save_overwrite_clear() -> Result<()> {
+    skip_if_no_db!(test_recipe_notes_save_overwrite_clear_impl)
+}
+
+async fn test_recipe_notes_save_overwrite_clear_impl(pool: &PgPool) -> Result<()> {
+    let telegram_id = TelegramId(64401);
+    get_or_create_user(pool, telegram_id, Some("en")).await?;
+    let recipe_id = create_recipe(pool, telegram_id, "Cookies").await?;
+    update_recipe_name(pool, recipe_id, "Cookies").await?;
+    let notes = |pool| async move {
+        Ok::<_, anyhow::Error>(
+            read_recipe_with_name(pool, recipe_id)
+                .await?
+                .and_then(|recipe| recipe.notes),
+        )
+    };
+
+    // New recipes have no notes
+    assert_eq!(notes(pool).await?, None);
+
+    // Save
+    assert!(
+        update_recipe_notes(pool, telegram_id, recipe_id, Some("Reduce sugar next time")).await?
+    );
+    assert_eq!(
+        notes(pool).await?.as_deref(),
+        Some("Reduce sugar next time")
+    );
+
+    // Overwrite, the notes being listed with the recipe too
+    assert!(
+        update_recipe_notes(
+            pool,
+            telegram_id,
+            recipe_id,
+            Some("Bake 2 min less\nUse brown sugar")
+        )
+        .await?
+    );
+    let recipes = get_recipes_by_name(pool, telegram_id, "Cookies").await?;
+    assert_eq!(
+        recipes[0].notes.as_deref(),
+        Some("Bake 2 min less\nUse brown sugar")
+    );
+
+    // Someone else cannot change the notes
+    assert!(!update_recipe_notes(pool, TelegramId(64402), recipe_id, None).await?);
+    assert!(notes(pool).await?.is_some());
+
+    // Clear
+    assert!(update_recipe_notes(pool, telegram_id, recipe_id, None).await?);
+    assert_eq!(notes(pool).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_weekly_digest_due_once_per_window() -> Result<()> {
+    skip_if_no_db!(test_weekly_digest_due_once_per_window_impl)
+}
+
+async fn test_weekly_digest_due_once_per_window_impl(pool: &PgPool) -> Result<()> {
+    use chrono::{TimeZone, Utc, Weekday};
+    use just_ingredients::scheduler::WeeklySchedule;
+
+    let telegram_id = TelegramId(64420);
+    let later_user = TelegramId(64421);
+    let friday_18 = WeeklySchedule::new(Weekday::Fri, 18)?;
+    set_user_digest_schedule(pool, telegram_id, Some(friday_18)).await?;
+    set_user_digest_schedule(
+        pool,
+        later_user,
+        Some(WeeklySchedule::new(Weekday::Fri, 19)?),
+    )
+    .await?;
+    assert_eq!(
+        get_user_digest_schedule(pool, telegram_id).await?,
+        Some(friday_18)
+    );
+
+    // October 2026: the 16th is a Friday
+    let due_ids = |recipients: Vec<DigestRecipient>| -> Vec<TelegramId> {
+        recipients
+            .into_iter()
+            .map(|recipient| recipient.telegram_id)
+            .filter(|id| *id == telegram_id || *id == later_user)
+            .collect()
+    };
+    let in_window = Utc.with_ymd_and_hms(2026, 10, 16, 18, 5, 0).unwrap();
+    assert_eq!(
+        due_ids(get_users_due_for_digest(pool, in_window).await?),
+        vec![telegram_id]
+    );
+
+    // Once sent, later sweeps in the same hour (e.g. after a restart) skip the user
+    mark_digest_sent(pool, telegram_id, in_window).await?;
+    let restarted = Utc.with_ymd_and_hms(2026, 10, 16, 18, 40, 0).unwrap();
+    assert!(due_ids(get_users_due_for_digest(pool, restarted).await?).is_empty());
+
+    // The next week is due again, unless the digest was turned off
+    let next_week = Utc.with_ymd_and_hms(2026, 10, 23, 18, 0, 0).unwrap();
+    assert_eq!(
+        due_ids(get_users_due_for_digest(pool, next_week).await?),
+        vec![telegram_id]
+    );
+    set_user_digest_schedule(pool, telegram_id, None).await?;
+    assert!(due_ids(get_users_due_for_digest(pool, next_week).await?).is_empty());
+    assert_eq!(get_user_digest_schedule(pool, telegram_id).await?, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cached_recipe_writes_evict_stale_entries() -> Result<()> {
+    skip_if_no_db!(test_cached_recipe_writes_evict_stale_entries_impl)
+}
+
+async fn test_cached_recipe_writes_evict_stale_entries_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::cache::{CacheManager, SharedCacheManager};
+    use std::sync::Arc;
+
+    let cache: SharedCacheManager = Arc::new(parking_lot::Mutex::new(CacheManager::new()));
+    let telegram_id = TelegramId(75320);
+    let user = get_or_create_user(pool, telegram_id, None).await?;
+    let recipe_id = create_recipe(pool, telegram_id, "content").await?;
+    update_recipe_name(pool, recipe_id, "Pancakes").await?;
+
+    // Warm both caches
+    let (names, _) = get_user_recipes_paginated_cached(pool, telegram_id, 10, 0, &cache).await?;
+    assert_eq!(names, vec!["Pancakes".to_string()]);
+    assert!(get_recipe_ingredients_cached(pool, recipe_id, &cache)
+        .await?
+        .is_empty());
+
+    // The next list query sees the new name, not the cached page
+    update_recipe_name_cached(pool, telegram_id, recipe_id, "Crêpes", &cache).await?;
+    let (names, _) = get_user_recipes_paginated_cached(pool, telegram_id, 10, 0, &cache).await?;
+    assert_eq!(names, vec!["Crêpes".to_string()]);
+
+    let ingredient_id = create_ingredient_cached(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "flour",
+        Some(2.0),
+        Some("cups"),
+        "2 cups flour",
+        &cache,
+    )
+    .await?;
+    let ingredients = get_recipe_ingredients_cached(pool, recipe_id, &cache).await?;
+    assert_eq!(ingredients.len(), 1);
+
+    delete_ingredient_cached(pool, recipe_id, ingredient_id, &cache).await?;
+    assert!(get_recipe_ingredients_cached(pool, recipe_id, &cache)
+        .await?
+        .is_empty());
+
+    delete_recipe_cached(pool, telegram_id, recipe_id, &cache).await?;
+    let (names, total) =
+        get_user_recipes_paginated_cached(pool, telegram_id, 10, 0, &cache).await?;
+    assert!(names.is_empty());
+    assert_eq!(total, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shared_backend_recipe_reads_see_other_instance_writes() -> Result<()> {
+    skip_if_no_db!(test_shared_backend_recipe_reads_see_other_instance_writes_impl)
+}
+
+/// Memory backend standing in for Redis shared by two bot processes
+struct SharedMemoryBackend(just_ingredients::cache_backend::MemoryCacheBackend);
+
+#[async_trait::async_trait]
+impl just_ingredients::cache_backend::CacheBackend for SharedMemoryBackend {
+    async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.0.get(key).await
+    }
+
+    async fn insert(&self, key: &str, value: Vec<u8>, ttl: std::time::Duration) {
+        self.0.insert(key, value, ttl).await
+    }
+
+    async fn invalidate(&self, key: &str) {
+        self.0.invalidate(key).await
+    }
+
+    fn name(&self) -> &'static str {
+        "shared-memory"
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+}
+
+async fn test_shared_backend_recipe_reads_see_other_instance_writes_impl(
+    pool: &PgPool,
+) -> Result<()> {
+    use just_ingredients::cache::{CacheManager, SharedCacheManager};
+    use just_ingredients::cache_backend::{CacheBackend, MemoryCacheBackend};
+    use std::sync::Arc;
+
+    let backend: Arc<dyn CacheBackend> = Arc::new(SharedMemoryBackend(MemoryCacheBackend::new()));
+    let first: SharedCacheManager = Arc::new(parking_lot::Mutex::new(CacheManager::with_backend(
+        Arc::clone(&backend),
+    )));
+    let second: SharedCacheManager =
+        Arc::new(parking_lot::Mutex::new(CacheManager::with_backend(backend)));
+    let telegram_id = TelegramId(75322);
+    let user = get_or_create_user(pool, telegram_id, None).await?;
+    let recipe_id = create_recipe(pool, telegram_id, "content").await?;
+    update_recipe_name(pool, recipe_id, "Pancakes").await?;
+
+    let (names, _) = get_user_recipes_paginated_cached(pool, telegram_id, 10, 0, &first).await?;
+    assert_eq!(names, vec!["Pancakes".to_string()]);
+    assert!(get_recipe_ingredients_cached(pool, recipe_id, &first)
+        .await?
+        .is_empty());
+
+    // Writes through the other instance's manager cannot evict this one's memory
+    update_recipe_name_cached(pool, telegram_id, recipe_id, "Crêpes", &second).await?;
+    create_ingredient_cached(
+        pool,
+        user.id,
+        Some(recipe_id),
+        "flour",
+        Some(2.0),
+        Some("cups"),
+        "2 cups flour",
+        &second,
+    )
+    .await?;
+
+    let (names, _) = get_user_recipes_paginated_cached(pool, telegram_id, 10, 0, &first).await?;
+    assert_eq!(names, vec!["Crêpes".to_string()]);
+    assert_eq!(
+        get_recipe_ingredients_cached(pool, recipe_id, &first)
+            .await?
+            .len(),
+        1
+    );
+    let by_recipe = get_ingredients_for_recipes_cached(pool, &[recipe_id], &first).await?;
+    assert_eq!(by_recipe.get(&recipe_id).map(Vec::len), Some(1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_second_recipe_selection_is_served_from_cache() -> Result<()> {
+    skip_if_no_db!(test_second_recipe_selection_is_served_from_cache_impl)
+}
+
+async fn test_second_recipe_selection_is_served_from_cache_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::cache::{CacheManager, SharedCacheManager};
+    use std::sync::Arc;
+
+    let cache: SharedCacheManager = Arc::new(parking_lot::Mutex::new(CacheManager::new()));
+    let telegram_id = TelegramId(75321);
+    let recipe_id = create_recipe(pool, telegram_id, "content").await?;
+    update_recipe_name(pool, recipe_id, "Pancakes").await?;
+
+    // Two taps on the same recipe: the first one fills the caches
+    for _ in 0..2 {
+        let recipes = get_recipes_by_name_cached(pool, telegram_id, "Pancakes", &cache).await?;
+        assert_eq!(recipes.len(), 1);
+        let recipe = read_recipe_with_name_cached(pool, recipe_id, &cache).await?;
+        assert_eq!(
+            recipe.and_then(|r| r.recipe_name).as_deref(),
+            Some("Pancakes")
+        );
+        get_recipe_ingredients_cached(pool, recipe_id, &cache).await?;
+    }
+
+    let stats = cache.lock().stats();
+    assert_eq!(
+        (
+            stats.recipes_by_name_cache.hits,
+            stats.recipes_by_name_cache.misses
+        ),
+        (1, 1)
+    );
+    assert_eq!(
+        (
+            stats.recipe_ingredients_cache.hits,
+            stats.recipe_ingredients_cache.misses
+        ),
+        (1, 1)
+    );
+    assert_eq!(stats.recipe_cache_entries, 1);
+
+    // Renaming evicts both the name lookup and the recipe
+    update_recipe_name_cached(pool, telegram_id, recipe_id, "Crêpes", &cache).await?;
+    assert!(
+        get_recipes_by_name_cached(pool, telegram_id, "Pancakes", &cache)
+            .await?
+            .is_empty()
+    );
+    let recipe = read_recipe_with_name_cached(pool, recipe_id, &cache).await?;
+    assert_eq!(
+        recipe.and_then(|r| r.recipe_name).as_deref(),
+        Some("Crêpes")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_ingredient_insert_rolls_back_recipe() -> Result<()> {
+    skip_if_no_db!(test_failed_ingredient_insert_rolls_back_recipe_impl)
+}
+
+async fn test_failed_ingredient_insert_rolls_back_recipe_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::bot::save_ingredients_to_database;
+    use just_ingredients::MeasurementMatch;
+
+    let ingredient = |name: &str| MeasurementMatch {
+        quantity: "2".to_string(),
+        measurement: Some("cups".to_string()),
+        ingredient_name: name.to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    };
+    // Postgres rejects NUL bytes in text, so the third insert fails
+    let ingredients = vec![
+        ingredient("flour"),
+        ingredient("sugar"),
+        ingredient("bad\0name"),
+        ingredient("milk"),
+    ];
+    let telegram_id = 86420;
+
+    let result = save_ingredients_to_database(
+        pool,
+        telegram_id,
+        "2 cups flour",
+        &ingredients,
+        "Pancakes",
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    assert!(result.is_err());
+
+    let recipes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM recipes WHERE telegram_id = $1")
+        .bind(telegram_id)
+        .fetch_one(pool)
+        .await?;
+    let ingredient_rows: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM ingredients i JOIN users u ON u.id = i.user_id \
+         WHERE u.telegram_id = $1",
+    )
+    .bind(telegram_id)
+    .fetch_one(pool)
+    .await?;
+    assert_eq!((recipes, ingredient_rows), (0, 0));
+
+    // The same save goes through once the bad line is fixed
+    let mut fixed = ingredients;
+    fixed[2] = ingredient("butter");
+    save_ingredients_to_database(
+        pool,
+        telegram_id,
+        "2 cups flour",
+        &fixed,
+        "Pancakes",
+        None,
+        None,
+        None,
+        None,
+    )
+    .await?;
+    let recipe_ids = get_recipes_by_name(pool, TelegramId(telegram_id), "Pancakes").await?;
+    assert_eq!(recipe_ids.len(), 1);
+    assert_eq!(
+        get_recipe_ingredients(pool, recipe_ids[0].id).await?.len(),
+        4
     );
 
-    // Check that required indexes exist
-    let indexes = vec![
-        ("recipes_content_tsv_idx", "recipes"),
-        ("ingredients_user_id_idx", "ingredients"),
-        ("ingredients_recipe_id_idx", "ingredients"),
-    ];
+    Ok(())
+}
 
-    for (index_name, table_name) in indexes {
-        let exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT 1 FROM pg_indexes WHERE tablename = $1 AND indexname = $2)",
+#[tokio::test]
+async fn test_create_ingredients_bulk() -> Result<()> {
+    skip_if_no_db!(test_create_ingredients_bulk_impl)
+}
+
+async fn test_create_ingredients_bulk_impl(pool: &PgPool) -> Result<()> {
+    let telegram_id = TelegramId(97531);
+    let user = get_or_create_user(pool, telegram_id, None).await?;
+    let recipe_id = create_recipe(pool, telegram_id, "content").await?;
+
+    let names: Vec<String> = (0..25).map(|i| format!("ingredient {i}")).collect();
+    let ingredients: Vec<NewIngredient> = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| NewIngredient {
+            name,
+            quantity: Some(Quantity::whole(i as i64)),
+            unit: (i % 2 == 0).then_some("g"),
+            raw_text: Some("content"),
+        })
+        .collect();
+
+    let mut conn = pool.acquire().await?;
+    assert!(create_ingredients_bulk(&mut conn, user.id, recipe_id, &[])
+        .await?
+        .is_empty());
+    let ids = create_ingredients_bulk(&mut conn, user.id, recipe_id, &ingredients).await?;
+    assert_eq!(ids.len(), 25);
+
+    // Ids come back in input order
+    for (id, expected) in ids.iter().zip(&ingredients) {
+        let stored = read_ingredient(pool, *id)
+            .await?
+            .expect("ingredient exists");
+        assert_eq!(stored.name, expected.name);
+        assert_eq!(stored.quantity, expected.quantity.map(Quantity::to_f64));
+        assert_eq!(stored.unit.as_deref(), expected.unit);
+        assert_eq!(stored.user_id, user.id);
+        assert_eq!(stored.recipe_id, Some(recipe_id));
+    }
+    assert_eq!(get_recipe_ingredients(pool, recipe_id).await?.len(), 25);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_update_recipe_ingredients_parses_fractions_and_keeps_unreadable_quantities(
+) -> Result<()> {
+    skip_if_no_db!(
+        test_update_recipe_ingredients_parses_fractions_and_keeps_unreadable_quantities_impl
+    )
+}
+
+async fn test_update_recipe_ingredients_parses_fractions_and_keeps_unreadable_quantities_impl(
+    pool: &PgPool,
+) -> Result<()> {
+    use just_ingredients::ingredient_editing::ingredients_to_measurement_matches;
+
+    let user = get_or_create_user(pool, TelegramId(86420), Some("en")).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "content").await?;
+    for (name, quantity) in [("sugar", 1.0), ("milk", 2.0), ("flour", 3.0), ("salt", 4.0)] {
+        create_ingredient(
+            pool,
+            user.id,
+            Some(recipe_id),
+            name,
+            Some(quantity),
+            None,
+            "",
         )
-        .bind(table_name)
-        .bind(index_name)
-        .fetch_one(pool)
         .await?;
-        assert!(
-            exists,
-            "Index {} should exist on table {}",
-            index_name, table_name
-        );
     }
 
+    let mut edited =
+        ingredients_to_measurement_matches(&get_recipe_ingredients(pool, recipe_id).await?);
+    edited[0].quantity = "1/2".to_string();
+    edited[1].quantity = "½".to_string();
+    edited[2].quantity = "1½".to_string();
+    edited[3].quantity = "a pinch".to_string();
+    let mut added = edited[0].clone();
+    added.ingredient_name = "butter".to_string();
+    added.quantity = "2.5".to_string();
+    edited.push(added);
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
+
+    let quantities: Vec<_> = get_recipe_ingredients(pool, recipe_id)
+        .await?
+        .into_iter()
+        .map(|i| (i.name, i.quantity))
+        .collect();
+    assert_eq!(
+        quantities,
+        vec![
+            ("sugar".to_string(), Some(0.5)),
+            ("milk".to_string(), Some(0.5)),
+            ("flour".to_string(), Some(1.5)),
+            // Unreadable quantities keep the saved value instead of becoming NULL
+            ("salt".to_string(), Some(4.0)),
+            ("butter".to_string(), Some(2.5)),
+        ]
+    );
+
     Ok(())
 }
 
-#[test]
-fn test_split_sql_statements_single_statement() {
-    let sql = "CREATE TABLE test (id INT);";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+#[tokio::test]
+async fn test_ingredients_keep_recipe_order_through_edits() -> Result<()> {
+    skip_if_no_db!(test_ingredients_keep_recipe_order_through_edits_impl)
+}
+
+async fn test_ingredients_keep_recipe_order_through_edits_impl(pool: &PgPool) -> Result<()> {
+    use just_ingredients::ingredient_editing::ingredients_to_measurement_matches;
+
+    let user = get_or_create_user(pool, TelegramId(86421), Some("en")).await?;
+    let recipe_id = create_recipe(pool, user.telegram_id, "content").await?;
+    let names = ["flour", "sugar", "eggs", "milk", "salt"];
+    let new_ingredients: Vec<NewIngredient> = names
+        .iter()
+        .map(|name| NewIngredient {
+            name,
+            quantity: Some(Quantity::whole(1)),
+            unit: None,
+            raw_text: None,
+        })
+        .collect();
+    // One INSERT gives every row the same created_at
+    let mut conn = pool.acquire().await?;
+    create_ingredients_bulk(&mut conn, user.id, recipe_id, &new_ingredients).await?;
+    drop(conn);
+
+    let names_in_order = |ingredients: Vec<Ingredient>| -> Vec<String> {
+        ingredients.into_iter().map(|i| i.name).collect()
+    };
     assert_eq!(
-        statements,
-        Ok(vec!["CREATE TABLE test (id INT);".to_string()])
+        names_in_order(get_recipe_ingredients(pool, recipe_id).await?),
+        names
     );
-}
 
-#[test]
-fn test_split_sql_statements_multiple_statements() {
-    let sql = "CREATE TABLE test (id INT); INSERT INTO test VALUES (1);";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    // Edit the 2nd ingredient
+    let mut edited =
+        ingredients_to_measurement_matches(&get_recipe_ingredients(pool, recipe_id).await?);
+    edited[1].ingredient_name = "brown sugar".to_string();
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
     assert_eq!(
-        statements,
-        Ok(vec![
-            "CREATE TABLE test (id INT);".to_string(),
-            "INSERT INTO test VALUES (1);".to_string()
-        ])
+        names_in_order(get_recipe_ingredients(pool, recipe_id).await?),
+        ["flour", "brown sugar", "eggs", "milk", "salt"]
     );
-}
 
-#[test]
-fn test_split_sql_statements_with_string_literals() {
-    let sql = "INSERT INTO test VALUES ('hello;world'); CREATE TABLE test2 (id INT);";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    // Add one at the end
+    let mut added = edited[0].clone();
+    added.ingredient_name = "butter".to_string();
+    edited.push(added);
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
     assert_eq!(
-        statements,
-        Ok(vec![
-            "INSERT INTO test VALUES ('hello;world');".to_string(),
-            "CREATE TABLE test2 (id INT);".to_string()
-        ])
+        names_in_order(get_recipe_ingredients(pool, recipe_id).await?),
+        ["flour", "brown sugar", "eggs", "milk", "salt", "butter"]
     );
-}
 
-#[test]
-fn test_split_sql_statements_with_double_quotes() {
-    let sql = r#"INSERT INTO test VALUES ("hello;world"); CREATE TABLE test2 (id INT);"#;
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+    // Delete the 2nd one
+    edited.remove(1);
+    update_recipe_ingredients(pool, recipe_id, &edited).await?;
     assert_eq!(
-        statements,
-        Ok(vec![
-            r#"INSERT INTO test VALUES ("hello;world");"#.to_string(),
-            "CREATE TABLE test2 (id INT);".to_string()
-        ])
+        names_in_order(get_recipe_ingredients(pool, recipe_id).await?),
+        ["flour", "eggs", "milk", "salt", "butter"]
+    );
+
+    // A single ingredient added later goes after the renumbered ones
+    create_ingredient(pool, user.id, Some(recipe_id), "vanilla", None, None, "").await?;
+    assert_eq!(
+        names_in_order(get_recipe_ingredients(pool, recipe_id).await?),
+        ["flour", "eggs", "milk", "salt", "butter", "vanilla"]
     );
+
+    Ok(())
 }
 
-#[test]
-fn test_split_sql_statements_with_comments() {
-    let sql = r#"
-        -- This is a comment
-        CREATE TABLE test (id INT);
-        -- Another comment
-        INSERT INTO test VALUES (1);
-    "#;
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+#[tokio::test]
+async fn test_user_language_preference() -> Result<()> {
+    skip_if_no_db!(test_user_language_preference_impl)
+}
+
+async fn test_user_language_preference_impl(pool: &PgPool) -> Result<()> {
+    let telegram_id = TelegramId(24680);
+
+    // The language copied from the Telegram client is not a choice
+    assert_eq!(get_user_language_preference(pool, telegram_id).await?, None);
+    get_or_create_user(pool, telegram_id, Some("en")).await?;
+    assert_eq!(get_user_language_preference(pool, telegram_id).await?, None);
+
+    set_user_language_preference(pool, telegram_id, "fr").await?;
     assert_eq!(
-        statements,
-        Ok(vec![
-            "-- This is a comment\n        CREATE TABLE test (id INT);".to_string(),
-            "-- Another comment\n        INSERT INTO test VALUES (1);".to_string()
-        ])
+        get_user_language_preference(pool, telegram_id).await?,
+        Some("fr".to_string())
+    );
+    let user = get_or_create_user(pool, telegram_id, Some("en")).await?;
+    assert_eq!(user.language_code, "fr");
+
+    // Choosing a language also works before the user exists
+    let new_user = TelegramId(24681);
+    set_user_language_preference(pool, new_user, "en").await?;
+    assert_eq!(
+        get_user_language_preference(pool, new_user).await?,
+        Some("en".to_string())
     );
+
+    Ok(())
 }
 
-#[test]
-fn test_split_sql_statements_mixed_strings_and_comments() {
-    let sql = r#"
-        -- Create table
-        CREATE TABLE test (
-            id INT,
-            name VARCHAR(100) DEFAULT 'test;value'
-        );
-        -- Insert data
-        INSERT INTO test VALUES (1, 'hello;world');
-    "#;
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
-    match statements {
-        Ok(stmts) => {
-            assert_eq!(stmts.len(), 2);
-            assert!(stmts[0].contains("CREATE TABLE"));
-            assert!(stmts[1].contains("INSERT INTO"));
-        }
-        Err(e) => panic!("Failed to split SQL: {}", e),
-    }
+#[tokio::test]
+async fn test_ingredient_names_are_normalized() -> Result<()> {
+    skip_if_no_db!(test_ingredient_names_are_normalized_impl)
 }
 
-#[test]
-fn test_split_sql_statements_empty_input() {
-    let sql = "";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
-    assert_eq!(statements, Ok(Vec::<String>::new()));
+async fn test_ingredient_names_are_normalized_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(955_201);
+    let user = get_or_create_user(pool, owner, None).await?;
+    let cake = create_recipe(pool, owner, "cake").await?;
+    let bread = create_recipe(pool, owner, "bread").await?;
+
+    let softened = create_ingredient(
+        pool,
+        user.id,
+        Some(cake),
+        "Butter, softened",
+        Some(100.0),
+        Some("g"),
+        "",
+    )
+    .await?;
+    let new_ingredients = [
+        NewIngredient {
+            name: "All-Purpose Flour",
+            quantity: Some(Quantity::whole(2)),
+            unit: Some("cups"),
+            raw_text: None,
+        },
+        NewIngredient {
+            name: "butter",
+            quantity: Some(Quantity::whole(50)),
+            unit: Some("g"),
+            raw_text: None,
+        },
+    ];
+    let mut conn = pool.acquire().await?;
+    create_ingredients_bulk(&mut conn, user.id, bread, &new_ingredients).await?;
+    drop(conn);
+
+    // The name as written is kept, the canonical one is stored next to it
+    let (name, normalized_name, notes): (String, Option<String>, Option<String>) =
+        sqlx::query_as("SELECT name, normalized_name, notes FROM ingredients WHERE id = $1")
+            .bind(softened)
+            .fetch_one(pool)
+            .await?;
+    assert_eq!(name, "Butter, softened");
+    assert_eq!(normalized_name.as_deref(), Some("butter"));
+    assert_eq!(notes.as_deref(), Some("softened"));
+
+    // Matching goes through canonical names
+    let others = find_other_recipes_with_ingredient(pool, owner, cake, "BUTTER").await?;
+    assert_eq!(others.iter().map(|r| r.id).collect::<Vec<_>>(), vec![bread]);
+    let matches = search_recipes_by_ingredient(pool, owner, "all purpose").await?;
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].recipe.id, bread);
+
+    // Renaming updates the canonical name too
+    update_ingredient(pool, softened, Some("Ghee"), None, None).await?;
+    let (normalized_name, notes): (Option<String>, Option<String>) =
+        sqlx::query_as("SELECT normalized_name, notes FROM ingredients WHERE id = $1")
+            .bind(softened)
+            .fetch_one(pool)
+            .await?;
+    assert_eq!(normalized_name.as_deref(), Some("ghee"));
+    assert_eq!(notes, None);
+
+    // Rows saved before normalization are filled in
+    sqlx::query("UPDATE ingredients SET normalized_name = NULL WHERE id = $1")
+        .bind(softened)
+        .execute(pool)
+        .await?;
+    assert_eq!(backfill_ingredient_normalized_names(pool).await?, 1);
+
+    Ok(())
 }
 
-#[test]
-fn test_split_sql_statements_only_whitespace() {
-    let sql = "   \n\t   ";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
-    assert_eq!(statements, Ok(Vec::<String>::new()));
+#[tokio::test]
+async fn test_users_batch_and_global_statistics() -> Result<()> {
+    skip_if_no_db!(test_users_batch_and_global_statistics_impl)
 }
 
-#[test]
-fn test_split_sql_statements_no_trailing_semicolon() {
-    let sql = "CREATE TABLE test (id INT)";
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
+async fn test_users_batch_and_global_statistics_impl(pool: &PgPool) -> Result<()> {
+    for telegram_id in [950_003, 950_001, 950_002] {
+        get_or_create_user(pool, TelegramId(telegram_id), Some("en")).await?;
+    }
+    create_recipe(pool, TelegramId(950_001), "flour 2 cups").await?;
+
+    // Batches follow telegram ids and resume after the last one seen
+    let first = get_users_batch(pool, Some(TelegramId(950_000)), 2).await?;
+    let ids: Vec<_> = first.iter().map(|user| user.telegram_id).collect();
+    assert_eq!(ids, vec![TelegramId(950_001), TelegramId(950_002)]);
+    let second = get_users_batch(pool, Some(TelegramId(950_002)), 2).await?;
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].telegram_id, TelegramId(950_003));
+    assert!(get_users_batch(pool, Some(TelegramId(950_003)), 2)
+        .await?
+        .is_empty());
+
+    let stats = get_global_statistics(pool).await?;
+    assert!(stats.users >= 3);
+    assert!(stats.recipes >= 1);
+    assert!(stats.recipes_this_week >= 1);
+    assert!(stats.active_users_this_week >= 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipe_share_copy_and_expiry() -> Result<()> {
+    skip_if_no_db!(test_recipe_share_copy_and_expiry_impl)
+}
+
+async fn test_recipe_share_copy_and_expiry_impl(pool: &PgPool) -> Result<()> {
+    use chrono::{Duration, Utc};
+
+    let sharer = TelegramId(955_001);
+    let friend = TelegramId(955_002);
+    let sharer_user = get_or_create_user(pool, sharer, Some("en")).await?;
+    let original = create_recipe(pool, sharer, "200 g flour\n2 eggs").await?;
+    update_recipe_name(pool, original, "Crêpes").await?;
+    let flour = create_ingredient(
+        pool,
+        sharer_user.id,
+        Some(original),
+        "flour",
+        Some(200.0),
+        Some("g"),
+        "200 g flour",
+    )
+    .await?;
+    create_ingredient(
+        pool,
+        sharer_user.id,
+        Some(original),
+        "eggs",
+        Some(2.0),
+        None,
+        "2 eggs",
+    )
+    .await?;
+    let now = Utc::now();
+
+    // Expired and unknown tokens copy nothing
+    create_recipe_share(
+        pool,
+        original,
+        sharer,
+        "expired0",
+        now - Duration::minutes(1),
+    )
+    .await?;
     assert_eq!(
-        statements,
-        Ok(vec!["CREATE TABLE test (id INT)".to_string()])
+        redeem_recipe_share(pool, "expired0", friend, now).await?,
+        ShareRedemption::Expired
+    );
+    assert_eq!(
+        redeem_recipe_share(pool, "missing0", friend, now).await?,
+        ShareRedemption::NotFound
     );
-}
 
-#[test]
-fn test_split_sql_statements_complex_migration_sql() {
-    let sql = r#"
-        -- Create users table
-        CREATE TABLE IF NOT EXISTS users (
-            id BIGSERIAL PRIMARY KEY,
-            telegram_id BIGINT UNIQUE NOT NULL,
-            language_code VARCHAR(10) DEFAULT 'en',
-            created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-            updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
-        );
+    // The sharer opening their own link does not use it up
+    create_recipe_share(pool, original, sharer, "valid000", now + Duration::days(7)).await?;
+    assert_eq!(
+        redeem_recipe_share(pool, "valid000", sharer, now).await?,
+        ShareRedemption::OwnRecipe
+    );
 
-        -- Create recipes table
-        CREATE TABLE IF NOT EXISTS recipes (
-            id BIGSERIAL PRIMARY KEY,
-            telegram_id BIGINT NOT NULL,
-            content TEXT NOT NULL,
-            recipe_name VARCHAR(255),
-            created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
-            content_tsv tsvector GENERATED ALWAYS AS (to_tsvector('english', content)) STORED
-        );
+    let ShareRedemption::Copied(copy) = redeem_recipe_share(pool, "valid000", friend, now).await?
+    else {
+        panic!("share should have been redeemed");
+    };
+    assert_eq!(
+        redeem_recipe_share(pool, "valid000", TelegramId(955_003), now).await?,
+        ShareRedemption::AlreadyUsed
+    );
 
-        -- Create indexes
-        CREATE INDEX IF NOT EXISTS recipes_content_tsv_idx ON recipes USING GIN (content_tsv);
-    "#;
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
-    match statements {
-        Ok(stmts) => {
-            assert_eq!(stmts.len(), 3);
-            assert!(stmts[0].contains("CREATE TABLE IF NOT EXISTS users"));
-            assert!(stmts[1].contains("CREATE TABLE IF NOT EXISTS recipes"));
-            assert!(stmts[2].contains("CREATE INDEX IF NOT EXISTS recipes_content_tsv_idx"));
-        }
-        Err(e) => panic!("Failed to split SQL: {}", e),
+    // The copy belongs to the friend, with fresh recipe and ingredient rows
+    assert_ne!(copy, original);
+    let copied = read_recipe_with_name(pool, copy).await?.unwrap();
+    assert_eq!(copied.telegram_id, friend);
+    assert_eq!(copied.recipe_name.as_deref(), Some("Crêpes"));
+    let friend_user = get_user_by_telegram_id(pool, friend).await?.unwrap();
+    let mut copied_ingredients = get_recipe_ingredients(pool, copy).await?;
+    copied_ingredients.sort_by_key(|i| i.id);
+    assert_eq!(
+        copied_ingredients
+            .iter()
+            .map(|i| (i.name.as_str(), i.quantity, i.unit.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![("flour", Some(200.0), Some("g")), ("eggs", Some(2.0), None)]
+    );
+    for ingredient in &copied_ingredients {
+        assert_ne!(ingredient.id, flour);
+        assert_eq!(ingredient.recipe_id, Some(copy));
+        assert_eq!(ingredient.user_id, friend_user.id);
     }
+
+    // Deleting the original leaves the copy intact
+    delete_recipe(pool, original).await?;
+    assert!(read_recipe_with_name(pool, copy).await?.is_some());
+    assert_eq!(get_recipe_ingredients(pool, copy).await?.len(), 2);
+
+    Ok(())
 }
 
-#[test]
-fn test_split_sql_statements_semicolon_in_string_and_comment() {
-    let sql = r#"
-        -- This comment has ; in it
-        INSERT INTO test VALUES ('value;with;semicolons');
-        -- Another ; comment
-        UPDATE test SET value = 'new;value';
-    "#;
-    let statements = just_ingredients::db::migrations::split_sql_statements(sql);
-    match statements {
-        Ok(stmts) => {
-            assert_eq!(stmts.len(), 2);
-            assert!(stmts[0].contains("INSERT INTO"));
-            assert!(stmts[1].contains("UPDATE"));
+#[tokio::test]
+async fn test_user_stats_aggregates() -> Result<()> {
+    skip_if_no_db!(test_user_stats_aggregates_impl)
+}
+
+async fn test_user_stats_aggregates_impl(pool: &PgPool) -> Result<()> {
+    let owner = TelegramId(957_001);
+    let other = TelegramId(957_002);
+    let user = get_or_create_user(pool, owner, Some("en")).await?;
+    let other_user = get_or_create_user(pool, other, Some("en")).await?;
+
+    // Seed recipes: this month, two months ago, and one in the trash
+    let fixtures: [(&str, i32, &[&str]); 4] = [
+        ("Pancakes", 0, &["flour", "eggs", "milk", "sugar"]),
+        ("Crêpes", 2, &["Flour (sifted)", "Eggs", "butter"]),
+        ("Omelette", 2, &["eggs"]),
+        (
+            "Trashed cake",
+            0,
+            &["eggs", "flour", "cocoa", "cream", "salt"],
+        ),
+    ];
+    let mut recipe_ids = Vec::new();
+    for (name, months_ago, ingredients) in fixtures {
+        let recipe_id = create_recipe(pool, owner, name).await?;
+        update_recipe_name(pool, recipe_id, name).await?;
+        sqlx::query(
+            "UPDATE recipes SET created_at = NOW() - make_interval(months => $2) WHERE id = $1",
+        )
+        .bind(recipe_id)
+        .bind(months_ago)
+        .execute(pool)
+        .await?;
+        for ingredient in ingredients {
+            create_ingredient(
+                pool,
+                user.id,
+                Some(recipe_id),
+                ingredient,
+                None,
+                None,
+                ingredient,
+            )
+            .await?;
         }
-        Err(e) => panic!("Failed to split SQL: {}", e),
+        recipe_ids.push(recipe_id);
     }
+    assert!(trash_recipe(pool, owner, recipe_ids[3]).await?);
+    let foreign = create_recipe(pool, other, "Soup").await?;
+    create_ingredient(
+        pool,
+        other_user.id,
+        Some(foreign),
+        "salt",
+        None,
+        None,
+        "salt",
+    )
+    .await?;
+
+    // Ingredients count once per recipe, by normalized name, ties in name order
+    assert_eq!(
+        get_top_ingredients_by_user(pool, owner, 3).await?,
+        vec![
+            ("eggs".to_string(), 3),
+            ("flour".to_string(), 2),
+            ("butter".to_string(), 1)
+        ]
+    );
+    assert_eq!(get_top_ingredients_by_user(pool, owner, 10).await?.len(), 5);
+
+    // Months run oldest first and include the empty ones
+    let counts: Vec<i64> = get_recipe_counts_by_month(pool, owner, 3)
+        .await?
+        .into_iter()
+        .map(|(_, count)| count)
+        .collect();
+    assert_eq!(counts, vec![2, 0, 1]);
+    let year = get_recipe_counts_by_month(pool, owner, 12).await?;
+    assert_eq!(year.len(), 12);
+    assert!(year.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    assert_eq!(year.iter().map(|(_, count)| count).sum::<i64>(), 3);
+
+    let longest = get_longest_recipe_by_user(pool, owner).await?.unwrap();
+    assert_eq!(longest.id, recipe_ids[0]);
+    assert_eq!(longest.recipe_name.as_deref(), Some("Pancakes"));
+    assert_eq!(longest.ingredient_count, 4);
+
+    // A user without recipes gets empty aggregates
+    let newcomer = TelegramId(957_003);
+    assert!(get_top_ingredients_by_user(pool, newcomer, 10)
+        .await?
+        .is_empty());
+    assert!(get_recipe_counts_by_month(pool, newcomer, 3)
+        .await?
+        .iter()
+        .all(|(_, count)| *count == 0));
+    assert!(get_longest_recipe_by_user(pool, newcomer).await?.is_none());
+
+    Ok(())
 }
@@ -31,6 +31,10 @@ async fn test_dialogue_state_serialization() -> Result<()> {
         start_pos: 0,
         end_pos: 6,
         requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
     }];
 
     let state = RecipeDialogueState::WaitingForRecipeName {
@@ -80,6 +84,10 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -89,6 +97,10 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -99,6 +111,7 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
         message_id: Some(123),
         extracted_text: "Test OCR text".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Verify state structure
@@ -110,6 +123,7 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
             message_id,
             extracted_text,
             recipe_name_from_caption: _,
+            last_deleted: _,
         } => {
             assert_eq!(recipe_name, "Test Recipe");
             assert_eq!(ingr.len(), 2);
@@ -164,6 +178,7 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
         extracted_text: "Test OCR text".to_string(),
         recipe_name_from_caption: None,
         message_id: None,
+        name_suggestions: vec!["Soup".to_string()],
     };
 
     match confirm_state {
@@ -173,7 +188,9 @@ async fn test_ingredient_review_dialogue_states() -> Result<()> {
             extracted_text,
             recipe_name_from_caption: _,
             message_id: _,
+            name_suggestions,
         } => {
+            assert_eq!(name_suggestions, vec!["Soup".to_string()]);
             assert_eq!(ingr.len(), 2);
             assert_eq!(language_code, Some("en".to_string()));
             assert_eq!(extracted_text, "Test OCR text");
@@ -228,6 +245,112 @@ fn test_ingredient_edit_validation() {
     assert_eq!(ingredient.ingredient_name.len(), 100); // Should be truncated to max length
 }
 
+/// Partial edits merge into the edited ingredient and show in the redrawn review keyboard
+#[test]
+fn test_partial_ingredient_edit_in_review_keyboard() {
+    use just_ingredients::bot::create_ingredient_review_keyboard;
+    use just_ingredients::localization::create_localization_manager;
+    use just_ingredients::validation::{parse_ingredient_edit, IngredientEdit};
+
+    let localization = create_localization_manager().expect("localization manager");
+    let ingredient = |quantity: &str, unit: Option<&str>, name: &str| MeasurementMatch {
+        quantity: quantity.to_string(),
+        measurement: unit.map(str::to_string),
+        ingredient_name: name.to_string(),
+        line_number: 0,
+        start_pos: 0,
+        end_pos: 0,
+        requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
+    };
+
+    for (input, expected) in [
+        ("3", "3 cups → flour"),
+        ("3 tbsp", "3 tbsp → flour"),
+        ("1 kg rye", "1 kg → rye"),
+    ] {
+        let mut ingredients = vec![
+            ingredient("2", None, "eggs"),
+            ingredient("2", Some("cups"), "flour"),
+        ];
+        match parse_ingredient_edit(&ingredients[1], input) {
+            Ok(IngredientEdit::Updated(updated)) => ingredients[1] = updated,
+            other => panic!("Expected an update for {input:?}, got {other:?}"),
+        }
+
+        let keyboard = create_ingredient_review_keyboard(&ingredients, Some("en"), &localization);
+        let rows = &keyboard.inline_keyboard;
+        assert_eq!(rows[0][0].text, "✏️ 2 → eggs");
+        assert_eq!(rows[1][0].text, format!("✏️ {expected}"), "{input:?}");
+    }
+
+    // "3 mugs" may be a unit or a new ingredient: the user is asked instead
+    let flour = ingredient("2", Some("cups"), "flour");
+    assert_eq!(
+        parse_ingredient_edit(&flour, "3 mugs"),
+        Ok(IngredientEdit::Ambiguous)
+    );
+}
+
+/// Showing the raw OCR text reads the review without changing it
+#[tokio::test]
+async fn test_raw_text_view_leaves_review_state_untouched() -> Result<()> {
+    use just_ingredients::bot::callbacks::review_callbacks::is_review_keyboard_callback;
+    use just_ingredients::bot::ocr_raw_text::{
+        is_hide_raw_text_callback, raw_text_for_review, SHOW_RAW_TEXT_CALLBACK,
+    };
+    use just_ingredients::dialogue::{DialogueStorage, RecipeDialogue};
+    use teloxide::types::ChatId;
+
+    let review = RecipeDialogueState::ReviewIngredients {
+        recipe_name: "Pancakes".to_string(),
+        ingredients: vec![MeasurementMatch {
+            quantity: "2".to_string(),
+            measurement: Some("cups".to_string()),
+            ingredient_name: "flour".to_string(),
+            line_number: 1,
+            start_pos: 0,
+            end_pos: 12,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        }],
+        language_code: Some("en".to_string()),
+        message_id: Some(42),
+        extracted_text: "PANCAKES\n2 cups flour\n1 e9g".to_string(),
+        recipe_name_from_caption: Some("Pancakes".to_string()),
+        last_deleted: None,
+    };
+    let dialogue = RecipeDialogue::new(DialogueStorage::in_memory(), ChatId(7));
+    dialogue.update(review.clone()).await?;
+
+    for _ in 0..2 {
+        assert_eq!(
+            raw_text_for_review(&dialogue).await?.as_deref(),
+            Some("PANCAKES\n2 cups flour\n1 e9g")
+        );
+    }
+    assert_eq!(
+        serde_json::to_value(dialogue.get().await?)?,
+        serde_json::to_value(Some(review))?
+    );
+
+    // Outside a review there is nothing to show
+    dialogue.update(RecipeDialogueState::Start).await?;
+    assert_eq!(raw_text_for_review(&dialogue).await?, None);
+
+    // Show belongs to the review keyboard; Hide works on any message, review or not
+    assert!(is_review_keyboard_callback(SHOW_RAW_TEXT_CALLBACK));
+    assert!(!is_review_keyboard_callback("raw_text_hide:41,42"));
+    assert!(is_hide_raw_text_callback("raw_text_hide:41,42"));
+    Ok(())
+}
+
 /// Test ingredient review command parsing
 #[test]
 fn test_ingredient_review_commands() {
@@ -326,6 +449,10 @@ fn test_dialogue_state_transitions_with_original_message_id() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -335,6 +462,10 @@ fn test_dialogue_state_transitions_with_original_message_id() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -378,34 +509,41 @@ fn test_dialogue_state_transitions_with_original_message_id() {
     let saved_ingredients = vec![
         just_ingredients::db::Ingredient {
             id: 1,
-            user_id: 100,
-            recipe_id: Some(200),
+            user_id: just_ingredients::db::UserId(100),
+            recipe_id: Some(just_ingredients::db::RecipeId(200)),
             name: "flour".to_string(),
             quantity: Some(2.0),
             unit: Some("cups".to_string()),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         },
         just_ingredients::db::Ingredient {
             id: 2,
-            user_id: 100,
-            recipe_id: Some(200),
+            user_id: just_ingredients::db::UserId(100),
+            recipe_id: Some(just_ingredients::db::RecipeId(200)),
             name: "eggs".to_string(),
             quantity: Some(3.0),
             unit: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         },
     ];
 
     let editing_saved_state = RecipeDialogueState::EditingSavedIngredient {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(
+            &saved_ingredients,
+        ),
         current_matches: ingredients.clone(),
         editing_index: 1,
         language_code: Some("en".to_string()),
         message_id: Some(789),
         original_message_id: Some(101112), // Original recipe display message ID
+        ingredients_version: 0,
     };
 
     // Verify the state structure includes original_message_id
@@ -417,6 +555,7 @@ fn test_dialogue_state_transitions_with_original_message_id() {
         language_code,
         message_id,
         original_message_id,
+        ..
     } = editing_saved_state
     {
         assert_eq!(recipe_id, 200);
@@ -448,6 +587,10 @@ fn test_review_to_editing_ingredient_transition() {
         start_pos: 0,
         end_pos: 6,
         requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
     }];
 
     // Simulate transition to editing (what happens when user clicks edit button)
@@ -491,13 +634,15 @@ fn test_saved_ingredients_to_editing_transition() {
     // Start with EditingSavedIngredients state
     let saved_ingredients = vec![just_ingredients::db::Ingredient {
         id: 1,
-        user_id: 100,
-        recipe_id: Some(200),
+        user_id: just_ingredients::db::UserId(100),
+        recipe_id: Some(just_ingredients::db::RecipeId(200)),
         name: "flour".to_string(),
         quantity: Some(2.0),
         unit: Some("cups".to_string()),
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
+        unit_dimension: None,
+        unit_system: None,
     }];
 
     let current_matches = vec![MeasurementMatch {
@@ -508,17 +653,24 @@ fn test_saved_ingredients_to_editing_transition() {
         start_pos: 0,
         end_pos: 6,
         requires_quantity_confirmation: false,
+        unit_dimension: None,
+        unit_system: None,
+        confidence: None,
+        quantity_max: None,
     }];
 
     // Simulate transition to editing single ingredient (what happens when user clicks edit button)
     let editing_single_state = RecipeDialogueState::EditingSavedIngredient {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(
+            &saved_ingredients,
+        ),
         current_matches: current_matches.clone(),
         editing_index: 0,
         language_code: Some("en".to_string()),
         message_id: Some(2001),          // New editing prompt message ID
         original_message_id: Some(2000), // Should track the original message ID
+        ingredients_version: 0,
     };
 
     // Verify the transition preserved the original message ID
@@ -557,6 +709,10 @@ async fn test_awaiting_quantity_correction_state() -> Result<()> {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: true,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -566,6 +722,10 @@ async fn test_awaiting_quantity_correction_state() -> Result<()> {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -606,3 +766,801 @@ async fn test_awaiting_quantity_correction_state() -> Result<()> {
     println!("✅ AwaitingQuantityCorrection state test passed");
     Ok(())
 }
+
+/// Test that a huge OCR text produces a bounded dialogue state
+#[test]
+fn test_huge_ocr_text_produces_bounded_state() {
+    use just_ingredients::dialogue::{
+        bound_extracted_text, cap_review_ingredients, serialized_state_size, RecipeDialogueState,
+        MAX_REVIEW_INGREDIENTS, MAX_SERIALIZED_STATE_BYTES, MAX_STORED_EXTRACTED_TEXT_BYTES,
+    };
+    use just_ingredients::text_processing::MeasurementMatch;
+
+    // 5,000 lines of noise with ingredients in the middle of the page
+    let mut lines: Vec<String> = (0..5000)
+        .map(|i| {
+            format!(
+                "Lorem ipsum dolor sit amet, line {} of a very dense page",
+                i
+            )
+        })
+        .collect();
+    let ingredients: Vec<MeasurementMatch> = (0..200)
+        .map(|i| {
+            let line_number = 2000 + i;
+            lines[line_number] = format!("{} g ingredient {}", i + 1, i);
+            MeasurementMatch {
+                quantity: (i + 1).to_string(),
+                measurement: Some("g".to_string()),
+                ingredient_name: format!("ingredient {}", i),
+                line_number,
+                start_pos: 0,
+                end_pos: 10,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            }
+        })
+        .collect();
+    let huge_text = lines.join("\n");
+    assert!(huge_text.len() > 200 * 1024);
+
+    let ingredients = cap_review_ingredients(ingredients);
+    assert_eq!(ingredients.len(), MAX_REVIEW_INGREDIENTS);
+
+    let bounded_text = bound_extracted_text(&huge_text, &ingredients, 3);
+    assert!(bounded_text.len() <= MAX_STORED_EXTRACTED_TEXT_BYTES);
+    assert!(bounded_text.starts_with("Lorem ipsum dolor sit amet, line 1997"));
+    assert!(bounded_text.contains("1 g ingredient 0"));
+    assert!(bounded_text.contains("50 g ingredient 49"));
+    assert!(!bounded_text.contains("61 g ingredient 60"));
+
+    let state = RecipeDialogueState::ReviewIngredients {
+        recipe_name: "Recipe".to_string(),
+        ingredients,
+        language_code: Some("en".to_string()),
+        message_id: Some(1),
+        extracted_text: bounded_text,
+        recipe_name_from_caption: None,
+        last_deleted: None,
+    };
+    assert!(serialized_state_size(&state) <= MAX_SERIALIZED_STATE_BYTES);
+}
+
+/// Test that the stored OCR text is cut on a character boundary
+#[test]
+fn test_bound_extracted_text_without_ingredients() {
+    use just_ingredients::dialogue::{bound_extracted_text, MAX_STORED_EXTRACTED_TEXT_BYTES};
+
+    let text = "é".repeat(MAX_STORED_EXTRACTED_TEXT_BYTES);
+    let bounded = bound_extracted_text(&text, &[], 3);
+    assert!(bounded.len() <= MAX_STORED_EXTRACTED_TEXT_BYTES);
+    assert!(bounded.chars().all(|c| c == 'é'));
+
+    assert_eq!(bound_extracted_text("short text", &[], 3), "short text");
+}
+
+/// Test that an oversized state is reported instead of failing the update
+#[test]
+fn test_oversized_state_size_is_recorded_without_panicking() {
+    use just_ingredients::dialogue::{
+        record_state_size, serialized_state_size, RecipeDialogueState, MAX_SERIALIZED_STATE_BYTES,
+        STATE_SIZE_SAMPLE_INTERVAL,
+    };
+
+    let state = RecipeDialogueState::WaitingForRecipeName {
+        extracted_text: "x".repeat(2 * MAX_SERIALIZED_STATE_BYTES),
+        ingredients: Vec::new(),
+        language_code: None,
+    };
+    assert!(serialized_state_size(&state) > MAX_SERIALIZED_STATE_BYTES);
+
+    // Enough updates to hit at least one sample
+    for _ in 0..STATE_SIZE_SAMPLE_INTERVAL {
+        record_state_size(&state);
+    }
+}
+
+/// Test that the full OCR text is kept for saving while the state holds a bounded copy
+#[test]
+fn test_full_review_text_kept_outside_state() {
+    use just_ingredients::dialogue::{
+        full_review_text, review_state_text, MAX_STORED_EXTRACTED_TEXT_BYTES,
+    };
+    use teloxide::types::ChatId;
+
+    let chat_id = ChatId(723_001);
+    let full_text = "Preface\n".repeat(MAX_STORED_EXTRACTED_TEXT_BYTES) + "2 cups flour";
+    let state_text = review_state_text(chat_id, &full_text, &[]);
+    assert!(state_text.len() <= MAX_STORED_EXTRACTED_TEXT_BYTES);
+
+    assert_eq!(full_review_text(chat_id, &state_text), full_text);
+
+    // A state from another extraction keeps its own text
+    assert_eq!(full_review_text(chat_id, "other text"), "other text");
+    assert_eq!(full_review_text(ChatId(723_002), &state_text), state_text);
+}
+
+/// Test that pending ingredients survive the "view existing" detour of a name conflict
+#[test]
+fn test_name_conflict_state_preserves_pending_ingredients() {
+    let ingredients = vec![
+        MeasurementMatch {
+            quantity: "3".to_string(),
+            measurement: None,
+            ingredient_name: "bananas".to_string(),
+            line_number: 0,
+            start_pos: 0,
+            end_pos: 9,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        },
+        MeasurementMatch {
+            quantity: "250".to_string(),
+            measurement: Some("g".to_string()),
+            ingredient_name: "flour".to_string(),
+            line_number: 1,
+            start_pos: 0,
+            end_pos: 11,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        },
+    ];
+
+    let state = RecipeDialogueState::ResolvingRecipeNameConflict {
+        recipe_name: "Banana Bread".to_string(),
+        ingredients: ingredients.clone(),
+        language_code: Some("en".to_string()),
+        extracted_text: "3 bananas\n250 g flour".to_string(),
+        newest_recipe_id: 42,
+        existing_count: 2,
+    };
+    assert_eq!(state.name(), "resolving_recipe_name_conflict");
+
+    // Dialogue storage round-trips the state between callbacks
+    let stored = serde_json::to_string(&state).expect("state should serialize");
+    let restored: RecipeDialogueState =
+        serde_json::from_str(&stored).expect("state should deserialize");
+
+    match restored {
+        RecipeDialogueState::ResolvingRecipeNameConflict {
+            recipe_name,
+            ingredients: restored_ingredients,
+            newest_recipe_id,
+            existing_count,
+            ..
+        } => {
+            assert_eq!(recipe_name, "Banana Bread");
+            assert_eq!(restored_ingredients, ingredients);
+            assert_eq!(newest_recipe_id, 42);
+            assert_eq!(existing_count, 2);
+        }
+        other => panic!("Unexpected dialogue state: {}", other.name()),
+    }
+}
+
+/// Test that a pending report keeps the interrupted review state to return to
+#[test]
+fn test_report_comment_state_restores_review() {
+    use just_ingredients::extraction_reports::ExtractionContext;
+
+    let review = RecipeDialogueState::ReviewIngredients {
+        recipe_name: "Recipe".to_string(),
+        ingredients: vec![],
+        language_code: Some("fr".to_string()),
+        message_id: Some(12),
+        extracted_text: "2 tasses de farine".to_string(),
+        recipe_name_from_caption: None,
+        last_deleted: None,
+    };
+    let context = ExtractionContext::new("2 tasses de farine", 0, None, None);
+    let state = RecipeDialogueState::AwaitingReportComment {
+        context: context.clone(),
+        language_code: Some("fr".to_string()),
+        prompt_message_id: Some(13),
+        resume_state: Box::new(review),
+    };
+    assert_eq!(state.name(), "awaiting_report_comment");
+
+    let stored = serde_json::to_string(&state).expect("state should serialize");
+    let restored: RecipeDialogueState =
+        serde_json::from_str(&stored).expect("state should deserialize");
+
+    match restored {
+        RecipeDialogueState::AwaitingReportComment {
+            context: restored_context,
+            resume_state,
+            ..
+        } => {
+            assert_eq!(restored_context, context);
+            assert_eq!(resume_state.name(), "review_ingredients");
+        }
+        other => panic!("Unexpected dialogue state: {}", other.name()),
+    }
+}
+
+/// Test ingredients stored in dialogue state before OCR confidence was tracked still load
+#[test]
+fn test_measurement_match_without_confidence_deserializes() {
+    let stored = r#"{
+        "quantity": "250",
+        "measurement": "g",
+        "ingredient_name": "farine",
+        "line_number": 0,
+        "start_pos": 0,
+        "end_pos": 5,
+        "requires_quantity_confirmation": false
+    }"#;
+
+    let ingredient: MeasurementMatch =
+        serde_json::from_str(stored).expect("old ingredient should deserialize");
+
+    assert_eq!(ingredient.ingredient_name, "farine");
+    assert_eq!(ingredient.confidence, None);
+}
+
+/// Review state used by the pending rename tests
+fn pending_review(recipe_name_from_caption: Option<&str>) -> RecipeDialogueState {
+    RecipeDialogueState::ReviewIngredients {
+        recipe_name: recipe_name_from_caption.unwrap_or("Recipe").to_string(),
+        ingredients: vec![
+            MeasurementMatch {
+                quantity: "2".to_string(),
+                measurement: Some("cups".to_string()),
+                ingredient_name: "flour".to_string(),
+                line_number: 0,
+                start_pos: 0,
+                end_pos: 12,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            },
+            MeasurementMatch {
+                quantity: "3".to_string(),
+                measurement: None,
+                ingredient_name: "eggs".to_string(),
+                line_number: 1,
+                start_pos: 0,
+                end_pos: 6,
+                requires_quantity_confirmation: false,
+                unit_dimension: None,
+                unit_system: None,
+                confidence: None,
+                quantity_max: None,
+            },
+        ],
+        language_code: Some("en".to_string()),
+        message_id: Some(7),
+        extracted_text: "2 cups flour\n3 eggs".to_string(),
+        recipe_name_from_caption: recipe_name_from_caption.map(str::to_string),
+        last_deleted: None,
+    }
+}
+
+/// Test that renaming the recipe under review only changes its name
+#[test]
+fn test_pending_rename_preserves_ingredients() {
+    for caption in [Some("Choclate Cake"), None] {
+        let RecipeDialogueState::ReviewIngredients {
+            ingredients: original_ingredients,
+            ..
+        } = pending_review(caption)
+        else {
+            unreachable!()
+        };
+
+        let renaming = pending_review(caption)
+            .into_pending_rename()
+            .expect("review should enter the rename prompt");
+        assert_eq!(renaming.name(), "renaming_pending_recipe");
+
+        // Dialogue storage round-trips the state until the name is typed
+        let stored = serde_json::to_string(&renaming).expect("state should serialize");
+        let restored: RecipeDialogueState =
+            serde_json::from_str(&stored).expect("state should deserialize");
+
+        match restored.into_renamed_review(Some("Chocolate Cake")) {
+            Some(RecipeDialogueState::ReviewIngredients {
+                recipe_name,
+                ingredients,
+                message_id,
+                extracted_text,
+                recipe_name_from_caption,
+                ..
+            }) => {
+                assert_eq!(recipe_name, "Chocolate Cake");
+                // Confirming saves under the caption name, so the new name goes there too
+                assert_eq!(recipe_name_from_caption.as_deref(), Some("Chocolate Cake"));
+                assert_eq!(ingredients, original_ingredients);
+                assert_eq!(message_id, Some(7));
+                assert_eq!(extracted_text, "2 cups flour\n3 eggs");
+            }
+            other => panic!("Unexpected dialogue state: {:?}", other.map(|s| s.name())),
+        }
+    }
+}
+
+/// Test that cancelling a pending rename returns to the unchanged review
+#[test]
+fn test_cancelled_pending_rename_keeps_name() {
+    for caption in [Some("Banana Bread"), None] {
+        let review = pending_review(caption)
+            .into_pending_rename()
+            .and_then(|state| state.into_renamed_review(None))
+            .expect("rename prompt should return to review");
+
+        match review {
+            RecipeDialogueState::ReviewIngredients {
+                recipe_name,
+                ingredients,
+                recipe_name_from_caption,
+                ..
+            } => {
+                assert_eq!(recipe_name, caption.unwrap_or("Recipe"));
+                assert_eq!(recipe_name_from_caption.as_deref(), caption);
+                assert_eq!(ingredients.len(), 2);
+            }
+            other => panic!("Unexpected dialogue state: {}", other.name()),
+        }
+    }
+}
+
+/// Test that rename transitions only apply to the matching states
+#[test]
+fn test_pending_rename_requires_review_state() {
+    assert!(RecipeDialogueState::Start.into_pending_rename().is_none());
+    assert!(pending_review(None)
+        .into_renamed_review(Some("Soup"))
+        .is_none());
+}
+
+/// Test that the undo slot of a review survives storage and old states still load
+#[test]
+fn test_review_state_keeps_last_deleted_ingredient() {
+    let RecipeDialogueState::ReviewIngredients {
+        recipe_name,
+        mut ingredients,
+        language_code,
+        message_id,
+        extracted_text,
+        recipe_name_from_caption,
+        ..
+    } = pending_review(Some("Pancakes"))
+    else {
+        unreachable!()
+    };
+    let original = ingredients.clone();
+    let deleted = (0, ingredients.remove(0));
+
+    let state = RecipeDialogueState::ReviewIngredients {
+        recipe_name,
+        ingredients,
+        language_code,
+        message_id,
+        extracted_text,
+        recipe_name_from_caption,
+        last_deleted: Some(deleted),
+    };
+    let stored = serde_json::to_string(&state).expect("state should serialize");
+    let restored: RecipeDialogueState =
+        serde_json::from_str(&stored).expect("state should deserialize");
+
+    match restored {
+        RecipeDialogueState::ReviewIngredients {
+            ingredients,
+            last_deleted: Some((index, ingredient)),
+            ..
+        } => {
+            assert_eq!(ingredients.len(), 1);
+            assert_eq!(index, 0);
+            assert_eq!(ingredient, original[0]);
+        }
+        other => panic!("Unexpected dialogue state: {}", other.name()),
+    }
+
+    // States stored before undo existed have nothing to restore
+    let mut old = serde_json::to_value(pending_review(None)).unwrap();
+    old["ReviewIngredients"]
+        .as_object_mut()
+        .unwrap()
+        .remove("last_deleted");
+    let restored: RecipeDialogueState = serde_json::from_value(old).expect("old state loads");
+    assert!(matches!(
+        restored,
+        RecipeDialogueState::ReviewIngredients {
+            last_deleted: None,
+            ..
+        }
+    ));
+}
+
+/// Saved recipe edits keep the version they started from, old states load as version 0
+#[test]
+fn test_saved_ingredient_editing_keeps_ingredients_version() {
+    let mut versioned = 0;
+    for state in every_dialogue_state() {
+        let stored = serde_json::to_value(&state).unwrap();
+        let Some((variant, fields)) = stored.as_object().and_then(|object| object.iter().next())
+        else {
+            continue;
+        };
+        if fields.get("ingredients_version").is_none() {
+            continue;
+        }
+        versioned += 1;
+        assert_eq!(fields["ingredients_version"], 1, "{variant}");
+
+        // Stored before versions existed
+        let mut old = stored.clone();
+        old[variant]
+            .as_object_mut()
+            .unwrap()
+            .remove("ingredients_version");
+        let restored: RecipeDialogueState = serde_json::from_value(old).expect("old state loads");
+        let restored = serde_json::to_value(&restored).unwrap();
+        assert_eq!(restored[variant]["ingredients_version"], 0, "{variant}");
+    }
+    assert_eq!(versioned, 3);
+}
+
+/// One state of every dialogue variant, with message ids 1 (message) and 2 (original or prompt)
+fn every_dialogue_state() -> Vec<RecipeDialogueState> {
+    use just_ingredients::extraction_reports::ExtractionContext;
+
+    let ingredients = match pending_review(None) {
+        RecipeDialogueState::ReviewIngredients { ingredients, .. } => ingredients,
+        _ => unreachable!(),
+    };
+    let language_code = Some("en".to_string());
+    let review = RecipeDialogueState::ReviewIngredients {
+        recipe_name: "Recipe".to_string(),
+        ingredients: ingredients.clone(),
+        language_code: language_code.clone(),
+        message_id: Some(1),
+        extracted_text: String::new(),
+        recipe_name_from_caption: None,
+        last_deleted: None,
+    };
+
+    vec![
+        RecipeDialogueState::Start,
+        RecipeDialogueState::WaitingForRecipeName {
+            extracted_text: String::new(),
+            ingredients: ingredients.clone(),
+            language_code: language_code.clone(),
+        },
+        review.clone(),
+        RecipeDialogueState::EditingIngredient {
+            recipe_name: "Recipe".to_string(),
+            ingredients: ingredients.clone(),
+            editing_index: 0,
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            original_message_id: Some(2),
+            extracted_text: String::new(),
+            recipe_name_from_caption: None,
+        },
+        RecipeDialogueState::WaitingForRecipeNameAfterConfirm {
+            ingredients: ingredients.clone(),
+            language_code: language_code.clone(),
+            extracted_text: String::new(),
+            recipe_name_from_caption: None,
+            message_id: Some(1),
+            name_suggestions: Vec::new(),
+        },
+        RecipeDialogueState::RenamingRecipe {
+            recipe_id: 1,
+            current_name: "Recipe".to_string(),
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::EditingSavedIngredients {
+            recipe_id: 1,
+            original_ingredients: vec![],
+            current_matches: ingredients.clone(),
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            last_deleted: None,
+            ingredients_version: 1,
+        },
+        RecipeDialogueState::EditingSavedIngredient {
+            recipe_id: 1,
+            original_ingredients: vec![],
+            current_matches: ingredients.clone(),
+            editing_index: 0,
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            original_message_id: Some(2),
+            ingredients_version: 1,
+        },
+        RecipeDialogueState::AddingIngredientToSavedRecipe {
+            recipe_id: 1,
+            original_ingredients: vec![],
+            current_matches: ingredients.clone(),
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            ingredients_version: 1,
+        },
+        RecipeDialogueState::AwaitingQuantityCorrection {
+            recipe_name: "Recipe".to_string(),
+            ingredients: ingredients.clone(),
+            ingredient_index: 0,
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            extracted_text: String::new(),
+            recipe_name_from_caption: None,
+        },
+        RecipeDialogueState::ConfirmingRenamePropagation {
+            recipe_id: 1,
+            old_name: "Old".to_string(),
+            new_name: "New".to_string(),
+            remaining_renames: vec![],
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::ResolvingRecipeNameConflict {
+            recipe_name: "Recipe".to_string(),
+            ingredients: ingredients.clone(),
+            language_code: language_code.clone(),
+            extracted_text: String::new(),
+            newest_recipe_id: 1,
+            existing_count: 1,
+        },
+        RecipeDialogueState::AwaitingReportComment {
+            context: ExtractionContext::new("2 cups flour", 0, None, None),
+            language_code: language_code.clone(),
+            prompt_message_id: Some(2),
+            resume_state: Box::new(review),
+        },
+        RecipeDialogueState::SelectingRecipesToDelete {
+            selected_ids: vec![1],
+            page: 0,
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::AwaitingSearchQuery {
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::ScalingRecipe {
+            recipe_id: 1,
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::ConfirmingAccountWipe {
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::RenamingPendingRecipe {
+            recipe_name: "Recipe".to_string(),
+            ingredients,
+            language_code: language_code.clone(),
+            message_id: Some(1),
+            extracted_text: String::new(),
+            recipe_name_from_caption: None,
+        },
+        RecipeDialogueState::TaggingRecipe {
+            recipe_id: 1,
+            language_code: language_code.clone(),
+        },
+        RecipeDialogueState::EditingRecipeNotes {
+            recipe_id: 1,
+            language_code,
+        },
+    ]
+}
+
+/// Test that /cancel, /start and /help get the user out of every dialogue state
+#[test]
+fn test_escape_commands_leave_every_dialogue_state() {
+    use just_ingredients::bot::dialogue_manager::is_dialogue_escape_command;
+    use std::collections::HashSet;
+
+    let states = every_dialogue_state();
+    let names: HashSet<&str> = states.iter().map(RecipeDialogueState::name).collect();
+    assert_eq!(
+        names.len(),
+        states.len(),
+        "every variant should appear once"
+    );
+    assert_eq!(
+        states.len(),
+        20,
+        "add new dialogue states to every_dialogue_state"
+    );
+
+    for state in &states {
+        // Every state but Start is left by the escape commands
+        assert_eq!(state.is_active(), state.name() != "start");
+
+        // Keyboards of the dialogue's messages are removed on the way out
+        let ids = state.keyboard_message_ids();
+        let expected: &[i32] = match state.name() {
+            "review_ingredients"
+            | "waiting_for_recipe_name_after_confirm"
+            | "editing_saved_ingredients"
+            | "adding_ingredient_to_saved_recipe"
+            | "awaiting_quantity_correction"
+            | "renaming_pending_recipe" => &[1],
+            "editing_ingredient" | "editing_saved_ingredient" => &[1, 2],
+            "awaiting_report_comment" => &[2, 1],
+            _ => &[],
+        };
+        assert_eq!(ids, expected, "{}", state.name());
+    }
+
+    for command in ["/cancel", "/start", "/start share_abc", "/help"] {
+        assert!(is_dialogue_escape_command(command), "{command}");
+    }
+    for input in ["cancel", "/recipes", "/cancelled", "Cancel cake", "/helpme"] {
+        assert!(!is_dialogue_escape_command(input), "{input}");
+    }
+}
+
+/// Test how many measurements make a text message an ingredient list
+#[test]
+fn test_ingredient_list_threshold_boundary() {
+    use just_ingredients::bot::process_ingredients_and_extract_matches;
+    use just_ingredients::bot::text_ingredients::{is_ingredient_list, MIN_PASTED_INGREDIENTS};
+
+    // Pasted text needs the threshold, a forwarded message a single measurement
+    assert!(!is_ingredient_list(0, false, MIN_PASTED_INGREDIENTS));
+    assert!(!is_ingredient_list(
+        MIN_PASTED_INGREDIENTS - 1,
+        false,
+        MIN_PASTED_INGREDIENTS
+    ));
+    assert!(is_ingredient_list(
+        MIN_PASTED_INGREDIENTS,
+        false,
+        MIN_PASTED_INGREDIENTS
+    ));
+    assert!(!is_ingredient_list(0, true, MIN_PASTED_INGREDIENTS));
+    assert!(is_ingredient_list(1, true, MIN_PASTED_INGREDIENTS));
+
+    // A configured threshold moves the boundary, but never below one measurement
+    assert!(!is_ingredient_list(3, false, 4));
+    assert!(is_ingredient_list(4, false, 4));
+    assert!(!is_ingredient_list(0, false, 0));
+    assert!(is_ingredient_list(1, false, 0));
+
+    let one = process_ingredients_and_extract_matches("I need 2 cups of flour", Some("en"));
+    let two = process_ingredients_and_extract_matches("2 cups flour\n3 eggs", Some("en"));
+    assert!(!is_ingredient_list(
+        one.len(),
+        false,
+        MIN_PASTED_INGREDIENTS
+    ));
+    assert!(is_ingredient_list(one.len(), true, MIN_PASTED_INGREDIENTS));
+    assert!(is_ingredient_list(two.len(), false, MIN_PASTED_INGREDIENTS));
+}
+
+/// Test that pasted ingredient lists never replace a dialogue waiting for input
+#[test]
+fn test_ingredient_text_only_starts_outside_dialogues() {
+    use just_ingredients::bot::text_ingredients::accepts_ingredient_text;
+
+    assert!(accepts_ingredient_text(None));
+    for state in every_dialogue_state() {
+        assert_eq!(
+            accepts_ingredient_text(Some(&state)),
+            state.name() == "start",
+            "{}",
+            state.name()
+        );
+    }
+}
+
+/// Test that the dialogue storage counts active dialogues per state as they change
+#[tokio::test]
+async fn test_tracked_storage_counts_active_dialogues() -> Result<()> {
+    use just_ingredients::dialogue::{DialogueStorage, RecipeDialogue};
+    use teloxide::types::ChatId;
+
+    let storage = DialogueStorage::in_memory();
+    let first = RecipeDialogue::new(storage.clone(), ChatId(1));
+    let second = RecipeDialogue::new(storage.clone(), ChatId(2));
+    let search = RecipeDialogueState::AwaitingSearchQuery {
+        language_code: None,
+    };
+    let tagging = RecipeDialogueState::TaggingRecipe {
+        recipe_id: 3,
+        language_code: None,
+    };
+
+    first.update(search.clone()).await?;
+    second.update(search.clone()).await?;
+    assert_eq!(
+        storage.active_dialogues().get("awaiting_search_query"),
+        Some(&2)
+    );
+
+    // Moving to another state moves the count, updating to the same state keeps it
+    second.update(tagging).await?;
+    first.update(search).await?;
+    let active = storage.active_dialogues();
+    assert_eq!(active.get("awaiting_search_query"), Some(&1));
+    assert_eq!(active.get("tagging_recipe"), Some(&1));
+
+    // Back at Start or exited is no longer active
+    first.update(RecipeDialogueState::Start).await?;
+    second.exit().await?;
+    assert!(storage.active_dialogues().is_empty());
+    Ok(())
+}
+
+/// Test that the sweep exits only the dialogues idle for longer than the limit
+#[tokio::test]
+async fn test_sweep_exits_idle_dialogues() -> Result<()> {
+    use just_ingredients::dialogue::{sweep_idle_dialogues, DialogueStorage, RecipeDialogue};
+    use std::time::{Duration, Instant};
+    use teloxide::types::ChatId;
+
+    let storage = DialogueStorage::in_memory();
+    let dialogue = RecipeDialogue::new(storage.clone(), ChatId(5));
+    dialogue
+        .update(RecipeDialogueState::ScalingRecipe {
+            recipe_id: 1,
+            language_code: None,
+        })
+        .await?;
+
+    let max_idle = Duration::from_secs(60);
+    assert_eq!(
+        sweep_idle_dialogues(&storage, max_idle, Instant::now()).await,
+        0
+    );
+    assert!(dialogue.get().await?.is_some());
+
+    let later = Instant::now() + Duration::from_secs(61);
+    let idle = storage.idle_dialogues(max_idle, later);
+    assert_eq!(idle.len(), 1);
+    assert_eq!(idle[0].chat_id, ChatId(5));
+    assert_eq!(idle[0].state, "scaling_recipe");
+
+    assert_eq!(sweep_idle_dialogues(&storage, max_idle, later).await, 1);
+    assert!(dialogue.get().await?.is_none());
+    assert!(storage.active_dialogues().is_empty());
+    Ok(())
+}
+
+/// Test that states the inner storage cannot load are reported, not hidden
+#[tokio::test]
+async fn test_tracked_storage_passes_load_failures_through() {
+    use just_ingredients::dialogue::TrackedStorage;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use teloxide::dispatching::dialogue::{Dialogue, Storage};
+    use teloxide::types::ChatId;
+
+    /// Storage whose states all fail to deserialize
+    struct CorruptStorage;
+
+    type Fut<T> = Pin<Box<dyn Future<Output = Result<T, String>> + Send>>;
+
+    impl Storage<RecipeDialogueState> for CorruptStorage {
+        type Error = String;
+
+        fn remove_dialogue(self: Arc<Self>, _: ChatId) -> Fut<()> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn update_dialogue(self: Arc<Self>, _: ChatId, _: RecipeDialogueState) -> Fut<()> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn get_dialogue(self: Arc<Self>, _: ChatId) -> Fut<Option<RecipeDialogueState>> {
+            Box::pin(async { Err("unknown variant `OldState`".to_string()) })
+        }
+    }
+
+    let storage = TrackedStorage::new(Arc::new(CorruptStorage));
+    let dialogue: Dialogue<RecipeDialogueState, _> = Dialogue::new(storage.clone(), ChatId(9));
+    assert!(dialogue.get().await.is_err());
+    assert!(storage.active_dialogues().is_empty());
+}
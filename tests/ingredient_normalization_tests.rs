@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use just_ingredients::ingredient_normalization::IngredientNormalizer;
+    use serde::Deserialize;
+    use std::path::Path;
+
+    /// A messy name and its expected canonical form
+    #[derive(Debug, Deserialize)]
+    struct CorpusEntry {
+        raw: String,
+        name: String,
+        notes: Option<String>,
+    }
+
+    /// Normalizer with the aliases shipped in `config/ingredient_aliases.json`
+    fn shipped_normalizer() -> IngredientNormalizer {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("config/ingredient_aliases.json");
+        IngredientNormalizer::from_json(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_corpus_names_normalize_to_canonical_forms() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ingredient_names_corpus.json");
+        let corpus: Vec<CorpusEntry> =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert!(corpus.len() >= 50, "corpus should cover about 50 names");
+
+        let normalizer = shipped_normalizer();
+        for entry in &corpus {
+            let normalized = normalizer.normalize(&entry.raw);
+            assert_eq!(normalized.name, entry.name, "name of '{}'", entry.raw);
+            assert_eq!(normalized.notes, entry.notes, "notes of '{}'", entry.raw);
+        }
+    }
+
+    #[test]
+    fn test_flour_spellings_share_one_canonical_name() {
+        let normalizer = shipped_normalizer();
+        let names: Vec<String> = ["All-purpose flour", "all purpose flour", "AP flour"]
+            .iter()
+            .map(|raw| normalizer.normalize(raw).name)
+            .collect();
+        assert!(
+            names.iter().all(|name| name == "all purpose flour"),
+            "{names:?}"
+        );
+    }
+
+    #[test]
+    fn test_without_aliases_only_spelling_is_normalized() {
+        let normalizer = IngredientNormalizer::default();
+        assert_eq!(normalizer.normalize("AP Flour").name, "ap flour");
+        assert_eq!(
+            normalizer.normalize(" All-Purpose  Flour").name,
+            "all purpose flour"
+        );
+    }
+}
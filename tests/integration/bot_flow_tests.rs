@@ -28,6 +28,10 @@ fn test_recipe_naming_dialogue_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "3".to_string(),
@@ -37,6 +41,10 @@ fn test_recipe_naming_dialogue_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -305,6 +313,7 @@ fn test_photo_caption_workflow_integration() {
         message_id: None,
         extracted_text: ocr_text.to_string(),
         recipe_name_from_caption: Some(recipe_name_candidate.to_string()),
+        last_deleted: None,
     };
 
     // Verify dialogue state contains caption-derived name
@@ -644,6 +653,7 @@ fn test_caption_preservation_after_ingredient_deletion() {
         message_id: Some(12345),
         extracted_text: ocr_text.to_string(),
         recipe_name_from_caption: recipe_name_from_caption.clone(),
+        last_deleted: None,
     };
 
     // Verify initial state has caption info
@@ -667,6 +677,7 @@ fn test_caption_preservation_after_ingredient_deletion() {
         message_id: Some(12345),
         extracted_text: ocr_text.to_string(),
         recipe_name_from_caption: recipe_name_from_caption.clone(), // This should be preserved!
+        last_deleted: None,
     };
 
     // Verify the caption info is still preserved after deletion
@@ -735,7 +746,7 @@ async fn test_database_integration_full_workflow() -> Result<(), Box<dyn std::er
     }
 
     // Test data
-    let telegram_id = 999999; // Use a test user ID
+    let telegram_id = db::TelegramId(999999); // Use a test user ID
     let recipe_content = "Test Recipe Content";
     let detector = MeasurementDetector::new().unwrap();
 
@@ -805,7 +816,7 @@ async fn test_database_integration_full_workflow() -> Result<(), Box<dyn std::er
     };
 
     assert!(!search_results.is_empty());
-    assert!(search_results.iter().any(|r| r.id == recipe_id));
+    assert!(search_results.iter().any(|r| r.recipe.id == recipe_id));
 
     // Step 4: Test recipe listing with pagination
     let (recipe_names, total) =
@@ -818,7 +829,7 @@ async fn test_database_integration_full_workflow() -> Result<(), Box<dyn std::er
     assert!(!recipe_names.is_empty());
 
     // Step 5: Test ingredient listing
-    let ingredients = match db::list_ingredients_by_user(&pool, telegram_id).await {
+    let ingredients = match db::list_ingredients_by_user(&pool, user.id).await {
         Ok(ings) => ings,
         Err(e) => panic!("Failed to list ingredients: {}", e);
     };
@@ -837,7 +848,7 @@ async fn test_database_integration_full_workflow() -> Result<(), Box<dyn std::er
 
     // Cleanup: Delete test data (in reverse order to maintain foreign key constraints)
     for ingredient in &ingredients {
-        if ingredient.user_id == telegram_id {
+        if ingredient.user_id == user.id {
             if let Err(e) = db::delete_ingredient(&pool, ingredient.id).await {
                 println!(
                     "Warning: Failed to cleanup ingredient {}: {}",
@@ -893,7 +904,7 @@ async fn test_saved_ingredient_editing_workflow() -> Result<(), Box<dyn std::err
     }
 
     // Test data
-    let telegram_id = 888888; // Use a different test user ID
+    let telegram_id = db::TelegramId(888888); // Use a different test user ID
     let detector = MeasurementDetector::new().unwrap();
 
     // Step 1: Create user and recipe with ingredients
@@ -1044,6 +1055,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -1053,6 +1068,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "1".to_string(),
@@ -1062,6 +1081,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 16,
             end_pos: 17,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -1072,6 +1095,7 @@ fn test_initial_recipe_creation_editing_workflow() {
         message_id: Some(1000), // Original recipe display message ID
         extracted_text: "2 cups flour\n3 eggs\n1 cup sugar".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Verify initial state
@@ -1127,6 +1151,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -1136,6 +1164,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "1".to_string(),
@@ -1145,6 +1177,10 @@ fn test_initial_recipe_creation_editing_workflow() {
             start_pos: 16,
             end_pos: 17,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -1156,6 +1192,7 @@ fn test_initial_recipe_creation_editing_workflow() {
         message_id: Some(1000), // Back to original message ID for replacement
         extracted_text: "2 cups flour\n3 eggs\n1 cup sugar".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Verify the flour ingredient was updated
@@ -1181,6 +1218,7 @@ fn test_initial_recipe_creation_editing_workflow() {
         message_id: Some(1000), // Original message ID restored
         extracted_text: "2 cups flour\n3 eggs\n1 cup sugar".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Verify cancel restored original ingredients
@@ -1220,6 +1258,8 @@ fn test_saved_recipe_editing_workflow() {
             unit: Some("cups".to_string()),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         },
         Ingredient {
             id: 2,
@@ -1230,6 +1270,8 @@ fn test_saved_recipe_editing_workflow() {
             unit: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         },
     ];
 
@@ -1242,6 +1284,10 @@ fn test_saved_recipe_editing_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "3".to_string(),
@@ -1251,15 +1297,20 @@ fn test_saved_recipe_editing_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
     let initial_state = RecipeDialogueState::EditingSavedIngredients {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: current_matches.clone(),
         language_code: Some("en".to_string()),
         message_id: Some(2000), // Original recipe display message ID
+        last_deleted: None,
     };
 
     // Verify initial state
@@ -1283,7 +1334,7 @@ fn test_saved_recipe_editing_workflow() {
     // This simulates handle_edit_saved_ingredient_button callback
     let editing_single_state = RecipeDialogueState::EditingSavedIngredient {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: current_matches.clone(),
         editing_index: 1, // Editing eggs (index 1)
         language_code: Some("en".to_string()),
@@ -1317,6 +1368,10 @@ fn test_saved_recipe_editing_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         MeasurementMatch {
             quantity: "4".to_string(),
@@ -1326,16 +1381,21 @@ fn test_saved_recipe_editing_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
     // Step 4: After successful edit, return to editing saved ingredients state
     let updated_editing_state = RecipeDialogueState::EditingSavedIngredients {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: updated_matches.clone(),
         language_code: Some("en".to_string()),
         message_id: Some(2000), // Back to original message ID for replacement
+        last_deleted: None,
     };
 
     // Verify the eggs ingredient was updated
@@ -1355,10 +1415,11 @@ fn test_saved_recipe_editing_workflow() {
     // Step 5: Test cancel functionality during editing
     let cancel_state = RecipeDialogueState::EditingSavedIngredients {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: current_matches.clone(), // Original matches restored
         language_code: Some("en".to_string()),
         message_id: Some(2000), // Original message ID restored
+        last_deleted: None,
     };
 
     // Verify cancel restored original matches
@@ -1396,6 +1457,10 @@ fn test_message_editing_edge_cases() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -1431,6 +1496,8 @@ fn test_message_editing_edge_cases() {
             unit: Some("cups".to_string()),
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
+            unit_dimension: None,
+            unit_system: None,
         },
     ];
 
@@ -1443,12 +1510,16 @@ fn test_message_editing_edge_cases() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
     let saved_editing_state_no_original = RecipeDialogueState::EditingSavedIngredient {
         recipe_id: 200,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: current_matches.clone(),
         editing_index: 0,
         language_code: Some("en".to_string()),
@@ -1475,6 +1546,7 @@ fn test_message_editing_edge_cases() {
         message_id: Some(3000),
         extracted_text: "Test OCR text".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Simulate multiple transitions while preserving message ID tracking
@@ -1504,10 +1576,11 @@ fn test_message_editing_edge_cases() {
     // Edge Case 4: Complex workflow with multiple message replacements
     let complex_editing_state = RecipeDialogueState::EditingSavedIngredients {
         recipe_id: 400,
-        original_ingredients: saved_ingredients.clone(),
+        original_ingredients: just_ingredients::ingredient_editing::snapshot_ingredients(&saved_ingredients),
         current_matches: current_matches.clone(),
         language_code: Some("en".to_string()),
         message_id: Some(4000), // Latest message ID after multiple edits
+        last_deleted: None,
     };
 
     // Verify complex state maintains proper structure
@@ -1551,6 +1624,10 @@ fn test_unified_multi_word_ingredient_bot_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "3".to_string(),
@@ -1560,6 +1637,10 @@ fn test_unified_multi_word_ingredient_bot_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "1".to_string(),
@@ -1569,6 +1650,10 @@ fn test_unified_multi_word_ingredient_bot_workflow() {
             start_pos: 16,
             end_pos: 17,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -1634,6 +1719,10 @@ fn test_quantity_correction_dialogue_workflow() {
             start_pos: 0,
             end_pos: 6,
             requires_quantity_confirmation: true,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "3".to_string(),
@@ -1643,6 +1732,10 @@ fn test_quantity_correction_dialogue_workflow() {
             start_pos: 8,
             end_pos: 9,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -152,7 +152,7 @@ fn test_quantity_only_edge_cases() {
         (
             "Bake at 350°F for 25 minutes",
             "350",
-            "°F", // This might be parsed as ingredient, but tests edge case
+            "°F", // Instruction line, skipped before extraction
             "Temperature with degree symbol",
         ),
         ("Serves 4 people", "4", "people", "Serves quantity"),
@@ -199,8 +199,13 @@ fn test_quantity_only_edge_cases() {
         let matches = detector.extract_ingredient_measurements(input_text);
 
         // For most cases, we expect at least one measurement
-        if expected_ingredient != "°F" {
-            // Skip the temperature case as it's an edge case
+        if expected_ingredient == "°F" {
+            assert!(
+                matches.is_empty(),
+                "Instruction lines should not produce measurements: {}",
+                description
+            );
+        } else {
             assert!(
                 !matches.is_empty(),
                 "Should find measurements in: {}",
@@ -802,6 +807,10 @@ fn test_multi_line_ingredients_ui_display_formatting() {
             start_pos: 0,
             end_pos: 20,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "1".to_string(),
@@ -811,6 +820,10 @@ fn test_multi_line_ingredients_ui_display_formatting() {
             start_pos: 0,
             end_pos: 15,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "3/4".to_string(),
@@ -820,6 +833,10 @@ fn test_multi_line_ingredients_ui_display_formatting() {
             start_pos: 0,
             end_pos: 28,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "1".to_string(),
@@ -829,6 +846,10 @@ fn test_multi_line_ingredients_ui_display_formatting() {
             start_pos: 0,
             end_pos: 18,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -870,6 +891,10 @@ fn test_dialogue_flow_integrity_with_multi_line_ingredients() {
             start_pos: 0,
             end_pos: 25,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
         just_ingredients::MeasurementMatch {
             quantity: "1".to_string(),
@@ -879,6 +904,10 @@ fn test_dialogue_flow_integrity_with_multi_line_ingredients() {
             start_pos: 0,
             end_pos: 5,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         },
     ];
 
@@ -890,6 +919,7 @@ fn test_dialogue_flow_integrity_with_multi_line_ingredients() {
         message_id: Some(123),
         extracted_text: "2 cups old-fashioned\nrolled oats\n1 cup sugar".to_string(),
         recipe_name_from_caption: None,
+        last_deleted: None,
     };
 
     // Verify state contains correct data
@@ -1583,6 +1613,209 @@ fn test_adaptive_preprocessing_performance_comparison() {
     println!("✅ Adaptive preprocessing performance comparison test passed");
 }
 
+#[test]
+fn test_source_class_routes_to_profile_stages() {
+    use just_ingredients::preprocessing::{
+        classify_source, ImageMetadata, ImageQuality, ImageQualityResult, PipelineConfig,
+        PreprocessingStage::*, SourceClass, SourceProfiles,
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let profiles = SourceProfiles::default();
+    // Tilted and noisy, so each profile's measurement-driven stages run
+    let medium = ImageQualityResult {
+        quality: ImageQuality::Medium,
+        contrast_ratio: 0.5,
+        brightness: 0.5,
+        sharpness: 0.5,
+        noise_estimate: 3.0,
+        skew_angle: 2.0,
+        processing_time_ms: 0,
+    };
+
+    let cases = [
+        (
+            "screenshot.png",
+            image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(0)),
+            SourceClass::Screenshot,
+            vec![Threshold],
+            "screenshot_medium_quality:threshold",
+        ),
+        (
+            "photo.png",
+            image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(12)),
+            SourceClass::Photo,
+            vec![Deskew, Denoise, Threshold],
+            "photo_medium_quality:deskew+denoise+threshold",
+        ),
+        (
+            "scan.tiff",
+            image::DynamicImage::ImageLuma8(
+                image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(0)).to_luma8(),
+            ),
+            SourceClass::Scan,
+            vec![Deskew, Threshold, Despeckle],
+            "scan_medium_quality:deskew+threshold+despeckle",
+        ),
+    ];
+
+    for (file_name, img, expected_class, expected_stages, expected_strategy) in cases {
+        let path = dir.path().join(file_name);
+        img.save(&path).unwrap();
+        let loaded = image::open(&path).unwrap();
+
+        let metadata = ImageMetadata::from_image(&path, &loaded);
+        let class = classify_source(&metadata);
+        assert_eq!(class, expected_class, "{file_name}: {metadata:?}");
+
+        let result = just_ingredients::ocr::apply_source_aware_preprocessing(
+            &loaded,
+            &medium,
+            class,
+            profiles.for_class(class),
+            &PipelineConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(result.stages, expected_stages, "{file_name}");
+        assert_eq!(result.preprocessing_strategy, expected_strategy);
+    }
+}
+
+#[test]
+fn test_quality_assessment_drives_pipeline_selection() {
+    use just_ingredients::preprocessing::{
+        assess_image_quality, select_pipeline, ImageQuality, PipelineConfig, PreprocessingStage::*,
+    };
+
+    let cfg = PipelineConfig::default();
+    let clean = assess_image_quality(&image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(
+        0,
+    )))
+    .unwrap();
+    assert!(clean.noise_estimate < cfg.noise_threshold, "{clean:?}");
+    let plan = select_pipeline(&clean, &cfg);
+    assert!(!plan.contains(Denoise) && !plan.contains(Clahe), "{plan:?}");
+
+    let noisy = assess_image_quality(&image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(
+        12,
+    )))
+    .unwrap();
+    assert!(noisy.noise_estimate >= cfg.noise_threshold, "{noisy:?}");
+    if noisy.quality != ImageQuality::High {
+        assert!(select_pipeline(&noisy, &cfg).contains(Denoise));
+    }
+}
+
+/// Cluttered pages where the ingredient list is a small part of the page,
+/// with the list's bounding box
+fn cluttered_page_fixtures() -> [(&'static str, just_ingredients::ocr::BBox); 2] {
+    use just_ingredients::ocr::BBox;
+    [
+        // Next to a large photo, under a title and a page rule
+        (
+            "tests/fixtures/cluttered_page_list_right.png",
+            BBox::new(820, 130, 960, 360),
+        ),
+        // Below a full-width photo, next to a dark advert block
+        (
+            "tests/fixtures/cluttered_page_list_bottom_left.png",
+            BBox::new(60, 510, 190, 720),
+        ),
+    ]
+}
+
+#[test]
+fn test_densest_text_column_finds_small_ingredient_list() {
+    use just_ingredients::preprocessing::{crop_text_column, locate_densest_text_column};
+
+    for (fixture, list) in cluttered_page_fixtures() {
+        let page = image::open(fixture).unwrap();
+        let column = locate_densest_text_column(&page)
+            .unwrap_or_else(|| panic!("{fixture}: no text column found"));
+
+        let cropped = crop_text_column(&page, &column).cropped_region;
+        assert!(
+            cropped.x0 <= list.x0
+                && cropped.y0 <= list.y0
+                && cropped.x1 >= list.x1
+                && cropped.y1 >= list.y1,
+            "{fixture}: {cropped:?} should contain the list {list:?}"
+        );
+        let page_area = page.width() as u64 * page.height() as u64;
+        assert!(
+            cropped.area() * 10 < page_area,
+            "{fixture}: {cropped:?} should be a small part of the page"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_ocr_reads_ingredient_list_on_cluttered_page() {
+    let config = OcrConfig::default();
+    let instance_manager = OcrInstanceManager::new();
+    let circuit_breaker = CircuitBreaker::new(config.recovery.clone());
+    let detector = MeasurementDetector::new().unwrap();
+
+    for (fixture, _) in cluttered_page_fixtures() {
+        let (text, confidence) =
+            extract_text_from_image(fixture, &config, &instance_manager, &circuit_breaker)
+                .await
+                .unwrap_or_else(|e| panic!("{fixture}: OCR failed: {e}"));
+
+        // Either the full page or the second pass over the list column finds the measurements
+        println!(
+            "{fixture}: strategy {:?}, text {text:?}",
+            confidence.preprocessing_strategy
+        );
+        assert!(detector.has_measurements(&text), "{fixture}: {text:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_second_pass_is_skipped_when_disabled() {
+    let config = OcrConfig {
+        second_pass_enabled: false,
+        ..OcrConfig::default()
+    };
+    let instance_manager = OcrInstanceManager::new();
+    let circuit_breaker = CircuitBreaker::new(config.recovery.clone());
+
+    for (fixture, _) in cluttered_page_fixtures() {
+        let (_, confidence) =
+            extract_text_from_image(fixture, &config, &instance_manager, &circuit_breaker)
+                .await
+                .unwrap_or_else(|e| panic!("{fixture}: OCR failed: {e}"));
+        let strategy = confidence.preprocessing_strategy.unwrap_or_default();
+        assert!(!strategy.ends_with("+column_crop"), "{fixture}: {strategy}");
+    }
+}
+
+#[test]
+fn test_retry_profiles_run_their_stages_as_listed() {
+    use just_ingredients::preprocessing::PreprocessingStage::*;
+
+    let img = image::DynamicImage::ImageRgb8(create_text_bars_rgb_image(12));
+    let expected = [
+        ("no_threshold", vec![Deskew, Denoise]),
+        (
+            "aggressive_denoise",
+            vec![Clahe, Denoise, Threshold, Despeckle],
+        ),
+        ("no_deskew", vec![Denoise, Threshold]),
+    ];
+
+    let profiles = just_ingredients::ocr_config::default_retry_profiles();
+    assert_eq!(profiles.len(), expected.len());
+    for (retry_profile, (name, stages)) in profiles.iter().zip(expected) {
+        let result = just_ingredients::ocr::apply_retry_profile(&img, retry_profile).unwrap();
+        assert_eq!(retry_profile.name, name);
+        assert_eq!(result.stages, stages, "{name}");
+        assert!(result
+            .preprocessing_strategy
+            .starts_with(&format!("retry_{name}:")));
+    }
+}
+
 #[test]
 fn test_deskew_integration_straight_text() {
     // Test that straight text is not over-corrected
@@ -1709,6 +1942,26 @@ fn test_deskew_performance_requirement() {
 }
 
 // Helper functions for creating test images
+fn create_text_bars_rgb_image(noise_amplitude: i32) -> image::RgbImage {
+    // Light page with dark text-like bars plus optional deterministic sensor noise
+    let mut seed: u32 = 42;
+    image::RgbImage::from_fn(300, 200, |x, y| {
+        let base = if (y / 12) % 3 == 0 && x > 20 && x < 280 {
+            25
+        } else {
+            230
+        };
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let jitter = if noise_amplitude > 0 {
+            (seed >> 16) as i32 % (2 * noise_amplitude + 1) - noise_amplitude
+        } else {
+            0
+        };
+        let value = (base + jitter).clamp(0, 255) as u8;
+        image::Rgb([value, value, value])
+    })
+}
+
 fn create_high_quality_test_image(width: u32, height: u32) -> image::DynamicImage {
     let mut img = image::GrayImage::new(width, height);
 
@@ -2026,3 +2279,41 @@ mod automated_recovery_integration_tests {
     // Full integration testing would be done through manual testing or
     // separate integration test suites with test images.
 }
+
+#[cfg(test)]
+mod recipe_extractor_tests {
+    use just_ingredients::ocr_config::OcrConfig;
+    use just_ingredients::text_processing::MeasurementConfig;
+    use just_ingredients::RecipeExtractor;
+
+    #[tokio::test]
+    async fn test_extract_from_text() {
+        let extractor =
+            RecipeExtractor::new(OcrConfig::default(), MeasurementConfig::default()).unwrap();
+
+        let result = extractor
+            .extract_from_text("2 cups flour\n1 tsp salt\nMix well.")
+            .await;
+
+        assert_eq!(result.text, "2 cups flour\n1 tsp salt\nMix well.");
+        let names: Vec<_> = result
+            .ingredients
+            .iter()
+            .map(|m| m.ingredient_name.as_str())
+            .collect();
+        assert_eq!(names, ["flour", "salt"]);
+        assert!(result.confidence.is_none());
+        assert_eq!(result.timing.ocr, std::time::Duration::ZERO);
+        assert_eq!(result.timing.parsing, result.timing.total);
+    }
+
+    #[test]
+    fn test_extractor_rejects_invalid_config() {
+        let measurement_config = MeasurementConfig {
+            max_combine_lines: 0,
+            ..MeasurementConfig::default()
+        };
+
+        assert!(RecipeExtractor::new(OcrConfig::default(), measurement_config).is_err());
+    }
+}
@@ -4,10 +4,12 @@
 //! testing message retrieval and formatting with various edge cases.
 
 use just_ingredients::localization::{
-    create_localization_manager, detect_language, t_args_lang, t_lang, LocalizationManager,
+    create_localization_manager, detect_language, parse_language_list, t_args_lang, t_lang,
+    t_plural, LanguageLoadState, LocalizationManager, LocalizationOptions,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[cfg(test)]
 mod tests {
@@ -247,6 +249,71 @@ mod tests {
         assert!(french_plural.contains("5"));
     }
 
+    /// Fluent wraps placeables in Unicode isolation marks; compare the visible text only
+    fn visible(text: String) -> String {
+        text.replace(['\u{2068}', '\u{2069}'], "")
+    }
+
+    #[test]
+    fn test_t_plural_english() {
+        let manager = setup_localization();
+        let review = |count| {
+            visible(t_plural(
+                &manager,
+                "review-title-count",
+                count,
+                &[],
+                Some("en"),
+            ))
+        };
+
+        assert_eq!(review(0), "Review your 0 ingredients");
+        assert_eq!(review(1), "Review your 1 ingredient");
+        assert_eq!(review(2), "Review your 2 ingredients");
+        assert_eq!(review(21), "Review your 21 ingredients");
+
+        let saved = visible(t_plural(
+            &manager,
+            "ingredients-saved",
+            1,
+            &[("recipe_name", "Banana Bread")],
+            Some("en"),
+        ));
+        assert_eq!(saved, "1 ingredient saved to \"Banana Bread\"");
+    }
+
+    #[test]
+    fn test_t_plural_french() {
+        let manager = setup_localization();
+        let total = |count| {
+            visible(t_plural(
+                &manager,
+                "ingredient-total",
+                count,
+                &[],
+                Some("fr"),
+            ))
+        };
+
+        // French treats zero as singular
+        assert_eq!(total(0), "0 ingrédient");
+        assert_eq!(total(1), "1 ingrédient");
+        assert_eq!(total(2), "2 ingrédients");
+        assert_eq!(total(21), "21 ingrédients");
+
+        let saved = visible(t_plural(
+            &manager,
+            "ingredients-saved",
+            21,
+            &[("recipe_name", "Pain aux bananes")],
+            Some("fr-FR"),
+        ));
+        assert_eq!(
+            saved,
+            "21 ingrédients enregistrés dans \"Pain aux bananes\""
+        );
+    }
+
     #[test]
     fn test_quantity_correction_prompt_localization() {
         let manager = setup_localization();
@@ -273,4 +340,124 @@ mod tests {
         // Ensure English and French are different
         assert_ne!(english_message, french_message);
     }
+
+    fn manager_with_options(preload: &[&str], ttl: Duration) -> LocalizationManager {
+        LocalizationManager::with_options(LocalizationOptions {
+            preload_languages: preload.iter().map(|l| l.to_string()).collect(),
+            negative_cache_ttl: ttl,
+        })
+        .expect("Failed to create localization manager")
+    }
+
+    #[test]
+    fn test_lazy_loading_keeps_english_eager() {
+        let manager = manager_with_options(&[], Duration::from_secs(300));
+
+        assert_eq!(
+            manager.language_status("en").state,
+            LanguageLoadState::Loaded
+        );
+        assert_eq!(
+            manager.language_status("fr").state,
+            LanguageLoadState::NotLoaded
+        );
+        assert_eq!(manager.language_status("fr").load_attempts, 0);
+
+        let message = manager.get_message_in_language("help-commands", "fr", None);
+        assert!(!message.starts_with("Missing translation:"));
+
+        let fr_status = manager.language_status("fr");
+        assert_eq!(fr_status.state, LanguageLoadState::Loaded);
+        assert_eq!(fr_status.load_attempts, 1);
+        assert!(fr_status.approx_memory_bytes > 0);
+    }
+
+    #[test]
+    fn test_lazy_load_once_under_concurrent_first_requests() {
+        let manager = Arc::new(manager_with_options(&[], Duration::from_secs(300)));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let manager = Arc::clone(&manager);
+                std::thread::spawn(move || {
+                    manager.get_message_in_language("help-commands", "fr", None)
+                })
+            })
+            .collect();
+
+        let messages: Vec<String> = handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Thread panicked"))
+            .collect();
+
+        assert!(messages.windows(2).all(|pair| pair[0] == pair[1]));
+        assert_eq!(manager.language_status("fr").load_attempts, 1);
+    }
+
+    #[test]
+    fn test_failed_language_load_is_negative_cached() {
+        let manager = manager_with_options(&[], Duration::from_secs(300));
+
+        let english = manager.get_message_in_language("help-commands", "en", None);
+        let first = manager.get_message_in_language("help-commands", "de", None);
+        let second = manager.get_message_in_language("help-commands", "de", None);
+
+        // Falls back to English without retrying the failed load
+        assert_eq!(first, english);
+        assert_eq!(second, english);
+        let status = manager.language_status("de");
+        assert_eq!(status.state, LanguageLoadState::Failed);
+        assert_eq!(status.load_attempts, 1);
+    }
+
+    #[test]
+    fn test_failed_language_load_retried_after_ttl() {
+        let manager = manager_with_options(&[], Duration::ZERO);
+
+        manager.get_message_in_language("help-commands", "de", None);
+        manager.get_message_in_language("help-commands", "de", None);
+
+        assert_eq!(manager.language_status("de").load_attempts, 2);
+    }
+
+    #[test]
+    fn test_unsupported_languages_share_one_bucket() {
+        let manager = manager_with_options(&[], Duration::from_secs(300));
+
+        for language in ["de", "es", "zz-not-a-language"] {
+            manager.get_message_in_language("help-commands", language, None);
+        }
+
+        let languages: Vec<String> = manager
+            .language_statuses()
+            .into_iter()
+            .map(|status| status.language)
+            .collect();
+        assert_eq!(languages, vec!["en", "fr", "other"]);
+
+        // The first failure is cached for the whole bucket
+        let status = manager.language_status("es");
+        assert_eq!(status.language, "other");
+        assert_eq!(status.state, LanguageLoadState::Failed);
+        assert_eq!(status.load_attempts, 1);
+    }
+
+    #[test]
+    fn test_preload_languages() {
+        let manager = manager_with_options(&["fr"], Duration::from_secs(300));
+
+        let fr_status = manager.language_status("fr");
+        assert_eq!(fr_status.state, LanguageLoadState::Loaded);
+        assert_eq!(fr_status.load_attempts, 1);
+
+        // Using a preloaded language does not load it again
+        manager.get_message_in_language("help-commands", "fr", None);
+        assert_eq!(manager.language_status("fr").load_attempts, 1);
+    }
+
+    #[test]
+    fn test_parse_language_list() {
+        assert_eq!(parse_language_list("fr, DE,,fr"), vec!["fr", "de"]);
+        assert!(parse_language_list("").is_empty());
+    }
 }
@@ -0,0 +1,141 @@
+//! # Measurement Detector Property Tests
+//!
+//! Generated ingredient lines and noise checked against invariants of
+//! `MeasurementDetector`, with a bounded case count so they run with `cargo test`.
+//! The fuzz target in `fuzz/` feeds arbitrary bytes to the same extraction.
+
+use just_ingredients::text_processing::{
+    load_measurement_units_config, LineKind, MeasurementDetector,
+};
+use proptest::prelude::*;
+use proptest::sample::select;
+use std::sync::LazyLock;
+
+/// Names of every configured unit
+static UNIT_NAMES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    load_measurement_units_config()
+        .measurement_units
+        .entries()
+        .map(|entry| entry.name.clone())
+        .collect()
+});
+
+/// Unicode fractions and the quantity extraction reports for them
+static UNICODE_FRACTIONS: [(&str, &str); 6] = [
+    ("½", "1/2"),
+    ("⅓", "1/3"),
+    ("⅔", "2/3"),
+    ("¼", "1/4"),
+    ("¾", "3/4"),
+    ("⅛", "1/8"),
+];
+
+/// Ingredient names of one to three words, with accents and hyphens
+const INGREDIENT: &str = "[a-zéèêàçôûïœ]{3,9}([ -][a-zéèêàçôûïœ]{3,9}){0,2}";
+
+/// A quantity as written, as extracted, and the upper bound of a range
+fn quantity() -> impl Strategy<Value = (String, String, Option<String>)> {
+    prop_oneof![
+        (1u32..=500).prop_map(|n| (n.to_string(), n.to_string(), None)),
+        (0u32..100, 1u32..10).prop_map(|(whole, tenths)| {
+            let quantity = format!("{whole}.{tenths}");
+            (quantity.clone(), quantity, None)
+        }),
+        (1u32..10, 2u32..10).prop_map(|(numerator, denominator)| {
+            let quantity = format!("{numerator}/{denominator}");
+            (quantity.clone(), quantity, None)
+        }),
+        select(&UNICODE_FRACTIONS[..]).prop_map(|(unicode, ascii)| (
+            unicode.to_string(),
+            ascii.to_string(),
+            None
+        )),
+        (1u32..10, select(&UNICODE_FRACTIONS[..])).prop_map(|(whole, (unicode, ascii))| {
+            (
+                format!("{whole}{unicode}"),
+                format!("{whole} {ascii}"),
+                None,
+            )
+        }),
+        (1u32..10, 1u32..6).prop_map(|(min, step)| {
+            let max = min + step;
+            (
+                format!("{min}-{max}"),
+                min.to_string(),
+                Some(max.to_string()),
+            )
+        }),
+    ]
+}
+
+/// Invariants holding for any text
+fn assert_consistent(detector: &MeasurementDetector, text: &str) -> Result<(), TestCaseError> {
+    let matches = detector.extract_ingredient_measurements(text);
+    prop_assert_eq!(detector.has_measurements(text), !matches.is_empty());
+    for m in &matches {
+        prop_assert!(m.start_pos <= m.end_pos, "{:?} in {:?}", m, text);
+        prop_assert!(m.end_pos <= text.len(), "{:?} in {:?}", m, text);
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn test_generated_line_yields_its_measurement(
+        (written, expected, expected_max) in quantity(),
+        unit in select(UNIT_NAMES.clone()),
+        ingredient in INGREDIENT,
+        attached in any::<bool>(),
+    ) {
+        let separator = if attached && unit.starts_with(char::is_alphabetic) { "" } else { " " };
+        let line = format!("{written}{separator}{unit} {ingredient}");
+        let detector = MeasurementDetector::new().unwrap();
+
+        // A longer unit the words happen to spell out rightly wins ("cuillère à soupe")
+        let after_quantity = format!("{unit} {ingredient}").to_lowercase();
+        prop_assume!(!UNIT_NAMES.iter().any(|other| {
+            other.len() > unit.len() && after_quantity.starts_with(&other.to_lowercase())
+        }));
+        prop_assume!(detector.classify_line(&line) == LineKind::Ingredient);
+
+        let matches = detector.extract_ingredient_measurements(&line);
+        prop_assert_eq!(matches.len(), 1, "{:?}", line);
+        let m = &matches[0];
+        prop_assert_eq!(&m.quantity, &expected);
+        let expected_unit = unit.to_lowercase();
+        prop_assert_eq!(m.measurement.as_deref(), Some(expected_unit.as_str()));
+        prop_assert_eq!(&m.quantity_max, &expected_max);
+        prop_assert_eq!(m.start_pos, 0);
+        prop_assert!(m.end_pos <= line.len());
+        prop_assert!(detector.has_measurements(&line));
+    }
+
+    #[test]
+    fn test_noise_never_breaks_extraction(text in "\\PC{0,80}") {
+        assert_consistent(&MeasurementDetector::new().unwrap(), &text)?;
+    }
+
+    #[test]
+    fn test_arbitrary_strings_never_break_extraction(text in any::<String>()) {
+        assert_consistent(&MeasurementDetector::new().unwrap(), &text)?;
+    }
+
+    #[test]
+    fn test_recipes_mixing_measurements_and_noise_stay_consistent(
+        lines in prop::collection::vec(
+            prop_oneof![
+                (quantity(), select(UNIT_NAMES.clone()), INGREDIENT)
+                    .prop_map(|((written, _, _), unit, ingredient)| {
+                        format!("{written} {unit} {ingredient}")
+                    }),
+                "[^0-9\n]{0,40}",
+                "\\PC{0,40}",
+            ],
+            0..12,
+        ),
+    ) {
+        assert_consistent(&MeasurementDetector::new().unwrap(), &lines.join("\n"))?;
+    }
+}
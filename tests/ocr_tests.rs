@@ -8,15 +8,17 @@ mod tests {
     use just_ingredients::circuit_breaker::CircuitBreaker;
     use just_ingredients::instance_manager::OcrInstanceManager;
     use just_ingredients::ocr::{
-        calculate_retry_delay, estimate_memory_usage, extract_hocr_from_image,
-        is_supported_image_format, map_measurement_to_bbox, parse_hocr_to_lines,
-        perform_constrained_ocr, validate_image_path, validate_image_with_format_limits, BBox,
-        ConstrainedOcrResult, HocrLine,
+        apply_line_confidences, calculate_retry_delay, estimate_memory_usage,
+        extract_hocr_from_image, is_supported_image_format, line_confidences_from_tsv,
+        map_measurement_to_bbox, merge_page_results, parse_hocr_to_lines, perform_constrained_ocr,
+        validate_image_path, validate_image_with_format_limits, BBox, ConfidenceFlag,
+        ConstrainedOcrResult, HocrLine, OcrConfidence,
     };
     use just_ingredients::ocr_config::{
         FormatSizeLimits, ModelType, OcrConfig, PageSegMode, RecoveryConfig,
     };
     use just_ingredients::ocr_errors::OcrError;
+    use just_ingredients::resource_limits::{check_download_size, prepare_image_for_ocr};
     use just_ingredients::text_processing::MeasurementMatch;
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -114,6 +116,32 @@ mod tests {
         assert_eq!(manager._instance_count(), 0);
     }
 
+    /// Test warm-up and checkout of pooled Tesseract instances
+    #[tokio::test]
+    async fn test_instance_manager_pool_checkout() {
+        let manager = OcrInstanceManager::new();
+        let config = OcrConfig::default();
+        assert!(config.pool.max_instances >= 2);
+
+        // Warm instances are created once
+        let warmed = manager.warm_up(&config).unwrap();
+        assert_eq!(warmed, config.pool.warm_instances);
+        assert_eq!(manager.warm_up(&config).unwrap(), 0);
+
+        // Concurrent checkouts get separate instances, up to max_instances
+        let first = manager.checkout(&config).await.unwrap();
+        let second = manager.checkout(&config).await.unwrap();
+        assert_eq!(manager._instance_count(), 2);
+        drop((first, second));
+
+        // Checked in instances are reused
+        let _again = manager.checkout(&config).await.unwrap();
+        assert_eq!(manager._instance_count(), 2);
+
+        assert_eq!(manager.recycle_all(), 2);
+        assert_eq!(manager._instance_count(), 0);
+    }
+
     /// Test instance manager with user patterns file configured
     #[test]
     fn test_instance_manager_with_user_patterns() {
@@ -1109,6 +1137,10 @@ mod tests {
             start_pos: 0,
             end_pos: 1,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         // Map the measurement to its bounding box
@@ -1131,6 +1163,10 @@ mod tests {
             start_pos: 0,
             end_pos: 1,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         let bbox = map_measurement_to_bbox(&measurement, &hocr_lines);
@@ -1153,6 +1189,10 @@ mod tests {
             start_pos: 0,
             end_pos: 1,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         let bbox = map_measurement_to_bbox(&measurement, &hocr_lines);
@@ -1172,6 +1212,10 @@ mod tests {
             start_pos: 0,
             end_pos: 1,
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         let bbox = map_measurement_to_bbox(&measurement, &hocr_lines);
@@ -1196,6 +1240,10 @@ mod tests {
             start_pos: 0,   // "2" starts at position 0
             end_pos: 1,     // "2" ends at position 1
             requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
         };
 
         let bbox = map_measurement_to_bbox(&measurement, &hocr_lines);
@@ -1263,4 +1311,236 @@ mod tests {
         assert!(result.character_whitelist.contains("."));
         assert_eq!(result.processing_time_ms, 150);
     }
+
+    /// Test per-line confidence is read from Tesseract TSV output
+    #[test]
+    fn test_line_confidences_from_tsv() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+4\t1\t1\t1\t1\t0\t10\t10\t300\t30\t-1\t\n\
+5\t1\t1\t1\t1\t1\t10\t10\t50\t30\t96\t250\n\
+5\t1\t1\t1\t1\t2\t70\t10\t20\t30\t90\tg\n\
+5\t1\t1\t1\t1\t3\t100\t10\t80\t30\t84\tfarine\n\
+4\t1\t1\t1\t2\t0\t10\t50\t300\t30\t-1\t\n\
+5\t1\t1\t1\t2\t1\t10\t50\t30\t30\t40\t3\n\
+5\t1\t1\t1\t2\t2\t50\t50\t80\t30\t20\toeufs\n\
+5\t1\t1\t1\t3\t1\t10\t90\t30\t30\t95\t \n\
+5\t1\t2\t1\t1\t1\t10\t130\t80\t30\t70\tsel\n";
+
+        let confidences = line_confidences_from_tsv(tsv);
+
+        // Empty words are skipped, and so are lines made only of them
+        assert_eq!(confidences.len(), 3);
+        assert!((confidences[0] - 0.9).abs() < 1e-6);
+        assert!((confidences[1] - 0.3).abs() < 1e-6);
+        assert!((confidences[2] - 0.7).abs() < 1e-6);
+        assert!(line_confidences_from_tsv("").is_empty());
+    }
+
+    /// Test ingredients take the confidence of their source line
+    #[test]
+    fn test_apply_line_confidences() {
+        let ingredient = |line_number| MeasurementMatch {
+            quantity: "1".to_string(),
+            measurement: None,
+            ingredient_name: "oeuf".to_string(),
+            line_number,
+            start_pos: 0,
+            end_pos: 1,
+            requires_quantity_confirmation: false,
+            unit_dimension: None,
+            unit_system: None,
+            confidence: None,
+            quantity_max: None,
+        };
+        let mut ingredients = vec![ingredient(0), ingredient(2), ingredient(5)];
+
+        apply_line_confidences(&mut ingredients, &[0.9, 0.4, 0.3]);
+
+        assert_eq!(ingredients[0].confidence, Some(0.9));
+        assert_eq!(ingredients[1].confidence, Some(0.3));
+        // Lines without a known confidence are not flagged
+        assert_eq!(ingredients[2].confidence, None);
+    }
+
+    fn page_result(text: &str, score: f32, line_confidences: Vec<f32>) -> (String, OcrConfidence) {
+        (
+            text.to_string(),
+            OcrConfidence {
+                overall_score: score,
+                text_quality_score: score,
+                pattern_score: score,
+                processing_score: score,
+                flags: Vec::new(),
+                preprocessing_strategy: None,
+                line_confidences,
+            },
+        )
+    }
+
+    /// Test the pages of a multi-page input are joined in order
+    #[test]
+    fn test_merge_page_results_joins_pages_in_order() {
+        let mut second = page_result("3 eggs", 0.5, vec![0.4]);
+        second.1.flags.push(ConfidenceFlag::TooShort);
+        let (text, confidence) = merge_page_results(vec![
+            page_result("250 g flour\n100 g sugar", 0.9, vec![0.9, 0.8]),
+            page_result("   ", 0.1, Vec::new()),
+            second,
+        ])
+        .unwrap();
+
+        assert_eq!(text, "250 g flour\n100 g sugar\n3 eggs");
+        assert_eq!(confidence.overall_score, 0.5);
+        assert_eq!(confidence.flags, vec![ConfidenceFlag::TooShort]);
+        assert_eq!(confidence.line_confidences, vec![0.9, 0.8, 0.4]);
+    }
+
+    /// Test line confidences are dropped when a page has none
+    #[test]
+    fn test_merge_page_results_drops_unaligned_line_confidences() {
+        let (_, confidence) = merge_page_results(vec![
+            page_result("250 g flour", 0.9, vec![0.9]),
+            page_result("3 eggs", 0.8, Vec::new()),
+        ])
+        .unwrap();
+        assert!(confidence.line_confidences.is_empty());
+
+        assert!(merge_page_results(vec![page_result("", 0.9, Vec::new())]).is_none());
+    }
+
+    /// Limits small enough for tiny fixture images to exceed each of them
+    fn small_image_limits() -> OcrConfig {
+        OcrConfig {
+            max_file_size: 4096,
+            max_width: 100,
+            max_height: 80,
+            max_pixels: 6_000,
+            ..OcrConfig::default()
+        }
+    }
+
+    /// Write a fixture image of the given size and format to a temporary file
+    fn fixture_image(width: u32, height: u32, format: image::ImageFormat) -> NamedTempFile {
+        let temp_file = NamedTempFile::new().unwrap();
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            width,
+            height,
+            image::Rgb([255, 255, 255]),
+        ))
+        .save_with_format(temp_file.path(), format)
+        .unwrap();
+        temp_file
+    }
+
+    fn image_dimensions(file: &NamedTempFile) -> (u32, u32) {
+        image::ImageReader::open(file.path())
+            .unwrap()
+            .with_guessed_format()
+            .unwrap()
+            .into_dimensions()
+            .unwrap()
+    }
+
+    /// Test the size reported by Telegram is checked before downloading
+    #[test]
+    fn test_download_size_over_max_file_size() {
+        let config = small_image_limits();
+        assert!(check_download_size(4096, &config).is_ok());
+        assert!(matches!(
+            check_download_size(4097, &config),
+            Err(OcrError::FileTooLarge(_))
+        ));
+    }
+
+    /// Test images within every limit are read as they are
+    #[tokio::test]
+    async fn test_prepare_image_within_limits() {
+        let image = fixture_image(100, 60, image::ImageFormat::Png);
+        let path = image.path().to_string_lossy();
+        assert_eq!(
+            prepare_image_for_ocr(&path, &small_image_limits())
+                .await
+                .unwrap(),
+            (100, 60)
+        );
+        assert_eq!(image_dimensions(&image), (100, 60));
+    }
+
+    /// Test an image wider than max_width is downscaled in place
+    #[tokio::test]
+    async fn test_prepare_image_over_max_width_is_downscaled() {
+        let image = fixture_image(200, 20, image::ImageFormat::Png);
+        let path = image.path().to_string_lossy();
+        assert_eq!(
+            prepare_image_for_ocr(&path, &small_image_limits())
+                .await
+                .unwrap(),
+            (100, 10)
+        );
+        assert_eq!(image_dimensions(&image), (100, 10));
+    }
+
+    /// Test an image taller than max_height is downscaled in place, keeping its format readable
+    #[tokio::test]
+    async fn test_prepare_image_over_max_height_is_downscaled() {
+        let image = fixture_image(30, 160, image::ImageFormat::Jpeg);
+        let path = image.path().to_string_lossy();
+        assert_eq!(
+            prepare_image_for_ocr(&path, &small_image_limits())
+                .await
+                .unwrap(),
+            (15, 80)
+        );
+        assert_eq!(image_dimensions(&image), (15, 80));
+        assert!(is_supported_image_format(&path, &OcrConfig::default()));
+    }
+
+    /// Test an image over max_pixels is rejected and left untouched
+    #[tokio::test]
+    async fn test_prepare_image_over_max_pixels_is_rejected() {
+        let image = fixture_image(100, 61, image::ImageFormat::Png);
+        let path = image.path().to_string_lossy();
+        assert!(matches!(
+            prepare_image_for_ocr(&path, &small_image_limits()).await,
+            Err(OcrError::ImageTooLarge(_))
+        ));
+        assert_eq!(image_dimensions(&image), (100, 61));
+    }
+
+    /// Test a malformed file pretending to be a PNG fails to load instead of reaching OCR
+    #[tokio::test]
+    async fn test_prepare_malformed_png_fails_to_load() {
+        let mut temp_file = NamedTempFile::with_suffix(".png").unwrap();
+        temp_file
+            .write_all(b"\x89PNG\r\n\x1a\nnot really an image header")
+            .unwrap();
+        let path = temp_file.path().to_string_lossy();
+        assert!(matches!(
+            prepare_image_for_ocr(&path, &OcrConfig::default()).await,
+            Err(OcrError::ImageLoad(_))
+        ));
+
+        // A truncated PNG gets past the header and fails while decoding
+        let image = fixture_image(50, 50, image::ImageFormat::Png);
+        let bytes = std::fs::read(image.path()).unwrap();
+        std::fs::write(image.path(), &bytes[..bytes.len() / 2]).unwrap();
+        let path = image.path().to_string_lossy();
+        assert!(matches!(
+            prepare_image_for_ocr(&path, &OcrConfig::default()).await,
+            Err(OcrError::ImageLoad(_))
+        ));
+    }
+
+    /// Test text that is no image at all is reported as a validation failure
+    #[tokio::test]
+    async fn test_prepare_unrecognised_file_fails_validation() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"250 g flour").unwrap();
+        let path = temp_file.path().to_string_lossy();
+        assert!(matches!(
+            prepare_image_for_ocr(&path, &OcrConfig::default()).await,
+            Err(OcrError::Validation(_))
+        ));
+    }
 }
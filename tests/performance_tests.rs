@@ -629,7 +629,7 @@ mod tests {
 
         for i in 0..iterations {
             let user_id = test_user_id + i as i64;
-            db::get_or_create_user(&db_pool, user_id, Some(test_language))
+            db::get_or_create_user(&db_pool, db::TelegramId(user_id), Some(test_language))
                 .await
                 .expect("Failed to create user");
         }
@@ -641,7 +641,7 @@ mod tests {
         let start = Instant::now();
         for i in 0..iterations {
             let user_id = test_user_id + i as i64;
-            let _user = db::get_user_by_telegram_id(&db_pool, user_id)
+            let _user = db::get_user_by_telegram_id(&db_pool, db::TelegramId(user_id))
                 .await
                 .expect("Failed to get user");
         }
@@ -695,7 +695,7 @@ mod tests {
         let test_recipe_name = "Performance Test Recipe";
 
         // Create test user
-        db::get_or_create_user(&db_pool, test_user_id, Some("en"))
+        let user = db::get_or_create_user(&db_pool, db::TelegramId(test_user_id), Some("en"))
             .await
             .expect("Failed to create user");
 
@@ -708,7 +708,7 @@ mod tests {
             let recipe_name = format!("{} {}", test_recipe_name, i);
             let ocr_text = format!("2 cups flour\n1 cup sugar\n3 eggs\nRecipe: {}", recipe_name);
 
-            let recipe_id = db::create_recipe(&db_pool, test_user_id, &ocr_text)
+            let recipe_id = db::create_recipe(&db_pool, db::TelegramId(test_user_id), &ocr_text)
                 .await
                 .expect("Failed to create recipe");
 
@@ -720,7 +720,7 @@ mod tests {
             // Add some ingredients
             db::create_ingredient(
                 &db_pool,
-                test_user_id,
+                user.id,
                 Some(recipe_id),
                 "flour",
                 Some(2.0),
@@ -731,7 +731,7 @@ mod tests {
             .ok();
             db::create_ingredient(
                 &db_pool,
-                test_user_id,
+                user.id,
                 Some(recipe_id),
                 "sugar",
                 Some(1.0),
@@ -742,7 +742,7 @@ mod tests {
             .ok();
             db::create_ingredient(
                 &db_pool,
-                test_user_id,
+                user.id,
                 Some(recipe_id),
                 "eggs",
                 Some(3.0),
@@ -758,14 +758,15 @@ mod tests {
 
         // Benchmark recipe lookup
         let start = Instant::now();
-        let _recipes = db::get_user_recipes_paginated(&db_pool, test_user_id, 1, 10)
-            .await
-            .expect("Failed to get recipes");
+        let _recipes =
+            db::get_user_recipes_paginated(&db_pool, db::TelegramId(test_user_id), 1, 10)
+                .await
+                .expect("Failed to get recipes");
         let lookup_duration = start.elapsed();
 
         // Benchmark search
         let start = Instant::now();
-        let _search_results = db::search_recipes(&db_pool, test_user_id, "flour")
+        let _search_results = db::search_recipes(&db_pool, db::TelegramId(test_user_id), "flour")
             .await
             .expect("Failed to search");
         let search_duration = start.elapsed();
@@ -818,7 +819,7 @@ mod tests {
         let iterations = 20;
 
         // Create test user
-        db::get_or_create_user(&db_pool, test_user_id, Some("en"))
+        let user = db::get_or_create_user(&db_pool, db::TelegramId(test_user_id), Some("en"))
             .await
             .expect("Failed to create user");
 
@@ -853,7 +854,7 @@ mod tests {
             let measurements = detector.extract_ingredient_measurements(cleaned_text);
 
             // 3. Database operations
-            let recipe_id = db::create_recipe(&db_pool, test_user_id, cleaned_text)
+            let recipe_id = db::create_recipe(&db_pool, db::TelegramId(test_user_id), cleaned_text)
                 .await
                 .expect("Failed to create recipe");
 
@@ -866,7 +867,7 @@ mod tests {
             for measurement in measurements {
                 db::create_ingredient(
                     &db_pool,
-                    test_user_id,
+                    user.id,
                     Some(recipe_id),
                     &measurement.ingredient_name,
                     measurement.quantity.parse::<f64>().ok(),
@@ -944,7 +945,7 @@ mod tests {
                 let user_id = 999999000 + user_id_offset as i64;
 
                 // Create user
-                db::get_or_create_user(&pool, user_id, Some("en"))
+                let user = db::get_or_create_user(&pool, db::TelegramId(user_id), Some("en"))
                     .await
                     .expect("Failed to create user");
 
@@ -953,7 +954,7 @@ mod tests {
                     let ocr_text = "2 cups flour\n3 eggs";
 
                     // Perform operations
-                    let recipe_id = db::create_recipe(&pool, user_id, ocr_text)
+                    let recipe_id = db::create_recipe(&pool, db::TelegramId(user_id), ocr_text)
                         .await
                         .expect("Failed to create recipe");
 
@@ -964,7 +965,7 @@ mod tests {
 
                     db::create_ingredient(
                         &pool,
-                        user_id,
+                        user.id,
                         Some(recipe_id),
                         "flour",
                         Some(2.0),
@@ -975,7 +976,7 @@ mod tests {
                     .ok();
                     db::create_ingredient(
                         &pool,
-                        user_id,
+                        user.id,
                         Some(recipe_id),
                         "eggs",
                         Some(3.0),
@@ -986,9 +987,10 @@ mod tests {
                     .ok();
 
                     // Simulate some read operations
-                    let _recipes = db::get_user_recipes_paginated(&pool, user_id, 1, 5)
-                        .await
-                        .ok();
+                    let _recipes =
+                        db::get_user_recipes_paginated(&pool, db::TelegramId(user_id), 1, 5)
+                            .await
+                            .ok();
                 }
 
                 // Cleanup
@@ -1421,4 +1423,70 @@ mod tests {
             "📊 Scalability test passed - performance scales reasonably with recipe complexity"
         );
     }
+
+    /// Photos being preprocessed must not starve text messages: on a single-threaded
+    /// runtime, a text handler keeps its latency while several photos are in flight
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_text_handlers_stay_responsive_while_photos_preprocess() {
+        use just_ingredients::ocr::apply_image_preprocessing;
+        use just_ingredients::ocr_config::OcrConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut seed: u32 = 7;
+        let photo = image::RgbImage::from_fn(2400, 1800, |x, y| {
+            // Text-like bars on a light page with sensor noise
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let base = if (y / 30) % 3 == 0 && x > 100 && x < 2300 {
+                30
+            } else {
+                220
+            };
+            let value = (base + (seed >> 16) as i32 % 25 - 12).clamp(0, 255) as u8;
+            image::Rgb([value, value, value])
+        });
+
+        let config = OcrConfig::default();
+        let mut photos = tokio::task::JoinSet::new();
+        for i in 0..4 {
+            let path = dir.path().join(format!("photo_{i}.jpg"));
+            photo.save(&path).unwrap();
+            let config = config.clone();
+            photos.spawn(async move {
+                apply_image_preprocessing(path.to_str().unwrap(), &config, None)
+                    .await
+                    .map(|(_, _, duration, strategy)| (duration, strategy))
+            });
+        }
+
+        let detector = MeasurementDetector::new().unwrap();
+        let mut worst = Duration::ZERO;
+        let mut handled = 0;
+        let photos_start = Instant::now();
+        while !photos.is_empty() {
+            tokio::select! {
+                finished = photos.join_next() => {
+                    let (duration, strategy) = finished.unwrap().unwrap().unwrap();
+                    println!("📷 Preprocessed a photo in {}ms ({strategy})", duration.as_millis());
+                }
+                _ = tokio::time::sleep(Duration::from_millis(10)) => {
+                    let start = Instant::now();
+                    let _ = detector.extract_ingredient_measurements("2 cups flour\n1 tsp salt");
+                    tokio::task::yield_now().await;
+                    worst = worst.max(start.elapsed());
+                    handled += 1;
+                }
+            }
+        }
+
+        println!(
+            "📊 Handled {handled} text messages in {}ms of photo preprocessing, worst latency {}ms",
+            photos_start.elapsed().as_millis(),
+            worst.as_millis()
+        );
+        assert!(handled > 0, "text messages should be handled meanwhile");
+        assert!(
+            worst < Duration::from_millis(250),
+            "text handler latency should stay low, worst was {worst:?}"
+        );
+    }
 }
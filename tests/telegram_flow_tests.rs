@@ -0,0 +1,324 @@
+//! End-to-end handler flows against a mocked Bot API and a Postgres database
+//!
+//! Updates go through the same `message_handler` and `callback_handler` entry
+//! points as in production. Telegram requests, file downloads included, are
+//! answered by the local server in `telegram_mock`; recipes are saved to the
+//! database at `DATABASE_URL_TEST`. Without that variable every flow is skipped.
+//!
+//! The flows share the test chat's rows, so they run one at a time and start
+//! from a chat without any data.
+
+use anyhow::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use teloxide::types::{CallbackQuery, ChatId, Message};
+use teloxide::Bot;
+use tokio::sync::{Mutex, MutexGuard};
+
+use just_ingredients::bot::ui_builder::recipe_selection_callback;
+use just_ingredients::bot::{callback_handler, message_handler, save_ingredients_to_database};
+use just_ingredients::db::{self, TelegramId};
+use just_ingredients::dialogue::{DialogueStorage, RecipeDialogue, RecipeDialogueState};
+use just_ingredients::localization::{create_localization_manager, t_lang, LocalizationManager};
+use just_ingredients::text_processing::MeasurementMatch;
+
+mod telegram_mock;
+
+use telegram_mock::{
+    callback_query, photo_message, text_message, TelegramMock, CHAT_ID, FIRST_MESSAGE_ID,
+};
+
+/// Held by the running flow, as every flow uses the test chat
+static DATABASE: Mutex<()> = Mutex::const_new(());
+
+/// Connection to the test database, emptied of the test chat's data
+///
+/// `None` when `DATABASE_URL_TEST` is not set. The guard keeps other flows
+/// waiting until this one ends.
+async fn test_database() -> Result<Option<(Arc<PgPool>, MutexGuard<'static, ()>)>> {
+    let Ok(database_url) = std::env::var("DATABASE_URL_TEST") else {
+        eprintln!("Skipping Telegram flow test: DATABASE_URL_TEST not set");
+        return Ok(None);
+    };
+    let guard = DATABASE.lock().await;
+    let pool = PgPool::connect(&database_url).await?;
+    db::init_database_schema(&pool).await?;
+    db::delete_all_user_data(&pool, TelegramId(CHAT_ID)).await?;
+    Ok(Some((Arc::new(pool), guard)))
+}
+
+/// A chat with the bot: the mocked Bot API, the database and the dialogue state
+struct Conversation {
+    telegram: TelegramMock,
+    pool: Arc<PgPool>,
+    storage: Arc<DialogueStorage>,
+    localization: Arc<LocalizationManager>,
+}
+
+impl Conversation {
+    fn new(pool: Arc<PgPool>, telegram: TelegramMock) -> Self {
+        Self {
+            telegram,
+            pool,
+            storage: DialogueStorage::in_memory(),
+            localization: create_localization_manager().expect("localization manager"),
+        }
+    }
+
+    fn bot(&self) -> Bot {
+        self.telegram.bot.clone()
+    }
+
+    fn dialogue(&self) -> RecipeDialogue {
+        RecipeDialogue::new(self.storage.clone(), ChatId(CHAT_ID))
+    }
+
+    /// Handle a message from the user
+    async fn send(&self, msg: Message) -> Result<()> {
+        message_handler(
+            self.bot(),
+            msg,
+            self.pool.clone(),
+            self.dialogue(),
+            self.localization.clone(),
+            None,
+        )
+        .await
+    }
+
+    /// Handle a button tap from the user
+    async fn tap(&self, q: CallbackQuery) -> Result<()> {
+        callback_handler(
+            self.bot(),
+            q,
+            self.pool.clone(),
+            self.dialogue(),
+            self.localization.clone(),
+        )
+        .await
+    }
+
+    fn t(&self, key: &str) -> String {
+        t_lang(&self.localization, key, Some("en"))
+    }
+
+    /// Ingredients and review message of the review in progress
+    async fn review(&self) -> Result<(Vec<MeasurementMatch>, i32)> {
+        match self.dialogue().get().await? {
+            Some(RecipeDialogueState::ReviewIngredients {
+                ingredients,
+                message_id: Some(message_id),
+                ..
+            }) => Ok((ingredients, message_id)),
+            state => panic!("expected an ingredient review, found {state:?}"),
+        }
+    }
+
+    /// Names of the test chat's recipes in the database
+    async fn saved_recipe_names(&self) -> Result<Vec<String>> {
+        Ok(
+            db::get_user_recipes_paginated(&self.pool, TelegramId(CHAT_ID), 100, 0)
+                .await?
+                .0,
+        )
+    }
+}
+
+/// Pasted ingredient list reviewed without OCR
+fn pasted_ingredients() -> String {
+    std::fs::read_to_string("tests/fixtures/pasted_ingredients.txt").unwrap()
+}
+
+#[tokio::test]
+async fn test_photo_with_caption_is_reviewed_confirmed_and_saved() -> Result<()> {
+    let Some((pool, _guard)) = test_database().await? else {
+        return Ok(());
+    };
+    let telegram = TelegramMock::start().await;
+    telegram.add_file(
+        "recipe-photo",
+        std::fs::read("test_images/recipe_with_fraction.jpg")?,
+    );
+    let chat = Conversation::new(pool, telegram);
+    let requests = chat.telegram.requests.clone();
+
+    chat.send(photo_message(1, "recipe-photo", Some("Pancakes")))
+        .await?;
+
+    // The photo is fetched, then the processing message turns into the review
+    assert_eq!(requests.calls("getFile")[0]["file_id"], "recipe-photo");
+    assert_eq!(requests.sent_texts(), [chat.t("processing-photo")]);
+    let (ingredients, review_message_id) = chat.review().await?;
+    assert!(!ingredients.is_empty());
+    let review = requests.calls("editMessageText").pop().unwrap();
+    assert_eq!(review["message_id"], review_message_id);
+    for ingredient in &ingredients {
+        assert!(review["text"]
+            .as_str()
+            .unwrap()
+            .contains(&ingredient.ingredient_name));
+    }
+    assert!(requests
+        .last_keyboard_data()
+        .contains(&"confirm".to_string()));
+    assert!(chat.saved_recipe_names().await?.is_empty());
+
+    requests.clear();
+    chat.tap(callback_query("confirm", review_message_id))
+        .await?;
+
+    // The caption names the recipe, so confirming saves it straight away
+    assert!(requests
+        .sent_texts()
+        .iter()
+        .any(|text| text.contains(&chat.t("workflow-recipe-saved"))));
+    assert_eq!(requests.calls("answerCallbackQuery").len(), 1);
+    assert!(chat.dialogue().get().await?.is_none());
+
+    assert_eq!(chat.saved_recipe_names().await?, ["Pancakes"]);
+    let recipes = db::get_recipes_by_name(&chat.pool, TelegramId(CHAT_ID), "Pancakes").await?;
+    let saved = db::get_recipe_ingredients(&chat.pool, recipes[0].id).await?;
+    let saved_names: Vec<_> = saved.iter().map(|i| i.name.as_str()).collect();
+    let reviewed_names: Vec<_> = ingredients
+        .iter()
+        .map(|i| i.ingredient_name.as_str())
+        .collect();
+    assert_eq!(saved_names, reviewed_names);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deleting_every_ingredient_leaves_an_empty_review() -> Result<()> {
+    let Some((pool, _guard)) = test_database().await? else {
+        return Ok(());
+    };
+    let chat = Conversation::new(pool, TelegramMock::start().await);
+    let requests = chat.telegram.requests.clone();
+
+    chat.send(text_message(1, &pasted_ingredients())).await?;
+
+    let review = requests.calls("sendMessage").pop().unwrap();
+    assert!(review["text"]
+        .as_str()
+        .unwrap()
+        .contains(&chat.t("text-ingredients-detected")));
+    let (ingredients, review_message_id) = chat.review().await?;
+    assert_eq!(ingredients.len(), 3);
+    assert!(requests
+        .last_keyboard_data()
+        .contains(&"delete_0".to_string()));
+
+    requests.clear();
+    for _ in 0..ingredients.len() {
+        chat.tap(callback_query("delete_0", review_message_id))
+            .await?;
+    }
+
+    // Each deletion edits the review in place, the last one into the empty review
+    let edits = requests.calls("editMessageText");
+    assert_eq!(edits.len(), 3);
+    assert!(edits
+        .iter()
+        .all(|edit| edit["message_id"] == review_message_id));
+    assert!(edits[2]["text"]
+        .as_str()
+        .unwrap()
+        .contains(&chat.t("review-no-ingredients")));
+    let buttons = requests.last_keyboard_data();
+    assert!(buttons.contains(&"add_more".to_string()));
+    assert!(buttons.contains(&"cancel_empty".to_string()));
+    assert!(buttons.contains(&"undo_delete".to_string()));
+
+    let (remaining, _) = chat.review().await?;
+    assert!(remaining.is_empty());
+    assert!(chat.saved_recipe_names().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancelled_review_saves_nothing() -> Result<()> {
+    let Some((pool, _guard)) = test_database().await? else {
+        return Ok(());
+    };
+    let chat = Conversation::new(pool, TelegramMock::start().await);
+    let requests = chat.telegram.requests.clone();
+
+    chat.send(text_message(1, &pasted_ingredients())).await?;
+    let (_, review_message_id) = chat.review().await?;
+
+    requests.clear();
+    chat.tap(callback_query("cancel_review", review_message_id))
+        .await?;
+
+    // The review collapses into a summary without buttons
+    let edit = requests.calls("editMessageText").pop().unwrap();
+    assert_eq!(edit["message_id"], review_message_id);
+    assert!(edit["text"].as_str().unwrap().starts_with('❌'));
+    assert_eq!(
+        edit["reply_markup"]["inline_keyboard"],
+        serde_json::json!([])
+    );
+    assert!(matches!(
+        chat.dialogue().get().await?,
+        None | Some(RecipeDialogueState::Start)
+    ));
+    assert!(chat.saved_recipe_names().await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipes_list_pages_through_saved_recipes() -> Result<()> {
+    let Some((pool, _guard)) = test_database().await? else {
+        return Ok(());
+    };
+    let names: Vec<String> = (1..=7).map(|n| format!("Recipe {n}")).collect();
+    for name in &names {
+        save_ingredients_to_database(
+            &pool,
+            CHAT_ID,
+            "2 cups flour",
+            &[],
+            name,
+            Some("en"),
+            None,
+            None,
+            None,
+        )
+        .await?;
+    }
+    let chat = Conversation::new(pool, TelegramMock::start().await);
+    let requests = chat.telegram.requests.clone();
+
+    chat.send(text_message(1, "/recipes")).await?;
+
+    // The first page lists five recipes and offers the next page
+    let first_page = requests.last_keyboard_data();
+    let listed = |buttons: &[String]| -> Vec<String> {
+        names
+            .iter()
+            .filter(|name| buttons.contains(&recipe_selection_callback(name)))
+            .cloned()
+            .collect()
+    };
+    let first_listed = listed(&first_page);
+    assert_eq!(first_listed.len(), 5);
+    assert!(first_page.contains(&"page:1".to_string()));
+    // The list is the first message sent
+    let list_message_id = FIRST_MESSAGE_ID;
+
+    requests.clear();
+    chat.tap(callback_query("page:1", list_message_id)).await?;
+
+    // The second page replaces the first in the same message
+    let edit = requests.calls("editMessageText").pop().unwrap();
+    assert_eq!(edit["message_id"], list_message_id);
+    let second_listed = listed(&requests.last_keyboard_data());
+    assert_eq!(second_listed.len(), 2);
+    assert!(second_listed
+        .iter()
+        .all(|name| !first_listed.contains(name)));
+    assert!(requests
+        .last_keyboard_data()
+        .contains(&"page:0".to_string()));
+    Ok(())
+}
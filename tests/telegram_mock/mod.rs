@@ -0,0 +1,333 @@
+//! A local HTTP server standing in for the Telegram Bot API
+//!
+//! Bots built by [`TelegramMock::start`] send their requests here. Every
+//! request is recorded as (method, JSON parameters) and answered like Telegram
+//! would, so handlers run unchanged and tests assert on what they sent. Files
+//! added with [`TelegramMock::add_file`] are served through `getFile` and the
+//! file download URL.
+//!
+//! Shared by test crates with `mod telegram_mock;`, so not every crate uses
+//! every helper.
+#![allow(dead_code)]
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+use teloxide::types::{CallbackQuery, Message};
+use teloxide::Bot;
+
+/// Chat of the private conversation the helpers build updates for
+pub const CHAT_ID: i64 = 42;
+
+/// Id of the first message the bot sends, the next ones counting up from it
+pub const FIRST_MESSAGE_ID: i32 = 100;
+
+/// Date of every message, as a date of 0 marks a message as inaccessible
+const MESSAGE_DATE: i64 = 1_700_000_000;
+
+/// Token of the mocked bot, part of every request path
+const TOKEN: &str = "1234:TEST";
+
+/// Requests received by the fake Bot API, as (method, JSON parameters)
+#[derive(Clone, Default)]
+pub struct SentRequests(Arc<Mutex<Vec<(String, Value)>>>);
+
+impl SentRequests {
+    /// Parameters of every request to `method`, in order
+    pub fn calls(&self, method: &str) -> Vec<Value> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case(method))
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+
+    /// Methods called, in order
+    pub fn methods(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Text of every message sent
+    pub fn sent_texts(&self) -> Vec<String> {
+        self.texts("sendMessage")
+    }
+
+    /// Text of every message edit
+    pub fn edited_texts(&self) -> Vec<String> {
+        self.texts("editMessageText")
+    }
+
+    /// Ids of every message deleted
+    pub fn deleted_ids(&self) -> Vec<i64> {
+        self.calls("deleteMessage")
+            .iter()
+            .filter_map(|params| params["message_id"].as_i64())
+            .collect()
+    }
+
+    /// Callback data of every inline button in the last keyboard sent or edited
+    pub fn last_keyboard_data(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|(_, params)| {
+                params["reply_markup"]["inline_keyboard"]
+                    .as_array()
+                    .cloned()
+            })
+            .unwrap_or_default()
+            .iter()
+            .filter_map(Value::as_array)
+            .flatten()
+            .filter_map(|button| button["callback_data"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    /// Forget the requests so far, to assert on the next step only
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    fn texts(&self, method: &str) -> Vec<String> {
+        self.calls(method)
+            .iter()
+            .map(|params| params["text"].as_str().unwrap_or_default().to_string())
+            .collect()
+    }
+}
+
+/// Downloadable files by file id, as (file path, content)
+type Files = HashMap<String, (String, Vec<u8>)>;
+
+/// A running fake Bot API and a bot sending its requests there
+pub struct TelegramMock {
+    pub bot: Bot,
+    pub requests: SentRequests,
+    files: Arc<Mutex<Files>>,
+}
+
+impl TelegramMock {
+    /// Start the server on a free local port
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = SentRequests::default();
+        let files = Arc::new(Mutex::new(HashMap::new()));
+        let next_message_id = Arc::new(AtomicI32::new(FIRST_MESSAGE_ID));
+
+        let state = (requests.clone(), files.clone(), next_message_id);
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = state.clone();
+                let service = hyper::service::service_fn(
+                    move |req: hyper::Request<hyper::body::Incoming>| {
+                        let (requests, files, next_message_id) = state.clone();
+                        async move {
+                            Ok::<_, std::convert::Infallible>(
+                                answer(req, &requests, &files, &next_message_id).await,
+                            )
+                        }
+                    },
+                );
+                tokio::spawn(
+                    hyper::server::conn::http1::Builder::new()
+                        .serve_connection(hyper_util::rt::TokioIo::new(stream), service),
+                );
+            }
+        });
+
+        let bot = Bot::new(TOKEN).set_api_url(reqwest::Url::parse(&api_url).unwrap());
+        Self {
+            bot,
+            requests,
+            files,
+        }
+    }
+
+    /// Serve `content` as the file with `file_id`
+    pub fn add_file(&self, file_id: &str, content: Vec<u8>) {
+        let path = format!("photos/{file_id}.jpg");
+        self.files
+            .lock()
+            .unwrap()
+            .insert(file_id.to_string(), (path, content));
+    }
+}
+
+/// Answer one request like the Bot API would
+async fn answer(
+    req: hyper::Request<hyper::body::Incoming>,
+    requests: &SentRequests,
+    files: &Mutex<Files>,
+    next_message_id: &AtomicI32,
+) -> hyper::Response<FullBody> {
+    let path = req.uri().path().to_string();
+
+    // File downloads are plain GETs of /file/bot<token>/<file path>
+    if let Some(file_path) = path.strip_prefix(&format!("/file/bot{TOKEN}/")) {
+        let content = files
+            .lock()
+            .unwrap()
+            .values()
+            .find(|(path, _)| path == file_path)
+            .map(|(_, content)| content.clone());
+        return match content {
+            Some(content) => hyper::Response::new(FullBody::from(content)),
+            None => hyper::Response::builder()
+                .status(404)
+                .body(FullBody::default())
+                .unwrap(),
+        };
+    }
+
+    let method = path.rsplit('/').next().unwrap_or("").to_string();
+    let params: Value =
+        serde_json::from_slice(&read_body(req.into_body()).await).unwrap_or_default();
+    let result = match method.to_ascii_lowercase().as_str() {
+        "sendmessage" => message_json(
+            next_message_id.fetch_add(1, Ordering::SeqCst),
+            params["text"].as_str().unwrap_or_default(),
+        ),
+        // An edited message keeps its id
+        "editmessagetext" | "editmessagereplymarkup" => message_json(
+            params["message_id"].as_i64().unwrap_or_default() as i32,
+            params["text"].as_str().unwrap_or_default(),
+        ),
+        "getfile" => {
+            let file_id = params["file_id"].as_str().unwrap_or_default();
+            match files.lock().unwrap().get(file_id) {
+                Some((file_path, content)) => json!({
+                    "file_id": file_id,
+                    "file_unique_id": format!("unique-{file_id}"),
+                    "file_size": content.len(),
+                    "file_path": file_path,
+                }),
+                None => {
+                    requests.0.lock().unwrap().push((method, params));
+                    return hyper::Response::new(
+                        json!({
+                            "ok": false,
+                            "error_code": 400,
+                            "description": "Bad Request: invalid file_id",
+                        })
+                        .to_string()
+                        .into_bytes()
+                        .into(),
+                    );
+                }
+            }
+        }
+        _ => json!(true),
+    };
+    requests.0.lock().unwrap().push((method, params));
+    hyper::Response::new(
+        json!({ "ok": true, "result": result })
+            .to_string()
+            .into_bytes()
+            .into(),
+    )
+}
+
+/// Response body sent in a single frame
+#[derive(Default)]
+struct FullBody(Option<hyper::body::Bytes>);
+
+impl From<Vec<u8>> for FullBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Some(bytes.into()))
+    }
+}
+
+impl hyper::body::Body for FullBody {
+    type Data = hyper::body::Bytes;
+    type Error = std::convert::Infallible;
+
+    fn poll_frame(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<hyper::body::Frame<Self::Data>, Self::Error>>> {
+        std::task::Poll::Ready(self.0.take().map(|data| Ok(hyper::body::Frame::data(data))))
+    }
+}
+
+/// Read a request body to the end
+async fn read_body(body: hyper::body::Incoming) -> Vec<u8> {
+    use hyper::body::Body;
+
+    let mut body = std::pin::pin!(body);
+    let mut bytes = Vec::new();
+    while let Some(frame) = std::future::poll_fn(|cx| body.as_mut().poll_frame(cx)).await {
+        if let Ok(data) = frame.map(|frame| frame.into_data()) {
+            bytes.extend_from_slice(&data.unwrap_or_default());
+        }
+    }
+    bytes
+}
+
+/// Message JSON as Telegram returns it
+pub fn message_json(message_id: i32, text: &str) -> Value {
+    json!({
+        "message_id": message_id,
+        "date": MESSAGE_DATE,
+        "chat": { "id": CHAT_ID, "type": "private", "first_name": "Test" },
+        "text": text,
+    })
+}
+
+/// The user sending updates, with an English client
+fn sender_json() -> Value {
+    json!({ "id": CHAT_ID, "is_bot": false, "first_name": "Test", "language_code": "en" })
+}
+
+/// Text message from the user
+pub fn text_message(message_id: i32, text: &str) -> Message {
+    let mut message = message_json(message_id, text);
+    message["from"] = sender_json();
+    if let Some(command) = text.strip_prefix('/') {
+        let length = command.split_whitespace().next().unwrap_or_default().len() + 1;
+        message["entities"] = json!([{ "type": "bot_command", "offset": 0, "length": length }]);
+    }
+    serde_json::from_value(message).unwrap()
+}
+
+/// Photo with `file_id` from the user, captioned when `caption` is given
+pub fn photo_message(message_id: i32, file_id: &str, caption: Option<&str>) -> Message {
+    let mut message = json!({
+        "message_id": message_id,
+        "date": MESSAGE_DATE,
+        "chat": { "id": CHAT_ID, "type": "private", "first_name": "Test" },
+        "from": sender_json(),
+        "photo": [{
+            "file_id": file_id,
+            "file_unique_id": format!("unique-{file_id}"),
+            "width": 1024,
+            "height": 768,
+        }],
+    });
+    if let Some(caption) = caption {
+        message["caption"] = json!(caption);
+    }
+    serde_json::from_value(message).unwrap()
+}
+
+/// Tap on a button with `data` under message `message_id`
+pub fn callback_query(data: &str, message_id: i32) -> CallbackQuery {
+    serde_json::from_value(json!({
+        "id": "1",
+        "from": sender_json(),
+        "message": message_json(message_id, "Keyboard"),
+        "chat_instance": "1",
+        "data": data,
+    }))
+    .unwrap()
+}
@@ -3,7 +3,7 @@
 //! This module provides common test setup functions to reduce code duplication
 //! across integration tests and improve test reliability and consistency.
 
-use just_ingredients::db;
+use just_ingredients::db::{self, RecipeId, TelegramId, UserId};
 use just_ingredients::text_processing::MeasurementDetector;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
@@ -49,7 +49,7 @@ pub async fn setup_test_database() -> Result<Option<Arc<PgPool>>, Box<dyn std::e
 /// Returns the created user
 pub async fn create_test_user(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     language_code: Option<&str>,
 ) -> Result<db::User, Box<dyn std::error::Error>> {
     let user = db::get_or_create_user(pool, telegram_id, language_code).await?;
@@ -61,10 +61,10 @@ pub async fn create_test_user(
 /// Returns the recipe ID
 pub async fn create_test_recipe(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     content: &str,
     name: Option<&str>,
-) -> Result<i64, Box<dyn std::error::Error>> {
+) -> Result<RecipeId, Box<dyn std::error::Error>> {
     let recipe_id = db::create_recipe(pool, telegram_id, content).await?;
 
     if let Some(recipe_name) = name {
@@ -80,8 +80,8 @@ pub async fn create_test_recipe(
 /// Returns the created ingredient IDs.
 pub async fn create_test_ingredients_from_text(
     pool: &PgPool,
-    user_id: i64,
-    recipe_id: Option<i64>,
+    user_id: UserId,
+    recipe_id: Option<RecipeId>,
     ocr_text: &str,
 ) -> Result<Vec<i64>, Box<dyn std::error::Error>> {
     let detector = MeasurementDetector::new()?;
@@ -113,11 +113,11 @@ pub async fn create_test_ingredients_from_text(
 /// Returns (user, recipe_id, ingredient_ids)
 pub async fn create_complete_test_recipe(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     recipe_content: &str,
     recipe_name: Option<&str>,
     ingredients_text: &str,
-) -> Result<(db::User, i64, Vec<i64>), Box<dyn std::error::Error>> {
+) -> Result<(db::User, RecipeId, Vec<i64>), Box<dyn std::error::Error>> {
     // Create user
     let user = create_test_user(pool, telegram_id, Some("en")).await?;
 
@@ -137,14 +137,16 @@ pub async fn create_complete_test_recipe(
 /// This should be called in test cleanup to avoid data pollution.
 pub async fn cleanup_test_data(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Get all ingredients for this user
-    let ingredients = db::list_ingredients_by_user(pool, telegram_id).await?;
+    // Get all ingredients for this user (ingredients are keyed by internal user ID)
+    if let Some(user) = db::get_user_by_telegram_id(pool, telegram_id).await? {
+        let ingredients = db::list_ingredients_by_user(pool, user.id).await?;
 
-    // Delete ingredients first (to satisfy foreign key constraints)
-    for ingredient in ingredients {
-        db::delete_ingredient(pool, ingredient.id).await?;
+        // Delete ingredients first (to satisfy foreign key constraints)
+        for ingredient in ingredients {
+            db::delete_ingredient(pool, ingredient.id).await?;
+        }
     }
 
     // Get all recipes for this user
@@ -167,7 +169,7 @@ pub async fn cleanup_test_data(
 /// More targeted cleanup for tests that create specific recipes.
 pub async fn cleanup_test_recipe(
     pool: &PgPool,
-    recipe_id: i64,
+    recipe_id: RecipeId,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Get ingredients for this recipe
     let ingredients = db::get_recipe_ingredients(pool, recipe_id).await?;
@@ -208,9 +210,9 @@ pub fn generate_test_id(prefix: &str) -> String {
 /// Convenience function that generates a unique recipe name to avoid conflicts
 pub async fn create_unique_test_recipe(
     pool: &PgPool,
-    telegram_id: i64,
+    telegram_id: TelegramId,
     content: &str,
-) -> Result<i64, Box<dyn std::error::Error>> {
+) -> Result<RecipeId, Box<dyn std::error::Error>> {
     let unique_name = generate_test_id("test_recipe");
     create_test_recipe(pool, telegram_id, content, Some(&unique_name)).await
 }
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use just_ingredients::text_processing::{MeasurementConfig, MeasurementDetector};
+    use just_ingredients::text_processing::{LineKind, MeasurementConfig, MeasurementDetector};
 
     fn create_detector() -> MeasurementDetector {
         MeasurementDetector::new().unwrap()
@@ -111,6 +111,141 @@ mod tests {
         assert!(detector.has_measurements("1 bouquet de thym"));
     }
 
+    #[test]
+    fn test_comprehensive_german_measurements() {
+        let detector = create_detector();
+
+        // Test volume measurements, abbreviated and written out
+        assert!(detector.has_measurements("2 EL Olivenöl"));
+        assert!(detector.has_measurements("1 Esslöffel Honig"));
+        assert!(detector.has_measurements("3 TL Zucker"));
+        assert!(detector.has_measurements("1 Teelöffel Zimt"));
+        assert!(detector.has_measurements("2 Tassen Mehl"));
+
+        // Test count measurements
+        assert!(detector.has_measurements("1 Prise Salz"));
+        assert!(detector.has_measurements("2 Prisen Pfeffer"));
+        assert!(detector.has_measurements("1 Messerspitze Muskat"));
+        assert!(detector.has_measurements("1 Päckchen Backpulver"));
+        assert!(detector.has_measurements("3 Zehen Knoblauch"));
+
+        let cases = [
+            ("2 EL Olivenöl", "el", "Olivenöl"),
+            ("1 Teelöffel Zimt", "teelöffel", "Zimt"),
+            ("2 Tassen Mehl", "tassen", "Mehl"),
+            ("2 Prisen Pfeffer", "prisen", "Pfeffer"),
+            ("1 Päckchen Backpulver", "päckchen", "Backpulver"),
+            ("2 Scheiben von Schinken", "scheiben", "Schinken"),
+        ];
+        for (text, unit, ingredient) in cases {
+            let matches = detector.extract_ingredient_measurements(text);
+            assert_eq!(matches.len(), 1, "{text}");
+            assert_eq!(matches[0].measurement.as_deref(), Some(unit), "{text}");
+            assert_eq!(matches[0].ingredient_name, ingredient, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_comprehensive_spanish_measurements() {
+        let detector = create_detector();
+
+        // Test volume measurements, abbreviated and written out
+        assert!(detector.has_measurements("2 tazas de harina"));
+        assert!(detector.has_measurements("1 cucharada de aceite"));
+        assert!(detector.has_measurements("3 cucharaditas de azúcar"));
+        assert!(detector.has_measurements("2 cdas de vinagre"));
+
+        // Test count measurements
+        assert!(detector.has_measurements("1 pizca de sal"));
+        assert!(detector.has_measurements("3 dientes de ajo"));
+        assert!(detector.has_measurements("1 lata de tomates"));
+        assert!(detector.has_measurements("1 puñado de almendras"));
+
+        // "cucharaditas" must not be cut short to "cucharada"
+        let cases = [
+            ("2 tazas de harina", "tazas", "harina"),
+            ("1 cucharada de aceite", "cucharada", "aceite"),
+            ("3 cucharaditas de azúcar", "cucharaditas", "azúcar"),
+            ("1 cucharadita del limón", "cucharadita", "limón"),
+            ("1 pizca de sal", "pizca", "sal"),
+            ("3 dientes de ajo", "dientes", "ajo"),
+        ];
+        for (text, unit, ingredient) in cases {
+            let matches = detector.extract_ingredient_measurements(text);
+            assert_eq!(matches.len(), 1, "{text}");
+            assert_eq!(matches[0].measurement.as_deref(), Some(unit), "{text}");
+            assert_eq!(matches[0].ingredient_name, ingredient, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_range_quantities() {
+        let detector = create_detector();
+
+        let cases = [
+            (
+                "2-3 tbsp olive oil",
+                "2",
+                Some("3"),
+                Some("tbsp"),
+                "olive oil",
+            ),
+            ("2 – 3 cups flour", "2", Some("3"), Some("cups"), "flour"),
+            ("2—3 cups flour", "2", Some("3"), Some("cups"), "flour"),
+            ("2 to 3 cups flour", "2", Some("3"), Some("cups"), "flour"),
+            ("1/2 to 1 cup milk", "1/2", Some("1"), Some("cup"), "milk"),
+            (
+                "2 à 3 gousses d'ail",
+                "2",
+                Some("3"),
+                Some("gousses"),
+                "ail",
+            ),
+            ("2 cups flour", "2", None, Some("cups"), "flour"),
+            ("2 tomatoes", "2", None, None, "tomatoes"),
+            // An upper bound below the lower one is not a range
+            ("3-2 cups flour", "3", None, Some("cups"), "flour"),
+        ];
+        for (text, quantity, quantity_max, unit, ingredient) in cases {
+            let matches = detector.extract_ingredient_measurements(text);
+            assert_eq!(matches.len(), 1, "{text}");
+            assert_eq!(matches[0].quantity, quantity, "{text}");
+            assert_eq!(matches[0].quantity_max.as_deref(), quantity_max, "{text}");
+            assert_eq!(matches[0].measurement.as_deref(), unit, "{text}");
+            assert_eq!(matches[0].ingredient_name, ingredient, "{text}");
+        }
+
+        let matches = detector.extract_ingredient_measurements("2-3 tbsp olive oil");
+        assert_eq!(matches[0].display_quantity(), "2–3");
+        assert_eq!(
+            matches[0].range_text().as_deref(),
+            Some("2–3 tbsp olive oil")
+        );
+        let single = detector.extract_ingredient_measurements("2 cups flour");
+        assert_eq!(single[0].display_quantity(), "2");
+        assert_eq!(single[0].range_text(), None);
+    }
+
+    #[test]
+    fn test_unit_languages_restrict_detected_units() {
+        let english_only = MeasurementDetector::with_config(MeasurementConfig {
+            unit_languages: Some(vec!["en".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+        // English and metric units are always detected
+        let matches = english_only.extract_ingredient_measurements("2 cups flour");
+        assert_eq!(matches[0].measurement.as_deref(), Some("cups"));
+        let matches = english_only.extract_ingredient_measurements("200 g Mehl");
+        assert_eq!(matches[0].measurement.as_deref(), Some("g"));
+
+        let matches = english_only.extract_ingredient_measurements("1 pizca de sal");
+        assert_eq!(matches[0].measurement, None);
+        let matches = english_only.extract_ingredient_measurements("1 Prise Salz");
+        assert_eq!(matches[0].measurement, None);
+    }
+
     #[test]
     fn test_abbreviations() {
         let detector = create_detector();
@@ -357,6 +492,204 @@ mod tests {
         assert_eq!(consumed, 0);
     }
 
+    #[test]
+    fn test_extract_multi_line_ingredient_wrapped_quantity() {
+        let detector = create_detector();
+
+        // Quantity wrapped away from its unit
+        let lines = ["2", "cups sugar"];
+        let (ingredient, consumed) = detector.extract_multi_line_ingredient(&lines, 0);
+        assert_eq!(ingredient, "sugar");
+        assert_eq!(consumed, 2);
+
+        // Mixed number wrapped away from its unit
+        let lines = ["1 1/2", "tsp salt"];
+        let (ingredient, consumed) = detector.extract_multi_line_ingredient(&lines, 0);
+        assert_eq!(ingredient, "salt");
+        assert_eq!(consumed, 2);
+
+        // Wrapped quantity whose ingredient continues on a third line
+        let lines = ["1", "cup old-fashioned rolled", "oats"];
+        let (ingredient, consumed) = detector.extract_multi_line_ingredient(&lines, 0);
+        assert_eq!(ingredient, "old-fashioned rolled oats");
+        assert_eq!(consumed, 3);
+
+        // No join: next line does not start with a unit
+        assert_eq!(detector.wrapped_quantity_line(&["2", "eggs"], 0), None);
+
+        // No join: next line is a measurement of its own
+        assert_eq!(
+            detector.wrapped_quantity_line(&["2", "3 cups flour"], 0),
+            None
+        );
+
+        // No join: the quantity line already holds a unit
+        assert_eq!(
+            detector.wrapped_quantity_line(&["2 cups", "sugar"], 0),
+            None
+        );
+
+        // No join: empty or missing next line
+        assert_eq!(detector.wrapped_quantity_line(&["2", ""], 0), None);
+        assert_eq!(detector.wrapped_quantity_line(&["2"], 0), None);
+    }
+
+    #[test]
+    fn test_wrapped_quantity_integration() {
+        let detector = create_detector();
+        let text = "Ingredients:\n2\ncups sugar\n1 1/2\ntsp salt\n3 eggs";
+
+        let matches = detector.extract_ingredient_measurements(text);
+
+        assert_eq!(matches.len(), 3);
+
+        assert_eq!(matches[0].quantity, "2");
+        assert_eq!(matches[0].measurement, Some("cups".to_string()));
+        assert_eq!(matches[0].ingredient_name, "sugar");
+        assert_eq!(matches[0].line_number, 1);
+
+        assert_eq!(matches[1].quantity, "1 1/2");
+        assert_eq!(matches[1].measurement, Some("tsp".to_string()));
+        assert_eq!(matches[1].ingredient_name, "salt");
+        assert_eq!(matches[1].line_number, 3);
+
+        assert_eq!(matches[2].quantity, "3");
+        assert_eq!(matches[2].measurement, None);
+        assert_eq!(matches[2].ingredient_name, "eggs");
+        assert_eq!(matches[2].line_number, 5);
+    }
+
+    #[test]
+    fn test_wrapped_quantity_join_can_be_disabled() {
+        let text = "2\ncups sugar";
+
+        let disabled = MeasurementDetector::with_config(MeasurementConfig {
+            join_wrapped_quantities: false,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(disabled.extract_ingredient_measurements(text).is_empty());
+
+        // A single-line limit leaves no room to join two lines
+        let single_line = MeasurementDetector::with_config(MeasurementConfig {
+            max_combine_lines: 1,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(single_line.extract_ingredient_measurements(text).is_empty());
+    }
+
+    #[test]
+    fn test_number_words_english() {
+        let detector = create_detector();
+        let text =
+            "two cups flour\nA pinch of salt\nThree eggs\nhalf a cup milk\n- twelve strawberries";
+
+        let matches = detector.extract_ingredient_measurements(text);
+
+        assert_eq!(matches.len(), 5);
+        assert_eq!(matches[0].quantity, "2");
+        assert_eq!(matches[0].measurement, Some("cups".to_string()));
+        assert_eq!(matches[0].ingredient_name, "flour");
+        assert_eq!(matches[1].quantity, "1");
+        assert_eq!(matches[1].measurement, Some("pinch".to_string()));
+        assert_eq!(matches[2].quantity, "3");
+        assert_eq!(matches[2].measurement, None);
+        assert_eq!(matches[2].ingredient_name, "eggs");
+        assert_eq!(matches[3].quantity, "1/2");
+        assert_eq!(matches[3].measurement, Some("cup".to_string()));
+        assert_eq!(matches[3].ingredient_name, "milk");
+        assert_eq!(matches[4].quantity, "12");
+        assert_eq!(matches[4].ingredient_name, "strawberries");
+
+        // Positions point at the original wording
+        assert_eq!(
+            &text[matches[0].start_pos..matches[0].end_pos],
+            "two cups flour"
+        );
+        assert_eq!(
+            &text[matches[3].start_pos..matches[3].end_pos],
+            "half a cup milk"
+        );
+        assert!(detector.has_measurements("two cups flour"));
+        assert!(detector.is_measurement_line("Three eggs"));
+    }
+
+    #[test]
+    fn test_number_words_french() {
+        let detector = create_detector();
+        let text = "une pincée de sel\ndeux oeufs\ndix-huit framboises\nun demi-litre de lait";
+
+        let matches = detector.extract_ingredient_measurements(text);
+
+        assert_eq!(matches.len(), 4);
+        assert_eq!(matches[0].quantity, "1");
+        assert_eq!(matches[0].measurement, Some("pincée".to_string()));
+        assert_eq!(matches[1].quantity, "2");
+        assert_eq!(matches[1].ingredient_name, "oeufs");
+        assert_eq!(matches[2].quantity, "18");
+        assert_eq!(matches[2].ingredient_name, "framboises");
+        assert_eq!(matches[3].quantity, "1/2");
+        assert_eq!(matches[3].measurement, Some("litre".to_string()));
+        assert_eq!(
+            &text[matches[1].start_pos..matches[1].end_pos],
+            "deux oeufs"
+        );
+    }
+
+    #[test]
+    fn test_number_words_ambiguous() {
+        let detector = create_detector();
+
+        // Articles without a unit, words that aren't quantities, and times
+        for line in [
+            "a little salt",
+            "A few leaves of basil",
+            "une belle tomate",
+            "One of my favourite cakes",
+            "deux de plus",
+            "Two hours later",
+            "tender",
+            "seven",
+            "sixty eggs",
+        ] {
+            assert_eq!(detector.number_word_line(line), None, "{line}");
+            assert!(
+                detector.extract_ingredient_measurements(line).is_empty(),
+                "{line}"
+            );
+        }
+
+        // A spelled-out ingredient line ends the one wrapped before it
+        let lines = ["1 cup old-fashioned rolled", "two eggs"];
+        assert_eq!(
+            detector.extract_multi_line_ingredient(&lines, 0),
+            ("old-fashioned rolled".to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_number_words_leave_digits_alone() {
+        let detector = create_detector();
+        let text = "2 cups flour\n1/2 cup sugar\n6 oeufs\n250 g beurre";
+
+        let matches = detector.extract_ingredient_measurements(text);
+
+        let quantities: Vec<&str> = matches.iter().map(|m| m.quantity.as_str()).collect();
+        assert_eq!(quantities, ["2", "1/2", "6", "250"]);
+        assert_eq!(detector.number_word_line("2 cups flour"), None);
+
+        let disabled = MeasurementDetector::with_config(MeasurementConfig {
+            parse_number_words: false,
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(disabled
+            .extract_ingredient_measurements("two cups flour")
+            .is_empty());
+        assert_eq!(disabled.extract_ingredient_measurements(text), matches);
+    }
+
     #[test]
     fn test_custom_pattern() {
         let pattern = r"\b\d+\s*(?:cups?|tablespoons?)\b";
@@ -679,6 +1012,21 @@ mod tests {
         assert_eq!(matches[0].ingredient_name, "very-long-ingredient"); // "of " removed, then truncated at word boundary
     }
 
+    #[test]
+    fn test_ingredient_length_limit_inside_accented_character() {
+        let config = MeasurementConfig {
+            max_ingredient_length: 18,
+            ..Default::default()
+        };
+        let detector = MeasurementDetector::with_config(config).unwrap();
+
+        // Byte 18 falls inside the "é" of "légère"
+        let matches = detector.extract_ingredient_measurements("20 cl crème-fraîche-légère");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].ingredient_name, "crème-fraîche-l");
+    }
+
     #[test]
     fn test_postprocessing_disabled() {
         let config = MeasurementConfig {
@@ -1093,12 +1441,11 @@ mod tests {
 
         // Build the new unified pattern (measurement optional, capture all remaining text)
         let config = just_ingredients::text_processing::load_measurement_units_config();
-        let mut all_units: Vec<String> = Vec::new();
-        all_units.extend(config.measurement_units.volume_units);
-        all_units.extend(config.measurement_units.weight_units);
-        all_units.extend(config.measurement_units.volume_units_metric);
-        all_units.extend(config.measurement_units.us_units);
-        all_units.extend(config.measurement_units.french_units);
+        let all_units: Vec<String> = config
+            .measurement_units
+            .entries()
+            .map(|entry| entry.name.clone())
+            .collect();
 
         let unique_units: std::collections::HashSet<String> = all_units.into_iter().collect();
         let mut sorted_units: Vec<String> = unique_units.into_iter().collect();
@@ -1181,12 +1528,11 @@ mod tests {
     fn test_unified_extraction_measurement_detection_accuracy() {
         // Test that the new unified pattern maintains measurement detection accuracy
         let config = just_ingredients::text_processing::load_measurement_units_config();
-        let mut all_units: Vec<String> = Vec::new();
-        all_units.extend(config.measurement_units.volume_units);
-        all_units.extend(config.measurement_units.weight_units);
-        all_units.extend(config.measurement_units.volume_units_metric);
-        all_units.extend(config.measurement_units.us_units);
-        all_units.extend(config.measurement_units.french_units);
+        let all_units: Vec<String> = config
+            .measurement_units
+            .entries()
+            .map(|entry| entry.name.clone())
+            .collect();
 
         let unique_units: std::collections::HashSet<String> = all_units.into_iter().collect();
         let mut sorted_units: Vec<String> = unique_units.into_iter().collect();
@@ -1440,6 +1786,124 @@ mod tests {
         assert_eq!(matches[5].quantity, "2");
         assert_eq!(matches[5].measurement, None);
         assert_eq!(matches[5].ingredient_name, "eggs room temperature");
+
+        // The "For the topping:" header is skipped, not read as an ingredient
+        assert_eq!(matches[6].quantity, "1/4");
+        assert_eq!(matches[6].ingredient_name, "flour");
+        assert_eq!(matches[8].ingredient_name, "cinnamon ground");
+        assert!(matches
+            .iter()
+            .all(|m| !m.ingredient_name.contains("topping")));
+    }
+
+    #[test]
+    fn test_full_recipe_skips_headers_and_instructions() {
+        let detector = create_detector();
+
+        // OCR dump of a whole recipe card, method included
+        let text = "Apple Crumble
+                   Serves 6
+                   
+                   Ingredients:
+                   4 apples, peeled
+                   1/2 cup brown sugar
+                   1 tsp cinnamon
+                   
+                   For the topping:
+                   1 cup flour
+                   1/2 cup cold butter
+                   
+                   Directions
+                   1. Preheat oven to 350°F (180°C).
+                   2. Slice the apples and toss with
+                   the sugar and cinnamon.
+                   3. Rub 1 cup flour into the butter.
+                   Preheat oven to 350
+                   Bake for 25 minutes
+                   Let cool 10 min before serving.";
+
+        let matches = detector.extract_ingredient_measurements(text);
+        let found: Vec<(&str, Option<&str>, &str)> = matches
+            .iter()
+            .map(|m| {
+                (
+                    m.quantity.as_str(),
+                    m.measurement.as_deref(),
+                    m.ingredient_name.as_str(),
+                )
+            })
+            .collect();
+
+        // Every true ingredient comes through, and nothing from the method
+        assert_eq!(
+            found,
+            vec![
+                ("4", None, "apples"),
+                ("1/2", Some("cup"), "brown sugar"),
+                ("1", Some("tsp"), "cinnamon"),
+                ("1", Some("cup"), "flour"),
+                ("1/2", Some("cup"), "cold butter"),
+            ]
+        );
+        assert!(matches.iter().all(|m| m.quantity != "350"
+            && m.quantity != "25"
+            && !m.ingredient_name.contains("minutes")));
+    }
+
+    #[test]
+    fn test_line_classification() {
+        let detector = create_detector();
+
+        for header in [
+            "For the topping:",
+            "Ingredients",
+            "INSTRUCTIONS:",
+            "Pour la pâte :",
+        ] {
+            assert_eq!(detector.classify_line(header), LineKind::Header, "{header}");
+        }
+        for instruction in [
+            "Preheat oven to 350",
+            "Bake for 25 minutes",
+            "1. Preheat oven to 180°C",
+            "Cuire 20 minutes à 180 °C",
+            "Enfournez à 200 degrés",
+            "Simmer 1-2 hours",
+        ] {
+            assert_eq!(
+                detector.classify_line(instruction),
+                LineKind::Instruction,
+                "{instruction}"
+            );
+        }
+        for ingredient in [
+            "2 cups flour",
+            "Mix 2 cups flour with 1 tbsp sugar",
+            "Flour: 2 cups",
+            "2 cups tomato",
+            "sauce",
+            "1 cup rolled oats",
+            "reduced-fat milk",
+        ] {
+            assert_eq!(
+                detector.classify_line(ingredient),
+                LineKind::Ingredient,
+                "{ingredient}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_line_classification_can_be_disabled() {
+        let detector = MeasurementDetector::with_config(MeasurementConfig {
+            skip_non_ingredient_lines: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let matches = detector.extract_ingredient_measurements("Bake for 25 minutes");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].quantity, "25");
     }
 
     #[test]
@@ -1531,4 +1995,186 @@ mod tests {
             total_ingredients
         );
     }
+
+    #[test]
+    fn test_every_configured_unit_has_classification() {
+        use just_ingredients::text_processing::{classify_unit, load_measurement_units_config};
+
+        let config = load_measurement_units_config();
+        assert!(config.validate().is_ok());
+
+        let mut entries = config.measurement_units.entries().peekable();
+        assert!(entries.peek().is_some(), "config should list units");
+        for entry in entries {
+            assert_eq!(
+                classify_unit(&entry.name),
+                Some((entry.dimension, entry.system)),
+                "unit '{}' should be classified",
+                entry.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_extraction_populates_unit_metadata() {
+        use just_ingredients::text_processing::{UnitDimension, UnitSystem};
+
+        let detector = create_detector();
+        let cases = [
+            (
+                "2 cups flour",
+                Some((UnitDimension::Volume, UnitSystem::Us)),
+            ),
+            (
+                "500 g sugar",
+                Some((UnitDimension::Weight, UnitSystem::Metric)),
+            ),
+            (
+                "1 lb butter",
+                Some((UnitDimension::Weight, UnitSystem::Imperial)),
+            ),
+            (
+                "250 ml lait",
+                Some((UnitDimension::Volume, UnitSystem::Metric)),
+            ),
+            (
+                "2 Tranches de jambon",
+                Some((UnitDimension::Count, UnitSystem::Neutral)),
+            ),
+            (
+                "3 cuillères à soupe d'huile",
+                Some((UnitDimension::Volume, UnitSystem::Metric)),
+            ),
+            ("6 oeufs", None),
+        ];
+
+        for (text, expected) in cases {
+            let matches = detector.extract_ingredient_measurements(text);
+            assert_eq!(matches.len(), 1, "one match expected for '{}'", text);
+            let actual = matches[0].unit_dimension.zip(matches[0].unit_system);
+            assert_eq!(actual, expected, "unit metadata for '{}'", text);
+        }
+    }
+
+    /// Every corpus text with the outputs of the public extraction entry points
+    fn extraction_snapshot() -> String {
+        let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let mut corpus: Vec<String> = serde_json::from_str(
+            &std::fs::read_to_string(fixtures.join("extraction_corpus.json")).unwrap(),
+        )
+        .unwrap();
+        for document in ["recipe_representative.txt", "recipe_200_lines.txt"] {
+            corpus.push(std::fs::read_to_string(fixtures.join(document)).unwrap());
+        }
+
+        let detector = create_detector();
+        let raw_detector = MeasurementDetector::with_config(MeasurementConfig {
+            enable_ingredient_postprocessing: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let snapshot: Vec<serde_json::Value> = corpus
+            .iter()
+            .map(|text| {
+                let mut units: Vec<String> = detector.get_unique_units(text).into_iter().collect();
+                units.sort();
+                serde_json::json!({
+                    "text": text,
+                    "matches": detector.extract_ingredient_measurements(text),
+                    "raw_matches": raw_detector.extract_ingredient_measurements(text),
+                    "has_measurements": detector.has_measurements(text),
+                    "measurement_lines": detector.extract_measurement_lines(text),
+                    "unique_units": units,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&snapshot).unwrap() + "\n"
+    }
+
+    /// Extraction output must stay byte-identical across performance refactors
+    ///
+    /// Regenerate the golden file with `UPDATE_EXTRACTION_GOLDEN=1` only for intended
+    /// behavior changes.
+    #[test]
+    fn test_extraction_matches_golden_snapshot() {
+        let golden_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/extraction_golden.json");
+        let snapshot = extraction_snapshot();
+
+        if std::env::var("UPDATE_EXTRACTION_GOLDEN").is_ok() {
+            std::fs::write(&golden_path, &snapshot).unwrap();
+            return;
+        }
+
+        let golden = std::fs::read_to_string(&golden_path).unwrap();
+        assert!(
+            snapshot == golden,
+            "extraction output differs from tests/fixtures/extraction_golden.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_measurement_units_override_reloads_without_restart() {
+        use just_ingredients::extractor::RecipeExtractor;
+        use just_ingredients::ocr_config::OcrConfig;
+        use just_ingredients::text_processing::{classify_unit, reload_measurement_units};
+        use just_ingredients::text_processing::{UnitDimension, UnitSystem};
+        use std::io::Write;
+
+        let measurement_of = |text: &str| {
+            create_detector()
+                .extract_ingredient_measurements(text)
+                .first()
+                .and_then(|m| m.measurement.clone())
+        };
+        assert_eq!(measurement_of("1 smidgen salt"), None);
+        // Created before the reload, like the extractor the bot reads photos with
+        let extractor =
+            RecipeExtractor::new(OcrConfig::default(), MeasurementConfig::default()).unwrap();
+
+        let mut overrides = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            overrides,
+            r#"{{"measurement_units": {{"us_units": [{{"name": "smidgen", "dimension": "count", "system": "neutral"}}]}}}}"#
+        )
+        .unwrap();
+        let path = overrides.path().to_str().unwrap().to_string();
+        reload_measurement_units(Some(&path)).unwrap();
+        assert_eq!(
+            measurement_of("1 smidgen salt"),
+            Some("smidgen".to_string())
+        );
+        assert_eq!(
+            classify_unit("Smidgen"),
+            Some((UnitDimension::Count, UnitSystem::Neutral))
+        );
+        let extracted = extractor.extract_from_text("1 smidgen salt").await;
+        assert_eq!(
+            extracted.ingredients[0].measurement.as_deref(),
+            Some("smidgen")
+        );
+        // Built-in units are kept alongside the override
+        assert_eq!(measurement_of("2 cups flour"), Some("cups".to_string()));
+
+        // A broken override is rejected and detection keeps the previous units
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(reload_measurement_units(Some(&path)).is_err());
+        assert_eq!(
+            measurement_of("1 smidgen salt"),
+            Some("smidgen".to_string())
+        );
+
+        // So is one classifying a built-in unit differently in another category
+        std::fs::write(
+            &path,
+            r#"{"measurement_units": {"weight_units": [{"name": "cups", "dimension": "weight", "system": "metric"}]}}"#,
+        )
+        .unwrap();
+        assert!(reload_measurement_units(Some(&path)).is_err());
+        assert_eq!(measurement_of("2 cups flour"), Some("cups".to_string()));
+
+        reload_measurement_units(None).unwrap();
+        assert_eq!(measurement_of("1 smidgen salt"), None);
+    }
 }